@@ -0,0 +1,291 @@
+//! gRPC Action Service
+//!
+//! Serves the action API over gRPC next to the HTTP backend, so simulation
+//! orchestrators and CLI tools can dispatch actions without a browser.
+//! Requests carry the same action type and JSON payload as
+//! `ActionBroker::dispatch`; see `proto/actions.proto` for the service.
+//!
+//! ```ignore
+//! let dispatcher = Arc::new(ActionDispatcher::new(db));
+//! action_grpc::serve(dispatcher, "0.0.0.0:50051".parse()?).await?;
+//! ```
+//!
+//! Besides the unary `Dispatch`, `Watch` streams heartbeats while a
+//! long-running action is in progress and `DispatchMany` runs a stream of
+//! actions in order, answering each as it completes.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use action_handlers::{ActionDispatcher, DispatchError};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Code, Request, Response, Status, Streaming};
+
+pub mod proto {
+    tonic::include_proto!("rubigo.actions.v1");
+}
+
+use proto::actions_server::{Actions, ActionsServer};
+use proto::{action_event, action_reply, ActionError, ActionEvent, ActionReply, ActionRequest};
+
+/// How often `Watch` reports that an action is still running
+pub const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// Replies buffered per stream before the sender waits for the client
+const STREAM_BUFFER: usize = 16;
+
+/// The `Actions` service, backed by an [`ActionDispatcher`]
+#[derive(Clone)]
+pub struct ActionService {
+    dispatcher: Arc<ActionDispatcher>,
+    heartbeat: Duration,
+}
+
+impl ActionService {
+    pub fn new(dispatcher: Arc<ActionDispatcher>) -> Self {
+        Self {
+            dispatcher,
+            heartbeat: DEFAULT_HEARTBEAT,
+        }
+    }
+
+    /// Report progress every `interval` instead of [`DEFAULT_HEARTBEAT`]
+    pub fn heartbeat(mut self, interval: Duration) -> Self {
+        self.heartbeat = interval;
+        self
+    }
+
+    /// Wrap for `tonic::transport::Server::add_service`
+    pub fn into_server(self) -> ActionsServer<Self> {
+        ActionsServer::new(self)
+    }
+}
+
+/// Serve the action service on `addr` until the process stops
+pub async fn serve(dispatcher: Arc<ActionDispatcher>, addr: SocketAddr) -> anyhow::Result<()> {
+    tracing::info!("gRPC action service listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(ActionService::new(dispatcher).into_server())
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+/// The status `Dispatch` answers with for a failed action
+pub fn status(error: DispatchError) -> Status {
+    let code = match &error {
+        DispatchError::UnknownAction(_) => Code::NotFound,
+        DispatchError::Deserialize(_) => Code::InvalidArgument,
+        DispatchError::Rejected(_) | DispatchError::PendingApproval(_) => Code::FailedPrecondition,
+        DispatchError::Serialize(_) | DispatchError::Database(_) | DispatchError::Plugin(_) => {
+            Code::Internal
+        }
+    };
+    Status::new(code, error.to_string())
+}
+
+fn parse_payload(payload_json: &str) -> Result<Value, DispatchError> {
+    if payload_json.trim().is_empty() {
+        return Ok(Value::Object(Default::default()));
+    }
+    serde_json::from_str(payload_json)
+        .map_err(|e| DispatchError::Deserialize(format!("Invalid payload JSON: {}", e)))
+}
+
+async fn run(
+    dispatcher: &ActionDispatcher,
+    request: &ActionRequest,
+) -> Result<Value, DispatchError> {
+    let payload = parse_payload(&request.payload_json)?;
+    dispatcher.handle_json(&request.action_type, payload).await
+}
+
+fn reply(request_id: String, result: Result<Value, DispatchError>) -> ActionReply {
+    let result = match result.map_err(status) {
+        Ok(response) => action_reply::Result::ResponseJson(response.to_string()),
+        Err(status) => action_reply::Result::Error(ActionError {
+            code: status.code() as i32,
+            message: status.message().to_string(),
+        }),
+    };
+    ActionReply {
+        request_id,
+        result: Some(result),
+    }
+}
+
+#[tonic::async_trait]
+impl Actions for ActionService {
+    async fn dispatch(
+        &self,
+        request: Request<ActionRequest>,
+    ) -> Result<Response<ActionReply>, Status> {
+        let request = request.into_inner();
+        let response = run(&self.dispatcher, &request).await.map_err(status)?;
+        Ok(Response::new(reply(request.request_id, Ok(response))))
+    }
+
+    type WatchStream = ReceiverStream<Result<ActionEvent, Status>>;
+
+    async fn watch(
+        &self,
+        request: Request<ActionRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let request = request.into_inner();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let dispatcher = self.dispatcher.clone();
+        let heartbeat = self.heartbeat;
+
+        tokio::spawn(async move {
+            let event = |event| ActionEvent {
+                request_id: request.request_id.clone(),
+                event: Some(event),
+            };
+            // A client that hung up stops getting events, but the action
+            // still runs to completion rather than stopping half-applied
+            let _ = tx
+                .send(Ok(event(action_event::Event::Started(proto::Started {}))))
+                .await;
+
+            let started = Instant::now();
+            let action = run(&dispatcher, &request);
+            tokio::pin!(action);
+            let mut ticks =
+                tokio::time::interval_at(tokio::time::Instant::now() + heartbeat, heartbeat);
+            let result = loop {
+                tokio::select! {
+                    result = &mut action => break result,
+                    _ = ticks.tick() => {
+                        let running = proto::Running {
+                            elapsed_ms: started.elapsed().as_millis() as u64,
+                        };
+                        let _ = tx.send(Ok(event(action_event::Event::Running(running)))).await;
+                    }
+                }
+            };
+
+            let finished = reply(request.request_id.clone(), result);
+            let _ = tx
+                .send(Ok(event(action_event::Event::Finished(finished))))
+                .await;
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    type DispatchManyStream = ReceiverStream<Result<ActionReply, Status>>;
+
+    async fn dispatch_many(
+        &self,
+        request: Request<Streaming<ActionRequest>>,
+    ) -> Result<Response<Self::DispatchManyStream>, Status> {
+        let mut requests = request.into_inner();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let dispatcher = self.dispatcher.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match requests.message().await {
+                    Ok(Some(request)) => {
+                        // A failed action is answered in its reply; the
+                        // stream carries on with the next one
+                        let result = run(&dispatcher, &request).await;
+                        if tx
+                            .send(Ok(reply(request.request_id, result)))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::Database;
+    use tokio_stream::StreamExt;
+
+    async fn service() -> ActionService {
+        let db = Database::init().await.unwrap();
+        ActionService::new(Arc::new(ActionDispatcher::new(db)))
+    }
+
+    fn request(action_type: &str, payload_json: &str) -> ActionRequest {
+        ActionRequest {
+            action_type: action_type.to_string(),
+            payload_json: payload_json.to_string(),
+            request_id: "r1".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_maps_errors_to_status_codes() {
+        let service = service().await;
+
+        let ok = service
+            .dispatch(Request::new(request(
+                "personnel.list",
+                r#"{"List": {"search": null, "department": null}}"#,
+            )))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(ok.request_id, "r1");
+        assert!(matches!(
+            ok.result,
+            Some(action_reply::Result::ResponseJson(_))
+        ));
+
+        let unknown = service
+            .dispatch(Request::new(request("tickets.list", "")))
+            .await
+            .unwrap_err();
+        assert_eq!(unknown.code(), Code::NotFound);
+
+        let malformed = service
+            .dispatch(Request::new(request("personnel.list", "{")))
+            .await
+            .unwrap_err();
+        assert_eq!(malformed.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn watch_starts_then_finishes() {
+        let service = service().await;
+        let events: Vec<_> = service
+            .watch(Request::new(request("tickets.list", "")))
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|event| event.unwrap().event.unwrap())
+            .collect()
+            .await;
+
+        assert!(matches!(
+            events.first(),
+            Some(action_event::Event::Started(_))
+        ));
+        match events.last() {
+            Some(action_event::Event::Finished(ActionReply {
+                result: Some(action_reply::Result::Error(error)),
+                ..
+            })) => assert_eq!(error.code, Code::NotFound as i32),
+            other => panic!("expected a failed reply, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Standalone gRPC action server
+//!
+//! Loads the same `rubigo.toml` settings as gui-server, seeds the scenario
+//! into a fresh database and serves the action service on
+//! `server.grpc_port` (`--grpc-port`), with the scenario's training
+//! exercises available as `training.*` actions.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use action_handlers::{ActionDispatcher, ApprovalPolicy, TrainingMode};
+use anyhow::Context;
+use db::Database;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let settings = config::Settings::load()?;
+    let default_filter = if settings.dev_mode {
+        "rubigo_grpc=debug,action_grpc=debug"
+    } else {
+        "rubigo_grpc=info,action_grpc=info"
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| default_filter.into()),
+        )
+        .init();
+
+    let db = Database::init_with_settings(&settings.database).await?;
+    let scenario = settings.paths.scenario.to_string_lossy();
+    match db::seed::from_path(&db.client, &scenario).await {
+        Ok(stats) => tracing::info!("Seeded scenario {}: {:?}", scenario, stats),
+        Err(e) => tracing::warn!("Could not seed scenario {}: {}", scenario, e),
+    }
+
+    let host: IpAddr = settings
+        .server
+        .host
+        .parse()
+        .with_context(|| format!("Invalid host '{}'", settings.server.host))?;
+    let addr = SocketAddr::new(host, settings.server.grpc_port);
+    let mut dispatcher = ActionDispatcher::new(db);
+    if settings.approvals.enabled() {
+        let approvals = &settings.approvals;
+        dispatcher = dispatcher.with_approvals(ApprovalPolicy::new(
+            approvals.protected_tag.clone(),
+            approvals.approvers.clone(),
+        ));
+    }
+    match scenario_loader::Scenario::load_from_path(&*scenario) {
+        Ok(loaded) => dispatcher = dispatcher.with_training(TrainingMode::new(loaded.exercises)),
+        Err(e) => tracing::warn!("No training exercises from {}: {}", scenario, e),
+    }
+    action_grpc::serve(Arc::new(dispatcher), addr).await
+}
@@ -0,0 +1,217 @@
+//! Activity action handlers
+//!
+//! An entity's feed merges its comments and audit entries with what other
+//! stores already know about it: lifecycle changes and maintenance of
+//! assets, and simulation runs whose logs mention the entity by name.
+
+use actions::{ActivityAction, ActivityData, ActivityQuery, ActivityResponse, CommentData};
+use anyhow::Result;
+use db::client::DbClient;
+use db::models::ActivityRecord;
+use db::repositories::{ActivityRepository, AssetRepository};
+
+/// Longest comment accepted, in characters
+const MAX_COMMENT_LEN: usize = 4000;
+
+/// Handle activity actions
+pub async fn handle(db: &DbClient, action: ActivityAction) -> Result<ActivityResponse> {
+    match action {
+        ActivityAction::Feed(query) => feed(db, query).await,
+        ActivityAction::Comment(entity, data) => comment(db, &entity, data).await,
+    }
+}
+
+/// `(kind, id)` of an entity key such as `asset:7`
+fn split_key(entity: &str) -> Option<(&str, &str)> {
+    entity
+        .split_once(':')
+        .filter(|(kind, id)| !kind.is_empty() && !id.is_empty())
+}
+
+/// Sort a feed newest first; entries at the same time keep their order
+fn newest_first(items: &mut [ActivityData]) {
+    items.sort_by(|a, b| b.at.cmp(&a.at));
+}
+
+async fn feed(db: &DbClient, query: ActivityQuery) -> Result<ActivityResponse> {
+    let Some((kind, id)) = split_key(&query.entity) else {
+        return Ok(ActivityResponse::Error(format!(
+            "Unknown entity: {}",
+            query.entity
+        )));
+    };
+
+    let mut items: Vec<ActivityData> = ActivityRepository::for_entity(db, &query.entity)
+        .await?
+        .into_iter()
+        .map(|r| {
+            let (title, detail) = match r.kind.as_str() {
+                "comment" => (format!("{} commented", r.actor), Some(r.body)),
+                _ => (r.body, None),
+            };
+            ActivityData {
+                kind: r.kind,
+                title,
+                detail,
+                actor: Some(r.actor),
+                at: r.at,
+            }
+        })
+        .collect();
+
+    if kind == "asset" {
+        items.extend(
+            AssetRepository::history(db, id)
+                .await?
+                .into_iter()
+                .map(|t| ActivityData {
+                    kind: "lifecycle".to_string(),
+                    title: format!("Moved from {} to {}", t.from, t.to),
+                    detail: t.note,
+                    actor: None,
+                    at: t.at,
+                }),
+        );
+        items.extend(
+            AssetRepository::tickets_for(db, id)
+                .await?
+                .into_iter()
+                .map(|t| ActivityData {
+                    kind: "maintenance".to_string(),
+                    title: format!("Maintenance scheduled: {}", t.title),
+                    detail: t.description,
+                    actor: None,
+                    at: t.scheduled_date,
+                }),
+        );
+    }
+
+    if let Some(label) = query
+        .label
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+    {
+        items.extend(
+            ActivityRepository::simulation_mentions(db, label)
+                .await?
+                .into_iter()
+                .map(|run| ActivityData {
+                    kind: "simulation".to_string(),
+                    title: format!("Referenced by a simulation run ({})", run.status),
+                    detail: None,
+                    actor: None,
+                    at: run.started_at,
+                }),
+        );
+    }
+
+    newest_first(&mut items);
+    Ok(ActivityResponse::Feed(items))
+}
+
+async fn comment(db: &DbClient, entity: &str, data: CommentData) -> Result<ActivityResponse> {
+    if split_key(entity).is_none() {
+        return Ok(ActivityResponse::Error(format!(
+            "Unknown entity: {}",
+            entity
+        )));
+    }
+    if data.author.trim().is_empty() {
+        return Ok(ActivityResponse::Error("Sign in to comment".to_string()));
+    }
+    let body = data.body.trim();
+    if body.is_empty() {
+        return Ok(ActivityResponse::Error(
+            "A comment can't be empty".to_string(),
+        ));
+    }
+    if body.chars().count() > MAX_COMMENT_LEN {
+        return Ok(ActivityResponse::Error(format!(
+            "Comments are limited to {} characters",
+            MAX_COMMENT_LEN
+        )));
+    }
+    let record = ActivityRecord::comment(entity, data.author, body.to_string(), data.at);
+    ActivityRepository::record(db, record).await?;
+    Ok(ActivityResponse::Success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::models::{LifecycleState, NetworkAsset};
+    use db::Database;
+
+    fn query(entity: &str) -> ActivityAction {
+        ActivityAction::Feed(ActivityQuery {
+            entity: entity.to_string(),
+            label: None,
+        })
+    }
+
+    #[test]
+    fn entity_keys_need_a_kind_and_id() {
+        assert_eq!(split_key("asset:sw1"), Some(("asset", "sw1")));
+        assert_eq!(split_key("asset:"), None);
+        assert_eq!(split_key("sw1"), None);
+    }
+
+    #[tokio::test]
+    async fn asset_feed_merges_comments_and_lifecycle() {
+        let db = Database::init().await.unwrap();
+        let asset: NetworkAsset =
+            serde_json::from_value(serde_json::json!({ "name": "SW1" })).unwrap();
+        AssetRepository::create_with_id(&db.client, "sw1", asset)
+            .await
+            .unwrap();
+        AssetRepository::transition(
+            &db.client,
+            "sw1",
+            LifecycleState::Maintenance,
+            Some("Fan noise".to_string()),
+            "2025-01-02T00:00:00Z",
+        )
+        .await
+        .unwrap();
+
+        let comment = CommentData {
+            author: "Ada".to_string(),
+            body: "  Fan replaced  ".to_string(),
+            at: "2025-01-03T00:00:00Z".to_string(),
+        };
+        let posted = handle(
+            &db.client,
+            ActivityAction::Comment("asset:sw1".to_string(), comment),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(posted, ActivityResponse::Success));
+
+        match handle(&db.client, query("asset:sw1")).await.unwrap() {
+            ActivityResponse::Feed(items) => {
+                let kinds: Vec<&str> = items.iter().map(|i| i.kind.as_str()).collect();
+                assert_eq!(kinds, vec!["comment", "lifecycle"]);
+                assert_eq!(items[0].detail.as_deref(), Some("Fan replaced"));
+            }
+            other => panic!("Expected a feed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_comments_are_refused() {
+        let db = Database::init().await.unwrap();
+        let comment = CommentData {
+            author: "Ada".to_string(),
+            body: "   ".to_string(),
+            at: "2025-01-03T00:00:00Z".to_string(),
+        };
+        let posted = handle(
+            &db.client,
+            ActivityAction::Comment("person:42".to_string(), comment),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(posted, ActivityResponse::Error(_)));
+    }
+}
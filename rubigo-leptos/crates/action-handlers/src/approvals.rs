@@ -0,0 +1,356 @@
+//! Change Request Approvals
+//!
+//! With an [`ApprovalPolicy`], the dispatcher holds changes to protected
+//! records (those carrying the policy's tag) as pending change requests
+//! instead of applying them; the caller gets
+//! [`DispatchError::PendingApproval`](crate::DispatchError). An approver
+//! then approves the request, which applies the held action, or rejects
+//! it. Notifiers hear about every step, e.g. to email the approvers.
+//!
+//! ```ignore
+//! let policy = ApprovalPolicy::new("protected", vec!["Grace Hopper".into()])
+//!     .with_notifier(Arc::new(InboxNotifier));
+//! let dispatcher = ActionDispatcher::new(db).with_approvals(policy);
+//! ```
+
+use std::sync::Arc;
+
+use actions::approvals::{review_refusal, target_entities, HELD_ACTIONS, STATUS_PENDING};
+use actions::{ChangeRequestData, ReviewData};
+use anyhow::Result;
+use async_trait::async_trait;
+use db::client::DbClient;
+use db::models::ChangeRequest;
+use db::repositories::{ChangeRequestRepository, TagRepository};
+use serde_json::Value;
+
+/// What happened to a change request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeRequestEvent {
+    /// A change was held and awaits review
+    Submitted,
+    Approved,
+    Rejected,
+    /// Approved, but applying the change failed
+    Failed,
+}
+
+/// Told about change requests as they are submitted and reviewed
+#[async_trait]
+pub trait ChangeRequestNotifier: Send + Sync {
+    async fn notify(&self, event: ChangeRequestEvent, request: &ChangeRequestData);
+}
+
+/// Which records are protected and who may approve changes to them
+#[derive(Clone)]
+pub struct ApprovalPolicy {
+    protected_tag: String,
+    approvers: Vec<String>,
+    notifiers: Vec<Arc<dyn ChangeRequestNotifier>>,
+}
+
+impl ApprovalPolicy {
+    /// Protect records tagged `protected_tag` (by name, any case), with
+    /// changes approved by one of `approvers`
+    pub fn new(protected_tag: impl Into<String>, approvers: Vec<String>) -> Self {
+        Self {
+            protected_tag: protected_tag.into(),
+            approvers,
+            notifiers: Vec::new(),
+        }
+    }
+
+    /// Tell `notifier` about change requests, after the notifiers added before it
+    pub fn with_notifier(mut self, notifier: Arc<dyn ChangeRequestNotifier>) -> Self {
+        self.notifiers.push(notifier);
+        self
+    }
+
+    pub fn approvers(&self) -> &[String] {
+        &self.approvers
+    }
+
+    pub(crate) async fn notify(&self, event: ChangeRequestEvent, request: &ChangeRequestData) {
+        tracing::info!(
+            "Change request {} {:?}: {}",
+            request.id,
+            event,
+            request.action_type
+        );
+        for notifier in &self.notifiers {
+            notifier.notify(event, request).await;
+        }
+    }
+
+    /// Protected records the action would change; empty if it can go ahead
+    pub(crate) async fn protected_targets(
+        &self,
+        db: &DbClient,
+        action_type: &str,
+        payload: &Value,
+    ) -> Result<Vec<String>> {
+        if !HELD_ACTIONS.contains(&action_type) {
+            return Ok(Vec::new());
+        }
+        let entities = target_entities(action_type, payload);
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+        let tags = TagRepository::list(db, None).await?;
+        let protected: Vec<String> = tags
+            .into_iter()
+            .filter(|tag| tag.name.eq_ignore_ascii_case(&self.protected_tag))
+            .filter_map(|tag| tag.id.map(|t| t.id.to_raw()))
+            .collect();
+        if protected.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut held: Vec<String> = TagRepository::links_for(db, &entities)
+            .await?
+            .into_iter()
+            .filter(|link| protected.contains(&link.tag_id.id.to_raw()))
+            .map(|link| link.entity)
+            .collect();
+        held.sort();
+        held.dedup();
+        Ok(held)
+    }
+}
+
+fn request_to_data(request: ChangeRequest) -> ChangeRequestData {
+    ChangeRequestData {
+        id: request.id.map(|t| t.id.to_raw()).unwrap_or_default(),
+        action_type: request.action_type,
+        payload: request.payload,
+        entities: request.entities,
+        requested_by: request.requested_by,
+        requested_at: request.requested_at,
+        status: request.status,
+        reviewed_by: request.reviewed_by,
+        review_note: request.review_note,
+        reviewed_at: request.reviewed_at,
+        error: request.error,
+    }
+}
+
+/// Requests with `status`, or all of them for `None`, newest first
+pub(crate) async fn list(db: &DbClient, status: Option<&str>) -> Result<Vec<ChangeRequestData>> {
+    let requests = ChangeRequestRepository::list(db, status).await?;
+    Ok(requests.into_iter().map(request_to_data).collect())
+}
+
+pub(crate) async fn get(db: &DbClient, id: &str) -> Result<Option<ChangeRequestData>> {
+    Ok(ChangeRequestRepository::get_by_id(db, id)
+        .await?
+        .map(request_to_data))
+}
+
+/// Keep an action as a pending change request
+pub(crate) async fn hold(
+    db: &DbClient,
+    action_type: &str,
+    payload: &Value,
+    entities: Vec<String>,
+) -> Result<ChangeRequestData> {
+    let now: Option<String> = db.query("RETURN <string> time::now()").await?.take(0)?;
+    let request = ChangeRequest {
+        id: None,
+        action_type: action_type.to_string(),
+        payload: payload.to_string(),
+        entities,
+        requested_by: None,
+        requested_at: now.unwrap_or_default(),
+        status: STATUS_PENDING.to_string(),
+        reviewed_by: None,
+        review_note: None,
+        reviewed_at: None,
+        error: None,
+    };
+    let created = ChangeRequestRepository::create(db, request).await?;
+    Ok(request_to_data(created))
+}
+
+/// The pending request `reviewer` may review, or why they can't
+pub(crate) async fn for_review(
+    db: &DbClient,
+    policy: &ApprovalPolicy,
+    id: &str,
+    reviewer: &str,
+) -> Result<Result<ChangeRequestData, String>> {
+    let Some(request) = ChangeRequestRepository::get_by_id(db, id).await? else {
+        return Ok(Err(format!("Change request not found: {}", id)));
+    };
+    let request = request_to_data(request);
+    Ok(
+        match review_refusal(&request, reviewer, &policy.approvers) {
+            Some(refusal) => Err(refusal),
+            None => Ok(request),
+        },
+    )
+}
+
+/// Record the outcome of a review
+pub(crate) async fn close(
+    db: &DbClient,
+    request: ChangeRequestData,
+    status: &str,
+    review: ReviewData,
+    error: Option<String>,
+) -> Result<ChangeRequestData> {
+    let id = request.id.clone();
+    let closed = ChangeRequest {
+        id: None,
+        action_type: request.action_type,
+        payload: request.payload,
+        entities: request.entities,
+        requested_by: request.requested_by,
+        requested_at: request.requested_at,
+        status: status.to_string(),
+        reviewed_by: Some(review.reviewer),
+        review_note: review.note.filter(|n| !n.trim().is_empty()),
+        reviewed_at: Some(review.at),
+        error,
+    };
+    let saved = ChangeRequestRepository::update(db, &id, closed)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Change request vanished: {}", id))?;
+    Ok(request_to_data(saved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionDispatcher, DispatchError};
+    use actions::approvals::{STATUS_APPROVED, STATUS_REJECTED};
+    use db::Database;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<ChangeRequestEvent>>);
+
+    #[async_trait]
+    impl ChangeRequestNotifier for Recorder {
+        async fn notify(&self, event: ChangeRequestEvent, _request: &ChangeRequestData) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    /// Dispatcher with `site:hq` protected and a "Tier" field on sites
+    async fn protected_site(recorder: Arc<Recorder>) -> ActionDispatcher {
+        let db = Database::init().await.unwrap();
+        let policy = ApprovalPolicy::new("Protected", vec!["Grace Hopper".to_string()])
+            .with_notifier(recorder);
+        let dispatcher = ActionDispatcher::new(db).with_approvals(policy);
+
+        let created = dispatcher
+            .handle_json(
+                "tag.create",
+                json!({"Create": {"name": "protected", "color": "#bf360c", "scope": "any"}}),
+            )
+            .await
+            .unwrap();
+        let tag_id = created["Created"]["id"].clone();
+        dispatcher
+            .handle_json(
+                "tag.assign",
+                json!({"Assign": {"tag_id": tag_id, "entities": ["site:hq"]}}),
+            )
+            .await
+            .unwrap();
+        dispatcher
+            .handle_json(
+                "custom_field.define",
+                json!({"Define": {"entity_kind": "site", "key": "", "label": "Tier", "kind": "text"}}),
+            )
+            .await
+            .unwrap();
+        dispatcher
+    }
+
+    fn review(reviewer: &str) -> Value {
+        json!({"reviewer": reviewer, "note": "Change window", "at": "2026-03-02T10:00:00Z"})
+    }
+
+    async fn tier(dispatcher: &ActionDispatcher, site: &str) -> Value {
+        let values = dispatcher
+            .handle_json("custom_field.values", json!({"Values": site}))
+            .await
+            .unwrap();
+        values["Values"]["tier"].clone()
+    }
+
+    #[tokio::test]
+    async fn changes_to_protected_records_wait_for_approval() {
+        let recorder = Arc::new(Recorder::default());
+        let dispatcher = protected_site(recorder.clone()).await;
+
+        // Unprotected records change right away
+        let set = json!({"SetValues": ["site:lab", {"tier": "Gold"}]});
+        dispatcher
+            .handle_json("custom_field.set_values", set)
+            .await
+            .unwrap();
+        assert_eq!(tier(&dispatcher, "site:lab").await, json!("Gold"));
+
+        let set = json!({"SetValues": ["site:hq", {"tier": "Gold"}]});
+        let id = match dispatcher.handle_json("custom_field.set_values", set).await {
+            Err(DispatchError::PendingApproval(id)) => id,
+            other => panic!("Expected the change to be held, got {:?}", other),
+        };
+        assert_eq!(tier(&dispatcher, "site:hq").await, Value::Null);
+
+        let refused = dispatcher
+            .handle_json(
+                "change_request.approve",
+                json!({"Approve": [id, review("Alan Turing")]}),
+            )
+            .await
+            .unwrap();
+        assert!(refused.get("Error").is_some());
+
+        let approved = dispatcher
+            .handle_json(
+                "change_request.approve",
+                json!({"Approve": [id, review("Grace Hopper")]}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(approved["Request"]["status"], json!(STATUS_APPROVED));
+        assert_eq!(tier(&dispatcher, "site:hq").await, json!("Gold"));
+
+        let events = recorder.0.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![ChangeRequestEvent::Submitted, ChangeRequestEvent::Approved]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejected_changes_are_dropped() {
+        let dispatcher = protected_site(Arc::new(Recorder::default())).await;
+        let set = json!({"SetValues": ["site:hq", {"tier": "Gold"}]});
+        let Err(DispatchError::PendingApproval(id)) =
+            dispatcher.handle_json("custom_field.set_values", set).await
+        else {
+            panic!("Expected the change to be held");
+        };
+
+        let rejected = dispatcher
+            .handle_json(
+                "change_request.reject",
+                json!({"Reject": [id, review("Grace Hopper")]}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rejected["Request"]["status"], json!(STATUS_REJECTED));
+        assert_eq!(tier(&dispatcher, "site:hq").await, Value::Null);
+
+        // Reviewed requests leave the queue
+        let pending = dispatcher
+            .handle_json("change_request.list", json!({"List": "pending"}))
+            .await
+            .unwrap();
+        assert_eq!(pending["List"], json!([]));
+    }
+}
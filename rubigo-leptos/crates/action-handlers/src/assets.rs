@@ -1,10 +1,17 @@
 //! Asset action handlers
 
-use actions::{AssetAction, AssetResponse, AssetData, AssetListQuery, CreateAssetData};
+use actions::{
+    AssetAction, AssetResponse, AssetData, AssetListQuery, CreateAssetData, MaintenanceData,
+    MoveAssetData, ScheduleMaintenanceData, TransitionAssetData,
+};
 use db::client::DbClient;
-use db::models::NetworkAsset;
+use db::models::{LifecycleState, MaintenanceTicket, NetworkAsset};
 use db::repositories::AssetRepository;
 use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+/// How long a deleted asset can be restored before it is purged
+const RESTORE_WINDOW_MINUTES: i64 = 2;
 
 /// Handle asset actions
 pub async fn handle(db: &DbClient, action: AssetAction) -> Result<AssetResponse> {
@@ -14,6 +21,39 @@ pub async fn handle(db: &DbClient, action: AssetAction) -> Result<AssetResponse>
         AssetAction::Create(data) => create(db, data).await,
         AssetAction::Update(id, data) => update(db, &id, data).await,
         AssetAction::Delete(id) => delete(db, &id).await,
+        AssetAction::Restore(id) => restore(db, &id).await,
+        AssetAction::Transition(id, data) => transition(db, &id, data).await,
+        AssetAction::Move(id, data) => move_asset(db, &id, data).await,
+        AssetAction::ScheduleMaintenance(data) => schedule_maintenance(db, data).await,
+        AssetAction::UpcomingMaintenance(until) => upcoming_maintenance(db, &until).await,
+    }
+}
+
+fn to_data(a: NetworkAsset, fallback_id: &str) -> AssetData {
+    let lifecycle = a.lifecycle_state().to_string();
+    AssetData {
+        id: a.id.map(|t| t.id.to_string()).unwrap_or_else(|| fallback_id.to_string()),
+        name: a.name,
+        manufacturer: a.manufacturer.unwrap_or_default(),
+        model: a.model.unwrap_or_default(),
+        serial_number: a.serial_number.unwrap_or_default(),
+        category: a.category.unwrap_or_default(),
+        status: a.status.unwrap_or_default(),
+        lifecycle,
+        warranty_end: a.warranty_end,
+        rack_id: a.rack_id.map(|r| r.id.to_raw()),
+        position_u: a.position_u.and_then(|u| u8::try_from(u).ok()),
+    }
+}
+
+fn to_maintenance_data(t: MaintenanceTicket) -> MaintenanceData {
+    MaintenanceData {
+        id: t.id.map(|t| t.id.to_string()).unwrap_or_default(),
+        asset_id: t.asset_id.id.to_string(),
+        title: t.title,
+        description: t.description,
+        scheduled_date: t.scheduled_date,
+        status: t.status.unwrap_or_else(|| "open".to_string()),
     }
 }
 
@@ -39,15 +79,7 @@ async fn list(db: &DbClient, query: AssetListQuery) -> Result<AssetResponse> {
                 true
             }
         })
-        .map(|a| AssetData {
-            id: a.id.map(|t| t.id.to_string()).unwrap_or_default(),
-            name: a.name,
-            manufacturer: a.manufacturer.unwrap_or_default(),
-            model: a.model.unwrap_or_default(),
-            serial_number: a.serial_number.unwrap_or_default(),
-            category: a.category.unwrap_or_default(),
-            status: a.status.unwrap_or_default(),
-        })
+        .map(|a| to_data(a, ""))
         .collect();
     
     // Apply pagination
@@ -67,15 +99,7 @@ async fn list(db: &DbClient, query: AssetListQuery) -> Result<AssetResponse> {
 
 async fn get(db: &DbClient, id: &str) -> Result<AssetResponse> {
     match AssetRepository::get_by_id(db, id).await? {
-        Some(a) => Ok(AssetResponse::Single(AssetData {
-            id: a.id.map(|t| t.id.to_string()).unwrap_or_else(|| id.to_string()),
-            name: a.name,
-            manufacturer: a.manufacturer.unwrap_or_default(),
-            model: a.model.unwrap_or_default(),
-            serial_number: a.serial_number.unwrap_or_default(),
-            category: a.category.unwrap_or_default(),
-            status: a.status.unwrap_or_default(),
-        })),
+        Some(a) => Ok(AssetResponse::Single(Box::new(to_data(a, id)))),
         None => Ok(AssetResponse::Error(format!("Asset not found: {}", id))),
     }
 }
@@ -96,19 +120,14 @@ async fn create(db: &DbClient, data: CreateAssetData) -> Result<AssetResponse> {
         space_id: None, // Would need to resolve from space_id string
         storage_location: data.storage_location,
         notes: data.notes,
+        lifecycle: None,
+        warranty_start: None,
+        warranty_end: None,
     };
     
     let created = AssetRepository::create(db, asset).await?;
     
-    Ok(AssetResponse::Single(AssetData {
-        id: created.id.map(|t| t.id.to_string()).unwrap_or_default(),
-        name: created.name,
-        manufacturer: created.manufacturer.unwrap_or_default(),
-        model: created.model.unwrap_or_default(),
-        serial_number: created.serial_number.unwrap_or_default(),
-        category: created.category.unwrap_or_default(),
-        status: created.status.unwrap_or_default(),
-    }))
+    Ok(AssetResponse::Single(Box::new(to_data(created, ""))))
 }
 
 async fn update(db: &DbClient, id: &str, _data: actions::UpdateAssetData) -> Result<AssetResponse> {
@@ -124,7 +143,73 @@ async fn update(db: &DbClient, id: &str, _data: actions::UpdateAssetData) -> Res
 }
 
 async fn delete(db: &DbClient, id: &str) -> Result<AssetResponse> {
-    // Note: SurrealDB delete doesn't error if not found
-    let _: Option<NetworkAsset> = db.delete(("asset", id)).await?;
+    let now = db_now(db).await?;
+    // Like a hard delete, an unknown ID is not an error
+    AssetRepository::soft_delete(db, id, now).await?;
+    // Finalize earlier deletions whose restore window has closed
+    AssetRepository::purge_deleted(db, restore_cutoff(now)).await?;
     Ok(AssetResponse::Success)
 }
+
+async fn restore(db: &DbClient, id: &str) -> Result<AssetResponse> {
+    let cutoff = restore_cutoff(db_now(db).await?);
+    match AssetRepository::restore(db, id, cutoff).await? {
+        Some(asset) => Ok(AssetResponse::Single(Box::new(to_data(asset, id)))),
+        None => Ok(AssetResponse::Error(format!("Asset can no longer be restored: {}", id))),
+    }
+}
+
+/// The database's clock
+///
+/// No clock in this crate. Fails rather than guess, since a wrong time
+/// would purge deletions that are still restorable.
+async fn db_now(db: &DbClient) -> Result<DateTime<Utc>> {
+    let now: Option<String> = db.query("RETURN <string> time::now()").await?.take(0)?;
+    let now = now.ok_or_else(|| anyhow::anyhow!("Database returned no time"))?;
+    Ok(DateTime::parse_from_rfc3339(&now)?.with_timezone(&Utc))
+}
+
+/// Oldest deletion time still inside the restore window
+fn restore_cutoff(now: DateTime<Utc>) -> DateTime<Utc> {
+    now - Duration::minutes(RESTORE_WINDOW_MINUTES)
+}
+
+async fn move_asset(db: &DbClient, id: &str, data: MoveAssetData) -> Result<AssetResponse> {
+    let position_u = data.position_u.map(i32::from);
+    match AssetRepository::relocate(db, id, data.rack_id.as_deref(), position_u).await? {
+        Some(asset) => Ok(AssetResponse::Single(Box::new(to_data(asset, id)))),
+        None => Ok(AssetResponse::Error(format!("Asset not found: {}", id))),
+    }
+}
+
+async fn transition(db: &DbClient, id: &str, data: TransitionAssetData) -> Result<AssetResponse> {
+    let Some(to) = LifecycleState::parse(&data.state) else {
+        return Ok(AssetResponse::Error(format!("Unknown lifecycle state: {}", data.state)));
+    };
+    let now = db_now(db).await?.to_rfc3339();
+    match AssetRepository::transition(db, id, to, data.note, &now).await {
+        Ok(asset) => Ok(AssetResponse::Single(Box::new(to_data(asset, id)))),
+        Err(e) => Ok(AssetResponse::Error(e.to_string())),
+    }
+}
+
+async fn schedule_maintenance(db: &DbClient, data: ScheduleMaintenanceData) -> Result<AssetResponse> {
+    if AssetRepository::get_by_id(db, &data.asset_id).await?.is_none() {
+        return Ok(AssetResponse::Error(format!("Asset not found: {}", data.asset_id)));
+    }
+    let ticket = MaintenanceTicket::open(
+        &data.asset_id,
+        data.title,
+        data.scheduled_date,
+        data.description,
+    );
+    let created = AssetRepository::create_ticket(db, ticket).await?;
+    Ok(AssetResponse::Maintenance(vec![to_maintenance_data(created)]))
+}
+
+async fn upcoming_maintenance(db: &DbClient, until: &str) -> Result<AssetResponse> {
+    let tickets = AssetRepository::upcoming_maintenance(db, until).await?;
+    Ok(AssetResponse::Maintenance(
+        tickets.into_iter().map(to_maintenance_data).collect(),
+    ))
+}
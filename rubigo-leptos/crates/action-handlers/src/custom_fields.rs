@@ -0,0 +1,256 @@
+//! Custom field action handlers
+
+use std::collections::BTreeMap;
+
+use actions::custom_fields::{field_key, merge_values, validate_definition};
+use actions::{CustomFieldAction, CustomFieldData, CustomFieldResponse};
+use anyhow::Result;
+use db::client::DbClient;
+use db::models::CustomField;
+use db::repositories::CustomFieldRepository;
+
+/// Handle custom field actions
+pub async fn handle(db: &DbClient, action: CustomFieldAction) -> Result<CustomFieldResponse> {
+    match action {
+        CustomFieldAction::Definitions(kind) => definitions(db, &kind).await,
+        CustomFieldAction::Define(data) => define(db, data).await,
+        CustomFieldAction::RemoveDefinition(id) => remove(db, &id).await,
+        CustomFieldAction::Values(entity) => values(db, &entity).await,
+        CustomFieldAction::SetValues(entity, changes) => set_values(db, &entity, changes).await,
+    }
+}
+
+fn field_to_data(field: CustomField) -> CustomFieldData {
+    CustomFieldData {
+        id: field.id.map(|t| t.id.to_raw()).unwrap_or_default(),
+        entity_kind: field.entity_kind,
+        key: field.key,
+        label: field.label,
+        kind: field.kind,
+        options: field.options,
+        required: field.required,
+        position: field.position,
+    }
+}
+
+async fn defined_for(db: &DbClient, kind: &str) -> Result<Vec<CustomFieldData>> {
+    let fields = CustomFieldRepository::definitions(db, kind).await?;
+    Ok(fields.into_iter().map(field_to_data).collect())
+}
+
+/// Kind of record an entity key is for, e.g. `asset` for `asset:7`
+fn entity_kind(entity: &str) -> Option<&str> {
+    match entity.split_once(':') {
+        Some((kind, id)) if !kind.is_empty() && !id.is_empty() => Some(kind),
+        _ => None,
+    }
+}
+
+async fn definitions(db: &DbClient, kind: &str) -> Result<CustomFieldResponse> {
+    Ok(CustomFieldResponse::Definitions(
+        defined_for(db, kind).await?,
+    ))
+}
+
+/// Add a field, or change a saved one
+///
+/// A saved field keeps its key and kind of record, so the values already
+/// stored under it stay with it.
+async fn define(db: &DbClient, mut data: CustomFieldData) -> Result<CustomFieldResponse> {
+    if !data.id.is_empty() {
+        let Some(saved) = CustomFieldRepository::get_by_id(db, &data.id).await? else {
+            return Ok(CustomFieldResponse::Error(format!(
+                "Custom field not found: {}",
+                data.id
+            )));
+        };
+        data.entity_kind = saved.entity_kind;
+        data.key = saved.key;
+    } else if data.key.is_empty() {
+        data.key = field_key(&data.label);
+    }
+    data.label = data.label.trim().to_string();
+    data.options = data
+        .options
+        .iter()
+        .map(|o| o.trim().to_string())
+        .filter(|o| !o.is_empty())
+        .collect();
+    if data.kind != "select" {
+        data.options.clear();
+    }
+
+    let existing = defined_for(db, &data.entity_kind).await?;
+    if let Some(error) = validate_definition(&data, &existing) {
+        return Ok(CustomFieldResponse::Error(error));
+    }
+    let field = CustomField {
+        id: None,
+        entity_kind: data.entity_kind,
+        key: data.key,
+        label: data.label,
+        kind: data.kind,
+        options: data.options,
+        required: data.required,
+        position: data.position,
+    };
+    let saved = if data.id.is_empty() {
+        Some(CustomFieldRepository::create(db, field).await?)
+    } else {
+        CustomFieldRepository::update(db, &data.id, field).await?
+    };
+    match saved {
+        Some(saved) => Ok(CustomFieldResponse::Defined(field_to_data(saved))),
+        None => Ok(CustomFieldResponse::Error(format!(
+            "Custom field not found: {}",
+            data.id
+        ))),
+    }
+}
+
+async fn remove(db: &DbClient, id: &str) -> Result<CustomFieldResponse> {
+    if CustomFieldRepository::delete(db, id).await? {
+        Ok(CustomFieldResponse::Success)
+    } else {
+        Ok(CustomFieldResponse::Error(format!(
+            "Custom field not found: {}",
+            id
+        )))
+    }
+}
+
+async fn values(db: &DbClient, entity: &str) -> Result<CustomFieldResponse> {
+    let Some(kind) = entity_kind(entity) else {
+        return Ok(CustomFieldResponse::Error(format!(
+            "Not an entity key: {}",
+            entity
+        )));
+    };
+    let fields = defined_for(db, kind).await?;
+    let mut stored = CustomFieldRepository::values(db, entity).await?;
+    // Values of removed fields stay stored but aren't shown
+    stored.retain(|key, _| fields.iter().any(|f| &f.key == key));
+    Ok(CustomFieldResponse::Values(stored))
+}
+
+async fn set_values(
+    db: &DbClient,
+    entity: &str,
+    changes: BTreeMap<String, String>,
+) -> Result<CustomFieldResponse> {
+    let Some(kind) = entity_kind(entity) else {
+        return Ok(CustomFieldResponse::Error(format!(
+            "Not an entity key: {}",
+            entity
+        )));
+    };
+    let fields = defined_for(db, kind).await?;
+    let stored = CustomFieldRepository::values(db, entity).await?;
+    match merge_values(&fields, &stored, &changes) {
+        Ok(merged) => {
+            CustomFieldRepository::set_values(db, entity, merged).await?;
+            Ok(CustomFieldResponse::Success)
+        }
+        Err(error) => Ok(CustomFieldResponse::Error(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::Database;
+
+    fn field(label: &str, kind: &str) -> CustomFieldData {
+        CustomFieldData {
+            id: String::new(),
+            entity_kind: "asset".to_string(),
+            key: String::new(),
+            label: label.to_string(),
+            kind: kind.to_string(),
+            options: Vec::new(),
+            required: false,
+            position: 0,
+        }
+    }
+
+    async fn define_field(db: &DbClient, data: CustomFieldData) -> CustomFieldData {
+        match handle(db, CustomFieldAction::Define(data)).await.unwrap() {
+            CustomFieldResponse::Defined(field) => field,
+            other => panic!("Expected a field, got {:?}", other),
+        }
+    }
+
+    fn changes(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn saved_fields_keep_their_key() {
+        let db = Database::init().await.unwrap();
+        let mut warranty = define_field(&db.client, field("Warranty ends", "date")).await;
+        assert_eq!(warranty.key, "warranty_ends");
+
+        warranty.label = "Warranty expiry".to_string();
+        warranty.key = "expiry".to_string();
+        let renamed = define_field(&db.client, warranty).await;
+        assert_eq!(renamed.key, "warranty_ends");
+        assert_eq!(renamed.label, "Warranty expiry");
+
+        let refused = handle(
+            &db.client,
+            CustomFieldAction::Define(field("warranty EXPIRY", "text")),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(refused, CustomFieldResponse::Error(_)));
+    }
+
+    #[tokio::test]
+    async fn values_follow_the_definitions() {
+        let db = Database::init().await.unwrap();
+        let cost = define_field(&db.client, field("Cost", "number")).await;
+        let mut tier = field("Tier", "select");
+        tier.options = vec!["Gold".to_string(), " Silver ".to_string(), "".to_string()];
+        let tier = define_field(&db.client, tier).await;
+        assert_eq!(tier.options, vec!["Gold", "Silver"]);
+
+        let set = CustomFieldAction::SetValues(
+            "asset:sw1".to_string(),
+            changes(&[("cost", "12.50"), ("tier", "Gold")]),
+        );
+        assert!(matches!(
+            handle(&db.client, set).await.unwrap(),
+            CustomFieldResponse::Success
+        ));
+        let bad =
+            CustomFieldAction::SetValues("asset:sw1".to_string(), changes(&[("tier", "Bronze")]));
+        assert!(matches!(
+            handle(&db.client, bad).await.unwrap(),
+            CustomFieldResponse::Error(_)
+        ));
+
+        let read = || {
+            handle(
+                &db.client,
+                CustomFieldAction::Values("asset:sw1".to_string()),
+            )
+        };
+        match read().await.unwrap() {
+            CustomFieldResponse::Values(values) => {
+                assert_eq!(values, changes(&[("cost", "12.5"), ("tier", "Gold")]))
+            }
+            other => panic!("Expected values, got {:?}", other),
+        }
+
+        handle(&db.client, CustomFieldAction::RemoveDefinition(cost.id))
+            .await
+            .unwrap();
+        match read().await.unwrap() {
+            CustomFieldResponse::Values(values) => assert_eq!(values, changes(&[("tier", "Gold")])),
+            other => panic!("Expected values, got {:?}", other),
+        }
+    }
+}
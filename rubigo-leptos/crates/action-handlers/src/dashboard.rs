@@ -0,0 +1,220 @@
+//! Dashboard action handlers
+
+use std::collections::{BTreeMap, HashMap};
+
+use actions::{
+    ChartData, DashboardAction, DashboardResponse, DashboardSnapshot, EventData, SitePointData,
+    StatData, WidgetData,
+};
+use anyhow::Result;
+use db::client::DbClient;
+use db::models::DashboardWidget;
+use db::repositories::{AssetRepository, DashboardRepository, GeoRepository, PersonRepository};
+
+/// Lifecycle changes listed under recent events
+const RECENT_EVENT_LIMIT: usize = 10;
+
+/// Widgets allowed on one dashboard
+const MAX_WIDGETS: usize = 24;
+
+/// Handle dashboard actions
+pub async fn handle(db: &DbClient, action: DashboardAction) -> Result<DashboardResponse> {
+    match action {
+        DashboardAction::GetLayout(user_id) => get_layout(db, &user_id).await,
+        DashboardAction::SaveLayout(user_id, widgets) => save_layout(db, &user_id, widgets).await,
+        DashboardAction::Snapshot => snapshot(db).await,
+    }
+}
+
+async fn get_layout(db: &DbClient, user_id: &str) -> Result<DashboardResponse> {
+    let layout = DashboardRepository::get_layout(db, user_id).await?;
+    Ok(DashboardResponse::Layout(layout.map(|l| {
+        l.widgets
+            .into_iter()
+            .map(|w| WidgetData {
+                id: w.id,
+                kind: w.kind,
+                metric: w.metric,
+            })
+            .collect()
+    })))
+}
+
+async fn save_layout(
+    db: &DbClient,
+    user_id: &str,
+    widgets: Vec<WidgetData>,
+) -> Result<DashboardResponse> {
+    if user_id.is_empty() {
+        return Ok(DashboardResponse::Error(
+            "A user is required to save a dashboard".to_string(),
+        ));
+    }
+    if widgets.len() > MAX_WIDGETS {
+        return Ok(DashboardResponse::Error(format!(
+            "A dashboard holds at most {} widgets",
+            MAX_WIDGETS
+        )));
+    }
+    let widgets = widgets
+        .into_iter()
+        .map(|w| DashboardWidget {
+            id: w.id,
+            kind: w.kind,
+            metric: w.metric,
+        })
+        .collect();
+    DashboardRepository::save_layout(db, user_id, widgets).await?;
+    Ok(DashboardResponse::Success)
+}
+
+/// Label/count pairs, largest first then by label
+fn tally(values: impl Iterator<Item = String>) -> Vec<(String, u64)> {
+    let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    let mut points: Vec<(String, u64)> = counts.into_iter().collect();
+    points.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    points
+}
+
+fn stat(key: &str, label: &str, value: usize) -> StatData {
+    StatData {
+        key: key.to_string(),
+        label: label.to_string(),
+        value: value as u64,
+    }
+}
+
+async fn snapshot(db: &DbClient) -> Result<DashboardResponse> {
+    let sites = GeoRepository::list_sites(db).await?;
+    let buildings = GeoRepository::list_buildings(db).await?;
+    let spaces = GeoRepository::list_spaces(db).await?;
+    let assets = AssetRepository::list_all(db).await?;
+    let people = PersonRepository::list_all(db).await?;
+    let open_maintenance = AssetRepository::upcoming_maintenance(db, "9999-12-31").await?;
+    let transitions = AssetRepository::recent_transitions(db, RECENT_EVENT_LIMIT).await?;
+
+    let stats = vec![
+        stat("sites", "Sites", sites.len()),
+        stat("buildings", "Buildings", buildings.len()),
+        stat("spaces", "Spaces", spaces.len()),
+        stat("assets", "Assets", assets.len()),
+        stat("personnel", "Personnel", people.len()),
+        stat(
+            "open_maintenance",
+            "Open Maintenance",
+            open_maintenance.len(),
+        ),
+    ];
+
+    let unknown = || "unknown".to_string();
+    let charts = vec![
+        ChartData {
+            key: "assets_by_status".to_string(),
+            title: "Assets by Status".to_string(),
+            points: tally(
+                assets
+                    .iter()
+                    .map(|a| a.status.clone().unwrap_or_else(unknown)),
+            ),
+        },
+        ChartData {
+            key: "assets_by_category".to_string(),
+            title: "Assets by Category".to_string(),
+            points: tally(
+                assets
+                    .iter()
+                    .map(|a| a.category.clone().unwrap_or_else(unknown)),
+            ),
+        },
+        ChartData {
+            key: "people_by_department".to_string(),
+            title: "People by Department".to_string(),
+            points: tally(people.iter().map(|p| p.department.clone())),
+        },
+    ];
+
+    let asset_names: HashMap<String, &str> = assets
+        .iter()
+        .filter_map(|a| Some((a.id.as_ref()?.id.to_raw(), a.name.as_str())))
+        .collect();
+    let recent_events = transitions
+        .into_iter()
+        .map(|t| {
+            let key = t.asset_id.id.to_raw();
+            let name = asset_names.get(&key).copied().unwrap_or(key.as_str());
+            EventData {
+                title: format!("{} moved to {}", name, t.to),
+                detail: t.note,
+                at: t.at,
+            }
+        })
+        .collect();
+
+    let sites = sites
+        .into_iter()
+        .map(|s| SitePointData {
+            name: s.name,
+            lat: s.lat,
+            lon: s.lon,
+        })
+        .collect();
+
+    Ok(DashboardResponse::Snapshot(DashboardSnapshot {
+        stats,
+        charts,
+        recent_events,
+        sites,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::Database;
+
+    #[test]
+    fn tally_orders_by_count() {
+        let points = tally(
+            [
+                "active", "storage", "active", "retired", "storage", "active",
+            ]
+            .into_iter()
+            .map(String::from),
+        );
+        assert_eq!(points[0], ("active".to_string(), 3));
+        assert_eq!(points[1], ("storage".to_string(), 2));
+    }
+
+    #[tokio::test]
+    async fn layout_round_trips() {
+        let db = Database::init().await.unwrap();
+
+        let empty = handle(&db.client, DashboardAction::GetLayout("abc123".to_string()))
+            .await
+            .unwrap();
+        assert!(matches!(empty, DashboardResponse::Layout(None)));
+
+        let widgets = vec![WidgetData {
+            id: "sites".to_string(),
+            kind: "stat".to_string(),
+            metric: Some("sites".to_string()),
+        }];
+        handle(
+            &db.client,
+            DashboardAction::SaveLayout("abc123".to_string(), widgets.clone()),
+        )
+        .await
+        .unwrap();
+
+        match handle(&db.client, DashboardAction::GetLayout("abc123".to_string()))
+            .await
+            .unwrap()
+        {
+            DashboardResponse::Layout(Some(saved)) => assert_eq!(saved, widgets),
+            other => panic!("Expected saved layout, got {:?}", other),
+        }
+    }
+}
@@ -3,12 +3,32 @@
 //! Routes actions to the appropriate handler based on action type.
 
 use crate::personnel;
+use crate::approvals::{self, ApprovalPolicy, ChangeRequestEvent};
+use crate::activity;
 use crate::assets;
-use actions::{PersonnelAction, PersonnelResponse, AssetAction, AssetResponse};
+use crate::custom_fields;
+use crate::dashboard;
+use crate::tags;
+use crate::training::{self, TrainingMode};
+use crate::hooks::ActionHook;
+use crate::plugin::{split_action_type, PluginRegistry};
+use actions::{
+    PersonnelAction, PersonnelResponse, AssetAction, AssetResponse, Codec, DashboardAction, DashboardResponse,
+    ActivityAction, ActivityResponse, TagAction, TagResponse, CustomFieldAction, CustomFieldResponse,
+    ChangeRequestAction, ChangeRequestResponse, TrainingAction, TrainingResponse, BatchAction,
+    BatchResponse, BatchRowResult,
+};
 use db::Database;
 use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 use thiserror::Error;
 
+/// How deep follow-up actions may chain before they are dropped, so two
+/// rules that trigger each other can't loop forever
+pub const MAX_FOLLOW_UP_DEPTH: usize = 4;
+
 #[derive(Error, Debug)]
 pub enum DispatchError {
     #[error("Unknown action type: {0}")]
@@ -19,17 +39,136 @@ pub enum DispatchError {
     Serialize(String),
     #[error("Database error: {0}")]
     Database(String),
+    #[error("Plugin error: {0}")]
+    Plugin(String),
+    #[error("Rejected: {0}")]
+    Rejected(String),
+    /// The action changes protected records and was kept as the change
+    /// request with this ID
+    #[error("Held for approval as change request {0}")]
+    PendingApproval(String),
+}
+
+/// A response encoded for the transport
+#[derive(Debug, Clone)]
+pub struct EncodedResponse {
+    /// Codec actually used; JSON when the requested one can't encode the response
+    pub codec: Codec,
+    pub body: Vec<u8>,
+}
+
+/// A routed action's response, kept typed until it is encoded
+enum Reply {
+    Personnel(PersonnelResponse),
+    Asset(AssetResponse),
+    Dashboard(DashboardResponse),
+    Activity(ActivityResponse),
+    Tag(TagResponse),
+    CustomField(CustomFieldResponse),
+    ChangeRequest(ChangeRequestResponse),
+    Training(TrainingResponse),
+    Batch(BatchResponse),
+    /// Plugin responses only exist as JSON
+    Json(Value),
+}
+
+impl Reply {
+    fn to_value(&self) -> Result<Value, DispatchError> {
+        let value = match self {
+            Reply::Personnel(r) => serde_json::to_value(r),
+            Reply::Asset(r) => serde_json::to_value(r),
+            Reply::Dashboard(r) => serde_json::to_value(r),
+            Reply::Activity(r) => serde_json::to_value(r),
+            Reply::Tag(r) => serde_json::to_value(r),
+            Reply::CustomField(r) => serde_json::to_value(r),
+            Reply::ChangeRequest(r) => serde_json::to_value(r),
+            Reply::Training(r) => serde_json::to_value(r),
+            Reply::Batch(r) => serde_json::to_value(r),
+            Reply::Json(v) => return Ok(v.clone()),
+        };
+        value.map_err(|e| DispatchError::Serialize(e.to_string()))
+    }
+
+    fn into_value(self) -> Result<Value, DispatchError> {
+        match self {
+            Reply::Json(v) => Ok(v),
+            typed => typed.to_value(),
+        }
+    }
+
+    fn encode(&self, codec: Codec) -> Result<EncodedResponse, DispatchError> {
+        // Binary codecs need the response type, which JSON values have lost
+        let codec = match self {
+            Reply::Json(_) => Codec::Json,
+            _ => codec,
+        };
+        let body = match self {
+            Reply::Personnel(r) => codec.encode(r),
+            Reply::Asset(r) => codec.encode(r),
+            Reply::Dashboard(r) => codec.encode(r),
+            Reply::Activity(r) => codec.encode(r),
+            Reply::Tag(r) => codec.encode(r),
+            Reply::CustomField(r) => codec.encode(r),
+            Reply::ChangeRequest(r) => codec.encode(r),
+            Reply::Training(r) => codec.encode(r),
+            Reply::Batch(r) => codec.encode(r),
+            Reply::Json(v) => codec.encode(v),
+        }
+        .map_err(|e| DispatchError::Serialize(e.to_string()))?;
+        Ok(EncodedResponse { codec, body })
+    }
 }
 
 /// Server-side action dispatcher
 pub struct ActionDispatcher {
     db: Database,
+    plugins: PluginRegistry,
+    hooks: Vec<Arc<dyn ActionHook>>,
+    approvals: Option<ApprovalPolicy>,
+    training: Option<TrainingMode>,
 }
 
 impl ActionDispatcher {
     /// Create a new dispatcher with a database connection
     pub fn new(db: Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            plugins: PluginRegistry::new(),
+            hooks: Vec::new(),
+            approvals: None,
+            training: None,
+        }
+    }
+    
+    /// Create a dispatcher that also routes the plugins' namespaces,
+    /// applying their pending migrations first
+    pub async fn with_plugins(db: Database, plugins: PluginRegistry) -> Result<Self, DispatchError> {
+        plugins.migrate(&db.client).await?;
+        Ok(Self {
+            db,
+            plugins,
+            hooks: Vec::new(),
+            approvals: None,
+            training: None,
+        })
+    }
+    
+    /// Run `hook` around every action, after the hooks added before it
+    pub fn with_hook(mut self, hook: Arc<dyn ActionHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+    
+    /// Hold changes to the records `policy` protects until they are approved
+    pub fn with_approvals(mut self, policy: ApprovalPolicy) -> Self {
+        self.approvals = Some(policy);
+        self
+    }
+    
+    /// Offer the scenario's exercises and track trainees' progress through them
+    pub fn with_training(mut self, training: TrainingMode) -> Self {
+        self.training = Some(training);
+        self
     }
     
     /// Get a reference to the database
@@ -51,29 +190,305 @@ impl ActionDispatcher {
             .map_err(|e| DispatchError::Database(e.to_string()))
     }
     
+    /// Handle a dashboard action
+    pub async fn handle_dashboard(&self, action: DashboardAction) -> Result<DashboardResponse, DispatchError> {
+        dashboard::handle(&self.db.client, action)
+            .await
+            .map_err(|e| DispatchError::Database(e.to_string()))
+    }
+    
+    /// Handle an activity action
+    pub async fn handle_activity(&self, action: ActivityAction) -> Result<ActivityResponse, DispatchError> {
+        activity::handle(&self.db.client, action)
+            .await
+            .map_err(|e| DispatchError::Database(e.to_string()))
+    }
+    
+    /// Handle a tag action
+    pub async fn handle_tag(&self, action: TagAction) -> Result<TagResponse, DispatchError> {
+        tags::handle(&self.db.client, action)
+            .await
+            .map_err(|e| DispatchError::Database(e.to_string()))
+    }
+    
+    /// Handle a custom field action
+    pub async fn handle_custom_field(&self, action: CustomFieldAction) -> Result<CustomFieldResponse, DispatchError> {
+        custom_fields::handle(&self.db.client, action)
+            .await
+            .map_err(|e| DispatchError::Database(e.to_string()))
+    }
+    
+    /// Handle a change request action
+    ///
+    /// Approving a request applies the change it holds, through the hooks
+    /// as usual; if that fails or is refused the request is marked failed
+    /// instead.
+    pub async fn handle_change_request(
+        &self,
+        action: ChangeRequestAction,
+    ) -> Result<ChangeRequestResponse, DispatchError> {
+        let db_error = |e: anyhow::Error| DispatchError::Database(e.to_string());
+        let (id, review, approve) = match action {
+            ChangeRequestAction::List(status) => {
+                let requests = approvals::list(&self.db.client, status.as_deref()).await.map_err(db_error)?;
+                return Ok(ChangeRequestResponse::List(requests));
+            }
+            ChangeRequestAction::Get(id) => {
+                return Ok(match approvals::get(&self.db.client, &id).await.map_err(db_error)? {
+                    Some(request) => ChangeRequestResponse::Request(Box::new(request)),
+                    None => ChangeRequestResponse::Error(format!("Change request not found: {}", id)),
+                });
+            }
+            ChangeRequestAction::Approve(id, review) => (id, review, true),
+            ChangeRequestAction::Reject(id, review) => (id, review, false),
+        };
+        
+        let Some(policy) = &self.approvals else {
+            return Ok(ChangeRequestResponse::Error("Change requests are not enabled".to_string()));
+        };
+        let request = match approvals::for_review(&self.db.client, policy, &id, &review.reviewer)
+            .await
+            .map_err(db_error)?
+        {
+            Ok(request) => request,
+            Err(refusal) => return Ok(ChangeRequestResponse::Error(refusal)),
+        };
+        
+        let (status, event, error) = if approve {
+            // Handlers refuse changes with an `Error` response rather than failing
+            let applied = match serde_json::from_str::<Value>(&request.payload) {
+                Ok(payload) => match self.dispatch(request.action_type.clone(), payload, 0, true).await {
+                    Ok(reply) => match reply.into_value()?.get("Error") {
+                        Some(refusal) => Err(DispatchError::Rejected(refusal.as_str().unwrap_or_default().to_string())),
+                        None => Ok(()),
+                    },
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(DispatchError::Deserialize(e.to_string())),
+            };
+            match applied {
+                Ok(()) => (actions::approvals::STATUS_APPROVED, ChangeRequestEvent::Approved, None),
+                Err(e) => (actions::approvals::STATUS_FAILED, ChangeRequestEvent::Failed, Some(e.to_string())),
+            }
+        } else {
+            (actions::approvals::STATUS_REJECTED, ChangeRequestEvent::Rejected, None)
+        };
+        let closed = approvals::close(&self.db.client, request, status, review, error)
+            .await
+            .map_err(db_error)?;
+        policy.notify(event, &closed).await;
+        Ok(ChangeRequestResponse::Request(Box::new(closed)))
+    }
+    
+    /// Handle a training action
+    pub async fn handle_training(&self, action: TrainingAction) -> Result<TrainingResponse, DispatchError> {
+        let Some(training) = &self.training else {
+            return Ok(TrainingResponse::Error("Training mode is not enabled".to_string()));
+        };
+        training::handle(&self.db.client, training, action)
+            .await
+            .map_err(|e| DispatchError::Database(e.to_string()))
+    }
+    
+    /// Handle a batch action
+    ///
+    /// Each record's action is dispatched on its own, through the hooks and
+    /// approvals; one failing, refused or held for approval is reported
+    /// against its row and the rest still run.
+    pub async fn handle_batch(&self, action: BatchAction) -> Result<BatchResponse, DispatchError> {
+        let BatchAction::Run(request) = action;
+        let steps = match actions::batch::expand(&request) {
+            Ok(steps) => steps,
+            Err(refusal) => return Ok(BatchResponse::Error(refusal)),
+        };
+        let mut results = Vec::with_capacity(steps.len());
+        for step in steps {
+            let error = match self.dispatch(step.action_type.to_string(), step.payload, 0, false).await {
+                Ok(reply) => reply
+                    .into_value()?
+                    .get("Error")
+                    .map(|refusal| refusal.as_str().unwrap_or_default().to_string()),
+                Err(e) => Some(e.to_string()),
+            };
+            results.push(BatchRowResult { row: step.row, error });
+        }
+        Ok(BatchResponse::Results(results))
+    }
+    
     /// Handle a raw JSON action by action type string
     /// Returns JSON response
+    ///
+    /// Hooks see the action first and may reject it; once it succeeded their
+    /// follow-up actions run in turn. A failing follow-up is logged but does
+    /// not fail the original action, which has already been applied.
     pub async fn handle_json(&self, action_type: &str, payload: Value) -> Result<Value, DispatchError> {
+        self.dispatch(action_type.to_string(), payload, 0, false).await?.into_value()
+    }
+    
+    /// Handle a raw JSON action, encoding the response with `codec`
+    ///
+    /// Brokers negotiate the codec (see [`Codec::negotiate`]). Responses
+    /// that only exist as JSON, such as plugin responses, are encoded as JSON
+    /// whatever was asked for; the returned codec says which was used.
+    pub async fn handle_encoded(
+        &self,
+        action_type: &str,
+        payload: Value,
+        codec: Codec,
+    ) -> Result<EncodedResponse, DispatchError> {
+        self.dispatch(action_type.to_string(), payload, 0, false).await?.encode(codec)
+    }
+    
+    /// Run an action and its follow-ups; `approved` actions are applied
+    /// even if they change protected records
+    fn dispatch(
+        &self,
+        action_type: String,
+        payload: Value,
+        depth: usize,
+        approved: bool,
+    ) -> Pin<Box<dyn Future<Output = Result<Reply, DispatchError>> + Send + '_>> {
+        Box::pin(async move {
+            for hook in &self.hooks {
+                hook.before(&action_type, &payload)
+                    .await
+                    .map_err(DispatchError::Rejected)?;
+            }
+            
+            if let Some(policy) = self.approvals.as_ref().filter(|_| !approved) {
+                let held = policy
+                    .protected_targets(&self.db.client, &action_type, &payload)
+                    .await
+                    .map_err(|e| DispatchError::Database(e.to_string()))?;
+                if !held.is_empty() {
+                    let request = approvals::hold(&self.db.client, &action_type, &payload, held)
+                        .await
+                        .map_err(|e| DispatchError::Database(e.to_string()))?;
+                    policy.notify(ChangeRequestEvent::Submitted, &request).await;
+                    return Err(DispatchError::PendingApproval(request.id));
+                }
+            }
+            
+            let reply = self.route(&action_type, payload.clone()).await?;
+            
+            // Only what the trainee did counts, not follow-ups or approved changes
+            if let Some(training) = self.training.as_ref().filter(|_| depth == 0 && !approved) {
+                let refused = reply.to_value()?.get("Error").is_some();
+                if !action_type.starts_with("training.") && !refused {
+                    if let Err(e) = training::observe(&self.db.client, training, &action_type, &payload).await {
+                        tracing::warn!("Could not record training progress for {}: {}", action_type, e);
+                    }
+                }
+            }
+            
+            if self.hooks.is_empty() {
+                return Ok(reply);
+            }
+            
+            let response = reply.to_value()?;
+            for hook in &self.hooks {
+                for follow_up in hook.after(&action_type, &payload, &response).await {
+                    if depth >= MAX_FOLLOW_UP_DEPTH {
+                        tracing::warn!(
+                            "Dropping follow-up {} of {}: chained too deep",
+                            follow_up.action_type, action_type
+                        );
+                        continue;
+                    }
+                    if let Err(e) = self.dispatch(follow_up.action_type.clone(), follow_up.payload, depth + 1, false).await {
+                        tracing::warn!("Follow-up {} of {} failed: {}", follow_up.action_type, action_type, e);
+                    }
+                }
+            }
+            Ok(reply)
+        })
+    }
+    
+    /// Run an action without hooks
+    async fn route(&self, action_type: &str, payload: Value) -> Result<Reply, DispatchError> {
         match action_type {
             // Personnel actions
             "personnel.list" | "personnel.get" => {
                 let action: PersonnelAction = serde_json::from_value(payload)
                     .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
-                let response = self.handle_personnel(action).await?;
-                serde_json::to_value(response)
-                    .map_err(|e| DispatchError::Serialize(e.to_string()))
+                self.handle_personnel(action).await.map(Reply::Personnel)
             }
             
             // Asset actions
-            "asset.list" | "asset.get" | "asset.create" | "asset.update" | "asset.delete" => {
+            "asset.list" | "asset.get" | "asset.create" | "asset.update" | "asset.delete"
+            | "asset.restore" | "asset.transition" | "asset.move"
+            | "asset.schedule_maintenance" | "asset.upcoming_maintenance" => {
                 let action: AssetAction = serde_json::from_value(payload)
                     .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
-                let response = self.handle_asset(action).await?;
-                serde_json::to_value(response)
-                    .map_err(|e| DispatchError::Serialize(e.to_string()))
+                self.handle_asset(action).await.map(Reply::Asset)
+            }
+            
+            // Dashboard actions
+            "dashboard.get_layout" | "dashboard.save_layout" | "dashboard.snapshot" => {
+                let action: DashboardAction = serde_json::from_value(payload)
+                    .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
+                self.handle_dashboard(action).await.map(Reply::Dashboard)
+            }
+            
+            // Activity actions
+            "activity.feed" | "activity.comment" => {
+                let action: ActivityAction = serde_json::from_value(payload)
+                    .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
+                self.handle_activity(action).await.map(Reply::Activity)
+            }
+            
+            // Tag actions
+            "tag.list" | "tag.create" | "tag.delete" | "tag.for_entities" | "tag.assign"
+            | "tag.unassign" => {
+                let action: TagAction = serde_json::from_value(payload)
+                    .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
+                self.handle_tag(action).await.map(Reply::Tag)
+            }
+            
+            // Custom field actions
+            "custom_field.definitions" | "custom_field.define" | "custom_field.remove_definition"
+            | "custom_field.values" | "custom_field.set_values" => {
+                let action: CustomFieldAction = serde_json::from_value(payload)
+                    .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
+                self.handle_custom_field(action).await.map(Reply::CustomField)
+            }
+            
+            // Change request actions
+            "change_request.list" | "change_request.get" | "change_request.approve" | "change_request.reject" => {
+                let action: ChangeRequestAction = serde_json::from_value(payload)
+                    .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
+                self.handle_change_request(action).await.map(Reply::ChangeRequest)
+            }
+            
+            // Training actions
+            "training.exercises" | "training.start" | "training.hint" | "training.progress"
+            | "training.finish" => {
+                let action: TrainingAction = serde_json::from_value(payload)
+                    .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
+                self.handle_training(action).await.map(Reply::Training)
+            }
+            
+            // Batch actions, run as one action per record
+            "batch.run" => {
+                let action: BatchAction = serde_json::from_value(payload)
+                    .map_err(|e| DispatchError::Deserialize(e.to_string()))?;
+                self.handle_batch(action).await.map(Reply::Batch)
             }
             
-            _ => Err(DispatchError::UnknownAction(action_type.to_string())),
+            // Plugin namespaces
+            _ => {
+                let (namespace, action) = split_action_type(action_type)
+                    .ok_or_else(|| DispatchError::UnknownAction(action_type.to_string()))?;
+                let plugin = self
+                    .plugins
+                    .get(namespace)
+                    .ok_or_else(|| DispatchError::UnknownAction(action_type.to_string()))?;
+                plugin
+                    .handle(&self.db.client, action, payload)
+                    .await
+                    .map(Reply::Json)
+                    .map_err(|e| DispatchError::Plugin(e.to_string()))
+            }
         }
     }
 }
@@ -94,4 +509,53 @@ mod tests {
         // Should succeed (empty list is fine)
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn encoded_responses_match_json() {
+        let db = Database::init().await.unwrap();
+        let dispatcher = ActionDispatcher::new(db);
+        let payload = json!({"List": {"search": null, "department": null}});
+
+        let json = dispatcher.handle_json("personnel.list", payload.clone()).await.unwrap();
+        let encoded = dispatcher
+            .handle_encoded("personnel.list", payload, Codec::Json)
+            .await
+            .unwrap();
+        assert_eq!(encoded.codec, Codec::Json);
+        assert_eq!(serde_json::from_slice::<Value>(&encoded.body).unwrap(), json);
+    }
+
+    #[tokio::test]
+    async fn batches_report_each_row() {
+        let db = Database::init().await.unwrap();
+        let dispatcher = ActionDispatcher::new(db);
+
+        let payload = json!({"Run": {"kind": "asset", "rows": ["a1", "a2"], "op": {"AssignTag": "missing"}}});
+        let response = dispatcher.handle_json("batch.run", payload).await.unwrap();
+        let results = response["Results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["row"], "a2");
+        assert_eq!(results[1]["error"], "Tag not found: missing");
+
+        // Kinds without bulk deletes are refused before anything runs
+        let payload = json!({"Run": {"kind": "person", "rows": ["p1"], "op": "Delete"}});
+        let response = dispatcher.handle_json("batch.run", payload).await.unwrap();
+        assert!(response.get("Error").is_some());
+    }
+
+    #[cfg(feature = "binary")]
+    #[tokio::test]
+    async fn binary_responses_decode_as_the_action_response() {
+        let db = Database::init().await.unwrap();
+        let dispatcher = ActionDispatcher::new(db);
+        let payload = json!({"List": {"search": null, "department": null}});
+
+        let encoded = dispatcher
+            .handle_encoded("personnel.list", payload, Codec::Postcard)
+            .await
+            .unwrap();
+        assert_eq!(encoded.codec, Codec::Postcard);
+        let response: PersonnelResponse = encoded.codec.decode(&encoded.body).unwrap();
+        assert!(matches!(response, PersonnelResponse::List(_)));
+    }
 }
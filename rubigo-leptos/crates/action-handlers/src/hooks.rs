@@ -0,0 +1,115 @@
+//! Action Hooks
+//!
+//! Code that runs around every dispatched action without owning a
+//! namespace: validation that can refuse an action before it touches the
+//! database, and automation that queues further actions once it succeeded.
+//!
+//! ```ignore
+//! let dispatcher = ActionDispatcher::new(db).with_hook(Arc::new(AuditHook));
+//! ```
+//!
+//! With the `wasm-rules` feature a [`rules::RuleHost`] is a hook too, so
+//! sandboxed WASM rule modules can be attached the same way.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// An action to run after the one that triggered it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowUp {
+    pub action_type: String,
+    pub payload: Value,
+}
+
+/// Runs before and after every action
+#[async_trait]
+pub trait ActionHook: Send + Sync {
+    /// Refuse the action with a reason, before anything is written
+    async fn before(&self, _action_type: &str, _payload: &Value) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Actions to dispatch once the action succeeded
+    async fn after(
+        &self,
+        _action_type: &str,
+        _payload: &Value,
+        _response: &Value,
+    ) -> Vec<FollowUp> {
+        Vec::new()
+    }
+}
+
+#[cfg(feature = "wasm-rules")]
+mod wasm {
+    use super::*;
+    use rules::{RuleEffect, RuleEvent, RuleHost};
+
+    #[async_trait]
+    impl ActionHook for RuleHost {
+        async fn before(&self, action_type: &str, payload: &Value) -> Result<(), String> {
+            let fired = self.fire(&RuleEvent::before(action_type, payload));
+            match fired.into_iter().find_map(|f| match f.effect {
+                RuleEffect::Reject { reason } => Some(format!("{}: {}", f.rule, reason)),
+                RuleEffect::Dispatch { .. } => None,
+            }) {
+                Some(reason) => Err(reason),
+                None => Ok(()),
+            }
+        }
+
+        async fn after(
+            &self,
+            action_type: &str,
+            payload: &Value,
+            response: &Value,
+        ) -> Vec<FollowUp> {
+            self.fire(&RuleEvent::after(action_type, payload, response))
+                .into_iter()
+                .filter_map(|f| match f.effect {
+                    RuleEffect::Dispatch {
+                        action_type,
+                        payload,
+                    } => Some(FollowUp {
+                        action_type,
+                        payload,
+                    }),
+                    RuleEffect::Reject { .. } => None,
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionDispatcher, DispatchError};
+    use db::Database;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    struct NoLists;
+
+    #[async_trait]
+    impl ActionHook for NoLists {
+        async fn before(&self, action_type: &str, _payload: &Value) -> Result<(), String> {
+            match action_type.ends_with(".list") {
+                true => Err("lists are disabled".to_string()),
+                false => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn hooks_can_reject_actions() {
+        let db = Database::init().await.unwrap();
+        let dispatcher = ActionDispatcher::new(db).with_hook(Arc::new(NoLists));
+
+        let payload = json!({"List": {"search": null, "department": null}});
+        assert!(matches!(
+            dispatcher.handle_json("personnel.list", payload).await,
+            Err(DispatchError::Rejected(reason)) if reason == "lists are disabled"
+        ));
+    }
+}
@@ -32,10 +32,42 @@
 //!
 //! // Handle an action JSON blob
 //! let response = dispatcher.handle_json("personnel.list", payload).await?;
+//!
+//! // Or in whichever codec the broker negotiated
+//! let codec = actions::Codec::negotiate(accept_header);
+//! let encoded = dispatcher.handle_encoded("personnel.list", payload, codec).await?;
 //! ```
+//!
+//! Other crates add action namespaces through [`plugin`]: register an
+//! [`ActionPlugin`] in a [`PluginRegistry`] and build the dispatcher with
+//! [`ActionDispatcher::with_plugins`].
+//!
+//! Validation and automation that apply across namespaces are [`hooks`]:
+//! an [`ActionHook`] can refuse an action before it runs and queue follow-up
+//! actions after it succeeded. The `wasm-rules` feature makes a
+//! `rules::RuleHost` usable as one, for user-provided WASM rules.
+//!
+//! Changes to protected records can be held for review with an
+//! [`ApprovalPolicy`] (see [`approvals`]).
+//!
+//! A [`TrainingMode`] lets a trainee work through the scenario's guided
+//! exercises, with progress tracked from the actions they dispatch (see
+//! [`training`]).
 
+mod activity;
+pub mod approvals;
 mod assets;
+mod custom_fields;
+mod dashboard;
 mod dispatcher;
+pub mod hooks;
 mod personnel;
+pub mod plugin;
+mod tags;
+pub mod training;
 
-pub use dispatcher::ActionDispatcher;
+pub use approvals::{ApprovalPolicy, ChangeRequestEvent, ChangeRequestNotifier};
+pub use dispatcher::{ActionDispatcher, DispatchError, EncodedResponse};
+pub use hooks::{ActionHook, FollowUp};
+pub use plugin::{ActionPlugin, Migration, PluginRegistry};
+pub use training::TrainingMode;
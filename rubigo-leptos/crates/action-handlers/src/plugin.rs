@@ -0,0 +1,253 @@
+//! Action Plugins
+//!
+//! Lets crates outside this one add action namespaces (`inventory.*`,
+//! `tickets.*`) without touching the dispatcher. A plugin names its
+//! namespace, lists the schema changes it needs and handles raw JSON
+//! actions; the host app registers it at startup:
+//!
+//! ```ignore
+//! let mut plugins = PluginRegistry::new();
+//! plugins.register(inventory::Plugin)?;
+//! plugins.register(tickets::Plugin)?;
+//! let dispatcher = ActionDispatcher::with_plugins(db, plugins).await?;
+//!
+//! dispatcher.handle_json("inventory.count", payload).await?;
+//! ```
+//!
+//! Migrations run when the dispatcher is built. Each applied migration is
+//! recorded in the `plugin_migration` table, so one that has already run
+//! against a database is skipped.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use db::client::DbClient;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::dispatcher::DispatchError;
+
+/// Namespaces handled by the dispatcher itself
+pub const BUILTIN_NAMESPACES: [&str; 3] = ["personnel", "asset", "dashboard"];
+
+/// A schema change a plugin needs before it can handle actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// Unique within the plugin, e.g. `"0001_create_items"`; never reuse one
+    pub id: &'static str,
+    /// SurrealQL statements run as one query
+    pub statements: &'static str,
+}
+
+impl Migration {
+    pub const fn new(id: &'static str, statements: &'static str) -> Self {
+        Self { id, statements }
+    }
+}
+
+/// Handlers for one action namespace
+#[async_trait]
+pub trait ActionPlugin: Send + Sync {
+    /// Namespace the plugin owns, e.g. `"inventory"` for `inventory.count`
+    fn namespace(&self) -> &'static str;
+
+    /// Schema changes, applied in order before the first action
+    fn migrations(&self) -> Vec<Migration> {
+        Vec::new()
+    }
+
+    /// Handle `action`, the part of the action type after the namespace
+    async fn handle(&self, db: &DbClient, action: &str, payload: Value) -> anyhow::Result<Value>;
+}
+
+/// Split `inventory.count` or `inventory/count` into namespace and action
+pub fn split_action_type(action_type: &str) -> Option<(&str, &str)> {
+    let (namespace, action) = action_type.split_once(['.', '/'])?;
+    (!namespace.is_empty() && !action.is_empty()).then_some((namespace, action))
+}
+
+/// Record of an applied migration
+#[derive(Debug, Serialize, Deserialize)]
+struct AppliedMigration {
+    namespace: String,
+    migration: String,
+}
+
+fn plugin_error(e: impl std::fmt::Display) -> DispatchError {
+    DispatchError::Plugin(e.to_string())
+}
+
+/// Plugins by namespace
+#[derive(Default, Clone)]
+pub struct PluginRegistry {
+    plugins: BTreeMap<&'static str, Arc<dyn ActionPlugin>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a plugin; its namespace must be new and not a built-in one
+    pub fn register(&mut self, plugin: impl ActionPlugin + 'static) -> Result<(), DispatchError> {
+        let namespace = plugin.namespace();
+        if namespace.is_empty() || namespace.contains(['.', '/']) {
+            return Err(DispatchError::Plugin(format!(
+                "Invalid namespace '{}'",
+                namespace
+            )));
+        }
+        if BUILTIN_NAMESPACES.contains(&namespace) || self.plugins.contains_key(namespace) {
+            return Err(DispatchError::Plugin(format!(
+                "Namespace '{}' is already registered",
+                namespace
+            )));
+        }
+        self.plugins.insert(namespace, Arc::new(plugin));
+        Ok(())
+    }
+
+    /// Registered namespaces, sorted
+    pub fn namespaces(&self) -> Vec<&'static str> {
+        self.plugins.keys().copied().collect()
+    }
+
+    pub fn get(&self, namespace: &str) -> Option<&Arc<dyn ActionPlugin>> {
+        self.plugins.get(namespace)
+    }
+
+    /// Apply every plugin's pending migrations, returning how many ran
+    pub async fn migrate(&self, db: &DbClient) -> Result<usize, DispatchError> {
+        let applied: Vec<AppliedMigration> =
+            db.select("plugin_migration").await.map_err(plugin_error)?;
+
+        let mut count = 0;
+        for (namespace, plugin) in &self.plugins {
+            for migration in plugin.migrations() {
+                let done = applied
+                    .iter()
+                    .any(|a| a.namespace == *namespace && a.migration == migration.id);
+                if done {
+                    continue;
+                }
+                db.query(migration.statements)
+                    .await
+                    .and_then(|response| response.check())
+                    .map_err(|e| {
+                        DispatchError::Plugin(format!(
+                            "Migration {}/{} failed: {}",
+                            namespace, migration.id, e
+                        ))
+                    })?;
+                let _: Option<AppliedMigration> = db
+                    .create("plugin_migration")
+                    .content(AppliedMigration {
+                        namespace: namespace.to_string(),
+                        migration: migration.id.to_string(),
+                    })
+                    .await
+                    .map_err(plugin_error)?;
+                tracing::info!("Applied migration {}/{}", namespace, migration.id);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ActionDispatcher;
+    use db::Database;
+    use serde_json::json;
+
+    struct Inventory;
+
+    #[derive(Deserialize)]
+    struct Item {
+        #[allow(dead_code)]
+        qty: u32,
+    }
+
+    #[async_trait]
+    impl ActionPlugin for Inventory {
+        fn namespace(&self) -> &'static str {
+            "inventory"
+        }
+
+        fn migrations(&self) -> Vec<Migration> {
+            vec![Migration::new(
+                "0001_items",
+                "DEFINE TABLE inventory_item SCHEMALESS; CREATE inventory_item:a SET qty = 3;",
+            )]
+        }
+
+        async fn handle(
+            &self,
+            db: &DbClient,
+            action: &str,
+            _payload: Value,
+        ) -> anyhow::Result<Value> {
+            match action {
+                "count" => {
+                    let items: Vec<Item> = db.select("inventory_item").await?;
+                    Ok(json!({ "count": items.len() }))
+                }
+                other => anyhow::bail!("Unknown inventory action '{}'", other),
+            }
+        }
+    }
+
+    #[test]
+    fn action_types_split_on_dot_or_slash() {
+        assert_eq!(
+            split_action_type("inventory.count"),
+            Some(("inventory", "count"))
+        );
+        assert_eq!(
+            split_action_type("tickets/open.list"),
+            Some(("tickets", "open.list"))
+        );
+        assert_eq!(split_action_type("inventory"), None);
+        assert_eq!(split_action_type(".count"), None);
+    }
+
+    #[test]
+    fn namespaces_are_registered_once() {
+        let mut plugins = PluginRegistry::new();
+        plugins.register(Inventory).unwrap();
+        assert!(plugins.register(Inventory).is_err());
+        assert_eq!(plugins.namespaces(), vec!["inventory"]);
+    }
+
+    #[tokio::test]
+    async fn plugin_actions_are_dispatched_after_migrations() {
+        let db = Database::init().await.unwrap();
+        let mut plugins = PluginRegistry::new();
+        plugins.register(Inventory).unwrap();
+        let dispatcher = ActionDispatcher::with_plugins(db, plugins.clone())
+            .await
+            .unwrap();
+
+        let response = dispatcher
+            .handle_json("inventory.count", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(response, json!({ "count": 1 }));
+
+        // Already applied, so the seed row is not inserted twice
+        assert_eq!(
+            plugins
+                .migrate(&dispatcher.database().client)
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(matches!(
+            dispatcher.handle_json("tickets.list", json!({})).await,
+            Err(DispatchError::UnknownAction(_))
+        ));
+    }
+}
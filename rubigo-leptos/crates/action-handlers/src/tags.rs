@@ -0,0 +1,188 @@
+//! Tag action handlers
+
+use std::collections::BTreeMap;
+
+use actions::tags::{scope_allows, validate_tag, TAG_SCOPES};
+use actions::{CreateTagData, EntityTagsData, TagAction, TagAssignmentData, TagData, TagResponse};
+use anyhow::Result;
+use db::client::DbClient;
+use db::models::Tag;
+use db::repositories::TagRepository;
+
+/// Records one bulk operation may touch
+const MAX_BULK_ENTITIES: usize = 500;
+
+/// Handle tag actions
+pub async fn handle(db: &DbClient, action: TagAction) -> Result<TagResponse> {
+    match action {
+        TagAction::List(scope) => list(db, scope).await,
+        TagAction::Create(data) => create(db, data).await,
+        TagAction::Delete(id) => delete(db, &id).await,
+        TagAction::ForEntities(entities) => for_entities(db, entities).await,
+        TagAction::Assign(data) => assign(db, data, true).await,
+        TagAction::Unassign(data) => assign(db, data, false).await,
+    }
+}
+
+fn tag_to_data(tag: Tag) -> TagData {
+    TagData {
+        id: tag.id.map(|t| t.id.to_raw()).unwrap_or_default(),
+        name: tag.name,
+        color: tag.color,
+        scope: tag.scope,
+    }
+}
+
+async fn list(db: &DbClient, scope: Option<String>) -> Result<TagResponse> {
+    if let Some(scope) = scope.as_deref().filter(|s| !TAG_SCOPES.contains(s)) {
+        return Ok(TagResponse::Error(format!("Unknown tag scope: {}", scope)));
+    }
+    let tags = TagRepository::list(db, scope.as_deref()).await?;
+    Ok(TagResponse::List(
+        tags.into_iter().map(tag_to_data).collect(),
+    ))
+}
+
+async fn create(db: &DbClient, data: CreateTagData) -> Result<TagResponse> {
+    let existing: Vec<TagData> = TagRepository::list(db, None)
+        .await?
+        .into_iter()
+        .map(tag_to_data)
+        .collect();
+    let color = data.color.to_lowercase();
+    if let Some(error) = validate_tag(&data.name, &color, &data.scope, &existing) {
+        return Ok(TagResponse::Error(error));
+    }
+    let tag = Tag {
+        id: None,
+        name: data.name.trim().to_string(),
+        color,
+        scope: data.scope,
+    };
+    let created = TagRepository::create(db, tag).await?;
+    Ok(TagResponse::Created(tag_to_data(created)))
+}
+
+async fn delete(db: &DbClient, id: &str) -> Result<TagResponse> {
+    if TagRepository::delete(db, id).await? {
+        Ok(TagResponse::Success)
+    } else {
+        Ok(TagResponse::Error(format!("Tag not found: {}", id)))
+    }
+}
+
+async fn for_entities(db: &DbClient, entities: Vec<String>) -> Result<TagResponse> {
+    let mut tags: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for link in TagRepository::links_for(db, &entities).await? {
+        tags.entry(link.entity)
+            .or_default()
+            .push(link.tag_id.id.to_raw());
+    }
+    Ok(TagResponse::Assignments(
+        tags.into_iter()
+            .map(|(entity, mut tag_ids)| {
+                tag_ids.sort();
+                EntityTagsData { entity, tag_ids }
+            })
+            .collect(),
+    ))
+}
+
+/// Put a tag on records, or take it off them
+///
+/// Every record must be one the tag's scope allows; otherwise nothing is
+/// changed.
+async fn assign(db: &DbClient, data: TagAssignmentData, on: bool) -> Result<TagResponse> {
+    if data.entities.len() > MAX_BULK_ENTITIES {
+        return Ok(TagResponse::Error(format!(
+            "Tags can be changed on at most {} records at once",
+            MAX_BULK_ENTITIES
+        )));
+    }
+    let Some(tag) = TagRepository::get_by_id(db, &data.tag_id).await? else {
+        return Ok(TagResponse::Error(format!(
+            "Tag not found: {}",
+            data.tag_id
+        )));
+    };
+    if let Some(entity) = data.entities.iter().find(|e| !scope_allows(&tag.scope, e)) {
+        return Ok(TagResponse::Error(format!(
+            "{} can't be put on {}",
+            tag.name, entity
+        )));
+    }
+    if on {
+        TagRepository::assign(db, &data.tag_id, &data.entities).await?;
+    } else {
+        TagRepository::unassign(db, &data.tag_id, &data.entities).await?;
+    }
+    Ok(TagResponse::Success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::Database;
+
+    async fn create_tag(db: &DbClient, name: &str, scope: &str) -> TagData {
+        let data = CreateTagData {
+            name: name.to_string(),
+            color: "#FF8A65".to_string(),
+            scope: scope.to_string(),
+        };
+        match handle(db, TagAction::Create(data)).await.unwrap() {
+            TagResponse::Created(tag) => tag,
+            other => panic!("Expected a new tag, got {:?}", other),
+        }
+    }
+
+    fn assignment(tag: &TagData, entities: &[&str]) -> TagAssignmentData {
+        TagAssignmentData {
+            tag_id: tag.id.clone(),
+            entities: entities.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn bulk_assignment_respects_scope() {
+        let db = Database::init().await.unwrap();
+        let spare = create_tag(&db.client, "Spare", "asset").await;
+        assert_eq!(spare.color, "#ff8a65");
+
+        let mixed = assignment(&spare, &["asset:sw1", "person:42"]);
+        let refused = handle(&db.client, TagAction::Assign(mixed)).await.unwrap();
+        assert!(matches!(refused, TagResponse::Error(_)));
+
+        let assets = assignment(&spare, &["asset:sw1", "asset:sw2"]);
+        handle(&db.client, TagAction::Assign(assets)).await.unwrap();
+        let entities = vec![
+            "asset:sw1".to_string(),
+            "asset:sw2".to_string(),
+            "person:42".to_string(),
+        ];
+        match handle(&db.client, TagAction::ForEntities(entities))
+            .await
+            .unwrap()
+        {
+            TagResponse::Assignments(assignments) => {
+                let tagged: Vec<&str> = assignments.iter().map(|a| a.entity.as_str()).collect();
+                assert_eq!(tagged, vec!["asset:sw1", "asset:sw2"]);
+                assert_eq!(assignments[0].tag_ids, vec![spare.id.clone()]);
+            }
+            other => panic!("Expected assignments, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn duplicate_names_are_refused() {
+        let db = Database::init().await.unwrap();
+        create_tag(&db.client, "Spare", "asset").await;
+        let data = CreateTagData {
+            name: "spare".to_string(),
+            color: "#4caf50".to_string(),
+            scope: "asset".to_string(),
+        };
+        let refused = handle(&db.client, TagAction::Create(data)).await.unwrap();
+        assert!(matches!(refused, TagResponse::Error(_)));
+    }
+}
@@ -0,0 +1,360 @@
+//! Training Mode
+//!
+//! With a [`TrainingMode`], the dispatcher handles `training.*` actions and
+//! watches the other actions it runs while a session is in progress: one
+//! matching the task at hand completes it, anything else counts as an
+//! extra action. Every step goes into the session's audit trail, kept as
+//! activity records like other audit entries, so the trainee's steps show
+//! in the session's activity feed next to the scorecard.
+//!
+//! ```ignore
+//! let scenario = scenario_loader::Scenario::load_from_path("scenarios/mmc")?;
+//! let dispatcher = ActionDispatcher::new(db).with_training(TrainingMode::new(scenario.exercises));
+//! ```
+
+use actions::training::action_matches;
+use actions::{
+    ExerciseData, StartTrainingData, TaskProgressData, TrainingAction, TrainingProgressData,
+    TrainingResponse,
+};
+use anyhow::Result;
+use db::client::DbClient;
+use db::models::{ActivityRecord, TrainingSession};
+use db::repositories::{ActivityRepository, TrainingRepository};
+use scenario_loader::Exercise;
+use serde_json::Value;
+
+/// The exercises trainees can work through
+#[derive(Debug, Clone, Default)]
+pub struct TrainingMode {
+    exercises: Vec<Exercise>,
+}
+
+impl TrainingMode {
+    pub fn new(exercises: Vec<Exercise>) -> Self {
+        Self { exercises }
+    }
+
+    pub fn exercises(&self) -> &[Exercise] {
+        &self.exercises
+    }
+
+    fn exercise(&self, name: &str) -> Option<&Exercise> {
+        self.exercises.iter().find(|e| e.name == name)
+    }
+}
+
+fn session_key(session: &TrainingSession) -> String {
+    session
+        .id
+        .as_ref()
+        .map(|t| t.id.to_raw())
+        .unwrap_or_default()
+}
+
+async fn now(db: &DbClient) -> Result<String> {
+    let now: Option<String> = db.query("RETURN <string> time::now()").await?.take(0)?;
+    Ok(now.unwrap_or_default())
+}
+
+/// Save a session's progress and note the step in its audit trail
+async fn record(
+    db: &DbClient,
+    mut session: TrainingSession,
+    body: String,
+    at: String,
+) -> Result<TrainingSession> {
+    let key = session_key(&session);
+    let entity = format!("training_session:{key}");
+    let trainee = session.trainee.clone();
+    session.id = None;
+    let saved = TrainingRepository::update(db, &key, session)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Training session not found: {}", key))?;
+    ActivityRepository::record(db, ActivityRecord::audit(&entity, trainee, body, at)).await?;
+    Ok(saved)
+}
+
+fn exercise_data(exercise: &Exercise) -> ExerciseData {
+    ExerciseData {
+        name: exercise.name.clone(),
+        description: exercise.description.clone(),
+        tasks: exercise.tasks.len(),
+        max_score: exercise.tasks.iter().map(|t| t.points).sum(),
+    }
+}
+
+/// A session as seen by the trainee; no tasks if the scenario no longer
+/// has its exercise
+fn progress(exercise: Option<&Exercise>, session: &TrainingSession) -> TrainingProgressData {
+    let tasks = exercise
+        .map(|e| e.tasks.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let used = session.hints_used.get(i).copied().unwrap_or(0) as usize;
+            let used = used.min(task.hints.len());
+            TaskProgressData {
+                title: task.title.clone(),
+                instructions: task.instructions.clone(),
+                points: task.points,
+                hints: task.hints[..used].to_vec(),
+                hints_left: task.hints.len() - used,
+                completed_at: session.completed_at.get(i).cloned().flatten(),
+            }
+        })
+        .collect();
+    TrainingProgressData {
+        session_id: session_key(session),
+        exercise: session.exercise.clone(),
+        trainee: session.trainee.clone(),
+        started_at: session.started_at.clone(),
+        finished_at: session.finished_at.clone(),
+        tasks,
+        extra_actions: session.extra_actions,
+    }
+}
+
+/// The finished session's summary line
+fn result_line(progress: &TrainingProgressData) -> String {
+    format!(
+        "Finished {}: {} of {} tasks, {} of {} points",
+        progress.exercise,
+        progress.completed(),
+        progress.tasks.len(),
+        progress.score(),
+        progress.max_score()
+    )
+}
+
+/// Handle training actions
+pub(crate) async fn handle(
+    db: &DbClient,
+    mode: &TrainingMode,
+    action: TrainingAction,
+) -> Result<TrainingResponse> {
+    match action {
+        TrainingAction::Exercises => Ok(TrainingResponse::Exercises(
+            mode.exercises.iter().map(exercise_data).collect(),
+        )),
+        TrainingAction::Start(data) => start(db, mode, data).await,
+        TrainingAction::Hint => hint(db, mode).await,
+        TrainingAction::Progress => Ok(match TrainingRepository::latest(db).await? {
+            Some(session) => TrainingResponse::Progress(Box::new(progress(
+                mode.exercise(&session.exercise),
+                &session,
+            ))),
+            None => TrainingResponse::Error("No training session has been started".to_string()),
+        }),
+        TrainingAction::Finish => {
+            let Some(mut session) = TrainingRepository::active(db).await? else {
+                return Ok(TrainingResponse::Error(
+                    "No training session in progress".to_string(),
+                ));
+            };
+            let at = now(db).await?;
+            session.finished_at = Some(at.clone());
+            let line = result_line(&progress(mode.exercise(&session.exercise), &session));
+            let session = record(db, session, line, at).await?;
+            Ok(TrainingResponse::Progress(Box::new(progress(
+                mode.exercise(&session.exercise),
+                &session,
+            ))))
+        }
+    }
+}
+
+async fn start(
+    db: &DbClient,
+    mode: &TrainingMode,
+    data: StartTrainingData,
+) -> Result<TrainingResponse> {
+    let Some(exercise) = mode.exercise(&data.exercise) else {
+        return Ok(TrainingResponse::Error(format!(
+            "Unknown exercise: {}",
+            data.exercise
+        )));
+    };
+    let at = now(db).await?;
+    if let Some(mut abandoned) = TrainingRepository::active(db).await? {
+        abandoned.finished_at = Some(at.clone());
+        let body = format!("Left {} unfinished", abandoned.exercise);
+        record(db, abandoned, body, at.clone()).await?;
+    }
+
+    let session = TrainingRepository::create(
+        db,
+        TrainingSession {
+            id: None,
+            exercise: exercise.name.clone(),
+            trainee: data.trainee,
+            started_at: at.clone(),
+            finished_at: None,
+            completed_at: vec![None; exercise.tasks.len()],
+            hints_used: vec![0; exercise.tasks.len()],
+            extra_actions: 0,
+        },
+    )
+    .await?;
+    let body = format!("Started {}", exercise.name);
+    let session = record(db, session, body, at).await?;
+    Ok(TrainingResponse::Progress(Box::new(progress(
+        Some(exercise),
+        &session,
+    ))))
+}
+
+async fn hint(db: &DbClient, mode: &TrainingMode) -> Result<TrainingResponse> {
+    let Some(mut session) = TrainingRepository::active(db).await? else {
+        return Ok(TrainingResponse::Error(
+            "No training session in progress".to_string(),
+        ));
+    };
+    let Some(exercise) = mode.exercise(&session.exercise) else {
+        return Ok(TrainingResponse::Error(format!(
+            "Unknown exercise: {}",
+            session.exercise
+        )));
+    };
+    let Some(current) = progress(Some(exercise), &session).current() else {
+        return Ok(TrainingResponse::Error("Every task is done".to_string()));
+    };
+    let task = &exercise.tasks[current];
+    session.hints_used.resize(exercise.tasks.len(), 0);
+    let used = session.hints_used[current] as usize;
+    let Some(text) = task.hints.get(used).cloned() else {
+        return Ok(TrainingResponse::Error(format!(
+            "No more hints for \"{}\"",
+            task.title
+        )));
+    };
+    session.hints_used[current] += 1;
+    let body = format!("Took hint {} for \"{}\"", used + 1, task.title);
+    let at = now(db).await?;
+    record(db, session, body, at).await?;
+    Ok(TrainingResponse::Hint(text))
+}
+
+/// Check an action the dispatcher ran against the task at hand
+///
+/// Finishes the session once its last task is done.
+pub(crate) async fn observe(
+    db: &DbClient,
+    mode: &TrainingMode,
+    action_type: &str,
+    payload: &Value,
+) -> Result<()> {
+    let Some(mut session) = TrainingRepository::active(db).await? else {
+        return Ok(());
+    };
+    let Some(exercise) = mode.exercise(&session.exercise) else {
+        return Ok(());
+    };
+    let Some(current) = progress(Some(exercise), &session).current() else {
+        return Ok(());
+    };
+    let task = &exercise.tasks[current];
+    let at = now(db).await?;
+    let expected = &task.expect;
+    let body = if action_matches(
+        &expected.action,
+        expected.payload.as_ref(),
+        action_type,
+        payload,
+    ) {
+        session.completed_at.resize(exercise.tasks.len(), None);
+        session.completed_at[current] = Some(at.clone());
+        format!("Completed \"{}\" with {}", task.title, action_type)
+    } else {
+        session.extra_actions += 1;
+        format!("Ran {} during \"{}\"", action_type, task.title)
+    };
+    let session = record(db, session, body, at).await?;
+
+    let done = progress(Some(exercise), &session);
+    if done.current().is_none() {
+        let at = now(db).await?;
+        let mut session = session;
+        session.finished_at = Some(at.clone());
+        record(db, session, result_line(&done), at).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ActionDispatcher;
+    use db::Database;
+    use scenario_loader::parse_exercises;
+    use serde_json::json;
+
+    const EXERCISE: &str = r#"
+[[exercises]]
+name = "Find the engineers"
+
+[[exercises.tasks]]
+title = "List the engineering department"
+hints = ["People can be listed by department"]
+points = 20
+expect = { action = "personnel.list", payload = { List = { department = "Engineering" } } }
+
+[[exercises.tasks]]
+title = "List everyone"
+expect = { action = "personnel.list" }
+"#;
+
+    #[tokio::test]
+    async fn dispatched_actions_complete_tasks_in_order() {
+        let db = Database::init().await.unwrap();
+        let mode = TrainingMode::new(parse_exercises(EXERCISE).unwrap());
+        let dispatcher = ActionDispatcher::new(db).with_training(mode);
+        let start =
+            json!({"Start": {"exercise": "Find the engineers", "trainee": "Riley Engineer"}});
+        dispatcher
+            .handle_json("training.start", start)
+            .await
+            .unwrap();
+
+        let everyone = json!({"List": {"search": null, "department": null}});
+        let engineers = json!({"List": {"search": null, "department": "Engineering"}});
+        dispatcher
+            .handle_json("personnel.list", everyone.clone())
+            .await
+            .unwrap();
+        let hint = dispatcher
+            .handle_json("training.hint", json!("Hint"))
+            .await
+            .unwrap();
+        assert_eq!(hint, json!({"Hint": "People can be listed by department"}));
+        dispatcher
+            .handle_json("personnel.list", engineers)
+            .await
+            .unwrap();
+        dispatcher
+            .handle_json("personnel.list", everyone)
+            .await
+            .unwrap();
+
+        let response = dispatcher
+            .handle_json("training.progress", json!("Progress"))
+            .await
+            .unwrap();
+        let progress: TrainingProgressData =
+            serde_json::from_value(response["Progress"].clone()).unwrap();
+        assert!(progress.finished_at.is_some());
+        assert_eq!(progress.extra_actions, 1);
+        assert_eq!((progress.score(), progress.max_score()), (25, 30));
+
+        // Started, the extra action, the hint, both tasks and the result
+        let trail =
+            ActivityRepository::for_entity(&dispatcher.database().client, &progress.entity())
+                .await
+                .unwrap();
+        assert_eq!(trail.len(), 6);
+        assert!(trail
+            .iter()
+            .all(|r| r.kind == "audit" && r.actor == "Riley Engineer"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Change Request Approvals
+//!
+//! Rules shared by the dispatcher and the review queue. Changes to
+//! protected records (e.g. production site topology) are not applied right
+//! away: the dispatcher keeps them as pending change requests until someone
+//! holding the approver role approves or rejects them.
+
+use serde_json::Value;
+
+use crate::types::ChangeRequestData;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_APPROVED: &str = "approved";
+pub const STATUS_REJECTED: &str = "rejected";
+/// Approved, but the change itself failed when applied
+pub const STATUS_FAILED: &str = "failed";
+
+/// Action types that change records, and so can be held for approval
+pub const HELD_ACTIONS: [&str; 8] = [
+    "asset.update",
+    "asset.delete",
+    "asset.transition",
+    "asset.move",
+    "asset.schedule_maintenance",
+    "custom_field.set_values",
+    "tag.assign",
+    "tag.unassign",
+];
+
+/// Entity keys of the records an action changes
+///
+/// Actions are externally tagged enums, so the record is the variant's
+/// string, the first element of its tuple, or the `entities` of its
+/// struct. Bare IDs get the action's namespace as their kind, e.g.
+/// `asset.update` of `"7"` changes `asset:7`.
+pub fn target_entities(action_type: &str, payload: &Value) -> Vec<String> {
+    let Some((namespace, _)) = action_type.split_once('.') else {
+        return Vec::new();
+    };
+    // A bare string is a unit variant, which changes nothing in particular
+    let content = match payload {
+        Value::Object(variant) if variant.len() == 1 => variant.values().next().unwrap_or(payload),
+        Value::String(_) => return Vec::new(),
+        _ => payload,
+    };
+    let ids: Vec<&str> = match content {
+        Value::String(id) => vec![id.as_str()],
+        Value::Array(items) => items.first().and_then(Value::as_str).into_iter().collect(),
+        Value::Object(fields) => fields
+            .get("entities")
+            .and_then(Value::as_array)
+            .map(|entities| entities.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    ids.into_iter()
+        .filter(|id| !id.is_empty())
+        .map(|id| match id.contains(':') {
+            true => id.to_string(),
+            false => format!("{namespace}:{id}"),
+        })
+        .collect()
+}
+
+/// Why `reviewer` can't review `request`, if they can't
+///
+/// Only pending requests can be reviewed, only by one of `approvers`, and
+/// never by whoever asked for the change.
+pub fn review_refusal(
+    request: &ChangeRequestData,
+    reviewer: &str,
+    approvers: &[String],
+) -> Option<String> {
+    if request.status != STATUS_PENDING {
+        return Some(format!(
+            "Change request {} is already {}",
+            request.id, request.status
+        ));
+    }
+    if !approvers.iter().any(|a| a.eq_ignore_ascii_case(reviewer)) {
+        return Some(format!("{reviewer} can't approve change requests"));
+    }
+    if request
+        .requested_by
+        .as_deref()
+        .is_some_and(|requester| requester.eq_ignore_ascii_case(reviewer))
+    {
+        return Some("Change requests need someone else's approval".to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn targets_come_from_the_variant() {
+        let update = json!({"Update": ["7", {"name": "Core switch"}]});
+        assert_eq!(target_entities("asset.update", &update), vec!["asset:7"]);
+        assert_eq!(
+            target_entities("asset.delete", &json!({"Delete": "7"})),
+            vec!["asset:7"]
+        );
+
+        let values = json!({"SetValues": ["site:hq", {"tier": "Gold"}]});
+        assert_eq!(
+            target_entities("custom_field.set_values", &values),
+            vec!["site:hq"]
+        );
+
+        let assign = json!({"Assign": {"tag_id": "t1", "entities": ["asset:1", "asset:2"]}});
+        assert_eq!(
+            target_entities("tag.assign", &assign),
+            vec!["asset:1", "asset:2"]
+        );
+
+        assert!(target_entities("asset.list", &json!("List")).is_empty());
+    }
+
+    #[test]
+    fn reviews_need_another_approver() {
+        let approvers = vec!["Grace Hopper".to_string(), "Ada Lovelace".to_string()];
+        let mut request = ChangeRequestData {
+            id: "cr1".to_string(),
+            action_type: "asset.delete".to_string(),
+            payload: r#"{"Delete":"7"}"#.to_string(),
+            entities: vec!["asset:7".to_string()],
+            requested_by: Some("Ada Lovelace".to_string()),
+            requested_at: "2026-03-02T09:00:00Z".to_string(),
+            status: STATUS_PENDING.to_string(),
+            reviewed_by: None,
+            review_note: None,
+            reviewed_at: None,
+            error: None,
+        };
+        assert_eq!(review_refusal(&request, "grace hopper", &approvers), None);
+        assert!(review_refusal(&request, "Ada Lovelace", &approvers).is_some());
+        assert!(review_refusal(&request, "Alan Turing", &approvers).is_some());
+
+        request.status = STATUS_APPROVED.to_string();
+        assert!(review_refusal(&request, "Grace Hopper", &approvers).is_some());
+    }
+}
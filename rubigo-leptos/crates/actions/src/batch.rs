@@ -0,0 +1,178 @@
+//! Batch Actions
+//!
+//! Rules shared by the dispatcher and the tables offering bulk edits. A
+//! [`BatchRequest`] is expanded into one ordinary action per record, which
+//! the dispatcher runs in turn so each change passes the same hooks and
+//! approvals as if it had been made alone. One record failing doesn't stop
+//! the rest; the response says how it went for each.
+
+use serde_json::Value;
+
+use crate::broker::Action;
+use crate::presence::entity_key;
+use crate::types::{
+    AssetAction, BatchOp, BatchRequest, BatchRowResult, TagAction, TagAssignmentData,
+    TransitionAssetData,
+};
+
+/// Records one batch may touch
+pub const MAX_BATCH_ROWS: usize = 500;
+
+/// One record's share of a batch: the action to run for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchStep {
+    pub row: String,
+    pub action_type: &'static str,
+    pub payload: Value,
+}
+
+/// The action `op` takes on the record `id` of `kind`, if that kind
+/// supports it
+///
+/// Tags go on records of any kind; status changes and deletes are only
+/// offered for assets.
+fn step_action(kind: &str, id: &str, op: &BatchOp) -> Option<(&'static str, Value)> {
+    let assignment = |tag_id: &String| TagAssignmentData {
+        tag_id: tag_id.clone(),
+        entities: vec![entity_key(kind, id)],
+    };
+    let (action_type, payload) = match (kind, op) {
+        (_, BatchOp::AssignTag(tag_id)) => {
+            let action = TagAction::Assign(assignment(tag_id));
+            (action.action_type(), serde_json::to_value(action))
+        }
+        (_, BatchOp::UnassignTag(tag_id)) => {
+            let action = TagAction::Unassign(assignment(tag_id));
+            (action.action_type(), serde_json::to_value(action))
+        }
+        ("asset", BatchOp::SetStatus(state)) => {
+            let data = TransitionAssetData {
+                state: state.clone(),
+                note: Some("Bulk edit".to_string()),
+            };
+            let action = AssetAction::Transition(id.to_string(), data);
+            (action.action_type(), serde_json::to_value(action))
+        }
+        ("asset", BatchOp::Delete) => {
+            let action = AssetAction::Delete(id.to_string());
+            (action.action_type(), serde_json::to_value(action))
+        }
+        _ => return None,
+    };
+    Some((action_type, payload.ok()?))
+}
+
+/// The per-record actions of `request`, or why it can't be run at all
+pub fn expand(request: &BatchRequest) -> Result<Vec<BatchStep>, String> {
+    if request.rows.is_empty() {
+        return Err("No records selected".to_string());
+    }
+    if request.rows.len() > MAX_BATCH_ROWS {
+        return Err(format!(
+            "At most {} records can be changed at once",
+            MAX_BATCH_ROWS
+        ));
+    }
+    let mut rows = request.rows.clone();
+    rows.sort();
+    rows.dedup();
+    if rows.len() != request.rows.len() {
+        return Err("A record is selected more than once".to_string());
+    }
+    request
+        .rows
+        .iter()
+        .map(|row| {
+            let (action_type, payload) = step_action(&request.kind, row, &request.op)
+                .ok_or_else(|| unsupported(&request.kind, &request.op))?;
+            Ok(BatchStep {
+                row: row.clone(),
+                action_type,
+                payload,
+            })
+        })
+        .collect()
+}
+
+fn unsupported(kind: &str, op: &BatchOp) -> String {
+    let what = match op {
+        BatchOp::AssignTag(_) | BatchOp::UnassignTag(_) => "retagged",
+        BatchOp::SetStatus(_) => "given a new status",
+        BatchOp::Delete => "deleted",
+    };
+    format!("Records of kind {} can't be {} in bulk", kind, what)
+}
+
+/// How many records of a batch were changed and how many weren't
+pub fn tally(results: &[BatchRowResult]) -> (usize, usize) {
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    (results.len() - failed, failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(kind: &str, rows: &[&str], op: BatchOp) -> BatchRequest {
+        BatchRequest {
+            kind: kind.to_string(),
+            rows: rows.iter().map(|r| r.to_string()).collect(),
+            op,
+        }
+    }
+
+    #[test]
+    fn batches_expand_to_one_action_per_record() {
+        let steps = expand(&request(
+            "asset",
+            &["a1", "a2"],
+            BatchOp::AssignTag("t".into()),
+        ))
+        .unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].row, "a2");
+        assert_eq!(steps[1].action_type, "tag.assign");
+        assert_eq!(
+            steps[1].payload,
+            serde_json::json!({ "Assign": { "tag_id": "t", "entities": ["asset:a2"] } })
+        );
+
+        let steps = expand(&request(
+            "asset",
+            &["a1"],
+            BatchOp::SetStatus("retired".into()),
+        ))
+        .unwrap();
+        assert_eq!(steps[0].action_type, "asset.transition");
+        assert_eq!(steps[0].payload["Transition"][1]["state"], "retired");
+        assert_eq!(
+            expand(&request("asset", &["a1"], BatchOp::Delete)).unwrap()[0].payload,
+            serde_json::json!({ "Delete": "a1" })
+        );
+    }
+
+    #[test]
+    fn batches_that_cant_run_are_refused_whole() {
+        assert_eq!(
+            expand(&request("person", &["p1"], BatchOp::Delete)),
+            Err("Records of kind person can't be deleted in bulk".to_string())
+        );
+        assert!(expand(&request("asset", &[], BatchOp::Delete)).is_err());
+        assert!(expand(&request("asset", &["a1", "a1"], BatchOp::Delete)).is_err());
+        let many: Vec<String> = (0..=MAX_BATCH_ROWS).map(|i| i.to_string()).collect();
+        let many: Vec<&str> = many.iter().map(String::as_str).collect();
+        assert!(expand(&request("asset", &many, BatchOp::Delete)).is_err());
+
+        let results = [
+            BatchRowResult {
+                row: "a1".into(),
+                error: None,
+            },
+            BatchRowResult {
+                row: "a2".into(),
+                error: Some("Asset not found: a2".into()),
+            },
+        ];
+        assert_eq!(tally(&results), (1, 1));
+    }
+}
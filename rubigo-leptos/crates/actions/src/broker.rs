@@ -5,11 +5,13 @@
 //! Tauri (native) and Axum (browser) deployments.
 
 use async_trait::async_trait;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error;
 
 /// Errors that can occur during action dispatch
-#[derive(Debug, Error)]
+///
+/// Serializable so recorded sessions can replay failures.
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
 pub enum ActionError {
     /// Serialization failed
     #[error("Failed to serialize action: {0}")]
@@ -0,0 +1,440 @@
+//! Resource Cache
+//!
+//! Keeps query responses on the client so a page revisited after navigation
+//! renders from the last known data at once, while a fresh copy is fetched
+//! in the background (stale-while-revalidate).
+//!
+//! Entries are keyed by action type and payload, and stored as JSON in a
+//! [`CacheStore`]: IndexedDB in the browser ([`IndexedDbStore`]), so the
+//! cache survives reloads, or memory elsewhere ([`MemoryStore`]).
+//!
+//! The server announces writes on its `/sse` stream as `change` events
+//! naming the kinds of data that moved, e.g. `["people","assets"]`.
+//! [`ResourceCache::apply_changes`] drops the entries those kinds feed, and
+//! in the browser [`ResourceCache::listen`] wires that up.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+
+use crate::broker::{Action, ActionBroker, ActionError};
+
+/// Name of the SSE event announcing data changes
+pub const CHANGE_EVENT: &str = "change";
+
+/// Where cached responses are kept
+#[async_trait(?Send)]
+pub trait CacheStore {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn put(&self, key: &str, value: String);
+    /// Remove every entry whose key starts with `prefix`
+    async fn remove_prefix(&self, prefix: &str);
+}
+
+/// Store that lasts as long as the page
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: RefCell<BTreeMap<String, String>>,
+}
+
+#[async_trait(?Send)]
+impl CacheStore for MemoryStore {
+    async fn get(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    async fn put(&self, key: &str, value: String) {
+        self.entries.borrow_mut().insert(key.to_string(), value);
+    }
+
+    async fn remove_prefix(&self, prefix: &str) {
+        self.entries
+            .borrow_mut()
+            .retain(|key, _| !key.starts_with(prefix));
+    }
+}
+
+/// Action type prefixes whose responses are built from a kind of data
+///
+/// Kinds are the server's data version names. An unknown kind may feed
+/// anything, so it invalidates everything.
+pub fn invalidated_by(kind: &str) -> &'static [&'static str] {
+    match kind {
+        "people" => &["personnel.", "dashboard.snapshot"],
+        "assets" | "maintenance" | "geo" => &["asset.", "dashboard.snapshot"],
+        "meetings" => &["dashboard.snapshot"],
+        "components" | "connections" | "cabling" | "desk_bookings" | "jobs" | "reports"
+        | "runs" | "flags" => &[],
+        _ => &[""],
+    }
+}
+
+/// Stale-while-revalidate cache in front of an [`ActionBroker`]
+///
+/// Only route queries through it; mutations go to the broker directly and
+/// are reflected here once the server's change event arrives.
+pub struct ResourceCache<B, S = MemoryStore> {
+    broker: B,
+    store: S,
+}
+
+impl<B: ActionBroker> ResourceCache<B> {
+    /// Cache in memory only
+    pub fn in_memory(broker: B) -> Self {
+        Self::new(broker, MemoryStore::default())
+    }
+}
+
+impl<B: ActionBroker, S: CacheStore> ResourceCache<B, S> {
+    pub fn new(broker: B, store: S) -> Self {
+        Self { broker, store }
+    }
+
+    pub fn broker(&self) -> &B {
+        &self.broker
+    }
+
+    /// Store key of an action: its type, then its JSON payload
+    pub fn key<A: Action>(action: &A) -> Result<String, ActionError> {
+        let payload =
+            serde_json::to_string(action).map_err(|e| ActionError::Serialization(e.to_string()))?;
+        Ok(format!("{}|{}", action.action_type(), payload))
+    }
+
+    /// The cached response to `action`, however old
+    pub async fn cached<A: Action>(&self, action: &A) -> Option<A::Response> {
+        let text = self.store.get(&Self::key(action).ok()?).await?;
+        serde_json::from_str(&text).ok()
+    }
+
+    /// Dispatch `action` and cache its response
+    pub async fn fetch<A: Action>(&self, action: A) -> Result<A::Response, ActionError> {
+        let key = Self::key(&action)?;
+        let response = self.broker.dispatch(action).await?;
+        if let Ok(text) = serde_json::to_string(&response) {
+            self.store.put(&key, text).await;
+        }
+        Ok(response)
+    }
+
+    /// Hand `on_value` the cached response at once, if there is one, then
+    /// the fresh response once it arrives and differs from the cached one
+    ///
+    /// Run it with `spawn_local` so the revalidation does not hold up the
+    /// caller. When there is a cached response, failing to revalidate is not
+    /// an error; the stale data stays on screen.
+    pub async fn query<A, F>(&self, action: A, mut on_value: F) -> Result<(), ActionError>
+    where
+        A: Action,
+        F: FnMut(A::Response),
+    {
+        let key = Self::key(&action)?;
+        let stale = self.store.get(&key).await;
+        let served = match stale.as_deref().map(serde_json::from_str) {
+            Some(Ok(value)) => {
+                on_value(value);
+                true
+            }
+            _ => false,
+        };
+
+        let fresh = match self.broker.dispatch(action).await {
+            Ok(fresh) => fresh,
+            Err(_) if served => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let text = serde_json::to_string(&fresh).ok();
+        if served && text.is_some() && text == stale {
+            return Ok(());
+        }
+        if let Some(text) = text {
+            self.store.put(&key, text).await;
+        }
+        on_value(fresh);
+        Ok(())
+    }
+
+    /// Forget responses to actions whose type starts with `prefix`
+    ///
+    /// `"asset."` drops every asset query, `"asset.list"` just the lists and
+    /// `""` everything.
+    pub async fn invalidate(&self, prefix: &str) {
+        self.store.remove_prefix(prefix).await;
+    }
+
+    /// Forget what the given kinds of data feed, see [`invalidated_by`]
+    pub async fn apply_changes<K: AsRef<str>>(&self, kinds: &[K]) {
+        for kind in kinds {
+            for prefix in invalidated_by(kind.as_ref()) {
+                self.invalidate(prefix).await;
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use idb::IndexedDbStore;
+
+#[cfg(target_arch = "wasm32")]
+mod idb {
+    use super::*;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{IdbDatabase, IdbKeyRange, IdbRequest, IdbTransactionMode};
+
+    const STORE: &str = "responses";
+
+    fn transport(e: JsValue) -> ActionError {
+        ActionError::Transport(e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+    }
+
+    /// Resolve with the result of an IndexedDB request
+    async fn complete(request: &IdbRequest) -> Result<JsValue, JsValue> {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
+            let done = request.clone();
+            let on_success = Closure::once_into_js(move || {
+                let _ = resolve.call1(&JsValue::NULL, &done.result().unwrap_or(JsValue::UNDEFINED));
+            });
+            let failed = request.clone();
+            let on_error = Closure::once_into_js(move || {
+                let error = failed.error().ok().flatten().map(JsValue::from);
+                let _ = reject.call1(&JsValue::NULL, &error.unwrap_or(JsValue::UNDEFINED));
+            });
+            request.set_onsuccess(Some(on_success.unchecked_ref()));
+            request.set_onerror(Some(on_error.unchecked_ref()));
+        });
+        JsFuture::from(promise).await
+    }
+
+    /// Responses kept in an IndexedDB database, surviving reloads
+    ///
+    /// Storage failures are logged and treated as cache misses.
+    pub struct IndexedDbStore {
+        db: IdbDatabase,
+    }
+
+    impl IndexedDbStore {
+        /// Open (creating if needed) the database `name`
+        pub async fn open(name: &str) -> Result<Self, ActionError> {
+            let factory = web_sys::window()
+                .ok_or_else(|| ActionError::Transport("No window".to_string()))?
+                .indexed_db()
+                .map_err(transport)?
+                .ok_or_else(|| ActionError::Transport("IndexedDB unavailable".to_string()))?;
+            let request = factory.open_with_u32(name, 1).map_err(transport)?;
+
+            let upgrading = request.clone();
+            let on_upgrade = Closure::once_into_js(move || {
+                if let Ok(db) = upgrading.result() {
+                    let db: IdbDatabase = db.unchecked_into();
+                    if let Err(e) = db.create_object_store(STORE) {
+                        web_sys::console::error_2(&"Cache store not created".into(), &e);
+                    }
+                }
+            });
+            request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+            let db = complete(&request).await.map_err(transport)?;
+            Ok(Self {
+                db: db.unchecked_into(),
+            })
+        }
+
+        async fn run(
+            &self,
+            mode: IdbTransactionMode,
+            op: impl FnOnce(&web_sys::IdbObjectStore) -> Result<IdbRequest, JsValue>,
+        ) -> Result<JsValue, JsValue> {
+            let tx = self.db.transaction_with_str_and_mode(STORE, mode)?;
+            let request = op(&tx.object_store(STORE)?)?;
+            complete(&request).await
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl CacheStore for IndexedDbStore {
+        async fn get(&self, key: &str) -> Option<String> {
+            let key = JsValue::from_str(key);
+            match self
+                .run(IdbTransactionMode::Readonly, |s| s.get(&key))
+                .await
+            {
+                Ok(value) => value.as_string(),
+                Err(e) => {
+                    web_sys::console::warn_2(&"Cache read failed".into(), &e);
+                    None
+                }
+            }
+        }
+
+        async fn put(&self, key: &str, value: String) {
+            let (key, value) = (JsValue::from_str(key), JsValue::from_str(&value));
+            let put = |s: &web_sys::IdbObjectStore| s.put_with_key(&value, &key);
+            if let Err(e) = self.run(IdbTransactionMode::Readwrite, put).await {
+                web_sys::console::warn_2(&"Cache write failed".into(), &e);
+            }
+        }
+
+        async fn remove_prefix(&self, prefix: &str) {
+            // Keys are strings, so every key with the prefix sorts below prefix + U+FFFF
+            let remove = |s: &web_sys::IdbObjectStore| {
+                let range = IdbKeyRange::bound(
+                    &JsValue::from_str(prefix),
+                    &JsValue::from_str(&format!("{}\u{ffff}", prefix)),
+                )?;
+                s.delete(&range)
+            };
+            if let Err(e) = self.run(IdbTransactionMode::Readwrite, remove).await {
+                web_sys::console::warn_2(&"Cache invalidation failed".into(), &e);
+            }
+        }
+    }
+
+    impl<B: ActionBroker + 'static, S: CacheStore + 'static> ResourceCache<B, S> {
+        /// Apply the server's change events from the SSE stream at `url`
+        ///
+        /// The returned source stays subscribed until it is closed.
+        pub fn listen(
+            cache: std::rc::Rc<Self>,
+            url: &str,
+        ) -> Result<web_sys::EventSource, ActionError> {
+            let source = web_sys::EventSource::new(url).map_err(transport)?;
+            let on_change = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(
+                move |event: web_sys::MessageEvent| {
+                    let Some(data) = event.data().as_string() else {
+                        return;
+                    };
+                    let kinds: Vec<String> = serde_json::from_str(&data).unwrap_or_default();
+                    let cache = cache.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        cache.apply_changes(&kinds).await;
+                    });
+                },
+            );
+            source
+                .add_event_listener_with_callback(CHANGE_EVENT, on_change.as_ref().unchecked_ref())
+                .map_err(transport)?;
+            // Lives as long as the page; the source holds the only reference
+            on_change.forget();
+            Ok(source)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PersonData, PersonnelAction, PersonnelListQuery, PersonnelResponse};
+    use std::cell::Cell;
+
+    /// Answers personnel lists with `names`, or fails once `down` is set
+    #[derive(Default)]
+    struct Directory {
+        names: RefCell<Vec<&'static str>>,
+        down: Cell<bool>,
+        calls: Cell<usize>,
+    }
+
+    #[async_trait(?Send)]
+    impl ActionBroker for Directory {
+        async fn dispatch<A: Action>(&self, action: A) -> Result<A::Response, ActionError> {
+            self.calls.set(self.calls.get() + 1);
+            if self.down.get() {
+                return Err(ActionError::Transport("offline".to_string()));
+            }
+            assert_eq!(action.action_type(), "personnel.list");
+            let people = self
+                .names
+                .borrow()
+                .iter()
+                .map(|name| PersonData {
+                    id: name.to_lowercase(),
+                    name: name.to_string(),
+                    email: None,
+                    department: None,
+                    title: None,
+                })
+                .collect();
+            let response = serde_json::to_value(PersonnelResponse::List(people)).unwrap();
+            Ok(serde_json::from_value(response).unwrap())
+        }
+    }
+
+    fn list() -> PersonnelAction {
+        PersonnelAction::List(PersonnelListQuery::default())
+    }
+
+    fn names(response: PersonnelResponse) -> Vec<String> {
+        match response {
+            PersonnelResponse::List(people) => people.into_iter().map(|p| p.name).collect(),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    async fn query(cache: &ResourceCache<Directory>) -> Result<Vec<Vec<String>>, ActionError> {
+        let mut seen = Vec::new();
+        cache.query(list(), |r| seen.push(names(r))).await?;
+        Ok(seen)
+    }
+
+    #[tokio::test]
+    async fn stale_data_first_then_fresh_when_it_changed() {
+        let cache = ResourceCache::in_memory(Directory::default());
+        cache.broker().names.borrow_mut().push("Ada");
+        assert_eq!(query(&cache).await.unwrap(), vec![vec!["Ada"]]);
+
+        // Unchanged: only the cached copy is delivered
+        assert_eq!(query(&cache).await.unwrap(), vec![vec!["Ada"]]);
+
+        cache.broker().names.borrow_mut().push("Grace");
+        assert_eq!(
+            query(&cache).await.unwrap(),
+            vec![vec!["Ada"], vec!["Ada", "Grace"]]
+        );
+        assert_eq!(
+            names(cache.cached(&list()).await.unwrap()),
+            ["Ada", "Grace"]
+        );
+        assert_eq!(cache.broker().calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_revalidation_keeps_stale_data() {
+        let cache = ResourceCache::in_memory(Directory::default());
+        cache.broker().down.set(true);
+        assert!(query(&cache).await.is_err());
+
+        cache.broker().down.set(false);
+        cache.fetch(list()).await.unwrap();
+        cache.broker().down.set(true);
+        assert_eq!(query(&cache).await.unwrap(), vec![Vec::<String>::new()]);
+    }
+
+    #[tokio::test]
+    async fn change_events_invalidate_what_they_feed() {
+        let cache = ResourceCache::in_memory(Directory::default());
+        cache.fetch(list()).await.unwrap();
+
+        cache.apply_changes(&["flags", "runs"]).await;
+        assert!(cache.cached(&list()).await.is_some());
+
+        cache.apply_changes(&["people"]).await;
+        assert!(cache.cached(&list()).await.is_none());
+
+        cache.fetch(list()).await.unwrap();
+        cache.apply_changes(&["something_new"]).await;
+        assert!(cache.cached(&list()).await.is_none());
+    }
+
+    #[test]
+    fn keys_start_with_the_action_type() {
+        let key = ResourceCache::<Directory>::key(&list()).unwrap();
+        assert!(key.starts_with("personnel.list|"));
+        assert_ne!(
+            key,
+            ResourceCache::<Directory>::key(&PersonnelAction::Get("a".into())).unwrap()
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Payload Codecs
+//!
+//! Actions are always sent as JSON; they are small. Responses can be large
+//! (asset lists, dashboard snapshots), so a broker may ask for a binary
+//! encoding instead. It lists the codecs it accepts, most preferred first,
+//! and the server answers with the first one it can produce for that action,
+//! naming it in the reply's content type. Anything the server can only
+//! produce as JSON, such as plugin responses, stays JSON.
+//!
+//! The binary codec is postcard, behind the `binary` feature.
+
+use crate::broker::ActionError;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Content type of JSON payloads
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// Content type of postcard payloads
+pub const POSTCARD_CONTENT_TYPE: &str = "application/x-postcard";
+
+/// Encoding of an action response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Codec {
+    #[default]
+    Json,
+    /// Compact, not self-describing: both ends must agree on the type
+    #[cfg(feature = "binary")]
+    Postcard,
+}
+
+impl Codec {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Codec::Json => JSON_CONTENT_TYPE,
+            #[cfg(feature = "binary")]
+            Codec::Postcard => POSTCARD_CONTENT_TYPE,
+        }
+    }
+
+    /// The codec a content type names, ignoring parameters like `charset`
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type.split(';').next()?.trim() {
+            JSON_CONTENT_TYPE => Some(Codec::Json),
+            #[cfg(feature = "binary")]
+            POSTCARD_CONTENT_TYPE => Some(Codec::Postcard),
+            _ => None,
+        }
+    }
+
+    /// `Accept` header asking for this codec, falling back to JSON
+    pub fn accept(self) -> String {
+        match self {
+            Codec::Json => JSON_CONTENT_TYPE.to_string(),
+            #[cfg(feature = "binary")]
+            Codec::Postcard => format!("{}, {}", POSTCARD_CONTENT_TYPE, JSON_CONTENT_TYPE),
+        }
+    }
+
+    /// The first codec in an `Accept` header that this build supports
+    ///
+    /// Types are taken in the order listed and q-values are ignored; with no
+    /// supported type the answer is JSON.
+    pub fn negotiate(accept: &str) -> Self {
+        accept
+            .split(',')
+            .find_map(Codec::from_content_type)
+            .unwrap_or_default()
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, ActionError> {
+        match self {
+            Codec::Json => {
+                serde_json::to_vec(value).map_err(|e| ActionError::Serialization(e.to_string()))
+            }
+            #[cfg(feature = "binary")]
+            Codec::Postcard => {
+                postcard::to_allocvec(value).map_err(|e| ActionError::Serialization(e.to_string()))
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, ActionError> {
+        match self {
+            Codec::Json => serde_json::from_slice(bytes)
+                .map_err(|e| ActionError::Deserialization(e.to_string())),
+            #[cfg(feature = "binary")]
+            Codec::Postcard => {
+                postcard::from_bytes(bytes).map_err(|e| ActionError::Deserialization(e.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AssetData, AssetResponse};
+
+    fn assets() -> AssetResponse {
+        AssetResponse::List(vec![AssetData {
+            id: "asset:1".to_string(),
+            name: "Core switch".to_string(),
+            manufacturer: "Cisco".to_string(),
+            model: "C9500".to_string(),
+            serial_number: "ABC123".to_string(),
+            category: "Network".to_string(),
+            status: "deployed".to_string(),
+            lifecycle: "deployed".to_string(),
+            warranty_end: None,
+            rack_id: Some("r1".to_string()),
+            position_u: Some(12),
+        }])
+    }
+
+    #[test]
+    fn content_types_ignore_parameters() {
+        assert_eq!(
+            Codec::from_content_type("application/json; charset=utf-8"),
+            Some(Codec::Json)
+        );
+        assert_eq!(Codec::from_content_type("text/html"), None);
+        assert_eq!(Codec::negotiate("text/html, */*"), Codec::Json);
+        assert_eq!(Codec::negotiate(""), Codec::Json);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let bytes = Codec::Json.encode(&assets()).unwrap();
+        let decoded: AssetResponse = Codec::Json.decode(&bytes).unwrap();
+        assert!(matches!(decoded, AssetResponse::List(list) if list[0].name == "Core switch"));
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn postcard_is_negotiated_and_smaller() {
+        assert_eq!(Codec::negotiate(&Codec::Postcard.accept()), Codec::Postcard);
+        assert_eq!(
+            Codec::negotiate("application/json, application/x-postcard"),
+            Codec::Json
+        );
+
+        let json = Codec::Json.encode(&assets()).unwrap();
+        let binary = Codec::Postcard.encode(&assets()).unwrap();
+        assert!(binary.len() < json.len());
+        let decoded: AssetResponse = Codec::Postcard.decode(&binary).unwrap();
+        assert!(matches!(decoded, AssetResponse::List(list) if list[0].serial_number == "ABC123"));
+    }
+
+    #[cfg(not(feature = "binary"))]
+    #[test]
+    fn postcard_is_refused_without_the_feature() {
+        assert_eq!(Codec::from_content_type(POSTCARD_CONTENT_TYPE), None);
+        assert_eq!(Codec::negotiate(POSTCARD_CONTENT_TYPE), Codec::Json);
+    }
+}
@@ -0,0 +1,299 @@
+//! Collaborative Editing
+//!
+//! Live, field-level sync for entity forms. Every tab editing a record
+//! joins `GET /api/collab/ws?persona=...&entity=...` (entity keys as in
+//! [`presence`](crate::presence)) and receives a [`ServerFrame::Snapshot`]
+//! of the fields edited so far, then each accepted [`FieldEdit`] as a
+//! [`ServerFrame::Field`].
+//!
+//! Merging is last-writer-wins per field: the server orders edits and
+//! stamps each field with a version, so two people typing in different
+//! fields never interfere. When an edit replaces a value its author had not
+//! seen yet, both sides get a conflict on that field to review. Saving
+//! still goes through the form's usual action; the channel only carries
+//! unsaved edits, and the server forgets a record once its last editor
+//! leaves.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// WebSocket endpoint, with `persona` and `entity` query parameters
+pub const COLLAB_PATH: &str = "/api/collab/ws";
+
+/// A field's current value and who set it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldState {
+    pub value: String,
+    /// Increases with every accepted edit to the record
+    pub version: u64,
+    pub author: String,
+}
+
+/// Client to server: set `field` to `value`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldEdit {
+    pub field: String,
+    pub value: String,
+    /// Version of the field the author last saw; 0 if none
+    pub base: u64,
+}
+
+/// An accepted edit, as broadcast to every editor of the record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldUpdate {
+    pub field: String,
+    pub state: FieldState,
+    /// Author of the value this edit replaced without having seen it
+    pub overwrote: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerFrame {
+    /// Fields edited so far, sent once on joining
+    Snapshot {
+        fields: BTreeMap<String, FieldState>,
+    },
+    Field(FieldUpdate),
+    Error {
+        message: String,
+    },
+}
+
+/// The server's copy of one record's unsaved edits
+#[derive(Debug, Clone, Default)]
+pub struct EntityDoc {
+    fields: BTreeMap<String, FieldState>,
+    clock: u64,
+}
+
+impl EntityDoc {
+    pub fn fields(&self) -> &BTreeMap<String, FieldState> {
+        &self.fields
+    }
+
+    /// Accept `edit` from `author`; the latest edit to a field always wins
+    pub fn apply(&mut self, author: &str, edit: FieldEdit) -> FieldUpdate {
+        self.clock += 1;
+        let state = FieldState {
+            value: edit.value,
+            version: self.clock,
+            author: author.to_string(),
+        };
+        let overwrote = self
+            .fields
+            .insert(edit.field.clone(), state.clone())
+            .filter(|previous| previous.version > edit.base && previous.author != author)
+            .map(|previous| previous.author);
+        FieldUpdate {
+            field: edit.field,
+            state,
+            overwrote,
+        }
+    }
+}
+
+/// One tab's view of a shared record
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CollabSession {
+    me: String,
+    /// Whether the snapshot has arrived; edits before it stay local
+    ready: bool,
+    fields: BTreeMap<String, FieldState>,
+    /// Fields with an edit sent but not yet echoed back
+    pending: BTreeSet<String>,
+    /// Fields whose value was contested, with the other author
+    conflicts: BTreeMap<String, String>,
+}
+
+impl CollabSession {
+    pub fn new(me: impl Into<String>) -> Self {
+        Self {
+            me: me.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Latest shared value of `field`, if anyone has edited it
+    pub fn value(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(|state| state.value.as_str())
+    }
+
+    /// Who else changed `field` in a way that collided with this tab
+    pub fn conflict(&self, field: &str) -> Option<&str> {
+        self.conflicts.get(field).map(String::as_str)
+    }
+
+    pub fn dismiss(&mut self, field: &str) {
+        self.conflicts.remove(field);
+    }
+
+    /// The frame to send for a local change, `None` if there is nothing new
+    /// to share or the session is not ready yet
+    pub fn local_edit(&mut self, field: &str, value: &str) -> Option<FieldEdit> {
+        if !self.ready || self.value(field) == Some(value) {
+            return None;
+        }
+        self.pending.insert(field.to_string());
+        Some(FieldEdit {
+            field: field.to_string(),
+            value: value.to_string(),
+            base: self.fields.get(field).map_or(0, |state| state.version),
+        })
+    }
+
+    /// Apply a frame from the server; returns the fields whose value the
+    /// form should take
+    pub fn receive(&mut self, frame: ServerFrame) -> Vec<String> {
+        match frame {
+            ServerFrame::Snapshot { fields } => {
+                self.ready = true;
+                // A resent snapshot must not undo edits still in flight
+                let changed = fields
+                    .keys()
+                    .filter(|field| !self.pending.contains(*field))
+                    .cloned()
+                    .collect();
+                self.fields = fields;
+                changed
+            }
+            ServerFrame::Field(update) => {
+                let field = update.field.clone();
+                let mine = update.state.author == self.me;
+                if mine {
+                    self.pending.remove(&field);
+                    if let Some(other) = update.overwrote {
+                        self.conflicts.insert(field.clone(), other);
+                    }
+                } else if self.pending.contains(&field) {
+                    // Ours was sent first but is applied after; it wins
+                    self.conflicts
+                        .insert(field.clone(), update.state.author.clone());
+                }
+                let keep_local = mine || self.pending.contains(&field);
+                self.fields.insert(field.clone(), update.state);
+                if keep_local {
+                    Vec::new()
+                } else {
+                    vec![field]
+                }
+            }
+            ServerFrame::Error { .. } => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(field: &str, value: &str, base: u64) -> FieldEdit {
+        FieldEdit {
+            field: field.to_string(),
+            value: value.to_string(),
+            base,
+        }
+    }
+
+    #[test]
+    fn doc_flags_blind_overwrites_only() {
+        let mut doc = EntityDoc::default();
+        let first = doc.apply("Ada", edit("title", "Standup", 0));
+        assert_eq!(first.overwrote, None);
+
+        // Refining your own value is not a conflict
+        assert_eq!(doc.apply("Ada", edit("title", "Daily", 0)).overwrote, None);
+
+        // Bo saw version 1 but Ada has since written version 2
+        let blind = doc.apply("Bo", edit("title", "Sync", 1));
+        assert_eq!(blind.overwrote.as_deref(), Some("Ada"));
+        assert_eq!(doc.fields()["title"].value, "Sync");
+
+        // Other fields merge independently
+        assert_eq!(
+            doc.apply("Ada", edit("location", "Room 1", 0)).overwrote,
+            None
+        );
+        assert_eq!(doc.fields().len(), 2);
+    }
+
+    #[test]
+    fn session_waits_for_snapshot_and_skips_known_values() {
+        let mut session = CollabSession::new("Ada");
+        assert_eq!(session.local_edit("title", "Standup"), None);
+
+        let mut fields = BTreeMap::new();
+        fields.insert(
+            "title".to_string(),
+            FieldState {
+                value: "Standup".into(),
+                version: 3,
+                author: "Bo".into(),
+            },
+        );
+        assert_eq!(
+            session.receive(ServerFrame::Snapshot { fields }),
+            vec!["title"]
+        );
+        assert_eq!(session.local_edit("title", "Standup"), None);
+        assert_eq!(session.local_edit("title", "Daily").unwrap().base, 3);
+    }
+
+    #[test]
+    fn session_keeps_pending_edit_and_records_conflict() {
+        let mut session = CollabSession::new("Ada");
+        session.receive(ServerFrame::Snapshot {
+            fields: BTreeMap::new(),
+        });
+        session.local_edit("title", "Mine").unwrap();
+
+        // Bo's edit lands first; the form keeps Ada's pending value
+        let theirs = FieldUpdate {
+            field: "title".into(),
+            state: FieldState {
+                value: "Theirs".into(),
+                version: 1,
+                author: "Bo".into(),
+            },
+            overwrote: None,
+        };
+        assert!(session.receive(ServerFrame::Field(theirs)).is_empty());
+        assert_eq!(session.conflict("title"), Some("Bo"));
+
+        // Then Ada's echo arrives and settles the field
+        let echo = FieldUpdate {
+            field: "title".into(),
+            state: FieldState {
+                value: "Mine".into(),
+                version: 2,
+                author: "Ada".into(),
+            },
+            overwrote: Some("Bo".into()),
+        };
+        assert!(session.receive(ServerFrame::Field(echo)).is_empty());
+        assert_eq!(session.value("title"), Some("Mine"));
+
+        session.dismiss("title");
+        assert_eq!(session.conflict("title"), None);
+
+        // An uncontested remote edit is applied to the form
+        let remote = FieldUpdate {
+            field: "location".into(),
+            state: FieldState {
+                value: "Room 1".into(),
+                version: 3,
+                author: "Bo".into(),
+            },
+            overwrote: None,
+        };
+        assert_eq!(
+            session.receive(ServerFrame::Field(remote)),
+            vec!["location"]
+        );
+    }
+}
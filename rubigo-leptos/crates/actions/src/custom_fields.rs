@@ -0,0 +1,253 @@
+//! Custom Fields
+//!
+//! Rules shared by the custom field handlers and the UI. Admins define extra
+//! fields per kind of record (`"asset"`, `"person"`, `"site"` or `"event"`,
+//! matching the kind in an entity key such as `asset:7`); each record keeps
+//! its values as text by field key, in the normalized form
+//! [`normalize_value`] produces.
+
+use std::collections::BTreeMap;
+
+use crate::types::CustomFieldData;
+
+/// Kinds of record custom fields can be defined for
+pub const FIELD_ENTITY_KINDS: [&str; 4] = ["asset", "person", "site", "event"];
+
+/// Every field type
+pub const FIELD_KINDS: [&str; 4] = ["text", "number", "date", "select"];
+
+/// Longest field label, in characters
+pub const MAX_FIELD_LABEL_LEN: usize = 60;
+
+/// Longest text value, in characters
+pub const MAX_FIELD_VALUE_LEN: usize = 2000;
+
+/// Key for a field labelled `label`, e.g. "Warranty ends" becomes `warranty_ends`
+pub fn field_key(label: &str) -> String {
+    let mut key = String::new();
+    for c in label.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            key.push(c.to_ascii_lowercase());
+        } else if !key.is_empty() && !key.ends_with('_') {
+            key.push('_');
+        }
+    }
+    key.trim_end_matches('_').to_string()
+}
+
+/// Why a field definition is invalid, if it is; `existing` are the fields
+/// already defined for the same kind of record, including the one being
+/// changed
+pub fn validate_definition(
+    field: &CustomFieldData,
+    existing: &[CustomFieldData],
+) -> Option<String> {
+    let label = field.label.trim();
+    if label.is_empty() {
+        return Some("A field needs a label".to_string());
+    }
+    if label.chars().count() > MAX_FIELD_LABEL_LEN {
+        return Some(format!(
+            "Field labels are limited to {MAX_FIELD_LABEL_LEN} characters"
+        ));
+    }
+    if !FIELD_ENTITY_KINDS.contains(&field.entity_kind.as_str()) {
+        return Some(format!("Unknown record kind: {}", field.entity_kind));
+    }
+    if !FIELD_KINDS.contains(&field.kind.as_str()) {
+        return Some(format!("Unknown field type: {}", field.kind));
+    }
+    if field.key.is_empty() || field_key(&field.key) != field.key {
+        return Some(format!("{} is not a valid field key", field.key));
+    }
+    if field.kind == "select" {
+        if field.options.iter().all(|o| o.trim().is_empty()) {
+            return Some("A select field needs at least one option".to_string());
+        }
+        let mut seen = Vec::new();
+        for option in field.options.iter().map(|o| o.trim()) {
+            if seen.contains(&option) {
+                return Some(format!("{option} is listed twice"));
+            }
+            seen.push(option);
+        }
+    }
+    let clash = existing.iter().any(|other| {
+        other.id != field.id && (other.key == field.key || other.label.eq_ignore_ascii_case(label))
+    });
+    clash.then(|| format!("A field named {label} already exists"))
+}
+
+/// A value as stored for `field`, or why it doesn't fit
+///
+/// Values are trimmed, numbers are written without redundant digits and
+/// dates must be `YYYY-MM-DD`. An empty value clears the field.
+pub fn normalize_value(field: &CustomFieldData, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(String::new());
+    }
+    match field.kind.as_str() {
+        "number" => match value.parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(n.to_string()),
+            _ => Err(format!("{}: {value} is not a number", field.label)),
+        },
+        "date" if is_date(value) => Ok(value.to_string()),
+        "date" => Err(format!("{}: {value} is not a YYYY-MM-DD date", field.label)),
+        "select" if field.options.iter().any(|o| o.trim() == value) => Ok(value.to_string()),
+        "select" => Err(format!(
+            "{}: {value} is not one of the options",
+            field.label
+        )),
+        _ if value.chars().count() > MAX_FIELD_VALUE_LEN => Err(format!(
+            "{}: values are limited to {MAX_FIELD_VALUE_LEN} characters",
+            field.label
+        )),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// Merge `changes` into a record's `stored` values
+///
+/// Every changed key must be a defined field and fit it, and required fields
+/// must end up with a value; otherwise nothing is merged. Emptied fields are
+/// dropped.
+pub fn merge_values(
+    fields: &[CustomFieldData],
+    stored: &BTreeMap<String, String>,
+    changes: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut merged = stored.clone();
+    for (key, value) in changes {
+        let field = fields
+            .iter()
+            .find(|f| &f.key == key)
+            .ok_or_else(|| format!("Unknown field: {key}"))?;
+        let value = normalize_value(field, value)?;
+        if value.is_empty() {
+            merged.remove(key);
+        } else {
+            merged.insert(key.clone(), value);
+        }
+    }
+    if let Some(missing) = fields
+        .iter()
+        .find(|f| f.required && !merged.contains_key(&f.key))
+    {
+        return Err(format!("{} is required", missing.label));
+    }
+    Ok(merged)
+}
+
+/// Whether `value` is a calendar date written `YYYY-MM-DD`
+fn is_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(label: &str, kind: &str) -> CustomFieldData {
+        CustomFieldData {
+            id: String::new(),
+            entity_kind: "asset".to_string(),
+            key: field_key(label),
+            label: label.to_string(),
+            kind: kind.to_string(),
+            options: Vec::new(),
+            required: false,
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn definitions_are_validated() {
+        assert_eq!(field_key(" Warranty ends (UTC) "), "warranty_ends_utc");
+
+        let mut existing = field("Warranty ends", "date");
+        existing.id = "w".to_string();
+        let existing = [existing];
+        assert_eq!(
+            validate_definition(&field("Cost centre", "text"), &existing),
+            None
+        );
+        assert!(validate_definition(&field("warranty ENDS", "text"), &existing).is_some());
+        // Changing a field doesn't clash with itself
+        assert_eq!(validate_definition(&existing[0], &existing), None);
+
+        assert!(validate_definition(&field("", "text"), &existing).is_some());
+        assert!(validate_definition(&field("Cost", "money"), &existing).is_some());
+        let mut tier = field("Tier", "select");
+        assert!(validate_definition(&tier, &existing).is_some());
+        tier.options = vec!["Gold".to_string(), "Silver".to_string()];
+        assert_eq!(validate_definition(&tier, &existing), None);
+        let mut person = field("Tier", "text");
+        person.entity_kind = "building".to_string();
+        assert!(validate_definition(&person, &existing).is_some());
+    }
+
+    #[test]
+    fn values_are_normalized_and_merged() {
+        let mut tier = field("Tier", "select");
+        tier.options = vec!["Gold".to_string(), "Silver".to_string()];
+        tier.required = true;
+        let fields = [
+            field("Cost", "number"),
+            field("Warranty ends", "date"),
+            tier,
+        ];
+
+        assert_eq!(
+            normalize_value(&fields[0], " 12.50 "),
+            Ok("12.5".to_string())
+        );
+        assert!(normalize_value(&fields[0], "twelve").is_err());
+        assert!(normalize_value(&fields[1], "2024-02-29").is_ok());
+        assert!(normalize_value(&fields[1], "2023-02-29").is_err());
+        assert!(normalize_value(&fields[2], "Bronze").is_err());
+
+        let changes = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let stored = changes(&[("tier", "Gold"), ("cost", "3")]);
+        let merged = merge_values(
+            &fields,
+            &stored,
+            &changes(&[("cost", ""), ("warranty_ends", "2027-01-31")]),
+        )
+        .unwrap();
+        assert_eq!(
+            merged,
+            changes(&[("tier", "Gold"), ("warranty_ends", "2027-01-31")])
+        );
+
+        assert!(merge_values(&fields, &stored, &changes(&[("tier", "")])).is_err());
+        assert!(merge_values(&fields, &stored, &changes(&[("colour", "red")])).is_err());
+    }
+}
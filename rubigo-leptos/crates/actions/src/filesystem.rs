@@ -0,0 +1,365 @@
+//! FileSystemBroker Capability
+//!
+//! Importing a scenario directory, saving an export where the user chooses
+//! and opening what was saved work differently per deployment: the Tauri
+//! shell shows native dialogs ([`TauriFileSystem`]), while a browser falls
+//! back to a directory picker, downloads and new tabs (`WebFileSystem`).
+//! Either way the caller gets `None` when the user cancels.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::broker::ActionError;
+
+/// File naming a scenario's modules; a scenario directory must have one
+pub const SCENARIO_MANIFEST: &str = "scenario.toml";
+
+/// Tauri command names, as registered by the shell
+pub const IMPORT_SCENARIO_COMMAND: &str = "import_scenario";
+pub const EXPORT_FILE_COMMAND: &str = "export_file";
+pub const OPEN_ARTIFACT_COMMAND: &str = "open_artifact";
+
+/// One TOML file of a scenario directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioFile {
+    /// File name within the directory, e.g. `personnel.toml`
+    pub name: String,
+    pub contents: String,
+}
+
+/// The TOML files at the top of a scenario directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioFiles {
+    /// Name of the chosen directory
+    pub directory: String,
+    pub files: Vec<ScenarioFile>,
+}
+
+impl ScenarioFiles {
+    /// Keep the TOML files, rejecting a directory without [`SCENARIO_MANIFEST`]
+    pub fn new(
+        directory: impl Into<String>,
+        files: Vec<ScenarioFile>,
+    ) -> Result<Self, ActionError> {
+        let directory = directory.into();
+        let files: Vec<ScenarioFile> = files
+            .into_iter()
+            .filter(|file| is_scenario_file(&file.name))
+            .collect();
+        if !files.iter().any(|file| file.name == SCENARIO_MANIFEST) {
+            return Err(ActionError::Rejected(format!(
+                "'{}' has no {}",
+                directory, SCENARIO_MANIFEST
+            )));
+        }
+        Ok(Self { directory, files })
+    }
+
+    /// Contents of the file `name`, as the manifest's module entries refer to it
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|file| file.name == name)
+            .map(|file| file.contents.as_str())
+    }
+}
+
+/// Whether `name` is a file a scenario is read from
+pub fn is_scenario_file(name: &str) -> bool {
+    name.ends_with(".toml") && !name.starts_with('.')
+}
+
+/// A document to save, such as a report or a CSV export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportRequest {
+    /// Suggested file name, with the extension of its format
+    pub file_name: String,
+    pub contents: Vec<u8>,
+}
+
+impl ExportRequest {
+    pub fn new(file_name: impl Into<String>, contents: impl Into<Vec<u8>>) -> Self {
+        Self {
+            file_name: file_name.into(),
+            contents: contents.into(),
+        }
+    }
+
+    /// Save dialog filter for the file's format: label and extensions
+    pub fn filter(&self) -> Option<(&'static str, &'static [&'static str])> {
+        let extension = self.file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+        Some(match extension.as_str() {
+            "csv" => ("CSV", &["csv"]),
+            "json" => ("JSON", &["json"]),
+            "xlsx" => ("Excel Workbook", &["xlsx"]),
+            "pdf" => ("PDF", &["pdf"]),
+            "html" | "htm" => ("HTML", &["html", "htm"]),
+            _ => return None,
+        })
+    }
+}
+
+/// Where an export ended up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedFile {
+    pub name: String,
+    /// Full path, when known; a browser download doesn't say
+    pub path: Option<String>,
+}
+
+/// Trait for file access implementations
+#[async_trait(?Send)]
+pub trait FileSystemBroker {
+    /// Let the user pick a scenario directory and read its TOML files
+    async fn import_scenario(&self) -> Result<Option<ScenarioFiles>, ActionError>;
+
+    /// Let the user choose where to save `request`
+    async fn export_file(&self, request: ExportRequest) -> Result<Option<SavedFile>, ActionError>;
+
+    /// Open a saved file or a URL with what the system (or browser) uses for it
+    async fn open_artifact(&self, target: &str) -> Result<(), ActionError>;
+}
+
+/// File access through the Tauri shell's native dialogs
+pub struct TauriFileSystem;
+
+#[cfg(target_arch = "wasm32")]
+impl TauriFileSystem {
+    async fn invoke<T: serde::de::DeserializeOwned>(
+        cmd: &str,
+        args: serde_json::Value,
+    ) -> Result<T, ActionError> {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen_futures::JsFuture;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+            fn tauri_invoke(cmd: &str, args: JsValue) -> js_sys::Promise;
+        }
+
+        let args = serde_wasm_bindgen::to_value(&args)
+            .map_err(|e| ActionError::Serialization(e.to_string()))?;
+        let result = JsFuture::from(tauri_invoke(cmd, args)).await.map_err(|e| {
+            ActionError::Transport(e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+        })?;
+        serde_wasm_bindgen::from_value(result)
+            .map_err(|e| ActionError::Deserialization(e.to_string()))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait(?Send)]
+impl FileSystemBroker for TauriFileSystem {
+    async fn import_scenario(&self) -> Result<Option<ScenarioFiles>, ActionError> {
+        Self::invoke(IMPORT_SCENARIO_COMMAND, serde_json::json!({})).await
+    }
+
+    async fn export_file(&self, request: ExportRequest) -> Result<Option<SavedFile>, ActionError> {
+        Self::invoke(
+            EXPORT_FILE_COMMAND,
+            serde_json::json!({ "request": request }),
+        )
+        .await
+    }
+
+    async fn open_artifact(&self, target: &str) -> Result<(), ActionError> {
+        Self::invoke(
+            OPEN_ARTIFACT_COMMAND,
+            serde_json::json!({ "target": target }),
+        )
+        .await
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait(?Send)]
+impl FileSystemBroker for TauriFileSystem {
+    async fn import_scenario(&self) -> Result<Option<ScenarioFiles>, ActionError> {
+        Err(unavailable())
+    }
+
+    async fn export_file(&self, _request: ExportRequest) -> Result<Option<SavedFile>, ActionError> {
+        Err(unavailable())
+    }
+
+    async fn open_artifact(&self, _target: &str) -> Result<(), ActionError> {
+        Err(unavailable())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unavailable() -> ActionError {
+    ActionError::Transport("TauriFileSystem requires WASM target in Tauri WebView".to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use web::{file_system, WebFileSystem};
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use super::*;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{HtmlAnchorElement, HtmlInputElement};
+
+    fn transport(e: JsValue) -> ActionError {
+        ActionError::Transport(e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+    }
+
+    fn window() -> Result<web_sys::Window, ActionError> {
+        web_sys::window().ok_or_else(|| ActionError::Transport("No window".to_string()))
+    }
+
+    fn document() -> Result<web_sys::Document, ActionError> {
+        window()?
+            .document()
+            .ok_or_else(|| ActionError::Transport("No document".to_string()))
+    }
+
+    /// The Tauri shell's file access when running in it, else the browser's
+    pub fn file_system() -> Box<dyn FileSystemBroker> {
+        if js_sys::Reflect::has(&js_sys::global(), &"__TAURI__".into()).unwrap_or(false) {
+            Box::new(TauriFileSystem)
+        } else {
+            Box::new(WebFileSystem)
+        }
+    }
+
+    /// File access with what a browser offers: a directory upload input,
+    /// downloads, and new tabs
+    pub struct WebFileSystem;
+
+    impl WebFileSystem {
+        /// Show a directory picker; resolves once files were chosen or the
+        /// picker was dismissed
+        async fn pick_directory() -> Result<Option<web_sys::FileList>, ActionError> {
+            let input: HtmlInputElement = document()?
+                .create_element("input")
+                .map_err(transport)?
+                .unchecked_into();
+            input.set_type("file");
+            input.set_multiple(true);
+            input
+                .set_attribute("webkitdirectory", "")
+                .map_err(transport)?;
+
+            let promise = js_sys::Promise::new(&mut |resolve, _| {
+                let done = Closure::once_into_js(move || {
+                    let _ = resolve.call0(&JsValue::NULL);
+                });
+                input.set_onchange(Some(done.unchecked_ref()));
+                let _ = input.add_event_listener_with_callback("cancel", done.unchecked_ref());
+            });
+            input.click();
+            JsFuture::from(promise).await.map_err(transport)?;
+            Ok(input.files().filter(|files| files.length() > 0))
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl FileSystemBroker for WebFileSystem {
+        async fn import_scenario(&self) -> Result<Option<ScenarioFiles>, ActionError> {
+            let Some(list) = Self::pick_directory().await? else {
+                return Ok(None);
+            };
+            let mut directory = String::new();
+            let mut files = Vec::new();
+            for file in (0..list.length()).filter_map(|i| list.item(i)) {
+                // `webkitRelativePath` is `<directory>/<name>`; deeper files
+                // belong to subdirectories
+                let relative = js_sys::Reflect::get(&file, &"webkitRelativePath".into())
+                    .ok()
+                    .and_then(|path| path.as_string())
+                    .unwrap_or_default();
+                let mut parts = relative.split('/');
+                let (Some(dir), Some(name), None) = (parts.next(), parts.next(), parts.next())
+                else {
+                    continue;
+                };
+                if !is_scenario_file(name) {
+                    continue;
+                }
+                directory = dir.to_string();
+                let contents = JsFuture::from(file.text()).await.map_err(transport)?;
+                files.push(ScenarioFile {
+                    name: name.to_string(),
+                    contents: contents.as_string().unwrap_or_default(),
+                });
+            }
+            ScenarioFiles::new(directory, files).map(Some)
+        }
+
+        async fn export_file(
+            &self,
+            request: ExportRequest,
+        ) -> Result<Option<SavedFile>, ActionError> {
+            let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(request.contents.as_slice()));
+            let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(transport)?;
+            let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(transport)?;
+
+            let anchor: HtmlAnchorElement = document()?
+                .create_element("a")
+                .map_err(transport)?
+                .unchecked_into();
+            anchor.set_href(&url);
+            anchor.set_download(&request.file_name);
+            anchor.click();
+            let _ = web_sys::Url::revoke_object_url(&url);
+
+            Ok(Some(SavedFile {
+                name: request.file_name,
+                path: None,
+            }))
+        }
+
+        async fn open_artifact(&self, target: &str) -> Result<(), ActionError> {
+            window()?
+                .open_with_url_and_target(target, "_blank")
+                .map_err(transport)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str) -> ScenarioFile {
+        ScenarioFile {
+            name: name.to_string(),
+            contents: format!("# {}", name),
+        }
+    }
+
+    #[test]
+    fn scenario_directories_need_a_manifest() {
+        let files = vec![
+            file(SCENARIO_MANIFEST),
+            file("personnel.toml"),
+            file("notes.md"),
+        ];
+        let scenario = ScenarioFiles::new("mmc", files).unwrap();
+        assert_eq!(scenario.files.len(), 2);
+        assert_eq!(scenario.get("personnel.toml"), Some("# personnel.toml"));
+        assert_eq!(scenario.get("notes.md"), None);
+
+        let err = ScenarioFiles::new("photos", vec![file("sites.toml")]).unwrap_err();
+        assert!(matches!(err, ActionError::Rejected(_)));
+    }
+
+    #[test]
+    fn export_filters_follow_the_extension() {
+        assert_eq!(
+            ExportRequest::new("people.CSV", "id,name").filter(),
+            Some(("CSV", &["csv"][..]))
+        );
+        assert_eq!(
+            ExportRequest::new("report.pdf", vec![]).filter().unwrap().0,
+            "PDF"
+        );
+        assert_eq!(ExportRequest::new("notes", vec![]).filter(), None);
+    }
+}
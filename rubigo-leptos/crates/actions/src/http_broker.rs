@@ -4,11 +4,14 @@
 //! Used in browser/Axum deployments.
 
 use crate::broker::{Action, ActionBroker, ActionError};
+use crate::codec::Codec;
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 
 /// HTTP-based action broker for browser deployments
 pub struct HttpBroker {
     base_url: String,
+    codec: Codec,
 }
 
 impl HttpBroker {
@@ -16,14 +19,112 @@ impl HttpBroker {
     pub fn new(base_url: impl Into<String>) -> Self {
         Self {
             base_url: base_url.into(),
+            codec: Codec::Json,
         }
     }
 
+    /// Ask for responses in `codec`; the server may still answer in JSON
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
     /// Create a broker pointing to the current origin
     pub fn from_origin() -> Self {
         // In browser, we can use relative URLs
         Self::new("/api/actions")
     }
+
+    /// Fetch a list endpoint such as `/api/people`, handing rows to `on_rows`
+    /// in batches as they arrive
+    ///
+    /// Asks for NDJSON so the first rows can be shown before the rest are
+    /// downloaded; a server that answers with a JSON array instead delivers
+    /// every row in one batch. Returns the number of rows received.
+    pub async fn stream_list<T, F>(&self, url: &str, mut on_rows: F) -> Result<usize, ActionError>
+    where
+        T: DeserializeOwned,
+        F: FnMut(Vec<T>),
+    {
+        #[cfg(target_arch = "wasm32")]
+        {
+            use crate::ndjson::{NdjsonDecoder, NDJSON_CONTENT_TYPE};
+            use gloo_net::http::Request;
+            use wasm_bindgen::JsCast;
+            use wasm_bindgen_futures::JsFuture;
+
+            let transport = |e: wasm_bindgen::JsValue| {
+                ActionError::Transport(e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+            };
+
+            let accept = format!(
+                "{}, {}",
+                NDJSON_CONTENT_TYPE,
+                crate::codec::JSON_CONTENT_TYPE
+            );
+            let response = Request::get(url)
+                .header("Accept", &accept)
+                .send()
+                .await
+                .map_err(|e| ActionError::Transport(e.to_string()))?;
+
+            if !response.ok() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(ActionError::Server(format!("HTTP {}: {}", status, text)));
+            }
+
+            let streamed = response
+                .headers()
+                .get("content-type")
+                .is_some_and(|ct| ct.starts_with(NDJSON_CONTENT_TYPE));
+            let body = match response.body() {
+                Some(body) if streamed => body,
+                _ => {
+                    let rows: Vec<T> = response
+                        .json()
+                        .await
+                        .map_err(|e| ActionError::Deserialization(e.to_string()))?;
+                    let count = rows.len();
+                    on_rows(rows);
+                    return Ok(count);
+                }
+            };
+
+            let reader: web_sys::ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+            let mut decoder = NdjsonDecoder::new();
+            let mut count = 0;
+            loop {
+                let result = JsFuture::from(reader.read()).await.map_err(transport)?;
+                let done = js_sys::Reflect::get(&result, &"done".into())
+                    .map_err(transport)?
+                    .as_bool()
+                    .unwrap_or(true);
+                if done {
+                    break;
+                }
+                let chunk = js_sys::Reflect::get(&result, &"value".into()).map_err(transport)?;
+                let rows: Vec<T> = decoder.push(&js_sys::Uint8Array::new(&chunk).to_vec())?;
+                if !rows.is_empty() {
+                    count += rows.len();
+                    on_rows(rows);
+                }
+            }
+            if let Some(row) = decoder.finish()? {
+                count += 1;
+                on_rows(vec![row]);
+            }
+            Ok(count)
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = (url, &mut on_rows);
+            Err(ActionError::Transport(
+                "HttpBroker requires WASM target. Use native client for non-browser.".to_string(),
+            ))
+        }
+    }
 }
 
 #[async_trait(?Send)]
@@ -44,7 +145,8 @@ impl ActionBroker for HttpBroker {
             use gloo_net::http::Request;
 
             let response = Request::post(&url)
-                .header("Content-Type", "application/json")
+                .header("Content-Type", crate::codec::JSON_CONTENT_TYPE)
+                .header("Accept", &self.codec.accept())
                 .body(&body)
                 .map_err(|e| ActionError::Transport(e.to_string()))?
                 .send()
@@ -57,19 +159,25 @@ impl ActionBroker for HttpBroker {
                 return Err(ActionError::Server(format!("HTTP {}: {}", status, text)));
             }
 
-            let text = response
-                .text()
+            // Decode whatever the server chose to answer with
+            let codec = response
+                .headers()
+                .get("content-type")
+                .and_then(|ct| Codec::from_content_type(&ct))
+                .unwrap_or_default();
+            let bytes = response
+                .binary()
                 .await
                 .map_err(|e| ActionError::Transport(e.to_string()))?;
 
-            serde_json::from_str(&text).map_err(|e| ActionError::Deserialization(e.to_string()))
+            codec.decode(&bytes)
         }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
             // Native implementation would use reqwest
             // For now, return an error as this is primarily for WASM
-            let _ = (url, body);
+            let _ = (url, body, self.codec);
             Err(ActionError::Transport(
                 "HttpBroker requires WASM target. Use native client for non-browser.".to_string(),
             ))
@@ -91,5 +199,6 @@ mod tests {
     fn http_broker_from_origin() {
         let broker = HttpBroker::from_origin();
         assert_eq!(broker.base_url, "/api/actions");
+        assert_eq!(broker.codec, Codec::Json);
     }
 }
@@ -21,12 +21,29 @@
 //!    └───────────┘           └───────────┘
 //! ```
 
+pub mod approvals;
+pub mod batch;
 pub mod broker;
+pub mod cache;
+pub mod codec;
+pub mod collab;
+pub mod custom_fields;
+pub mod filesystem;
 pub mod http_broker;
+pub mod ndjson;
+pub mod presence;
+pub mod sync;
+pub mod tags;
 pub mod tauri_broker;
+pub mod training;
 pub mod types;
+pub mod updates;
 
 pub use broker::{Action, ActionBroker, ActionError, NoOpBroker};
+pub use cache::ResourceCache;
+pub use codec::Codec;
+pub use filesystem::{FileSystemBroker, TauriFileSystem};
 pub use http_broker::HttpBroker;
+pub use ndjson::NdjsonDecoder;
 pub use tauri_broker::TauriBroker;
 pub use types::*;
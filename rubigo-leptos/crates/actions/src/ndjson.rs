@@ -0,0 +1,84 @@
+//! NDJSON Decoding
+//!
+//! Large list endpoints stream one JSON value per line when asked for
+//! `application/x-ndjson`. [`NdjsonDecoder`] turns the chunks of such a body
+//! back into rows as they arrive; a line split across chunks is held until
+//! the rest of it comes in.
+
+use crate::broker::ActionError;
+use serde::de::DeserializeOwned;
+
+/// Content type of newline-delimited JSON
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Incremental decoder for a newline-delimited JSON body
+#[derive(Debug, Default)]
+pub struct NdjsonDecoder {
+    /// Start of a line whose newline has not arrived yet
+    partial: Vec<u8>,
+}
+
+impl NdjsonDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rows completed by `chunk`, in order
+    pub fn push<T: DeserializeOwned>(&mut self, chunk: &[u8]) -> Result<Vec<T>, ActionError> {
+        self.partial.extend_from_slice(chunk);
+        let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        complete
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+            .map(|line| {
+                serde_json::from_slice(line)
+                    .map_err(|e| ActionError::Deserialization(e.to_string()))
+            })
+            .collect()
+    }
+
+    /// The last row, if the body did not end with a newline
+    pub fn finish<T: DeserializeOwned>(self) -> Result<Option<T>, ActionError> {
+        if self.partial.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        serde_json::from_slice(&self.partial)
+            .map(Some)
+            .map_err(|e| ActionError::Deserialization(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_split_across_chunks() {
+        let mut decoder = NdjsonDecoder::new();
+        assert_eq!(decoder.push::<u32>(b"1\n2").unwrap(), vec![1]);
+        assert_eq!(decoder.push::<u32>(b"3\n\n4").unwrap(), vec![23]);
+        assert!(decoder.push::<u32>(b"5").unwrap().is_empty());
+        assert_eq!(decoder.finish::<u32>().unwrap(), Some(45));
+    }
+
+    #[test]
+    fn trailing_newline_leaves_nothing_to_finish() {
+        let mut decoder = NdjsonDecoder::new();
+        let rows: Vec<String> = decoder.push(b"\"a\"\n\"b\"\n").unwrap();
+        assert_eq!(rows, vec!["a", "b"]);
+        assert_eq!(decoder.finish::<String>().unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_lines_are_errors() {
+        let mut decoder = NdjsonDecoder::new();
+        assert!(matches!(
+            decoder.push::<u32>(b"{oops\n"),
+            Err(ActionError::Deserialization(_))
+        ));
+    }
+}
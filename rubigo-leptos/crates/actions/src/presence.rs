@@ -0,0 +1,183 @@
+//! Presence
+//!
+//! Who is connected and what they have open. Each tab registers its persona
+//! over `GET /api/presence/ws?persona=...` and reports the page and entity
+//! it shows as a [`PresenceUpdate`]; the server answers every change with
+//! the full [`Roster`]. Entities are keyed `"<kind>:<id>"` (see
+//! [`entity_key`]) so any detail panel can ask who else is on its record.
+
+use serde::{Deserialize, Serialize};
+
+/// WebSocket endpoint, with the persona in the `persona` query parameter
+pub const PRESENCE_PATH: &str = "/api/presence/ws";
+
+/// What a tab shows, sent by the client whenever it changes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceUpdate {
+    /// Page (tab) name, e.g. `"assets"`
+    pub page: String,
+    /// Record open in a detail panel or form
+    pub entity: Option<String>,
+    /// Whether the record is open for editing rather than viewing
+    #[serde(default)]
+    pub editing: bool,
+}
+
+/// One connected tab
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Presence {
+    /// Persona (person name)
+    pub persona: String,
+    pub page: String,
+    pub entity: Option<String>,
+    pub editing: bool,
+}
+
+impl Presence {
+    pub fn new(persona: impl Into<String>) -> Self {
+        Self {
+            persona: persona.into(),
+            page: String::new(),
+            entity: None,
+            editing: false,
+        }
+    }
+
+    pub fn apply(&mut self, update: PresenceUpdate) {
+        self.page = update.page;
+        self.entity = update.entity;
+        self.editing = update.editing;
+    }
+}
+
+/// Every connected tab, as broadcast by the server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Roster {
+    pub entries: Vec<Presence>,
+}
+
+impl Roster {
+    /// Online personas, once each (a persona may have several tabs), sorted
+    pub fn online(&self) -> Vec<&Presence> {
+        let mut online: Vec<&Presence> = Vec::new();
+        for entry in &self.entries {
+            match online.iter_mut().find(|p| p.persona == entry.persona) {
+                // Prefer the tab that has a record open
+                Some(seen) if seen.entity.is_none() && entry.entity.is_some() => *seen = entry,
+                Some(_) => {}
+                None => online.push(entry),
+            }
+        }
+        online.sort_by(|a, b| a.persona.cmp(&b.persona));
+        online
+    }
+
+    /// Other personas editing `entity`
+    pub fn editors(&self, entity: &str, me: &str) -> Vec<String> {
+        self.others_on(entity, me, |p| p.editing)
+    }
+
+    /// Other personas viewing, but not editing, `entity`
+    pub fn viewers(&self, entity: &str, me: &str) -> Vec<String> {
+        let editors = self.editors(entity, me);
+        self.others_on(entity, me, |p| !p.editing)
+            .into_iter()
+            .filter(|name| !editors.contains(name))
+            .collect()
+    }
+
+    fn others_on(&self, entity: &str, me: &str, keep: impl Fn(&Presence) -> bool) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|p| p.persona != me && p.entity.as_deref() == Some(entity) && keep(p))
+            .map(|p| p.persona.clone())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+/// Key for a record, e.g. `entity_key("asset", "42")` is `"asset:42"`
+pub fn entity_key(kind: &str, id: &str) -> String {
+    format!("{kind}:{id}")
+}
+
+/// "Ada is editing this asset", "Ada and Bo are ...", "Ada, Bo and Cy are ..."
+///
+/// `None` when nobody else is editing.
+pub fn editing_warning(names: &[String], noun: &str) -> Option<String> {
+    let who = join_names(names)?;
+    let verb = if names.len() == 1 { "is" } else { "are" };
+    Some(format!("{who} {verb} editing this {noun}"))
+}
+
+/// "Ada", "Ada and Bo", "Ada, Bo and Cy"
+pub fn join_names(names: &[String]) -> Option<String> {
+    match names {
+        [] => None,
+        [one] => Some(one.clone()),
+        [rest @ .., last] => Some(format!("{} and {last}", rest.join(", "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(persona: &str, entity: Option<&str>, editing: bool) -> Presence {
+        Presence {
+            persona: persona.to_string(),
+            page: "assets".to_string(),
+            entity: entity.map(str::to_string),
+            editing,
+        }
+    }
+
+    #[test]
+    fn online_lists_each_persona_once() {
+        let roster = Roster {
+            entries: vec![
+                at("Bo", None, false),
+                at("Ada", None, false),
+                at("Bo", Some("asset:1"), false),
+            ],
+        };
+        let online = roster.online();
+        assert_eq!(online.len(), 2);
+        assert_eq!(online[0].persona, "Ada");
+        assert_eq!(online[1].entity.as_deref(), Some("asset:1"));
+    }
+
+    #[test]
+    fn editors_and_viewers_exclude_me() {
+        let roster = Roster {
+            entries: vec![
+                at("Me", Some("asset:1"), true),
+                at("Ada", Some("asset:1"), true),
+                at("Ada", Some("asset:1"), false),
+                at("Bo", Some("asset:1"), false),
+                at("Cy", Some("asset:2"), true),
+            ],
+        };
+        assert_eq!(roster.editors("asset:1", "Me"), vec!["Ada"]);
+        assert_eq!(roster.viewers("asset:1", "Me"), vec!["Bo"]);
+        assert!(roster.editors("asset:3", "Me").is_empty());
+    }
+
+    #[test]
+    fn warning_names_every_editor() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(editing_warning(&[], "asset"), None);
+        assert_eq!(
+            editing_warning(&names(&["Ada"]), "asset").as_deref(),
+            Some("Ada is editing this asset")
+        );
+        assert_eq!(
+            editing_warning(&names(&["Ada", "Bo", "Cy"]), "event").as_deref(),
+            Some("Ada, Bo and Cy are editing this event")
+        );
+        assert_eq!(entity_key("asset", "42"), "asset:42");
+    }
+}
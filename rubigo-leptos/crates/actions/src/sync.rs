@@ -0,0 +1,135 @@
+//! Replica Sync Protocol
+//!
+//! The Tauri shell keeps its own SurrealDB and trades record changes with a
+//! gui-server: it pulls what changed on the server since its cursor, then
+//! pushes its own edits at [`SYNC_PATH`]. A change carries the whole record
+//! (or nothing, for a delete), so applying one is an upsert and the latest
+//! change to a record is all that matters.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Server route for both directions: `GET ?since=&replica=` pulls, `POST` pushes
+pub const SYNC_PATH: &str = "/api/sync/changes";
+
+/// Event the Tauri shell emits with a [`SyncStatus`] payload
+pub const SYNC_STATUS_EVENT: &str = "sync-status";
+
+/// The state of one record after a write
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordChange {
+    pub table: String,
+    /// Record key within `table`
+    pub id: String,
+    /// Record content; `None` when it was deleted
+    pub data: Option<Value>,
+    /// When the write happened, in nanoseconds since the Unix epoch
+    pub modified_at: i64,
+}
+
+impl RecordChange {
+    pub fn is_delete(&self) -> bool {
+        self.data.is_none()
+    }
+
+    fn key(&self) -> (&str, &str) {
+        (&self.table, &self.id)
+    }
+}
+
+/// Changes made on the server after the requested cursor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PullResponse {
+    pub changes: Vec<RecordChange>,
+    /// Pass as `since` next time; unchanged when there was nothing new
+    pub cursor: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PushRequest {
+    /// Identifies the client, so its own changes aren't pulled back
+    pub replica: String,
+    /// Cursor of the client's last pull; server changes after it conflict
+    pub base: i64,
+    pub changes: Vec<RecordChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PushResponse {
+    /// Number of changes the server applied
+    pub applied: usize,
+    /// The server's version of records whose pushed change lost a conflict
+    pub rejected: Vec<RecordChange>,
+}
+
+/// Where a replica stands with its remote, for the connection indicator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SyncStatus {
+    /// No remote is configured; all data stays on this machine
+    LocalOnly,
+    Syncing {
+        pending: usize,
+    },
+    /// Everything local has reached the server
+    Synced,
+    /// The remote could not be reached; local edits are kept until it can
+    Offline {
+        pending: usize,
+        error: String,
+    },
+}
+
+/// The last change to each record, in order of those last changes
+///
+/// A journal holds every write; only the final state of a record needs to
+/// travel.
+pub fn latest_per_record(changes: Vec<RecordChange>) -> Vec<RecordChange> {
+    let mut latest: Vec<RecordChange> = Vec::with_capacity(changes.len());
+    for change in changes {
+        latest.retain(|kept| kept.key() != change.key());
+        latest.push(change);
+    }
+    latest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn change(id: &str, data: Option<Value>, modified_at: i64) -> RecordChange {
+        RecordChange {
+            table: "person".into(),
+            id: id.into(),
+            data,
+            modified_at,
+        }
+    }
+
+    #[test]
+    fn only_the_last_change_to_a_record_is_kept() {
+        let changes = vec![
+            change("a", Some(json!({ "name": "Ada" })), 1),
+            change("b", Some(json!({ "name": "Bob" })), 2),
+            change("a", Some(json!({ "name": "Ada L" })), 3),
+            change("b", None, 4),
+        ];
+        let latest = latest_per_record(changes);
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].data, Some(json!({ "name": "Ada L" })));
+        assert!(latest[1].is_delete());
+    }
+
+    #[test]
+    fn status_is_tagged_for_the_frontend() {
+        let status = SyncStatus::Offline {
+            pending: 2,
+            error: "connection refused".into(),
+        };
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(value["state"], "offline");
+        assert_eq!(value["pending"], 2);
+        assert_eq!(serde_json::from_value::<SyncStatus>(value).unwrap(), status);
+    }
+}
@@ -0,0 +1,92 @@
+//! Tags
+//!
+//! Rules shared by the tag handlers and the UI. A tag has a name, a color
+//! and a scope: the kind of record it can be put on (`"asset"`,
+//! `"person"`, `"site"` or `"run"`, matching the kind in an entity key such
+//! as `asset:7`), or [`ANY_SCOPE`] for every kind.
+
+use crate::types::TagData;
+
+/// Scope of tags usable on any record
+pub const ANY_SCOPE: &str = "any";
+
+/// Every valid scope, [`ANY_SCOPE`] first
+pub const TAG_SCOPES: [&str; 5] = [ANY_SCOPE, "asset", "person", "site", "run"];
+
+/// Longest tag name, in characters
+pub const MAX_TAG_NAME_LEN: usize = 40;
+
+/// Whether `color` is a `#rrggbb` hex color
+pub fn is_tag_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Whether a tag with `scope` can be put on the record with `entity` key
+pub fn scope_allows(scope: &str, entity: &str) -> bool {
+    match entity.split_once(':') {
+        Some((kind, id)) if !id.is_empty() => scope == ANY_SCOPE || scope == kind,
+        _ => false,
+    }
+}
+
+/// Why a new tag is invalid, if it is; `existing` are the tags already defined
+pub fn validate_tag(name: &str, color: &str, scope: &str, existing: &[TagData]) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Some("A tag needs a name".to_string());
+    }
+    if name.chars().count() > MAX_TAG_NAME_LEN {
+        return Some(format!(
+            "Tag names are limited to {MAX_TAG_NAME_LEN} characters"
+        ));
+    }
+    if !is_tag_color(color) {
+        return Some(format!("{color} is not a #rrggbb color"));
+    }
+    if !TAG_SCOPES.contains(&scope) {
+        return Some(format!("Unknown tag scope: {scope}"));
+    }
+    // Names are unique among the tags a record of the scope could carry
+    let clash = existing.iter().any(|tag| {
+        tag.name.eq_ignore_ascii_case(name)
+            && (tag.scope == scope || tag.scope == ANY_SCOPE || scope == ANY_SCOPE)
+    });
+    clash.then(|| format!("A tag named {name} already exists"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str, scope: &str) -> TagData {
+        TagData {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            color: "#4caf50".to_string(),
+            scope: scope.to_string(),
+        }
+    }
+
+    #[test]
+    fn scopes_match_entity_kinds() {
+        assert!(scope_allows("asset", "asset:7"));
+        assert!(scope_allows(ANY_SCOPE, "run:abc"));
+        assert!(!scope_allows("asset", "person:7"));
+        assert!(!scope_allows(ANY_SCOPE, "asset:"));
+        assert!(!scope_allows(ANY_SCOPE, "asset"));
+    }
+
+    #[test]
+    fn new_tags_are_validated() {
+        let existing = [tag("Critical", "asset"), tag("Remote", ANY_SCOPE)];
+        assert_eq!(validate_tag("Spare", "#ff8a65", "asset", &existing), None);
+        // Same name on another kind of record is fine, unless either is "any"
+        assert_eq!(validate_tag("critical", "#ff8a65", "site", &existing), None);
+        assert!(validate_tag("critical", "#ff8a65", "asset", &existing).is_some());
+        assert!(validate_tag("remote", "#ff8a65", "site", &existing).is_some());
+
+        assert!(validate_tag("  ", "#ff8a65", "asset", &existing).is_some());
+        assert!(validate_tag("Spare", "orange", "asset", &existing).is_some());
+        assert!(validate_tag("Spare", "#ff8a65", "building", &existing).is_some());
+    }
+}
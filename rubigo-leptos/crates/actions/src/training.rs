@@ -0,0 +1,89 @@
+//! Training Exercises
+//!
+//! Rules shared by the dispatcher and the scorecard. A scenario can define
+//! guided exercises: tasks worked through in order, each done once the
+//! trainee dispatches the action it expects. Hints are revealed one at a
+//! time and each costs part of the task's points.
+
+use serde_json::Value;
+
+/// Share of a task's points each hint taken costs, in percent
+pub const HINT_COST_PERCENT: u32 = 25;
+
+/// Whether `payload` has every field of `expected`
+///
+/// Objects match when each of the expected keys matches, whatever else
+/// the payload has; arrays match element by element from the start. An
+/// empty object matches anything, standing in for fields the exercise
+/// doesn't care about (e.g. the ID in `{ Transition = [{}, { state =
+/// "retired" }] }`). Anything else must be equal, numbers by value.
+pub fn payload_matches(expected: &Value, payload: &Value) -> bool {
+    match (expected, payload) {
+        (Value::Object(fields), _) if fields.is_empty() => true,
+        (Value::Object(fields), Value::Object(actual)) => fields
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|v| payload_matches(value, v))),
+        (Value::Array(items), Value::Array(actual)) => {
+            items.len() <= actual.len()
+                && items.iter().zip(actual).all(|(e, a)| payload_matches(e, a))
+        }
+        (Value::Number(e), Value::Number(a)) => e.as_f64() == a.as_f64(),
+        _ => expected == payload,
+    }
+}
+
+/// Whether an action completes a task expecting `expected_type` and,
+/// when given, `expected_payload`
+pub fn action_matches(
+    expected_type: &str,
+    expected_payload: Option<&Value>,
+    action_type: &str,
+    payload: &Value,
+) -> bool {
+    expected_type == action_type && expected_payload.is_none_or(|e| payload_matches(e, payload))
+}
+
+/// Points earned for a task completed after taking `hints_used` hints
+pub fn task_score(points: u32, hints_used: u32) -> u32 {
+    let cost = points * HINT_COST_PERCENT / 100;
+    points.saturating_sub(cost.saturating_mul(hints_used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn payloads_match_on_the_expected_fields() {
+        let transition = json!({"Transition": ["7", {"state": "retired", "note": "End of life"}]});
+        let expected = json!({"Transition": [{}, {"state": "retired"}]});
+        assert!(payload_matches(&expected, &transition));
+        assert!(!payload_matches(
+            &json!({"Transition": [{}, {"state": "in_service"}]}),
+            &transition
+        ));
+        assert!(!payload_matches(&json!({"Delete": {}}), &transition));
+        assert!(payload_matches(&json!({"n": 3.0}), &json!({"n": 3})));
+
+        assert!(action_matches(
+            "asset.list",
+            None,
+            "asset.list",
+            &json!("List")
+        ));
+        assert!(!action_matches(
+            "asset.list",
+            None,
+            "asset.get",
+            &json!("List")
+        ));
+    }
+
+    #[test]
+    fn hints_cost_a_share_of_the_points() {
+        assert_eq!(task_score(20, 0), 20);
+        assert_eq!(task_score(20, 1), 15);
+        assert_eq!(task_score(20, 5), 0);
+    }
+}
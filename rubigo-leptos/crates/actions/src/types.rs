@@ -5,6 +5,7 @@
 
 use crate::broker::Action;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // =============================================================================
 // Asset Actions
@@ -17,12 +18,22 @@ pub enum AssetAction {
     Create(CreateAssetData),
     /// Update an existing asset
     Update(String, UpdateAssetData),
-    /// Delete an asset by ID
+    /// Delete an asset by ID; it stays restorable for a short window
     Delete(String),
+    /// Bring back a recently deleted asset by ID
+    Restore(String),
     /// List assets with optional filters
     List(AssetListQuery),
     /// Get a single asset by ID
     Get(String),
+    /// Move an asset to a new lifecycle state
+    Transition(String, TransitionAssetData),
+    /// Move an asset to another rack position
+    Move(String, MoveAssetData),
+    /// Schedule maintenance work on an asset
+    ScheduleMaintenance(ScheduleMaintenanceData),
+    /// Open maintenance due on or before a date (YYYY-MM-DD)
+    UpcomingMaintenance(String),
 }
 
 impl Action for AssetAction {
@@ -33,8 +44,13 @@ impl Action for AssetAction {
             AssetAction::Create(_) => "asset.create",
             AssetAction::Update(_, _) => "asset.update",
             AssetAction::Delete(_) => "asset.delete",
+            AssetAction::Restore(_) => "asset.restore",
             AssetAction::List(_) => "asset.list",
             AssetAction::Get(_) => "asset.get",
+            AssetAction::Transition(_, _) => "asset.transition",
+            AssetAction::Move(_, _) => "asset.move",
+            AssetAction::ScheduleMaintenance(_) => "asset.schedule_maintenance",
+            AssetAction::UpcomingMaintenance(_) => "asset.upcoming_maintenance",
         }
     }
 }
@@ -66,6 +82,91 @@ pub struct UpdateAssetData {
     // Add other updatable fields as needed
 }
 
+/// Stage of an asset's service life
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LifecycleState {
+    Ordered,
+    #[default]
+    Deployed,
+    Maintenance,
+    Retired,
+}
+
+impl LifecycleState {
+    pub const ALL: [LifecycleState; 4] = [
+        LifecycleState::Ordered,
+        LifecycleState::Deployed,
+        LifecycleState::Maintenance,
+        LifecycleState::Retired,
+    ];
+
+    /// States reachable from this one; retirement is final
+    pub fn next_states(&self) -> &'static [LifecycleState] {
+        match self {
+            LifecycleState::Ordered => &[LifecycleState::Deployed, LifecycleState::Retired],
+            LifecycleState::Deployed => &[LifecycleState::Maintenance, LifecycleState::Retired],
+            LifecycleState::Maintenance => &[LifecycleState::Deployed, LifecycleState::Retired],
+            LifecycleState::Retired => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: LifecycleState) -> bool {
+        self.next_states().contains(&next)
+    }
+
+    /// Parse the stored/displayed form (e.g. "maintenance")
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|state| state.to_string() == s.to_lowercase())
+    }
+}
+
+impl std::fmt::Display for LifecycleState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LifecycleState::Ordered => write!(f, "ordered"),
+            LifecycleState::Deployed => write!(f, "deployed"),
+            LifecycleState::Maintenance => write!(f, "maintenance"),
+            LifecycleState::Retired => write!(f, "retired"),
+        }
+    }
+}
+
+impl std::str::FromStr for LifecycleState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("Unknown lifecycle state '{}'", s))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionAssetData {
+    /// Target state: "ordered", "deployed", "maintenance" or "retired"
+    pub state: String,
+    pub note: Option<String>,
+}
+
+/// Where an asset is racked; no rack takes it out of its rack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveAssetData {
+    pub rack_id: Option<String>,
+    /// Lowest rack unit the asset occupies
+    pub position_u: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleMaintenanceData {
+    pub asset_id: String,
+    pub title: String,
+    /// YYYY-MM-DD
+    pub scheduled_date: String,
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AssetListQuery {
     pub category: Option<String>,
@@ -78,9 +179,11 @@ pub struct AssetListQuery {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AssetResponse {
     /// Single asset returned
-    Single(AssetData),
+    Single(Box<AssetData>),
     /// List of assets
     List(Vec<AssetData>),
+    /// Maintenance tickets
+    Maintenance(Vec<MaintenanceData>),
     /// Operation succeeded with no data
     Success,
     /// Operation failed
@@ -96,9 +199,27 @@ pub struct AssetData {
     pub serial_number: String,
     pub category: String,
     pub status: String,
+    #[serde(default)]
+    pub lifecycle: String,
+    #[serde(default)]
+    pub warranty_end: Option<String>,
+    #[serde(default)]
+    pub rack_id: Option<String>,
+    #[serde(default)]
+    pub position_u: Option<u8>,
     // Extend as needed
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceData {
+    pub id: String,
+    pub asset_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub scheduled_date: String,
+    pub status: String,
+}
+
 // =============================================================================
 // Personnel Actions
 // =============================================================================
@@ -145,10 +266,605 @@ pub struct PersonData {
     pub title: Option<String>,
 }
 
+// =============================================================================
+// Dashboard Actions
+// =============================================================================
+
+/// Actions backing the home dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DashboardAction {
+    /// Saved widget layout for a user ID
+    GetLayout(String),
+    /// Replace a user's widget layout
+    SaveLayout(String, Vec<WidgetData>),
+    /// Current figures shown by the widgets
+    Snapshot,
+}
+
+impl Action for DashboardAction {
+    type Response = DashboardResponse;
+
+    fn action_type(&self) -> &'static str {
+        match self {
+            DashboardAction::GetLayout(_) => "dashboard.get_layout",
+            DashboardAction::SaveLayout(_, _) => "dashboard.save_layout",
+            DashboardAction::Snapshot => "dashboard.snapshot",
+        }
+    }
+}
+
+/// One placed widget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetData {
+    /// Unique within the layout
+    pub id: String,
+    /// "stat", "chart", "recent_events" or "map_snapshot"
+    pub kind: String,
+    /// Stat or chart key the widget shows
+    #[serde(default)]
+    pub metric: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DashboardResponse {
+    /// Saved layout, `None` if the user has not arranged one yet
+    Layout(Option<Vec<WidgetData>>),
+    Snapshot(DashboardSnapshot),
+    Success,
+    Error(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DashboardSnapshot {
+    pub stats: Vec<StatData>,
+    pub charts: Vec<ChartData>,
+    /// Newest first
+    pub recent_events: Vec<EventData>,
+    pub sites: Vec<SitePointData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatData {
+    /// "sites", "buildings", "spaces", "assets", "personnel" or "open_maintenance"
+    pub key: String,
+    pub label: String,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartData {
+    /// "assets_by_status", "assets_by_category" or "people_by_department"
+    pub key: String,
+    pub title: String,
+    /// Label/count pairs, largest first
+    pub points: Vec<(String, u64)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventData {
+    pub title: String,
+    pub detail: Option<String>,
+    /// ISO 8601 timestamp
+    pub at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SitePointData {
+    pub name: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+}
+
+// =============================================================================
+// Activity Actions
+// =============================================================================
+
+/// Actions behind the activity tab of detail panels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ActivityAction {
+    /// Chronological feed of one entity
+    Feed(ActivityQuery),
+    /// Comment on an entity, by entity key
+    Comment(String, CommentData),
+}
+
+impl Action for ActivityAction {
+    type Response = ActivityResponse;
+
+    fn action_type(&self) -> &'static str {
+        match self {
+            ActivityAction::Feed(_) => "activity.feed",
+            ActivityAction::Comment(_, _) => "activity.comment",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityQuery {
+    /// Entity key, e.g. `asset:7`, `site:hq`, `person:42` or `event:3`
+    pub entity: String,
+    /// Name the entity goes by, for finding simulation runs that mention it
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentData {
+    pub author: String,
+    pub body: String,
+    /// ISO 8601 timestamp
+    pub at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ActivityResponse {
+    /// Newest first
+    Feed(Vec<ActivityData>),
+    Success,
+    Error(String),
+}
+
+/// One entry of an entity's activity feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityData {
+    /// "comment", "audit", "lifecycle", "maintenance" or "simulation"
+    pub kind: String,
+    pub title: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+    /// Who commented or made the change, when known
+    #[serde(default)]
+    pub actor: Option<String>,
+    /// ISO 8601 timestamp or YYYY-MM-DD
+    pub at: String,
+}
+
+// =============================================================================
+// Tag Actions
+// =============================================================================
+
+/// Actions for tags and the records carrying them
+///
+/// Records are addressed by entity key, e.g. `asset:7` or `run:abc`; see
+/// [`crate::tags`] for scopes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TagAction {
+    /// Tags usable on a kind of record, or every tag for `None`
+    List(Option<String>),
+    Create(CreateTagData),
+    /// Delete a tag by ID, taking it off every record
+    Delete(String),
+    /// Tags carried by each of the records
+    ForEntities(Vec<String>),
+    /// Put a tag on records
+    Assign(TagAssignmentData),
+    /// Take a tag off records
+    Unassign(TagAssignmentData),
+}
+
+impl Action for TagAction {
+    type Response = TagResponse;
+
+    fn action_type(&self) -> &'static str {
+        match self {
+            TagAction::List(_) => "tag.list",
+            TagAction::Create(_) => "tag.create",
+            TagAction::Delete(_) => "tag.delete",
+            TagAction::ForEntities(_) => "tag.for_entities",
+            TagAction::Assign(_) => "tag.assign",
+            TagAction::Unassign(_) => "tag.unassign",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTagData {
+    pub name: String,
+    /// `#rrggbb`
+    pub color: String,
+    /// "any", "asset", "person", "site" or "run"
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagAssignmentData {
+    pub tag_id: String,
+    /// Entity keys of the records
+    pub entities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TagResponse {
+    List(Vec<TagData>),
+    Created(TagData),
+    /// Tag IDs per record, for records carrying any
+    Assignments(Vec<EntityTagsData>),
+    Success,
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagData {
+    pub id: String,
+    pub name: String,
+    /// `#rrggbb`
+    pub color: String,
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityTagsData {
+    pub entity: String,
+    pub tag_ids: Vec<String>,
+}
+
+// =============================================================================
+// Batch Actions
+// =============================================================================
+
+/// One operation applied to many records of a kind, as chosen in a table's
+/// bulk-action bar
+///
+/// The dispatcher runs it as one action per record, so hooks and approvals
+/// see each change; see [`crate::batch`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BatchAction {
+    Run(BatchRequest),
+}
+
+impl Action for BatchAction {
+    type Response = BatchResponse;
+
+    fn action_type(&self) -> &'static str {
+        match self {
+            BatchAction::Run(_) => "batch.run",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRequest {
+    /// Kind of record, as in an entity key: "asset", "person", ...
+    pub kind: String,
+    /// IDs of the records
+    pub rows: Vec<String>,
+    pub op: BatchOp,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BatchOp {
+    /// Put the tag with this ID on each record
+    AssignTag(String),
+    /// Take the tag with this ID off each record
+    UnassignTag(String),
+    /// Move each record to this status
+    SetStatus(String),
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BatchResponse {
+    /// How it went for each record, in request order
+    Results(Vec<BatchRowResult>),
+    /// Nothing was run
+    Error(String),
+}
+
+/// Outcome of a batch operation on one record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRowResult {
+    pub row: String,
+    /// Why it failed, or was held for approval; `None` once applied
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+// =============================================================================
+// Custom Field Actions
+// =============================================================================
+
+/// Actions for admin-defined fields and their values on records
+///
+/// See [`crate::custom_fields`] for the field types and value rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CustomFieldAction {
+    /// Fields defined for a kind of record, e.g. `"asset"`, in form order
+    Definitions(String),
+    /// Add a field, or change the one with the same ID
+    Define(CustomFieldData),
+    /// Remove a field definition by ID; stored values stay but are no
+    /// longer shown
+    RemoveDefinition(String),
+    /// Values of a record's fields, by entity key
+    Values(String),
+    /// Change some of a record's values; an empty value clears the field
+    SetValues(String, BTreeMap<String, String>),
+}
+
+impl Action for CustomFieldAction {
+    type Response = CustomFieldResponse;
+
+    fn action_type(&self) -> &'static str {
+        match self {
+            CustomFieldAction::Definitions(_) => "custom_field.definitions",
+            CustomFieldAction::Define(_) => "custom_field.define",
+            CustomFieldAction::RemoveDefinition(_) => "custom_field.remove_definition",
+            CustomFieldAction::Values(_) => "custom_field.values",
+            CustomFieldAction::SetValues(_, _) => "custom_field.set_values",
+        }
+    }
+}
+
+/// Definition of one custom field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomFieldData {
+    /// Empty for a field not saved yet
+    #[serde(default)]
+    pub id: String,
+    /// "asset", "person", "site" or "event"
+    pub entity_kind: String,
+    /// Key the values are stored under, e.g. `warranty_ends`
+    pub key: String,
+    pub label: String,
+    /// "text", "number", "date" or "select"
+    pub kind: String,
+    /// Choices of a select field
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// Order in forms, lowest first
+    #[serde(default)]
+    pub position: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CustomFieldResponse {
+    Definitions(Vec<CustomFieldData>),
+    Defined(CustomFieldData),
+    /// Values by field key, for the fields still defined
+    Values(BTreeMap<String, String>),
+    Success,
+    Error(String),
+}
+
+// =============================================================================
+// Change Request Actions
+// =============================================================================
+
+/// Actions of the review queue for changes held for approval
+///
+/// Requests are created by the dispatcher when it holds a change to a
+/// protected record; see [`crate::approvals`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChangeRequestAction {
+    /// Requests with a status, or all of them for `None`, newest first
+    List(Option<String>),
+    Get(String),
+    /// Approve a request by ID, applying the change it holds
+    Approve(String, ReviewData),
+    /// Reject a request by ID; the change is dropped
+    Reject(String, ReviewData),
+}
+
+impl Action for ChangeRequestAction {
+    type Response = ChangeRequestResponse;
+
+    fn action_type(&self) -> &'static str {
+        match self {
+            ChangeRequestAction::List(_) => "change_request.list",
+            ChangeRequestAction::Get(_) => "change_request.get",
+            ChangeRequestAction::Approve(_, _) => "change_request.approve",
+            ChangeRequestAction::Reject(_, _) => "change_request.reject",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewData {
+    /// Persona reviewing the request
+    pub reviewer: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// ISO 8601 timestamp
+    pub at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChangeRequestResponse {
+    List(Vec<ChangeRequestData>),
+    Request(Box<ChangeRequestData>),
+    Error(String),
+}
+
+/// A change held for approval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeRequestData {
+    pub id: String,
+    /// Action type of the held change, e.g. `asset.update`
+    pub action_type: String,
+    /// The held action as JSON
+    pub payload: String,
+    /// Entity keys of the protected records it changes
+    pub entities: Vec<String>,
+    /// Persona who asked for the change, when known
+    #[serde(default)]
+    pub requested_by: Option<String>,
+    /// ISO 8601 timestamp
+    pub requested_at: String,
+    /// "pending", "approved", "rejected" or "failed"
+    pub status: String,
+    #[serde(default)]
+    pub reviewed_by: Option<String>,
+    #[serde(default)]
+    pub review_note: Option<String>,
+    #[serde(default)]
+    pub reviewed_at: Option<String>,
+    /// Why the approved change could not be applied
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+// =============================================================================
+// Training Actions
+// =============================================================================
+
+/// Actions of training mode, where the trainee works through one of the
+/// scenario's guided exercises
+///
+/// While a session runs, the dispatcher checks every other action it
+/// handles against the current task; see [`crate::training`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TrainingAction {
+    /// Exercises the scenario defines
+    Exercises,
+    /// Start an exercise, ending the session in progress if there is one
+    Start(StartTrainingData),
+    /// Reveal the current task's next hint
+    Hint,
+    /// The session in progress, or the scorecard of the last one
+    Progress,
+    /// End the session in progress
+    Finish,
+}
+
+impl Action for TrainingAction {
+    type Response = TrainingResponse;
+
+    fn action_type(&self) -> &'static str {
+        match self {
+            TrainingAction::Exercises => "training.exercises",
+            TrainingAction::Start(_) => "training.start",
+            TrainingAction::Hint => "training.hint",
+            TrainingAction::Progress => "training.progress",
+            TrainingAction::Finish => "training.finish",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartTrainingData {
+    /// Exercise name
+    pub exercise: String,
+    /// Persona doing the exercise
+    pub trainee: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TrainingResponse {
+    Exercises(Vec<ExerciseData>),
+    Progress(Box<TrainingProgressData>),
+    /// The hint just revealed
+    Hint(String),
+    Error(String),
+}
+
+/// An exercise as offered to trainees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseData {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub tasks: usize,
+    /// Points for doing every task without hints
+    pub max_score: u32,
+}
+
+/// A trainee's session: where they are and, once finished, their scorecard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingProgressData {
+    pub session_id: String,
+    pub exercise: String,
+    pub trainee: String,
+    /// ISO 8601 timestamp
+    pub started_at: String,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    /// In the order they are done
+    pub tasks: Vec<TaskProgressData>,
+    /// Actions dispatched that didn't complete the task at hand
+    pub extra_actions: u32,
+}
+
+impl TrainingProgressData {
+    /// Entity key of the session's audit trail, for `ActivityAction::Feed`
+    pub fn entity(&self) -> String {
+        format!("training_session:{}", self.session_id)
+    }
+
+    /// Index of the task at hand; `None` once all are done
+    pub fn current(&self) -> Option<usize> {
+        self.tasks.iter().position(|t| t.completed_at.is_none())
+    }
+
+    pub fn completed(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|t| t.completed_at.is_some())
+            .count()
+    }
+
+    pub fn score(&self) -> u32 {
+        self.tasks.iter().map(TaskProgressData::earned).sum()
+    }
+
+    pub fn max_score(&self) -> u32 {
+        self.tasks.iter().map(|t| t.points).sum()
+    }
+}
+
+/// One task of a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskProgressData {
+    pub title: String,
+    #[serde(default)]
+    pub instructions: Option<String>,
+    pub points: u32,
+    /// Hints taken so far, in order
+    pub hints: Vec<String>,
+    /// Hints still available
+    pub hints_left: usize,
+    /// ISO 8601 timestamp
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+impl TaskProgressData {
+    /// Points earned; none until the task is done
+    pub fn earned(&self) -> u32 {
+        match self.completed_at {
+            Some(_) => crate::training::task_score(self.points, self.hints.len() as u32),
+            None => 0,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn lifecycle_transitions() {
+        use LifecycleState::*;
+        assert!(Ordered.can_transition_to(Deployed));
+        assert!(Deployed.can_transition_to(Maintenance));
+        assert!(Maintenance.can_transition_to(Deployed));
+        assert!(!Ordered.can_transition_to(Maintenance));
+        assert!(Retired.next_states().is_empty());
+    }
+
+    #[test]
+    fn lifecycle_state_round_trips() {
+        for state in LifecycleState::ALL {
+            assert_eq!(state.to_string().parse::<LifecycleState>().unwrap(), state);
+        }
+        assert!("broken".parse::<LifecycleState>().is_err());
+        assert_eq!(
+            serde_json::to_string(&LifecycleState::Maintenance).unwrap(),
+            "\"maintenance\""
+        );
+    }
+
     #[test]
     fn asset_action_types() {
         let create = AssetAction::Create(CreateAssetData {
@@ -170,5 +886,140 @@ mod tests {
 
         let delete = AssetAction::Delete("123".to_string());
         assert_eq!(delete.action_type(), "asset.delete");
+
+        let restore = AssetAction::Restore("123".to_string());
+        assert_eq!(restore.action_type(), "asset.restore");
+
+        let moved = AssetAction::Move(
+            "123".to_string(),
+            MoveAssetData {
+                rack_id: Some("r1".to_string()),
+                position_u: Some(12),
+            },
+        );
+        assert_eq!(moved.action_type(), "asset.move");
+
+        let transition = AssetAction::Transition(
+            "123".to_string(),
+            TransitionAssetData {
+                state: "retired".to_string(),
+                note: None,
+            },
+        );
+        assert_eq!(transition.action_type(), "asset.transition");
+    }
+
+    #[test]
+    fn dashboard_action_types() {
+        let save = DashboardAction::SaveLayout(
+            "abc123".to_string(),
+            vec![WidgetData {
+                id: "w1".to_string(),
+                kind: "stat".to_string(),
+                metric: Some("sites".to_string()),
+            }],
+        );
+        assert_eq!(save.action_type(), "dashboard.save_layout");
+        assert_eq!(
+            DashboardAction::Snapshot.action_type(),
+            "dashboard.snapshot"
+        );
+    }
+
+    #[test]
+    fn activity_action_types() {
+        let feed = ActivityAction::Feed(ActivityQuery {
+            entity: "asset:7".to_string(),
+            label: None,
+        });
+        assert_eq!(feed.action_type(), "activity.feed");
+
+        let comment = ActivityAction::Comment(
+            "asset:7".to_string(),
+            CommentData {
+                author: "Ada".to_string(),
+                body: "Fan replaced".to_string(),
+                at: "2026-01-05T10:00:00Z".to_string(),
+            },
+        );
+        assert_eq!(comment.action_type(), "activity.comment");
+    }
+
+    #[test]
+    fn tag_action_types() {
+        let assign = TagAction::Assign(TagAssignmentData {
+            tag_id: "critical".to_string(),
+            entities: vec!["asset:7".to_string(), "asset:8".to_string()],
+        });
+        assert_eq!(assign.action_type(), "tag.assign");
+        assert_eq!(TagAction::List(None).action_type(), "tag.list");
+        assert_eq!(
+            TagAction::ForEntities(vec![]).action_type(),
+            "tag.for_entities"
+        );
+    }
+
+    #[test]
+    fn custom_field_action_types() {
+        assert_eq!(
+            CustomFieldAction::Definitions("asset".to_string()).action_type(),
+            "custom_field.definitions"
+        );
+        let set = CustomFieldAction::SetValues(
+            "asset:7".to_string(),
+            BTreeMap::from([("warranty_ends".to_string(), "2027-01-31".to_string())]),
+        );
+        assert_eq!(set.action_type(), "custom_field.set_values");
+    }
+
+    #[test]
+    fn change_request_action_types() {
+        let review = ReviewData {
+            reviewer: "Grace Hopper".to_string(),
+            note: Some("Scheduled window".to_string()),
+            at: "2026-03-02T10:00:00Z".to_string(),
+        };
+        assert_eq!(
+            ChangeRequestAction::Approve("cr1".to_string(), review.clone()).action_type(),
+            "change_request.approve"
+        );
+        assert_eq!(
+            ChangeRequestAction::Reject("cr1".to_string(), review).action_type(),
+            "change_request.reject"
+        );
+        assert_eq!(
+            ChangeRequestAction::List(None).action_type(),
+            "change_request.list"
+        );
+    }
+
+    #[test]
+    fn training_scorecards_add_up_completed_tasks() {
+        assert_eq!(TrainingAction::Hint.action_type(), "training.hint");
+        let task = |points, hints: &[&str], completed_at: Option<&str>| TaskProgressData {
+            title: "Task".to_string(),
+            instructions: None,
+            points,
+            hints: hints.iter().map(|h| h.to_string()).collect(),
+            hints_left: 0,
+            completed_at: completed_at.map(str::to_string),
+        };
+        let progress = TrainingProgressData {
+            session_id: "s1".to_string(),
+            exercise: "Retire a switch".to_string(),
+            trainee: "Riley Engineer".to_string(),
+            started_at: "2026-03-02T09:00:00Z".to_string(),
+            finished_at: None,
+            tasks: vec![
+                task(10, &[], Some("2026-03-02T09:01:00Z")),
+                task(20, &["Use the lifecycle"], Some("2026-03-02T09:05:00Z")),
+                task(10, &[], None),
+            ],
+            extra_actions: 2,
+        };
+        assert_eq!(progress.entity(), "training_session:s1");
+        assert_eq!(progress.current(), Some(2));
+        assert_eq!(progress.completed(), 2);
+        assert_eq!((progress.score(), progress.max_score()), (25, 40));
     }
 }
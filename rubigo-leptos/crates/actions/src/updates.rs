@@ -0,0 +1,75 @@
+//! Desktop Updates
+//!
+//! What the Tauri shell tells the frontend while it checks for, downloads
+//! and installs a new version of itself. Channels travel by name
+//! (`"stable"`, `"beta"`), as in `[runtime.updates]`.
+
+use serde::{Deserialize, Serialize};
+
+/// Event the shell emits when the user asks to check for updates from the
+/// native menu; no payload
+pub const CHECK_UPDATES_EVENT: &str = "check-updates";
+
+/// Event carrying [`UpdateProgress`] while an update downloads
+pub const UPDATE_PROGRESS_EVENT: &str = "update-progress";
+
+/// Release channels offered in the app, with their labels
+pub const CHANNELS: [(&str, &str); 2] = [("stable", "Stable"), ("beta", "Beta")];
+
+/// A newer version found on the selected channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    /// Release notes, as written for the release
+    pub notes: Option<String>,
+    /// Publication date, as given in the update manifest
+    pub date: Option<String>,
+    pub channel: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UpdateProgress {
+    pub downloaded: u64,
+    /// Size of the download, when the server sends it
+    pub total: Option<u64>,
+}
+
+impl UpdateProgress {
+    /// Fraction downloaded, if the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.downloaded as f64 / total as f64).min(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_fraction_needs_a_total() {
+        let progress = UpdateProgress {
+            downloaded: 50,
+            total: Some(200),
+        };
+        assert_eq!(progress.fraction(), Some(0.25));
+        assert_eq!(
+            UpdateProgress {
+                total: None,
+                ..progress
+            }
+            .fraction(),
+            None
+        );
+        assert_eq!(
+            UpdateProgress {
+                total: Some(0),
+                ..progress
+            }
+            .fraction(),
+            None
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Admin API client
+//!
+//! Thin wrapper over the gui-server JSON API that turns its
+//! `{ "error": { "code", "message" } }` envelope into an error message.
+
+use anyhow::{bail, Context, Result};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+struct ErrorEnvelope {
+    error: ErrorBody,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: String,
+    message: String,
+}
+
+pub struct Api {
+    http: reqwest::Client,
+    base: String,
+}
+
+impl Api {
+    /// Client for the server at `base`, e.g. `http://127.0.0.1:3000`
+    pub fn new(base: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base: base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path)
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http.request(method, self.url(path))
+    }
+
+    async fn send(&self, request: RequestBuilder, path: &str) -> Result<Response> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Could not reach {}", self.url(path)))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<ErrorEnvelope>(&body) {
+            Ok(envelope) => bail!(
+                "{} ({}): {}",
+                status,
+                envelope.error.code,
+                envelope.error.message
+            ),
+            Err(_) if body.is_empty() => bail!("{} from {}", status, path),
+            Err(_) => bail!("{} from {}: {}", status, path, body),
+        }
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self.send(self.request(Method::GET, path), path).await?;
+        Ok(response.json().await?)
+    }
+
+    /// GET the raw body, for output written straight to a file
+    pub async fn get_text(&self, path: &str) -> Result<String> {
+        let response = self.send(self.request(Method::GET, path), path).await?;
+        Ok(response.text().await?)
+    }
+
+    pub async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        let response = self
+            .send(self.request(Method::POST, path).json(body), path)
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    /// POST a raw text body, e.g. a document read from a file
+    pub async fn post_text<T: DeserializeOwned>(&self, path: &str, body: String) -> Result<T> {
+        let request = self
+            .request(Method::POST, path)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain")
+            .body(body);
+        let response = self.send(request, path).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<()> {
+        self.send(self.request(Method::DELETE, path), path).await?;
+        Ok(())
+    }
+}
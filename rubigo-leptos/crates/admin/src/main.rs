@@ -0,0 +1,375 @@
+//! rubigo-admin
+//!
+//! Administration for a running gui-server. The database lives in the
+//! server process, so every command goes through its HTTP API:
+//!
+//! ```text
+//! rubigo-admin seed                       # seed if the database is empty
+//! rubigo-admin reseed --scenario other.toml
+//! rubigo-admin export-scenario -o snapshot.json
+//! rubigo-admin export-topology --format toml -o topology.toml
+//! rubigo-admin import-topology topology.tf.json [--dry-run]
+//! rubigo-admin migrate [--status]
+//! rubigo-admin runs list | runs delete <id>
+//! rubigo-admin users create --name "Ada" --email ada@example.com --site hq ...
+//! rubigo-admin vacuum [--dry-run]
+//! ```
+//!
+//! The server address comes from `--server`, `RUBIGO_ADMIN_SERVER`, or the
+//! port in `rubigo.toml`.
+
+mod client;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use client::Api;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "rubigo-admin",
+    version,
+    about = "Administer a running rubigo-leptos server"
+)]
+struct Cli {
+    /// Server base URL; defaults to http://127.0.0.1:<server.port> from rubigo.toml
+    #[arg(long, env = "RUBIGO_ADMIN_SERVER", global = true)]
+    server: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Seed the scenario; does nothing if one is already loaded
+    Seed {
+        /// Scenario TOML as seen from the server; defaults to `paths.scenario`
+        #[arg(long)]
+        scenario: Option<String>,
+    },
+    /// Clear the scenario tables and seed again
+    Reseed {
+        #[arg(long)]
+        scenario: Option<String>,
+    },
+    /// Write everything the scenario seeded as JSON
+    ExportScenario {
+        /// File to write; prints to stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write the site hierarchy and topology as code
+    ExportTopology {
+        /// `tf` (Terraform JSON) or `toml`
+        #[arg(long, default_value = "tf")]
+        format: String,
+        /// File to write; prints to stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Apply a topology document written by `export-topology`
+    ImportTopology {
+        file: PathBuf,
+        /// `tf` or `toml`; taken from the file extension when omitted
+        #[arg(long)]
+        format: Option<String>,
+        /// Only show what would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Apply pending schema migrations
+    Migrate {
+        /// Only show which migrations have been applied
+        #[arg(long)]
+        status: bool,
+    },
+    /// Simulation runs
+    #[command(subcommand)]
+    Runs(RunsCommand),
+    /// People who can sign in as a persona
+    #[command(subcommand)]
+    Users(UsersCommand),
+    /// Remove records left behind by deletes, e.g. the buildings of a deleted site
+    Vacuum {
+        /// Only count what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RunsCommand {
+    List,
+    Delete { id: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum UsersCommand {
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long, default_value = "")]
+        title: String,
+        #[arg(long, default_value = "")]
+        department: String,
+        /// Site id, `site:key` or `key`
+        #[arg(long)]
+        site: String,
+        /// Manager's person id
+        #[arg(long)]
+        manager: Option<String>,
+        /// e.g. Employee, ITAdmin, Engineer
+        #[arg(long, default_value = "Employee")]
+        role: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct SeedReport {
+    scenario: String,
+    cleared_tables: usize,
+    seeded: bool,
+    components: usize,
+    people: usize,
+}
+
+#[derive(Deserialize)]
+struct ApplyReport {
+    created: BTreeMap<String, usize>,
+    updated: BTreeMap<String, usize>,
+    unchanged: BTreeMap<String, usize>,
+}
+
+/// Format of a topology file, from its extension
+fn topology_format(file: &std::path::Path) -> &'static str {
+    match file.extension().and_then(|e| e.to_str()) {
+        Some("toml") => "toml",
+        _ => "tf",
+    }
+}
+
+#[derive(Deserialize)]
+struct MigrationStatus {
+    id: String,
+    applied_at: Option<String>,
+}
+
+/// Record key of a SurrealDB id as the API serializes it
+fn record_key(id: &Value) -> String {
+    match id {
+        Value::String(s) => s.clone(),
+        Value::Object(thing) => match thing.get("id") {
+            // `{ "tb": "run", "id": { "String": "abc" } }`
+            Some(Value::Object(key)) => key.values().next().map(record_key).unwrap_or_default(),
+            Some(key) => record_key(key),
+            None => String::new(),
+        },
+        other => other.to_string(),
+    }
+}
+
+fn default_server() -> String {
+    let settings = config::Loader::new()
+        .env(std::env::vars())
+        .load()
+        .unwrap_or_default();
+    format!("http://127.0.0.1:{}", settings.server.port)
+}
+
+async fn seed(api: &Api, scenario: Option<String>, reset: bool) -> Result<()> {
+    let report: SeedReport = api
+        .post(
+            "/api/admin/seed",
+            &json!({ "scenario": scenario, "reset": reset }),
+        )
+        .await?;
+    if reset {
+        println!("Cleared {} tables", report.cleared_tables);
+    }
+    if report.seeded {
+        println!("Seeded {}", report.scenario);
+    } else {
+        println!("A scenario is already loaded; use `reseed` to replace it");
+    }
+    println!("{} components, {} people", report.components, report.people);
+    Ok(())
+}
+
+async fn run(api: &Api, command: Command) -> Result<()> {
+    match command {
+        Command::Seed { scenario } => seed(api, scenario, false).await?,
+        Command::Reseed { scenario } => seed(api, scenario, true).await?,
+        Command::ExportScenario { output } => {
+            let snapshot = api.get_text("/api/admin/scenario").await?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, snapshot)?;
+                    println!("Wrote {}", path.display());
+                }
+                None => println!("{}", snapshot),
+            }
+        }
+        Command::ExportTopology { format, output } => {
+            let document = api
+                .get_text(&format!("/api/iac/export?format={}", format))
+                .await?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, document)?;
+                    println!("Wrote {}", path.display());
+                }
+                None => println!("{}", document),
+            }
+        }
+        Command::ImportTopology {
+            file,
+            format,
+            dry_run,
+        } => {
+            let format = format.unwrap_or_else(|| topology_format(&file).to_string());
+            let document = std::fs::read_to_string(&file)?;
+            let path = format!("/api/iac/import?format={}&dry_run={}", format, dry_run);
+            let report: ApplyReport = api.post_text(&path, document).await?;
+            let (created, updated) = if dry_run {
+                ("Would create", "Would update")
+            } else {
+                ("Created", "Updated")
+            };
+            for (verb, counts) in [
+                (created, &report.created),
+                (updated, &report.updated),
+                ("Unchanged", &report.unchanged),
+            ] {
+                for (kind, count) in counts {
+                    println!("{} {} {}", verb, count, kind);
+                }
+            }
+        }
+        Command::Migrate { status: true } => {
+            let migrations: Vec<MigrationStatus> = api.get("/api/admin/migrations").await?;
+            for m in migrations {
+                println!(
+                    "{:<24} {}",
+                    m.id,
+                    m.applied_at.as_deref().unwrap_or("pending")
+                );
+            }
+        }
+        Command::Migrate { status: false } => {
+            let applied: Vec<String> = api.post("/api/admin/migrations", &json!({})).await?;
+            if applied.is_empty() {
+                println!("Schema is up to date");
+            }
+            for id in applied {
+                println!("Applied {}", id);
+            }
+        }
+        Command::Runs(RunsCommand::List) => {
+            let runs: Vec<Value> = api.get("/api/runs").await?;
+            for run in &runs {
+                println!(
+                    "{:<24} {:<12} {}",
+                    record_key(&run["id"]),
+                    run["status"].as_str().unwrap_or_default(),
+                    run["started_at"].as_str().unwrap_or_default()
+                );
+            }
+            println!("{} runs", runs.len());
+        }
+        Command::Runs(RunsCommand::Delete { id }) => {
+            api.delete(&format!("/api/runs/{}", id)).await?;
+            println!("Deleted run {}", id);
+        }
+        Command::Users(UsersCommand::Create {
+            name,
+            email,
+            title,
+            department,
+            site,
+            manager,
+            role,
+        }) => {
+            let body = json!({
+                "name": name,
+                "email": email,
+                "title": title,
+                "department": department,
+                "site_id": site,
+                "manager_id": manager,
+                "role": role,
+            });
+            let person: Value = api.post("/api/people", &body).await?;
+            println!("Created {} ({})", name, record_key(&person["id"]));
+        }
+        Command::Vacuum { dry_run } => {
+            let path = format!("/api/admin/vacuum?dry_run={}", dry_run);
+            let removed: BTreeMap<String, usize> = api.post(&path, &json!({})).await?;
+            let verb = if dry_run { "Would remove" } else { "Removed" };
+            if removed.is_empty() {
+                println!("Nothing to vacuum");
+            }
+            for (table, count) in removed {
+                println!("{} {} {}", verb, count, table);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let api = Api::new(&cli.server.unwrap_or_else(default_server));
+    run(&api, cli.command).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_is_consistent() {
+        Cli::command().debug_assert();
+        let cli = Cli::try_parse_from([
+            "rubigo-admin",
+            "--server",
+            "http://x",
+            "vacuum",
+            "--dry-run",
+        ])
+        .unwrap();
+        assert_eq!(cli.server.as_deref(), Some("http://x"));
+        assert!(matches!(cli.command, Command::Vacuum { dry_run: true }));
+    }
+
+    #[test]
+    fn topology_format_follows_extension() {
+        assert_eq!(
+            topology_format(std::path::Path::new("topology.toml")),
+            "toml"
+        );
+        assert_eq!(topology_format(std::path::Path::new("main.tf.json")), "tf");
+    }
+
+    #[test]
+    fn record_keys_from_strings_and_things() {
+        assert_eq!(record_key(&json!("abc")), "abc");
+        assert_eq!(
+            record_key(&json!({ "tb": "run", "id": { "String": "xyz" } })),
+            "xyz"
+        );
+        assert_eq!(
+            record_key(&json!({ "tb": "run", "id": { "Number": 7 } })),
+            "7"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+#!/bin/bash
+# Build the viewer as a standalone WASM module for the ui-app.
+#
+# Usage: build-module.sh <out-dir> [--release]
+#
+# Output:
+#   <out-dir>/bevy_viewer.js, <out-dir>/bevy_viewer_bg.wasm
+
+set -e
+
+OUT_DIR="$1"
+PROFILE="${2:-}"
+
+if [ -z "$OUT_DIR" ]; then
+    echo "Usage: $0 <out-dir> [--release]"
+    exit 1
+fi
+
+if ! command -v wasm-bindgen &> /dev/null; then
+    echo "wasm-bindgen CLI not found: cargo install wasm-bindgen-cli"
+    exit 1
+fi
+
+cd "$(dirname "$0")"
+
+# Embed the ui-app's assets, which the globe loads from
+export BEVY_ASSET_ROOT="$(cd ../ui-app && pwd)"
+
+cargo rustc --lib $PROFILE \
+    --target wasm32-unknown-unknown \
+    --features module \
+    --crate-type cdylib
+
+if [ "$PROFILE" = "--release" ]; then
+    WASM=../../target/wasm32-unknown-unknown/release/bevy_viewer.wasm
+else
+    WASM=../../target/wasm32-unknown-unknown/debug/bevy_viewer.wasm
+fi
+
+wasm-bindgen --target web --no-typescript --out-dir "$OUT_DIR" "$WASM"
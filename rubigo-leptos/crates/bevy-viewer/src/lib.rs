@@ -1,14 +1,20 @@
 //! Bevy Viewer - Reusable 3D Viewer Library
 //!
 //! Provides embeddable 3D visualization capabilities using Bevy.
-//! Designed to be compiled into the main ui-app.
+//! Built as a separate WASM module (feature `module`) that the ui-app
+//! fetches on demand, keeping Bevy out of its main bundle.
 
 pub mod camera;
 pub mod geo;
+#[cfg(all(feature = "module", target_arch = "wasm32"))]
+pub mod module;
 pub mod viewers;
 
 use bevy::prelude::*;
 
+/// Version of the module API; bump when `module`'s exports change
+pub const MODULE_API_VERSION: u32 = 1;
+
 /// Main plugin that sets up the viewer infrastructure
 pub struct BevyViewerPlugin;
 
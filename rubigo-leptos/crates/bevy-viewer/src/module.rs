@@ -0,0 +1,67 @@
+//! Standalone WASM Module
+//!
+//! Entry points for building the viewer as its own WASM module, which the
+//! ui-app fetches when the Sites page needs the globe instead of linking
+//! Bevy into its main bundle. These functions are the whole API between the
+//! two modules; nothing else crosses the boundary.
+
+use std::cell::Cell;
+
+use bevy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{BevyViewerPlugin, MODULE_API_VERSION};
+
+thread_local! {
+    static STARTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Version of this API; the ui-app refuses to start a module built against another
+#[wasm_bindgen]
+pub fn api_version() -> u32 {
+    MODULE_API_VERSION
+}
+
+/// Start the globe viewer on the canvas matching `canvas` (a CSS selector)
+///
+/// Bevy's event loop can only be created once per page, so later calls
+/// return `false` and leave the running viewer alone.
+#[wasm_bindgen]
+pub fn start_globe(canvas: &str) -> bool {
+    if STARTED.with(|started| started.replace(true)) {
+        log::warn!("Globe viewer already running");
+        return false;
+    }
+    console_error_panic_hook::set_once();
+    globe_app(canvas).run();
+    true
+}
+
+fn globe_app(canvas: &str) -> App {
+    let mut app = App::new();
+
+    // Add EmbeddedAssetPlugin BEFORE DefaultPlugins for WASM asset loading
+    app.add_plugins(bevy_embedded_assets::EmbeddedAssetPlugin::default());
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    canvas: Some(canvas.into()),
+                    fit_canvas_to_parent: true,
+                    prevent_default_event_handling: true,
+                    transparent: true, // Enable transparent canvas background
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(bevy::asset::AssetPlugin {
+                file_path: "assets".into(),
+                ..default()
+            }),
+    );
+
+    app.add_plugins(BevyViewerPlugin);
+
+    app
+}
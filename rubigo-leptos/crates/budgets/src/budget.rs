@@ -0,0 +1,313 @@
+//! Budgets and measurements
+//!
+//! Budgets live in the `[budgets]` section of `rubigo.toml`; paths there
+//! are relative to the file. The sizes of the previous check are kept in
+//! a JSON history file so each run can show what changed.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+/// Section of `rubigo.toml` holding the budgets
+pub const SECTION: &str = "budgets";
+
+/// History file used when the section names none
+const DEFAULT_HISTORY: &str = "target/wasm-sizes.json";
+
+/// The `[budgets]` section
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budgets {
+    /// Sizes recorded by the last check
+    #[serde(default = "default_history")]
+    pub history: PathBuf,
+    #[serde(default, rename = "artifact")]
+    pub artifacts: Vec<Artifact>,
+}
+
+/// One checked build output
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Artifact {
+    pub name: String,
+    /// File to measure; `*` in the file name matches any run of characters
+    /// and every match counts toward the budget
+    pub path: String,
+    /// Largest allowed size, e.g. `"1.5 MiB"`
+    pub max: String,
+}
+
+fn default_history() -> PathBuf {
+    PathBuf::from(DEFAULT_HISTORY)
+}
+
+impl Budgets {
+    /// Read the `[budgets]` section of a `rubigo.toml`
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("Reading {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut file: toml::Table = toml::from_str(text).context("Parsing rubigo.toml")?;
+        let section = file
+            .remove(SECTION)
+            .ok_or_else(|| anyhow!("rubigo.toml has no [{}] section", SECTION))?;
+        let budgets: Self = section
+            .try_into()
+            .with_context(|| format!("Invalid [{}]", SECTION))?;
+        for artifact in &budgets.artifacts {
+            parse_size(&artifact.max).with_context(|| format!("Budget for {}", artifact.name))?;
+        }
+        Ok(budgets)
+    }
+}
+
+/// Parse a size such as `512`, `300 KiB`, `1.5 MiB` or `2 MB` into bytes
+pub fn parse_size(raw: &str) -> Result<u64> {
+    let raw = raw.trim();
+    let split = raw
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(raw.len());
+    let (number, unit) = raw.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| anyhow!("Expected a size like '1.5 MiB', got '{}'", raw))?;
+    let scale = match unit.trim() {
+        "" | "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        other => bail!("Unknown size unit '{}' (use B, KB, MB, KiB or MiB)", other),
+    };
+    Ok((number * scale).round() as u64)
+}
+
+/// Human-readable size in binary units
+pub fn format_size(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.2} MiB", bytes / (1024.0 * 1024.0))
+    } else if bytes >= 1024.0 {
+        format!("{:.1} KiB", bytes / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Signed change between two sizes, e.g. `+12.0 KiB`
+pub fn format_change(previous: Option<u64>, current: u64) -> String {
+    match previous {
+        None => "new".to_string(),
+        Some(previous) if previous == current => "±0".to_string(),
+        Some(previous) if current > previous => format!("+{}", format_size(current - previous)),
+        Some(previous) => format!("-{}", format_size(previous - current)),
+    }
+}
+
+/// Whether a file name matches a pattern where `*` matches any run of characters
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Files an artifact path refers to, relative to `root`
+pub fn resolve(root: &Path, path: &str) -> Result<Vec<PathBuf>> {
+    let full = root.join(path);
+    let file_name = full
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if !file_name.contains('*') {
+        return Ok(if full.is_file() {
+            vec![full]
+        } else {
+            Vec::new()
+        });
+    }
+    let dir = full.parent().unwrap_or(root);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut found = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        if entry.file_type()?.is_file() && name.to_str().is_some_and(|n| matches(file_name, n)) {
+            found.push(entry.path());
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// One artifact's result
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub name: String,
+    /// Total size of every matching file; `None` when nothing matched
+    pub size: Option<u64>,
+    pub budget: u64,
+    pub previous: Option<u64>,
+}
+
+impl Measurement {
+    pub fn over_budget(&self) -> bool {
+        self.size.is_some_and(|size| size > self.budget)
+    }
+}
+
+/// Measure every artifact against its budget
+pub fn measure(
+    root: &Path,
+    budgets: &Budgets,
+    history: &BTreeMap<String, u64>,
+) -> Result<Vec<Measurement>> {
+    budgets
+        .artifacts
+        .iter()
+        .map(|artifact| {
+            let files = resolve(root, &artifact.path)?;
+            let size = if files.is_empty() {
+                None
+            } else {
+                let mut total = 0;
+                for file in &files {
+                    total += std::fs::metadata(file)?.len();
+                }
+                Some(total)
+            };
+            Ok(Measurement {
+                name: artifact.name.clone(),
+                size,
+                budget: parse_size(&artifact.max)?,
+                previous: history.get(&artifact.name).copied(),
+            })
+        })
+        .collect()
+}
+
+/// Sizes recorded by the previous check; empty when there was none
+pub fn load_history(path: &Path) -> Result<BTreeMap<String, u64>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => {
+            serde_json::from_str(&text).with_context(|| format!("Parsing {}", path.display()))
+        }
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
+
+/// Record this check's sizes, keeping entries for artifacts not built this time
+pub fn save_history(
+    path: &Path,
+    mut history: BTreeMap<String, u64>,
+    measured: &[Measurement],
+) -> Result<()> {
+    for m in measured {
+        if let Some(size) = m.size {
+            history.insert(m.name.clone(), size);
+        }
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&history)?)
+        .with_context(|| format!("Writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_parse_in_decimal_and_binary_units() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("300 KiB").unwrap(), 300 * 1024);
+        assert_eq!(parse_size("1.5MiB").unwrap(), 1_572_864);
+        assert_eq!(parse_size("2 MB").unwrap(), 2_000_000);
+        assert!(parse_size("2 GB").is_err());
+        assert!(parse_size("big").is_err());
+    }
+
+    #[test]
+    fn wildcards_match_file_names() {
+        assert!(matches("ui-app-*_bg.wasm", "ui-app-3f9a1c_bg.wasm"));
+        assert!(matches("*.wasm", "router_wasm.wasm"));
+        assert!(!matches("*.wasm", "router_wasm.js"));
+        assert!(!matches("a*b*c", "ac"));
+        assert!(matches("exact.wasm", "exact.wasm"));
+    }
+
+    #[test]
+    fn section_parses_and_validates_sizes() {
+        let budgets = Budgets::parse(
+            r#"
+            [runtime]
+            dev_mode = true
+
+            [budgets]
+            [[budgets.artifact]]
+            name = "ui-app"
+            path = "crates/ui-app/dist/*_bg.wasm"
+            max = "4 MiB"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(budgets.history, PathBuf::from(DEFAULT_HISTORY));
+        assert_eq!(budgets.artifacts[0].name, "ui-app");
+        assert!(
+            Budgets::parse("[[budgets.artifact]]\nname = \"x\"\npath = \"x\"\nmax = \"lots\"")
+                .is_err()
+        );
+        assert!(Budgets::parse("[runtime]").is_err());
+    }
+
+    #[test]
+    fn measurement_compares_against_budget_and_history() {
+        let dir = std::env::temp_dir().join(format!("rubigo-budgets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a_bg.wasm"), vec![0u8; 600]).unwrap();
+        std::fs::write(dir.join("b_bg.wasm"), vec![0u8; 600]).unwrap();
+        let budgets = Budgets::parse(
+            r#"
+            [[budgets.artifact]]
+            name = "bundle"
+            path = "*_bg.wasm"
+            max = "1 KiB"
+
+            [[budgets.artifact]]
+            name = "missing"
+            path = "nothing.wasm"
+            max = "1 KiB"
+            "#,
+        )
+        .unwrap();
+        let history = BTreeMap::from([("bundle".to_string(), 1000)]);
+        let measured = measure(&dir, &budgets, &history).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(measured[0].size, Some(1200));
+        assert!(measured[0].over_budget());
+        assert_eq!(format_change(measured[0].previous, 1200), "+200 B");
+        assert_eq!(measured[1].size, None);
+        assert!(!measured[1].over_budget());
+    }
+}
@@ -0,0 +1,140 @@
+//! rubigo-budgets
+//!
+//! Build step that measures the compiled WASM artifacts (component modules,
+//! the gui-server hydration bundle, the ui-app bundle) against the budgets
+//! declared in `rubigo.toml`, prints the change since the previous check,
+//! and exits non-zero when any artifact is over budget:
+//!
+//! ```text
+//! cargo run -p budgets                  # after a release build
+//! cargo run -p budgets -- --no-save     # check without updating the history
+//! ```
+//!
+//! Artifacts that have not been built are reported and skipped unless
+//! `--require-all` is given.
+
+mod budget;
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+
+use budget::{format_change, format_size, Budgets, Measurement};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "rubigo-budgets",
+    version,
+    about = "Check WASM artifact sizes against rubigo.toml budgets"
+)]
+struct Cli {
+    /// rubigo.toml to read; defaults to the nearest one from the working directory
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Fail when an artifact has not been built
+    #[arg(long)]
+    require_all: bool,
+
+    /// Don't record this check's sizes for the next diff
+    #[arg(long)]
+    no_save: bool,
+}
+
+fn print_report(measured: &[Measurement]) {
+    let width = measured
+        .iter()
+        .map(|m| m.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Artifact".len());
+    println!(
+        "{:<width$}  {:>11}  {:>11}  {:>5}  Change",
+        "Artifact", "Size", "Budget", "Used"
+    );
+    for m in measured {
+        let Some(size) = m.size else {
+            println!(
+                "{:<width$}  {:>11}  {:>11}  {:>5}  -",
+                m.name,
+                "not built",
+                format_size(m.budget),
+                "-"
+            );
+            continue;
+        };
+        let used = size as f64 / m.budget as f64 * 100.0;
+        let flag = if m.over_budget() { "  OVER BUDGET" } else { "" };
+        println!(
+            "{:<width$}  {:>11}  {:>11}  {:>4.0}%  {}{}",
+            m.name,
+            format_size(size),
+            format_size(m.budget),
+            used,
+            format_change(m.previous, size),
+            flag
+        );
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let config_path = match cli.config {
+        Some(path) => path,
+        None => {
+            let cwd = std::env::current_dir().context("Reading working directory")?;
+            config::find_file(&cwd).with_context(|| {
+                format!("No {} found from {}", config::CONFIG_FILE, cwd.display())
+            })?
+        }
+    };
+    let root = config_path.parent().map(PathBuf::from).unwrap_or_default();
+    let budgets = Budgets::from_file(&config_path)?;
+
+    let history_path = root.join(&budgets.history);
+    let history = budget::load_history(&history_path)?;
+    let measured = budget::measure(&root, &budgets, &history)?;
+    print_report(&measured);
+
+    if !cli.no_save {
+        budget::save_history(&history_path, history, &measured)?;
+    }
+
+    let missing: Vec<&str> = measured
+        .iter()
+        .filter(|m| m.size.is_none())
+        .map(|m| m.name.as_str())
+        .collect();
+    let over: Vec<&str> = measured
+        .iter()
+        .filter(|m| m.over_budget())
+        .map(|m| m.name.as_str())
+        .collect();
+    if !over.is_empty() {
+        bail!("Over budget: {}", over.join(", "));
+    }
+    if cli.require_all && !missing.is_empty() {
+        bail!("Not built: {}", missing.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn repository_budgets_parse() {
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../../rubigo.toml"));
+        let budgets = Budgets::from_file(&path).unwrap();
+        assert!(!budgets.artifacts.is_empty());
+    }
+}
@@ -0,0 +1,1048 @@
+//! Layered Configuration
+//!
+//! Typed runtime settings shared by gui-server, the action backend (`db`)
+//! and the Tauri shell. Each layer overrides the one before it:
+//!
+//! 1. Built-in defaults ([`Settings::default`])
+//! 2. The `[runtime]` section of `rubigo.toml`, found in the working
+//!    directory or the nearest parent (or given with `--config`)
+//! 3. Environment variables: `RUBIGO_<SECTION>__<KEY>`, e.g.
+//!    `RUBIGO_SERVER__PORT=4000`, plus the older `PORT`, `DEV_MODE`,
+//!    `CITIES_DB_PATH` and `SCENARIO_PATH`
+//! 4. Command-line flags: `--port`, `--grpc-port`, `--host`, `--dev`,
+//!    `--scenario`, `--feature name[=bool]` and `--set section.key=value`
+//!
+//! Relative paths are resolved against the working directory, as before.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! let settings = config::Settings::load()?;
+//! let addr = (settings.server.host.as_str(), settings.server.port);
+//! if settings.flag("new_calendar") { /* ... */ }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+/// File searched for in the working directory and its parents
+pub const CONFIG_FILE: &str = "rubigo.toml";
+
+/// Section of [`CONFIG_FILE`] holding runtime settings
+pub const RUNTIME_SECTION: &str = "runtime";
+
+/// Prefix of environment variables that override settings
+pub const ENV_PREFIX: &str = "RUBIGO_";
+
+/// Older environment variables still honoured, with the key they set
+const LEGACY_ENV: [(&str, &str); 4] = [
+    ("PORT", "server.port"),
+    ("DEV_MODE", "dev_mode"),
+    ("CITIES_DB_PATH", "paths.cities"),
+    ("SCENARIO_PATH", "paths.scenario"),
+];
+
+/// Section whose keys are feature flag names
+const FEATURES_SECTION: &str = "features";
+
+/// All runtime settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub server: ServerSettings,
+    pub paths: PathSettings,
+    pub database: DatabaseSettings,
+    pub sync: SyncSettings,
+    pub updates: UpdateSettings,
+    pub approvals: ApprovalSettings,
+    pub scheduler: SchedulerSettings,
+    pub email: EmailSettings,
+    pub auth: AuthSettings,
+    pub telemetry: TelemetrySettings,
+    pub netbox: NetboxSettings,
+    /// Development conveniences: persona switching, auto-reload, verbose logs
+    pub dev_mode: bool,
+    /// Feature flags by name; unknown flags are off
+    pub features: BTreeMap<String, bool>,
+}
+
+/// HTTP and gRPC listeners
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    /// Port of the gRPC action service
+    pub grpc_port: u16,
+}
+
+/// Data files loaded at startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathSettings {
+    /// City list imported first, for a fast start
+    pub cities: PathBuf,
+    /// Full city list imported in the background
+    pub cities_full: PathBuf,
+    /// Directory holding the country and state GeoJSON files
+    pub geo_dir: PathBuf,
+    /// Scenario seeded into the database
+    pub scenario: PathBuf,
+}
+
+/// SurrealDB namespace and database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    pub namespace: String,
+    pub database: String,
+}
+
+/// Desktop replica sync with a remote gui-server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SyncSettings {
+    /// Base URL of the gui-server to sync with; the Tauri shell works
+    /// offline only when unset
+    pub remote: Option<String>,
+    /// Seconds between sync rounds
+    pub interval_secs: u64,
+    /// Which side keeps a record both edited since the last sync
+    pub conflict_policy: ConflictPolicy,
+}
+
+/// How a record edited on both sides between syncs is settled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// The later edit wins, by modification time; the server on a tie
+    #[default]
+    LastWriteWins,
+    ServerWins,
+    ClientWins,
+}
+
+impl ConflictPolicy {
+    /// Whether the client's edit beats the server's, given when each was made
+    pub fn client_wins(self, client_modified: i64, server_modified: i64) -> bool {
+        match self {
+            ConflictPolicy::LastWriteWins => client_modified > server_modified,
+            ConflictPolicy::ServerWins => false,
+            ConflictPolicy::ClientWins => true,
+        }
+    }
+}
+
+/// Where the Tauri shell looks for new versions of itself
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpdateSettings {
+    /// Update manifest URL; `{channel}` is replaced by the channel name and
+    /// Tauri fills in `{{target}}`, `{{arch}}` and `{{current_version}}`.
+    /// Update checks are off when unset.
+    pub endpoint: Option<String>,
+    /// Channel used until the user picks one in the app
+    pub channel: UpdateChannel,
+}
+
+/// Release track: stable releases only, or also pre-releases
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn name(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [UpdateChannel::Stable, UpdateChannel::Beta]
+            .into_iter()
+            .find(|channel| channel.name() == name)
+    }
+}
+
+impl UpdateSettings {
+    /// Manifest URL for `channel`, if update checks are configured
+    pub fn endpoint_for(&self, channel: UpdateChannel) -> Option<String> {
+        self.endpoint
+            .as_ref()
+            .map(|template| template.replace("{channel}", channel.name()))
+    }
+}
+
+/// Change requests for edits to protected records
+///
+/// Edits to records carrying the protected tag wait for approval by one of
+/// the approvers. The workflow is off while nobody can approve.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApprovalSettings {
+    /// Name of the tag marking protected records
+    pub protected_tag: String,
+    /// Personas holding the change approver role
+    pub approvers: Vec<String>,
+}
+
+impl ApprovalSettings {
+    /// Whether edits to protected records are held for approval
+    pub fn enabled(&self) -> bool {
+        !self.approvers.is_empty()
+    }
+}
+
+impl Default for ApprovalSettings {
+    fn default() -> Self {
+        Self {
+            protected_tag: "protected".to_string(),
+            approvers: Vec::new(),
+        }
+    }
+}
+
+/// Recurring server tasks (gui-server)
+///
+/// Schedules themselves are kept in the database and edited at
+/// `/api/schedules`; these settings cover the service as a whole.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulerSettings {
+    /// Run due schedules; when off they can still be run by hand
+    pub enabled: bool,
+    /// Directory the scenario export task writes to
+    pub export_dir: PathBuf,
+    /// Scenario exports kept; older ones are deleted
+    pub exports_kept: usize,
+    /// Personas told when a scheduled run fails; when empty, whoever is on
+    /// call for the default escalation policy, or everyone when nobody is
+    pub alert: Vec<String>,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            export_dir: PathBuf::from("exports"),
+            exports_kept: 7,
+            alert: Vec::new(),
+        }
+    }
+}
+
+/// Outgoing email (gui-server)
+///
+/// Meeting invites, report deliveries and mentions are queued and sent in
+/// the background. The console transport only logs each message, so
+/// nothing leaves a development machine by accident.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailSettings {
+    pub transport: EmailTransport,
+    /// Sender address, optionally with a display name
+    pub from: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    /// Upgrade the SMTP connection with STARTTLS before authenticating
+    pub starttls: bool,
+}
+
+/// How queued emails leave the server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTransport {
+    /// Write messages to the log instead of sending them
+    #[default]
+    Console,
+    Smtp,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            transport: EmailTransport::default(),
+            from: "Rubigo <rubigo@localhost>".to_string(),
+            smtp_host: "localhost".to_string(),
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+            starttls: true,
+        }
+    }
+}
+
+/// Live telemetry from real devices (gui-server)
+///
+/// Which components are polled, and how, is set per component on the
+/// Components page or at `/api/components/{id}/telemetry`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetrySettings {
+    /// Poll components with a live source; when off they keep their last readings
+    pub poll: bool,
+    /// How long a device has to answer a poll
+    pub timeout_secs: u64,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            poll: true,
+            timeout_secs: 5,
+        }
+    }
+}
+
+/// NetBox import (gui-server)
+///
+/// With a NetBox URL set, the `netbox.sync` schedule and
+/// `POST /api/netbox/sync` copy its sites, racks, devices and cables into
+/// the database. Which NetBox field feeds each Rubigo field can be changed
+/// in `fields`; see `operations::netbox::FIELDS` for the names and defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetboxSettings {
+    /// Instance URL, e.g. `https://netbox.example.com`. The import is off when unset.
+    pub url: Option<String>,
+    /// API token, sent as `Authorization: Token …`
+    pub token: Option<String>,
+    /// Objects fetched per API request
+    pub page_size: u32,
+    /// Refresh records imported earlier and remove those gone from NetBox;
+    /// when off a sync only adds objects it has not imported yet
+    pub resync: bool,
+    /// Rubigo field to dotted NetBox path, e.g. `site_name = "facility"`
+    pub fields: BTreeMap<String, String>,
+    /// NetBox site status to Rubigo status, e.g. `staging = "active"`
+    pub statuses: BTreeMap<String, String>,
+}
+
+impl NetboxSettings {
+    pub fn enabled(&self) -> bool {
+        self.url.is_some()
+    }
+}
+
+impl Default for NetboxSettings {
+    fn default() -> Self {
+        Self {
+            url: None,
+            token: None,
+            page_size: 100,
+            resync: true,
+            fields: BTreeMap::new(),
+            statuses: BTreeMap::new(),
+        }
+    }
+}
+
+/// Corporate identity (gui-server)
+///
+/// With an OIDC issuer set, people sign in through the identity provider
+/// instead of picking a seeded persona; with an LDAP server set, the
+/// `directory.sync` schedule copies its people into the database. Both map
+/// the external groups people belong to onto roles with `group_roles`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    pub oidc: OidcSettings,
+    pub ldap: LdapSettings,
+    /// Group to role mappings; the first one matching a person's groups
+    /// wins, and people in no mapped group keep their current role
+    pub group_roles: Vec<GroupRole>,
+}
+
+/// OpenID Connect sign-in (authorization code flow with PKCE)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OidcSettings {
+    /// Issuer URL, whose `/.well-known/openid-configuration` describes the
+    /// provider. Sign-in through the provider is off when unset.
+    pub issuer: Option<String>,
+    pub client_id: String,
+    /// Secret of a confidential client; public clients rely on PKCE alone
+    pub client_secret: Option<String>,
+    /// This server's callback, as registered with the provider
+    pub redirect_url: String,
+    pub scopes: Vec<String>,
+    /// ID token claim listing the person's groups
+    pub groups_claim: String,
+}
+
+impl OidcSettings {
+    pub fn enabled(&self) -> bool {
+        self.issuer.is_some()
+    }
+}
+
+impl Default for OidcSettings {
+    fn default() -> Self {
+        Self {
+            issuer: None,
+            client_id: "rubigo".to_string(),
+            client_secret: None,
+            redirect_url: "http://localhost:3000/auth/oidc/callback".to_string(),
+            scopes: ["openid", "profile", "email"].map(String::from).to_vec(),
+            groups_claim: "groups".to_string(),
+        }
+    }
+}
+
+/// LDAP directory sync of personnel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LdapSettings {
+    /// Server URL, e.g. `ldaps://ldap.example.com`. The sync is off when unset.
+    pub url: Option<String>,
+    /// DN to bind as; binds anonymously when unset
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    /// Where people are searched for
+    pub base_dn: String,
+    pub user_filter: String,
+    pub name_attribute: String,
+    pub email_attribute: String,
+    pub title_attribute: String,
+    pub department_attribute: String,
+    /// Attribute listing the groups a person belongs to
+    pub group_attribute: String,
+    /// Site new people are placed at, by name; the first site when unset
+    pub site: Option<String>,
+}
+
+impl LdapSettings {
+    pub fn enabled(&self) -> bool {
+        self.url.is_some()
+    }
+}
+
+impl Default for LdapSettings {
+    fn default() -> Self {
+        Self {
+            url: None,
+            bind_dn: None,
+            bind_password: None,
+            base_dn: String::new(),
+            user_filter: "(objectClass=person)".to_string(),
+            name_attribute: "cn".to_string(),
+            email_attribute: "mail".to_string(),
+            title_attribute: "title".to_string(),
+            department_attribute: "department".to_string(),
+            group_attribute: "memberOf".to_string(),
+            site: None,
+        }
+    }
+}
+
+/// People in `group` get `role`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupRole {
+    /// Group name, or its full DN for LDAP groups
+    pub group: String,
+    /// Role name, e.g. `ITAdmin` or `Engineer`
+    pub role: String,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 3000,
+            grpc_port: 50051,
+        }
+    }
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            cities: PathBuf::from("../../common/geo/worldcities_dev.csv"),
+            cities_full: PathBuf::from("../../common/geo/worldcities.csv"),
+            geo_dir: PathBuf::from("../../common/geo"),
+            scenario: PathBuf::from("../../common/scenarios/mmc/scenario.toml"),
+        }
+    }
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            namespace: "nexosim".to_string(),
+            database: "main".to_string(),
+        }
+    }
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            remote: None,
+            interval_secs: 30,
+            conflict_policy: ConflictPolicy::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load from every layer, using the process environment and arguments
+    pub fn load() -> Result<Self> {
+        Loader::new()
+            .env(std::env::vars())
+            .args(std::env::args().skip(1))
+            .load()
+    }
+
+    /// Whether feature flag `name` is on
+    pub fn flag(&self, name: &str) -> bool {
+        self.features.get(name).copied().unwrap_or(false)
+    }
+}
+
+/// Builder for [`Settings`] from explicit sources
+///
+/// [`Settings::load`] covers the usual case; tests and embedders (Tauri)
+/// use this to control which environment and arguments are read.
+#[derive(Debug, Clone, Default)]
+pub struct Loader {
+    file: Option<PathBuf>,
+    search_from: Option<PathBuf>,
+    file_text: Option<String>,
+    env: Vec<(String, String)>,
+    args: Vec<String>,
+}
+
+impl Loader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read this file instead of searching for [`CONFIG_FILE`]; it must exist
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Search for [`CONFIG_FILE`] from this directory instead of the
+    /// working directory
+    pub fn search_from(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.search_from = Some(dir.into());
+        self
+    }
+
+    /// Environment variables to apply
+    pub fn env<K: Into<String>, V: Into<String>>(
+        mut self,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.env = vars
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self
+    }
+
+    /// Command-line arguments to apply, without the program name
+    pub fn args<A: Into<String>>(mut self, args: impl IntoIterator<Item = A>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Merge all layers into typed settings
+    pub fn load(self) -> Result<Settings> {
+        let flags = parse_args(&self.args)?;
+        let mut value = Value::try_from(Settings::default()).context("Default settings")?;
+
+        // rubigo.toml
+        let explicit = flags
+            .config
+            .clone()
+            .or_else(|| self.env_var("RUBIGO_CONFIG").map(PathBuf::from))
+            .or(self.file.clone());
+        let text = match (&self.file_text, explicit) {
+            (Some(text), _) => Some(text.clone()),
+            (None, Some(path)) => Some(
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Reading {}", path.display()))?,
+            ),
+            (None, None) => {
+                let start = match &self.search_from {
+                    Some(dir) => dir.clone(),
+                    None => std::env::current_dir().context("Reading working directory")?,
+                };
+                find_file(&start)
+                    .map(|path| {
+                        std::fs::read_to_string(&path)
+                            .with_context(|| format!("Reading {}", path.display()))
+                    })
+                    .transpose()?
+            }
+        };
+        if let Some(text) = text {
+            let mut file: Table = toml::from_str(&text).context("Parsing rubigo.toml")?;
+            if let Some(runtime) = file.remove(RUNTIME_SECTION) {
+                if !runtime.is_table() {
+                    bail!("[{}] in rubigo.toml must be a table", RUNTIME_SECTION);
+                }
+                merge(&mut value, runtime);
+            }
+        }
+
+        // Environment: legacy names first so the prefixed form wins
+        for (name, key) in LEGACY_ENV {
+            if let Some(raw) = self.env_var(name) {
+                set(&mut value, key, &raw)
+                    .with_context(|| format!("Environment variable {}", name))?;
+            }
+        }
+        let mut prefixed: Vec<&(String, String)> = self
+            .env
+            .iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX) && name != "RUBIGO_CONFIG")
+            .collect();
+        prefixed.sort();
+        for (name, raw) in prefixed {
+            let key = name[ENV_PREFIX.len()..].to_lowercase().replace("__", ".");
+            set(&mut value, &key, raw).with_context(|| format!("Environment variable {}", name))?;
+        }
+
+        // Command line
+        for (key, raw) in &flags.overrides {
+            set(&mut value, key, raw).with_context(|| format!("Flag for {}", key))?;
+        }
+
+        value.try_into().context("Invalid settings")
+    }
+
+    fn env_var(&self, name: &str) -> Option<String> {
+        self.env
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.clone())
+    }
+}
+
+/// Nearest [`CONFIG_FILE`] in `dir` or its parents
+pub fn find_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Flags recognised on the command line
+#[derive(Debug, Default, PartialEq)]
+struct Flags {
+    config: Option<PathBuf>,
+    /// `(key, raw value)` in the order given
+    overrides: Vec<(String, String)>,
+}
+
+fn parse_args(args: &[String]) -> Result<Flags> {
+    let mut flags = Flags::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        // `--name=value` and `--name value` are equivalent
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |flag: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| anyhow!("{} needs a value", flag))
+        };
+        match name {
+            "--config" => flags.config = Some(PathBuf::from(value(name)?)),
+            "--port" => flags.overrides.push(("server.port".into(), value(name)?)),
+            "--grpc-port" => flags
+                .overrides
+                .push(("server.grpc_port".into(), value(name)?)),
+            "--host" => flags.overrides.push(("server.host".into(), value(name)?)),
+            "--scenario" => flags
+                .overrides
+                .push(("paths.scenario".into(), value(name)?)),
+            "--dev" => flags.overrides.push(("dev_mode".into(), "true".into())),
+            "--feature" => {
+                let spec = value(name)?;
+                let (flag, on) = spec.split_once('=').unwrap_or((spec.as_str(), "true"));
+                flags
+                    .overrides
+                    .push((format!("{}.{}", FEATURES_SECTION, flag), on.to_string()));
+            }
+            "--set" => {
+                let spec = value(name)?;
+                let (key, raw) = spec
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("--set expects key=value, got '{}'", spec))?;
+                flags.overrides.push((key.to_string(), raw.to_string()));
+            }
+            other => bail!("Unknown flag '{}'", other),
+        }
+    }
+    Ok(flags)
+}
+
+/// Overlay `overlay` onto `base`, merging tables key by key
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base), Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Set dotted `key` to `raw`, parsed as the type already at that key
+///
+/// Keys the defaults leave out are `Option<String>` fields, entries of a
+/// string map, or typos that deserializing rejects, so they are set as text;
+/// only feature flags are booleans. Nothing is guessed from how `raw` looks,
+/// which would make a password of `123456` a number.
+fn set(root: &mut Value, key: &str, raw: &str) -> Result<()> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts
+        .pop()
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| anyhow!("Empty key"))?;
+    let missing = match parts.as_slice() {
+        [FEATURES_SECTION] => Value::Boolean(false),
+        _ => Value::String(String::new()),
+    };
+    let mut table = root
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("Settings are not a table"))?;
+    for part in parts {
+        table = table
+            .entry(part)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow!("'{}' in '{}' is not a section", part, key))?;
+    }
+    let current = table.get(last).unwrap_or(&missing);
+    let value = coerce(current, raw).with_context(|| format!("Setting '{}'", key))?;
+    table.insert(last.to_string(), value);
+    Ok(())
+}
+
+/// Parse `raw` as the type of `current`
+fn coerce(current: &Value, raw: &str) -> Result<Value> {
+    let raw = raw.trim();
+    Ok(match current {
+        Value::Boolean(_) => Value::Boolean(
+            parse_bool(raw).ok_or_else(|| anyhow!("Expected true or false, got '{}'", raw))?,
+        ),
+        Value::Integer(_) => Value::Integer(
+            raw.parse()
+                .with_context(|| format!("Expected a whole number, got '{}'", raw))?,
+        ),
+        Value::Float(_) => Value::Float(
+            raw.parse()
+                .with_context(|| format!("Expected a number, got '{}'", raw))?,
+        ),
+        Value::Table(_) => bail!("'{}' replaces a whole section", raw),
+        _ => Value::String(raw.to_string()),
+    })
+}
+
+fn parse_bool(raw: &str) -> Option<bool> {
+    match raw.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+        [app]
+        name = "Rubigo"
+
+        [runtime]
+        dev_mode = true
+
+        [runtime.server]
+        port = 4000
+
+        [runtime.features]
+        new_calendar = true
+    "#;
+
+    fn loader() -> Loader {
+        Loader {
+            file_text: Some(FILE.to_string()),
+            ..Loader::new()
+        }
+    }
+
+    #[test]
+    fn defaults_apply_without_other_layers() {
+        let settings = Loader {
+            file_text: Some(String::new()),
+            ..Loader::new()
+        }
+        .load()
+        .unwrap();
+        assert_eq!(settings, Settings::default());
+        assert!(!settings.flag("new_calendar"));
+    }
+
+    #[test]
+    fn file_runtime_section_overrides_defaults() {
+        let settings = loader().load().unwrap();
+        assert_eq!(settings.server.port, 4000);
+        assert_eq!(settings.server.host, "0.0.0.0");
+        assert!(settings.dev_mode);
+        assert!(settings.flag("new_calendar"));
+    }
+
+    #[test]
+    fn env_overrides_file_and_prefixed_beats_legacy() {
+        let settings = loader()
+            .env([
+                ("PORT", "5000"),
+                ("RUBIGO_SERVER__PORT", "5001"),
+                ("DEV_MODE", "0"),
+                ("RUBIGO_FEATURES__BEVY_GLOBE", "on"),
+                ("HOME", "/root"),
+            ])
+            .load()
+            .unwrap();
+        assert_eq!(settings.server.port, 5001);
+        assert!(!settings.dev_mode);
+        assert!(settings.flag("bevy_globe"));
+    }
+
+    #[test]
+    fn flags_override_env() {
+        let settings = loader()
+            .env([("RUBIGO_SERVER__PORT", "5001")])
+            .args([
+                "--port=6000",
+                "--feature",
+                "new_calendar=false",
+                "--set",
+                "paths.scenario=scenarios/demo.toml",
+            ])
+            .load()
+            .unwrap();
+        assert_eq!(settings.server.port, 6000);
+        assert!(!settings.flag("new_calendar"));
+        assert_eq!(
+            settings.paths.scenario,
+            PathBuf::from("scenarios/demo.toml")
+        );
+    }
+
+    #[test]
+    fn bad_values_and_unknown_keys_are_errors() {
+        assert!(loader().env([("PORT", "http")]).load().is_err());
+        assert!(loader().args(["--set", "server.prot=1"]).load().is_err());
+        assert!(loader().args(["--verbose"]).load().is_err());
+        assert!(loader().args(["--port"]).load().is_err());
+    }
+
+    #[test]
+    fn sync_settings_load_from_env() {
+        let settings = loader()
+            .env([
+                ("RUBIGO_SYNC__REMOTE", "http://hq.example:3000"),
+                ("RUBIGO_SYNC__CONFLICT_POLICY", "server_wins"),
+            ])
+            .load()
+            .unwrap();
+        assert_eq!(
+            settings.sync.remote.as_deref(),
+            Some("http://hq.example:3000")
+        );
+        assert_eq!(settings.sync.interval_secs, 30);
+        assert_eq!(settings.sync.conflict_policy, ConflictPolicy::ServerWins);
+        assert!(loader()
+            .env([("RUBIGO_SYNC__CONFLICT_POLICY", "coin_toss")])
+            .load()
+            .is_err());
+    }
+
+    #[test]
+    fn update_endpoint_follows_the_channel() {
+        let settings = loader()
+            .env([(
+                "RUBIGO_UPDATES__ENDPOINT",
+                "https://releases.example/{channel}/{{target}}/{{current_version}}",
+            )])
+            .load()
+            .unwrap();
+        assert_eq!(settings.updates.channel, UpdateChannel::Stable);
+        assert_eq!(
+            settings.updates.endpoint_for(UpdateChannel::Beta).unwrap(),
+            "https://releases.example/beta/{{target}}/{{current_version}}"
+        );
+        assert_eq!(UpdateChannel::from_name("beta"), Some(UpdateChannel::Beta));
+        assert_eq!(UpdateChannel::from_name("nightly"), None);
+        assert_eq!(
+            UpdateSettings::default().endpoint_for(UpdateChannel::Stable),
+            None
+        );
+    }
+
+    #[test]
+    fn approvals_are_off_without_approvers() {
+        assert!(!Settings::default().approvals.enabled());
+        let settings = Loader {
+            file_text: Some(
+                r#"
+                [runtime.approvals]
+                protected_tag = "production"
+                approvers = ["Grace Hopper"]
+                "#
+                .to_string(),
+            ),
+            ..Loader::new()
+        }
+        .load()
+        .unwrap();
+        assert!(settings.approvals.enabled());
+        assert_eq!(settings.approvals.protected_tag, "production");
+    }
+
+    #[test]
+    fn scheduler_settings_load_from_env() {
+        let settings = loader()
+            .env([
+                ("RUBIGO_SCHEDULER__ENABLED", "false"),
+                ("RUBIGO_SCHEDULER__EXPORTS_KEPT", "30"),
+            ])
+            .load()
+            .unwrap();
+        assert!(!settings.scheduler.enabled);
+        assert_eq!(settings.scheduler.exports_kept, 30);
+        assert_eq!(settings.scheduler.export_dir, PathBuf::from("exports"));
+    }
+
+    #[test]
+    fn email_settings_load_from_env() {
+        let settings = loader()
+            .env([
+                ("RUBIGO_EMAIL__TRANSPORT", "smtp"),
+                ("RUBIGO_EMAIL__SMTP_HOST", "mail.example.com"),
+                ("RUBIGO_EMAIL__SMTP_PORT", "2525"),
+            ])
+            .load()
+            .unwrap();
+        assert_eq!(settings.email.transport, EmailTransport::Smtp);
+        assert_eq!(settings.email.smtp_host, "mail.example.com");
+        assert_eq!(settings.email.smtp_port, 2525);
+        assert!(settings.email.starttls);
+
+        // Optional text stays text, however it looks
+        let settings = loader()
+            .env([
+                ("RUBIGO_EMAIL__SMTP_USERNAME", "yes"),
+                ("RUBIGO_EMAIL__SMTP_PASSWORD", "123456"),
+            ])
+            .load()
+            .unwrap();
+        assert_eq!(settings.email.smtp_username.as_deref(), Some("yes"));
+        assert_eq!(settings.email.smtp_password.as_deref(), Some("123456"));
+        assert_eq!(
+            loader().load().unwrap().email.transport,
+            EmailTransport::Console
+        );
+    }
+
+    #[test]
+    fn auth_settings_load_from_file_and_env() {
+        assert!(!Settings::default().auth.oidc.enabled());
+        let settings = Loader {
+            file_text: Some(
+                r#"
+                [runtime.auth.oidc]
+                issuer = "https://id.example.com"
+
+                [[runtime.auth.group_roles]]
+                group = "it-admins"
+                role = "ITAdmin"
+                "#
+                .to_string(),
+            ),
+            ..Loader::new()
+        }
+        .env([("RUBIGO_AUTH__LDAP__URL", "ldaps://ldap.example.com")])
+        .load()
+        .unwrap();
+        assert!(settings.auth.oidc.enabled());
+        assert_eq!(settings.auth.oidc.groups_claim, "groups");
+        assert!(settings.auth.ldap.enabled());
+        assert_eq!(settings.auth.ldap.group_attribute, "memberOf");
+        assert_eq!(settings.auth.group_roles[0].role, "ITAdmin");
+    }
+
+    #[test]
+    fn telemetry_settings_load_from_env() {
+        let settings = loader()
+            .env([
+                ("RUBIGO_TELEMETRY__POLL", "false"),
+                ("RUBIGO_TELEMETRY__TIMEOUT_SECS", "2"),
+            ])
+            .load()
+            .unwrap();
+        assert!(!settings.telemetry.poll);
+        assert_eq!(settings.telemetry.timeout_secs, 2);
+        assert!(loader().load().unwrap().telemetry.poll);
+    }
+
+    #[test]
+    fn netbox_settings_load_field_mappings() {
+        assert!(!Settings::default().netbox.enabled());
+        let settings = Loader {
+            file_text: Some(
+                r#"
+                [runtime.netbox]
+                url = "https://netbox.example.com"
+
+                [runtime.netbox.fields]
+                site_name = "facility"
+                "#
+                .to_string(),
+            ),
+            ..Loader::new()
+        }
+        .env([("RUBIGO_NETBOX__RESYNC", "false")])
+        .load()
+        .unwrap();
+        assert!(settings.netbox.enabled());
+        assert!(!settings.netbox.resync);
+        assert_eq!(settings.netbox.page_size, 100);
+        assert_eq!(settings.netbox.fields["site_name"], "facility");
+    }
+
+    #[test]
+    fn conflict_policies() {
+        assert!(ConflictPolicy::LastWriteWins.client_wins(20, 10));
+        assert!(!ConflictPolicy::LastWriteWins.client_wins(10, 10));
+        assert!(!ConflictPolicy::ServerWins.client_wins(20, 10));
+        assert!(ConflictPolicy::ClientWins.client_wins(10, 20));
+    }
+}
@@ -14,12 +14,53 @@ pub struct Database {
 }
 
 impl Database {
-    /// Initialize an in-memory SurrealDB instance
+    /// Initialize an in-memory SurrealDB instance with the default names
     pub async fn init() -> Result<Self> {
+        Self::init_with_settings(&config::DatabaseSettings::default()).await
+    }
+
+    /// Initialize an in-memory SurrealDB instance using the configured
+    /// namespace and database
+    pub async fn init_with_settings(settings: &config::DatabaseSettings) -> Result<Self> {
         let client = Surreal::new::<Mem>(()).await?;
-        
+        Self::prepare(&client, settings).await?;
+
+        tracing::info!(
+            "Database initialized (in-memory, schemaless): {}/{}",
+            settings.namespace,
+            settings.database
+        );
+
+        Ok(Self { client })
+    }
+
+    /// Open (or create) a database stored in the directory at `path`
+    ///
+    /// Data outlives the process, which the Tauri shell needs to work offline.
+    #[cfg(feature = "local-file")]
+    pub async fn open(
+        path: &std::path::Path,
+        settings: &config::DatabaseSettings,
+    ) -> Result<Self> {
+        use surrealdb::engine::local::SurrealKv;
+
+        let client = Surreal::new::<SurrealKv>(path.to_path_buf()).await?;
+        Self::prepare(&client, settings).await?;
+
+        tracing::info!(
+            "Database opened at {}: {}/{}",
+            path.display(),
+            settings.namespace,
+            settings.database
+        );
+
+        Ok(Self { client })
+    }
+
+    /// Select the namespace and database and define the tables
+    async fn prepare(client: &DbClient, settings: &config::DatabaseSettings) -> Result<()> {
         // Select namespace and database
-        client.use_ns("nexosim").use_db("main").await?;
+        client.use_ns(&settings.namespace).use_db(&settings.database).await?;
         
         // Define tables as schemaless for flexibility
         client.query("DEFINE TABLE person SCHEMALESS;").await?;
@@ -28,12 +69,20 @@ impl Database {
         client.query("DEFINE TABLE floor SCHEMALESS;").await?;
         client.query("DEFINE TABLE space SCHEMALESS;").await?;
         client.query("DEFINE TABLE asset SCHEMALESS;").await?;
+        client.query("DEFINE TABLE asset_transition SCHEMALESS;").await?;
+        client.query("DEFINE TABLE deleted_asset SCHEMALESS;").await?;
+        client.query("DEFINE TABLE maintenance_ticket SCHEMALESS;").await?;
         client.query("DEFINE TABLE calendar_event SCHEMALESS;").await?;
         client.query("DEFINE TABLE component SCHEMALESS;").await?;
-        
-        tracing::info!("Database initialized (in-memory, schemaless)");
-        
-        Ok(Self { client })
+        client.query("DEFINE TABLE dashboard_layout SCHEMALESS;").await?;
+        client.query("DEFINE TABLE activity SCHEMALESS;").await?;
+        client.query("DEFINE TABLE tag SCHEMALESS;").await?;
+        client.query("DEFINE TABLE tag_link SCHEMALESS;").await?;
+        client.query("DEFINE TABLE custom_field SCHEMALESS;").await?;
+        client.query("DEFINE TABLE custom_field_values SCHEMALESS;").await?;
+        client.query("DEFINE TABLE change_request SCHEMALESS;").await?;
+
+        Ok(())
     }
     
     /// Initialize with a specific namespace and database name
@@ -4,10 +4,13 @@
 //!
 //! # Architecture
 //!
-//! - **client**: Database connection and initialization
+//! - **client**: Database connection and initialization; names come from
+//!   `config::DatabaseSettings` via `Database::init_with_settings`
 //! - **models**: Data structures for entities (Person, Site, Asset, etc.)
 //! - **repositories**: CRUD operations for each entity type
 //! - **seed**: Populate database from scenario-loader data
+//! - **sync**: Change journal and push/pull sync between a local replica
+//!   (the Tauri shell) and gui-server
 //!
 //! # Usage
 //!
@@ -31,5 +34,6 @@ pub mod client;
 pub mod models;
 pub mod repositories;
 pub mod seed;
+pub mod sync;
 
 pub use client::Database;
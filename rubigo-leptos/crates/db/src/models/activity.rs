@@ -0,0 +1,55 @@
+//! Activity models
+//!
+//! Comments and audit entries recorded against any entity, and the parts of
+//! simulation runs the activity feed refers to.
+
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Comment or audit entry about one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityRecord {
+    pub id: Option<Thing>,
+    /// Entity key, e.g. `asset:7` or `person:42`
+    pub entity: String,
+    /// "comment" or "audit"
+    pub kind: String,
+    /// Who commented or made the change
+    pub actor: String,
+    /// Comment text or a description of the change
+    pub body: String,
+    /// ISO 8601 timestamp
+    pub at: String,
+}
+
+impl ActivityRecord {
+    pub fn comment(entity: &str, actor: String, body: String, at: String) -> Self {
+        Self {
+            id: None,
+            entity: entity.to_string(),
+            kind: "comment".to_string(),
+            actor,
+            body,
+            at,
+        }
+    }
+
+    pub fn audit(entity: &str, actor: String, body: String, at: String) -> Self {
+        Self {
+            id: None,
+            entity: entity.to_string(),
+            kind: "audit".to_string(),
+            actor,
+            body,
+            at,
+        }
+    }
+}
+
+/// A simulation run whose log mentions an entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationMention {
+    pub id: Option<Thing>,
+    pub started_at: String,
+    pub status: String,
+}
@@ -1,5 +1,7 @@
 //! Asset models - Network equipment, servers, storage
 
+pub use actions::types::LifecycleState;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 
@@ -74,4 +76,83 @@ pub struct NetworkAsset {
     pub storage_location: Option<String>,
     #[serde(default)]
     pub notes: Option<String>,
+    /// Lifecycle state (see [`LifecycleState`]); unset means deployed
+    #[serde(default)]
+    pub lifecycle: Option<String>,
+    #[serde(default)]
+    pub warranty_start: Option<String>,
+    #[serde(default)]
+    pub warranty_end: Option<String>,
+}
+
+impl NetworkAsset {
+    pub fn lifecycle_state(&self) -> LifecycleState {
+        self.lifecycle
+            .as_deref()
+            .and_then(LifecycleState::parse)
+            .unwrap_or_default()
+    }
+}
+
+/// Recorded lifecycle state change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetTransition {
+    pub id: Option<Thing>,
+    pub asset_id: Thing,
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// ISO 8601 timestamp
+    pub at: String,
+}
+
+/// Asset removed from inventory but still restorable
+///
+/// Deleting an asset moves it here; it is purged for good once the restore
+/// window has passed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedAsset {
+    pub id: Option<Thing>,
+    pub asset: NetworkAsset,
+    /// RFC 3339 timestamp
+    pub deleted_at: String,
+}
+
+impl DeletedAsset {
+    /// When the asset was deleted; `None` if `deleted_at` doesn't parse
+    pub fn deleted_time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.deleted_at)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+/// Scheduled maintenance work on an asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceTicket {
+    pub id: Option<Thing>,
+    pub asset_id: Thing,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// YYYY-MM-DD
+    pub scheduled_date: String,
+    /// "open", "in_progress" or "closed"
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl MaintenanceTicket {
+    /// New open ticket for the asset with record key `asset_id`
+    pub fn open(asset_id: &str, title: String, scheduled_date: String, description: Option<String>) -> Self {
+        Self {
+            id: None,
+            asset_id: Thing::from(("asset", asset_id)),
+            title,
+            description,
+            scheduled_date,
+            status: Some("open".to_string()),
+        }
+    }
 }
@@ -0,0 +1,30 @@
+//! Change request models
+
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// A change to protected records, held until it is reviewed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeRequest {
+    pub id: Option<Thing>,
+    /// Action type of the held change, e.g. `asset.update`
+    pub action_type: String,
+    /// The held action as JSON text, replayed when approved
+    pub payload: String,
+    /// Entity keys of the protected records it changes
+    pub entities: Vec<String>,
+    #[serde(default)]
+    pub requested_by: Option<String>,
+    pub requested_at: String,
+    /// "pending", "approved", "rejected" or "failed"
+    pub status: String,
+    #[serde(default)]
+    pub reviewed_by: Option<String>,
+    #[serde(default)]
+    pub review_note: Option<String>,
+    #[serde(default)]
+    pub reviewed_at: Option<String>,
+    /// Why the approved change could not be applied
+    #[serde(default)]
+    pub error: Option<String>,
+}
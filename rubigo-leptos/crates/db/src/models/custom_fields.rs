@@ -0,0 +1,39 @@
+//! Custom field models
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// An admin-defined field on one kind of record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomField {
+    pub id: Option<Thing>,
+    /// Kind of record the field is on ("asset", "person", "site" or "event")
+    pub entity_kind: String,
+    /// Key the values are stored under
+    pub key: String,
+    pub label: String,
+    /// "text", "number", "date" or "select"
+    pub kind: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub position: u32,
+}
+
+/// Custom field values of one record
+///
+/// Stored as one object per record, so defining a field needs no migration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomFieldValues {
+    /// `custom_field_values:⟨entity key⟩`
+    pub id: Option<Thing>,
+    /// Entity key of the record, e.g. `asset:7`
+    pub entity: String,
+    /// Values by field key
+    #[serde(default)]
+    pub values: BTreeMap<String, String>,
+}
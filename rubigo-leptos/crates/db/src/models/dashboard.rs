@@ -0,0 +1,21 @@
+//! Dashboard layout model
+
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// A widget placed on a user's dashboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DashboardWidget {
+    pub id: String,
+    pub kind: String,
+    #[serde(default)]
+    pub metric: Option<String>,
+}
+
+/// A user's dashboard, keyed by the user's ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardLayout {
+    pub id: Option<Thing>,
+    /// Widgets in display order
+    pub widgets: Vec<DashboardWidget>,
+}
@@ -2,10 +2,22 @@
 //!
 //! Data structures for entities stored in the database.
 
+pub mod activity;
 pub mod assets;
+pub mod change_requests;
+pub mod custom_fields;
+pub mod dashboard;
 pub mod geo;
 pub mod person;
+pub mod tags;
+pub mod training;
 
+pub use activity::*;
 pub use assets::*;
+pub use change_requests::*;
+pub use custom_fields::*;
+pub use dashboard::*;
 pub use geo::*;
 pub use person::*;
+pub use tags::*;
+pub use training::*;
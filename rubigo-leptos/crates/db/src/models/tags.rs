@@ -0,0 +1,24 @@
+//! Tag models
+
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// A label that can be put on records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: Option<Thing>,
+    pub name: String,
+    /// `#rrggbb`
+    pub color: String,
+    /// Kind of record the tag is for ("asset", "person", "site", "run"), or "any"
+    pub scope: String,
+}
+
+/// A tag put on one record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagLink {
+    pub id: Option<Thing>,
+    pub tag_id: Thing,
+    /// Entity key of the record, e.g. `asset:7`
+    pub entity: String,
+}
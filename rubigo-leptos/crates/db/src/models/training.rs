@@ -0,0 +1,29 @@
+//! Training session models
+
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// A trainee working through one of the scenario's exercises
+///
+/// The exercise itself lives in the scenario; per-task progress is kept
+/// by position in its task list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingSession {
+    pub id: Option<Thing>,
+    /// Exercise name
+    pub exercise: String,
+    /// Persona doing the exercise
+    pub trainee: String,
+    pub started_at: String,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    /// When each task was done, by position
+    #[serde(default)]
+    pub completed_at: Vec<Option<String>>,
+    /// Hints taken for each task, by position
+    #[serde(default)]
+    pub hints_used: Vec<u32>,
+    /// Actions that didn't complete the task at hand
+    #[serde(default)]
+    pub extra_actions: u32,
+}
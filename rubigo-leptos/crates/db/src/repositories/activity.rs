@@ -0,0 +1,104 @@
+//! Activity repository
+
+use crate::client::DbClient;
+use crate::models::{ActivityRecord, SimulationMention};
+use anyhow::Result;
+
+pub struct ActivityRepository;
+
+impl ActivityRepository {
+    /// Record a comment or audit entry
+    pub async fn record(db: &DbClient, record: ActivityRecord) -> Result<ActivityRecord> {
+        let created: Option<ActivityRecord> = db.create("activity").content(record).await?;
+        created.ok_or_else(|| anyhow::anyhow!("Failed to record activity"))
+    }
+
+    /// Comments and audit entries about an entity, oldest first
+    pub async fn for_entity(db: &DbClient, entity: &str) -> Result<Vec<ActivityRecord>> {
+        let records: Vec<ActivityRecord> = db
+            .query("SELECT * FROM activity WHERE entity = $entity ORDER BY at ASC")
+            .bind(("entity", entity.to_string()))
+            .await?
+            .take(0)?;
+        Ok(records)
+    }
+
+    /// Simulation runs whose log mentions `needle`, newest first
+    pub async fn simulation_mentions(
+        db: &DbClient,
+        needle: &str,
+    ) -> Result<Vec<SimulationMention>> {
+        let runs: Vec<SimulationMention> = db
+            .query(
+                "SELECT id, started_at, status FROM run \
+                 WHERE string::contains(array::join(logs, '\\n'), $needle) \
+                 ORDER BY started_at DESC",
+            )
+            .bind(("needle", needle.to_string()))
+            .await?
+            .take(0)?;
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn records_are_kept_per_entity() {
+        let db = Database::init().await.unwrap();
+        for (entity, body, at) in [
+            ("asset:sw1", "Second", "2025-01-02T00:00:00Z"),
+            ("asset:sw1", "First", "2025-01-01T00:00:00Z"),
+            ("asset:sw2", "Elsewhere", "2025-01-01T00:00:00Z"),
+        ] {
+            let record = ActivityRecord::comment(
+                entity,
+                "Ada".to_string(),
+                body.to_string(),
+                at.to_string(),
+            );
+            ActivityRepository::record(&db.client, record)
+                .await
+                .unwrap();
+        }
+
+        let records = ActivityRepository::for_entity(&db.client, "asset:sw1")
+            .await
+            .unwrap();
+        let bodies: Vec<&str> = records.iter().map(|r| r.body.as_str()).collect();
+        assert_eq!(bodies, vec!["First", "Second"]);
+    }
+
+    #[tokio::test]
+    async fn simulation_runs_are_found_by_mention() {
+        let db = Database::init().await.unwrap();
+        db.client
+            .query("CREATE run CONTENT $run")
+            .bind((
+                "run",
+                json!({
+                    "started_at": "2025-01-01 00:00:00 UTC",
+                    "status": "completed",
+                    "logs": ["Simulation initialized", "Router core-1 forwarded 12 packets"],
+                }),
+            ))
+            .await
+            .unwrap();
+
+        let runs = ActivityRepository::simulation_mentions(&db.client, "core-1")
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, "completed");
+        assert!(
+            ActivityRepository::simulation_mentions(&db.client, "edge-9")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! Asset repository
 
 use crate::client::DbClient;
-use crate::models::NetworkAsset;
+use crate::models::{AssetTransition, DeletedAsset, LifecycleState, MaintenanceTicket, NetworkAsset};
+use chrono::{DateTime, Utc};
+use surrealdb::sql::Thing;
 use anyhow::Result;
 
 pub struct AssetRepository;
@@ -54,4 +56,296 @@ impl AssetRepository {
             .take(0)?;
         Ok(assets)
     }
+
+    /// Move an asset to a new lifecycle state, recording the transition
+    pub async fn transition(
+        db: &DbClient,
+        id: &str,
+        to: LifecycleState,
+        note: Option<String>,
+        at: &str,
+    ) -> Result<NetworkAsset> {
+        let mut asset = Self::get_by_id(db, id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Asset not found: {}", id))?;
+        let from = asset.lifecycle_state();
+        if !from.can_transition_to(to) {
+            anyhow::bail!("Cannot move asset from {} to {}", from, to);
+        }
+
+        asset.id = None;
+        asset.lifecycle = Some(to.to_string());
+        let updated: Option<NetworkAsset> = db.update(("asset", id)).content(asset).await?;
+        let updated = updated.ok_or_else(|| anyhow::anyhow!("Failed to update asset"))?;
+
+        let _: Option<AssetTransition> = db
+            .create("asset_transition")
+            .content(AssetTransition {
+                id: None,
+                asset_id: Thing::from(("asset", id)),
+                from: from.to_string(),
+                to: to.to_string(),
+                note,
+                at: at.to_string(),
+            })
+            .await?;
+        Ok(updated)
+    }
+
+    /// Lifecycle history of an asset, oldest first
+    pub async fn history(db: &DbClient, id: &str) -> Result<Vec<AssetTransition>> {
+        let transitions: Vec<AssetTransition> = db
+            .query(
+                "SELECT * FROM asset_transition \
+                 WHERE asset_id = type::thing('asset', $id) ORDER BY at ASC",
+            )
+            .bind(("id", id.to_string()))
+            .await?
+            .take(0)?;
+        Ok(transitions)
+    }
+
+    /// Most recent lifecycle changes across all assets, newest first
+    pub async fn recent_transitions(db: &DbClient, limit: usize) -> Result<Vec<AssetTransition>> {
+        let transitions: Vec<AssetTransition> = db
+            .query("SELECT * FROM asset_transition ORDER BY at DESC LIMIT $limit")
+            .bind(("limit", limit))
+            .await?
+            .take(0)?;
+        Ok(transitions)
+    }
+
+    /// Put an asset in another rack position, or take it out of its rack
+    ///
+    /// Returns `None` if there is no such asset.
+    pub async fn relocate(
+        db: &DbClient,
+        id: &str,
+        rack_id: Option<&str>,
+        position_u: Option<i32>,
+    ) -> Result<Option<NetworkAsset>> {
+        let Some(mut asset) = Self::get_by_id(db, id).await? else {
+            return Ok(None);
+        };
+        asset.id = None;
+        asset.rack_id = rack_id.map(|rack| Thing::from(("rack", rack)));
+        asset.position_u = position_u;
+        let updated: Option<NetworkAsset> = db.update(("asset", id)).content(asset).await?;
+        Ok(updated)
+    }
+
+    /// Remove an asset from inventory, keeping it restorable
+    ///
+    /// Returns `false` if there is no such asset.
+    pub async fn soft_delete(db: &DbClient, id: &str, at: DateTime<Utc>) -> Result<bool> {
+        let Some(mut asset) = Self::get_by_id(db, id).await? else {
+            return Ok(false);
+        };
+        asset.id = None;
+        let _: Option<DeletedAsset> = db
+            .upsert(("deleted_asset", id))
+            .content(DeletedAsset {
+                id: None,
+                asset,
+                deleted_at: at.to_rfc3339(),
+            })
+            .await?;
+        let _: Option<NetworkAsset> = db.delete(("asset", id)).await?;
+        Ok(true)
+    }
+
+    /// Put a soft-deleted asset back, unless it was deleted before `not_before`
+    ///
+    /// Returns `None` if the asset is unknown or its restore window has passed.
+    pub async fn restore(
+        db: &DbClient,
+        id: &str,
+        not_before: DateTime<Utc>,
+    ) -> Result<Option<NetworkAsset>> {
+        let deleted: Option<DeletedAsset> = db.select(("deleted_asset", id)).await?;
+        let Some(deleted) = deleted.filter(|d| d.deleted_time().is_some_and(|t| t >= not_before))
+        else {
+            return Ok(None);
+        };
+        let restored = Self::create_with_id(db, id, deleted.asset).await?;
+        let _: Option<DeletedAsset> = db.delete(("deleted_asset", id)).await?;
+        Ok(Some(restored))
+    }
+
+    /// Permanently remove assets soft-deleted before `before`
+    ///
+    /// Records whose deletion time doesn't parse are kept, since there is no
+    /// telling whether their window has passed.
+    pub async fn purge_deleted(db: &DbClient, before: DateTime<Utc>) -> Result<usize> {
+        let deleted: Vec<DeletedAsset> = db.select("deleted_asset").await?;
+        let mut purged = 0;
+        for record in deleted {
+            let Some(at) = record.deleted_time() else {
+                tracing::warn!(
+                    "Keeping deleted asset {:?} with unreadable time {:?}",
+                    record.id,
+                    record.deleted_at
+                );
+                continue;
+            };
+            if let Some(id) = record.id.filter(|_| at < before) {
+                let _: Option<DeletedAsset> = db.delete(("deleted_asset", id.id.to_raw())).await?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Schedule maintenance on an asset
+    pub async fn create_ticket(db: &DbClient, ticket: MaintenanceTicket) -> Result<MaintenanceTicket> {
+        let created: Option<MaintenanceTicket> =
+            db.create("maintenance_ticket").content(ticket).await?;
+        created.ok_or_else(|| anyhow::anyhow!("Failed to create maintenance ticket"))
+    }
+
+    /// Tickets not yet closed that are due on or before `until` (YYYY-MM-DD)
+    pub async fn upcoming_maintenance(db: &DbClient, until: &str) -> Result<Vec<MaintenanceTicket>> {
+        let tickets: Vec<MaintenanceTicket> = db
+            .query(
+                "SELECT * FROM maintenance_ticket \
+                 WHERE scheduled_date <= $until AND status != 'closed' \
+                 ORDER BY scheduled_date ASC",
+            )
+            .bind(("until", until.to_string()))
+            .await?
+            .take(0)?;
+        Ok(tickets)
+    }
+
+    /// Every maintenance ticket of an asset, earliest first
+    pub async fn tickets_for(db: &DbClient, id: &str) -> Result<Vec<MaintenanceTicket>> {
+        let tickets: Vec<MaintenanceTicket> = db
+            .query(
+                "SELECT * FROM maintenance_ticket \
+                 WHERE asset_id = type::thing('asset', $id) ORDER BY scheduled_date ASC",
+            )
+            .bind(("id", id.to_string()))
+            .await?
+            .take(0)?;
+        Ok(tickets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    fn asset(name: &str) -> NetworkAsset {
+        NetworkAsset {
+            id: None,
+            name: name.to_string(),
+            category: Some("Network".to_string()),
+            manufacturer: None,
+            model: None,
+            serial_number: None,
+            mac_address: None,
+            status: None,
+            rack_id: None,
+            position_u: None,
+            height_u: None,
+            space_id: None,
+            storage_location: None,
+            notes: None,
+            lifecycle: None,
+            warranty_start: None,
+            warranty_end: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn lifecycle_transitions_are_validated_and_recorded() {
+        let db = Database::init().await.unwrap();
+        AssetRepository::create_with_id(&db.client, "sw1", asset("SW1")).await.unwrap();
+
+        let updated = AssetRepository::transition(
+            &db.client,
+            "sw1",
+            LifecycleState::Maintenance,
+            None,
+            "2025-01-01T00:00:00Z",
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.lifecycle_state(), LifecycleState::Maintenance);
+
+        // Maintenance -> Ordered is not a valid move
+        let invalid = AssetRepository::transition(
+            &db.client,
+            "sw1",
+            LifecycleState::Ordered,
+            None,
+            "2025-01-02T00:00:00Z",
+        )
+        .await;
+        assert!(invalid.is_err());
+
+        let history = AssetRepository::history(&db.client, "sw1").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].to, "maintenance");
+    }
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn soft_deleted_assets_restore_within_window() {
+        let db = Database::init().await.unwrap();
+        AssetRepository::create_with_id(&db.client, "sw1", asset("SW1")).await.unwrap();
+        AssetRepository::create_with_id(&db.client, "sw2", asset("SW2")).await.unwrap();
+
+        assert!(AssetRepository::soft_delete(&db.client, "sw1", at("2025-01-01T00:00:00Z")).await.unwrap());
+        assert!(AssetRepository::soft_delete(&db.client, "sw2", at("2025-01-01T00:05:00Z")).await.unwrap());
+        assert!(!AssetRepository::soft_delete(&db.client, "missing", at("2025-01-01T00:05:00Z")).await.unwrap());
+        assert!(AssetRepository::list_all(&db.client).await.unwrap().is_empty());
+
+        // sw1 is outside the window, sw2 comes back intact; an offset time
+        // compares by instant, not by its text
+        let cutoff = at("2025-01-01T01:02:00+01:00");
+        assert!(AssetRepository::restore(&db.client, "sw1", cutoff).await.unwrap().is_none());
+        let restored = AssetRepository::restore(&db.client, "sw2", cutoff).await.unwrap();
+        assert_eq!(restored.map(|a| a.name), Some("SW2".to_string()));
+
+        assert_eq!(AssetRepository::purge_deleted(&db.client, cutoff).await.unwrap(), 1);
+        assert_eq!(AssetRepository::list_all(&db.client).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unreadable_deletion_times_are_never_purged() {
+        let db = Database::init().await.unwrap();
+        let _: Option<DeletedAsset> = db
+            .client
+            .upsert(("deleted_asset", "sw1"))
+            .content(DeletedAsset {
+                id: None,
+                asset: asset("SW1"),
+                deleted_at: String::new(),
+            })
+            .await
+            .unwrap();
+
+        let now = at("2025-01-01T00:00:00Z");
+        assert_eq!(AssetRepository::purge_deleted(&db.client, now).await.unwrap(), 0);
+        assert!(AssetRepository::restore(&db.client, "sw1", now).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn assets_move_between_racks() {
+        let db = Database::init().await.unwrap();
+        AssetRepository::create_with_id(&db.client, "sw1", asset("SW1")).await.unwrap();
+
+        let moved = AssetRepository::relocate(&db.client, "sw1", Some("r2"), Some(12))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(moved.rack_id.map(|r| r.id.to_raw()), Some("r2".to_string()));
+        assert_eq!(moved.position_u, Some(12));
+        assert!(AssetRepository::relocate(&db.client, "missing", None, None).await.unwrap().is_none());
+    }
 }
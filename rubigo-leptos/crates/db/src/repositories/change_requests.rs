@@ -0,0 +1,108 @@
+//! Change request repository
+
+use crate::client::DbClient;
+use crate::models::ChangeRequest;
+use anyhow::Result;
+
+pub struct ChangeRequestRepository;
+
+impl ChangeRequestRepository {
+    /// Requests with `status`, or all of them for `None`, newest first
+    pub async fn list(db: &DbClient, status: Option<&str>) -> Result<Vec<ChangeRequest>> {
+        let requests: Vec<ChangeRequest> = match status {
+            Some(status) => db
+                .query("SELECT * FROM change_request WHERE status = $status ORDER BY requested_at DESC")
+                .bind(("status", status.to_string()))
+                .await?
+                .take(0)?,
+            None => db
+                .query("SELECT * FROM change_request ORDER BY requested_at DESC")
+                .await?
+                .take(0)?,
+        };
+        Ok(requests)
+    }
+
+    pub async fn get_by_id(db: &DbClient, id: &str) -> Result<Option<ChangeRequest>> {
+        let request: Option<ChangeRequest> = db.select(("change_request", id)).await?;
+        Ok(request)
+    }
+
+    pub async fn create(db: &DbClient, request: ChangeRequest) -> Result<ChangeRequest> {
+        let created: Option<ChangeRequest> = db.create("change_request").content(request).await?;
+        created.ok_or_else(|| anyhow::anyhow!("Failed to create change request"))
+    }
+
+    /// Save a reviewed request
+    pub async fn update(
+        db: &DbClient,
+        id: &str,
+        request: ChangeRequest,
+    ) -> Result<Option<ChangeRequest>> {
+        let updated: Option<ChangeRequest> =
+            db.update(("change_request", id)).content(request).await?;
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    fn request(entity: &str, requested_at: &str) -> ChangeRequest {
+        ChangeRequest {
+            id: None,
+            action_type: "asset.delete".to_string(),
+            payload: format!(r#"{{"Delete":"{}"}}"#, entity),
+            entities: vec![format!("asset:{}", entity)],
+            requested_by: None,
+            requested_at: requested_at.to_string(),
+            status: "pending".to_string(),
+            reviewed_by: None,
+            review_note: None,
+            reviewed_at: None,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_are_listed_newest_first_by_status() {
+        let db = Database::init().await.unwrap();
+        ChangeRequestRepository::create(&db.client, request("sw1", "2026-03-01T09:00:00Z"))
+            .await
+            .unwrap();
+        let later =
+            ChangeRequestRepository::create(&db.client, request("sw2", "2026-03-02T09:00:00Z"))
+                .await
+                .unwrap();
+
+        let pending = ChangeRequestRepository::list(&db.client, Some("pending"))
+            .await
+            .unwrap();
+        let entities: Vec<&str> = pending.iter().map(|r| r.entities[0].as_str()).collect();
+        assert_eq!(entities, vec!["asset:sw2", "asset:sw1"]);
+
+        let id = later.id.clone().unwrap().id.to_raw();
+        let mut approved = later;
+        approved.id = None;
+        approved.status = "approved".to_string();
+        ChangeRequestRepository::update(&db.client, &id, approved)
+            .await
+            .unwrap();
+        assert_eq!(
+            ChangeRequestRepository::list(&db.client, Some("pending"))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            ChangeRequestRepository::list(&db.client, None)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Custom field repository
+
+use std::collections::BTreeMap;
+
+use crate::client::DbClient;
+use crate::models::{CustomField, CustomFieldValues};
+use anyhow::Result;
+
+pub struct CustomFieldRepository;
+
+impl CustomFieldRepository {
+    /// Fields defined for a kind of record, in form order
+    pub async fn definitions(db: &DbClient, entity_kind: &str) -> Result<Vec<CustomField>> {
+        let fields: Vec<CustomField> = db
+            .query("SELECT * FROM custom_field WHERE entity_kind = $kind ORDER BY position ASC, label ASC")
+            .bind(("kind", entity_kind.to_string()))
+            .await?
+            .take(0)?;
+        Ok(fields)
+    }
+
+    pub async fn get_by_id(db: &DbClient, id: &str) -> Result<Option<CustomField>> {
+        let field: Option<CustomField> = db.select(("custom_field", id)).await?;
+        Ok(field)
+    }
+
+    pub async fn create(db: &DbClient, field: CustomField) -> Result<CustomField> {
+        let created: Option<CustomField> = db.create("custom_field").content(field).await?;
+        created.ok_or_else(|| anyhow::anyhow!("Failed to create custom field"))
+    }
+
+    pub async fn update(
+        db: &DbClient,
+        id: &str,
+        field: CustomField,
+    ) -> Result<Option<CustomField>> {
+        let updated: Option<CustomField> = db.update(("custom_field", id)).content(field).await?;
+        Ok(updated)
+    }
+
+    /// Returns `false` if there is no such field
+    pub async fn delete(db: &DbClient, id: &str) -> Result<bool> {
+        let deleted: Option<CustomField> = db.delete(("custom_field", id)).await?;
+        Ok(deleted.is_some())
+    }
+
+    /// Stored values of a record by field key; empty if it has none
+    pub async fn values(db: &DbClient, entity: &str) -> Result<BTreeMap<String, String>> {
+        let stored: Option<CustomFieldValues> = db.select(("custom_field_values", entity)).await?;
+        Ok(stored.map(|s| s.values).unwrap_or_default())
+    }
+
+    /// Replace a record's stored values
+    pub async fn set_values(
+        db: &DbClient,
+        entity: &str,
+        values: BTreeMap<String, String>,
+    ) -> Result<()> {
+        let _: Option<CustomFieldValues> = db
+            .upsert(("custom_field_values", entity))
+            .content(CustomFieldValues {
+                id: None,
+                entity: entity.to_string(),
+                values,
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    fn field(label: &str, position: u32) -> CustomField {
+        CustomField {
+            id: None,
+            entity_kind: "asset".to_string(),
+            key: label.to_lowercase(),
+            label: label.to_string(),
+            kind: "text".to_string(),
+            options: Vec::new(),
+            required: false,
+            position,
+        }
+    }
+
+    #[tokio::test]
+    async fn definitions_are_listed_in_form_order() {
+        let db = Database::init().await.unwrap();
+        CustomFieldRepository::create(&db.client, field("Owner", 2))
+            .await
+            .unwrap();
+        CustomFieldRepository::create(&db.client, field("Budget", 1))
+            .await
+            .unwrap();
+        let mut person = field("Badge", 0);
+        person.entity_kind = "person".to_string();
+        CustomFieldRepository::create(&db.client, person)
+            .await
+            .unwrap();
+
+        let labels: Vec<String> = CustomFieldRepository::definitions(&db.client, "asset")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.label)
+            .collect();
+        assert_eq!(labels, vec!["Budget", "Owner"]);
+    }
+
+    #[tokio::test]
+    async fn values_are_kept_per_record() {
+        let db = Database::init().await.unwrap();
+        assert!(CustomFieldRepository::values(&db.client, "asset:sw1")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let values = BTreeMap::from([("owner".to_string(), "Network team".to_string())]);
+        CustomFieldRepository::set_values(&db.client, "asset:sw1", values.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            CustomFieldRepository::values(&db.client, "asset:sw1")
+                .await
+                .unwrap(),
+            values
+        );
+        assert!(CustomFieldRepository::values(&db.client, "asset:sw2")
+            .await
+            .unwrap()
+            .is_empty());
+
+        CustomFieldRepository::set_values(&db.client, "asset:sw1", BTreeMap::new())
+            .await
+            .unwrap();
+        assert!(CustomFieldRepository::values(&db.client, "asset:sw1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
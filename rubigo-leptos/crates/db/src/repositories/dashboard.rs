@@ -0,0 +1,74 @@
+//! Dashboard layout repository
+
+use crate::client::DbClient;
+use crate::models::{DashboardLayout, DashboardWidget};
+use anyhow::Result;
+
+pub struct DashboardRepository;
+
+impl DashboardRepository {
+    /// Saved layout for a user
+    pub async fn get_layout(db: &DbClient, user_id: &str) -> Result<Option<DashboardLayout>> {
+        let layout: Option<DashboardLayout> = db.select(("dashboard_layout", user_id)).await?;
+        Ok(layout)
+    }
+
+    /// Replace a user's layout
+    pub async fn save_layout(
+        db: &DbClient,
+        user_id: &str,
+        widgets: Vec<DashboardWidget>,
+    ) -> Result<DashboardLayout> {
+        let layout = DashboardLayout { id: None, widgets };
+        let saved: Option<DashboardLayout> = db
+            .upsert(("dashboard_layout", user_id))
+            .content(layout)
+            .await?;
+        saved.ok_or_else(|| anyhow::anyhow!("Failed to save dashboard for {}", user_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    fn widget(id: &str, kind: &str) -> DashboardWidget {
+        DashboardWidget {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            metric: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn layouts_are_saved_per_user() {
+        let db = Database::init().await.unwrap();
+        assert!(DashboardRepository::get_layout(&db.client, "abc123")
+            .await
+            .unwrap()
+            .is_none());
+
+        DashboardRepository::save_layout(&db.client, "abc123", vec![widget("a", "chart")])
+            .await
+            .unwrap();
+        DashboardRepository::save_layout(
+            &db.client,
+            "abc123",
+            vec![widget("b", "map_snapshot"), widget("a", "chart")],
+        )
+        .await
+        .unwrap();
+
+        let layout = DashboardRepository::get_layout(&db.client, "abc123")
+            .await
+            .unwrap()
+            .unwrap();
+        let ids: Vec<&str> = layout.widgets.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a"]);
+        assert!(DashboardRepository::get_layout(&db.client, "def456")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -2,10 +2,22 @@
 //!
 //! CRUD operations for each entity type.
 
+pub mod activity;
 pub mod assets;
+pub mod change_requests;
+pub mod custom_fields;
+pub mod dashboard;
 pub mod geo;
 pub mod person;
+pub mod tags;
+pub mod training;
 
+pub use activity::ActivityRepository;
 pub use assets::AssetRepository;
+pub use change_requests::ChangeRequestRepository;
+pub use custom_fields::CustomFieldRepository;
+pub use dashboard::DashboardRepository;
 pub use geo::GeoRepository;
 pub use person::PersonRepository;
+pub use tags::TagRepository;
+pub use training::TrainingRepository;
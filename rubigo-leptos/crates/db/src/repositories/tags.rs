@@ -0,0 +1,166 @@
+//! Tag repository
+
+use crate::client::DbClient;
+use crate::models::{Tag, TagLink};
+use anyhow::Result;
+use surrealdb::sql::Thing;
+
+pub struct TagRepository;
+
+impl TagRepository {
+    /// Tags usable on records of `scope`, or every tag for `None`, by name
+    pub async fn list(db: &DbClient, scope: Option<&str>) -> Result<Vec<Tag>> {
+        let tags: Vec<Tag> = match scope {
+            Some(scope) => db
+                .query("SELECT * FROM tag WHERE scope = $scope OR scope = 'any' ORDER BY name ASC")
+                .bind(("scope", scope.to_string()))
+                .await?
+                .take(0)?,
+            None => db
+                .query("SELECT * FROM tag ORDER BY name ASC")
+                .await?
+                .take(0)?,
+        };
+        Ok(tags)
+    }
+
+    pub async fn get_by_id(db: &DbClient, id: &str) -> Result<Option<Tag>> {
+        let tag: Option<Tag> = db.select(("tag", id)).await?;
+        Ok(tag)
+    }
+
+    pub async fn create(db: &DbClient, tag: Tag) -> Result<Tag> {
+        let created: Option<Tag> = db.create("tag").content(tag).await?;
+        created.ok_or_else(|| anyhow::anyhow!("Failed to create tag"))
+    }
+
+    /// Delete a tag and take it off every record
+    ///
+    /// Returns `false` if there is no such tag.
+    pub async fn delete(db: &DbClient, id: &str) -> Result<bool> {
+        db.query("DELETE tag_link WHERE tag_id = type::thing('tag', $id)")
+            .bind(("id", id.to_string()))
+            .await?;
+        let deleted: Option<Tag> = db.delete(("tag", id)).await?;
+        Ok(deleted.is_some())
+    }
+
+    /// Put a tag on records; records already carrying it are left as they are
+    pub async fn assign(db: &DbClient, tag_id: &str, entities: &[String]) -> Result<()> {
+        for entity in entities {
+            let _: Option<TagLink> = db
+                .upsert(("tag_link", format!("{tag_id}|{entity}")))
+                .content(TagLink {
+                    id: None,
+                    tag_id: Thing::from(("tag", tag_id)),
+                    entity: entity.clone(),
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Take a tag off records
+    pub async fn unassign(db: &DbClient, tag_id: &str, entities: &[String]) -> Result<()> {
+        db.query("DELETE tag_link WHERE tag_id = type::thing('tag', $id) AND entity IN $entities")
+            .bind(("id", tag_id.to_string()))
+            .bind(("entities", entities.to_vec()))
+            .await?;
+        Ok(())
+    }
+
+    /// Tags put on any of the records
+    pub async fn links_for(db: &DbClient, entities: &[String]) -> Result<Vec<TagLink>> {
+        let links: Vec<TagLink> = db
+            .query("SELECT * FROM tag_link WHERE entity IN $entities")
+            .bind(("entities", entities.to_vec()))
+            .await?
+            .take(0)?;
+        Ok(links)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    fn tag(name: &str, scope: &str) -> Tag {
+        Tag {
+            id: None,
+            name: name.to_string(),
+            color: "#4caf50".to_string(),
+            scope: scope.to_string(),
+        }
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn tags_are_listed_by_scope() {
+        let db = Database::init().await.unwrap();
+        TagRepository::create(&db.client, tag("Spare", "asset"))
+            .await
+            .unwrap();
+        TagRepository::create(&db.client, tag("Remote", "any"))
+            .await
+            .unwrap();
+        TagRepository::create(&db.client, tag("Lead", "person"))
+            .await
+            .unwrap();
+
+        let names = |tags: Vec<Tag>| tags.into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(
+                TagRepository::list(&db.client, Some("asset"))
+                    .await
+                    .unwrap()
+            ),
+            vec!["Remote", "Spare"]
+        );
+        assert_eq!(
+            TagRepository::list(&db.client, None).await.unwrap().len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn assignments_are_idempotent_and_removed_with_the_tag() {
+        let db = Database::init().await.unwrap();
+        let spare = TagRepository::create(&db.client, tag("Spare", "asset"))
+            .await
+            .unwrap();
+        let id = spare.id.unwrap().id.to_raw();
+
+        TagRepository::assign(&db.client, &id, &keys(&["asset:sw1", "asset:sw2"]))
+            .await
+            .unwrap();
+        TagRepository::assign(&db.client, &id, &keys(&["asset:sw1"]))
+            .await
+            .unwrap();
+        let all = keys(&["asset:sw1", "asset:sw2", "asset:sw3"]);
+        assert_eq!(
+            TagRepository::links_for(&db.client, &all)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        TagRepository::unassign(&db.client, &id, &keys(&["asset:sw2"]))
+            .await
+            .unwrap();
+        let links = TagRepository::links_for(&db.client, &all).await.unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].entity, "asset:sw1");
+
+        assert!(TagRepository::delete(&db.client, &id).await.unwrap());
+        assert!(TagRepository::links_for(&db.client, &all)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(!TagRepository::delete(&db.client, &id).await.unwrap());
+    }
+}
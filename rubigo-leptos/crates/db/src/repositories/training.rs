@@ -0,0 +1,100 @@
+//! Training session repository
+
+use crate::client::DbClient;
+use crate::models::TrainingSession;
+use anyhow::Result;
+
+pub struct TrainingRepository;
+
+impl TrainingRepository {
+    pub async fn create(db: &DbClient, session: TrainingSession) -> Result<TrainingSession> {
+        let created: Option<TrainingSession> =
+            db.create("training_session").content(session).await?;
+        created.ok_or_else(|| anyhow::anyhow!("Failed to create training session"))
+    }
+
+    /// The most recently started session, finished or not
+    pub async fn latest(db: &DbClient) -> Result<Option<TrainingSession>> {
+        let sessions: Vec<TrainingSession> = db
+            .query("SELECT * FROM training_session ORDER BY started_at DESC LIMIT 1")
+            .await?
+            .take(0)?;
+        Ok(sessions.into_iter().next())
+    }
+
+    /// The session in progress, if any
+    pub async fn active(db: &DbClient) -> Result<Option<TrainingSession>> {
+        Ok(Self::latest(db).await?.filter(|s| s.finished_at.is_none()))
+    }
+
+    /// Save a session's progress
+    pub async fn update(
+        db: &DbClient,
+        id: &str,
+        session: TrainingSession,
+    ) -> Result<Option<TrainingSession>> {
+        let updated: Option<TrainingSession> =
+            db.update(("training_session", id)).content(session).await?;
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    fn session(exercise: &str, started_at: &str) -> TrainingSession {
+        TrainingSession {
+            id: None,
+            exercise: exercise.to_string(),
+            trainee: "Riley Engineer".to_string(),
+            started_at: started_at.to_string(),
+            finished_at: None,
+            completed_at: vec![None, None],
+            hints_used: vec![0, 0],
+            extra_actions: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn only_the_latest_unfinished_session_is_active() {
+        let db = Database::init().await.unwrap();
+        assert!(TrainingRepository::active(&db.client)
+            .await
+            .unwrap()
+            .is_none());
+        TrainingRepository::create(&db.client, session("First", "2026-03-01T09:00:00Z"))
+            .await
+            .unwrap();
+        let later =
+            TrainingRepository::create(&db.client, session("Second", "2026-03-02T09:00:00Z"))
+                .await
+                .unwrap();
+        let active = TrainingRepository::active(&db.client)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(active.exercise, "Second");
+
+        let id = later.id.clone().unwrap().id.to_raw();
+        let mut finished = later;
+        finished.id = None;
+        finished.finished_at = Some("2026-03-02T09:30:00Z".to_string());
+        TrainingRepository::update(&db.client, &id, finished)
+            .await
+            .unwrap();
+        assert!(TrainingRepository::active(&db.client)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            TrainingRepository::latest(&db.client)
+                .await
+                .unwrap()
+                .unwrap()
+                .exercise,
+            "Second"
+        );
+    }
+}
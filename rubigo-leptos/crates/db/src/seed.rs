@@ -175,6 +175,9 @@ pub async fn from_scenario(db: &DbClient, scenario: &Scenario) -> Result<SeedSta
             space_id,
             storage_location: asset.storage_location.clone(),
             notes: asset.notes.clone(),
+            lifecycle: None,
+            warranty_start: None,
+            warranty_end: None,
         };
         AssetRepository::create_with_id(db, &asset_id, db_asset).await?;
         stats.assets += 1;
@@ -0,0 +1,613 @@
+//! Change tracking and replica sync
+//!
+//! Every write to a [`SYNCED_TABLES`] record is journaled by a table event
+//! into `sync_change`, whoever made it, so nothing has to remember to record
+//! its changes. Both ends of a sync use the journal:
+//!
+//! - gui-server serves entries after a cursor ([`changes_since`]) and applies
+//!   what replicas push ([`receive`]), settling conflicts by policy
+//! - the Tauri shell's local database is a [`Replica`]: it pulls and applies
+//!   the server's changes, then pushes the entries it made itself
+//!
+//! An entry's `origin` says where the change came from: none for a write made
+//! on this database, the replica ID for one pushed by that replica, and
+//! `remote` on a replica for one pulled from the server. Record content
+//! travels as SurrealDB serializes its values, so record links and datetimes
+//! survive the trip.
+//!
+//! Changes carry the server's table names. A replica keeps some tables under
+//! its own name ([`REPLICA_TABLES`]), so it renames them, and links to their
+//! records, on the way in and out.
+
+use actions::sync::{latest_per_record, PullResponse, PushRequest, PushResponse, RecordChange};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use config::ConflictPolicy;
+use serde::Deserialize;
+use surrealdb::sql;
+
+use crate::client::DbClient;
+
+/// Tables whose records are kept in step between server and replicas
+pub const SYNCED_TABLES: [&str; 9] = [
+    "person",
+    "site",
+    "building",
+    "floor",
+    "space",
+    "network_asset",
+    "meeting",
+    "maintenance_ticket",
+    "desk_booking",
+];
+
+/// Synced tables a replica's repositories keep under another name, as
+/// (server, replica)
+pub const REPLICA_TABLES: [(&str, &str); 1] = [("network_asset", "asset")];
+
+const JOURNAL: &str = "sync_change";
+
+/// Origin of journal entries a replica pulled from the server
+const REMOTE: &str = "remote";
+
+/// Origin a replica gives its own entries once the server has them
+const PUSHED: &str = "pushed";
+
+/// How a database names the synced tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Naming {
+    /// As [`SYNCED_TABLES`] does
+    Server,
+    /// With [`REPLICA_TABLES`] renamed
+    Replica,
+}
+
+impl Naming {
+    /// This database's name for a synced table
+    fn local(self, table: &str) -> &str {
+        match self {
+            Naming::Server => table,
+            Naming::Replica => REPLICA_TABLES
+                .iter()
+                .find(|(server, _)| *server == table)
+                .map_or(table, |(_, replica)| replica),
+        }
+    }
+
+    /// Point links in `value` from the synced tables to this database's names
+    fn to_local(self, value: &mut sql::Value) {
+        if self == Naming::Replica {
+            for (server, replica) in REPLICA_TABLES {
+                relink(value, server, replica);
+            }
+        }
+    }
+
+    /// Point links in `value` from this database's names to the synced tables
+    fn to_synced(self, value: &mut sql::Value) {
+        if self == Naming::Replica {
+            for (server, replica) in REPLICA_TABLES {
+                relink(value, replica, server);
+            }
+        }
+    }
+}
+
+/// Move every record link in `value` from table `from` to `to`
+fn relink(value: &mut sql::Value, from: &str, to: &str) {
+    match value {
+        sql::Value::Thing(thing) if thing.tb == from => thing.tb = to.to_string(),
+        sql::Value::Array(items) => items.iter_mut().for_each(|v| relink(v, from, to)),
+        sql::Value::Object(fields) => fields.values_mut().for_each(|v| relink(v, from, to)),
+        _ => {}
+    }
+}
+
+/// Start journaling writes to the synced tables; safe to call again
+pub async fn track(db: &DbClient) -> Result<()> {
+    journal(db, Naming::Server).await
+}
+
+/// Journal writes to the synced tables under their synced names
+async fn journal(db: &DbClient, naming: Naming) -> Result<()> {
+    db.query(format!(
+        "DEFINE TABLE IF NOT EXISTS {JOURNAL} SCHEMALESS;
+         DEFINE INDEX IF NOT EXISTS {JOURNAL}_at ON {JOURNAL} FIELDS at;"
+    ))
+    .await?
+    .check()?;
+    for table in SYNCED_TABLES {
+        let local = naming.local(table);
+        db.query(format!(
+            "DEFINE EVENT OVERWRITE journal ON TABLE {local} THEN (
+                CREATE {JOURNAL} CONTENT {{
+                    table: '{table}',
+                    record: <string> record::id($value.id),
+                    data: IF $event = 'DELETE' THEN NONE ELSE $after END,
+                    at: time::nano(time::now()),
+                    origin: NONE
+                }}
+            );"
+        ))
+        .await?
+        .check()
+        .with_context(|| format!("Journaling {}", local))?;
+    }
+    Ok(())
+}
+
+fn check_table(table: &str) -> Result<()> {
+    if !SYNCED_TABLES.contains(&table) {
+        bail!("Table '{}' is not synced", table);
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    table: String,
+    record: String,
+    at: i64,
+}
+
+/// Journal entries matching `condition`, oldest first
+async fn entries(
+    db: &DbClient,
+    naming: Naming,
+    condition: &str,
+    binds: Vec<(&'static str, sql::Value)>,
+) -> Result<Vec<RecordChange>> {
+    // Content is read separately to keep SurrealDB's own encoding; both reads
+    // stop at the same instant so they see the same entries
+    let until = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(i64::MAX, |d| d.as_nanos() as i64);
+    let condition = format!("({condition}) AND at <= $until");
+    let mut request = db
+        .query(format!(
+            "SELECT table, record, at FROM {JOURNAL} WHERE {condition} ORDER BY at;"
+        ))
+        .query(format!(
+            "SELECT VALUE data FROM (SELECT data, at FROM {JOURNAL} WHERE {condition} ORDER BY at);"
+        ))
+        .bind(("until", until));
+    for (name, value) in binds {
+        request = request.bind((name, value));
+    }
+    let mut response = request.await?.check()?;
+    let meta: Vec<Entry> = response.take(0)?;
+    let data: surrealdb::Value = response.take(1)?;
+    let sql::Value::Array(data) = data.into_inner() else {
+        bail!("Journal data is not a list");
+    };
+    if data.len() != meta.len() {
+        bail!("Journal changed while it was read");
+    }
+    meta.into_iter()
+        .zip(data)
+        .map(|(entry, data)| {
+            let data = match data {
+                sql::Value::None | sql::Value::Null => None,
+                mut value => {
+                    naming.to_synced(&mut value);
+                    Some(serde_json::to_value(value)?)
+                }
+            };
+            Ok(RecordChange {
+                table: entry.table,
+                id: entry.record,
+                data,
+                modified_at: entry.at,
+            })
+        })
+        .collect()
+}
+
+/// Write `change` to its record, tagging the journal entry that causes
+async fn apply(db: &DbClient, naming: Naming, change: &RecordChange, origin: &str) -> Result<()> {
+    check_table(&change.table)?;
+    let mut data: Option<sql::Value> = change
+        .data
+        .clone()
+        .map(serde_json::from_value)
+        .transpose()
+        .context("Record content")?;
+    if let Some(data) = &mut data {
+        naming.to_local(data);
+    }
+    let write = match data {
+        Some(_) => "UPSERT type::thing($local, $id) CONTENT $data;",
+        None => "DELETE type::thing($local, $id);",
+    };
+    db.query(format!(
+        "BEGIN TRANSACTION;
+         LET $start = time::nano(time::now());
+         {write}
+         UPDATE {JOURNAL} SET origin = $origin
+             WHERE table = $table AND record = $id AND origin = NONE AND at >= $start;
+         COMMIT TRANSACTION;"
+    ))
+    .bind(("table", change.table.clone()))
+    .bind(("local", naming.local(&change.table).to_string()))
+    .bind(("id", change.id.clone()))
+    .bind(("data", data.unwrap_or_default()))
+    .bind(("origin", origin.to_string()))
+    .await?
+    .check()
+    .with_context(|| format!("Applying {}:{}", change.table, change.id))?;
+    Ok(())
+}
+
+/// Changes after `since`, except those pushed by `replica`
+pub async fn changes_since(db: &DbClient, since: i64, replica: &str) -> Result<PullResponse> {
+    let changes = entries(
+        db,
+        Naming::Server,
+        "at > $since AND origin != $replica",
+        vec![("since", since.into()), ("replica", replica.into())],
+    )
+    .await?;
+    let cursor = changes.last().map_or(since, |c| c.modified_at);
+    Ok(PullResponse {
+        changes: latest_per_record(changes),
+        cursor,
+    })
+}
+
+/// Apply a replica's changes on the server
+///
+/// A pushed change conflicts when the record also changed here after the
+/// replica's last pull. `policy` decides which side keeps it; when the server
+/// does, its version is returned so the replica can take it.
+pub async fn receive(
+    db: &DbClient,
+    request: PushRequest,
+    policy: ConflictPolicy,
+) -> Result<PushResponse> {
+    let changes = latest_per_record(request.changes);
+    for change in &changes {
+        check_table(&change.table)?;
+    }
+    let mut response = PushResponse::default();
+    for change in changes {
+        let server = entries(
+            db,
+            Naming::Server,
+            "table = $table AND record = $id AND at > $base AND origin != $replica",
+            vec![
+                ("table", change.table.clone().into()),
+                ("id", change.id.clone().into()),
+                ("base", request.base.into()),
+                ("replica", request.replica.clone().into()),
+            ],
+        )
+        .await?
+        .pop();
+        match server {
+            Some(server) if !policy.client_wins(change.modified_at, server.modified_at) => {
+                response.rejected.push(server);
+            }
+            _ => {
+                apply(db, Naming::Server, &change, &request.replica).await?;
+                response.applied += 1;
+            }
+        }
+    }
+    Ok(response)
+}
+
+/// Carries changes between a replica and the server
+#[async_trait]
+pub trait Remote: Send + Sync {
+    async fn pull(&self, since: i64, replica: &str) -> Result<PullResponse>;
+    async fn push(&self, request: PushRequest) -> Result<PushResponse>;
+}
+
+/// What one sync round moved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub pulled: usize,
+    pub pushed: usize,
+    /// Changes on either side given up to the other by the conflict policy
+    pub overruled: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplicaState {
+    replica: String,
+    cursor: i64,
+}
+
+/// A local database kept in step with a gui-server
+pub struct Replica {
+    db: DbClient,
+    id: String,
+    policy: ConflictPolicy,
+    /// Server journal position of the last pull
+    cursor: i64,
+}
+
+impl Replica {
+    /// Journal `db` and load its replica ID and cursor, creating them on
+    /// first use
+    pub async fn open(db: DbClient, policy: ConflictPolicy) -> Result<Self> {
+        journal(&db, Naming::Replica).await?;
+        let state: Option<ReplicaState> = db
+            .query(
+                "IF !(SELECT * FROM ONLY sync_state:replica) {
+                     CREATE sync_state:replica SET replica = <string> rand::uuid::v4(), cursor = 0;
+                 };
+                 SELECT replica, cursor FROM ONLY sync_state:replica;",
+            )
+            .await?
+            .check()?
+            .take(1)?;
+        let state = state.context("Replica state missing")?;
+        Ok(Self {
+            db,
+            id: state.replica,
+            policy,
+            cursor: state.cursor,
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Local changes not yet on the server, latest per record
+    pub async fn pending(&self) -> Result<Vec<RecordChange>> {
+        Ok(latest_per_record(
+            entries(&self.db, Naming::Replica, "origin = NONE", vec![]).await?,
+        ))
+    }
+
+    /// Pull the server's changes, then push ours
+    ///
+    /// Pulling first settles conflicts here, so a push conflicts only with
+    /// changes made on the server during this round.
+    pub async fn sync(&mut self, remote: &dyn Remote) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+
+        let pulled = remote.pull(self.cursor, &self.id).await?;
+        let pending = self.pending().await?;
+        for change in &pulled.changes {
+            let local = pending
+                .iter()
+                .find(|p| p.table == change.table && p.id == change.id);
+            if let Some(local) = local {
+                if self
+                    .policy
+                    .client_wins(local.modified_at, change.modified_at)
+                {
+                    report.overruled += 1;
+                    continue;
+                }
+                self.drop_pending(local).await?;
+                report.overruled += 1;
+            }
+            apply(&self.db, Naming::Replica, change, REMOTE).await?;
+            report.pulled += 1;
+        }
+        self.set_cursor(pulled.cursor).await?;
+
+        let changes = self.pending().await?;
+        let Some(last) = changes.last().map(|c| c.modified_at) else {
+            return Ok(report);
+        };
+        let pushed = remote
+            .push(PushRequest {
+                replica: self.id.clone(),
+                base: self.cursor,
+                changes,
+            })
+            .await?;
+        self.mark_pushed(last).await?;
+        report.pushed = pushed.applied;
+        for server in &pushed.rejected {
+            apply(&self.db, Naming::Replica, server, REMOTE).await?;
+            report.overruled += 1;
+        }
+        Ok(report)
+    }
+
+    async fn drop_pending(&self, change: &RecordChange) -> Result<()> {
+        self.db
+            .query(format!(
+                "UPDATE {JOURNAL} SET origin = $origin WHERE table = $table AND record = $id AND origin = NONE;"
+            ))
+            .bind(("origin", REMOTE))
+            .bind(("table", change.table.clone()))
+            .bind(("id", change.id.clone()))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn mark_pushed(&self, until: i64) -> Result<()> {
+        self.db
+            .query(format!(
+                "UPDATE {JOURNAL} SET origin = $origin WHERE origin = NONE AND at <= $until;"
+            ))
+            .bind(("origin", PUSHED))
+            .bind(("until", until))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn set_cursor(&mut self, cursor: i64) -> Result<()> {
+        self.db
+            .query("UPDATE sync_state:replica SET cursor = $cursor;")
+            .bind(("cursor", cursor))
+            .await?
+            .check()?;
+        self.cursor = cursor;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::AssetRepository;
+    use crate::Database;
+    use serde_json::json;
+
+    /// The server side of a sync, in process
+    struct Server {
+        db: DbClient,
+        policy: ConflictPolicy,
+    }
+
+    #[async_trait]
+    impl Remote for Server {
+        async fn pull(&self, since: i64, replica: &str) -> Result<PullResponse> {
+            changes_since(&self.db, since, replica).await
+        }
+
+        async fn push(&self, request: PushRequest) -> Result<PushResponse> {
+            receive(&self.db, request, self.policy).await
+        }
+    }
+
+    async fn server(policy: ConflictPolicy) -> Server {
+        let db = Database::init().await.unwrap().client;
+        track(&db).await.unwrap();
+        Server { db, policy }
+    }
+
+    async fn name(db: &DbClient, id: &str) -> Option<String> {
+        db.query("SELECT VALUE name FROM ONLY type::thing('person', $id);")
+            .bind(("id", id.to_string()))
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap()
+    }
+
+    async fn set_name(db: &DbClient, id: &str, name: &str) {
+        db.query("UPSERT type::thing('person', $id) SET name = $name;")
+            .bind(("id", id.to_string()))
+            .bind(("name", name.to_string()))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn writes_are_journaled() {
+        let db = Database::init().await.unwrap().client;
+        track(&db).await.unwrap();
+        set_name(&db, "ada", "Ada").await;
+        db.query("DELETE person:ada;")
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let pulled = changes_since(&db, 0, "desk-1").await.unwrap();
+        assert_eq!(pulled.changes.len(), 1);
+        assert!(pulled.changes[0].is_delete());
+        assert!(pulled.cursor > 0);
+        assert!(changes_since(&db, pulled.cursor, "desk-1")
+            .await
+            .unwrap()
+            .changes
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn changes_flow_both_ways() {
+        let server = server(ConflictPolicy::LastWriteWins).await;
+        let local = Database::init().await.unwrap().client;
+        let mut replica = Replica::open(local.clone(), ConflictPolicy::LastWriteWins)
+            .await
+            .unwrap();
+
+        set_name(&server.db, "ada", "Ada").await;
+        set_name(&local, "bob", "Bob").await;
+        let report = replica.sync(&server).await.unwrap();
+        assert_eq!((report.pulled, report.pushed), (1, 1));
+        assert_eq!(name(&local, "ada").await.as_deref(), Some("Ada"));
+        assert_eq!(name(&server.db, "bob").await.as_deref(), Some("Bob"));
+
+        // Nothing echoes back
+        assert_eq!(replica.sync(&server).await.unwrap(), SyncReport::default());
+        assert!(replica.pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn replica_assets_sync_as_network_assets() {
+        let server = server(ConflictPolicy::LastWriteWins).await;
+        let local = Database::init().await.unwrap().client;
+        let mut replica = Replica::open(local.clone(), ConflictPolicy::LastWriteWins)
+            .await
+            .unwrap();
+
+        let asset = serde_json::from_value(json!({ "name": "core-sw-01" })).unwrap();
+        AssetRepository::create_with_id(&local, "sw1", asset)
+            .await
+            .unwrap();
+        assert_eq!(replica.sync(&server).await.unwrap().pushed, 1);
+        let pushed: Option<String> = server
+            .db
+            .query("SELECT VALUE name FROM ONLY network_asset:sw1;")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(pushed.as_deref(), Some("core-sw-01"));
+
+        server
+            .db
+            .query("UPDATE network_asset:sw1 SET name = 'core-sw-02';")
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        assert_eq!(replica.sync(&server).await.unwrap().pulled, 1);
+        let pulled = AssetRepository::get_by_id(&local, "sw1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(pulled.name, "core-sw-02");
+        assert!(replica.pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn conflicts_follow_the_policy() {
+        let server = server(ConflictPolicy::ServerWins).await;
+        let local = Database::init().await.unwrap().client;
+        let mut replica = Replica::open(local.clone(), ConflictPolicy::ServerWins)
+            .await
+            .unwrap();
+        set_name(&server.db, "ada", "Ada").await;
+        replica.sync(&server).await.unwrap();
+
+        set_name(&local, "ada", "Ada (desk)").await;
+        set_name(&server.db, "ada", "Ada (web)").await;
+        let report = replica.sync(&server).await.unwrap();
+        assert_eq!(report.overruled, 1);
+        assert_eq!(name(&local, "ada").await.as_deref(), Some("Ada (web)"));
+        assert_eq!(name(&server.db, "ada").await.as_deref(), Some("Ada (web)"));
+
+        let pushed = receive(
+            &server.db,
+            PushRequest {
+                replica: replica.id().to_string(),
+                base: 0,
+                changes: vec![RecordChange {
+                    table: "secrets".into(),
+                    id: "x".into(),
+                    data: Some(json!({})),
+                    modified_at: 1,
+                }],
+            },
+            ConflictPolicy::ClientWins,
+        )
+        .await;
+        assert!(pushed.is_err());
+    }
+}
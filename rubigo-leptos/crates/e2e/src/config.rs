@@ -0,0 +1,86 @@
+//! Run configuration
+//!
+//! All settings come from environment variables so the harness can run
+//! unchanged locally and in CI.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+/// Harness configuration
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// WebDriver endpoint, e.g. chromedriver or geckodriver (`WEBDRIVER_URL`)
+    pub webdriver_url: String,
+    /// Browser requested from the driver (`E2E_BROWSER`)
+    pub browser: String,
+    /// Run the browser without a window (`E2E_HEADLESS=0` to watch)
+    pub headless: bool,
+    /// gui-server binary (`GUI_SERVER_BIN`)
+    pub server_bin: PathBuf,
+    /// Working directory for the server, so its relative asset paths resolve
+    pub server_dir: PathBuf,
+    /// Scenario seeded at startup (`E2E_SCENARIO`)
+    pub scenario: PathBuf,
+    /// Show the server's output (`E2E_SERVER_LOGS=1`)
+    pub server_logs: bool,
+    /// Longest wait for any single condition (`E2E_TIMEOUT_SECS`)
+    pub timeout: Duration,
+    /// Restrict the run to these flows (`E2E_FLOWS=sign_in,create_asset`)
+    pub flows: Option<Vec<String>>,
+}
+
+impl Config {
+    /// Load configuration from the environment
+    pub fn from_env() -> Result<Self> {
+        let root = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../.."));
+        let server_dir = root.join("gui-server");
+
+        let timeout = match std::env::var("E2E_TIMEOUT_SECS") {
+            Ok(v) => Duration::from_secs(
+                v.parse()
+                    .context("E2E_TIMEOUT_SECS must be whole seconds")?,
+            ),
+            Err(_) => Duration::from_secs(30),
+        };
+
+        let flows = std::env::var("E2E_FLOWS").ok().map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        });
+
+        Ok(Self {
+            webdriver_url: std::env::var("WEBDRIVER_URL")
+                .unwrap_or_else(|_| "http://localhost:9515".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            browser: std::env::var("E2E_BROWSER").unwrap_or_else(|_| "chrome".to_string()),
+            headless: std::env::var("E2E_HEADLESS").map_or(true, |v| v != "0"),
+            server_bin: std::env::var("GUI_SERVER_BIN")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| server_dir.join("target/debug/gui-server")),
+            server_dir,
+            scenario: std::env::var("E2E_SCENARIO")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| {
+                    PathBuf::from(concat!(
+                        env!("CARGO_MANIFEST_DIR"),
+                        "/fixtures/scenario.toml"
+                    ))
+                }),
+            server_logs: std::env::var("E2E_SERVER_LOGS").is_ok_and(|v| v == "1"),
+            timeout,
+            flows,
+        })
+    }
+
+    /// Whether a flow should run
+    pub fn includes(&self, flow: &str) -> bool {
+        self.flows
+            .as_ref()
+            .is_none_or(|list| list.iter().any(|f| f == flow))
+    }
+}
@@ -0,0 +1,209 @@
+//! User journeys
+//!
+//! Each flow drives the browser the way a user would and then checks the
+//! result through the API or a pushed event, so a flow fails when any
+//! layer between the form and the database breaks.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::server::Server;
+use crate::sse::EventStream;
+use crate::webdriver::{wait_until, Session};
+
+/// Every flow, in the order they run
+pub const ALL: [&str; 4] = [
+    "sign_in",
+    "create_asset",
+    "schedule_meeting",
+    "start_simulation",
+];
+
+/// What a flow runs against
+pub struct Context<'a> {
+    pub server: &'a Server,
+    pub browser: &'a Session,
+    /// Person signed in as
+    pub persona: String,
+    pub timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct PersonaState {
+    current_persona: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Titled {
+    #[serde(alias = "name")]
+    title: String,
+}
+
+/// Run a flow by name
+pub async fn run(name: &str, ctx: &Context<'_>) -> Result<()> {
+    match name {
+        "sign_in" => sign_in(ctx).await,
+        "create_asset" => create_asset(ctx).await,
+        "schedule_meeting" => schedule_meeting(ctx).await,
+        "start_simulation" => start_simulation(ctx).await,
+        other => bail!("unknown flow '{other}'; expected one of {ALL:?}"),
+    }
+}
+
+/// Sign in by picking the persona's card on the sign-in screen
+pub async fn sign_in(ctx: &Context<'_>) -> Result<()> {
+    let browser = ctx.browser;
+    browser.goto(&ctx.server.url("/")).await?;
+
+    let state: PersonaState = ctx.server.get_json("/api/persona").await?;
+    if state.current_persona.as_deref() == Some(ctx.persona.as_str()) {
+        return Ok(());
+    }
+    if let Some(button) = browser.find_all("#sign-in-btn").await?.into_iter().next() {
+        browser.click(&button).await?;
+    }
+    let card = browser
+        .find(&format!(
+            ".persona-card[data-name=\"{}\"]",
+            ctx.persona.replace('"', "\\\"")
+        ))
+        .await?;
+    browser.click(&card).await?;
+
+    wait_until(ctx.timeout, "the persona to be set", &mut || async {
+        let state: PersonaState = ctx.server.get_json("/api/persona").await?;
+        Ok((state.current_persona.as_deref() == Some(ctx.persona.as_str())).then_some(()))
+    })
+    .await?;
+    // The switcher reloads the page once the persona is stored
+    browser
+        .wait_for("the dashboard after sign-in", || async {
+            Ok(browser
+                .find_all("#sign-in-btn")
+                .await?
+                .is_empty()
+                .then_some(()))
+        })
+        .await
+}
+
+/// Add a network component through the Components tab dialog
+///
+/// Components are the assets the simulator runs; the asset inventory's own
+/// form has no handler yet, so this is the creation path users have.
+pub async fn create_asset(ctx: &Context<'_>) -> Result<()> {
+    let browser = ctx.browser;
+    let name = unique("e2e-switch");
+    browser.goto(&ctx.server.url("/?tab=components")).await?;
+
+    let open = browser.find("button[aria-haspopup=\"dialog\"]").await?;
+    browser.click(&open).await?;
+    let form = "form[action=\"/components/create\"]";
+    let field = browser
+        .find(&format!("{form} input[name=\"name\"]"))
+        .await?;
+    browser.fill(&field, &name).await?;
+    browser
+        .execute(
+            "document.querySelector(arguments[0]).value = arguments[1];",
+            vec![
+                format!("{form} select[name=\"component_type\"]").into(),
+                "switch".into(),
+            ],
+        )
+        .await?;
+    let submit = browser
+        .find(&format!("{form} button[type=\"submit\"]"))
+        .await?;
+    browser.click(&submit).await?;
+
+    browser
+        .wait_for_text("#components-table tbody", &name)
+        .await?;
+    wait_for_listed(ctx, "/api/components", &name).await
+}
+
+/// Schedule a meeting from the calendar's New Event form
+pub async fn schedule_meeting(ctx: &Context<'_>) -> Result<()> {
+    let browser = ctx.browser;
+    let title = unique("E2E sync");
+    browser
+        .goto(&ctx.server.url("/?tab=calendar&modal=new"))
+        .await?;
+
+    let field = browser
+        .find("form.event-form input[name=\"title\"]")
+        .await?;
+    browser.fill(&field, &title).await?;
+    let submit = browser
+        .find("form.event-form button[type=\"submit\"]")
+        .await?;
+    browser.click(&submit).await?;
+
+    wait_for_listed(ctx, "/api/events", &title).await?;
+    browser
+        .wait_for("the calendar after saving", || async {
+            Ok(browser
+                .current_url()
+                .await?
+                .contains("tab=calendar")
+                .then_some(()))
+        })
+        .await
+}
+
+/// Start a simulation run and wait for its completion notification
+pub async fn start_simulation(ctx: &Context<'_>) -> Result<()> {
+    let browser = ctx.browser;
+    // Subscribe first so the notification cannot be missed
+    let url = reqwest::Url::parse_with_params(
+        &ctx.server.url("/api/notifications/stream"),
+        [("persona", ctx.persona.as_str())],
+    )?;
+    let mut events = EventStream::connect(ctx.server.http(), url.as_str()).await?;
+    let runs_before: Vec<Value> = ctx.server.get_json("/api/runs").await?;
+
+    browser.goto(&ctx.server.url("/?tab=simulation")).await?;
+    let start = browser
+        .find("form[action=\"/simulation/start\"] button[type=\"submit\"]")
+        .await?;
+    browser.click(&start).await?;
+
+    events
+        .wait_for("notification", ctx.timeout, |event| {
+            event
+                .json()
+                .is_ok_and(|v| v["kind"] == "simulation_complete")
+        })
+        .await?;
+    let runs: Vec<Value> = ctx.server.get_json("/api/runs").await?;
+    if runs.len() <= runs_before.len() {
+        bail!("simulation finished but no run was recorded");
+    }
+    Ok(())
+}
+
+/// Wait until a list endpoint returns an item with this title or name
+async fn wait_for_listed(ctx: &Context<'_>, path: &str, title: &str) -> Result<()> {
+    wait_until(
+        ctx.timeout,
+        &format!("'{title}' in {path}"),
+        &mut || async {
+            let items: Vec<Titled> = ctx.server.get_json(path).await?;
+            Ok(items.iter().any(|item| item.title == title).then_some(()))
+        },
+    )
+    .await
+}
+
+/// `prefix` with a timestamp, so reruns against one server don't collide
+fn unique(prefix: &str) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    format!("{prefix} {millis}")
+}
@@ -0,0 +1,36 @@
+//! End-to-End Tests
+//!
+//! Boots gui-server against a fixture scenario on an in-memory database and
+//! drives the real UI in a browser over WebDriver, so regressions anywhere
+//! in the form → handler → database → SSE pipeline fail a run.
+//!
+//! # Architecture
+//!
+//! - **server**: Spawns gui-server on a free port and waits for `/readyz`
+//! - **webdriver**: Minimal W3C WebDriver client (sessions, elements, waits)
+//! - **sse**: Server-sent event reader for waiting on pushed updates
+//! - **flows**: The user journeys under test
+//! - **config**: Environment-driven run configuration
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Build the server and its hydrate bundle, start a driver, then:
+//! (cd gui-server && ./build-hydrate.sh && cargo build)
+//! chromedriver --port=9515 &
+//! cargo run -p e2e
+//!
+//! # One flow, with a visible browser and server logs
+//! E2E_FLOWS=start_simulation E2E_HEADLESS=0 E2E_SERVER_LOGS=1 cargo run -p e2e
+//! ```
+
+pub mod config;
+pub mod flows;
+pub mod server;
+pub mod sse;
+pub mod webdriver;
+
+pub use config::Config;
+pub use server::Server;
+pub use sse::{EventStream, SseEvent};
+pub use webdriver::{Element, Session};
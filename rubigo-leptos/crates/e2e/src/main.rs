@@ -0,0 +1,44 @@
+//! End-to-end runner
+//!
+//! Starts a gui-server and a browser session, runs each selected flow in
+//! order, and exits non-zero if any fail. Flows share the session, so a
+//! later flow starts signed in.
+
+use anyhow::{bail, Result};
+use e2e::flows::{self, Context};
+use e2e::{Config, Server, Session};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = Config::from_env()?;
+    let server = Server::start(&config).await?;
+    let browser = Session::start(&config).await?;
+    let ctx = Context {
+        server: &server,
+        browser: &browser,
+        persona: server.first_persona().await?,
+        timeout: config.timeout,
+    };
+    tracing::info!("signing in as {}", ctx.persona);
+
+    let mut failures = Vec::new();
+    for flow in flows::ALL.into_iter().filter(|f| config.includes(f)) {
+        match flows::run(flow, &ctx).await {
+            Ok(()) => tracing::info!("{flow}: pass"),
+            Err(e) => {
+                tracing::error!("{flow}: {e:#}");
+                failures.push(flow);
+            }
+        }
+    }
+
+    browser.close().await?;
+    server.stop().await?;
+
+    if !failures.is_empty() {
+        bail!("{} flow(s) failed: {}", failures.len(), failures.join(", "));
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! gui-server under test
+//!
+//! Each run starts its own server on a free local port with a fresh
+//! in-memory database seeded from the fixture scenario, and stops it when
+//! the [`Server`] is dropped.
+
+use std::net::TcpListener;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::process::{Child, Command};
+
+use crate::config::Config;
+
+/// Interval between readiness probes
+const POLL: Duration = Duration::from_millis(250);
+
+/// Time allowed for seeding and the startup imports
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A running gui-server
+pub struct Server {
+    child: Child,
+    base_url: String,
+    http: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct PersonSummary {
+    name: String,
+}
+
+impl Server {
+    /// Spawn the server and wait until `/readyz` reports the seed is done
+    pub async fn start(config: &Config) -> Result<Self> {
+        if !config.server_bin.exists() {
+            bail!(
+                "{} not found; build it with `cargo build` in gui-server or set GUI_SERVER_BIN",
+                config.server_bin.display()
+            );
+        }
+        let scenario = config
+            .scenario
+            .canonicalize()
+            .with_context(|| format!("scenario {}", config.scenario.display()))?;
+        let port = free_port()?;
+
+        let output = || {
+            if config.server_logs {
+                Stdio::inherit()
+            } else {
+                Stdio::null()
+            }
+        };
+        let child = Command::new(&config.server_bin)
+            .current_dir(&config.server_dir)
+            .env("RUBIGO_SERVER__HOST", "127.0.0.1")
+            .env("RUBIGO_SERVER__PORT", port.to_string())
+            .env("RUBIGO_PATHS__SCENARIO", &scenario)
+            .env("RUBIGO_DEV_MODE", "true")
+            .stdout(output())
+            .stderr(output())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to start {}", config.server_bin.display()))?;
+
+        let mut server = Self {
+            child,
+            base_url: format!("http://127.0.0.1:{port}"),
+            http: reqwest::Client::new(),
+        };
+        server.wait_ready().await?;
+        tracing::info!("gui-server ready at {}", server.base_url);
+        Ok(server)
+    }
+
+    async fn wait_ready(&mut self) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + STARTUP_TIMEOUT;
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                bail!("gui-server exited during startup ({status}); rerun with E2E_SERVER_LOGS=1");
+            }
+            let ready = self
+                .http
+                .get(self.url("/readyz"))
+                .send()
+                .await
+                .is_ok_and(|r| r.status().is_success());
+            if ready {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() > deadline {
+                bail!("gui-server not ready after {:?}", STARTUP_TIMEOUT);
+            }
+            tokio::time::sleep(POLL).await;
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Absolute URL for a path on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// GET a JSON API endpoint
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self.http.get(self.url(path)).send().await?;
+        let response = response
+            .error_for_status()
+            .with_context(|| format!("GET {path}"))?;
+        Ok(response.json().await?)
+    }
+
+    /// Name of a seeded person to sign in as
+    pub async fn first_persona(&self) -> Result<String> {
+        let people: Vec<PersonSummary> = self.get_json("/api/people?sort=name").await?;
+        people
+            .into_iter()
+            .next()
+            .map(|p| p.name)
+            .context("the scenario seeded no people")
+    }
+
+    /// Stop the server
+    pub async fn stop(mut self) -> Result<()> {
+        self.child.kill().await?;
+        Ok(())
+    }
+}
+
+/// A local port nothing is listening on
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
@@ -0,0 +1,157 @@
+//! Server-sent events
+//!
+//! Subscribes to a stream such as `/api/notifications/stream` and waits for
+//! an event the flow expects, so flows assert on what the server pushed
+//! rather than on sleeps.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// One dispatched event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// `event:` field; `message` when the server sent none
+    pub event: String,
+    /// `data:` lines joined with newlines
+    pub data: String,
+}
+
+impl SseEvent {
+    /// Parse the data as JSON
+    pub fn json(&self) -> Result<serde_json::Value> {
+        serde_json::from_str(&self.data)
+            .with_context(|| format!("{} event data is not JSON", self.event))
+    }
+}
+
+/// Incremental parser for the `text/event-stream` format
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: String,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Feed received text and return every event it completes
+    pub fn push(&mut self, chunk: &str) -> Vec<SseEvent> {
+        self.buffer.push_str(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=end).collect();
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                // Blank line dispatches; comment-only blocks (keep-alives) carry no data
+                let event = self.event.take();
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: event.unwrap_or_else(|| "message".to_string()),
+                        data: std::mem::take(&mut self.data).join("\n"),
+                    });
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+/// Open event stream
+pub struct EventStream {
+    response: reqwest::Response,
+    parser: SseParser,
+    pending: VecDeque<SseEvent>,
+}
+
+impl EventStream {
+    /// Subscribe to `url`
+    pub async fn connect(http: &reqwest::Client, url: &str) -> Result<Self> {
+        let response = http
+            .get(url)
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("subscribing to {url}"))?;
+        Ok(Self {
+            response,
+            parser: SseParser::default(),
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Next event, or `None` once the server closes the stream
+    pub async fn next(&mut self) -> Result<Option<SseEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            let Some(chunk) = self.response.chunk().await? else {
+                return Ok(None);
+            };
+            let text = std::str::from_utf8(&chunk)
+                .map_err(|e| anyhow!("event stream is not UTF-8: {e}"))?;
+            self.pending.extend(self.parser.push(text));
+        }
+    }
+
+    /// Wait for an event named `event` whose data satisfies `matches`,
+    /// skipping everything else
+    pub async fn wait_for(
+        &mut self,
+        event: &str,
+        timeout: Duration,
+        mut matches: impl FnMut(&SseEvent) -> bool,
+    ) -> Result<SseEvent> {
+        let wait = async {
+            while let Some(received) = self.next().await? {
+                if received.event == event && matches(&received) {
+                    return Ok(received);
+                }
+            }
+            bail!("stream closed before a '{event}' event arrived")
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| anyhow!("no '{event}' event within {timeout:?}"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push("event: notif").is_empty());
+        assert!(parser.push("ication\ndata: {\"kind\":").is_empty());
+        let events = parser.push("\"simulation_complete\"}\r\n\r\n:ping\n\ndata: a\ndata: b\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "notification".into(),
+                    data: "{\"kind\":\"simulation_complete\"}".into()
+                },
+                SseEvent {
+                    event: "message".into(),
+                    data: "a\nb".into()
+                },
+            ]
+        );
+        assert_eq!(events[0].json().unwrap()["kind"], "simulation_complete");
+    }
+}
@@ -0,0 +1,262 @@
+//! WebDriver client
+//!
+//! Just enough of the W3C WebDriver protocol for the flows: one session
+//! per run, CSS lookups, clicks, typing, and polling waits. Works with any
+//! conforming driver (chromedriver, geckodriver, selenium).
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+
+/// Key under which drivers return element references
+const ELEMENT_KEY: &str = "element-6066-11e4-a52e-4f735466cecf";
+
+/// Interval between checks while waiting
+const POLL: Duration = Duration::from_millis(200);
+
+/// Browser session
+pub struct Session {
+    http: reqwest::Client,
+    /// `<driver>/session/<id>`
+    url: String,
+    timeout: Duration,
+}
+
+/// Element in the current page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element(String);
+
+impl Session {
+    /// Open a browser session through the configured driver
+    pub async fn start(config: &Config) -> Result<Self> {
+        let http = reqwest::Client::new();
+        let mut args = vec!["--window-size=1280,900"];
+        if config.headless {
+            args.push("--headless=new");
+        }
+        let capabilities = json!({
+            "capabilities": {
+                "alwaysMatch": {
+                    "browserName": config.browser,
+                    "goog:chromeOptions": { "args": args },
+                    "moz:firefoxOptions": { "args": if config.headless { vec!["-headless"] } else { vec![] } },
+                }
+            }
+        });
+        let url = format!("{}/session", config.webdriver_url);
+        let response = http
+            .post(&url)
+            .json(&capabilities)
+            .send()
+            .await
+            .with_context(|| {
+                format!(
+                    "no WebDriver at {}; start chromedriver or set WEBDRIVER_URL",
+                    config.webdriver_url
+                )
+            })?;
+        let value = unwrap_response(response).await?;
+        let id = value["sessionId"]
+            .as_str()
+            .ok_or_else(|| anyhow!("driver returned no session id: {value}"))?;
+        Ok(Self {
+            http,
+            url: format!("{url}/{id}"),
+            timeout: config.timeout,
+        })
+    }
+
+    async fn command(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let request = self.http.request(method, format!("{}{}", self.url, path));
+        let request = match body {
+            Some(body) => request.json(&body),
+            None => request,
+        };
+        unwrap_response(request.send().await?).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value> {
+        self.command(reqwest::Method::POST, path, Some(body)).await
+    }
+
+    async fn get(&self, path: &str) -> Result<Value> {
+        self.command(reqwest::Method::GET, path, None).await
+    }
+
+    pub async fn goto(&self, url: &str) -> Result<()> {
+        self.post("/url", json!({ "url": url })).await?;
+        Ok(())
+    }
+
+    pub async fn current_url(&self) -> Result<String> {
+        Ok(self
+            .get("/url")
+            .await?
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// All elements matching a CSS selector
+    pub async fn find_all(&self, selector: &str) -> Result<Vec<Element>> {
+        let found = self
+            .post(
+                "/elements",
+                json!({ "using": "css selector", "value": selector }),
+            )
+            .await?;
+        found
+            .as_array()
+            .map(|list| list.iter().map(element_ref).collect())
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+
+    /// First element matching a CSS selector, waiting for it to appear
+    pub async fn find(&self, selector: &str) -> Result<Element> {
+        self.wait_for(&format!("element '{selector}'"), || async {
+            Ok(self.find_all(selector).await?.into_iter().next())
+        })
+        .await
+    }
+
+    pub async fn click(&self, element: &Element) -> Result<()> {
+        self.post(&format!("/element/{}/click", element.0), json!({}))
+            .await?;
+        Ok(())
+    }
+
+    /// Clear a field and type into it
+    pub async fn fill(&self, element: &Element, text: &str) -> Result<()> {
+        self.post(&format!("/element/{}/clear", element.0), json!({}))
+            .await?;
+        self.post(
+            &format!("/element/{}/value", element.0),
+            json!({ "text": text }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Rendered text of an element
+    pub async fn text(&self, element: &Element) -> Result<String> {
+        Ok(self
+            .get(&format!("/element/{}/text", element.0))
+            .await?
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// Run a script in the page and return its result
+    pub async fn execute(&self, script: &str, args: Vec<Value>) -> Result<Value> {
+        self.post("/execute/sync", json!({ "script": script, "args": args }))
+            .await
+    }
+
+    /// Poll `check` until it yields a value or the timeout passes
+    pub async fn wait_for<T, F, Fut>(&self, what: &str, mut check: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Option<T>>>,
+    {
+        wait_until(self.timeout, what, &mut check).await
+    }
+
+    /// Wait until an element's text contains `needle`
+    pub async fn wait_for_text(&self, selector: &str, needle: &str) -> Result<()> {
+        self.wait_for(&format!("'{needle}' in '{selector}'"), || async {
+            for element in self.find_all(selector).await? {
+                // Elements can be replaced by a re-render between lookup and read
+                if self.text(&element).await.is_ok_and(|t| t.contains(needle)) {
+                    return Ok(Some(()));
+                }
+            }
+            Ok(None)
+        })
+        .await
+    }
+
+    /// End the session and close the browser
+    pub async fn close(self) -> Result<()> {
+        self.command(reqwest::Method::DELETE, "", None).await?;
+        Ok(())
+    }
+}
+
+/// Poll `check` every [`POLL`] until it yields a value or `timeout` passes
+pub async fn wait_until<T, F, Fut>(timeout: Duration, what: &str, check: &mut F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(value) = check().await? {
+            return Ok(value);
+        }
+        if tokio::time::Instant::now() > deadline {
+            bail!("timed out after {timeout:?} waiting for {what}");
+        }
+        tokio::time::sleep(POLL).await;
+    }
+}
+
+/// The `value` of a driver response, or its error as an `Err`
+async fn unwrap_response(response: reqwest::Response) -> Result<Value> {
+    let status = response.status();
+    let mut body: Value = response
+        .json()
+        .await
+        .context("driver response is not JSON")?;
+    let value = body["value"].take();
+    if !status.is_success() {
+        bail!(
+            "WebDriver {}: {}",
+            value["error"].as_str().unwrap_or("error"),
+            value["message"].as_str().unwrap_or_default()
+        );
+    }
+    Ok(value)
+}
+
+fn element_ref(value: &Value) -> Result<Element> {
+    value[ELEMENT_KEY]
+        .as_str()
+        .map(|id| Element(id.to_string()))
+        .ok_or_else(|| anyhow!("not an element reference: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn element_references_use_the_w3c_key() {
+        let element = element_ref(&json!({ ELEMENT_KEY: "abc-123" })).unwrap();
+        assert_eq!(element, Element("abc-123".to_string()));
+        assert!(element_ref(&json!({ "ELEMENT": "abc-123" })).is_err());
+    }
+
+    #[tokio::test]
+    async fn wait_until_times_out_with_context() {
+        let mut calls = 0;
+        let mut check = || {
+            calls += 1;
+            async { Ok::<Option<()>, anyhow::Error>(None) }
+        };
+        let err = wait_until(Duration::from_millis(1), "nothing", &mut check)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("waiting for nothing"));
+        assert!(calls >= 1);
+    }
+}
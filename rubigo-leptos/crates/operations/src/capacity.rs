@@ -0,0 +1,165 @@
+//! Capacity planning
+//!
+//! How full racks, spaces and devices are, and when they will run out.
+//! [`Usage`] pairs what is used with what there is, for rack units, the
+//! power and cooling budgeted for a space, or a device's ports. [`place`]
+//! checks where a new device would go in a rack, and [`exhaustion`]
+//! extends the trend in daily usage samples to the day it reaches the total.
+
+use serde::{Deserialize, Serialize};
+
+/// Share of a total at which usage is flagged before it runs out
+pub const WARN_AT: f64 = 0.8;
+
+/// How close usage is to its total
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Ok,
+    Warning,
+    Over,
+}
+
+/// Amount used out of a total, in whatever unit the two share
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    pub used: f64,
+    pub total: f64,
+}
+
+impl Usage {
+    pub fn new(used: f64, total: f64) -> Self {
+        Self { used, total }
+    }
+
+    /// Share used, `None` when there is no total to measure against
+    pub fn ratio(&self) -> Option<f64> {
+        (self.total > 0.0).then(|| self.used / self.total)
+    }
+
+    pub fn free(&self) -> f64 {
+        (self.total - self.used).max(0.0)
+    }
+
+    pub fn level(&self) -> Level {
+        match self.ratio() {
+            Some(r) if r > 1.0 => Level::Over,
+            Some(r) if r >= WARN_AT => Level::Warning,
+            _ => Level::Ok,
+        }
+    }
+
+    /// The same usage with `more` added
+    pub fn plus(&self, more: f64) -> Self {
+        Self::new(self.used + more, self.total)
+    }
+}
+
+/// Check that a device `height` units tall fits at `position` (1 = bottom)
+/// in a rack `rack_height` units tall, among `occupied` `(position, height)`
+/// pairs
+pub fn place(
+    rack_height: u8,
+    occupied: &[(u8, u8)],
+    position: u8,
+    height: u8,
+) -> Result<(), String> {
+    let height = height.max(1);
+    if position == 0 {
+        return Err("Rack positions start at U1".to_string());
+    }
+    let top = u16::from(position) + u16::from(height) - 1;
+    if top > u16::from(rack_height) {
+        return Err(format!(
+            "U{}–U{} runs past the top of a {}U rack",
+            position, top, rack_height
+        ));
+    }
+    let clash = occupied.iter().find(|&&(at, h)| {
+        let end = u16::from(at) + u16::from(h.max(1)) - 1;
+        u16::from(at) <= top && u16::from(position) <= end
+    });
+    match clash {
+        Some((at, h)) => Err(format!(
+            "U{}–U{} overlaps the device at U{}–U{}",
+            position,
+            top,
+            at,
+            u16::from(*at) + u16::from((*h).max(1)) - 1
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The day usage reaches `total` if it keeps the trend of `samples`, given
+/// as `(day, used)` with days counted from any fixed origin
+///
+/// The trend is a least-squares line. `None` when there are fewer than two
+/// days to fit or usage is flat or falling; a total already reached gives
+/// the last sampled day.
+pub fn exhaustion(samples: &[(i64, f64)], total: f64) -> Option<i64> {
+    let &(last_day, last_used) = samples.iter().max_by_key(|(day, _)| *day)?;
+    if last_used >= total {
+        return Some(last_day);
+    }
+    let n = samples.len() as f64;
+    let mean_day = samples.iter().map(|&(d, _)| d as f64).sum::<f64>() / n;
+    let mean_used = samples.iter().map(|&(_, u)| u).sum::<f64>() / n;
+    let spread: f64 = samples
+        .iter()
+        .map(|&(d, _)| (d as f64 - mean_day).powi(2))
+        .sum();
+    if spread == 0.0 {
+        return None;
+    }
+    let slope = samples
+        .iter()
+        .map(|&(d, u)| (d as f64 - mean_day) * (u - mean_used))
+        .sum::<f64>()
+        / spread;
+    if slope <= 0.0 {
+        return None;
+    }
+    let day = mean_day + (total - mean_used) / slope;
+    Some((day.ceil() as i64).max(last_day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_levels_and_placement() {
+        assert_eq!(Usage::new(10.0, 42.0).level(), Level::Ok);
+        assert_eq!(Usage::new(36.0, 42.0).level(), Level::Warning);
+        assert_eq!(Usage::new(43.0, 42.0).level(), Level::Over);
+        assert_eq!(Usage::new(5.0, 0.0).ratio(), None);
+        assert_eq!(Usage::new(5.0, 0.0).level(), Level::Ok);
+        assert_eq!(Usage::new(40.0, 42.0).plus(4.0).free(), 0.0);
+
+        let occupied = [(1, 2), (10, 1)];
+        assert_eq!(place(42, &occupied, 3, 4), Ok(()));
+        assert_eq!(
+            place(42, &occupied, 9, 2),
+            Err("U9–U10 overlaps the device at U10–U10".to_string())
+        );
+        assert_eq!(place(42, &occupied, 41, 2), Ok(()));
+        assert_eq!(
+            place(42, &occupied, 42, 2),
+            Err("U42–U43 runs past the top of a 42U rack".to_string())
+        );
+        assert!(place(42, &occupied, 0, 1).is_err());
+    }
+
+    #[test]
+    fn exhaustion_follows_the_trend() {
+        // Two units a day from 30 of 42: full on day 16
+        let samples = [(10, 30.0), (11, 32.0), (12, 34.0)];
+        assert_eq!(exhaustion(&samples, 42.0), Some(16));
+        assert_eq!(exhaustion(&[(1, 5.0), (2, 5.0)], 42.0), None);
+        assert_eq!(exhaustion(&[(1, 9.0), (2, 5.0)], 42.0), None);
+        assert_eq!(exhaustion(&[(3, 5.0)], 42.0), None);
+        assert_eq!(exhaustion(&[(3, 45.0)], 42.0), Some(3));
+        assert_eq!(exhaustion(&[], 42.0), None);
+    }
+}
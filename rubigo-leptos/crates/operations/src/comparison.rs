@@ -0,0 +1,272 @@
+//! Simulation run comparison
+//!
+//! Lines up what two or more runs measured so a topology change can be
+//! judged by numbers: per-node metrics are matched by component id, and
+//! traces (one value per simulation step) by step index. The first run is
+//! the baseline every other run's deltas are taken against. A node or step
+//! one run has and another lacks shows as `None` rather than being dropped,
+//! since added and removed nodes are usually the point of the comparison.
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// Per-node metrics recorded for every run, in the order tables show them
+pub const NODE_METRICS: [&str; 4] = ["out_links", "in_links", "reachable", "max_hops"];
+
+/// What one run measured, as [`compare`] reads it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunMetrics {
+    /// How the run is labelled in charts and table headings
+    pub label: String,
+    /// Node names and metric values, by component id
+    pub nodes: BTreeMap<u32, (String, BTreeMap<String, f64>)>,
+    /// Series with one value per step, by name
+    pub traces: BTreeMap<String, Vec<f64>>,
+}
+
+/// One trace across every run, padded to the longest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trace {
+    pub name: String,
+    /// One series per run, in run order, each `steps` long
+    pub series: Vec<Vec<Option<f64>>>,
+    pub steps: usize,
+}
+
+impl Trace {
+    /// Smallest and largest value in any run, for a shared chart scale
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.series
+            .iter()
+            .flatten()
+            .flatten()
+            .fold(None, |range, &v| match range {
+                None => Some((v, v)),
+                Some((lo, hi)) => Some((f64::min(lo, v), f64::max(hi, v))),
+            })
+    }
+}
+
+/// One metric of one node across every run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeltaRow {
+    pub node: u32,
+    pub name: String,
+    pub metric: String,
+    /// Value in each run, in run order
+    pub values: Vec<Option<f64>>,
+    /// Change from the baseline for each run after the first
+    pub deltas: Vec<Option<f64>>,
+}
+
+impl DeltaRow {
+    /// Whether any run differs from the baseline, including by having the
+    /// node when the baseline does not or the other way round
+    pub fn changed(&self) -> bool {
+        let baseline = self.values[0];
+        self.values[1..].iter().any(|v| *v != baseline)
+    }
+}
+
+/// Runs lined up against the first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Comparison {
+    /// Run labels, baseline first
+    pub runs: Vec<String>,
+    pub traces: Vec<Trace>,
+    /// Node rows ordered by node then by [`NODE_METRICS`], other metrics last
+    pub rows: Vec<DeltaRow>,
+}
+
+impl Comparison {
+    /// Rows where some run differs from the baseline
+    pub fn changed(&self) -> impl Iterator<Item = &DeltaRow> {
+        self.rows.iter().filter(|r| r.changed())
+    }
+}
+
+/// Align `runs`, the first being the baseline; at least two are needed
+pub fn compare(runs: &[RunMetrics]) -> Result<Comparison, String> {
+    if runs.len() < 2 {
+        return Err(format!(
+            "Comparing needs at least two runs, got {}",
+            runs.len()
+        ));
+    }
+
+    let names: BTreeSet<&String> = runs.iter().flat_map(|r| r.traces.keys()).collect();
+    let traces = names
+        .into_iter()
+        .map(|name| {
+            let steps = runs
+                .iter()
+                .filter_map(|r| r.traces.get(name).map(Vec::len))
+                .max()
+                .unwrap_or_default();
+            let series = runs
+                .iter()
+                .map(|r| {
+                    let values = r.traces.get(name).map(Vec::as_slice).unwrap_or_default();
+                    (0..steps).map(|i| values.get(i).copied()).collect()
+                })
+                .collect();
+            Trace {
+                name: name.clone(),
+                series,
+                steps,
+            }
+        })
+        .collect();
+
+    let nodes: BTreeSet<u32> = runs.iter().flat_map(|r| r.nodes.keys().copied()).collect();
+    let mut rows = Vec::new();
+    for node in nodes {
+        // The newest name wins, so a renamed node reads as it does now
+        let name = runs
+            .iter()
+            .rev()
+            .find_map(|r| r.nodes.get(&node).map(|(name, _)| name.clone()))
+            .unwrap_or_default();
+        let mut metrics: Vec<&String> = runs
+            .iter()
+            .filter_map(|r| r.nodes.get(&node))
+            .flat_map(|(_, m)| m.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        metrics.sort_by_key(|m| NODE_METRICS.iter().position(|known| known == m));
+        for metric in metrics {
+            let values: Vec<Option<f64>> = runs
+                .iter()
+                .map(|r| r.nodes.get(&node).and_then(|(_, m)| m.get(metric)).copied())
+                .collect();
+            let deltas = values[1..]
+                .iter()
+                .map(|v| Some(v.as_ref()? - values[0]?))
+                .collect();
+            rows.push(DeltaRow {
+                node,
+                name: name.clone(),
+                metric: metric.clone(),
+                values,
+                deltas,
+            });
+        }
+    }
+
+    Ok(Comparison {
+        runs: runs.iter().map(|r| r.label.clone()).collect(),
+        traces,
+        rows,
+    })
+}
+
+/// [`NODE_METRICS`] for each of `nodes` in the directed graph `links`
+///
+/// Links to nodes outside `nodes` are ignored. `reachable` counts the other
+/// nodes a node can send to, and `max_hops` is how many links the farthest
+/// of them is away.
+pub fn node_metrics(nodes: &[u32], links: &[(u32, u32)]) -> BTreeMap<u32, BTreeMap<String, f64>> {
+    let known: BTreeSet<u32> = nodes.iter().copied().collect();
+    let mut next: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+    for &(from, to) in links {
+        if from != to && known.contains(&from) && known.contains(&to) {
+            next.entry(from).or_default().insert(to);
+        }
+    }
+    let in_links = |node: u32| next.values().filter(|to| to.contains(&node)).count();
+
+    known
+        .iter()
+        .map(|&node| {
+            let mut hops = BTreeMap::from([(node, 0usize)]);
+            let mut queue = VecDeque::from([node]);
+            while let Some(at) = queue.pop_front() {
+                let distance = hops[&at];
+                for &to in next.get(&at).into_iter().flatten() {
+                    if let Entry::Vacant(entry) = hops.entry(to) {
+                        entry.insert(distance + 1);
+                        queue.push_back(to);
+                    }
+                }
+            }
+            let metrics = BTreeMap::from([
+                (
+                    "out_links".to_string(),
+                    next.get(&node).map_or(0, BTreeSet::len) as f64,
+                ),
+                ("in_links".to_string(), in_links(node) as f64),
+                ("reachable".to_string(), (hops.len() - 1) as f64),
+                (
+                    "max_hops".to_string(),
+                    hops.values().copied().max().unwrap_or_default() as f64,
+                ),
+            ]);
+            (node, metrics)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(label: &str, links: &[(u32, u32)], step_ms: &[f64]) -> RunMetrics {
+        let ids: BTreeSet<u32> = links.iter().flat_map(|&(a, b)| [a, b]).collect();
+        let ids: Vec<u32> = ids.into_iter().collect();
+        RunMetrics {
+            label: label.to_string(),
+            nodes: node_metrics(&ids, links)
+                .into_iter()
+                .map(|(id, m)| (id, (format!("n{id}"), m)))
+                .collect(),
+            traces: BTreeMap::from([("step_ms".to_string(), step_ms.to_vec())]),
+        }
+    }
+
+    #[test]
+    fn node_metrics_follow_links() {
+        let metrics = node_metrics(&[1, 2, 3, 4], &[(1, 2), (2, 3), (1, 3), (3, 3), (3, 9)]);
+        assert_eq!(metrics[&1]["out_links"], 2.0);
+        assert_eq!(metrics[&3]["in_links"], 2.0);
+        assert_eq!(metrics[&3]["out_links"], 0.0);
+        assert_eq!(metrics[&1]["reachable"], 2.0);
+        assert_eq!(metrics[&1]["max_hops"], 1.0);
+        assert_eq!(metrics[&2]["max_hops"], 1.0);
+        assert_eq!(metrics[&4]["reachable"], 0.0);
+        assert_eq!(metrics.len(), 4);
+    }
+
+    #[test]
+    fn runs_align_by_node_and_step() {
+        let before = run("before", &[(1, 2), (2, 3)], &[1.0, 2.0, 3.0]);
+        let after = run("after", &[(1, 2), (1, 4)], &[2.0, 2.0]);
+        let comparison = compare(&[before.clone(), after]).unwrap();
+        assert_eq!(comparison.runs, vec!["before", "after"]);
+
+        let trace = &comparison.traces[0];
+        assert_eq!(trace.steps, 3);
+        assert_eq!(trace.series[1], vec![Some(2.0), Some(2.0), None]);
+        assert_eq!(trace.range(), Some((1.0, 3.0)));
+
+        let row = |node: u32, metric: &str| {
+            comparison
+                .rows
+                .iter()
+                .find(|r| r.node == node && r.metric == metric)
+                .unwrap()
+        };
+        assert_eq!(row(2, "reachable").deltas, vec![Some(-1.0)]);
+        assert_eq!(row(1, "reachable").deltas, vec![Some(0.0)]);
+        assert_eq!(row(3, "in_links").values, vec![Some(1.0), None]);
+        assert_eq!(row(4, "in_links").deltas, vec![None]);
+        assert!(row(1, "out_links").changed());
+        assert!(!row(2, "in_links").changed());
+        assert_eq!(comparison.rows[0].metric, "out_links");
+        assert!(comparison.changed().all(DeltaRow::changed));
+
+        assert!(compare(&[before]).is_err());
+    }
+}
@@ -0,0 +1,405 @@
+//! Email
+//!
+//! Templates for the messages the server emails: meeting invites (with an
+//! iCalendar attachment calendars can import), report deliveries and chat
+//! mentions, along with each person's choice of which of those they get.
+//! Messages are plain text; sending them is up to the server.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What an email is about, for preferences and the outbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailKind {
+    Invite,
+    Report,
+    Mention,
+}
+
+impl EmailKind {
+    pub const ALL: [EmailKind; 3] = [EmailKind::Invite, EmailKind::Report, EmailKind::Mention];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmailKind::Invite => "invite",
+            EmailKind::Report => "report",
+            EmailKind::Mention => "mention",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EmailKind::Invite => "Meeting invitations",
+            EmailKind::Report => "Report deliveries",
+            EmailKind::Mention => "Mentions in chat",
+        }
+    }
+}
+
+/// Which emails a person wants; everything until they say otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailPreferences {
+    pub invites: bool,
+    pub reports: bool,
+    pub mentions: bool,
+}
+
+impl Default for EmailPreferences {
+    fn default() -> Self {
+        Self {
+            invites: true,
+            reports: true,
+            mentions: true,
+        }
+    }
+}
+
+impl EmailPreferences {
+    pub fn allows(&self, kind: EmailKind) -> bool {
+        match kind {
+            EmailKind::Invite => self.invites,
+            EmailKind::Report => self.reports,
+            EmailKind::Mention => self.mentions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// An email before it is addressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub kind: EmailKind,
+    pub subject: String,
+    pub text: String,
+    pub attachments: Vec<Attachment>,
+}
+
+/// Someone taking part in a meeting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attendee {
+    pub name: String,
+    pub email: String,
+}
+
+/// What an invite says about its meeting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InviteDetails {
+    /// Stable across updates so calendars replace rather than duplicate
+    pub uid: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// Local times as `YYYY-MM-DDTHH:MM[:SS]`
+    pub start: String,
+    pub end: String,
+    pub all_day: bool,
+    /// IANA zone the times are in
+    pub timezone: String,
+    /// `start` and `end` in UTC, which the attachment uses so calendars
+    /// needn't know `timezone`; required unless the meeting is all day
+    pub utc_start: Option<DateTime<Utc>>,
+    pub utc_end: Option<DateTime<Utc>>,
+    pub location: Option<String>,
+    pub organizer: Option<Attendee>,
+    pub attendees: Vec<Attendee>,
+}
+
+/// The date of an ISO 8601 time
+fn ics_date(iso: &str) -> Option<NaiveDate> {
+    let (date, _) = NaiveDate::parse_and_remainder(iso.trim(), "%Y-%m-%d").ok()?;
+    Some(date)
+}
+
+/// Escape text for an iCalendar property value
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Quote text for an iCalendar parameter value, such as a `CN`
+///
+/// Quoted values can't hold a `"` or a line break, so those are written as
+/// RFC 6868 `^'` and `^n`.
+fn ics_param(text: &str) -> String {
+    let text = text
+        .replace('^', "^^")
+        .replace('"', "^'")
+        .replace("\r\n", "^n")
+        .replace('\n', "^n");
+    format!("\"{text}\"")
+}
+
+/// Fold a content line at 75 octets, as iCalendar requires
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+/// iCalendar `REQUEST` for the meeting; `stamp` is the current UTC time
+/// as `YYYYMMDDTHHMMSSZ`
+pub fn invite_ics(details: &InviteDetails, stamp: &str) -> Result<String, String> {
+    let invalid = |time: &str| format!("{time} is not a YYYY-MM-DDTHH:MM time");
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Rubigo//Calendar//EN".to_string(),
+        "METHOD:REQUEST".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", details.uid),
+        format!("DTSTAMP:{stamp}"),
+    ];
+    if details.all_day {
+        let start = ics_date(&details.start).ok_or_else(|| invalid(&details.start))?;
+        // The end date is exclusive
+        let end = ics_date(&details.end)
+            .and_then(|end| end.succ_opt())
+            .ok_or_else(|| invalid(&details.end))?;
+        lines.push(format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+    } else {
+        let (Some(start), Some(end)) = (details.utc_start, details.utc_end) else {
+            return Err(format!("{} has no UTC start and end", details.title));
+        };
+        let utc = |time: DateTime<Utc>| time.format("%Y%m%dT%H%M%SZ").to_string();
+        lines.push(format!("DTSTART:{}", utc(start)));
+        lines.push(format!("DTEND:{}", utc(end)));
+    }
+    lines.push(format!("SUMMARY:{}", ics_text(&details.title)));
+    if let Some(description) = &details.description {
+        lines.push(format!("DESCRIPTION:{}", ics_text(description)));
+    }
+    if let Some(location) = &details.location {
+        lines.push(format!("LOCATION:{}", ics_text(location)));
+    }
+    if let Some(organizer) = &details.organizer {
+        lines.push(format!(
+            "ORGANIZER;CN={}:mailto:{}",
+            ics_param(&organizer.name),
+            organizer.email
+        ));
+    }
+    for attendee in &details.attendees {
+        lines.push(format!(
+            "ATTENDEE;CN={};ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:{}",
+            ics_param(&attendee.name),
+            attendee.email
+        ));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    Ok(lines.iter().map(|line| fold(line) + "\r\n").collect())
+}
+
+/// Invitation to `details`' meeting, with the meeting attached as `invite.ics`
+pub fn invite_email(details: &InviteDetails, stamp: &str) -> Result<EmailMessage, String> {
+    let ics = invite_ics(details, stamp)?;
+    let when = if details.all_day {
+        format!(
+            "{} (all day)",
+            details.start.get(..10).unwrap_or(&details.start)
+        )
+    } else {
+        format!(
+            "{} to {} ({})",
+            details.start.replacen('T', " ", 1),
+            details.end.replacen('T', " ", 1),
+            details.timezone
+        )
+    };
+    let mut text = match &details.organizer {
+        Some(organizer) => format!("{} invited you to {}.\n\n", organizer.name, details.title),
+        None => format!("You are invited to {}.\n\n", details.title),
+    };
+    text.push_str(&format!("When: {when}\n"));
+    if let Some(location) = &details.location {
+        text.push_str(&format!("Where: {location}\n"));
+    }
+    if let Some(description) = &details.description {
+        text.push_str(&format!("\n{description}\n"));
+    }
+    text.push_str("\nOpen the attached invite to add it to your calendar.\n");
+    Ok(EmailMessage {
+        kind: EmailKind::Invite,
+        subject: format!("Invitation: {}", details.title),
+        text,
+        attachments: vec![Attachment {
+            filename: "invite.ics".to_string(),
+            content_type: "text/calendar; method=REQUEST; charset=UTF-8".to_string(),
+            content: ics.into_bytes(),
+        }],
+    })
+}
+
+/// A generated report, attached
+pub fn report_email(title: &str, generated_at: &str, attachment: Attachment) -> EmailMessage {
+    EmailMessage {
+        kind: EmailKind::Report,
+        subject: format!("Report: {title}"),
+        text: format!(
+            "{title}, generated {generated_at}, is attached as {}.\n",
+            attachment.filename
+        ),
+        attachments: vec![attachment],
+    }
+}
+
+/// `author` mentioned the recipient in `conversation`
+pub fn mention_email(author: &str, conversation: &str, body: &str) -> EmailMessage {
+    let quoted: String = body.lines().map(|line| format!("> {line}\n")).collect();
+    EmailMessage {
+        kind: EmailKind::Mention,
+        subject: format!("{author} mentioned you in {conversation}"),
+        text: format!(
+            "{author} mentioned you in {conversation}:\n\n{quoted}\nReply in Rubigo's chat.\n"
+        ),
+        attachments: Vec::new(),
+    }
+}
+
+/// People in `names` that `body` mentions as `@Name` (any case), in the
+/// order of `names` and without repeats
+///
+/// Longer names win, so `@Ada Lovelace` doesn't also mention an `Ada`.
+pub fn mentioned(body: &str, names: &[String]) -> Vec<String> {
+    let lower = body.to_lowercase();
+    let mut by_length: Vec<&String> = names.iter().collect();
+    by_length.sort_by_key(|name| std::cmp::Reverse(name.len()));
+
+    let mut claimed: Vec<std::ops::Range<usize>> = Vec::new();
+    let mut found: Vec<&String> = Vec::new();
+    for name in by_length {
+        let needle = format!("@{}", name.to_lowercase());
+        for (at, _) in lower.match_indices(&needle) {
+            let end = at + needle.len();
+            let whole = !lower[end..].starts_with(|c: char| c.is_alphanumeric());
+            let free = !claimed.iter().any(|range| range.contains(&at));
+            if whole && free {
+                claimed.push(at..end);
+                if !found.contains(&name) {
+                    found.push(name);
+                }
+            }
+        }
+    }
+    names
+        .iter()
+        .filter(|name| found.contains(name))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meeting(all_day: bool) -> InviteDetails {
+        InviteDetails {
+            uid: "meeting-1@rubigo".to_string(),
+            title: "Design review, part 2".to_string(),
+            description: Some("Bring notes;\nand questions".to_string()),
+            start: "2026-03-02T09:00:00".to_string(),
+            end: "2026-03-02T10:30".to_string(),
+            all_day,
+            timezone: "America/New_York".to_string(),
+            utc_start: "2026-03-02T14:00:00Z".parse().ok(),
+            utc_end: "2026-03-02T15:30:00Z".parse().ok(),
+            location: Some("Room 4".to_string()),
+            organizer: Some(Attendee {
+                name: "Grace Hopper".to_string(),
+                email: "grace@example.com".to_string(),
+            }),
+            attendees: vec![Attendee {
+                name: "Ada Lovelace".to_string(),
+                email: "ada@example.com".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn invites_carry_an_icalendar_request() {
+        let ics = invite_ics(&meeting(false), "20260301T120000Z").unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("METHOD:REQUEST\r\n"));
+        assert!(ics.contains("DTSTART:20260302T140000Z\r\n"));
+        assert!(ics.contains("DTEND:20260302T153000Z\r\n"));
+        assert!(!ics.contains("TZID"));
+        assert!(ics.contains("SUMMARY:Design review\\, part 2\r\n"));
+        assert!(ics.contains("DESCRIPTION:Bring notes\\;\\nand questions\r\n"));
+        assert!(ics.contains("mailto:ada@example.com"));
+        assert!(ics.lines().all(|line| line.len() <= 75));
+
+        let ics = invite_ics(&meeting(true), "20260301T120000Z").unwrap();
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260302\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20260303\r\n"));
+
+        let email = invite_email(&meeting(false), "20260301T120000Z").unwrap();
+        assert_eq!(email.subject, "Invitation: Design review, part 2");
+        assert!(email.text.starts_with("Grace Hopper invited you"));
+        assert_eq!(email.attachments[0].filename, "invite.ics");
+
+        let mut broken = meeting(false);
+        broken.utc_start = None;
+        assert!(invite_ics(&broken, "20260301T120000Z").is_err());
+        let mut broken = meeting(true);
+        broken.start = "soon".to_string();
+        assert!(invite_ics(&broken, "20260301T120000Z").is_err());
+    }
+
+    #[test]
+    fn names_are_quoted_in_parameters() {
+        let mut details = meeting(false);
+        details.organizer = Some(Attendee {
+            name: "Doe, Jane".to_string(),
+            email: "jane@example.com".to_string(),
+        });
+        details.attendees[0].name = "Bob \"The Builder\"".to_string();
+        let ics = invite_ics(&details, "20260301T120000Z").unwrap();
+        assert!(ics.contains("ORGANIZER;CN=\"Doe, Jane\":mailto:jane@example.com\r\n"));
+        assert!(ics.contains("ATTENDEE;CN=\"Bob ^'The Builder^'\";ROLE="));
+    }
+
+    #[test]
+    fn mentions_and_preferences() {
+        let names: Vec<String> = ["Ada", "Ada Lovelace", "Grace Hopper"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(
+            mentioned("@grace hopper and @Ada Lovelace, see this", &names),
+            vec!["Ada Lovelace", "Grace Hopper"]
+        );
+        assert_eq!(mentioned("@Ada: and @ada again", &names), vec!["Ada"]);
+        assert!(mentioned("@Adam, email ada@example.com", &names).is_empty());
+
+        let email = mention_email("Ada", "Ops", "@Grace Hopper\nlook");
+        assert!(email.text.contains("> @Grace Hopper\n> look\n"));
+
+        let prefs = EmailPreferences {
+            mentions: false,
+            ..Default::default()
+        };
+        assert!(prefs.allows(EmailKind::Invite));
+        assert!(!prefs.allows(EmailKind::Mention));
+    }
+}
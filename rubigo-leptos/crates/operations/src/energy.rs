@@ -0,0 +1,168 @@
+//! Energy accounting
+//!
+//! Power drawn by simulated components and the energy it adds up to. Each
+//! component draws between an idle and a full-load figure in proportion to
+//! its load; a [`Meter`] records every component's draw once per simulation
+//! step, each step standing for [`STEP_SECONDS`] of operation, and sums it
+//! into watt-hours per component or per site with [`Meter::by_site`].
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Operating time one simulation step stands for
+pub const STEP_SECONDS: f64 = 60.0;
+
+/// Watts drawn between `idle_w` and `max_w` at `load`, from 0 to 1
+pub fn draw(idle_w: f64, max_w: f64, load: f64) -> f64 {
+    idle_w + (max_w - idle_w).max(0.0) * load.clamp(0.0, 1.0)
+}
+
+/// Load of each node as its share of the busiest node's link count
+pub fn loads(links: &BTreeMap<u32, f64>) -> BTreeMap<u32, f64> {
+    let busiest = links.values().copied().fold(0.0, f64::max);
+    links
+        .iter()
+        .map(|(&node, &count)| {
+            let load = if busiest > 0.0 { count / busiest } else { 0.0 };
+            (node, load)
+        })
+        .collect()
+}
+
+/// Energy drawn by the components of one site over a run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SiteEnergy {
+    pub site: String,
+    pub components: usize,
+    pub energy_wh: f64,
+    pub average_w: f64,
+    /// Highest total draw of the site's components in any one step
+    pub peak_w: f64,
+}
+
+/// Watts drawn by each component, one reading per step
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Meter {
+    readings: BTreeMap<u32, Vec<f64>>,
+    steps: usize,
+}
+
+impl Meter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one step, with the watts drawn by each component in it
+    pub fn step(&mut self, watts: impl IntoIterator<Item = (u32, f64)>) {
+        for (node, w) in watts {
+            let series = self.readings.entry(node).or_default();
+            series.resize(self.steps, 0.0);
+            series.push(w);
+        }
+        self.steps += 1;
+        for series in self.readings.values_mut() {
+            series.resize(self.steps, 0.0);
+        }
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Watt-hours drawn by `node` over the steps recorded
+    pub fn energy_wh(&self, node: u32) -> f64 {
+        self.readings.get(&node).map_or(0.0, |series| {
+            series.iter().sum::<f64>() * STEP_SECONDS / 3600.0
+        })
+    }
+
+    /// Average watts drawn by `node`
+    pub fn average_w(&self, node: u32) -> f64 {
+        match self.steps {
+            0 => 0.0,
+            steps => self
+                .readings
+                .get(&node)
+                .map_or(0.0, |s| s.iter().sum::<f64>() / steps as f64),
+        }
+    }
+
+    /// Total watts drawn in each step, as a trace
+    pub fn total_w(&self) -> Vec<f64> {
+        (0..self.steps)
+            .map(|step| self.readings.values().map(|series| series[step]).sum())
+            .collect()
+    }
+
+    /// Energy per site, naming each component's site with `site_of`, in
+    /// site order
+    pub fn by_site(&self, site_of: impl Fn(u32) -> String) -> Vec<SiteEnergy> {
+        let mut sites: BTreeMap<String, (usize, Vec<f64>)> = BTreeMap::new();
+        for (&node, series) in &self.readings {
+            let (components, total) = sites
+                .entry(site_of(node))
+                .or_insert_with(|| (0, vec![0.0; self.steps]));
+            *components += 1;
+            for (sum, w) in total.iter_mut().zip(series) {
+                *sum += w;
+            }
+        }
+        sites
+            .into_iter()
+            .map(|(site, (components, total))| {
+                let sum: f64 = total.iter().sum();
+                SiteEnergy {
+                    site,
+                    components,
+                    energy_wh: sum * STEP_SECONDS / 3600.0,
+                    average_w: if self.steps > 0 {
+                        sum / self.steps as f64
+                    } else {
+                        0.0
+                    },
+                    peak_w: total.iter().copied().fold(0.0, f64::max),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_follows_load() {
+        assert_eq!(draw(100.0, 300.0, 0.0), 100.0);
+        assert_eq!(draw(100.0, 300.0, 0.5), 200.0);
+        assert_eq!(draw(100.0, 300.0, 2.0), 300.0);
+        assert_eq!(draw(100.0, 50.0, 1.0), 100.0);
+
+        let links = BTreeMap::from([(1, 4.0), (2, 1.0), (3, 0.0)]);
+        assert_eq!(
+            loads(&links),
+            BTreeMap::from([(1, 1.0), (2, 0.25), (3, 0.0)])
+        );
+    }
+
+    #[test]
+    fn meter_sums_steps_by_site() {
+        let mut meter = Meter::new();
+        meter.step([(1, 120.0), (2, 60.0)]);
+        meter.step([(1, 240.0), (2, 60.0), (3, 30.0)]);
+        assert_eq!(meter.steps(), 2);
+        assert_eq!(meter.total_w(), vec![180.0, 330.0]);
+        // Two minutes at an average of 180 W
+        assert_eq!(meter.energy_wh(1), 6.0);
+        assert_eq!(meter.average_w(3), 15.0);
+
+        let sites = meter.by_site(|node| if node == 3 { "B" } else { "A" }.to_string());
+        assert_eq!(sites.len(), 2);
+        assert_eq!((sites[0].site.as_str(), sites[0].components), ("A", 2));
+        assert_eq!(sites[0].energy_wh, 8.0);
+        assert_eq!(sites[0].average_w, 240.0);
+        assert_eq!(sites[0].peak_w, 300.0);
+        assert_eq!((sites[1].average_w, sites[1].peak_w), (15.0, 30.0));
+    }
+}
@@ -0,0 +1,293 @@
+//! Infrastructure-as-code documents
+//!
+//! The topology and site hierarchy as declarative resources, laid out like
+//! Terraform's JSON configuration syntax:
+//!
+//! ```text
+//! { "resource": { "rubigo_rack": { "hq_rack_1": { "name": "Rack 1", "space": "${rubigo_space.hq_hall.id}" } } } }
+//! ```
+//!
+//! Each resource has a kind from [`KINDS`], an address unique within its
+//! kind, and attributes; a resource points at another with a [`reference`]
+//! string. The same document is written as TOML by nesting tables the same
+//! way, so both formats carry exactly what [`to_document`] produces and
+//! read back through [`from_document`].
+
+use std::collections::BTreeSet;
+
+use serde_json::{json, Map, Value};
+
+/// Provider name resources are prefixed with
+pub const PROVIDER: &str = "rubigo";
+
+/// Resource kinds, parents before the resources that refer to them
+pub const KINDS: [&str; 8] = [
+    "rubigo_site",
+    "rubigo_building",
+    "rubigo_floor",
+    "rubigo_space",
+    "rubigo_rack",
+    "rubigo_component",
+    "rubigo_device",
+    "rubigo_connection",
+];
+
+/// One declared resource
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resource {
+    pub kind: String,
+    pub address: String,
+    pub attributes: Map<String, Value>,
+}
+
+impl Resource {
+    pub fn new(kind: &str, address: String) -> Self {
+        Self {
+            kind: kind.to_string(),
+            address,
+            attributes: Map::new(),
+        }
+    }
+
+    /// Set `key`, leaving out `null`s so the document stays valid TOML
+    pub fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        let value = without_nulls(value.into());
+        if !value.is_null() {
+            self.attributes.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    /// `kind.address`, as used in messages
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.kind, self.address)
+    }
+
+    pub fn text(&self, key: &str) -> Result<&str, String> {
+        self.attributes
+            .get(key)
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("{} needs a text `{}`", self.name(), key))
+    }
+
+    /// The resource `key` refers to, as `(kind, address)`
+    pub fn reference(&self, key: &str) -> Result<(&str, &str), String> {
+        let text = self.text(key)?;
+        parse_reference(text)
+            .ok_or_else(|| format!("{}: `{}` is not a reference: {}", self.name(), key, text))
+    }
+}
+
+/// `value` with `null` members and elements dropped, at any depth
+pub fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, without_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .filter(|v| !v.is_null())
+                .map(without_nulls)
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// A resource address for `name`: lowercase letters, digits and
+/// underscores, not starting with a digit
+pub fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    let slug = slug.trim_matches('_');
+    match slug.chars().next() {
+        None => "unnamed".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{slug}"),
+        Some(_) => slug.to_string(),
+    }
+}
+
+/// Hands out addresses unique within each kind
+#[derive(Debug, Default)]
+pub struct Addresses {
+    taken: BTreeSet<(String, String)>,
+}
+
+impl Addresses {
+    /// The slug of `name`, numbered from `_2` when already taken
+    pub fn assign(&mut self, kind: &str, name: &str) -> String {
+        let base = slug(name);
+        let mut address = base.clone();
+        let mut n = 1;
+        while !self.taken.insert((kind.to_string(), address.clone())) {
+            n += 1;
+            address = format!("{base}_{n}");
+        }
+        address
+    }
+}
+
+/// Interpolation pointing at the resource `kind.address`
+pub fn reference(kind: &str, address: &str) -> String {
+    format!("${{{kind}.{address}.id}}")
+}
+
+/// `(kind, address)` of a [`reference`]
+pub fn parse_reference(text: &str) -> Option<(&str, &str)> {
+    let inner = text.strip_prefix("${")?.strip_suffix(".id}")?;
+    let (kind, address) = inner.split_once('.')?;
+    (!kind.is_empty() && !address.is_empty() && !address.contains('.')).then_some((kind, address))
+}
+
+/// The document holding `resources`, with the provider declared
+pub fn to_document(resources: &[Resource]) -> Value {
+    let mut kinds = Map::new();
+    for resource in resources {
+        let by_address = kinds
+            .entry(resource.kind.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(by_address) = by_address {
+            by_address.insert(
+                resource.address.clone(),
+                Value::Object(resource.attributes.clone()),
+            );
+        }
+    }
+    json!({
+        "terraform": { "required_providers": { PROVIDER: { "source": "kstewart83/rubigo" } } },
+        "resource": kinds,
+    })
+}
+
+/// Resources in `document`, parents first
+///
+/// Blocks other than `resource` are ignored. Unknown kinds and references
+/// to resources the document does not declare are errors.
+pub fn from_document(document: &Value) -> Result<Vec<Resource>, String> {
+    let Some(kinds) = document.get("resource") else {
+        return Ok(Vec::new());
+    };
+    let kinds = kinds
+        .as_object()
+        .ok_or("`resource` must map kinds to resources")?;
+    let mut resources = Vec::new();
+    for (kind, by_address) in kinds {
+        if !KINDS.contains(&kind.as_str()) {
+            return Err(format!(
+                "Unknown resource kind '{}', expected one of {}",
+                kind,
+                KINDS.join(", ")
+            ));
+        }
+        let by_address = by_address
+            .as_object()
+            .ok_or_else(|| format!("`{kind}` must map addresses to attributes"))?;
+        for (address, attributes) in by_address {
+            let attributes = attributes
+                .as_object()
+                .ok_or_else(|| format!("{kind}.{address} must be a table of attributes"))?;
+            resources.push(Resource {
+                kind: kind.clone(),
+                address: address.clone(),
+                attributes: attributes.clone(),
+            });
+        }
+    }
+    resources.sort_by_key(|r| KINDS.iter().position(|k| *k == r.kind));
+
+    let declared: BTreeSet<(&str, &str)> = resources
+        .iter()
+        .map(|r| (r.kind.as_str(), r.address.as_str()))
+        .collect();
+    for resource in &resources {
+        for value in resource.attributes.values() {
+            let Some((kind, address)) = value.as_str().and_then(parse_reference) else {
+                continue;
+            };
+            if !declared.contains(&(kind, address)) {
+                return Err(format!(
+                    "{} refers to {}.{}, which is not declared",
+                    resource.name(),
+                    kind,
+                    address
+                ));
+            }
+        }
+    }
+    Ok(resources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_and_references() {
+        assert_eq!(slug("HQ Rack #1"), "hq_rack_1");
+        assert_eq!(slug("42nd St"), "_42nd_st");
+        assert_eq!(slug("--"), "unnamed");
+        let mut addresses = Addresses::default();
+        assert_eq!(addresses.assign("rubigo_rack", "Rack 1"), "rack_1");
+        assert_eq!(addresses.assign("rubigo_rack", "rack-1"), "rack_1_2");
+        assert_eq!(addresses.assign("rubigo_site", "Rack 1"), "rack_1");
+
+        let r = reference("rubigo_space", "hq_hall");
+        assert_eq!(r, "${rubigo_space.hq_hall.id}");
+        assert_eq!(parse_reference(&r), Some(("rubigo_space", "hq_hall")));
+        assert_eq!(parse_reference("rubigo_space.hq_hall"), None);
+        assert_eq!(parse_reference("${rubigo_space.id}"), None);
+    }
+
+    #[test]
+    fn documents_round_trip_in_dependency_order() {
+        let resources = vec![
+            Resource::new("rubigo_rack", "r1".to_string())
+                .with("name", "Rack 1")
+                .with("height_u", 42)
+                .with("space", reference("rubigo_space", "hall")),
+            Resource::new("rubigo_space", "hall".to_string())
+                .with("name", "Hall")
+                .with("space_type", Value::Null)
+                .with("bounds", json!({ "x": 1.0, "tag": null })),
+        ];
+        let document = to_document(&resources);
+        assert_eq!(
+            document["resource"]["rubigo_rack"]["r1"]["space"],
+            "${rubigo_space.hall.id}"
+        );
+        assert!(document["resource"]["rubigo_space"]["hall"]
+            .get("space_type")
+            .is_none());
+        assert_eq!(
+            document["resource"]["rubigo_space"]["hall"]["bounds"],
+            json!({ "x": 1.0 })
+        );
+
+        let read = from_document(&document).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0], resources[1]);
+        assert_eq!(read[1], resources[0]);
+        assert_eq!(read[1].reference("space"), Ok(("rubigo_space", "hall")));
+        assert!(read[1].text("label").is_err());
+
+        let dangling = to_document(&resources[..1]);
+        assert_eq!(
+            from_document(&dangling),
+            Err("rubigo_rack.r1 refers to rubigo_space.hall, which is not declared".to_string())
+        );
+        let unknown = json!({ "resource": { "aws_instance": { "web": {} } } });
+        assert!(from_document(&unknown)
+            .unwrap_err()
+            .starts_with("Unknown resource kind"));
+    }
+}
@@ -0,0 +1,230 @@
+//! External Identity
+//!
+//! Rules shared by OIDC sign-in and the LDAP directory sync: which ID
+//! token claims are acceptable, who the token or directory entry
+//! describes, and which role their groups give them. Groups are matched by
+//! name, so an LDAP group's full DN (`cn=IT Admins,ou=groups,...`) matches
+//! a mapping for either the DN or `IT Admins`.
+//!
+//! A first sign-in is only matched to an existing person by email when the
+//! provider vouches for the address (`email_verified`); otherwise an admin
+//! has to link the identity first (see [`existing_person`]).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Seconds of clock difference tolerated when checking token expiry
+pub const CLOCK_SKEW_SECS: i64 = 60;
+
+/// A person as an identity provider or directory describes them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalUser {
+    /// `oidc` or `ldap`
+    pub provider: String,
+    /// Stable ID at the provider: the `sub` claim or the entry's DN
+    pub subject: String,
+    pub name: String,
+    pub email: String,
+    /// Whether the provider checked that `email` is theirs; directory
+    /// entries are maintained by admins, so always
+    pub email_verified: bool,
+    pub title: Option<String>,
+    pub department: Option<String>,
+    pub groups: Vec<String>,
+}
+
+/// Check an ID token's claims were issued to us, for this sign-in, and
+/// are still current
+///
+/// Its signature is checked by the server, against the provider's keys.
+pub fn check_id_token(
+    claims: &Value,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+    now: i64,
+) -> Result<(), String> {
+    if claims["iss"].as_str().map(|iss| iss.trim_end_matches('/'))
+        != Some(issuer.trim_end_matches('/'))
+    {
+        return Err("ID token is from another issuer".to_string());
+    }
+    let audience = match &claims["aud"] {
+        Value::String(aud) => aud == client_id,
+        Value::Array(auds) => auds.iter().any(|aud| aud.as_str() == Some(client_id)),
+        _ => false,
+    };
+    if !audience {
+        return Err("ID token is for another client".to_string());
+    }
+    match claims["exp"].as_i64() {
+        Some(exp) if exp + CLOCK_SKEW_SECS > now => {}
+        Some(_) => return Err("ID token has expired".to_string()),
+        None => return Err("ID token has no expiry".to_string()),
+    }
+    if claims["nonce"].as_str() != Some(nonce) {
+        return Err("ID token is from another sign-in".to_string());
+    }
+    Ok(())
+}
+
+/// The person described by ID token `claims`, with groups from `groups_claim`
+pub fn user_from_claims(claims: &Value, groups_claim: &str) -> Result<ExternalUser, String> {
+    let text = |claim: &str| {
+        claims[claim]
+            .as_str()
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let subject = text("sub").ok_or("ID token has no subject")?;
+    let email = text("email").ok_or("ID token has no email; request the email scope")?;
+    let name = text("name")
+        .or_else(|| match (text("given_name"), text("family_name")) {
+            (Some(given), Some(family)) => Some(format!("{given} {family}")),
+            _ => None,
+        })
+        .or_else(|| text("preferred_username"))
+        .unwrap_or_else(|| email.clone());
+    let groups = match &claims[groups_claim] {
+        Value::Array(groups) => groups
+            .iter()
+            .filter_map(|g| g.as_str())
+            .map(str::to_string)
+            .collect(),
+        Value::String(group) => vec![group.clone()],
+        _ => Vec::new(),
+    };
+    // Some providers send the flag as a string
+    let email_verified = match &claims["email_verified"] {
+        Value::Bool(verified) => *verified,
+        Value::String(verified) => verified == "true",
+        _ => false,
+    };
+    Ok(ExternalUser {
+        provider: "oidc".to_string(),
+        subject,
+        name,
+        email,
+        email_verified,
+        title: text("title"),
+        department: text("department"),
+        groups,
+    })
+}
+
+/// The person `user` signs in as: the one their identity is linked to,
+/// otherwise the one with their email if the provider verified it, and
+/// `Ok(None)` when a new person should be created
+///
+/// An unverified email matching someone is refused, since anyone able to
+/// get a token for that address would otherwise take over their account.
+pub fn existing_person<P>(
+    user: &ExternalUser,
+    linked: Option<P>,
+    by_email: Option<P>,
+) -> Result<Option<P>, String> {
+    match (linked, by_email) {
+        (Some(person), _) => Ok(Some(person)),
+        (None, Some(person)) if user.email_verified => Ok(Some(person)),
+        (None, Some(_)) => Err(format!(
+            "{} is not verified by the identity provider; an admin has to link this sign-in to its person",
+            user.email
+        )),
+        (None, None) => Ok(None),
+    }
+}
+
+/// A group's name: the first RDN's value for a DN, otherwise the group as given
+pub fn group_name(group: &str) -> &str {
+    match group.split_once(',') {
+        Some((rdn, _)) => rdn.split_once('=').map_or(group, |(_, value)| value.trim()),
+        None => group,
+    }
+}
+
+/// Role given by the first of `mappings` (group, role) matching one of `groups`
+pub fn role_for<'a>(
+    groups: &[String],
+    mappings: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Option<&'a str> {
+    mappings.into_iter().find_map(|(group, role)| {
+        groups
+            .iter()
+            .any(|g| g.eq_ignore_ascii_case(group) || group_name(g).eq_ignore_ascii_case(group))
+            .then_some(role)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn id_token_claims_are_checked() {
+        let claims = json!({
+            "iss": "https://id.example.com/",
+            "aud": ["rubigo", "other"],
+            "exp": 1_000,
+            "nonce": "n-1",
+            "sub": "u-42",
+            "email": "ada@example.com",
+            "given_name": "Ada",
+            "family_name": "Lovelace",
+            "groups": ["engineers"],
+        });
+        assert_eq!(
+            check_id_token(&claims, "https://id.example.com", "rubigo", "n-1", 900),
+            Ok(())
+        );
+        assert!(
+            check_id_token(&claims, "https://other.example.com", "rubigo", "n-1", 900).is_err()
+        );
+        assert!(check_id_token(&claims, "https://id.example.com", "someone", "n-1", 900).is_err());
+        assert!(check_id_token(&claims, "https://id.example.com", "rubigo", "n-2", 900).is_err());
+        assert!(check_id_token(&claims, "https://id.example.com", "rubigo", "n-1", 1_100).is_err());
+
+        let user = user_from_claims(&claims, "groups").unwrap();
+        assert_eq!(user.name, "Ada Lovelace");
+        assert_eq!(user.subject, "u-42");
+        assert_eq!(user.groups, ["engineers"]);
+        assert!(!user.email_verified);
+        assert!(user_from_claims(&json!({ "sub": "u-1" }), "groups").is_err());
+    }
+
+    #[test]
+    fn unverified_emails_never_match_an_existing_person() {
+        let claims = json!({ "sub": "u-7", "email": "ada@example.com" });
+        let unverified = user_from_claims(&claims, "groups").unwrap();
+        assert!(existing_person(&unverified, None, Some("ada")).is_err());
+        assert_eq!(
+            existing_person(&unverified, Some("ada"), Some("ada")),
+            Ok(Some("ada"))
+        );
+        assert_eq!(existing_person::<&str>(&unverified, None, None), Ok(None));
+
+        for flag in [json!(true), json!("true")] {
+            let mut claims = claims.clone();
+            claims["email_verified"] = flag;
+            let verified = user_from_claims(&claims, "groups").unwrap();
+            assert_eq!(
+                existing_person(&verified, None, Some("ada")),
+                Ok(Some("ada"))
+            );
+        }
+    }
+
+    #[test]
+    fn first_matching_group_gives_the_role() {
+        let mappings = [("IT Admins", "ITAdmin"), ("engineers", "Engineer")];
+        let groups = vec![
+            "engineers".to_string(),
+            "cn=IT Admins,ou=groups,dc=example,dc=com".to_string(),
+        ];
+        assert_eq!(role_for(&groups, mappings), Some("ITAdmin"));
+        assert_eq!(role_for(&groups[..1], mappings), Some("Engineer"));
+        assert_eq!(role_for(&["sales".to_string()], mappings), None);
+        assert_eq!(group_name("cn=IT Admins,ou=groups"), "IT Admins");
+        assert_eq!(group_name("engineers"), "engineers");
+    }
+}
@@ -0,0 +1,22 @@
+//! Operations - Server-Side Rules
+//!
+//! The parts of the server's scheduled tasks, integrations and simulation
+//! analysis that need no I/O: cron schedules, webhook filters and retries,
+//! email templates, identity claims, NetBox and infrastructure-as-code
+//! mapping, live telemetry decoding, topology diffs, run comparison,
+//! capacity, energy and on-call rotations. `gui-server` does the I/O around
+//! them. They live apart from `actions` so that crate stays small enough
+//! for the WASM clients.
+
+pub mod capacity;
+pub mod comparison;
+pub mod email;
+pub mod energy;
+pub mod iac;
+pub mod identity;
+pub mod netbox;
+pub mod oncall;
+pub mod schedule;
+pub mod telemetry;
+pub mod topology;
+pub mod webhooks;
@@ -0,0 +1,273 @@
+//! NetBox import rules
+//!
+//! How objects from a NetBox instance's REST API become Rubigo records.
+//! Each Rubigo field is read from a dotted path into the NetBox JSON (see
+//! [`FIELDS`]), which `[runtime.netbox.fields]` can point elsewhere, and
+//! NetBox site statuses are translated with [`STATUSES`]. Whether an object
+//! is created, updated or left alone is decided by [`plan`], which is what
+//! makes repeated syncs idempotent.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+/// Rubigo fields with the NetBox path each is read from by default
+pub const FIELDS: [(&str, &str); 13] = [
+    ("site_name", "name"),
+    ("site_status", "status.value"),
+    ("site_lat", "latitude"),
+    ("site_lon", "longitude"),
+    ("rack_name", "name"),
+    ("rack_height", "u_height"),
+    ("building", "location.name"),
+    ("floor", ""),
+    ("space", "location.name"),
+    ("device_name", "name"),
+    ("device_position", "position"),
+    ("cable_label", "label"),
+    ("cable_length", "length"),
+];
+
+/// NetBox site statuses with the Rubigo status each becomes by default
+pub const STATUSES: [(&str, &str); 5] = [
+    ("planned", "planned"),
+    ("staging", "planned"),
+    ("active", "active"),
+    ("decommissioning", "active"),
+    ("retired", "inactive"),
+];
+
+/// Rack height used when NetBox does not give one
+pub const DEFAULT_RACK_HEIGHT: u8 = 42;
+
+/// The value at a dotted `path` in `object`, e.g. `status.value`
+///
+/// Numeric segments index into arrays. An empty path or a `null` value
+/// gives `None`.
+pub fn lookup<'a>(object: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return None;
+    }
+    let value = path
+        .split('.')
+        .try_fold(object, |value, segment| match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => value.get(segment),
+        })?;
+    (!value.is_null()).then_some(value)
+}
+
+/// Field paths and status names in effect, defaults merged with overrides
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldMap {
+    paths: BTreeMap<String, String>,
+    statuses: BTreeMap<String, String>,
+}
+
+impl Default for FieldMap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new(), &BTreeMap::new())
+    }
+}
+
+impl FieldMap {
+    /// Defaults with `fields` and `statuses` laid over them
+    pub fn new(fields: &BTreeMap<String, String>, statuses: &BTreeMap<String, String>) -> Self {
+        let mut paths: BTreeMap<String, String> = FIELDS
+            .iter()
+            .map(|&(field, path)| (field.to_string(), path.to_string()))
+            .collect();
+        paths.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+        let mut names: BTreeMap<String, String> = STATUSES
+            .iter()
+            .map(|&(from, to)| (from.to_string(), to.to_string()))
+            .collect();
+        names.extend(statuses.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Self {
+            paths,
+            statuses: names,
+        }
+    }
+
+    /// Fields mapped to something other than a known Rubigo field
+    pub fn unknown_fields(&self) -> Vec<&str> {
+        self.paths
+            .keys()
+            .map(String::as_str)
+            .filter(|field| !FIELDS.iter().any(|(known, _)| known == field))
+            .collect()
+    }
+
+    fn value<'a>(&self, object: &'a Value, field: &str) -> Option<&'a Value> {
+        lookup(
+            object,
+            self.paths
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// `field` of `object` as text; numbers are written out, blanks are `None`
+    pub fn text(&self, object: &Value, field: &str) -> Option<String> {
+        let text = match self.value(object, field)? {
+            Value::String(s) => s.trim().to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            _ => return None,
+        };
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// `field` of `object` as a number; NetBox sends decimals as strings
+    pub fn number(&self, object: &Value, field: &str) -> Option<f64> {
+        match self.value(object, field)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Rubigo status for a NetBox site status; unknown ones pass through
+    pub fn status(&self, netbox: &str) -> String {
+        self.statuses
+            .get(netbox)
+            .cloned()
+            .unwrap_or_else(|| netbox.to_string())
+    }
+}
+
+/// The NetBox id of `object`
+pub fn object_id(object: &Value) -> Option<u64> {
+    object.get("id").and_then(Value::as_u64)
+}
+
+/// The NetBox id of the object `object` refers to through `key`, e.g. a
+/// rack's `site`
+pub fn related_id(object: &Value, key: &str) -> Option<u64> {
+    object.get(key).and_then(object_id)
+}
+
+/// One end of a NetBox cable, on a device interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CableEnd {
+    /// NetBox id of the interface
+    pub interface: u64,
+    pub device: u64,
+    pub name: String,
+}
+
+/// The interfaces a cable connects, or `None` when either end is not a
+/// device interface (circuits, power and patch panel ports are not imported)
+pub fn cable_ends(cable: &Value) -> Option<(CableEnd, CableEnd)> {
+    let end = |side: &str| -> Option<CableEnd> {
+        let termination = lookup(cable, &format!("{side}_terminations.0"))?;
+        if termination.get("object_type").and_then(Value::as_str) != Some("dcim.interface") {
+            return None;
+        }
+        let interface = termination.get("object")?;
+        Some(CableEnd {
+            interface: termination
+                .get("object_id")
+                .and_then(Value::as_u64)
+                .or_else(|| object_id(interface))?,
+            device: related_id(interface, "device")?,
+            name: interface.get("name").and_then(Value::as_str)?.to_string(),
+        })
+    };
+    Some((end("a")?, end("b")?))
+}
+
+/// What a sync does with one NetBox object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    Create,
+    Update,
+    Skip,
+}
+
+/// Objects not imported before are created; imported ones are refreshed
+/// only in re-sync mode
+pub fn plan(linked: bool, resync: bool) -> SyncAction {
+    match (linked, resync) {
+        (false, _) => SyncAction::Create,
+        (true, true) => SyncAction::Update,
+        (true, false) => SyncAction::Skip,
+    }
+}
+
+/// Imported objects that NetBox no longer has, which a re-sync removes
+pub fn stale(linked: impl IntoIterator<Item = u64>, seen: &BTreeSet<u64>) -> Vec<u64> {
+    linked.into_iter().filter(|id| !seen.contains(id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fields_follow_paths_and_overrides() {
+        let site = json!({
+            "id": 7,
+            "name": "dc-east",
+            "facility": "East Data Centre",
+            "status": {"value": "staging", "label": "Staging"},
+            "latitude": "40.712800",
+            "longitude": -74.006,
+            "tenant": null,
+        });
+        let defaults = FieldMap::default();
+        assert_eq!(
+            defaults.text(&site, "site_name").as_deref(),
+            Some("dc-east")
+        );
+        assert_eq!(
+            defaults.status(&defaults.text(&site, "site_status").unwrap()),
+            "planned"
+        );
+        assert_eq!(defaults.number(&site, "site_lat"), Some(40.7128));
+        assert_eq!(defaults.number(&site, "site_lon"), Some(-74.006));
+        assert_eq!(defaults.text(&site, "floor"), None);
+
+        let fields = BTreeMap::from([
+            ("site_name".to_string(), "facility".to_string()),
+            ("site_tenant".to_string(), "tenant".to_string()),
+        ]);
+        let statuses = BTreeMap::from([("staging".to_string(), "active".to_string())]);
+        let mapped = FieldMap::new(&fields, &statuses);
+        assert_eq!(
+            mapped.text(&site, "site_name").as_deref(),
+            Some("East Data Centre")
+        );
+        assert_eq!(mapped.status("staging"), "active");
+        assert_eq!(mapped.status("offline"), "offline");
+        assert_eq!(mapped.unknown_fields(), vec!["site_tenant"]);
+        assert_eq!(lookup(&site, "tenant"), None);
+    }
+
+    #[test]
+    fn cables_and_resync_plans() {
+        let cable = json!({
+            "id": 3,
+            "a_terminations": [{"object_type": "dcim.interface", "object_id": 11,
+                "object": {"id": 11, "name": "eth0", "device": {"id": 1, "name": "sw1"}}}],
+            "b_terminations": [{"object_type": "dcim.interface", "object_id": 12,
+                "object": {"id": 12, "name": "Gi1/0/1", "device": {"id": 2, "name": "sw2"}}}],
+        });
+        let (a, b) = cable_ends(&cable).unwrap();
+        assert_eq!((a.interface, a.device, a.name.as_str()), (11, 1, "eth0"));
+        assert_eq!((b.interface, b.device, b.name.as_str()), (12, 2, "Gi1/0/1"));
+
+        let circuit = json!({
+            "a_terminations": [{"object_type": "circuits.circuittermination", "object_id": 4, "object": {}}],
+            "b_terminations": cable["b_terminations"].clone(),
+        });
+        assert_eq!(cable_ends(&circuit), None);
+
+        assert_eq!(plan(false, false), SyncAction::Create);
+        assert_eq!(plan(true, false), SyncAction::Skip);
+        assert_eq!(plan(true, true), SyncAction::Update);
+        assert_eq!(stale([1, 2, 3], &BTreeSet::from([1, 3])), vec![2]);
+    }
+}
@@ -0,0 +1,133 @@
+//! On-call Rotations
+//!
+//! Who is on call when. A rotation hands over every `shift_hours` from its
+//! start, through its members in turn and back to the first; nobody is on
+//! call before the start. An escalation policy is a chain of rotations,
+//! each paged once an unacknowledged incident has been open for that
+//! level's delay.
+//!
+//! Times are UTC minutes, as for the scheduled tasks.
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// A rotation through `members`, handing over every `shift_hours`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rotation {
+    pub start: DateTime<Utc>,
+    pub shift_hours: u32,
+    pub members: Vec<String>,
+}
+
+/// One member's turn on call, from `start` up to `end`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shift {
+    pub member: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Rotation {
+    fn shift_length(&self) -> TimeDelta {
+        TimeDelta::hours(i64::from(self.shift_hours))
+    }
+
+    /// The `index`th shift since the start
+    fn shift(&self, index: i64) -> Shift {
+        let start = self.start + TimeDelta::hours(i64::from(self.shift_hours) * index);
+        Shift {
+            member: self.members[index as usize % self.members.len()].clone(),
+            start,
+            end: start + self.shift_length(),
+        }
+    }
+
+    /// The shift covering `at`; `None` before the start, or when the
+    /// rotation has nobody in it or no shift length
+    pub fn shift_at(&self, at: &DateTime<Utc>) -> Option<Shift> {
+        if self.members.is_empty() || self.shift_hours == 0 || *at < self.start {
+            return None;
+        }
+        let since = (*at - self.start).num_minutes();
+        Some(self.shift(since / self.shift_length().num_minutes()))
+    }
+
+    /// Who is on call at `at`
+    pub fn on_call(&self, at: &DateTime<Utc>) -> Option<String> {
+        self.shift_at(at).map(|shift| shift.member)
+    }
+
+    /// Shifts overlapping `from` up to `to`, in order
+    pub fn shifts(&self, from: &DateTime<Utc>, to: &DateTime<Utc>) -> Vec<Shift> {
+        if self.members.is_empty() || self.shift_hours == 0 || to <= from || *to <= self.start {
+            return Vec::new();
+        }
+        let first = (*from - self.start).num_minutes().max(0) / self.shift_length().num_minutes();
+        (first..)
+            .map(|index| self.shift(index))
+            .take_while(|shift| shift.start < *to)
+            .collect()
+    }
+}
+
+/// How many levels of an escalation chain have been paged after an
+/// incident has gone `open_minutes` unacknowledged; `delays` are each
+/// level's minutes after opening, the first normally 0
+pub fn levels_due(delays: &[u32], open_minutes: i64) -> usize {
+    delays
+        .iter()
+        .take_while(|&&delay| i64::from(delay) <= open_minutes)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::parse_minute;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        parse_minute(s).unwrap()
+    }
+
+    #[test]
+    fn members_take_turns_from_the_start() {
+        let rotation = Rotation {
+            start: at("2026-02-27 09:00"),
+            shift_hours: 24,
+            members: vec!["Ann".into(), "Bob".into(), "Cy".into()],
+        };
+        assert_eq!(rotation.on_call(&at("2026-02-27 08:59")), None);
+        assert_eq!(
+            rotation.on_call(&at("2026-02-27 09:00")).as_deref(),
+            Some("Ann")
+        );
+        assert_eq!(
+            rotation.on_call(&at("2026-02-28 23:00")).as_deref(),
+            Some("Bob")
+        );
+        // Across the end of February and back round to the first member
+        assert_eq!(
+            rotation.on_call(&at("2026-03-01 09:00")).as_deref(),
+            Some("Cy")
+        );
+        assert_eq!(
+            rotation.on_call(&at("2026-03-02 10:00")).as_deref(),
+            Some("Ann")
+        );
+
+        let shifts = rotation.shifts(&at("2026-02-28 12:00"), &at("2026-03-02 10:00"));
+        let members: Vec<&str> = shifts.iter().map(|s| s.member.as_str()).collect();
+        assert_eq!(members, vec!["Bob", "Cy", "Ann"]);
+        assert_eq!(shifts[1].start, at("2026-03-01 09:00"));
+        assert_eq!(shifts[1].end, at("2026-03-02 09:00"));
+    }
+
+    #[test]
+    fn escalation_pages_each_level_after_its_delay() {
+        let delays = [0, 15, 45];
+        assert_eq!(levels_due(&delays, 0), 1);
+        assert_eq!(levels_due(&delays, 14), 1);
+        assert_eq!(levels_due(&delays, 15), 2);
+        assert_eq!(levels_due(&delays, 600), 3);
+        assert_eq!(levels_due(&[], 600), 0);
+    }
+}
@@ -0,0 +1,254 @@
+//! Cron Schedules
+//!
+//! Parsing and evaluation of the five-field cron expressions used by the
+//! server's scheduled tasks: `minute hour day-of-month month day-of-week`,
+//! each `*`, a value, a range (`1-5`), a step (`*/15`, `0-30/10`) or a
+//! comma-separated list of those. Days of the week run from 0 (Sunday) to
+//! 6, with 7 also meaning Sunday. `@hourly`, `@daily` (or `@midnight`),
+//! `@weekly` and `@monthly` stand for the usual expressions; `@nightly`,
+//! 02:00 every day, is Rubigo's own and not understood by other crons.
+//!
+//! As in classic cron, when both the day of the month and the day of the
+//! week are restricted, a day matching either one matches. Times are UTC
+//! and carry no seconds; they are written `YYYY-MM-DD HH:MM`
+//! ([`format_minute`], [`parse_minute`]).
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeDelta, Timelike, Utc};
+
+/// Expressions the `@` shorthands stand for
+const ALIASES: [(&str, &str); 6] = [
+    ("@hourly", "0 * * * *"),
+    ("@daily", "0 0 * * *"),
+    ("@midnight", "0 0 * * *"),
+    ("@nightly", "0 2 * * *"),
+    ("@weekly", "0 0 * * 0"),
+    ("@monthly", "0 0 1 * *"),
+];
+
+/// How far ahead [`CronSchedule::next_after`] looks; long enough to reach
+/// a 29 February that also has to fall on a given weekday
+const SEARCH_DAYS: u32 = 366 * 8;
+
+const MINUTE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// The start of the minute `time` falls in
+pub fn minute_of(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.with_second(0)
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(time)
+}
+
+/// `time` written `YYYY-MM-DD HH:MM`
+pub fn format_minute(time: &DateTime<Utc>) -> String {
+    time.format(MINUTE_FORMAT).to_string()
+}
+
+/// Read a UTC `YYYY-MM-DD HH:MM` (or `YYYY-MM-DDTHH:MM`), ignoring seconds
+/// or a zone after it
+pub fn parse_minute(s: &str) -> Result<DateTime<Utc>, String> {
+    let invalid = || format!("{s} is not a YYYY-MM-DD HH:MM time");
+    let text = s.trim().replacen('T', " ", 1);
+    let (time, _) =
+        NaiveDateTime::parse_and_remainder(&text, MINUTE_FORMAT).map_err(|_| invalid())?;
+    Ok(time.and_utc())
+}
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    /// Bits 1-31
+    days: u32,
+    /// Bits 1-12
+    months: u16,
+    /// Bits 0-6, Sunday first
+    weekdays: u8,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field expression or one of the `@` shorthands
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expanded = ALIASES
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(expr))
+            .map_or(expr, |(_, expanded)| expanded);
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "{expr} needs five fields: minute hour day month weekday"
+            ));
+        };
+        // 7 is Sunday too
+        let weekdays = field(weekday, 0, 7, "day of the week")?;
+        let weekdays = (weekdays | weekdays >> 7) & 0x7f;
+        Ok(Self {
+            minutes: field(minute, 0, 59, "minute")?,
+            hours: field(hour, 0, 23, "hour")? as u32,
+            days: field(day, 1, 31, "day of the month")? as u32,
+            months: field(month, 1, 12, "month")? as u16,
+            weekdays: weekdays as u8,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    pub fn matches(&self, time: &DateTime<Utc>) -> bool {
+        self.matches_day(time)
+            && self.hours & 1 << time.hour() != 0
+            && self.minutes & 1 << time.minute() != 0
+    }
+
+    /// First matching minute after `time`, `None` if there is none in
+    /// the next few years (e.g. `0 0 30 2 *`)
+    pub fn next_after(&self, time: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut candidate = minute_of(*time) + TimeDelta::minutes(1);
+        for _ in 0..SEARCH_DAYS {
+            if self.matches_day(&candidate) {
+                let hours = candidate.hour()..24;
+                for hour in hours.filter(|h| self.hours & 1 << h != 0) {
+                    let first = if hour == candidate.hour() {
+                        candidate.minute()
+                    } else {
+                        0
+                    };
+                    if let Some(minute) = (first..60).find(|m| self.minutes & 1 << m != 0) {
+                        let date = candidate.date_naive();
+                        return date.and_hms_opt(hour, minute, 0).map(|t| t.and_utc());
+                    }
+                }
+            }
+            candidate = candidate
+                .date_naive()
+                .succ_opt()?
+                .and_time(Default::default())
+                .and_utc();
+        }
+        None
+    }
+
+    fn matches_day(&self, time: &DateTime<Utc>) -> bool {
+        if self.months & 1 << time.month() == 0 {
+            return false;
+        }
+        let day = self.days & 1 << time.day() != 0;
+        let weekday = self.weekdays & 1 << time.weekday().num_days_from_sunday() != 0;
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Bits set for the values a field allows between `min` and `max`
+fn field(text: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("{text} is not a valid {name} ({min}-{max})");
+    let mut bits = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let value = |v: &str| {
+            v.parse::<u32>()
+                .ok()
+                .filter(|v| (min..=max).contains(v))
+                .ok_or_else(invalid)
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` runs from 5 to the end
+                None if part.contains('/') => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if step == 0 || start > end {
+            return Err(invalid());
+        }
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        parse_minute(s).unwrap()
+    }
+
+    fn next(expr: &str, after: &str) -> Option<String> {
+        let schedule = CronSchedule::parse(expr).unwrap();
+        schedule.next_after(&at(after)).map(|t| format_minute(&t))
+    }
+
+    #[test]
+    fn expressions_are_parsed() {
+        assert!(CronSchedule::parse("*/15 * * * *").is_ok());
+        assert!(CronSchedule::parse("0 2 * * 1-5").is_ok());
+        assert!(CronSchedule::parse("0,30 8-18/2 1,15 * 7").is_ok());
+        assert!(CronSchedule::parse("@Nightly").is_ok());
+
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 0 * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 5-1 * * *").is_err());
+        assert!(CronSchedule::parse("@yearly").is_err());
+
+        assert_eq!(
+            format_minute(&at("2026-03-02 02:00:59 UTC")),
+            "2026-03-02 02:00"
+        );
+        assert_eq!(at("2026-03-02T02:00"), at("2026-03-02 02:00"));
+        assert!(parse_minute("2026-02-29 00:00").is_err());
+        assert!(parse_minute("soon").is_err());
+    }
+
+    #[test]
+    fn next_runs_follow_the_fields() {
+        assert_eq!(
+            next("*/15 * * * *", "2026-03-02 10:07"),
+            Some("2026-03-02 10:15".into())
+        );
+        // Strictly after, never the same minute
+        assert_eq!(
+            next("@nightly", "2026-03-02 02:00"),
+            Some("2026-03-03 02:00".into())
+        );
+        assert_eq!(
+            next("0 6 * * 1", "2026-12-29 07:00"),
+            Some("2027-01-04 06:00".into())
+        );
+        // Day of the month or of the week
+        assert_eq!(
+            next("0 0 13 * 5", "2026-03-01 00:00"),
+            Some("2026-03-06 00:00".into())
+        );
+        assert_eq!(
+            next("0 12 29 2 *", "2026-03-01 00:00"),
+            Some("2028-02-29 12:00".into())
+        );
+        assert_eq!(next("0 0 30 2 *", "2026-03-01 00:00"), None);
+    }
+}
@@ -0,0 +1,425 @@
+//! Live Telemetry
+//!
+//! Rules for reading metrics from real devices, so a component can take
+//! its numbers from the device it stands for instead of the simulation.
+//! Devices are read over SNMPv2c (the interface counters of one
+//! interface, from IF-MIB) or from a small agent endpoint that answers
+//! with a JSON object of named numbers. Either way a poll yields
+//! [`METRICS`] by name; counters are turned into per-second rates
+//! between polls.
+//!
+//! Only GET requests are needed, so the SNMP codec here covers that much
+//! of BER: integers, strings, OIDs, NULL and the SNMP application types.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// Version field of SNMPv2c messages
+pub const SNMP_V2C: i64 = 1;
+
+/// Metrics a poll can report, with the IF-MIB or MIB-2 object each comes
+/// from over SNMP; interface objects get the interface index appended
+pub const METRICS: [(&str, &str); 7] = [
+    ("uptime_secs", "1.3.6.1.2.1.1.3.0"),
+    ("in_octets", "1.3.6.1.2.1.31.1.1.1.6"),
+    ("out_octets", "1.3.6.1.2.1.31.1.1.1.10"),
+    ("in_packets", "1.3.6.1.2.1.31.1.1.1.7"),
+    ("out_packets", "1.3.6.1.2.1.31.1.1.1.11"),
+    ("in_errors", "1.3.6.1.2.1.2.2.1.14"),
+    ("out_errors", "1.3.6.1.2.1.2.2.1.20"),
+];
+
+/// Metrics that only ever count up, reported as rates between polls
+pub const COUNTERS: [&str; 6] = [
+    "in_octets",
+    "out_octets",
+    "in_packets",
+    "out_packets",
+    "in_errors",
+    "out_errors",
+];
+
+/// OIDs to ask for, by metric, for interface `if_index`
+pub fn snmp_oids(if_index: u32) -> Vec<(&'static str, String)> {
+    METRICS
+        .iter()
+        .map(|&(metric, oid)| match metric {
+            "uptime_secs" => (metric, oid.to_string()),
+            _ => (metric, format!("{oid}.{if_index}")),
+        })
+        .collect()
+}
+
+/// A value in an SNMP response
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnmpValue {
+    Integer(i64),
+    /// Counter32, Gauge32, Counter64 and unsigned values
+    Unsigned(u64),
+    /// Hundredths of a second
+    TimeTicks(u64),
+    Text(Vec<u8>),
+    Oid(String),
+    Null,
+    /// The agent has no such object or instance
+    Missing,
+}
+
+impl SnmpValue {
+    /// The value as a number, with time ticks in seconds
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SnmpValue::Integer(v) => Some(*v as f64),
+            SnmpValue::Unsigned(v) => Some(*v as f64),
+            SnmpValue::TimeTicks(v) => Some(*v as f64 / 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded SNMP message
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnmpMessage {
+    pub community: String,
+    /// PDU tag, e.g. `0xA0` for GetRequest or `0xA2` for Response
+    pub pdu: u8,
+    pub request_id: i64,
+    pub error_status: i64,
+    pub varbinds: Vec<(String, SnmpValue)>,
+}
+
+const GET_REQUEST: u8 = 0xA0;
+/// PDU tag of an agent's answer
+pub const RESPONSE: u8 = 0xA2;
+
+fn encode_length(len: usize, out: &mut Vec<u8>) {
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+}
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    encode_length(content.len(), &mut out);
+    out.extend_from_slice(content);
+    out
+}
+
+fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    // Drop leading bytes that only repeat the sign
+    let mut start = 0;
+    while start < 7 {
+        let (b, next) = (bytes[start], bytes[start + 1]);
+        if (b == 0x00 && next & 0x80 == 0) || (b == 0xFF && next & 0x80 != 0) {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    tlv(0x02, &bytes[start..])
+}
+
+fn encode_oid(oid: &str) -> Result<Vec<u8>, String> {
+    let arcs: Vec<u64> = oid
+        .trim_start_matches('.')
+        .split('.')
+        .map(|arc| arc.parse().map_err(|_| format!("Invalid OID '{oid}'")))
+        .collect::<Result<_, _>>()?;
+    if arcs.len() < 2 || arcs[0] > 2 {
+        return Err(format!("Invalid OID '{oid}'"));
+    }
+    let mut content = Vec::new();
+    for arc in std::iter::once(arcs[0] * 40 + arcs[1]).chain(arcs[2..].iter().copied()) {
+        let mut chunk = vec![(arc & 0x7F) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            chunk.push((rest & 0x7F) as u8 | 0x80);
+            rest >>= 7;
+        }
+        chunk.reverse();
+        content.extend(chunk);
+    }
+    Ok(tlv(0x06, &content))
+}
+
+/// An SNMPv2c GetRequest for `oids`
+pub fn encode_get(community: &str, request_id: i64, oids: &[String]) -> Result<Vec<u8>, String> {
+    let mut varbinds = Vec::new();
+    for oid in oids {
+        let mut varbind = encode_oid(oid)?;
+        varbind.extend(tlv(0x05, &[]));
+        varbinds.extend(tlv(0x30, &varbind));
+    }
+    let mut pdu = encode_integer(request_id);
+    pdu.extend(encode_integer(0));
+    pdu.extend(encode_integer(0));
+    pdu.extend(tlv(0x30, &varbinds));
+
+    let mut message = encode_integer(SNMP_V2C);
+    message.extend(tlv(0x04, community.as_bytes()));
+    message.extend(tlv(GET_REQUEST, &pdu));
+    Ok(tlv(0x30, &message))
+}
+
+/// Reads tag-length-value items from a buffer
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn next(&mut self) -> Result<(u8, &'a [u8]), String> {
+        let truncated = || "Truncated SNMP message".to_string();
+        let (&tag, rest) = self.data.split_first().ok_or_else(truncated)?;
+        let (&first, mut rest) = rest.split_first().ok_or_else(truncated)?;
+        let len = if first < 0x80 {
+            first as usize
+        } else {
+            let count = (first & 0x7F) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return Err("Bad length in SNMP message".to_string());
+            }
+            let len = rest[..count]
+                .iter()
+                .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+            rest = &rest[count..];
+            len
+        };
+        if rest.len() < len {
+            return Err(truncated());
+        }
+        let (content, rest) = rest.split_at(len);
+        self.data = rest;
+        Ok((tag, content))
+    }
+
+    fn expect(&mut self, tag: u8) -> Result<&'a [u8], String> {
+        match self.next()? {
+            (t, content) if t == tag => Ok(content),
+            (t, _) => Err(format!("Expected tag {tag:#04x}, got {t:#04x}")),
+        }
+    }
+}
+
+fn decode_unsigned(content: &[u8]) -> u64 {
+    content.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+}
+
+fn decode_integer(content: &[u8]) -> i64 {
+    let negative = content.first().is_some_and(|b| b & 0x80 != 0);
+    let start = if negative { -1i64 } else { 0 };
+    content.iter().fold(start, |acc, b| (acc << 8) | *b as i64)
+}
+
+fn decode_oid(content: &[u8]) -> String {
+    let mut arcs = Vec::new();
+    let mut value = 0u64;
+    for b in content {
+        value = (value << 7) | (b & 0x7F) as u64;
+        if b & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (value / 40).min(2);
+                arcs.push(first);
+                arcs.push(value - first * 40);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        }
+    }
+    arcs.iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn decode_value(tag: u8, content: &[u8]) -> SnmpValue {
+    match tag {
+        0x02 => SnmpValue::Integer(decode_integer(content)),
+        0x04 | 0x40 | 0x44 => SnmpValue::Text(content.to_vec()),
+        0x05 => SnmpValue::Null,
+        0x06 => SnmpValue::Oid(decode_oid(content)),
+        0x41 | 0x42 | 0x46 | 0x47 => SnmpValue::Unsigned(decode_unsigned(content)),
+        0x43 => SnmpValue::TimeTicks(decode_unsigned(content)),
+        _ => SnmpValue::Missing,
+    }
+}
+
+/// Decode an SNMPv1 or v2c message
+pub fn decode(data: &[u8]) -> Result<SnmpMessage, String> {
+    let mut message = Reader {
+        data: Reader { data }.expect(0x30)?,
+    };
+    message.expect(0x02)?;
+    let community = String::from_utf8_lossy(message.expect(0x04)?).into_owned();
+    let (pdu, content) = message.next()?;
+    let mut fields = Reader { data: content };
+    let request_id = decode_integer(fields.expect(0x02)?);
+    let error_status = decode_integer(fields.expect(0x02)?);
+    fields.expect(0x02)?;
+
+    let mut list = Reader {
+        data: fields.expect(0x30)?,
+    };
+    let mut varbinds = Vec::new();
+    while !list.data.is_empty() {
+        let mut varbind = Reader {
+            data: list.expect(0x30)?,
+        };
+        let oid = decode_oid(varbind.expect(0x06)?);
+        let (tag, value) = varbind.next()?;
+        varbinds.push((oid, decode_value(tag, value)));
+    }
+    Ok(SnmpMessage {
+        community,
+        pdu,
+        request_id,
+        error_status,
+        varbinds,
+    })
+}
+
+/// Metrics from a response to [`encode_get`] for [`snmp_oids`]
+pub fn snmp_metrics(
+    oids: &[(&'static str, String)],
+    response: &SnmpMessage,
+) -> BTreeMap<String, f64> {
+    oids.iter()
+        .filter_map(|(metric, oid)| {
+            let (_, value) = response.varbinds.iter().find(|(o, _)| o == oid)?;
+            Some((metric.to_string(), value.as_f64()?))
+        })
+        .collect()
+}
+
+/// Metrics from an agent's answer: the numbers in its `metrics` object,
+/// or at its top level when it has none
+pub fn agent_metrics(body: &Value) -> BTreeMap<String, f64> {
+    let object = body
+        .get("metrics")
+        .and_then(Value::as_object)
+        .or_else(|| body.as_object());
+    object
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| Some((name.clone(), value.as_f64()?)))
+        .collect()
+}
+
+/// Per-second rates of the [`COUNTERS`] in `current`, `secs` after `previous`
+///
+/// A counter lower than before was reset or wrapped, so all of its current
+/// value is counted.
+pub fn counter_rates(
+    previous: &BTreeMap<String, f64>,
+    current: &BTreeMap<String, f64>,
+    secs: f64,
+) -> BTreeMap<String, f64> {
+    if secs <= 0.0 {
+        return BTreeMap::new();
+    }
+    COUNTERS
+        .iter()
+        .filter_map(|&counter| {
+            let (before, now) = (previous.get(counter)?, current.get(counter)?);
+            let delta = if now >= before { now - before } else { *now };
+            Some((format!("{counter}_per_sec"), delta / secs))
+        })
+        .collect()
+}
+
+/// Packets sent and received between two polls, logged as the
+/// component's metric like the simulation's packet count
+pub fn packets_between(previous: &BTreeMap<String, f64>, current: &BTreeMap<String, f64>) -> f64 {
+    ["in_packets", "out_packets"]
+        .iter()
+        .filter_map(|counter| {
+            let (before, now) = (previous.get(*counter)?, current.get(*counter)?);
+            Some(if now >= before { now - before } else { *now })
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn get_requests_round_trip() {
+        assert_eq!(
+            encode_oid("1.3.6.1.2.1.1.3.0").unwrap(),
+            [0x06, 0x08, 0x2B, 0x06, 0x01, 0x02, 0x01, 0x01, 0x03, 0x00]
+        );
+        assert_eq!(encode_integer(128), [0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(encode_integer(-1), [0x02, 0x01, 0xFF]);
+        assert!(encode_oid("not.an.oid").is_err());
+
+        let oids: Vec<String> = snmp_oids(3).into_iter().map(|(_, oid)| oid).collect();
+        assert_eq!(oids[1], "1.3.6.1.2.1.31.1.1.1.6.3");
+        let request = encode_get("public", 4242, &oids).unwrap();
+        let decoded = decode(&request).unwrap();
+        assert_eq!(decoded.community, "public");
+        assert_eq!(decoded.pdu, GET_REQUEST);
+        assert_eq!(decoded.request_id, 4242);
+        assert_eq!(
+            decoded
+                .varbinds
+                .iter()
+                .map(|(o, _)| o.clone())
+                .collect::<Vec<_>>(),
+            oids
+        );
+        assert!(decoded.varbinds.iter().all(|(_, v)| *v == SnmpValue::Null));
+        assert!(decode(&request[..request.len() - 3]).is_err());
+    }
+
+    #[test]
+    fn responses_become_metrics_and_rates() {
+        let oids = snmp_oids(1);
+        let varbind = |oid: &str, value: Vec<u8>| {
+            let mut content = encode_oid(oid).unwrap();
+            content.extend(value);
+            tlv(0x30, &content)
+        };
+        let mut list = varbind(&oids[0].1, tlv(0x43, &[0x01, 0x00])); // 256 ticks
+        list.extend(varbind(
+            &oids[3].1,
+            tlv(0x46, &[0x01, 0x00, 0x00, 0x00, 0x00]),
+        )); // Counter64
+        list.extend(varbind(&oids[4].1, tlv(0x81, &[])));
+        let mut pdu = encode_integer(7);
+        pdu.extend(encode_integer(0));
+        pdu.extend(encode_integer(0));
+        pdu.extend(tlv(0x30, &list));
+        let mut message = encode_integer(SNMP_V2C);
+        message.extend(tlv(0x04, b"public"));
+        message.extend(tlv(RESPONSE, &pdu));
+
+        let response = decode(&tlv(0x30, &message)).unwrap();
+        assert_eq!(response.pdu, RESPONSE);
+        let metrics = snmp_metrics(&oids, &response);
+        assert_eq!(metrics["uptime_secs"], 2.56);
+        assert_eq!(metrics["in_packets"], 4_294_967_296.0);
+        assert!(!metrics.contains_key("out_packets"));
+
+        let agent = agent_metrics(
+            &json!({ "metrics": { "in_packets": 10, "out_packets": 5, "model": "x" } }),
+        );
+        assert_eq!(agent.len(), 2);
+        let later = agent_metrics(&json!({ "in_packets": 40, "out_packets": 2 }));
+        let rates = counter_rates(&agent, &later, 10.0);
+        assert_eq!(rates["in_packets_per_sec"], 3.0);
+        assert_eq!(rates["out_packets_per_sec"], 0.2);
+        assert_eq!(packets_between(&agent, &later), 32.0);
+    }
+}
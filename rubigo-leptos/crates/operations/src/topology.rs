@@ -0,0 +1,106 @@
+//! Topology diffs
+//!
+//! Compares two versions of a set of records keyed by id, such as the
+//! components of a sandbox against those it was branched from. Records are
+//! compared as JSON so any serializable type can be diffed. [`conflicts`]
+//! does the three-way check a merge needs: which records both the branch
+//! and the live side changed since the branch was taken, in different ways.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How a record differs between two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Change {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        }
+    }
+}
+
+/// Records that differ from `from` to `to`; unchanged ones are left out
+pub fn diff<K: Ord + Clone>(
+    from: &BTreeMap<K, Value>,
+    to: &BTreeMap<K, Value>,
+) -> BTreeMap<K, Change> {
+    let keys: BTreeSet<&K> = from.keys().chain(to.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let change = match (from.get(key), to.get(key)) {
+                (None, Some(_)) => Change::Added,
+                (Some(_), None) => Change::Removed,
+                (Some(a), Some(b)) if a != b => Change::Changed,
+                _ => return None,
+            };
+            Some((key.clone(), change))
+        })
+        .collect()
+}
+
+/// Records `branch` and `live` both changed since `base`, and that they
+/// no longer agree on
+///
+/// Making the same edit on both sides is not a conflict.
+pub fn conflicts<K: Ord + Clone>(
+    base: &BTreeMap<K, Value>,
+    branch: &BTreeMap<K, Value>,
+    live: &BTreeMap<K, Value>,
+) -> Vec<K> {
+    let live_changes = diff(base, live);
+    diff(base, branch)
+        .into_keys()
+        .filter(|key| live_changes.contains_key(key) && branch.get(key) != live.get(key))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diffs_and_three_way_conflicts() {
+        let base = BTreeMap::from([
+            (1, json!({"name": "r1"})),
+            (2, json!({"name": "s1"})),
+            (3, json!({"name": "s2"})),
+        ]);
+        let branch = BTreeMap::from([
+            (1, json!({"name": "core"})),
+            (3, json!({"name": "s2"})),
+            (4, json!({"name": "fw"})),
+        ]);
+        assert_eq!(
+            diff(&base, &branch),
+            BTreeMap::from([
+                (1, Change::Changed),
+                (2, Change::Removed),
+                (4, Change::Added)
+            ])
+        );
+        assert!(diff(&base, &base).is_empty());
+
+        // Live renamed 1 differently, dropped 2 as the branch did, and added
+        // its own 4
+        let live = BTreeMap::from([
+            (1, json!({"name": "edge"})),
+            (3, json!({"name": "s2"})),
+            (4, json!({"name": "printer"})),
+        ]);
+        assert_eq!(conflicts(&base, &branch, &live), vec![1, 4]);
+        assert!(conflicts(&base, &branch, &base).is_empty());
+        assert_eq!(Change::Added.as_str(), "added");
+    }
+}
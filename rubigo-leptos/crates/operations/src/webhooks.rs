@@ -0,0 +1,144 @@
+//! Webhooks
+//!
+//! Rules for the server's outbound webhooks: which events exist, how a
+//! webhook's filters select them, and when failed deliveries are retried.
+//! Entity change events are named after the kind of record and what
+//! happened to it, e.g. `asset.changed` or `person.deleted`; filters are
+//! event names, `kind.*` for everything about one kind of record, or `*`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Sent by hand from the admin page to check a webhook's endpoint
+pub const TEST_EVENT: &str = "webhook.test";
+
+/// A simulation run finished
+pub const SIMULATION_COMPLETED: &str = "simulation.completed";
+
+/// Kinds of record that raise change events, by the table they live in
+pub const ENTITY_TABLES: [(&str, &str); 9] = [
+    ("person", "person"),
+    ("site", "site"),
+    ("building", "building"),
+    ("floor", "floor"),
+    ("space", "space"),
+    ("network_asset", "asset"),
+    ("meeting", "event"),
+    ("maintenance_ticket", "maintenance"),
+    ("desk_booking", "desk_booking"),
+];
+
+/// Deliveries made before one is given up on
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+
+/// Body of every delivery; the signature covers it byte for byte
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Delivery ID, the same across retries so receivers can drop repeats
+    pub id: String,
+    pub event: String,
+    pub occurred_at: String,
+    pub data: Value,
+}
+
+/// Event raised when a record in `table` changes or is deleted
+pub fn change_event(table: &str, deleted: bool) -> String {
+    let kind = ENTITY_TABLES
+        .iter()
+        .find(|(t, _)| *t == table)
+        .map_or(table, |(_, kind)| kind);
+    let what = if deleted { "deleted" } else { "changed" };
+    format!("{kind}.{what}")
+}
+
+/// Every event a webhook can subscribe to
+pub fn known_events() -> Vec<String> {
+    let mut events: Vec<String> = ENTITY_TABLES
+        .iter()
+        .flat_map(|(table, _)| [change_event(table, false), change_event(table, true)])
+        .collect();
+    events.push(SIMULATION_COMPLETED.to_string());
+    events
+}
+
+/// Whether a webhook subscribed to `filters` hears about `event`
+///
+/// Test events reach every webhook.
+pub fn event_matches(filters: &[String], event: &str) -> bool {
+    event == TEST_EVENT || filters.iter().any(|filter| filter_matches(filter, event))
+}
+
+fn filter_matches(filter: &str, event: &str) -> bool {
+    if filter == "*" {
+        return true;
+    }
+    match filter.strip_suffix(".*") {
+        Some(kind) => event.split_once('.').is_some_and(|(of, _)| of == kind),
+        None => filter == event,
+    }
+}
+
+/// Why a webhook can't be registered, if it can't
+pub fn validate_webhook(url: &str, filters: &[String]) -> Option<String> {
+    let url = url.trim();
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    if host.is_none_or(|host| host.is_empty() || host.starts_with('/')) {
+        return Some(format!("{url} is not an http(s) URL"));
+    }
+    if filters.is_empty() {
+        return Some("A webhook needs at least one event".to_string());
+    }
+    let known = known_events();
+    filters
+        .iter()
+        .find(|filter| !known.iter().any(|event| filter_matches(filter, event)))
+        .map(|filter| format!("{filter} matches no event"))
+}
+
+/// Seconds to wait before attempt `attempt + 1` after attempt `attempt`
+/// failed; `None` once [`MAX_DELIVERY_ATTEMPTS`] have been made
+pub fn retry_delay_secs(attempt: u32) -> Option<u64> {
+    (attempt < MAX_DELIVERY_ATTEMPTS).then(|| 10 * 6u64.pow(attempt.saturating_sub(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filters(list: &[&str]) -> Vec<String> {
+        list.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn filters_select_events() {
+        assert_eq!(change_event("network_asset", false), "asset.changed");
+        assert_eq!(change_event("person", true), "person.deleted");
+
+        let assets = filters(&["asset.*", "simulation.completed"]);
+        assert!(event_matches(&assets, "asset.deleted"));
+        assert!(event_matches(&assets, "simulation.completed"));
+        assert!(!event_matches(&assets, "person.changed"));
+        assert!(event_matches(&assets, TEST_EVENT));
+        assert!(event_matches(&filters(&["*"]), "site.changed"));
+        assert!(!event_matches(&[], "site.changed"));
+    }
+
+    #[test]
+    fn webhooks_are_validated_and_retried() {
+        let events = filters(&["asset.*"]);
+        assert_eq!(
+            validate_webhook("https://chat.example.com/hook", &events),
+            None
+        );
+        assert!(validate_webhook("ftp://example.com", &events).is_some());
+        assert!(validate_webhook("https://", &events).is_some());
+        assert!(validate_webhook("https://example.com", &[]).is_some());
+        assert!(validate_webhook("https://example.com", &filters(&["rack.*"])).is_some());
+
+        assert_eq!(retry_delay_secs(1), Some(10));
+        assert_eq!(retry_delay_secs(2), Some(60));
+        assert_eq!(retry_delay_secs(MAX_DELIVERY_ATTEMPTS), None);
+    }
+}
@@ -0,0 +1,437 @@
+//! WASM Business Rules
+//!
+//! Hosts user-provided WebAssembly modules that validate and automate
+//! actions, e.g. "when an asset is created in site X, schedule its first
+//! maintenance". Each rule sees every action twice:
+//!
+//! - **before** it runs, and may reject it with a reason
+//! - **after** it succeeded, and may ask for follow-up actions
+//!
+//! Rules are sandboxed: a fresh instance per event, no imports beyond the
+//! two below, a fuel budget per call and a memory cap. A rule that traps or
+//! runs out of fuel is logged and skipped; it never blocks an action.
+//!
+//! # Guest ABI
+//!
+//! Modules follow the `nexosim-guest` conventions (see its `rules` module):
+//!
+//! - exports `memory`, `guest_alloc(len) -> ptr` and `on_event(ptr, len) -> i32`
+//! - imports `env.host_log(ptr, len)` and `env.emit(ptr, len)`
+//!
+//! `on_event` receives a JSON [`RuleEvent`]; each `emit` call passes one JSON
+//! [`RuleEffect`].
+//!
+//! # Usage
+//!
+//! ```ignore
+//! let mut host = rules::RuleHost::new(rules::Limits::default())?;
+//! host.load_dir("rules")?;
+//! let effects = host.fire(&RuleEvent::before("asset.create", &payload));
+//! ```
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasmtime::{
+    Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+};
+
+/// Fuel for one call into a rule, roughly one unit per instruction
+pub const DEFAULT_FUEL: u64 = 10_000_000;
+
+/// Largest linear memory a rule may grow to
+pub const DEFAULT_MEMORY_BYTES: usize = 16 << 20;
+
+/// Largest message a rule may log or emit
+const MAX_MESSAGE_BYTES: usize = 64 << 10;
+
+/// Sandbox limits applied to every call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub fuel: u64,
+    pub memory_bytes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            fuel: DEFAULT_FUEL,
+            memory_bytes: DEFAULT_MEMORY_BYTES,
+        }
+    }
+}
+
+/// When a rule is consulted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    /// Before the action runs; rules may reject it
+    Before,
+    /// After the action succeeded; rules may dispatch follow-ups
+    After,
+}
+
+/// What a rule is told about an action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleEvent {
+    pub phase: Phase,
+    /// e.g. `asset.create`
+    pub action_type: String,
+    pub payload: Value,
+    /// The handler's response, in the `after` phase
+    pub response: Option<Value>,
+}
+
+impl RuleEvent {
+    pub fn before(action_type: &str, payload: &Value) -> Self {
+        Self {
+            phase: Phase::Before,
+            action_type: action_type.to_string(),
+            payload: payload.clone(),
+            response: None,
+        }
+    }
+
+    pub fn after(action_type: &str, payload: &Value, response: &Value) -> Self {
+        Self {
+            phase: Phase::After,
+            action_type: action_type.to_string(),
+            payload: payload.clone(),
+            response: Some(response.clone()),
+        }
+    }
+}
+
+/// What a rule asks for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "effect", rename_all = "snake_case")]
+pub enum RuleEffect {
+    /// Stop the action; only honoured in the `before` phase
+    Reject { reason: String },
+    /// Run another action; only honoured in the `after` phase
+    Dispatch { action_type: String, payload: Value },
+}
+
+/// An effect with the rule that asked for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fired {
+    pub rule: String,
+    pub effect: RuleEffect,
+}
+
+struct Rule {
+    name: String,
+    module: Module,
+}
+
+/// Per-call state
+struct Sandbox {
+    limits: StoreLimits,
+    rule: String,
+    effects: Vec<RuleEffect>,
+}
+
+/// Loaded rules and the engine that runs them
+pub struct RuleHost {
+    engine: Engine,
+    linker: Linker<Sandbox>,
+    rules: Vec<Rule>,
+    limits: Limits,
+}
+
+impl RuleHost {
+    pub fn new(limits: Limits) -> Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+
+        let mut linker = Linker::new(&engine);
+        linker.func_wrap(
+            "env",
+            "host_log",
+            |mut caller: Caller<'_, Sandbox>, ptr: i32, len: i32| {
+                if let Some(bytes) = read_guest(&mut caller, ptr, len) {
+                    let rule = &caller.data().rule;
+                    tracing::info!("[rule {}]: {}", rule, String::from_utf8_lossy(&bytes));
+                }
+            },
+        )?;
+        linker.func_wrap(
+            "env",
+            "emit",
+            |mut caller: Caller<'_, Sandbox>, ptr: i32, len: i32| {
+                let Some(bytes) = read_guest(&mut caller, ptr, len) else {
+                    return;
+                };
+                match serde_json::from_slice::<RuleEffect>(&bytes) {
+                    Ok(effect) => caller.data_mut().effects.push(effect),
+                    Err(e) => tracing::warn!(
+                        "Rule {} emitted an invalid effect: {}",
+                        caller.data().rule,
+                        e
+                    ),
+                }
+            },
+        )?;
+
+        Ok(Self {
+            engine,
+            linker,
+            rules: Vec::new(),
+            limits,
+        })
+    }
+
+    /// Compile and add a rule; `bytes` may be a `.wasm` binary or `.wat` text
+    pub fn load(&mut self, name: impl Into<String>, bytes: &[u8]) -> Result<()> {
+        let name = name.into();
+        let module = Module::new(&self.engine, bytes)
+            .with_context(|| format!("Invalid rule module '{}'", name))?;
+        for export in ["memory", "guest_alloc", "on_event"] {
+            if module.get_export(export).is_none() {
+                return Err(anyhow!("Rule '{}' does not export '{}'", name, export));
+            }
+        }
+        self.rules.retain(|r| r.name != name);
+        self.rules.push(Rule { name, module });
+        Ok(())
+    }
+
+    /// Load every `.wasm` file in `dir`, named after the file; returns how many
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<usize> {
+        let mut paths: Vec<_> = std::fs::read_dir(dir.as_ref())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+        paths.sort();
+        for path in &paths {
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let bytes = std::fs::read(path)?;
+            tracing::info!("Loading rule {} from {:?}", name, path);
+            self.load(name, &bytes)?;
+        }
+        Ok(paths.len())
+    }
+
+    /// Names of the loaded rules, in load order
+    pub fn names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    /// Run every rule on `event`, collecting the effects that apply to its phase
+    pub fn fire(&self, event: &RuleEvent) -> Vec<Fired> {
+        let Ok(input) = serde_json::to_vec(event) else {
+            return Vec::new();
+        };
+        let mut fired = Vec::new();
+        for rule in &self.rules {
+            match self.call(rule, &input) {
+                Ok(effects) => fired.extend(
+                    effects
+                        .into_iter()
+                        .filter(|effect| applies(effect, event.phase))
+                        .map(|effect| Fired {
+                            rule: rule.name.clone(),
+                            effect,
+                        }),
+                ),
+                Err(e) => tracing::warn!(
+                    "Rule {} failed on {}: {:#}",
+                    rule.name,
+                    event.action_type,
+                    e
+                ),
+            }
+        }
+        fired
+    }
+
+    fn call(&self, rule: &Rule, input: &[u8]) -> Result<Vec<RuleEffect>> {
+        let sandbox = Sandbox {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.limits.memory_bytes)
+                .instances(1)
+                .build(),
+            rule: rule.name.clone(),
+            effects: Vec::new(),
+        };
+        let mut store = Store::new(&self.engine, sandbox);
+        store.limiter(|s| &mut s.limits);
+        store.set_fuel(self.limits.fuel)?;
+
+        let instance = self.linker.instantiate(&mut store, &rule.module)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow!("missing memory export"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "guest_alloc")?;
+        let on_event = instance.get_typed_func::<(i32, i32), i32>(&mut store, "on_event")?;
+
+        let len = i32::try_from(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, input)?;
+        on_event.call(&mut store, (ptr, len))?;
+
+        Ok(std::mem::take(&mut store.data_mut().effects))
+    }
+}
+
+/// Whether `effect` may be acted on in `phase`
+fn applies(effect: &RuleEffect, phase: Phase) -> bool {
+    matches!(
+        (effect, phase),
+        (RuleEffect::Reject { .. }, Phase::Before) | (RuleEffect::Dispatch { .. }, Phase::After)
+    )
+}
+
+/// Copy `len` bytes at `ptr` out of the caller's memory, if in bounds
+fn read_guest(caller: &mut Caller<'_, Sandbox>, ptr: i32, len: i32) -> Option<Vec<u8>> {
+    let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+        return None;
+    };
+    let start = ptr as u32 as usize;
+    let len = (len as u32 as usize).min(MAX_MESSAGE_BYTES);
+    memory
+        .data(&*caller)
+        .get(start..start.checked_add(len)?)
+        .map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Module whose `on_event` emits `effect` when the event mentions `needle`
+    fn emitting_rule(needle: &str, effect: &str) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        format!(
+            r#"(module
+                (import "env" "emit" (func $emit (param i32 i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{effect}")
+                (data (i32.const 512) "{needle}")
+                (global $next (mut i32) (i32.const 1024))
+                (func (export "guest_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                (func $matches (param $at i32) (result i32)
+                    (local $i i32)
+                    (loop $next_byte
+                        (if (i32.ge_u (local.get $i) (i32.const {needle_len}))
+                            (then (return (i32.const 1))))
+                        (if (i32.ne
+                                (i32.load8_u (i32.add (local.get $at) (local.get $i)))
+                                (i32.load8_u (i32.add (i32.const 512) (local.get $i))))
+                            (then (return (i32.const 0))))
+                        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                        (br $next_byte))
+                    (i32.const 0))
+                (func (export "on_event") (param $ptr i32) (param $len i32) (result i32)
+                    (local $at i32)
+                    (block $done
+                        (loop $scan
+                            (br_if $done (i32.gt_u
+                                (i32.add (local.get $at) (i32.const {needle_len}))
+                                (local.get $len)))
+                            (if (call $matches (i32.add (local.get $ptr) (local.get $at)))
+                                (then
+                                    (call $emit (i32.const 0) (i32.const {effect_len}))
+                                    (br $done)))
+                            (local.set $at (i32.add (local.get $at) (i32.const 1)))
+                            (br $scan)))
+                    (i32.const 0)))"#,
+            effect = escape(effect),
+            effect_len = effect.len(),
+            needle = escape(needle),
+            needle_len = needle.len(),
+        )
+    }
+
+    const SPINNING_RULE: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "guest_alloc") (param i32) (result i32) (i32.const 0))
+        (func (export "on_event") (param i32 i32) (result i32)
+            (loop $forever (br $forever))
+            (i32.const 0)))"#;
+
+    #[test]
+    fn rules_reject_before_and_dispatch_after() {
+        let mut host = RuleHost::new(Limits::default()).unwrap();
+        host.load(
+            "no_lab_assets",
+            emitting_rule(
+                "site:lab",
+                r#"{"effect":"reject","reason":"Lab is closed"}"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        host.load(
+            "first_service",
+            emitting_rule(
+                "\"phase\":\"after\"",
+                r#"{"effect":"dispatch","action_type":"asset.schedule_maintenance","payload":{}}"#,
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let payload = json!({ "Create": { "site": "site:lab" } });
+        let before = host.fire(&RuleEvent::before("asset.create", &payload));
+        assert_eq!(
+            before,
+            vec![Fired {
+                rule: "no_lab_assets".to_string(),
+                effect: RuleEffect::Reject {
+                    reason: "Lab is closed".to_string()
+                },
+            }]
+        );
+
+        // A reject emitted after the fact is ignored
+        let after = host.fire(&RuleEvent::after(
+            "asset.create",
+            &payload,
+            &json!({ "ok": true }),
+        ));
+        assert_eq!(after.len(), 1);
+        assert!(
+            matches!(&after[0].effect, RuleEffect::Dispatch { action_type, .. } if action_type == "asset.schedule_maintenance")
+        );
+
+        let elsewhere = host.fire(&RuleEvent::before(
+            "asset.create",
+            &json!({ "site": "site:hq" }),
+        ));
+        assert!(elsewhere.is_empty());
+    }
+
+    #[test]
+    fn runaway_rules_are_stopped_by_fuel() {
+        let mut host = RuleHost::new(Limits {
+            fuel: 10_000,
+            ..Limits::default()
+        })
+        .unwrap();
+        host.load("spin", SPINNING_RULE.as_bytes()).unwrap();
+        assert!(host
+            .fire(&RuleEvent::before("asset.list", &json!({})))
+            .is_empty());
+    }
+
+    #[test]
+    fn modules_without_the_entry_points_are_refused() {
+        let mut host = RuleHost::new(Limits::default()).unwrap();
+        let error = host.load("empty", b"(module)").unwrap_err();
+        assert!(error.to_string().contains("does not export"));
+        assert!(host.names().is_empty());
+    }
+}
@@ -44,6 +44,10 @@ struct ModuleRefs {
     assets: Option<String>,
     #[serde(default)]
     events: Option<String>,
+    #[serde(default)]
+    chat: Option<String>,
+    #[serde(default)]
+    training: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,6 +80,28 @@ struct EventsToml {
     events: Vec<Event>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ChatToml {
+    #[serde(default)]
+    conversations: Vec<Conversation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrainingToml {
+    #[serde(default)]
+    exercises: Vec<Exercise>,
+}
+
+/// Parse the `[[conversations]]` of a chat module file
+pub fn parse_conversations(chat_toml: &str) -> Result<Vec<Conversation>, ScenarioError> {
+    Ok(toml::from_str::<ChatToml>(chat_toml)?.conversations)
+}
+
+/// Parse the `[[exercises]]` of a training module file
+pub fn parse_exercises(training_toml: &str) -> Result<Vec<Exercise>, ScenarioError> {
+    Ok(toml::from_str::<TrainingToml>(training_toml)?.exercises)
+}
+
 impl Scenario {
     /// Load a scenario from a directory path (runtime)
     pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
@@ -127,6 +153,24 @@ impl Scenario {
             vec![]
         };
 
+        // Load chat history
+        let conversations = if let Some(ref file) = scenario_toml.modules.chat {
+            let path = base.join(file);
+            let content = std::fs::read_to_string(path)?;
+            parse_conversations(&content)?
+        } else {
+            vec![]
+        };
+
+        // Load training exercises
+        let exercises = if let Some(ref file) = scenario_toml.modules.training {
+            let path = base.join(file);
+            let content = std::fs::read_to_string(path)?;
+            parse_exercises(&content)?
+        } else {
+            vec![]
+        };
+
         Ok(Scenario {
             name: scenario_toml.scenario.name,
             short_name: scenario_toml.scenario.short_name,
@@ -139,6 +183,8 @@ impl Scenario {
             spaces,
             assets,
             events,
+            conversations,
+            exercises,
         })
     }
 
@@ -188,8 +234,22 @@ impl Scenario {
             spaces,
             assets,
             events,
+            conversations: vec![],
+            exercises: vec![],
         })
     }
+
+    /// Add chat history from an embedded chat module file
+    pub fn with_chat_toml(mut self, chat_toml: &str) -> Result<Self, ScenarioError> {
+        self.conversations = parse_conversations(chat_toml)?;
+        Ok(self)
+    }
+
+    /// Add exercises from an embedded training module file
+    pub fn with_training_toml(mut self, training_toml: &str) -> Result<Self, ScenarioError> {
+        self.exercises = parse_exercises(training_toml)?;
+        Ok(self)
+    }
 }
 
 #[cfg(test)]
@@ -207,4 +267,56 @@ short_name = "test"
         assert_eq!(scenario.name, "Test Scenario");
         assert_eq!(scenario.short_name, "test");
     }
+
+    #[test]
+    fn parse_chat_history() {
+        let toml = r#"
+[[conversations]]
+participants = ["Sarah Kim", "James Wilson"]
+
+[[conversations.messages]]
+from = "Sarah Kim"
+sent_at = "2025-01-06 09:15:00 UTC"
+body = "Is the core switch swap still on for tonight?"
+
+[[conversations.messages]]
+from = "James Wilson"
+sent_at = "2025-01-06 09:17:00 UTC"
+body = "Yes, 22:00 as planned."
+"#;
+        let conversations = parse_conversations(toml).unwrap();
+        assert_eq!(conversations.len(), 1);
+        assert_eq!(conversations[0].title, None);
+        assert_eq!(conversations[0].participants, vec!["Sarah Kim", "James Wilson"]);
+        assert_eq!(conversations[0].messages[1].from, "James Wilson");
+    }
+
+    #[test]
+    fn parse_training_exercises() {
+        let toml = r#"
+[[exercises]]
+name = "Retire a switch"
+description = "Take the old access switch out of service"
+
+[[exercises.tasks]]
+title = "Find the switch"
+expect = { action = "asset.list" }
+
+[[exercises.tasks]]
+title = "Retire it"
+hints = ["Assets move through lifecycle states", "Transition it to retired"]
+points = 20
+expect = { action = "asset.transition", payload = { Transition = [{}, { state = "retired" }] } }
+"#;
+        let exercises = parse_exercises(toml).unwrap();
+        assert_eq!(exercises.len(), 1);
+        let tasks = &exercises[0].tasks;
+        assert_eq!(tasks[0].points, 10);
+        assert_eq!(tasks[0].expect.payload, None);
+        assert_eq!(tasks[1].hints.len(), 2);
+        assert_eq!(
+            tasks[1].expect.payload.as_ref().unwrap()["Transition"][1]["state"],
+            "retired"
+        );
+    }
 }
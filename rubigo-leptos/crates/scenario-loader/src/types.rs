@@ -17,6 +17,10 @@ pub struct Scenario {
     pub assets: Vec<Asset>,
     #[serde(default)]
     pub events: Vec<Event>,
+    #[serde(default)]
+    pub conversations: Vec<Conversation>,
+    #[serde(default)]
+    pub exercises: Vec<Exercise>,
 }
 
 /// Person/employee record
@@ -172,6 +176,67 @@ pub struct Event {
     pub timezone: Option<String>,
 }
 
+/// Chat conversation with its message history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    /// Optional name; direct messages usually leave it unset
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Participant names, matching `Person::name`
+    pub participants: Vec<String>,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+}
+
+/// Chat message in a seeded conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    /// Sender name, one of the conversation's participants
+    pub from: String,
+    /// `YYYY-MM-DD HH:MM:SS UTC`
+    pub sent_at: String,
+    pub body: String,
+}
+
+/// Guided training exercise: tasks the trainee works through in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exercise {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tasks: Vec<ExerciseTask>,
+}
+
+/// One step of an exercise, done once the trainee dispatches the expected action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExerciseTask {
+    pub title: String,
+    #[serde(default)]
+    pub instructions: Option<String>,
+    pub expect: ExpectedAction,
+    /// Revealed one at a time on request, each costing some of the points
+    #[serde(default)]
+    pub hints: Vec<String>,
+    #[serde(default = "default_task_points")]
+    pub points: u32,
+}
+
+fn default_task_points() -> u32 {
+    10
+}
+
+/// The action that completes a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectedAction {
+    /// Action type, e.g. `asset.transition`
+    pub action: String,
+    /// Fields the action's payload must contain, nested as in its JSON;
+    /// any payload will do when unset
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+}
+
 impl Person {
     /// Get unique ID - uses explicit id if set, otherwise generates from email
     pub fn get_id(&self) -> String {
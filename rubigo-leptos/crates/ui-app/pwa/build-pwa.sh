@@ -0,0 +1,54 @@
+#!/bin/bash
+# Add the PWA files to a Trunk build: web app manifest, icon, offline page
+# and the service worker (pwa/sw.js with its precache list filled in).
+#
+# Usage: build-pwa.sh <dist-dir>
+#
+# Output:
+#   <dist-dir>/manifest.webmanifest, icon.svg, offline.html, sw.js
+
+set -e
+
+DIST="$1"
+
+if [ -z "$DIST" ]; then
+    echo "Usage: $0 <dist-dir>"
+    exit 1
+fi
+
+cd "$(dirname "$0")"
+
+NAME="Rubigo"
+DESCRIPTION=$(sed -n 's:.*<title>\(.*\)</title>.*:\1:p' ../index.html)
+BACKGROUND="#0f0f14"
+
+cp ../../../rubigo-logo.svg "$DIST/icon.svg"
+cp offline.html "$DIST/offline.html"
+
+cat > "$DIST/manifest.webmanifest" <<MANIFEST
+{
+  "name": "$NAME",
+  "short_name": "$NAME",
+  "description": "$DESCRIPTION",
+  "start_url": "/",
+  "scope": "/",
+  "display": "standalone",
+  "background_color": "$BACKGROUND",
+  "theme_color": "$BACKGROUND",
+  "icons": [
+    { "src": "/icon.svg", "sizes": "any", "type": "image/svg+xml", "purpose": "any maskable" }
+  ]
+}
+MANIFEST
+
+# The app shell: every top-level file of the build, which leaves out the
+# globe module and geo assets (cached on first use instead)
+SHELL=$(cd "$DIST" && find . -maxdepth 1 -type f ! -name sw.js -printf '%P\n' | sort)
+VERSION=$(cd "$DIST" && cat $SHELL | sha256sum | cut -c1-12)
+FILES=$(printf '"/%s", ' $SHELL | sed 's/, $//')
+
+sed -e "s|__CACHE_VERSION__|$VERSION|" \
+    -e "s|__SHELL_FILES__|[\"/\", $FILES]|" \
+    sw.js > "$DIST/sw.js"
+
+echo "PWA: $(echo "$SHELL" | wc -l) shell files, cache rubigo-$VERSION"
@@ -0,0 +1,81 @@
+// Rubigo service worker
+//
+// Template filled in by build-pwa.sh: __CACHE_VERSION__ is a hash of the
+// app shell and __SHELL_FILES__ lists it. The shell is index.html plus the
+// hashed JS, WASM and CSS Trunk writes; the scenario data is embedded in the
+// WASM, so a cached shell is a working app offline.
+//
+// - Navigations: network first, falling back to the cached index.html and
+//   then to offline.html.
+// - Other same-origin GETs: cache first. Files outside the shell (the globe
+//   module, geo assets) are cached the first time they load.
+// - /api/ requests always go to the network.
+//
+// A new build installs alongside the old one and waits; the page offers to
+// reload and activates it with a SKIP_WAITING message.
+
+const CACHE = "rubigo-__CACHE_VERSION__";
+const SHELL = __SHELL_FILES__;
+const OFFLINE_PAGE = "/offline.html";
+
+self.addEventListener("install", (event) => {
+    event.waitUntil(caches.open(CACHE).then((cache) => cache.addAll(SHELL)));
+});
+
+self.addEventListener("activate", (event) => {
+    event.waitUntil(
+        caches
+            .keys()
+            .then((keys) =>
+                Promise.all(
+                    keys
+                        .filter((key) => key.startsWith("rubigo-") && key !== CACHE)
+                        .map((key) => caches.delete(key)),
+                ),
+            )
+            .then(() => self.clients.claim()),
+    );
+});
+
+self.addEventListener("message", (event) => {
+    if (event.data && event.data.type === "SKIP_WAITING") {
+        self.skipWaiting();
+    }
+});
+
+self.addEventListener("fetch", (event) => {
+    const request = event.request;
+    const url = new URL(request.url);
+    if (request.method !== "GET" || url.origin !== self.location.origin) {
+        return;
+    }
+    if (url.pathname.startsWith("/api/")) {
+        return;
+    }
+
+    if (request.mode === "navigate") {
+        // Client-side routes all load the same shell
+        event.respondWith(
+            fetch(request).catch(() =>
+                caches
+                    .match("/index.html")
+                    .then((shell) => shell || caches.match(OFFLINE_PAGE)),
+            ),
+        );
+        return;
+    }
+
+    event.respondWith(
+        caches.match(request).then(
+            (cached) =>
+                cached ||
+                fetch(request).then((response) => {
+                    if (response.ok) {
+                        const copy = response.clone();
+                        caches.open(CACHE).then((cache) => cache.put(request, copy));
+                    }
+                    return response;
+                }),
+        ),
+    );
+});
@@ -0,0 +1,102 @@
+//! Activity Feeds
+//!
+//! Loads the "Activity" tab of detail panels through `ActivityAction`s on
+//! the action broker, and posts comments as the signed-in persona.
+//! [`provide_activity`] records who is signed in; pages pass
+//! [`Activity::opener`] to panels that show a feed.
+
+use crate::broker;
+use actions::{ActionBroker, ActivityAction, ActivityResponse, CommentData};
+use leptos::prelude::*;
+use ui_core::features::activity::{ActivityFeed, ActivityQuery};
+use ui_core::recorder::{use_recorder, Recorder};
+
+/// Signed-in persona, for commenting
+#[derive(Clone, Copy)]
+pub struct Activity {
+    persona: Signal<Option<String>>,
+}
+
+impl Activity {
+    /// Callback loading a record's feed
+    ///
+    /// Feeds are fetched once when opened and again after each comment.
+    pub fn opener(&self) -> Callback<ActivityQuery, ActivityFeed> {
+        let persona = self.persona;
+        let recorder = StoredValue::new_local(use_recorder());
+        Callback::new(move |query: ActivityQuery| open(query, persona, recorder.get_value()))
+    }
+}
+
+pub fn provide_activity(persona: Signal<Option<String>>) {
+    provide_context(Activity { persona });
+}
+
+pub fn use_activity() -> Activity {
+    expect_context()
+}
+
+/// Fetch the feed for `query` into `items`
+///
+/// On failure the feed shows as empty rather than loading forever.
+fn load(
+    query: ActivityQuery,
+    items: RwSignal<Option<Vec<actions::ActivityData>>>,
+    recorder: Option<Recorder>,
+) {
+    leptos::task::spawn_local(async move {
+        match broker(recorder).dispatch(ActivityAction::Feed(query)).await {
+            Ok(ActivityResponse::Feed(feed)) => items.set(Some(feed)),
+            other => {
+                match other {
+                    Ok(ActivityResponse::Error(e)) => log::warn!("Activity unavailable: {}", e),
+                    Err(e) => log::warn!("Activity unavailable: {}", e),
+                    Ok(_) => {}
+                }
+                if items.get_untracked().is_none() {
+                    items.set(Some(Vec::new()));
+                }
+            }
+        }
+    });
+}
+
+fn open(
+    query: ActivityQuery,
+    persona: Signal<Option<String>>,
+    recorder: Option<Recorder>,
+) -> ActivityFeed {
+    let items = RwSignal::new(None);
+    let entity = query.entity.clone();
+    let query = StoredValue::new(query);
+    let recorder = StoredValue::new_local(recorder);
+    load(query.get_value(), items, recorder.get_value());
+
+    let on_comment = Callback::new(move |body: String| {
+        let Some(author) = persona.get_untracked() else {
+            log::warn!("Comment not posted: nobody is signed in");
+            return;
+        };
+        let comment = CommentData {
+            author,
+            body,
+            at: chrono::Utc::now().to_rfc3339(),
+        };
+        let action = ActivityAction::Comment(entity.clone(), comment);
+        leptos::task::spawn_local(async move {
+            match broker(recorder.get_value()).dispatch(action).await {
+                Ok(ActivityResponse::Success) => {
+                    load(query.get_value(), items, recorder.get_value())
+                }
+                Ok(ActivityResponse::Error(e)) => log::warn!("Comment not posted: {}", e),
+                Ok(_) => {}
+                Err(e) => log::warn!("Comment not posted: {}", e),
+            }
+        });
+    });
+
+    ActivityFeed {
+        items: items.into(),
+        on_comment,
+    }
+}
@@ -0,0 +1,99 @@
+//! Assets
+//!
+//! Loads the inventory through `AssetAction`s on the action broker and
+//! applies moves and deletions, each recorded with the undo manager: a
+//! deletion is undone with `asset.restore` while the server still keeps the
+//! asset, a move by moving the asset back.
+
+use crate::broker;
+use actions::{ActionBroker, AssetAction, AssetListQuery, AssetResponse, MoveAssetData};
+use leptos::prelude::*;
+use ui_core::features::assets::{location_label, AssetData, AssetInventoryFeed};
+use ui_core::hooks::use_undo;
+use ui_core::recorder::{use_recorder, Recorder};
+
+fn load(
+    assets: RwSignal<Option<Vec<AssetData>>>,
+    error: RwSignal<Option<String>>,
+    recorder: Option<Recorder>,
+) {
+    leptos::task::spawn_local(async move {
+        match broker(recorder)
+            .dispatch(AssetAction::List(AssetListQuery::default()))
+            .await
+        {
+            Ok(AssetResponse::List(list)) => assets.set(Some(list)),
+            other => {
+                let message = match other {
+                    Ok(AssetResponse::Error(e)) => e,
+                    Err(e) => e.to_string(),
+                    Ok(_) => "Unexpected response".to_string(),
+                };
+                log::warn!("Assets unavailable: {}", message);
+                error.set(Some(message));
+                if assets.get_untracked().is_none() {
+                    assets.set(Some(Vec::new()));
+                }
+            }
+        }
+    });
+}
+
+/// The inventory, loaded now and again after each change
+pub fn feed() -> AssetInventoryFeed {
+    let assets = RwSignal::new(None);
+    let error = RwSignal::new(None);
+    let recorder = StoredValue::new_local(use_recorder());
+    let undo = use_undo();
+    load(assets, error, recorder.get_value());
+
+    // Changes are refused or applied, then the list is reloaded
+    let apply = move |action: AssetAction| {
+        leptos::task::spawn_local(async move {
+            match broker(recorder.get_value()).dispatch(action).await {
+                Ok(AssetResponse::Error(e)) => error.set(Some(e)),
+                Ok(_) => error.set(None),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            load(assets, error, recorder.get_value());
+        });
+    };
+
+    let on_delete = Callback::new(move |asset: AssetData| {
+        let id = asset.id;
+        apply(AssetAction::Delete(id.clone()));
+        let restore_id = id.clone();
+        undo.record(
+            format!("Deleted {}", asset.name),
+            move || apply(AssetAction::Restore(restore_id.clone())),
+            move || apply(AssetAction::Delete(id.clone())),
+        );
+    });
+
+    let on_move = Callback::new(move |(asset, to): (AssetData, MoveAssetData)| {
+        let from = MoveAssetData {
+            rack_id: asset.rack_id,
+            position_u: asset.position_u,
+        };
+        let label = format!(
+            "Moved {} to {}",
+            asset.name,
+            location_label(to.rack_id.as_deref(), to.position_u)
+        );
+        let id = asset.id;
+        apply(AssetAction::Move(id.clone(), to.clone()));
+        let back_id = id.clone();
+        undo.record(
+            label,
+            move || apply(AssetAction::Move(back_id.clone(), from.clone())),
+            move || apply(AssetAction::Move(id.clone(), to.clone())),
+        );
+    });
+
+    AssetInventoryFeed {
+        assets: assets.into(),
+        on_delete,
+        on_move,
+        error: error.into(),
+    }
+}
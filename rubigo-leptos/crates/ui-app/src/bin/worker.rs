@@ -0,0 +1,9 @@
+//! Web worker for expensive client work
+//!
+//! Built by Trunk alongside the app (see `index.html`) and started by the
+//! app's `WorkerBridge`.
+
+fn main() {
+    console_error_panic_hook::set_once();
+    ui_core::worker::WorkerRegistry::builtin().serve();
+}
@@ -0,0 +1,100 @@
+//! Change Request Reviews
+//!
+//! Loads the review queue through `ChangeRequestAction`s on the action
+//! broker and reviews requests as the signed-in persona, which the server
+//! checks against its approvers. [`provide_reviews`] records who is signed
+//! in; the Reviews page takes its feed from [`Reviews::feed`].
+
+use crate::broker;
+use actions::{ActionBroker, ChangeRequestAction, ChangeRequestResponse, ReviewData};
+use leptos::prelude::*;
+use ui_core::features::change_requests::{ChangeRequestData, ReviewQueueFeed};
+use ui_core::recorder::{use_recorder, Recorder};
+
+/// Signed-in persona, for reviewing
+#[derive(Clone, Copy)]
+pub struct Reviews {
+    persona: Signal<Option<String>>,
+}
+
+impl Reviews {
+    /// Every change request, loaded now and again after each review
+    pub fn feed(&self) -> ReviewQueueFeed {
+        open(self.persona, use_recorder())
+    }
+}
+
+pub fn provide_reviews(persona: Signal<Option<String>>) {
+    provide_context(Reviews { persona });
+}
+
+pub fn use_reviews() -> Reviews {
+    expect_context()
+}
+
+fn load(
+    requests: RwSignal<Option<Vec<ChangeRequestData>>>,
+    error: RwSignal<Option<String>>,
+    recorder: Option<Recorder>,
+) {
+    leptos::task::spawn_local(async move {
+        match broker(recorder)
+            .dispatch(ChangeRequestAction::List(None))
+            .await
+        {
+            Ok(ChangeRequestResponse::List(list)) => requests.set(Some(list)),
+            other => {
+                let message = match other {
+                    Ok(ChangeRequestResponse::Error(e)) => e,
+                    Err(e) => e.to_string(),
+                    Ok(_) => "Unexpected response".to_string(),
+                };
+                log::warn!("Change requests unavailable: {}", message);
+                error.set(Some(message));
+                if requests.get_untracked().is_none() {
+                    requests.set(Some(Vec::new()));
+                }
+            }
+        }
+    });
+}
+
+fn open(persona: Signal<Option<String>>, recorder: Option<Recorder>) -> ReviewQueueFeed {
+    let requests = RwSignal::new(None);
+    let error = RwSignal::new(None);
+    let recorder = StoredValue::new_local(recorder);
+    load(requests, error, recorder.get_value());
+
+    let review = move |id: String, note: Option<String>, approve: bool| {
+        let Some(reviewer) = persona.get_untracked() else {
+            log::warn!("Change request not reviewed: nobody is signed in");
+            return;
+        };
+        let review = ReviewData {
+            reviewer,
+            note,
+            at: chrono::Utc::now().to_rfc3339(),
+        };
+        let action = if approve {
+            ChangeRequestAction::Approve(id, review)
+        } else {
+            ChangeRequestAction::Reject(id, review)
+        };
+        leptos::task::spawn_local(async move {
+            match broker(recorder.get_value()).dispatch(action).await {
+                Ok(ChangeRequestResponse::Request(_)) => error.set(None),
+                Ok(ChangeRequestResponse::Error(e)) => error.set(Some(e)),
+                Ok(_) => {}
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            load(requests, error, recorder.get_value());
+        });
+    };
+
+    ReviewQueueFeed {
+        requests: requests.into(),
+        on_approve: Callback::new(move |(id, note)| review(id, note, true)),
+        on_reject: Callback::new(move |(id, note)| review(id, note, false)),
+        error: error.into(),
+    }
+}
@@ -0,0 +1,115 @@
+//! Collaborative Editing
+//!
+//! Connects edit forms to the gui-server's collab WebSocket, so people
+//! editing the same record see each other's changes as they type (see
+//! [`actions::collab`]). [`provide_collab`] records who is signed in;
+//! pages pass [`Collab::opener`] to forms that support it.
+//!
+//! The Tauri shell edits its own database and has no collab server, so
+//! forms there stay single-user.
+
+use leptos::prelude::*;
+use ui_core::features::collab::{CollabFeed, CollabSession, FieldEdit, ServerFrame, COLLAB_PATH};
+use ui_core::utils::socket_url;
+use wasm_bindgen::prelude::*;
+use web_sys::{MessageEvent, WebSocket};
+
+/// Signed-in persona, for opening collab sessions
+#[derive(Clone, Copy)]
+pub struct Collab {
+    persona: Signal<Option<String>>,
+}
+
+impl Collab {
+    /// Callback opening the shared edits of a record by entity key; `None`
+    /// where there is no collab server
+    ///
+    /// The session closes when the reactive scope it was opened in is
+    /// cleaned up, e.g. when the form closes.
+    pub fn opener(&self) -> Option<Callback<String, CollabFeed>> {
+        if crate::desktop::in_tauri() {
+            return None;
+        }
+        let persona = self.persona;
+        Some(Callback::new(move |entity: String| {
+            open(&persona.get_untracked().unwrap_or_default(), &entity)
+        }))
+    }
+}
+
+pub fn provide_collab(persona: Signal<Option<String>>) {
+    provide_context(Collab { persona });
+}
+
+pub fn use_collab() -> Collab {
+    expect_context()
+}
+
+/// Join `entity`'s session as `persona`
+///
+/// If the socket can't be opened the feed never becomes ready, and the
+/// form simply isn't shared.
+fn open(persona: &str, entity: &str) -> CollabFeed {
+    let session = RwSignal::new(CollabSession::new(persona));
+    let query = format!(
+        "{COLLAB_PATH}?persona={}&entity={}",
+        js_sys::encode_uri_component(persona),
+        js_sys::encode_uri_component(entity)
+    );
+    let socket = socket_url(&query).and_then(|url| WebSocket::new(&url).ok());
+    let socket = StoredValue::new_local(socket);
+
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |ev: MessageEvent| {
+        let Some(text) = ev.data().as_string() else {
+            return;
+        };
+        match serde_json::from_str::<ServerFrame>(&text) {
+            Ok(ServerFrame::Error { message }) => log::warn!("collab: {message}"),
+            Ok(frame) => session.update(|session| {
+                session.receive(frame);
+            }),
+            Err(e) => log::warn!("collab: unreadable frame: {e}"),
+        }
+    });
+    socket.with_value(|socket| {
+        if let Some(socket) = socket {
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        }
+    });
+    // Kept with the session and dropped along with it
+    StoredValue::new_local(on_message);
+
+    on_cleanup(move || {
+        socket.with_value(|socket| {
+            if let Some(socket) = socket {
+                socket.set_onmessage(None);
+                let _ = socket.close();
+            }
+        });
+    });
+
+    let send = move |edit: FieldEdit| {
+        let Ok(text) = serde_json::to_string(&edit) else {
+            return;
+        };
+        socket.with_value(|socket| {
+            if let Some(socket) = socket {
+                let _ = socket.send_with_str(&text);
+            }
+        });
+    };
+
+    CollabFeed {
+        session: session.into(),
+        on_edit: Callback::new(move |(field, value): (String, String)| {
+            if let Some(Some(edit)) =
+                session.try_update(|session| session.local_edit(&field, &value))
+            {
+                send(edit);
+            }
+        }),
+        on_dismiss: Callback::new(move |field: String| {
+            session.update(|session| session.dismiss(&field));
+        }),
+    }
+}
@@ -0,0 +1,139 @@
+//! Custom Fields
+//!
+//! Loads and saves admin-defined fields through `CustomFieldAction`s on the
+//! action broker: values for detail panels ([`opener`]) and definitions for
+//! the admin page ([`admin_feed`]).
+
+use std::collections::BTreeMap;
+
+use crate::broker;
+use actions::{ActionBroker, CustomFieldAction, CustomFieldData, CustomFieldResponse};
+use leptos::prelude::*;
+use ui_core::features::custom_fields::{
+    CustomFieldAdminFeed, CustomFieldsFeed, FIELD_ENTITY_KINDS,
+};
+use ui_core::recorder::{use_recorder, Recorder};
+
+/// Callback loading a record's custom fields by entity key
+pub fn opener() -> Callback<String, CustomFieldsFeed> {
+    let recorder = StoredValue::new_local(use_recorder());
+    Callback::new(move |entity: String| open(entity, recorder.get_value()))
+}
+
+/// Log why an action failed and hand back the refusal to show, if any
+fn refusal(
+    result: Result<CustomFieldResponse, actions::ActionError>,
+    what: &str,
+) -> Option<String> {
+    match result {
+        Ok(CustomFieldResponse::Error(e)) => {
+            log::warn!("{}: {}", what, e);
+            Some(e)
+        }
+        Err(e) => {
+            log::warn!("{}: {}", what, e);
+            Some(format!("{}: {}", what, e))
+        }
+        Ok(_) => None,
+    }
+}
+
+/// Fetch the fields defined for `kind` into `fields`
+fn load_definitions(
+    kind: String,
+    fields: RwSignal<Option<Vec<CustomFieldData>>>,
+    recorder: Option<Recorder>,
+) {
+    leptos::task::spawn_local(async move {
+        match broker(recorder)
+            .dispatch(CustomFieldAction::Definitions(kind))
+            .await
+        {
+            Ok(CustomFieldResponse::Definitions(defined)) => fields.set(Some(defined)),
+            other => {
+                refusal(other, "Custom fields unavailable");
+                fields.set(Some(Vec::new()));
+            }
+        }
+    });
+}
+
+fn load_values(
+    entity: String,
+    values: RwSignal<BTreeMap<String, String>>,
+    recorder: Option<Recorder>,
+) {
+    leptos::task::spawn_local(async move {
+        match broker(recorder)
+            .dispatch(CustomFieldAction::Values(entity))
+            .await
+        {
+            Ok(CustomFieldResponse::Values(stored)) => values.set(stored),
+            other => {
+                refusal(other, "Custom field values unavailable");
+            }
+        }
+    });
+}
+
+fn open(entity: String, recorder: Option<Recorder>) -> CustomFieldsFeed {
+    let fields = RwSignal::new(None);
+    let values = RwSignal::new(BTreeMap::new());
+    let error = RwSignal::new(None);
+    let kind = entity.split(':').next().unwrap_or_default().to_string();
+    let entity = StoredValue::new(entity);
+    let recorder = StoredValue::new_local(recorder);
+    load_definitions(kind, fields, recorder.get_value());
+    load_values(entity.get_value(), values, recorder.get_value());
+
+    let on_save = Callback::new(move |changes: BTreeMap<String, String>| {
+        let action = CustomFieldAction::SetValues(entity.get_value(), changes);
+        leptos::task::spawn_local(async move {
+            let result = broker(recorder.get_value()).dispatch(action).await;
+            let refused = refusal(result, "Custom fields not saved");
+            error.set(refused);
+            // Reload either way, so the panel shows what was actually stored
+            load_values(entity.get_value(), values, recorder.get_value());
+        });
+    });
+
+    CustomFieldsFeed {
+        fields: fields.into(),
+        values: values.into(),
+        on_save,
+        error: error.into(),
+    }
+}
+
+/// Definitions of one kind of record at a time, for the admin page
+pub fn admin_feed() -> CustomFieldAdminFeed {
+    let recorder = StoredValue::new_local(use_recorder());
+    let entity_kind = RwSignal::new(FIELD_ENTITY_KINDS[0].to_string());
+    let fields = RwSignal::new(None);
+    let error = RwSignal::new(None);
+    let reload =
+        move || load_definitions(entity_kind.get_untracked(), fields, recorder.get_value());
+    reload();
+
+    // Changes are refused or applied, then the list is reloaded
+    let apply = move |action: CustomFieldAction| {
+        leptos::task::spawn_local(async move {
+            let result = broker(recorder.get_value()).dispatch(action).await;
+            error.set(refusal(result, "Custom field not changed"));
+            reload();
+        });
+    };
+
+    CustomFieldAdminFeed {
+        entity_kind: entity_kind.into(),
+        fields: Signal::derive(move || fields.get().unwrap_or_default()),
+        on_kind: Callback::new(move |kind: String| {
+            entity_kind.set(kind);
+            error.set(None);
+            reload();
+        }),
+        on_define: Callback::new(move |field| apply(CustomFieldAction::Define(field))),
+        on_remove: Callback::new(move |id| apply(CustomFieldAction::RemoveDefinition(id))),
+        error: error.into(),
+    }
+}
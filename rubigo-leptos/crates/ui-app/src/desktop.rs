@@ -0,0 +1,282 @@
+//! Desktop Shell Bridge
+//!
+//! When running inside the Tauri shell, its native menu and tray ask for a
+//! page by emitting a `navigate` event with the route as payload
+//! (`gui-tauri/src-tauri/src/menu.rs`). [`DesktopBridge`] follows those
+//! events with the router. The shell also reports how its local database is
+//! syncing, which [`connection_status`] turns into the header's indicator,
+//! and updates itself through [`UpdateDialog`]. In a browser there is no
+//! `__TAURI__` global and none of these do anything.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use actions::sync::{SyncStatus, SYNC_STATUS_EVENT};
+use actions::updates::{
+    UpdateInfo, UpdateProgress, CHANNELS, CHECK_UPDATES_EVENT, UPDATE_PROGRESS_EVENT,
+};
+use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
+use serde::de::DeserializeOwned;
+use ui_core::elements::Modal;
+use ui_core::layout::ConnectionStatus;
+use ui_core::primitives::{Button, ButtonVariant, Select, SelectOption};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+/// Must match `menu::NAVIGATE_EVENT` in the Tauri shell
+const NAVIGATE_EVENT: &str = "navigate";
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen)]
+    fn tauri_listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+    fn tauri_invoke(cmd: &str) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+    fn tauri_invoke_with(cmd: &str, args: &JsValue) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "app"], js_name = getVersion)]
+    fn tauri_app_version() -> js_sys::Promise;
+}
+
+thread_local! {
+    static LISTENING: Cell<bool> = const { Cell::new(false) };
+    /// Navigation for the currently mounted router
+    static NAVIGATE: RefCell<Option<Rc<dyn Fn(&str)>>> = RefCell::new(None);
+}
+
+pub(crate) fn in_tauri() -> bool {
+    js_sys::Reflect::has(&js_sys::global(), &JsValue::from_str("__TAURI__")).unwrap_or(false)
+}
+
+/// Payload of a Tauri event (`{ event, id, payload }`)
+fn event_payload(event: &JsValue) -> Option<JsValue> {
+    js_sys::Reflect::get(event, &JsValue::from_str("payload")).ok()
+}
+
+/// Route carried by a navigate event
+fn event_route(event: &JsValue) -> Option<String> {
+    event_payload(event)?
+        .as_string()
+        .filter(|route| route.starts_with('/'))
+}
+
+/// Call `handler` with the payload of every `event` the shell emits
+fn on_event(event: &str, mut handler: impl FnMut(JsValue) + 'static) {
+    let handler = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+        if let Some(payload) = event_payload(&event) {
+            handler(payload);
+        }
+    });
+    let _ = tauri_listen(event, &handler);
+    handler.forget();
+}
+
+/// Run a shell command; `args` is an object of named arguments
+async fn invoke<T: DeserializeOwned>(cmd: &str, args: &JsValue) -> Result<T, String> {
+    let value = JsFuture::from(tauri_invoke_with(cmd, args))
+        .await
+        .map_err(|e| e.as_string().unwrap_or_else(|| format!("{:?}", e)))?;
+    serde_wasm_bindgen::from_value(value).map_err(|e| e.to_string())
+}
+
+fn to_connection(status: SyncStatus) -> ConnectionStatus {
+    match status {
+        SyncStatus::LocalOnly | SyncStatus::Synced => ConnectionStatus::Connected,
+        SyncStatus::Syncing { .. } => ConnectionStatus::Syncing,
+        SyncStatus::Offline { pending, .. } => ConnectionStatus::Offline { pending },
+    }
+}
+
+/// Listen once for the shell's navigate events
+///
+/// The listener outlives any one router (signing out unmounts it), so it
+/// forwards to whichever navigation is current.
+fn listen() {
+    if LISTENING.replace(true) {
+        return;
+    }
+    let handler = Closure::<dyn FnMut(JsValue)>::new(|event: JsValue| {
+        let Some(route) = event_route(&event) else {
+            return;
+        };
+        let navigate = NAVIGATE.with_borrow(|navigate| navigate.clone());
+        if let Some(navigate) = navigate {
+            navigate(&route);
+        }
+    });
+    let _ = tauri_listen(NAVIGATE_EVENT, &handler);
+    handler.forget();
+}
+
+/// Follows navigation requests from the desktop shell's menu and tray
+///
+/// Place inside the `<Router>`.
+#[component]
+pub fn DesktopBridge() -> impl IntoView {
+    if in_tauri() {
+        let navigate = use_navigate();
+        NAVIGATE.set(Some(Rc::new(move |route: &str| {
+            navigate(route, Default::default())
+        })));
+        listen();
+        on_cleanup(|| NAVIGATE.set(None));
+    }
+}
+
+/// Connection indicator for the header
+///
+/// Connected in a browser; in the desktop shell, follows its sync status,
+/// starting from the current one since the first event may already be past.
+pub fn connection_status() -> Signal<ConnectionStatus> {
+    if !in_tauri() {
+        return ConnectionStatus::Connected.into();
+    }
+    let status = RwSignal::new(ConnectionStatus::Syncing);
+    let update = move |value: JsValue| match serde_wasm_bindgen::from_value::<SyncStatus>(value) {
+        Ok(sync) => status.set(to_connection(sync)),
+        Err(e) => log::warn!("Unreadable sync status: {}", e),
+    };
+
+    on_event(SYNC_STATUS_EVENT, update);
+
+    wasm_bindgen_futures::spawn_local(async move {
+        match JsFuture::from(tauri_invoke("sync_status")).await {
+            Ok(current) => update(current),
+            Err(e) => log::warn!("Could not read sync status: {:?}", e),
+        }
+    });
+    status.into()
+}
+
+/// Where the update dialog is in checking for and installing an update
+#[derive(Debug, Clone, PartialEq)]
+enum UpdateState {
+    Idle,
+    Checking,
+    UpToDate,
+    Available(UpdateInfo),
+    /// Downloading; progress arrives once the download starts
+    Installing(Option<UpdateProgress>),
+    Failed(String),
+}
+
+/// "Check for updates" dialog for the desktop shell
+///
+/// Opens (and checks) when the shell's menu asks for it. Shows the release
+/// notes of a newer version, installs it on request, and lets the user move
+/// between the stable and beta channels.
+#[component]
+pub fn UpdateDialog() -> impl IntoView {
+    let open = RwSignal::new(false);
+    let state = RwSignal::new(UpdateState::Idle);
+    let channel = RwSignal::new(String::new());
+    let current = RwSignal::new(String::new());
+
+    let check = move || {
+        state.set(UpdateState::Checking);
+        wasm_bindgen_futures::spawn_local(async move {
+            state.set(
+                match invoke::<Option<UpdateInfo>>("check_for_update", &JsValue::UNDEFINED).await {
+                    Ok(Some(info)) => UpdateState::Available(info),
+                    Ok(None) => UpdateState::UpToDate,
+                    Err(e) => UpdateState::Failed(e),
+                },
+            );
+        });
+    };
+
+    if in_tauri() {
+        on_event(CHECK_UPDATES_EVENT, move |_| {
+            open.set(true);
+            check();
+        });
+        on_event(UPDATE_PROGRESS_EVENT, move |payload| {
+            if let Ok(progress) = serde_wasm_bindgen::from_value::<UpdateProgress>(payload) {
+                state.set(UpdateState::Installing(Some(progress)));
+            }
+        });
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Ok(name) = invoke::<String>("update_channel", &JsValue::UNDEFINED).await {
+                channel.set(name);
+            }
+            if let Ok(version) = JsFuture::from(tauri_app_version()).await {
+                current.set(version.as_string().unwrap_or_default());
+            }
+        });
+    }
+
+    let change_channel = Callback::new(move |name: String| {
+        wasm_bindgen_futures::spawn_local(async move {
+            let args = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&args, &"channel".into(), &name.into());
+            match invoke::<()>("set_update_channel", &args).await {
+                Ok(()) => check(),
+                Err(e) => state.set(UpdateState::Failed(e)),
+            }
+        });
+    });
+
+    let install = Callback::new(move |_| {
+        state.set(UpdateState::Installing(None));
+        wasm_bindgen_futures::spawn_local(async move {
+            // On success the app restarts into the new version
+            if let Err(e) = invoke::<()>("install_update", &JsValue::UNDEFINED).await {
+                state.set(UpdateState::Failed(e));
+            }
+        });
+    });
+
+    let channels: Vec<SelectOption> = CHANNELS
+        .iter()
+        .map(|(value, label)| SelectOption::new(*value, *label))
+        .collect();
+
+    view! {
+        <Modal open=open title="Software Update">
+            <div class="update-dialog">
+                <p class="update-current">"Installed version " {move || current.get()}</p>
+                <Select
+                    value=channel
+                    options=channels.clone()
+                    label="Release channel"
+                    on_change=change_channel
+                />
+                <div class="update-status" role="status">
+                    {move || match state.get() {
+                        UpdateState::Idle => ().into_any(),
+                        UpdateState::Checking => view! { <p>"Checking for updates…"</p> }.into_any(),
+                        UpdateState::UpToDate => view! { <p>"You're up to date."</p> }.into_any(),
+                        UpdateState::Available(info) => view! {
+                            <h3>"Version " {info.version} " is available"</h3>
+                            {info.date.map(|date| view! { <p class="update-date">"Released " {date}</p> })}
+                            {info.notes.map(|notes| view! { <pre class="update-notes">{notes}</pre> })}
+                            <Button on_click=install>"Install and Restart"</Button>
+                        }.into_any(),
+                        UpdateState::Installing(progress) => {
+                            let fraction = progress.and_then(|p| p.fraction());
+                            view! {
+                                <p>"Downloading update…"</p>
+                                {match fraction {
+                                    Some(fraction) => view! { <progress max="1" value=fraction></progress> }.into_any(),
+                                    None => view! { <progress></progress> }.into_any(),
+                                }}
+                            }.into_any()
+                        }
+                        UpdateState::Failed(error) => view! {
+                            <p class="update-error">"Update failed: " {error}</p>
+                        }.into_any(),
+                    }}
+                </div>
+                <div class="update-actions">
+                    <Button variant=ButtonVariant::Secondary on_click=Callback::new(move |_| check())>
+                        "Check Now"
+                    </Button>
+                </div>
+            </div>
+        </Modal>
+    }
+}
@@ -0,0 +1,182 @@
+//! Globe Viewer
+//!
+//! The Bevy globe is built as its own WASM module
+//! (`crates/bevy-viewer/build-module.sh`) and fetched the first time the
+//! Sites page shows it, so the main bundle carries no Bevy code. The module's
+//! exports, declared in [`GlobeModule`], are the only calls between the two.
+
+use leptos::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Where the Trunk build puts the module, relative to the site root
+const MODULE_BASE: &str = "/globe";
+
+/// Must match `bevy_viewer::MODULE_API_VERSION`
+const API_VERSION: u32 = 1;
+
+const CANVAS_ID: &str = "bevy_canvas";
+
+#[wasm_bindgen(module = "/src/globe_loader.js")]
+extern "C" {
+    #[wasm_bindgen(catch)]
+    async fn load_globe_module(
+        base: &str,
+        on_progress: &Closure<dyn FnMut(f64, f64)>,
+    ) -> Result<JsValue, JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// Exports of the globe module (`bevy_viewer::module`)
+    type GlobeModule;
+
+    #[wasm_bindgen(method)]
+    fn api_version(this: &GlobeModule) -> u32;
+
+    #[wasm_bindgen(method)]
+    fn start_globe(this: &GlobeModule, canvas: &str) -> bool;
+}
+
+/// Bytes of the module downloaded so far
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LoadProgress {
+    loaded: f64,
+    /// Zero when the server sent no length
+    total: f64,
+}
+
+impl LoadProgress {
+    /// Fraction downloaded, if the total is known
+    ///
+    /// A compressed response's length is smaller than the bytes read, so
+    /// this is capped at one.
+    fn fraction(&self) -> Option<f64> {
+        (self.total > 0.0).then(|| (self.loaded / self.total).min(1.0))
+    }
+
+    fn label(&self) -> String {
+        match self.fraction() {
+            Some(f) => format!("Loading 3D viewer… {:.0}%", f * 100.0),
+            None => format!("Loading 3D viewer… {:.1} MB", self.loaded / 1_000_000.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum GlobeStatus {
+    Loading(LoadProgress),
+    Running,
+    Failed(String),
+}
+
+fn check_version(found: u32) -> Result<(), String> {
+    if found == API_VERSION {
+        Ok(())
+    } else {
+        Err(format!(
+            "Globe module API v{found} does not match the app (v{API_VERSION}); rebuild both"
+        ))
+    }
+}
+
+fn describe(error: JsValue) -> String {
+    match error.dyn_ref::<js_sys::Error>() {
+        Some(e) => e.message().into(),
+        None => error.as_string().unwrap_or_else(|| format!("{error:?}")),
+    }
+}
+
+async fn load_and_start(on_progress: &Closure<dyn FnMut(f64, f64)>) -> Result<(), String> {
+    let module: GlobeModule = load_globe_module(MODULE_BASE, on_progress)
+        .await
+        .map_err(describe)?
+        .unchecked_into();
+    check_version(module.api_version())?;
+    // Bevy's event loop outlives the page it was started on
+    if module.start_globe(&format!("#{CANVAS_ID}")) {
+        Ok(())
+    } else {
+        Err("The viewer is already running; reload the page to show it again".to_string())
+    }
+}
+
+/// The 3D globe, with a progress bar while its module downloads
+#[component]
+pub fn GlobeViewer() -> impl IntoView {
+    let status = RwSignal::new(GlobeStatus::Loading(LoadProgress::default()));
+
+    // The canvas is mounted by the time this runs, so Bevy can find it
+    Effect::new(move |_| {
+        leptos::task::spawn_local(async move {
+            let on_progress = Closure::<dyn FnMut(f64, f64)>::new(move |loaded, total| {
+                status.set(GlobeStatus::Loading(LoadProgress { loaded, total }));
+            });
+            let result = load_and_start(&on_progress).await;
+            status.set(match result {
+                Ok(()) => GlobeStatus::Running,
+                Err(e) => {
+                    log::error!("Globe viewer: {}", e);
+                    GlobeStatus::Failed(e)
+                }
+            });
+        });
+    });
+
+    view! {
+        <canvas id=CANVAS_ID></canvas>
+        {move || match status.get() {
+            GlobeStatus::Running => ().into_any(),
+            GlobeStatus::Loading(progress) => view! {
+                <div class="globe-loading" role="status">
+                    <progress max="1" value=progress.fraction()></progress>
+                    <p>{progress.label()}</p>
+                </div>
+            }
+            .into_any(),
+            GlobeStatus::Failed(message) => view! {
+                <div class="globe-loading globe-error" role="alert">
+                    <p>"The 3D viewer could not be loaded"</p>
+                    <p class="info-hint">{message}</p>
+                </div>
+            }
+            .into_any(),
+        }}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_is_a_capped_fraction_when_the_length_is_known() {
+        let half = LoadProgress {
+            loaded: 512.0,
+            total: 1024.0,
+        };
+        assert_eq!(half.fraction(), Some(0.5));
+        assert_eq!(half.label(), "Loading 3D viewer… 50%");
+
+        let compressed = LoadProgress {
+            loaded: 3000.0,
+            total: 1000.0,
+        };
+        assert_eq!(compressed.fraction(), Some(1.0));
+
+        let unknown = LoadProgress {
+            loaded: 2_500_000.0,
+            total: 0.0,
+        };
+        assert_eq!(unknown.fraction(), None);
+        assert_eq!(unknown.label(), "Loading 3D viewer… 2.5 MB");
+    }
+
+    #[test]
+    fn mismatched_module_is_refused() {
+        assert!(check_version(API_VERSION).is_ok());
+        assert!(check_version(API_VERSION + 1)
+            .unwrap_err()
+            .contains("rebuild"));
+    }
+}
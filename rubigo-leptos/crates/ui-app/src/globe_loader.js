@@ -0,0 +1,53 @@
+// Fetches the globe viewer's WASM module (crates/bevy-viewer/build-module.sh)
+// and reports download progress. The module is cached, so revisiting the
+// Sites page does not download it again.
+
+let loading = null;
+
+async function fetchWithProgress(url, onProgress) {
+    const response = await fetch(url);
+    if (!response.ok) {
+        throw new Error(`${url}: ${response.status} ${response.statusText}`);
+    }
+    const total = Number(response.headers.get("content-length")) || 0;
+    if (!response.body) {
+        const bytes = new Uint8Array(await response.arrayBuffer());
+        onProgress(bytes.length, total);
+        return bytes;
+    }
+
+    const reader = response.body.getReader();
+    const chunks = [];
+    let loaded = 0;
+    for (;;) {
+        const { done, value } = await reader.read();
+        if (done) break;
+        chunks.push(value);
+        loaded += value.length;
+        onProgress(loaded, total);
+    }
+
+    const bytes = new Uint8Array(loaded);
+    let offset = 0;
+    for (const chunk of chunks) {
+        bytes.set(chunk, offset);
+        offset += chunk.length;
+    }
+    return bytes;
+}
+
+export function load_globe_module(base, onProgress) {
+    if (!loading) {
+        loading = (async () => {
+            const bytes = await fetchWithProgress(`${base}/bevy_viewer_bg.wasm`, onProgress);
+            const module = await import(`${base}/bevy_viewer.js`);
+            await module.default({ module_or_path: bytes });
+            return module;
+        })();
+        // Let a failed load be retried
+        loading.catch(() => {
+            loading = null;
+        });
+    }
+    return loading;
+}
@@ -3,11 +3,33 @@
 //! This is the new client-side rendered application using the refactored
 //! component architecture.
 
+mod activity;
+mod assets;
+mod change_requests;
+mod collab;
+mod custom_fields;
+mod desktop;
+mod globe;
+mod pwa;
+mod training;
+
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use desktop::{DesktopBridge, UpdateDialog};
+use globe::GlobeViewer;
 use leptos::prelude::*;
 use leptos_router::components::*;
 use leptos_router::path;
+use ui_core::elements::{
+    provide_help, provide_toasts, provide_tours, HelpButton, HelpPanel, LazyIsland, ToastRegion,
+    Tour, TourAnchor, TourOverlay,
+};
+use ui_core::features::calendar::{CalendarEvent, EventType, ParticipantInfo, RecurrenceFrequency};
+use ui_core::features::reminders::{provide_reminders, system_notifier, ReminderSettings};
 use ui_core::features::user_session::{PersonaSwitcher, SignInScreen, UserInfo};
-use ui_core::layout::{ConnectionStatus, Layout, NavItem};
+use ui_core::hooks::{provide_undo, DEFAULT_UNDO_WINDOW_MS};
+use ui_core::layout::{Layout, NavItem};
+use ui_core::recorder::{provide_recorder, use_recorder, RecordRoutes, Recorder, RecordingBroker};
+use ui_core::worker::{provide_worker, WorkerBridge};
 
 fn main() {
     console_error_panic_hook::set_once();
@@ -55,9 +77,53 @@ fn get_nav_items() -> Vec<NavItem> {
             icon: "🔗",
             href: "/connections",
         },
+        NavItem {
+            id: "reviews",
+            label: "Reviews",
+            icon: "✅",
+            href: "/reviews",
+        },
+        NavItem {
+            id: "training",
+            label: "Training",
+            icon: "🎓",
+            href: "/training",
+        },
+        NavItem {
+            id: "fields",
+            label: "Custom Fields",
+            icon: "🧩",
+            href: "/fields",
+        },
     ]
 }
 
+/// The server's action broker, logging to `recorder` while a session is
+/// recorded
+fn broker(recorder: Option<Recorder>) -> RecordingBroker<actions::HttpBroker> {
+    RecordingBroker::new(actions::HttpBroker::from_origin(), recorder)
+}
+
+/// Shown while a session is recorded, with a button to save it
+#[component]
+fn RecordingIndicator(recorder: Recorder) -> impl IntoView {
+    let recorder = StoredValue::new_local(recorder);
+    let download = move |_| {
+        let name = format!("rubigo-session-{}.json", chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+        if let Err(e) = recorder.with_value(|r| r.download(&name)) {
+            log::warn!("Session download failed: {:?}", e);
+        }
+    };
+
+    view! {
+        <div class="recording-indicator" role="status">
+            <span class="recording-dot" aria-hidden="true"></span>
+            "Recording session"
+            <button type="button" on:click=download>"Download"</button>
+        </div>
+    }
+}
+
 /// Load available people for persona selection
 fn get_available_people() -> Vec<UserInfo> {
     use scenario_loader::embedded;
@@ -97,6 +163,104 @@ fn App() -> impl IntoView {
     let current_user: RwSignal<Option<UserInfo>> = RwSignal::new(initial_user);
     let persona_overlay_open = RwSignal::new(false);
 
+    // Transient notifications, including the Undo offer for destructive actions
+    provide_toasts(3);
+    let undo = provide_undo(DEFAULT_UNDO_WINDOW_MS);
+
+    // Offline app shell, with newer builds offered through a toast
+    let pwa = pwa::provide_pwa();
+
+    // Meeting reminders for the signed-in persona, with lead times kept
+    // between sessions
+    let reminder_settings = get_storage()
+        .and_then(|storage| storage.get_item("rubigo_reminders").ok().flatten())
+        .and_then(|json| serde_json::from_str::<ReminderSettings>(&json).ok())
+        .unwrap_or_default();
+    let reminders = provide_reminders(
+        Signal::stored(scenario_events()),
+        Signal::derive(move || current_user.get().map(|user| user.id)),
+        reminder_settings,
+        system_notifier(),
+    );
+    Effect::new(move |_| {
+        let settings = reminders.settings().get();
+        if let (Some(storage), Ok(json)) = (get_storage(), serde_json::to_string(&settings)) {
+            let _ = storage.set_item("rubigo_reminders", &json);
+        }
+    });
+    let toast_action = {
+        let undo = undo.toast_handler();
+        let snooze = reminders.toast_handler();
+        let update = pwa.toast_handler();
+        Callback::new(move |toast_id: u64| {
+            undo.run(toast_id);
+            snooze.run(toast_id);
+            update.run(toast_id);
+        })
+    };
+
+    // Help topics bundled with the app, opened from the Help menu and the
+    // "?" buttons beside titles
+    provide_help(ui_core::features::bundled_topics());
+
+    // Guided tours, from the Help menu or by themselves on first visit;
+    // which ones each persona has seen is kept between sessions
+    let tours_key = |user_id: &str| format!("rubigo_tours_{}", user_id);
+    let seen_tours = move |user: Option<UserInfo>| -> Vec<String> {
+        user.and_then(|user| get_storage()?.get_item(&tours_key(&user.id)).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    };
+    let tours = provide_tours(
+        vec![
+            Tour::new("getting-started", "Getting started").auto_start(),
+            Tour::new("training", "Training exercises"),
+        ],
+        seen_tours(current_user.get_untracked()),
+    );
+    Effect::new(move |previous: Option<Option<String>>| {
+        let user = current_user.get();
+        let id = user.as_ref().map(|user| user.id.clone());
+        if previous.is_some_and(|previous| previous != id) {
+            tours.restore(seen_tours(user));
+        }
+        id
+    });
+    Effect::new(move |_| {
+        let completed = tours.completed().get();
+        let user = current_user.get_untracked();
+        if let (Some(user), Some(storage), Ok(json)) =
+            (user, get_storage(), serde_json::to_string(&completed))
+        {
+            let _ = storage.set_item(&tours_key(&user.id), &json);
+        }
+    });
+
+    // Edit forms shared live with other signed-in people
+    collab::provide_collab(Signal::derive(move || current_user.get().map(|user| user.name)));
+
+    // Comments in detail panels are posted as the signed-in persona
+    activity::provide_activity(Signal::derive(move || current_user.get().map(|user| user.name)));
+
+    // Change requests are reviewed as the signed-in persona
+    change_requests::provide_reviews(Signal::derive(move || current_user.get().map(|user| user.name)));
+
+    // Exercises are done by the signed-in persona
+    training::provide_training(Signal::derive(move || current_user.get().map(|user| user.name)));
+
+    // Recurrence expansion and other heavy client work runs off the main thread
+    provide_worker(WorkerBridge::spawn("./worker_loader.js"));
+
+    // In the desktop shell, the header shows how its local database is syncing
+    let connection = desktop::connection_status();
+
+    // Session recording for bug reports, opted into from the console with
+    // `localStorage.setItem("rubigo_record", "1")`
+    let recorder = get_storage()
+        .and_then(|storage| storage.get_item("rubigo_record").ok().flatten())
+        .filter(|value| value == "1")
+        .map(|_| provide_recorder());
+
     // Callbacks for session management
     let open_persona_switcher = Callback::new(move |_: ()| {
         log::info!("Opening persona switcher");
@@ -114,6 +278,8 @@ fn App() -> impl IntoView {
 
     let handle_persona_select = Callback::new(move |user: UserInfo| {
         log::info!("Selected persona: {}", user.name);
+        // Picking a persona is a click, which browsers require before asking
+        reminders.request_permission();
         // Save to localStorage
         if let Some(storage) = get_storage() {
             let _ = storage.set_item("rubigo_user_id", &user.id);
@@ -122,6 +288,12 @@ fn App() -> impl IntoView {
     });
 
     view! {
+        <ToastRegion on_action=toast_action />
+        <TourOverlay />
+        <HelpPanel />
+        <UpdateDialog />
+        {recorder.map(|recorder| view! { <RecordingIndicator recorder=recorder /> })}
+
         // Persona switcher overlay - wrapped in reactive closure
         {move || {
             let people = available_people.clone();
@@ -149,20 +321,28 @@ fn App() -> impl IntoView {
                 let user = current_user.get();
                 view! {
                     <Router>
+                        <RecordRoutes />
+                        <DesktopBridge />
                         <Layout
                             nav_items=nav
-                            status=ConnectionStatus::Connected
+                            status=connection
                             current_user=user
                             on_switch_identity=open_persona_switcher
                             on_sign_out=handle_sign_out
                         >
                             <Routes fallback=|| view! { <PlaceholderPage title="404 - Not Found" /> }>
-                                <Route path=path!("/") view=HomePage />
+                                <Route path=path!("/") view=move || {
+                                    let user_id = current_user.get_untracked().map(|u| u.id).unwrap_or_default();
+                                    view! { <HomePage user_id=user_id /> }
+                                } />
                                 <Route path=path!("/calendar") view=CalendarPageWrapper />
                                 <Route path=path!("/personnel") view=PersonnelPageWrapper />
                                 <Route path=path!("/sites") view=SitesPageWrapper />
-                                <Route path=path!("/assets") view=|| view! { <PlaceholderPage title="Assets" /> } />
+                                <Route path=path!("/assets") view=AssetsPageWrapper />
                                 <Route path=path!("/connections") view=|| view! { <PlaceholderPage title="Connections" /> } />
+                                <Route path=path!("/reviews") view=ReviewsPageWrapper />
+                                <Route path=path!("/training") view=TrainingPageWrapper />
+                                <Route path=path!("/fields") view=CustomFieldsPageWrapper />
                             </Routes>
                         </Layout>
                     </Router>
@@ -172,47 +352,189 @@ fn App() -> impl IntoView {
     }
 }
 
-/// Home page - the first migrated module
+/// Home page - dashboard of configurable widgets
+///
+/// The layout is saved per user and the figures are refreshed periodically,
+/// both through `DashboardAction`s on the action broker.
 #[component]
-fn HomePage() -> impl IntoView {
+fn HomePage(
+    /// ID of the signed-in user, whose layout is loaded and saved
+    user_id: String,
+) -> impl IntoView {
+    use actions::{ActionBroker, DashboardAction, DashboardResponse, WidgetData};
+    use ui_core::features::dashboard::{
+        default_layout, DashboardData, DashboardFeed, WidgetConfig, WidgetKind,
+    };
+    use ui_core::features::DashboardGrid;
     use ui_core::primitives::*;
 
+    let layout = RwSignal::new(default_layout());
+    let data = RwSignal::new(None::<DashboardData>);
+    let user_id = StoredValue::new(user_id);
+    let recorder = StoredValue::new_local(use_recorder());
+
+    leptos::task::spawn_local(async move {
+        match broker(recorder.get_value())
+            .dispatch(DashboardAction::GetLayout(user_id.get_value()))
+            .await
+        {
+            Ok(DashboardResponse::Layout(Some(saved))) => layout.set(
+                saved
+                    .into_iter()
+                    .filter_map(|w| {
+                        Some(WidgetConfig::new(
+                            w.id,
+                            WidgetKind::parse(&w.kind)?,
+                            w.metric.as_deref(),
+                        ))
+                    })
+                    .collect(),
+            ),
+            Ok(_) => {}
+            Err(e) => log::warn!("Dashboard layout unavailable: {}", e),
+        }
+    });
+
+    load_dashboard_data(data, recorder.get_value());
+    let refresh = set_interval_with_handle(
+        move || load_dashboard_data(data, recorder.get_value()),
+        DASHBOARD_REFRESH,
+    );
+    on_cleanup(move || {
+        if let Ok(handle) = refresh {
+            handle.clear();
+        }
+    });
+
+    let on_layout_change = Callback::new(move |widgets: Vec<WidgetConfig>| {
+        layout.set(widgets.clone());
+        let widgets = widgets
+            .into_iter()
+            .map(|w| WidgetData {
+                id: w.id,
+                kind: w.kind.as_str().to_string(),
+                metric: w.metric,
+            })
+            .collect();
+        let action = DashboardAction::SaveLayout(user_id.get_value(), widgets);
+        leptos::task::spawn_local(async move {
+            match broker(recorder.get_value()).dispatch(action).await {
+                Ok(DashboardResponse::Error(e)) => log::warn!("Dashboard layout not saved: {}", e),
+                Err(e) => log::warn!("Dashboard layout not saved: {}", e),
+                Ok(_) => {}
+            }
+        });
+    });
+
+    let feed = DashboardFeed {
+        layout: layout.into(),
+        data: data.into(),
+        on_layout_change,
+    };
+
     view! {
         <div class="home-page">
-            <h1>"Welcome to Network Simulation"</h1>
+            <h1>"Welcome to Network Simulation" <HelpButton help_key="getting-started" /></h1>
             <p class="subtitle">"Refactored with Leptos 0.8 and reactive architecture"</p>
 
-            <div class="stats-grid">
-                <StatCard title="Sites" value="24" icon="🌍" />
-                <StatCard title="Assets" value="156" icon="🖥️" />
-                <StatCard title="Personnel" value="89" icon="👥" />
-                <StatCard title="Connections" value="42" icon="🔗" />
-            </div>
-
-            <div class="quick-actions">
-                <h2>"Quick Actions"</h2>
-                <div class="action-buttons">
-                    <Button variant=ButtonVariant::Primary>"Add Site"</Button>
-                    <Button variant=ButtonVariant::Secondary>"Add Asset"</Button>
-                    <Button variant=ButtonVariant::Secondary>"View Calendar"</Button>
+            <TourAnchor
+                tour="getting-started"
+                order=1
+                title="Your dashboard"
+                body="Widgets show the figures you care about and refresh on their own. Rearrange them and the layout is saved for you."
+            >
+                <div class="dashboard">
+                    <DashboardGrid feed=feed />
                 </div>
-            </div>
+            </TourAnchor>
+
+            <TourAnchor
+                tour="getting-started"
+                order=2
+                title="Quick actions"
+                body="Shortcuts to the things you do most. The sidebar reaches every other page, and the ? in the header replays this tour."
+            >
+                <div class="quick-actions">
+                    <h2>"Quick Actions"</h2>
+                    <div class="action-buttons">
+                        <Button variant=ButtonVariant::Primary>"Add Site"</Button>
+                        <Button variant=ButtonVariant::Secondary>"Add Asset"</Button>
+                        <Button variant=ButtonVariant::Secondary>"View Calendar"</Button>
+                    </div>
+                </div>
+            </TourAnchor>
         </div>
     }
 }
 
-/// Stat card component
-#[component]
-fn StatCard(title: &'static str, value: &'static str, icon: &'static str) -> impl IntoView {
-    view! {
-        <div class="stat-card">
-            <span class="stat-icon">{icon}</span>
-            <div class="stat-content">
-                <span class="stat-value">{value}</span>
-                <span class="stat-title">{title}</span>
-            </div>
-        </div>
-    }
+/// How often the dashboard figures are reloaded
+const DASHBOARD_REFRESH: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Fetch the current dashboard figures into `data`
+///
+/// On failure the widgets fall back to empty figures rather than spinning.
+fn load_dashboard_data(
+    data: RwSignal<Option<ui_core::features::dashboard::DashboardData>>,
+    recorder: Option<Recorder>,
+) {
+    use actions::{ActionBroker, DashboardAction, DashboardResponse};
+    use ui_core::features::dashboard::{
+        ChartSeries, DashboardData, RecentEvent, SitePoint, StatValue,
+    };
+
+    leptos::task::spawn_local(async move {
+        match broker(recorder)
+            .dispatch(DashboardAction::Snapshot)
+            .await
+        {
+            Ok(DashboardResponse::Snapshot(snapshot)) => data.set(Some(DashboardData {
+                stats: snapshot
+                    .stats
+                    .into_iter()
+                    .map(|s| StatValue {
+                        key: s.key,
+                        label: s.label,
+                        value: s.value,
+                    })
+                    .collect(),
+                charts: snapshot
+                    .charts
+                    .into_iter()
+                    .map(|c| ChartSeries {
+                        key: c.key,
+                        title: c.title,
+                        points: c.points,
+                    })
+                    .collect(),
+                events: snapshot
+                    .recent_events
+                    .into_iter()
+                    .map(|e| RecentEvent {
+                        title: e.title,
+                        detail: e.detail,
+                        at: e.at,
+                    })
+                    .collect(),
+                sites: snapshot
+                    .sites
+                    .into_iter()
+                    .map(|s| SitePoint {
+                        name: s.name,
+                        lat: s.lat,
+                        lon: s.lon,
+                    })
+                    .collect(),
+            })),
+            other => {
+                if let Err(e) = other {
+                    log::warn!("Dashboard figures unavailable: {}", e);
+                }
+                if data.get_untracked().is_none() {
+                    data.set(Some(DashboardData::default()));
+                }
+            }
+        }
+    });
 }
 
 /// Placeholder for tabs not yet migrated
@@ -263,83 +585,111 @@ fn PersonnelPageWrapper() -> impl IntoView {
         })
         .collect();
 
+    let activity = activity::use_activity().opener();
+    let custom_fields = custom_fields::opener();
+
     view! {
-        <PersonnelPage employees=employees />
+        <PersonnelPage employees=employees activity=Some(activity) custom_fields=Some(custom_fields) />
     }
 }
 
-/// Calendar page with sample events
-#[component]
-fn CalendarPageWrapper() -> impl IntoView {
-    use chrono::{NaiveDateTime, TimeZone, Utc};
-    use scenario_loader::embedded;
-    use ui_core::features::calendar::{
-        CalendarEvent, CalendarPage, EventType, RecurrenceFrequency,
+/// Convert scenario Event to ui-core CalendarEvent
+fn convert_event(
+    e: &scenario_loader::Event,
+    idx: usize,
+    people: &[scenario_loader::Person],
+) -> CalendarEvent {
+    // Parse start/end times
+    let start = NaiveDateTime::parse_from_str(&e.start_time, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .map(|dt| Utc.from_utc_datetime(&dt))
+        .unwrap_or_else(Utc::now);
+    let end = NaiveDateTime::parse_from_str(&e.end_time, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .map(|dt| Utc.from_utc_datetime(&dt))
+        .unwrap_or_else(Utc::now);
+
+    // Map event type
+    let event_type = match e.event_type.as_deref() {
+        Some("standup") => EventType::Standup,
+        Some("all-hands") => EventType::AllHands,
+        Some("1:1") => EventType::OneOnOne,
+        Some("training") => EventType::Training,
+        Some("interview") => EventType::Interview,
+        Some("holiday") => EventType::Holiday,
+        Some("conference") => EventType::Conference,
+        Some("review") => EventType::Review,
+        Some("planning") => EventType::Planning,
+        Some("appointment") => EventType::Appointment,
+        Some("reminder") => EventType::Reminder,
+        Some("out-of-office") => EventType::OutOfOffice,
+        _ => EventType::Meeting,
     };
-    use ui_core::primitives::PersonOption;
 
-    // Convert scenario Event to ui-core CalendarEvent
-    fn convert_event(e: &scenario_loader::Event, idx: usize) -> CalendarEvent {
-        // Parse start/end times
-        let start = NaiveDateTime::parse_from_str(&e.start_time, "%Y-%m-%dT%H:%M:%S")
-            .ok()
-            .map(|dt| Utc.from_utc_datetime(&dt))
-            .unwrap_or_else(Utc::now);
-        let end = NaiveDateTime::parse_from_str(&e.end_time, "%Y-%m-%dT%H:%M:%S")
+    // Map recurrence
+    let recurrence = match e.recurrence.as_deref() {
+        Some("daily") => RecurrenceFrequency::Daily,
+        Some("weekly") => RecurrenceFrequency::Weekly,
+        Some("monthly") => RecurrenceFrequency::Monthly,
+        Some("yearly") => RecurrenceFrequency::Yearly,
+        _ => RecurrenceFrequency::None,
+    };
+
+    // Parse recurrence until date
+    let recurrence_until = e.recurrence_until.as_ref().and_then(|s| {
+        NaiveDateTime::parse_from_str(&format!("{}T00:00:00", s), "%Y-%m-%dT%H:%M:%S")
             .ok()
             .map(|dt| Utc.from_utc_datetime(&dt))
-            .unwrap_or_else(Utc::now);
-
-        // Map event type
-        let event_type = match e.event_type.as_deref() {
-            Some("standup") => EventType::Standup,
-            Some("all-hands") => EventType::AllHands,
-            Some("1:1") => EventType::OneOnOne,
-            Some("training") => EventType::Training,
-            Some("interview") => EventType::Interview,
-            Some("holiday") => EventType::Holiday,
-            Some("conference") => EventType::Conference,
-            Some("review") => EventType::Review,
-            Some("planning") => EventType::Planning,
-            Some("appointment") => EventType::Appointment,
-            Some("reminder") => EventType::Reminder,
-            Some("out-of-office") => EventType::OutOfOffice,
-            _ => EventType::Meeting,
-        };
-
-        // Map recurrence
-        let recurrence = match e.recurrence.as_deref() {
-            Some("daily") => RecurrenceFrequency::Daily,
-            Some("weekly") => RecurrenceFrequency::Weekly,
-            Some("monthly") => RecurrenceFrequency::Monthly,
-            Some("yearly") => RecurrenceFrequency::Yearly,
-            _ => RecurrenceFrequency::None,
-        };
-
-        // Parse recurrence until date
-        let recurrence_until = e.recurrence_until.as_ref().and_then(|s| {
-            NaiveDateTime::parse_from_str(&format!("{}T00:00:00", s), "%Y-%m-%dT%H:%M:%S")
-                .ok()
-                .map(|dt| Utc.from_utc_datetime(&dt))
-        });
+    });
 
-        let mut cal_event =
-            CalendarEvent::new(format!("event_{}", idx), e.title.clone(), start, end);
-        cal_event.description = e.description.clone();
-        cal_event.location = e.location.clone().or_else(|| e.virtual_url.clone());
-        cal_event.event_type = event_type;
-        cal_event.recurrence = recurrence;
-        cal_event.recurrence_days = e.recurrence_days.clone().unwrap_or_default();
-        cal_event.recurrence_until = recurrence_until;
-        cal_event
-    }
+    let mut cal_event =
+        CalendarEvent::new(format!("event_{}", idx), e.title.clone(), start, end);
+    cal_event.description = e.description.clone();
+    cal_event.location = e.location.clone().or_else(|| e.virtual_url.clone());
+    cal_event.event_type = event_type;
+    cal_event.recurrence = recurrence;
+    cal_event.recurrence_days = e.recurrence_days.clone().unwrap_or_default();
+    cal_event.recurrence_until = recurrence_until;
+
+    // Organizer and participants, as far as the scenario knows them
+    let participant = |id: &String| {
+        people
+            .iter()
+            .find(|p| &p.get_id() == id)
+            .map(|p| ParticipantInfo::new(id.clone(), p.name.clone()))
+    };
+    cal_event.organizers = e.organizer_id.iter().filter_map(participant).collect();
+    cal_event.participants = e
+        .participant_ids
+        .iter()
+        .flatten()
+        .filter_map(participant)
+        .collect();
+    cal_event
+}
 
-    // Load events from embedded scenario data
-    let events: Vec<CalendarEvent> = embedded::events()
+/// Calendar events from the embedded scenario
+fn scenario_events() -> Vec<CalendarEvent> {
+    use scenario_loader::embedded;
+
+    let people = embedded::personnel();
+    embedded::events()
         .iter()
         .enumerate()
-        .map(|(idx, e)| convert_event(e, idx))
-        .collect();
+        .map(|(idx, e)| convert_event(e, idx, people))
+        .collect()
+}
+
+/// Calendar page with sample events
+#[component]
+fn CalendarPageWrapper() -> impl IntoView {
+    use scenario_loader::embedded;
+    use ui_core::features::calendar::CalendarPage;
+    use ui_core::primitives::PersonOption;
+
+    let events = scenario_events();
+    let collab = collab::use_collab().opener();
+    let activity = activity::use_activity().opener();
 
     // Load personnel for organizer/participant selection
     let people: Vec<PersonOption> = embedded::personnel()
@@ -347,49 +697,95 @@ fn CalendarPageWrapper() -> impl IntoView {
         .map(|p| PersonOption::new(p.get_id(), p.name.clone()).with_title(p.title.clone()))
         .collect();
 
+    // The month grid is the heaviest view in the app; mount it once it is on screen
     view! {
-        <CalendarPage initial_events=events available_people=people />
+        <LazyIsland label="Loading calendar" height="640px">
+            <CalendarPage
+                initial_events=events.clone()
+                available_people=people.clone()
+                collab=collab
+                activity=Some(activity)
+            />
+        </LazyIsland>
     }
 }
 
-/// Sites page with 3D Bevy globe visualization
+/// Asset inventory; moves and deletions can be undone from their toast,
+/// and each asset's details show its activity
 #[component]
-fn SitesPageWrapper() -> impl IntoView {
-    use bevy::prelude::*;
-    use leptos_bevy_canvas::prelude::*;
-
-    // Initialize the Bevy app for the globe viewer
-    fn init_bevy_app() -> App {
-        let mut app = App::new();
-
-        // Add EmbeddedAssetPlugin BEFORE DefaultPlugins for WASM asset loading
-        app.add_plugins(bevy_embedded_assets::EmbeddedAssetPlugin::default());
-
-        app.add_plugins(
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        // leptos-bevy-canvas creates a canvas with this default ID
-                        canvas: Some("#bevy_canvas".into()),
-                        fit_canvas_to_parent: true,
-                        prevent_default_event_handling: true,
-                        transparent: true, // Enable transparent canvas background
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .set(bevy::asset::AssetPlugin {
-                    file_path: "assets".into(),
-                    ..default()
-                }),
-        );
-
-        // Add our viewer plugins
-        app.add_plugins(bevy_viewer::BevyViewerPlugin);
-
-        app
+fn AssetsPageWrapper() -> impl IntoView {
+    use ui_core::features::assets::AssetInventory;
+
+    let feed = assets::feed();
+    let activity = activity::use_activity().opener();
+
+    view! {
+        <div class="admin-page">
+            <h1>"Assets"</h1>
+            <p class="admin-subtitle">"Move equipment between racks, or retire it from the inventory"</p>
+            <AssetInventory feed=feed activity=Some(activity) />
+        </div>
+    }
+}
+
+/// Review queue for changes to protected records
+#[component]
+fn ReviewsPageWrapper() -> impl IntoView {
+    use ui_core::features::change_requests::ReviewQueue;
+
+    let feed = change_requests::use_reviews().feed();
+
+    view! {
+        <div class="admin-page">
+            <h1>"Reviews" <HelpButton help_key="reviews" /></h1>
+            <p class="admin-subtitle">"Changes to protected records wait here for an approver"</p>
+            <ReviewQueue feed=feed />
+        </div>
+    }
+}
+
+/// Guided exercises from the scenario, with the trainee's scorecard
+#[component]
+fn TrainingPageWrapper() -> impl IntoView {
+    use ui_core::features::training::TrainingPanel;
+
+    let feed = training::use_training().feed();
+
+    view! {
+        <div class="admin-page">
+            <h1>"Training" <HelpButton help_key="training" /></h1>
+            <p class="admin-subtitle">"Work through an exercise in the app; each step is checked as you go"</p>
+            <TourAnchor
+                tour="training"
+                order=1
+                title="Exercises"
+                body="Start an exercise, then do each task in the app itself. Hints help but cost points."
+            >
+                <TrainingPanel feed=feed />
+            </TourAnchor>
+        </div>
+    }
+}
+
+/// Admin page defining the custom fields of each kind of record
+#[component]
+fn CustomFieldsPageWrapper() -> impl IntoView {
+    use ui_core::features::custom_fields::CustomFieldEditor;
+
+    let feed = custom_fields::admin_feed();
+
+    view! {
+        <div class="admin-page">
+            <h1>"Custom Fields"</h1>
+            <p class="admin-subtitle">"Extra fields shown on records' forms and detail panels"</p>
+            <CustomFieldEditor feed=feed />
+        </div>
     }
+}
 
+/// Sites page with 3D Bevy globe visualization
+#[component]
+fn SitesPageWrapper() -> impl IntoView {
     view! {
         <div class="sites-page">
             <div class="sites-header">
@@ -397,11 +793,11 @@ fn SitesPageWrapper() -> impl IntoView {
                 <p class="sites-subtitle">"Global site locations and infrastructure"</p>
             </div>
 
+            // The globe module is only fetched once it is scrolled into view
             <div class="globe-container">
-                <BevyCanvas
-                    init=init_bevy_app
-                    attr:id="bevy_canvas"
-                />
+                <LazyIsland label="Loading globe" height="400px">
+                    <GlobeViewer />
+                </LazyIsland>
             </div>
 
             <div class="sites-info">
@@ -0,0 +1,157 @@
+//! Progressive Web App
+//!
+//! Registers the service worker that `pwa/build-pwa.sh` adds to the build.
+//! It caches the app shell, scenario data included (it's embedded in the
+//! WASM), so the app installs and starts offline. A new build's worker
+//! installs in the background and waits; [`provide_pwa`] then shows an
+//! "Update available" toast whose Reload button activates it and reloads
+//! the page.
+//!
+//! The Tauri shell bundles its files and updates through
+//! [`UpdateDialog`](crate::desktop::UpdateDialog), so no worker is
+//! registered there.
+
+use leptos::prelude::*;
+use ui_core::elements::{use_toasts, Toast, ToastLevel, ToastQueue};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    ServiceWorker, ServiceWorkerContainer, ServiceWorkerRegistration, ServiceWorkerState,
+};
+
+/// Where the build puts the worker; at the root so it controls every route
+const SERVICE_WORKER_URL: &str = "/sw.js";
+
+/// Message that makes a waiting worker take over, handled in `pwa/sw.js`
+const SKIP_WAITING: &str = "SKIP_WAITING";
+
+/// The update offer, see [`provide_pwa`]
+#[derive(Clone, Copy)]
+pub struct PwaUpdates {
+    /// Installed worker waiting to take over
+    waiting: StoredValue<Option<ServiceWorker>, LocalStorage>,
+    /// The "Update available" toast, once shown
+    toast: StoredValue<Option<u64>>,
+    /// Set once the user chose to reload
+    reloading: StoredValue<bool>,
+    toasts: RwSignal<ToastQueue>,
+}
+
+impl PwaUpdates {
+    /// `on_action` handler for the toast region: Reload activates the
+    /// waiting worker, and the page reloads once it has taken over
+    pub fn toast_handler(&self) -> Callback<u64> {
+        let updates = *self;
+        Callback::new(move |toast_id: u64| {
+            if updates.toast.get_value() != Some(toast_id) {
+                return;
+            }
+            updates.waiting.with_value(|waiting| {
+                if let Some(worker) = waiting {
+                    let message = js_sys::Object::new();
+                    let _ = js_sys::Reflect::set(&message, &"type".into(), &SKIP_WAITING.into());
+                    if worker.post_message(&message).is_ok() {
+                        updates.reloading.set_value(true);
+                    }
+                }
+            });
+        })
+    }
+
+    /// Offer `worker`; the toast is shown once per page load
+    fn offer(&self, worker: ServiceWorker) {
+        self.waiting.set_value(Some(worker));
+        if self.toast.get_value().is_some() {
+            return;
+        }
+        let toast = Toast::new(ToastLevel::Info, "Update available")
+            .message("A new version of Rubigo is ready.")
+            .action("Reload")
+            .sticky();
+        let id = self.toasts.try_update(|queue| queue.push(toast));
+        self.toast.set_value(id);
+    }
+}
+
+/// Whether the browser supports service workers here (they need HTTPS or
+/// localhost)
+fn service_worker_container() -> Option<ServiceWorkerContainer> {
+    let navigator = window().navigator();
+    js_sys::Reflect::has(&navigator, &"serviceWorker".into())
+        .unwrap_or(false)
+        .then(|| navigator.service_worker())
+}
+
+/// Register the service worker and watch for updates
+///
+/// Needs the toast queue from [`provide_toasts`](ui_core::elements::provide_toasts);
+/// pass [`PwaUpdates::toast_handler`] on to the toast region.
+pub fn provide_pwa() -> PwaUpdates {
+    let updates = PwaUpdates {
+        waiting: StoredValue::new_local(None),
+        toast: StoredValue::new(None),
+        reloading: StoredValue::new(false),
+        toasts: use_toasts(),
+    };
+    if crate::desktop::in_tauri() {
+        return updates;
+    }
+    let Some(container) = service_worker_container() else {
+        return updates;
+    };
+
+    // The new worker took over after Reload: load the new shell
+    let on_controller_change = Closure::<dyn FnMut()>::new(move || {
+        if updates.reloading.get_value() {
+            let _ = window().location().reload();
+        }
+    });
+    container.set_oncontrollerchange(Some(on_controller_change.as_ref().unchecked_ref()));
+    // Listeners live as long as the page
+    on_controller_change.forget();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let registration: ServiceWorkerRegistration =
+            match JsFuture::from(container.register(SERVICE_WORKER_URL)).await {
+                Ok(registration) => registration.unchecked_into(),
+                Err(e) => {
+                    log::warn!("Service worker not registered: {:?}", e);
+                    return;
+                }
+            };
+
+        // Without a controller this is the first install, not an update
+        let is_update = {
+            let container = container.clone();
+            move || container.controller().is_some()
+        };
+
+        if let Some(waiting) = registration.waiting() {
+            if is_update() {
+                updates.offer(waiting);
+            }
+        }
+
+        let on_update_found = {
+            let registration = registration.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                let Some(installing) = registration.installing() else {
+                    return;
+                };
+                let worker = installing.clone();
+                let is_update = is_update.clone();
+                let on_state_change = Closure::<dyn FnMut()>::new(move || {
+                    if worker.state() == ServiceWorkerState::Installed && is_update() {
+                        updates.offer(worker.clone());
+                    }
+                });
+                installing.set_onstatechange(Some(on_state_change.as_ref().unchecked_ref()));
+                on_state_change.forget();
+            })
+        };
+        registration.set_onupdatefound(Some(on_update_found.as_ref().unchecked_ref()));
+        on_update_found.forget();
+    });
+
+    updates
+}
@@ -0,0 +1,134 @@
+//! Training Mode
+//!
+//! Runs the scenario's guided exercises through `TrainingAction`s on the
+//! action broker, with the signed-in persona as the trainee. Every other
+//! action the app dispatches meanwhile is checked by the server against
+//! the task at hand, so the session is reloaded whenever the Training page
+//! is opened. [`provide_training`] records who is signed in; the Training
+//! page takes its feed from [`Training::feed`].
+
+use crate::broker;
+use actions::{
+    ActionBroker, ActivityAction, ActivityQuery, ActivityResponse, StartTrainingData,
+    TrainingAction, TrainingResponse,
+};
+use leptos::prelude::*;
+use ui_core::features::training::{ActivityData, ExerciseData, TrainingFeed, TrainingProgressData};
+use ui_core::recorder::{use_recorder, Recorder};
+
+/// Signed-in persona, for starting exercises
+#[derive(Clone, Copy)]
+pub struct Training {
+    persona: Signal<Option<String>>,
+}
+
+impl Training {
+    /// The exercises and the latest session, loaded now and again after
+    /// each request
+    pub fn feed(&self) -> TrainingFeed {
+        open(self.persona, use_recorder())
+    }
+}
+
+pub fn provide_training(persona: Signal<Option<String>>) {
+    provide_context(Training { persona });
+}
+
+pub fn use_training() -> Training {
+    expect_context()
+}
+
+/// Everything a training page shows
+#[derive(Clone, Copy)]
+struct State {
+    exercises: RwSignal<Vec<ExerciseData>>,
+    progress: RwSignal<Option<TrainingProgressData>>,
+    trail: RwSignal<Vec<ActivityData>>,
+    error: RwSignal<Option<String>>,
+}
+
+fn refusal(response: Result<TrainingResponse, actions::ActionError>) -> String {
+    match response {
+        Ok(TrainingResponse::Error(e)) => e,
+        Err(e) => e.to_string(),
+        Ok(_) => "Unexpected response".to_string(),
+    }
+}
+
+/// Fetch the exercises, the latest session and its trail
+fn load(state: State, recorder: Option<Recorder>) {
+    leptos::task::spawn_local(async move {
+        let broker = broker(recorder);
+        match broker.dispatch(TrainingAction::Exercises).await {
+            Ok(TrainingResponse::Exercises(exercises)) => state.exercises.set(exercises),
+            other => {
+                let message = refusal(other);
+                log::warn!("Training exercises unavailable: {}", message);
+                state.error.set(Some(message));
+                return;
+            }
+        }
+        // Before the first session there is no progress, which isn't an error
+        let Ok(TrainingResponse::Progress(progress)) =
+            broker.dispatch(TrainingAction::Progress).await
+        else {
+            return;
+        };
+        let query = ActivityQuery {
+            entity: progress.entity(),
+            label: None,
+        };
+        state.progress.set(Some(*progress));
+        match broker.dispatch(ActivityAction::Feed(query)).await {
+            Ok(ActivityResponse::Feed(trail)) => state.trail.set(trail),
+            Ok(ActivityResponse::Error(e)) => log::warn!("Training trail unavailable: {}", e),
+            Err(e) => log::warn!("Training trail unavailable: {}", e),
+            Ok(_) => {}
+        }
+    });
+}
+
+fn open(persona: Signal<Option<String>>, recorder: Option<Recorder>) -> TrainingFeed {
+    let state = State {
+        exercises: RwSignal::new(Vec::new()),
+        progress: RwSignal::new(None),
+        trail: RwSignal::new(Vec::new()),
+        error: RwSignal::new(None),
+    };
+    let recorder = StoredValue::new_local(recorder);
+    load(state, recorder.get_value());
+
+    let send = move |action: TrainingAction| {
+        leptos::task::spawn_local(async move {
+            match broker(recorder.get_value()).dispatch(action).await {
+                Ok(TrainingResponse::Error(e)) => state.error.set(Some(e)),
+                Ok(_) => state.error.set(None),
+                Err(e) => state.error.set(Some(e.to_string())),
+            }
+            load(state, recorder.get_value());
+        });
+    };
+
+    let start = move |exercise: String| {
+        let Some(trainee) = persona.get_untracked() else {
+            state
+                .error
+                .set(Some("Sign in to start an exercise".to_string()));
+            return;
+        };
+        send(TrainingAction::Start(StartTrainingData {
+            exercise,
+            trainee,
+        }));
+    };
+
+    TrainingFeed {
+        exercises: state.exercises.into(),
+        progress: state.progress.into(),
+        trail: state.trail.into(),
+        on_start: Callback::new(start),
+        on_hint: Callback::new(move |()| send(TrainingAction::Hint)),
+        on_finish: Callback::new(move |()| send(TrainingAction::Finish)),
+        error: state.error.into(),
+    }
+}
@@ -0,0 +1,290 @@
+//! Accordion Component
+//!
+//! A stack of collapsible sections with coordinated expansion.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ui_core::elements::{Accordion, AccordionItem, AccordionMode};
+//!
+//! let items = vec![
+//!     AccordionItem::new("general", "General", || view! { <p>"General settings"</p> }),
+//!     AccordionItem::new("network", "Network", || view! { <p>"Network settings"</p> }),
+//!     AccordionItem::new("advanced", "Advanced", || view! { <p>"Advanced"</p> }).disabled(),
+//! ];
+//!
+//! view! {
+//!     <Accordion
+//!         items=items
+//!         mode=AccordionMode::Single
+//!         on_change=Callback::new(|open: Vec<String>| log!("{open:?}"))
+//!     />
+//! }
+//! ```
+//!
+//! # Keyboard
+//!
+//! - `ArrowDown` / `ArrowUp` move focus between headers (wrapping)
+//! - `Home` / `End` jump to the first / last header
+//! - `Enter` / `Space` toggle the focused section
+//!
+//! # Best Practices
+//!
+//! - Use `Single` mode when sections are alternatives (e.g. FAQ)
+//! - Use `Multiple` mode when users compare content across sections
+//! - Keep headers short and descriptive
+//! - Avoid nesting accordions more than one level deep
+
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/elements/accordion/accordion.module.css"
+);
+
+/// Accordion section definition
+#[derive(Clone)]
+pub struct AccordionItem {
+    /// Unique identifier
+    pub id: String,
+    /// Header label
+    pub title: String,
+    /// Disabled state
+    pub disabled: bool,
+    /// Section content
+    pub content: ViewFn,
+}
+
+impl AccordionItem {
+    /// Create a new accordion section
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        content: impl Into<ViewFn>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            disabled: false,
+            content: content.into(),
+        }
+    }
+
+    /// Set disabled state
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+}
+
+/// Expansion coordination between sections
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccordionMode {
+    /// At most one section is open; opening one closes the others
+    #[default]
+    Single,
+    /// Any number of sections may be open at once
+    Multiple,
+}
+
+impl AccordionMode {
+    /// Compute the expanded set after toggling `id`
+    pub fn toggle(&self, expanded: &[String], id: &str) -> Vec<String> {
+        let is_open = expanded.iter().any(|e| e == id);
+        match (self, is_open) {
+            (_, true) => expanded.iter().filter(|e| *e != id).cloned().collect(),
+            (AccordionMode::Single, false) => vec![id.to_string()],
+            (AccordionMode::Multiple, false) => {
+                let mut next = expanded.to_vec();
+                next.push(id.to_string());
+                next
+            }
+        }
+    }
+}
+
+/// Resolve the header index that should receive focus for a navigation key.
+///
+/// Disabled headers are skipped. Returns `None` for keys that are not
+/// navigation keys or when no header is focusable.
+fn focus_target(key: &str, current: usize, disabled: &[bool]) -> Option<usize> {
+    let len = disabled.len();
+    let enabled = |i: &usize| !disabled[*i];
+
+    match key {
+        "Home" => (0..len).find(enabled),
+        "End" => (0..len).rev().find(enabled),
+        "ArrowDown" => (1..=len).map(|step| (current + step) % len).find(enabled),
+        "ArrowUp" => (1..=len)
+            .map(|step| (current + len - step) % len)
+            .find(enabled),
+        _ => None,
+    }
+}
+
+/// Collapsible section list component
+#[component]
+pub fn Accordion(
+    /// Accordion sections
+    items: Vec<AccordionItem>,
+    /// Single-open vs multi-open coordination
+    #[prop(default = AccordionMode::Single)]
+    mode: AccordionMode,
+    /// Expanded section IDs (reactive signal)
+    #[prop(optional)]
+    expanded: Option<RwSignal<Vec<String>>>,
+    /// DOM id prefix for headers and panels
+    #[prop(optional, into)]
+    id: Option<String>,
+    /// Callback with the expanded section IDs after every change
+    #[prop(optional)]
+    on_change: Option<Callback<Vec<String>>>,
+) -> impl IntoView {
+    let expanded = expanded.unwrap_or_else(|| RwSignal::new(Vec::new()));
+    let prefix = id.unwrap_or_else(|| "accordion".to_string());
+
+    let header_ids: Vec<String> = items
+        .iter()
+        .map(|item| format!("{prefix}-header-{}", item.id))
+        .collect();
+    let disabled: Vec<bool> = items.iter().map(|item| item.disabled).collect();
+
+    let focus_header = move |dom_id: &str| {
+        use wasm_bindgen::JsCast;
+
+        if let Some(el) = document()
+            .get_element_by_id(dom_id)
+            .and_then(|el| el.dyn_into::<web_sys::HtmlElement>().ok())
+        {
+            let _ = el.focus();
+        }
+    };
+
+    view! {
+        <div class=style::accordion>
+            {items.into_iter().enumerate().map(|(index, item)| {
+                let header_id = header_ids[index].clone();
+                let panel_id = format!("{prefix}-panel-{}", item.id);
+                let item_id = item.id.clone();
+                let item_id_open = item.id.clone();
+                let is_disabled = item.disabled;
+                let header_ids = header_ids.clone();
+                let disabled = disabled.clone();
+                let content = item.content;
+
+                let is_open = Memo::new(move |_| expanded.with(|e| e.contains(&item_id_open)));
+
+                let toggle = move || {
+                    if is_disabled {
+                        return;
+                    }
+                    let next = expanded.with(|e| mode.toggle(e, &item_id));
+                    expanded.set(next.clone());
+                    if let Some(callback) = on_change {
+                        callback.run(next);
+                    }
+                };
+
+                let on_keydown = move |e: web_sys::KeyboardEvent| {
+                    if let Some(target) = focus_target(&e.key(), index, &disabled) {
+                        e.prevent_default();
+                        focus_header(&header_ids[target]);
+                    }
+                };
+
+                view! {
+                    <div class=move || {
+                        if is_open.get() {
+                            format!("{} {}", style::accordion_item, style::open)
+                        } else {
+                            style::accordion_item.to_string()
+                        }
+                    }>
+                        <h3 class=style::accordion_heading>
+                            <button
+                                id=header_id.clone()
+                                class=style::accordion_header
+                                aria-expanded=move || is_open.get().to_string()
+                                aria-controls=panel_id.clone()
+                                aria-disabled=is_disabled.to_string()
+                                disabled=is_disabled
+                                on:click=move |_| toggle()
+                                on:keydown=on_keydown
+                            >
+                                <span class=style::accordion_title>{item.title}</span>
+                                <span class=style::accordion_chevron aria-hidden="true">"▾"</span>
+                            </button>
+                        </h3>
+                        <div
+                            id=panel_id
+                            class=style::accordion_panel
+                            role="region"
+                            aria-labelledby=header_id
+                            hidden=move || !is_open.get()
+                        >
+                            {move || is_open.get().then(|| content.run())}
+                        </div>
+                    </div>
+                }
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn single_mode_opens_one_at_a_time() {
+        let mode = AccordionMode::Single;
+        let open = mode.toggle(&[], "a");
+        assert_eq!(open, ids(&["a"]));
+
+        let open = mode.toggle(&open, "b");
+        assert_eq!(open, ids(&["b"]));
+
+        let open = mode.toggle(&open, "b");
+        assert!(open.is_empty());
+    }
+
+    #[test]
+    fn multiple_mode_keeps_others_open() {
+        let mode = AccordionMode::Multiple;
+        let open = mode.toggle(&[], "a");
+        let open = mode.toggle(&open, "b");
+        assert_eq!(open, ids(&["a", "b"]));
+
+        let open = mode.toggle(&open, "a");
+        assert_eq!(open, ids(&["b"]));
+    }
+
+    #[test]
+    fn arrow_navigation_wraps() {
+        let disabled = [false, false, false];
+        assert_eq!(focus_target("ArrowDown", 0, &disabled), Some(1));
+        assert_eq!(focus_target("ArrowDown", 2, &disabled), Some(0));
+        assert_eq!(focus_target("ArrowUp", 0, &disabled), Some(2));
+        assert_eq!(focus_target("Home", 2, &disabled), Some(0));
+        assert_eq!(focus_target("End", 0, &disabled), Some(2));
+        assert_eq!(focus_target("Enter", 0, &disabled), None);
+    }
+
+    #[test]
+    fn arrow_navigation_skips_disabled() {
+        let disabled = [false, true, false];
+        assert_eq!(focus_target("ArrowDown", 0, &disabled), Some(2));
+        assert_eq!(focus_target("ArrowUp", 2, &disabled), Some(0));
+        assert_eq!(focus_target("ArrowDown", 0, &[true, true]), None);
+    }
+
+    #[test]
+    fn accordion_mode_default() {
+        assert_eq!(AccordionMode::default(), AccordionMode::Single);
+    }
+}
@@ -15,6 +15,7 @@
 //!     <Card
 //!         title="Dashboard"
 //!         subtitle="Overview of your data"
+//!         help_key="dashboard"
 //!         footer=view! { <Button>"Action"</Button> }
 //!     >
 //!         <p>"Card content"</p>
@@ -29,6 +30,7 @@
 //! - Use subtitles for additional context
 //! - Place primary actions in the footer
 
+use crate::elements::HelpButton;
 use leptos::prelude::*;
 
 stylance::import_crate_style!(style, "src/elements/card/card.module.css");
@@ -67,6 +69,9 @@ pub fn Card(
     /// Visual variant
     #[prop(default = CardVariant::Default)]
     variant: CardVariant,
+    /// Help topic opened from a "?" beside the title
+    #[prop(optional, into)]
+    help_key: Option<String>,
     /// Footer content (optional)
     #[prop(optional)]
     footer: Option<Children>,
@@ -79,7 +84,10 @@ pub fn Card(
         <div class=card_class>
             {(title.is_some() || subtitle.is_some()).then(|| view! {
                 <div class=style::card_header>
-                    {title.map(|t| view! { <h3 class=style::card_title>{t}</h3> })}
+                    <div class=style::card_title_row>
+                        {title.map(|t| view! { <h3 class=style::card_title>{t}</h3> })}
+                        {help_key.map(|key| view! { <HelpButton help_key=key /> })}
+                    </div>
                     {subtitle.map(|s| view! { <p class=style::card_subtitle>{s}</p> })}
                 </div>
             })}
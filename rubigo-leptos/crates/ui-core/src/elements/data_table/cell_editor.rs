@@ -0,0 +1,281 @@
+//! Inline Cell Editing
+//!
+//! Columns given a [`CellEditor`] can be edited in place: double-click a
+//! cell, change the value, then press Enter or click away to save, or
+//! Escape to cancel. The value is checked first, by the editor's own rules
+//! and the column's validator, and shown as saved straight away while the
+//! host stores it; if the host reports the save failed the old value comes
+//! back with the error under the cell.
+
+use leptos::prelude::*;
+use std::collections::HashMap;
+use wasm_bindgen::JsCast;
+
+use super::column_type::{render_cell, ColumnType};
+use super::DataRow;
+use crate::primitives::{DateInput, Input, InputSize, InputType, Select, SelectOption, SelectSize};
+
+stylance::import_crate_style!(style, "src/elements/data_table/data_table.module.css");
+
+/// How a column's cells are edited in place
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellEditor {
+    Text,
+    Number,
+    /// One of the options
+    Select(Vec<SelectOption>),
+    /// A YYYY-MM-DD date
+    Date,
+}
+
+/// Extra check on a new cell value: why it's invalid, if it is
+pub type CellValidator = fn(&str) -> Option<String>;
+
+/// A cell value changed in place
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellEdit {
+    pub row: String,
+    /// Column key
+    pub column: String,
+    pub value: String,
+    /// Value before the edit, to restore or compare
+    pub previous: String,
+}
+
+/// A cell edit the host couldn't save
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellError {
+    pub row: String,
+    /// Column key
+    pub column: String,
+    pub error: String,
+}
+
+/// Why `value` can't go in a cell edited with `editor`, if it can't
+pub fn check_cell(
+    editor: &CellEditor,
+    validator: Option<CellValidator>,
+    value: &str,
+) -> Option<String> {
+    let value = value.trim();
+    let invalid = match editor {
+        CellEditor::Text => None,
+        CellEditor::Number => (!value.is_empty() && value.parse::<f64>().is_err())
+            .then(|| "Enter a number".to_string()),
+        CellEditor::Select(options) => (!options.iter().any(|o| o.value == value))
+            .then(|| "Choose one of the options".to_string()),
+        CellEditor::Date => (!value.is_empty()
+            && chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_err())
+        .then(|| "Enter a date as YYYY-MM-DD".to_string()),
+    };
+    invalid.or_else(|| validator.and_then(|validate| validate(value)))
+}
+
+type CellKey = (String, String);
+
+/// Edit state shared by a table's cells
+#[derive(Clone, Copy)]
+pub(super) struct CellEdits {
+    /// Saved values not yet in the rows, by row and column
+    values: RwSignal<HashMap<CellKey, String>>,
+    /// Errors shown under cells
+    errors: RwSignal<HashMap<CellKey, String>>,
+    /// Cell being edited
+    editing: RwSignal<Option<CellKey>>,
+    on_edit: Callback<CellEdit>,
+}
+
+impl CellEdits {
+    /// Edit state reporting saves to `on_edit`; failures reported in
+    /// `failed` restore the old value
+    pub(super) fn new(on_edit: Callback<CellEdit>, failed: Option<Signal<Vec<CellError>>>) -> Self {
+        let edits = Self {
+            values: RwSignal::new(HashMap::new()),
+            errors: RwSignal::new(HashMap::new()),
+            editing: RwSignal::new(None),
+            on_edit,
+        };
+        if let Some(failed) = failed {
+            Effect::watch(
+                move || failed.get(),
+                move |failed, _, _| {
+                    for f in failed {
+                        let key = (f.row.clone(), f.column.clone());
+                        edits.values.update(|v| {
+                            v.remove(&key);
+                        });
+                        edits.errors.update(|e| {
+                            e.insert(key, f.error.clone());
+                        });
+                    }
+                },
+                false,
+            );
+        }
+        edits
+    }
+}
+
+/// A table cell that can be edited in place
+#[component]
+pub(super) fn EditableCell(
+    edits: CellEdits,
+    row: DataRow,
+    column: String,
+    /// Column header, labelling the cell in card mode
+    header: String,
+    /// Value in the row
+    value: String,
+    /// How the value is shown while not editing
+    column_type: ColumnType,
+    editor: CellEditor,
+    validator: Option<CellValidator>,
+) -> impl IntoView {
+    let class = match column_type.class() {
+        Some(class) => format!("{} {}", style::editable_cell, class),
+        None => style::editable_cell.to_string(),
+    };
+    let shown_as = StoredValue::new((column_type, row.clone()));
+    let key = StoredValue::new((row.id, column));
+    let original = StoredValue::new(value);
+    let editor = StoredValue::new(editor);
+    let shown = move || {
+        key.with_value(|key| edits.values.with(|v| v.get(key).cloned()))
+            .unwrap_or_else(|| original.get_value())
+    };
+    let is_editing = move || key.with_value(|key| edits.editing.with(|e| e.as_ref() == Some(key)));
+    let error = move || key.with_value(|key| edits.errors.with(|e| e.get(key).cloned()));
+    let draft = RwSignal::new(String::new());
+
+    let start = move |_| {
+        draft.set(shown());
+        edits.editing.set(Some(key.get_value()));
+    };
+    let cancel = move || {
+        edits.editing.set(None);
+        key.with_value(|key| {
+            edits.errors.update(|e| {
+                e.remove(key);
+            })
+        });
+    };
+    let commit = move || {
+        if !is_editing() {
+            return;
+        }
+        let value = draft.get_untracked().trim().to_string();
+        let previous = shown();
+        if let Some(invalid) = editor.with_value(|editor| check_cell(editor, validator, &value)) {
+            key.with_value(|key| {
+                edits.errors.update(|e| {
+                    e.insert(key.clone(), invalid);
+                })
+            });
+            return;
+        }
+        cancel();
+        if value == previous {
+            return;
+        }
+        let (row, column) = key.get_value();
+        edits.values.update(|v| {
+            v.insert((row.clone(), column.clone()), value.clone());
+        });
+        edits.on_edit.run(CellEdit {
+            row,
+            column,
+            value,
+            previous,
+        });
+    };
+
+    // Focus the editor once it is shown
+    let editor_ref = NodeRef::<leptos::html::Div>::new();
+    Effect::new(move |_| {
+        if let Some(wrapper) = editor_ref.get() {
+            if let Ok(Some(field)) = wrapper.query_selector("input, select") {
+                if let Ok(field) = field.dyn_into::<web_sys::HtmlElement>() {
+                    let _ = field.focus();
+                }
+            }
+        }
+    });
+
+    let field = move || {
+        let on_change = Callback::new(move |_: String| commit());
+        match editor.get_value() {
+            CellEditor::Text => view! { <Input value=draft size=InputSize::Small /> }.into_any(),
+            CellEditor::Number => view! {
+                <Input value=draft input_type=InputType::Number size=InputSize::Small />
+            }
+            .into_any(),
+            CellEditor::Select(options) => view! {
+                <Select value=draft options=options size=SelectSize::Small on_change=on_change />
+            }
+            .into_any(),
+            CellEditor::Date => view! {
+                <DateInput value=draft show_today_button=false on_change=on_change />
+            }
+            .into_any(),
+        }
+    };
+
+    let display = move || {
+        let value = shown();
+        shown_as.with_value(|(column_type, row)| {
+            key.with_value(|(_, column)| render_cell(column_type, column, row, &value))
+        })
+    };
+
+    view! {
+        <td
+            data-label=header
+            class=class
+            title="Double-click to edit"
+            on:click=|ev| ev.stop_propagation()
+            on:dblclick=start
+        >
+            <Show when=is_editing fallback=display>
+                <div
+                    node_ref=editor_ref
+                    class=style::cell_editor
+                    on:focusout=move |_| commit()
+                    on:keydown=move |ev| match ev.key().as_str() {
+                        "Enter" => commit(),
+                        "Escape" => cancel(),
+                        _ => {}
+                    }
+                >
+                    {field}
+                </div>
+            </Show>
+            {move || error().map(|error| view! {
+                <p class=style::cell_error role="alert">{error}</p>
+            })}
+        </td>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_values_are_checked_by_editor_then_validator() {
+        fn no_spaces(value: &str) -> Option<String> {
+            value.contains(' ').then(|| "No spaces".to_string())
+        }
+        assert_eq!(check_cell(&CellEditor::Text, None, "any thing"), None);
+        assert_eq!(
+            check_cell(&CellEditor::Text, Some(no_spaces), "any thing"),
+            Some("No spaces".to_string())
+        );
+        assert_eq!(check_cell(&CellEditor::Number, None, " 4.5 "), None);
+        assert!(check_cell(&CellEditor::Number, None, "four").is_some());
+        assert_eq!(check_cell(&CellEditor::Date, None, "2024-02-29"), None);
+        assert!(check_cell(&CellEditor::Date, None, "2023-02-29").is_some());
+        let statuses = CellEditor::Select(vec![SelectOption::new("active", "Active")]);
+        assert_eq!(check_cell(&statuses, None, "active"), None);
+        assert!(check_cell(&statuses, None, "gone").is_some());
+    }
+}
@@ -0,0 +1,177 @@
+//! Column Types
+//!
+//! Cells hold their raw values (numbers as digits, dates as YYYY-MM-DD or
+//! RFC 3339) and a column's [`ColumnType`] decides how they are shown, so
+//! hosts don't format everything into strings first. Some types read a
+//! companion cell of the same row, named after the column's key: the photo
+//! of an `Avatar` column `owner` is in cell `owner_photo`, the target of a
+//! `Link` column `site` in cell `site_href`.
+
+use leptos::prelude::*;
+
+use super::DataRow;
+use crate::primitives::{Avatar, AvatarSize, Badge, BadgeSize, BadgeVariant};
+
+stylance::import_crate_style!(style, "src/elements/data_table/data_table.module.css");
+
+/// Renders a cell from its row and value
+pub type CellRenderer = fn(&DataRow, &str) -> AnyView;
+
+/// How a column's values are shown
+#[derive(Debug, Clone, Default)]
+pub enum ColumnType {
+    #[default]
+    Text,
+    /// Digits grouped in thousands, right-aligned
+    Number,
+    /// Date or timestamp shown as e.g. "Jan 5, 2024"
+    Date,
+    /// Status badge, colored by [`badge_variant`]
+    Badge,
+    /// Name beside an avatar, the photo from cell `<key>_photo`
+    Avatar,
+    /// Link to cell `<key>_href`, or to the value itself
+    Link,
+    Custom(CellRenderer),
+}
+
+impl ColumnType {
+    /// Cell class, for types aligned differently
+    pub(super) fn class(&self) -> Option<&'static str> {
+        matches!(self, ColumnType::Number).then_some(style::number_cell)
+    }
+}
+
+/// Badge color for a status value
+pub fn badge_variant(status: &str) -> BadgeVariant {
+    match status.to_lowercase().replace([' ', '-'], "_").as_str() {
+        "active" | "deployed" | "online" | "ok" | "approved" | "done" | "resolved" => {
+            BadgeVariant::Success
+        }
+        "pending" | "ordered" | "maintenance" | "on_leave" | "degraded" | "warning" => {
+            BadgeVariant::Warning
+        }
+        "failed" | "error" | "offline" | "retired" | "rejected" | "critical" => BadgeVariant::Error,
+        "new" | "open" | "in_progress" => BadgeVariant::Primary,
+        _ => BadgeVariant::Default,
+    }
+}
+
+/// `value` with its digits grouped in thousands; non-numbers unchanged
+pub fn format_number(value: &str) -> String {
+    let value = value.trim();
+    if value.parse::<f64>().is_err() {
+        return value.to_string();
+    }
+    let (sign, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value),
+    };
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (unsigned, None),
+    };
+    if !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return value.to_string();
+    }
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    match fraction {
+        Some(fraction) => format!("{sign}{grouped}.{fraction}"),
+        None => format!("{sign}{grouped}"),
+    }
+}
+
+/// A YYYY-MM-DD date or RFC 3339 timestamp as e.g. "Jan 5, 2024";
+/// anything else unchanged
+pub fn format_date(value: &str) -> String {
+    let value = value.trim();
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|at| at.date_naive())
+        });
+    match date {
+        Some(date) => date.format("%b %-d, %Y").to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// Cell `key` of `row` shown as `column_type`
+pub(super) fn render_cell(
+    column_type: &ColumnType,
+    key: &str,
+    row: &DataRow,
+    value: &str,
+) -> AnyView {
+    if value.is_empty() && !matches!(column_type, ColumnType::Custom(_)) {
+        return ().into_any();
+    }
+    let companion = |suffix: &str| row.cells.get(&format!("{key}_{suffix}")).cloned();
+    match column_type {
+        ColumnType::Text => value.to_string().into_any(),
+        ColumnType::Number => format_number(value).into_any(),
+        ColumnType::Date => {
+            view! { <time datetime=value.to_string()>{format_date(value)}</time> }.into_any()
+        }
+        ColumnType::Badge => {
+            let variant = badge_variant(value);
+            view! { <Badge variant=variant size=BadgeSize::Small>{value.to_string()}</Badge> }
+                .into_any()
+        }
+        ColumnType::Avatar => {
+            let avatar = match companion("photo") {
+                Some(photo) => view! {
+                    <Avatar name=value.to_string() photo_url=photo size=AvatarSize::Small />
+                }
+                .into_any(),
+                None => {
+                    view! { <Avatar name=value.to_string() size=AvatarSize::Small /> }.into_any()
+                }
+            };
+            view! {
+                <span class=style::avatar_cell>
+                    {avatar}
+                    <span>{value.to_string()}</span>
+                </span>
+            }
+            .into_any()
+        }
+        ColumnType::Link => {
+            let href = companion("href").unwrap_or_else(|| value.to_string());
+            view! {
+                <a href=href class=style::link_cell on:click=|ev| ev.stop_propagation()>
+                    {value.to_string()}
+                </a>
+            }
+            .into_any()
+        }
+        ColumnType::Custom(render) => render(row, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_values_are_formatted_for_display() {
+        assert_eq!(format_number("1234567"), "1,234,567");
+        assert_eq!(format_number("-1234.50"), "-1,234.50");
+        assert_eq!(format_number("999"), "999");
+        assert_eq!(format_number("n/a"), "n/a");
+        assert_eq!(format_date("2024-01-05"), "Jan 5, 2024");
+        assert_eq!(format_date("2024-03-10T08:30:00Z"), "Mar 10, 2024");
+        assert_eq!(format_date("soon"), "soon");
+        assert_eq!(badge_variant("On leave"), BadgeVariant::Warning);
+        assert_eq!(badge_variant("deployed"), BadgeVariant::Success);
+        assert_eq!(badge_variant("whatever"), BadgeVariant::Default);
+    }
+}
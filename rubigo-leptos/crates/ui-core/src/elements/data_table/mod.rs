@@ -1,10 +1,45 @@
 //! Data Table Component
 //!
 //! Generic clickable table with column definitions and row data.
+//!
+//! Given an `export_url` (e.g. `/api/export/sites?q=rack`, carrying the
+//! view's current filters), the table shows an Export menu that downloads
+//! the same rows as CSV, JSON or Excel.
+//!
+//! On phone widths each row is shown as a card, its cells labelled with the
+//! column headers.
+//!
+//! Given the `tags` rows can carry, the table adds a Tags column and a tag
+//! filter.
+//!
+//! With `on_bulk`, rows can be selected (shift-click selects a range, the
+//! header checkbox every row matching the filter) and a bulk-action bar
+//! offers tagging, `bulk_statuses` and `bulk_delete` for the selection. The
+//! host applies the edit, e.g. as a batch action, updates its `rows` signal
+//! and reports how it went for each row through `bulk_results`: rows that
+//! failed stay selected and show why. Selection and tag filter are kept
+//! across row updates; selected rows that are gone are dropped.
+//!
+//! A column's [`ColumnType`] decides how its raw cell values are shown:
+//! formatted numbers and dates, status badges, avatars, links or a custom
+//! renderer. Columns made `editable` can be edited in place with
+//! `on_cell_edit`; see [`cell_editor`] for how saves and errors flow.
+
+mod cell_editor;
+mod column_type;
 
 use leptos::prelude::*;
 use std::collections::HashMap;
 
+pub use cell_editor::{check_cell, CellEdit, CellEditor, CellError, CellValidator};
+use cell_editor::{CellEdits, EditableCell};
+use column_type::render_cell;
+pub use column_type::{badge_variant, format_date, format_number, CellRenderer, ColumnType};
+
+use crate::hooks::use_breakpoint;
+use crate::primitives::tag_picker::tags_by_id;
+use crate::primitives::{TagChip, TagOption, TagPicker};
+
 stylance::import_crate_style!(style, "src/elements/data_table/data_table.module.css");
 
 /// Column definition
@@ -16,6 +51,12 @@ pub struct DataColumn {
     pub header: String,
     /// Optional width (e.g. "200px", "30%")
     pub width: Option<String>,
+    /// How values are shown
+    pub column_type: ColumnType,
+    /// How cells are edited in place, for editable columns
+    pub editor: Option<CellEditor>,
+    /// Extra check on edited values
+    pub validator: Option<CellValidator>,
 }
 
 impl DataColumn {
@@ -24,6 +65,9 @@ impl DataColumn {
             key: key.into(),
             header: header.into(),
             width: None,
+            column_type: ColumnType::Text,
+            editor: None,
+            validator: None,
         }
     }
 
@@ -31,6 +75,24 @@ impl DataColumn {
         self.width = Some(width.into());
         self
     }
+
+    /// Show values as `column_type`
+    pub fn with_type(mut self, column_type: ColumnType) -> Self {
+        self.column_type = column_type;
+        self
+    }
+
+    /// Let cells be edited in place with `editor`
+    pub fn editable(mut self, editor: CellEditor) -> Self {
+        self.editor = Some(editor);
+        self
+    }
+
+    /// Check edited values with `validator` as well as the editor's rules
+    pub fn validate(mut self, validator: CellValidator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
 }
 
 /// Row data with ID and cell values
@@ -40,6 +102,8 @@ pub struct DataRow {
     pub id: String,
     /// Cell values keyed by column key
     pub cells: HashMap<String, String>,
+    /// IDs of the tags the row carries
+    pub tags: Vec<String>,
 }
 
 impl DataRow {
@@ -47,6 +111,7 @@ impl DataRow {
         Self {
             id: id.into(),
             cells: HashMap::new(),
+            tags: Vec::new(),
         }
     }
 
@@ -54,6 +119,133 @@ impl DataRow {
         self.cells.insert(key.into(), value.into());
         self
     }
+
+    pub fn tags<I: Into<String>>(mut self, ids: impl IntoIterator<Item = I>) -> Self {
+        self.tags = ids.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// An operation on the selected rows
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkOp {
+    /// Put the tag with this ID on the rows
+    AddTag(String),
+    /// Take the tag with this ID off the rows
+    RemoveTag(String),
+    /// Move the rows to this status
+    SetStatus(String),
+    Delete,
+}
+
+/// An operation chosen in the bulk-action bar and the rows it applies to
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkEdit {
+    pub op: BulkOp,
+    /// IDs of the selected rows
+    pub rows: Vec<String>,
+}
+
+/// How a bulk edit went for one row
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkResult {
+    pub row: String,
+    /// Why it failed; `None` once applied
+    pub error: Option<String>,
+}
+
+/// Selection after checking (or unchecking) row `id`
+///
+/// With `shift`, every row shown from the `anchor`, the row last clicked,
+/// through `id` goes the same way.
+fn toggle_rows(
+    shown: &[String],
+    selection: &[String],
+    anchor: Option<&str>,
+    id: &str,
+    checked: bool,
+    shift: bool,
+) -> Vec<String> {
+    let position = |id: &str| shown.iter().position(|row| row == id);
+    let range = match (shift, anchor.and_then(position), position(id)) {
+        (true, Some(from), Some(to)) => shown[from.min(to)..=from.max(to)].to_vec(),
+        _ => vec![id.to_string()],
+    };
+    let mut selected: Vec<String> = selection
+        .iter()
+        .filter(|row| !range.contains(row))
+        .cloned()
+        .collect();
+    if checked {
+        selected.extend(range);
+    }
+    selected
+}
+
+/// Whether a row carrying `row_tags` passes a filter on `wanted` (all of them)
+fn has_tags(row_tags: &[String], wanted: &[String]) -> bool {
+    wanted.iter().all(|tag| row_tags.contains(tag))
+}
+
+/// Filter and selection of a table, kept while its rows change
+#[derive(Clone, Copy)]
+struct TableState {
+    rows: Signal<Vec<DataRow>>,
+    /// Rows shown are those carrying every tag filtered on
+    tag_filter: RwSignal<Vec<String>>,
+    /// Rows checked, including any the host has since removed
+    checked: RwSignal<Vec<String>>,
+    /// Checked rows that still exist; bulk edits only touch these
+    selection: Memo<Vec<String>>,
+    /// Row last clicked, where a shift-click range starts
+    anchor: RwSignal<Option<String>>,
+}
+
+impl TableState {
+    fn new(rows: Signal<Vec<DataRow>>) -> Self {
+        let checked = RwSignal::new(Vec::<String>::new());
+        let selection = Memo::new(move |_| {
+            rows.with(|rows| {
+                checked.with(|checked| {
+                    checked
+                        .iter()
+                        .filter(|id| rows.iter().any(|row| row.id == **id))
+                        .cloned()
+                        .collect()
+                })
+            })
+        });
+        Self {
+            rows,
+            tag_filter: RwSignal::new(Vec::new()),
+            checked,
+            selection,
+            anchor: RwSignal::new(None),
+        }
+    }
+
+    fn visible(&self) -> Vec<DataRow> {
+        let wanted = self.tag_filter.get();
+        self.rows.with(|rows| {
+            rows.iter()
+                .filter(|row| has_tags(&row.tags, &wanted))
+                .cloned()
+                .collect()
+        })
+    }
+
+    fn visible_ids(&self) -> Vec<String> {
+        self.visible().into_iter().map(|row| row.id).collect()
+    }
+}
+
+/// Download formats offered by the Export menu: (query value, label)
+const EXPORT_FORMATS: [(&str, &str); 3] = [("csv", "CSV"), ("json", "JSON"), ("xlsx", "Excel")];
+
+/// `url` with its `format` query parameter set
+fn export_link(url: &str, format: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{separator}format={format}")
 }
 
 /// Data table component
@@ -61,32 +253,314 @@ impl DataRow {
 pub fn DataTable(
     /// Column definitions
     columns: Vec<DataColumn>,
-    /// Row data
-    rows: Vec<DataRow>,
+    /// Row data; selection and filters are kept when it changes
+    #[prop(into)]
+    rows: Signal<Vec<DataRow>>,
     /// Callback when row is clicked (receives row ID)
     #[prop(optional)]
     on_row_click: Option<Callback<String>>,
+    /// Export endpoint including the current filters; shows an Export menu
+    #[prop(optional, into)]
+    export_url: Option<Signal<String>>,
+    /// Tags rows can carry; shows a Tags column and a tag filter
+    #[prop(optional, into)]
+    tags: Option<Signal<Vec<TagOption>>>,
+    /// Adds row selection and a bulk-action bar for the selected rows,
+    /// with Add/Remove tag menus when there are `tags`
+    #[prop(optional)]
+    on_bulk: Option<Callback<BulkEdit>>,
+    /// Statuses the bulk-action bar can move rows to
+    #[prop(optional)]
+    bulk_statuses: Vec<String>,
+    /// Offer deleting the selected rows
+    #[prop(optional)]
+    bulk_delete: bool,
+    /// How the last bulk edit went for each row
+    #[prop(optional, into)]
+    bulk_results: Option<Signal<Vec<BulkResult>>>,
+    /// Saves a cell edited in place; editable columns need it
+    #[prop(optional)]
+    on_cell_edit: Option<Callback<CellEdit>>,
+    /// Cell edits the host couldn't save; their old values come back
+    #[prop(optional, into)]
+    cell_errors: Option<Signal<Vec<CellError>>>,
 ) -> impl IntoView {
+    let breakpoint = use_breakpoint();
+    let container_class = move || {
+        if breakpoint.get().is_mobile() {
+            format!("{} {}", style::table_container, style::cards)
+        } else {
+            style::table_container.to_string()
+        }
+    };
+    let export_menu = export_url.map(|url| {
+        view! {
+            <details class=style::export_menu>
+                <summary class=style::export_button>"Export"</summary>
+                <div class=style::export_options>
+                    {EXPORT_FORMATS
+                        .into_iter()
+                        .map(|(format, label)| {
+                            view! {
+                                <a
+                                    class=style::export_option
+                                    href=move || url.with(|url| export_link(url, format))
+                                    download=""
+                                >
+                                    {label}
+                                </a>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </div>
+            </details>
+        }
+    });
+
+    let table = TableState::new(rows);
+    let TableState {
+        tag_filter,
+        checked,
+        selection,
+        anchor,
+        ..
+    } = table;
+    let columns = StoredValue::new(columns);
+    let visible = move || table.visible();
+    let visible_ids = move || table.visible_ids();
+    // Selected rows hidden by a new filter are dropped, so bulk edits only
+    // touch rows in view
+    Effect::watch(
+        move || tag_filter.get(),
+        move |_, _, _| {
+            checked.set(Vec::new());
+            anchor.set(None);
+        },
+        false,
+    );
+    // Once a bulk edit is reported, only the rows it failed on stay selected
+    let results = bulk_results.unwrap_or_else(|| Signal::stored(Vec::new()));
+    Effect::watch(
+        move || results.get(),
+        move |results, _, _| {
+            checked.update(|s| {
+                s.retain(|id| results.iter().any(|r| r.row == *id && r.error.is_some()))
+            });
+        },
+        false,
+    );
+    let row_error = move |id: &str| {
+        results.with(|results| {
+            results
+                .iter()
+                .find(|r| r.row == id)
+                .and_then(|r| r.error.clone())
+        })
+    };
+
+    let selectable = on_bulk.is_some();
+    let cell_edits = on_cell_edit.map(|on_edit| CellEdits::new(on_edit, cell_errors));
+
+    let tag_filter_view = tags.map(|tags| {
+        view! {
+            <div class=style::tag_filter>
+                <TagPicker tags=tags selected=tag_filter placeholder="Filter by tag" />
+            </div>
+        }
+    });
+    let bulk_bar = on_bulk.map(|on_bulk| {
+        let confirm_delete = RwSignal::new(false);
+        let apply = move |op: BulkOp| {
+            confirm_delete.set(false);
+            on_bulk.run(BulkEdit {
+                op,
+                rows: selection.get_untracked(),
+            });
+        };
+        let tag_options = move || {
+            tags.map(|tags| {
+                tags.get()
+                    .into_iter()
+                    .map(|tag| view! { <option value=tag.id>{tag.name}</option> })
+                    .collect_view()
+            })
+        };
+        // Built anew each time the bar is shown
+        let tag_menus = move || {
+            tags.map(|_| {
+                view! {
+                    {bulk_menu(
+                        "Add tag…",
+                        "Add tag to selected rows",
+                        tag_options,
+                        move |id| apply(BulkOp::AddTag(id)),
+                    )}
+                    {bulk_menu(
+                        "Remove tag…",
+                        "Remove tag from selected rows",
+                        tag_options,
+                        move |id| apply(BulkOp::RemoveTag(id)),
+                    )}
+                }
+            })
+        };
+        let bulk_statuses = StoredValue::new(bulk_statuses);
+        let status_menu = move || {
+            bulk_statuses.with_value(|statuses| {
+                (!statuses.is_empty()).then(|| {
+                    let options = statuses
+                        .iter()
+                        .map(|status| {
+                            view! { <option value=status.clone()>{status.clone()}</option> }
+                        })
+                        .collect_view();
+                    bulk_menu(
+                        "Set status…",
+                        "Set status of selected rows",
+                        options,
+                        move |status| apply(BulkOp::SetStatus(status)),
+                    )
+                })
+            })
+        };
+        let delete_button = move || {
+            bulk_delete.then(|| {
+                view! {
+                    <Show
+                        when=move || confirm_delete.get()
+                        fallback=move || view! {
+                            <button
+                                type="button"
+                                class=style::bulk_button
+                                on:click=move |_| confirm_delete.set(true)
+                            >
+                                "Delete"
+                            </button>
+                        }
+                    >
+                        <span>{move || format!("Delete {}?", selection.with(Vec::len))}</span>
+                        <button
+                            type="button"
+                            class=style::bulk_danger
+                            on:click=move |_| apply(BulkOp::Delete)
+                        >
+                            "Delete"
+                        </button>
+                        <button
+                            type="button"
+                            class=style::bulk_button
+                            on:click=move |_| confirm_delete.set(false)
+                        >
+                            "Cancel"
+                        </button>
+                    </Show>
+                }
+            })
+        };
+        let unselected = move || {
+            let shown = visible_ids();
+            let missing = selection.with(|s| shown.iter().filter(|id| !s.contains(id)).count());
+            (missing > 0).then_some(shown.len())
+        };
+        view! {
+            <Show when=move || selection.with(|s| !s.is_empty())>
+                <div class=style::bulk_bar role="toolbar" aria-label="Selected rows">
+                    <span>{move || format!("{} selected", selection.with(Vec::len))}</span>
+                    {move || unselected().map(|matching| view! {
+                        <button
+                            type="button"
+                            class=style::bulk_link
+                            on:click=move |_| checked.set(visible_ids())
+                        >
+                            {format!("Select all {} matching", matching)}
+                        </button>
+                    })}
+                    {tag_menus}
+                    {status_menu}
+                    {delete_button}
+                    <button
+                        type="button"
+                        class=style::bulk_clear
+                        on:click=move |_| {
+                            checked.set(Vec::new());
+                            confirm_delete.set(false);
+                        }
+                    >
+                        "Clear"
+                    </button>
+                </div>
+            </Show>
+        }
+    });
+    let bulk_report = bulk_results.map(|results| {
+        let summary = move || {
+            results.with(|results| {
+                let failed = results.iter().filter(|r| r.error.is_some()).count();
+                match (results.len() - failed, failed) {
+                    (done, 0) => format!("Updated {} rows", done),
+                    (done, failed) => format!("Updated {} rows, {} failed", done, failed),
+                }
+            })
+        };
+        view! {
+            <Show when=move || results.with(|r| !r.is_empty())>
+                <p class=style::bulk_report role="status">{summary}</p>
+            </Show>
+        }
+    });
+    let has_toolbar = export_menu.is_some() || tag_filter_view.is_some();
+
+    let all_selected = move || {
+        let shown = visible_ids();
+        !shown.is_empty() && selection.with(|s| shown.iter().all(|id| s.contains(id)))
+    };
+    let select_all = move |ev: leptos::ev::Event| {
+        let ids = if event_target_checked(&ev) {
+            visible_ids()
+        } else {
+            Vec::new()
+        };
+        checked.set(ids);
+    };
+
     view! {
-        <div class=style::table_container>
+        {has_toolbar.then(|| view! {
+            <div class=style::toolbar>
+                {tag_filter_view}
+                {export_menu}
+            </div>
+        })}
+        {bulk_bar}
+        {bulk_report}
+        <div class=container_class>
             <table class=style::table>
                 <thead>
                     <tr>
-                        {columns.iter().map(|col| {
+                        {selectable.then(|| view! {
+                            <th class=style::select_cell>
+                                <input
+                                    type="checkbox"
+                                    aria-label="Select all rows"
+                                    prop:checked=all_selected
+                                    on:change=select_all
+                                />
+                            </th>
+                        })}
+                        {columns.with_value(|columns| columns.iter().map(|col| {
                             let header = col.header.clone();
                             let style_attr = col.width.as_ref().map(|w| format!("width: {}", w));
                             view! {
                                 <th style=style_attr>{header}</th>
                             }
-                        }).collect::<Vec<_>>()}
+                        }).collect::<Vec<_>>())}
+                        {tags.map(|_| view! { <th>"Tags"</th> })}
                     </tr>
                 </thead>
                 <tbody>
-                    {rows.into_iter().map(|row| {
+                    {move || visible().into_iter().map(|row| {
                         let row_id = row.id.clone();
                         let row_id_click = row_id.clone();
-                        let cells = row.cells;
-                        let cols = columns.clone();
+                        let row_tags = row.tags.clone();
 
                         view! {
                             <tr
@@ -97,15 +571,190 @@ pub fn DataTable(
                                     }
                                 }
                             >
-                                {cols.iter().map(|col| {
-                                    let value = cells.get(&col.key).cloned().unwrap_or_default();
-                                    view! { <td>{value}</td> }
-                                }).collect::<Vec<_>>()}
+                                {selectable.then(|| {
+                                    let id = row_id.clone();
+                                    let id_checked = row_id.clone();
+                                    let id_error = row_id.clone();
+                                    view! {
+                                        <td
+                                            class=style::select_cell
+                                            data-label="Select"
+                                            on:click=|ev| ev.stop_propagation()
+                                        >
+                                            <input
+                                                type="checkbox"
+                                                aria-label="Select row"
+                                                prop:checked=move || selection.with(|s| s.contains(&id_checked))
+                                                on:click=move |ev| {
+                                                    let shown = visible_ids();
+                                                    checked.set(toggle_rows(
+                                                        &shown,
+                                                        &selection.get_untracked(),
+                                                        anchor.get_untracked().as_deref(),
+                                                        &id,
+                                                        event_target_checked(&ev),
+                                                        ev.shift_key(),
+                                                    ));
+                                                    anchor.set(Some(id.clone()));
+                                                }
+                                            />
+                                            {move || row_error(&id_error).map(|error| view! {
+                                                <span
+                                                    class=style::row_error
+                                                    title=error.clone()
+                                                    aria-label=error
+                                                >
+                                                    "⚠"
+                                                </span>
+                                            })}
+                                        </td>
+                                    }
+                                })}
+                                {columns.with_value(|cols| cols.iter().map(|col| {
+                                    let value = row.cells.get(&col.key).cloned().unwrap_or_default();
+                                    match (cell_edits, col.editor.clone()) {
+                                        (Some(edits), Some(editor)) => view! {
+                                            <EditableCell
+                                                edits=edits
+                                                row=row.clone()
+                                                column=col.key.clone()
+                                                header=col.header.clone()
+                                                value=value
+                                                column_type=col.column_type.clone()
+                                                editor=editor
+                                                validator=col.validator
+                                            />
+                                        }
+                                        .into_any(),
+                                        _ => view! {
+                                            <td
+                                                data-label=col.header.clone()
+                                                class=col.column_type.class()
+                                            >
+                                                {render_cell(&col.column_type, &col.key, &row, &value)}
+                                            </td>
+                                        }
+                                        .into_any(),
+                                    }
+                                }).collect::<Vec<_>>())}
+                                {tags.map(|tags| view! {
+                                    <td data-label="Tags">
+                                        <span class=style::tag_cell>
+                                            {move || tags.with(|tags| tags_by_id(tags, &row_tags))
+                                                .into_iter()
+                                                .map(|tag| view! { <TagChip tag=tag /> })
+                                                .collect_view()}
+                                        </span>
+                                    </td>
+                                })}
                             </tr>
                         }
-                    }).collect::<Vec<_>>()}
+                    }).collect_view()}
                 </tbody>
             </table>
         </div>
     }
 }
+
+/// Menu of the bulk-action bar; choosing an option calls `on_choose` with
+/// its value
+fn bulk_menu(
+    placeholder: &'static str,
+    label: &'static str,
+    options: impl IntoView + 'static,
+    on_choose: impl Fn(String) + 'static,
+) -> impl IntoView {
+    let value = RwSignal::new(String::new());
+    view! {
+        <select
+            class=style::bulk_select
+            aria-label=label
+            prop:value=move || value.get()
+            on:change=move |ev| {
+                let chosen = event_target_value(&ev);
+                if !chosen.is_empty() {
+                    on_choose(chosen);
+                }
+                value.set(String::new());
+            }
+        >
+            <option value="">{placeholder}</option>
+            {options}
+        </select>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_link_appends_format_to_existing_query() {
+        assert_eq!(
+            export_link("/api/export/sites", "csv"),
+            "/api/export/sites?format=csv"
+        );
+        assert_eq!(
+            export_link("/api/export/sites?q=rack", "xlsx"),
+            "/api/export/sites?q=rack&format=xlsx"
+        );
+    }
+
+    #[test]
+    fn shift_click_selects_the_range_from_the_last_row_clicked() {
+        let shown: Vec<String> = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        let selection = toggle_rows(&shown, &[], None, "b", true, false);
+        assert_eq!(selection, ids(&["b"]));
+        let selection = toggle_rows(&shown, &selection, Some("b"), "d", true, true);
+        assert_eq!(selection, ids(&["b", "c", "d"]));
+        // Unchecking a range upwards; rows outside it are kept
+        let selection = toggle_rows(
+            &shown,
+            &ids(&["a", "b", "c", "d"]),
+            Some("d"),
+            "c",
+            false,
+            true,
+        );
+        assert_eq!(selection, ids(&["a", "b"]));
+        // Without a shown anchor only the row clicked changes
+        let selection = toggle_rows(&shown, &selection, Some("gone"), "e", true, true);
+        assert_eq!(selection, ids(&["a", "b", "e"]));
+    }
+
+    #[test]
+    fn tag_filter_needs_every_wanted_tag() {
+        let row = DataRow::new("1").tags(["critical", "remote"]);
+        assert!(has_tags(&row.tags, &[]));
+        assert!(has_tags(&row.tags, &["remote".to_string()]));
+        assert!(!has_tags(
+            &row.tags,
+            &["remote".to_string(), "spare".to_string()]
+        ));
+    }
+
+    #[test]
+    fn selection_and_filter_survive_new_rows() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let rows = RwSignal::new(vec![
+            DataRow::new("a").tags(["remote"]),
+            DataRow::new("b").tags(["remote"]),
+            DataRow::new("c"),
+        ]);
+        let table = TableState::new(rows.into());
+        table.tag_filter.set(ids(&["remote"]));
+        table.checked.set(ids(&["a", "b"]));
+
+        rows.set(vec![
+            DataRow::new("b").tags(["remote"]),
+            DataRow::new("c"),
+            DataRow::new("d").tags(["remote"]),
+        ]);
+        assert_eq!(table.tag_filter.get_untracked(), ids(&["remote"]));
+        assert_eq!(table.visible_ids(), ids(&["b", "d"]));
+        // "a" is gone, so it no longer counts as selected
+        assert_eq!(table.selection.get_untracked(), ids(&["b"]));
+    }
+}
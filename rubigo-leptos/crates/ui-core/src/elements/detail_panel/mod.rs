@@ -0,0 +1,398 @@
+//! DetailPanel Component
+//!
+//! The body of an entity's details [`SlidePanel`](crate::elements::SlidePanel):
+//! a header with the entity's avatar, name and status, then a row of tabs.
+//! A tab's content is only built the first time it is opened, so a tab that
+//! loads data (an activity feed, comments) asks for it only when someone
+//! looks, and is kept after that so switching back doesn't load it again.
+//! Tabs with an edit view get an "Edit" button in the header; while editing
+//! they show the edit view instead, until "Save", "Cancel" or another tab
+//! is opened.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use ui_core::elements::{DetailHeader, DetailPanel, DetailSection, DetailTab, SlidePanel};
+//!
+//! view! {
+//!     <SlidePanel open=show title="Asset Details">
+//!         {move || selected.get().map(|asset| {
+//!             let header = DetailHeader::new(asset.name.clone())
+//!                 .subtitle(asset.model.clone())
+//!                 .status(asset.status.clone());
+//!             let tabs = vec![
+//!                 DetailTab::overview(move || view! {
+//!                     <DetailSection title="Location">{asset.location.clone()}</DetailSection>
+//!                 })
+//!                 .editable(move || view! { <AssetForm /> }),
+//!                 DetailTab::custom("history", "History", move || view! { <History /> }),
+//!             ];
+//!             view! { <DetailPanel header=header tabs=tabs on_save=save /> }
+//!         })}
+//!     </SlidePanel>
+//! }
+//! ```
+
+use leptos::prelude::*;
+
+use crate::elements::data_table::badge_variant;
+use crate::elements::{TabItem, Tabs};
+use crate::primitives::{Avatar, AvatarSize, Badge, BadgeSize, Button, ButtonSize, ButtonVariant};
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/elements/detail_panel/detail_panel.module.css"
+);
+
+/// What a detail tab shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailTabKind {
+    /// The entity's own fields
+    Overview,
+    /// Everything that happened to the entity
+    Activity,
+    /// Comments only
+    Comments,
+    /// Anything else
+    Custom,
+}
+
+impl DetailTabKind {
+    /// Tab id and label of the standard kinds
+    fn standard(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            DetailTabKind::Overview => Some(("overview", "Overview")),
+            DetailTabKind::Activity => Some(("activity", "Activity")),
+            DetailTabKind::Comments => Some(("comments", "Comments")),
+            DetailTabKind::Custom => None,
+        }
+    }
+}
+
+/// One tab of a detail panel
+#[derive(Clone)]
+pub struct DetailTab {
+    /// Unique identifier
+    pub id: String,
+    /// Display label
+    pub label: String,
+    pub kind: DetailTabKind,
+    /// Badge count (optional)
+    pub badge: Option<u32>,
+    /// Tab content, built when the tab is first opened
+    pub content: ViewFn,
+    /// Shown instead of the content while editing
+    pub edit: Option<ViewFn>,
+}
+
+impl DetailTab {
+    fn standard(kind: DetailTabKind, content: impl Into<ViewFn>) -> Self {
+        let (id, label) = kind.standard().unwrap_or_default();
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            kind,
+            badge: None,
+            content: content.into(),
+            edit: None,
+        }
+    }
+
+    /// The "Overview" tab
+    pub fn overview(content: impl Into<ViewFn>) -> Self {
+        Self::standard(DetailTabKind::Overview, content)
+    }
+
+    /// The "Activity" tab
+    pub fn activity(content: impl Into<ViewFn>) -> Self {
+        Self::standard(DetailTabKind::Activity, content)
+    }
+
+    /// The "Comments" tab
+    pub fn comments(content: impl Into<ViewFn>) -> Self {
+        Self::standard(DetailTabKind::Comments, content)
+    }
+
+    /// A tab of the host's own
+    pub fn custom(
+        id: impl Into<String>,
+        label: impl Into<String>,
+        content: impl Into<ViewFn>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            kind: DetailTabKind::Custom,
+            badge: None,
+            content: content.into(),
+            edit: None,
+        }
+    }
+
+    /// Add a badge count
+    pub fn badge(mut self, count: u32) -> Self {
+        self.badge = Some(count);
+        self
+    }
+
+    /// Allow editing, showing `edit` while in edit mode
+    pub fn editable(mut self, edit: impl Into<ViewFn>) -> Self {
+        self.edit = Some(edit.into());
+        self
+    }
+
+    fn tab_item(&self) -> TabItem {
+        let item = TabItem::new(self.id.clone(), self.label.clone());
+        match self.badge {
+            Some(count) => item.badge(count),
+            None => item,
+        }
+    }
+}
+
+/// Who or what a detail panel is about
+#[derive(Clone)]
+pub struct DetailHeader {
+    /// Name of the entity
+    pub title: String,
+    /// Line under the name, e.g. a job title or model
+    pub subtitle: Option<String>,
+    /// Photo, otherwise initials of the title
+    pub photo_url: Option<String>,
+    /// Shown instead of the avatar, e.g. "🏢" for a site
+    pub icon: Option<String>,
+    /// Status badge, colored by [`badge_variant`]
+    pub status: Option<String>,
+    /// Extra header buttons, e.g. to copy a link
+    pub actions: Option<ViewFn>,
+}
+
+impl DetailHeader {
+    /// Header for the entity named `title`
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            subtitle: None,
+            photo_url: None,
+            icon: None,
+            status: None,
+            actions: None,
+        }
+    }
+
+    pub fn subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn photo_url(mut self, url: impl Into<String>) -> Self {
+        self.photo_url = Some(url.into());
+        self
+    }
+
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    pub fn status(mut self, status: impl Into<String>) -> Self {
+        self.status = Some(status.into());
+        self
+    }
+
+    pub fn actions(mut self, actions: impl Into<ViewFn>) -> Self {
+        self.actions = Some(actions.into());
+        self
+    }
+}
+
+/// `wanted` if there is a tab of that id, otherwise the first tab
+fn landing_tab(tabs: &[DetailTab], wanted: &str) -> String {
+    tabs.iter()
+        .find(|t| t.id == wanted)
+        .or_else(|| tabs.first())
+        .map(|t| t.id.clone())
+        .unwrap_or_default()
+}
+
+/// Header, tabs and lazily built tab content of an entity's details
+#[component]
+pub fn DetailPanel(
+    header: DetailHeader,
+    tabs: Vec<DetailTab>,
+    /// Active tab ID; keep one signal across entities to stay on the same tab
+    #[prop(optional)]
+    active_tab: Option<RwSignal<String>>,
+    /// Edit mode, off whenever the panel is built or the tab changes
+    #[prop(optional)]
+    editing: Option<RwSignal<bool>>,
+    /// Called by "Save" in edit mode
+    #[prop(optional)]
+    on_save: Option<Callback<()>>,
+) -> impl IntoView {
+    let active = active_tab.unwrap_or_else(|| RwSignal::new(String::new()));
+    let editing = editing.unwrap_or_else(|| RwSignal::new(false));
+    let landing = landing_tab(&tabs, &active.get_untracked());
+    if active.get_untracked() != landing {
+        active.set(landing.clone());
+    }
+    if editing.get_untracked() {
+        editing.set(false);
+    }
+
+    // Tabs opened so far; their content stays mounted. Edits belong to the
+    // tab they were started on, so switching tabs leaves edit mode.
+    let opened = RwSignal::new(vec![landing]);
+    Effect::new(move |previous: Option<String>| {
+        let id = active.get();
+        if previous.is_some_and(|previous| previous != id) {
+            editing.set(false);
+        }
+        if !opened.with_untracked(|o| o.contains(&id)) {
+            opened.update(|o| o.push(id.clone()));
+        }
+        id
+    });
+
+    let editable = StoredValue::new(
+        tabs.iter()
+            .filter(|t| t.edit.is_some())
+            .map(|t| t.id.clone())
+            .collect::<Vec<_>>(),
+    );
+    let can_edit = move || active.with(|a| editable.with_value(|e| e.contains(a)));
+    let save = Callback::new(move |_: leptos::ev::MouseEvent| {
+        if let Some(on_save) = on_save {
+            on_save.run(());
+        }
+        editing.set(false);
+    });
+    let edit_buttons = move || match (can_edit(), editing.get()) {
+        (false, _) => ().into_any(),
+        (true, false) => view! {
+            <Button
+                variant=ButtonVariant::Secondary
+                size=ButtonSize::Small
+                on_click=Callback::new(move |_| editing.set(true))
+            >
+                "Edit"
+            </Button>
+        }
+        .into_any(),
+        (true, true) => view! {
+            <Button
+                variant=ButtonVariant::Ghost
+                size=ButtonSize::Small
+                on_click=Callback::new(move |_| editing.set(false))
+            >
+                "Cancel"
+            </Button>
+            <Button size=ButtonSize::Small on_click=save>
+                "Save"
+            </Button>
+        }
+        .into_any(),
+    };
+
+    let DetailHeader {
+        title,
+        subtitle,
+        photo_url,
+        icon,
+        status,
+        actions,
+    } = header;
+    let avatar = match (icon, photo_url) {
+        (Some(icon), _) => view! {
+            <span class=style::detail_icon aria-hidden="true">{icon}</span>
+        }
+        .into_any(),
+        (None, Some(url)) => {
+            view! { <Avatar name=title.clone() photo_url=url size=AvatarSize::Large /> }.into_any()
+        }
+        (None, None) => view! { <Avatar name=title.clone() size=AvatarSize::Large /> }.into_any(),
+    };
+    let items: Vec<TabItem> = tabs.iter().map(DetailTab::tab_item).collect();
+
+    view! {
+        <div class=style::detail_panel>
+            <div class=style::detail_header>
+                {avatar}
+                <div class=style::detail_heading>
+                    <h3 class=style::detail_title>{title}</h3>
+                    {subtitle.map(|s| view! { <p class=style::detail_subtitle>{s}</p> })}
+                    {status.map(|s| {
+                        let variant = badge_variant(&s);
+                        view! { <Badge variant=variant size=BadgeSize::Small>{s}</Badge> }
+                    })}
+                </div>
+                <div class=style::detail_actions>
+                    {actions.map(|actions| actions.run())}
+                    {edit_buttons}
+                </div>
+            </div>
+
+            {(items.len() > 1).then(|| view! { <Tabs items=items active_tab=active /> })}
+
+            {tabs.into_iter().map(|tab| {
+                let DetailTab { id, content, edit, .. } = tab;
+                let has_edit = edit.is_some();
+                let loaded = Memo::new({
+                    let id = id.clone();
+                    move |_| opened.with(|o| o.contains(&id))
+                });
+                view! {
+                    <div
+                        class=style::detail_tab
+                        role="tabpanel"
+                        hidden=move || active.with(|a| *a != id)
+                    >
+                        <Show when=move || loaded.get()>
+                            <div hidden=move || has_edit && editing.get()>{content.run()}</div>
+                        </Show>
+                        {edit.map(|edit| view! {
+                            <Show when=move || editing.get()>{edit.run()}</Show>
+                        })}
+                    </div>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
+
+/// A titled group of fields in a detail tab
+#[component]
+pub fn DetailSection(
+    /// Small caps heading
+    #[prop(into)]
+    title: String,
+    children: Children,
+) -> impl IntoView {
+    view! {
+        <section class=style::detail_section>
+            <h4>{title}</h4>
+            {children()}
+        </section>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panels_land_on_the_wanted_tab_or_the_first() {
+        let tabs = vec![
+            DetailTab::overview(|| ()),
+            DetailTab::comments(|| ()),
+            DetailTab::custom("maintenance", "Maintenance", || ()).badge(2),
+        ];
+        assert_eq!(tabs[1].id, "comments");
+        assert_eq!(tabs[2].kind, DetailTabKind::Custom);
+        assert_eq!(tabs[2].tab_item().badge, Some(2));
+        assert_eq!(landing_tab(&tabs, "maintenance"), "maintenance");
+        assert_eq!(landing_tab(&tabs, "activity"), "overview");
+        assert_eq!(landing_tab(&[], "activity"), "");
+    }
+}
@@ -0,0 +1,213 @@
+//! Help Panel Component
+//!
+//! The slide-in panel showing a topic, search results or the list of
+//! topics, and the button that opens it at a topic.
+
+use super::markdown::{slug, Block, Inline};
+use super::{split_key, use_help, Help, HelpTopic};
+use crate::elements::{PanelSize, SlidePanel};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/elements/help/help.module.css"
+);
+
+/// Help panel, opened with [`Help::show`] or a [`HelpButton`]
+#[component]
+pub fn HelpPanel(
+    /// Help to show; defaults to the one from [`provide_help`](super::provide_help)
+    #[prop(optional)]
+    help: Option<Help>,
+) -> impl IntoView {
+    let help = help.unwrap_or_else(use_help);
+
+    // Bring a deep-linked section into view once the topic is rendered
+    Effect::new(move |_| {
+        let Some(help_key) = help.showing.get().filter(|_| help.open.get()) else {
+            return;
+        };
+        if let (_, Some(section)) = split_key(&help_key) {
+            let id = format!("help-{}", section);
+            request_animation_frame(move || {
+                if let Some(element) = document().get_element_by_id(&id) {
+                    element.scroll_into_view();
+                }
+            });
+        }
+    });
+
+    view! {
+        <SlidePanel open=help.open title="Help" size=PanelSize::Large>
+            <div class=style::help>
+                <input
+                    type="search"
+                    class=style::search
+                    placeholder="Search help"
+                    aria-label="Search help"
+                    prop:value=move || help.query.get()
+                    on:input=move |ev| help.query.set(event_target_value(&ev))
+                />
+                {move || {
+                    let query = help.query.get();
+                    if !query.trim().is_empty() {
+                        return results(help, &query).into_any();
+                    }
+                    let topic = help.showing.get().map(|help_key| {
+                        let (key, _) = split_key(&help_key);
+                        help.topic(key)
+                    });
+                    match topic {
+                        Some(Some(topic)) => topic_view(help, topic).into_any(),
+                        Some(None) => view! {
+                            <p class=style::empty>"There is no help on this yet."</p>
+                        }
+                        .into_any(),
+                        None => index(help).into_any(),
+                    }
+                }}
+            </div>
+        </SlidePanel>
+    }
+}
+
+/// Small "?" button opening help at `help_key`; nothing when there is no
+/// such topic
+#[component]
+pub fn HelpButton(
+    /// Topic key, optionally with a section: `calendar#recurring-events`
+    #[prop(into)]
+    help_key: String,
+    /// Accessible label
+    #[prop(optional, into)]
+    label: Option<String>,
+) -> impl IntoView {
+    let help = use_context::<Help>().filter(|help| help.has(&help_key));
+    let label = label.unwrap_or_else(|| "Help".to_string());
+    help.map(|help| {
+        view! {
+            <button
+                type="button"
+                class=style::help_button
+                aria-label=label.clone()
+                title=label
+                on:click=move |_| help.show(&help_key)
+            >
+                "?"
+            </button>
+        }
+    })
+}
+
+/// Every topic with its opening line
+fn index(help: Help) -> impl IntoView {
+    view! {
+        <ul class=style::list>
+            {help.topics().into_iter().map(|topic| {
+                let key = topic.key.clone();
+                view! {
+                    <li>
+                        <button type="button" class=style::entry on:click=move |_| help.show(&key)>
+                            <span class=style::entry_title>{topic.title.clone()}</span>
+                            {topic.summary().map(|s| view! { <span class=style::entry_text>{s}</span> })}
+                        </button>
+                    </li>
+                }
+            }).collect_view()}
+        </ul>
+    }
+}
+
+/// Topics matching `query`, each opening at the matching section
+fn results(help: Help, query: &str) -> impl IntoView {
+    let hits = help.search(query);
+    if hits.is_empty() {
+        return view! { <p class=style::empty>{format!("Nothing found for \"{}\"", query.trim())}</p> }.into_any();
+    }
+    view! {
+        <ul class=style::list>
+            {hits.into_iter().map(|hit| {
+                let help_key = hit.help_key();
+                view! {
+                    <li>
+                        <button type="button" class=style::entry on:click=move |_| help.show(&help_key)>
+                            <span class=style::entry_title>{hit.title}</span>
+                            <span class=style::entry_text>{hit.snippet}</span>
+                        </button>
+                    </li>
+                }
+            }).collect_view()}
+        </ul>
+    }
+    .into_any()
+}
+
+fn topic_view(help: Help, topic: HelpTopic) -> impl IntoView {
+    view! {
+        <article class=style::topic>
+            <button type="button" class=style::back on:click=move |_| help.showing.set(None)>
+                "← All topics"
+            </button>
+            <h2 class=style::topic_title>{topic.title}</h2>
+            {topic.blocks.into_iter().map(|block| block_view(help, block)).collect_view()}
+        </article>
+    }
+}
+
+fn block_view(help: Help, block: Block) -> AnyView {
+    match block {
+        Block::Heading { level: 2, text } => {
+            view! { <h3 id=format!("help-{}", slug(&text))>{text}</h3> }.into_any()
+        }
+        Block::Heading { text, .. } => {
+            view! { <h4 id=format!("help-{}", slug(&text))>{text}</h4> }.into_any()
+        }
+        Block::Paragraph(inlines) => view! { <p>{inline_views(help, inlines)}</p> }.into_any(),
+        Block::List { ordered, items } => {
+            let items = items
+                .into_iter()
+                .map(|item| view! { <li>{inline_views(help, item)}</li> })
+                .collect_view();
+            if ordered {
+                view! { <ol>{items}</ol> }.into_any()
+            } else {
+                view! { <ul>{items}</ul> }.into_any()
+            }
+        }
+        Block::Note(inlines) => {
+            view! { <p class=style::note>{inline_views(help, inlines)}</p> }.into_any()
+        }
+        Block::Code(code) => view! { <pre class=style::code><code>{code}</code></pre> }.into_any(),
+    }
+}
+
+fn inline_views(help: Help, inlines: Vec<Inline>) -> impl IntoView {
+    inlines
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => text.into_any(),
+            Inline::Strong(text) => view! { <strong>{text}</strong> }.into_any(),
+            Inline::Code(text) => view! { <code>{text}</code> }.into_any(),
+            Inline::Link { text, href } => match href.strip_prefix("help:") {
+                Some(key) => {
+                    let key = key.to_string();
+                    view! {
+                        <a
+                            href="#"
+                            on:click=move |ev| {
+                                ev.prevent_default();
+                                help.show(&key);
+                            }
+                        >
+                            {text}
+                        </a>
+                    }
+                    .into_any()
+                }
+                None => view! { <a href=href target="_blank" rel="noopener noreferrer">{text}</a> }
+                    .into_any(),
+            },
+        })
+        .collect_view()
+}
@@ -0,0 +1,257 @@
+//! Help Markdown
+//!
+//! The subset of markdown help topics are written in: `##`/`###`
+//! headings, paragraphs, `-` and `1.` lists, `>` notes and fenced code,
+//! with `**bold**`, `` `code` `` and `[links](…)` inline. A link to
+//! `help:<key>` opens another topic. Anything else reads as plain text.
+
+/// Inline run of text
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Strong(String),
+    Code(String),
+    Link { text: String, href: String },
+}
+
+/// Block of a help topic
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// Section heading; level 2 or 3
+    Heading {
+        level: u8,
+        text: String,
+    },
+    Paragraph(Vec<Inline>),
+    List {
+        ordered: bool,
+        items: Vec<Vec<Inline>>,
+    },
+    Note(Vec<Inline>),
+    Code(String),
+}
+
+impl Block {
+    /// Text of the block without markup, for search
+    pub fn text(&self) -> String {
+        match self {
+            Block::Heading { text, .. } | Block::Code(text) => text.clone(),
+            Block::Paragraph(inlines) | Block::Note(inlines) => plain(inlines),
+            Block::List { items, .. } => {
+                items.iter().map(|i| plain(i)).collect::<Vec<_>>().join(" ")
+            }
+        }
+    }
+}
+
+/// Text of inline runs without markup
+pub fn plain(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(t) | Inline::Strong(t) | Inline::Code(t) => t.as_str(),
+            Inline::Link { text, .. } => text.as_str(),
+        })
+        .collect()
+}
+
+/// Anchor ID of a heading, e.g. "Recurring events" -> "recurring-events"
+pub fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parse inline markup
+pub fn inlines(text: &str) -> Vec<Inline> {
+    let mut out = Vec::new();
+    let mut buf = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let parsed = match c {
+            '*' if rest.starts_with("**") => rest[2..]
+                .find("**")
+                .map(|end| (Inline::Strong(rest[2..2 + end].to_string()), 4 + end)),
+            '`' => rest[1..]
+                .find('`')
+                .map(|end| (Inline::Code(rest[1..1 + end].to_string()), 2 + end)),
+            '[' => rest
+                .find("](")
+                .filter(|mid| !rest[1..*mid].contains(']'))
+                .and_then(|mid| {
+                    let close = rest[mid + 2..].find(')')?;
+                    let link = Inline::Link {
+                        text: rest[1..mid].to_string(),
+                        href: rest[mid + 2..mid + 2 + close].to_string(),
+                    };
+                    Some((link, mid + 3 + close))
+                }),
+            _ => None,
+        };
+        match parsed {
+            Some((inline, len)) => {
+                if !buf.is_empty() {
+                    out.push(Inline::Text(std::mem::take(&mut buf)));
+                }
+                out.push(inline);
+                rest = &rest[len..];
+            }
+            None => {
+                buf.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !buf.is_empty() {
+        out.push(Inline::Text(buf));
+    }
+    out
+}
+
+/// List item text and whether the list is ordered, for a list line
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some((false, item));
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    (digits > 0)
+        .then(|| line[digits..].strip_prefix(". "))
+        .flatten()
+        .map(|item| (true, item))
+}
+
+/// Parse a topic into its title (the `#` heading) and blocks
+pub fn parse(markdown: &str) -> (Option<String>, Vec<Block>) {
+    let mut title = None;
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut note: Vec<&str> = Vec::new();
+    let mut list: Option<(bool, Vec<Vec<Inline>>)> = None;
+    let mut code: Option<Vec<&str>> = None;
+
+    fn flush(
+        blocks: &mut Vec<Block>,
+        paragraph: &mut Vec<&str>,
+        note: &mut Vec<&str>,
+        list: &mut Option<(bool, Vec<Vec<Inline>>)>,
+    ) {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(inlines(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+        if !note.is_empty() {
+            blocks.push(Block::Note(inlines(&note.join(" "))));
+            note.clear();
+        }
+        if let Some((ordered, items)) = list.take() {
+            blocks.push(Block::List { ordered, items });
+        }
+    }
+
+    for line in markdown.lines() {
+        if let Some(lines) = &mut code {
+            if line.trim_start().starts_with("```") {
+                blocks.push(Block::Code(lines.join("\n")));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+
+        let line = line.trim();
+        if line.starts_with("```") {
+            flush(&mut blocks, &mut paragraph, &mut note, &mut list);
+            code = Some(Vec::new());
+        } else if line.is_empty() {
+            flush(&mut blocks, &mut paragraph, &mut note, &mut list);
+        } else if let Some(text) = line.strip_prefix("# ") {
+            flush(&mut blocks, &mut paragraph, &mut note, &mut list);
+            title.get_or_insert_with(|| text.trim().to_string());
+        } else if line.starts_with("##") {
+            flush(&mut blocks, &mut paragraph, &mut note, &mut list);
+            let level = if line.starts_with("###") { 3 } else { 2 };
+            let text = line.trim_start_matches('#').trim().to_string();
+            blocks.push(Block::Heading { level, text });
+        } else if let Some(text) = line.strip_prefix('>') {
+            note.push(text.trim());
+        } else if let Some((ordered, item)) = list_item(line) {
+            if !paragraph.is_empty() || !note.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut note, &mut list);
+            }
+            match &mut list {
+                Some((o, items)) if *o == ordered => items.push(inlines(item)),
+                _ => {
+                    flush(&mut blocks, &mut paragraph, &mut note, &mut list);
+                    list = Some((ordered, vec![inlines(item)]));
+                }
+            }
+        } else if let Some((_, items)) = &mut list {
+            // Continuation of the last item
+            if let Some(last) = items.last_mut() {
+                last.push(Inline::Text(" ".to_string()));
+                last.extend(inlines(line));
+            }
+        } else {
+            paragraph.push(line);
+        }
+    }
+    if let Some(lines) = code {
+        blocks.push(Block::Code(lines.join("\n")));
+    }
+    flush(&mut blocks, &mut paragraph, &mut note, &mut list);
+    (title, blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_help_subset() {
+        let (title, blocks) = parse(
+            "# Calendar\n\nEvents **repeat** with a\nrule.\n\n## Recurring events\n\n- Daily\n- See [sites](help:sites)\n\n1. One\n\n> Drag to `move` it\n\n```\nFREQ=DAILY\n```\n",
+        );
+        assert_eq!(title.as_deref(), Some("Calendar"));
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Paragraph(vec![
+                    Inline::Text("Events ".into()),
+                    Inline::Strong("repeat".into()),
+                    Inline::Text(" with a rule.".into()),
+                ]),
+                Block::Heading {
+                    level: 2,
+                    text: "Recurring events".into()
+                },
+                Block::List {
+                    ordered: false,
+                    items: vec![
+                        vec![Inline::Text("Daily".into())],
+                        vec![
+                            Inline::Text("See ".into()),
+                            Inline::Link {
+                                text: "sites".into(),
+                                href: "help:sites".into()
+                            },
+                        ],
+                    ],
+                },
+                Block::List {
+                    ordered: true,
+                    items: vec![vec![Inline::Text("One".into())]]
+                },
+                Block::Note(vec![
+                    Inline::Text("Drag to ".into()),
+                    Inline::Code("move".into()),
+                    Inline::Text(" it".into()),
+                ]),
+                Block::Code("FREQ=DAILY".into()),
+            ]
+        );
+        assert_eq!(slug("Recurring events & rules"), "recurring-events-rules");
+    }
+}
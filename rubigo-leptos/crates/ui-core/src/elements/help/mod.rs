@@ -0,0 +1,289 @@
+//! Help Component
+//!
+//! In-app documentation: topics written in markdown, shown in a slide-in
+//! panel with search.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ui_core::elements::{provide_help, HelpButton, HelpPanel, HelpTopic};
+//!
+//! // Once, near the app root
+//! provide_help(vec![HelpTopic::parse("calendar", include_str!("calendar.md"))]);
+//!
+//! view! { <HelpPanel /> }
+//!
+//! // Next to what the topic explains; `#` picks a section
+//! view! { <HelpButton help_key="calendar#recurring-events" /> }
+//! ```
+//!
+//! `Card` and `SlidePanel` take a `help_key` too, showing the button in
+//! their header. Keys of topics that aren't provided show nothing, so
+//! components can link to help the host app may not have.
+//!
+//! # Writing Topics
+//!
+//! One file per page or component, named by its key. The `#` heading is
+//! the topic's title; `##` headings are the sections search results and
+//! deep links point at; a `[link](help:sites)` opens another topic. See
+//! [`markdown`] for the markup understood.
+
+mod help_panel;
+pub mod markdown;
+
+pub use help_panel::{HelpButton, HelpPanel};
+
+use leptos::prelude::*;
+use markdown::Block;
+
+/// Characters of context shown either side of a search match
+const SNIPPET_CONTEXT: usize = 60;
+
+/// A help topic
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpTopic {
+    /// Key components link to it by
+    pub key: String,
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+impl HelpTopic {
+    /// Parse a topic from markdown; untitled topics are named by their key
+    pub fn parse(key: impl Into<String>, markdown: &str) -> Self {
+        let key = key.into();
+        let (title, blocks) = markdown::parse(markdown);
+        Self {
+            title: title.unwrap_or_else(|| key.clone()),
+            key,
+            blocks,
+        }
+    }
+
+    /// Opening paragraph, shown in the topic index
+    pub fn summary(&self) -> Option<String> {
+        self.blocks.iter().find_map(|block| match block {
+            Block::Paragraph(inlines) => Some(markdown::plain(inlines)),
+            _ => None,
+        })
+    }
+}
+
+/// A topic matching a search
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpHit {
+    pub key: String,
+    pub title: String,
+    /// Section of the first match, as a deep link anchor
+    pub section: Option<String>,
+    /// Text around the first match
+    pub snippet: String,
+}
+
+impl HelpHit {
+    /// Help key opening the topic at the matching section
+    pub fn help_key(&self) -> String {
+        match &self.section {
+            Some(section) => format!("{}#{}", self.key, section),
+            None => self.key.clone(),
+        }
+    }
+}
+
+/// Split a help key into topic key and section, `calendar#recurring-events`
+pub fn split_key(help_key: &str) -> (&str, Option<&str>) {
+    match help_key.split_once('#') {
+        Some((key, section)) => (key, Some(section)),
+        None => (help_key, None),
+    }
+}
+
+/// `text` around byte offset `at`, on character boundaries
+fn snippet(text: &str, at: usize) -> String {
+    let mut start = at.saturating_sub(SNIPPET_CONTEXT);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (at + SNIPPET_CONTEXT).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    let mut out = text[start..end].trim().to_string();
+    if start > 0 {
+        out.insert(0, '…');
+    }
+    if end < text.len() {
+        out.push('…');
+    }
+    out
+}
+
+/// Topics containing every word of `query`, best first
+///
+/// Words in a title count most, then in a heading, then in the text.
+pub fn search(topics: &[HelpTopic], query: &str) -> Vec<HelpHit> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits: Vec<(u32, HelpHit)> = topics
+        .iter()
+        .filter_map(|topic| {
+            let title = topic.title.to_lowercase();
+            let mut section = None;
+            let mut texts = Vec::new();
+            for block in &topic.blocks {
+                if let Block::Heading { text, .. } = block {
+                    section = Some(text.clone());
+                }
+                texts.push((
+                    section.clone(),
+                    block.text(),
+                    matches!(block, Block::Heading { .. }),
+                ));
+            }
+
+            let mut score = 0;
+            for word in &words {
+                let in_title = title.contains(word.as_str());
+                let in_heading = texts.iter().any(|(_, text, heading)| {
+                    *heading && text.to_lowercase().contains(word.as_str())
+                });
+                let in_text = texts
+                    .iter()
+                    .any(|(_, text, _)| text.to_lowercase().contains(word.as_str()));
+                score += match (in_title, in_heading, in_text) {
+                    (true, _, _) => 3,
+                    (_, true, _) => 2,
+                    (_, _, true) => 1,
+                    _ => return None,
+                };
+            }
+
+            let first = &words[0];
+            let (section, snippet) = texts
+                .iter()
+                .find_map(|(section, text, _)| {
+                    let at = text.to_lowercase().find(first.as_str())?;
+                    Some((section.as_deref().map(markdown::slug), snippet(text, at)))
+                })
+                .unwrap_or_else(|| (None, topic.summary().unwrap_or_default()));
+            Some((
+                score,
+                HelpHit {
+                    key: topic.key.clone(),
+                    title: topic.title.clone(),
+                    section,
+                    snippet,
+                },
+            ))
+        })
+        .collect();
+    hits.sort_by(|(a, x), (b, y)| b.cmp(a).then_with(|| x.title.cmp(&y.title)));
+    hits.into_iter().map(|(_, hit)| hit).collect()
+}
+
+/// Shared help state, see [`provide_help`]
+#[derive(Clone, Copy)]
+pub struct Help {
+    topics: StoredValue<Vec<HelpTopic>>,
+    /// Whether the panel is open
+    pub open: RwSignal<bool>,
+    /// Help key shown; `None` for the index
+    pub showing: RwSignal<Option<String>>,
+    pub query: RwSignal<String>,
+}
+
+impl Help {
+    /// Open the panel at a topic, `key` or `key#section`
+    pub fn show(&self, help_key: &str) {
+        self.query.set(String::new());
+        self.showing.set(Some(help_key.to_string()));
+        self.open.set(true);
+    }
+
+    /// Open the panel at the list of topics
+    pub fn show_index(&self) {
+        self.showing.set(None);
+        self.open.set(true);
+    }
+
+    /// Whether a topic for `help_key` is provided
+    pub fn has(&self, help_key: &str) -> bool {
+        let (key, _) = split_key(help_key);
+        self.topics
+            .with_value(|topics| topics.iter().any(|t| t.key == key))
+    }
+
+    pub fn topic(&self, key: &str) -> Option<HelpTopic> {
+        self.topics
+            .with_value(|topics| topics.iter().find(|t| t.key == key).cloned())
+    }
+
+    pub fn topics(&self) -> Vec<HelpTopic> {
+        self.topics.get_value()
+    }
+
+    pub fn search(&self, query: &str) -> Vec<HelpHit> {
+        self.topics.with_value(|topics| search(topics, query))
+    }
+}
+
+/// Provide help topics below the app root
+pub fn provide_help(topics: Vec<HelpTopic>) -> Help {
+    let help = Help {
+        topics: StoredValue::new(topics),
+        open: RwSignal::new(false),
+        showing: RwSignal::new(None),
+        query: RwSignal::new(String::new()),
+    };
+    provide_context(help);
+    help
+}
+
+/// Access the help provided by [`provide_help`]
+pub fn use_help() -> Help {
+    expect_context::<Help>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics() -> Vec<HelpTopic> {
+        vec![
+            HelpTopic::parse(
+                "calendar",
+                "# Calendar\n\nPlan meetings.\n\n## Recurring events\n\nEvents can repeat daily or weekly.",
+            ),
+            HelpTopic::parse("sites", "# Sites\n\nWhere equipment lives. Meetings happen in rooms."),
+        ]
+    }
+
+    #[test]
+    fn search_ranks_titles_over_text_and_links_to_the_section() {
+        let hits = search(&topics(), "Weekly");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].help_key(), "calendar#recurring-events");
+        assert_eq!(hits[0].snippet, "Events can repeat daily or weekly.");
+
+        let keys: Vec<_> = search(&topics(), "meetings")
+            .into_iter()
+            .map(|h| h.key)
+            .collect();
+        assert_eq!(keys, ["calendar", "sites"]);
+        let keys: Vec<_> = search(&topics(), "sites meetings")
+            .into_iter()
+            .map(|h| h.key)
+            .collect();
+        assert_eq!(keys, ["sites"]);
+        assert!(search(&topics(), "  ").is_empty());
+
+        assert_eq!(
+            split_key("calendar#recurring-events"),
+            ("calendar", Some("recurring-events"))
+        );
+        assert_eq!(HelpTopic::parse("untitled", "Text").title, "untitled");
+    }
+}
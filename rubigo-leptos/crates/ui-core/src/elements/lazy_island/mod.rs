@@ -0,0 +1,199 @@
+//! LazyIsland Component
+//!
+//! Defers a heavy section of the page until it scrolls into view.
+//!
+//! Until then a [`Skeleton`] holds its place. Once the section comes within
+//! `root_margin` of the viewport its loader runs, and the children are
+//! mounted when the loader resolves.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use ui_core::elements::{IslandLoader, LazyIsland};
+//!
+//! // Under `cargo leptos --split`, a `#[lazy]` function lives in its own
+//! // WASM chunk, fetched the first time it is called
+//! #[lazy]
+//! fn load_globe() {}
+//!
+//! view! {
+//!     <LazyIsland label="Loading globe" height="520px" load=IslandLoader::new(load_globe)>
+//!         <Globe />
+//!     </LazyIsland>
+//! }
+//! ```
+//!
+//! Without a loader the children mount as soon as they are visible. With a
+//! single-binary build (Trunk) this still skips their setup, such as starting
+//! a Bevy app, for sections the user never scrolls to.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use leptos::prelude::*;
+
+use crate::primitives::{Skeleton, SkeletonShape};
+
+/// Start loading this far before the island scrolls into view
+pub const DEFAULT_ROOT_MARGIN: &str = "200px";
+
+/// Loads an island's code; resolves once the island can render
+#[derive(Clone)]
+pub struct IslandLoader(Rc<dyn Fn() -> Pin<Box<dyn Future<Output = ()>>>>);
+
+impl IslandLoader {
+    pub fn new<F, Fut>(load: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        Self(Rc::new(move || Box::pin(load())))
+    }
+
+    fn load(&self) -> Pin<Box<dyn Future<Output = ()>>> {
+        (self.0)()
+    }
+}
+
+/// Where an island is in its lifecycle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IslandState {
+    /// Off screen; the skeleton is shown
+    #[default]
+    Waiting,
+    /// Visible and loading; the skeleton is still shown
+    Loading,
+    /// Loaded; the children are mounted
+    Ready,
+}
+
+impl IslandState {
+    /// Value of the wrapper's `data-island` attribute
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IslandState::Waiting => "waiting",
+            IslandState::Loading => "loading",
+            IslandState::Ready => "ready",
+        }
+    }
+
+    /// State after the island comes into view; only a waiting island starts loading
+    pub fn visible(self) -> Self {
+        match self {
+            IslandState::Waiting => IslandState::Loading,
+            other => other,
+        }
+    }
+}
+
+/// Mount `children` once they scroll into view and their code has loaded
+#[component]
+pub fn LazyIsland(
+    /// Announced while the island loads, e.g. "Loading globe"
+    #[prop(into)]
+    label: String,
+    /// Placeholder height; match the island's height to avoid layout shift
+    #[prop(into, default = "320px".to_string())]
+    height: String,
+    /// CSS margin around the viewport within which loading starts
+    #[prop(into, default = DEFAULT_ROOT_MARGIN.to_string())]
+    root_margin: String,
+    /// Fetches the island's code before it mounts
+    #[prop(optional)]
+    load: Option<IslandLoader>,
+    /// The deferred section
+    children: ChildrenFn,
+) -> impl IntoView {
+    let state = RwSignal::new(IslandState::Waiting);
+    let node = NodeRef::<leptos::html::Div>::new();
+
+    let start = move || {
+        let next = state.get_untracked().visible();
+        if next == state.get_untracked() {
+            return;
+        }
+        state.set(next);
+        match load.clone() {
+            Some(loader) => leptos::task::spawn_local(async move {
+                loader.load().await;
+                state.set(IslandState::Ready);
+            }),
+            None => state.set(IslandState::Ready),
+        }
+    };
+
+    // Watch for the wrapper entering the viewport, then stop watching
+    Effect::new(move |_| {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let Some(element) = node.get() else {
+            return;
+        };
+        let on_visible = start.clone();
+        let callback = Closure::<dyn FnMut(js_sys::Array, web_sys::IntersectionObserver)>::new(
+            move |entries: js_sys::Array, observer: web_sys::IntersectionObserver| {
+                let visible = entries.iter().any(|e| {
+                    e.unchecked_into::<web_sys::IntersectionObserverEntry>()
+                        .is_intersecting()
+                });
+                if visible {
+                    observer.disconnect();
+                    on_visible();
+                }
+            },
+        );
+        let options = web_sys::IntersectionObserverInit::new();
+        options.set_root_margin(&root_margin);
+
+        match web_sys::IntersectionObserver::new_with_options(
+            callback.as_ref().unchecked_ref(),
+            &options,
+        ) {
+            Ok(observer) => {
+                observer.observe(&element);
+                let handle = send_wrapper::SendWrapper::new((observer, callback));
+                Owner::on_cleanup(move || {
+                    let (observer, callback) = handle.take();
+                    observer.disconnect();
+                    drop(callback);
+                });
+            }
+            // No IntersectionObserver to wait on; load straight away
+            Err(_) => start(),
+        }
+    });
+
+    view! {
+        <div node_ref=node data-island=move || state.get().as_str()>
+            <Show
+                when=move || state.get() == IslandState::Ready
+                fallback=move || view! {
+                    <Skeleton shape=SkeletonShape::Block height=height.clone() label=label.clone() />
+                }
+            >
+                {children()}
+            </Show>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_waiting_island_starts_loading() {
+        assert_eq!(IslandState::Waiting.visible(), IslandState::Loading);
+        assert_eq!(IslandState::Loading.visible(), IslandState::Loading);
+        assert_eq!(IslandState::Ready.visible(), IslandState::Ready);
+    }
+
+    #[test]
+    fn island_state_default_and_names() {
+        assert_eq!(IslandState::default(), IslandState::Waiting);
+        assert_eq!(IslandState::Loading.as_str(), "loading");
+        assert_eq!(IslandState::Ready.as_str(), "ready");
+    }
+}
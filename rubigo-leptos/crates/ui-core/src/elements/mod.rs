@@ -6,29 +6,53 @@
 //!
 //! ## Components
 //!
+//! - [`Accordion`] - Collapsible sections with single/multi-open modes
 //! - [`Card`] - Container for grouping related content
 //! - [`DataTable`] - Generic data table with column definitions
+//! - [`DetailPanel`] - Entity details with header, lazy tabs and edit mode
 //! - [`FilterDropdown`] - Dropdown for filtering lists
+//! - [`HelpPanel`] - Searchable in-app documentation
+//! - [`LazyIsland`] - Section mounted once it scrolls into view
 //! - [`Modal`] - Dialog overlay for focused interactions
 //! - [`Pagination`] - Table pagination controls
+//! - [`PrintButton`] - Prints the page in its print layout
 //! - [`SlidePanel`] - Slide-in panel from right
 //! - [`Table`] - Data table with columns and rows
 //! - [`Tabs`] - Tabbed navigation interface
+//! - [`ToastRegion`] - Queued transient notifications
+//! - [`TourOverlay`] - Guided product tours over annotated elements
 
+pub mod accordion;
 pub mod card;
 pub mod data_table;
+pub mod detail_panel;
 pub mod filter_dropdown;
+pub mod help;
+pub mod lazy_island;
 pub mod modal;
 pub mod pagination;
+pub mod print;
 pub mod slide_panel;
 pub mod table;
 pub mod tabs;
+pub mod toast;
+pub mod tour;
 
+pub use accordion::{Accordion, AccordionItem, AccordionMode};
 pub use card::{Card, CardVariant};
-pub use data_table::{DataColumn, DataRow, DataTable};
+pub use data_table::{
+    BulkEdit, BulkOp, BulkResult, CellEdit, CellEditor, CellError, CellValidator, DataColumn,
+    DataRow, DataTable,
+};
+pub use detail_panel::{DetailHeader, DetailPanel, DetailSection, DetailTab, DetailTabKind};
 pub use filter_dropdown::FilterDropdown;
+pub use help::{provide_help, use_help, Help, HelpButton, HelpPanel, HelpTopic};
+pub use lazy_island::{IslandLoader, IslandState, LazyIsland};
 pub use modal::{Modal, ModalSize};
 pub use pagination::Pagination;
+pub use print::{PrintButton, PrintHeader};
 pub use slide_panel::{PanelSize, SlidePanel};
 pub use table::{Table, TableColumn, TableVariant};
 pub use tabs::{TabItem, Tabs, TabsVariant};
+pub use toast::{provide_toasts, use_toasts, Toast, ToastLevel, ToastQueue, ToastRegion};
+pub use tour::{provide_tours, use_tours, HelpMenu, Tour, TourAnchor, TourOverlay, Tours};
@@ -0,0 +1,80 @@
+//! Print Components
+//!
+//! [`PrintButton`] starts a print of the current page through
+//! [`PrintMode`](crate::hooks::PrintMode); [`PrintHeader`] is the title
+//! block the [`Layout`](crate::layout::Layout) prints in place of its
+//! header and sidebar.
+
+use chrono::{DateTime, Local, TimeZone};
+use leptos::prelude::*;
+
+use crate::hooks::use_print_mode;
+use crate::primitives::{Button, ButtonSize, ButtonVariant};
+
+stylance::import_crate_style!(style, "src/elements/print/print.module.css");
+
+/// Print footnote, e.g. "Printed December 9, 2024 at 14:05"
+fn printed_label<Tz: TimeZone>(at: DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    format!("Printed {}", at.format("%B %-d, %Y at %H:%M"))
+}
+
+/// "Print" action for a page; hidden on the printed copy
+#[component]
+pub fn PrintButton(
+    /// Heading of the printed page
+    #[prop(into)]
+    title: String,
+) -> impl IntoView {
+    let print = use_print_mode();
+    let title = StoredValue::new(title);
+
+    view! {
+        <span class=style::print_button>
+            <Button
+                variant=ButtonVariant::Secondary
+                size=ButtonSize::Small
+                on_click=Callback::new(move |_| print.print(title.get_value()))
+            >
+                "🖨 Print"
+            </Button>
+        </span>
+    }
+}
+
+/// Title block shown at the top of the page while printing
+///
+/// Printed from the browser's menu rather than a [`PrintButton`], the page
+/// is headed with the document title.
+#[component]
+pub fn PrintHeader() -> impl IntoView {
+    let print = use_print_mode();
+    let title = move || {
+        print
+            .title()
+            .or_else(|| Some(document().title()).filter(|title| !title.is_empty()))
+    };
+
+    view! {
+        <Show when=move || print.is_active()>
+            <div class=style::print_header>
+                {move || title().map(|title| view! { <h1 class=style::print_title>{title}</h1> })}
+                <span class=style::print_meta>"Rubigo · "{printed_label(Local::now())}</span>
+            </div>
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn printed_label_reads_naturally() {
+        let at = Utc.with_ymd_and_hms(2024, 12, 9, 14, 5, 0).unwrap();
+        assert_eq!(printed_label(at), "Printed December 9, 2024 at 14:05");
+    }
+}
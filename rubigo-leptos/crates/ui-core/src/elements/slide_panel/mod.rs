@@ -21,6 +21,7 @@
 //! }
 //! ```
 
+use crate::elements::HelpButton;
 use leptos::prelude::*;
 
 stylance::import_crate_style!(
@@ -61,6 +62,9 @@ pub fn SlidePanel(
     /// Size variant
     #[prop(default = PanelSize::Medium)]
     size: PanelSize,
+    /// Help topic opened from a "?" in the header
+    #[prop(optional, into)]
+    help_key: Option<String>,
     /// Main content - use ChildrenFn for reactive rendering
     children: ChildrenFn,
 ) -> impl IntoView {
@@ -99,9 +103,12 @@ pub fn SlidePanel(
                 >
                     <div class=style::panel_header>
                         {title.clone().map(|t| view! { <h2 class=style::panel_title>{t}</h2> })}
-                        <button class=style::panel_close on:click=close>
-                            "✕"
-                        </button>
+                        <div class=style::panel_actions>
+                            {help_key.clone().map(|key| view! { <HelpButton help_key=key /> })}
+                            <button class=style::panel_close on:click=close>
+                                "✕"
+                            </button>
+                        </div>
                     </div>
                     <div class=style::panel_content>
                         {children()}
@@ -0,0 +1,476 @@
+//! Toast Component
+//!
+//! Transient notifications backed by a queue model.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ui_core::elements::{provide_toasts, use_toasts, Toast, ToastLevel, ToastRegion};
+//!
+//! // Once, near the app root
+//! provide_toasts(3);
+//!
+//! view! { <ToastRegion /> }
+//!
+//! // Anywhere below the root
+//! let toasts = use_toasts();
+//! toasts.update(|q| {
+//!     q.push(Toast::new(ToastLevel::Success, "Asset saved"));
+//! });
+//! ```
+//!
+//! # Queue Semantics
+//!
+//! - At most `max_visible` toasts are shown; the rest wait in order
+//! - Visible toasts auto-dismiss after their duration; hovering pauses the timer
+//! - Assertive toasts (`Error`) preempt the oldest polite toast when full
+//!
+//! # Best Practices
+//!
+//! - Keep messages to one short sentence
+//! - Reserve `Error` for failures that need attention
+//! - Use sticky toasts (`.sticky()`) only when the user must act
+//! - Give a toast an action button (`.action("Undo")`) for a one-click
+//!   follow-up; the region reports presses through `on_action`
+
+use std::collections::VecDeque;
+
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/elements/toast/toast.module.css"
+);
+
+/// Default auto-dismiss duration in milliseconds
+pub const DEFAULT_TOAST_DURATION_MS: u32 = 5000;
+
+/// Timer resolution used by [`ToastRegion`]
+const TICK_MS: u32 = 100;
+
+/// Toast severity level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToastLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    fn class(&self) -> &'static str {
+        match self {
+            ToastLevel::Info => style::toast_info,
+            ToastLevel::Success => style::toast_success,
+            ToastLevel::Warning => style::toast_warning,
+            ToastLevel::Error => style::toast_error,
+        }
+    }
+
+    /// Assertive toasts interrupt the user and may preempt others
+    pub fn is_assertive(&self) -> bool {
+        matches!(self, ToastLevel::Error)
+    }
+
+    /// ARIA role for the live region entry
+    pub fn role(&self) -> &'static str {
+        if self.is_assertive() {
+            "alert"
+        } else {
+            "status"
+        }
+    }
+
+    /// ARIA live politeness
+    pub fn aria_live(&self) -> &'static str {
+        if self.is_assertive() {
+            "assertive"
+        } else {
+            "polite"
+        }
+    }
+}
+
+/// Toast definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    /// Identifier, assigned by [`ToastQueue::push`]
+    pub id: u64,
+    /// Severity level
+    pub level: ToastLevel,
+    /// Primary message
+    pub title: String,
+    /// Optional secondary text
+    pub message: Option<String>,
+    /// Auto-dismiss duration; `None` keeps the toast until dismissed
+    pub duration_ms: Option<u32>,
+    /// Label of an action button, e.g. "Undo"
+    pub action: Option<String>,
+}
+
+impl Toast {
+    /// Create a new toast
+    pub fn new(level: ToastLevel, title: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            level,
+            title: title.into(),
+            message: None,
+            duration_ms: Some(DEFAULT_TOAST_DURATION_MS),
+            action: None,
+        }
+    }
+
+    /// Add secondary text
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Set auto-dismiss duration
+    pub fn duration(mut self, ms: u32) -> Self {
+        self.duration_ms = Some(ms);
+        self
+    }
+
+    /// Keep visible until dismissed
+    pub fn sticky(mut self) -> Self {
+        self.duration_ms = None;
+        self
+    }
+
+    /// Show an action button with this label
+    pub fn action(mut self, label: impl Into<String>) -> Self {
+        self.action = Some(label.into());
+        self
+    }
+}
+
+/// A toast currently on screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveToast {
+    pub toast: Toast,
+    /// Remaining time before auto-dismiss
+    pub remaining_ms: Option<u32>,
+    /// Timer paused (hover/focus)
+    pub paused: bool,
+}
+
+/// Queue state machine for toasts
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToastQueue {
+    visible: Vec<ActiveToast>,
+    pending: VecDeque<Toast>,
+    max_visible: usize,
+    next_id: u64,
+}
+
+impl Default for ToastQueue {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl ToastQueue {
+    /// Create an empty queue showing at most `max_visible` toasts
+    pub fn new(max_visible: usize) -> Self {
+        Self {
+            visible: Vec::new(),
+            pending: VecDeque::new(),
+            max_visible: max_visible.max(1),
+            next_id: 1,
+        }
+    }
+
+    /// Toasts currently on screen, oldest first
+    pub fn visible(&self) -> &[ActiveToast] {
+        &self.visible
+    }
+
+    /// Number of toasts waiting for a slot
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Enqueue a toast, returning its assigned ID
+    pub fn push(&mut self, mut toast: Toast) -> u64 {
+        toast.id = self.next_id;
+        self.next_id += 1;
+        let id = toast.id;
+
+        if self.visible.len() < self.max_visible {
+            self.show(toast);
+        } else if toast.level.is_assertive() {
+            // Bump the oldest polite toast back to the head of the queue
+            match self
+                .visible
+                .iter()
+                .position(|t| !t.toast.level.is_assertive())
+            {
+                Some(index) => {
+                    let bumped = self.visible.remove(index);
+                    self.queue_ahead(bumped.toast);
+                    self.show(toast);
+                }
+                None => self.queue_ahead(toast),
+            }
+        } else {
+            self.pending.push_back(toast);
+        }
+
+        id
+    }
+
+    /// Queue `toast` behind the assertive toasts already waiting but ahead
+    /// of the polite ones, so errors still show in the order they came
+    fn queue_ahead(&mut self, toast: Toast) {
+        let index = self
+            .pending
+            .iter()
+            .rposition(|t| t.level.is_assertive())
+            .map_or(0, |last| last + 1);
+        self.pending.insert(index, toast);
+    }
+
+    /// Remove a toast (visible or pending)
+    pub fn dismiss(&mut self, id: u64) {
+        self.visible.retain(|t| t.toast.id != id);
+        self.pending.retain(|t| t.id != id);
+        self.promote();
+    }
+
+    /// Remove every toast
+    pub fn clear(&mut self) {
+        self.visible.clear();
+        self.pending.clear();
+    }
+
+    /// Pause the auto-dismiss timer of a visible toast
+    pub fn pause(&mut self, id: u64) {
+        if let Some(t) = self.visible.iter_mut().find(|t| t.toast.id == id) {
+            t.paused = true;
+        }
+    }
+
+    /// Resume the auto-dismiss timer of a visible toast
+    pub fn resume(&mut self, id: u64) {
+        if let Some(t) = self.visible.iter_mut().find(|t| t.toast.id == id) {
+            t.paused = false;
+        }
+    }
+
+    /// Advance timers by `elapsed_ms`, expiring finished toasts
+    pub fn tick(&mut self, elapsed_ms: u32) {
+        for t in self.visible.iter_mut().filter(|t| !t.paused) {
+            if let Some(remaining) = t.remaining_ms.as_mut() {
+                *remaining = remaining.saturating_sub(elapsed_ms);
+            }
+        }
+        let before = self.visible.len();
+        self.visible.retain(|t| t.remaining_ms != Some(0));
+        if self.visible.len() != before {
+            self.promote();
+        }
+    }
+
+    fn show(&mut self, toast: Toast) {
+        self.visible.push(ActiveToast {
+            remaining_ms: toast.duration_ms,
+            paused: false,
+            toast,
+        });
+    }
+
+    fn promote(&mut self) {
+        while self.visible.len() < self.max_visible {
+            match self.pending.pop_front() {
+                Some(toast) => self.show(toast),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Provide a shared toast queue to descendants
+pub fn provide_toasts(max_visible: usize) -> RwSignal<ToastQueue> {
+    let queue = RwSignal::new(ToastQueue::new(max_visible));
+    provide_context(queue);
+    queue
+}
+
+/// Access the toast queue provided by [`provide_toasts`]
+pub fn use_toasts() -> RwSignal<ToastQueue> {
+    expect_context::<RwSignal<ToastQueue>>()
+}
+
+/// Live region rendering the visible toasts
+#[component]
+pub fn ToastRegion(
+    /// Queue to render; defaults to the one from [`provide_toasts`]
+    #[prop(optional)]
+    queue: Option<RwSignal<ToastQueue>>,
+    /// Called with the toast ID when its action button is pressed; the toast
+    /// is dismissed afterwards
+    #[prop(optional)]
+    on_action: Option<Callback<u64>>,
+) -> impl IntoView {
+    let queue = queue.unwrap_or_else(use_toasts);
+
+    let handle = set_interval_with_handle(
+        move || {
+            if !queue.with_untracked(|q| q.visible().is_empty()) {
+                queue.update(|q| q.tick(TICK_MS));
+            }
+        },
+        std::time::Duration::from_millis(TICK_MS as u64),
+    );
+    on_cleanup(move || {
+        if let Ok(handle) = handle {
+            handle.clear();
+        }
+    });
+
+    view! {
+        <div class=style::toast_region aria-label="Notifications">
+            <For
+                each=move || queue.with(|q| q.visible().to_vec())
+                key=|t| t.toast.id
+                children=move |active| {
+                    let toast = active.toast;
+                    let id = toast.id;
+                    let toast_class = format!("{} {}", style::toast, toast.level.class());
+
+                    view! {
+                        <div
+                            class=toast_class
+                            role=toast.level.role()
+                            aria-live=toast.level.aria_live()
+                            aria-atomic="true"
+                            on:mouseenter=move |_| queue.update(|q| q.pause(id))
+                            on:mouseleave=move |_| queue.update(|q| q.resume(id))
+                            on:focusin=move |_| queue.update(|q| q.pause(id))
+                            on:focusout=move |_| queue.update(|q| q.resume(id))
+                        >
+                            <div class=style::toast_body>
+                                <div class=style::toast_title>{toast.title}</div>
+                                {toast.message.map(|m| view! { <div class=style::toast_message>{m}</div> })}
+                            </div>
+                            {toast.action.map(|label| view! {
+                                <button
+                                    class=style::toast_action
+                                    on:click=move |_| {
+                                        if let Some(cb) = on_action {
+                                            cb.run(id);
+                                        }
+                                        queue.update(|q| q.dismiss(id));
+                                    }
+                                >
+                                    {label}
+                                </button>
+                            })}
+                            <button
+                                class=style::toast_close
+                                aria-label="Dismiss notification"
+                                on:click=move |_| queue.update(|q| q.dismiss(id))
+                            >
+                                "✕"
+                            </button>
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(queue: &ToastQueue) -> Vec<u64> {
+        queue.visible().iter().map(|t| t.toast.id).collect()
+    }
+
+    #[test]
+    fn push_respects_max_visible() {
+        let mut queue = ToastQueue::new(2);
+        let a = queue.push(Toast::new(ToastLevel::Info, "a"));
+        let b = queue.push(Toast::new(ToastLevel::Info, "b"));
+        queue.push(Toast::new(ToastLevel::Info, "c"));
+
+        assert_eq!(ids(&queue), vec![a, b]);
+        assert_eq!(queue.pending_len(), 1);
+    }
+
+    #[test]
+    fn dismiss_promotes_pending() {
+        let mut queue = ToastQueue::new(1);
+        let a = queue.push(Toast::new(ToastLevel::Info, "a"));
+        let b = queue.push(Toast::new(ToastLevel::Info, "b"));
+
+        queue.dismiss(a);
+        assert_eq!(ids(&queue), vec![b]);
+        assert_eq!(queue.pending_len(), 0);
+    }
+
+    #[test]
+    fn tick_expires_and_pause_holds() {
+        let mut queue = ToastQueue::new(3);
+        let a = queue.push(Toast::new(ToastLevel::Info, "a").duration(300));
+        let b = queue.push(Toast::new(ToastLevel::Info, "b").duration(300));
+        let c = queue.push(Toast::new(ToastLevel::Info, "c").sticky());
+
+        queue.pause(b);
+        queue.tick(200);
+        queue.tick(200);
+        assert_eq!(ids(&queue), vec![b, c]);
+
+        queue.resume(b);
+        queue.tick(200);
+        assert_eq!(ids(&queue), vec![b, c]);
+        queue.tick(100);
+        assert_eq!(ids(&queue), vec![c]);
+        assert!(!ids(&queue).contains(&a));
+    }
+
+    #[test]
+    fn assertive_preempts_oldest_polite() {
+        let mut queue = ToastQueue::new(2);
+        let a = queue.push(Toast::new(ToastLevel::Info, "a"));
+        let b = queue.push(Toast::new(ToastLevel::Success, "b"));
+        let err = queue.push(Toast::new(ToastLevel::Error, "boom"));
+
+        assert_eq!(ids(&queue), vec![b, err]);
+        assert_eq!(queue.pending_len(), 1);
+
+        queue.dismiss(err);
+        assert_eq!(ids(&queue), vec![b, a]);
+    }
+
+    #[test]
+    fn assertive_bursts_keep_their_order() {
+        let mut queue = ToastQueue::new(1);
+        let first = queue.push(Toast::new(ToastLevel::Error, "first"));
+        let polite = queue.push(Toast::new(ToastLevel::Info, "polite"));
+        let second = queue.push(Toast::new(ToastLevel::Error, "second"));
+        let third = queue.push(Toast::new(ToastLevel::Error, "third"));
+
+        assert_eq!(ids(&queue), vec![first]);
+        queue.dismiss(first);
+        assert_eq!(ids(&queue), vec![second]);
+        queue.dismiss(second);
+        assert_eq!(ids(&queue), vec![third]);
+        queue.dismiss(third);
+        assert_eq!(ids(&queue), vec![polite]);
+    }
+
+    #[test]
+    fn level_aria_output() {
+        assert_eq!(ToastLevel::Error.role(), "alert");
+        assert_eq!(ToastLevel::Error.aria_live(), "assertive");
+        assert_eq!(ToastLevel::Info.role(), "status");
+        assert_eq!(ToastLevel::Warning.aria_live(), "polite");
+    }
+}
@@ -0,0 +1,79 @@
+//! Help Menu Component
+//!
+//! Header button listing the help topics, when provided, and the tours,
+//! each started with a click. Tours with no steps on the current page are
+//! shown disabled.
+
+use super::Tours;
+use crate::elements::Help;
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/elements/tour/tour.module.css"
+);
+
+/// Help button and its menu of topics and tours
+#[component]
+pub fn HelpMenu() -> impl IntoView {
+    let help = use_context::<Help>();
+    let tours = use_context::<Tours>();
+    let open = RwSignal::new(false);
+
+    view! {
+        <div class=style::help_menu>
+            <button
+                class=style::help_trigger
+                aria-label="Help"
+                aria-haspopup="menu"
+                aria-expanded=move || open.get().to_string()
+                on:click=move |_| open.update(|o| *o = !*o)
+            >
+                "?"
+            </button>
+            <Show when=move || open.get()>
+                <ul class=style::help_list role="menu" aria-label="Help">
+                    {help.map(|help| view! {
+                        <li role="none">
+                            <button
+                                class=style::help_item
+                                role="menuitem"
+                                on:click=move |_| {
+                                    open.set(false);
+                                    help.show_index();
+                                }
+                            >
+                                <span>"Help topics"</span>
+                            </button>
+                        </li>
+                    })}
+                    {tours.map(|tours| tours.tours().into_iter().map(move |tour| {
+                        let id = StoredValue::new(tour.id.clone());
+                        let available = move || id.with_value(|id| tours.has_steps(id));
+                        let done = move || id.with_value(|id| tours.is_completed(id));
+                        view! {
+                            <li role="none">
+                                <button
+                                    class=style::help_item
+                                    role="menuitem"
+                                    disabled=move || !available()
+                                    title=move || (!available()).then_some("Not on this page")
+                                    on:click=move |_| {
+                                        open.set(false);
+                                        id.with_value(|id| tours.start(id));
+                                    }
+                                >
+                                    <span>{tour.title}</span>
+                                    {move || done().then(|| view! {
+                                        <span class=style::help_done aria-label="Completed">"✔"</span>
+                                    })}
+                                </button>
+                            </li>
+                        }
+                    }).collect_view())}
+                </ul>
+            </Show>
+        </div>
+    }
+}
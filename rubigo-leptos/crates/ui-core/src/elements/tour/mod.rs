@@ -0,0 +1,428 @@
+//! Tour Component
+//!
+//! Guided product tours: a spotlight walks the user through parts of the
+//! page, one step at a time.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ui_core::elements::{provide_tours, use_tours, Tour, TourAnchor, TourOverlay};
+//!
+//! // Once, near the app root, with the tours this user has already seen
+//! let tours = provide_tours(
+//!     vec![Tour::new("getting-started", "Getting started").auto_start()],
+//!     completed,
+//! );
+//!
+//! view! { <TourOverlay /> }
+//!
+//! // Steps are declared on the elements they point at
+//! view! {
+//!     <TourAnchor tour="getting-started" order=1 title="Dashboard" body="Your figures at a glance">
+//!         <DashboardGrid feed=feed />
+//!     </TourAnchor>
+//! }
+//!
+//! // Anywhere below the root, e.g. from a menu
+//! use_tours().start("getting-started");
+//! ```
+//!
+//! # Behaviour
+//!
+//! - A tour's steps are the anchors mounted when it runs, in `order`; steps
+//!   on other pages are not shown
+//! - Finishing or skipping a tour marks it completed; the host app keeps
+//!   [`Tours::completed`] per user and hands it back with [`Tours::restore`]
+//! - Auto-start tours run by themselves the first time their steps are on
+//!   screen, until completed
+//! - The header's Help menu lists the tours once they are provided
+
+mod help_menu;
+mod tour_anchor;
+mod tour_overlay;
+
+pub use help_menu::HelpMenu;
+pub use tour_anchor::TourAnchor;
+pub use tour_overlay::TourOverlay;
+
+use std::collections::BTreeSet;
+
+use leptos::prelude::*;
+
+/// Attribute marking the element a step points at, set by [`TourAnchor`]
+pub const TOUR_ATTRIBUTE: &str = "data-tour-step";
+
+/// A tour offered in the Help menu
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tour {
+    /// Identifier the steps refer to
+    pub id: String,
+    /// Name shown in the Help menu
+    pub title: String,
+    /// Start by itself the first time its steps are on screen
+    pub auto_start: bool,
+}
+
+impl Tour {
+    /// Create a tour started from the Help menu
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            auto_start: false,
+        }
+    }
+
+    /// Start the tour by itself until the user has completed it
+    pub fn auto_start(mut self) -> Self {
+        self.auto_start = true;
+        self
+    }
+}
+
+/// One step of a tour, declared by a [`TourAnchor`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TourStep {
+    /// Tour the step belongs to
+    pub tour: String,
+    /// Position within the tour; lower comes first
+    pub order: u32,
+    pub title: String,
+    pub body: String,
+}
+
+/// The step on screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentStep {
+    /// Anchor key, the value of its [`TOUR_ATTRIBUTE`]
+    pub key: u64,
+    pub step: TourStep,
+    /// Zero-based position among the tour's mounted steps
+    pub index: usize,
+    pub total: usize,
+}
+
+impl CurrentStep {
+    pub fn is_first(&self) -> bool {
+        self.index == 0
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.index + 1 == self.total
+    }
+
+    /// Progress label, e.g. "2 of 5"
+    pub fn progress(&self) -> String {
+        format!("{} of {}", self.index + 1, self.total)
+    }
+}
+
+/// State machine for tours: the mounted steps, the running tour and the
+/// tours completed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TourEngine {
+    steps: Vec<(u64, TourStep)>,
+    next_key: u64,
+    /// Running tour and the index of its step on screen
+    active: Option<(String, usize)>,
+    completed: BTreeSet<String>,
+}
+
+impl TourEngine {
+    /// Create an engine knowing which tours are already completed
+    pub fn new(completed: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            completed: completed.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Add a mounted step, returning its anchor key
+    pub fn register(&mut self, step: TourStep) -> u64 {
+        self.next_key += 1;
+        self.steps.push((self.next_key, step));
+        self.next_key
+    }
+
+    /// Remove an unmounted step
+    ///
+    /// A running tour left without steps ends, without counting as
+    /// completed.
+    pub fn unregister(&mut self, key: u64) {
+        self.steps.retain(|(k, _)| *k != key);
+        if let Some((tour, _)) = &self.active {
+            if !self.has_steps(tour) {
+                self.active = None;
+            }
+        }
+    }
+
+    /// Mounted steps of `tour`, in order
+    pub fn steps_of(&self, tour: &str) -> Vec<(u64, &TourStep)> {
+        let mut steps: Vec<_> = self
+            .steps
+            .iter()
+            .filter(|(_, step)| step.tour == tour)
+            .map(|(key, step)| (*key, step))
+            .collect();
+        steps.sort_by_key(|(key, step)| (step.order, *key));
+        steps
+    }
+
+    /// Whether any step of `tour` is mounted
+    pub fn has_steps(&self, tour: &str) -> bool {
+        self.steps.iter().any(|(_, step)| step.tour == tour)
+    }
+
+    /// Start `tour` from its first step; false when none is mounted
+    pub fn start(&mut self, tour: &str) -> bool {
+        if !self.has_steps(tour) {
+            return false;
+        }
+        self.active = Some((tour.to_string(), 0));
+        true
+    }
+
+    /// The step on screen, if a tour is running
+    ///
+    /// The index is clamped to the steps still mounted, so a tour keeps
+    /// going when an earlier anchor goes away.
+    pub fn current(&self) -> Option<CurrentStep> {
+        let (tour, index) = self.active.as_ref()?;
+        let steps = self.steps_of(tour);
+        let index = (*index).min(steps.len().checked_sub(1)?);
+        let (key, step) = steps[index];
+        Some(CurrentStep {
+            key,
+            step: step.clone(),
+            index,
+            total: steps.len(),
+        })
+    }
+
+    /// Running tour
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_ref().map(|(tour, _)| tour.as_str())
+    }
+
+    /// Move to the next step; past the last one the tour is completed
+    pub fn next(&mut self) {
+        let Some(current) = self.current() else {
+            return;
+        };
+        if current.is_last() {
+            self.skip();
+        } else if let Some((_, index)) = &mut self.active {
+            *index = current.index + 1;
+        }
+    }
+
+    /// Move to the previous step
+    pub fn back(&mut self) {
+        let Some(current) = self.current() else {
+            return;
+        };
+        if let Some((_, index)) = &mut self.active {
+            *index = current.index.saturating_sub(1);
+        }
+    }
+
+    /// End the running tour, marking it completed so it isn't offered
+    /// again by itself
+    pub fn skip(&mut self) {
+        if let Some((tour, _)) = self.active.take() {
+            self.completed.insert(tour);
+        }
+    }
+
+    pub fn is_completed(&self, tour: &str) -> bool {
+        self.completed.contains(tour)
+    }
+
+    /// Completed tour IDs, sorted
+    pub fn completed(&self) -> Vec<String> {
+        self.completed.iter().cloned().collect()
+    }
+
+    /// Replace the completed tours, e.g. for another user; stops any tour
+    pub fn restore(&mut self, completed: impl IntoIterator<Item = String>) {
+        self.completed = completed.into_iter().collect();
+        self.active = None;
+    }
+
+    /// First of `tours` to start by itself: not completed and with steps
+    /// mounted, while no tour runs
+    pub fn due<'a>(&self, tours: &'a [Tour]) -> Option<&'a Tour> {
+        if self.active.is_some() {
+            return None;
+        }
+        tours
+            .iter()
+            .find(|t| t.auto_start && !self.is_completed(&t.id) && self.has_steps(&t.id))
+    }
+}
+
+/// Shared tour state, see [`provide_tours`]
+#[derive(Clone, Copy)]
+pub struct Tours {
+    engine: RwSignal<TourEngine>,
+    tours: StoredValue<Vec<Tour>>,
+}
+
+impl Tours {
+    /// The tours offered in the Help menu
+    pub fn tours(&self) -> Vec<Tour> {
+        self.tours.get_value()
+    }
+
+    /// Start a tour, e.g. from a menu; false when none of its steps is on
+    /// this page
+    pub fn start(&self, tour: &str) -> bool {
+        let mut started = false;
+        self.engine.update(|e| started = e.start(tour));
+        started
+    }
+
+    pub fn next(&self) {
+        self.engine.update(TourEngine::next);
+    }
+
+    pub fn back(&self) {
+        self.engine.update(TourEngine::back);
+    }
+
+    pub fn skip(&self) {
+        self.engine.update(TourEngine::skip);
+    }
+
+    /// The step on screen
+    pub fn current(&self) -> Signal<Option<CurrentStep>> {
+        let engine = self.engine;
+        Signal::derive(move || engine.with(TourEngine::current))
+    }
+
+    /// Whether `tour` can start on this page
+    pub fn has_steps(&self, tour: &str) -> bool {
+        self.engine.with(|e| e.has_steps(tour))
+    }
+
+    pub fn is_completed(&self, tour: &str) -> bool {
+        self.engine.with(|e| e.is_completed(tour))
+    }
+
+    /// Completed tour IDs, for the host app to keep per user
+    pub fn completed(&self) -> Signal<Vec<String>> {
+        let engine = self.engine;
+        Signal::derive(move || engine.with(TourEngine::completed))
+    }
+
+    /// Load the completed tours of another user
+    pub fn restore(&self, completed: Vec<String>) {
+        self.engine.update(|e| e.restore(completed));
+    }
+
+    pub(crate) fn register(&self, step: TourStep) -> u64 {
+        let mut key = 0;
+        self.engine.update(|e| key = e.register(step));
+        key
+    }
+
+    pub(crate) fn unregister(&self, key: u64) {
+        self.engine.update(|e| e.unregister(key));
+    }
+}
+
+/// Provide tours below the app root
+///
+/// `completed` lists the tours the user has already finished or skipped;
+/// auto-start tours among the rest start once their steps mount.
+pub fn provide_tours(tours: Vec<Tour>, completed: Vec<String>) -> Tours {
+    let tours = Tours {
+        engine: RwSignal::new(TourEngine::new(completed)),
+        tours: StoredValue::new(tours),
+    };
+    provide_context(tours);
+
+    Effect::new(move |_| {
+        let due = tours.engine.with(|e| {
+            tours
+                .tours
+                .with_value(|all| e.due(all).map(|t| t.id.clone()))
+        });
+        if let Some(id) = due {
+            tours.start(&id);
+        }
+    });
+    tours
+}
+
+/// Access the tours provided by [`provide_tours`]
+pub fn use_tours() -> Tours {
+    expect_context::<Tours>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(tour: &str, order: u32) -> TourStep {
+        TourStep {
+            tour: tour.to_string(),
+            order,
+            title: format!("{tour} {order}"),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn steps_run_in_order_and_finishing_completes_the_tour() {
+        let mut engine = TourEngine::default();
+        let second = engine.register(step("intro", 2));
+        let first = engine.register(step("intro", 1));
+        engine.register(step("calendar", 1));
+        assert!(!engine.start("reports"));
+        assert!(engine.start("intro"));
+
+        let current = engine.current().unwrap();
+        assert_eq!(
+            (current.key, current.progress()),
+            (first, "1 of 2".to_string())
+        );
+        engine.back();
+        assert!(engine.current().unwrap().is_first());
+        engine.next();
+        assert_eq!(engine.current().unwrap().key, second);
+        assert!(engine.current().unwrap().is_last());
+
+        engine.next();
+        assert_eq!(engine.current(), None);
+        assert!(engine.is_completed("intro"));
+        assert_eq!(engine.completed(), vec!["intro".to_string()]);
+    }
+
+    #[test]
+    fn auto_start_tours_wait_for_their_steps_until_completed() {
+        let tours = vec![
+            Tour::new("reports", "Reports"),
+            Tour::new("intro", "Getting started").auto_start(),
+        ];
+        let mut engine = TourEngine::new(Vec::new());
+        assert_eq!(engine.due(&tours), None);
+
+        let key = engine.register(step("intro", 1));
+        assert_eq!(engine.due(&tours).map(|t| t.id.as_str()), Some("intro"));
+        engine.start("intro");
+        assert_eq!(engine.due(&tours), None);
+
+        // Navigating away ends the tour without completing it
+        engine.unregister(key);
+        assert_eq!(engine.active(), None);
+        engine.register(step("intro", 1));
+        engine.start("intro");
+        engine.skip();
+        assert_eq!(engine.due(&tours), None);
+
+        engine.restore(Vec::new());
+        assert!(engine.due(&tours).is_some());
+    }
+}
@@ -0,0 +1,49 @@
+//! Tour Anchor Component
+//!
+//! Declares a tour step on the content it wraps. The step is part of its
+//! tour while the anchor is mounted.
+
+use super::{use_tours, TourStep};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/elements/tour/tour.module.css"
+);
+
+/// Wraps content the spotlight can point at
+#[component]
+pub fn TourAnchor(
+    /// Tour the step belongs to
+    #[prop(into)]
+    tour: String,
+    /// Position within the tour; lower comes first
+    order: u32,
+    /// Step heading
+    #[prop(into)]
+    title: String,
+    /// Step text
+    #[prop(into)]
+    body: String,
+    /// Wrap in a `span` for inline content
+    #[prop(optional)]
+    inline: bool,
+    children: Children,
+) -> impl IntoView {
+    let tours = use_tours();
+    let key = tours.register(TourStep {
+        tour,
+        order,
+        title,
+        body,
+    });
+    on_cleanup(move || tours.unregister(key));
+
+    let key = key.to_string();
+    if inline {
+        view! { <span class=style::anchor_inline data-tour-step=key>{children()}</span> }.into_any()
+    } else {
+        view! { <div class=style::anchor data-tour-step=key>{children()}</div> }.into_any()
+    }
+}
@@ -0,0 +1,171 @@
+//! Tour Overlay Component
+//!
+//! Dims the page around the step's anchor and shows the step in a card
+//! next to it, with Back, Next and Skip. The spotlight follows the anchor
+//! as the window scrolls or resizes. Arrow keys move between steps and
+//! Escape skips the tour.
+
+use super::{use_tours, CurrentStep, Tours, TOUR_ATTRIBUTE};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/elements/tour/tour.module.css"
+);
+
+/// Space kept around the anchor inside the spotlight, in pixels
+const SPOTLIGHT_PADDING: f64 = 8.0;
+
+/// Height the card needs below the anchor before it goes above instead
+const CARD_ROOM: f64 = 220.0;
+
+/// Where an anchor sits in the viewport
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Spot {
+    top: f64,
+    left: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Viewport position of the anchor for `step`, scrolled into view first
+/// when `scroll` is set
+fn locate(step: &CurrentStep, scroll: bool) -> Option<Spot> {
+    let selector = format!("[{}=\"{}\"]", TOUR_ATTRIBUTE, step.key);
+    let element = document().query_selector(&selector).ok().flatten()?;
+    if scroll {
+        element.scroll_into_view();
+    }
+    let rect = element.get_bounding_client_rect();
+    Some(Spot {
+        top: rect.top() - SPOTLIGHT_PADDING,
+        left: rect.left() - SPOTLIGHT_PADDING,
+        width: rect.width() + SPOTLIGHT_PADDING * 2.0,
+        height: rect.height() + SPOTLIGHT_PADDING * 2.0,
+    })
+}
+
+/// Card position: below the spotlight when there is room, else above;
+/// centred without one
+fn card_style(spot: Option<Spot>) -> String {
+    let Some(spot) = spot else {
+        return "top: 50%; left: 50%; transform: translate(-50%, -50%);".to_string();
+    };
+    let viewport = window()
+        .inner_height()
+        .ok()
+        .and_then(|h| h.as_f64())
+        .unwrap_or_default();
+    let left = spot.left.max(16.0);
+    if spot.top + spot.height + CARD_ROOM <= viewport {
+        format!(
+            "top: {}px; left: {}px;",
+            spot.top + spot.height + 12.0,
+            left
+        )
+    } else {
+        format!(
+            "top: {}px; left: {}px; transform: translateY(-100%);",
+            (spot.top - 12.0).max(16.0),
+            left
+        )
+    }
+}
+
+/// Spotlight and step card for the running tour
+#[component]
+pub fn TourOverlay(
+    /// Tours to show; defaults to the ones from [`provide_tours`](super::provide_tours)
+    #[prop(optional)]
+    tours: Option<Tours>,
+) -> impl IntoView {
+    let tours = tours.unwrap_or_else(use_tours);
+    let current = tours.current();
+    let spot = RwSignal::new(None::<Spot>);
+
+    // Measure once the anchor is laid out, bringing it into view
+    Effect::new(move |_| match current.get() {
+        Some(step) => request_animation_frame(move || spot.set(locate(&step, true))),
+        None => spot.set(None),
+    });
+
+    let follow = move || {
+        if let Some(step) = current.get_untracked() {
+            spot.set(locate(&step, false));
+        }
+    };
+    let resize = window_event_listener(leptos::ev::resize, move |_| follow());
+    let scroll = window_event_listener(leptos::ev::scroll, move |_| follow());
+    let keys = window_event_listener(leptos::ev::keydown, move |ev| {
+        if current.with_untracked(Option::is_none) {
+            return;
+        }
+        match ev.key().as_str() {
+            "Escape" => tours.skip(),
+            "ArrowRight" => tours.next(),
+            "ArrowLeft" => tours.back(),
+            _ => {}
+        }
+    });
+    on_cleanup(move || {
+        resize.remove();
+        scroll.remove();
+        keys.remove();
+    });
+
+    move || {
+        current.get().map(|step| {
+            let spotlight = move || match spot.get() {
+                Some(s) => format!(
+                    "top: {}px; left: {}px; width: {}px; height: {}px;",
+                    s.top, s.left, s.width, s.height
+                ),
+                None => "display: none;".to_string(),
+            };
+            let backdrop_class = move || {
+                if spot.get().is_some() {
+                    style::backdrop.to_string()
+                } else {
+                    format!("{} {}", style::backdrop, style::backdrop_dim)
+                }
+            };
+            let (first, last, progress) = (step.is_first(), step.is_last(), step.progress());
+
+            view! {
+                <div class=backdrop_class></div>
+                <div class=style::spotlight style=spotlight aria-hidden="true"></div>
+                <div
+                    class=style::card
+                    style=move || card_style(spot.get())
+                    role="dialog"
+                    aria-modal="true"
+                    aria-labelledby="tour-step-title"
+                >
+                    <div class=style::card_header>
+                        <h3 id="tour-step-title" class=style::card_title>{step.step.title}</h3>
+                        <span class=style::card_progress>{progress}</span>
+                    </div>
+                    <p class=style::card_body>{step.step.body}</p>
+                    <div class=style::card_actions>
+                        <button class=style::link_button on:click=move |_| tours.skip()>
+                            "Skip tour"
+                        </button>
+                        <div class=style::card_nav>
+                            <button
+                                class=style::secondary
+                                disabled=first
+                                on:click=move |_| tours.back()
+                            >
+                                "Back"
+                            </button>
+                            <button class=style::primary on:click=move |_| tours.next()>
+                                {if last { "Done" } else { "Next" }}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+}
@@ -0,0 +1,75 @@
+//! Activity Timeline Component
+//!
+//! Body of a detail panel's "Activity" tab: a comment box above the
+//! record's feed, newest entry first.
+
+use super::{kind_icon, when_label, ActivityFeed};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/activity/activity.module.css"
+);
+
+/// Comment box and chronological feed of one record
+#[component]
+pub fn ActivityTimeline(
+    /// The record's feed
+    feed: ActivityFeed,
+) -> impl IntoView {
+    let draft = RwSignal::new(String::new());
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let body = draft.get_untracked();
+        if body.trim().is_empty() {
+            return;
+        }
+        feed.on_comment.run(body);
+        draft.set(String::new());
+    };
+
+    view! {
+        <div class=style::activity>
+            <form class=style::comment_form on:submit=on_submit>
+                <textarea
+                    rows="2"
+                    placeholder="Add a comment"
+                    aria-label="Comment"
+                    prop:value=move || draft.get()
+                    on:input=move |ev| draft.set(event_target_value(&ev))
+                ></textarea>
+                <button type="submit" disabled=move || draft.with(|d| d.trim().is_empty())>
+                    "Comment"
+                </button>
+            </form>
+
+            {move || match feed.items.get() {
+                None => view! { <p class=style::activity_empty>"Loading activity…"</p> }.into_any(),
+                Some(items) if items.is_empty() => {
+                    view! { <p class=style::activity_empty>"No activity yet"</p> }.into_any()
+                }
+                Some(items) => view! {
+                    <ol class=style::timeline aria-live="polite">
+                        {items.into_iter().map(|item| view! {
+                            <li class=style::entry>
+                                <span class=style::entry_icon aria-hidden="true">{kind_icon(&item.kind)}</span>
+                                <div class=style::entry_body>
+                                    <span class=style::entry_title>{item.title}</span>
+                                    {item.detail.map(|detail| view! {
+                                        <p class=style::entry_detail>{detail}</p>
+                                    })}
+                                    <time class=style::entry_time datetime=item.at.clone()>
+                                        {when_label(&item.at)}
+                                    </time>
+                                </div>
+                            </li>
+                        }).collect_view()}
+                    </ol>
+                }
+                .into_any(),
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,114 @@
+//! Activity Module
+//!
+//! Chronological feed of everything that happened to one record: comments,
+//! audit entries, asset lifecycle and maintenance, and simulation runs that
+//! mention it. Detail panels show it in an "Activity" tab, and the comments
+//! alone in a "Comments" tab; the host app loads the feed (e.g. through
+//! `ActivityAction::Feed`) and posts comments.
+
+mod activity_timeline;
+
+pub use actions::{ActivityData, ActivityQuery};
+pub use activity_timeline::ActivityTimeline;
+
+use crate::elements::{DetailTab, TabItem};
+use leptos::prelude::*;
+
+/// Tab id of a detail panel's activity feed, next to `"details"`
+pub const ACTIVITY_TAB: &str = "activity";
+
+/// One record's feed, supplied by the host app
+#[derive(Clone, Copy)]
+pub struct ActivityFeed {
+    /// Newest first; `None` while loading
+    pub items: Signal<Option<Vec<ActivityData>>>,
+    /// Called with the text of a new comment
+    pub on_comment: Callback<String>,
+}
+
+/// "Details" and "Activity" tabs for a detail panel
+pub fn panel_tabs() -> Vec<TabItem> {
+    vec![
+        TabItem::new("details", "Details"),
+        TabItem::new(ACTIVITY_TAB, "Activity"),
+    ]
+}
+
+/// "Activity" tab of a [`DetailPanel`](crate::elements::DetailPanel),
+/// loading the feed of `query` when first opened
+pub fn activity_tab(
+    load: Callback<ActivityQuery, ActivityFeed>,
+    query: ActivityQuery,
+) -> DetailTab {
+    DetailTab::activity(move || view! { <ActivityTimeline feed=load.run(query.clone()) /> })
+}
+
+/// "Comments" tab of a [`DetailPanel`](crate::elements::DetailPanel): the
+/// feed of `query` without anything but comments
+pub fn comments_tab(
+    load: Callback<ActivityQuery, ActivityFeed>,
+    query: ActivityQuery,
+) -> DetailTab {
+    DetailTab::comments(move || {
+        let feed = load.run(query.clone());
+        let comments = ActivityFeed {
+            items: Signal::derive(move || feed.items.get().map(only_comments)),
+            on_comment: feed.on_comment,
+        };
+        view! { <ActivityTimeline feed=comments /> }
+    })
+}
+
+/// The comments among `items`
+pub fn only_comments(items: Vec<ActivityData>) -> Vec<ActivityData> {
+    items
+        .into_iter()
+        .filter(|item| item.kind == "comment")
+        .collect()
+}
+
+/// Icon for an entry kind
+pub fn kind_icon(kind: &str) -> &'static str {
+    match kind {
+        "comment" => "💬",
+        "audit" => "📝",
+        "lifecycle" => "🔄",
+        "maintenance" => "🔧",
+        "simulation" => "▶",
+        _ => "•",
+    }
+}
+
+/// Timestamp as shown in the feed, e.g. "2025-01-03 10:00"
+///
+/// Date-only entries are shown as they are.
+pub fn when_label(at: &str) -> String {
+    let at = at.trim_end_matches(" UTC").replacen('T', " ", 1);
+    at.chars().take(16).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_are_shortened_to_the_minute() {
+        assert_eq!(when_label("2025-01-03T10:00:42Z"), "2025-01-03 10:00");
+        assert_eq!(when_label("2025-01-03 10:00:42 UTC"), "2025-01-03 10:00");
+        assert_eq!(when_label("2025-01-03"), "2025-01-03");
+        assert_eq!(kind_icon("unknown"), "•");
+    }
+
+    #[test]
+    fn comments_tab_keeps_only_comments() {
+        let entry = |kind: &str| ActivityData {
+            kind: kind.to_string(),
+            title: kind.to_string(),
+            detail: None,
+            actor: None,
+            at: "2025-01-03".to_string(),
+        };
+        let items = vec![entry("audit"), entry("comment"), entry("lifecycle")];
+        assert_eq!(only_comments(items), vec![entry("comment")]);
+    }
+}
@@ -0,0 +1,246 @@
+//! Asset Inventory Component
+//!
+//! One row per asset with its location, a rack and unit to move it to, and
+//! a Delete button. Clicking an asset's name opens its details in a
+//! [`DetailPanel`], where "Edit" moves it too.
+
+use super::{location_label, parse_move, AssetData, AssetInventoryFeed};
+use crate::elements::data_table::badge_variant;
+use crate::elements::{DetailHeader, DetailPanel, DetailSection, DetailTab, PanelSize, SlidePanel};
+use crate::features::activity::{activity_tab, comments_tab, ActivityFeed, ActivityQuery};
+use crate::features::presence::entity_key;
+use crate::primitives::{Badge, BadgeSize};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/assets/assets.module.css"
+);
+
+/// Every asset, with controls to move or delete it
+#[component]
+pub fn AssetInventory(
+    /// The assets and change actions
+    feed: AssetInventoryFeed,
+    /// Loads an asset's activity feed; the details panel gets "Activity"
+    /// and "Comments" tabs when set
+    #[prop(optional)]
+    activity: Option<Callback<ActivityQuery, ActivityFeed>>,
+) -> impl IntoView {
+    let selected_id = RwSignal::new(Option::<String>::None);
+    let show_details = RwSignal::new(false);
+    let panel_tab = RwSignal::new("overview".to_string());
+    let open = Callback::new(move |id: String| {
+        selected_id.set(Some(id));
+        show_details.set(true);
+    });
+    // Looked up again on every change, so a moved asset shows where it went
+    let selected = Signal::derive(move || {
+        let id = selected_id.get()?;
+        feed.assets
+            .with(|assets| assets.as_ref()?.iter().find(|a| a.id == id).cloned())
+    });
+
+    view! {
+        <div class=style::inventory>
+            {move || feed.error.get().map(|error| view! {
+                <p class=style::error role="alert">{error}</p>
+            })}
+
+            {move || match feed.assets.get() {
+                None => view! { <p class=style::empty>"Loading assets…"</p> }.into_any(),
+                Some(assets) if assets.is_empty() => {
+                    view! { <p class=style::empty>"No assets"</p> }.into_any()
+                }
+                Some(assets) => view! {
+                    <table class=style::assets>
+                        <thead>
+                            <tr>
+                                <th>"Name"</th>
+                                <th>"Model"</th>
+                                <th>"Status"</th>
+                                <th>"Location"</th>
+                                <th>"Move to"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {assets.into_iter().map(|asset| asset_row(asset, feed, open)).collect_view()}
+                        </tbody>
+                    </table>
+                }
+                .into_any(),
+            }}
+
+            <SlidePanel open=show_details title="Asset Details" size=PanelSize::Medium>
+                {move || selected.get().map(|asset| asset_details(asset, feed, activity, panel_tab))}
+            </SlidePanel>
+        </div>
+    }
+}
+
+/// Details panel of one asset; editing moves it
+fn asset_details(
+    asset: AssetData,
+    feed: AssetInventoryFeed,
+    activity: Option<Callback<ActivityQuery, ActivityFeed>>,
+    panel_tab: RwSignal<String>,
+) -> impl IntoView {
+    let rack = RwSignal::new(asset.rack_id.clone().unwrap_or_default());
+    let unit = RwSignal::new(asset.position_u.map(|u| u.to_string()).unwrap_or_default());
+    let target = Memo::new(move |_| parse_move(&rack.get(), &unit.get()));
+
+    let header = DetailHeader::new(asset.name.clone()).subtitle(
+        format!("{} {}", asset.manufacturer, asset.model)
+            .trim()
+            .to_string(),
+    );
+    let header = match asset.status.as_str() {
+        "" => header,
+        status => header.status(status),
+    };
+    let query = ActivityQuery {
+        entity: entity_key("asset", &asset.id),
+        label: Some(asset.name.clone()),
+    };
+    let save = Callback::new({
+        let asset = asset.clone();
+        move |_: ()| {
+            if let Some(target) = target.get_untracked() {
+                feed.on_move.run((asset.clone(), target));
+            }
+        }
+    });
+    let name = asset.name.clone();
+    let mut tabs = vec![
+        DetailTab::overview(move || overview(&asset)).editable(move || {
+            view! {
+                <DetailSection title="Move to">
+                    <div class=style::move_to>
+                        <input
+                            type="text"
+                            placeholder="Rack"
+                            aria-label=format!("Rack for {name}")
+                            prop:value=move || rack.get()
+                            on:input=move |ev| rack.set(event_target_value(&ev))
+                        />
+                        <input
+                            type="text"
+                            class=style::unit
+                            placeholder="U"
+                            aria-label=format!("Rack unit for {name}")
+                            aria-invalid=move || target.with(Option::is_none).to_string()
+                            prop:value=move || unit.get()
+                            on:input=move |ev| unit.set(event_target_value(&ev))
+                        />
+                    </div>
+                </DetailSection>
+            }
+        }),
+    ];
+    if let Some(load) = activity {
+        tabs.push(activity_tab(load, query.clone()));
+        tabs.push(comments_tab(load, query));
+    }
+
+    view! { <DetailPanel header=header tabs=tabs active_tab=panel_tab on_save=save /> }
+}
+
+/// Overview tab of an asset's details
+fn overview(asset: &AssetData) -> impl IntoView {
+    let field = |label: &'static str, value: &str| {
+        (!value.is_empty()).then(|| {
+            let value = value.to_string();
+            view! { <p><strong>{label}": "</strong>{value}</p> }
+        })
+    };
+
+    view! {
+        <DetailSection title="Hardware">
+            {field("Category", &asset.category)}
+            {field("Serial number", &asset.serial_number)}
+            {field("Lifecycle", &asset.lifecycle)}
+            {field("Warranty ends", asset.warranty_end.as_deref().unwrap_or_default())}
+        </DetailSection>
+        <DetailSection title="Location">
+            <p>{location_label(asset.rack_id.as_deref(), asset.position_u)}</p>
+        </DetailSection>
+    }
+}
+
+/// One asset; the move fields start at its current location
+fn asset_row(asset: AssetData, feed: AssetInventoryFeed, open: Callback<String>) -> impl IntoView {
+    let rack = RwSignal::new(asset.rack_id.clone().unwrap_or_default());
+    let unit = RwSignal::new(asset.position_u.map(|u| u.to_string()).unwrap_or_default());
+    let target = Memo::new(move |_| parse_move(&rack.get(), &unit.get()));
+    let location = location_label(asset.rack_id.as_deref(), asset.position_u);
+    let model = format!("{} {}", asset.manufacturer, asset.model);
+    let status = asset.status.clone();
+    let name = asset.name.clone();
+    let id = asset.id.clone();
+    let asset = StoredValue::new(asset);
+    let unchanged = move || {
+        target.with(|t| match t {
+            Some(t) => asset.with_value(|a| a.rack_id == t.rack_id && a.position_u == t.position_u),
+            None => true,
+        })
+    };
+    let move_asset = move |_| {
+        if let Some(target) = target.get_untracked() {
+            feed.on_move.run((asset.get_value(), target));
+        }
+    };
+
+    view! {
+        <tr>
+            <td>
+                <button
+                    type="button"
+                    class=style::name
+                    title="Show details"
+                    on:click=move |_| open.run(id.clone())
+                >
+                    {name.clone()}
+                </button>
+            </td>
+            <td>{model.trim().to_string()}</td>
+            <td>
+                {(!status.is_empty()).then(|| view! {
+                    <Badge variant=badge_variant(&status) size=BadgeSize::Small>{status.clone()}</Badge>
+                })}
+            </td>
+            <td>{location}</td>
+            <td class=style::move_to>
+                <input
+                    type="text"
+                    placeholder="Rack"
+                    aria-label=format!("Rack for {name}")
+                    prop:value=move || rack.get()
+                    on:input=move |ev| rack.set(event_target_value(&ev))
+                />
+                <input
+                    type="text"
+                    class=style::unit
+                    placeholder="U"
+                    aria-label=format!("Rack unit for {name}")
+                    aria-invalid=move || target.with(Option::is_none).to_string()
+                    prop:value=move || unit.get()
+                    on:input=move |ev| unit.set(event_target_value(&ev))
+                />
+                <button type="button" disabled=unchanged on:click=move_asset>
+                    "Move"
+                </button>
+            </td>
+            <td>
+                <button
+                    type="button"
+                    class=style::delete
+                    on:click=move |_| feed.on_delete.run(asset.get_value())
+                >
+                    "Delete"
+                </button>
+            </td>
+        </tr>
+    }
+}
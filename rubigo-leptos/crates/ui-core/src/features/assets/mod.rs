@@ -0,0 +1,88 @@
+//! Assets Module
+//!
+//! Inventory of the network's assets, where each can be moved to another
+//! rack position or deleted. The host app loads the assets and sends the
+//! changes, e.g. through `AssetAction`s; deletions stay restorable on the
+//! server for a short window, so the host can offer to undo them (see
+//! [`use_undo`](crate::hooks::use_undo)), and a move is undone by moving
+//! the asset back.
+
+mod asset_inventory;
+
+pub use actions::{AssetData, MoveAssetData};
+pub use asset_inventory::AssetInventory;
+
+use leptos::prelude::*;
+
+/// Highest rack unit a position can name
+pub const MAX_RACK_UNIT: u8 = 52;
+
+/// Assets and the changes made to them, supplied by the host app
+#[derive(Clone, Copy)]
+pub struct AssetInventoryFeed {
+    /// `None` while loading
+    pub assets: Signal<Option<Vec<AssetData>>>,
+    /// Called with the asset to delete
+    pub on_delete: Callback<AssetData>,
+    /// Called with the asset and where to move it
+    pub on_move: Callback<(AssetData, MoveAssetData)>,
+    /// Why the last change was refused, if it was
+    pub error: Signal<Option<String>>,
+}
+
+/// Where an asset is, e.g. "R-01 · U12"
+pub fn location_label(rack_id: Option<&str>, position_u: Option<u8>) -> String {
+    match (rack_id, position_u) {
+        (Some(rack), Some(u)) => format!("{rack} · U{u}"),
+        (Some(rack), None) => rack.to_string(),
+        (None, _) => "Not racked".to_string(),
+    }
+}
+
+/// The move typed into a row: a blank rack unracks the asset
+///
+/// `None` if the unit isn't a rack unit.
+pub fn parse_move(rack: &str, unit: &str) -> Option<MoveAssetData> {
+    let rack = rack.trim();
+    if rack.is_empty() {
+        return Some(MoveAssetData {
+            rack_id: None,
+            position_u: None,
+        });
+    }
+    let unit = unit.trim().trim_start_matches(['U', 'u']);
+    let position_u = match unit {
+        "" => None,
+        unit => Some(
+            unit.parse::<u8>()
+                .ok()
+                .filter(|u| (1..=MAX_RACK_UNIT).contains(u))?,
+        ),
+    };
+    Some(MoveAssetData {
+        rack_id: Some(rack.to_string()),
+        position_u,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_are_read_from_the_row() {
+        assert_eq!(
+            parse_move(" R-01 ", "U12"),
+            Some(MoveAssetData {
+                rack_id: Some("R-01".to_string()),
+                position_u: Some(12),
+            })
+        );
+        assert_eq!(parse_move("R-01", "").and_then(|m| m.position_u), None);
+        assert_eq!(parse_move("", "12").and_then(|m| m.rack_id), None);
+        assert_eq!(parse_move("R-01", "0"), None);
+        assert_eq!(parse_move("R-01", "top"), None);
+        assert_eq!(location_label(Some("R-01"), Some(12)), "R-01 · U12");
+        assert_eq!(location_label(None, Some(12)), "Not racked");
+    }
+}
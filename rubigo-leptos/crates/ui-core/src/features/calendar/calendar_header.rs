@@ -1,10 +1,13 @@
 //! Calendar Header Component
 //!
-//! Navigation controls, view toggle, and work week toggle.
+//! Navigation controls, view toggle, and work week toggle. The month view
+//! also offers a printed copy.
 
 use chrono::{DateTime, Datelike, Duration, Utc};
 use leptos::prelude::*;
 
+use crate::elements::PrintButton;
+
 stylance::import_crate_style!(
     #[allow(dead_code)]
     style,
@@ -175,6 +178,10 @@ pub fn CalendarHeader(
         style::view_btn.to_string()
     };
 
+    // The grid's own title names the month on paper
+    let print_button =
+        (view == CalendarView::Month).then(|| view! { <PrintButton title="Calendar" /> });
+
     view! {
         <div class=style::calendar_header>
             <div class=style::title_group>
@@ -187,6 +194,7 @@ pub fn CalendarHeader(
             </div>
 
             <div class=style::controls>
+                {print_button}
                 <label class=style::work_week_toggle>
                     <input
                         type="checkbox"
@@ -12,10 +12,16 @@ use super::day_view::DayView;
 use super::event_modal::EventModal;
 use super::month_view::MonthView;
 use super::week_view::WeekView;
-use crate::elements::SlidePanel;
+use crate::elements::{SlidePanel, Tabs};
+use crate::features::activity::{panel_tabs, ActivityFeed, ActivityQuery, ActivityTimeline, ACTIVITY_TAB};
+use crate::features::collab::CollabFeed;
+use crate::features::presence::entity_key;
+use crate::features::reminders::{lead_label, use_reminders, LEAD_CHOICES};
 use crate::primitives::{
     get_browser_timezone, timezone_display_name, timezone_offset_minutes, Button, ButtonVariant,
+    Select, SelectOption, SelectSize,
 };
+use crate::utils::{clipboard, ShareLink};
 
 stylance::import_crate_style!(
     #[allow(dead_code)]
@@ -64,9 +70,18 @@ pub fn CalendarPage(
     /// Available people for organizer/participant selection
     #[prop(default = vec![])]
     available_people: Vec<crate::primitives::PersonOption>,
+    /// Opens the shared edits of an event, by entity key, while its edit
+    /// form is open
+    #[prop(default = None)]
+    collab: Option<Callback<String, CollabFeed>>,
+    /// Loads an event's activity feed; the details panel gets an
+    /// "Activity" tab when set
+    #[prop(default = None)]
+    activity: Option<Callback<ActivityQuery, ActivityFeed>>,
 ) -> impl IntoView {
     let query = use_query_map();
     let navigate = use_navigate();
+    let reminders = use_reminders();
     let now = Utc::now();
 
     // Convert to signal for reactivity (new events can be added)
@@ -100,6 +115,8 @@ pub fn CalendarPage(
         .get("week")
         .and_then(|w| NaiveDate::parse_from_str(w.as_str(), "%Y-%m-%d").ok())
         .unwrap_or_else(|| get_week_start(today));
+    // A shared link may open an event's details
+    let initial_event = query.get().get("event").filter(|id| !id.is_empty());
 
     // Reactive state
     let current_year = RwSignal::new(initial_year);
@@ -107,8 +124,9 @@ pub fn CalendarPage(
     let view_mode = RwSignal::new(initial_view);
     let work_week = RwSignal::new(initial_work_week);
     let week_start = RwSignal::new(initial_week_start);
-    let selected_event_id = RwSignal::new(Option::<String>::None);
-    let panel_open = RwSignal::new(false);
+    let panel_open = RwSignal::new(initial_event.is_some());
+    let selected_event_id = RwSignal::new(initial_event);
+    let panel_tab = RwSignal::new("details".to_string());
     let show_event_modal = RwSignal::new(false);
     let editing_event: RwSignal<Option<CalendarEvent>> = RwSignal::new(None);
     // Delete confirmation state
@@ -272,6 +290,13 @@ pub fn CalendarPage(
         editing_event.set(None); // Clear editing state
     });
 
+    // Leave the shared edit session once the form is closed
+    Effect::new(move |_| {
+        if !show_event_modal.get() && editing_event.get_untracked().is_some() {
+            editing_event.set(None);
+        }
+    });
+
     // Find selected event for details panel
     let selected_event = Memo::new(move |_| {
         selected_event_id
@@ -365,11 +390,22 @@ pub fn CalendarPage(
             <SlidePanel
                 open=panel_open
                 title="Event Details".to_string()
+                help_key="calendar#event-details"
             >
                 {move || {
                     if let Some(event) = selected_event.get() {
+                        let feed = activity.map(|open| {
+                            open.run(ActivityQuery {
+                                entity: entity_key("event", &event.id),
+                                label: Some(event.title.clone()),
+                            })
+                        });
                         view! {
-                            <div class=style::event_details>
+                            {feed.map(|_| view! { <Tabs items=panel_tabs() active_tab=panel_tab /> })}
+                            <div
+                                class=style::event_details
+                                hidden=move || feed.is_some() && panel_tab.get() == ACTIVITY_TAB
+                            >
                                 // Title
                                 <h2 class=style::event_title>{event.title.clone()}</h2>
 
@@ -494,6 +530,41 @@ pub fn CalendarPage(
                                     }
                                 })}
 
+                                // Reminder lead time (when the app schedules reminders)
+                                {reminders.map(|reminders| {
+                                    let event_id = event.id.clone();
+                                    let lead = RwSignal::new(
+                                        reminders
+                                            .lead_for(&event_id)
+                                            .map_or("none".to_string(), |m| m.to_string()),
+                                    );
+                                    let options: Vec<SelectOption> = std::iter::once(None)
+                                        .chain(LEAD_CHOICES.into_iter().map(Some))
+                                        .map(|m| {
+                                            let value = m.map_or("none".to_string(), |m| m.to_string());
+                                            SelectOption::new(value, lead_label(m))
+                                        })
+                                        .collect();
+                                    let on_change = Callback::new(move |value: String| {
+                                        reminders.request_permission();
+                                        reminders.set_lead(&event_id, value.parse().ok());
+                                    });
+                                    view! {
+                                        <>
+                                            <div class=style::detail_divider></div>
+                                            <div class=style::detail_row>
+                                                <span class=style::detail_label>"Reminder"</span>
+                                                <Select
+                                                    value=lead
+                                                    options=options
+                                                    size=SelectSize::Small
+                                                    on_change=on_change
+                                                />
+                                            </div>
+                                        </>
+                                    }
+                                })}
+
                                 // Location (if present)
                                 {event.location.as_ref().map(|loc| {
                                     view! {
@@ -582,6 +653,17 @@ pub fn CalendarPage(
                                             </Button>
                                         }
                                     }
+                                    {
+                                        let link = ShareLink::current().param("event", &event.id).url();
+                                        let handle_copy = Callback::new(move |_: web_sys::MouseEvent| {
+                                            clipboard::copy_text(link.clone(), "link");
+                                        });
+                                        view! {
+                                            <Button variant=ButtonVariant::Secondary on_click=handle_copy>
+                                                "Copy Link"
+                                            </Button>
+                                        }
+                                    }
                                     {
                                         let event_is_recurring = event.is_recurring();
                                         let event_id_for_delete = event.id.clone();
@@ -609,6 +691,11 @@ pub fn CalendarPage(
                                     }
                                 </div>
                             </div>
+                            {feed.map(|feed| view! {
+                                <div hidden=move || panel_tab.get() != ACTIVITY_TAB>
+                                    <ActivityTimeline feed=feed />
+                                </div>
+                            })}
                         }.into_any()
                     } else {
                         view! { <p>"No event selected"</p> }.into_any()
@@ -620,14 +707,18 @@ pub fn CalendarPage(
             {move || {
                 let people = available_people.clone();
                 match editing_event.get() {
-                    Some(evt) => view! {
-                        <EventModal
-                            open=show_event_modal
-                            event=evt
-                            available_people=people
-                            on_save=on_event_save
-                        />
-                    }.into_any(),
+                    Some(evt) => {
+                        let feed = collab.map(|open| open.run(entity_key("event", &evt.id)));
+                        view! {
+                            <EventModal
+                                open=show_event_modal
+                                event=evt
+                                available_people=people
+                                on_save=on_event_save
+                                collab=feed
+                            />
+                        }.into_any()
+                    }
                     None => view! {
                         <EventModal
                             open=show_event_modal
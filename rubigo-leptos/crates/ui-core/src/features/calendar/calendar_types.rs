@@ -3,10 +3,13 @@
 //! Core types for calendar events, recurrence, and display.
 
 use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::worker::WorkerTask;
+
 /// Event type with associated colors
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum EventType {
     #[default]
     Meeting,
@@ -65,7 +68,7 @@ impl EventType {
 }
 
 /// Recurrence frequency
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum RecurrenceFrequency {
     #[default]
     None,
@@ -76,7 +79,7 @@ pub enum RecurrenceFrequency {
 }
 
 /// Minimal person info for display in events
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParticipantInfo {
     pub id: String,
     pub name: String,
@@ -92,7 +95,7 @@ impl ParticipantInfo {
 }
 
 /// Represents a deviation/exception for a specific instance of a recurring event
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct InstanceDeviation {
     /// The original occurrence date this deviation applies to (YYYY-MM-DD format)
     pub original_date: String,
@@ -156,7 +159,7 @@ pub struct InstanceData {
 }
 
 /// A calendar event for display
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CalendarEvent {
     pub id: String,
     pub title: String,
@@ -413,6 +416,40 @@ pub fn week_days(date: NaiveDate, work_week: bool) -> Vec<NaiveDate> {
     }
 }
 
+/// Indices into `events` of those occurring on each of `days`
+pub fn expand_occurrences(events: &[CalendarEvent], days: &[NaiveDate]) -> Vec<Vec<usize>> {
+    days.iter()
+        .map(|day| {
+            events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| event.occurs_on(*day))
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect()
+}
+
+/// Input of [`ExpandRecurrences`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpandRecurrencesInput {
+    pub events: Vec<CalendarEvent>,
+    pub days: Vec<NaiveDate>,
+}
+
+/// [`expand_occurrences`] as a worker task, for views spanning many events
+pub struct ExpandRecurrences;
+
+impl WorkerTask for ExpandRecurrences {
+    const NAME: &'static str = "calendar.expand_recurrences";
+    type Input = ExpandRecurrencesInput;
+    type Output = Vec<Vec<usize>>;
+
+    fn run(input: Self::Input) -> Self::Output {
+        expand_occurrences(&input.events, &input.days)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -572,4 +609,25 @@ mod tests {
         let instance = event.get_instance_data(dec_9);
         assert!(instance.is_none());
     }
+
+    #[test]
+    fn test_expand_occurrences() {
+        let start = Utc.with_ymd_and_hms(2024, 12, 2, 9, 0, 0).unwrap(); // Monday
+        let end = Utc.with_ymd_and_hms(2024, 12, 2, 10, 0, 0).unwrap();
+        let mut weekly = CalendarEvent::new("1", "Weekly Event", start, end);
+        weekly.recurrence = RecurrenceFrequency::Weekly;
+        let once = CalendarEvent::new("2", "One-time Event", start, end);
+
+        let days = week_days(NaiveDate::from_ymd_opt(2024, 12, 9).unwrap(), true);
+        let input = ExpandRecurrencesInput {
+            events: vec![weekly, once],
+            days: days.clone(),
+        };
+        // The input crosses to the worker as JSON
+        let input = serde_json::from_value(serde_json::to_value(input).unwrap()).unwrap();
+        let by_day = ExpandRecurrences::run(input);
+        assert_eq!(by_day.len(), days.len());
+        assert_eq!(by_day[0], vec![0]);
+        assert!(by_day[1..].iter().all(Vec::is_empty));
+    }
 }
@@ -1,11 +1,15 @@
 //! Event Modal Component
 //!
 //! Modal form for creating and editing calendar events.
+//!
+//! Given a [`CollabFeed`], an edit form shares its unsaved changes with
+//! everyone else editing the same event, and flags fields they changed too.
 
 use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
 use leptos::prelude::*;
 
 use crate::elements::Modal;
+use crate::features::collab::{bind_field, CollabFeed, FieldConflict};
 use crate::primitives::{
     get_browser_timezone, timezone_display_name, timezone_offset_minutes, Button, ButtonVariant,
     Checkbox, DateInput, Input, PersonOption, PersonSearch, Select, SelectOption, TimeInput,
@@ -34,6 +38,9 @@ pub fn EventModal(
     /// Callback on save
     #[prop(optional)]
     on_save: Option<Callback<CalendarEvent>>,
+    /// Live edits shared with other editors of the event
+    #[prop(default = None)]
+    collab: Option<CollabFeed>,
 ) -> impl IntoView {
     // Determine if editing
     let is_edit = event.is_some();
@@ -147,6 +154,19 @@ pub fn EventModal(
             .unwrap_or_default(),
     );
 
+    if let Some(feed) = collab {
+        bind_field(feed, "title", title);
+        bind_field(feed, "description", description);
+        bind_field(feed, "start_date", start_date);
+        bind_field(feed, "start_time", start_time);
+        bind_field(feed, "end_date", end_date);
+        bind_field(feed, "end_time", end_time);
+        bind_field(feed, "timezone", timezone);
+        bind_field(feed, "event_type", event_type);
+        bind_field(feed, "recurrence", recurrence);
+        bind_field(feed, "location", location);
+    }
+
     // Reset form when modal opens (for new event creation)
     Effect::new(move |_| {
         if open.get() && !is_edit {
@@ -284,6 +304,7 @@ pub fn EventModal(
                 <div class=style::form_group>
                     <label class=style::form_label>"Title"</label>
                     <Input value=title placeholder="Event title" />
+                    <FieldConflict feed=collab field="title" />
                 </div>
 
                 <div class=style::form_group>
@@ -295,6 +316,7 @@ pub fn EventModal(
                         on:input=move |ev| description.set(event_target_value(&ev))
                         rows="3"
                     ></textarea>
+                    <FieldConflict feed=collab field="description" />
                 </div>
 
                 // All Day options row
@@ -320,17 +342,22 @@ pub fn EventModal(
                                 <DateInput value=start_date label="Start Date".to_string() />
                                 <TimeInput value=start_time label="Start Time".to_string() disabled=is_all_day />
                             </div>
+                            <FieldConflict feed=collab field="start_date" />
+                            <FieldConflict feed=collab field="start_time" />
 
                             <div class=style::form_row>
                                 <DateInput value=end_date label="End Date".to_string() />
                                 <TimeInput value=end_time label="End Time".to_string() disabled=is_all_day />
                             </div>
+                            <FieldConflict feed=collab field="end_date" />
+                            <FieldConflict feed=collab field="end_time" />
                         </>
                     }
                 }}
 
                 <div class=style::form_group>
                     <TimezoneSelect value=timezone label="Timezone".to_string() />
+                    <FieldConflict feed=collab field="timezone" />
                 </div>
 
                 // Local time preview (shows converted time ONLY if timezone differs from user's local)
@@ -408,6 +435,7 @@ pub fn EventModal(
                             options=event_type_options.get_value()
                             label="Event Type".to_string()
                         />
+                        <FieldConflict feed=collab field="event_type" />
                     </div>
                     <div class=style::form_group>
                         <Select
@@ -415,6 +443,7 @@ pub fn EventModal(
                             options=recurrence_options.get_value()
                             label="Recurrence".to_string()
                         />
+                        <FieldConflict feed=collab field="recurrence" />
                     </div>
                 </div>
 
@@ -481,6 +510,7 @@ pub fn EventModal(
                 <div class=style::form_group>
                     <label class=style::form_label>"Location"</label>
                     <Input value=location placeholder="Room or virtual URL" />
+                    <FieldConflict feed=collab field="location" />
                 </div>
 
                 <div class=style::form_group>
@@ -13,7 +13,10 @@ mod week_view;
 
 pub use calendar_header::CalendarHeader;
 pub use calendar_page::CalendarPage;
-pub use calendar_types::{CalendarEvent, EventType, ParticipantInfo, RecurrenceFrequency};
+pub use calendar_types::{
+    expand_occurrences, CalendarEvent, EventType, ExpandRecurrences, ExpandRecurrencesInput,
+    ParticipantInfo, RecurrenceFrequency,
+};
 pub use day_view::DayView;
 pub use event_modal::EventModal;
 pub use month_view::MonthView;
@@ -0,0 +1,66 @@
+//! Change Requests Module
+//!
+//! Review queue for changes to protected records, which the server holds
+//! until an approver approves or rejects them (see
+//! [`actions::approvals`]). The host app loads the requests and sends the
+//! reviews, e.g. through `ChangeRequestAction`s; whoever isn't an approver
+//! gets the server's refusal back as the feed's error.
+
+mod review_queue;
+
+pub use actions::approvals::{STATUS_APPROVED, STATUS_FAILED, STATUS_PENDING, STATUS_REJECTED};
+pub use actions::ChangeRequestData;
+pub use review_queue::ReviewQueue;
+
+use crate::primitives::BadgeVariant;
+use leptos::prelude::*;
+
+/// Change requests and review actions supplied by the host app
+#[derive(Clone, Copy)]
+pub struct ReviewQueueFeed {
+    /// Newest first; `None` while loading
+    pub requests: Signal<Option<Vec<ChangeRequestData>>>,
+    /// Called with the request ID and an optional note
+    pub on_approve: Callback<(String, Option<String>)>,
+    /// Called with the request ID and an optional note
+    pub on_reject: Callback<(String, Option<String>)>,
+    /// Why the last review was refused, if it was
+    pub error: Signal<Option<String>>,
+}
+
+/// What a held action does, e.g. "Update asset" for `asset.update`
+pub fn change_label(action_type: &str) -> String {
+    let Some((namespace, verb)) = action_type.split_once('.') else {
+        return action_type.to_string();
+    };
+    let verb = verb.replace('_', " ");
+    let mut label: String = verb.chars().take(1).flat_map(char::to_uppercase).collect();
+    label.push_str(&verb[verb.chars().next().map_or(0, char::len_utf8)..]);
+    format!("{label} {}", namespace.replace('_', " "))
+}
+
+/// Badge color of a request status
+pub fn status_variant(status: &str) -> BadgeVariant {
+    match status {
+        STATUS_PENDING => BadgeVariant::Warning,
+        STATUS_APPROVED => BadgeVariant::Success,
+        STATUS_FAILED => BadgeVariant::Error,
+        _ => BadgeVariant::Default,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_labelled_by_action() {
+        assert_eq!(change_label("asset.update"), "Update asset");
+        assert_eq!(
+            change_label("custom_field.set_values"),
+            "Set values custom field"
+        );
+        assert_eq!(change_label("unknown"), "unknown");
+        assert_eq!(status_variant(STATUS_PENDING), BadgeVariant::Warning);
+    }
+}
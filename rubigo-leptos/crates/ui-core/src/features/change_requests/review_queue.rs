@@ -0,0 +1,119 @@
+//! Review Queue Component
+//!
+//! Pending change requests with Approve and Reject buttons, above the
+//! requests already reviewed.
+
+use super::{change_label, status_variant, ChangeRequestData, ReviewQueueFeed, STATUS_PENDING};
+use crate::primitives::{Badge, BadgeSize};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/change_requests/change_requests.module.css"
+);
+
+/// Change requests awaiting review, then the reviewed ones
+#[component]
+pub fn ReviewQueue(
+    /// The requests and review actions
+    feed: ReviewQueueFeed,
+) -> impl IntoView {
+    view! {
+        <div class=style::queue>
+            {move || feed.error.get().map(|error| view! {
+                <p class=style::error role="alert">{error}</p>
+            })}
+
+            {move || match feed.requests.get() {
+                None => view! { <p class=style::empty>"Loading change requests…"</p> }.into_any(),
+                Some(requests) => {
+                    let (pending, reviewed): (Vec<_>, Vec<_>) =
+                        requests.into_iter().partition(|r| r.status == STATUS_PENDING);
+                    view! {
+                        <section>
+                            <h2>"Awaiting review"</h2>
+                            {if pending.is_empty() {
+                                view! { <p class=style::empty>"Nothing to review"</p> }.into_any()
+                            } else {
+                                view! {
+                                    <ol class=style::requests>
+                                        {pending.into_iter().map(|r| request_item(r, Some(feed))).collect_view()}
+                                    </ol>
+                                }
+                                .into_any()
+                            }}
+                        </section>
+                        {(!reviewed.is_empty()).then(|| view! {
+                            <section>
+                                <h2>"Reviewed"</h2>
+                                <ol class=style::requests>
+                                    {reviewed.into_iter().map(|r| request_item(r, None)).collect_view()}
+                                </ol>
+                            </section>
+                        })}
+                    }
+                    .into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+/// One request; pending ones get a note box and review buttons
+fn request_item(request: ChangeRequestData, review: Option<ReviewQueueFeed>) -> impl IntoView {
+    let note = RwSignal::new(String::new());
+    let id = StoredValue::new(request.id.clone());
+    let note_text = move || Some(note.get_untracked()).filter(|n| !n.trim().is_empty());
+    let reviewed = request
+        .reviewed_by
+        .clone()
+        .map(|by| match &request.reviewed_at {
+            Some(at) => format!("{} by {by}, {at}", request.status),
+            None => format!("{} by {by}", request.status),
+        });
+
+    view! {
+        <li class=style::request>
+            <div class=style::request_header>
+                <span class=style::request_title>{change_label(&request.action_type)}</span>
+                <Badge variant=status_variant(&request.status) size=BadgeSize::Small>
+                    {request.status.clone()}
+                </Badge>
+            </div>
+            <p class=style::request_meta>
+                {request.entities.join(", ")}" · requested "{request.requested_at.clone()}
+                {request.requested_by.clone().map(|by| format!(" by {by}"))}
+            </p>
+            <pre class=style::payload>{request.payload.clone()}</pre>
+            {reviewed.map(|line| view! { <p class=style::request_meta>{line}</p> })}
+            {request.review_note.clone().map(|n| view! { <p class=style::note>{n}</p> })}
+            {request.error.clone().map(|e| view! { <p class=style::error>{e}</p> })}
+            {review.map(|feed| view! {
+                <div class=style::review>
+                    <input
+                        type="text"
+                        placeholder="Note (optional)"
+                        aria-label="Review note"
+                        prop:value=move || note.get()
+                        on:input=move |ev| note.set(event_target_value(&ev))
+                    />
+                    <button
+                        type="button"
+                        class=style::reject
+                        on:click=move |_| feed.on_reject.run((id.get_value(), note_text()))
+                    >
+                        "Reject"
+                    </button>
+                    <button
+                        type="button"
+                        class=style::approve
+                        on:click=move |_| feed.on_approve.run((id.get_value(), note_text()))
+                    >
+                        "Approve"
+                    </button>
+                </div>
+            })}
+        </li>
+    }
+}
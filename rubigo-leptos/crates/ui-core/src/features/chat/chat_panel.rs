@@ -0,0 +1,145 @@
+//! Chat Panel Component
+//!
+//! Two-pane chat: conversations on the left, the selected thread and a
+//! composer on the right.
+
+use super::{preview, ChatFeed};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/chat/chat.module.css"
+);
+
+/// Chat panel with conversation list, thread and composer
+#[component]
+pub fn ChatPanel(
+    /// Name of the signed-in persona, used to align their messages
+    #[prop(into)]
+    me: String,
+    /// Conversations, messages and callbacks
+    feed: ChatFeed,
+) -> impl IntoView {
+    let draft = RwSignal::new(String::new());
+    let me = StoredValue::new(me);
+
+    let submit = move || {
+        let body = draft.get_untracked();
+        if body.trim().is_empty() || feed.selected.get_untracked().is_none() {
+            return;
+        }
+        feed.on_send.run(body);
+        draft.set(String::new());
+    };
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        submit();
+    };
+
+    let on_keydown = move |ev: web_sys::KeyboardEvent| {
+        // Enter sends, Shift+Enter adds a line
+        if ev.key() == "Enter" && !ev.shift_key() {
+            ev.prevent_default();
+            submit();
+        }
+    };
+
+    let selected_title = move || {
+        let selected = feed.selected.get()?;
+        feed.conversations.with(|list| {
+            list.iter()
+                .find(|c| c.id == selected)
+                .map(|c| c.title.clone())
+        })
+    };
+
+    view! {
+        <div class=style::chat_panel>
+            <aside class=style::conversation_list>
+                <select
+                    class=style::new_chat
+                    aria-label="Start a conversation"
+                    on:change=move |ev| {
+                        let name = event_target_value(&ev);
+                        if !name.is_empty() {
+                            feed.on_start.run(name);
+                        }
+                    }
+                >
+                    <option value="" selected>"New message…"</option>
+                    {move || feed.contacts.get().into_iter().map(|name| view! {
+                        <option value=name.clone()>{name.clone()}</option>
+                    }).collect_view()}
+                </select>
+
+                <ul>
+                    {move || feed.conversations.get().into_iter().map(|conversation| {
+                        let id = conversation.id.clone();
+                        let is_selected = {
+                            let id = id.clone();
+                            move || feed.selected.get().as_deref() == Some(id.as_str())
+                        };
+                        view! {
+                            <li>
+                                <button
+                                    type="button"
+                                    class=style::conversation_item
+                                    class:selected=is_selected
+                                    on:click=move |_| feed.selected.set(Some(id.clone()))
+                                >
+                                    <span class=style::conversation_title>{conversation.title}</span>
+                                    {conversation.last_message.map(|body| view! {
+                                        <span class=style::conversation_preview>{preview(&body)}</span>
+                                    })}
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+            </aside>
+
+            <section class=style::thread>
+                {move || match selected_title() {
+                    Some(title) => view! {
+                        <header class=style::thread_header>{title}</header>
+                        <ol class=style::messages aria-live="polite">
+                            {move || feed.messages.get().into_iter().map(|message| {
+                                let mine = me.with_value(|me| *me == message.sender);
+                                let class = if mine {
+                                    format!("{} {}", style::message, style::message_mine)
+                                } else {
+                                    style::message.to_string()
+                                };
+                                view! {
+                                    <li class=class>
+                                        <span class=style::message_meta>
+                                            {message.sender} " · " {message.sent_at}
+                                        </span>
+                                        <span class=style::message_body>{message.body}</span>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ol>
+                        <form class=style::composer on:submit=on_submit>
+                            <textarea
+                                rows="2"
+                                placeholder="Write a message"
+                                prop:value=move || draft.get()
+                                on:input=move |ev| draft.set(event_target_value(&ev))
+                                on:keydown=on_keydown
+                            ></textarea>
+                            <button type="submit" disabled=move || draft.with(|d| d.trim().is_empty())>
+                                "Send"
+                            </button>
+                        </form>
+                    }.into_any(),
+                    None => view! {
+                        <p class=style::thread_empty>"Select a conversation or start a new one."</p>
+                    }.into_any(),
+                }}
+            </section>
+        </div>
+    }
+}
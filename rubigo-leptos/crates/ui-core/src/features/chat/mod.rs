@@ -0,0 +1,76 @@
+//! Chat Module
+//!
+//! Conversation list and message thread for persona-to-persona chat.
+//! The host app owns the data and transport (e.g. a WebSocket); the panel
+//! renders what it is given and reports selections and sends.
+
+mod chat_panel;
+
+pub use chat_panel::ChatPanel;
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Longest last-message preview shown in the conversation list
+const PREVIEW_CHARS: usize = 60;
+
+/// Conversation as listed in the panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatConversation {
+    pub id: String,
+    pub title: String,
+    pub participants: Vec<String>,
+    pub last_message: Option<String>,
+    pub updated_at: String,
+}
+
+/// Message in a thread
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessageItem {
+    pub id: String,
+    pub conversation_id: String,
+    pub sender: String,
+    pub body: String,
+    pub sent_at: String,
+}
+
+/// Single-line preview of a message, cut at a character boundary
+pub fn preview(body: &str) -> String {
+    let line = body.lines().next().unwrap_or_default();
+    if line.chars().count() > PREVIEW_CHARS || body.lines().nth(1).is_some() {
+        let cut: String = line.chars().take(PREVIEW_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Conversation data and actions supplied by the host app
+#[derive(Clone, Copy)]
+pub struct ChatFeed {
+    /// Most recent first
+    pub conversations: Signal<Vec<ChatConversation>>,
+    /// Selected conversation id
+    pub selected: RwSignal<Option<String>>,
+    /// Messages of the selected conversation, oldest first
+    pub messages: Signal<Vec<ChatMessageItem>>,
+    /// People a new conversation can be started with
+    pub contacts: Signal<Vec<String>>,
+    /// Called with the message body for the selected conversation
+    pub on_send: Callback<String>,
+    /// Called with a contact name to open a direct conversation
+    pub on_start: Callback<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_keeps_first_line() {
+        assert_eq!(preview("On my way"), "On my way");
+        assert_eq!(preview("Agenda:\n1. Switch swap"), "Agenda:…");
+        let long = "a".repeat(PREVIEW_CHARS + 5);
+        assert_eq!(preview(&long).chars().count(), PREVIEW_CHARS + 1);
+    }
+}
@@ -0,0 +1,50 @@
+//! Field Conflict Component
+//!
+//! Notice under a form field when another editor's change collided with
+//! this user's. The latest edit has already been kept; the notice says who
+//! else changed the field so the value can be checked before saving.
+
+use super::CollabFeed;
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/collab/collab.module.css"
+);
+
+/// Per-field conflict indicator; renders nothing without a feed or conflict
+#[component]
+pub fn FieldConflict(
+    /// Shared edits of the record, if the form is collaborative
+    feed: Option<CollabFeed>,
+    /// Field name as bound with `bind_field`
+    field: &'static str,
+) -> impl IntoView {
+    let feed = feed?;
+    let author = move || {
+        feed.session
+            .with(|session| session.conflict(field).map(str::to_string))
+    };
+
+    Some(move || {
+        author().map(|author| {
+            view! {
+                <div class=style::conflict role="status">
+                    <span aria-hidden="true">"⚠"</span>
+                    <span class=style::conflict_text>
+                        {format!("{author} also changed this field")}
+                    </span>
+                    <button
+                        type="button"
+                        class=style::conflict_dismiss
+                        aria-label="Dismiss"
+                        on:click=move |_| feed.on_dismiss.run(field.to_string())
+                    >
+                        "×"
+                    </button>
+                </div>
+            }
+        })
+    })
+}
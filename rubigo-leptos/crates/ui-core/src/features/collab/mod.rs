@@ -0,0 +1,63 @@
+//! Collab Module
+//!
+//! Live co-editing for entity forms. The host app owns the session and its
+//! transport (e.g. the collab WebSocket); a form binds its field signals
+//! with [`bind_field`] and shows a [`FieldConflict`] under each one:
+//!
+//! ```ignore
+//! if let Some(feed) = collab {
+//!     bind_field(feed, "title", title);
+//! }
+//! view! { <Input value=title /> <FieldConflict feed=collab field="title" /> }
+//! ```
+
+mod field_conflict;
+
+pub use actions::collab::{CollabSession, FieldEdit, ServerFrame, COLLAB_PATH};
+pub use field_conflict::FieldConflict;
+
+use leptos::prelude::*;
+
+/// Shared edits of one record, supplied by the host app
+#[derive(Clone, Copy)]
+pub struct CollabFeed {
+    pub session: Signal<CollabSession>,
+    /// Called with (field, value) when the user changes a bound field
+    pub on_edit: Callback<(String, String)>,
+    /// Called with the field whose conflict notice was dismissed
+    pub on_dismiss: Callback<String>,
+}
+
+/// Keep `value` in step with `field` of the shared record
+///
+/// Other editors' values are written into the signal as they arrive, and
+/// local changes are reported through [`CollabFeed::on_edit`]. The initial
+/// value is not reported, so opening a form never overwrites edits already
+/// in progress.
+pub fn bind_field(feed: CollabFeed, field: &'static str, value: RwSignal<String>) {
+    // Remote to local
+    Effect::new(move |_| {
+        let shared = feed
+            .session
+            .with(|session| session.value(field).map(str::to_string));
+        if let Some(shared) = shared {
+            if value.get_untracked() != shared {
+                value.set(shared);
+            }
+        }
+    });
+
+    // Local to remote; values just taken from the session match it and are skipped
+    Effect::watch(
+        move || value.get(),
+        move |current, _, _| {
+            let known = feed
+                .session
+                .with_untracked(|session| session.value(field) == Some(current.as_str()));
+            if !known {
+                feed.on_edit.run((field.to_string(), current.clone()));
+            }
+        },
+        false,
+    );
+}
@@ -0,0 +1,130 @@
+//! Custom Field Editor Component
+//!
+//! Admin list of the custom fields defined for one kind of record, with a
+//! form adding another.
+
+use super::{
+    kind_label, parse_options, CustomFieldAdminFeed, CustomFieldData, FIELD_ENTITY_KINDS,
+    FIELD_KINDS,
+};
+use crate::primitives::{Checkbox, Input, Select, SelectOption};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/custom_fields/custom_fields.module.css"
+);
+
+/// Define and remove the custom fields of a kind of record
+#[component]
+pub fn CustomFieldEditor(
+    /// Fields of the kind being edited
+    feed: CustomFieldAdminFeed,
+) -> impl IntoView {
+    let entity_kind = RwSignal::new(feed.entity_kind.get_untracked());
+    let label = RwSignal::new(String::new());
+    let kind = RwSignal::new(FIELD_KINDS[0].to_string());
+    let options = RwSignal::new(String::new());
+    let required = RwSignal::new(false);
+
+    let kinds_of_record = FIELD_ENTITY_KINDS
+        .iter()
+        .map(|k| SelectOption::new(*k, *k))
+        .collect::<Vec<_>>();
+    let field_kinds = FIELD_KINDS
+        .iter()
+        .map(|k| SelectOption::new(*k, kind_label(k)))
+        .collect::<Vec<_>>();
+
+    let add = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        if label.with_untracked(|l| l.trim().is_empty()) {
+            return;
+        }
+        let field = CustomFieldData {
+            id: String::new(),
+            entity_kind: feed.entity_kind.get_untracked(),
+            key: String::new(),
+            label: label.get_untracked(),
+            kind: kind.get_untracked(),
+            options: parse_options(&options.get_untracked()),
+            required: required.get_untracked(),
+            position: feed.fields.with_untracked(|f| f.len()) as u32,
+        };
+        feed.on_define.run(field);
+        label.set(String::new());
+        options.set(String::new());
+        required.set(false);
+    };
+
+    view! {
+        <div class=style::editor>
+            <Select
+                value=entity_kind
+                options=kinds_of_record
+                label="Record kind"
+                on_change=feed.on_kind
+            />
+
+            {move || feed.error.get().map(|error| view! {
+                <p class=style::error role="alert">{error}</p>
+            })}
+
+            {move || {
+                let fields = feed.fields.get();
+                if fields.is_empty() {
+                    return view! { <p class=style::empty>"No custom fields yet"</p> }.into_any();
+                }
+                view! {
+                    <table class=style::definitions>
+                        <thead>
+                            <tr>
+                                <th>"Label"</th>
+                                <th>"Type"</th>
+                                <th>"Options"</th>
+                                <th>"Required"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {fields.into_iter().map(|field| {
+                                let id = field.id.clone();
+                                view! {
+                                    <tr>
+                                        <td>{field.label}<code>{field.key}</code></td>
+                                        <td>{kind_label(&field.kind)}</td>
+                                        <td>{field.options.join(", ")}</td>
+                                        <td>{if field.required { "Yes" } else { "" }}</td>
+                                        <td>
+                                            <button
+                                                type="button"
+                                                class=style::remove
+                                                on:click=move |_| feed.on_remove.run(id.clone())
+                                            >
+                                                "Remove"
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }
+                .into_any()
+            }}
+
+            <form class=style::add_form on:submit=add>
+                <Input value=label placeholder="Label" />
+                <Select value=kind options=field_kinds />
+                <div hidden=move || kind.get() != "select">
+                    <Input value=options placeholder="Options, comma-separated" />
+                </div>
+                <Checkbox checked=required label="Required" />
+                <button type="submit" class=style::primary disabled=move || label.with(|l| l.trim().is_empty())>
+                    "Add field"
+                </button>
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,103 @@
+//! Custom Field Renderer Component
+//!
+//! Draws whatever custom fields are defined for a record: an input matching
+//! each field's type in a form, or label and value pairs in a detail panel.
+
+use std::collections::BTreeMap;
+
+use super::{display_value, CustomFieldData};
+use crate::primitives::{DateInput, Input, InputType, Select, SelectOption};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/custom_fields/custom_fields.module.css"
+);
+
+/// Inputs or read-only values for a record's custom fields
+///
+/// Edits are written into `values` by field key as they are made; a
+/// cleared field is kept as an empty value so saving clears it too.
+#[component]
+pub fn CustomFieldRenderer(
+    /// Fields to show, in order
+    #[prop(into)]
+    fields: Signal<Vec<CustomFieldData>>,
+    /// Values by field key
+    values: RwSignal<BTreeMap<String, String>>,
+    /// Show values instead of inputs
+    #[prop(default = false)]
+    readonly: bool,
+) -> impl IntoView {
+    move || {
+        let fields = fields.get();
+        if readonly {
+            view! {
+                <dl class=style::values>
+                    {fields.into_iter().map(|field| {
+                        let key = field.key.clone();
+                        view! {
+                            <dt>{field.label}</dt>
+                            <dd>{move || values.with(|v| display_value(v.get(&key)))}</dd>
+                        }
+                    }).collect_view()}
+                </dl>
+            }
+            .into_any()
+        } else {
+            view! {
+                <div class=style::fields>
+                    {fields.into_iter().map(|field| field_input(field, values)).collect_view()}
+                </div>
+            }
+            .into_any()
+        }
+    }
+}
+
+/// Labelled input for one field, bound to its entry in `values`
+fn field_input(
+    field: CustomFieldData,
+    values: RwSignal<BTreeMap<String, String>>,
+) -> impl IntoView {
+    let key = field.key.clone();
+    let value = RwSignal::new(values.with_untracked(|v| v.get(&key).cloned().unwrap_or_default()));
+    let on_change = Callback::new(move |new: String| {
+        values.update(|v| {
+            v.insert(key.clone(), new);
+        });
+    });
+    let label = if field.required {
+        format!("{} *", field.label)
+    } else {
+        field.label.clone()
+    };
+
+    let input = match field.kind.as_str() {
+        "number" => view! {
+            <Input value=value input_type=InputType::Number on_change=on_change />
+        }
+        .into_any(),
+        "date" => view! {
+            <DateInput value=value required=field.required show_today_button=false on_change=on_change />
+        }
+        .into_any(),
+        "select" => {
+            let options = field
+                .options
+                .iter()
+                .map(|o| SelectOption::new(o.clone(), o.clone()))
+                .collect::<Vec<_>>();
+            view! { <Select value=value options=options on_change=on_change /> }.into_any()
+        }
+        _ => view! { <Input value=value on_change=on_change /> }.into_any(),
+    };
+
+    view! {
+        <label class=style::field>
+            <span class=style::field_label>{label}</span>
+            {input}
+        </label>
+    }
+}
@@ -0,0 +1,77 @@
+//! Custom Fields Section Component
+//!
+//! "Custom fields" section of a detail panel: the record's values, with an
+//! Edit button swapping them for inputs until they are saved or cancelled.
+
+use super::{CustomFieldRenderer, CustomFieldsFeed};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/custom_fields/custom_fields.module.css"
+);
+
+/// A record's custom fields; renders nothing while none are defined
+#[component]
+pub fn CustomFieldsSection(
+    /// The record's fields and values
+    feed: CustomFieldsFeed,
+) -> impl IntoView {
+    let editing = RwSignal::new(false);
+    let draft = RwSignal::new(feed.values.get_untracked());
+    let fields = Signal::derive(move || feed.fields.get().unwrap_or_default());
+
+    // Show saved values whenever they change and nothing is being edited
+    Effect::new(move |_| {
+        let values = feed.values.get();
+        if !editing.get_untracked() {
+            draft.set(values);
+        }
+    });
+
+    let edit = move |_| {
+        draft.set(feed.values.get_untracked());
+        editing.set(true);
+    };
+    let cancel = move |_| {
+        draft.set(feed.values.get_untracked());
+        editing.set(false);
+    };
+    let save = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        feed.on_save.run(draft.get_untracked());
+        editing.set(false);
+    };
+
+    move || {
+        if fields.with(|f| f.is_empty()) {
+            return None;
+        }
+        Some(view! {
+            <form class=style::section on:submit=save>
+                <div class=style::section_header>
+                    <h4>"Custom fields"</h4>
+                    {move || if editing.get() {
+                        view! {
+                            <div class=style::section_actions>
+                                <button type="button" on:click=cancel>"Cancel"</button>
+                                <button type="submit" class=style::primary>"Save"</button>
+                            </div>
+                        }.into_any()
+                    } else {
+                        view! {
+                            <button type="button" on:click=edit>"Edit"</button>
+                        }.into_any()
+                    }}
+                </div>
+                {move || feed.error.get().map(|error| view! {
+                    <p class=style::error role="alert">{error}</p>
+                })}
+                {move || view! {
+                    <CustomFieldRenderer fields=fields values=draft readonly=!editing.get() />
+                }}
+            </form>
+        })
+    }
+}
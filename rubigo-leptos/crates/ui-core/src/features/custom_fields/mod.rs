@@ -0,0 +1,92 @@
+//! Custom Fields Module
+//!
+//! Admin-defined extra fields on records (see [`actions::custom_fields`]).
+//! [`CustomFieldRenderer`] draws the inputs for whatever fields are defined;
+//! detail panels show a record's values with a [`CustomFieldsSection`], and
+//! admins define the fields with a [`CustomFieldEditor`]. The host app loads
+//! and saves both, e.g. through `CustomFieldAction`s.
+
+mod custom_field_editor;
+mod custom_field_renderer;
+mod custom_fields_section;
+
+pub use actions::custom_fields::{FIELD_ENTITY_KINDS, FIELD_KINDS};
+pub use actions::CustomFieldData;
+pub use custom_field_editor::CustomFieldEditor;
+pub use custom_field_renderer::CustomFieldRenderer;
+pub use custom_fields_section::CustomFieldsSection;
+
+use std::collections::BTreeMap;
+
+use leptos::prelude::*;
+
+/// One record's custom fields, supplied by the host app
+#[derive(Clone, Copy)]
+pub struct CustomFieldsFeed {
+    /// Fields defined for the record's kind, in form order; `None` while loading
+    pub fields: Signal<Option<Vec<CustomFieldData>>>,
+    /// Stored values by field key
+    pub values: Signal<BTreeMap<String, String>>,
+    /// Called with the edited values; cleared fields are empty
+    pub on_save: Callback<BTreeMap<String, String>>,
+    /// Why the last save was refused, if it was
+    pub error: Signal<Option<String>>,
+}
+
+/// Field definitions of one kind of record, supplied by the host app
+#[derive(Clone, Copy)]
+pub struct CustomFieldAdminFeed {
+    /// Kind of record being edited, one of [`FIELD_ENTITY_KINDS`]
+    pub entity_kind: Signal<String>,
+    /// Its fields, in form order
+    pub fields: Signal<Vec<CustomFieldData>>,
+    /// Called with another kind of record to edit
+    pub on_kind: Callback<String>,
+    /// Called with a new field, or a changed one keeping its ID
+    pub on_define: Callback<CustomFieldData>,
+    /// Called with the ID of a field to remove
+    pub on_remove: Callback<String>,
+    /// Why the last change was refused, if it was
+    pub error: Signal<Option<String>>,
+}
+
+/// Name of a field type as shown to admins
+pub fn kind_label(kind: &str) -> &'static str {
+    match kind {
+        "text" => "Text",
+        "number" => "Number",
+        "date" => "Date",
+        "select" => "Select",
+        _ => "Unknown",
+    }
+}
+
+/// Value as shown in a detail panel; unset fields show a dash
+pub fn display_value(value: Option<&String>) -> String {
+    match value.map(|v| v.trim()) {
+        Some(v) if !v.is_empty() => v.to_string(),
+        _ => "—".to_string(),
+    }
+}
+
+/// Options of a select field typed as a comma-separated list
+pub fn parse_options(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(|o| o.trim().to_string())
+        .filter(|o| !o.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_and_values_are_shown_cleanly() {
+        assert_eq!(parse_options(" Gold, Silver ,, "), vec!["Gold", "Silver"]);
+        assert_eq!(display_value(Some(&"  ".to_string())), "—");
+        assert_eq!(display_value(None), "—");
+        assert_eq!(display_value(Some(&"Gold".to_string())), "Gold");
+        assert_eq!(kind_label("date"), "Date");
+    }
+}
@@ -0,0 +1,296 @@
+//! Dashboard Grid Component
+//!
+//! Four-column grid of widget cards. In edit mode cards can be dragged onto
+//! each other to reorder, nudged with the arrow buttons (keyboard friendly),
+//! removed, or added from the registry.
+
+use super::{
+    move_widget, next_widget_id, ChartSeries, DashboardData, DashboardFeed, RecentEvent, SitePoint,
+    WidgetConfig, WidgetKind, WIDGET_REGISTRY,
+};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/dashboard/dashboard.module.css"
+);
+
+/// Dashboard of configurable widgets
+#[component]
+pub fn DashboardGrid(
+    /// Layout, figures and the layout-change callback
+    feed: DashboardFeed,
+) -> impl IntoView {
+    let editing = RwSignal::new(false);
+    // Value of the add-widget picker, reset after each pick
+    let picked = RwSignal::new(String::new());
+    let dragging = RwSignal::new(None::<String>);
+    let drop_target = RwSignal::new(None::<String>);
+
+    // Apply a change to a copy of the layout and hand it to the host
+    let change = move |edit: &dyn Fn(&mut Vec<WidgetConfig>) -> bool| {
+        let mut layout = feed.layout.get_untracked();
+        if edit(&mut layout) {
+            feed.on_layout_change.run(layout);
+        }
+    };
+
+    let drop_on = move |target: String| {
+        let dragged = dragging.get_untracked();
+        dragging.set(None);
+        drop_target.set(None);
+        let Some(dragged) = dragged.filter(|id| *id != target) else {
+            return;
+        };
+        change(&|layout| {
+            let Some(to) = layout.iter().position(|w| w.id == target) else {
+                return false;
+            };
+            move_widget(layout, &dragged, to)
+        });
+    };
+
+    let nudge = move |id: String, step: isize| {
+        change(&|layout| {
+            let Some(from) = layout.iter().position(|w| w.id == id) else {
+                return false;
+            };
+            let to = from
+                .checked_add_signed(step)
+                .filter(|to| *to < layout.len());
+            to.is_some_and(|to| move_widget(layout, &id, to))
+        });
+    };
+
+    let add = move |choice: String| {
+        let (kind, metric) = choice.split_once(':').unwrap_or((choice.as_str(), ""));
+        let Some(kind) = WidgetKind::parse(kind) else {
+            return;
+        };
+        let metric = (!metric.is_empty()).then_some(metric);
+        change(&|layout| {
+            let id = next_widget_id(layout, kind);
+            layout.push(WidgetConfig::new(id, kind, metric));
+            true
+        });
+    };
+
+    let remove = move |id: String| {
+        change(&|layout| {
+            let before = layout.len();
+            layout.retain(|w| w.id != id);
+            layout.len() != before
+        });
+    };
+
+    view! {
+        <section class=style::dashboard>
+            <div class=style::toolbar>
+                {move || editing.get().then(|| view! {
+                    <select
+                        class=style::add_widget
+                        aria-label="Add widget"
+                        on:change=move |ev| {
+                            let choice = event_target_value(&ev);
+                            if !choice.is_empty() {
+                                add(choice);
+                            }
+                            picked.set(String::new());
+                        }
+                        prop:value=move || picked.get()
+                    >
+                        <option value="" selected>"Add widget…"</option>
+                        {WIDGET_REGISTRY.iter().flat_map(|spec| {
+                            let kind = spec.kind.as_str();
+                            if spec.metrics.is_empty() {
+                                vec![(kind.to_string(), format!("{} {}", spec.icon, spec.label))]
+                            } else {
+                                spec.metrics
+                                    .iter()
+                                    .map(|m| (format!("{kind}:{}", m.key), format!("{} {} · {}", spec.icon, spec.label, m.label)))
+                                    .collect()
+                            }
+                        }).map(|(value, label)| view! { <option value=value>{label}</option> }).collect_view()}
+                    </select>
+                })}
+                <button
+                    type="button"
+                    class=style::edit_toggle
+                    aria-pressed=move || editing.get().to_string()
+                    on:click=move |_| editing.update(|e| *e = !*e)
+                >
+                    {move || if editing.get() { "Done" } else { "Customize" }}
+                </button>
+            </div>
+
+            <div class=style::grid class:editing=move || editing.get()>
+                <For
+                    each=move || feed.layout.get()
+                    key=|widget| widget.id.clone()
+                    let:widget
+                >
+                    {
+                        let id = StoredValue::new(widget.id.clone());
+                        let span = format!("grid-column: span {}", widget.kind.spec().columns);
+                        let icon = widget.metric_spec().map(|m| m.icon).unwrap_or(widget.kind.spec().icon);
+                        let title = widget.title();
+                        let is_target = move || {
+                            drop_target.get().as_deref() == Some(id.get_value().as_str())
+                                && dragging.get().as_deref() != Some(id.get_value().as_str())
+                        };
+                        view! {
+                            <article
+                                class=style::widget
+                                class:dragover=is_target
+                                style=span
+                                draggable=move || if editing.get() { "true" } else { "false" }
+                                on:dragstart=move |ev: web_sys::DragEvent| {
+                                    if let Some(transfer) = ev.data_transfer() {
+                                        let _ = transfer.set_data("text/plain", &id.get_value());
+                                    }
+                                    dragging.set(Some(id.get_value()));
+                                }
+                                on:dragover=move |ev: web_sys::DragEvent| {
+                                    if dragging.get_untracked().is_some() {
+                                        ev.prevent_default();
+                                        drop_target.set(Some(id.get_value()));
+                                    }
+                                }
+                                on:drop=move |ev: web_sys::DragEvent| {
+                                    ev.prevent_default();
+                                    drop_on(id.get_value());
+                                }
+                                on:dragend=move |_| {
+                                    dragging.set(None);
+                                    drop_target.set(None);
+                                }
+                            >
+                                <header class=style::widget_header>
+                                    <span class=style::widget_icon aria-hidden="true">{icon}</span>
+                                    <h3>{title}</h3>
+                                    {move || editing.get().then(|| view! {
+                                        <div class=style::widget_controls>
+                                            <button type="button" aria-label=format!("Move {title} earlier") on:click=move |_| nudge(id.get_value(), -1)>"◀"</button>
+                                            <button type="button" aria-label=format!("Move {title} later") on:click=move |_| nudge(id.get_value(), 1)>"▶"</button>
+                                            <button type="button" aria-label=format!("Remove {title}") on:click=move |_| remove(id.get_value())>"✕"</button>
+                                        </div>
+                                    })}
+                                </header>
+                                <div class=style::widget_body>
+                                    {
+                                        let widget = widget.clone();
+                                        move || match feed.data.get() {
+                                            Some(data) => widget_body(&widget, &data),
+                                            None => view! { <p class=style::muted>"Loading…"</p> }.into_any(),
+                                        }
+                                    }
+                                </div>
+                            </article>
+                        }
+                    }
+                </For>
+            </div>
+
+            {move || feed.layout.with(Vec::is_empty).then(|| view! {
+                <p class=style::muted>"No widgets. Use Customize to add some."</p>
+            })}
+        </section>
+    }
+}
+
+fn widget_body(widget: &WidgetConfig, data: &DashboardData) -> AnyView {
+    let metric = widget.metric.as_deref().unwrap_or_default();
+    match widget.kind {
+        WidgetKind::Stat => {
+            let value = data
+                .stat(metric)
+                .map(|s| s.value.to_string())
+                .unwrap_or_else(|| "—".to_string());
+            view! { <span class=style::stat_value>{value}</span> }.into_any()
+        }
+        WidgetKind::Chart => match data.chart(metric) {
+            Some(series) => bar_chart(series),
+            None => view! { <p class=style::muted>"No data"</p> }.into_any(),
+        },
+        WidgetKind::RecentEvents => recent_events(&data.events),
+        WidgetKind::MapSnapshot => map_snapshot(&data.sites),
+    }
+}
+
+fn bar_chart(series: &ChartSeries) -> AnyView {
+    if series.points.is_empty() {
+        return view! { <p class=style::muted>"No data"</p> }.into_any();
+    }
+    let max = series
+        .points
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    view! {
+        <ul class=style::bars aria-label=series.title.clone()>
+            {series.points.iter().map(|(label, count)| {
+                let width = format!("width: {:.1}%", *count as f64 / max as f64 * 100.0);
+                view! {
+                    <li>
+                        <span class=style::bar_label>{label.clone()}</span>
+                        <span class=style::bar_track>
+                            <span class=style::bar style=width></span>
+                        </span>
+                        <span class=style::bar_count>{*count}</span>
+                    </li>
+                }
+            }).collect_view()}
+        </ul>
+    }
+    .into_any()
+}
+
+fn recent_events(events: &[RecentEvent]) -> AnyView {
+    if events.is_empty() {
+        return view! { <p class=style::muted>"No recent events"</p> }.into_any();
+    }
+    view! {
+        <ol class=style::events>
+            {events.iter().map(|event| view! {
+                <li>
+                    <span class=style::event_title>{event.title.clone()}</span>
+                    {event.detail.clone().map(|detail| view! { <span class=style::muted>{detail}</span> })}
+                    <time class=style::event_time datetime=event.at.clone()>{event.at.clone()}</time>
+                </li>
+            }).collect_view()}
+        </ol>
+    }
+    .into_any()
+}
+
+/// Sites on an equirectangular world frame (x = longitude, y = -latitude)
+fn map_snapshot(sites: &[SitePoint]) -> AnyView {
+    let located: Vec<(&str, f64, f64)> = sites
+        .iter()
+        .filter_map(|s| Some((s.name.as_str(), s.lon?, s.lat?)))
+        .collect();
+    let caption = format!("{} of {} sites located", located.len(), sites.len());
+    view! {
+        <figure class=style::map>
+            <svg viewBox="-180 -90 360 180" role="img" aria-label=caption.clone()>
+                <rect x="-180" y="-90" width="360" height="180" class=style::map_sea />
+                {(-150..=150).step_by(30).map(|lon| view! {
+                    <line x1=lon y1="-90" x2=lon y2="90" class=style::map_grid />
+                }).collect_view()}
+                {(-60..=60).step_by(30).map(|lat| view! {
+                    <line x1="-180" y1=lat x2="180" y2=lat class=style::map_grid />
+                }).collect_view()}
+                {located.into_iter().map(|(name, lon, lat)| view! {
+                    <circle cx=lon cy={-lat} r="3" class=style::map_site>
+                        <title>{name.to_string()}</title>
+                    </circle>
+                }).collect_view()}
+            </svg>
+            <figcaption class=style::muted>{caption}</figcaption>
+        </figure>
+    }
+    .into_any()
+}
@@ -0,0 +1,350 @@
+//! Dashboard Module
+//!
+//! Grid of configurable widgets for the home page. The registry below lists
+//! the widget kinds and the figures they can show; the host app supplies a
+//! layout (per user) and the live figures, and is told when the user
+//! rearranges, adds or removes widgets so it can persist the new layout.
+
+mod dashboard_grid;
+
+pub use dashboard_grid::DashboardGrid;
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Kinds of widget a dashboard can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WidgetKind {
+    /// A single count, e.g. number of sites
+    Stat,
+    /// Bar chart of label/count pairs
+    Chart,
+    /// Latest activity, newest first
+    RecentEvents,
+    /// Sites plotted on a world outline
+    MapSnapshot,
+}
+
+impl WidgetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WidgetKind::Stat => "stat",
+            WidgetKind::Chart => "chart",
+            WidgetKind::RecentEvents => "recent_events",
+            WidgetKind::MapSnapshot => "map_snapshot",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        WIDGET_REGISTRY
+            .iter()
+            .map(|spec| spec.kind)
+            .find(|kind| kind.as_str() == s)
+    }
+
+    pub fn spec(&self) -> &'static WidgetSpec {
+        WIDGET_REGISTRY
+            .iter()
+            .find(|spec| spec.kind == *self)
+            .expect("every widget kind is registered")
+    }
+}
+
+/// Registry entry describing a widget kind
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidgetSpec {
+    pub kind: WidgetKind,
+    pub label: &'static str,
+    pub icon: &'static str,
+    /// Grid columns the widget spans (the grid has four)
+    pub columns: u8,
+    /// Figures the widget can be pointed at; empty if it takes none
+    pub metrics: &'static [MetricSpec],
+}
+
+/// A figure a stat or chart widget can show
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricSpec {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub icon: &'static str,
+}
+
+const STAT_METRICS: &[MetricSpec] = &[
+    MetricSpec {
+        key: "sites",
+        label: "Sites",
+        icon: "🌍",
+    },
+    MetricSpec {
+        key: "buildings",
+        label: "Buildings",
+        icon: "🏢",
+    },
+    MetricSpec {
+        key: "spaces",
+        label: "Spaces",
+        icon: "🚪",
+    },
+    MetricSpec {
+        key: "assets",
+        label: "Assets",
+        icon: "🖥️",
+    },
+    MetricSpec {
+        key: "personnel",
+        label: "Personnel",
+        icon: "👥",
+    },
+    MetricSpec {
+        key: "open_maintenance",
+        label: "Open Maintenance",
+        icon: "🔧",
+    },
+];
+
+const CHART_METRICS: &[MetricSpec] = &[
+    MetricSpec {
+        key: "assets_by_status",
+        label: "Assets by Status",
+        icon: "📊",
+    },
+    MetricSpec {
+        key: "assets_by_category",
+        label: "Assets by Category",
+        icon: "📊",
+    },
+    MetricSpec {
+        key: "people_by_department",
+        label: "People by Department",
+        icon: "📊",
+    },
+];
+
+/// All widget kinds, in the order offered when adding a widget
+pub const WIDGET_REGISTRY: &[WidgetSpec] = &[
+    WidgetSpec {
+        kind: WidgetKind::Stat,
+        label: "Stat",
+        icon: "🔢",
+        columns: 1,
+        metrics: STAT_METRICS,
+    },
+    WidgetSpec {
+        kind: WidgetKind::Chart,
+        label: "Chart",
+        icon: "📊",
+        columns: 2,
+        metrics: CHART_METRICS,
+    },
+    WidgetSpec {
+        kind: WidgetKind::RecentEvents,
+        label: "Recent Events",
+        icon: "🕑",
+        columns: 2,
+        metrics: &[],
+    },
+    WidgetSpec {
+        kind: WidgetKind::MapSnapshot,
+        label: "Map Snapshot",
+        icon: "🗺️",
+        columns: 2,
+        metrics: &[],
+    },
+];
+
+/// A widget placed on a dashboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetConfig {
+    /// Unique within the layout
+    pub id: String,
+    pub kind: WidgetKind,
+    /// Metric key for stat and chart widgets
+    #[serde(default)]
+    pub metric: Option<String>,
+}
+
+impl WidgetConfig {
+    pub fn new(id: impl Into<String>, kind: WidgetKind, metric: Option<&str>) -> Self {
+        Self {
+            id: id.into(),
+            kind,
+            metric: metric.map(String::from),
+        }
+    }
+
+    /// Registry entry for the widget's metric, if it has one
+    pub fn metric_spec(&self) -> Option<&'static MetricSpec> {
+        let key = self.metric.as_deref()?;
+        self.kind.spec().metrics.iter().find(|m| m.key == key)
+    }
+
+    /// Heading shown on the widget card
+    pub fn title(&self) -> &'static str {
+        self.metric_spec()
+            .map(|m| m.label)
+            .unwrap_or(self.kind.spec().label)
+    }
+}
+
+/// Layout shown until the user arranges their own
+pub fn default_layout() -> Vec<WidgetConfig> {
+    vec![
+        WidgetConfig::new("stat-sites", WidgetKind::Stat, Some("sites")),
+        WidgetConfig::new("stat-assets", WidgetKind::Stat, Some("assets")),
+        WidgetConfig::new("stat-personnel", WidgetKind::Stat, Some("personnel")),
+        WidgetConfig::new("stat-buildings", WidgetKind::Stat, Some("buildings")),
+        WidgetConfig::new(
+            "chart-assets-by-status",
+            WidgetKind::Chart,
+            Some("assets_by_status"),
+        ),
+        WidgetConfig::new("recent-events", WidgetKind::RecentEvents, None),
+        WidgetConfig::new("map-snapshot", WidgetKind::MapSnapshot, None),
+    ]
+}
+
+/// Move the widget `id` to `to` (an index into the layout without it)
+///
+/// Returns `false` if no widget has that id.
+pub fn move_widget(layout: &mut Vec<WidgetConfig>, id: &str, to: usize) -> bool {
+    let Some(from) = layout.iter().position(|w| w.id == id) else {
+        return false;
+    };
+    let widget = layout.remove(from);
+    layout.insert(to.min(layout.len()), widget);
+    true
+}
+
+/// Unused widget id for a new widget of `kind`
+pub fn next_widget_id(layout: &[WidgetConfig], kind: WidgetKind) -> String {
+    (1..)
+        .map(|n| format!("{}-{}", kind.as_str().replace('_', "-"), n))
+        .find(|id| layout.iter().all(|w| &w.id != id))
+        .expect("unbounded range always yields an id")
+}
+
+/// Count shown by a stat widget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatValue {
+    pub key: String,
+    pub label: String,
+    pub value: u64,
+}
+
+/// Label/count pairs drawn by a chart widget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    pub key: String,
+    pub title: String,
+    pub points: Vec<(String, u64)>,
+}
+
+/// Entry in the recent events widget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentEvent {
+    pub title: String,
+    pub detail: Option<String>,
+    pub at: String,
+}
+
+/// Site marker on the map snapshot; sites without coordinates are counted
+/// but not drawn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SitePoint {
+    pub name: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+}
+
+/// Current figures for every widget
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DashboardData {
+    pub stats: Vec<StatValue>,
+    pub charts: Vec<ChartSeries>,
+    pub events: Vec<RecentEvent>,
+    pub sites: Vec<SitePoint>,
+}
+
+impl DashboardData {
+    pub fn stat(&self, key: &str) -> Option<&StatValue> {
+        self.stats.iter().find(|s| s.key == key)
+    }
+
+    pub fn chart(&self, key: &str) -> Option<&ChartSeries> {
+        self.charts.iter().find(|c| c.key == key)
+    }
+}
+
+/// Layout and figures supplied by the host app
+#[derive(Clone, Copy)]
+pub struct DashboardFeed {
+    pub layout: Signal<Vec<WidgetConfig>>,
+    /// `None` while the figures are loading
+    pub data: Signal<Option<DashboardData>>,
+    /// Called with the whole layout after every rearrange, add or remove
+    pub on_layout_change: Callback<Vec<WidgetConfig>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(layout: &[WidgetConfig]) -> Vec<&str> {
+        layout.iter().map(|w| w.id.as_str()).collect()
+    }
+
+    #[test]
+    fn registry_covers_every_kind() {
+        for kind in [
+            WidgetKind::Stat,
+            WidgetKind::Chart,
+            WidgetKind::RecentEvents,
+            WidgetKind::MapSnapshot,
+        ] {
+            assert_eq!(WidgetKind::parse(kind.as_str()), Some(kind));
+            assert_eq!(kind.spec().kind, kind);
+        }
+        assert_eq!(WidgetKind::parse("clock"), None);
+    }
+
+    #[test]
+    fn default_layout_metrics_are_registered() {
+        for widget in default_layout() {
+            assert_eq!(
+                widget.metric.is_some(),
+                widget.metric_spec().is_some(),
+                "{}",
+                widget.id
+            );
+        }
+        assert_eq!(default_layout()[0].title(), "Sites");
+    }
+
+    #[test]
+    fn widgets_move_within_layout() {
+        let mut layout = default_layout();
+        assert!(move_widget(&mut layout, "map-snapshot", 0));
+        assert_eq!(ids(&layout)[0], "map-snapshot");
+
+        assert!(move_widget(&mut layout, "map-snapshot", 99));
+        assert_eq!(ids(&layout).last(), Some(&"map-snapshot"));
+
+        assert!(!move_widget(&mut layout, "missing", 0));
+        assert_eq!(layout.len(), default_layout().len());
+    }
+
+    #[test]
+    fn new_widget_ids_are_unique() {
+        let layout = vec![
+            WidgetConfig::new("chart-1", WidgetKind::Chart, None),
+            WidgetConfig::new("chart-2", WidgetKind::Chart, None),
+        ];
+        assert_eq!(next_widget_id(&layout, WidgetKind::Chart), "chart-3");
+        assert_eq!(
+            next_widget_id(&layout, WidgetKind::RecentEvents),
+            "recent-events-1"
+        );
+    }
+}
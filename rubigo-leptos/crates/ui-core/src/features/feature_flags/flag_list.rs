@@ -0,0 +1,85 @@
+//! Flag List Component
+//!
+//! One row per flag: name, description, a switch and, when the flag was
+//! toggled away from its default, a reset button.
+
+use super::FlagListFeed;
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/feature_flags/flag_list.module.css"
+);
+
+/// Feature flag toggles for the admin page
+#[component]
+pub fn FlagList(
+    /// Flags and toggle callbacks
+    feed: FlagListFeed,
+) -> impl IntoView {
+    view! {
+        <div class=style::flag_list>
+            <Show
+                when=move || feed.flags.with(|flags| !flags.is_empty())
+                fallback=|| view! { <p class=style::empty>"No feature flags are defined."</p> }
+            >
+                <For
+                    each=move || feed.flags.get()
+                    key=|flag| (flag.name.clone(), flag.enabled, flag.default)
+                    children=move |flag| {
+                        let name = flag.name.clone();
+                        let saving = {
+                            let name = name.clone();
+                            move || feed.saving.with(|s| s.as_deref() == Some(name.as_str()))
+                        };
+                        let on_change = {
+                            let name = name.clone();
+                            move |ev| feed.on_toggle.run((name.clone(), event_target_checked(&ev)))
+                        };
+                        let on_reset = {
+                            let name = name.clone();
+                            move |_| feed.on_reset.run(name.clone())
+                        };
+                        let row_class = if flag.overridden() {
+                            format!("{} {}", style::flag_row, style::overridden)
+                        } else {
+                            style::flag_row.to_string()
+                        };
+                        let default_label = if flag.default { "on" } else { "off" };
+                        view! {
+                            <div class=row_class>
+                                <div class=style::flag_text>
+                                    <code class=style::flag_name>{name.clone()}</code>
+                                    <span class=style::flag_description>{flag.description.clone()}</span>
+                                    <span class=style::flag_default>"Default: " {default_label}</span>
+                                </div>
+                                {flag.overridden().then(|| view! {
+                                    <button
+                                        type="button"
+                                        class=style::reset_button
+                                        disabled=saving.clone()
+                                        on:click=on_reset
+                                    >
+                                        "Reset"
+                                    </button>
+                                })}
+                                <label class=style::switch title=name.clone()>
+                                    <input
+                                        type="checkbox"
+                                        role="switch"
+                                        class=style::switch_input
+                                        prop:checked=flag.enabled
+                                        disabled=saving
+                                        on:change=on_change
+                                    />
+                                    <span class=style::switch_track></span>
+                                </label>
+                            </div>
+                        }
+                    }
+                />
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,25 @@
+//! Feature Flags Module
+//!
+//! Admin list of runtime feature flags with a switch per flag and a way
+//! back to the default. The host app owns the flags and persists toggles;
+//! components read them with [`use_flag`](crate::hooks::use_flag).
+
+mod flag_list;
+
+pub use flag_list::FlagList;
+
+use leptos::prelude::*;
+
+use crate::hooks::FeatureFlag;
+
+/// Flags and toggle actions supplied by the host app
+#[derive(Clone, Copy)]
+pub struct FlagListFeed {
+    pub flags: Signal<Vec<FeatureFlag>>,
+    /// Flag whose change is being saved, if any
+    pub saving: Signal<Option<String>>,
+    /// Called with the flag name and its new state
+    pub on_toggle: Callback<(String, bool)>,
+    /// Called with the flag name to drop its override
+    pub on_reset: Callback<String>,
+}
@@ -0,0 +1,67 @@
+//! Help Module
+//!
+//! The app's help topics, bundled at build time from `ui-core/help/`: one
+//! markdown file per page or component, keyed by file name. Hand them to
+//! [`provide_help`](crate::elements::provide_help) and link to them with a
+//! `help_key`, e.g. `calendar#recurring-events`.
+
+use crate::elements::HelpTopic;
+
+/// Key and markdown of each bundled topic, in index order
+const TOPICS: &[(&str, &str)] = &[
+    (
+        "getting-started",
+        include_str!("../../../help/getting-started.md"),
+    ),
+    ("dashboard", include_str!("../../../help/dashboard.md")),
+    ("calendar", include_str!("../../../help/calendar.md")),
+    ("personnel", include_str!("../../../help/personnel.md")),
+    ("reviews", include_str!("../../../help/reviews.md")),
+    ("training", include_str!("../../../help/training.md")),
+];
+
+/// The bundled help topics
+pub fn bundled_topics() -> Vec<HelpTopic> {
+    TOPICS
+        .iter()
+        .map(|(key, markdown)| HelpTopic::parse(*key, markdown))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::help::markdown::{slug, Block, Inline};
+
+    #[test]
+    fn bundled_links_lead_to_bundled_topics() {
+        let topics = bundled_topics();
+        let has = |key: &str| topics.iter().any(|t| t.key == key);
+        for topic in &topics {
+            assert_ne!(topic.title, topic.key, "{} has no title", topic.key);
+            for block in &topic.blocks {
+                let inlines: Vec<&Inline> = match block {
+                    Block::Paragraph(i) | Block::Note(i) => i.iter().collect(),
+                    Block::List { items, .. } => items.iter().flatten().collect(),
+                    _ => Vec::new(),
+                };
+                for inline in inlines {
+                    if let Inline::Link { href, .. } = inline {
+                        let key = href.strip_prefix("help:").unwrap_or(href);
+                        assert!(has(key), "{} links to missing topic {}", topic.key, key);
+                    }
+                }
+            }
+        }
+
+        // Sections the components deep-link to
+        for help_key in ["personnel#employee-details", "calendar#event-details"] {
+            let (key, section) = help_key.split_once('#').unwrap();
+            let topic = topics.iter().find(|t| t.key == key).unwrap();
+            assert!(topic
+                .blocks
+                .iter()
+                .any(|b| matches!(b, Block::Heading { text, .. } if slug(text) == section)));
+        }
+    }
+}
@@ -0,0 +1,453 @@
+//! Import Wizard Component
+//!
+//! Three steps: choose a CSV file or paste rows, map its columns onto
+//! fields, then review
+//! the validation preview and submit as a dry run or a real import. The
+//! preview is computed locally; the server's report is shown once it
+//! answers.
+
+use super::{
+    auto_map, detect_header, mapped_row, unmapped_required, validate, CsvTable, ImportFeed,
+    ImportRequest, RowError, PREVIEW_ROWS,
+};
+use crate::utils::clipboard;
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/import/import_wizard.module.css"
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Upload,
+    Map,
+    Review,
+}
+
+impl Step {
+    const ALL: [(Step, &'static str); 3] = [
+        (Step::Upload, "Upload"),
+        (Step::Map, "Map columns"),
+        (Step::Review, "Review"),
+    ];
+}
+
+/// CSV import wizard
+#[component]
+pub fn ImportWizard(
+    /// Target fields, last report and the submit callback
+    feed: ImportFeed,
+) -> impl IntoView {
+    let step = RwSignal::new(Step::Upload);
+    let file_name = RwSignal::new(None::<String>);
+    let read_error = RwSignal::new(None::<String>);
+    let csv_text = RwSignal::new(String::new());
+    let has_header = RwSignal::new(true);
+    let mapping = RwSignal::new(Vec::<Option<String>>::new());
+    let dry_run = RwSignal::new(true);
+
+    let table = Memo::new(move |_| csv_text.with(|text| CsvTable::parse(text, has_header.get())));
+    let errors = Memo::new(move |_| {
+        feed.fields
+            .with(|fields| table.with(|t| mapping.with(|m| validate(t, m, fields))))
+    });
+    let missing = Memo::new(move |_| {
+        feed.fields.with(|fields| {
+            mapping.with(|m| {
+                unmapped_required(m, fields)
+                    .into_iter()
+                    .map(|f| f.label.clone())
+                    .collect::<Vec<_>>()
+            })
+        })
+    });
+
+    let remap = move || {
+        let headers = table.with_untracked(|t| t.headers.clone());
+        mapping.set(
+            feed.fields
+                .with_untracked(|fields| auto_map(&headers, fields)),
+        );
+    };
+
+    let load = move |name: String, text: String| {
+        has_header.set(
+            feed.fields
+                .with_untracked(|fields| detect_header(&text, fields)),
+        );
+        csv_text.set(text);
+        file_name.set(Some(name));
+        read_error.set(None);
+        remap();
+        step.set(Step::Map);
+    };
+
+    let on_file = move |ev: ev::Event| {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let input: web_sys::HtmlInputElement = event_target(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let Ok(reader) = web_sys::FileReader::new() else {
+            return;
+        };
+        let name = file.name();
+        let loaded = reader.clone();
+        let on_load = Closure::once_into_js(move || {
+            match loaded.result().ok().and_then(|text| text.as_string()) {
+                Some(text) => load(name, text),
+                None => read_error.set(Some(format!("Could not read {name} as text"))),
+            }
+        });
+        reader.set_onload(Some(on_load.unchecked_ref()));
+        if reader.read_as_text(&file).is_err() {
+            read_error.set(Some("Could not read the selected file".to_string()));
+        }
+    };
+
+    // Rows pasted from a spreadsheet arrive tab-separated
+    let on_paste = move |ev: ev::ClipboardEvent| {
+        if let Some(text) = clipboard::pasted_text(&ev) {
+            ev.prevent_default();
+            load("Pasted rows".to_string(), clipboard::as_csv(&text));
+        }
+    };
+
+    let paste_button = move |_| {
+        leptos::task::spawn_local(async move {
+            match clipboard::read_text().await {
+                Ok(text) if !text.trim().is_empty() => {
+                    load("Pasted rows".to_string(), clipboard::as_csv(&text))
+                }
+                Ok(_) => read_error.set(Some("The clipboard is empty".to_string())),
+                Err(_) => read_error.set(Some(
+                    "The browser didn't allow reading the clipboard; paste with Ctrl+V instead"
+                        .to_string(),
+                )),
+            }
+        });
+    };
+
+    let restart = move |_| {
+        csv_text.set(String::new());
+        file_name.set(None);
+        mapping.set(Vec::new());
+        step.set(Step::Upload);
+    };
+
+    let submit = move |_| {
+        feed.on_submit.run(ImportRequest {
+            csv: csv_text.get_untracked(),
+            has_header: has_header.get_untracked(),
+            mapping: mapping.get_untracked(),
+            dry_run: dry_run.get_untracked(),
+        });
+    };
+
+    let steps = Step::ALL
+        .into_iter()
+        .map(|(s, label)| {
+            view! {
+                <li class=move || {
+                    if step.get() == s { style::step_active } else { style::step }
+                }>{label}</li>
+            }
+        })
+        .collect_view();
+
+    let upload = move || {
+        view! {
+            <div class=style::upload tabindex="0" on:paste=on_paste>
+                <label class=style::drop_zone>
+                    <span class=style::drop_title>"Choose a CSV file"</span>
+                    <span class=style::hint>
+                        "Or paste rows copied from a spreadsheet. The first row may hold column names; you can map columns in the next step."
+                    </span>
+                    <input type="file" accept=".csv,text/csv" on:change=on_file />
+                </label>
+                <div class=style::actions>
+                    <button type="button" class=style::secondary on:click=paste_button>
+                        "Paste rows"
+                    </button>
+                </div>
+                {move || read_error.get().map(|e| view! { <p class=style::error_text>{e}</p> })}
+            </div>
+        }
+    };
+
+    let map_columns = move || {
+        let fields = feed.fields.get();
+        let rows = move || {
+            table.with(|t| {
+                t.headers
+                    .iter()
+                    .enumerate()
+                    .map(|(column, header)| {
+                        let sample = t.cell(0, column).to_string();
+                        let options = fields
+                            .iter()
+                            .map(|f| {
+                                let key = f.key.clone();
+                                let label = if f.required {
+                                    format!("{} *", f.label)
+                                } else {
+                                    f.label.clone()
+                                };
+                                let current = key.clone();
+                                view! {
+                                    <option
+                                        value=key
+                                        selected=move || {
+                                            mapping.with(|m| {
+                                                m.get(column).cloned().flatten().as_deref()
+                                                    == Some(current.as_str())
+                                            })
+                                        }
+                                    >
+                                        {label}
+                                    </option>
+                                }
+                            })
+                            .collect_view();
+                        view! {
+                            <tr>
+                                <td class=style::column_name>{header.clone()}</td>
+                                <td class=style::sample>{sample}</td>
+                                <td>
+                                    <select on:change=move |ev| {
+                                        let key = event_target_value(&ev);
+                                        mapping
+                                            .update(|m| {
+                                                if m.len() <= column {
+                                                    m.resize(column + 1, None);
+                                                }
+                                                m[column] = (!key.is_empty()).then_some(key);
+                                            });
+                                    }>
+                                        <option
+                                            value=""
+                                            selected=move || {
+                                                mapping.with(|m| m.get(column).cloned().flatten().is_none())
+                                            }
+                                        >
+                                            "— Skip —"
+                                        </option>
+                                        {options}
+                                    </select>
+                                </td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()
+            })
+        };
+
+        view! {
+            <div class=style::mapping>
+                <label class=style::checkbox>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || has_header.get()
+                        on:change=move |ev| {
+                            has_header.set(event_target_checked(&ev));
+                            remap();
+                        }
+                    />
+                    "First row is a header"
+                </label>
+                <table class=style::table>
+                    <thead>
+                        <tr>
+                            <th>"Column"</th>
+                            <th>"First value"</th>
+                            <th>"Field"</th>
+                        </tr>
+                    </thead>
+                    <tbody>{rows}</tbody>
+                </table>
+                {move || {
+                    let missing = missing.get();
+                    (!missing.is_empty())
+                        .then(|| {
+                            view! {
+                                <p class=style::error_text>
+                                    "Map a column to: " {missing.join(", ")}
+                                </p>
+                            }
+                        })
+                }}
+                <div class=style::actions>
+                    <button type="button" class=style::secondary on:click=restart>
+                        "Start over"
+                    </button>
+                    <button
+                        type="button"
+                        class=style::primary
+                        disabled=move || !missing.with(Vec::is_empty)
+                        on:click=move |_| step.set(Step::Review)
+                    >
+                        "Next"
+                    </button>
+                </div>
+            </div>
+        }
+    };
+
+    let review = move || {
+        let fields = feed.fields.get();
+        let columns: Vec<(String, String)> = mapping.with(|m| {
+            fields
+                .iter()
+                .filter(|f| m.iter().flatten().any(|key| *key == f.key))
+                .map(|f| (f.key.clone(), f.label.clone()))
+                .collect()
+        });
+        let header = columns
+            .iter()
+            .map(|(_, label)| view! { <th>{label.clone()}</th> })
+            .collect_view();
+        let total = table.with(|t| t.rows.len());
+        let all_errors = errors.get();
+        let failing_rows = rows_with_errors(&all_errors);
+        let preview = (0..total.min(PREVIEW_ROWS))
+            .map(|row| {
+                let values = table.with(|t| mapping.with(|m| mapped_row(t, row, m)));
+                let row_errors: Vec<String> = all_errors
+                    .iter()
+                    .filter(|e| e.row == row + 1)
+                    .map(|e| e.message.clone())
+                    .collect();
+                let cells = columns
+                    .iter()
+                    .map(|(key, _)| {
+                        let value = values
+                            .iter()
+                            .find(|(k, _)| k == key)
+                            .map(|(_, v)| v.clone())
+                            .unwrap_or_default();
+                        let invalid = all_errors
+                            .iter()
+                            .any(|e| e.row == row + 1 && e.field.as_deref() == Some(key.as_str()));
+                        let class = if invalid { style::cell_error } else { "" };
+                        view! { <td class=class>{value}</td> }
+                    })
+                    .collect_view();
+                let class = if row_errors.is_empty() {
+                    ""
+                } else {
+                    style::row_error
+                };
+                view! {
+                    <tr class=class>
+                        <td class=style::row_number>{row + 1}</td>
+                        {cells}
+                        <td class=style::error_text>{row_errors.join("; ")}</td>
+                    </tr>
+                }
+            })
+            .collect_view();
+        let hidden_errors = failing_rows
+            .iter()
+            .filter(|row| **row > PREVIEW_ROWS)
+            .count();
+
+        view! {
+            <div class=style::review>
+                <p class=style::summary>
+                    {format!(
+                        "{} rows, {} valid, {} with errors",
+                        total,
+                        total - failing_rows.len(),
+                        failing_rows.len(),
+                    )}
+                    {(!failing_rows.is_empty()).then_some(" (rows with errors are skipped)")}
+                </p>
+                <table class=style::table>
+                    <thead>
+                        <tr>
+                            <th>"Row"</th>
+                            {header}
+                            <th>"Problems"</th>
+                        </tr>
+                    </thead>
+                    <tbody>{preview}</tbody>
+                </table>
+                {(total > PREVIEW_ROWS)
+                    .then(|| {
+                        view! {
+                            <p class=style::hint>
+                                {format!(
+                                    "Showing the first {} of {} rows; {} more rows have errors.",
+                                    PREVIEW_ROWS,
+                                    total,
+                                    hidden_errors,
+                                )}
+                            </p>
+                        }
+                    })}
+                <label class=style::checkbox>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || dry_run.get()
+                        on:change=move |ev| dry_run.set(event_target_checked(&ev))
+                    />
+                    "Dry run (validate on the server without saving)"
+                </label>
+                <div class=style::actions>
+                    <button type="button" class=style::secondary on:click=move |_| step.set(Step::Map)>
+                        "Back"
+                    </button>
+                    <button
+                        type="button"
+                        class=style::primary
+                        disabled=move || feed.busy.get()
+                        on:click=submit
+                    >
+                        {move || if dry_run.get() { "Run dry run" } else { "Import" }}
+                    </button>
+                </div>
+                {move || feed.report.get().map(|report| {
+                    let errors = report
+                        .errors
+                        .iter()
+                        .map(|e| view! { <li>{format!("Row {}: {}", e.row, e.message)}</li> })
+                        .collect_view();
+                    let outcome = if report.dry_run {
+                        format!("Dry run: {} of {} rows would be imported", report.valid, report.total)
+                    } else {
+                        format!("Imported {} of {} rows", report.imported, report.total)
+                    };
+                    view! {
+                        <div class=style::report>
+                            <strong>{outcome}</strong>
+                            <ul>{errors}</ul>
+                        </div>
+                    }
+                })}
+            </div>
+        }
+    };
+
+    view! {
+        <div class=style::wizard>
+            <ol class=style::steps>{steps}</ol>
+            {move || file_name.get().map(|name| view! { <p class=style::hint>{name}</p> })}
+            {move || match step.get() {
+                Step::Upload => upload().into_any(),
+                Step::Map => map_columns().into_any(),
+                Step::Review => review().into_any(),
+            }}
+        </div>
+    }
+}
+
+/// Distinct 1-based rows that have at least one error
+fn rows_with_errors(errors: &[RowError]) -> Vec<usize> {
+    let mut rows: Vec<usize> = errors.iter().map(|e| e.row).collect();
+    rows.dedup();
+    rows
+}
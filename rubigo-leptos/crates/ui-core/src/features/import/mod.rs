@@ -0,0 +1,407 @@
+//! Import Module
+//!
+//! CSV import wizard: upload a file, confirm whether its first row is a
+//! header, map columns onto the target's fields, review row-level errors,
+//! then run the import as a dry run or for real.
+//!
+//! Parsing, column mapping and validation live here so the server applies
+//! exactly the checks the preview showed. The host app supplies the target's
+//! fields and submits the [`ImportRequest`]; the server answers with an
+//! [`ImportReport`].
+
+mod import_wizard;
+
+pub use import_wizard::ImportWizard;
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Data rows shown in the wizard's preview
+pub const PREVIEW_ROWS: usize = 10;
+
+/// How a field's text is checked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFieldKind {
+    #[default]
+    Text,
+    Number,
+    Integer,
+    Email,
+}
+
+/// A field of the import target that columns can be mapped onto
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportField {
+    pub key: String,
+    pub label: String,
+    pub required: bool,
+    pub kind: ImportFieldKind,
+}
+
+impl ImportField {
+    pub fn new(key: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            label: label.into(),
+            required: false,
+            kind: ImportFieldKind::Text,
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn kind(mut self, kind: ImportFieldKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Problem with `value` for this field, if any
+    pub fn check(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return self.required.then(|| format!("{} is required", self.label));
+        }
+        let valid = match self.kind {
+            ImportFieldKind::Text => true,
+            ImportFieldKind::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            ImportFieldKind::Integer => value.parse::<i64>().is_ok(),
+            ImportFieldKind::Email => value
+                .split_once('@')
+                .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.')),
+        };
+        (!valid).then(|| {
+            let expected = match self.kind {
+                ImportFieldKind::Text => "text",
+                ImportFieldKind::Number => "a number",
+                ImportFieldKind::Integer => "a whole number",
+                ImportFieldKind::Email => "an email address",
+            };
+            format!("{} must be {}, got '{}'", self.label, expected, value)
+        })
+    }
+}
+
+/// Parsed CSV file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvTable {
+    /// Column names; `Column N` when the file has no header row
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// Split `text` into headers and rows
+    pub fn parse(text: &str, has_header: bool) -> Self {
+        let mut records = parse_csv(text);
+        let width = records.iter().map(Vec::len).max().unwrap_or(0);
+        let headers = if has_header && !records.is_empty() {
+            let mut headers = records.remove(0);
+            headers.resize(width, String::new());
+            headers
+                .into_iter()
+                .enumerate()
+                .map(|(i, h)| match h.trim() {
+                    "" => format!("Column {}", i + 1),
+                    name => name.to_string(),
+                })
+                .collect()
+        } else {
+            (1..=width).map(|i| format!("Column {}", i)).collect()
+        };
+        Self {
+            headers,
+            rows: records,
+        }
+    }
+
+    /// Cell `column` of `row`, empty when the row is short
+    pub fn cell(&self, row: usize, column: usize) -> &str {
+        self.rows
+            .get(row)
+            .and_then(|r| r.get(column))
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// RFC 4180 records, with quoted fields, `""` escapes and CRLF or LF line ends
+///
+/// Blank lines are skipped.
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if quoted {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match ch {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// Whether the first record looks like column names
+///
+/// True when every cell is filled, none is a number and the names are
+/// distinct, or when the cells match field keys or labels.
+pub fn detect_header(text: &str, fields: &[ImportField]) -> bool {
+    let records = parse_csv(text);
+    let Some(first) = records.first() else {
+        return false;
+    };
+    if first.iter().any(|cell| field_for(cell, fields).is_some()) {
+        return true;
+    }
+    let mut seen = Vec::new();
+    first.iter().all(|cell| {
+        let cell = cell.trim().to_lowercase();
+        let fresh = !cell.is_empty() && cell.parse::<f64>().is_err() && !seen.contains(&cell);
+        seen.push(cell);
+        fresh
+    })
+}
+
+/// Key of the field whose key or label matches `header`, ignoring case,
+/// spaces and punctuation
+fn field_for<'a>(header: &str, fields: &'a [ImportField]) -> Option<&'a str> {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    let header = normalize(header);
+    if header.is_empty() {
+        return None;
+    }
+    fields
+        .iter()
+        .find(|f| normalize(&f.key) == header || normalize(&f.label) == header)
+        .map(|f| f.key.as_str())
+}
+
+/// Initial mapping: each column to the field of the same name, each field
+/// used at most once
+pub fn auto_map(headers: &[String], fields: &[ImportField]) -> Vec<Option<String>> {
+    let mut used: Vec<&str> = Vec::new();
+    headers
+        .iter()
+        .map(|header| {
+            let key = field_for(header, fields).filter(|key| !used.contains(key))?;
+            used.push(key);
+            Some(key.to_string())
+        })
+        .collect()
+}
+
+/// Required fields no column is mapped onto
+pub fn unmapped_required<'a>(
+    mapping: &[Option<String>],
+    fields: &'a [ImportField],
+) -> Vec<&'a ImportField> {
+    fields
+        .iter()
+        .filter(|f| f.required && !mapping.iter().flatten().any(|key| *key == f.key))
+        .collect()
+}
+
+/// Values of data row `row` keyed by field, per `mapping`
+pub fn mapped_row(
+    table: &CsvTable,
+    row: usize,
+    mapping: &[Option<String>],
+) -> Vec<(String, String)> {
+    mapping
+        .iter()
+        .enumerate()
+        .filter_map(|(column, key)| {
+            let key = key.as_ref()?;
+            Some((key.clone(), table.cell(row, column).trim().to_string()))
+        })
+        .collect()
+}
+
+/// Problem with one cell of an import
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowError {
+    /// 1-based data row, not counting the header
+    pub row: usize,
+    /// Field key; `None` for problems with the row as a whole
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Per-field errors for every data row
+pub fn validate(
+    table: &CsvTable,
+    mapping: &[Option<String>],
+    fields: &[ImportField],
+) -> Vec<RowError> {
+    let mut errors = Vec::new();
+    for row in 0..table.rows.len() {
+        let values = mapped_row(table, row, mapping);
+        for field in fields {
+            let value = values
+                .iter()
+                .find(|(key, _)| *key == field.key)
+                .map(|(_, v)| v.as_str())
+                .unwrap_or_default();
+            if let Some(message) = field.check(value) {
+                errors.push(RowError {
+                    row: row + 1,
+                    field: Some(field.key.clone()),
+                    message,
+                });
+            }
+        }
+    }
+    errors
+}
+
+/// Import submitted to the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportRequest {
+    /// The uploaded file's text
+    pub csv: String,
+    pub has_header: bool,
+    /// Field key per CSV column; `None` skips the column
+    pub mapping: Vec<Option<String>>,
+    /// Validate only, write nothing
+    pub dry_run: bool,
+}
+
+impl ImportRequest {
+    pub fn table(&self) -> CsvTable {
+        CsvTable::parse(&self.csv, self.has_header)
+    }
+}
+
+/// Outcome of an import or dry run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Data rows in the file
+    pub total: usize,
+    /// Rows without errors; imported unless this was a dry run
+    pub valid: usize,
+    /// Rows written
+    pub imported: usize,
+    /// Rows with errors are skipped
+    pub errors: Vec<RowError>,
+}
+
+/// Import target and transport supplied by the host app
+#[derive(Clone, Copy)]
+pub struct ImportFeed {
+    /// Fields of the import target
+    pub fields: Signal<Vec<ImportField>>,
+    /// Result of the last submission
+    pub report: Signal<Option<ImportReport>>,
+    /// True while a submission is in flight
+    pub busy: Signal<bool>,
+    pub on_submit: Callback<ImportRequest>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<ImportField> {
+        vec![
+            ImportField::new("name", "Name").required(),
+            ImportField::new("email", "Email").kind(ImportFieldKind::Email),
+            ImportField::new("latitude", "Latitude").kind(ImportFieldKind::Number),
+        ]
+    }
+
+    #[test]
+    fn parses_quotes_escapes_and_line_endings() {
+        let records =
+            parse_csv("name,notes\r\n\"Smith, Ann\",\"said \"\"hi\"\"\nline two\"\n\nBob,\n");
+        assert_eq!(
+            records,
+            vec![
+                vec!["name", "notes"],
+                vec!["Smith, Ann", "said \"hi\"\nline two"],
+                vec!["Bob", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_header_rows() {
+        assert!(detect_header("Full Name,E-mail\nAnn,a@x.io", &fields()));
+        assert!(detect_header("site,city\nhq,Paris", &[]));
+        assert!(!detect_header("Ann,12.5\nBob,3", &[]));
+        assert!(!detect_header("a,a\nb,c", &[]));
+    }
+
+    #[test]
+    fn auto_map_matches_keys_and_labels_once() {
+        let headers = vec![
+            "NAME".to_string(),
+            "e-mail".to_string(),
+            "Name".to_string(),
+            "x".to_string(),
+        ];
+        assert_eq!(
+            auto_map(&headers, &fields()),
+            vec![
+                Some("name".to_string()),
+                Some("email".to_string()),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn validation_reports_rows_and_fields() {
+        let table = CsvTable::parse("name,email,lat\nAnn,ann@x.io,51.5\n,bob,north\n", true);
+        let mapping = vec![
+            Some("name".to_string()),
+            Some("email".to_string()),
+            Some("latitude".to_string()),
+        ];
+        let errors = validate(&table, &mapping, &fields());
+        let found: Vec<(usize, &str)> = errors
+            .iter()
+            .map(|e| (e.row, e.field.as_deref().unwrap_or_default()))
+            .collect();
+        assert_eq!(found, vec![(2, "name"), (2, "email"), (2, "latitude")]);
+        assert!(unmapped_required(&mapping, &fields()).is_empty());
+        assert_eq!(unmapped_required(&[None], &fields()).len(), 1);
+    }
+}
@@ -3,12 +3,45 @@
 //! Features are composed components that implement specific
 //! domain functionality like Personnel, Assets, Calendar, etc.
 
+pub mod activity;
+pub mod assets;
 pub mod calendar;
+pub mod change_requests;
+pub mod chat;
+pub mod collab;
+pub mod custom_fields;
+pub mod dashboard;
+pub mod feature_flags;
+pub mod help;
+pub mod import;
+pub mod notifications;
 pub mod personnel;
+pub mod presence;
+pub mod reminders;
 pub mod sites;
+pub mod training;
 pub mod user_session;
 
+pub use activity::{ActivityFeed, ActivityTimeline};
+pub use assets::{AssetInventory, AssetInventoryFeed};
 pub use calendar::{CalendarEvent, CalendarHeader, CalendarPage, EventType, MonthView, WeekView};
+pub use change_requests::{ReviewQueue, ReviewQueueFeed};
+pub use chat::{ChatConversation, ChatFeed, ChatMessageItem, ChatPanel};
+pub use collab::{bind_field, CollabFeed, FieldConflict};
+pub use custom_fields::{
+    CustomFieldAdminFeed, CustomFieldEditor, CustomFieldRenderer, CustomFieldsFeed,
+    CustomFieldsSection,
+};
+pub use dashboard::{DashboardData, DashboardFeed, DashboardGrid, WidgetConfig, WidgetKind};
+pub use feature_flags::{FlagList, FlagListFeed};
+pub use help::bundled_topics;
+pub use import::{
+    ImportFeed, ImportField, ImportFieldKind, ImportReport, ImportRequest, ImportWizard, RowError,
+};
+pub use notifications::{NotificationBell, NotificationFeed, NotificationItem};
 pub use personnel::{EmployeeCard, PersonnelPage};
+pub use presence::{OnlineIndicator, PresenceBanner, PresenceFeed};
+pub use reminders::{provide_reminders, use_reminders, ReminderSettings, Reminders};
 pub use sites::SitesPage;
+pub use training::{TrainingFeed, TrainingPanel};
 pub use user_session::{PersonaSwitcher, SignInScreen, UserInfo, UserSessionWidget};
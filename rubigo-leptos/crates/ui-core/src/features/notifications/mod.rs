@@ -0,0 +1,76 @@
+//! Notifications Module
+//!
+//! Header bell with an unread badge and an inbox panel. The feed is
+//! owned by the host app, which loads items and keeps them live (e.g.
+//! over server-sent events); the bell only renders and reports reads.
+
+mod notification_bell;
+
+pub use notification_bell::NotificationBell;
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Largest count shown on the badge before it reads "9+"
+const BADGE_MAX: usize = 9;
+
+/// One inbox entry as seen by the current persona
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationItem {
+    pub id: String,
+    /// `meeting_invite`, `simulation_complete`, `import_finished`, ...
+    pub kind: String,
+    pub title: String,
+    pub body: Option<String>,
+    /// Page opened when the entry is clicked
+    pub link: Option<String>,
+    pub created_at: String,
+    pub read: bool,
+}
+
+impl NotificationItem {
+    /// Icon for the entry's kind
+    pub fn icon(&self) -> &'static str {
+        match self.kind.as_str() {
+            "meeting_invite" => "📅",
+            "simulation_complete" => "▶",
+            "import_finished" => "📥",
+            "schedule_failed" => "⏰",
+            "incident_assigned" => "🚨",
+            "incident_escalated" => "📟",
+            _ => "🔔",
+        }
+    }
+}
+
+/// Badge text for an unread count, `None` when there is nothing unread
+pub fn badge_label(unread: usize) -> Option<String> {
+    match unread {
+        0 => None,
+        n if n > BADGE_MAX => Some(format!("{BADGE_MAX}+")),
+        n => Some(n.to_string()),
+    }
+}
+
+/// Inbox state and actions supplied by the host app
+#[derive(Clone, Copy)]
+pub struct NotificationFeed {
+    /// Newest first
+    pub items: Signal<Vec<NotificationItem>>,
+    pub unread: Signal<usize>,
+    /// Called with the item id when an entry is opened
+    pub on_read: Callback<String>,
+    pub on_read_all: Callback<()>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn badge_caps_large_counts() {
+        assert_eq!(badge_label(0), None);
+        assert_eq!(badge_label(3).as_deref(), Some("3"));
+        assert_eq!(badge_label(42).as_deref(), Some("9+"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Notification Bell Component
+//!
+//! Header button with an unread badge that opens the inbox panel.
+
+use super::{badge_label, NotificationFeed};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/notifications/notifications.module.css"
+);
+
+/// Notification bell and inbox panel for the header
+#[component]
+pub fn NotificationBell(
+    /// Inbox contents and read callbacks
+    feed: NotificationFeed,
+) -> impl IntoView {
+    let panel_open = RwSignal::new(false);
+
+    let toggle_panel = move |_: web_sys::MouseEvent| {
+        panel_open.update(|open| *open = !*open);
+    };
+
+    let handle_read_all = move |_: web_sys::MouseEvent| {
+        feed.on_read_all.run(());
+    };
+
+    let panel_class = move || {
+        if panel_open.get() {
+            format!("{} open", style::inbox_panel)
+        } else {
+            style::inbox_panel.to_string()
+        }
+    };
+
+    let trigger_label = move || match feed.unread.get() {
+        0 => "Notifications".to_string(),
+        n => format!("Notifications ({n} unread)"),
+    };
+
+    view! {
+        <div id="notification-bell" class=style::notification_bell>
+            <button
+                id="notification-bell-trigger"
+                class=style::bell_trigger
+                aria-label=trigger_label
+                aria-expanded=move || panel_open.get().to_string()
+                on:click=toggle_panel
+            >
+                <span aria-hidden="true">"🔔"</span>
+                {move || badge_label(feed.unread.get()).map(|label| view! {
+                    <span class=style::bell_badge>{label}</span>
+                })}
+            </button>
+
+            <div id="notification-inbox" class=panel_class role="dialog" aria-label="Notifications">
+                <div class=style::inbox_header>
+                    <span class=style::inbox_title>"Notifications"</span>
+                    <button
+                        class=style::inbox_action
+                        disabled=move || feed.unread.get() == 0
+                        on:click=handle_read_all
+                    >
+                        "Mark all read"
+                    </button>
+                </div>
+
+                <ul class=style::inbox_list>
+                    {move || {
+                        let items = feed.items.get();
+                        if items.is_empty() {
+                            return view! {
+                                <li class=style::inbox_empty>"You're all caught up"</li>
+                            }.into_any();
+                        }
+                        items.into_iter().map(|item| {
+                            let id = item.id.clone();
+                            let icon = item.icon();
+                            let class = if item.read {
+                                style::inbox_item.to_string()
+                            } else {
+                                format!("{} {}", style::inbox_item, style::inbox_item_unread)
+                            };
+                            view! {
+                                <li>
+                                    <a
+                                        class=class
+                                        href=item.link.clone().unwrap_or_else(|| "#".to_string())
+                                        on:click=move |_| {
+                                            panel_open.set(false);
+                                            feed.on_read.run(id.clone());
+                                        }
+                                    >
+                                        <span class=style::item_icon aria-hidden="true">{icon}</span>
+                                        <span class=style::item_text>
+                                            <span class=style::item_title>{item.title}</span>
+                                            {item.body.map(|body| view! {
+                                                <span class=style::item_body>{body}</span>
+                                            })}
+                                            <span class=style::item_time>{item.created_at}</span>
+                                        </span>
+                                    </a>
+                                </li>
+                            }
+                        }).collect_view().into_any()
+                    }}
+                </ul>
+            </div>
+        </div>
+    }
+}
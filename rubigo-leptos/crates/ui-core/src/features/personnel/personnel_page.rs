@@ -1,11 +1,21 @@
 //! Personnel Page Component
 //!
 //! Main personnel directory page with search, filtering, and employee grid.
+//! Printed, it becomes a roster: every filtered employee in one table.
 
 use super::employee_card::{Employee, EmployeeCard};
-use crate::elements::{PanelSize, SlidePanel};
+use crate::elements::{
+    DetailHeader, DetailPanel, DetailSection, DetailTab, PanelSize, PrintButton, SlidePanel,
+};
+use crate::features::activity::{activity_tab, comments_tab, ActivityFeed, ActivityQuery};
+use crate::features::custom_fields::{CustomFieldsFeed, CustomFieldsSection};
+use crate::features::presence::entity_key;
+use crate::hooks::{use_print_mode, use_url_state, use_url_state_with};
+use crate::utils::{clipboard, ShareLink};
 use leptos::prelude::*;
-use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::NavigateOptions;
+use std::fmt;
+use std::str::FromStr;
 
 stylance::import_crate_style!(style, "src/features/personnel/personnel_page.module.css");
 
@@ -17,6 +27,27 @@ pub enum ViewMode {
     Table,
 }
 
+impl fmt::Display for ViewMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ViewMode::Card => "card",
+            ViewMode::Table => "table",
+        })
+    }
+}
+
+impl FromStr for ViewMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "card" => Ok(ViewMode::Card),
+            "table" => Ok(ViewMode::Table),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Personnel page component
 #[component]
 pub fn PersonnelPage(
@@ -25,46 +56,47 @@ pub fn PersonnelPage(
     /// Callback when employee is selected (optional external handler)
     #[prop(optional)]
     on_select: Option<Callback<String>>,
+    /// Loads a person's activity feed; the details panel gets "Activity"
+    /// and "Comments" tabs when set
+    #[prop(default = None)]
+    activity: Option<Callback<ActivityQuery, ActivityFeed>>,
+    /// Loads a person's custom fields by entity key; the details panel
+    /// shows them when set
+    #[prop(default = None)]
+    custom_fields: Option<Callback<String, CustomFieldsFeed>>,
 ) -> impl IntoView {
-    // Get query params for initial state
-    let query = use_query_map();
-    let navigate = use_navigate();
-
-    // Parse view mode from query param
-    let initial_view = query
-        .get_untracked()
-        .get("view")
-        .map(|v| {
-            if v == "card" {
-                ViewMode::Card
-            } else {
-                ViewMode::Table
-            }
-        })
-        .unwrap_or(ViewMode::Table);
-
-    // State
-    let search = RwSignal::new(String::new());
-    let department_filter = RwSignal::new(String::new());
-    let view_mode = RwSignal::new(initial_view);
-    let selected_employee = RwSignal::new(None::<Employee>);
-    let show_details = RwSignal::new(false);
+    // State, mirrored into the query string so a deep link restores the view
+    let search = use_url_state_with(
+        "q",
+        String::new(),
+        NavigateOptions {
+            replace: true,
+            scroll: false,
+            ..Default::default()
+        },
+    );
+    let department_filter = use_url_state("dept", String::new());
+    let view_mode = use_url_state("view", ViewMode::default());
+    let selected_id = use_url_state("person", String::new());
+    let show_details = RwSignal::new(!selected_id.get_untracked().is_empty());
+    let panel_tab = RwSignal::new("overview".to_string());
 
     // Pagination state
-    let current_page = RwSignal::new(1usize);
-    let page_size = RwSignal::new(10usize);
+    let current_page = use_url_state("page", 1usize);
+    let page_size = use_url_state("size", 10usize);
+    let print = use_print_mode();
 
-    // Update URL when view mode changes
+    // The details panel is open exactly while a person is selected
     Effect::new(move |_| {
-        let mode = view_mode.get();
-        let view_param = match mode {
-            ViewMode::Card => "card",
-            ViewMode::Table => "table",
-        };
-        navigate(
-            &format!("/personnel?view={}", view_param),
-            Default::default(),
-        );
+        let open = !selected_id.get().is_empty();
+        if show_details.get_untracked() != open {
+            show_details.set(open);
+        }
+    });
+    Effect::new(move |_| {
+        if !show_details.get() && !selected_id.get_untracked().is_empty() {
+            selected_id.set(String::new());
+        }
     });
 
     // Get unique departments
@@ -78,6 +110,12 @@ pub fn PersonnelPage(
     // Clone for closures
     let employees_for_filter = employees.clone();
     let employees_for_lookup = employees.clone();
+    let employees_for_details = employees.clone();
+
+    let selected_employee = Signal::derive(move || {
+        let id = selected_id.get();
+        employees_for_details.iter().find(|e| e.id == id).cloned()
+    });
 
     // Filtered employees
     let filtered = move || {
@@ -102,9 +140,8 @@ pub fn PersonnelPage(
 
     // Handle employee selection
     let handle_select = move |id: String| {
-        if let Some(emp) = employees_for_lookup.iter().find(|e| e.id == id) {
-            selected_employee.set(Some(emp.clone()));
-            show_details.set(true);
+        if employees_for_lookup.iter().any(|e| e.id == id) {
+            selected_id.set(id.clone());
         }
         // Also call external handler if provided
         if let Some(cb) = on_select {
@@ -121,6 +158,14 @@ pub fn PersonnelPage(
             <div class=style::header>
                 <h1 class=style::title>"👥 Personnel"</h1>
                 <div class=style::header_actions>
+                    <PrintButton title="Personnel Roster" />
+                    <button
+                        class=style::view_btn
+                        title="Copy a link to this list with its filters"
+                        on:click=move |_| clipboard::copy_text(ShareLink::current().without("person").url(), "link")
+                    >
+                        "🔗 Copy link"
+                    </button>
                     <div class=style::stats>
                         <span class=style::stat>{employee_count}" employees"</span>
                         <span class=style::stat>{department_count}" departments"</span>
@@ -155,6 +200,7 @@ pub fn PersonnelPage(
                     type="text"
                     placeholder="Search by name, title, or email..."
                     class=style::search_input
+                    prop:value=move || search.get()
                     on:input=move |ev| search.set(event_target_value(&ev))
                 />
                 <select
@@ -165,12 +211,16 @@ pub fn PersonnelPage(
                     {departments.iter().map(|dept| {
                         let value = dept.clone();
                         let text = dept.clone();
-                        view! { <option value=value>{text}</option> }
+                        let selected = {
+                            let dept = dept.clone();
+                            move || department_filter.get() == dept
+                        };
+                        view! { <option value=value selected=selected>{text}</option> }
                     }).collect::<Vec<_>>()}
                 </select>
             </div>
 
-            {move || match view_mode.get() {
+            {move || match if print.is_active() { ViewMode::Table } else { view_mode.get() } {
                 ViewMode::Card => view! {
                     <div class=style::grid>
                         {filtered().into_iter().map(|emp| {
@@ -182,7 +232,8 @@ pub fn PersonnelPage(
                     // Calculate pagination values
                     let all_items = filtered();
                     let total_items = all_items.len();
-                    let size = page_size.get();
+                    // The printed roster lists everyone on one page
+                    let size = if print.is_active() { total_items.max(1) } else { page_size.get() };
                     let total_pages = if total_items == 0 { 1 } else { (total_items + size - 1) / size };
                     let page = current_page.get().min(total_pages);
                     let start = (page - 1) * size;
@@ -261,10 +312,9 @@ pub fn PersonnelPage(
                                         current_page.set(1);
                                     }
                                 >
-                                    <option value="5">"5 per page"</option>
-                                    <option value="10" selected=true>"10 per page"</option>
-                                    <option value="25">"25 per page"</option>
-                                    <option value="50">"50 per page"</option>
+                                    {[5usize, 10, 25, 50].into_iter().map(|n| view! {
+                                        <option value=n.to_string() selected=n == size>{format!("{} per page", n)}</option>
+                                    }).collect::<Vec<_>>()}
                                 </select>
                             </div>
                         </div>
@@ -277,62 +327,73 @@ pub fn PersonnelPage(
                 open=show_details
                 title="Employee Details"
                 size=PanelSize::Medium
+                help_key="personnel#employee-details"
             >
                 {move || selected_employee.get().map(|emp| {
-                    let photo_url = emp.photo_url.clone();
-                    let has_photo = photo_url.is_some();
-                    let initials = emp.initials();
+                    let link = ShareLink::entity("person", &emp.id).map(|l| l.url()).unwrap_or_default();
+                    let header = DetailHeader::new(emp.name.clone())
+                        .subtitle(emp.title.clone())
+                        .actions(move || {
+                            let link = link.clone();
+                            view! {
+                                <button
+                                    class=style::view_btn
+                                    title="Copy a link to this person"
+                                    on:click=move |_| clipboard::copy_text(link.clone(), "link")
+                                >
+                                    "🔗"
+                                </button>
+                            }
+                        });
+                    let header = match emp.photo_url.clone() {
+                        Some(url) => header.photo_url(url),
+                        None => header,
+                    };
+                    let query = ActivityQuery {
+                        entity: entity_key("person", &emp.id),
+                        label: Some(emp.name.clone()),
+                    };
+                    let mut tabs = vec![DetailTab::overview(move || overview(&emp, custom_fields))];
+                    if let Some(load) = activity {
+                        tabs.push(activity_tab(load, query.clone()));
+                        tabs.push(comments_tab(load, query));
+                    }
 
-                    view! {
-                    <div class=style::detail_content>
-                        <div class=style::detail_header>
-                            {if has_photo {
-                                view! {
-                                    <img
-                                        src=photo_url.unwrap_or_default()
-                                        class=style::detail_photo
-                                    />
-                                }.into_any()
-                            } else {
-                                view! {
-                                    <div class=style::detail_avatar>
-                                        {initials}
-                                    </div>
-                                }.into_any()
-                            }}
-                            <div>
-                                <h3 class=style::detail_name>{emp.name.clone()}</h3>
-                                <p class=style::detail_title>{emp.title.clone()}</p>
-                            </div>
-                        </div>
+                    view! { <DetailPanel header=header tabs=tabs active_tab=panel_tab /> }
+                })}
+            </SlidePanel>
+        </div>
+    }
+}
 
-                        <div class=style::detail_section>
-                            <h4>"Contact"</h4>
-                            <p><strong>"Email: "</strong>{emp.email.clone()}</p>
-                            {emp.phone.clone().map(|p| view! { <p><strong>"Phone: "</strong>{p}</p> })}
-                        </div>
+/// Overview tab of a person's details
+fn overview(
+    emp: &Employee,
+    custom_fields: Option<Callback<String, CustomFieldsFeed>>,
+) -> impl IntoView {
+    let fields = custom_fields.map(|open| open.run(entity_key("person", &emp.id)));
 
-                        <div class=style::detail_section>
-                            <h4>"Organization"</h4>
-                            <p><strong>"Department: "</strong>{emp.department.clone()}</p>
-                        </div>
+    view! {
+        <DetailSection title="Contact">
+            <p><strong>"Email: "</strong>{emp.email.clone()}</p>
+            {emp.phone.clone().map(|p| view! { <p><strong>"Phone: "</strong>{p}</p> })}
+        </DetailSection>
 
-                        <div class=style::detail_section>
-                            <h4>"Location"</h4>
-                            <p>{emp.location()}</p>
-                        </div>
+        <DetailSection title="Organization">
+            <p><strong>"Department: "</strong>{emp.department.clone()}</p>
+        </DetailSection>
 
-                        {emp.bio.clone().map(|bio| view! {
-                            <div class=style::detail_section>
-                                <h4>"Bio"</h4>
-                                <p>{bio}</p>
-                            </div>
-                        })}
-                    </div>
-                    }
-                })}
-            </SlidePanel>
-        </div>
+        <DetailSection title="Location">
+            <p>{emp.location()}</p>
+        </DetailSection>
+
+        {emp.bio.clone().map(|bio| view! {
+            <DetailSection title="Bio">
+                <p>{bio}</p>
+            </DetailSection>
+        })}
+
+        {fields.map(|feed| view! { <CustomFieldsSection feed=feed /> })}
     }
 }
 
@@ -345,4 +406,12 @@ mod tests {
         let employees: Vec<Employee> = vec![];
         assert!(employees.is_empty());
     }
+
+    #[test]
+    fn view_mode_round_trips_through_query() {
+        for mode in [ViewMode::Card, ViewMode::Table] {
+            assert_eq!(mode.to_string().parse::<ViewMode>(), Ok(mode));
+        }
+        assert!("grid".parse::<ViewMode>().is_err());
+    }
 }
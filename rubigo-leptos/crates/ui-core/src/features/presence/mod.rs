@@ -0,0 +1,58 @@
+//! Presence Module
+//!
+//! Shows who else is online and what they have open. The host app keeps
+//! the roster live (e.g. over the presence WebSocket); the components only
+//! render it:
+//!
+//! - [`OnlineIndicator`]: header button listing online personas with the
+//!   page or record each is on
+//! - [`PresenceBanner`]: warning for a detail panel when someone else is
+//!   editing its record, or a note when others are viewing it
+
+mod online_indicator;
+mod presence_banner;
+
+pub use actions::presence::{
+    editing_warning, entity_key, join_names, Presence, PresenceUpdate, Roster, PRESENCE_PATH,
+};
+pub use online_indicator::OnlineIndicator;
+pub use presence_banner::PresenceBanner;
+
+use leptos::prelude::*;
+
+/// Roster supplied by the host app
+#[derive(Clone, Copy)]
+pub struct PresenceFeed {
+    pub roster: Signal<Roster>,
+}
+
+/// What a persona has open, e.g. "assets · asset:42 (editing)"
+pub fn activity_label(presence: &Presence) -> String {
+    let page = if presence.page.is_empty() {
+        "home"
+    } else {
+        presence.page.as_str()
+    };
+    match (&presence.entity, presence.editing) {
+        (Some(entity), true) => format!("{page} · editing {entity}"),
+        (Some(entity), false) => format!("{page} · {entity}"),
+        (None, _) => page.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_names_page_and_record() {
+        let mut presence = Presence::new("Ada");
+        assert_eq!(activity_label(&presence), "home");
+        presence.apply(PresenceUpdate {
+            page: "assets".into(),
+            entity: Some("asset:42".into()),
+            editing: true,
+        });
+        assert_eq!(activity_label(&presence), "assets · editing asset:42");
+    }
+}
@@ -0,0 +1,98 @@
+//! Online Indicator Component
+//!
+//! Header button with the number of other personas online that opens a
+//! list of who they are and what they have open.
+
+use super::{activity_label, PresenceFeed};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/presence/presence.module.css"
+);
+
+/// Online personas for the header
+#[component]
+pub fn OnlineIndicator(
+    /// Current persona, left out of the list
+    #[prop(into)]
+    me: String,
+    /// Live roster
+    feed: PresenceFeed,
+) -> impl IntoView {
+    let panel_open = RwSignal::new(false);
+    let me = StoredValue::new(me);
+
+    let others = Memo::new(move |_| {
+        let roster = feed.roster.get();
+        me.with_value(|me| {
+            roster
+                .online()
+                .into_iter()
+                .filter(|p| &p.persona != me)
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let panel_class = move || {
+        if panel_open.get() {
+            format!("{} open", style::online_panel)
+        } else {
+            style::online_panel.to_string()
+        }
+    };
+
+    let trigger_label = move || match others.with(Vec::len) {
+        1 => "1 other person online".to_string(),
+        n => format!("{n} other people online"),
+    };
+
+    view! {
+        <div id="online-indicator" class=style::online_indicator>
+            <button
+                class=style::online_trigger
+                aria-label=trigger_label
+                aria-expanded=move || panel_open.get().to_string()
+                on:click=move |_| panel_open.update(|open| *open = !*open)
+            >
+                <span class=style::online_dot aria-hidden="true"></span>
+                {move || others.with(Vec::len)}
+            </button>
+
+            <div class=panel_class role="dialog" aria-label="Online">
+                <ul class=style::online_list>
+                    {move || {
+                        let others = others.get();
+                        if others.is_empty() {
+                            return view! {
+                                <li class=style::online_entry>
+                                    <span class=style::online_activity>"Nobody else is online"</span>
+                                </li>
+                            }
+                            .into_any();
+                        }
+                        others
+                            .into_iter()
+                            .map(|presence| {
+                                let activity_class = if presence.editing {
+                                    format!("{} {}", style::online_activity, style::online_editing)
+                                } else {
+                                    style::online_activity.to_string()
+                                };
+                                view! {
+                                    <li class=style::online_entry>
+                                        <span class=style::online_name>{presence.persona.clone()}</span>
+                                        <span class=activity_class>{activity_label(&presence)}</span>
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                            .into_any()
+                    }}
+                </ul>
+            </div>
+        </div>
+    }
+}
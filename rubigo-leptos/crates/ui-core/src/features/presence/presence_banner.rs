@@ -0,0 +1,69 @@
+//! Presence Banner Component
+//!
+//! Sits at the top of a detail panel. When another persona is editing the
+//! panel's record it warns "Ada is editing this asset"; when others only
+//! have it open it notes who is viewing. Renders nothing otherwise.
+
+use super::{editing_warning, join_names, PresenceFeed};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/presence/presence.module.css"
+);
+
+/// Editing / viewing notice for the record a panel shows
+#[component]
+pub fn PresenceBanner(
+    /// Current persona, never warned about itself
+    #[prop(into)]
+    me: String,
+    /// Entity key of the record shown (see `entity_key`), `None` when closed
+    #[prop(into)]
+    entity: Signal<Option<String>>,
+    /// Record kind for the message, e.g. "asset"
+    #[prop(into)]
+    noun: String,
+    /// Live roster
+    feed: PresenceFeed,
+) -> impl IntoView {
+    let me = StoredValue::new(me);
+    let noun = StoredValue::new(noun);
+
+    let notice = move || {
+        let entity = entity.get()?;
+        let roster = feed.roster.get();
+        let me = me.get_value();
+        let editors = roster.editors(&entity, &me);
+        if let Some(warning) = noun.with_value(|noun| editing_warning(&editors, noun)) {
+            return Some((true, warning));
+        }
+        join_names(&roster.viewers(&entity, &me))
+            .map(|names| (false, format!("Also viewing: {names}")))
+    };
+
+    move || {
+        notice().map(|(editing, text)| {
+            let (class, icon, role) = if editing {
+                (
+                    format!("{} {}", style::banner, style::banner_editing),
+                    "✎",
+                    "alert",
+                )
+            } else {
+                (
+                    format!("{} {}", style::banner, style::banner_viewing),
+                    "👁",
+                    "status",
+                )
+            };
+            view! {
+                <div class=class role=role>
+                    <span aria-hidden="true">{icon}</span>
+                    <span>{text}</span>
+                </div>
+            }
+        })
+    }
+}
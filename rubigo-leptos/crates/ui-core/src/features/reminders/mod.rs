@@ -0,0 +1,301 @@
+//! Reminders Module
+//!
+//! Notifies the signed-in persona shortly before their meetings start. The
+//! [`ReminderEngine`] works out which occurrences of the persona's calendar
+//! events are due a reminder, honouring each event's lead time and any
+//! snooze; [`provide_reminders`] runs it on a timer, showing each reminder
+//! as a system notification (browser or Tauri) and a toast with a "Snooze"
+//! button.
+
+mod notifier;
+mod scheduler;
+
+pub use notifier::{system_notifier, BrowserNotifier, Notifier, TauriNotifier};
+pub use scheduler::{provide_reminders, use_reminders, Reminders};
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::features::calendar::CalendarEvent;
+
+/// Lead time of events without their own
+pub const DEFAULT_LEAD_MINUTES: u32 = 10;
+
+/// How long "Snooze" puts a reminder off
+pub const DEFAULT_SNOOZE_MINUTES: u32 = 5;
+
+/// Lead times offered per event, in minutes
+pub const LEAD_CHOICES: [u32; 6] = [0, 5, 10, 15, 30, 60];
+
+/// Days ahead searched for occurrences; longer than any lead time
+const LOOKAHEAD_DAYS: i64 = 1;
+
+/// Reminder preferences, kept by the host app between sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReminderSettings {
+    pub enabled: bool,
+    pub default_lead_minutes: u32,
+    pub snooze_minutes: u32,
+    /// Lead time per event ID; `None` turns the event's reminders off
+    pub lead_minutes: HashMap<String, Option<u32>>,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_lead_minutes: DEFAULT_LEAD_MINUTES,
+            snooze_minutes: DEFAULT_SNOOZE_MINUTES,
+            lead_minutes: HashMap::new(),
+        }
+    }
+}
+
+impl ReminderSettings {
+    /// Minutes before `event_id` starts to remind, `None` for no reminder
+    pub fn lead_for(&self, event_id: &str) -> Option<u32> {
+        if !self.enabled {
+            return None;
+        }
+        match self.lead_minutes.get(event_id) {
+            Some(lead) => *lead,
+            None => Some(self.default_lead_minutes),
+        }
+    }
+
+    /// Give `event_id` its own lead time (or none)
+    pub fn set_lead(&mut self, event_id: impl Into<String>, minutes: Option<u32>) {
+        self.lead_minutes.insert(event_id.into(), minutes);
+    }
+}
+
+/// A reminder for one occurrence of an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reminder {
+    /// Event ID and occurrence date, e.g. `event_3@2024-12-09`
+    pub key: String,
+    pub event_id: String,
+    pub title: String,
+    pub location: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+}
+
+impl Reminder {
+    /// Notification text, e.g. "Starts in 10 min · Room 4"
+    pub fn body(&self, now: DateTime<Utc>) -> String {
+        let minutes = (self.starts_at - now).num_minutes().max(0);
+        let when = match minutes {
+            0 => "Starting now".to_string(),
+            1 => "Starts in 1 min".to_string(),
+            n => format!("Starts in {} min", n),
+        };
+        match &self.location {
+            Some(location) => format!("{} · {}", when, location),
+            None => when,
+        }
+    }
+}
+
+/// How a lead time reads in a picker, e.g. "10 minutes before"
+pub fn lead_label(minutes: Option<u32>) -> String {
+    match minutes {
+        None => "None".to_string(),
+        Some(0) => "At start time".to_string(),
+        Some(1) => "1 minute before".to_string(),
+        Some(60) => "1 hour before".to_string(),
+        Some(n) => format!("{} minutes before", n),
+    }
+}
+
+/// Whether `person_id` takes part in `event`
+///
+/// Events that name nobody are treated as everyone's.
+pub fn involves(event: &CalendarEvent, person_id: &str) -> bool {
+    let mut people = event
+        .organizers
+        .iter()
+        .chain(&event.participants)
+        .peekable();
+    people.peek().is_none() || people.any(|p| p.id == person_id)
+}
+
+/// When the occurrence of `event` on `date` starts, if it occurs then
+fn occurrence_start(event: &CalendarEvent, date: NaiveDate) -> Option<DateTime<Utc>> {
+    let instance = event.get_instance_data(date)?;
+    // Recurring instances carry the series' start; keep its time of day
+    if instance.start_time.date_naive() == date {
+        Some(instance.start_time)
+    } else {
+        Some(date.and_time(instance.start_time.time()).and_utc())
+    }
+}
+
+/// Decides which reminders are due, remembering those already shown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReminderEngine {
+    pub settings: ReminderSettings,
+    /// Keys of reminders already shown
+    fired: HashSet<String>,
+    /// Keys of snoozed reminders and when they are due again
+    snoozed: HashMap<String, DateTime<Utc>>,
+}
+
+impl ReminderEngine {
+    pub fn new(settings: ReminderSettings) -> Self {
+        Self {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    /// Reminders for `person_id`'s occurrences that haven't started yet,
+    /// soonest due first
+    pub fn scheduled(
+        &self,
+        events: &[CalendarEvent],
+        person_id: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<Reminder> {
+        let today = now.date_naive();
+        let mut reminders: Vec<Reminder> = events
+            .iter()
+            .filter(|event| !event.deleted && involves(event, person_id))
+            .flat_map(|event| {
+                (0..=LOOKAHEAD_DAYS).filter_map(move |offset| {
+                    let date = today + Duration::days(offset);
+                    let starts_at = occurrence_start(event, date).filter(|start| *start > now)?;
+                    let lead = self.settings.lead_for(&event.id)?;
+                    let key = format!("{}@{}", event.id, date.format("%Y-%m-%d"));
+                    let due_at = self
+                        .snoozed
+                        .get(&key)
+                        .copied()
+                        .unwrap_or(starts_at - Duration::minutes(lead as i64));
+                    Some(Reminder {
+                        key,
+                        event_id: event.id.clone(),
+                        title: event.title.clone(),
+                        location: event.location.clone(),
+                        starts_at,
+                        due_at,
+                    })
+                })
+            })
+            .collect();
+        reminders.sort_by_key(|r| r.due_at);
+        reminders
+    }
+
+    /// Reminders due by `now` and not yet shown; they count as shown after
+    pub fn take_due(
+        &mut self,
+        events: &[CalendarEvent],
+        person_id: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<Reminder> {
+        let scheduled = self.scheduled(events, person_id, now);
+        // Forget occurrences that have started
+        self.fired
+            .retain(|key| scheduled.iter().any(|r| &r.key == key));
+        self.snoozed
+            .retain(|key, _| scheduled.iter().any(|r| &r.key == key));
+
+        let due: Vec<Reminder> = scheduled
+            .into_iter()
+            .filter(|r| r.due_at <= now && !self.fired.contains(&r.key))
+            .collect();
+        self.fired.extend(due.iter().map(|r| r.key.clone()));
+        due
+    }
+
+    /// Show the reminder `key` again after the snooze time
+    pub fn snooze(&mut self, key: &str, now: DateTime<Utc>) {
+        self.fired.remove(key);
+        self.snoozed.insert(
+            key.to_string(),
+            now + Duration::minutes(self.settings.snooze_minutes as i64),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::calendar::{ParticipantInfo, RecurrenceFrequency};
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 12, 9, hour, minute, 0).unwrap()
+    }
+
+    fn meeting(id: &str, start: DateTime<Utc>) -> CalendarEvent {
+        let mut event = CalendarEvent::new(id, "Design review", start, start + Duration::hours(1));
+        event.participants = vec![ParticipantInfo::new("ada", "Ada")];
+        event
+    }
+
+    #[test]
+    fn reminders_fire_once_at_the_lead_time() {
+        let events = vec![meeting("1", at(10, 0))];
+        let mut engine = ReminderEngine::default();
+
+        assert!(engine.take_due(&events, "ada", at(9, 49)).is_empty());
+        let due = engine.take_due(&events, "ada", at(9, 50));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].body(at(9, 50)), "Starts in 10 min");
+        assert!(engine.take_due(&events, "ada", at(9, 51)).is_empty());
+
+        // Not for someone else, nor once the meeting has started
+        assert!(ReminderEngine::default()
+            .take_due(&events, "bob", at(9, 55))
+            .is_empty());
+        assert!(ReminderEngine::default()
+            .take_due(&events, "ada", at(10, 0))
+            .is_empty());
+    }
+
+    #[test]
+    fn lead_time_can_be_set_per_event() {
+        let events = vec![meeting("1", at(10, 0)), meeting("2", at(10, 0))];
+        let mut settings = ReminderSettings::default();
+        settings.set_lead("1", Some(30));
+        settings.set_lead("2", None);
+        let mut engine = ReminderEngine::new(settings);
+
+        let due = engine.take_due(&events, "ada", at(9, 30));
+        assert_eq!(
+            due.iter().map(|r| r.event_id.as_str()).collect::<Vec<_>>(),
+            ["1"]
+        );
+        assert!(engine.take_due(&events, "ada", at(9, 55)).is_empty());
+        assert_eq!(lead_label(Some(30)), "30 minutes before");
+        assert_eq!(lead_label(None), "None");
+    }
+
+    #[test]
+    fn snoozed_reminders_come_back() {
+        let events = vec![meeting("1", at(10, 0))];
+        let mut engine = ReminderEngine::default();
+        let key = engine.take_due(&events, "ada", at(9, 50))[0].key.clone();
+
+        engine.snooze(&key, at(9, 51));
+        assert!(engine.take_due(&events, "ada", at(9, 55)).is_empty());
+        assert_eq!(engine.take_due(&events, "ada", at(9, 56)).len(), 1);
+    }
+
+    #[test]
+    fn recurring_events_are_reminded_on_each_occurrence() {
+        let mut standup = meeting("s", Utc.with_ymd_and_hms(2024, 12, 2, 9, 0, 0).unwrap());
+        standup.recurrence = RecurrenceFrequency::Daily;
+        let scheduled = ReminderEngine::default().scheduled(&[standup], "ada", at(8, 0));
+
+        assert_eq!(scheduled.len(), 2);
+        assert_eq!(scheduled[0].key, "s@2024-12-09");
+        assert_eq!(scheduled[0].starts_at, at(9, 0));
+        assert_eq!(scheduled[1].key, "s@2024-12-10");
+    }
+}
@@ -0,0 +1,89 @@
+//! System Notifications
+//!
+//! Shows a reminder outside the page: through the Web Notifications API in
+//! a browser, or as a native notification from the Tauri shell (its
+//! notification plugin, exposed on `window.__TAURI__.notification`).
+
+use wasm_bindgen::prelude::*;
+
+/// Where reminders are shown outside the app
+pub trait Notifier {
+    /// Ask for permission to notify, if the user hasn't decided yet
+    fn request_permission(&self);
+
+    /// Show a notification; does nothing without permission
+    fn notify(&self, title: &str, body: &str);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "notification"], js_name = isPermissionGranted)]
+    fn tauri_is_permission_granted() -> js_sys::Promise;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "notification"], js_name = requestPermission)]
+    fn tauri_request_permission() -> js_sys::Promise;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "notification"], js_name = sendNotification)]
+    fn tauri_send_notification(options: &JsValue);
+}
+
+/// Web Notifications API
+pub struct BrowserNotifier;
+
+impl Notifier for BrowserNotifier {
+    fn request_permission(&self) {
+        if web_sys::Notification::permission() == web_sys::NotificationPermission::Default {
+            let _ = web_sys::Notification::request_permission();
+        }
+    }
+
+    fn notify(&self, title: &str, body: &str) {
+        if web_sys::Notification::permission() != web_sys::NotificationPermission::Granted {
+            return;
+        }
+        let options = web_sys::NotificationOptions::new();
+        options.set_body(body);
+        if let Err(e) = web_sys::Notification::new_with_options(title, &options) {
+            web_sys::console::warn_2(&"Notification not shown".into(), &e);
+        }
+    }
+}
+
+/// Native notifications from the Tauri shell
+pub struct TauriNotifier;
+
+impl Notifier for TauriNotifier {
+    fn request_permission(&self) {
+        wasm_bindgen_futures::spawn_local(async {
+            let granted = wasm_bindgen_futures::JsFuture::from(tauri_is_permission_granted())
+                .await
+                .ok()
+                .and_then(|granted| granted.as_bool())
+                .unwrap_or(false);
+            if !granted {
+                let _ = wasm_bindgen_futures::JsFuture::from(tauri_request_permission()).await;
+            }
+        });
+    }
+
+    fn notify(&self, title: &str, body: &str) {
+        let options = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&options, &"title".into(), &title.into());
+        let _ = js_sys::Reflect::set(&options, &"body".into(), &body.into());
+        tauri_send_notification(&options);
+    }
+}
+
+/// The Tauri shell's notifications when running in it, else the browser's
+///
+/// `None` where neither is available (e.g. an old browser).
+pub fn system_notifier() -> Option<Box<dyn Notifier>> {
+    let global = js_sys::global();
+    if js_sys::Reflect::has(&global, &"__TAURI__".into()).unwrap_or(false) {
+        Some(Box::new(TauriNotifier))
+    } else if js_sys::Reflect::has(&global, &"Notification".into()).unwrap_or(false) {
+        Some(Box::new(BrowserNotifier))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,167 @@
+//! Reminder Scheduler
+//!
+//! Checks the persona's calendar every [`CHECK_INTERVAL_MS`] and shows what
+//! the [`ReminderEngine`] says is due. Checking on a timer rather than
+//! sleeping until the next reminder copes with edits to the calendar and
+//! with the machine waking from sleep.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use leptos::prelude::*;
+
+use super::{Notifier, ReminderEngine, ReminderSettings};
+use crate::elements::{use_toasts, Toast, ToastLevel, ToastQueue};
+use crate::features::calendar::CalendarEvent;
+
+/// How often due reminders are looked for
+const CHECK_INTERVAL_MS: u64 = 15_000;
+
+/// How long a reminder toast stays up
+const TOAST_MS: u32 = 30_000;
+
+/// Shared reminder scheduler, see [`provide_reminders`]
+#[derive(Clone, Copy)]
+pub struct Reminders {
+    engine: RwSignal<ReminderEngine>,
+    events: Signal<Vec<CalendarEvent>>,
+    /// Signed-in persona; no reminders while signed out
+    person: Signal<Option<String>>,
+    notifier: StoredValue<Option<Box<dyn Notifier>>, LocalStorage>,
+    /// Toast ID -> reminder key for the Snooze buttons on screen
+    toast_reminders: StoredValue<HashMap<u64, String>>,
+    toasts: RwSignal<ToastQueue>,
+}
+
+impl Reminders {
+    /// Current preferences, for the host app to keep
+    pub fn settings(&self) -> Signal<ReminderSettings> {
+        let engine = self.engine;
+        Signal::derive(move || engine.with(|e| e.settings.clone()))
+    }
+
+    /// Lead time of `event_id` in minutes, `None` when it has no reminder
+    pub fn lead_for(&self, event_id: &str) -> Option<u32> {
+        self.engine.with(|e| e.settings.lead_for(event_id))
+    }
+
+    /// Change the lead time of one event
+    pub fn set_lead(&self, event_id: &str, minutes: Option<u32>) {
+        self.engine
+            .update(|e| e.settings.set_lead(event_id, minutes));
+    }
+
+    /// Turn all reminders on or off
+    pub fn set_enabled(&self, enabled: bool) {
+        if enabled {
+            self.request_permission();
+        }
+        self.engine.update(|e| e.settings.enabled = enabled);
+    }
+
+    /// Ask for notification permission; call from a click, as browsers
+    /// ignore requests made without one
+    pub fn request_permission(&self) {
+        self.notifier.with_value(|notifier| {
+            if let Some(notifier) = notifier {
+                notifier.request_permission();
+            }
+        });
+    }
+
+    /// Put off the reminder `key`
+    pub fn snooze(&self, key: &str) {
+        self.engine.update(|e| e.snooze(key, Utc::now()));
+    }
+
+    /// `on_action` handler for [`ToastRegion`](crate::elements::ToastRegion)
+    /// that snoozes the reminder behind a pressed Snooze button
+    pub fn toast_handler(&self) -> Callback<u64> {
+        let reminders = *self;
+        Callback::new(move |toast_id: u64| {
+            let key = reminders
+                .toast_reminders
+                .try_update_value(|m| m.remove(&toast_id))
+                .flatten();
+            if let Some(key) = key {
+                reminders.snooze(&key);
+            }
+        })
+    }
+
+    /// Show every reminder that has come due
+    fn check(&self) {
+        let Some(person) = self.person.get_untracked() else {
+            return;
+        };
+        let now = Utc::now();
+        let due = self.events.with_untracked(|events| {
+            self.engine
+                .try_update(|e| e.take_due(events, &person, now))
+                .unwrap_or_default()
+        });
+
+        for reminder in due {
+            let body = reminder.body(now);
+            self.notifier.with_value(|notifier| {
+                if let Some(notifier) = notifier {
+                    notifier.notify(&reminder.title, &body);
+                }
+            });
+            let toast = Toast::new(ToastLevel::Info, reminder.title.clone())
+                .message(body)
+                .action("Snooze")
+                .duration(TOAST_MS);
+            if let Some(toast_id) = self.toasts.try_update(|q| q.push(toast)) {
+                self.toast_reminders.update_value(|m| {
+                    m.insert(toast_id, reminder.key);
+                });
+            }
+        }
+    }
+}
+
+/// Start reminding `person` of their `events`, and provide the scheduler
+/// to descendants
+///
+/// Needs the toast queue from [`provide_toasts`](crate::elements::provide_toasts).
+/// Without a `notifier` reminders only appear as toasts.
+pub fn provide_reminders(
+    events: Signal<Vec<CalendarEvent>>,
+    person: Signal<Option<String>>,
+    settings: ReminderSettings,
+    notifier: Option<Box<dyn Notifier>>,
+) -> Reminders {
+    let reminders = Reminders {
+        engine: RwSignal::new(ReminderEngine::new(settings)),
+        events,
+        person,
+        notifier: StoredValue::new_local(notifier),
+        toast_reminders: StoredValue::new(HashMap::new()),
+        toasts: use_toasts(),
+    };
+
+    let handle = set_interval_with_handle(
+        move || reminders.check(),
+        std::time::Duration::from_millis(CHECK_INTERVAL_MS),
+    );
+    on_cleanup(move || {
+        if let Ok(handle) = handle {
+            handle.clear();
+        }
+    });
+    // Don't wait a whole interval after signing in
+    Effect::new(move |_| {
+        if person.with(Option::is_some) {
+            reminders.check();
+        }
+    });
+
+    provide_context(reminders);
+    reminders
+}
+
+/// Access the scheduler provided by [`provide_reminders`], if any
+pub fn use_reminders() -> Option<Reminders> {
+    use_context::<Reminders>()
+}
@@ -0,0 +1,64 @@
+//! Training Module
+//!
+//! Training mode: the trainee picks one of the scenario's guided exercises,
+//! works through its tasks in the app while the server watches the actions
+//! they dispatch, and gets a scorecard at the end (see
+//! [`actions::training`]). The host app loads the exercises and the
+//! session and sends the trainee's requests, e.g. through
+//! `TrainingAction`s; the session's trail is its activity feed, at
+//! [`TrainingProgressData::entity`].
+
+mod training_panel;
+
+pub use actions::training::HINT_COST_PERCENT;
+pub use actions::{ActivityData, ExerciseData, TaskProgressData, TrainingProgressData};
+pub use training_panel::TrainingPanel;
+
+use crate::primitives::BadgeVariant;
+use leptos::prelude::*;
+
+/// Exercises, the session and the trainee's requests, supplied by the host app
+#[derive(Clone, Copy)]
+pub struct TrainingFeed {
+    pub exercises: Signal<Vec<ExerciseData>>,
+    /// The session in progress, or the last one; `None` before the first
+    pub progress: Signal<Option<TrainingProgressData>>,
+    /// The session's audit trail, newest first
+    pub trail: Signal<Vec<ActivityData>>,
+    /// Called with the name of the exercise to start
+    pub on_start: Callback<String>,
+    pub on_hint: Callback<()>,
+    pub on_finish: Callback<()>,
+    /// Why the last request was refused, if it was
+    pub error: Signal<Option<String>>,
+}
+
+/// Share of the possible points, in whole percent
+pub fn percent(score: u32, max_score: u32) -> u32 {
+    match max_score {
+        0 => 0,
+        max => score * 100 / max,
+    }
+}
+
+/// Verdict and badge color of a finished session's score
+pub fn grade(score: u32, max_score: u32) -> (&'static str, BadgeVariant) {
+    match percent(score, max_score) {
+        90.. => ("Excellent", BadgeVariant::Success),
+        60.. => ("Passed", BadgeVariant::Primary),
+        _ => ("Needs practice", BadgeVariant::Warning),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_are_graded_by_share_of_points() {
+        assert_eq!(percent(25, 30), 83);
+        assert_eq!(grade(25, 30).0, "Passed");
+        assert_eq!(grade(30, 30).1, BadgeVariant::Success);
+        assert_eq!(grade(0, 0).0, "Needs practice");
+    }
+}
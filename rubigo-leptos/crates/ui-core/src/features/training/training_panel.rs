@@ -0,0 +1,208 @@
+//! Training Panel Component
+//!
+//! While a session runs: the task at hand with its instructions and hints,
+//! and the checklist of tasks. Once it is finished: the scorecard and the
+//! trail of what the trainee did. Either way, the exercises to start.
+
+use super::{grade, percent, ActivityData, TrainingFeed, TrainingProgressData, HINT_COST_PERCENT};
+use crate::features::activity::{kind_icon, when_label};
+use crate::primitives::{Badge, BadgeSize};
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/features/training/training.module.css"
+);
+
+/// Exercise picker, the session in progress and the last scorecard
+#[component]
+pub fn TrainingPanel(
+    /// The exercises, the session and the trainee's requests
+    feed: TrainingFeed,
+) -> impl IntoView {
+    view! {
+        <div class=style::training>
+            {move || feed.error.get().map(|error| view! {
+                <p class=style::error role="alert">{error}</p>
+            })}
+
+            {move || match feed.progress.get() {
+                Some(progress) if progress.finished_at.is_none() => session(progress, feed).into_any(),
+                Some(progress) => scorecard(progress).into_any(),
+                None => ().into_any(),
+            }}
+
+            {move || {
+                let trail = feed.trail.get();
+                (!trail.is_empty()).then(|| trail_view(trail))
+            }}
+
+            <section>
+                <h2>"Exercises"</h2>
+                {move || {
+                    let exercises = feed.exercises.get();
+                    if exercises.is_empty() {
+                        return view! { <p class=style::empty>"This scenario has no exercises"</p> }.into_any();
+                    }
+                    view! {
+                        <ul class=style::exercises>
+                            {exercises.into_iter().map(|exercise| {
+                                let name = exercise.name.clone();
+                                view! {
+                                    <li class=style::exercise>
+                                        <div>
+                                            <span class=style::exercise_name>{exercise.name.clone()}</span>
+                                            <p class=style::meta>
+                                                {format!("{} tasks · {} points", exercise.tasks, exercise.max_score)}
+                                            </p>
+                                            {exercise.description.map(|d| view! { <p class=style::description>{d}</p> })}
+                                        </div>
+                                        <button
+                                            type="button"
+                                            class=style::primary
+                                            on:click=move |_| feed.on_start.run(name.clone())
+                                        >
+                                            "Start"
+                                        </button>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                    }
+                    .into_any()
+                }}
+            </section>
+        </div>
+    }
+}
+
+/// The session in progress
+fn session(progress: TrainingProgressData, feed: TrainingFeed) -> impl IntoView {
+    let current = progress.current();
+    let task = current.map(|i| progress.tasks[i].clone());
+    let checklist = progress
+        .tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let (mark, class) = match (task.completed_at.is_some(), Some(i) == current) {
+                (true, _) => ("✔", style::task_done),
+                (false, true) => ("▶", style::task_current),
+                (false, false) => ("○", style::task),
+            };
+            view! {
+                <li class=class>
+                    <span aria-hidden="true">{mark}</span>
+                    " "
+                    {task.title.clone()}
+                </li>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <section class=style::card>
+            <div class=style::header>
+                <h2>{progress.exercise.clone()}</h2>
+                <span class=style::meta>
+                    {format!("{} of {} tasks · {}", progress.completed(), progress.tasks.len(), progress.trainee)}
+                </span>
+            </div>
+            {task.map(|task| {
+                let cost = task.points * HINT_COST_PERCENT / 100;
+                view! {
+                    <div class=style::current>
+                        <h3>{task.title}</h3>
+                        {task.instructions.map(|i| view! { <p class=style::description>{i}</p> })}
+                        {(!task.hints.is_empty()).then(|| view! {
+                            <ol class=style::hints>
+                                {task.hints.into_iter().map(|h| view! { <li>{h}</li> }).collect_view()}
+                            </ol>
+                        })}
+                        {(task.hints_left > 0).then(|| view! {
+                            <button type="button" class=style::secondary on:click=move |_| feed.on_hint.run(())>
+                                {format!("Hint (−{cost} points)")}
+                            </button>
+                        })}
+                    </div>
+                }
+            })}
+            <ol class=style::checklist>{checklist}</ol>
+            <button type="button" class=style::secondary on:click=move |_| feed.on_finish.run(())>
+                "Finish"
+            </button>
+        </section>
+    }
+}
+
+/// Points per task and the overall result of a finished session
+fn scorecard(progress: TrainingProgressData) -> impl IntoView {
+    let (score, max_score) = (progress.score(), progress.max_score());
+    let (verdict, variant) = grade(score, max_score);
+    let rows = progress
+        .tasks
+        .iter()
+        .map(|task| {
+            view! {
+                <tr>
+                    <td>{task.title.clone()}</td>
+                    <td>{task.completed_at.as_deref().map(when_label).unwrap_or_else(|| "Not done".to_string())}</td>
+                    <td>{task.hints.len()}</td>
+                    <td class=style::points>{format!("{} / {}", task.earned(), task.points)}</td>
+                </tr>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <section class=style::card>
+            <div class=style::header>
+                <h2>{format!("Scorecard: {}", progress.exercise)}</h2>
+                <Badge variant=variant size=BadgeSize::Small>{verdict}</Badge>
+            </div>
+            <p class=style::score>
+                {format!("{score} of {max_score} points ({}%)", percent(score, max_score))}
+            </p>
+            <p class=style::meta>
+                {format!(
+                    "{} · {} of {} tasks · {} extra actions",
+                    progress.trainee,
+                    progress.completed(),
+                    progress.tasks.len(),
+                    progress.extra_actions
+                )}
+            </p>
+            <table class=style::scorecard>
+                <thead>
+                    <tr>
+                        <th>"Task"</th>
+                        <th>"Done"</th>
+                        <th>"Hints"</th>
+                        <th>"Points"</th>
+                    </tr>
+                </thead>
+                <tbody>{rows}</tbody>
+            </table>
+        </section>
+    }
+}
+
+/// What the trainee did, from the session's audit trail
+fn trail_view(trail: Vec<ActivityData>) -> impl IntoView {
+    view! {
+        <section>
+            <h2>"Trail"</h2>
+            <ol class=style::trail>
+                {trail.into_iter().map(|item| view! {
+                    <li>
+                        <span aria-hidden="true">{kind_icon(&item.kind)}</span>
+                        " "
+                        {item.title}
+                        <span class=style::meta>{format!(" · {}", when_label(&item.at))}</span>
+                    </li>
+                }).collect_view()}
+            </ol>
+        </section>
+    }
+}
@@ -0,0 +1,100 @@
+//! Breakpoint
+//!
+//! Which screen class the viewport falls in, as a signal that follows window
+//! resizes. Components switch structure on it (the sidebar becomes a drawer,
+//! tables become cards); purely visual tweaks stay in CSS media queries at
+//! the same widths.
+//!
+//! ```ignore
+//! let breakpoint = use_breakpoint();
+//! view! { <Show when=move || breakpoint.get().is_mobile()>"…"</Show> }
+//! ```
+
+use leptos::prelude::*;
+
+/// Widest viewport, in CSS pixels, treated as a phone
+pub const MOBILE_MAX_WIDTH: f64 = 640.0;
+
+/// Widest viewport, in CSS pixels, treated as a tablet
+pub const TABLET_MAX_WIDTH: f64 = 1024.0;
+
+/// Screen class of the viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Breakpoint {
+    Mobile,
+    Tablet,
+    Desktop,
+}
+
+impl Breakpoint {
+    /// Screen class of a viewport `width` CSS pixels wide
+    pub fn from_width(width: f64) -> Self {
+        if width <= MOBILE_MAX_WIDTH {
+            Breakpoint::Mobile
+        } else if width <= TABLET_MAX_WIDTH {
+            Breakpoint::Tablet
+        } else {
+            Breakpoint::Desktop
+        }
+    }
+
+    pub fn is_mobile(self) -> bool {
+        self == Breakpoint::Mobile
+    }
+
+    pub fn is_desktop(self) -> bool {
+        self == Breakpoint::Desktop
+    }
+}
+
+/// Shared so every caller reuses one resize listener
+#[derive(Clone, Copy)]
+struct BreakpointContext(Signal<Breakpoint>);
+
+/// Current screen class, updated as the window resizes
+///
+/// The first call creates the listener and provides it to descendants;
+/// later calls below it share that signal.
+pub fn use_breakpoint() -> Signal<Breakpoint> {
+    if let Some(BreakpointContext(breakpoint)) = use_context() {
+        return breakpoint;
+    }
+
+    let breakpoint = RwSignal::new(current());
+    let handle = window_event_listener(leptos::ev::resize, move |_| {
+        let now = current();
+        if breakpoint.get_untracked() != now {
+            breakpoint.set(now);
+        }
+    });
+    on_cleanup(move || handle.remove());
+
+    let breakpoint = breakpoint.read_only().into();
+    provide_context(BreakpointContext(breakpoint));
+    breakpoint
+}
+
+/// Screen class of the window right now
+fn current() -> Breakpoint {
+    let width = window()
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .unwrap_or(TABLET_MAX_WIDTH + 1.0);
+    Breakpoint::from_width(width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths_map_to_screen_classes() {
+        assert_eq!(Breakpoint::from_width(375.0), Breakpoint::Mobile);
+        assert_eq!(Breakpoint::from_width(640.0), Breakpoint::Mobile);
+        assert_eq!(Breakpoint::from_width(641.0), Breakpoint::Tablet);
+        assert_eq!(Breakpoint::from_width(1024.0), Breakpoint::Tablet);
+        assert_eq!(Breakpoint::from_width(1440.0), Breakpoint::Desktop);
+        assert!(Breakpoint::Mobile < Breakpoint::Desktop);
+    }
+}
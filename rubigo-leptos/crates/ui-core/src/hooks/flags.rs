@@ -0,0 +1,121 @@
+//! Feature Flags
+//!
+//! Lets a half-migrated feature ship behind a switch, so the new and old
+//! versions (calendar, Bevy globe vs SVG map) can be swapped at runtime
+//! without a rebuild. The host app loads the flags from the server's
+//! `/api/flags` and provides them once; components ask for one by name.
+//!
+//! ```ignore
+//! // Near the app root
+//! let flags = provide_flags(Vec::new());
+//! // ...once /api/flags answers
+//! flags.replace(loaded);
+//!
+//! // In a component
+//! let new_calendar = use_flag("new_calendar");
+//! view! { <Show when=move || new_calendar.get() fallback=OldCalendar><NewCalendar /></Show> }
+//! ```
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A flag with its current state, as served by `/api/flags`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureFlag {
+    pub name: String,
+    pub description: String,
+    pub enabled: bool,
+    /// State when nobody has toggled it, from code or `rubigo.toml`
+    pub default: bool,
+}
+
+impl FeatureFlag {
+    /// Toggled away from its default at runtime
+    pub fn overridden(&self) -> bool {
+        self.enabled != self.default
+    }
+}
+
+/// Whether `name` is on in `flags`; unknown flags are off
+pub fn is_enabled(flags: &[FeatureFlag], name: &str) -> bool {
+    flags.iter().any(|f| f.name == name && f.enabled)
+}
+
+/// Shared flag state, see [`provide_flags`]
+#[derive(Clone, Copy)]
+pub struct FlagSet {
+    flags: RwSignal<Vec<FeatureFlag>>,
+}
+
+impl FlagSet {
+    /// Replace every flag, e.g. with a fresh `/api/flags` response
+    pub fn replace(&self, flags: Vec<FeatureFlag>) {
+        self.flags.set(flags);
+    }
+
+    /// Update one flag after it was toggled
+    pub fn update(&self, flag: FeatureFlag) {
+        self.flags.update(
+            |flags| match flags.iter_mut().find(|f| f.name == flag.name) {
+                Some(existing) => *existing = flag,
+                None => flags.push(flag),
+            },
+        );
+    }
+
+    /// Whether `name` is on, tracking changes
+    pub fn enabled(&self, name: &str) -> bool {
+        self.flags.with(|flags| is_enabled(flags, name))
+    }
+
+    /// Every flag, tracking changes
+    pub fn all(&self) -> Signal<Vec<FeatureFlag>> {
+        self.flags.into()
+    }
+}
+
+/// Provide feature flags to descendants
+pub fn provide_flags(initial: Vec<FeatureFlag>) -> FlagSet {
+    let set = FlagSet {
+        flags: RwSignal::new(initial),
+    };
+    provide_context(set);
+    set
+}
+
+/// Whether feature `name` is on
+///
+/// Off when no flags were provided, so a component keeps its old behaviour
+/// in hosts that don't know about flags yet.
+pub fn use_flag(name: &'static str) -> Signal<bool> {
+    match use_context::<FlagSet>() {
+        Some(set) => Signal::derive(move || set.enabled(name)),
+        None => Signal::derive(|| false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(name: &str, enabled: bool, default: bool) -> FeatureFlag {
+        FeatureFlag {
+            name: name.to_string(),
+            description: String::new(),
+            enabled,
+            default,
+        }
+    }
+
+    #[test]
+    fn unknown_and_disabled_flags_are_off() {
+        let flags = vec![
+            flag("new_calendar", true, false),
+            flag("bevy_globe", false, true),
+        ];
+        assert!(is_enabled(&flags, "new_calendar"));
+        assert!(!is_enabled(&flags, "bevy_globe"));
+        assert!(!is_enabled(&flags, "missing"));
+        assert!(flags.iter().all(FeatureFlag::overridden));
+    }
+}
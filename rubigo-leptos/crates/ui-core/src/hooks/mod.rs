@@ -0,0 +1,25 @@
+//! UI Hooks
+//!
+//! Reactive helpers shared by components and pages. Unlike the component
+//! modules these render nothing; they set up signals and effects in the
+//! calling component's scope.
+//!
+//! ## Hooks
+//!
+//! - [`use_breakpoint`] - Screen class of the viewport, following resizes
+//! - [`use_flag`] - Runtime feature flag, provided by the host app
+//! - [`use_print_mode`] - Whether the page is being rendered for printing
+//! - [`use_undo`] - Undo/redo for destructive actions, offered via toast
+//! - [`use_url_state`] - Signal mirrored into a URL query parameter
+
+pub mod breakpoint;
+pub mod flags;
+pub mod print;
+pub mod undo;
+pub mod url_state;
+
+pub use breakpoint::{use_breakpoint, Breakpoint, MOBILE_MAX_WIDTH, TABLET_MAX_WIDTH};
+pub use flags::{provide_flags, use_flag, FeatureFlag, FlagSet};
+pub use print::{use_print_mode, PrintMode};
+pub use undo::{provide_undo, use_undo, UndoManager, DEFAULT_UNDO_WINDOW_MS};
+pub use url_state::{use_url_state, use_url_state_with};
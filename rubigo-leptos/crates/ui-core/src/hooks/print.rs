@@ -0,0 +1,80 @@
+//! Print Mode
+//!
+//! Lets pages render a clean copy for paper. While printing, whether from a
+//! [`PrintButton`](crate::elements::PrintButton) or the browser's own Print
+//! command, [`PrintMode::is_active`] is true: the [`Layout`](crate::layout::Layout)
+//! swaps its chrome for a title block and pages drop what only makes sense
+//! on screen, such as pagination. Page breaks and colours are handled by
+//! each module's `@media print` rules.
+//!
+//! ```ignore
+//! let print = use_print_mode();
+//! let rows = move || if print.is_active() { all() } else { current_page() };
+//! ```
+
+use leptos::prelude::*;
+
+/// Shared print state, see [`use_print_mode`]
+#[derive(Clone, Copy)]
+pub struct PrintMode {
+    active: RwSignal<bool>,
+    /// Heading of the printed page, set by [`PrintMode::print`]
+    title: RwSignal<Option<String>>,
+}
+
+impl PrintMode {
+    /// Whether the page is being rendered for printing
+    pub fn is_active(&self) -> bool {
+        self.active.get()
+    }
+
+    /// Heading of the page being printed, if the print was started with one
+    pub fn title(&self) -> Option<String> {
+        self.title.get()
+    }
+
+    /// Render the print layout and open the print dialog
+    ///
+    /// The dialog waits a frame so the print layout is on screen before the
+    /// browser snapshots the page.
+    pub fn print(&self, title: impl Into<String>) {
+        self.title.set(Some(title.into()));
+        self.active.set(true);
+        request_animation_frame(|| {
+            if let Err(e) = window().print() {
+                web_sys::console::warn_2(&"Print failed".into(), &e);
+            }
+        });
+    }
+}
+
+/// Print state for this page
+///
+/// The first call listens for the browser's print events and provides the
+/// state to descendants; later calls below it share it.
+pub fn use_print_mode() -> PrintMode {
+    if let Some(print) = use_context::<PrintMode>() {
+        return print;
+    }
+
+    let print = PrintMode {
+        active: RwSignal::new(false),
+        title: RwSignal::new(None),
+    };
+    let before = window_event_listener(leptos::ev::beforeprint, move |_| {
+        if !print.active.get_untracked() {
+            print.active.set(true);
+        }
+    });
+    let after = window_event_listener(leptos::ev::afterprint, move |_| {
+        print.active.set(false);
+        print.title.set(None);
+    });
+    on_cleanup(move || {
+        before.remove();
+        after.remove();
+    });
+
+    provide_context(print);
+    print
+}
@@ -0,0 +1,277 @@
+//! Undo
+//!
+//! Records reversible actions so a destructive change can be taken back.
+//! Each recorded action raises a toast with an "Undo" button for a few
+//! seconds; pressing it runs the inverse. Undone actions can be redone.
+//!
+//! The caller performs the action itself and hands over both directions,
+//! so one manager covers local edits (removing a participant) as well as
+//! server round trips (deleting an asset, whose inverse dispatches
+//! `asset.restore` before the server purges the soft-deleted record).
+//!
+//! ```ignore
+//! // Once, near the app root, below `provide_toasts`
+//! let undo = provide_undo(DEFAULT_UNDO_WINDOW_MS);
+//! view! { <ToastRegion on_action=undo.toast_handler() /> }
+//!
+//! // After deleting
+//! use_undo().record(
+//!     format!("Deleted {name}"),
+//!     move || restore(&id),
+//!     move || delete(&id),
+//! );
+//! ```
+
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+
+use crate::elements::{use_toasts, Toast, ToastLevel, ToastQueue};
+
+/// How long the Undo toast stays up
+pub const DEFAULT_UNDO_WINDOW_MS: u32 = 8000;
+
+/// Entries kept before the oldest are forgotten
+const HISTORY_LIMIT: usize = 50;
+
+/// A recorded action
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoEntry<T> {
+    pub id: u64,
+    /// Past-tense description, e.g. "Deleted SW-01"
+    pub label: String,
+    pub action: T,
+}
+
+/// Undo and redo stacks
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoHistory<T> {
+    done: Vec<UndoEntry<T>>,
+    undone: Vec<UndoEntry<T>>,
+    limit: usize,
+    next_id: u64,
+}
+
+impl<T: Clone> UndoHistory<T> {
+    /// Empty history remembering at most `limit` actions
+    pub fn new(limit: usize) -> Self {
+        Self {
+            done: Vec::new(),
+            undone: Vec::new(),
+            limit: limit.max(1),
+            next_id: 1,
+        }
+    }
+
+    /// Record a newly performed action, returning its ID
+    ///
+    /// Anything undone earlier can no longer be redone.
+    pub fn record(&mut self, label: impl Into<String>, action: T) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.undone.clear();
+        self.done.push(UndoEntry {
+            id,
+            label: label.into(),
+            action,
+        });
+        if self.done.len() > self.limit {
+            self.done.remove(0);
+        }
+        id
+    }
+
+    /// Undo a specific action, e.g. from its toast
+    pub fn undo(&mut self, id: u64) -> Option<UndoEntry<T>> {
+        let index = self.done.iter().position(|e| e.id == id)?;
+        let entry = self.done.remove(index);
+        self.undone.push(entry.clone());
+        Some(entry)
+    }
+
+    /// Undo the most recent action
+    pub fn undo_last(&mut self) -> Option<UndoEntry<T>> {
+        let id = self.done.last()?.id;
+        self.undo(id)
+    }
+
+    /// Redo the most recently undone action
+    pub fn redo_last(&mut self) -> Option<UndoEntry<T>> {
+        let entry = self.undone.pop()?;
+        self.done.push(entry.clone());
+        Some(entry)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+}
+
+/// Both directions of a recorded action
+#[derive(Clone, Copy)]
+pub struct Reversible {
+    pub undo: Callback<()>,
+    pub redo: Callback<()>,
+}
+
+/// Shared undo manager, see [`provide_undo`]
+#[derive(Clone, Copy)]
+pub struct UndoManager {
+    history: RwSignal<UndoHistory<Reversible>>,
+    /// Toast ID -> entry ID for the Undo buttons on screen
+    toast_entries: StoredValue<HashMap<u64, u64>>,
+    toasts: RwSignal<ToastQueue>,
+    window_ms: u32,
+}
+
+impl UndoManager {
+    /// Record an action that has just been performed and offer to undo it
+    pub fn record(
+        &self,
+        label: impl Into<String>,
+        undo: impl Fn() + Send + Sync + 'static,
+        redo: impl Fn() + Send + Sync + 'static,
+    ) -> u64 {
+        let label = label.into();
+        let action = Reversible {
+            undo: Callback::new(move |_| undo()),
+            redo: Callback::new(move |_| redo()),
+        };
+        let id = self
+            .history
+            .try_update(|h| h.record(label.clone(), action))
+            .unwrap_or_default();
+        let toast = Toast::new(ToastLevel::Info, label)
+            .action("Undo")
+            .duration(self.window_ms);
+        if let Some(toast_id) = self.toasts.try_update(|q| q.push(toast)) {
+            self.toast_entries.update_value(|m| {
+                m.insert(toast_id, id);
+            });
+        }
+        id
+    }
+
+    /// Undo a specific action; `false` if it is unknown or already undone
+    pub fn undo(&self, id: u64) -> bool {
+        self.run(|h| h.undo(id), |a| a.undo)
+    }
+
+    /// Undo the most recent action
+    pub fn undo_last(&self) -> bool {
+        self.run(UndoHistory::undo_last, |a| a.undo)
+    }
+
+    /// Redo the most recently undone action
+    pub fn redo_last(&self) -> bool {
+        self.run(UndoHistory::redo_last, |a| a.redo)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.with(UndoHistory::can_undo)
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.with(UndoHistory::can_redo)
+    }
+
+    /// `on_action` handler for [`ToastRegion`](crate::elements::ToastRegion)
+    /// that undoes the action behind a pressed Undo button
+    pub fn toast_handler(&self) -> Callback<u64> {
+        let manager = *self;
+        Callback::new(move |toast_id: u64| {
+            let entry = manager
+                .toast_entries
+                .try_update_value(|m| m.remove(&toast_id))
+                .flatten();
+            if let Some(id) = entry {
+                manager.undo(id);
+            }
+        })
+    }
+
+    fn run(
+        &self,
+        step: impl FnOnce(&mut UndoHistory<Reversible>) -> Option<UndoEntry<Reversible>>,
+        direction: impl FnOnce(Reversible) -> Callback<()>,
+    ) -> bool {
+        match self.history.try_update(step).flatten() {
+            Some(entry) => {
+                direction(entry.action).run(());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Provide an undo manager to descendants
+///
+/// Needs the toast queue from [`provide_toasts`](crate::elements::provide_toasts).
+pub fn provide_undo(window_ms: u32) -> UndoManager {
+    let manager = UndoManager {
+        history: RwSignal::new(UndoHistory::new(HISTORY_LIMIT)),
+        toast_entries: StoredValue::new(HashMap::new()),
+        toasts: use_toasts(),
+        window_ms,
+    };
+    provide_context(manager);
+    manager
+}
+
+/// Access the manager provided by [`provide_undo`]
+pub fn use_undo() -> UndoManager {
+    expect_context::<UndoManager>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels<T>(entries: &[UndoEntry<T>]) -> Vec<&str> {
+        entries.iter().map(|e| e.label.as_str()).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_move_between_stacks() {
+        let mut history = UndoHistory::new(10);
+        history.record("a", 1);
+        history.record("b", 2);
+
+        assert_eq!(history.undo_last().map(|e| e.action), Some(2));
+        assert_eq!(labels(&history.done), vec!["a"]);
+        assert!(history.can_redo());
+
+        assert_eq!(history.redo_last().map(|e| e.action), Some(2));
+        assert_eq!(labels(&history.done), vec!["a", "b"]);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn specific_entry_can_be_undone_once() {
+        let mut history = UndoHistory::new(10);
+        let a = history.record("a", ());
+        history.record("b", ());
+
+        assert!(history.undo(a).is_some());
+        assert!(history.undo(a).is_none());
+        assert_eq!(labels(&history.done), vec!["b"]);
+    }
+
+    #[test]
+    fn recording_clears_redo_and_respects_limit() {
+        let mut history = UndoHistory::new(2);
+        history.record("a", ());
+        history.record("b", ());
+        history.undo_last();
+        history.record("c", ());
+        history.record("d", ());
+
+        assert!(!history.can_redo());
+        assert_eq!(labels(&history.done), vec!["c", "d"]);
+    }
+}
@@ -0,0 +1,93 @@
+//! URL State
+//!
+//! Keeps a signal and a query parameter in step, so filters, the selected
+//! tab or an open detail panel survive a reload and can be shared as a deep
+//! link. Each change pushes a history entry, letting Back step through
+//! earlier views the same way the gui-server's query-param pages do.
+//!
+//! Must be called inside a `Router`.
+//!
+//! ```ignore
+//! let department = use_url_state("dept", String::new());
+//! // /personnel?dept=Engineering restores the filter on load
+//! ```
+
+use std::str::FromStr;
+
+use leptos::prelude::*;
+use leptos_router::hooks::query_signal_with_options;
+use leptos_router::NavigateOptions;
+
+/// Signal backed by the query parameter `key`
+///
+/// The URL wins on load and on Back/Forward; setting the signal updates the
+/// URL with a new history entry. When the value equals `default` the
+/// parameter is dropped so links stay short. Values that fail to parse fall
+/// back to `default`.
+pub fn use_url_state<T>(key: &'static str, default: T) -> RwSignal<T>
+where
+    T: FromStr + ToString + Clone + PartialEq + Send + Sync + 'static,
+{
+    use_url_state_with(
+        key,
+        default,
+        NavigateOptions {
+            scroll: false,
+            ..Default::default()
+        },
+    )
+}
+
+/// [`use_url_state`] with explicit navigation options
+///
+/// Pass `replace: true` for values that change on every keystroke, such as
+/// a search box, so they don't flood the history.
+pub fn use_url_state_with<T>(key: &'static str, default: T, options: NavigateOptions) -> RwSignal<T>
+where
+    T: FromStr + ToString + Clone + PartialEq + Send + Sync + 'static,
+{
+    let (param, set_param) = query_signal_with_options::<T>(key, options);
+    let default = StoredValue::new(default);
+    let from_url = move |param: Option<T>| param.unwrap_or_else(|| default.get_value());
+
+    let state = RwSignal::new(from_url(param.get_untracked()));
+
+    // URL -> signal: deep links and Back/Forward
+    Effect::new(move |_| {
+        let value = from_url(param.get());
+        if state.with_untracked(|current| *current != value) {
+            state.set(value);
+        }
+    });
+
+    // Signal -> URL, skipped when the URL already says the same thing
+    Effect::new(move |_| {
+        let value = state.get();
+        if from_url(param.get_untracked()) != value {
+            set_param.set(default.with_value(|default| query_value(value, default)));
+        }
+    });
+
+    state
+}
+
+/// Parameter value to write for `value`; `None` removes the parameter
+fn query_value<T: PartialEq>(value: T, default: &T) -> Option<T> {
+    (value != *default).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_left_out_of_the_url() {
+        assert_eq!(query_value(String::new(), &String::new()), None);
+        assert_eq!(
+            query_value("Engineering".to_string(), &String::new()),
+            Some("Engineering".to_string())
+        );
+        assert_eq!(query_value(1usize, &1), None);
+        assert_eq!(query_value(3usize, &1), Some(3));
+    }
+}
@@ -1,9 +1,12 @@
 //! Header Component
 //!
 //! Main application header with Rubigo branding, status, and user controls.
+//! A Help menu shows once help topics or tours are provided.
 
 #![allow(dead_code)]
 
+use crate::elements::{Help, HelpMenu, Tours};
+use crate::features::notifications::{NotificationBell, NotificationFeed};
 use crate::features::user_session::{UserInfo, UserSessionWidget};
 use leptos::prelude::*;
 
@@ -14,34 +17,74 @@ stylance::import_crate_style!(style, "src/layout/header/header.module.css");
 pub enum ConnectionStatus {
     Connected,
     Disconnected,
+    /// Desktop replica trading changes with the server
+    Syncing,
+    /// Desktop replica working locally; `pending` edits await the server
+    Offline {
+        pending: usize,
+    },
+}
+
+impl ConnectionStatus {
+    fn class(self) -> &'static str {
+        match self {
+            ConnectionStatus::Connected => "connected",
+            ConnectionStatus::Disconnected => "disconnected",
+            ConnectionStatus::Syncing => "syncing",
+            ConnectionStatus::Offline { .. } => "offline",
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            ConnectionStatus::Connected => "Connected".to_string(),
+            ConnectionStatus::Disconnected => "Disconnected".to_string(),
+            ConnectionStatus::Syncing => "Syncing".to_string(),
+            ConnectionStatus::Offline { pending: 0 } => "Offline".to_string(),
+            ConnectionStatus::Offline { pending } => format!("Offline · {} pending", pending),
+        }
+    }
 }
 
 /// Header component with Rubigo branding
 #[component]
 pub fn Header(
     /// Connection status
-    #[prop(default = ConnectionStatus::Connected)]
-    status: ConnectionStatus,
+    #[prop(into, default = ConnectionStatus::Connected.into())]
+    status: Signal<ConnectionStatus>,
     /// Current user (if authenticated)
     current_user: Option<UserInfo>,
     /// Callback to open persona switcher
     on_switch_identity: Callback<()>,
     /// Callback to sign out
     on_sign_out: Callback<()>,
+    /// Notification inbox for the signed-in user
+    #[prop(default = None)]
+    notifications: Option<NotificationFeed>,
+    /// Opens the navigation drawer; adds a menu button to the header
+    #[prop(default = None)]
+    on_menu: Option<Callback<()>>,
+    /// Whether the menu button is shown, e.g. only on phone widths
+    #[prop(into, default = true.into())]
+    show_menu: Signal<bool>,
 ) -> impl IntoView {
-    let status_class = match status {
-        ConnectionStatus::Connected => format!("{} connected", style::status_indicator),
-        ConnectionStatus::Disconnected => format!("{} disconnected", style::status_indicator),
-    };
-
-    let status_text = match status {
-        ConnectionStatus::Connected => "Connected",
-        ConnectionStatus::Disconnected => "Disconnected",
-    };
+    let status_class = move || format!("{} {}", style::status_indicator, status.get().class());
+    let status_text = move || status.get().label();
 
     view! {
         <header class=style::header>
             <div class=style::header_left>
+                {on_menu.map(|on_menu| view! {
+                    <Show when=move || show_menu.get()>
+                        <button
+                            class=style::menu_button
+                            aria-label="Open navigation"
+                            on:click=move |_| on_menu.run(())
+                        >
+                            "☰"
+                        </button>
+                    </Show>
+                })}
                 <a href="/" class=style::header_logo>
                     // Rubigo Logo SVG (inline for simplicity)
                     <svg class=style::logo_icon width="28" height="28" viewBox="0 0 512 512" fill="none" xmlns="http://www.w3.org/2000/svg">
@@ -78,9 +121,13 @@ pub fn Header(
             <div class=style::header_right>
                 <div class=status_class>
                     <span class=style::status_dot></span>
-                    {status_text}
+                    <span class=style::status_label>{status_text}</span>
                 </div>
 
+                {(use_context::<Help>().is_some() || use_context::<Tours>().is_some()).then(|| view! { <HelpMenu /> })}
+
+                {notifications.map(|feed| view! { <NotificationBell feed=feed /> })}
+
                 {move || {
                     if let Some(user) = current_user.clone() {
                         view! {
@@ -107,4 +154,14 @@ mod tests {
     fn connection_status_variants() {
         assert_ne!(ConnectionStatus::Connected, ConnectionStatus::Disconnected);
     }
+
+    #[test]
+    fn offline_label_counts_pending_edits() {
+        assert_eq!(ConnectionStatus::Offline { pending: 0 }.label(), "Offline");
+        assert_eq!(
+            ConnectionStatus::Offline { pending: 3 }.label(),
+            "Offline · 3 pending"
+        );
+        assert_eq!(ConnectionStatus::Offline { pending: 3 }.class(), "offline");
+    }
 }
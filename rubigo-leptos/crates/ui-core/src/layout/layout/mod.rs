@@ -1,14 +1,22 @@
 //! Layout Component
 //!
 //! Main application layout with header, sidebar, and content area.
+//!
+//! The sidebar follows [`use_breakpoint`]: full on desktops, an icon rail on
+//! tablets, and on phones either a drawer behind the header's menu button or
+//! a [`BottomNav`], as chosen by `mobile_nav`. Printed, only the content is
+//! kept, under a [`PrintHeader`].
 
 use leptos::prelude::*;
 
 stylance::import_crate_style!(style, "src/layout/layout/layout.module.css");
 
 use super::header::{ConnectionStatus, Header};
-use super::sidebar::{NavItem, Sidebar};
+use super::sidebar::{BottomNav, MobileNav, NavItem, Sidebar, SidebarVariant};
+use crate::elements::PrintHeader;
+use crate::features::notifications::NotificationFeed;
 use crate::features::user_session::UserInfo;
+use crate::hooks::{use_breakpoint, use_print_mode, Breakpoint};
 
 /// Main application layout
 #[component]
@@ -16,37 +24,93 @@ pub fn Layout(
     /// Navigation items for sidebar
     nav_items: Vec<NavItem>,
     /// Connection status
-    #[prop(default = ConnectionStatus::Connected)]
-    status: ConnectionStatus,
+    #[prop(into, default = ConnectionStatus::Connected.into())]
+    status: Signal<ConnectionStatus>,
     /// Current user (if authenticated)
     current_user: Option<UserInfo>,
     /// Callback to open persona switcher
     on_switch_identity: Callback<()>,
     /// Callback to sign out
     on_sign_out: Callback<()>,
+    /// Notification inbox for the signed-in user
+    #[prop(default = None)]
+    notifications: Option<NotificationFeed>,
+    /// Navigation on phone widths
+    #[prop(optional)]
+    mobile_nav: MobileNav,
     /// Page content
     children: Children,
 ) -> impl IntoView {
+    // Both are shared with the pages below
+    let breakpoint = use_breakpoint();
+    use_print_mode();
+    let drawer_open = RwSignal::new(false);
+    let is_mobile = move || breakpoint.get().is_mobile();
+
+    // A drawer left open shouldn't reappear after widening the window
+    Effect::new(move |_| {
+        if !is_mobile() {
+            drawer_open.set(false);
+        }
+    });
+
+    let variant = Signal::derive(move || match breakpoint.get() {
+        Breakpoint::Desktop => SidebarVariant::Full,
+        Breakpoint::Tablet => SidebarVariant::Rail,
+        Breakpoint::Mobile => SidebarVariant::Drawer,
+    });
+    let show_sidebar = move || !is_mobile() || drawer_open.get();
+    let close_drawer = Callback::new(move |_| drawer_open.set(false));
+    let on_menu =
+        (mobile_nav == MobileNav::Drawer).then(|| Callback::new(move |_| drawer_open.set(true)));
+    let show_bottom_nav = move || is_mobile() && mobile_nav == MobileNav::BottomNav;
+    let layout_class = move || {
+        if show_bottom_nav() {
+            format!("{} {}", style::app_layout, style::with_bottom_nav)
+        } else {
+            style::app_layout.to_string()
+        }
+    };
+    let bottom_items = nav_items.clone();
+
     view! {
-        <div class=style::app_layout>
+        <div class=layout_class>
             <Header
                 status=status
                 current_user=current_user
                 on_switch_identity=on_switch_identity
                 on_sign_out=on_sign_out
+                notifications=notifications
+                on_menu=on_menu
+                show_menu=Signal::derive(is_mobile)
             />
 
             <div class=style::layout_body>
-                <Sidebar
-                    items=nav_items
-                />
+                <Show when=show_sidebar>
+                    <Sidebar
+                        items=nav_items.clone()
+                        variant=variant
+                        on_navigate=close_drawer
+                    />
+                </Show>
+                <Show when=move || drawer_open.get()>
+                    <div
+                        class=style::drawer_backdrop
+                        on:click=move |_| drawer_open.set(false)
+                    ></div>
+                </Show>
 
                 <main class=style::layout_main>
                     <div class=style::layout_content>
+                        <PrintHeader />
                         {children()}
                     </div>
                 </main>
             </div>
+
+            <Show when=show_bottom_nav>
+                <BottomNav items=bottom_items.clone() />
+            </Show>
         </div>
     }
 }
@@ -8,4 +8,4 @@ pub mod sidebar;
 
 pub use header::{ConnectionStatus, Header};
 pub use layout::Layout;
-pub use sidebar::{NavItem, Sidebar};
+pub use sidebar::{BottomNav, MobileNav, NavItem, Sidebar, SidebarVariant, BOTTOM_NAV_MAX_ITEMS};
@@ -1,6 +1,8 @@
 //! Sidebar Component
 //!
-//! Navigation sidebar with router-aware active state.
+//! Navigation sidebar with router-aware active state. On narrow screens the
+//! [`Layout`](super::Layout) shows it as an icon rail, a slide-in drawer or,
+//! in its place, a [`BottomNav`].
 
 #![allow(dead_code)]
 
@@ -19,27 +21,65 @@ pub struct NavItem {
     pub href: &'static str,
 }
 
+/// How the sidebar is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SidebarVariant {
+    /// Icons and labels, beside the content
+    #[default]
+    Full,
+    /// Icons only, for tablet widths
+    Rail,
+    /// Slid in over the content from the hamburger button
+    Drawer,
+}
+
+/// Navigation on phone widths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MobileNav {
+    /// Hamburger button in the header opening the sidebar as a drawer
+    #[default]
+    Drawer,
+    /// Tab bar along the bottom of the screen
+    BottomNav,
+}
+
+/// Items shown in the bottom nav; more would be too narrow to tap
+pub const BOTTOM_NAV_MAX_ITEMS: usize = 5;
+
+/// Whether `href` is the page at `path` or one of its sub-pages
+fn is_active(href: &str, path: &str) -> bool {
+    if href == "/" {
+        path == "/"
+    } else {
+        path.starts_with(href)
+    }
+}
+
 /// Sidebar navigation component
 #[component]
 pub fn Sidebar(
     /// List of navigation items
     items: Vec<NavItem>,
+    /// Full, icon rail or drawer
+    #[prop(into, default = SidebarVariant::Full.into())]
+    variant: Signal<SidebarVariant>,
+    /// Called after an item is chosen, e.g. to close the drawer
+    #[prop(optional)]
+    on_navigate: Option<Callback<()>>,
 ) -> impl IntoView {
     let location = use_location();
+    let sidebar_class = move || match variant.get() {
+        SidebarVariant::Full => style::sidebar.to_string(),
+        SidebarVariant::Rail => format!("{} {}", style::sidebar, style::sidebar_rail),
+        SidebarVariant::Drawer => format!("{} {}", style::sidebar, style::sidebar_drawer),
+    };
 
     view! {
-        <aside class=style::sidebar>
+        <aside class=sidebar_class>
             <nav class=style::sidebar_nav>
                 {items.into_iter().map(|item| {
                     let href = item.href;
-                    let is_active = move || {
-                        let path = location.pathname.get();
-                        if href == "/" {
-                            path == "/"
-                        } else {
-                            path.starts_with(href)
-                        }
-                    };
+                    let is_active = move || location.pathname.with(|path| is_active(href, path));
 
                     view! {
                         <A
@@ -52,6 +92,12 @@ pub fn Sidebar(
                                     style::nav_item.to_string()
                                 }
                             }
+                            title=item.label
+                            on:click=move |_| {
+                                if let Some(cb) = on_navigate {
+                                    cb.run(());
+                                }
+                            }
                         >
                             <span class=style::nav_icon>{item.icon}</span>
                             <span class=style::nav_label>{item.label}</span>
@@ -67,6 +113,42 @@ pub fn Sidebar(
     }
 }
 
+/// Tab bar of the first [`BOTTOM_NAV_MAX_ITEMS`] navigation items, fixed to
+/// the bottom of the screen
+#[component]
+pub fn BottomNav(
+    /// List of navigation items
+    items: Vec<NavItem>,
+) -> impl IntoView {
+    let location = use_location();
+
+    view! {
+        <nav class=style::bottom_nav>
+            {items.into_iter().take(BOTTOM_NAV_MAX_ITEMS).map(|item| {
+                let href = item.href;
+                let is_active = move || location.pathname.with(|path| is_active(href, path));
+
+                view! {
+                    <A
+                        href=item.href
+                        {..}
+                        class=move || {
+                            if is_active() {
+                                format!("{} {}", style::bottom_nav_item, style::bottom_nav_item_active)
+                            } else {
+                                style::bottom_nav_item.to_string()
+                            }
+                        }
+                    >
+                        <span class=style::nav_icon>{item.icon}</span>
+                        <span class=style::bottom_nav_label>{item.label}</span>
+                    </A>
+                }
+            }).collect::<Vec<_>>()}
+        </nav>
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +163,12 @@ mod tests {
         };
         assert_eq!(item.id, "home");
     }
+
+    #[test]
+    fn home_is_only_active_on_the_root() {
+        assert!(is_active("/", "/"));
+        assert!(!is_active("/", "/calendar"));
+        assert!(is_active("/personnel", "/personnel/42"));
+        assert!(!is_active("/personnel", "/sites"));
+    }
 }
@@ -7,15 +7,28 @@
 //! - `elements` - Composed components (Card, Table, Modal, Tabs)
 //! - `layout` - Structural components (Header, Sidebar, Layout)
 //! - `features` - Domain-specific compositions (Personnel, Assets, etc.)
+//! - `hooks` - Reactive helpers such as URL-synced state
 //! - `pages` - Full page layouts
+//! - `utils` - Clipboard and share-link helpers
+//! - `recorder` - Opt-in session recording and replay for bug reports
+//! - `worker` - Runs expensive tasks on a web worker
+//! - `testing` - Mock broker and DOM helpers for component tests
+//!   (`testing` feature)
 
 pub mod elements;
 pub mod features;
+pub mod hooks;
 pub mod layout;
 pub mod primitives;
+pub mod recorder;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod utils;
+pub mod worker;
 
 // Re-export commonly used items
 pub use elements::*;
 pub use features::*;
+pub use hooks::*;
 pub use layout::*;
 pub use primitives::*;
@@ -13,6 +13,8 @@ pub mod input;
 pub mod person_search;
 pub mod search_input;
 pub mod select;
+pub mod skeleton;
+pub mod tag_picker;
 pub mod time_input;
 pub mod timezone_select;
 
@@ -27,6 +29,8 @@ pub use input::{Input, InputSize, InputType};
 pub use person_search::{PersonOption, PersonSearch};
 pub use search_input::SearchInput;
 pub use select::{Select, SelectOption, SelectSize};
+pub use skeleton::{Skeleton, SkeletonShape};
+pub use tag_picker::{TagChip, TagOption, TagPicker};
 pub use time_input::TimeInput;
 pub use timezone_select::{
     get_browser_timezone, timezone_display_name, timezone_full_display, timezone_offset_minutes,
@@ -2,6 +2,7 @@
 //!
 //! A searchable dropdown for selecting multiple people from a list.
 
+use crate::hooks::UndoManager;
 use leptos::prelude::*;
 
 stylance::import_crate_style!(
@@ -95,13 +96,54 @@ pub fn PersonSearch(
         }
     };
 
+    // Removals can be undone when the app provides an undo manager
+    let undo = use_context::<UndoManager>();
+    let people_for_undo = StoredValue::new(people.clone());
+
     let handle_remove = move |person_id: String| {
+        let Some(index) = selected.with_untracked(|ids| ids.iter().position(|id| *id == person_id))
+        else {
+            return;
+        };
         selected.update(|ids| {
-            ids.retain(|id| id != &person_id);
+            ids.remove(index);
         });
         if let Some(cb) = on_change {
             cb.run(selected.get());
         }
+
+        if let Some(undo) = undo {
+            let name = people_for_undo
+                .with_value(|people| {
+                    people
+                        .iter()
+                        .find(|p| p.id == person_id)
+                        .map(|p| p.name.clone())
+                })
+                .unwrap_or_else(|| person_id.clone());
+            // The picker may be gone by the time Undo is pressed
+            let notify = move || {
+                if let (Some(cb), Some(ids)) = (on_change, selected.try_get_untracked()) {
+                    cb.run(ids);
+                }
+            };
+            let restore_id = person_id.clone();
+            undo.record(
+                format!("Removed {}", name),
+                move || {
+                    selected.try_update(|ids| {
+                        if !ids.contains(&restore_id) {
+                            ids.insert(index.min(ids.len()), restore_id.clone());
+                        }
+                    });
+                    notify();
+                },
+                move || {
+                    selected.try_update(|ids| ids.retain(|id| *id != person_id));
+                    notify();
+                },
+            );
+        }
     };
 
     let handle_input = move |ev: web_sys::Event| {
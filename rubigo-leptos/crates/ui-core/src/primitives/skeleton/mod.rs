@@ -0,0 +1,83 @@
+//! Skeleton Component
+//!
+//! A shimmering placeholder shown in place of content that is still loading.
+
+use leptos::prelude::*;
+
+stylance::import_crate_style!(style, "src/primitives/skeleton/skeleton.module.css");
+
+/// Skeleton shape
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SkeletonShape {
+    /// A line of text
+    Text,
+    /// A card, chart or canvas
+    #[default]
+    Block,
+    /// An avatar or icon
+    Circle,
+}
+
+impl SkeletonShape {
+    fn class_name(&self) -> &'static str {
+        match self {
+            SkeletonShape::Text => style::skeleton_text,
+            SkeletonShape::Block => style::skeleton_block,
+            SkeletonShape::Circle => style::skeleton_circle,
+        }
+    }
+}
+
+/// Loading placeholder
+///
+/// # Example
+/// ```ignore
+/// use ui_core::primitives::{Skeleton, SkeletonShape};
+/// use leptos::prelude::*;
+///
+/// view! {
+///     <Skeleton shape=SkeletonShape::Block height="320px" label="Loading globe" />
+/// }
+/// ```
+#[component]
+pub fn Skeleton(
+    /// Shape of the placeholder
+    #[prop(default = SkeletonShape::Block)]
+    shape: SkeletonShape,
+    /// CSS width; fills the container by default
+    #[prop(into, default = "100%".to_string())]
+    width: String,
+    /// CSS height; text lines default to one line
+    #[prop(optional, into)]
+    height: Option<String>,
+    /// Announced to screen readers while loading
+    #[prop(into, default = "Loading".to_string())]
+    label: String,
+) -> impl IntoView {
+    let class = format!("{} {}", style::skeleton, shape.class_name());
+    let style = match height {
+        Some(height) => format!("width: {}; height: {};", width, height),
+        None => format!("width: {};", width),
+    };
+
+    view! {
+        <div class=class style=style role="status" aria-busy="true" aria-label=label></div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skeleton_shape_returns_class() {
+        assert!(!SkeletonShape::Text.class_name().is_empty());
+        assert!(!SkeletonShape::Block.class_name().is_empty());
+        assert!(!SkeletonShape::Circle.class_name().is_empty());
+    }
+
+    #[test]
+    fn skeleton_shape_default() {
+        assert_eq!(SkeletonShape::default(), SkeletonShape::Block);
+    }
+}
@@ -0,0 +1,236 @@
+//! Tag Picker Component
+//!
+//! Colored tag chips with a menu to add more. Used to tag a record and to
+//! pick the tags a list is filtered by. With `on_create`, a name typed into
+//! the menu's field that matches no tag can be added as a new tag.
+
+use leptos::prelude::*;
+
+stylance::import_crate_style!(
+    #[allow(dead_code)]
+    style,
+    "src/primitives/tag_picker/tag_picker.module.css"
+);
+
+/// A tag that can be picked
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagOption {
+    pub id: String,
+    pub name: String,
+    /// CSS color, e.g. `#4caf50`
+    pub color: String,
+}
+
+impl TagOption {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, color: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            color: color.into(),
+        }
+    }
+}
+
+/// Tags in `tags` whose IDs are in `ids`, in the order of `ids`
+pub fn tags_by_id(tags: &[TagOption], ids: &[String]) -> Vec<TagOption> {
+    ids.iter()
+        .filter_map(|id| tags.iter().find(|t| &t.id == id).cloned())
+        .collect()
+}
+
+/// Tags not yet picked whose name contains `query`, ignoring case
+fn addable(tags: &[TagOption], selected: &[String], query: &str) -> Vec<TagOption> {
+    let query = query.trim().to_lowercase();
+    tags.iter()
+        .filter(|t| !selected.contains(&t.id))
+        .filter(|t| t.name.to_lowercase().contains(&query))
+        .cloned()
+        .collect()
+}
+
+/// Tag chip, as shown by the picker and in tagged lists
+#[component]
+pub fn TagChip(
+    tag: TagOption,
+    /// Shows a remove button calling this
+    #[prop(optional)]
+    on_remove: Option<Callback<()>>,
+) -> impl IntoView {
+    let name = tag.name.clone();
+    view! {
+        <span class=style::chip style=format!("--tag-color: {}", tag.color)>
+            <span class=style::chip_dot aria-hidden="true"></span>
+            {tag.name}
+            {on_remove.map(|remove| view! {
+                <button
+                    type="button"
+                    class=style::chip_remove
+                    aria-label=format!("Remove {name}")
+                    on:click=move |ev| {
+                        ev.stop_propagation();
+                        remove.run(());
+                    }
+                >
+                    "×"
+                </button>
+            })}
+        </span>
+    }
+}
+
+/// Multi-select tag picker
+#[component]
+pub fn TagPicker(
+    /// Tags that can be picked
+    #[prop(into)]
+    tags: Signal<Vec<TagOption>>,
+    /// Picked tag IDs
+    selected: RwSignal<Vec<String>>,
+    /// Text of the add button
+    #[prop(default = "Add tag")]
+    placeholder: &'static str,
+    /// Callback when the selection changes
+    #[prop(optional)]
+    on_change: Option<Callback<Vec<String>>>,
+    /// Called with the name of a new tag to create; the host adds it to
+    /// `tags` (and may select it)
+    #[prop(optional)]
+    on_create: Option<Callback<String>>,
+) -> impl IntoView {
+    let open = RwSignal::new(false);
+    let query = RwSignal::new(String::new());
+
+    let set_selected = move |ids: Vec<String>| {
+        selected.set(ids.clone());
+        if let Some(callback) = on_change {
+            callback.run(ids);
+        }
+    };
+    let pick = move |id: String| {
+        let mut ids = selected.get_untracked();
+        if !ids.contains(&id) {
+            ids.push(id);
+            set_selected(ids);
+        }
+        query.set(String::new());
+        open.set(false);
+    };
+    let unpick = move |id: String| {
+        let ids = selected
+            .get_untracked()
+            .into_iter()
+            .filter(|s| *s != id)
+            .collect();
+        set_selected(ids);
+    };
+
+    let options = move || tags.with(|tags| addable(tags, &selected.get(), &query.get()));
+    let new_name = move || {
+        let name = query.get().trim().to_string();
+        let exists = tags.with(|tags| tags.iter().any(|t| t.name.eq_ignore_ascii_case(&name)));
+        (on_create.is_some() && !name.is_empty() && !exists).then_some(name)
+    };
+    let create = move || {
+        if let (Some(create), Some(name)) = (on_create, new_name()) {
+            create.run(name);
+            query.set(String::new());
+            open.set(false);
+        }
+    };
+
+    view! {
+        <div class=style::tag_picker>
+            {move || {
+                tags.with(|tags| tags_by_id(tags, &selected.get()))
+                    .into_iter()
+                    .map(|tag| {
+                        let id = tag.id.clone();
+                        view! { <TagChip tag=tag on_remove=Callback::new(move |_| unpick(id.clone())) /> }
+                    })
+                    .collect_view()
+            }}
+            <div class=style::add>
+                <button
+                    type="button"
+                    class=style::add_button
+                    aria-expanded=move || open.get().to_string()
+                    on:click=move |_| open.update(|o| *o = !*o)
+                >
+                    {format!("+ {placeholder}")}
+                </button>
+                <Show when=move || open.get()>
+                    <div class=style::menu role="listbox">
+                        <input
+                            type="text"
+                            class=style::menu_search
+                            placeholder="Find a tag"
+                            prop:value=move || query.get()
+                            on:input=move |ev| query.set(event_target_value(&ev))
+                            on:keydown=move |ev: web_sys::KeyboardEvent| match ev.key().as_str() {
+                                "Enter" => {
+                                    ev.prevent_default();
+                                    match options().into_iter().next() {
+                                        Some(tag) => pick(tag.id),
+                                        None => create(),
+                                    }
+                                }
+                                "Escape" => open.set(false),
+                                _ => {}
+                            }
+                        />
+                        {move || options().into_iter().map(|tag| {
+                            let id = tag.id.clone();
+                            view! {
+                                <button
+                                    type="button"
+                                    class=style::menu_option
+                                    role="option"
+                                    on:click=move |_| pick(id.clone())
+                                >
+                                    <TagChip tag=tag />
+                                </button>
+                            }
+                        }).collect_view()}
+                        {move || new_name().map(|name| view! {
+                            <button type="button" class=style::menu_option on:click=move |_| create()>
+                                {format!("Create \"{name}\"")}
+                            </button>
+                        })}
+                    </div>
+                </Show>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags() -> Vec<TagOption> {
+        vec![
+            TagOption::new("1", "Critical", "#ef4444"),
+            TagOption::new("2", "Spare", "#10b981"),
+            TagOption::new("3", "Remote", "#6366f1"),
+        ]
+    }
+
+    #[test]
+    fn picked_tags_keep_selection_order() {
+        let picked = tags_by_id(
+            &tags(),
+            &["3".to_string(), "missing".to_string(), "1".to_string()],
+        );
+        let names: Vec<&str> = picked.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Remote", "Critical"]);
+    }
+
+    #[test]
+    fn addable_tags_exclude_picked_and_match_query() {
+        let options = addable(&tags(), &["1".to_string()], "");
+        assert_eq!(options.len(), 2);
+        let options = addable(&tags(), &[], "re");
+        let names: Vec<&str> = options.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Remote"]);
+    }
+}
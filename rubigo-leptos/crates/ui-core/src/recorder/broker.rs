@@ -0,0 +1,95 @@
+//! Broker wrapper that records dispatched actions
+
+use actions::{Action, ActionBroker, ActionError};
+use async_trait::async_trait;
+
+use super::{Recorder, SessionEvent};
+
+/// Passes actions through to `inner`, logging each with its response while
+/// a recorder is attached
+pub struct RecordingBroker<B> {
+    inner: B,
+    recorder: Option<Recorder>,
+}
+
+impl<B: ActionBroker> RecordingBroker<B> {
+    /// Wrap `inner`; with no recorder this is a plain pass-through
+    pub fn new(inner: B, recorder: Option<Recorder>) -> Self {
+        Self { inner, recorder }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+#[async_trait(?Send)]
+impl<B: ActionBroker> ActionBroker for RecordingBroker<B> {
+    async fn dispatch<A: Action>(&self, action: A) -> Result<A::Response, ActionError> {
+        let Some(recorder) = &self.recorder else {
+            return self.inner.dispatch(action).await;
+        };
+
+        let action_type = action.action_type().to_string();
+        let payload = serde_json::to_value(&action).unwrap_or_default();
+        let result = self.inner.dispatch(action).await;
+        let response = match &result {
+            Ok(response) => serde_json::to_value(response)
+                .map_err(|e| ActionError::Serialization(e.to_string())),
+            Err(e) => Err(e.clone()),
+        };
+        recorder.record(SessionEvent::Action {
+            action_type,
+            payload,
+            response,
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBroker;
+    use actions::{PersonnelAction, PersonnelResponse};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    fn ready<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("broker future was pending"),
+        }
+    }
+
+    #[test]
+    fn records_actions_with_their_responses() {
+        let mock = MockBroker::new();
+        mock.respond("personnel.list", PersonnelResponse::List(vec![]));
+        let recorder = Recorder::new();
+        let broker = RecordingBroker::new(mock.clone(), Some(recorder.clone()));
+
+        ready(broker.dispatch(PersonnelAction::List(Default::default()))).unwrap();
+        ready(broker.dispatch(PersonnelAction::Get("p1".into()))).unwrap_err();
+
+        let entries = recorder.session().entries;
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            &entries[0].event,
+            SessionEvent::Action { action_type, response: Ok(_), .. } if action_type == "personnel.list"
+        ));
+        assert!(matches!(
+            &entries[1].event,
+            SessionEvent::Action {
+                response: Err(ActionError::Transport(_)),
+                ..
+            }
+        ));
+
+        // Without a recorder nothing is kept, but actions still go through
+        let plain = RecordingBroker::new(mock.clone(), None);
+        ready(plain.dispatch(PersonnelAction::List(Default::default()))).unwrap();
+        assert_eq!(mock.dispatched().len(), 3);
+    }
+}
@@ -0,0 +1,295 @@
+//! Session Recording
+//!
+//! Opt-in capture of what happened in a session, to reproduce a bug report
+//! without guessing at the steps. While a [`Recorder`] is provided it logs:
+//!
+//! - every action dispatched through a [`RecordingBroker`], with the
+//!   response the app received
+//! - route changes, via [`RecordRoutes`]
+//! - events components report with [`Recorder::event`]
+//!
+//! The log downloads as a JSON [`SessionFile`]. [`Replay`] re-drives a fresh
+//! app instance from one: its broker answers actions with the recorded
+//! responses, and the route changes and component events are fed back in
+//! order. Anything the fresh instance dispatches differently is reported as
+//! a [`Divergence`].
+//!
+//! ```ignore
+//! // Near the app root, only when the user asked for it
+//! if recording_requested() {
+//!     provide_recorder();
+//! }
+//! let broker = RecordingBroker::new(HttpBroker::from_origin(), use_recorder());
+//! view! { <Router><RecordRoutes /> ... </Router> }
+//!
+//! // In a component
+//! if let Some(recorder) = use_recorder() {
+//!     recorder.event("asset_table", "sort", &column);
+//! }
+//!
+//! // In a browser test
+//! let replay = Replay::new(SessionFile::from_json(include_str!("bug-123.json"))?);
+//! let mounted = mount(move || view! { <App broker=replay.broker() /> });
+//! replay.run(|step| drive(&mounted, step)).await;
+//! assert!(replay.divergences().is_empty());
+//! ```
+
+mod broker;
+mod replay;
+
+pub use broker::RecordingBroker;
+pub use replay::{Divergence, Replay, ReplayBroker};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use chrono::{DateTime, Utc};
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Format of [`SessionFile`]; bumped when old files can no longer be read
+pub const SESSION_FORMAT: u32 = 1;
+
+/// Something that happened during a recorded session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// An action sent to the broker and what came back
+    Action {
+        action_type: String,
+        payload: Value,
+        response: Result<Value, actions::ActionError>,
+    },
+    /// The router moved to `path`, including any query string
+    Route { path: String },
+    /// A component reported an interaction
+    Component {
+        source: String,
+        name: String,
+        #[serde(default)]
+        detail: Value,
+    },
+}
+
+/// One [`SessionEvent`] with when it happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEntry {
+    /// Milliseconds since recording started
+    pub at_ms: i64,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
+
+/// A recorded session, as downloaded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionFile {
+    pub format: u32,
+    pub started_at: DateTime<Utc>,
+    pub entries: Vec<SessionEntry>,
+}
+
+impl SessionFile {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            format: SESSION_FORMAT,
+            started_at,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Read a downloaded session, refusing files from a newer format
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let file: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if file.format > SESSION_FORMAT {
+            return Err(format!(
+                "Session format {} is newer than supported ({})",
+                file.format, SESSION_FORMAT
+            ));
+        }
+        Ok(file)
+    }
+}
+
+/// Shared session log, see [`provide_recorder`]
+///
+/// Cheap to clone; clones append to the same session.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    session: Rc<RefCell<SessionFile>>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// Start recording now
+    pub fn new() -> Self {
+        Self {
+            session: Rc::new(RefCell::new(SessionFile::new(Utc::now()))),
+        }
+    }
+
+    pub fn record(&self, event: SessionEvent) {
+        let mut session = self.session.borrow_mut();
+        let at_ms = (Utc::now() - session.started_at).num_milliseconds();
+        session.entries.push(SessionEntry { at_ms, event });
+    }
+
+    /// Record a route change, skipping repeats of the current route
+    pub fn route(&self, path: impl Into<String>) {
+        let path = path.into();
+        let repeat = self
+            .session
+            .borrow()
+            .entries
+            .iter()
+            .rev()
+            .find_map(|e| match &e.event {
+                SessionEvent::Route { path } => Some(path.clone()),
+                _ => None,
+            });
+        if repeat.as_deref() != Some(path.as_str()) {
+            self.record(SessionEvent::Route { path });
+        }
+    }
+
+    /// Record an interaction reported by a component
+    ///
+    /// `source` names the component and `name` what happened; `detail`
+    /// carries whatever is needed to repeat it.
+    pub fn event(&self, source: &str, name: &str, detail: impl Serialize) {
+        self.record(SessionEvent::Component {
+            source: source.to_string(),
+            name: name.to_string(),
+            detail: serde_json::to_value(detail).unwrap_or_default(),
+        });
+    }
+
+    /// Copy of the session so far
+    pub fn session(&self) -> SessionFile {
+        self.session.borrow().clone()
+    }
+
+    /// Discard what was recorded and start over
+    pub fn restart(&self) {
+        *self.session.borrow_mut() = SessionFile::new(Utc::now());
+    }
+
+    /// Save the session as a file through the browser
+    pub fn download(&self, filename: &str) -> Result<(), wasm_bindgen::JsValue> {
+        use wasm_bindgen::JsCast;
+
+        let json = self.session().to_json();
+        let parts = js_sys::Array::of1(&json.into());
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type("application/json");
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+        let link: web_sys::HtmlAnchorElement = document().create_element("a")?.unchecked_into();
+        link.set_href(&url);
+        link.set_download(filename);
+        link.click();
+        web_sys::Url::revoke_object_url(&url)
+    }
+}
+
+/// Start recording and share the recorder with descendants
+pub fn provide_recorder() -> Recorder {
+    let recorder = Recorder::new();
+    provide_context(SendWrapper::new(recorder.clone()));
+    recorder
+}
+
+/// The session recorder, if recording was turned on
+pub fn use_recorder() -> Option<Recorder> {
+    use_context::<SendWrapper<Recorder>>().map(SendWrapper::take)
+}
+
+/// Records route changes while a recorder is provided; place inside the
+/// `<Router>`
+#[component]
+pub fn RecordRoutes() -> impl IntoView {
+    if let Some(recorder) = use_recorder() {
+        let location = leptos_router::hooks::use_location();
+        Effect::new(move |_| {
+            let search = location.search.get();
+            let path = match search.as_str() {
+                "" => location.pathname.get(),
+                s if s.starts_with('?') => format!("{}{}", location.pathname.get(), s),
+                s => format!("{}?{}", location.pathname.get(), s),
+            };
+            recorder.route(path);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_events_and_skips_repeated_routes() {
+        let recorder = Recorder::new();
+        recorder.route("/assets");
+        recorder.route("/assets");
+        recorder.event("asset_table", "sort", "name");
+        recorder.route("/assets");
+        recorder.route("/people?q=ada");
+
+        let events: Vec<SessionEvent> = recorder
+            .session()
+            .entries
+            .into_iter()
+            .map(|e| e.event)
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[1],
+            SessionEvent::Component {
+                source: "asset_table".into(),
+                name: "sort".into(),
+                detail: "name".into(),
+            }
+        );
+        assert_eq!(
+            events[2],
+            SessionEvent::Route {
+                path: "/people?q=ada".into()
+            }
+        );
+
+        recorder.restart();
+        assert!(recorder.session().entries.is_empty());
+    }
+
+    #[test]
+    fn session_files_round_trip() {
+        let recorder = Recorder::new();
+        recorder.record(SessionEvent::Action {
+            action_type: "asset.get".into(),
+            payload: serde_json::json!({"Get": "a1"}),
+            response: Err(actions::ActionError::Server("HTTP 500".into())),
+        });
+        recorder.route("/");
+        let session = recorder.session();
+
+        let json = session.to_json();
+        assert!(json.contains("\"kind\": \"action\""));
+        assert_eq!(SessionFile::from_json(&json).unwrap(), session);
+
+        let newer = json.replacen("\"format\": 1", "\"format\": 99", 1);
+        assert!(SessionFile::from_json(&newer)
+            .unwrap_err()
+            .contains("newer"));
+    }
+}
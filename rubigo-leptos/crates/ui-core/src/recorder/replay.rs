@@ -0,0 +1,245 @@
+//! Replaying a recorded session against a fresh app instance
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use actions::{Action, ActionBroker, ActionError};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{SessionEvent, SessionFile};
+
+/// Where a replay did not go as recorded
+#[derive(Debug, Clone, PartialEq)]
+pub enum Divergence {
+    /// The action was dispatched with a different payload than recorded
+    Payload {
+        action_type: String,
+        recorded: Value,
+        replayed: Value,
+    },
+    /// The action was dispatched more often than recorded
+    Unexpected { action_type: String, payload: Value },
+    /// A recorded action was never dispatched
+    Missing { action_type: String, payload: Value },
+}
+
+type Recorded = (Value, Result<Value, ActionError>);
+
+#[derive(Debug, Default)]
+struct State {
+    /// Recorded payloads and responses per action type, oldest first
+    pending: HashMap<String, VecDeque<Recorded>>,
+    divergences: Vec<Divergence>,
+}
+
+/// Answers actions with the responses recorded in a session
+///
+/// Actions of one type are matched to recordings in order. Clones share
+/// their state, so the replay can inspect what the app under test sent.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayBroker {
+    state: Rc<RefCell<State>>,
+}
+
+impl ReplayBroker {
+    pub fn new(session: &SessionFile) -> Self {
+        let mut state = State::default();
+        for entry in &session.entries {
+            if let SessionEvent::Action {
+                action_type,
+                payload,
+                response,
+            } = &entry.event
+            {
+                state
+                    .pending
+                    .entry(action_type.clone())
+                    .or_default()
+                    .push_back((payload.clone(), response.clone()));
+            }
+        }
+        Self {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    /// Divergences so far, followed by recorded actions still outstanding
+    pub fn divergences(&self) -> Vec<Divergence> {
+        let state = self.state.borrow();
+        let mut missing: Vec<Divergence> = state
+            .pending
+            .iter()
+            .flat_map(|(action_type, queue)| {
+                queue.iter().map(|(payload, _)| Divergence::Missing {
+                    action_type: action_type.clone(),
+                    payload: payload.clone(),
+                })
+            })
+            .collect();
+        missing.sort_by(|a, b| format!("{:?}", a).cmp(&format!("{:?}", b)));
+        state.divergences.iter().cloned().chain(missing).collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl ActionBroker for ReplayBroker {
+    async fn dispatch<A: Action>(&self, action: A) -> Result<A::Response, ActionError> {
+        let action_type = action.action_type().to_string();
+        let replayed =
+            serde_json::to_value(&action).map_err(|e| ActionError::Serialization(e.to_string()))?;
+
+        let mut state = self.state.borrow_mut();
+        let Some((recorded, response)) = state
+            .pending
+            .get_mut(&action_type)
+            .and_then(VecDeque::pop_front)
+        else {
+            state.divergences.push(Divergence::Unexpected {
+                action_type: action_type.clone(),
+                payload: replayed,
+            });
+            return Err(ActionError::Transport(format!(
+                "Replay: '{}' was not recorded",
+                action_type
+            )));
+        };
+        if recorded != replayed {
+            state.divergences.push(Divergence::Payload {
+                action_type,
+                recorded,
+                replayed,
+            });
+        }
+        drop(state);
+
+        serde_json::from_value(response?).map_err(|e| ActionError::Deserialization(e.to_string()))
+    }
+}
+
+/// Re-drives a recorded session
+pub struct Replay {
+    session: SessionFile,
+    broker: ReplayBroker,
+}
+
+impl Replay {
+    pub fn new(session: SessionFile) -> Self {
+        let broker = ReplayBroker::new(&session);
+        Self { session, broker }
+    }
+
+    /// Broker for the app under replay
+    pub fn broker(&self) -> ReplayBroker {
+        self.broker.clone()
+    }
+
+    /// Route changes and component events, in recorded order
+    ///
+    /// Actions are left out; the app dispatches those itself in response.
+    pub fn steps(&self) -> impl Iterator<Item = &SessionEvent> {
+        self.session
+            .entries
+            .iter()
+            .map(|entry| &entry.event)
+            .filter(|event| !matches!(event, SessionEvent::Action { .. }))
+    }
+
+    /// Hand each step to `drive`, letting the app settle after each
+    ///
+    /// `drive` navigates for a route step and repeats the interaction for a
+    /// component event, e.g. by clicking the element it names.
+    pub async fn run<F: FnMut(&SessionEvent)>(&self, mut drive: F) {
+        for step in self.steps() {
+            drive(step);
+            leptos::task::tick().await;
+        }
+    }
+
+    /// See [`ReplayBroker::divergences`]
+    pub fn divergences(&self) -> Vec<Divergence> {
+        self.broker.divergences()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::Recorder;
+    use actions::{PersonnelAction, PersonnelListQuery, PersonnelResponse};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    fn ready<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("broker future was pending"),
+        }
+    }
+
+    fn search(text: &str) -> PersonnelAction {
+        PersonnelAction::List(PersonnelListQuery {
+            search: Some(text.into()),
+            department: None,
+        })
+    }
+
+    fn session() -> SessionFile {
+        let recorder = Recorder::new();
+        recorder.route("/people");
+        recorder.event("person_search", "input", "ada");
+        for (text, response) in [
+            (
+                "ada",
+                Ok(serde_json::to_value(PersonnelResponse::List(vec![])).unwrap()),
+            ),
+            ("grace", Err(ActionError::Server("HTTP 503".into()))),
+        ] {
+            recorder.record(SessionEvent::Action {
+                action_type: "personnel.list".into(),
+                payload: serde_json::to_value(search(text)).unwrap(),
+                response,
+            });
+        }
+        recorder.session()
+    }
+
+    #[test]
+    fn replays_recorded_responses_in_order() {
+        let replay = Replay::new(session());
+        assert_eq!(replay.steps().count(), 2);
+
+        let broker = replay.broker();
+        assert!(matches!(
+            ready(broker.dispatch(search("ada"))),
+            Ok(PersonnelResponse::List(_))
+        ));
+        assert_eq!(
+            ready(broker.dispatch(search("grace"))).unwrap_err(),
+            ActionError::Server("HTTP 503".into())
+        );
+        assert!(replay.divergences().is_empty());
+    }
+
+    #[test]
+    fn reports_divergences() {
+        let replay = Replay::new(session());
+        let broker = replay.broker();
+        ready(broker.dispatch(search("alan"))).unwrap();
+        ready(broker.dispatch(PersonnelAction::Get("p1".into()))).unwrap_err();
+
+        let divergences = replay.divergences();
+        assert_eq!(divergences.len(), 3);
+        assert!(
+            matches!(&divergences[0], Divergence::Payload { replayed, .. } if replayed.to_string().contains("alan"))
+        );
+        assert!(
+            matches!(&divergences[1], Divergence::Unexpected { action_type, .. } if action_type == "personnel.get")
+        );
+        assert!(
+            matches!(&divergences[2], Divergence::Missing { payload, .. } if payload.to_string().contains("grace"))
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Mock action broker
+//!
+//! Stands in for `HttpBroker`/`TauriBroker` in tests. Responses are
+//! scripted per action type; every dispatch is recorded so a test can
+//! assert on what a component sent.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use actions::{Action, ActionBroker, ActionError};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+
+/// An action the broker received
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dispatched {
+    pub action_type: String,
+    /// The action as JSON
+    pub payload: Value,
+}
+
+/// Scripted outcome of one dispatch
+#[derive(Debug, Clone)]
+enum Reply {
+    Ok(Value),
+    Err(ActionError),
+}
+
+#[derive(Debug, Default)]
+struct State {
+    dispatched: Vec<Dispatched>,
+    replies: HashMap<String, VecDeque<Reply>>,
+}
+
+/// Broker that records actions and answers from a script
+///
+/// Replies for an action type are used in the order they were scripted;
+/// the last one keeps answering once the rest are used up. Unscripted
+/// action types fail with a transport error naming the action.
+///
+/// Clones share the same script and record, so a test can keep one handle
+/// while the component under test owns another.
+#[derive(Debug, Clone, Default)]
+pub struct MockBroker {
+    state: Rc<RefCell<State>>,
+}
+
+impl MockBroker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Script a successful response for `action_type`
+    pub fn respond(&self, action_type: &str, response: impl Serialize) -> &Self {
+        let value = serde_json::to_value(response).expect("scripted response must serialize");
+        self.push(action_type, Reply::Ok(value))
+    }
+
+    /// Script a failure for `action_type`
+    pub fn fail(&self, action_type: &str, error: ActionError) -> &Self {
+        self.push(action_type, Reply::Err(error))
+    }
+
+    fn push(&self, action_type: &str, reply: Reply) -> &Self {
+        self.state
+            .borrow_mut()
+            .replies
+            .entry(action_type.to_string())
+            .or_default()
+            .push_back(reply);
+        self
+    }
+
+    /// Every action received, oldest first
+    pub fn dispatched(&self) -> Vec<Dispatched> {
+        self.state.borrow().dispatched.clone()
+    }
+
+    /// Payloads of the actions of one type, oldest first
+    pub fn calls(&self, action_type: &str) -> Vec<Value> {
+        self.state
+            .borrow()
+            .dispatched
+            .iter()
+            .filter(|d| d.action_type == action_type)
+            .map(|d| d.payload.clone())
+            .collect()
+    }
+
+    /// Panic unless `action_type` was dispatched; returns its latest payload
+    #[track_caller]
+    pub fn assert_dispatched(&self, action_type: &str) -> Value {
+        match self.calls(action_type).pop() {
+            Some(payload) => payload,
+            None => panic!(
+                "expected '{}' to be dispatched; got {:?}",
+                action_type,
+                self.dispatched()
+                    .iter()
+                    .map(|d| &d.action_type)
+                    .collect::<Vec<_>>()
+            ),
+        }
+    }
+
+    /// Forget recorded actions, keeping the script
+    pub fn clear(&self) {
+        self.state.borrow_mut().dispatched.clear();
+    }
+
+    fn reply(&self, action_type: &str) -> Option<Reply> {
+        let mut state = self.state.borrow_mut();
+        let queue = state.replies.get_mut(action_type)?;
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl ActionBroker for MockBroker {
+    async fn dispatch<A: Action>(&self, action: A) -> Result<A::Response, ActionError> {
+        let action_type = action.action_type();
+        let payload =
+            serde_json::to_value(&action).map_err(|e| ActionError::Serialization(e.to_string()))?;
+        self.state.borrow_mut().dispatched.push(Dispatched {
+            action_type: action_type.to_string(),
+            payload,
+        });
+
+        match self.reply(action_type) {
+            Some(Reply::Ok(value)) => serde_json::from_value(value)
+                .map_err(|e| ActionError::Deserialization(e.to_string())),
+            Some(Reply::Err(error)) => Err(error),
+            None => Err(ActionError::Transport(format!(
+                "MockBroker: no response scripted for '{}'",
+                action_type
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Rename {
+        id: String,
+        name: String,
+    }
+
+    impl Action for Rename {
+        type Response = bool;
+
+        fn action_type(&self) -> &'static str {
+            "asset.rename"
+        }
+    }
+
+    /// The mock never awaits, so one poll completes it
+    fn ready<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("MockBroker future was pending"),
+        }
+    }
+
+    fn rename(name: &str) -> Rename {
+        Rename {
+            id: "asset:1".into(),
+            name: name.into(),
+        }
+    }
+
+    #[test]
+    fn records_and_replays_script_in_order() {
+        let broker = MockBroker::new();
+        broker
+            .respond("asset.rename", false)
+            .respond("asset.rename", true);
+
+        assert!(!ready(broker.dispatch(rename("a"))).unwrap());
+        assert!(ready(broker.dispatch(rename("b"))).unwrap());
+        // The last reply repeats
+        assert!(ready(broker.dispatch(rename("c"))).unwrap());
+
+        assert_eq!(broker.calls("asset.rename").len(), 3);
+        assert_eq!(broker.assert_dispatched("asset.rename")["name"], "c");
+    }
+
+    #[test]
+    fn unscripted_and_failing_actions_error() {
+        let broker = MockBroker::new();
+        let err = ready(broker.dispatch(rename("a"))).unwrap_err();
+        assert!(err.to_string().contains("asset.rename"));
+
+        broker.fail("asset.rename", ActionError::Rejected("read only".into()));
+        let err = ready(broker.clone().dispatch(rename("b"))).unwrap_err();
+        assert!(matches!(err, ActionError::Rejected(m) if m == "read only"));
+        // Recorded through either handle
+        assert_eq!(broker.dispatched().len(), 2);
+    }
+}
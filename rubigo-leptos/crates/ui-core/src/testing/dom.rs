@@ -0,0 +1,177 @@
+//! DOM mounting and assertions
+//!
+//! Mounts a view into its own container under `<body>` so tests don't see
+//! each other's markup, and unmounts it when the [`Mounted`] is dropped.
+//! These need a browser: run them under `wasm-bindgen-test`.
+
+use leptos::mount::{mount_to, UnmountHandle};
+use leptos::prelude::*;
+use leptos::tachys::view::{Mountable, Render};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Event, EventInit, HtmlElement, HtmlInputElement};
+
+/// A mounted view
+pub struct Mounted<M: Mountable> {
+    container: HtmlElement,
+    handle: Option<UnmountHandle<M>>,
+}
+
+/// Mount `view` into a fresh container
+pub fn mount<F, N>(view: F) -> Mounted<<N as Render>::State>
+where
+    F: FnOnce() -> N + 'static,
+    N: IntoView,
+{
+    let document = document();
+    let container: HtmlElement = document
+        .create_element("div")
+        .expect("create container")
+        .unchecked_into();
+    container.set_attribute("data-test-root", "").ok();
+    document
+        .body()
+        .expect("document has a body")
+        .append_child(&container)
+        .expect("attach container");
+
+    let handle = mount_to(container.clone(), view);
+    Mounted {
+        container,
+        handle: Some(handle),
+    }
+}
+
+/// Let pending effects and spawned tasks run before asserting
+pub async fn settle() {
+    leptos::task::tick().await;
+}
+
+impl<M: Mountable> Mounted<M> {
+    /// The container the view was mounted into
+    pub fn root(&self) -> &HtmlElement {
+        &self.container
+    }
+
+    /// First element matching a CSS selector
+    pub fn query(&self, selector: &str) -> Option<Element> {
+        self.container
+            .query_selector(selector)
+            .expect("valid selector")
+    }
+
+    /// Every element matching a CSS selector
+    pub fn query_all(&self, selector: &str) -> Vec<Element> {
+        let list = self
+            .container
+            .query_selector_all(selector)
+            .expect("valid selector");
+        (0..list.length())
+            .filter_map(|i| list.item(i))
+            .filter_map(|node| node.dyn_into::<Element>().ok())
+            .collect()
+    }
+
+    /// First matching element, panicking if there is none
+    #[track_caller]
+    pub fn get(&self, selector: &str) -> Element {
+        self.query(selector)
+            .unwrap_or_else(|| panic!("no element matches '{}' in:\n{}", selector, self.html()))
+    }
+
+    /// Text of the whole view
+    pub fn text(&self) -> String {
+        self.container.text_content().unwrap_or_default()
+    }
+
+    /// Text of the first matching element
+    #[track_caller]
+    pub fn text_of(&self, selector: &str) -> String {
+        self.get(selector).text_content().unwrap_or_default()
+    }
+
+    /// Rendered markup, for failure messages
+    pub fn html(&self) -> String {
+        self.container.inner_html()
+    }
+
+    #[track_caller]
+    pub fn assert_text_contains(&self, needle: &str) {
+        let text = self.text();
+        assert!(
+            text.contains(needle),
+            "expected '{}' in rendered text:\n{}",
+            needle,
+            text
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_count(&self, selector: &str, expected: usize) {
+        let found = self.query_all(selector).len();
+        assert_eq!(
+            found,
+            expected,
+            "elements matching '{}' in:\n{}",
+            selector,
+            self.html()
+        );
+    }
+
+    /// Click the first matching element
+    #[track_caller]
+    pub fn click(&self, selector: &str) {
+        self.get(selector).unchecked_into::<HtmlElement>().click();
+    }
+
+    /// Set an input's value and fire `input` as if the user typed it
+    #[track_caller]
+    pub fn input(&self, selector: &str, value: &str) {
+        let input: HtmlInputElement = self.get(selector).unchecked_into();
+        input.set_value(value);
+        self.fire(&input, "input");
+    }
+
+    /// Dispatch a bubbling event of type `kind` on `target`
+    pub fn fire(&self, target: &Element, kind: &str) {
+        let init = EventInit::new();
+        init.set_bubbles(true);
+        let event = Event::new_with_event_init_dict(kind, &init).expect("create event");
+        target.dispatch_event(&event).expect("dispatch event");
+    }
+}
+
+impl<M: Mountable> Drop for Mounted<M> {
+    fn drop(&mut self) {
+        // Unmount before detaching so cleanups still find their nodes
+        self.handle.take();
+        self.container.remove();
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::primitives::{PersonOption, PersonSearch};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn person_search_lists_selected_people() {
+        let selected = RwSignal::new(vec!["p2".to_string()]);
+        let people = vec![
+            PersonOption::new("p1", "Ada Lovelace"),
+            PersonOption::new("p2", "Grace Hopper"),
+        ];
+        let view = mount(move || view! { <PersonSearch selected=selected people=people /> });
+        settle().await;
+
+        view.assert_text_contains("Grace Hopper");
+        view.get("input");
+
+        selected.set(vec![]);
+        settle().await;
+        assert!(!view.text().contains("Grace Hopper"));
+    }
+}
@@ -0,0 +1,35 @@
+//! Testing Utilities
+//!
+//! Helpers for unit-testing components without a server. Enabled for this
+//! crate's own tests and, for downstream crates, with the `testing`
+//! feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! ui-core = { path = "../ui-core", features = ["testing"] }
+//! wasm-bindgen-test = "0.3"
+//! ```
+//!
+//! - [`MockBroker`] - Records dispatched actions and returns scripted responses
+//! - [`mount`] - Mounts a view into a fresh container for DOM assertions
+//!   (browser tests, run with `wasm-pack test --headless --chrome`)
+//!
+//! ```ignore
+//! use wasm_bindgen_test::*;
+//! wasm_bindgen_test_configure!(run_in_browser);
+//!
+//! #[wasm_bindgen_test]
+//! async fn shows_matching_people() {
+//!     let selected = RwSignal::new(vec![]);
+//!     let view = mount(move || view! { <PersonSearch selected people=people() /> });
+//!     view.input("input", "ada");
+//!     settle().await;
+//!     view.assert_text_contains("Ada Lovelace");
+//! }
+//! ```
+
+mod broker;
+mod dom;
+
+pub use broker::{Dispatched, MockBroker};
+pub use dom::{mount, settle, Mounted};
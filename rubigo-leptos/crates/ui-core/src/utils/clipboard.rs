@@ -0,0 +1,145 @@
+//! Clipboard
+//!
+//! Copies text and JSON through the async Clipboard API, confirming with a
+//! toast when [`provide_toasts`](crate::elements::provide_toasts) has been
+//! called, and reads pasted text for import flows. Rows copied from a
+//! spreadsheet arrive tab-separated; [`as_csv`] turns them into the CSV the
+//! import wizard reads.
+//!
+//! ```ignore
+//! use ui_core::utils::clipboard;
+//!
+//! clipboard::copy_text(link.url(), "link");
+//! clipboard::copy_json(&asset, "asset");
+//! ```
+
+use leptos::prelude::*;
+use serde::Serialize;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::elements::{Toast, ToastLevel, ToastQueue};
+
+/// How long the confirmation toast stays up
+const TOAST_MS: u32 = 2500;
+
+fn clipboard() -> Result<web_sys::Clipboard, String> {
+    web_sys::window()
+        .map(|window| window.navigator().clipboard())
+        .ok_or_else(|| "No clipboard available".to_string())
+}
+
+/// Put `text` on the clipboard
+///
+/// Browsers only allow this from a user gesture such as a click, and on
+/// secure origins.
+pub async fn write_text(text: &str) -> Result<(), String> {
+    JsFuture::from(clipboard()?.write_text(text))
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("{:?}", e))
+}
+
+/// Text on the clipboard; the browser may ask the user first
+pub async fn read_text() -> Result<String, String> {
+    let text = JsFuture::from(clipboard()?.read_text())
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    text.as_string()
+        .ok_or_else(|| "Clipboard holds no text".to_string())
+}
+
+/// Copy `text`, then toast "Copied <what>" or the failure
+pub fn copy_text(text: impl Into<String>, what: &str) {
+    let text = text.into();
+    let what = what.to_string();
+    let toasts = use_context::<RwSignal<ToastQueue>>();
+    leptos::task::spawn_local(async move {
+        let toast = match write_text(&text).await {
+            Ok(()) => {
+                Toast::new(ToastLevel::Success, format!("Copied {}", what)).duration(TOAST_MS)
+            }
+            Err(e) => {
+                web_sys::console::warn_1(&format!("Copy failed: {}", e).into());
+                Toast::new(ToastLevel::Error, format!("Couldn't copy {}", what))
+                    .message("The browser didn't allow access to the clipboard")
+            }
+        };
+        if let Some(toasts) = toasts {
+            toasts.update(|q| {
+                q.push(toast);
+            });
+        }
+    });
+}
+
+/// Copy `value` as pretty-printed JSON, see [`copy_text`]
+pub fn copy_json<T: Serialize>(value: &T, what: &str) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => copy_text(json, what),
+        Err(e) => web_sys::console::warn_1(&format!("Copy failed: {}", e).into()),
+    }
+}
+
+/// Plain text of a `paste` event
+pub fn pasted_text(ev: &web_sys::ClipboardEvent) -> Option<String> {
+    ev.clipboard_data()
+        .and_then(|data| data.get_data("text/plain").ok())
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// Pasted rows as CSV
+///
+/// Tab-separated text, as spreadsheets copy it, is converted, quoting
+/// fields that need it; anything else is taken to be CSV already.
+pub fn as_csv(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() || !lines.iter().all(|line| line.contains('\t')) {
+        return text.to_string();
+    }
+    lines
+        .iter()
+        .map(|line| {
+            line.split('\t')
+                .map(csv_field)
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `field` as a CSV field, quoted when it holds a comma, quote or newline
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::import::parse_csv;
+
+    #[test]
+    fn spreadsheet_rows_become_csv() {
+        let pasted = "name\tnotes\r\nSmith, Ann\tsaid \"hi\"\r\n\r\nBob\t\r\n";
+        let csv = as_csv(pasted);
+        assert_eq!(csv, "name,notes\n\"Smith, Ann\",\"said \"\"hi\"\"\"\nBob,");
+        assert_eq!(
+            parse_csv(&csv),
+            vec![
+                vec!["name".to_string(), "notes".to_string()],
+                vec!["Smith, Ann".to_string(), "said \"hi\"".to_string()],
+                vec!["Bob".to_string(), String::new()],
+            ]
+        );
+
+        // CSV is left alone
+        assert_eq!(as_csv("a,b\n1,2"), "a,b\n1,2");
+    }
+}
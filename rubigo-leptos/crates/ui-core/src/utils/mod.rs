@@ -0,0 +1,14 @@
+//! Utilities
+//!
+//! Browser helpers that aren't components:
+//!
+//! - [`clipboard`] - Copy text and JSON with toast feedback, read pasted rows
+//! - [`share`] - Deep links to entity details and filtered lists
+//! - [`socket`] - WebSocket URLs on the page's own host
+
+pub mod clipboard;
+pub mod share;
+pub mod socket;
+
+pub use share::ShareLink;
+pub use socket::socket_url;
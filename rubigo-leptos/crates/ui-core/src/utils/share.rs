@@ -0,0 +1,207 @@
+//! Share Links
+//!
+//! Deep links carrying view state in the query string, the same parameters
+//! [`use_url_state`](crate::hooks::use_url_state) reads back: a filtered
+//! list as it is on screen, or the details of one entity.
+//!
+//! ```ignore
+//! use ui_core::utils::{clipboard, ShareLink};
+//!
+//! // The view as it is, filters and all
+//! clipboard::copy_text(ShareLink::current().url(), "link");
+//!
+//! // One person's details
+//! let link = ShareLink::entity("person", "p42").unwrap();
+//! assert_eq!(link.href(), "/personnel?person=p42");
+//!
+//! // A filtered list
+//! let link = ShareLink::new("/personnel").param("dept", "Research & Development");
+//! assert_eq!(link.href(), "/personnel?dept=Research%20%26%20Development");
+//! ```
+
+/// Where each kind of entity shows its details: kind, route and the query
+/// parameter selecting it
+pub const ENTITY_ROUTES: &[(&str, &str, &str)] = &[
+    ("person", "/personnel", "person"),
+    ("event", "/calendar", "event"),
+];
+
+/// A link to a route with view state in its query string
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShareLink {
+    path: String,
+    params: Vec<(String, String)>,
+}
+
+impl ShareLink {
+    /// Link to `path` with no view state
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Link opening the details of entity `id` of `kind`; `None` for kinds
+    /// without a details view, see [`ENTITY_ROUTES`]
+    pub fn entity(kind: &str, id: &str) -> Option<Self> {
+        let (_, path, param) = ENTITY_ROUTES.iter().find(|(k, _, _)| *k == kind)?;
+        Some(Self::new(*path).param(param, id))
+    }
+
+    /// Link from an `href` such as `/personnel?dept=Engineering`
+    pub fn parse(href: &str) -> Self {
+        let (path, query) = href.split_once('?').unwrap_or((href, ""));
+        let query = query.split('#').next().unwrap_or_default();
+        let params = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(key), decode(value))
+            })
+            .collect();
+        Self {
+            path: path.to_string(),
+            params,
+        }
+    }
+
+    /// Link to the page as it is on screen
+    pub fn current() -> Self {
+        let location = web_sys::window().map(|w| w.location());
+        let href = location
+            .map(|l| {
+                format!(
+                    "{}{}",
+                    l.pathname().unwrap_or_default(),
+                    l.search().unwrap_or_default()
+                )
+            })
+            .unwrap_or_else(|| "/".to_string());
+        Self::parse(&href)
+    }
+
+    /// Set query parameter `key`, replacing any value it had; an empty
+    /// value removes it
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        let value = value.to_string();
+        self.params.retain(|(k, _)| k != key);
+        if !value.is_empty() {
+            self.params.push((key.to_string(), value));
+        }
+        self
+    }
+
+    /// Leave out query parameter `key`
+    pub fn without(mut self, key: &str) -> Self {
+        self.params.retain(|(k, _)| k != key);
+        self
+    }
+
+    /// Value of query parameter `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Path and query, for links within the app
+    pub fn href(&self) -> String {
+        if self.params.is_empty() {
+            return self.path.clone();
+        }
+        let query = self
+            .params
+            .iter()
+            .map(|(k, v)| format!("{}={}", encode(k), encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        format!("{}?{}", self.path, query)
+    }
+
+    /// Absolute URL on this origin, for sharing outside the app
+    pub fn url(&self) -> String {
+        let origin = web_sys::window()
+            .and_then(|w| w.location().origin().ok())
+            .unwrap_or_default();
+        format!("{}{}", origin, self.href())
+    }
+}
+
+/// Percent-encode a query component, keeping unreserved characters
+pub fn encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Decode a query component; `+` reads as a space, as forms send it
+pub fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match bytes
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 2;
+                }
+                None => out.push(b'%'),
+            },
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_carry_view_state_through_the_query() {
+        let link = ShareLink::new("/personnel")
+            .param("dept", "Research & Development")
+            .param("q", "ann")
+            .param("q", "");
+        assert_eq!(link.href(), "/personnel?dept=Research%20%26%20Development");
+        assert_eq!(ShareLink::parse(&link.href()), link);
+
+        let parsed = ShareLink::parse("/calendar?year=2024&view=week&event=e%2F1&q=a+b#top");
+        assert_eq!(parsed.get("event"), Some("e/1"));
+        assert_eq!(parsed.get("q"), Some("a b"));
+        assert_eq!(
+            parsed.clone().without("q").param("event", "e2").href(),
+            "/calendar?year=2024&view=week&event=e2"
+        );
+
+        assert_eq!(
+            ShareLink::entity("person", "p42")
+                .map(|l| l.href())
+                .as_deref(),
+            Some("/personnel?person=p42")
+        );
+        assert_eq!(ShareLink::entity("invoice", "1"), None);
+        assert_eq!(decode("caf%C3%A9%2"), "café%2");
+    }
+}
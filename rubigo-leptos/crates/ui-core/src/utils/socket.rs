@@ -0,0 +1,39 @@
+//! Socket URLs
+//!
+//! WebSocket endpoints (presence, chat, collaborative editing) are served
+//! by the same host as the page, over `wss://` when the page is on HTTPS.
+
+/// `ws://` or `wss://` URL for a path on the current host
+///
+/// `None` outside a browser window.
+pub fn socket_url(path: &str) -> Option<String> {
+    let location = web_sys::window()?.location();
+    Some(url_for(
+        &location.protocol().ok()?,
+        &location.host().ok()?,
+        path,
+    ))
+}
+
+/// Socket URL for `path` on `host`, given the page's protocol (`https:`)
+fn url_for(protocol: &str, host: &str, path: &str) -> String {
+    let scheme = if protocol == "https:" { "wss" } else { "ws" };
+    format!("{scheme}://{host}{path}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secure_pages_use_secure_sockets() {
+        assert_eq!(
+            url_for("https:", "rubigo.example:8443", "/api/chat/ws?persona=p1"),
+            "wss://rubigo.example:8443/api/chat/ws?persona=p1"
+        );
+        assert_eq!(
+            url_for("http:", "localhost:3000", "/ws"),
+            "ws://localhost:3000/ws"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Page side: posting tasks to the worker and awaiting their replies
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{Reply, ReplyId, Request, WorkerError, WorkerTask};
+
+/// Resolve and reject functions of the promise each caller awaits
+type Pending = Rc<RefCell<HashMap<u64, (js_sys::Function, js_sys::Function)>>>;
+
+struct Remote {
+    worker: web_sys::Worker,
+    pending: Pending,
+    next_id: Cell<u64>,
+    _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    _on_error: Closure<dyn FnMut(web_sys::ErrorEvent)>,
+}
+
+impl Drop for Remote {
+    fn drop(&mut self) {
+        self.worker.terminate();
+    }
+}
+
+/// Handle for running [`WorkerTask`]s on a web worker
+///
+/// Cheap to clone; the worker is terminated when the last clone is dropped.
+#[derive(Clone, Default)]
+pub struct WorkerBridge {
+    /// `None` runs tasks inline on the calling thread
+    remote: Option<Rc<Remote>>,
+}
+
+impl WorkerBridge {
+    /// Start the worker script at `url`, e.g. Trunk's `worker_loader.js`
+    ///
+    /// Falls back to running tasks inline if the worker can't be created.
+    pub fn spawn(url: &str) -> Self {
+        match Self::try_spawn(url) {
+            Ok(bridge) => bridge,
+            Err(e) => {
+                web_sys::console::warn_2(&"Running worker tasks inline:".into(), &e);
+                Self::inline()
+            }
+        }
+    }
+
+    fn try_spawn(url: &str) -> Result<Self, JsValue> {
+        let worker = web_sys::Worker::new(url)?;
+        let pending: Pending = Rc::default();
+
+        let replies = pending.clone();
+        let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(
+            move |event: web_sys::MessageEvent| {
+                let Some(text) = event.data().as_string() else {
+                    return;
+                };
+                let Ok(ReplyId { id }) = serde_json::from_str(&text) else {
+                    return;
+                };
+                if let Some((resolve, _)) = replies.borrow_mut().remove(&id) {
+                    let _ = resolve.call1(&JsValue::NULL, &JsValue::from_str(&text));
+                }
+            },
+        );
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        // A crashed worker answers nothing more; fail everyone still waiting
+        let failed = pending.clone();
+        let on_error =
+            Closure::<dyn FnMut(web_sys::ErrorEvent)>::new(move |event: web_sys::ErrorEvent| {
+                let message = JsValue::from_str(&event.message());
+                for (_, (_, reject)) in failed.borrow_mut().drain() {
+                    let _ = reject.call1(&JsValue::NULL, &message);
+                }
+            });
+        worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            remote: Some(Rc::new(Remote {
+                worker,
+                pending,
+                next_id: Cell::new(0),
+                _on_message: on_message,
+                _on_error: on_error,
+            })),
+        })
+    }
+
+    /// A bridge without a worker that runs every task inline
+    pub fn inline() -> Self {
+        Self { remote: None }
+    }
+
+    /// Whether tasks run on a worker
+    pub fn is_remote(&self) -> bool {
+        self.remote.is_some()
+    }
+
+    /// Run `T` with `input`, off the main thread when a worker is running
+    pub async fn run<T: WorkerTask>(&self, input: T::Input) -> Result<T::Output, WorkerError> {
+        let Some(remote) = &self.remote else {
+            return Ok(T::run(input));
+        };
+
+        let id = remote.next_id.get();
+        remote.next_id.set(id + 1);
+        let request = Request {
+            id,
+            task: T::NAME.to_string(),
+            input: serde_json::to_value(&input).map_err(|e| WorkerError::Codec(e.to_string()))?,
+        };
+        let message =
+            serde_json::to_string(&request).map_err(|e| WorkerError::Codec(e.to_string()))?;
+
+        let reply = js_sys::Promise::new(&mut |resolve, reject| {
+            remote.pending.borrow_mut().insert(id, (resolve, reject));
+        });
+        if let Err(e) = remote.worker.post_message(&JsValue::from_str(&message)) {
+            remote.pending.borrow_mut().remove(&id);
+            return Err(WorkerError::Transport(describe(&e)));
+        }
+
+        let text = wasm_bindgen_futures::JsFuture::from(reply)
+            .await
+            .map_err(|e| WorkerError::Transport(describe(&e)))?
+            .as_string()
+            .unwrap_or_default();
+        let reply: Reply =
+            serde_json::from_str(&text).map_err(|e| WorkerError::Codec(e.to_string()))?;
+        serde_json::from_value(reply.result?).map_err(|e| WorkerError::Codec(e.to_string()))
+    }
+}
+
+fn describe(error: &JsValue) -> String {
+    error.as_string().unwrap_or_else(|| format!("{:?}", error))
+}
+
+/// Share a worker with descendants, see [`use_worker`]
+pub fn provide_worker(bridge: WorkerBridge) {
+    provide_context(SendWrapper::new(bridge));
+}
+
+/// The worker provided by an ancestor, or an inline bridge if there is none
+pub fn use_worker() -> WorkerBridge {
+    use_context::<SendWrapper<WorkerBridge>>()
+        .map(SendWrapper::take)
+        .unwrap_or_else(WorkerBridge::inline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl WorkerTask for Sum {
+        const NAME: &'static str = "sum";
+        type Input = Vec<u64>;
+        type Output = u64;
+
+        fn run(input: Vec<u64>) -> u64 {
+            input.iter().sum()
+        }
+    }
+
+    #[test]
+    fn without_a_worker_tasks_run_inline() {
+        let bridge = use_worker();
+        assert!(!bridge.is_remote());
+        let total = futures_lite_block_on(bridge.run::<Sum>(vec![1, 2, 3]));
+        assert_eq!(total, Ok(6));
+    }
+
+    /// Polls a future that completes without waiting on anything
+    fn futures_lite_block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::task::{Context, Poll, Waker};
+        let mut future = std::pin::pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("inline task did not complete"),
+        }
+    }
+}
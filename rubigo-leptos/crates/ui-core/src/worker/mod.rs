@@ -0,0 +1,113 @@
+//! Web Worker Offloading
+//!
+//! Runs expensive client work (recurrence expansion, search indexing, path
+//! projection) on a dedicated web worker, so the main thread stays free to
+//! handle input and paint.
+//!
+//! Work is split into [`WorkerTask`]s: plain functions from a serializable
+//! input to a serializable output, named so both sides agree on them. The
+//! worker is a second WASM binary of the app that registers the tasks it can
+//! run in a [`WorkerRegistry`]; the page talks to it through a
+//! [`WorkerBridge`], which hands each call a future resolving to the output.
+//! Messages are JSON strings, matched to their callers by id.
+//!
+//! ```ignore
+//! // src/bin/worker.rs, built by Trunk with `data-type="worker"`
+//! fn main() {
+//!     WorkerRegistry::builtin().register::<BuildIndex>().serve();
+//! }
+//!
+//! // Near the app root
+//! provide_worker(WorkerBridge::spawn("./worker_loader.js"));
+//!
+//! // In a component
+//! let worker = use_worker();
+//! spawn_local(async move {
+//!     let by_day = worker.run::<ExpandRecurrences>(input).await;
+//! });
+//! ```
+//!
+//! Without a worker (SSR, tests, or a browser that refused to start one)
+//! the bridge runs tasks inline, so callers never need a second code path.
+
+mod bridge;
+mod registry;
+
+pub use bridge::{provide_worker, use_worker, WorkerBridge};
+pub use registry::WorkerRegistry;
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// A unit of work that can run on the worker
+pub trait WorkerTask: 'static {
+    /// Identifies the task in messages; unique within a registry
+    const NAME: &'static str;
+    type Input: Serialize + DeserializeOwned;
+    type Output: Serialize + DeserializeOwned;
+
+    fn run(input: Self::Input) -> Self::Output;
+}
+
+/// Why a task did not produce an output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WorkerError {
+    /// The input or output could not be (de)serialized
+    Codec(String),
+    /// The message could not be delivered, or the worker crashed
+    Transport(String),
+    /// The worker does not know the task
+    UnknownTask(String),
+}
+
+impl fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkerError::Codec(e) => write!(f, "Worker message could not be encoded: {}", e),
+            WorkerError::Transport(e) => write!(f, "Worker unavailable: {}", e),
+            WorkerError::UnknownTask(name) => write!(f, "Worker has no task '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for WorkerError {}
+
+/// Page to worker
+#[derive(Debug, Serialize, Deserialize)]
+struct Request {
+    id: u64,
+    task: String,
+    input: serde_json::Value,
+}
+
+/// Worker to page
+#[derive(Debug, Serialize, Deserialize)]
+struct Reply {
+    id: u64,
+    result: Result<serde_json::Value, WorkerError>,
+}
+
+/// Just the id of a [`Reply`], to find its caller before decoding the rest
+#[derive(Deserialize)]
+struct ReplyId {
+    id: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_survive_the_round_trip() {
+        let reply = Reply {
+            id: 7,
+            result: Err(WorkerError::UnknownTask("index".into())),
+        };
+        let text = serde_json::to_string(&reply).unwrap();
+        let id: ReplyId = serde_json::from_str(&text).unwrap();
+        let back: Reply = serde_json::from_str(&text).unwrap();
+        assert_eq!(id.id, 7);
+        assert_eq!(back.result, Err(WorkerError::UnknownTask("index".into())));
+    }
+}
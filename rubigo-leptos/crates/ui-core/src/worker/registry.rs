@@ -0,0 +1,124 @@
+//! Worker side: the tasks a worker binary can run
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{Reply, Request, WorkerError, WorkerTask};
+use crate::features::calendar::ExpandRecurrences;
+
+type Handler = fn(Value) -> Result<Value, WorkerError>;
+
+fn call<T: WorkerTask>(input: Value) -> Result<Value, WorkerError> {
+    let input = serde_json::from_value(input).map_err(|e| WorkerError::Codec(e.to_string()))?;
+    serde_json::to_value(T::run(input)).map_err(|e| WorkerError::Codec(e.to_string()))
+}
+
+/// Tasks a worker answers requests for
+#[derive(Default)]
+pub struct WorkerRegistry {
+    tasks: HashMap<&'static str, Handler>,
+}
+
+impl WorkerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the tasks ui-core's own components offload
+    pub fn builtin() -> Self {
+        Self::new().register::<ExpandRecurrences>()
+    }
+
+    pub fn register<T: WorkerTask>(mut self) -> Self {
+        self.tasks.insert(T::NAME, call::<T>);
+        self
+    }
+
+    /// Answer one request message; `None` if it was not a request
+    pub fn handle(&self, message: &str) -> Option<String> {
+        let request: Request = serde_json::from_str(message).ok()?;
+        let result = match self.tasks.get(request.task.as_str()) {
+            Some(handler) => handler(request.input),
+            None => Err(WorkerError::UnknownTask(request.task)),
+        };
+        serde_json::to_string(&Reply {
+            id: request.id,
+            result,
+        })
+        .ok()
+    }
+
+    /// Answer requests posted to this worker until it is terminated
+    ///
+    /// Call from the worker binary's `main`.
+    pub fn serve(self) {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let scope: web_sys::DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+        let reply_to = scope.clone();
+        let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(
+            move |event: web_sys::MessageEvent| {
+                let Some(reply) = event.data().as_string().and_then(|m| self.handle(&m)) else {
+                    return;
+                };
+                if let Err(e) = reply_to.post_message(&JsValue::from_str(&reply)) {
+                    web_sys::console::error_2(&"Worker reply failed".into(), &e);
+                }
+            },
+        );
+        scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        // The handler lives as long as the worker
+        on_message.forget();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Double;
+
+    impl WorkerTask for Double {
+        const NAME: &'static str = "double";
+        type Input = Vec<u32>;
+        type Output = Vec<u32>;
+
+        fn run(input: Vec<u32>) -> Vec<u32> {
+            input.into_iter().map(|n| n * 2).collect()
+        }
+    }
+
+    fn reply(registry: &WorkerRegistry, task: &str, input: Value) -> Reply {
+        let request = serde_json::to_string(&Request {
+            id: 3,
+            task: task.into(),
+            input,
+        })
+        .unwrap();
+        serde_json::from_str(&registry.handle(&request).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn requests_run_their_task() {
+        let registry = WorkerRegistry::new().register::<Double>();
+        let answer = reply(&registry, "double", serde_json::json!([1, 2]));
+        assert_eq!(answer.id, 3);
+        assert_eq!(answer.result, Ok(serde_json::json!([2, 4])));
+
+        let bad_input = reply(&registry, "double", serde_json::json!("two"));
+        assert!(matches!(bad_input.result, Err(WorkerError::Codec(_))));
+    }
+
+    #[test]
+    fn unknown_tasks_and_stray_messages() {
+        let registry = WorkerRegistry::builtin();
+        let answer = reply(&registry, "double", serde_json::json!([1]));
+        assert_eq!(
+            answer.result,
+            Err(WorkerError::UnknownTask("double".into()))
+        );
+        assert_eq!(registry.handle("not a request"), None);
+    }
+}
@@ -0,0 +1,420 @@
+//! Accessibility Inspection
+//!
+//! Walks the rendered preview of the active component and reports each
+//! element's computed role, ARIA attributes, tab order and text contrast,
+//! flagging basic violations.
+
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Containers that hold the live component preview on a docs page
+const PREVIEW_SELECTOR: &str = ".component-docs .preview-container, \
+     .component-docs .preview, \
+     .component-docs .preview-row, \
+     .component-docs .variant-grid";
+
+/// WCAG AA minimum contrast for body text
+const MIN_CONTRAST: f64 = 4.5;
+
+/// Inspection result for a single element
+#[derive(Debug, Clone, PartialEq)]
+pub struct A11yNode {
+    pub tag: String,
+    pub role: Option<String>,
+    pub name: Option<String>,
+    pub aria: Vec<(String, String)>,
+    pub tab_order: Option<usize>,
+    pub contrast: Option<f64>,
+    pub warnings: Vec<String>,
+}
+
+/// Parse a computed CSS color (`rgb(..)` / `rgba(..)`) into RGBA
+pub fn parse_css_color(value: &str) -> Option<(u8, u8, u8, f64)> {
+    let value = value.trim();
+    let inner = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+
+    let parts: Vec<&str> = inner
+        .split([',', ' ', '/'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if parts.len() < 3 {
+        return None;
+    }
+
+    let channel = |s: &str| {
+        s.parse::<f64>()
+            .ok()
+            .map(|v| v.round().clamp(0.0, 255.0) as u8)
+    };
+    let alpha = match parts.get(3) {
+        Some(a) => a.parse::<f64>().ok()?,
+        None => 1.0,
+    };
+
+    Some((
+        channel(parts[0])?,
+        channel(parts[1])?,
+        channel(parts[2])?,
+        alpha,
+    ))
+}
+
+/// WCAG relative luminance of an sRGB color
+pub fn relative_luminance(r: u8, g: u8, b: u8) -> f64 {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAG contrast ratio between two colors (1.0 - 21.0)
+pub fn contrast_ratio(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> f64 {
+    let l1 = relative_luminance(fg.0, fg.1, fg.2);
+    let l2 = relative_luminance(bg.0, bg.1, bg.2);
+    let (hi, lo) = if l1 > l2 { (l1, l2) } else { (l2, l1) };
+    (hi + 0.05) / (lo + 0.05)
+}
+
+/// Implicit ARIA role for common HTML elements
+pub fn implicit_role(tag: &str, input_type: Option<&str>) -> Option<&'static str> {
+    let role = match tag {
+        "button" => "button",
+        "a" => "link",
+        "select" => "combobox",
+        "textarea" => "textbox",
+        "table" => "table",
+        "tr" => "row",
+        "th" => "columnheader",
+        "td" => "cell",
+        "ul" | "ol" => "list",
+        "li" => "listitem",
+        "nav" => "navigation",
+        "dialog" => "dialog",
+        "img" => "img",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "input" => match input_type.unwrap_or("text") {
+            "checkbox" => "checkbox",
+            "radio" => "radio",
+            "range" => "slider",
+            "button" | "submit" | "reset" => "button",
+            "search" => "searchbox",
+            _ => "textbox",
+        },
+        _ => return None,
+    };
+    Some(role)
+}
+
+/// Roles that must expose an accessible name
+fn requires_name(role: &str) -> bool {
+    matches!(
+        role,
+        "button"
+            | "link"
+            | "checkbox"
+            | "radio"
+            | "textbox"
+            | "combobox"
+            | "searchbox"
+            | "slider"
+            | "tab"
+            | "switch"
+            | "img"
+            | "dialog"
+    )
+}
+
+fn is_focusable(el: &web_sys::Element, tag: &str) -> Option<i32> {
+    if el.has_attribute("disabled") || el.get_attribute("aria-hidden").as_deref() == Some("true") {
+        return None;
+    }
+    if let Some(tabindex) = el
+        .get_attribute("tabindex")
+        .and_then(|t| t.parse::<i32>().ok())
+    {
+        return (tabindex >= 0).then_some(tabindex);
+    }
+    match tag {
+        "button" | "select" | "textarea" | "input" => Some(0),
+        "a" if el.has_attribute("href") => Some(0),
+        _ => None,
+    }
+}
+
+/// Resolve the first non-transparent background behind an element
+fn effective_background(window: &web_sys::Window, el: &web_sys::Element) -> (u8, u8, u8) {
+    let mut current = Some(el.clone());
+    while let Some(node) = current {
+        if let Some((r, g, b, a)) = window
+            .get_computed_style(&node)
+            .ok()
+            .flatten()
+            .and_then(|s| s.get_property_value("background-color").ok())
+            .and_then(|c| parse_css_color(&c))
+        {
+            if a > 0.0 {
+                return (r, g, b);
+            }
+        }
+        current = node.parent_element();
+    }
+    (255, 255, 255)
+}
+
+fn has_direct_text(el: &web_sys::Element) -> bool {
+    let children = el.child_nodes();
+    (0..children.length()).any(|i| {
+        children.get(i).is_some_and(|n| {
+            n.node_type() == web_sys::Node::TEXT_NODE
+                && n.text_content().is_some_and(|t| !t.trim().is_empty())
+        })
+    })
+}
+
+/// Inspect every element inside the current preview
+pub fn inspect_preview() -> Vec<A11yNode> {
+    let Some(window) = web_sys::window() else {
+        return Vec::new();
+    };
+    let document = document();
+    let Ok(containers) = document.query_selector_all(PREVIEW_SELECTOR) else {
+        return Vec::new();
+    };
+
+    let mut nodes = Vec::new();
+    let mut focusable: Vec<(i32, usize)> = Vec::new();
+
+    for c in 0..containers.length() {
+        let Some(container) = containers
+            .get(c)
+            .and_then(|n| n.dyn_into::<web_sys::Element>().ok())
+        else {
+            continue;
+        };
+        let Ok(elements) = container.query_selector_all("*") else {
+            continue;
+        };
+
+        for i in 0..elements.length() {
+            let Some(el) = elements
+                .get(i)
+                .and_then(|n| n.dyn_into::<web_sys::Element>().ok())
+            else {
+                continue;
+            };
+            let tag = el.tag_name().to_lowercase();
+            let input_type = el.get_attribute("type");
+            let role = el
+                .get_attribute("role")
+                .or_else(|| implicit_role(&tag, input_type.as_deref()).map(String::from));
+
+            let attrs = el.attributes();
+            let aria: Vec<(String, String)> = (0..attrs.length())
+                .filter_map(|a| attrs.item(a))
+                .filter(|a| a.name().starts_with("aria-"))
+                .map(|a| (a.name(), a.value()))
+                .collect();
+
+            let tab_index = is_focusable(&el, &tag);
+            let has_text = has_direct_text(&el);
+
+            // Only report elements that carry semantics, are focusable or render text
+            if role.is_none() && aria.is_empty() && tab_index.is_none() && !has_text {
+                continue;
+            }
+
+            let name = el
+                .get_attribute("aria-label")
+                .or_else(|| el.get_attribute("alt"))
+                .or_else(|| el.get_attribute("title"))
+                .or_else(|| el.get_attribute("placeholder"))
+                .or_else(|| el.text_content())
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty() || el.has_attribute("aria-labelledby"));
+
+            let contrast = has_text
+                .then(|| {
+                    let style = window.get_computed_style(&el).ok().flatten()?;
+                    let (r, g, b, _) = parse_css_color(&style.get_property_value("color").ok()?)?;
+                    Some(contrast_ratio(
+                        (r, g, b),
+                        effective_background(&window, &el),
+                    ))
+                })
+                .flatten();
+
+            let mut warnings = Vec::new();
+            if let Some(role) = role.as_deref() {
+                if requires_name(role) && name.is_none() {
+                    warnings.push(format!("{role} has no accessible name"));
+                }
+            }
+            if tag == "img" && !el.has_attribute("alt") {
+                warnings.push("img is missing alt text".to_string());
+            }
+            if tab_index.is_some_and(|t| t > 0) {
+                warnings.push("positive tabindex overrides natural focus order".to_string());
+            }
+            if let Some(ratio) = contrast.filter(|r| *r < MIN_CONTRAST) {
+                warnings.push(format!("contrast {ratio:.2}:1 is below {MIN_CONTRAST}:1"));
+            }
+            for (attr, value) in &aria {
+                if matches!(
+                    attr.as_str(),
+                    "aria-controls" | "aria-labelledby" | "aria-describedby"
+                ) && value
+                    .split_whitespace()
+                    .any(|id| document.get_element_by_id(id).is_none())
+                {
+                    warnings.push(format!("{attr} references a missing id"));
+                }
+            }
+
+            if let Some(t) = tab_index {
+                focusable.push((t, nodes.len()));
+            }
+
+            nodes.push(A11yNode {
+                tag,
+                role,
+                name: name.map(|n| n.chars().take(40).collect()),
+                aria,
+                tab_order: None,
+                contrast,
+                warnings,
+            });
+        }
+    }
+
+    // Positive tabindex first (ascending), then tabindex=0 in DOM order
+    focusable.sort_by_key(|(t, index)| (if *t == 0 { i32::MAX } else { *t }, *index));
+    for (order, (_, index)) in focusable.into_iter().enumerate() {
+        nodes[index].tab_order = Some(order + 1);
+    }
+
+    nodes
+}
+
+/// Accessibility panel for the active component preview
+#[component]
+pub fn A11yPanel(
+    /// Active component name; the panel re-inspects when it changes
+    active: RwSignal<String>,
+) -> impl IntoView {
+    let open = RwSignal::new(false);
+    let nodes = RwSignal::new(Vec::<A11yNode>::new());
+
+    let refresh = move || {
+        request_animation_frame(move || nodes.set(inspect_preview()));
+    };
+
+    Effect::new(move |_| {
+        active.track();
+        if open.get() {
+            refresh();
+        }
+    });
+
+    let warning_count = move || nodes.with(|n| n.iter().map(|n| n.warnings.len()).sum::<usize>());
+
+    view! {
+        <aside class="a11y-panel" class:open=move || open.get()>
+            <button class="a11y-toggle" on:click=move |_| open.update(|o| *o = !*o)>
+                "Accessibility"
+                {move || {
+                    let count = warning_count();
+                    (open.get() && count > 0).then(|| view! { <span class="a11y-count">{count}</span> })
+                }}
+            </button>
+
+            <Show when=move || open.get()>
+                <div class="a11y-body">
+                    <div class="a11y-toolbar">
+                        <span>{move || format!("{} elements", nodes.with(Vec::len))}</span>
+                        <button class="control-input" on:click=move |_| refresh()>"Re-inspect"</button>
+                    </div>
+                    <table class="props-table a11y-table">
+                        <thead>
+                            <tr>
+                                <th>"Tab"</th>
+                                <th>"Element"</th>
+                                <th>"Role / Name"</th>
+                                <th>"ARIA"</th>
+                                <th>"Contrast"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || nodes.get().into_iter().map(|node| {
+                                let has_warnings = !node.warnings.is_empty();
+                                view! {
+                                    <tr class=if has_warnings { "a11y-warn" } else { "" }>
+                                        <td>{node.tab_order.map(|o| o.to_string()).unwrap_or_default()}</td>
+                                        <td><code>{format!("<{}>", node.tag)}</code></td>
+                                        <td>
+                                            <code class="type">{node.role.unwrap_or_else(|| "-".to_string())}</code>
+                                            " "
+                                            {node.name.unwrap_or_default()}
+                                        </td>
+                                        <td>
+                                            {node.aria.into_iter().map(|(k, v)| view! {
+                                                <div><code>{format!("{k}=\"{v}\"")}</code></div>
+                                            }).collect::<Vec<_>>()}
+                                        </td>
+                                        <td>{node.contrast.map(|c| format!("{c:.2}:1")).unwrap_or_default()}</td>
+                                    </tr>
+                                    {has_warnings.then(|| view! {
+                                        <tr class="a11y-warn-row">
+                                            <td></td>
+                                            <td colspan="4">
+                                                {node.warnings.into_iter().map(|w| view! {
+                                                    <div>"⚠ " {w}</div>
+                                                }).collect::<Vec<_>>()}
+                                            </td>
+                                        </tr>
+                                    })}
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                </div>
+            </Show>
+        </aside>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_computed_colors() {
+        assert_eq!(parse_css_color("rgb(255, 0, 10)"), Some((255, 0, 10, 1.0)));
+        assert_eq!(parse_css_color("rgba(0, 0, 0, 0)"), Some((0, 0, 0, 0.0)));
+        assert_eq!(parse_css_color("rgb(1 2 3 / 0.5)"), Some((1, 2, 3, 0.5)));
+        assert_eq!(parse_css_color("transparent"), None);
+    }
+
+    #[test]
+    fn contrast_extremes() {
+        let black_white = contrast_ratio((0, 0, 0), (255, 255, 255));
+        assert!((black_white - 21.0).abs() < 0.01);
+        assert!((contrast_ratio((10, 10, 10), (10, 10, 10)) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn implicit_roles() {
+        assert_eq!(implicit_role("button", None), Some("button"));
+        assert_eq!(implicit_role("input", Some("checkbox")), Some("checkbox"));
+        assert_eq!(implicit_role("input", None), Some("textbox"));
+        assert_eq!(implicit_role("div", None), None);
+    }
+}
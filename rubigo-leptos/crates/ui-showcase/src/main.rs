@@ -2,6 +2,8 @@
 //!
 //! Interactive documentation and exploration for ui-core components
 
+mod a11y;
+
 use leptos::prelude::*;
 use ui_core::elements::*;
 use ui_core::primitives::*;
@@ -69,6 +71,11 @@ const COMPONENTS: &[ComponentMeta] = &[
         description: "SVG icon wrapper with consistent sizing",
         category: "Primitives",
     },
+    ComponentMeta {
+        name: "TagPicker",
+        description: "Colored tag chips with a searchable add menu",
+        category: "Primitives",
+    },
     // Elements (L1)
     ComponentMeta {
         name: "Card",
@@ -95,6 +102,11 @@ const COMPONENTS: &[ComponentMeta] = &[
         description: "Slide-in panel from right with blur backdrop",
         category: "Elements",
     },
+    ComponentMeta {
+        name: "DetailPanel",
+        description: "Entity details with header, lazy tabs and edit mode",
+        category: "Elements",
+    },
     ComponentMeta {
         name: "Avatar",
         description: "Photo with fallback initials",
@@ -132,6 +144,7 @@ fn App() -> impl IntoView {
             <main class="showcase-main">
                 <ComponentView active=active_component />
             </main>
+            <a11y::A11yPanel active=active_component />
         </div>
     }
 }
@@ -197,12 +210,14 @@ fn ComponentView(active: RwSignal<String>) -> impl IntoView {
                     "Badge" => view! { <BadgeDocs /> }.into_any(),
                     "Checkbox" => view! { <CheckboxDocs /> }.into_any(),
                     "Icon" => view! { <IconDocs /> }.into_any(),
+                    "TagPicker" => view! { <TagPickerDocs /> }.into_any(),
                     // Elements
                     "Card" => view! { <CardDocs /> }.into_any(),
                     "Table" => view! { <TableDocs /> }.into_any(),
                     "Modal" => view! { <ModalDocs /> }.into_any(),
                     "Tabs" => view! { <TabsDocs /> }.into_any(),
                     "SlidePanel" => view! { <SlidePanelDocs /> }.into_any(),
+                    "DetailPanel" => view! { <DetailPanelDocs /> }.into_any(),
                     "Avatar" => view! { <AvatarDocs /> }.into_any(),
                     "SearchInput" => view! { <SearchInputDocs /> }.into_any(),
                     "FilterDropdown" => view! { <FilterDropdownDocs /> }.into_any(),
@@ -640,6 +655,59 @@ fn CheckboxDocs() -> impl IntoView {
     }
 }
 
+// ============================================================================
+// TAG PICKER DOCUMENTATION
+// ============================================================================
+
+fn sample_tags() -> Vec<TagOption> {
+    vec![
+        TagOption::new("critical", "Critical", "#ef4444"),
+        TagOption::new("spare", "Spare", "#10b981"),
+        TagOption::new("remote", "Remote", "#6366f1"),
+    ]
+}
+
+#[component]
+fn TagPickerDocs() -> impl IntoView {
+    let tags = RwSignal::new(sample_tags());
+    let selected = RwSignal::new(vec!["critical".to_string()]);
+    let on_create = Callback::new(move |name: String| {
+        let id = name.to_lowercase().replace(' ', "-");
+        tags.update(|tags| tags.push(TagOption::new(id.clone(), name, "#f59e0b")));
+        selected.update(|selected| selected.push(id));
+    });
+
+    view! {
+        <article class="component-docs">
+            <header class="docs-header">
+                <h1>"TagPicker"</h1>
+                <p class="description">"Colored tag chips with a searchable menu to add more, or create a new tag."</p>
+            </header>
+
+            <section class="docs-section">
+                <h2>"Preview"</h2>
+                <div class="preview-container">
+                    <div class="preview-area">
+                        <TagPicker tags=tags selected=selected on_create=on_create />
+                    </div>
+                    <p class="preview-value">"Selected: " {move || selected.get().join(", ")}</p>
+                </div>
+            </section>
+
+            <section class="docs-section">
+                <h2>"Props"</h2>
+                <PropsTable props=vec![
+                    PropInfo { name: "tags", prop_type: "Signal<Vec<TagOption>>", default: "-", description: "Tags that can be picked" },
+                    PropInfo { name: "selected", prop_type: "RwSignal<Vec<String>>", default: "-", description: "Picked tag IDs" },
+                    PropInfo { name: "placeholder", prop_type: "&'static str", default: "\"Add tag\"", description: "Text of the add button" },
+                    PropInfo { name: "on_change", prop_type: "Option<Callback<Vec<String>>>", default: "None", description: "Selection change handler" },
+                    PropInfo { name: "on_create", prop_type: "Option<Callback<String>>", default: "None", description: "Creates a tag from a name matching no tag" },
+                ] />
+            </section>
+        </article>
+    }
+}
+
 // ============================================================================
 // ICON DOCUMENTATION
 // ============================================================================
@@ -1262,6 +1330,121 @@ fn SlidePanelDocs() -> impl IntoView {
     }
 }
 
+// ============================================================================
+// DETAIL PANEL DOCUMENTATION
+// ============================================================================
+
+#[component]
+fn DetailPanelDocs() -> impl IntoView {
+    let show_asset = RwSignal::new(false);
+    let show_site = RwSignal::new(false);
+    let location = RwSignal::new("Rack B4, Building 2".to_string());
+    let draft = RwSignal::new(String::new());
+    // Counts how often the maintenance tab was built, to show it loads once
+    let loads = RwSignal::new(0u32);
+
+    let save = Callback::new(move |_: ()| location.set(draft.get_untracked()));
+
+    view! {
+        <article class="component-docs">
+            <header class="docs-header">
+                <h1>"DetailPanel"</h1>
+                <p class="description">"Body of an entity's details SlidePanel: a header with avatar, name and status, tabs whose content is built the first time they are opened, and an edit mode."</p>
+            </header>
+
+            <section class="docs-section">
+                <h2>"Asset and site"</h2>
+                <p>"The asset's Overview can be edited; its Maintenance tab is only loaded once opened. The site shows an icon in place of an avatar."</p>
+                <div class="preview-container" style="padding: 24px;">
+                    <div class="preview-row">
+                        <Button on_click=Callback::new(move |_| show_asset.set(true))>
+                            "Open asset"
+                        </Button>
+                        <Button
+                            variant=ButtonVariant::Secondary
+                            on_click=Callback::new(move |_| show_site.set(true))
+                        >
+                            "Open site"
+                        </Button>
+                    </div>
+
+                    <SlidePanel open=show_asset title="Asset Details">
+                        {move || {
+                            let header = DetailHeader::new("Core Switch 01")
+                                .subtitle("Cisco Nexus 9336C")
+                                .icon("🖧")
+                                .status("Deployed");
+                            let tabs = vec![
+                                DetailTab::overview(move || view! {
+                                    <DetailSection title="Location">
+                                        <p>{move || location.get()}</p>
+                                    </DetailSection>
+                                    <DetailSection title="Serial">
+                                        <p>"FDO2231R0XY"</p>
+                                    </DetailSection>
+                                })
+                                .editable(move || {
+                                    draft.set(location.get_untracked());
+                                    view! {
+                                        <DetailSection title="Location">
+                                            <Input value=draft />
+                                        </DetailSection>
+                                    }
+                                }),
+                                DetailTab::custom("maintenance", "Maintenance", move || {
+                                    loads.update(|n| *n += 1);
+                                    view! {
+                                        <DetailSection title="History">
+                                            <p>"2024-03-02 — Firmware upgraded to 10.3(4a)"</p>
+                                            <p>"2023-11-18 — Fan module replaced"</p>
+                                        </DetailSection>
+                                        <p style="color: var(--text-secondary);">
+                                            {move || format!("Loaded {} time(s)", loads.get())}
+                                        </p>
+                                    }
+                                })
+                                .badge(2),
+                            ];
+                            view! { <DetailPanel header=header tabs=tabs on_save=save /> }
+                        }}
+                    </SlidePanel>
+
+                    <SlidePanel open=show_site title="Site Details" size=PanelSize::Small>
+                        {move || {
+                            let header = DetailHeader::new("Headquarters")
+                                .subtitle("Arlington, VA")
+                                .icon("🏢")
+                                .status("Online");
+                            let tabs = vec![
+                                DetailTab::overview(|| view! {
+                                    <DetailSection title="Address">
+                                        <p>"1000 Wilson Blvd, Arlington, VA 22209"</p>
+                                    </DetailSection>
+                                }),
+                                DetailTab::custom("assets", "Assets", || view! {
+                                    <p>"42 assets on site"</p>
+                                }),
+                            ];
+                            view! { <DetailPanel header=header tabs=tabs /> }
+                        }}
+                    </SlidePanel>
+                </div>
+            </section>
+
+            <section class="docs-section">
+                <h2>"Props"</h2>
+                <PropsTable props=vec![
+                    PropInfo { name: "header", prop_type: "DetailHeader", default: "-", description: "Title, subtitle, photo or icon, status and extra buttons" },
+                    PropInfo { name: "tabs", prop_type: "Vec<DetailTab>", default: "-", description: "Overview, Activity, Comments or custom tabs; each built when first opened" },
+                    PropInfo { name: "active_tab", prop_type: "Option<RwSignal<String>>", default: "None", description: "Active tab, e.g. to keep it across entities" },
+                    PropInfo { name: "editing", prop_type: "Option<RwSignal<bool>>", default: "None", description: "Edit mode, for tabs made editable" },
+                    PropInfo { name: "on_save", prop_type: "Option<Callback<()>>", default: "None", description: "Called by Save in edit mode" },
+                ] />
+            </section>
+        </article>
+    }
+}
+
 // ============================================================================
 // AVATAR DOCUMENTATION
 // ============================================================================
@@ -1455,6 +1638,119 @@ fn DataTableDocs() -> impl IntoView {
             .cell("email", "carol@example.com")
             .cell("role", "Manager"),
     ];
+    let export_columns = columns.clone();
+    let export_rows = rows.clone();
+
+    // Raw values rendered by column type
+    let typed_columns = vec![
+        DataColumn::new("name", "Name").with_type(ColumnType::Avatar),
+        DataColumn::new("status", "Status").with_type(ColumnType::Badge),
+        DataColumn::new("hired", "Hired").with_type(ColumnType::Date),
+        DataColumn::new("tickets", "Tickets closed").with_type(ColumnType::Number),
+        DataColumn::new("profile", "Profile").with_type(ColumnType::Link),
+        DataColumn::new("load", "Load").with_type(ColumnType::Custom(|_, value| {
+            let percent = value.parse::<u32>().unwrap_or(0).min(100);
+            view! { <progress max="100" value=percent>{format!("{percent}%")}</progress> }.into_any()
+        })),
+    ];
+    let typed_rows = vec![
+        DataRow::new("1")
+            .cell("name", "Alice Johnson")
+            .cell("status", "Active")
+            .cell("hired", "2019-04-01")
+            .cell("tickets", "12840")
+            .cell("profile", "alice")
+            .cell("profile_href", "/personnel?person=1")
+            .cell("load", "72"),
+        DataRow::new("2")
+            .cell("name", "Bob Smith")
+            .cell("status", "On leave")
+            .cell("hired", "2022-11-15T09:00:00Z")
+            .cell("tickets", "930")
+            .cell("profile", "bob")
+            .cell("profile_href", "/personnel?person=2")
+            .cell("load", "15"),
+    ];
+
+    // Editable copy: emails are checked before saving, and the host refuses
+    // to make anyone but a manager a director
+    let edit_columns = vec![
+        DataColumn::new("name", "Name").editable(CellEditor::Text),
+        DataColumn::new("email", "Email")
+            .editable(CellEditor::Text)
+            .validate(|email| (!email.contains('@')).then(|| "Enter an email address".to_string())),
+        DataColumn::new("role", "Role").editable(CellEditor::Select(
+            ["Engineer", "Designer", "Manager", "Director"]
+                .into_iter()
+                .map(|role| SelectOption::new(role, role))
+                .collect(),
+        )),
+        DataColumn::new("start", "Start date").editable(CellEditor::Date),
+    ];
+    let editable = RwSignal::new(
+        rows.iter()
+            .cloned()
+            .map(|row| row.cell("start", "2024-01-15"))
+            .collect::<Vec<_>>(),
+    );
+    let cell_errors = RwSignal::new(Vec::<CellError>::new());
+    let on_cell_edit = Callback::new(move |edit: CellEdit| {
+        if edit.value == "Director" && edit.previous != "Manager" {
+            cell_errors.set(vec![CellError {
+                row: edit.row,
+                column: edit.column,
+                error: "Only managers can be made directors".to_string(),
+            }]);
+            return;
+        }
+        editable.update(|rows| {
+            if let Some(row) = rows.iter_mut().find(|row| row.id == edit.row) {
+                row.cells.insert(edit.column, edit.value);
+            }
+        });
+    });
+
+    // Tagged copy of the rows with a status; bulk edits update them, and
+    // managers refuse to be deleted to show per-row failures
+    let mut tag_columns = columns.clone();
+    tag_columns.push(DataColumn::new("status", "Status"));
+    let tagged = RwSignal::new(vec![
+        rows[0].clone().cell("status", "Active").tags(["critical"]),
+        rows[1].clone().cell("status", "Active").tags(["remote", "spare"]),
+        rows[2].clone().cell("status", "On leave").tags(["remote"]),
+    ]);
+    let bulk_results = RwSignal::new(Vec::<BulkResult>::new());
+    let on_bulk = Callback::new(move |edit: BulkEdit| {
+        let mut results = Vec::new();
+        tagged.update(|rows| {
+            for id in &edit.rows {
+                let Some(index) = rows.iter().position(|row| row.id == *id) else {
+                    continue;
+                };
+                let row = &mut rows[index];
+                let mut error = None;
+                match &edit.op {
+                    BulkOp::AddTag(tag) | BulkOp::RemoveTag(tag) => {
+                        row.tags.retain(|t| t != tag);
+                        if matches!(edit.op, BulkOp::AddTag(_)) {
+                            row.tags.push(tag.clone());
+                        }
+                    }
+                    BulkOp::SetStatus(status) => {
+                        row.cells.insert("status".to_string(), status.clone());
+                    }
+                    BulkOp::Delete if row.cells.get("role").is_some_and(|r| r == "Manager") => {
+                        error = Some("Managers can't be deleted here".to_string());
+                    }
+                    BulkOp::Delete => {
+                        rows.remove(index);
+                    }
+                }
+                results.push(BulkResult { row: id.clone(), error });
+            }
+        });
+        bulk_results.set(results);
+    });
 
     view! {
         <article class="component-docs">
@@ -1475,12 +1771,77 @@ fn DataTableDocs() -> impl IntoView {
                 </div>
             </section>
 
+            <section class="docs-section">
+                <h2>"Export"</h2>
+                <p>"Pass an export URL carrying the current filters to offer CSV, JSON and Excel downloads of the same rows."</p>
+                <div class="preview-container">
+                    <div class="component-preview">
+                        <DataTable
+                            columns=export_columns
+                            rows=export_rows
+                            export_url="/api/export/people?q=example"
+                        />
+                    </div>
+                </div>
+            </section>
+
+            <section class="docs-section">
+                <h2>"Column types"</h2>
+                <p>"Cells hold raw values; a column's type shows them as formatted numbers and dates, status badges, avatars, links or through a custom renderer."</p>
+                <div class="preview-container">
+                    <div class="component-preview">
+                        <DataTable columns=typed_columns rows=typed_rows />
+                    </div>
+                </div>
+            </section>
+
+            <section class="docs-section">
+                <h2>"Inline editing"</h2>
+                <p>"Give columns an editor and pass on_cell_edit: double-click a cell to edit it, Enter or click away to save, Escape to cancel. Values show as saved at once; cell_errors restores those the host couldn't save."</p>
+                <div class="preview-container">
+                    <div class="component-preview">
+                        <DataTable
+                            columns=edit_columns
+                            rows=editable
+                            on_cell_edit=on_cell_edit
+                            cell_errors=cell_errors
+                        />
+                    </div>
+                </div>
+            </section>
+
+            <section class="docs-section">
+                <h2>"Tags and bulk edits"</h2>
+                <p>"Pass the tags rows can carry to add a Tags column and a tag filter. With on_bulk, rows can be selected (shift-click for a range) and tagged, given a status or deleted together; bulk_results reports rows that failed."</p>
+                <div class="preview-container">
+                    <div class="component-preview">
+                        <DataTable
+                            columns=tag_columns
+                            rows=tagged
+                            tags=Signal::stored(sample_tags())
+                            on_bulk=on_bulk
+                            bulk_statuses=vec!["Active".to_string(), "On leave".to_string()]
+                            bulk_delete=true
+                            bulk_results=bulk_results
+                        />
+                    </div>
+                </div>
+            </section>
+
             <section class="docs-section">
                 <h2>"Props"</h2>
                 <PropsTable props=vec![
-                    PropInfo { name: "columns", prop_type: "Vec<DataColumn>", default: "-", description: "Column definitions with key and header" },
-                    PropInfo { name: "rows", prop_type: "Vec<DataRow>", default: "-", description: "Row data with id and cells" },
+                    PropInfo { name: "columns", prop_type: "Vec<DataColumn>", default: "-", description: "Column definitions: key, header, type and editor" },
+                    PropInfo { name: "rows", prop_type: "Signal<Vec<DataRow>>", default: "-", description: "Row data with id and cells; selection and tag filter are kept when it changes" },
                     PropInfo { name: "on_row_click", prop_type: "Option<Callback<String>>", default: "None", description: "Callback when row is clicked" },
+                    PropInfo { name: "export_url", prop_type: "Option<Signal<String>>", default: "None", description: "Export endpoint with current filters; shows an Export menu" },
+                    PropInfo { name: "tags", prop_type: "Option<Signal<Vec<TagOption>>>", default: "None", description: "Tags rows can carry; shows a Tags column and tag filter" },
+                    PropInfo { name: "on_bulk", prop_type: "Option<Callback<BulkEdit>>", default: "None", description: "Row selection and a bulk-action bar for the selected rows" },
+                    PropInfo { name: "bulk_statuses", prop_type: "Vec<String>", default: "[]", description: "Statuses the bulk-action bar can set" },
+                    PropInfo { name: "bulk_delete", prop_type: "bool", default: "false", description: "Offer deleting the selected rows" },
+                    PropInfo { name: "bulk_results", prop_type: "Option<Signal<Vec<BulkResult>>>", default: "None", description: "How the last bulk edit went per row; failed rows stay selected" },
+                    PropInfo { name: "on_cell_edit", prop_type: "Option<Callback<CellEdit>>", default: "None", description: "Saves cells of editable columns, edited in place" },
+                    PropInfo { name: "cell_errors", prop_type: "Option<Signal<Vec<CellError>>>", default: "None", description: "Cell edits that couldn't be saved; their old values come back" },
                 ] />
             </section>
         </article>
@@ -0,0 +1,171 @@
+//! Browser capture
+//!
+//! Opens the showcase in headless Chromium, selects components through the
+//! sidebar, and screenshots the rendered `.component-docs` article.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::cdp::browser_protocol::emulation::{MediaFeature, SetEmulatedMediaParams};
+use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+use chromiumoxide::Page;
+use futures::StreamExt;
+use tokio::task::JoinHandle;
+
+use crate::config::{Theme, VisualState};
+
+/// Viewport used for every capture so baselines are comparable
+const VIEWPORT: (u32, u32) = (1280, 900);
+
+/// Time allowed for Leptos to re-render after a navigation click
+const SETTLE: Duration = Duration::from_millis(250);
+
+/// Freezes animations, transitions and the caret so captures are stable
+const FREEZE_CSS: &str = r#"
+    const style = document.createElement('style');
+    style.textContent = `*, *::before, *::after {
+        animation: none !important;
+        transition: none !important;
+        caret-color: transparent !important;
+    }`;
+    document.head.appendChild(style);
+"#;
+
+/// Interactive element in the preview that receives hover/focus states
+const INTERACTIVE_SELECTOR: &str = ".component-docs .preview-area button, \
+     .component-docs .preview-area input, \
+     .component-docs .preview-area select, \
+     .component-docs .preview-area [tabindex]";
+
+/// Headless showcase session
+pub struct ShowcaseSession {
+    browser: Browser,
+    handler: JoinHandle<()>,
+    page: Page,
+}
+
+impl ShowcaseSession {
+    /// Launch Chromium and load the showcase
+    pub async fn launch(showcase_url: &str) -> Result<Self> {
+        let config = BrowserConfig::builder()
+            .window_size(VIEWPORT.0, VIEWPORT.1)
+            .build()
+            .map_err(|e| anyhow!("browser config: {e}"))?;
+
+        let (browser, mut events) = Browser::launch(config)
+            .await
+            .context("failed to launch headless Chromium")?;
+        let handler = tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let page = browser.new_page(showcase_url).await?;
+        page.wait_for_navigation().await?;
+        page.find_element(".sidebar-nav")
+            .await
+            .context("showcase sidebar not found - is SHOWCASE_URL serving ui-showcase?")?;
+        page.evaluate(FREEZE_CSS).await?;
+
+        Ok(Self {
+            browser,
+            handler,
+            page,
+        })
+    }
+
+    /// Component names listed in the showcase sidebar
+    pub async fn components(&self) -> Result<Vec<String>> {
+        let names = self
+            .page
+            .evaluate(
+                "Array.from(document.querySelectorAll('.sidebar-nav li'))
+                    .map(li => li.textContent.trim())",
+            )
+            .await?
+            .into_value::<Vec<String>>()?;
+        Ok(names)
+    }
+
+    /// Switch the emulated `prefers-color-scheme`
+    pub async fn set_theme(&self, theme: Theme) -> Result<()> {
+        let params = SetEmulatedMediaParams::builder()
+            .features(vec![MediaFeature::new(
+                "prefers-color-scheme",
+                theme.as_str(),
+            )])
+            .build();
+        self.page.execute(params).await?;
+        Ok(())
+    }
+
+    /// Select a component and capture it in the given state.
+    ///
+    /// Returns `None` when the state does not apply (no interactive element).
+    pub async fn capture(&self, component: &str, state: VisualState) -> Result<Option<Vec<u8>>> {
+        self.select(component).await?;
+
+        match state {
+            VisualState::Default => {}
+            VisualState::Hover | VisualState::Focus => {
+                let Ok(element) = self.page.find_element(INTERACTIVE_SELECTOR).await else {
+                    return Ok(None);
+                };
+                if state == VisualState::Hover {
+                    element.hover().await?;
+                } else {
+                    element.focus().await?;
+                }
+                tokio::time::sleep(SETTLE).await;
+            }
+        }
+
+        let png = self
+            .page
+            .find_element(".component-docs")
+            .await
+            .with_context(|| format!("{component}: .component-docs not rendered"))?
+            .screenshot(CaptureScreenshotFormat::Png)
+            .await?;
+        Ok(Some(png))
+    }
+
+    async fn select(&self, component: &str) -> Result<()> {
+        let name = serde_json::to_string(component)?;
+        let found = self
+            .page
+            .evaluate(format!(
+                "(() => {{
+                    document.activeElement && document.activeElement.blur();
+                    const li = Array.from(document.querySelectorAll('.sidebar-nav li'))
+                        .find(li => li.textContent.trim() === {name});
+                    if (li) li.click();
+                    return !!li;
+                }})()"
+            ))
+            .await?
+            .into_value::<bool>()?;
+
+        if !found {
+            return Err(anyhow!("component '{component}' not in showcase sidebar"));
+        }
+
+        // Move the pointer away so hover from a previous capture does not leak
+        self.page
+            .move_mouse(chromiumoxide::layout::Point { x: 0.0, y: 0.0 })
+            .await?;
+        tokio::time::sleep(SETTLE).await;
+        Ok(())
+    }
+
+    /// Close the browser
+    pub async fn close(mut self) -> Result<()> {
+        self.browser.close().await?;
+        self.handler.await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! Run configuration
+//!
+//! All settings come from environment variables so the harness can run
+//! unchanged locally and in CI.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+/// Color scheme the showcase is rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Dark, Theme::Light];
+
+    /// Value for the `prefers-color-scheme` media feature
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+}
+
+/// Interaction state applied to the preview before capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisualState {
+    Default,
+    Hover,
+    Focus,
+}
+
+impl VisualState {
+    pub const ALL: [VisualState; 3] =
+        [VisualState::Default, VisualState::Hover, VisualState::Focus];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VisualState::Default => "default",
+            VisualState::Hover => "hover",
+            VisualState::Focus => "focus",
+        }
+    }
+}
+
+/// Harness configuration
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Base URL of a running ui-showcase (`SHOWCASE_URL`)
+    pub showcase_url: String,
+    /// Directory holding committed baselines
+    pub baseline_dir: PathBuf,
+    /// Directory for actual/diff images of failing captures (`VISUAL_OUTPUT_DIR`)
+    pub output_dir: PathBuf,
+    /// Maximum fraction of differing pixels before failing (`VISUAL_THRESHOLD`)
+    pub threshold: f64,
+    /// Per-channel difference ignored as anti-aliasing noise (`VISUAL_TOLERANCE`)
+    pub tolerance: u8,
+    /// Overwrite baselines instead of comparing (`UPDATE_BASELINES=1`)
+    pub update_baselines: bool,
+    /// Restrict the run to these component names (`VISUAL_COMPONENTS=Button,Modal`)
+    pub components: Option<Vec<String>>,
+}
+
+impl Config {
+    /// Load configuration from the environment
+    pub fn from_env() -> Result<Self> {
+        let showcase_url = std::env::var("SHOWCASE_URL")
+            .context("SHOWCASE_URL must point at a running ui-showcase")?;
+
+        let threshold = match std::env::var("VISUAL_THRESHOLD") {
+            Ok(v) => v.parse().context("VISUAL_THRESHOLD must be a number")?,
+            Err(_) => 0.001,
+        };
+
+        let tolerance = match std::env::var("VISUAL_TOLERANCE") {
+            Ok(v) => v.parse().context("VISUAL_TOLERANCE must be 0-255")?,
+            Err(_) => 8,
+        };
+
+        let components = std::env::var("VISUAL_COMPONENTS").ok().map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        });
+
+        Ok(Self {
+            showcase_url: showcase_url.trim_end_matches('/').to_string(),
+            baseline_dir: PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/baselines")),
+            output_dir: std::env::var("VISUAL_OUTPUT_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("test-results/visual")),
+            threshold,
+            tolerance,
+            update_baselines: std::env::var("UPDATE_BASELINES").is_ok_and(|v| v == "1"),
+            components,
+        })
+    }
+
+    /// Whether a component should be captured in this run
+    pub fn includes(&self, component: &str) -> bool {
+        self.components
+            .as_ref()
+            .is_none_or(|list| list.iter().any(|c| c == component))
+    }
+}
+
+/// File-safe name for a capture: `<component>--<state>.png`
+pub fn capture_file_name(component: &str, state: VisualState) -> String {
+    let slug: String = component
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{slug}--{}.png", state.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_file_name_is_slugged() {
+        assert_eq!(
+            capture_file_name("Design Tokens", VisualState::Hover),
+            "design-tokens--hover.png"
+        );
+        assert_eq!(
+            capture_file_name("DataTable", VisualState::Default),
+            "datatable--default.png"
+        );
+    }
+}
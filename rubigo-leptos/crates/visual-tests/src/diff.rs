@@ -0,0 +1,123 @@
+//! Pixel diffing
+//!
+//! Compares two RGBA images pixel by pixel. A pixel counts as different when
+//! any channel differs by more than the tolerance, which absorbs font
+//! anti-aliasing jitter between runs.
+
+use image::{Rgba, RgbaImage};
+
+/// Outcome of comparing a capture against its baseline
+#[derive(Debug, Clone)]
+pub struct DiffResult {
+    /// Pixels compared (the overlapping area)
+    pub total_pixels: u64,
+    /// Pixels exceeding the tolerance
+    pub differing_pixels: u64,
+    /// Baseline and capture have different dimensions
+    pub size_mismatch: bool,
+    /// Visualization: differing pixels in red over a dimmed baseline
+    pub diff_image: RgbaImage,
+}
+
+impl DiffResult {
+    /// Fraction of differing pixels in `0.0..=1.0`
+    pub fn ratio(&self) -> f64 {
+        if self.total_pixels == 0 {
+            return 0.0;
+        }
+        self.differing_pixels as f64 / self.total_pixels as f64
+    }
+
+    /// Whether the difference should fail the run
+    pub fn exceeds(&self, threshold: f64) -> bool {
+        self.size_mismatch || self.ratio() > threshold
+    }
+}
+
+/// Compare `actual` against `baseline`
+pub fn compare(baseline: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> DiffResult {
+    let width = baseline.width().min(actual.width());
+    let height = baseline.height().min(actual.height());
+    let size_mismatch = baseline.dimensions() != actual.dimensions();
+
+    let mut diff_image = RgbaImage::new(width, height);
+    let mut differing_pixels = 0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let a = baseline.get_pixel(x, y);
+            let b = actual.get_pixel(x, y);
+
+            let differs =
+                a.0.iter()
+                    .zip(b.0.iter())
+                    .any(|(ca, cb)| ca.abs_diff(*cb) > tolerance);
+
+            let out = if differs {
+                differing_pixels += 1;
+                Rgba([255, 0, 0, 255])
+            } else {
+                let [r, g, b, _] = a.0;
+                Rgba([r / 3, g / 3, b / 3, 255])
+            };
+            diff_image.put_pixel(x, y, out);
+        }
+    }
+
+    DiffResult {
+        total_pixels: u64::from(width) * u64::from(height),
+        differing_pixels,
+        size_mismatch,
+        diff_image,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(w: u32, h: u32, color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(w, h, Rgba(color))
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let img = solid(4, 4, [10, 20, 30, 255]);
+        let result = compare(&img, &img, 0);
+
+        assert_eq!(result.differing_pixels, 0);
+        assert!(!result.exceeds(0.0));
+    }
+
+    #[test]
+    fn tolerance_absorbs_small_differences() {
+        let a = solid(2, 2, [100, 100, 100, 255]);
+        let b = solid(2, 2, [104, 98, 100, 255]);
+
+        assert_eq!(compare(&a, &b, 8).differing_pixels, 0);
+        assert_eq!(compare(&a, &b, 2).differing_pixels, 4);
+    }
+
+    #[test]
+    fn ratio_against_threshold() {
+        let a = solid(10, 10, [0, 0, 0, 255]);
+        let mut b = a.clone();
+        b.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+
+        let result = compare(&a, &b, 0);
+        assert_eq!(result.differing_pixels, 1);
+        assert!((result.ratio() - 0.01).abs() < f64::EPSILON);
+        assert!(result.exceeds(0.005));
+        assert!(!result.exceeds(0.02));
+    }
+
+    #[test]
+    fn size_mismatch_always_fails() {
+        let a = solid(4, 4, [0, 0, 0, 255]);
+        let b = solid(4, 5, [0, 0, 0, 255]);
+
+        let result = compare(&a, &b, 0);
+        assert!(result.size_mismatch);
+        assert!(result.exceeds(1.0));
+    }
+}
@@ -0,0 +1,28 @@
+//! Visual Regression Tests
+//!
+//! Drives headless Chromium against the ui-showcase, captures a screenshot
+//! of every component's documentation page per state and theme, and diffs
+//! each capture against a committed baseline.
+//!
+//! # Architecture
+//!
+//! - **capture**: Browser session, navigation and screenshots
+//! - **diff**: Pixel comparison with per-channel tolerance
+//! - **config**: Environment-driven run configuration
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Serve the showcase (./showcase.sh), then:
+//! SHOWCASE_URL=http://<host>:<port> cargo run -p visual-tests
+//!
+//! # Accept the current rendering as the new baseline
+//! SHOWCASE_URL=... UPDATE_BASELINES=1 cargo run -p visual-tests
+//! ```
+
+pub mod capture;
+pub mod config;
+pub mod diff;
+
+pub use config::{Config, Theme, VisualState};
+pub use diff::{compare, DiffResult};
@@ -0,0 +1,142 @@
+//! Visual regression runner
+//!
+//! Captures every showcase component across states and themes, compares
+//! each screenshot with its baseline, and exits non-zero on regressions.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use visual_tests::capture::ShowcaseSession;
+use visual_tests::config::capture_file_name;
+use visual_tests::{compare, Config, Theme, VisualState};
+
+/// One row in the JSON report
+#[derive(Debug, Serialize)]
+struct CaptureReport {
+    component: String,
+    state: &'static str,
+    theme: &'static str,
+    status: &'static str,
+    diff_ratio: Option<f64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = Config::from_env()?;
+    let session = ShowcaseSession::launch(&config.showcase_url).await?;
+    let components = session.components().await?;
+
+    tokio::fs::create_dir_all(&config.output_dir).await?;
+
+    let mut report = Vec::new();
+    let mut failures = 0;
+
+    for theme in Theme::ALL {
+        session.set_theme(theme).await?;
+        let baseline_dir = config.baseline_dir.join(theme.as_str());
+        let output_dir = config.output_dir.join(theme.as_str());
+        tokio::fs::create_dir_all(&baseline_dir).await?;
+        tokio::fs::create_dir_all(&output_dir).await?;
+
+        for component in components.iter().filter(|c| config.includes(c)) {
+            for state in VisualState::ALL {
+                let Some(png) = session.capture(component, state).await? else {
+                    continue;
+                };
+
+                let file_name = capture_file_name(component, state);
+                let baseline_path = baseline_dir.join(&file_name);
+
+                let (status, diff_ratio) = if config.update_baselines {
+                    tokio::fs::write(&baseline_path, &png).await?;
+                    ("updated", None)
+                } else if !baseline_path.exists() {
+                    tracing::warn!(
+                        "{}: no baseline, writing {}",
+                        component,
+                        baseline_path.display()
+                    );
+                    tokio::fs::write(&baseline_path, &png).await?;
+                    ("new", None)
+                } else {
+                    let ratio = check(&config, &baseline_path, &png, &output_dir.join(&file_name))?;
+                    match ratio {
+                        Ok(ratio) => ("pass", Some(ratio)),
+                        Err(ratio) => {
+                            failures += 1;
+                            tracing::error!(
+                                "{} [{} / {}]: {:.3}% pixels differ",
+                                component,
+                                state.as_str(),
+                                theme.as_str(),
+                                ratio * 100.0
+                            );
+                            ("fail", Some(ratio))
+                        }
+                    }
+                };
+
+                report.push(CaptureReport {
+                    component: component.clone(),
+                    state: state.as_str(),
+                    theme: theme.as_str(),
+                    status,
+                    diff_ratio,
+                });
+            }
+        }
+    }
+
+    session.close().await?;
+
+    let report_path = config.output_dir.join("report.json");
+    tokio::fs::write(&report_path, serde_json::to_vec_pretty(&report)?).await?;
+    tracing::info!(
+        "{} captures, report at {}",
+        report.len(),
+        report_path.display()
+    );
+
+    if failures > 0 {
+        bail!(
+            "{failures} visual regression(s); see {}",
+            config.output_dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Compare a capture against its baseline, writing actual/diff images on failure.
+///
+/// Returns `Ok(ratio)` when within threshold and `Err(ratio)` otherwise.
+fn check(
+    config: &Config,
+    baseline_path: &Path,
+    png: &[u8],
+    failure_path: &Path,
+) -> Result<Result<f64, f64>> {
+    let baseline = image::open(baseline_path)
+        .with_context(|| format!("reading {}", baseline_path.display()))?
+        .to_rgba8();
+    let actual = image::load_from_memory(png)?.to_rgba8();
+
+    let result = compare(&baseline, &actual, config.tolerance);
+    let ratio = if result.size_mismatch {
+        1.0
+    } else {
+        result.ratio()
+    };
+
+    if !result.exceeds(config.threshold) {
+        return Ok(Ok(ratio));
+    }
+
+    actual.save(failure_path.with_extension("actual.png"))?;
+    result
+        .diff_image
+        .save(failure_path.with_extension("diff.png"))?;
+    Ok(Err(ratio))
+}
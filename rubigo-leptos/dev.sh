@@ -50,6 +50,10 @@ fi
 
 echo "Environment initialized with PORT=$PORT"
 
+# Build hydration islands (interactive parts of the SSR pages)
+echo "Building gui-server hydration bundle..."
+./gui-server/build-hydrate.sh || echo "Warning: hydration bundle build failed; pages will be static"
+
 # Start gui-server
 echo "Starting gui-server in watch mode on port $PORT..."
 cd gui-server
@@ -0,0 +1,85 @@
+#!/bin/bash
+# Fingerprint and precompress gui-server static assets for production.
+#
+# Run after build-hydrate.sh. Copies the wasm bundle and stylesheets into
+# assets/dist/ under content-hashed names, writes .gz (and .br when brotli is
+# installed) next to each file, and records the mapping for the server:
+#
+#   assets/dist/manifest.json  logical name -> hashed file name
+#   assets/dist/hash.txt       js/wasm hashes read by leptos HydrationScripts
+#
+# The server serves assets/dist/ at /static/ with immutable cache headers.
+# Without a manifest it falls back to the plain /assets/ paths.
+
+set -e
+
+cd "$(dirname "$0")"
+
+DIST=assets/dist
+
+hash_of() {
+    if command -v sha256sum &> /dev/null; then
+        sha256sum "$1" | cut -c1-16
+    else
+        shasum -a 256 "$1" | cut -c1-16
+    fi
+}
+
+compress() {
+    gzip -9 -k -f "$1"
+    if command -v brotli &> /dev/null; then
+        brotli -q 11 -k -f "$1"
+    fi
+}
+
+rm -rf "$DIST"
+mkdir -p "$DIST"
+
+entries=()
+
+# name: logical asset name (as referenced by the server), src: file on disk
+fingerprint() {
+    local name=$1 src=$2 hash stem ext hashed
+    if [ ! -f "$src" ]; then
+        echo "Skipping $name: $src not found"
+        return
+    fi
+    hash=$(hash_of "$src")
+    stem="${name%.*}"
+    ext="${name##*.}"
+    hashed="$stem.$hash.$ext"
+    cp "$src" "$DIST/$hashed"
+    compress "$DIST/$hashed"
+    entries+=("  \"$name\": \"$hashed\"")
+    echo "$name -> $hashed"
+}
+
+fingerprint style.css assets/style.css
+fingerprint ui-core.css assets/ui-core.css
+fingerprint script.js assets/script.js
+
+# Hydration bundle: HydrationScripts expects <output>.<hash>.js / .wasm
+if [ -f assets/pkg/gui_server.js ] && [ -f assets/pkg/gui_server_bg.wasm ]; then
+    js_hash=$(hash_of assets/pkg/gui_server.js)
+    wasm_hash=$(hash_of assets/pkg/gui_server_bg.wasm)
+    cp assets/pkg/gui_server.js "$DIST/gui_server.$js_hash.js"
+    cp assets/pkg/gui_server_bg.wasm "$DIST/gui_server.$wasm_hash.wasm"
+    if [ -d assets/pkg/snippets ]; then
+        cp -r assets/pkg/snippets "$DIST/snippets"
+    fi
+    compress "$DIST/gui_server.$js_hash.js"
+    compress "$DIST/gui_server.$wasm_hash.wasm"
+    printf 'js: %s\nwasm: %s\n' "$js_hash" "$wasm_hash" > "$DIST/hash.txt"
+    echo "gui_server.js -> gui_server.$js_hash.js"
+    echo "gui_server_bg.wasm -> gui_server.$wasm_hash.wasm"
+else
+    echo "Skipping hydration bundle: run build-hydrate.sh first"
+fi
+
+{
+    echo "{"
+    (IFS=$'\n'; echo "${entries[*]}" | sed '$!s/$/,/')
+    echo "}"
+} > "$DIST/manifest.json"
+
+echo "Wrote $DIST/manifest.json"
@@ -0,0 +1,25 @@
+#!/bin/bash
+# Build the hydration islands (wasm) and the ui-core stylesheet for gui-server.
+#
+# Output:
+#   assets/pkg/gui_server.js, assets/pkg/gui_server_bg.wasm
+#   assets/ui-core.css
+
+set -e
+
+cd "$(dirname "$0")"
+
+if ! command -v wasm-bindgen &> /dev/null; then
+    echo "wasm-bindgen CLI not found: cargo install wasm-bindgen-cli"
+    exit 1
+fi
+
+cargo build --lib --release \
+    --target wasm32-unknown-unknown \
+    --no-default-features --features hydrate
+
+wasm-bindgen --target web --no-typescript \
+    --out-dir assets/pkg \
+    target/wasm32-unknown-unknown/release/gui_server.wasm
+
+stylance ../crates/ui-core --output-file assets/ui-core.css
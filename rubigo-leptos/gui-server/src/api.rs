@@ -1,100 +1,398 @@
 #![allow(dead_code)]
 
+use crate::identity::acting_as;
+use crate::query::QueryOptions;
 use crate::AppState;
 use axum::extract::{State, Query, Path};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
 use nexosim_hybrid::config::{ComponentConfig, ConnectionConfig};
-use nexosim_hybrid::database::simulation::SimulationRun;
+use nexosim_hybrid::database::simulation::{Fault, SimulationRun};
 use nexosim_hybrid::database::{components::ComponentRepository, connections::ConnectionRepository};
+use serde::Serialize;
+use utoipa::ToSchema;
 
-pub async fn list_components(State(state): State<AppState>) -> Json<Vec<ComponentConfig>> {
+// ============================================================================
+// Error Envelope
+// ============================================================================
+
+/// Error body shared by all mutating JSON endpoints:
+/// `{ "error": { "code": "not_found", "message": "..." } }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiError {
+    #[serde(skip)]
+    status: StatusCode,
+    error: ApiErrorBody,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ApiErrorBody {
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            error: ApiErrorBody { code, message: message.into() },
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, "conflict", message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", message)
+    }
+
+    /// What went wrong, for logging when the error is not sent as a response
+    pub fn message(&self) -> &str {
+        &self.error.message
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// Parse a record id given as `table:key` or a bare `key`
+fn parse_id(value: &str, table: &str) -> ApiResult<surrealdb::sql::Thing> {
+    let key = match value.split_once(':') {
+        Some((prefix, key)) if prefix == table => key,
+        Some(_) => {
+            return Err(ApiError::bad_request(format!("Expected a {table} id, got '{value}'")))
+        }
+        None => value,
+    };
+    if key.is_empty() {
+        return Err(ApiError::bad_request(format!("Invalid {table} id: '{value}'")));
+    }
+    Ok(surrealdb::sql::Thing::from((table, key)))
+}
+
+/// Strip an optional `table:` prefix from a path id
+pub(crate) fn record_key<'a>(value: &'a str, table: &str) -> &'a str {
+    value
+        .strip_prefix(table)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or(value)
+}
+
+/// Map a repository `Option` result to 404 when the record is missing
+pub(crate) fn found<T>(value: Option<T>, what: &str, id: &str) -> ApiResult<T> {
+    value.ok_or_else(|| ApiError::not_found(format!("{what} '{id}' not found")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/components",
+    tag = "components",
+    params(QueryOptions),
+    responses((status = 200, description = "All components", body = Vec<ComponentConfig>))
+)]
+pub async fn list_components(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> Json<Vec<ComponentConfig>> {
     let components = ComponentRepository::get_all(&state.db.client)
         .await
         .unwrap_or_default();
-    Json(components)
+    Json(options.apply(components))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/components",
+    tag = "components",
+    request_body = ComponentConfig,
+    responses(
+        (status = 201, description = "Component created", body = ComponentConfig),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
 pub async fn create_component(
     State(state): State<AppState>,
     Json(payload): Json<ComponentConfig>,
-) -> impl IntoResponse {
-    match ComponentRepository::create(&state.db.client, payload).await {
-        Ok(c) => (StatusCode::CREATED, Json(c)).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
-    }
+) -> ApiResult<(StatusCode, Json<ComponentConfig>)> {
+    let created = ComponentRepository::create(&state.db.client, payload).await?;
+    Ok((StatusCode::CREATED, Json(created)))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/components/{id}",
+    tag = "components",
+    params(("id" = u32, Path, description = "Component id")),
+    request_body = ComponentConfig,
+    responses(
+        (status = 200, description = "Component updated", body = ComponentConfig),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
 pub async fn update_component(
     State(state): State<AppState>,
-    axum::extract::Path(id): axum::extract::Path<u32>,
-    Json(payload): Json<ComponentConfig>,
-) -> impl IntoResponse {
-    match ComponentRepository::update(&state.db.client, id, payload).await {
-        Ok(c) => (StatusCode::OK, Json(c)).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
-    }
+    Path(id): Path<u32>,
+    Json(mut payload): Json<ComponentConfig>,
+) -> ApiResult<Json<ComponentConfig>> {
+    payload.id = id;
+    let updated = ComponentRepository::update(&state.db.client, id, payload).await?;
+    Ok(Json(updated))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/components/{id}",
+    tag = "components",
+    params(("id" = u32, Path, description = "Component id")),
+    responses(
+        (status = 204, description = "Component deleted"),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
 pub async fn delete_component(
     State(state): State<AppState>,
-    axum::extract::Path(id): axum::extract::Path<u32>,
-) -> impl IntoResponse {
-    match ComponentRepository::delete(&state.db.client, id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
-    }
+    Path(id): Path<u32>,
+) -> ApiResult<StatusCode> {
+    ComponentRepository::delete(&state.db.client, id).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
-pub async fn list_connections(State(state): State<AppState>) -> Json<Vec<ConnectionConfig>> {
+#[utoipa::path(
+    get,
+    path = "/api/connections",
+    tag = "connections",
+    params(QueryOptions),
+    responses((status = 200, description = "All connections", body = Vec<ConnectionConfig>))
+)]
+pub async fn list_connections(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> Json<Vec<ConnectionConfig>> {
     let connections = ConnectionRepository::get_all(&state.db.client).await.unwrap_or_default();
-    Json(connections)
+    Json(options.apply(connections))
 }
 
-#[allow(dead_code)]
+#[utoipa::path(
+    post,
+    path = "/api/connections",
+    tag = "connections",
+    request_body = ConnectionConfig,
+    responses(
+        (status = 201, description = "Connection created", body = ConnectionConfig),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
 pub async fn create_connection(
     State(state): State<AppState>,
     Json(config): Json<ConnectionConfig>,
-) -> impl IntoResponse {
-    let _ = ConnectionRepository::create(&state.db.client, config).await;
+) -> ApiResult<(StatusCode, Json<ConnectionConfig>)> {
+    let created = ConnectionRepository::create(&state.db.client, config).await?;
+    Ok((StatusCode::CREATED, Json(created)))
 }
 
-#[allow(dead_code)]
+#[utoipa::path(
+    delete,
+    path = "/api/connections/{from}/{to}",
+    tag = "connections",
+    params(
+        ("from" = u32, Path, description = "Source component id"),
+        ("to" = u32, Path, description = "Target component id"),
+    ),
+    responses(
+        (status = 204, description = "Connection deleted"),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
 pub async fn delete_connection(
     State(state): State<AppState>,
-    axum::extract::Path((from, to)): axum::extract::Path<(u32, u32)>,
-) -> impl IntoResponse {
-    let _ = ConnectionRepository::delete(&state.db.client, from, to).await;
+    Path((from, to)): Path<(u32, u32)>,
+) -> ApiResult<StatusCode> {
+    ConnectionRepository::delete(&state.db.client, from, to).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
-pub async fn list_runs(State(state): State<AppState>) -> Json<Vec<SimulationRun>> {
+#[utoipa::path(
+    get,
+    path = "/api/runs",
+    tag = "runs",
+    params(QueryOptions),
+    responses((status = 200, description = "Simulation run history", body = Vec<SimulationRun>))
+)]
+pub async fn list_runs(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> Json<Vec<SimulationRun>> {
     use nexosim_hybrid::database::simulation::SimulationRepository;
     let runs = SimulationRepository::get_all(&state.db.client).await.unwrap_or_default();
-    Json(runs)
+    Json(options.apply(runs))
+}
+
+/// Options for a new simulation run
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RunRequest {
+    /// Components to fail part way through the run
+    #[serde(default)]
+    pub faults: Vec<Fault>,
+}
+
+/// Start a simulation run against the current topology
+#[utoipa::path(
+    post,
+    path = "/api/runs",
+    tag = "runs",
+    request_body(content = RunRequest, description = "Faults to inject; without a body the run has none"),
+    responses(
+        (status = 201, description = "Run completed", body = SimulationRun),
+        (status = 500, description = "Simulation failed", body = ApiError),
+    )
+)]
+pub async fn create_run(
+    State(state): State<AppState>,
+    body: Option<Json<RunRequest>>,
+) -> ApiResult<(StatusCode, Json<SimulationRun>)> {
+    let faults = body.map(|Json(req)| req.faults).unwrap_or_default();
+    let run = crate::simulation::run(&state, faults).await?;
+    Ok((StatusCode::CREATED, Json(run)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/runs/{id}",
+    tag = "runs",
+    params(("id" = String, Path, description = "Run id (`run:key` or `key`)")),
+    responses(
+        (status = 204, description = "Run deleted"),
+        (status = 404, description = "Run not found", body = ApiError),
+    )
+)]
+pub async fn delete_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    use nexosim_hybrid::database::simulation::SimulationRepository;
+    let key = record_key(&id, "run");
+    found(SimulationRepository::get_by_id(&state.db.client, key).await?, "Run", &id)?;
+    SimulationRepository::delete(&state.db.client, key).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
-use nexosim_hybrid::database::geo::{self, City, Region, Site};
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct CompareQuery {
+    /// Comma-separated run ids, baseline first
+    pub ids: String,
+}
 
+/// Align two or more runs: traces by step, node metrics by component, with
+/// each later run's change from the first
+#[utoipa::path(
+    get,
+    path = "/api/runs/compare",
+    tag = "runs",
+    params(CompareQuery),
+    responses(
+        (status = 200, description = "Run labels, aligned traces and per-node delta rows", body = Object),
+        (status = 400, description = "Fewer than two runs given", body = ApiError),
+        (status = 404, description = "Run not found", body = ApiError),
+    )
+)]
+pub async fn compare_runs(
+    State(state): State<AppState>,
+    Query(query): Query<CompareQuery>,
+) -> ApiResult<Json<operations::comparison::Comparison>> {
+    use nexosim_hybrid::database::simulation::SimulationRepository;
+    let mut runs = Vec::new();
+    for id in query.ids.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+        let run = found(SimulationRepository::get_by_id(&state.db.client, record_key(id, "run")).await?, "Run", id)?;
+        runs.push(crate::simulation::measurements(&run));
+    }
+    let comparison = operations::comparison::compare(&runs).map_err(ApiError::bad_request)?;
+    Ok(Json(comparison))
+}
+
+use nexosim_hybrid::database::city_search::CityQuery;
+use nexosim_hybrid::database::geo::{self, City, Person, Region, Site};
+use nexosim_hybrid::database::versions::{self, Data};
+
+/// Fuzzy city search ranked by match quality and population
+///
+/// The total match count is returned in `X-Total-Count` for paging.
+#[utoipa::path(
+    get,
+    path = "/api/cities/search",
+    tag = "regions",
+    params(CityQuery),
+    responses((
+        status = 200,
+        description = "Matching cities, best first",
+        body = Vec<City>,
+        headers(("x-total-count" = usize, description = "Matches across all pages")),
+    ))
+)]
 pub async fn search_cities(
     State(state): State<AppState>,
-    Query(params): Query<std::collections::HashMap<String, String>>,
-) -> Json<Vec<City>> {
-    let query = params.get("q").cloned().unwrap_or_default();
-    let cities = geo::GeoRepository::search_cities(&state.db.client, &query)
-        .await
-        .unwrap_or_default();
-    Json(cities)
+    Query(query): Query<CityQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let page = geo::GeoRepository::search_cities(&state.db.client, &query).await?;
+    Ok((
+        [("x-total-count", page.total.to_string())],
+        Json(page.cities),
+    ))
 }
 
-pub async fn list_sites(State(state): State<AppState>) -> Json<Vec<Site>> {
+#[utoipa::path(
+    get,
+    path = "/api/sites",
+    tag = "sites",
+    params(QueryOptions),
+    responses((status = 200, description = "All sites", body = Vec<Site>))
+)]
+pub async fn list_sites(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> Json<Vec<Site>> {
     let sites = geo::GeoRepository::list_sites(&state.db.client)
         .await
         .unwrap_or_default();
-    Json(sites)
+    Json(options.apply(sites))
 }
 
-#[allow(dead_code)]
+#[utoipa::path(
+    get,
+    path = "/api/regions/search",
+    tag = "regions",
+    params(("q" = Option<String>, Query, description = "Region name filter")),
+    responses((status = 200, description = "Matching regions", body = Vec<Region>))
+)]
 pub async fn search_regions(
     State(state): State<AppState>,
     axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
@@ -106,63 +404,204 @@ pub async fn search_regions(
     Json(regions)
 }
 
-pub async fn list_regions(State(state): State<AppState>) -> Json<Vec<Region>> {
+#[utoipa::path(
+    get,
+    path = "/api/regions",
+    tag = "regions",
+    params(QueryOptions),
+    responses((status = 200, description = "All regions", body = Vec<Region>))
+)]
+pub async fn list_regions(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> Json<Vec<Region>> {
     let regions = geo::GeoRepository::list_regions(&state.db.client)
         .await
         .unwrap_or_default();
-    Json(regions)
+    Json(options.apply(regions))
 }
 
 /// Get geographic features as GeoJSON FeatureCollection
-/// Query params: type (optional) - "country" or "state"
+/// Query params:
+/// - type (optional) - "country" or "state"
+/// - bbox (optional) - `minLon,minLat,maxLon,maxLat`; only intersecting features
+/// - tolerance (optional) - Douglas-Peucker tolerance in degrees
+///
+/// With `Accept: application/x-ndjson` the features are streamed one per
+/// line instead of wrapped in a collection.
+#[utoipa::path(
+    get,
+    path = "/api/geo/features",
+    tag = "geo",
+    params(
+        ("type" = Option<String>, Query, description = "`country` or `state`"),
+        ("bbox" = Option<String>, Query, description = "Viewport `minLon,minLat,maxLon,maxLat`; `minLon > maxLon` crosses the antimeridian"),
+        ("tolerance" = Option<f64>, Query, description = "Simplification tolerance in degrees (Douglas-Peucker)"),
+    ),
+    responses(
+        (status = 200, description = "GeoJSON FeatureCollection, or one Feature per line", content(
+            (Object = "application/json"),
+            (Object = "application/x-ndjson"),
+        )),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 400, description = "Invalid bbox or tolerance", body = ApiError),
+    )
+)]
 pub async fn list_geo_features(
     State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
     Query(params): Query<std::collections::HashMap<String, String>>,
-) -> impl IntoResponse {
+) -> axum::response::Response {
+    use crate::components::geospatial::simplify::{geometry_bbox, simplify_geometry, BBox};
+
     let feature_type = params.get("type").map(|s| s.as_str());
-    
+
+    let bbox = match params.get("bbox") {
+        Some(value) => match BBox::parse(value) {
+            Some(bbox) => Some(bbox),
+            None => {
+                return ApiError::bad_request("bbox must be minLon,minLat,maxLon,maxLat in degrees")
+                    .into_response()
+            }
+        },
+        None => None,
+    };
+    let tolerance = match params.get("tolerance").map(|t| t.parse::<f64>()) {
+        Some(Ok(t)) if t.is_finite() && t >= 0.0 => t,
+        Some(_) => {
+            return ApiError::bad_request("tolerance must be a non-negative number")
+                .into_response()
+        }
+        None => 0.0,
+    };
+
     match geo::GeoRepository::list_geo_features(&state.db.client, feature_type).await {
         Ok(features) => {
             // Convert to GeoJSON FeatureCollection format
-            let geojson_features: Vec<serde_json::Value> = features.iter().map(|f| {
-                serde_json::json!({
-                    "type": "Feature",
-                    "properties": {
-                        "name": f.name,
-                        "feature_type": f.feature_type,
-                        "iso_code": f.iso_code,
-                    },
-                    "geometry": f.geometry
+            let geojson_features: Vec<serde_json::Value> = features
+                .iter()
+                .filter(|f| match (&bbox, geometry_bbox(&f.geometry)) {
+                    (Some(view), Some(extent)) => view.intersects(&extent),
+                    _ => true,
+                })
+                .map(|f| {
+                    serde_json::json!({
+                        "type": "Feature",
+                        "properties": {
+                            "name": f.name,
+                            "feature_type": f.feature_type,
+                            "iso_code": f.iso_code,
+                        },
+                        "geometry": simplify_geometry(&f.geometry, tolerance)
+                    })
                 })
-            }).collect();
-            
+                .collect();
+
+            if crate::ndjson::requested(&headers) {
+                return crate::ndjson::stream(geojson_features);
+            }
+
             let feature_collection = serde_json::json!({
                 "type": "FeatureCollection",
                 "features": geojson_features
             });
-            
-            Json(feature_collection).into_response()
+
+            crate::static_assets::json_with_etag(&headers, &feature_collection)
         }
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
-#[allow(dead_code)]
-pub async fn create_site(
-    State(state): State<AppState>,
-    Json(site): Json<Site>,
-) -> impl IntoResponse {
-    match geo::GeoRepository::create_site(&state.db.client, site).await {
-        Ok(s) => (StatusCode::CREATED, Json(s)).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+/// Great-circle route between two sites or coordinates
+#[derive(Serialize, ToSchema)]
+pub struct GreatCircleRoute {
+    pub distance_km: f64,
+    /// Degrees clockwise from north at the start point
+    pub initial_bearing: f64,
+    /// `[lon, lat]`
+    pub midpoint: [f64; 2],
+    /// GeoJSON `LineString`, or `MultiLineString` when crossing the antimeridian
+    #[schema(value_type = Object)]
+    pub geometry: serde_json::Value,
+}
+
+/// Resolve a route endpoint given as a site id or `lon,lat`
+fn route_endpoint(value: &str, sites: &[Site]) -> ApiResult<(f64, f64)> {
+    if let Some((lon, lat)) = value.split_once(',') {
+        return match (lon.trim().parse::<f64>(), lat.trim().parse::<f64>()) {
+            (Ok(lon), Ok(lat)) if (-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat) => {
+                Ok((lon, lat))
+            }
+            _ => Err(ApiError::bad_request(format!("Invalid coordinates '{value}'"))),
+        };
     }
+    let key = record_key(value, "site");
+    let site = sites
+        .iter()
+        .find(|s| s.id.as_ref().is_some_and(|t| t.id.to_raw() == key));
+    found(site, "Site", value).map(|s| s.location)
+}
+
+/// Compute the great-circle route between two sites or coordinates
+#[utoipa::path(
+    get,
+    path = "/api/geo/route",
+    tag = "geo",
+    params(
+        ("from" = String, Query, description = "Site id or `lon,lat`"),
+        ("to" = String, Query, description = "Site id or `lon,lat`"),
+        ("segments" = Option<usize>, Query, description = "Samples along the arc (default: one per 2 degrees, max 512)"),
+    ),
+    responses(
+        (status = 200, description = "Route geometry and metrics", body = GreatCircleRoute),
+        (status = 400, description = "Missing or invalid endpoint", body = ApiError),
+        (status = 404, description = "Site not found", body = ApiError),
+    )
+)]
+pub async fn great_circle_route(
+    State(state): State<AppState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> ApiResult<Json<GreatCircleRoute>> {
+    use crate::components::geospatial::great_circle;
+
+    let (Some(from), Some(to)) = (params.get("from"), params.get("to")) else {
+        return Err(ApiError::bad_request("Both 'from' and 'to' are required"));
+    };
+    let sites = geo::GeoRepository::list_sites(&state.db.client).await?;
+    let (a, b) = (route_endpoint(from, &sites)?, route_endpoint(to, &sites)?);
+
+    let segments = match params.get("segments") {
+        Some(n) => n
+            .parse::<usize>()
+            .map_err(|_| ApiError::bad_request("segments must be a positive integer"))?,
+        None => great_circle::segment_count(a, b, great_circle::DEFAULT_STEP_DEG),
+    };
+    let points = great_circle::route(a, b, segments);
+    let lines: Vec<Vec<[f64; 2]>> = great_circle::split_at_antimeridian(&points)
+        .into_iter()
+        .map(|line| line.into_iter().map(|(lon, lat)| [lon, lat]).collect())
+        .collect();
+    let geometry = match lines.as_slice() {
+        [line] => serde_json::json!({ "type": "LineString", "coordinates": line }),
+        _ => serde_json::json!({ "type": "MultiLineString", "coordinates": lines }),
+    };
+    let (mid_lon, mid_lat) = great_circle::midpoint(a, b);
+
+    Ok(Json(GreatCircleRoute {
+        distance_km: great_circle::distance_km(a, b),
+        initial_bearing: great_circle::initial_bearing(a, b),
+        midpoint: [mid_lon, mid_lat],
+        geometry,
+    }))
 }
 
-use nexosim_hybrid::database::geo::{Rack, Device};
+use nexosim_hybrid::database::cabling::{Cable, CablingError, CablingRepository, PatchPanel, Port, PortMedia};
+use nexosim_hybrid::database::device_links::{DeviceLinkRepository, PhysicalLocation};
+use nexosim_hybrid::database::floorplan::{self, SpaceBounds};
+use nexosim_hybrid::database::geo::{Building, Device, Floor, Rack, Space};
 use serde::Deserialize;
-use surrealdb::sql::thing;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateRegionRequest {
     pub name: String,
     pub city: String,
@@ -171,8 +610,7 @@ pub struct CreateRegionRequest {
     pub lon: f64,
 }
 
-#[allow(dead_code)]
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateSiteRequest {
     pub name: String,
     pub status: String,
@@ -181,7 +619,7 @@ pub struct CreateSiteRequest {
     pub region_id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateRackRequest {
     pub name: String,
     pub space_id: String,
@@ -189,33 +627,67 @@ pub struct CreateRackRequest {
 }
 
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateBuildingRequest {
     pub name: String,
     pub site_id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateFloorRequest {
     pub name: String,
     pub building_id: String,
     pub level: i16,
+    /// Floorplan outline in metres
+    #[serde(default)]
+    #[schema(value_type = Vec<Vec<f64>>)]
+    pub outline: Vec<(f64, f64)>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateSpaceRequest {
     pub name: String,
     pub floor_id: String,
+    #[serde(default)]
+    pub locator: String,
+    pub space_type: Option<String>,
+    /// Position on the floorplan
+    #[serde(default)]
+    pub bounds: Option<SpaceBounds>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateDeviceRequest {
     pub name: String,
     pub rack_id: String,
     pub position_u: u8,
     pub component_id: Option<String>,
+    /// Rack units taken; defaults to 1
+    #[serde(default = "one_unit")]
+    pub height_u: u8,
+    /// Power drawn in watts
+    #[serde(default)]
+    pub power_w: u32,
 }
 
+fn one_unit() -> u8 {
+    1
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct CreateDeviceQuery {
+    /// Create the device even when it goes over capacity
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sites/{id}/buildings",
+    tag = "buildings",
+    params(("id" = String, Path, description = "Site id")),
+    responses((status = 200, description = "Buildings in the site", body = Vec<Building>))
+)]
 pub async fn list_buildings(
     State(state): State<AppState>,
     axum::extract::Path(site_id): axum::extract::Path<String>,
@@ -226,6 +698,13 @@ pub async fn list_buildings(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/buildings/{id}/floors",
+    tag = "floors",
+    params(("id" = String, Path, description = "Building id")),
+    responses((status = 200, description = "Floors in the building", body = Vec<Floor>))
+)]
 pub async fn list_floors(
     State(state): State<AppState>,
     axum::extract::Path(building_id): axum::extract::Path<String>,
@@ -236,6 +715,13 @@ pub async fn list_floors(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/floors/{id}/spaces",
+    tag = "spaces",
+    params(("id" = String, Path, description = "Floor id")),
+    responses((status = 200, description = "Spaces in the floor", body = Vec<Space>))
+)]
 pub async fn list_spaces(
     State(state): State<AppState>,
     axum::extract::Path(floor_id): axum::extract::Path<String>,
@@ -246,6 +732,13 @@ pub async fn list_spaces(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/spaces/{id}/racks",
+    tag = "racks",
+    params(("id" = String, Path, description = "Space id")),
+    responses((status = 200, description = "Racks in the space", body = Vec<Rack>))
+)]
 pub async fn list_racks(
     State(state): State<AppState>,
     axum::extract::Path(space_id): axum::extract::Path<String>,
@@ -256,89 +749,1813 @@ pub async fn list_racks(
     }
 }
 
-#[allow(dead_code)]
-pub async fn create_rack(
+#[utoipa::path(
+    get,
+    path = "/api/racks/{id}/devices",
+    tag = "devices",
+    params(("id" = String, Path, description = "Rack id")),
+    responses((status = 200, description = "Devices in the rack", body = Vec<Device>))
+)]
+pub async fn list_devices(
     State(state): State<AppState>,
-    Json(req): Json<CreateRackRequest>,
+    axum::extract::Path(rack_id): axum::extract::Path<String>,
 ) -> impl IntoResponse {
-    let space_thing = match thing(&req.space_id) {
-        Ok(t) => t,
-        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid space_id: {}", e)).into_response(),
-    };
+    match geo::GeoRepository::list_devices(&state.db.client, &rack_id).await {
+        Ok(d) => Json(d).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
 
-    let rack = Rack {
+// ============================================================================
+// Hierarchy Mutations
+// ============================================================================
+
+#[utoipa::path(
+    post,
+    path = "/api/regions",
+    tag = "regions",
+    request_body = CreateRegionRequest,
+    responses(
+        (status = 201, description = "Region created", body = Region),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn create_region(
+    State(state): State<AppState>,
+    Json(req): Json<CreateRegionRequest>,
+) -> ApiResult<(StatusCode, Json<Region>)> {
+    let region = Region {
         id: None,
         name: req.name,
-        space_id: space_thing,
-        height_u: req.height_u,
+        city: req.city,
+        country: req.country,
+        population: 0,
+        location: (req.lon, req.lat),
     };
+    let created: Option<Region> = state
+        .db
+        .client
+        .create("region")
+        .content(region)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    versions::bump(Data::Geo);
+    let created = created.ok_or_else(|| ApiError::internal("Failed to create region"))?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
 
-    match geo::GeoRepository::create_rack(&state.db.client, rack).await {
-        Ok(r) => (StatusCode::CREATED, Json(r)).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+impl CreateSiteRequest {
+    fn into_site(self) -> ApiResult<Site> {
+        Ok(Site {
+            id: None,
+            region_id: Some(parse_id(&self.region_id, "region")?),
+            name: self.name,
+            location: (self.lon, self.lat),
+            status: self.status,
+        })
     }
 }
 
-// Implement handlers for create_building, create_floor, create_space if needed by API
-// For now, these might be minimal implementation to satisfy compilation if used elsewhere
-// But since we are updating api.rs, we should probably add them for completeness if the frontend uses them via API.
-// However, the frontend creates things via Form POST handlers in main.rs mostly for the "actions".
-// API handlers are mainly for fetching.
-// I will skip adding create handlers for Building/Floor/Space in API struct unless I see them used.
+#[utoipa::path(
+    post,
+    path = "/api/sites",
+    tag = "sites",
+    request_body = CreateSiteRequest,
+    responses(
+        (status = 201, description = "Site created", body = Site),
+        (status = 400, description = "Invalid reference id", body = ApiError),
+    )
+)]
+pub async fn create_site(
+    State(state): State<AppState>,
+    Json(req): Json<CreateSiteRequest>,
+) -> ApiResult<(StatusCode, Json<Site>)> {
+    let created = geo::GeoRepository::create_site(&state.db.client, req.into_site()?).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/sites/{id}",
+    tag = "sites",
+    params(("id" = String, Path, description = "Site id (`site:key` or `key`)")),
+    request_body = CreateSiteRequest,
+    responses(
+        (status = 200, description = "Site updated", body = Site),
+        (status = 404, description = "Site not found", body = ApiError),
+    )
+)]
+pub async fn update_site(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateSiteRequest>,
+) -> ApiResult<Json<Site>> {
+    let key = record_key(&id, "site");
+    let updated = geo::GeoRepository::update_site(&state.db.client, key, req.into_site()?).await?;
+    Ok(Json(found(updated, "Site", &id)?))
+}
 
+#[utoipa::path(
+    delete,
+    path = "/api/sites/{id}",
+    tag = "sites",
+    params(("id" = String, Path, description = "Site id (`site:key` or `key`)")),
+    responses(
+        (status = 204, description = "Site deleted"),
+        (status = 404, description = "Site not found", body = ApiError),
+    )
+)]
+pub async fn delete_site(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = geo::GeoRepository::delete_site(&state.db.client, record_key(&id, "site")).await?;
+    found(deleted, "Site", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-pub async fn list_devices(
+impl CreateBuildingRequest {
+    fn into_building(self) -> ApiResult<Building> {
+        Ok(Building {
+            id: None,
+            site_id: parse_id(&self.site_id, "site")?,
+            name: self.name,
+        })
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/buildings",
+    tag = "buildings",
+    request_body = CreateBuildingRequest,
+    responses(
+        (status = 201, description = "Building created", body = Building),
+        (status = 400, description = "Invalid reference id", body = ApiError),
+    )
+)]
+pub async fn create_building(
     State(state): State<AppState>,
-    axum::extract::Path(rack_id): axum::extract::Path<String>,
-) -> impl IntoResponse {
-    match geo::GeoRepository::list_devices(&state.db.client, &rack_id).await {
-        Ok(d) => Json(d).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    Json(req): Json<CreateBuildingRequest>,
+) -> ApiResult<(StatusCode, Json<Building>)> {
+    let created = geo::GeoRepository::create_building(&state.db.client, req.into_building()?).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/buildings/{id}",
+    tag = "buildings",
+    params(("id" = String, Path, description = "Building id (`building:key` or `key`)")),
+    request_body = CreateBuildingRequest,
+    responses(
+        (status = 200, description = "Building updated", body = Building),
+        (status = 404, description = "Building not found", body = ApiError),
+    )
+)]
+pub async fn update_building(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateBuildingRequest>,
+) -> ApiResult<Json<Building>> {
+    let key = record_key(&id, "building");
+    let updated =
+        geo::GeoRepository::update_building(&state.db.client, key, req.into_building()?).await?;
+    Ok(Json(found(updated, "Building", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/buildings/{id}",
+    tag = "buildings",
+    params(("id" = String, Path, description = "Building id (`building:key` or `key`)")),
+    responses(
+        (status = 204, description = "Building deleted"),
+        (status = 404, description = "Building not found", body = ApiError),
+    )
+)]
+pub async fn delete_building(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted =
+        geo::GeoRepository::delete_building(&state.db.client, record_key(&id, "building")).await?;
+    found(deleted, "Building", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+impl CreateFloorRequest {
+    fn into_floor(self) -> ApiResult<Floor> {
+        Ok(Floor {
+            id: None,
+            building_id: parse_id(&self.building_id, "building")?,
+            name: self.name,
+            level: self.level,
+            outline: self.outline,
+        })
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/floors",
+    tag = "floors",
+    request_body = CreateFloorRequest,
+    responses(
+        (status = 201, description = "Floor created", body = Floor),
+        (status = 400, description = "Invalid reference id", body = ApiError),
+    )
+)]
+pub async fn create_floor(
+    State(state): State<AppState>,
+    Json(req): Json<CreateFloorRequest>,
+) -> ApiResult<(StatusCode, Json<Floor>)> {
+    let created = geo::GeoRepository::create_floor(&state.db.client, req.into_floor()?).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/floors/{id}",
+    tag = "floors",
+    params(("id" = String, Path, description = "Floor id (`floor:key` or `key`)")),
+    request_body = CreateFloorRequest,
+    responses(
+        (status = 200, description = "Floor updated", body = Floor),
+        (status = 404, description = "Floor not found", body = ApiError),
+    )
+)]
+pub async fn update_floor(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateFloorRequest>,
+) -> ApiResult<Json<Floor>> {
+    let key = record_key(&id, "floor");
+    let updated = geo::GeoRepository::update_floor(&state.db.client, key, req.into_floor()?).await?;
+    Ok(Json(found(updated, "Floor", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/floors/{id}",
+    tag = "floors",
+    params(("id" = String, Path, description = "Floor id (`floor:key` or `key`)")),
+    responses(
+        (status = 204, description = "Floor deleted"),
+        (status = 404, description = "Floor not found", body = ApiError),
+    )
+)]
+pub async fn delete_floor(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = geo::GeoRepository::delete_floor(&state.db.client, record_key(&id, "floor")).await?;
+    found(deleted, "Floor", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct FloorOutlineRequest {
+    /// `[[x, y], ...]` in metres, a GeoJSON Polygon, or `"x,y x,y ..."` text
+    #[schema(value_type = Object)]
+    pub outline: serde_json::Value,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/floors/{id}/outline",
+    tag = "floors",
+    params(("id" = String, Path, description = "Floor id (`floor:key` or `key`)")),
+    request_body = FloorOutlineRequest,
+    responses(
+        (status = 200, description = "Outline saved", body = Floor),
+        (status = 400, description = "Outline could not be parsed", body = ApiError),
+        (status = 404, description = "Floor not found", body = ApiError),
+    )
+)]
+pub async fn set_floor_outline(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<FloorOutlineRequest>,
+) -> ApiResult<Json<Floor>> {
+    let text = match &req.outline {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    let outline = floorplan::parse_outline(&text).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let key = record_key(&id, "floor");
+    let updated = floorplan::FloorplanRepository::set_outline(&state.db.client, key, outline).await?;
+    Ok(Json(found(updated, "Floor", &id)?))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SpaceBoundsRequest {
+    /// New rectangle in metres; `null` removes the space from the floorplan
+    pub bounds: Option<SpaceBounds>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/spaces/{id}/bounds",
+    tag = "spaces",
+    params(("id" = String, Path, description = "Space id (`space:key` or `key`)")),
+    request_body = SpaceBoundsRequest,
+    responses(
+        (status = 200, description = "Space placed, clamped to the floor outline", body = Space),
+        (status = 404, description = "Space not found", body = ApiError),
+    )
+)]
+pub async fn set_space_bounds(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<SpaceBoundsRequest>,
+) -> ApiResult<Json<Space>> {
+    let key = record_key(&id, "space");
+    let updated = floorplan::FloorplanRepository::set_space_bounds(&state.db.client, key, req.bounds).await?;
+    Ok(Json(found(updated, "Space", &id)?))
+}
+
+impl CreateSpaceRequest {
+    fn into_space(self) -> ApiResult<Space> {
+        Ok(Space {
+            id: None,
+            floor_id: parse_id(&self.floor_id, "floor")?,
+            name: self.name,
+            locator: self.locator,
+            space_type: self.space_type,
+            bounds: self.bounds,
+        })
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/spaces",
+    tag = "spaces",
+    request_body = CreateSpaceRequest,
+    responses(
+        (status = 201, description = "Space created", body = Space),
+        (status = 400, description = "Invalid reference id", body = ApiError),
+    )
+)]
+pub async fn create_space(
+    State(state): State<AppState>,
+    Json(req): Json<CreateSpaceRequest>,
+) -> ApiResult<(StatusCode, Json<Space>)> {
+    let created = geo::GeoRepository::create_space(&state.db.client, req.into_space()?).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/spaces/{id}",
+    tag = "spaces",
+    params(("id" = String, Path, description = "Space id (`space:key` or `key`)")),
+    request_body = CreateSpaceRequest,
+    responses(
+        (status = 200, description = "Space updated", body = Space),
+        (status = 404, description = "Space not found", body = ApiError),
+    )
+)]
+pub async fn update_space(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateSpaceRequest>,
+) -> ApiResult<Json<Space>> {
+    let key = record_key(&id, "space");
+    let updated = geo::GeoRepository::update_space(&state.db.client, key, req.into_space()?).await?;
+    Ok(Json(found(updated, "Space", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/spaces/{id}",
+    tag = "spaces",
+    params(("id" = String, Path, description = "Space id (`space:key` or `key`)")),
+    responses(
+        (status = 204, description = "Space deleted"),
+        (status = 404, description = "Space not found", body = ApiError),
+    )
+)]
+pub async fn delete_space(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = geo::GeoRepository::delete_space(&state.db.client, record_key(&id, "space")).await?;
+    found(deleted, "Space", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+impl CreateRackRequest {
+    fn into_rack(self) -> ApiResult<Rack> {
+        Ok(Rack {
+            id: None,
+            space_id: parse_id(&self.space_id, "space")?,
+            name: self.name,
+            height_u: self.height_u,
+        })
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/racks",
+    tag = "racks",
+    request_body = CreateRackRequest,
+    responses(
+        (status = 201, description = "Rack created", body = Rack),
+        (status = 400, description = "Invalid reference id", body = ApiError),
+    )
+)]
+pub async fn create_rack(
+    State(state): State<AppState>,
+    Json(req): Json<CreateRackRequest>,
+) -> ApiResult<(StatusCode, Json<Rack>)> {
+    let created = geo::GeoRepository::create_rack(&state.db.client, req.into_rack()?).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/racks/{id}",
+    tag = "racks",
+    params(("id" = String, Path, description = "Rack id (`rack:key` or `key`)")),
+    request_body = CreateRackRequest,
+    responses(
+        (status = 200, description = "Rack updated", body = Rack),
+        (status = 404, description = "Rack not found", body = ApiError),
+    )
+)]
+pub async fn update_rack(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateRackRequest>,
+) -> ApiResult<Json<Rack>> {
+    let key = record_key(&id, "rack");
+    let updated = geo::GeoRepository::update_rack(&state.db.client, key, req.into_rack()?).await?;
+    Ok(Json(found(updated, "Rack", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/racks/{id}",
+    tag = "racks",
+    params(("id" = String, Path, description = "Rack id (`rack:key` or `key`)")),
+    responses(
+        (status = 204, description = "Rack deleted"),
+        (status = 404, description = "Rack not found", body = ApiError),
+    )
+)]
+pub async fn delete_rack(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = geo::GeoRepository::delete_rack(&state.db.client, record_key(&id, "rack")).await?;
+    found(deleted, "Rack", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+impl CreateDeviceRequest {
+    pub(crate) fn into_device(self) -> ApiResult<Device> {
+        Ok(Device {
+            id: None,
+            rack_id: parse_id(&self.rack_id, "rack")?,
+            component_id: self
+                .component_id
+                .as_deref()
+                .map(|c| parse_id(c, "component"))
+                .transpose()?,
+            name: self.name,
+            position_u: self.position_u,
+            height_u: self.height_u,
+            power_w: self.power_w,
+        })
     }
 }
 
-#[allow(dead_code)]
+#[utoipa::path(
+    post,
+    path = "/api/devices",
+    tag = "devices",
+    params(CreateDeviceQuery),
+    request_body = CreateDeviceRequest,
+    responses(
+        (status = 201, description = "Device created", body = Device),
+        (status = 400, description = "Invalid reference id", body = ApiError),
+        (status = 409, description = "The device would go over capacity; see `/api/capacity/check`", body = ApiError),
+    )
+)]
 pub async fn create_device(
     State(state): State<AppState>,
+    Query(query): Query<CreateDeviceQuery>,
     Json(req): Json<CreateDeviceRequest>,
-) -> impl IntoResponse {
-    let rack_thing = match thing(&req.rack_id) {
-        Ok(t) => t,
-        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid rack_id: {}", e)).into_response(),
+) -> ApiResult<(StatusCode, Json<Device>)> {
+    let device = req.into_device()?;
+    if !query.force {
+        let warnings = crate::capacity::check_device(&state.db.client, &device).await?;
+        if !warnings.is_empty() {
+            return Err(ApiError::conflict(warnings.join("; ")));
+        }
+    }
+    let created = geo::GeoRepository::create_device(&state.db.client, device).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/devices/{id}",
+    tag = "devices",
+    params(("id" = String, Path, description = "Device id (`device:key` or `key`)")),
+    request_body = CreateDeviceRequest,
+    responses(
+        (status = 200, description = "Device updated", body = Device),
+        (status = 404, description = "Device not found", body = ApiError),
+    )
+)]
+pub async fn update_device(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateDeviceRequest>,
+) -> ApiResult<Json<Device>> {
+    let key = record_key(&id, "device");
+    let updated = geo::GeoRepository::update_device(&state.db.client, key, req.into_device()?).await?;
+    Ok(Json(found(updated, "Device", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/devices/{id}",
+    tag = "devices",
+    params(("id" = String, Path, description = "Device id (`device:key` or `key`)")),
+    responses(
+        (status = 204, description = "Device deleted"),
+        (status = 404, description = "Device not found", body = ApiError),
+    )
+)]
+pub async fn delete_device(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted =
+        geo::GeoRepository::delete_device(&state.db.client, record_key(&id, "device")).await?;
+    found(deleted, "Device", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LinkDeviceRequest {
+    /// Simulation component the device stands in for; `null` unlinks it
+    pub component_id: Option<u32>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/devices/{id}/component",
+    tag = "devices",
+    params(("id" = String, Path, description = "Device id (`device:key` or `key`)")),
+    request_body = LinkDeviceRequest,
+    responses(
+        (status = 200, description = "Device linked or unlinked", body = Device),
+        (status = 400, description = "Unknown component", body = ApiError),
+        (status = 404, description = "Device not found", body = ApiError),
+    )
+)]
+pub async fn link_device(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<LinkDeviceRequest>,
+) -> ApiResult<Json<Device>> {
+    if let Some(component) = req.component_id {
+        let components = ComponentRepository::get_all(&state.db.client).await?;
+        if !components.iter().any(|c| c.id == component) {
+            return Err(ApiError::bad_request(format!("Component {component} does not exist")));
+        }
+    }
+    let key = record_key(&id, "device");
+    let linked = DeviceLinkRepository::link(&state.db.client, key, req.component_id).await?;
+    Ok(Json(found(linked, "Device", &id)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/components/{id}/location",
+    tag = "components",
+    params(("id" = u32, Path, description = "Component id")),
+    responses(
+        (status = 200, description = "Where the component's device is racked", body = PhysicalLocation),
+        (status = 404, description = "Component is not linked to a device", body = ApiError),
+    )
+)]
+pub async fn component_location(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> ApiResult<Json<PhysicalLocation>> {
+    let mut locations = DeviceLinkRepository::component_locations(&state.db.client).await?;
+    Ok(Json(found(locations.remove(&id), "Location of component", &id.to_string())?))
+}
+
+// ============================================================================
+// Physical Cabling
+// ============================================================================
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreatePatchPanelRequest {
+    pub name: String,
+    pub rack_id: String,
+    pub position_u: u8,
+    pub port_count: u16,
+    #[serde(default)]
+    pub media: PortMedia,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreatePortRequest {
+    /// `device:key` or `patch_panel:key`
+    pub owner: String,
+    pub name: String,
+    #[serde(default)]
+    pub media: PortMedia,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateCableRequest {
+    pub a: String,
+    pub b: String,
+    pub label: Option<String>,
+    pub length_m: Option<f32>,
+}
+
+/// Ports may only belong to devices and patch panels
+fn parse_port_owner(value: &str) -> ApiResult<surrealdb::sql::Thing> {
+    match value.split_once(':') {
+        Some(("device", _)) => parse_id(value, "device"),
+        Some(("patch_panel", _)) => parse_id(value, "patch_panel"),
+        _ => Err(ApiError::bad_request(format!(
+            "Port owner must be a device or patch_panel id, got '{value}'"
+        ))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/patch-panels",
+    tag = "cabling",
+    request_body = CreatePatchPanelRequest,
+    responses(
+        (status = 201, description = "Patch panel created with numbered ports", body = PatchPanel),
+        (status = 400, description = "Invalid rack id", body = ApiError),
+    )
+)]
+pub async fn create_patch_panel(
+    State(state): State<AppState>,
+    Json(req): Json<CreatePatchPanelRequest>,
+) -> ApiResult<(StatusCode, Json<PatchPanel>)> {
+    let panel = PatchPanel {
+        id: None,
+        name: req.name,
+        rack_id: parse_id(&req.rack_id, "rack")?,
+        position_u: req.position_u,
+        port_count: req.port_count,
+        media: req.media,
     };
+    let created = CablingRepository::create_panel(&state.db.client, panel).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
 
-    let component_thing = match req.component_id {
-        Some(s) => match thing(&s) {
-            Ok(t) => Some(t),
-            Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid component_id: {}", e)).into_response(),
-        },
-        None => None,
+#[utoipa::path(
+    delete,
+    path = "/api/patch-panels/{id}",
+    tag = "cabling",
+    params(("id" = String, Path, description = "Patch panel id (`patch_panel:key` or `key`)")),
+    responses(
+        (status = 204, description = "Patch panel, its ports and their cables deleted"),
+        (status = 404, description = "Patch panel not found", body = ApiError),
+    )
+)]
+pub async fn delete_patch_panel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted =
+        CablingRepository::delete_panel(&state.db.client, record_key(&id, "patch_panel")).await?;
+    found(deleted, "Patch panel", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ports",
+    tag = "cabling",
+    request_body = CreatePortRequest,
+    responses(
+        (status = 201, description = "Port created", body = Port),
+        (status = 400, description = "Invalid owner id", body = ApiError),
+    )
+)]
+pub async fn create_port(
+    State(state): State<AppState>,
+    Json(req): Json<CreatePortRequest>,
+) -> ApiResult<(StatusCode, Json<Port>)> {
+    let port = Port {
+        id: None,
+        owner: parse_port_owner(&req.owner)?,
+        name: req.name,
+        media: req.media,
     };
+    let created = CablingRepository::create_port(&state.db.client, port).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/ports/{id}",
+    tag = "cabling",
+    params(("id" = String, Path, description = "Port id (`port:key` or `key`)")),
+    responses(
+        (status = 204, description = "Port and its cables deleted"),
+        (status = 404, description = "Port not found", body = ApiError),
+    )
+)]
+pub async fn delete_port(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = CablingRepository::delete_port(&state.db.client, record_key(&id, "port")).await?;
+    found(deleted, "Port", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/cables",
+    tag = "cabling",
+    params(QueryOptions),
+    responses((status = 200, description = "All cables", body = Vec<Cable>))
+)]
+pub async fn list_cables(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> ApiResult<Json<Vec<Cable>>> {
+    Ok(Json(options.apply(CablingRepository::list_all_cables(&state.db.client).await?)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/cables",
+    tag = "cabling",
+    request_body = CreateCableRequest,
+    responses(
+        (status = 201, description = "Cable created", body = Cable),
+        (status = 400, description = "Invalid port id or mismatched media", body = ApiError),
+        (status = 409, description = "A port has no free connection", body = ApiError),
+    )
+)]
+pub async fn create_cable(
+    State(state): State<AppState>,
+    Json(req): Json<CreateCableRequest>,
+) -> ApiResult<(StatusCode, Json<Cable>)> {
+    let cable = Cable {
+        id: None,
+        a: parse_id(&req.a, "port")?,
+        b: parse_id(&req.b, "port")?,
+        label: req.label.filter(|l| !l.is_empty()),
+        length_m: req.length_m,
+    };
+    match CablingRepository::create_cable(&state.db.client, cable).await {
+        Ok(created) => Ok((StatusCode::CREATED, Json(created))),
+        Err(e) => Err(match e.downcast_ref::<CablingError>() {
+            Some(err @ CablingError::PortOccupied(_)) => ApiError::conflict(err.to_string()),
+            Some(err) => ApiError::bad_request(err.to_string()),
+            None => e.into(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/cables/{id}",
+    tag = "cabling",
+    params(("id" = String, Path, description = "Cable id (`cable:key` or `key`)")),
+    responses(
+        (status = 204, description = "Cable deleted"),
+        (status = 404, description = "Cable not found", body = ApiError),
+    )
+)]
+pub async fn delete_cable(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = CablingRepository::delete_cable(&state.db.client, record_key(&id, "cable")).await?;
+    found(deleted, "Cable", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/cabling/connections",
+    tag = "cabling",
+    responses((status = 200, description = "Connections implied by cabling between linked devices", body = Vec<ConnectionConfig>))
+)]
+pub async fn projected_connections(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<ConnectionConfig>>> {
+    Ok(Json(CablingRepository::project(&state.db.client).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/cabling/sync",
+    tag = "cabling",
+    responses((status = 200, description = "Connections newly created from cabling", body = Vec<ConnectionConfig>))
+)]
+pub async fn sync_cabling(State(state): State<AppState>) -> ApiResult<Json<Vec<ConnectionConfig>>> {
+    Ok(Json(CablingRepository::sync_connections(&state.db.client).await?))
+}
+
+// ============================================================================
+// Desk Booking
+// ============================================================================
+
+use nexosim_hybrid::database::desks::{BookingConflict, BookingKind, DeskBooking, DeskRepository};
+use nexosim_hybrid::database::geo::Desk;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateDeskRequest {
+    pub name: String,
+    pub space_id: String,
+}
 
-    let device = Device {
+#[derive(Deserialize, ToSchema)]
+pub struct CreateDeskBookingRequest {
+    pub desk_id: String,
+    pub person_id: String,
+    #[serde(default)]
+    pub kind: BookingKind,
+    /// `YYYY-MM-DD`; required for day bookings
+    pub date: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/desks",
+    tag = "desks",
+    params(QueryOptions),
+    responses((status = 200, description = "All desks", body = Vec<Desk>))
+)]
+pub async fn list_desks(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> ApiResult<Json<Vec<Desk>>> {
+    Ok(Json(options.apply(geo::GeoRepository::list_all_desks(&state.db.client).await?)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/desks",
+    tag = "desks",
+    request_body = CreateDeskRequest,
+    responses(
+        (status = 201, description = "Desk created", body = Desk),
+        (status = 400, description = "Invalid space id", body = ApiError),
+    )
+)]
+pub async fn create_desk(
+    State(state): State<AppState>,
+    Json(req): Json<CreateDeskRequest>,
+) -> ApiResult<(StatusCode, Json<Desk>)> {
+    let desk = Desk {
         id: None,
         name: req.name,
-        rack_id: rack_thing,
-        position_u: req.position_u,
-        component_id: component_thing,
+        space_id: parse_id(&req.space_id, "space")?,
+        assigned_to: None,
     };
+    let created = geo::GeoRepository::create_desk(&state.db.client, desk).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
 
-    match geo::GeoRepository::create_device(&state.db.client, device).await {
-        Ok(d) => (StatusCode::CREATED, Json(d)).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+#[utoipa::path(
+    get,
+    path = "/api/desk-bookings",
+    tag = "desks",
+    params(("date" = Option<String>, Query, description = "Only bookings holding a desk on this `YYYY-MM-DD` date")),
+    responses((status = 200, description = "Desk bookings", body = Vec<DeskBooking>))
+)]
+pub async fn list_desk_bookings(
+    State(state): State<AppState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> ApiResult<Json<Vec<DeskBooking>>> {
+    let bookings = match params.get("date") {
+        Some(date) => DeskRepository::bookings_on(&state.db.client, date).await?,
+        None => DeskRepository::list_all_bookings(&state.db.client).await?,
+    };
+    Ok(Json(bookings))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/desk-bookings",
+    tag = "desks",
+    request_body = CreateDeskBookingRequest,
+    responses(
+        (status = 201, description = "Desk booked", body = DeskBooking),
+        (status = 400, description = "Invalid id or date", body = ApiError),
+        (status = 409, description = "Desk or person already booked", body = ApiError),
+    )
+)]
+pub async fn create_desk_booking(
+    State(state): State<AppState>,
+    Json(req): Json<CreateDeskBookingRequest>,
+) -> ApiResult<(StatusCode, Json<DeskBooking>)> {
+    let booking = DeskBooking {
+        id: None,
+        desk_id: parse_id(&req.desk_id, "desk")?,
+        person_id: parse_id(&req.person_id, "person")?,
+        kind: req.kind,
+        date: req.date.filter(|d| !d.is_empty()),
+    };
+    match DeskRepository::book(&state.db.client, booking).await {
+        Ok(created) => Ok((StatusCode::CREATED, Json(created))),
+        Err(e) => Err(match e.downcast_ref::<BookingConflict>() {
+            Some(err @ BookingConflict::InvalidDate) => ApiError::bad_request(err.to_string()),
+            Some(err) => ApiError::conflict(err.to_string()),
+            None => e.into(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/desk-bookings/{id}",
+    tag = "desks",
+    params(("id" = String, Path, description = "Booking id (`desk_booking:key` or `key`)")),
+    responses(
+        (status = 204, description = "Booking cancelled"),
+        (status = 404, description = "Booking not found", body = ApiError),
+    )
+)]
+pub async fn cancel_desk_booking(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = DeskRepository::cancel(&state.db.client, record_key(&id, "desk_booking")).await?;
+    found(deleted, "Desk booking", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Calendar Events
+// ============================================================================
+
+use nexosim_hybrid::database::calendar::{CalendarRepository, Meeting};
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    params(QueryOptions),
+    responses((status = 200, description = "All calendar events", body = Vec<Meeting>))
+)]
+pub async fn list_events(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> ApiResult<Json<Vec<Meeting>>> {
+    Ok(Json(options.apply(CalendarRepository::get_all(&state.db.client).await?)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/events",
+    tag = "events",
+    request_body = Meeting,
+    responses(
+        (status = 201, description = "Event created", body = Meeting),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn create_event(
+    State(state): State<AppState>,
+    Json(mut meeting): Json<Meeting>,
+) -> ApiResult<(StatusCode, Json<Meeting>)> {
+    meeting.id = None;
+    let created = CalendarRepository::create(&state.db.client, meeting).await?;
+    state.notifications.invite(&created).await;
+    state.email.invite(&created).await;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/events/{id}",
+    tag = "events",
+    params(("id" = String, Path, description = "Event id (`meeting:key` or `key`)")),
+    request_body = Meeting,
+    responses(
+        (status = 200, description = "Event updated", body = Meeting),
+        (status = 404, description = "Event not found", body = ApiError),
+    )
+)]
+pub async fn update_event(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(mut meeting): Json<Meeting>,
+) -> ApiResult<Json<Meeting>> {
+    meeting.id = None;
+    let key = record_key(&id, "meeting");
+    let updated = CalendarRepository::update(&state.db.client, key, meeting).await?;
+    Ok(Json(found(updated, "Event", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/events/{id}",
+    tag = "events",
+    params(("id" = String, Path, description = "Event id (`meeting:key` or `key`)")),
+    responses(
+        (status = 204, description = "Event deleted"),
+        (status = 404, description = "Event not found", body = ApiError),
+    )
+)]
+pub async fn delete_event(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = CalendarRepository::delete(&state.db.client, record_key(&id, "meeting")).await?;
+    found(deleted, "Event", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Background Jobs
+// ============================================================================
+
+use nexosim_hybrid::database::jobs::{Job, JobRepository, JobStatus};
+
+#[utoipa::path(
+    get,
+    path = "/api/jobs",
+    tag = "jobs",
+    params(QueryOptions),
+    responses((status = 200, description = "All jobs, newest first", body = Vec<Job>))
+)]
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> ApiResult<Json<Vec<Job>>> {
+    Ok(Json(options.apply(JobRepository::get_all(&state.db.client).await?)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job id (`job:key` or `key`)")),
+    responses(
+        (status = 200, description = "Job status and progress", body = Job),
+        (status = 404, description = "Job not found", body = ApiError),
+    )
+)]
+pub async fn get_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Job>> {
+    let job = JobRepository::get_by_id(&state.db.client, record_key(&id, "job")).await?;
+    Ok(Json(found(job, "Job", &id)?))
+}
+
+/// Re-queue a job that exhausted its automatic retries
+#[utoipa::path(
+    post,
+    path = "/api/jobs/{id}/retry",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job id (`job:key` or `key`)")),
+    responses(
+        (status = 202, description = "Job queued again"),
+        (status = 404, description = "Job not found", body = ApiError),
+        (status = 409, description = "Job has not failed", body = ApiError),
+    )
+)]
+pub async fn retry_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let key = record_key(&id, "job");
+    let job = found(JobRepository::get_by_id(&state.db.client, key).await?, "Job", &id)?;
+    if job.status != JobStatus::Failed {
+        return Err(ApiError::conflict(format!("Job '{id}' is {}", job.status)));
+    }
+    state.jobs.retry(key).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+// ============================================================================
+// Notifications
+// ============================================================================
+
+use crate::notifications::PersonaQuery;
+use nexosim_hybrid::database::notifications::{NotificationRepository, NotificationView};
+
+/// Inbox size returned by `GET /api/notifications`
+const INBOX_LIMIT: usize = 50;
+
+#[derive(Deserialize, ToSchema)]
+pub struct PersonaRequest {
+    /// Persona (person name) doing the reading
+    pub persona: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UnreadCount {
+    pub persona: String,
+    pub unread: usize,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    tag = "notifications",
+    params(PersonaQuery),
+    responses((status = 200, description = "Newest notifications for the persona", body = Vec<NotificationView>))
+)]
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> ApiResult<Json<Vec<NotificationView>>> {
+    let persona = acting_as(&state, &jar, &query.persona).await?;
+    let inbox = NotificationRepository::list_for(&state.db.client, &persona, INBOX_LIMIT).await?;
+    Ok(Json(inbox))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications/unread",
+    tag = "notifications",
+    params(PersonaQuery),
+    responses((status = 200, description = "Unread count for the persona", body = UnreadCount))
+)]
+pub async fn unread_notifications(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> ApiResult<Json<UnreadCount>> {
+    let persona = acting_as(&state, &jar, &query.persona).await?;
+    let unread = NotificationRepository::unread_count(&state.db.client, &persona).await?;
+    Ok(Json(UnreadCount { persona, unread }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications/{id}/read",
+    tag = "notifications",
+    params(("id" = String, Path, description = "Notification id (`notification:key` or `key`)")),
+    request_body = PersonaRequest,
+    responses(
+        (status = 200, description = "Notification as the persona now sees it", body = NotificationView),
+        (status = 404, description = "Notification not found for this persona", body = ApiError),
+    )
+)]
+pub async fn read_notification(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Json(req): Json<PersonaRequest>,
+) -> ApiResult<Json<NotificationView>> {
+    let persona = acting_as(&state, &jar, &req.persona).await?;
+    let key = record_key(&id, "notification");
+    let notification = NotificationRepository::mark_read(&state.db.client, key, &persona).await?;
+    Ok(Json(found(notification, "Notification", &id)?.view_for(&persona)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications/read-all",
+    tag = "notifications",
+    request_body = PersonaRequest,
+    responses((status = 200, description = "Unread count afterwards (always zero)", body = UnreadCount))
+)]
+pub async fn read_all_notifications(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(req): Json<PersonaRequest>,
+) -> ApiResult<Json<UnreadCount>> {
+    let persona = acting_as(&state, &jar, &req.persona).await?;
+    NotificationRepository::mark_all_read(&state.db.client, &persona).await?;
+    let unread = NotificationRepository::unread_count(&state.db.client, &persona).await?;
+    Ok(Json(UnreadCount { persona, unread }))
+}
+
+// ============================================================================
+// Chat
+// ============================================================================
+
+use nexosim_hybrid::database::chat::{ChatError, ChatRepository, ConversationView, MessageView};
+
+#[derive(Deserialize, ToSchema)]
+pub struct StartConversationRequest {
+    /// Persona starting the conversation
+    pub persona: String,
+    /// Names of the other participants
+    pub participants: Vec<String>,
+    /// Group name; leave unset for a direct message
+    pub title: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SendMessageRequest {
+    /// Persona sending the message
+    pub persona: String,
+    pub body: String,
+}
+
+fn chat_error(e: anyhow::Error) -> ApiError {
+    match e.downcast_ref::<ChatError>() {
+        Some(err @ ChatError::NotParticipant(_)) => ApiError::not_found(err.to_string()),
+        Some(err) => ApiError::bad_request(err.to_string()),
+        None => e.into(),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/chat/conversations",
+    tag = "chat",
+    params(PersonaQuery),
+    responses(
+        (status = 200, description = "The persona's conversations, most recent first", body = Vec<ConversationView>),
+        (status = 400, description = "Unknown persona", body = ApiError),
+    )
+)]
+pub async fn list_conversations(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> ApiResult<Json<Vec<ConversationView>>> {
+    let persona = acting_as(&state, &jar, &query.persona).await?;
+    let conversations = ChatRepository::conversations_for(&state.db.client, &persona)
+        .await
+        .map_err(chat_error)?;
+    Ok(Json(conversations))
+}
+
+/// Start a conversation; a direct message reuses the existing one between the same people
+#[utoipa::path(
+    post,
+    path = "/api/chat/conversations",
+    tag = "chat",
+    request_body = StartConversationRequest,
+    responses(
+        (status = 201, description = "Conversation to post into", body = ConversationView),
+        (status = 400, description = "Unknown person or too few participants", body = ApiError),
+    )
+)]
+pub async fn start_conversation(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(req): Json<StartConversationRequest>,
+) -> ApiResult<impl IntoResponse> {
+    let persona = acting_as(&state, &jar, &req.persona).await?;
+    let now = crate::clock::now();
+    let conversation = ChatRepository::start(&state.db.client, &persona, &req.participants, req.title, &now)
+        .await
+        .map_err(chat_error)?;
+    Ok((StatusCode::CREATED, Json(conversation)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/chat/conversations/{id}/messages",
+    tag = "chat",
+    params(
+        ("id" = String, Path, description = "Conversation id (`conversation:key` or `key`)"),
+        PersonaQuery,
+    ),
+    responses(
+        (status = 200, description = "Messages, oldest first", body = Vec<MessageView>),
+        (status = 404, description = "Conversation not found for this persona", body = ApiError),
+    )
+)]
+pub async fn list_messages(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> ApiResult<Json<Vec<MessageView>>> {
+    let persona = acting_as(&state, &jar, &query.persona).await?;
+    let messages = ChatRepository::messages(&state.db.client, record_key(&id, "conversation"), &persona)
+        .await
+        .map_err(chat_error)?;
+    Ok(Json(found(messages, "Conversation", &id)?))
+}
+
+/// Post a message; it is also delivered to the participants' open chat sockets
+#[utoipa::path(
+    post,
+    path = "/api/chat/conversations/{id}/messages",
+    tag = "chat",
+    params(("id" = String, Path, description = "Conversation id (`conversation:key` or `key`)")),
+    request_body = SendMessageRequest,
+    responses(
+        (status = 201, description = "Stored message", body = MessageView),
+        (status = 400, description = "Empty or oversized message", body = ApiError),
+        (status = 404, description = "Conversation not found for this persona", body = ApiError),
+    )
+)]
+pub async fn send_message(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Json(req): Json<SendMessageRequest>,
+) -> ApiResult<impl IntoResponse> {
+    let persona = acting_as(&state, &jar, &req.persona).await?;
+    let sent = state
+        .chat
+        .send(record_key(&id, "conversation"), &persona, &req.body)
+        .await
+        .map_err(chat_error)?;
+    Ok((StatusCode::CREATED, Json(found(sent, "Conversation", &id)?)))
+}
+
+// ============================================================================
+// Reports
+// ============================================================================
+
+use nexosim_hybrid::database::reports::{GeneratedReport, ReportDocument, ReportFormat, ReportKind, ReportRepository};
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct ReportFormatQuery {
+    /// `pdf` (default) or `html`
+    pub format: Option<String>,
+}
+
+/// Document bytes with a content type and download file name
+fn report_response(report: &GeneratedReport) -> ApiResult<axum::response::Response> {
+    let disposition = format!("inline; filename=\"{}\"", report.file_name());
+    let headers = [
+        (axum::http::header::CONTENT_TYPE, report.format.content_type().to_string()),
+        (axum::http::header::CONTENT_DISPOSITION, disposition),
+    ];
+    Ok((headers, report.bytes()?).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports",
+    tag = "reports",
+    params(QueryOptions),
+    responses((status = 200, description = "Stored report documents, newest first", body = Vec<ReportDocument>))
+)]
+pub async fn list_reports(
+    State(state): State<AppState>,
+    Query(options): Query<QueryOptions>,
+) -> ApiResult<Json<Vec<ReportDocument>>> {
+    Ok(Json(options.apply(ReportRepository::list(&state.db.client).await?)))
+}
+
+/// Generate a report from current data; the document is stored and returned
+#[utoipa::path(
+    get,
+    path = "/api/reports/{kind}",
+    tag = "reports",
+    params(
+        ("kind" = String, Path, description = "`asset-inventory`, `personnel-roster` or `simulation-summary`"),
+        ReportFormatQuery,
+    ),
+    responses(
+        (status = 200, description = "Rendered report (PDF, or HTML with `format=html`)", content_type = "application/pdf"),
+        (status = 400, description = "Unknown report kind or format", body = ApiError),
+    )
+)]
+pub async fn generate_report(
+    State(state): State<AppState>,
+    Path(kind): Path<String>,
+    Query(query): Query<ReportFormatQuery>,
+) -> ApiResult<axum::response::Response> {
+    let kind: ReportKind = kind.parse().map_err(|e: anyhow::Error| ApiError::bad_request(e.to_string()))?;
+    let format: ReportFormat = match query.format.as_deref() {
+        Some(format) => format.parse().map_err(|e: anyhow::Error| ApiError::bad_request(e.to_string()))?,
+        None => ReportFormat::default(),
+    };
+    let report = crate::reports::generate(&state.db.client, kind, format).await?;
+    report_response(&report)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/documents/{id}",
+    tag = "reports",
+    params(("id" = String, Path, description = "Report id (`report:key` or `key`)")),
+    responses(
+        (status = 200, description = "Stored report in the format it was generated in", content_type = "application/pdf"),
+        (status = 404, description = "Report not found", body = ApiError),
+    )
+)]
+pub async fn get_report_document(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<axum::response::Response> {
+    let report = ReportRepository::get(&state.db.client, record_key(&id, "report")).await?;
+    report_response(&found(report, "Report", &id)?)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/reports/documents/{id}",
+    tag = "reports",
+    params(("id" = String, Path, description = "Report id (`report:key` or `key`)")),
+    responses(
+        (status = 204, description = "Report deleted"),
+        (status = 404, description = "Report not found", body = ApiError),
+    )
+)]
+pub async fn delete_report_document(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = ReportRepository::delete(&state.db.client, record_key(&id, "report")).await?;
+    found(deleted, "Report", &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct ExportFormatQuery {
+    /// `csv` (default), `json` or `xlsx`
+    pub format: Option<String>,
+}
+
+/// Download a list as a file, filtered like its list endpoint
+#[utoipa::path(
+    get,
+    path = "/api/export/{resource}",
+    tag = "export",
+    params(
+        ("resource" = String, Path, description = "List to export, e.g. `sites`, `people` or `cables`"),
+        ExportFormatQuery,
+        QueryOptions,
+    ),
+    responses(
+        (status = 200, description = "Rows as CSV, or JSON/XLSX with `format`", content_type = "text/csv"),
+        (status = 400, description = "Unknown format", body = ApiError),
+        (status = 404, description = "Unknown resource", body = ApiError),
+    )
+)]
+pub async fn export_list(
+    State(state): State<AppState>,
+    Path(resource): Path<String>,
+    Query(query): Query<ExportFormatQuery>,
+    Query(options): Query<QueryOptions>,
+) -> ApiResult<axum::response::Response> {
+    use crate::export::{self, ExportFormat};
+
+    let format: ExportFormat = match query.format.as_deref() {
+        Some(format) => format.parse().map_err(|e: anyhow::Error| ApiError::bad_request(e.to_string()))?,
+        None => ExportFormat::default(),
+    };
+    let rows = export::fetch(&state.db.client, &resource, &options).await?;
+    let rows = rows.ok_or_else(|| {
+        ApiError::not_found(format!(
+            "Cannot export '{resource}'; expected one of {}",
+            export::RESOURCES.join(", ")
+        ))
+    })?;
+
+    let file_name = format!("{}-{}.{}", resource, chrono::Utc::now().format("%Y%m%d"), format.extension());
+    let headers = [
+        (axum::http::header::CONTENT_TYPE, format.content_type().to_string()),
+        (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{file_name}\"")),
+    ];
+    Ok((headers, export::body(&resource, rows, format)?).into_response())
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Fields a CSV import into `resource` can map columns onto
+#[utoipa::path(
+    get,
+    path = "/api/import/{resource}",
+    tag = "import",
+    params(("resource" = String, Path, description = "Import target, `people` or `sites`")),
+    responses(
+        (status = 200, description = "Target fields with required flag and kind", body = Object),
+        (status = 404, description = "Unknown resource", body = ApiError),
+    )
+)]
+pub async fn import_fields(Path(resource): Path<String>) -> ApiResult<Json<Vec<ui_core::features::ImportField>>> {
+    crate::import::fields(&resource).map(Json).ok_or_else(|| unknown_import(&resource))
+}
+
+/// Validate a CSV file and import its valid rows, or only validate with `dry_run`
+#[utoipa::path(
+    post,
+    path = "/api/import/{resource}",
+    tag = "import",
+    params(("resource" = String, Path, description = "Import target, `people` or `sites`")),
+    request_body(content = Object, description = "CSV text, header flag, field per column and `dry_run`"),
+    responses(
+        (status = 200, description = "Row counts and row-level errors", body = Object),
+        (status = 404, description = "Unknown resource", body = ApiError),
+    )
+)]
+pub async fn run_import(
+    State(state): State<AppState>,
+    Path(resource): Path<String>,
+    Json(request): Json<ui_core::features::ImportRequest>,
+) -> ApiResult<Json<ui_core::features::ImportReport>> {
+    let report = crate::import::run(&state.db.client, &resource, &request).await?;
+    report.map(Json).ok_or_else(|| unknown_import(&resource))
+}
+
+fn unknown_import(resource: &str) -> ApiError {
+    ApiError::not_found(format!(
+        "Cannot import into '{resource}'; expected one of {}",
+        crate::import::RESOURCES.join(", ")
+    ))
+}
+
+// ============================================================================
+// Feature Flags
+// ============================================================================
+
+#[derive(Deserialize, ToSchema)]
+pub struct FlagUpdate {
+    /// New state; `null` drops the override so the flag follows its default
+    pub enabled: Option<bool>,
+}
+
+/// Every feature flag with its description, current state and default
+#[utoipa::path(
+    get,
+    path = "/api/flags",
+    tag = "flags",
+    responses((status = 200, description = "All feature flags", body = Object))
+)]
+pub async fn list_flags(State(state): State<AppState>) -> ApiResult<Json<Vec<ui_core::hooks::FeatureFlag>>> {
+    Ok(Json(crate::flags::list(&state.db.client, &state.settings).await?))
+}
+
+/// Turn a feature flag on or off at runtime, or reset it to its default
+#[utoipa::path(
+    put,
+    path = "/api/flags/{name}",
+    tag = "flags",
+    params(("name" = String, Path, description = "Flag name, e.g. `new_calendar`")),
+    request_body = FlagUpdate,
+    responses(
+        (status = 200, description = "The flag's new state", body = Object),
+        (status = 404, description = "Unknown flag", body = ApiError),
+    )
+)]
+pub async fn set_flag(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(update): Json<FlagUpdate>,
+) -> ApiResult<Json<ui_core::hooks::FeatureFlag>> {
+    let flag = crate::flags::set(&state.db.client, &state.settings, &name, update.enabled).await?;
+    flag.map(Json).ok_or_else(|| ApiError::not_found(format!("No feature flag named '{name}'")))
+}
+
+// ============================================================================
+// Administration
+// ============================================================================
+
+use nexosim_hybrid::database::maintenance::{MaintenanceRepository, MigrationStatus};
+
+#[derive(Deserialize, ToSchema)]
+pub struct SeedRequest {
+    /// Scenario TOML to seed from; defaults to `paths.scenario`
+    pub scenario: Option<String>,
+    /// Clear the scenario tables first, so the seed replaces what is there
+    #[serde(default)]
+    pub reset: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SeedReport {
+    pub scenario: String,
+    /// Tables emptied before seeding; 0 unless `reset` was set
+    pub cleared_tables: usize,
+    /// False when the database already held a scenario and `reset` was not set
+    pub seeded: bool,
+    pub components: usize,
+    pub people: usize,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VacuumQuery {
+    /// Only count what would be removed
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Seed the scenario, optionally replacing the current one
+#[utoipa::path(
+    post,
+    path = "/api/admin/seed",
+    tag = "admin",
+    request_body = SeedRequest,
+    responses(
+        (status = 200, description = "What was seeded", body = SeedReport),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn seed_scenario(
+    State(state): State<AppState>,
+    Json(req): Json<SeedRequest>,
+) -> ApiResult<Json<SeedReport>> {
+    let db = &state.db.client;
+    let scenario = req
+        .scenario
+        .unwrap_or_else(|| state.settings.paths.scenario.to_string_lossy().into_owned());
+    let cleared_tables = if req.reset { MaintenanceRepository::clear_scenario(db).await? } else { 0 };
+    let seeded = ComponentRepository::get_all(db).await?.is_empty();
+    ComponentRepository::seed_from_toml(db, &scenario).await?;
+
+    Ok(Json(SeedReport {
+        scenario,
+        cleared_tables,
+        seeded,
+        components: ComponentRepository::get_all(db).await?.len(),
+        people: geo::GeoRepository::list_all_people(db).await?.len(),
+    }))
+}
+
+/// Everything the scenario seeded, as currently stored
+#[utoipa::path(
+    get,
+    path = "/api/admin/scenario",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Scenario snapshot: regions through events, keyed by table", body = Object),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn export_scenario(State(state): State<AppState>) -> ApiResult<impl IntoResponse> {
+    Ok(Json(MaintenanceRepository::snapshot(&state.db.client).await?))
+}
+
+/// Known schema migrations and when each was applied
+#[utoipa::path(
+    get,
+    path = "/api/admin/migrations",
+    tag = "admin",
+    responses((status = 200, description = "Migration status", body = Vec<MigrationStatus>))
+)]
+pub async fn list_migrations(State(state): State<AppState>) -> ApiResult<Json<Vec<MigrationStatus>>> {
+    Ok(Json(MaintenanceRepository::migrations(&state.db.client).await?))
+}
+
+/// Apply pending schema migrations
+#[utoipa::path(
+    post,
+    path = "/api/admin/migrations",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Ids of the migrations applied", body = Vec<String>),
+        (status = 500, description = "A migration failed", body = ApiError),
+    )
+)]
+pub async fn run_migrations(State(state): State<AppState>) -> ApiResult<Json<Vec<String>>> {
+    let now = chrono::Utc::now().to_rfc3339();
+    Ok(Json(MaintenanceRepository::migrate(&state.db.client, &now).await?))
+}
+
+/// Remove records left behind by deletes, e.g. the buildings of a deleted site
+#[utoipa::path(
+    post,
+    path = "/api/admin/vacuum",
+    tag = "admin",
+    params(VacuumQuery),
+    responses((status = 200, description = "Records removed (or found, for a dry run) by table", body = Object))
+)]
+pub async fn vacuum(
+    State(state): State<AppState>,
+    Query(query): Query<VacuumQuery>,
+) -> ApiResult<Json<std::collections::BTreeMap<String, usize>>> {
+    Ok(Json(MaintenanceRepository::vacuum(&state.db.client, query.dry_run).await?))
+}
+
 /// List all people for persona selection
-pub async fn list_people(State(state): State<AppState>) -> impl IntoResponse {
+///
+/// Streamed as NDJSON, one person per line, with `Accept: application/x-ndjson`.
+#[utoipa::path(
+    get,
+    path = "/api/people",
+    tag = "people",
+    params(QueryOptions),
+    responses((status = 200, description = "All people", content(
+        (Vec<Person> = "application/json"),
+        (Person = "application/x-ndjson"),
+    )))
+)]
+pub async fn list_people(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Query(options): Query<QueryOptions>,
+) -> impl IntoResponse {
     use nexosim_hybrid::database::geo;
     match geo::GeoRepository::list_all_people(&state.db.client).await {
-        Ok(people) => Json(people).into_response(),
+        Ok(people) => crate::ndjson::list(&headers, options.apply(people)),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
+/// List network assets
+///
+/// Streamed as NDJSON, one asset per line, with `Accept: application/x-ndjson`.
+#[utoipa::path(
+    get,
+    path = "/api/assets",
+    tag = "assets",
+    params(QueryOptions),
+    responses(
+        (status = 200, description = "All network assets", content(
+            (Object = "application/json"),
+            (Object = "application/x-ndjson"),
+        )),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn list_assets(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Query(options): Query<QueryOptions>,
+) -> ApiResult<axum::response::Response> {
+    let assets = geo::GeoRepository::list_all_assets(&state.db.client).await?;
+    Ok(crate::ndjson::list(&headers, options.apply(assets)))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreatePersonRequest {
+    pub name: String,
+    pub email: String,
+    pub title: String,
+    pub department: String,
+    /// `site:key` or `key`
+    pub site_id: String,
+    /// `person:key` or `key`
+    pub manager_id: Option<String>,
+    #[serde(default)]
+    pub role: nexosim_hybrid::config::RoleType,
+}
+
+impl CreatePersonRequest {
+    fn into_person(self) -> ApiResult<Person> {
+        Ok(Person {
+            id: None,
+            name: self.name,
+            email: self.email,
+            title: self.title,
+            department: self.department,
+            site_id: parse_id(&self.site_id, "site")?,
+            space_id: None,
+            manager_id: self.manager_id.as_deref().map(|id| parse_id(id, "person")).transpose()?,
+            role: self.role,
+            photo: None,
+            bio: None,
+            desk_phone: None,
+            cell_phone: None,
+            photo_data: None,
+        })
+    }
+}
+
+/// Add a person, e.g. a new user account
+#[utoipa::path(
+    post,
+    path = "/api/people",
+    tag = "people",
+    request_body = CreatePersonRequest,
+    responses(
+        (status = 201, description = "Person created", body = Person),
+        (status = 400, description = "Invalid site or manager id", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn create_person(
+    State(state): State<AppState>,
+    Json(req): Json<CreatePersonRequest>,
+) -> ApiResult<(StatusCode, Json<Person>)> {
+    let created = geo::GeoRepository::create_person(&state.db.client, req.into_person()?).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
 /// Get photo for a person by ID (returns as binary image)
+#[utoipa::path(
+    get,
+    path = "/api/people/{id}/photo",
+    tag = "people",
+    params(("id" = String, Path, description = "Person id")),
+    responses(
+        (status = 200, description = "Photo", content_type = "image/png"),
+        (status = 404, description = "Person or photo not found"),
+    )
+)]
 pub async fn get_person_photo(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -1,21 +1,46 @@
 //! App module - Page rendering with Leptos 0.8 SSR
+//!
+//! Pages are rendered on the server; interactive parts are `#[island]`
+//! components from `gui_server::islands`, hydrated by the wasm bundle that
+//! `build-hydrate.sh` writes to `assets/pkg/`.
 
+use leptos::config::LeptosOptions;
+use leptos::hydration::HydrationScripts;
 use leptos::prelude::*;
 use leptos::IntoView;
+use std::sync::Arc;
 
+use crate::static_assets::asset_url;
 use crate::{Region, SimulationRun, Site};
 use nexosim_hybrid::config::{ComponentConfig, ConnectionConfig};
+use nexosim_hybrid::database::asset_lifecycle::{LifecycleTransition, MaintenanceTicket};
+use nexosim_hybrid::database::cabling::{Cable, PatchPanel, Port};
 use nexosim_hybrid::database::calendar::Meeting;
-use nexosim_hybrid::database::geo::{Building, Device, Floor, GeoFeature, NetworkAsset, Rack, Space};
+use nexosim_hybrid::database::desks::DeskBooking;
+use nexosim_hybrid::database::device_links::PhysicalLocation;
+use nexosim_hybrid::database::geo::{Building, Desk, Device, Floor, GeoFeature, NetworkAsset, Rack, Space};
+use nexosim_hybrid::database::jobs::Job;
+use nexosim_hybrid::database::schedules::Schedule;
+use nexosim_hybrid::database::reports::ReportDocument;
+use nexosim_hybrid::database::webhooks::{Webhook, WebhookDelivery};
+use ui_core::hooks::flags::is_enabled;
 // Import components from the new module structure
 use crate::components::assets_module::AssetsModule;
 use crate::components::calendar_module::CalendarModule;
+use crate::components::capacity_tab::CapacityTab;
 use crate::components::chat_module::ChatModule;
 use crate::components::components_tab::ComponentsTab;
 use crate::components::connections_tab::ConnectionsTab;
 use crate::components::contracts_module::ContractsModule;
 use crate::components::email_module::EmailModule;
 use crate::components::finance_module::FinanceModule;
+use crate::components::flags_tab::FlagsTab;
+use crate::components::jobs_tab::JobsTab;
+use crate::components::webhooks_tab::WebhooksTab;
+use crate::components::reports_tab::ReportsTab;
+use crate::components::maintenance_tab::MaintenanceTab;
+use crate::components::incidents_tab::IncidentsTab;
+use crate::components::oncall_tab::OnCallTab;
 use crate::components::sites_tab::SitesTab;
 use crate::components::meetings_module::MeetingsModule;
 use crate::components::metrics_tab::MetricsTab;
@@ -23,6 +48,7 @@ use crate::components::persona_switcher::PersonaSwitcher;
 use crate::components::personnel_module::{PersonnelModule, PersonnelModuleOrgChart};
 use crate::components::sign_in_screen::SignInScreen;
 use crate::components::user_session_widget::UserSessionWidget;
+use gui_server::islands::{NotificationCenter, PresenceClient};
 use crate::components::presentations_module::PresentationsModule;
 use crate::components::requirements_module::RequirementsModule;
 use crate::components::development_module::DevelopmentModule;
@@ -35,6 +61,8 @@ use crate::components::widgets::landing_page::LandingPage;
 /// Data needed to render the page
 pub struct PageData {
     pub components: Vec<ComponentConfig>,
+    /// Components switched away from simulated telemetry, with their readings
+    pub telemetry_sources: Vec<nexosim_hybrid::database::telemetry_sources::TelemetrySource>,
     pub connections: Vec<ConnectionConfig>,
     pub regions: Vec<Region>,
     pub sites: Vec<Site>,
@@ -43,8 +71,48 @@ pub struct PageData {
     pub spaces: Vec<Space>,
     pub racks: Vec<Rack>,
     pub devices: Vec<Device>,
+    /// Where each linked simulation component is racked, by component id
+    pub component_locations: std::collections::HashMap<u32, PhysicalLocation>,
+    pub patch_panels: Vec<PatchPanel>,
+    pub ports: Vec<Port>,
+    pub cables: Vec<Cable>,
+    /// Connections implied by cabling that do not exist yet
+    pub pending_connections: Vec<ConnectionConfig>,
     pub assets: Vec<NetworkAsset>,
+    pub maintenance_tickets: Vec<MaintenanceTicket>,
+    pub expiring_warranties: Vec<NetworkAsset>,
+    pub lifecycle_transitions: Vec<LifecycleTransition>,
+    pub desks: Vec<Desk>,
+    pub desk_bookings: Vec<DeskBooking>,
+    /// Date the desk booking panel shows (`YYYY-MM-DD`)
+    pub booking_date: String,
+    pub today: String,
     pub runs: Vec<SimulationRun>,
+    /// Keys of the runs picked for comparison, baseline first
+    pub compare: Vec<String>,
+    /// The picked runs lined up, when at least two are picked
+    pub comparison: Option<Result<operations::comparison::Comparison, String>>,
+    pub sandboxes: Vec<nexosim_hybrid::database::sandbox::Sandbox>,
+    /// The sandbox picked with `sandbox=`, with its edits since branching
+    pub open_sandbox: Option<(nexosim_hybrid::database::sandbox::Sandbox, crate::sandbox::SandboxDiff)>,
+    /// Utilization and exhaustion figures, loaded for the capacity tab
+    pub capacity: Option<crate::capacity::CapacityReport>,
+    /// The device being checked on the capacity tab
+    pub capacity_check: Option<crate::capacity::DeviceCheck>,
+    /// Every incident, loaded for the incident board
+    pub incidents: Vec<nexosim_hybrid::database::incidents::Incident>,
+    /// Key of the incident open on the board
+    pub selected_incident: Option<String>,
+    pub rotations: Vec<nexosim_hybrid::database::oncall::Rotation>,
+    pub escalation_policies: Vec<nexosim_hybrid::database::oncall::EscalationPolicy>,
+    /// Who is on call for each rotation right now
+    pub on_call: Vec<crate::oncall::OnCallNow>,
+    pub jobs: Vec<Job>,
+    pub schedules: Vec<Schedule>,
+    pub webhooks: Vec<Webhook>,
+    /// Latest deliveries across all webhooks, newest first
+    pub webhook_deliveries: Vec<WebhookDelivery>,
+    pub reports: Vec<ReportDocument>,
     pub geo_features: Vec<GeoFeature>,
     pub cached_country_paths: Vec<String>,
     pub cached_state_paths: Vec<String>,
@@ -61,15 +129,43 @@ pub struct PageData {
     pub modal: Option<String>,
 
     // Persona system fields
-    #[allow(dead_code)]
     pub dev_mode: bool,
+    /// People sign in through the OIDC provider
+    pub sso: bool,
     pub current_persona: Option<String>,
     pub people: Vec<nexosim_hybrid::database::geo::Person>,
     pub meetings: Vec<Meeting>,
+    /// Feature flags in their current state
+    pub flags: Vec<ui_core::hooks::FeatureFlag>,
+    /// Which emails the current persona gets
+    pub email_preferences: operations::email::EmailPreferences,
+    /// Emails to the current persona, newest first
+    pub email_outbox: Vec<nexosim_hybrid::database::email::OutboundEmail>,
+}
+
+/// What the page around the active tab needs: header, sidebar and persona switcher
+pub struct Shell {
+    pub active_tab: String,
+    pub current_persona: Option<String>,
+    pub people: Vec<nexosim_hybrid::database::geo::Person>,
+}
+
+/// Render the active tab on its own, ready to be cached and passed to [`render_page`]
+pub fn render_tab_html(data: &PageData) -> String {
+    let owner = Owner::new();
+    owner.set();
+
+    owner.with(|| {
+        use leptos::tachys::view::RenderHtml;
+        let view = render_tab(data).into_view();
+        let mut buf = String::new();
+        view.to_html_with_buf(&mut buf, &mut Default::default(), true, false, vec![]);
+        buf
+    })
 }
 
-/// Render the complete HTML page using Leptos 0.8 SSR
-pub fn render_page(data: PageData) -> String {
+/// Render the complete HTML page using Leptos 0.8 SSR, around a tab from [`render_tab_html`]
+pub fn render_page(shell: Shell, tab_html: Arc<str>) -> String {
     // Create a reactive owner for SSR context
     let owner = Owner::new();
     owner.set();
@@ -77,7 +173,7 @@ pub fn render_page(data: PageData) -> String {
     // Use the reactive owner to render
     let html = owner.with(|| {
         use leptos::tachys::view::RenderHtml;
-        let view = view! { <HomePage data=data /> }.into_view();
+        let view = view! { <HomePage shell=shell tab_html=tab_html /> }.into_view();
         let mut buf = String::new();
         view.to_html_with_buf(&mut buf, &mut Default::default(), true, false, vec![]);
         buf
@@ -87,9 +183,26 @@ pub fn render_page(data: PageData) -> String {
     format!("<!DOCTYPE html>{}", html)
 }
 
+/// Location of the islands wasm bundle built by `build-hydrate.sh`,
+/// or its fingerprinted copy when `build-assets.sh` has been run
+fn hydration_options() -> LeptosOptions {
+    match crate::static_assets::hydration_hash_file() {
+        Some(hash_file) => LeptosOptions::builder()
+            .output_name("gui_server")
+            .site_pkg_dir(crate::static_assets::DIST_URL.trim_start_matches('/'))
+            .hash_files(true)
+            .hash_file(hash_file)
+            .build(),
+        None => LeptosOptions::builder()
+            .output_name("gui_server")
+            .site_pkg_dir("assets/pkg")
+            .build(),
+    }
+}
+
 #[component]
-fn HomePage(data: PageData) -> impl IntoView {
-    let active_tab = data.active_tab.clone();
+fn HomePage(shell: Shell, tab_html: Arc<str>) -> impl IntoView {
+    let active_tab = shell.active_tab.clone();
 
     view! {
         <html lang="en">
@@ -97,7 +210,9 @@ fn HomePage(data: PageData) -> impl IntoView {
                 <meta charset="UTF-8"/>
                 <meta name="viewport" content="width=device-width, initial-scale=1.0"/>
                 <title>"Rubigo"</title>
-                <link rel="stylesheet" href="/assets/style.css"/>
+                <link rel="stylesheet" href=asset_url("style.css")/>
+                <link rel="stylesheet" href=asset_url("ui-core.css")/>
+                <HydrationScripts options=hydration_options() islands=true/>
             </head>
             <body>
                 <div class="app-container">
@@ -112,25 +227,25 @@ fn HomePage(data: PageData) -> impl IntoView {
                                 <span>"Detecting"</span>
                             </div>
                             <div id="sse-status" class="sse-status">"Connecting..."</div>
-                            <UserSessionWidget current_persona=data.current_persona.clone() />
+                            {shell.current_persona.clone().map(|persona| view! { <PresenceClient persona=persona /> })}
+                            {shell.current_persona.clone().map(|persona| view! { <NotificationCenter persona=persona /> })}
+                            <UserSessionWidget current_persona=shell.current_persona.clone() />
                         </div>
                     </header>
 
                     <div class="app-layout">
-                        {if data.current_persona.is_some() {
+                        {if shell.current_persona.is_some() {
                             view! { <Sidebar active_tab=active_tab.clone() /> }.into_any()
                         } else {
                             view! { <span></span> }.into_any()
                         }}
                         
-                        <main class="main-content">
-                            {render_tab(&data)}
-                        </main>
+                        <main class="main-content" inner_html=tab_html></main>
                     </div>
 
                     <PersonaSwitcher
-                        current_persona=data.current_persona.clone()
-                        people=data.people.clone()
+                        current_persona=shell.current_persona.clone()
+                        people=shell.people.clone()
                     />
                 </div>
 
@@ -196,7 +311,7 @@ fn HomePage(data: PageData) -> impl IntoView {
                     })();
                     "#
                 </script>
-                <script src="/assets/script.js"></script>
+                <script src=asset_url("script.js")></script>
             </body>
         </html>
     }
@@ -221,7 +336,8 @@ fn TabLink(tab: &'static str, active: String, label: &'static str) -> impl IntoV
 fn render_tab(data: &PageData) -> impl IntoView {
     // If no persona selected, show sign-in screen
     if data.current_persona.is_none() {
-        return view! { <SignInScreen /> }.into_any();
+        let personas = data.dev_mode || !data.sso;
+        return view! { <SignInScreen sso=data.sso personas=personas /> }.into_any();
     }
 
     // Get current persona from people list
@@ -236,12 +352,18 @@ fn render_tab(data: &PageData) -> impl IntoView {
             region_count=data.regions.len()
             site_count=data.sites.len()
             building_count=data.buildings.len()
+            runs=data.runs.clone()
         /> }.into_any(),
-        "components" => view! { <ComponentsTab components=data.components.clone()/> }.into_any(),
+        "components" => view! { <ComponentsTab components=data.components.clone() sources=data.telemetry_sources.clone()/> }.into_any(),
         "connections" => view! { <ConnectionsTab connections=data.connections.clone() components=data.components.clone()/> }.into_any(),
-        "simulation" => view! { <SimulationTab runs=data.runs.clone()/> }.into_any(),
+        "simulation" => view! { <SimulationTab runs=data.runs.clone() components=data.components.clone() locations=data.component_locations.clone() selected=data.compare.clone() comparison=data.comparison.clone() sandboxes=data.sandboxes.clone() open_sandbox=data.open_sandbox.clone()/> }.into_any(),
         "metrics" => view! { <MetricsTab/> }.into_any(),
-        "sites" => view! { <SitesTab regions=data.regions.clone() sites=data.sites.clone() buildings=data.buildings.clone() floors=data.floors.clone() spaces=data.spaces.clone() racks=data.racks.clone() devices=data.devices.clone() geo_features=data.geo_features.clone() cached_country_paths=data.cached_country_paths.clone() cached_state_paths=data.cached_state_paths.clone() cached_globe_country_paths=data.cached_globe_country_paths.clone() cached_globe_state_paths=data.cached_globe_state_paths.clone() view=data.geo_view.clone()/> }.into_any(),
+        "jobs" => view! { <JobsTab jobs=data.jobs.clone() schedules=data.schedules.clone()/> }.into_any(),
+        "flags" => view! { <FlagsTab flags=data.flags.clone()/> }.into_any(),
+        "webhooks" => view! { <WebhooksTab webhooks=data.webhooks.clone() deliveries=data.webhook_deliveries.clone()/> }.into_any(),
+        "reports" => view! { <ReportsTab reports=data.reports.clone()/> }.into_any(),
+        "capacity" => view! { <CapacityTab report=data.capacity.clone() check=data.capacity_check.clone()/> }.into_any(),
+        "sites" => view! { <SitesTab regions=data.regions.clone() sites=data.sites.clone() buildings=data.buildings.clone() floors=data.floors.clone() spaces=data.spaces.clone() racks=data.racks.clone() devices=data.devices.clone() desks=data.desks.clone() desk_bookings=data.desk_bookings.clone() people=data.people.clone() components=data.components.clone() patch_panels=data.patch_panels.clone() ports=data.ports.clone() cables=data.cables.clone() pending_connections=data.pending_connections.clone() geo_features=data.geo_features.clone() cached_country_paths=data.cached_country_paths.clone() cached_state_paths=data.cached_state_paths.clone() cached_globe_country_paths=data.cached_globe_country_paths.clone() cached_globe_state_paths=data.cached_globe_state_paths.clone() view=data.geo_view.clone() bevy_globe=is_enabled(&data.flags, "bevy_globe")/> }.into_any(),
         // New module stubs  
         "personnel" => {
             if data.view.as_deref() == Some("orgchart") {
@@ -256,10 +378,32 @@ fn render_tab(data: &PageData) -> impl IntoView {
             }
         },
         "assets" => view! { <AssetsModule
+            persona=data.current_persona.clone().unwrap_or_default()
             assets=data.assets.clone()
             racks=data.racks.clone()
             spaces=data.spaces.clone()
         /> }.into_any(),
+        "maintenance" => view! { <MaintenanceTab
+            assets=data.assets.clone()
+            tickets=data.maintenance_tickets.clone()
+            expiring=data.expiring_warranties.clone()
+            transitions=data.lifecycle_transitions.clone()
+            today=data.today.clone()
+        /> }.into_any(),
+        "incidents" => view! { <IncidentsTab
+            incidents=data.incidents.clone()
+            selected=data.selected_incident.clone()
+            people=data.people.clone()
+            sites=data.sites.clone()
+            components=data.components.clone()
+            policies=data.escalation_policies.clone()
+        /> }.into_any(),
+        "oncall" => view! { <OnCallTab
+            on_call=data.on_call.clone()
+            rotations=data.rotations.clone()
+            policies=data.escalation_policies.clone()
+            people=data.people.clone()
+        /> }.into_any(),
         "tasks" => view! { <TasksModule/> }.into_any(),
         "contracts" => view! { <ContractsModule/> }.into_any(),
         "finance" => view! { <FinanceModule/> }.into_any(),
@@ -278,14 +422,22 @@ fn render_tab(data: &PageData) -> impl IntoView {
                 spaces=data.spaces.clone()
                 people=data.people.clone()
                 meetings=data.meetings.clone()
+                desks=data.desks.clone()
+                desk_bookings=data.desk_bookings.clone()
+                booking_date=data.booking_date.clone()
             /> 
         }.into_any(),
 
 
 
 
-        "chat" => view! { <ChatModule/> }.into_any(),
-        "email" => view! { <EmailModule/> }.into_any(),
+        "chat" => view! {
+            <ChatModule
+                persona=data.current_persona.clone().unwrap_or_default()
+                people=data.people.clone()
+            />
+        }.into_any(),
+        "email" => view! { <EmailModule preferences=data.email_preferences outbox=data.email_outbox.clone()/> }.into_any(),
         "meetings" => view! { <MeetingsModule/> }.into_any(),
         "presentations" => view! { <PresentationsModule/> }.into_any(),
         _ => view! { <LandingPage 
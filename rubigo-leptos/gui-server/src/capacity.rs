@@ -0,0 +1,684 @@
+//! Capacity planning
+//!
+//! How full each rack, space and device is: rack units from device heights
+//! and patch panels, power and cooling against the budget set for each
+//! space, and cabled ports out of the ports on each device. Devices with no
+//! rated power count the average their linked component drew in the latest
+//! simulation of the live topology (see `operations::energy`). The
+//! `capacity.snapshot` scheduled task records these figures daily, and the
+//! report projects from that history when each one will run out.
+//!
+//! New devices are checked before they are created: a device that overlaps
+//! another, runs past the top of its rack or pushes a rack or space past
+//! [`operations::capacity::WARN_AT`] of its capacity gets warnings, and is only
+//! created when the caller forces it.
+
+use std::collections::BTreeMap;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use nexosim_hybrid::database::cabling::{CablingRepository, PatchPanel};
+use nexosim_hybrid::database::capacity::{
+    CapacityKind, CapacityRepository, CapacitySnapshot, PortLoad, RackLoad, SpaceBudget,
+};
+use nexosim_hybrid::database::geo::{Device, GeoRepository, Rack, Space};
+use nexosim_hybrid::database::simulation::{SimulationRepository, SimulationRun};
+use nexosim_hybrid::database::DbClient;
+use operations::capacity::{exhaustion, place, Level, Usage};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+use utoipa::ToSchema;
+
+use crate::api::{found, record_key, ApiError, ApiResult, CreateDeviceRequest};
+use crate::AppState;
+
+/// Days of snapshots exhaustion dates are projected from
+pub const HISTORY_DAYS: i64 = 90;
+
+/// Today, as days since the Unix epoch
+pub fn today() -> i64 {
+    chrono::Utc::now().timestamp().div_euclid(86_400)
+}
+
+/// `YYYY-MM-DD` for a day counted from the Unix epoch
+pub fn date(day: i64) -> String {
+    chrono::DateTime::from_timestamp(day * 86_400, 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// A quantity without a trailing `.0`
+pub fn amount(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+/// A warning for `what` reaching `usage`, if it is close to or over capacity
+fn warning(what: &str, usage: Usage, unit: &str) -> Option<String> {
+    let (used, total) = (amount(usage.used), amount(usage.total));
+    match (usage.level(), usage.ratio()) {
+        (Level::Over, _) => Some(format!(
+            "{what} would exceed its capacity: {used} of {total} {unit}"
+        )),
+        (Level::Warning, Some(ratio)) => Some(format!(
+            "{what} would be {:.0}% full: {used} of {total} {unit}",
+            ratio * 100.0
+        )),
+        _ => None,
+    }
+}
+
+/// One capacity figure with its projected exhaustion date
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Figure {
+    pub used: f64,
+    /// 0 when there is nothing to measure against, such as a space
+    /// without a budget
+    pub total: f64,
+    #[schema(value_type = String)]
+    pub level: Level,
+    /// When `used` reaches `total` at its recent rate, `YYYY-MM-DD`
+    pub exhausts_on: Option<String>,
+}
+
+impl Figure {
+    /// `usage` today, projected along `history` as `(day, used)`
+    fn new(usage: Usage, history: &[(i64, f64)], today: i64) -> Self {
+        let mut samples: Vec<(i64, f64)> = history
+            .iter()
+            .copied()
+            .filter(|(day, _)| *day != today)
+            .collect();
+        samples.push((today, usage.used));
+        let exhausts_on = (usage.total > 0.0)
+            .then(|| exhaustion(&samples, usage.total))
+            .flatten()
+            .map(date);
+        Self {
+            used: usage.used,
+            total: usage.total,
+            level: usage.level(),
+            exhausts_on,
+        }
+    }
+
+    pub fn usage(&self) -> Usage {
+        Usage::new(self.used, self.total)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RackCapacity {
+    pub id: String,
+    pub name: String,
+    pub space: String,
+    pub devices: u64,
+    /// Rack units
+    pub units: Figure,
+    pub power_kw: f64,
+    /// Part of `power_kw` taken from simulation, for devices with no rating
+    pub simulated_kw: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SpaceCapacity {
+    pub id: String,
+    pub name: String,
+    pub racks: usize,
+    /// Whether power and cooling budgets have been set
+    pub budgeted: bool,
+    /// Kilowatts drawn by the devices in the space
+    pub power: Figure,
+    /// Kilowatts of heat to remove, taken to equal the power drawn
+    pub cooling: Figure,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeviceCapacity {
+    pub id: String,
+    pub name: String,
+    pub rack: String,
+    /// Cabled ports out of the device's ports
+    pub ports: Figure,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CapacityReport {
+    /// `YYYY-MM-DD`
+    pub day: String,
+    pub racks: Vec<RackCapacity>,
+    pub spaces: Vec<SpaceCapacity>,
+    /// Devices with ports
+    pub devices: Vec<DeviceCapacity>,
+}
+
+impl CapacityReport {
+    /// Figures close to or over capacity, as `(what, figure)`
+    pub fn alerts(&self) -> Vec<(String, &Figure)> {
+        let racks = self
+            .racks
+            .iter()
+            .map(|r| (format!("Rack {} units", r.name), &r.units));
+        let spaces = self.spaces.iter().flat_map(|s| {
+            [
+                (format!("{} power", s.name), &s.power),
+                (format!("{} cooling", s.name), &s.cooling),
+            ]
+        });
+        let devices = self
+            .devices
+            .iter()
+            .map(|d| (format!("{} ports", d.name), &d.ports));
+        racks
+            .chain(spaces)
+            .chain(devices)
+            .filter(|(_, figure)| figure.level != Level::Ok)
+            .collect()
+    }
+}
+
+/// Everything the figures are worked out from
+struct Inventory {
+    racks: Vec<Rack>,
+    spaces: Vec<Space>,
+    devices: Vec<Device>,
+    panels: Vec<PatchPanel>,
+    loads: Vec<RackLoad>,
+    ports: Vec<PortLoad>,
+    budgets: Vec<SpaceBudget>,
+    /// Watts simulated for unrated devices linked to a component, by device
+    simulated: BTreeMap<String, f64>,
+}
+
+/// Average watts drawn in the latest run of the live topology by each
+/// device with no rated power whose component was simulated
+fn simulated_watts(devices: &[Device], runs: &[SimulationRun]) -> BTreeMap<String, f64> {
+    let Some(run) = runs
+        .iter()
+        .filter(|r| r.sandbox.is_none())
+        .max_by(|a, b| a.started_at.cmp(&b.started_at))
+    else {
+        return BTreeMap::new();
+    };
+    devices
+        .iter()
+        .filter(|d| d.power_w == 0)
+        .filter_map(|d| {
+            let component = d.component_id.as_ref()?.id.to_raw();
+            let node = run
+                .nodes
+                .iter()
+                .find(|n| n.component.to_string() == component)?;
+            Some((d.id.as_ref()?.to_string(), *node.metrics.get("average_w")?))
+        })
+        .collect()
+}
+
+impl Inventory {
+    async fn load(db: &DbClient) -> anyhow::Result<Self> {
+        let devices = GeoRepository::list_all_devices(db).await?;
+        let simulated = simulated_watts(&devices, &SimulationRepository::get_all(db).await?);
+        Ok(Self {
+            racks: GeoRepository::list_all_racks(db).await?,
+            spaces: GeoRepository::list_all_spaces(db).await?,
+            devices,
+            panels: CablingRepository::list_all_panels(db).await?,
+            loads: CapacityRepository::rack_loads(db).await?,
+            ports: CapacityRepository::port_loads(db).await?,
+            budgets: CapacityRepository::budgets(db).await?,
+            simulated,
+        })
+    }
+
+    fn load_of(&self, rack: &Thing) -> Option<&RackLoad> {
+        self.loads.iter().find(|l| &l.rack_id == rack)
+    }
+
+    fn budget_of(&self, space: &Thing) -> Option<&SpaceBudget> {
+        self.budgets.iter().find(|b| &b.space_id == space)
+    }
+
+    fn rack_units(&self, rack: &Rack) -> Usage {
+        let used = rack
+            .id
+            .as_ref()
+            .and_then(|id| self.load_of(id))
+            .map_or(0, |l| l.used_u);
+        Usage::new(used as f64, rack.height_u as f64)
+    }
+
+    /// Kilowatts simulated for the unrated devices in `rack`
+    fn simulated_kw(&self, rack: &Thing) -> f64 {
+        self.devices
+            .iter()
+            .filter(|d| &d.rack_id == rack)
+            .filter_map(|d| self.simulated.get(&d.id.as_ref()?.to_string()))
+            .sum::<f64>()
+            / 1000.0
+    }
+
+    /// Kilowatts drawn in `rack`, rated or simulated
+    fn rack_power(&self, rack: &Thing) -> f64 {
+        self.load_of(rack)
+            .map_or(0.0, |l| l.power_w as f64 / 1000.0)
+            + self.simulated_kw(rack)
+    }
+
+    /// Kilowatts drawn by the racks in `space`
+    fn space_power(&self, space: &Thing) -> f64 {
+        self.racks
+            .iter()
+            .filter(|r| &r.space_id == space)
+            .filter_map(|r| Some(self.rack_power(r.id.as_ref()?)))
+            .sum()
+    }
+
+    /// Power and cooling for `space`, against its budget or against nothing
+    fn space_usage(&self, space: &Thing) -> (Usage, Usage) {
+        let used = self.space_power(space);
+        let (power, cooling) = self
+            .budget_of(space)
+            .map_or((0.0, 0.0), |b| (b.power_kw, b.cooling_kw));
+        (Usage::new(used, power), Usage::new(used, cooling))
+    }
+
+    /// Every figure today, by subject and kind
+    fn usages(&self) -> BTreeMap<(String, CapacityKind), Usage> {
+        let mut usages = BTreeMap::new();
+        for rack in &self.racks {
+            let Some(id) = &rack.id else { continue };
+            usages.insert((id.to_string(), CapacityKind::RackU), self.rack_units(rack));
+        }
+        for space in &self.spaces {
+            let Some(id) = &space.id else { continue };
+            let (power, cooling) = self.space_usage(id);
+            usages.insert((id.to_string(), CapacityKind::Power), power);
+            usages.insert((id.to_string(), CapacityKind::Cooling), cooling);
+        }
+        for load in &self.ports {
+            let usage = Usage::new(load.cabled as f64, load.ports as f64);
+            usages.insert((load.owner.to_string(), CapacityKind::Ports), usage);
+        }
+        usages
+    }
+}
+
+/// Record today's figures for every rack, space and device with ports
+pub async fn snapshot(db: &DbClient, today: i64) -> anyhow::Result<usize> {
+    let snapshots = Inventory::load(db)
+        .await?
+        .usages()
+        .into_iter()
+        .map(|((subject, kind), usage)| CapacitySnapshot {
+            id: None,
+            subject,
+            kind,
+            day: today,
+            used: usage.used,
+            total: usage.total,
+        })
+        .collect();
+    CapacityRepository::record(db, snapshots).await
+}
+
+/// Capacity of everything on `today`, with exhaustion dates
+pub async fn report(db: &DbClient, today: i64) -> anyhow::Result<CapacityReport> {
+    let inventory = Inventory::load(db).await?;
+    let mut history: BTreeMap<(String, CapacityKind), Vec<(i64, f64)>> = BTreeMap::new();
+    for s in CapacityRepository::snapshots(db, today - HISTORY_DAYS).await? {
+        history
+            .entry((s.subject, s.kind))
+            .or_default()
+            .push((s.day, s.used));
+    }
+    let figure = |id: &Thing, kind: CapacityKind, usage: Usage| {
+        let samples = history
+            .get(&(id.to_string(), kind))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        Figure::new(usage, samples, today)
+    };
+    let key = |id: &Thing| id.id.to_raw();
+
+    let space_names: BTreeMap<String, String> = inventory
+        .spaces
+        .iter()
+        .filter_map(|s| Some((s.id.as_ref()?.to_string(), s.name.clone())))
+        .collect();
+    let rack_names: BTreeMap<String, String> = inventory
+        .racks
+        .iter()
+        .filter_map(|r| Some((r.id.as_ref()?.to_string(), r.name.clone())))
+        .collect();
+
+    let mut racks: Vec<RackCapacity> = inventory
+        .racks
+        .iter()
+        .filter_map(|rack| {
+            let id = rack.id.as_ref()?;
+            let load = inventory.load_of(id);
+            Some(RackCapacity {
+                id: key(id),
+                name: rack.name.clone(),
+                space: space_names
+                    .get(&rack.space_id.to_string())
+                    .cloned()
+                    .unwrap_or_default(),
+                devices: load.map_or(0, |l| l.devices),
+                units: figure(id, CapacityKind::RackU, inventory.rack_units(rack)),
+                power_kw: inventory.rack_power(id),
+                simulated_kw: inventory.simulated_kw(id),
+            })
+        })
+        .collect();
+    racks.sort_by(|a, b| (&a.space, &a.name).cmp(&(&b.space, &b.name)));
+
+    let mut spaces: Vec<SpaceCapacity> = inventory
+        .spaces
+        .iter()
+        .filter_map(|space| {
+            let id = space.id.as_ref()?;
+            let (power, cooling) = inventory.space_usage(id);
+            Some(SpaceCapacity {
+                id: key(id),
+                name: space.name.clone(),
+                racks: inventory.racks.iter().filter(|r| &r.space_id == id).count(),
+                budgeted: inventory.budget_of(id).is_some(),
+                power: figure(id, CapacityKind::Power, power),
+                cooling: figure(id, CapacityKind::Cooling, cooling),
+            })
+        })
+        .collect();
+    spaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut devices: Vec<DeviceCapacity> = inventory
+        .ports
+        .iter()
+        .filter_map(|load| {
+            let device = inventory
+                .devices
+                .iter()
+                .find(|d| d.id.as_ref() == Some(&load.owner))?;
+            Some(DeviceCapacity {
+                id: key(&load.owner),
+                name: device.name.clone(),
+                rack: rack_names
+                    .get(&device.rack_id.to_string())
+                    .cloned()
+                    .unwrap_or_default(),
+                ports: figure(
+                    &load.owner,
+                    CapacityKind::Ports,
+                    Usage::new(load.cabled as f64, load.ports as f64),
+                ),
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| (&a.rack, &a.name).cmp(&(&b.rack, &b.name)));
+
+    Ok(CapacityReport {
+        day: date(today),
+        racks,
+        spaces,
+        devices,
+    })
+}
+
+/// What would go over, or close to, capacity if `device` were created;
+/// empty when it fits comfortably
+pub async fn check_device(db: &DbClient, device: &Device) -> anyhow::Result<Vec<String>> {
+    let inventory = Inventory::load(db).await?;
+    let Some(rack) = inventory
+        .racks
+        .iter()
+        .find(|r| r.id.as_ref() == Some(&device.rack_id))
+    else {
+        return Ok(vec![format!("Rack {} does not exist", device.rack_id)]);
+    };
+    let mut warnings = Vec::new();
+
+    let occupied: Vec<(u8, u8)> = inventory
+        .devices
+        .iter()
+        .filter(|d| d.rack_id == device.rack_id)
+        .map(|d| (d.position_u, d.height_u))
+        .chain(
+            inventory
+                .panels
+                .iter()
+                .filter(|p| p.rack_id == device.rack_id)
+                .map(|p| (p.position_u, 1)),
+        )
+        .collect();
+    if let Err(clash) = place(rack.height_u, &occupied, device.position_u, device.height_u) {
+        warnings.push(clash);
+    }
+    let units = inventory
+        .rack_units(rack)
+        .plus(device.height_u.max(1) as f64);
+    warnings.extend(warning(&format!("Rack {}", rack.name), units, "U"));
+
+    if inventory.budget_of(&rack.space_id).is_some() {
+        let space = inventory
+            .spaces
+            .iter()
+            .find(|s| s.id.as_ref() == Some(&rack.space_id));
+        let name = space.map_or_else(|| rack.space_id.to_string(), |s| s.name.clone());
+        let (power, cooling) = inventory.space_usage(&rack.space_id);
+        let added = device.power_w as f64 / 1000.0;
+        warnings.extend(warning(&format!("{name} power"), power.plus(added), "kW"));
+        warnings.extend(warning(
+            &format!("{name} cooling"),
+            cooling.plus(added),
+            "kW",
+        ));
+    }
+    Ok(warnings)
+}
+
+/// A device checked on the Capacity page before it is created
+#[derive(Debug, Clone)]
+pub struct DeviceCheck {
+    pub device: Device,
+    pub warnings: Vec<String>,
+}
+
+/// Percent-encode a query string value
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// The Capacity page checking `device`, where a form that tried to create
+/// it sends people to see its warnings
+pub fn check_link(device: &Device) -> String {
+    format!(
+        "/?tab=capacity&name={}&rack_id={}&position_u={}&height_u={}&power_w={}",
+        encode(&device.name),
+        encode(&device.rack_id.to_string()),
+        device.position_u,
+        device.height_u,
+        device.power_w
+    )
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+#[utoipa::path(
+    get,
+    path = "/api/capacity",
+    tag = "capacity",
+    responses((status = 200, description = "Rack, space and port capacity with exhaustion dates", body = CapacityReport))
+)]
+pub async fn get_report(State(state): State<AppState>) -> ApiResult<Json<CapacityReport>> {
+    Ok(Json(report(&state.db.client, today()).await?))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CapacityCheck {
+    /// Empty when the device fits
+    pub warnings: Vec<String>,
+}
+
+/// Check a device against rack, power and cooling capacity without creating it
+#[utoipa::path(
+    post,
+    path = "/api/capacity/check",
+    tag = "capacity",
+    request_body = CreateDeviceRequest,
+    responses(
+        (status = 200, description = "What the device would push close to or over capacity", body = CapacityCheck),
+        (status = 400, description = "Invalid reference id", body = ApiError),
+    )
+)]
+pub async fn check(
+    State(state): State<AppState>,
+    Json(req): Json<CreateDeviceRequest>,
+) -> ApiResult<Json<CapacityCheck>> {
+    let warnings = check_device(&state.db.client, &req.into_device()?).await?;
+    Ok(Json(CapacityCheck { warnings }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BudgetInput {
+    pub power_kw: f64,
+    pub cooling_kw: f64,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/spaces/{id}/budget",
+    tag = "capacity",
+    params(("id" = String, Path, description = "Space id (`space:key` or `key`)")),
+    request_body = BudgetInput,
+    responses(
+        (status = 200, description = "Budget saved", body = SpaceBudget),
+        (status = 400, description = "Negative budget", body = ApiError),
+        (status = 404, description = "Space not found", body = ApiError),
+    )
+)]
+pub async fn set_budget(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<BudgetInput>,
+) -> ApiResult<Json<SpaceBudget>> {
+    if input.power_kw < 0.0 || input.cooling_kw < 0.0 {
+        return Err(ApiError::bad_request("Budgets cannot be negative"));
+    }
+    let key = record_key(&id, "space");
+    let space: Option<Space> = state
+        .db
+        .client
+        .select(("space", key))
+        .await
+        .map_err(anyhow::Error::from)?;
+    found(space, "Space", &id)?;
+    let budget = SpaceBudget {
+        id: None,
+        space_id: Thing::from(("space", key)),
+        power_kw: input.power_kw,
+        cooling_kw: input.cooling_kw,
+    };
+    Ok(Json(
+        CapacityRepository::set_budget(&state.db.client, budget).await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warnings_and_projections() {
+        assert_eq!(warning("Rack A1", Usage::new(20.0, 42.0), "U"), None);
+        assert_eq!(
+            warning("Rack A1", Usage::new(36.0, 42.0), "U").as_deref(),
+            Some("Rack A1 would be 86% full: 36 of 42 U")
+        );
+        assert_eq!(
+            warning("Hall power", Usage::new(4.5, 4.0), "kW").as_deref(),
+            Some("Hall power would exceed its capacity: 4.5 of 4 kW")
+        );
+        assert_eq!(warning("Hall power", Usage::new(4.5, 0.0), "kW"), None);
+
+        // A unit a day from 30 of 42 over the last two days: full in 10
+        let figure = Figure::new(Usage::new(32.0, 42.0), &[(100, 30.0), (101, 31.0)], 102);
+        assert_eq!(figure.level, Level::Warning);
+        assert_eq!(figure.exhausts_on.as_deref(), Some(date(112).as_str()));
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(
+            Figure::new(Usage::new(32.0, 0.0), &[(101, 1.0)], 102).exhausts_on,
+            None
+        );
+
+        let device = Device {
+            id: None,
+            name: "core sw/1".to_string(),
+            rack_id: Thing::from(("rack", "r1")),
+            position_u: 10,
+            component_id: None,
+            height_u: 2,
+            power_w: 350,
+        };
+        assert_eq!(
+            check_link(&device),
+            "/?tab=capacity&name=core%20sw%2F1&rack_id=rack%3Ar1&position_u=10&height_u=2&power_w=350"
+        );
+    }
+
+    #[test]
+    fn unrated_devices_take_the_latest_live_run() {
+        use nexosim_hybrid::database::simulation::RunNode;
+        let device = |key: &str, component: Option<u32>, power_w: u32| Device {
+            id: Some(Thing::from(("device", key))),
+            name: key.to_string(),
+            rack_id: Thing::from(("rack", "r1")),
+            position_u: 1,
+            component_id: component.map(|c| Thing::from(("component", c.to_string().as_str()))),
+            height_u: 1,
+            power_w,
+        };
+        let run = |started_at: &str, sandbox: Option<&str>, average_w: f64| SimulationRun {
+            id: None,
+            started_at: started_at.to_string(),
+            status: "completed".to_string(),
+            logs: Vec::new(),
+            nodes: vec![RunNode {
+                component: 1,
+                name: "r1".to_string(),
+                metrics: [("average_w".to_string(), average_w)].into(),
+            }],
+            traces: Default::default(),
+            sandbox: sandbox.map(str::to_string),
+            sites: Vec::new(),
+            faults: Vec::new(),
+        };
+        let devices = [
+            device("a", Some(1), 0),
+            device("b", Some(1), 300),
+            device("c", None, 0),
+        ];
+        let runs = [
+            run("2025-01-06", None, 250.0),
+            run("2025-01-05", None, 200.0),
+            run("2025-01-07", Some("s"), 900.0),
+        ];
+        assert_eq!(
+            simulated_watts(&devices, &runs),
+            BTreeMap::from([("device:a".to_string(), 250.0)])
+        );
+        assert!(simulated_watts(&devices, &[]).is_empty());
+    }
+}
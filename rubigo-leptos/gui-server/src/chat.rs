@@ -0,0 +1,159 @@
+//! Chat broker
+//!
+//! Stores chat messages and delivers them to connected participants. Each
+//! browser tab opens `GET /api/chat/ws?persona=...` (WebSocket) and
+//! receives every new message in the persona's conversations; it can also
+//! send over the same socket instead of `POST`ing to the JSON API.
+//!
+//! Frames are JSON. Client to server:
+//! `{ "conversation_id": "...", "body": "..." }`. Server to client:
+//! `{ "type": "message", ...MessageView }` or
+//! `{ "type": "error", "message": "..." }`.
+
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use axum_extra::extract::cookie::CookieJar;
+use futures::{SinkExt, StreamExt};
+use nexosim_hybrid::database::chat::{ChatRepository, MessageView};
+use nexosim_hybrid::database::Database;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::email::EmailService;
+use crate::identity::acting_as;
+use crate::notifications::PersonaQuery;
+use crate::AppState;
+
+/// Messages buffered for slow sockets before they start lagging
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A stored message and the personas it goes to
+#[derive(Clone, Debug)]
+pub struct Delivery {
+    pub recipients: Vec<String>,
+    pub message: MessageView,
+}
+
+#[derive(Clone)]
+pub struct ChatBroker {
+    db: Arc<Database>,
+    tx: broadcast::Sender<Delivery>,
+    email: EmailService,
+}
+
+impl ChatBroker {
+    pub fn new(db: Arc<Database>, email: EmailService) -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { db, tx, email }
+    }
+
+    /// Store a message from `persona`, deliver it to the conversation and
+    /// email the participants it mentions
+    ///
+    /// `None` if the conversation does not exist.
+    pub async fn send(
+        &self,
+        conversation: &str,
+        persona: &str,
+        body: &str,
+    ) -> anyhow::Result<Option<MessageView>> {
+        let now = crate::clock::now();
+        let Some((message, recipients)) =
+            ChatRepository::send(&self.db.client, conversation, persona, body, &now).await?
+        else {
+            return Ok(None);
+        };
+        self.email.mentions(&message, &recipients).await;
+        // No open sockets is not an error
+        let _ = self.tx.send(Delivery {
+            recipients,
+            message: message.clone(),
+        });
+        Ok(Some(message))
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Delivery> {
+        self.tx.subscribe()
+    }
+}
+
+#[derive(Deserialize)]
+struct ClientFrame {
+    conversation_id: String,
+    body: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerFrame {
+    Message(MessageView),
+    Error { message: String },
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/chat/ws",
+    tag = "chat",
+    params(PersonaQuery),
+    responses(
+        (status = 101, description = "WebSocket carrying the persona's chat messages"),
+        (status = 401, description = "Not signed in", body = crate::api::ApiError),
+        (status = 403, description = "Signed in as another persona", body = crate::api::ApiError),
+    )
+)]
+pub async fn socket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> Response {
+    match acting_as(&state, &jar, &query.persona).await {
+        Ok(persona) => ws.on_upgrade(move |socket| session(socket, state, persona)),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn session(socket: WebSocket, state: AppState, persona: String) {
+    let (mut outgoing, mut incoming) = socket.split();
+    let mut deliveries = state.chat.subscribe();
+
+    loop {
+        let frame = tokio::select! {
+            delivery = deliveries.recv() => match delivery {
+                Ok(d) if d.recipients.contains(&persona) => ServerFrame::Message(d.message),
+                // A lagged socket misses messages; the panel reloads on reconnect
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            received = incoming.next() => match received {
+                Some(Ok(Message::Text(text))) => {
+                    let result = match serde_json::from_str::<ClientFrame>(&text) {
+                        Ok(frame) => state
+                            .chat
+                            .send(&frame.conversation_id, &persona, &frame.body)
+                            .await
+                            .map_err(|e| e.to_string())
+                            .and_then(|sent| sent.map(|_| ()).ok_or_else(|| "Conversation not found".to_string())),
+                        Err(e) => Err(format!("Invalid frame: {e}")),
+                    };
+                    // The sender gets its own message back as a delivery
+                    match result {
+                        Ok(()) => continue,
+                        Err(message) => ServerFrame::Error { message },
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        let Ok(text) = serde_json::to_string(&frame) else {
+            continue;
+        };
+        if outgoing.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Server Clock
+//!
+//! The current time in the two forms the server records: a timestamp for
+//! when something happened, and the minute schedules and rotations count in.
+
+use chrono::{DateTime, Utc};
+use operations::schedule::minute_of;
+
+/// When something happened, e.g. "2025-01-01 09:30:00 UTC"
+pub fn now() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// The current minute, UTC
+pub fn minute() -> DateTime<Utc> {
+    minute_of(Utc::now())
+}
@@ -0,0 +1,218 @@
+//! Collaborative editing hub
+//!
+//! Relays unsaved form edits between everyone editing the same record.
+//! Each tab opens `GET /api/collab/ws?persona=...&entity=...` (WebSocket),
+//! gets a snapshot of the record's edits so far and then every accepted
+//! edit; see [`actions::collab`] for the merge rules.
+//!
+//! Frames are JSON. Client to server: a [`FieldEdit`]
+//! (`{ "field": "title", "value": "...", "base": 3 }`). Server to client:
+//! `{ "type": "snapshot", "fields": {...} }`, `{ "type": "field", ... }` or
+//! `{ "type": "error", "message": "..." }`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use actions::collab::{EntityDoc, FieldEdit, FieldUpdate, ServerFrame};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use axum_extra::extract::cookie::CookieJar;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast;
+
+use crate::identity::acting_as;
+use crate::AppState;
+
+/// Edits buffered for slow sockets before they start lagging
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A record's edits and how many tabs have it open
+#[derive(Default)]
+struct Shared {
+    doc: EntityDoc,
+    editors: usize,
+}
+
+#[derive(Clone)]
+pub struct CollabHub {
+    /// Records being edited, by entity key
+    docs: Arc<Mutex<HashMap<String, Shared>>>,
+    tx: broadcast::Sender<(String, FieldUpdate)>,
+}
+
+impl Default for CollabHub {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            docs: Arc::new(Mutex::new(HashMap::new())),
+            tx,
+        }
+    }
+}
+
+impl CollabHub {
+    /// Open `entity` for another editor; returns the snapshot to send
+    pub fn join(&self, entity: &str) -> ServerFrame {
+        let mut docs = self.docs.lock().unwrap();
+        let shared = docs.entry(entity.to_string()).or_default();
+        shared.editors += 1;
+        ServerFrame::Snapshot {
+            fields: shared.doc.fields().clone(),
+        }
+    }
+
+    pub fn snapshot(&self, entity: &str) -> ServerFrame {
+        let docs = self.docs.lock().unwrap();
+        let fields = docs
+            .get(entity)
+            .map(|shared| shared.doc.fields().clone())
+            .unwrap_or_default();
+        ServerFrame::Snapshot { fields }
+    }
+
+    /// Merge an edit and relay it to the record's editors
+    pub fn edit(&self, entity: &str, author: &str, edit: FieldEdit) {
+        let update = {
+            let mut docs = self.docs.lock().unwrap();
+            let Some(shared) = docs.get_mut(entity) else {
+                return;
+            };
+            shared.doc.apply(author, edit)
+        };
+        // No open sockets is not an error
+        let _ = self.tx.send((entity.to_string(), update));
+    }
+
+    /// Close `entity` for an editor; the last one out discards the edits
+    pub fn leave(&self, entity: &str) {
+        let mut docs = self.docs.lock().unwrap();
+        if let Some(shared) = docs.get_mut(entity) {
+            shared.editors = shared.editors.saturating_sub(1);
+            if shared.editors == 0 {
+                docs.remove(entity);
+            }
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<(String, FieldUpdate)> {
+        self.tx.subscribe()
+    }
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct CollabQuery {
+    /// Name of the person editing
+    pub persona: String,
+    /// Record being edited, e.g. `event:42` or `asset:7`
+    pub entity: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/collab/ws",
+    tag = "collab",
+    params(CollabQuery),
+    responses(
+        (status = 101, description = "WebSocket carrying unsaved edits to the record"),
+        (status = 401, description = "Not signed in", body = crate::api::ApiError),
+        (status = 403, description = "Signed in as another persona", body = crate::api::ApiError),
+    )
+)]
+pub async fn socket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<CollabQuery>,
+) -> Response {
+    match acting_as(&state, &jar, &query.persona).await {
+        Ok(persona) => {
+            let query = CollabQuery { persona, ..query };
+            ws.on_upgrade(move |socket| session(socket, state, query))
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn session(socket: WebSocket, state: AppState, query: CollabQuery) {
+    let CollabQuery { persona, entity } = query;
+    let (mut outgoing, mut incoming) = socket.split();
+    // Subscribe before the snapshot so no edit falls between them
+    let mut updates = state.collab.subscribe();
+    let mut next = Some(state.collab.join(&entity));
+
+    loop {
+        let frame = match next.take() {
+            Some(frame) => frame,
+            None => tokio::select! {
+                update = updates.recv() => match update {
+                    Ok((key, update)) if key == entity => ServerFrame::Field(update),
+                    Ok(_) => continue,
+                    // A lagged socket missed edits; resend the whole record
+                    Err(broadcast::error::RecvError::Lagged(_)) => state.collab.snapshot(&entity),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                received = incoming.next() => match received {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<FieldEdit>(&text) {
+                        Ok(edit) => {
+                            // The author gets its edit back as an update
+                            state.collab.edit(&entity, &persona, edit);
+                            continue;
+                        }
+                        Err(e) => ServerFrame::Error { message: format!("Invalid frame: {e}") },
+                    },
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                },
+            },
+        };
+        let Ok(text) = serde_json::to_string(&frame) else {
+            continue;
+        };
+        if outgoing.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+
+    state.collab.leave(&entity);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(field: &str, value: &str) -> FieldEdit {
+        FieldEdit {
+            field: field.into(),
+            value: value.into(),
+            base: 0,
+        }
+    }
+
+    #[test]
+    fn edits_reach_later_editors_until_the_last_leaves() {
+        let hub = CollabHub::default();
+        let mut updates = hub.subscribe();
+
+        hub.join("event:1");
+        hub.edit("event:1", "Ada", edit("title", "Standup"));
+        assert_eq!(updates.try_recv().unwrap().1.state.value, "Standup");
+
+        let ServerFrame::Snapshot { fields } = hub.join("event:1") else {
+            panic!("expected a snapshot")
+        };
+        assert_eq!(fields["title"].author, "Ada");
+
+        hub.leave("event:1");
+        hub.leave("event:1");
+        let ServerFrame::Snapshot { fields } = hub.join("event:1") else {
+            panic!("expected a snapshot")
+        };
+        assert!(fields.is_empty());
+
+        // Edits to records nobody has open are dropped
+        hub.edit("event:2", "Ada", edit("title", "Lost"));
+        assert!(updates.try_recv().is_err());
+    }
+}
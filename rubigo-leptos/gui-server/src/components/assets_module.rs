@@ -4,12 +4,17 @@
 
 use leptos::prelude::*;
 use leptos::IntoView;
+use nexosim_hybrid::database::asset_lifecycle::LifecycleState;
 use nexosim_hybrid::database::geo::{AssetCategory, AssetStatus, NetworkAsset, Rack, Space};
 use std::collections::HashMap;
 
+use gui_server::islands::PresenceNotice;
+
 /// Assets module main component
 #[component]
 pub fn AssetsModule(
+    /// Persona viewing the module, for the details panel's presence notice
+    persona: String,
     assets: Vec<NetworkAsset>,
     racks: Vec<Rack>,
     spaces: Vec<Space>,
@@ -235,6 +240,7 @@ pub fn AssetsModule(
                     <p class="panel-subtitle" id="asset-panel-model"></p>
                 </div>
                 <div class="panel-content">
+                    <PresenceNotice persona=persona noun="asset".to_string() />
                     <div class="detail-section">
                         <h3>"Identification"</h3>
                         <div class="detail-row">
@@ -510,11 +516,18 @@ pub fn AssetsModule(
 
                     document.getElementById('asset-details-panel').classList.add('open');
                     document.getElementById('asset-panel-overlay').classList.add('open');
+                    reportPresence({ entity: 'asset:' + id });
                 }
 
                 function closeAssetDetails() {
                     document.getElementById('asset-details-panel').classList.remove('open');
                     document.getElementById('asset-panel-overlay').classList.remove('open');
+                    reportPresence({});
+                }
+
+                // Tell the presence islands which asset is open
+                function reportPresence(record) {
+                    window.dispatchEvent(new CustomEvent('rubigo:presence', { detail: JSON.stringify(record) }));
                 }
 
                 function confirmDeleteAsset() {
@@ -588,6 +601,9 @@ pub fn AssetForm(
         space_id: None,
         storage_location: None,
         notes: None,
+        lifecycle: LifecycleState::Deployed,
+        warranty_start: None,
+        warranty_end: None,
     });
 
     let asset_id = asset.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default();
@@ -5,7 +5,8 @@
 use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
 use leptos::prelude::*;
 use nexosim_hybrid::database::calendar::{Meeting, MeetingType};
-use nexosim_hybrid::database::geo::{Building, Floor, Person, Space};
+use nexosim_hybrid::database::desks::{holders_on, BookingKind, DeskBooking};
+use nexosim_hybrid::database::geo::{Building, Desk, Floor, Person, Space};
 use uuid::Uuid;
 
 // -----------------------------------------------------------------------------
@@ -86,6 +87,10 @@ pub fn CalendarModule(
     #[prop(default = vec![])] spaces: Vec<Space>,
     #[prop(default = vec![])] people: Vec<Person>,
     #[prop(default = vec![])] meetings: Vec<Meeting>,
+    #[prop(default = vec![])] desks: Vec<Desk>,
+    #[prop(default = vec![])] desk_bookings: Vec<DeskBooking>,
+    /// Date shown by the desk booking panel (`YYYY-MM-DD`)
+    #[prop(default = String::new())] booking_date: String,
 ) -> impl IntoView {
     // Determine current date
     let now = Utc::now();
@@ -131,6 +136,14 @@ pub fn CalendarModule(
                     spaces=spaces.clone()
                     people=people.clone()
                 /> }.into_any()
+            } else if modal.as_deref() == Some("desk") {
+                view! { <DeskBookingModal
+                    date=booking_date.clone()
+                    desks=desks.clone()
+                    spaces=spaces.clone()
+                    people=people.clone()
+                    bookings=desk_bookings.clone()
+                /> }.into_any()
             } else {
                 view! { <span /> }.into_any()
             }}
@@ -384,6 +397,10 @@ fn CalendarHeader(current_date: DateTime<Utc>, view: String, work_week: bool) ->
                     <button class="view-btn" disabled>"Day"</button>
                 </div>
 
+                <a href="/?tab=calendar&modal=desk" class="btn-secondary">
+                    <span>"Book Desk"</span>
+                </a>
+
                 <a href="/?tab=calendar&modal=new" class="btn-primary">
                     <span>"+"</span>
                     <span>"New Event"</span>
@@ -455,6 +472,9 @@ fn MonthView(
             MeetingType::Conference => "#ec4899", // Pink
             MeetingType::Review => "#14b8a6",     // Teal
             MeetingType::Planning => "#f97316",   // Orange
+            MeetingType::Maintenance => "#eab308", // Yellow
+            MeetingType::DeskBooking => "#0ea5e9", // Sky
+            MeetingType::OnCall => "#dc2626",     // Deep red
             MeetingType::Meeting => "#6b7280",    // Gray
         }
     }
@@ -639,6 +659,9 @@ fn WeekView(
             MeetingType::Conference => "#ec4899",
             MeetingType::Review => "#14b8a6",
             MeetingType::Planning => "#f97316",
+            MeetingType::Maintenance => "#eab308",
+            MeetingType::DeskBooking => "#0ea5e9",
+            MeetingType::OnCall => "#dc2626",
             MeetingType::Meeting => "#6b7280",
         }
     }
@@ -1828,3 +1851,115 @@ fn EventModal(
 
     }
 }
+
+/// Desk booking panel for one date
+///
+/// Lists every desk with who holds it on `date`, lets day bookings be
+/// cancelled and books a free desk for a day or as a permanent seat.
+#[component]
+fn DeskBookingModal(
+    date: String,
+    desks: Vec<Desk>,
+    spaces: Vec<Space>,
+    people: Vec<Person>,
+    bookings: Vec<DeskBooking>,
+) -> impl IntoView {
+    let thing_string = |id: &Option<surrealdb::sql::Thing>| id.as_ref().map(|t| t.to_string()).unwrap_or_default();
+    let space_names: std::collections::HashMap<String, String> = spaces
+        .iter()
+        .map(|s| (thing_string(&s.id), s.name.clone()))
+        .collect();
+    let person_name = |id: &surrealdb::sql::Thing| {
+        people
+            .iter()
+            .find(|p| p.id.as_ref() == Some(id))
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    };
+    let holders = holders_on(&bookings, &date);
+
+    let mut rows: Vec<(String, String, String, Option<&DeskBooking>)> = desks
+        .iter()
+        .map(|d| {
+            let id = thing_string(&d.id);
+            let space = space_names.get(&d.space_id.to_string()).cloned().unwrap_or_default();
+            (space, d.name.clone(), id.clone(), holders.get(&id).copied())
+        })
+        .collect();
+    rows.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+    let free: Vec<(String, String)> = rows
+        .iter()
+        .filter(|(_, _, _, holder)| holder.is_none())
+        .map(|(space, name, id, _)| (id.clone(), format!("{} ({})", name, space)))
+        .collect();
+
+    view! {
+        <div class="modal-overlay">
+            <div class="modal-content">
+                <div class="modal-header">
+                    <h3>"Desk Booking"</h3>
+                    <a href="/?tab=calendar" class="modal-close">"×"</a>
+                </div>
+                <div class="modal-body">
+                    <form action="/" method="get" class="form-row">
+                        <input type="hidden" name="tab" value="calendar"/>
+                        <input type="hidden" name="modal" value="desk"/>
+                        <div class="form-group"><label>"Date"</label><input type="date" name="date" class="form-input" value=date.clone()/></div>
+                        <button type="submit" class="btn btn-secondary btn-sm">"Show"</button>
+                    </form>
+
+                    <table class="data-table desk-table">
+                        <thead><tr><th>"Space"</th><th>"Desk"</th><th>"Status"</th><th></th></tr></thead>
+                        <tbody>
+                            {rows.iter().map(|(space, name, _, holder)| {
+                                let (status, cancel) = match holder {
+                                    None => ("Free".to_string(), None),
+                                    Some(b) if b.kind == BookingKind::Permanent => (format!("Seated: {}", person_name(&b.person_id)), None),
+                                    Some(b) => (format!("Booked: {}", person_name(&b.person_id)), Some(thing_string(&b.id))),
+                                };
+                                view! {
+                                    <tr>
+                                        <td>{space.clone()}</td>
+                                        <td><strong>{name.clone()}</strong></td>
+                                        <td class={if holder.is_none() { "desk-free" } else { "text-muted" }}>{status}</td>
+                                        <td>{cancel.map(|id| view! {
+                                            <form action=format!("/desk-bookings/{}/delete", id) method="post" style="display:inline;">
+                                                <input type="hidden" name="date" value=date.clone()/>
+                                                <button type="submit" class="btn btn-sm btn-secondary">"Cancel"</button>
+                                            </form>
+                                        })}</td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                    {rows.is_empty().then(|| view! { <p class="text-muted text-center">"No desks yet. Add them from a space in the Sites tab."</p> })}
+
+                    <form action="/desk-bookings/create" method="post" class="form-row desk-booking-form">
+                        <input type="hidden" name="date" value=date.clone()/>
+                        <div class="form-group">
+                            <label>"Person"</label>
+                            <select name="person_id" class="form-select" required>
+                                {people.iter().map(|p| view! { <option value=thing_string(&p.id)>{p.name.clone()}</option> }).collect_view()}
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label>"Desk"</label>
+                            <select name="desk_id" class="form-select" required>
+                                {free.into_iter().map(|(id, label)| view! { <option value=id>{label}</option> }).collect_view()}
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label>"Booking"</label>
+                            <select name="kind" class="form-select">
+                                <option value=BookingKind::Day.to_string()>{format!("Day ({})", date)}</option>
+                                <option value=BookingKind::Permanent.to_string()>"Permanent seat"</option>
+                            </select>
+                        </div>
+                        <button type="submit" class="btn btn-primary btn-sm">"Book"</button>
+                    </form>
+                </div>
+            </div>
+        </div>
+    }
+}
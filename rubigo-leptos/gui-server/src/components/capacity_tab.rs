@@ -0,0 +1,271 @@
+//! Capacity Tab
+//!
+//! Utilization bars for rack units, space power and cooling, and device
+//! ports, each with the date it is projected to run out. Spaces get a form
+//! for their power and cooling budget, and a device can be checked against
+//! capacity before it is created, or created anyway.
+
+use crate::capacity::{amount, CapacityReport, DeviceCheck, Figure};
+use leptos::prelude::*;
+use operations::capacity::Level;
+
+fn level_color(level: Level) -> &'static str {
+    match level {
+        Level::Ok => "var(--color-success)",
+        Level::Warning => "var(--color-warning)",
+        Level::Over => "var(--color-error)",
+    }
+}
+
+/// `used / total unit (percent)`, or just the amount used when there is no total
+fn label(figure: &Figure, unit: &str) -> String {
+    match figure.usage().ratio() {
+        Some(ratio) => {
+            format!(
+                "{} / {} {} ({:.0}%)",
+                amount(figure.used),
+                amount(figure.total),
+                unit,
+                ratio * 100.0
+            )
+        }
+        None => format!("{} {}", amount(figure.used), unit),
+    }
+}
+
+#[component]
+fn UsageBar(figure: Figure, unit: &'static str) -> impl IntoView {
+    let width = figure
+        .usage()
+        .ratio()
+        .map_or(0.0, |r| (r * 100.0).min(100.0));
+    let fill = format!(
+        "width: {:.1}%; background: {};",
+        width,
+        level_color(figure.level)
+    );
+    view! {
+        <div class="capacity-usage">
+            <div class="capacity-bar"><div class="capacity-fill" style=fill></div></div>
+            <span class="text-muted">{label(&figure, unit)}</span>
+        </div>
+    }
+}
+
+fn exhausts(figure: &Figure) -> String {
+    figure
+        .exhausts_on
+        .clone()
+        .unwrap_or_else(|| "—".to_string())
+}
+
+#[component]
+fn CheckDevice(report: CapacityReport, check: Option<DeviceCheck>) -> impl IntoView {
+    let device = check.as_ref().map(|c| c.device.clone());
+    let rack = device
+        .as_ref()
+        .map(|d| d.rack_id.id.to_raw())
+        .unwrap_or_default();
+    let name = device.as_ref().map(|d| d.name.clone()).unwrap_or_default();
+    let position = device.as_ref().map_or(1, |d| d.position_u);
+    let height = device.as_ref().map_or(1, |d| d.height_u);
+    let power = device.as_ref().map_or(0, |d| d.power_w);
+
+    view! {
+        <div class="card">
+            <h2>"Check a Device"</h2>
+            <p class="text-muted">"See what a new device would do to its rack and space before creating it."</p>
+            <form action="/" method="get" class="form-row" style="display: flex; gap: 12px; align-items: flex-end; flex-wrap: wrap;">
+                <input type="hidden" name="tab" value="capacity"/>
+                <div class="form-group"><label for="check-name">"Name"</label><input type="text" id="check-name" name="name" value=name.clone()/></div>
+                <div class="form-group">
+                    <label for="check-rack">"Rack"</label>
+                    <select id="check-rack" name="rack_id" required>
+                        {report.racks.iter().map(|r| {
+                            let selected = r.id == rack;
+                            view! { <option value=r.id.clone() selected=selected>{format!("{} ({})", r.name, r.space)}</option> }
+                        }).collect_view()}
+                    </select>
+                </div>
+                <div class="form-group"><label for="check-position">"Position (U)"</label><input type="number" id="check-position" name="position_u" min="1" required value=position.to_string()/></div>
+                <div class="form-group"><label for="check-height">"Height (U)"</label><input type="number" id="check-height" name="height_u" min="1" required value=height.to_string()/></div>
+                <div class="form-group"><label for="check-power">"Power (W)"</label><input type="number" id="check-power" name="power_w" min="0" required value=power.to_string()/></div>
+                <button type="submit" class="btn btn-secondary">"Check"</button>
+            </form>
+            {check.map(|check| {
+                let device = check.device;
+                let fits = check.warnings.is_empty();
+                view! {
+                    <div style="margin-top: 12px;">
+                        {if fits {
+                            view! { <p style="color: var(--color-success);">"Fits within capacity."</p> }.into_any()
+                        } else {
+                            view! {
+                                <ul>
+                                    {check.warnings.into_iter().map(|w| view! { <li style="color: var(--color-warning);">{w}</li> }).collect_view()}
+                                </ul>
+                            }.into_any()
+                        }}
+                        <form action="/devices/create" method="post" style="display: flex; gap: 8px; align-items: center;">
+                            <input type="hidden" name="rack_id" value=device.rack_id.to_string()/>
+                            <input type="hidden" name="position_u" value=device.position_u.to_string()/>
+                            <input type="hidden" name="height_u" value=device.height_u.to_string()/>
+                            <input type="hidden" name="power_w" value=device.power_w.to_string()/>
+                            <input type="text" name="name" placeholder="Device name" required value=device.name/>
+                            {(!fits).then(|| view! { <input type="hidden" name="force" value="1"/> })}
+                            <button type="submit" class={if fits { "btn btn-primary" } else { "btn btn-danger" }}>
+                                {if fits { "Create device" } else { "Create anyway" }}
+                            </button>
+                        </form>
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
+
+#[component]
+pub fn CapacityTab(
+    /// `None` when the figures could not be loaded
+    report: Option<CapacityReport>,
+    /// A device being checked, from the check form or a refused create
+    #[prop(default = None)]
+    check: Option<DeviceCheck>,
+) -> impl IntoView {
+    let Some(report) = report else {
+        return view! { <div class="card"><p class="text-muted">"Capacity figures are unavailable."</p></div> }
+            .into_any();
+    };
+    let alerts: Vec<(String, Figure)> = report
+        .alerts()
+        .into_iter()
+        .map(|(what, f)| (what, f.clone()))
+        .collect();
+
+    view! {
+        {(!alerts.is_empty()).then(|| view! {
+            <div class="card">
+                <h2>"Warnings"</h2>
+                <ul>
+                    {alerts.into_iter().map(|(what, figure)| {
+                        let when = figure.exhausts_on.clone().map(|d| format!(", runs out {d}")).unwrap_or_default();
+                        let state = if figure.level == Level::Over { "over capacity" } else { "nearly full" };
+                        view! { <li style=format!("color: {};", level_color(figure.level))>{format!("{what} {state}{when}")}</li> }
+                    }).collect_view()}
+                </ul>
+            </div>
+        })}
+
+        <CheckDevice report=report.clone() check=check/>
+
+        <div class="card">
+            <h2>"Racks"</h2>
+            <p class="text-muted">{format!("Rack units taken by devices and patch panels on {}. Exhaustion dates extend the daily snapshots.", report.day)}</p>
+            {if report.racks.is_empty() {
+                view! { <p class="text-muted">"No racks yet."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table">
+                        <thead><tr><th>"Rack"</th><th>"Space"</th><th>"Devices"</th><th>"Units"</th><th>"Power"</th><th>"Full by"</th></tr></thead>
+                        <tbody>
+                            {report.racks.iter().map(|r| view! {
+                                <tr>
+                                    <td><a href=format!("/?tab=sites&rack_id=rack:{}", r.id)>{r.name.clone()}</a></td>
+                                    <td>{r.space.clone()}</td>
+                                    <td>{r.devices}</td>
+                                    <td><UsageBar figure=r.units.clone() unit="U"/></td>
+                                    <td>
+                                        {format!("{} kW", amount(r.power_kw))}
+                                        {(r.simulated_kw > 0.0).then(|| view! {
+                                            <span class="text-muted">{format!(" ({} simulated)", amount(r.simulated_kw))}</span>
+                                        })}
+                                    </td>
+                                    <td>{exhausts(&r.units)}</td>
+                                </tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+
+        <div class="card">
+            <h2>"Power and Cooling"</h2>
+            <p class="text-muted">"Power drawn by each space's devices against its budget; the heat to remove is taken to equal the power drawn. Devices without a power rating count what their component drew in the latest simulation."</p>
+            {if report.spaces.is_empty() {
+                view! { <p class="text-muted">"No spaces yet."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table">
+                        <thead><tr><th>"Space"</th><th>"Racks"</th><th>"Power"</th><th>"Cooling"</th><th>"Full by"</th><th>"Budget (kW)"</th></tr></thead>
+                        <tbody>
+                            {report.spaces.iter().map(|s| {
+                                let first = [&s.power, &s.cooling].into_iter().filter_map(|f| f.exhausts_on.clone()).min();
+                                let budget_url = format!("/spaces/{}/budget", s.id);
+                                view! {
+                                    <tr>
+                                        <td>{s.name.clone()}</td>
+                                        <td>{s.racks}</td>
+                                        <td><UsageBar figure=s.power.clone() unit="kW"/></td>
+                                        <td><UsageBar figure=s.cooling.clone() unit="kW"/></td>
+                                        <td>{first.unwrap_or_else(|| "—".to_string())}</td>
+                                        <td>
+                                            <form action=budget_url method="post" style="display: flex; gap: 6px;">
+                                                <input type="number" name="power_kw" min="0" step="0.1" required style="width: 80px;"
+                                                    placeholder="Power" value=s.budgeted.then(|| amount(s.power.total))/>
+                                                <input type="number" name="cooling_kw" min="0" step="0.1" required style="width: 80px;"
+                                                    placeholder="Cooling" value=s.budgeted.then(|| amount(s.cooling.total))/>
+                                                <button type="submit" class="btn btn-sm btn-secondary">"Save"</button>
+                                            </form>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+
+        <div class="card">
+            <h2>"Ports"</h2>
+            {if report.devices.is_empty() {
+                view! { <p class="text-muted">"No devices have ports yet."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table">
+                        <thead><tr><th>"Device"</th><th>"Rack"</th><th>"Cabled"</th><th>"Full by"</th></tr></thead>
+                        <tbody>
+                            {report.devices.iter().map(|d| view! {
+                                <tr>
+                                    <td><a href=format!("/?tab=sites&device_id=device:{}", d.id)>{d.name.clone()}</a></td>
+                                    <td>{d.rack.clone()}</td>
+                                    <td><UsageBar figure=d.ports.clone() unit="ports"/></td>
+                                    <td>{exhausts(&d.ports)}</td>
+                                </tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+    }.into_any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_show_share_only_with_a_total() {
+        let figure = |used: f64, total: f64| Figure {
+            used,
+            total,
+            level: operations::capacity::Usage::new(used, total).level(),
+            exhausts_on: None,
+        };
+        assert_eq!(label(&figure(38.0, 42.0), "U"), "38 / 42 U (90%)");
+        assert_eq!(label(&figure(2.5, 0.0), "kW"), "2.5 kW");
+        assert_eq!(level_color(figure(43.0, 42.0).level), "var(--color-error)");
+    }
+}
@@ -1,38 +1,36 @@
-//! Chat Module Stub
+//! Chat Module
 //!
-//! Placeholder for the Chat module.
-//! Will handle team messaging and collaboration.
+//! Persona-to-persona messaging. The page shell is server-rendered; the
+//! conversation list, threads and live delivery live in the `ChatClient`
+//! island.
 
+use gui_server::islands::ChatClient;
 use leptos::prelude::*;
 use leptos::IntoView;
+use nexosim_hybrid::database::geo::Person;
 
-/// Chat module placeholder component
+/// Chat module for the signed-in persona
 #[component]
-pub fn ChatModule() -> impl IntoView {
+pub fn ChatModule(
+    /// Persona (person name) chatting
+    persona: String,
+    /// Everyone in the directory
+    people: Vec<Person>,
+) -> impl IntoView {
+    let mut contacts: Vec<String> = people
+        .into_iter()
+        .map(|p| p.name)
+        .filter(|name| *name != persona)
+        .collect();
+    contacts.sort();
+
     view! {
-        <div class="module-stub">
+        <div class="card chat-module">
             <div class="module-stub-header">
                 <span class="module-stub-icon">"💬"</span>
                 <h1 class="module-stub-title">"Chat"</h1>
             </div>
-            <div class="module-stub-content">
-                <p class="module-stub-description">
-                    "Real-time team communication. Direct messages, channels, and integrations with your workflow."
-                </p>
-                <div class="module-stub-features">
-                    <h3>"Planned Features"</h3>
-                    <ul>
-                        <li>"Direct Messages"</li>
-                        <li>"Team Channels"</li>
-                        <li>"File Sharing"</li>
-                        <li>"Thread Replies"</li>
-                        <li>"Search & History"</li>
-                    </ul>
-                </div>
-                <div class="module-stub-cta">
-                    <span class="coming-soon-label">"Coming Soon"</span>
-                </div>
-            </div>
+            <ChatClient persona=persona contacts=contacts />
         </div>
     }
 }
@@ -1,44 +1,124 @@
 use crate::ComponentConfig;
+use gui_server::islands::{AsyncForm, DialogTrigger, TableFilter};
 use leptos::prelude::*;
+use nexosim_hybrid::database::telemetry_sources::{TelemetryMode, TelemetrySource};
 
+/// Where a component's telemetry comes from, its latest live readings, and
+/// a dialog to switch it between simulated and live
 #[component]
-pub fn ComponentsTab(components: Vec<ComponentConfig>) -> impl IntoView {
+fn TelemetryCell(component_id: u32, source: TelemetrySource) -> impl IntoView {
+    let action = format!("/components/{}/telemetry", component_id);
+    let status_class = match (&source.error, source.mode.is_live()) {
+        (Some(_), _) => "job-status job-status-failed",
+        (None, true) => "job-status job-status-succeeded",
+        (None, false) => "job-status",
+    };
+    let packets = source
+        .rates
+        .get("in_packets_per_sec")
+        .zip(source.rates.get("out_packets_per_sec"))
+        .map(|(rx, tx)| format!("{:.1} pkt/s in, {:.1} pkt/s out", rx, tx));
+    let uptime = source.readings.get("uptime_secs").map(|secs| format!("up {:.0} h", secs / 3600.0));
+    let option = |mode: TelemetryMode, label: &'static str| {
+        view! { <option value=mode.to_string() selected={source.mode == mode}>{label}</option> }
+    };
+    let title = format!("Telemetry for component {}", component_id);
+
+    view! {
+        <span class=status_class>{source.mode.to_string()}</span>
+        {packets.map(|p| view! { <div class="job-detail">{p}</div> })}
+        {uptime.map(|u| view! { <div class="job-detail">{u}</div> })}
+        {source.error.clone().map(|e| view! { <div class="job-detail">{e}</div> })}
+        <DialogTrigger label="Telemetry".to_string() title=title class="btn btn-sm btn-secondary">
+            <form action=action method="post" class="form-stack">
+                <div class="form-group">
+                    <label>"Source"</label>
+                    <select name="mode">
+                        {option(TelemetryMode::Simulated, "Simulated")}
+                        {option(TelemetryMode::Snmp, "SNMP")}
+                        {option(TelemetryMode::Agent, "Agent endpoint")}
+                    </select>
+                </div>
+                <div class="form-group">
+                    <label>"Target"</label>
+                    <input type="text" name="target" value=source.target.clone() placeholder="host[:port] or https://device/metrics"/>
+                </div>
+                <div class="form-row">
+                    <div class="form-group">
+                        <label>"SNMP community"</label>
+                        <input type="text" name="community" value=source.community.clone()/>
+                    </div>
+                    <div class="form-group">
+                        <label>"Interface index"</label>
+                        <input type="number" name="if_index" min="1" value=source.if_index.to_string()/>
+                    </div>
+                    <div class="form-group">
+                        <label>"Poll every (s)"</label>
+                        <input type="number" name="interval_secs" min="5" value=source.interval_secs.to_string()/>
+                    </div>
+                </div>
+                <button type="submit" class="btn btn-primary">"Save"</button>
+            </form>
+        </DialogTrigger>
+    }
+}
+
+#[component]
+pub fn ComponentsTab(components: Vec<ComponentConfig>, sources: Vec<TelemetrySource>) -> impl IntoView {
     view! {
         <div class="card">
             <h2>"Components"</h2>
 
-            <form action="/components/create" method="post" class="form-inline">
-                <input type="text" name="id" placeholder="ID (optional)" style="width: 100px;"/>
-                <input type="text" name="name" placeholder="Component name" required/>
-                <select name="component_type">
-                    <option value="router">"Router"</option>
-                    <option value="switch">"Switch"</option>
-                </select>
-                <button type="submit" class="btn btn-primary">"Add Component"</button>
-            </form>
+            <div class="form-inline">
+                <TableFilter target="components-table".to_string() />
+                <DialogTrigger label="Add Component".to_string() title="New Component".to_string()>
+                    <AsyncForm action="/components/create".to_string() class="form-inline" swap="#components-table tbody">
+                        <input type="text" name="id" placeholder="ID (optional)" style="width: 100px;"/>
+                        <input type="text" name="name" placeholder="Component name" required/>
+                        <select name="component_type">
+                            <option value="router">"Router"</option>
+                            <option value="switch">"Switch"</option>
+                        </select>
+                        <input type="number" name="idle_w" min="0" placeholder="Idle W" style="width: 90px;"/>
+                        <input type="number" name="max_w" min="0" placeholder="Max W" style="width: 90px;"/>
+                        <button type="submit" class="btn btn-primary">"Add Component"</button>
+                    </AsyncForm>
+                </DialogTrigger>
+            </div>
 
-            <table class="data-table">
+            <table class="data-table" id="components-table">
                 <thead>
                     <tr>
                         <th>"ID"</th>
                         <th>"Name"</th>
                         <th>"Type"</th>
+                        <th>"Power"</th>
+                        <th>"Telemetry"</th>
                         <th>"Actions"</th>
                     </tr>
                 </thead>
                 <tbody>
                     {components.into_iter().map(|c| {
                         let type_str = format!("{:?}", c.component_type);
+                        let power = c.power_profile();
+                        let power = format!("{:.0}–{:.0} W{}", power.idle_w, power.max_w, if c.power.is_none() { " (default)" } else { "" });
                         let delete_url = format!("/components/{}/delete", c.id);
+                        let source = sources
+                            .iter()
+                            .find(|s| s.component_id == c.id)
+                            .cloned()
+                            .unwrap_or_else(|| TelemetrySource::simulated(c.id));
                         view! {
                             <tr>
                                 <td>{c.id}</td>
                                 <td>{c.name}</td>
                                 <td>{type_str}</td>
+                                <td class="text-muted">{power}</td>
+                                <td><TelemetryCell component_id=c.id source=source/></td>
                                 <td>
-                                    <form action=delete_url method="post" style="display:inline;">
+                                    <AsyncForm action=delete_url remove_closest="tr">
                                         <button type="submit" class="btn btn-danger btn-sm">"Delete"</button>
-                                    </form>
+                                    </AsyncForm>
                                 </td>
                             </tr>
                         }
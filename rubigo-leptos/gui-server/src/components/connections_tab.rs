@@ -1,5 +1,6 @@
 use leptos::prelude::*;
 use crate::{ConnectionConfig, ComponentConfig};
+use gui_server::islands::{AsyncForm, TableFilter};
 
 #[component]
 pub fn ConnectionsTab(connections: Vec<ConnectionConfig>, components: Vec<ComponentConfig>) -> impl IntoView {
@@ -7,7 +8,7 @@ pub fn ConnectionsTab(connections: Vec<ConnectionConfig>, components: Vec<Compon
         <div class="card">
             <h2>"Connections"</h2>
             
-            <form action="/connections/create" method="post" class="form-inline">
+            <AsyncForm action="/connections/create".to_string() class="form-inline" swap="#connections-table tbody">
                 <select name="from_id">
                     {components.iter().map(|c| {
                         let val = c.id.to_string();
@@ -24,9 +25,11 @@ pub fn ConnectionsTab(connections: Vec<ConnectionConfig>, components: Vec<Compon
                     }).collect_view()}
                 </select>
                 <button type="submit" class="btn btn-primary">"Add Connection"</button>
-            </form>
+            </AsyncForm>
+
+            <TableFilter target="connections-table".to_string() />
             
-            <table class="data-table">
+            <table class="data-table" id="connections-table">
                 <thead>
                     <tr>
                         <th>"From"</th>
@@ -42,9 +45,9 @@ pub fn ConnectionsTab(connections: Vec<ConnectionConfig>, components: Vec<Compon
                                 <td>{c.from}</td>
                                 <td>{c.to}</td>
                                 <td>
-                                    <form action=delete_url method="post" style="display:inline;">
+                                    <AsyncForm action=delete_url remove_closest="tr">
                                         <button type="submit" class="btn btn-danger btn-sm">"Delete"</button>
-                                    </form>
+                                    </AsyncForm>
                                 </td>
                             </tr>
                         }
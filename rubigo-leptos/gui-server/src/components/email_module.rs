@@ -1,38 +1,85 @@
-//! Email Module Stub
+//! Email Module
 //!
-//! Placeholder for the Email module.
-//! Will handle organizational email and communications.
+//! The current persona's email preferences and the emails sent to them:
+//! meeting invites, report deliveries and chat mentions.
 
+use operations::email::{EmailKind, EmailPreferences};
 use leptos::prelude::*;
 use leptos::IntoView;
+use nexosim_hybrid::database::email::OutboundEmail;
 
-/// Email module placeholder component
 #[component]
-pub fn EmailModule() -> impl IntoView {
+pub fn EmailModule(preferences: EmailPreferences, outbox: Vec<OutboundEmail>) -> impl IntoView {
     view! {
-        <div class="module-stub">
-            <div class="module-stub-header">
-                <span class="module-stub-icon">"📧"</span>
-                <h1 class="module-stub-title">"Email"</h1>
-            </div>
-            <div class="module-stub-content">
-                <p class="module-stub-description">
-                    "Centralized email management. Send, receive, and organize communications across your organization."
-                </p>
-                <div class="module-stub-features">
-                    <h3>"Planned Features"</h3>
-                    <ul>
-                        <li>"Inbox Management"</li>
-                        <li>"Compose & Reply"</li>
-                        <li>"Folders & Labels"</li>
-                        <li>"Search"</li>
-                        <li>"Templates"</li>
-                    </ul>
-                </div>
-                <div class="module-stub-cta">
-                    <span class="coming-soon-label">"Coming Soon"</span>
-                </div>
+        <div class="card">
+            <h2>"Email Preferences"</h2>
+            <p class="text-muted">"Choose which emails are sent to the address in your directory entry."</p>
+            <form action="/email/preferences" method="post" class="form-stack">
+                {EmailKind::ALL.into_iter().map(|kind| {
+                    let name = match kind {
+                        EmailKind::Invite => "invites",
+                        EmailKind::Report => "reports",
+                        EmailKind::Mention => "mentions",
+                    };
+                    view! {
+                        <label class="day-toggle">
+                            <input type="checkbox" name=name value="on" checked=preferences.allows(kind)/>
+                            <span>{kind.label()}</span>
+                        </label>
+                    }
+                }).collect_view()}
+                <button type="submit" class="btn btn-primary">"Save"</button>
+            </form>
+        </div>
+
+        <div class="card">
+            <div style="display: flex; justify-content: space-between; align-items: center;">
+                <h2>"Sent to You"</h2>
+                <a href="/?tab=email" class="btn btn-sm btn-secondary">"Refresh"</a>
             </div>
+
+            {if outbox.is_empty() {
+                view! { <p class="text-muted">"No emails have been sent to you."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" id="email-outbox-table">
+                        <thead>
+                            <tr>
+                                <th>"Subject"</th>
+                                <th>"To"</th>
+                                <th>"Status"</th>
+                                <th>"Queued"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {outbox.into_iter().map(|email| {
+                                let status_class = format!("job-status email-status-{}", email.status);
+                                let attachments: Vec<String> = email.attachments.iter().map(|a| a.filename.clone()).collect();
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <strong>{email.subject}</strong>
+                                            <details>
+                                                <summary class="text-muted">"Message"</summary>
+                                                <pre class="job-detail">{email.text}</pre>
+                                                {(!attachments.is_empty()).then(|| view! {
+                                                    <div class="text-muted">{format!("Attached: {}", attachments.join(", "))}</div>
+                                                })}
+                                            </details>
+                                        </td>
+                                        <td class="text-muted">{email.address}</td>
+                                        <td>
+                                            <span class=status_class>{email.status.to_string()}</span>
+                                            {email.error.map(|e| view! { <div class="job-detail">{e}</div> })}
+                                        </td>
+                                        <td class="text-muted">{email.sent_at.unwrap_or(email.created_at)}</td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
         </div>
     }
 }
@@ -0,0 +1,17 @@
+use gui_server::islands::FlagToggles;
+use leptos::prelude::*;
+use ui_core::hooks::FeatureFlag;
+
+#[component]
+pub fn FlagsTab(flags: Vec<FeatureFlag>) -> impl IntoView {
+    view! {
+        <div class="card">
+            <h2>"Feature Flags"</h2>
+            <p class="text-muted">
+                "Switch half-migrated features between their old and new versions. Changes apply on the next page load; "
+                "defaults come from " <code>"rubigo.toml"</code> "."
+            </p>
+            <FlagToggles flags=flags />
+        </div>
+    }
+}
@@ -1,3 +1,4 @@
+use crate::components::geospatial::great_circle::{route, route_to_path, segment_count, DEFAULT_STEP_DEG};
 use crate::components::geospatial::projections::{geometry_to_path, Orthographic, Projection};
 use crate::Region;
 use leptos::prelude::*;
@@ -10,6 +11,8 @@ pub fn GlobeView(
     #[prop(default = None)] center: Option<(f64, f64)>,
     #[prop(default = vec![])] cached_country_paths: Vec<String>,
     #[prop(default = vec![])] cached_state_paths: Vec<String>,
+    /// Great-circle arcs to draw, as `(from, to)` in `(lon, lat)`
+    #[prop(default = vec![])] arcs: Vec<((f64, f64), (f64, f64))>,
 ) -> impl IntoView {
     let size = 400.0;
 
@@ -83,6 +86,19 @@ pub fn GlobeView(
         .center(center_lon, center_lat)
         .fit_size(size, size);
 
+    let arcs_view = arcs
+        .iter()
+        .filter_map(|&(from, to)| {
+            let points = route(from, to, segment_count(from, to, DEFAULT_STEP_DEG));
+            let d = route_to_path(&points, &proj);
+            (!d.is_empty()).then(|| {
+                view! {
+                    <path d=d fill="none" stroke="var(--color-primary)" stroke-width="1.2" stroke-opacity="0.7"/>
+                }
+            })
+        })
+        .collect_view();
+
     let markers = regions
         .iter()
         .filter_map(|r| {
@@ -147,6 +163,8 @@ pub fn GlobeView(
                 {country_paths_view}
                 // US state paths (rendered on top)
                 {state_paths_view}
+                // Region-to-site arcs
+                {arcs_view}
                 // Region markers
                 {markers}
             </svg>
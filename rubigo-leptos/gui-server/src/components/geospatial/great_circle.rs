@@ -0,0 +1,250 @@
+//! Great-circle routes between geographic points
+//!
+//! Shortest paths on the sphere, used to draw arcs between sites on the
+//! globe and served as GeoJSON from `/api/geo/route`:
+//! - [`distance_km`]: haversine distance
+//! - [`initial_bearing`] / [`midpoint`] / [`interpolate`]
+//! - [`route`]: the arc sampled into segments for rendering
+//! - [`route_to_path`]: SVG path through a [`Projection`]
+//!
+//! Points are `(lon, lat)` in degrees, matching `Site::location`.
+
+use std::f64::consts::PI;
+
+use super::projections::Projection;
+
+/// Mean Earth radius (IUGG)
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// Default angular spacing between route samples, in degrees
+pub const DEFAULT_STEP_DEG: f64 = 2.0;
+
+/// Upper bound on route samples
+pub const MAX_SEGMENTS: usize = 512;
+
+/// Central angles this close to π (about 6 m on Earth) count as antipodal
+const ANTIPODAL_RAD: f64 = 1e-6;
+
+fn to_radians((lon, lat): (f64, f64)) -> (f64, f64) {
+    (lon.to_radians(), lat.to_radians())
+}
+
+fn to_vector(p: (f64, f64)) -> [f64; 3] {
+    let (lambda, phi) = to_radians(p);
+    [
+        phi.cos() * lambda.cos(),
+        phi.cos() * lambda.sin(),
+        phi.sin(),
+    ]
+}
+
+fn from_vector([x, y, z]: [f64; 3]) -> (f64, f64) {
+    (
+        y.atan2(x).to_degrees(),
+        z.atan2((x * x + y * y).sqrt()).to_degrees(),
+    )
+}
+
+/// Central angle between two points, in radians
+pub fn central_angle(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (l1, p1) = to_radians(a);
+    let (l2, p2) = to_radians(b);
+    let h = ((p2 - p1) / 2.0).sin().powi(2) + p1.cos() * p2.cos() * ((l2 - l1) / 2.0).sin().powi(2);
+    2.0 * h.sqrt().min(1.0).asin()
+}
+
+/// Great-circle distance in kilometres (haversine)
+pub fn distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    central_angle(a, b) * EARTH_RADIUS_KM
+}
+
+/// Initial bearing from `a` towards `b`, degrees clockwise from north
+pub fn initial_bearing(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (l1, p1) = to_radians(a);
+    let (l2, p2) = to_radians(b);
+    let y = (l2 - l1).sin() * p2.cos();
+    let x = p1.cos() * p2.sin() - p1.sin() * p2.cos() * (l2 - l1).cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+/// Point at fraction `f` (0..=1) along the great circle from `a` to `b`
+///
+/// Antipodal points have no unique great circle between them; the route is
+/// then taken north along `a`'s meridian, over the North Pole (or along the
+/// prime meridian when `a` is a pole).
+pub fn interpolate(a: (f64, f64), b: (f64, f64), f: f64) -> (f64, f64) {
+    let delta = central_angle(a, b);
+    if delta < 1e-12 {
+        return a;
+    }
+    if PI - delta < ANTIPODAL_RAD {
+        return antipodal_interpolate(a, b, f);
+    }
+    let (va, vb) = (to_vector(a), to_vector(b));
+    let wa = ((1.0 - f) * delta).sin() / delta.sin();
+    let wb = (f * delta).sin() / delta.sin();
+    from_vector([
+        wa * va[0] + wb * vb[0],
+        wa * va[1] + wb * vb[1],
+        wa * va[2] + wb * vb[2],
+    ])
+}
+
+/// [`interpolate`] for (nearly) antipodal points, where dividing by the
+/// sine of the central angle would only amplify rounding noise
+fn antipodal_interpolate(a: (f64, f64), b: (f64, f64), f: f64) -> (f64, f64) {
+    if f >= 1.0 {
+        return b;
+    }
+    let va = to_vector(a);
+    // Unit vector 90° from `a` towards the North Pole
+    let towards = if va[2].abs() > 1.0 - 1e-12 {
+        [1.0, 0.0, 0.0]
+    } else {
+        let n = [-va[2] * va[0], -va[2] * va[1], 1.0 - va[2] * va[2]];
+        let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        [n[0] / len, n[1] / len, n[2] / len]
+    };
+    let (c, s) = ((f * PI).cos(), (f * PI).sin());
+    from_vector([
+        c * va[0] + s * towards[0],
+        c * va[1] + s * towards[1],
+        c * va[2] + s * towards[2],
+    ])
+}
+
+/// Halfway point along the great circle
+pub fn midpoint(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    interpolate(a, b, 0.5)
+}
+
+/// Segments needed to keep samples at most `step_deg` apart
+pub fn segment_count(a: (f64, f64), b: (f64, f64), step_deg: f64) -> usize {
+    let degrees = central_angle(a, b).to_degrees();
+    ((degrees / step_deg.max(1e-6)).ceil() as usize).clamp(1, MAX_SEGMENTS)
+}
+
+/// The route from `a` to `b` as `segments + 1` points, endpoints included
+///
+/// Antipodal points are joined over the North Pole, see [`interpolate`].
+pub fn route(a: (f64, f64), b: (f64, f64), segments: usize) -> Vec<(f64, f64)> {
+    let segments = segments.clamp(1, MAX_SEGMENTS);
+    (0..=segments)
+        .map(|i| interpolate(a, b, i as f64 / segments as f64))
+        .collect()
+}
+
+/// Split a route where it crosses the antimeridian, for flat maps and GeoJSON
+pub fn split_at_antimeridian(points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    let mut lines = vec![Vec::new()];
+    for (i, &p) in points.iter().enumerate() {
+        if i > 0 {
+            let prev = points[i - 1];
+            if (p.0 - prev.0).abs() > 180.0 {
+                // Latitude where the segment meets +/-180
+                let edge = if prev.0 > 0.0 { 180.0 } else { -180.0 };
+                let p_lon = p.0 + if edge > 0.0 { 360.0 } else { -360.0 };
+                let t = (edge - prev.0) / (p_lon - prev.0);
+                let lat = prev.1 + t * (p.1 - prev.1);
+                lines.last_mut().unwrap().push((edge, lat));
+                lines.push(vec![(-edge, lat)]);
+            }
+        }
+        lines.last_mut().unwrap().push(p);
+    }
+    lines
+}
+
+/// SVG path for a route; hidden stretches (globe back side) break the line
+pub fn route_to_path(points: &[(f64, f64)], projection: &dyn Projection) -> String {
+    let mut path = String::new();
+    let mut pen_down = false;
+    for &(lon, lat) in points {
+        let p = projection.project(lon, lat);
+        if !p.visible {
+            pen_down = false;
+            continue;
+        }
+        let cmd = if pen_down { 'L' } else { 'M' };
+        path.push_str(&format!("{}{:.1},{:.1}", cmd, p.x, p.y));
+        pen_down = true;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::geospatial::projections::Orthographic;
+
+    const LONDON: (f64, f64) = (-0.1278, 51.5074);
+    const NEW_YORK: (f64, f64) = (-74.0060, 40.7128);
+
+    #[test]
+    fn london_to_new_york() {
+        let d = distance_km(LONDON, NEW_YORK);
+        assert!((d - 5570.0).abs() < 10.0, "distance {d}");
+
+        let bearing = initial_bearing(LONDON, NEW_YORK);
+        assert!((bearing - 288.3).abs() < 0.5, "bearing {bearing}");
+
+        // The arc bends north of both endpoints
+        let (_, mid_lat) = midpoint(LONDON, NEW_YORK);
+        assert!(mid_lat > 51.5);
+    }
+
+    #[test]
+    fn route_keeps_endpoints() {
+        let points = route(LONDON, NEW_YORK, 16);
+        assert_eq!(points.len(), 17);
+        let (first, last) = (points[0], points[16]);
+        assert!((first.0 - LONDON.0).abs() < 1e-9 && (first.1 - LONDON.1).abs() < 1e-9);
+        assert!((last.0 - NEW_YORK.0).abs() < 1e-9 && (last.1 - NEW_YORK.1).abs() < 1e-9);
+        assert_eq!(segment_count(LONDON, LONDON, DEFAULT_STEP_DEG), 1);
+    }
+
+    #[test]
+    fn antipodal_routes_go_over_the_north_pole() {
+        let a = (10.0, 0.0);
+        let b = (-170.0, 0.0);
+        let (_, mid_lat) = midpoint(a, b);
+        assert!((mid_lat - 90.0).abs() < 1e-9, "midpoint latitude {mid_lat}");
+
+        let points = route(a, b, 8);
+        for p in &points {
+            assert!(p.0.is_finite() && p.1.is_finite());
+        }
+        // Northwards along a's meridian, then down the opposite one
+        assert!((points[2].0 - 10.0).abs() < 1e-9 && (points[2].1 - 45.0).abs() < 1e-9);
+        assert!((points[6].0 - -170.0).abs() < 1e-9 && (points[6].1 - 45.0).abs() < 1e-9);
+        assert_eq!(points[8], b);
+
+        // Nearly antipodal endpoints give the same, stable route
+        let (_, near_lat) = midpoint(a, (-170.0 + 1e-9, 1e-9));
+        assert!((near_lat - 90.0).abs() < 1e-6);
+
+        // From a pole the route follows the prime meridian
+        let (lon, lat) = interpolate((0.0, 90.0), (0.0, -90.0), 0.5);
+        assert!(lon.abs() < 1e-9 && lat.abs() < 1e-9);
+    }
+
+    #[test]
+    fn splits_pacific_crossing() {
+        let tokyo = (139.6917, 35.6895);
+        let san_francisco = (-122.4194, 37.7749);
+        let lines = split_at_antimeridian(&route(tokyo, san_francisco, 32));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].last().unwrap().0, 180.0);
+        assert_eq!(lines[1][0].0, -180.0);
+    }
+
+    #[test]
+    fn hidden_points_break_the_path() {
+        let proj = Orthographic::new().center(0.0, 0.0).fit_size(400.0, 400.0);
+        let path = route_to_path(
+            &[(0.0, 0.0), (10.0, 0.0), (180.0, 0.0), (-10.0, 0.0)],
+            &proj,
+        );
+        assert_eq!(path.matches('M').count(), 2);
+    }
+}
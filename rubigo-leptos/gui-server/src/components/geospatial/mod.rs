@@ -1,3 +1,5 @@
 pub mod globe_view;
+pub mod great_circle;
 pub mod map_view;
 pub mod projections;
+pub mod simplify;
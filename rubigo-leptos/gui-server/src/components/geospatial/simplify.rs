@@ -0,0 +1,305 @@
+//! Viewport filtering and geometry simplification for GeoJSON
+//!
+//! Used by `/api/geo/features` so map clients can request only the features
+//! in view, at a resolution that suits the zoom level:
+//! - [`BBox`]: `minLon,minLat,maxLon,maxLat` viewport, antimeridian-aware
+//! - [`simplify_geometry`]: Douglas-Peucker on every ring / line
+
+use serde_json::Value;
+
+/// Geographic bounding box in degrees
+///
+/// `min_lon > max_lon` describes a box that crosses the antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+impl BBox {
+    /// Parse `minLon,minLat,maxLon,maxLat`
+    pub fn parse(value: &str) -> Option<Self> {
+        let parts: Vec<f64> = value
+            .split(',')
+            .map(|p| p.trim().parse::<f64>().ok())
+            .collect::<Option<_>>()?;
+        let [min_lon, min_lat, max_lon, max_lat] = parts.as_slice() else {
+            return None;
+        };
+        let valid_lon = |v: f64| (-180.0..=180.0).contains(&v);
+        let valid_lat = |v: f64| (-90.0..=90.0).contains(&v);
+        if !(valid_lon(*min_lon)
+            && valid_lon(*max_lon)
+            && valid_lat(*min_lat)
+            && valid_lat(*max_lat))
+            || min_lat > max_lat
+        {
+            return None;
+        }
+        Some(Self {
+            min_lon: *min_lon,
+            min_lat: *min_lat,
+            max_lon: *max_lon,
+            max_lat: *max_lat,
+        })
+    }
+
+    /// Longitude spans as non-wrapping intervals
+    fn lon_ranges(&self) -> Vec<(f64, f64)> {
+        if self.min_lon <= self.max_lon {
+            vec![(self.min_lon, self.max_lon)]
+        } else {
+            vec![(self.min_lon, 180.0), (-180.0, self.max_lon)]
+        }
+    }
+
+    /// True when the two boxes overlap
+    pub fn intersects(&self, other: &BBox) -> bool {
+        if self.max_lat < other.min_lat || self.min_lat > other.max_lat {
+            return false;
+        }
+        self.lon_ranges().iter().any(|(a0, a1)| {
+            other
+                .lon_ranges()
+                .iter()
+                .any(|(b0, b1)| a0 <= b1 && b0 <= a1)
+        })
+    }
+}
+
+/// Read a GeoJSON position as `[lon, lat]`
+fn position(value: &Value) -> Option<[f64; 2]> {
+    let coords = value.as_array()?;
+    Some([coords.first()?.as_f64()?, coords.get(1)?.as_f64()?])
+}
+
+fn extend(bbox: &mut Option<BBox>, [lon, lat]: [f64; 2]) {
+    match bbox {
+        Some(b) => {
+            b.min_lon = b.min_lon.min(lon);
+            b.min_lat = b.min_lat.min(lat);
+            b.max_lon = b.max_lon.max(lon);
+            b.max_lat = b.max_lat.max(lat);
+        }
+        None => {
+            *bbox = Some(BBox {
+                min_lon: lon,
+                min_lat: lat,
+                max_lon: lon,
+                max_lat: lat,
+            })
+        }
+    }
+}
+
+fn collect_bbox(coords: &Value, bbox: &mut Option<BBox>) {
+    if let Some(p) = position(coords) {
+        extend(bbox, p);
+    } else if let Some(items) = coords.as_array() {
+        for item in items {
+            collect_bbox(item, bbox);
+        }
+    }
+}
+
+/// Bounding box of a GeoJSON geometry
+pub fn geometry_bbox(geometry: &Value) -> Option<BBox> {
+    let mut bbox = None;
+    match geometry.get("type").and_then(Value::as_str) {
+        Some("GeometryCollection") => {
+            for g in geometry.get("geometries")?.as_array()? {
+                if let Some(b) = geometry_bbox(g) {
+                    extend(&mut bbox, [b.min_lon, b.min_lat]);
+                    extend(&mut bbox, [b.max_lon, b.max_lat]);
+                }
+            }
+        }
+        _ => collect_bbox(geometry.get("coordinates")?, &mut bbox),
+    }
+    bbox
+}
+
+/// Perpendicular distance from `p` to the segment `a`-`b`
+fn segment_distance(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len_sq = dx * dx + dy * dy;
+    if len_sq == 0.0 {
+        return ((p[0] - a[0]).powi(2) + (p[1] - a[1]).powi(2)).sqrt();
+    }
+    let t = (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len_sq).clamp(0.0, 1.0);
+    let (x, y) = (a[0] + t * dx, a[1] + t * dy);
+    ((p[0] - x).powi(2) + (p[1] - y).powi(2)).sqrt()
+}
+
+/// Douglas-Peucker line simplification (iterative)
+///
+/// Keeps the endpoints and every point farther than `tolerance` (degrees)
+/// from the simplified line.
+pub fn douglas_peucker(points: &[[f64; 2]], tolerance: f64) -> Vec<[f64; 2]> {
+    if points.len() < 3 || tolerance <= 0.0 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let mut max_dist = 0.0;
+        let mut index = start;
+        for i in start + 1..end {
+            let d = segment_distance(points[i], points[start], points[end]);
+            if d > max_dist {
+                max_dist = d;
+                index = i;
+            }
+        }
+        if max_dist > tolerance {
+            keep[index] = true;
+            stack.push((start, index));
+            stack.push((index, end));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, k)| k.then_some(*p))
+        .collect()
+}
+
+fn positions(line: &Value) -> Option<Vec<[f64; 2]>> {
+    line.as_array()?.iter().map(position).collect()
+}
+
+fn to_value(points: Vec<[f64; 2]>) -> Value {
+    Value::Array(
+        points
+            .into_iter()
+            .map(|[x, y]| serde_json::json!([x, y]))
+            .collect(),
+    )
+}
+
+/// Simplify a line; rings keep at least four positions (closed triangle)
+fn simplify_line(line: &Value, tolerance: f64, ring: bool) -> Value {
+    let Some(points) = positions(line) else {
+        return line.clone();
+    };
+    let simplified = douglas_peucker(&points, tolerance);
+    let min = if ring { 4 } else { 2 };
+    if simplified.len() < min {
+        return line.clone();
+    }
+    to_value(simplified)
+}
+
+fn simplify_lines(lines: &Value, tolerance: f64, ring: bool) -> Value {
+    match lines.as_array() {
+        Some(items) => Value::Array(
+            items
+                .iter()
+                .map(|l| simplify_line(l, tolerance, ring))
+                .collect(),
+        ),
+        None => lines.clone(),
+    }
+}
+
+/// Simplify every line and ring of a GeoJSON geometry
+pub fn simplify_geometry(geometry: &Value, tolerance: f64) -> Value {
+    if tolerance <= 0.0 {
+        return geometry.clone();
+    }
+    let Some(kind) = geometry.get("type").and_then(Value::as_str) else {
+        return geometry.clone();
+    };
+
+    let mut out = geometry.clone();
+    if kind == "GeometryCollection" {
+        if let Some(geometries) = geometry.get("geometries").and_then(Value::as_array) {
+            out["geometries"] = Value::Array(
+                geometries
+                    .iter()
+                    .map(|g| simplify_geometry(g, tolerance))
+                    .collect(),
+            );
+        }
+        return out;
+    }
+
+    let Some(coords) = geometry.get("coordinates") else {
+        return out;
+    };
+    let simplified = match kind {
+        "LineString" => simplify_line(coords, tolerance, false),
+        "MultiLineString" => simplify_lines(coords, tolerance, false),
+        "Polygon" => simplify_lines(coords, tolerance, true),
+        "MultiPolygon" => match coords.as_array() {
+            Some(polygons) => Value::Array(
+                polygons
+                    .iter()
+                    .map(|rings| simplify_lines(rings, tolerance, true))
+                    .collect(),
+            ),
+            None => coords.clone(),
+        },
+        _ => coords.clone(),
+    };
+    out["coordinates"] = simplified;
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_bbox() {
+        let b = BBox::parse("-10, 20, 30, 40").unwrap();
+        assert_eq!(b.min_lon, -10.0);
+        assert_eq!(b.max_lat, 40.0);
+        assert!(BBox::parse("1,2,3").is_none());
+        assert!(BBox::parse("0,50,10,40").is_none());
+        assert!(BBox::parse("0,0,200,10").is_none());
+    }
+
+    #[test]
+    fn bbox_intersection_across_antimeridian() {
+        let pacific = BBox::parse("170,-10,-170,10").unwrap();
+        let fiji = BBox::parse("177,-19,179,-16").unwrap();
+        let samoa = BBox::parse("-173,-14,-171,-13").unwrap();
+        let africa = BBox::parse("-20,-35,50,35").unwrap();
+        assert!(pacific.intersects(&fiji));
+        assert!(pacific.intersects(&samoa));
+        assert!(!pacific.intersects(&africa));
+    }
+
+    #[test]
+    fn douglas_peucker_drops_collinear_points() {
+        let line = [[0.0, 0.0], [1.0, 0.01], [2.0, 0.0], [3.0, 5.0], [4.0, 0.0]];
+        let simplified = douglas_peucker(&line, 0.1);
+        assert_eq!(
+            simplified,
+            vec![[0.0, 0.0], [2.0, 0.0], [3.0, 5.0], [4.0, 0.0]]
+        );
+        assert_eq!(douglas_peucker(&line, 0.0), line.to_vec());
+    }
+
+    #[test]
+    fn polygon_rings_stay_closed() {
+        let square = json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]]
+        });
+        // Large tolerance would collapse the ring; it is kept as-is instead
+        assert_eq!(simplify_geometry(&square, 10.0), square);
+
+        let bbox = geometry_bbox(&square).unwrap();
+        assert_eq!((bbox.min_lon, bbox.max_lat), (0.0, 1.0));
+    }
+}
@@ -0,0 +1,281 @@
+//! Incidents Tab
+//!
+//! The incident board: a column per status with a card for each incident,
+//! the form that opens one, and the selected incident's timeline with the
+//! forms that move, assign and annotate it. Incidents opened by simulated
+//! faults carry a training badge.
+
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+use nexosim_hybrid::config::ComponentConfig;
+use nexosim_hybrid::database::geo::{Person, Site};
+use nexosim_hybrid::database::incidents::{Incident, IncidentStatus, Severity};
+use nexosim_hybrid::database::oncall::EscalationPolicy;
+use surrealdb::sql::Thing;
+
+fn thing_key(id: &Option<Thing>) -> String {
+    id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+}
+
+/// Incidents grouped into the board's columns, in workflow order
+fn columns(incidents: &[Incident]) -> Vec<(IncidentStatus, Vec<&Incident>)> {
+    IncidentStatus::ALL
+        .into_iter()
+        .map(|status| {
+            (
+                status,
+                incidents.iter().filter(|i| i.status == status).collect(),
+            )
+        })
+        .collect()
+}
+
+#[component]
+pub fn IncidentsTab(
+    /// All incidents, most severe first
+    incidents: Vec<Incident>,
+    /// Key of the incident whose detail is shown
+    selected: Option<String>,
+    people: Vec<Person>,
+    sites: Vec<Site>,
+    components: Vec<ComponentConfig>,
+    /// Escalation policies an incident can page
+    policies: Vec<EscalationPolicy>,
+) -> impl IntoView {
+    let person_names: HashMap<String, String> = people
+        .iter()
+        .map(|p| (thing_key(&p.id), p.name.clone()))
+        .collect();
+    let site_names: HashMap<String, String> = sites
+        .iter()
+        .map(|s| (thing_key(&s.id), s.name.clone()))
+        .collect();
+    let component_names: HashMap<u32, String> =
+        components.iter().map(|c| (c.id, c.name.clone())).collect();
+    let affected = move |incident: &Incident| {
+        let components = incident.components.iter().map(|id| {
+            component_names
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| format!("Component {id}"))
+        });
+        let sites = incident.sites.iter().map(|s| {
+            site_names
+                .get(&s.id.to_raw())
+                .cloned()
+                .unwrap_or_else(|| s.to_string())
+        });
+        components.chain(sites).collect::<Vec<_>>().join(", ")
+    };
+    let assignee_of = move |incident: &Incident| {
+        incident.assignee_id.as_ref().map(|a| {
+            person_names
+                .get(&a.id.to_raw())
+                .cloned()
+                .unwrap_or_else(|| a.to_string())
+        })
+    };
+
+    let policy_names: HashMap<String, String> =
+        policies.iter().map(|p| (p.key(), p.name.clone())).collect();
+    let escalation_of = move |incident: &Incident| {
+        let policy = incident.escalation_policy_id.as_ref()?;
+        let name = policy_names
+            .get(&policy.id.to_raw())
+            .cloned()
+            .unwrap_or_else(|| policy.to_string());
+        Some(format!(
+            "Escalation: {}, {} level(s) paged",
+            name, incident.escalation_level
+        ))
+    };
+
+    let active = incidents.iter().filter(|i| i.status.is_active()).count();
+    let detail = selected.and_then(|key| incidents.iter().find(|i| i.key() == key).cloned());
+
+    let board = columns(&incidents)
+        .into_iter()
+        .map(|(status, cards)| {
+            view! {
+                <div class="incident-column">
+                    <h4>{format!("{} ({})", status, cards.len())}</h4>
+                    {cards.into_iter().map(|incident| {
+                        let href = format!("/?tab=incidents&incident={}", incident.key());
+                        view! {
+                            <a href=href class=format!("incident-card severity-{}", incident.severity)>
+                                <div class="incident-card-title">{incident.title.clone()}</div>
+                                <div class="incident-card-meta">
+                                    <span class=format!("severity-badge severity-{}", incident.severity)>
+                                        {incident.severity.to_string()}
+                                    </span>
+                                    {incident.training.then(|| view! { <span class="training-badge">"training"</span> })}
+                                    <span class="text-muted">{assignee_of(incident).unwrap_or_else(|| "unassigned".to_string())}</span>
+                                </div>
+                            </a>
+                        }
+                    }).collect_view()}
+                </div>
+            }
+        })
+        .collect_view();
+
+    let detail_view = detail.map(|incident| {
+        let key = incident.key();
+        let status_url = format!("/incidents/{}/status", key);
+        let assign_url = format!("/incidents/{}/assign", key);
+        let note_url = format!("/incidents/{}/note", key);
+        let delete_url = format!("/incidents/{}/delete", key);
+        let assignee = incident.assignee_id.as_ref().map(|a| a.id.to_raw()).unwrap_or_default();
+        let next = incident.status.next_states();
+        view! {
+            <div class="card" id="incident-detail">
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h3>{incident.title.clone()}</h3>
+                    <a href="/?tab=incidents" class="btn btn-sm btn-secondary">"Close"</a>
+                </div>
+                <div class="incident-card-meta">
+                    <span class=format!("severity-badge severity-{}", incident.severity)>{incident.severity.to_string()}</span>
+                    <span class="job-status">{incident.status.to_string()}</span>
+                    {incident.training.then(|| view! { <span class="training-badge">"training"</span> })}
+                    <span class="text-muted">{format!("Opened {}", incident.opened_at)}</span>
+                    {incident.resolved_at.clone().map(|at| view! { <span class="text-muted">{format!("Resolved {at}")}</span> })}
+                </div>
+                {incident.description.clone().map(|d| view! { <p>{d}</p> })}
+                {escalation_of(&incident).map(|e| view! { <p class="text-muted">{e}</p> })}
+                <p class="text-muted">{format!("Affects: {}", Some(affected(&incident)).filter(|a| !a.is_empty()).unwrap_or_else(|| "nothing recorded".to_string()))}</p>
+
+                {(!next.is_empty()).then(|| view! {
+                    <form action=status_url method="post" class="form-inline">
+                        <input type="text" name="note" placeholder="Note (optional)"/>
+                        {next.iter().map(|s| view! {
+                            <button type="submit" name="status" value=s.to_string() class="btn btn-sm btn-primary">
+                                {format!("Mark {}", s)}
+                            </button>
+                        }).collect_view()}
+                    </form>
+                })}
+
+                <form action=assign_url method="post" class="form-inline">
+                    <label for="incident-assignee">"Assignee"</label>
+                    <select id="incident-assignee" name="assignee_id">
+                        <option value="">"Unassigned"</option>
+                        {people.iter().map(|p| {
+                            let id = thing_key(&p.id);
+                            let chosen = id == assignee;
+                            view! { <option value=id selected=chosen>{p.name.clone()}</option> }
+                        }).collect_view()}
+                    </select>
+                    <button type="submit" class="btn btn-sm btn-secondary">"Assign"</button>
+                </form>
+
+                <h4>"Timeline"</h4>
+                <ul class="incident-timeline">
+                    {incident.timeline.iter().map(|entry| view! {
+                        <li>
+                            <span class="text-muted">{entry.at.clone()}</span>
+                            " "
+                            <strong>{entry.author.clone().unwrap_or_else(|| "system".to_string())}</strong>
+                            " "
+                            {entry.text.clone()}
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <form action=note_url method="post" class="form-inline">
+                    <input type="text" name="text" required placeholder="Add a note"/>
+                    <button type="submit" class="btn btn-sm btn-secondary">"Add"</button>
+                </form>
+                <form action=delete_url method="post" style="margin-top: 8px;">
+                    <button type="submit" class="btn btn-sm btn-danger">"Delete Incident"</button>
+                </form>
+            </div>
+        }
+    });
+
+    view! {
+        <div class="card">
+            <h2>"Incidents"</h2>
+            <p class="text-muted">{format!("{} active, {} in total", active, incidents.len())}</p>
+            <div class="incident-board" id="incident-board">{board}</div>
+        </div>
+
+        {detail_view}
+
+        <div class="card">
+            <h3>"Open an Incident"</h3>
+            <form action="/incidents/create" method="post" class="form-stack">
+                <div class="form-group">
+                    <label for="incident-title">"Title"</label>
+                    <input type="text" id="incident-title" name="title" required placeholder="e.g. Core switch unreachable"/>
+                </div>
+                <div class="form-row">
+                    <div class="form-group">
+                        <label for="incident-severity">"Severity"</label>
+                        <select id="incident-severity" name="severity">
+                            {Severity::ALL.into_iter().map(|s| view! {
+                                <option value=s.to_string() selected={s == Severity::default()}>{s.to_string()}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                    <div class="form-group">
+                        <label for="incident-component">"Component"</label>
+                        <select id="incident-component" name="component_id">
+                            <option value="">"None"</option>
+                            {components.iter().map(|c| view! {
+                                <option value=c.id.to_string()>{c.name.clone()}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                    <div class="form-group">
+                        <label for="incident-site">"Site"</label>
+                        <select id="incident-site" name="site_id">
+                            <option value="">"None"</option>
+                            {sites.iter().map(|s| view! {
+                                <option value=thing_key(&s.id)>{s.name.clone()}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                    <div class="form-group">
+                        <label for="incident-new-assignee">"Assignee"</label>
+                        <select id="incident-new-assignee" name="assignee_id">
+                            <option value="">"Whoever is on call"</option>
+                            {people.iter().map(|p| view! {
+                                <option value=thing_key(&p.id)>{p.name.clone()}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                    <div class="form-group">
+                        <label for="incident-policy">"Escalation"</label>
+                        <select id="incident-policy" name="escalation_policy_id">
+                            <option value="">"Default policy"</option>
+                            {policies.iter().map(|p| view! {
+                                <option value=p.key()>{p.name.clone()}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                </div>
+                <div class="form-group">
+                    <label for="incident-description">"Description"</label>
+                    <textarea id="incident-description" name="description" rows="2"></textarea>
+                </div>
+                <button type="submit" class="btn btn-primary">"Open Incident"</button>
+            </form>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_has_a_column_per_status() {
+        let mut mitigated = Incident::new("b", Severity::Minor, None, "t0");
+        mitigated.status = IncidentStatus::Mitigated;
+        let incidents = [Incident::new("a", Severity::Major, None, "t0"), mitigated];
+        let board = columns(&incidents);
+        assert_eq!(board.len(), IncidentStatus::ALL.len());
+        let counts: Vec<usize> = board.iter().map(|(_, cards)| cards.len()).collect();
+        assert_eq!(counts, vec![1, 0, 1, 0, 0]);
+    }
+}
@@ -0,0 +1,134 @@
+use gui_server::islands::ImportClient;
+use leptos::prelude::*;
+use nexosim_hybrid::database::jobs::{Job, JobStatus};
+use nexosim_hybrid::database::schedules::Schedule;
+
+#[component]
+pub fn JobsTab(jobs: Vec<Job>, schedules: Vec<Schedule>) -> impl IntoView {
+    let import_targets: Vec<String> = crate::import::RESOURCES
+        .iter()
+        .map(|r| r.to_string())
+        .collect();
+
+    view! {
+        <div class="card">
+            <h2>"Import Data"</h2>
+            <p class="text-muted">"Upload a CSV file, map its columns onto fields and check the rows with a dry run before importing."</p>
+            <ImportClient resources=import_targets />
+        </div>
+
+        <div class="card">
+            <h2>"Scheduled Tasks"</h2>
+            <p class="text-muted">"Times are UTC. Schedules are edited through "<code>"/api/schedules"</code>"; failed runs alert the inbox."</p>
+
+            {if schedules.is_empty() {
+                view! { <p class="text-muted">"No schedules have been set up."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" id="schedules-table">
+                        <thead>
+                            <tr>
+                                <th>"Schedule"</th>
+                                <th>"When"</th>
+                                <th>"Next run"</th>
+                                <th>"Last run"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {schedules.into_iter().map(|schedule| {
+                                let run_url = format!("/schedules/{}/run", schedule.key());
+                                let next = if schedule.enabled {
+                                    schedule.next_run_at.clone().unwrap_or_else(|| "never".to_string())
+                                } else {
+                                    "disabled".to_string()
+                                };
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <strong>{schedule.name}</strong>
+                                            <div class="text-muted"><code>{schedule.task}</code></div>
+                                        </td>
+                                        <td><code>{schedule.cron}</code></td>
+                                        <td class="text-muted">{next}</td>
+                                        <td>
+                                            {schedule.last_status.map(|status| view! {
+                                                <span class=format!("job-status job-status-{}", status)>{status.to_string()}</span>
+                                            })}
+                                            <div class="text-muted">{schedule.last_run_at}</div>
+                                        </td>
+                                        <td>
+                                            <form action=run_url method="post" style="display:inline;">
+                                                <button type="submit" class="btn btn-sm btn-secondary">"Run now"</button>
+                                            </form>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+
+        <div class="card">
+            <div style="display: flex; justify-content: space-between; align-items: center;">
+                <h2>"Background Jobs"</h2>
+                <a href="/?tab=jobs" class="btn btn-sm btn-secondary">"Refresh"</a>
+            </div>
+
+            {if jobs.is_empty() {
+                view! { <p class="text-muted">"No background jobs have been queued."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" id="jobs-table">
+                        <thead>
+                            <tr>
+                                <th>"Job"</th>
+                                <th>"Status"</th>
+                                <th>"Progress"</th>
+                                <th>"Attempts"</th>
+                                <th>"Updated"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {jobs.into_iter().map(|job| {
+                                let retry_url = format!("/jobs/{}/retry", job.key());
+                                let failed = job.status == JobStatus::Failed;
+                                let detail = job.error.clone().or(job.result.clone());
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <strong>{job.label}</strong>
+                                            <div class="text-muted"><code>{job.kind}</code></div>
+                                            {detail.map(|d| view! { <div class="job-detail">{d}</div> })}
+                                        </td>
+                                        <td>
+                                            <span class=format!("job-status job-status-{}", job.status)>
+                                                {job.status.to_string()}
+                                            </span>
+                                        </td>
+                                        <td>
+                                            <progress class="job-progress" max="100" value=job.progress></progress>
+                                            <span class="text-muted">{format!(" {}%", job.progress)}</span>
+                                        </td>
+                                        <td>{format!("{}/{}", job.attempts, job.max_attempts)}</td>
+                                        <td class="text-muted">{job.updated_at}</td>
+                                        <td>
+                                            {failed.then(|| view! {
+                                                <form action=retry_url method="post" style="display:inline;">
+                                                    <button type="submit" class="btn btn-sm btn-primary">"Retry"</button>
+                                                </form>
+                                            })}
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+    }
+}
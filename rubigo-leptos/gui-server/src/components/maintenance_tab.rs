@@ -0,0 +1,223 @@
+//! Maintenance Tab
+//!
+//! Asset lifecycle overview: upcoming maintenance tickets, warranties about
+//! to expire, lifecycle state changes and the forms that drive them.
+//! Scheduled tickets also appear on the calendar.
+
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+use nexosim_hybrid::database::asset_lifecycle::{
+    LifecycleState, LifecycleTransition, MaintenanceTicket, TicketStatus,
+};
+use nexosim_hybrid::database::geo::NetworkAsset;
+
+fn asset_key(asset: &NetworkAsset) -> String {
+    asset.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+}
+
+#[component]
+pub fn MaintenanceTab(
+    assets: Vec<NetworkAsset>,
+    /// Open tickets due within the look-ahead window (overdue included)
+    tickets: Vec<MaintenanceTicket>,
+    /// Assets with warranties ending soon
+    expiring: Vec<NetworkAsset>,
+    /// Recent lifecycle changes, newest first
+    transitions: Vec<LifecycleTransition>,
+    /// Today as YYYY-MM-DD, for flagging overdue tickets
+    today: String,
+) -> impl IntoView {
+    let names: HashMap<String, String> = assets
+        .iter()
+        .map(|a| (asset_key(a), a.name.clone()))
+        .collect();
+    let name_of = move |id: &surrealdb::sql::Thing| {
+        names
+            .get(&id.id.to_raw())
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    };
+
+    let state_counts: Vec<(LifecycleState, usize)> = LifecycleState::ALL
+        .into_iter()
+        .map(|s| (s, assets.iter().filter(|a| a.lifecycle == s).count()))
+        .collect();
+
+    // Only assets that can still move are offered in the lifecycle form
+    let movable: Vec<&NetworkAsset> = assets
+        .iter()
+        .filter(|a| !a.lifecycle.next_states().is_empty())
+        .collect();
+
+    view! {
+        <div class="card">
+            <div style="display: flex; justify-content: space-between; align-items: center;">
+                <h2>"Maintenance & Lifecycle"</h2>
+                <a href="/?tab=calendar" class="btn btn-sm btn-secondary">"Open Calendar"</a>
+            </div>
+            <div class="assets-stats">
+                {state_counts.into_iter().map(|(state, count)| view! {
+                    <span class=format!("stat-item lifecycle-badge lifecycle-{}", state)>
+                        {format!("{} {}", count, state)}
+                    </span>
+                }).collect_view()}
+            </div>
+        </div>
+
+        <div class="card">
+            <h3>"Upcoming Maintenance"</h3>
+            {if tickets.is_empty() {
+                view! { <p class="text-muted">"Nothing scheduled in the next 30 days."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" id="maintenance-table">
+                        <thead>
+                            <tr>
+                                <th>"Date"</th>
+                                <th>"Asset"</th>
+                                <th>"Work"</th>
+                                <th>"Status"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {tickets.iter().map(|ticket| {
+                                let overdue = ticket.scheduled_date.as_str() < today.as_str();
+                                let close_url = format!("/maintenance/{}/close", ticket.key());
+                                view! {
+                                    <tr>
+                                        <td class:maintenance-overdue=overdue>{ticket.scheduled_date.clone()}</td>
+                                        <td>{name_of(&ticket.asset_id)}</td>
+                                        <td>
+                                            <strong>{ticket.title.clone()}</strong>
+                                            {ticket.description.clone().map(|d| view! { <div class="job-detail">{d}</div> })}
+                                        </td>
+                                        <td>
+                                            <span class="job-status">
+                                                {if overdue { "overdue".to_string() } else { ticket.status.to_string() }}
+                                            </span>
+                                        </td>
+                                        <td>
+                                            {(ticket.status != TicketStatus::Closed).then(|| view! {
+                                                <form action=close_url method="post" style="display:inline;">
+                                                    <button type="submit" class="btn btn-sm btn-secondary">"Close"</button>
+                                                </form>
+                                            })}
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+
+        <div class="card">
+            <h3>"Schedule Maintenance"</h3>
+            <form action="/maintenance/create" method="post" class="form-stack">
+                <div class="form-row">
+                    <div class="form-group">
+                        <label for="maintenance-asset">"Asset"</label>
+                        <select id="maintenance-asset" name="asset_id" required>
+                            {assets.iter().filter(|a| a.lifecycle != LifecycleState::Retired).map(|a| view! {
+                                <option value=asset_key(a)>{a.name.clone()}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                    <div class="form-group">
+                        <label for="maintenance-date">"Date"</label>
+                        <input type="date" id="maintenance-date" name="scheduled_date" required value=today.clone()/>
+                    </div>
+                </div>
+                <div class="form-group">
+                    <label for="maintenance-title">"Work"</label>
+                    <input type="text" id="maintenance-title" name="title" required placeholder="e.g. Replace PSU"/>
+                </div>
+                <div class="form-group">
+                    <label for="maintenance-description">"Notes"</label>
+                    <textarea id="maintenance-description" name="description" rows="2"></textarea>
+                </div>
+                <button type="submit" class="btn btn-primary">"Schedule"</button>
+            </form>
+        </div>
+
+        <div class="card">
+            <h3>"Change Lifecycle State"</h3>
+            <form action="/lifecycle/transition" method="post" class="form-stack">
+                <div class="form-row">
+                    <div class="form-group">
+                        <label for="lifecycle-asset">"Asset"</label>
+                        <select id="lifecycle-asset" name="asset_id" required>
+                            {movable.iter().map(|a| view! {
+                                <option value=asset_key(a)>{format!("{} ({})", a.name, a.lifecycle)}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                    <div class="form-group">
+                        <label for="lifecycle-state">"New State"</label>
+                        <select id="lifecycle-state" name="state" required>
+                            {LifecycleState::ALL.into_iter().map(|s| view! {
+                                <option value=s.to_string()>{s.to_string()}</option>
+                            }).collect_view()}
+                        </select>
+                    </div>
+                </div>
+                <div class="form-group">
+                    <label for="lifecycle-note">"Note"</label>
+                    <input type="text" id="lifecycle-note" name="note" placeholder="Reason for the change"/>
+                </div>
+                <button type="submit" class="btn btn-primary">"Apply"</button>
+            </form>
+
+            <h3>"Recent Changes"</h3>
+            {if transitions.is_empty() {
+                view! { <p class="text-muted">"No lifecycle changes recorded."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table">
+                        <tbody>
+                            {transitions.iter().map(|t| view! {
+                                <tr>
+                                    <td class="text-muted">{t.at.clone()}</td>
+                                    <td>{name_of(&t.asset_id)}</td>
+                                    <td>{format!("{} → {}", t.from, t.to)}</td>
+                                    <td class="text-muted">{t.note.clone().unwrap_or_default()}</td>
+                                </tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+
+        <div class="card">
+            <h3>"Warranties Expiring (90 days)"</h3>
+            {if expiring.is_empty() {
+                view! { <p class="text-muted">"No warranties expire in the next 90 days."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table">
+                        <thead>
+                            <tr>
+                                <th>"Asset"</th>
+                                <th>"Model"</th>
+                                <th>"Warranty Ends"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {expiring.iter().map(|a| view! {
+                                <tr>
+                                    <td>{a.name.clone()}</td>
+                                    <td>{format!("{} {}", a.manufacturer, a.model)}</td>
+                                    <td>{a.warranty_end.clone().unwrap_or_default()}</td>
+                                </tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+    }
+}
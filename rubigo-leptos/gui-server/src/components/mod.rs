@@ -1,5 +1,6 @@
 pub mod assets_module;
 pub mod calendar_module;
+pub mod capacity_tab;
 pub mod chat_module;
 pub mod components_tab;
 pub mod connections_tab;
@@ -7,18 +8,28 @@ pub mod contracts_module;
 pub mod development_module;
 pub mod email_module;
 pub mod finance_module;
+pub mod flags_tab;
 pub mod geospatial;
+pub mod incidents_tab;
+pub mod jobs_tab;
+pub mod maintenance_tab;
 pub mod meetings_module;
 pub mod metrics_tab;
+pub mod oncall_tab;
 pub mod persona_switcher;
 pub mod personnel_module;
 pub mod presentations_module;
+pub mod rack_cabling;
+pub mod reports_tab;
 pub mod requirements_module;
 pub mod risk_module;
+pub mod run_comparison;
+pub mod sandbox_panel;
 pub mod sidebar;
 pub mod sign_in_screen;
 pub mod simulation_tab;
 pub mod sites_tab;
 pub mod tasks_module;
 pub mod user_session_widget;
+pub mod webhooks_tab;
 pub mod widgets;
@@ -0,0 +1,222 @@
+//! On-call Tab
+//!
+//! Who is on call right now, the rotations over personnel with the form
+//! that sets one up, and the escalation policies that page them. Shifts
+//! also appear on the calendar.
+
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+use nexosim_hybrid::database::geo::Person;
+use nexosim_hybrid::database::oncall::{EscalationPolicy, Rotation};
+
+use crate::oncall::OnCallNow;
+
+/// Level rows offered by the new policy form
+const POLICY_LEVELS: usize = 3;
+
+/// A policy's chain as `Primary → Managers (after 30 min)`
+fn chain(policy: &EscalationPolicy, rotation_names: &HashMap<String, String>) -> String {
+    policy
+        .levels
+        .iter()
+        .map(|level| {
+            let key = level.rotation_id.id.to_raw();
+            let name = rotation_names.get(&key).cloned().unwrap_or(key);
+            match level.after_minutes {
+                0 => name,
+                minutes => format!("{name} (after {minutes} min)"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+#[component]
+pub fn OnCallTab(
+    on_call: Vec<OnCallNow>,
+    rotations: Vec<Rotation>,
+    policies: Vec<EscalationPolicy>,
+    people: Vec<Person>,
+) -> impl IntoView {
+    let person_key = |p: &Person| p.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default();
+    let person_names: HashMap<String, String> = people
+        .iter()
+        .map(|p| (person_key(p), p.name.clone()))
+        .collect();
+    let rotation_names: HashMap<String, String> = rotations
+        .iter()
+        .map(|r| (r.key(), r.name.clone()))
+        .collect();
+
+    view! {
+        <div class="card">
+            <h2>"On-call"</h2>
+            {if on_call.is_empty() {
+                view! { <p class="text-muted">"No rotations yet."</p> }.into_any()
+            } else {
+                view! {
+                    <div class="stats-grid" id="on-call-now">
+                        {on_call.into_iter().map(|now| view! {
+                            <div class="stat-card">
+                                <div class="stat-label">{now.rotation}</div>
+                                <div class="stat-value">{now.person.unwrap_or_else(|| "Nobody".to_string())}</div>
+                                {now.until.map(|until| view! { <div class="text-muted">{format!("until {until} UTC")}</div> })}
+                            </div>
+                        }).collect_view()}
+                    </div>
+                }.into_any()
+            }}
+            <a href="/?tab=calendar" class="btn btn-sm btn-secondary">"Shifts on the calendar"</a>
+        </div>
+
+        <div class="card">
+            <h3>"Rotations"</h3>
+            {(!rotations.is_empty()).then(|| view! {
+                <table class="data-table">
+                    <thead>
+                        <tr>
+                            <th>"Name"</th>
+                            <th>"Members, in turn"</th>
+                            <th>"Shift"</th>
+                            <th>"From"</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rotations.iter().map(|rotation| {
+                            let members = rotation
+                                .members
+                                .iter()
+                                .map(|m| person_names.get(&m.id.to_raw()).cloned().unwrap_or_else(|| m.to_string()))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            let delete_url = format!("/oncall/rotations/{}/delete", rotation.key());
+                            view! {
+                                <tr>
+                                    <td>{rotation.name.clone()}</td>
+                                    <td>{members}</td>
+                                    <td>{format!("{} h", rotation.shift_hours)}</td>
+                                    <td class="text-muted">{format!("{} UTC", rotation.start)}</td>
+                                    <td>
+                                        <form action=delete_url method="post" style="display:inline;">
+                                            <button type="submit" class="btn btn-sm btn-secondary">"Delete"</button>
+                                        </form>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            })}
+            <form action="/oncall/rotations/create" method="post" class="form-stack">
+                <div class="form-row">
+                    <div class="form-group">
+                        <label for="rotation-name">"Name"</label>
+                        <input type="text" id="rotation-name" name="name" required placeholder="e.g. Network primary"/>
+                    </div>
+                    <div class="form-group">
+                        <label for="rotation-start">"First handover (UTC)"</label>
+                        <input type="datetime-local" id="rotation-start" name="start" required/>
+                    </div>
+                    <div class="form-group">
+                        <label for="rotation-hours">"Shift (hours)"</label>
+                        <input type="number" id="rotation-hours" name="shift_hours" min="1" value="168" required/>
+                    </div>
+                </div>
+                <div class="form-group">
+                    <span>"Members"</span>
+                    <div class="day-toggle-group">
+                        {people.iter().map(|p| view! {
+                            <label class="day-toggle">
+                                <input type="checkbox" name="member" value=person_key(p)/>
+                                <span>{p.name.clone()}</span>
+                            </label>
+                        }).collect_view()}
+                    </div>
+                </div>
+                <button type="submit" class="btn btn-primary">"Create Rotation"</button>
+            </form>
+        </div>
+
+        <div class="card">
+            <h3>"Escalation Policies"</h3>
+            {(!policies.is_empty()).then(|| view! {
+                <table class="data-table">
+                    <tbody>
+                        {policies.iter().map(|policy| {
+                            let delete_url = format!("/oncall/policies/{}/delete", policy.key());
+                            view! {
+                                <tr>
+                                    <td>
+                                        {policy.name.clone()}
+                                        {policy.default.then(|| view! { <span class="job-status">"default"</span> })}
+                                    </td>
+                                    <td>{chain(policy, &rotation_names)}</td>
+                                    <td>
+                                        <form action=delete_url method="post" style="display:inline;">
+                                            <button type="submit" class="btn btn-sm btn-secondary">"Delete"</button>
+                                        </form>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            })}
+            <form action="/oncall/policies/create" method="post" class="form-stack">
+                <div class="form-row">
+                    <div class="form-group">
+                        <label for="policy-name">"Name"</label>
+                        <input type="text" id="policy-name" name="name" required placeholder="e.g. Network outages"/>
+                    </div>
+                    <label class="day-toggle">
+                        <input type="checkbox" name="default" value="on"/>
+                        <span>"Default for new incidents and alerts"</span>
+                    </label>
+                </div>
+                {(0..POLICY_LEVELS).map(|level| view! {
+                    <div class="form-row">
+                        <div class="form-group">
+                            <label>{format!("Level {}", level + 1)}</label>
+                            <select name="level_rotation" required={level == 0}>
+                                <option value="">"—"</option>
+                                {rotations.iter().map(|r| view! {
+                                    <option value=r.key()>{r.name.clone()}</option>
+                                }).collect_view()}
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label>"After (minutes)"</label>
+                            <input type="number" name="level_minutes" min="0" value={(level * 15).to_string()}/>
+                        </div>
+                    </div>
+                }).collect_view()}
+                <button type="submit" class="btn btn-primary">"Create Policy"</button>
+            </form>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexosim_hybrid::database::oncall::EscalationLevel;
+    use surrealdb::sql::Thing;
+
+    #[test]
+    fn chains_read_in_paging_order() {
+        let level = |rotation: &str, after_minutes| EscalationLevel {
+            rotation_id: Thing::from(("rotation", rotation)),
+            after_minutes,
+        };
+        let policy = EscalationPolicy {
+            id: None,
+            name: "Network".into(),
+            levels: vec![level("primary", 0), level("managers", 30)],
+            default: true,
+        };
+        let names = HashMap::from([("primary".to_string(), "Primary".to_string())]);
+        assert_eq!(chain(&policy, &names), "Primary → managers (after 30 min)");
+    }
+}
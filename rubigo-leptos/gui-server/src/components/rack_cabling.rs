@@ -0,0 +1,208 @@
+//! Rack Cabling
+//!
+//! Documents the physical cabling of one rack: patch panels, device ports
+//! and the cables between them. Cables may run to ports in other racks.
+//! The projection card shows which simulation connections the cabling
+//! implies and creates the missing ones.
+
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+use nexosim_hybrid::config::ConnectionConfig;
+use nexosim_hybrid::database::cabling::{occupancy, Cable, PatchPanel, Port, PortMedia};
+use nexosim_hybrid::database::geo::Device;
+
+fn thing_string(id: &Option<surrealdb::sql::Thing>) -> String {
+    id.as_ref().map(|t| t.to_string()).unwrap_or_default()
+}
+
+#[component]
+pub fn RackCabling(
+    rack_id: String,
+    /// All devices and panels, so cables to other racks can be labelled
+    devices: Vec<Device>,
+    panels: Vec<PatchPanel>,
+    ports: Vec<Port>,
+    cables: Vec<Cable>,
+    /// Connections implied by the cabling that the simulation does not have yet
+    #[prop(default = vec![])]
+    pending: Vec<ConnectionConfig>,
+) -> impl IntoView {
+    // Owner (device or panel) display names and which rack they sit in
+    let mut owners: HashMap<String, (String, String)> = HashMap::new();
+    for d in &devices {
+        owners.insert(thing_string(&d.id), (d.name.clone(), d.rack_id.to_string()));
+    }
+    for p in &panels {
+        owners.insert(thing_string(&p.id), (p.name.clone(), p.rack_id.to_string()));
+    }
+    let in_rack = |port: &Port| {
+        owners
+            .get(&port.owner.to_string())
+            .is_some_and(|(_, rack)| *rack == rack_id)
+    };
+    let port_label = |port: &Port| {
+        let owner = owners
+            .get(&port.owner.to_string())
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| port.owner.to_string());
+        format!("{} / {}", owner, port.name)
+    };
+    let ports_by_id: HashMap<String, &Port> =
+        ports.iter().map(|p| (thing_string(&p.id), p)).collect();
+    let end_label = |end: &surrealdb::sql::Thing| {
+        ports_by_id
+            .get(&end.to_string())
+            .map(|p| port_label(p))
+            .unwrap_or_else(|| end.to_string())
+    };
+
+    let rack_panels: Vec<&PatchPanel> = panels
+        .iter()
+        .filter(|p| p.rack_id.to_string() == rack_id)
+        .collect();
+    let rack_devices: Vec<&Device> = devices
+        .iter()
+        .filter(|d| d.rack_id.to_string() == rack_id)
+        .collect();
+    let rack_ports: Vec<&Port> = ports.iter().filter(|p| in_rack(p)).collect();
+    let rack_cables: Vec<&Cable> = cables
+        .iter()
+        .filter(|c| {
+            [&c.a, &c.b].into_iter().any(|end| {
+                ports_by_id
+                    .get(&end.to_string())
+                    .is_some_and(|p| in_rack(p))
+            })
+        })
+        .collect();
+
+    // Ports with a free connection, this rack first
+    let mut free: Vec<(bool, String, String)> = ports
+        .iter()
+        .filter(|p| {
+            p.id.as_ref()
+                .is_some_and(|id| occupancy(id, &cables) < p.capacity())
+        })
+        .map(|p| (!in_rack(p), port_label(p), thing_string(&p.id)))
+        .collect();
+    free.sort();
+    let port_options = move || {
+        free.iter()
+            .map(|(elsewhere, label, id)| {
+                let label = if *elsewhere {
+                    format!("{} (other rack)", label)
+                } else {
+                    label.clone()
+                };
+                view! { <option value=id.clone()>{label}</option> }
+            })
+            .collect_view()
+    };
+
+    let rack_field = move || view! { <input type="hidden" name="rack_id" value=rack_id.clone()/> };
+    let media_select = || {
+        view! {
+            <select name="media">
+                {[PortMedia::Copper, PortMedia::Fiber].into_iter().map(|m| view! {
+                    <option value=m.to_string()>{m.to_string()}</option>
+                }).collect_view()}
+            </select>
+        }
+    };
+
+    view! {
+        <hr style="border: none; border-top: 1px solid var(--border-subtle); margin: var(--space-4) 0;"/>
+        <h3 style="margin: 0 0 var(--space-3) 0;">"Cabling"</h3>
+
+        <table class="data-table" id="cable-table">
+            <thead><tr><th>"End A"</th><th>"End B"</th><th>"Label"</th><th></th></tr></thead>
+            <tbody>
+                {rack_cables.iter().map(|c| {
+                    let delete_url = format!("/cables/{}/delete", thing_string(&c.id));
+                    view! {
+                        <tr>
+                            <td>{end_label(&c.a)}</td>
+                            <td>{end_label(&c.b)}</td>
+                            <td class="text-muted">{c.label.clone().unwrap_or_default()}</td>
+                            <td>
+                                <form action=delete_url method="post" style="display:inline;">
+                                    {rack_field()}
+                                    <button type="submit" class="btn btn-sm btn-secondary">"Unplug"</button>
+                                </form>
+                            </td>
+                        </tr>
+                    }
+                }).collect_view()}
+            </tbody>
+        </table>
+        {rack_cables.is_empty().then(|| view! { <p class="text-muted text-center" style="padding: var(--space-4);">"No cables documented."</p> })}
+
+        <form action="/cables/create" method="post" class="form-row cabling-form">
+            {rack_field()}
+            <div class="form-group"><label>"From"</label><select name="a" required>{port_options()}</select></div>
+            <div class="form-group"><label>"To"</label><select name="b" required>{port_options()}</select></div>
+            <div class="form-group"><label>"Label"</label><input type="text" name="label" placeholder="e.g. C-0142"/></div>
+            <button type="submit" class="btn btn-primary btn-sm">"Add Cable"</button>
+        </form>
+
+        <h4>"Ports"</h4>
+        <div class="cabling-ports">
+            {rack_devices.iter().map(|d| (thing_string(&d.id), d.name.clone()))
+                .chain(rack_panels.iter().map(|p| (thing_string(&p.id), format!("{} (panel U{})", p.name, p.position_u))))
+                .map(|(owner, name)| {
+                    let owned: Vec<&&Port> = rack_ports.iter().filter(|p| p.owner.to_string() == owner).collect();
+                    view! {
+                        <div class="cabling-owner">
+                            <strong>{name}</strong>
+                            <div class="cabling-port-list">
+                                {owned.into_iter().map(|p| {
+                                    let used = p.id.as_ref().map(|id| occupancy(id, &cables)).unwrap_or(0);
+                                    let class = if used >= p.capacity() { "port-chip port-full" } else if used > 0 { "port-chip port-partial" } else { "port-chip" };
+                                    view! { <span class=class title=format!("{} · {}/{} in use", p.media, used, p.capacity())>{p.name.clone()}</span> }
+                                }).collect_view()}
+                            </div>
+                        </div>
+                    }
+                }).collect_view()}
+        </div>
+
+        <form action="/ports/create" method="post" class="form-row cabling-form">
+            {rack_field()}
+            <div class="form-group">
+                <label>"Device"</label>
+                <select name="owner" required>
+                    {rack_devices.iter().map(|d| view! { <option value=thing_string(&d.id)>{d.name.clone()}</option> }).collect_view()}
+                </select>
+            </div>
+            <div class="form-group"><label>"Port"</label><input type="text" name="name" required placeholder="e.g. Gi1/0/1"/></div>
+            <div class="form-group"><label>"Media"</label>{media_select()}</div>
+            <button type="submit" class="btn btn-secondary btn-sm">"Add Port"</button>
+        </form>
+
+        <form action="/patch-panels/create" method="post" class="form-row cabling-form">
+            {rack_field()}
+            <div class="form-group"><label>"Patch Panel"</label><input type="text" name="name" required placeholder="e.g. PP-A1"/></div>
+            <div class="form-group"><label>"Position (U)"</label><input type="number" name="position_u" required min="1"/></div>
+            <div class="form-group"><label>"Ports"</label><input type="number" name="port_count" required value="24" min="1" max="96"/></div>
+            <div class="form-group"><label>"Media"</label>{media_select()}</div>
+            <button type="submit" class="btn btn-secondary btn-sm">"Add Panel"</button>
+        </form>
+
+        <div class="cabling-projection">
+            {if pending.is_empty() {
+                view! { <p class="text-muted">"Simulation connections match the documented cabling."</p> }.into_any()
+            } else {
+                view! {
+                    <p>{format!("{} connection(s) implied by cabling are missing from the simulation: ", pending.len())}
+                        {pending.iter().map(|c| format!("{} ↔ {}", c.from, c.to)).collect::<Vec<_>>().join(", ")}
+                    </p>
+                    <form action="/cabling/sync" method="post">
+                        {rack_field()}
+                        <button type="submit" class="btn btn-primary btn-sm">"Create Connections"</button>
+                    </form>
+                }.into_any()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,86 @@
+use leptos::prelude::*;
+use nexosim_hybrid::database::reports::{ReportDocument, ReportKind};
+
+/// Human-readable document size
+fn size_label(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+#[component]
+pub fn ReportsTab(reports: Vec<ReportDocument>) -> impl IntoView {
+    view! {
+        <div class="card">
+            <h2>"Generate Report"</h2>
+            <p class="text-muted">"Reports capture the current data as an HTML page or a PDF and are kept below for download."</p>
+            <form action="/reports/generate" method="post" style="display: flex; gap: 12px; align-items: flex-end;">
+                <div class="form-group">
+                    <label for="report-kind">"Report"</label>
+                    <select id="report-kind" name="kind">
+                        {ReportKind::ALL.into_iter().map(|kind| view! {
+                            <option value=kind.to_string()>{kind.title()}</option>
+                        }).collect_view()}
+                    </select>
+                </div>
+                <div class="form-group">
+                    <label for="report-format">"Format"</label>
+                    <select id="report-format" name="format">
+                        <option value="pdf" selected>"PDF"</option>
+                        <option value="html">"HTML"</option>
+                    </select>
+                </div>
+                <button type="submit" class="btn btn-primary">"Generate"</button>
+            </form>
+        </div>
+
+        <div class="card">
+            <h2>"Documents"</h2>
+            {if reports.is_empty() {
+                view! { <p class="text-muted">"No reports have been generated yet."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" id="reports-table">
+                        <thead>
+                            <tr>
+                                <th>"Report"</th>
+                                <th>"Format"</th>
+                                <th>"Size"</th>
+                                <th>"Generated"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {reports.into_iter().map(|report| {
+                                let open_url = format!("/api/reports/documents/{}", report.id);
+                                let email_url = format!("/reports/{}/email", report.id);
+                                let delete_url = format!("/reports/{}/delete", report.id);
+                                view! {
+                                    <tr>
+                                        <td><strong>{report.title}</strong></td>
+                                        <td>{report.format.extension().to_uppercase()}</td>
+                                        <td class="text-muted">{size_label(report.size_bytes)}</td>
+                                        <td class="text-muted">{report.generated_at}</td>
+                                        <td>
+                                            <a href=open_url target="_blank" class="btn btn-sm btn-secondary">"Open"</a>
+                                            <form action=email_url method="post" style="display:inline;">
+                                                <button type="submit" class="btn btn-sm btn-secondary" title="Email it to yourself">"Email"</button>
+                                            </form>
+                                            <form action=delete_url method="post" style="display:inline;">
+                                                <button type="submit" class="btn btn-sm btn-danger">"Delete"</button>
+                                            </form>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+    }
+}
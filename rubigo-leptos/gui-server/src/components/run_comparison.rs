@@ -0,0 +1,133 @@
+//! Run Comparison
+//!
+//! Two or more simulation runs side by side: for each trace, one chart per
+//! run on a shared scale, then a table of every node metric with each later
+//! run's change from the first. Rows that differ are highlighted, and nodes
+//! only some runs had show a dash where they were missing.
+
+use leptos::prelude::*;
+use operations::comparison::{Comparison, Trace};
+
+/// Chart size in pixels, matching the `<svg>` in [`TraceCharts`]
+const CHART_WIDTH: f64 = 240.0;
+const CHART_HEIGHT: f64 = 80.0;
+
+/// Whole numbers without decimals, others to two places
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn delta(value: f64) -> String {
+    if value > 0.0 {
+        format!("+{}", number(value))
+    } else {
+        number(value)
+    }
+}
+
+/// SVG polyline points for one run's series, scaled into the chart
+fn points(series: &[Option<f64>], steps: usize, (lo, hi): (f64, f64)) -> String {
+    let span = if hi > lo { hi - lo } else { 1.0 };
+    let step = CHART_WIDTH / steps.saturating_sub(1).max(1) as f64;
+    series
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| {
+            let y = CHART_HEIGHT - (v.as_ref()? - lo) / span * CHART_HEIGHT;
+            Some(format!("{:.1},{:.1}", i as f64 * step, y))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[component]
+fn TraceCharts(trace: Trace, runs: Vec<String>) -> impl IntoView {
+    let range = trace.range().unwrap_or((0.0, 1.0));
+    view! {
+        <h4>{trace.name.clone()}</h4>
+        <p class="text-muted" style="font-size: 12px;">
+            {format!("{} steps, {} to {}", trace.steps, number(range.0), number(range.1))}
+        </p>
+        <div style="display: flex; gap: 16px; flex-wrap: wrap; margin-bottom: 16px;">
+            {trace.series.iter().zip(runs).map(|(series, label)| {
+                let line = points(series, trace.steps, range);
+                view! {
+                    <figure style="margin: 0;">
+                        <svg width="240" height="80" viewBox="0 0 240 80" style="background: var(--bg-body); border-radius: 4px;">
+                            <polyline points=line fill="none" stroke="var(--color-primary)" stroke-width="2"/>
+                        </svg>
+                        <figcaption class="text-muted" style="font-size: 12px;">{label}</figcaption>
+                    </figure>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
+
+#[component]
+pub fn RunComparison(comparison: Comparison) -> impl IntoView {
+    let changed = comparison.changed().count();
+    let rows = comparison.rows.len();
+    let later = comparison.runs[1..].to_vec();
+    view! {
+        <div class="card" style="margin-bottom: 24px;">
+            <h3>"Run Comparison"</h3>
+            <p class="text-muted">
+                {format!("Baseline {} against {}", comparison.runs[0], later.join(", "))}
+            </p>
+
+            {if comparison.traces.iter().all(|t| t.steps == 0) {
+                view! { <p class="text-muted">"These runs recorded no traces."</p> }.into_any()
+            } else {
+                comparison.traces.iter().cloned().map(|trace| {
+                    view! { <TraceCharts trace=trace runs=comparison.runs.clone()/> }
+                }).collect_view().into_any()
+            }}
+
+            <h4>"Per-Node Deltas"</h4>
+            <p class="text-muted">{format!("{changed} of {rows} node metrics changed")}</p>
+            <table class="data-table">
+                <thead>
+                    <tr>
+                        <th>"Node"</th>
+                        <th>"Metric"</th>
+                        {comparison.runs.iter().map(|label| view! { <th>{label.clone()}</th> }).collect_view()}
+                        {later.iter().map(|label| view! { <th>{format!("Δ {label}")}</th> }).collect_view()}
+                    </tr>
+                </thead>
+                <tbody>
+                    {comparison.rows.iter().map(|row| {
+                        let style = if row.changed() { "font-weight: 600;" } else { "" };
+                        let cell = |v: &Option<f64>, show: fn(f64) -> String| v.map(show).unwrap_or_else(|| "—".to_string());
+                        view! {
+                            <tr style=style>
+                                <td>{row.name.clone()}" "<span class="text-muted">{format!("#{}", row.node)}</span></td>
+                                <td>{row.metric.clone()}</td>
+                                {row.values.iter().map(|v| view! { <td>{cell(v, number)}</td> }).collect_view()}
+                                {row.deltas.iter().map(|v| view! { <td>{cell(v, delta)}</td> }).collect_view()}
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chart_points_scale_and_stop_at_missing_steps() {
+        let line = points(&[Some(0.0), Some(10.0), None], 3, (0.0, 10.0));
+        assert_eq!(line, "0.0,80.0 120.0,0.0");
+        assert_eq!(points(&[Some(5.0)], 1, (5.0, 5.0)), "0.0,80.0");
+        assert_eq!(delta(2.0), "+2");
+        assert_eq!(delta(-0.126), "-0.13");
+    }
+}
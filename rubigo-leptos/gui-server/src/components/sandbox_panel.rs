@@ -0,0 +1,288 @@
+//! Sandbox Panel
+//!
+//! Lists topology sandboxes and opens one: its edits drawn over the topology
+//! it was branched from, forms to add and remove components and connections,
+//! and buttons to simulate, merge or discard it. Runs of the sandbox link to
+//! the comparison view against the latest live run.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::sandbox::SandboxDiff;
+use crate::SimulationRun;
+use leptos::prelude::*;
+use nexosim_hybrid::database::sandbox::Sandbox;
+use operations::topology::Change;
+
+/// Diagram size in pixels, matching the `<svg>` in [`TopologyDiff`]
+const DIAGRAM_SIZE: f64 = 360.0;
+const NODE_RADIUS: f64 = 14.0;
+
+fn key(sandbox: &Sandbox) -> String {
+    sandbox
+        .id
+        .as_ref()
+        .map(|t| t.id.to_raw())
+        .unwrap_or_default()
+}
+
+/// Stroke colour for an edit, or for no edit
+fn color(change: Option<Change>) -> &'static str {
+    match change {
+        Some(Change::Added) => "var(--color-success)",
+        Some(Change::Removed) => "var(--color-error)",
+        Some(Change::Changed) => "var(--color-warning)",
+        None => "var(--color-steel)",
+    }
+}
+
+/// Where node `i` of `count` sits on a circle filling the diagram
+fn position(i: usize, count: usize) -> (f64, f64) {
+    let centre = DIAGRAM_SIZE / 2.0;
+    if count == 1 {
+        return (centre, centre);
+    }
+    let radius = centre - NODE_RADIUS * 3.0;
+    let angle = std::f64::consts::TAU * i as f64 / count as f64 - std::f64::consts::FRAC_PI_2;
+    (centre + radius * angle.cos(), centre + radius * angle.sin())
+}
+
+/// The branched-from and edited topologies drawn as one graph, coloured by
+/// what the sandbox did to each node and link
+#[component]
+fn TopologyDiff(sandbox: Sandbox, diff: SandboxDiff) -> impl IntoView {
+    let mut names: BTreeMap<u32, String> = BTreeMap::new();
+    for c in sandbox
+        .base
+        .components
+        .iter()
+        .chain(&sandbox.topology.components)
+    {
+        names.insert(c.id, c.name.clone());
+    }
+    let node_changes: BTreeMap<u32, Change> =
+        diff.components.iter().map(|c| (c.id, c.change)).collect();
+    let link_changes: BTreeMap<(u32, u32), Change> = diff
+        .connections
+        .iter()
+        .map(|c| ((c.from, c.to), c.change))
+        .collect();
+    let links: BTreeSet<(u32, u32)> = sandbox
+        .base
+        .connections
+        .iter()
+        .chain(&sandbox.topology.connections)
+        .map(|c| (c.from, c.to))
+        .collect();
+    let at: BTreeMap<u32, (f64, f64)> = names
+        .keys()
+        .enumerate()
+        .map(|(i, id)| (*id, position(i, names.len())))
+        .collect();
+
+    view! {
+        <svg width="360" height="360" viewBox="0 0 360 360" style="background: var(--bg-body); border-radius: 8px;">
+            {links.into_iter().filter_map(|(from, to)| {
+                let ((x1, y1), (x2, y2)) = (*at.get(&from)?, *at.get(&to)?);
+                let change = link_changes.get(&(from, to)).copied();
+                let dash = if change == Some(Change::Removed) { "4 4" } else { "" };
+                Some(view! {
+                    <line x1=x1.to_string() y1=y1.to_string() x2=x2.to_string() y2=y2.to_string()
+                        stroke=color(change) stroke-width="2" stroke-dasharray=dash/>
+                })
+            }).collect_view()}
+            {names.into_iter().map(|(id, name)| {
+                let (x, y) = at[&id];
+                let change = node_changes.get(&id).copied();
+                let dash = if change == Some(Change::Removed) { "4 3" } else { "" };
+                view! {
+                    <g>
+                        <title>{format!("{} #{}", name, id)}</title>
+                        <circle cx=x.to_string() cy=y.to_string() r=NODE_RADIUS.to_string()
+                            fill="var(--bg-card)" stroke=color(change) stroke-width="3" stroke-dasharray=dash/>
+                        <text x=x.to_string() y=(y + 4.0).to_string() text-anchor="middle" font-size="11" fill="var(--text-primary)">
+                            {id.to_string()}
+                        </text>
+                    </g>
+                }
+            }).collect_view()}
+        </svg>
+        <div class="text-muted" style="font-size: 12px; display: flex; gap: 12px; margin-top: 4px;">
+            <span style="color: var(--color-success);">"● added"</span>
+            <span style="color: var(--color-error);">"● removed"</span>
+            <span style="color: var(--color-warning);">"● changed"</span>
+            <span style="color: var(--color-steel);">"● unchanged"</span>
+        </div>
+    }
+}
+
+#[component]
+fn OpenSandbox(sandbox: Sandbox, diff: SandboxDiff, runs: Vec<SimulationRun>) -> impl IntoView {
+    let id = key(&sandbox);
+    let action = |path: &str| format!("/sandboxes/{}/{}", id, path);
+    let run_key = |r: &SimulationRun| r.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default();
+    let latest_live = runs.iter().find(|r| r.sandbox.is_none()).map(run_key);
+    let sandbox_runs: Vec<&SimulationRun> = runs
+        .iter()
+        .filter(|r| r.sandbox.as_deref() == Some(sandbox.name.as_str()))
+        .collect();
+    let components = sandbox.topology.components.clone();
+    let option = |c: &nexosim_hybrid::config::ComponentConfig| {
+        view! { <option value=c.id.to_string()>{format!("{} ({})", c.name, c.id)}</option> }
+    };
+
+    view! {
+        <div class="card" style="margin-top: 16px;">
+            <div style="display: flex; justify-content: space-between; align-items: center;">
+                <h4>{sandbox.name.clone()}</h4>
+                <div style="display: flex; gap: 8px;">
+                    <form action=action("run") method="post">
+                        <button type="submit" class="btn btn-primary btn-sm">"Simulate"</button>
+                    </form>
+                    <form action=action("merge") method="post" style="display: flex; gap: 6px; align-items: center;">
+                        {(!diff.conflicts.is_empty()).then(|| view! {
+                            <label style="font-size: 12px;"><input type="checkbox" name="force" value="1"/>" Overwrite live"</label>
+                        })}
+                        <button type="submit" class="btn btn-secondary btn-sm" disabled=diff.is_empty()>"Merge"</button>
+                    </form>
+                    <form action=action("discard") method="post">
+                        <button type="submit" class="btn btn-danger btn-sm">"Discard"</button>
+                    </form>
+                </div>
+            </div>
+            <p class="text-muted">
+                {format!("Branched {}: {} component and {} connection edits", sandbox.created_at, diff.components.len(), diff.connections.len())}
+            </p>
+            {(!diff.conflicts.is_empty()).then(|| view! {
+                <p style="color: var(--color-error);">
+                    {format!("Changed on the live topology since branching: {}", diff.conflicts.join(", "))}
+                </p>
+            })}
+
+            <div style="display: flex; gap: 24px; flex-wrap: wrap; align-items: flex-start;">
+                <div>
+                    <TopologyDiff sandbox=sandbox.clone() diff=diff.clone()/>
+                </div>
+                <div style="flex: 1; min-width: 320px;">
+                    <table class="data-table">
+                        <thead><tr><th>"Component"</th><th>"Type"</th><th></th></tr></thead>
+                        <tbody>
+                            {components.iter().map(|c| {
+                                let remove = action(&format!("components/{}/delete", c.id));
+                                view! {
+                                    <tr>
+                                        <td>{c.name.clone()}" "<span class="text-muted">{format!("#{}", c.id)}</span></td>
+                                        <td>{format!("{:?}", c.component_type)}</td>
+                                        <td>
+                                            <form action=remove method="post">
+                                                <button type="submit" class="btn btn-danger btn-sm">"Remove"</button>
+                                            </form>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                    <form action=action("components/create") method="post" class="form-row" style="margin-top: 8px;">
+                        <input type="text" name="name" placeholder="Name" required/>
+                        <input type="number" name="id" min="0" placeholder="Id (optional)"/>
+                        <select name="component_type">
+                            <option value="router">"Router"</option>
+                            <option value="switch">"Switch"</option>
+                        </select>
+                        <button type="submit" class="btn btn-secondary btn-sm">"Add component"</button>
+                    </form>
+
+                    <table class="data-table" style="margin-top: 16px;">
+                        <thead><tr><th>"Connection"</th><th></th></tr></thead>
+                        <tbody>
+                            {sandbox.topology.connections.iter().map(|c| {
+                                let remove = action(&format!("connections/{}/{}/delete", c.from, c.to));
+                                view! {
+                                    <tr>
+                                        <td>{format!("{} → {}", c.from, c.to)}</td>
+                                        <td>
+                                            <form action=remove method="post">
+                                                <button type="submit" class="btn btn-danger btn-sm">"Remove"</button>
+                                            </form>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                    <form action=action("connections/create") method="post" class="form-row" style="margin-top: 8px;">
+                        <select name="from_id">{components.iter().map(option).collect_view()}</select>
+                        <select name="to_id">{components.iter().map(option).collect_view()}</select>
+                        <button type="submit" class="btn btn-secondary btn-sm">"Connect"</button>
+                    </form>
+                </div>
+            </div>
+
+            <h4 style="margin-top: 16px;">"Sandbox Runs"</h4>
+            {if sandbox_runs.is_empty() {
+                view! { <p class="text-muted">"Not simulated yet."</p> }.into_any()
+            } else {
+                view! {
+                    <ul>
+                        {sandbox_runs.into_iter().map(|r| {
+                            let compare = latest_live.as_ref().map(|live| {
+                                let href = format!("/?tab=simulation&sandbox={}&compare={},{}", id, live, run_key(r));
+                                view! { " — " <a href=href>"Compare with latest live run"</a> }
+                            });
+                            view! { <li>{format!("{} ({})", r.started_at, r.status)}{compare}</li> }
+                        }).collect_view()}
+                    </ul>
+                }.into_any()
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn SandboxPanel(
+    sandboxes: Vec<Sandbox>,
+    /// The sandbox opened with `sandbox=`, with its edits since branching
+    #[prop(default = None)]
+    open: Option<(Sandbox, SandboxDiff)>,
+    /// All runs, to find the open sandbox's and the latest live one
+    #[prop(default = vec![])]
+    runs: Vec<SimulationRun>,
+) -> impl IntoView {
+    let open_key = open.as_ref().map(|(s, _)| key(s));
+    view! {
+        <h3>"Sandboxes"</h3>
+        <p class="text-muted">"Branch the live topology to try changes out, simulate them, then merge or discard."</p>
+        <form action="/sandboxes/create" method="post" class="form-row" style="margin-bottom: 12px;">
+            <input type="text" name="name" placeholder="Sandbox name"/>
+            <button type="submit" class="btn btn-secondary">"Branch live topology"</button>
+        </form>
+        {(!sandboxes.is_empty()).then(|| view! {
+            <div style="display: flex; gap: 8px; flex-wrap: wrap;">
+                {sandboxes.iter().map(|s| {
+                    let k = key(s);
+                    let class = if open_key.as_deref() == Some(k.as_str()) { "btn btn-primary btn-sm" } else { "btn btn-secondary btn-sm" };
+                    let href = format!("/?tab=simulation&sandbox={}", k);
+                    view! { <a href=href class=class>{format!("{} ({} components)", s.name, s.topology.components.len())}</a> }
+                }).collect_view()}
+            </div>
+        })}
+        {open.map(|(sandbox, diff)| view! { <OpenSandbox sandbox=sandbox diff=diff runs=runs/> })}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nodes_sit_on_a_circle_inside_the_diagram() {
+        assert_eq!(position(0, 1), (180.0, 180.0));
+        let (x, y) = position(0, 4);
+        assert!((x - 180.0).abs() < 1e-9 && (y - 42.0).abs() < 1e-9);
+        for i in 0..7 {
+            let (x, y) = position(i, 7);
+            assert!(x >= NODE_RADIUS && x <= DIAGRAM_SIZE - NODE_RADIUS);
+            assert!(y >= NODE_RADIUS && y <= DIAGRAM_SIZE - NODE_RADIUS);
+        }
+    }
+}
@@ -106,6 +106,34 @@ fn get_sidebar_items() -> Vec<SidebarItem> {
             href: "/?tab=assets",
             coming_soon: false,
         },
+        SidebarItem {
+            id: "maintenance",
+            label: "Maintenance",
+            icon: SidebarIcon::Emoji("🔧"),
+            href: "/?tab=maintenance",
+            coming_soon: false,
+        },
+        SidebarItem {
+            id: "incidents",
+            label: "Incidents",
+            icon: SidebarIcon::Emoji("🚨"),
+            href: "/?tab=incidents",
+            coming_soon: false,
+        },
+        SidebarItem {
+            id: "oncall",
+            label: "On-call",
+            icon: SidebarIcon::Emoji("📟"),
+            href: "/?tab=oncall",
+            coming_soon: false,
+        },
+        SidebarItem {
+            id: "capacity",
+            label: "Capacity",
+            icon: SidebarIcon::Emoji("📊"),
+            href: "/?tab=capacity",
+            coming_soon: false,
+        },
         SidebarItem {
             id: "components",
             label: "Components",
@@ -134,6 +162,34 @@ fn get_sidebar_items() -> Vec<SidebarItem> {
             href: "/?tab=metrics",
             coming_soon: false,
         },
+        SidebarItem {
+            id: "jobs",
+            label: "Jobs",
+            icon: SidebarIcon::Emoji("🗂️"),
+            href: "/?tab=jobs",
+            coming_soon: false,
+        },
+        SidebarItem {
+            id: "reports",
+            label: "Reports",
+            icon: SidebarIcon::Emoji("📑"),
+            href: "/?tab=reports",
+            coming_soon: false,
+        },
+        SidebarItem {
+            id: "flags",
+            label: "Feature Flags",
+            icon: SidebarIcon::Emoji("🚩"),
+            href: "/?tab=flags",
+            coming_soon: false,
+        },
+        SidebarItem {
+            id: "webhooks",
+            label: "Webhooks",
+            icon: SidebarIcon::Emoji("🪝"),
+            href: "/?tab=webhooks",
+            coming_soon: false,
+        },
         // New modules (stub pages)
         SidebarItem {
             id: "tasks",
@@ -1,13 +1,15 @@
 //! Sign In Screen - Displayed when no user is signed in
 //!
-//! Shows a centered sign-in card with a button to launch the persona selector.
+//! Shows a centered sign-in card with a button to sign in through the
+//! identity provider when OIDC is set up, and one to launch the persona
+//! selector where personas can still be picked.
 
 use leptos::prelude::*;
 use leptos::IntoView;
 
 /// Sign-in screen shown when no persona is selected
 #[component]
-pub fn SignInScreen() -> impl IntoView {
+pub fn SignInScreen(sso: bool, personas: bool) -> impl IntoView {
     view! {
         <div class="sign-in-screen">
             <div class="sign-in-card">
@@ -19,9 +21,16 @@ pub fn SignInScreen() -> impl IntoView {
                 </div>
                 <h1 class="sign-in-title">"Welcome to Rubigo"</h1>
                 <p class="sign-in-subtitle">"Sign in to access your dashboard"</p>
-                <button class="btn btn-primary sign-in-btn" id="sign-in-btn">
-                    "Sign In"
-                </button>
+                {sso.then(|| view! {
+                    <a href="/auth/oidc/login" class="btn btn-primary sign-in-btn" id="sso-sign-in-btn">
+                        "Sign In with SSO"
+                    </a>
+                })}
+                {personas.then(|| view! {
+                    <button class="btn btn-primary sign-in-btn" id="sign-in-btn">
+                        {if sso { "Pick a Persona" } else { "Sign In" }}
+                    </button>
+                })}
             </div>
         </div>
 
@@ -1,8 +1,44 @@
+use std::collections::HashMap;
+
+use crate::components::run_comparison::RunComparison;
+use crate::components::sandbox_panel::SandboxPanel;
+use crate::sandbox::SandboxDiff;
 use crate::SimulationRun;
+use operations::comparison::Comparison;
 use leptos::prelude::*;
+use nexosim_hybrid::config::ComponentConfig;
+use nexosim_hybrid::database::device_links::PhysicalLocation;
+use nexosim_hybrid::database::sandbox::Sandbox;
 
 #[component]
-pub fn SimulationTab(runs: Vec<SimulationRun>) -> impl IntoView {
+pub fn SimulationTab(
+    runs: Vec<SimulationRun>,
+    #[prop(default = vec![])] components: Vec<ComponentConfig>,
+    /// Physical location of components linked to a racked device
+    #[prop(default = HashMap::new())] locations: HashMap<u32, PhysicalLocation>,
+    /// Keys of the runs picked for comparison, baseline first
+    #[prop(default = vec![])] selected: Vec<String>,
+    /// The picked runs lined up, once two or more are picked
+    #[prop(default = None)] comparison: Option<Result<Comparison, String>>,
+    #[prop(default = vec![])] sandboxes: Vec<Sandbox>,
+    /// The sandbox opened with `sandbox=`, with its edits since branching
+    #[prop(default = None)] open_sandbox: Option<(Sandbox, SandboxDiff)>,
+) -> impl IntoView {
+    // Link adding a run to the comparison, or taking it out
+    let toggle = |key: &str| {
+        let mut keys = selected.clone();
+        match keys.iter().position(|k| k == key) {
+            Some(i) => {
+                keys.remove(i);
+            }
+            None => keys.push(key.to_string()),
+        }
+        if keys.is_empty() {
+            "/?tab=simulation".to_string()
+        } else {
+            format!("/?tab=simulation&compare={}", keys.join(","))
+        }
+    };
     view! {
         <div class="card">
             <h2>"Simulation"</h2>
@@ -11,6 +47,66 @@ pub fn SimulationTab(runs: Vec<SimulationRun>) -> impl IntoView {
                 <button type="submit" class="btn btn-primary">"Start Simulation"</button>
             </form>
 
+            <details style="margin-top: 12px;">
+                <summary style="cursor: pointer; color: var(--color-primary);">"Inject a fault"</summary>
+                <form action="/simulation/start" method="post" class="form-inline" style="margin-top: 8px;">
+                    <select name="fault_component" required>
+                        {components.iter().map(|c| view! {
+                            <option value=c.id.to_string()>{format!("{} (#{})", c.name, c.id)}</option>
+                        }).collect_view()}
+                    </select>
+                    <label>"fails at step "<input type="number" name="fault_step" min="1" value="5" required style="width: 70px;"/></label>
+                    <label class="day-toggle">
+                        <input type="checkbox" name="open_incident" value="on" checked/>
+                        <span>"Open a training incident"</span>
+                    </label>
+                    <button type="submit" class="btn btn-secondary">"Start with fault"</button>
+                </form>
+            </details>
+
+            <h3>"Simulated Nodes"</h3>
+            {if components.is_empty() {
+                view! { <p class="text-muted">"No components to simulate."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" style="margin-bottom: 24px;">
+                        <thead>
+                            <tr>
+                                <th>"Component"</th>
+                                <th>"Type"</th>
+                                <th>"Physical Location"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {components.iter().map(|c| {
+                                let location = locations.get(&c.id).map(|loc| {
+                                    let href = format!("/?tab=sites&device_id={}", loc.device_id);
+                                    view! { <a href=href>{format!("{} — {}", loc.device, loc.path())}</a> }.into_any()
+                                }).unwrap_or_else(|| view! { <span class="text-muted">"Not linked"</span> }.into_any());
+                                view! {
+                                    <tr>
+                                        <td><strong>{c.name.clone()}</strong>" "<span class="text-muted">{format!("#{}", c.id)}</span></td>
+                                        <td>{format!("{:?}", c.component_type)}</td>
+                                        <td>{location}</td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+
+            {match comparison {
+                Some(Ok(comparison)) => view! { <RunComparison comparison=comparison/> }.into_any(),
+                Some(Err(message)) => view! { <p style="color: var(--color-error);">{message}</p> }.into_any(),
+                None if selected.len() == 1 => view! {
+                    <p class="text-muted">"Add another run to compare it against the first."</p>
+                }.into_any(),
+                None => ().into_any(),
+            }}
+
+            <SandboxPanel sandboxes=sandboxes open=open_sandbox runs=runs.clone()/>
+
             <h3>"Previous Runs"</h3>
             {if runs.is_empty() {
                 view! { <p class="text-muted">"No simulation runs yet. Click 'Start Simulation' to create one."</p> }.into_any()
@@ -21,16 +117,37 @@ pub fn SimulationTab(runs: Vec<SimulationRun>) -> impl IntoView {
                             let id = r.id.as_ref().map(|t| t.id.to_string()).unwrap_or_default();
                             let delete_url = format!("/runs/{}/delete", id);
                             let log_count = r.logs.len();
+                            let key = r.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default();
+                            let picked = selected.iter().position(|k| *k == key);
+                            let compare_url = toggle(&key);
+                            let compare_label = match picked {
+                                Some(0) => "Baseline — remove".to_string(),
+                                Some(i) => format!("Compared #{} — remove", i + 1),
+                                None if r.nodes.is_empty() => "Compare (no metrics)".to_string(),
+                                None => "Compare".to_string(),
+                            };
                             view! {
                                 <div class="card" style="margin-bottom: 16px;">
                                     <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: 12px;">
                                         <div>
                                             <strong>{r.started_at.clone()}</strong>
                                             <span class="text-muted" style="margin-left: 12px;">{format!("Status: {}", r.status)}</span>
+                                            {r.sandbox.clone().map(|name| view! {
+                                                <span class="text-muted" style="margin-left: 12px;">{format!("Sandbox: {}", name)}</span>
+                                            })}
+                                            {r.faults.iter().map(|f| {
+                                                let name = r.nodes.iter().find(|n| n.component == f.component).map_or_else(|| format!("#{}", f.component), |n| n.name.clone());
+                                                view! {
+                                                    <span style="margin-left: 12px; color: var(--color-error);">{format!("Fault: {} at step {}", name, f.step)}</span>
+                                                }
+                                            }).collect_view()}
+                                        </div>
+                                        <div>
+                                            <a href=compare_url class="btn btn-secondary btn-sm">{compare_label}</a>
+                                            <form action=delete_url method="post" style="display:inline; margin-left: 8px;">
+                                                <button type="submit" class="btn btn-danger btn-sm">"Delete"</button>
+                                            </form>
                                         </div>
-                                        <form action=delete_url method="post" style="display:inline;">
-                                            <button type="submit" class="btn btn-danger btn-sm">"Delete"</button>
-                                        </form>
                                     </div>
                                     <details>
                                         <summary style="cursor: pointer; color: var(--color-primary);">
@@ -1,8 +1,15 @@
 use crate::components::geospatial::globe_view::GlobeView;
 use crate::components::geospatial::map_view::MapView;
+use crate::components::rack_cabling::RackCabling;
 use crate::{Region, Site};
+use gui_server::islands::{FloorplanEditor, PlanSpace};
 use leptos::prelude::*;
-use nexosim_hybrid::database::geo::{Building, Device, Floor, GeoFeature, Rack, Space};
+use nexosim_hybrid::config::{ComponentConfig, ConnectionConfig};
+use nexosim_hybrid::database::cabling::{Cable, PatchPanel, Port};
+use nexosim_hybrid::database::desks::{BookingKind, DeskBooking};
+use nexosim_hybrid::database::device_links::component_key;
+use nexosim_hybrid::database::floorplan;
+use nexosim_hybrid::database::geo::{Building, Device, Desk, Floor, GeoFeature, Person, Rack, Space};
 
 /// Geospatial view state based on URL params
 #[derive(Clone, PartialEq, Debug)]
@@ -34,12 +41,26 @@ pub fn SitesTab(
     #[prop(default = vec![])] spaces: Vec<Space>,
     #[prop(default = vec![])] racks: Vec<Rack>,
     #[prop(default = vec![])] devices: Vec<Device>,
+    #[prop(default = vec![])] desks: Vec<Desk>,
+    /// Permanent seats are shown on the space's desk list
+    #[prop(default = vec![])] desk_bookings: Vec<DeskBooking>,
+    /// People, for floorplan occupancy and desk holders
+    #[prop(default = vec![])] people: Vec<Person>,
+    /// Simulation components offered when linking a device
+    #[prop(default = vec![])] components: Vec<ComponentConfig>,
+    #[prop(default = vec![])] patch_panels: Vec<PatchPanel>,
+    #[prop(default = vec![])] ports: Vec<Port>,
+    #[prop(default = vec![])] cables: Vec<Cable>,
+    /// Cabling-implied connections missing from the simulation
+    #[prop(default = vec![])] pending_connections: Vec<ConnectionConfig>,
     geo_features: Vec<GeoFeature>,
     #[prop(default = vec![])] cached_country_paths: Vec<String>,
     #[prop(default = vec![])] cached_state_paths: Vec<String>,
     #[prop(default = vec![])] cached_globe_country_paths: Vec<String>,
     #[prop(default = vec![])] cached_globe_state_paths: Vec<String>,
     #[prop(default = GeoView::RegionList)] view: GeoView,
+    /// Offer the Bevy 3D globe (`bevy_globe` feature flag); otherwise only the SVG globe renders
+    #[prop(default = true)] bevy_globe: bool,
 ) -> impl IntoView {
     // Determine map center based on current view
     let map_center = match &view {
@@ -74,6 +95,29 @@ pub fn SitesTab(
                 acc
             });
 
+    // Great-circle arcs from each region to its sites
+    let region_locations: std::collections::HashMap<String, (f64, f64)> = regions
+        .iter()
+        .filter_map(|r| r.id.as_ref().map(|t| (t.to_string(), r.location)))
+        .collect();
+    let site_arcs: Vec<((f64, f64), (f64, f64))> = sites
+        .iter()
+        .filter_map(|s| {
+            let region = region_locations.get(&s.region_id.as_ref()?.to_string())?;
+            Some((*region, s.location))
+        })
+        .collect();
+    let globe = view! {
+        <GlobeView
+            features=geo_features.clone()
+            regions=regions.clone()
+            center=map_center
+            cached_country_paths=cached_globe_country_paths.clone()
+            cached_state_paths=cached_globe_state_paths.clone()
+            arcs=site_arcs
+        />
+    };
+
     view! {
         <div class="sites-container">
             // Map visualizations
@@ -90,22 +134,27 @@ pub fn SitesTab(
                 </div>
                 <div class="card">
                     <h3>"3D Globe"</h3>
-                    // Container for Bevy WASM viewer (will be initialized by JavaScript)
-                    // Falls back to SVG GlobeView when GPU is unavailable
-                    <div id="earth-viewer-container">
-                        // Canvas for Bevy WASM to render into (required - Bevy looks for this element)
-                        <canvas id="earth-canvas"></canvas>
-                        // SVG fallback (shown initially and when CPU mode is detected)
-                        <div class="globe-view" id="svg-globe-fallback">
-                            <GlobeView
-                                features=geo_features.clone()
-                                regions=regions.clone()
-                                center=map_center
-                                cached_country_paths=cached_globe_country_paths.clone()
-                                cached_state_paths=cached_globe_state_paths.clone()
-                            />
-                        </div>
-                    </div>
+                    {if bevy_globe {
+                        view! {
+                            // Container for Bevy WASM viewer (will be initialized by JavaScript)
+                            // Falls back to SVG GlobeView when GPU is unavailable
+                            <div id="earth-viewer-container">
+                                // Canvas for Bevy WASM to render into (required - Bevy looks for this element)
+                                <canvas id="earth-canvas"></canvas>
+                                // SVG fallback (shown initially and when CPU mode is detected)
+                                <div class="globe-view" id="svg-globe-fallback">
+                                    {globe}
+                                </div>
+                            </div>
+                        }.into_any()
+                    } else {
+                        // Without the container the viewer script never loads Bevy
+                        view! {
+                            <div class="globe-view">
+                                {globe}
+                            </div>
+                        }.into_any()
+                    }}
                 </div>
             </div>
 
@@ -119,13 +168,13 @@ pub fn SitesTab(
                 GeoView::CreateBuilding(site_id) => render_create_building(site_id.clone(), sites.clone()),
                 GeoView::BuildingDetail(id) => render_building_detail(id.clone(), buildings.clone(), sites.clone(), floors.clone()),
                 GeoView::CreateFloor(building_id) => render_create_floor(building_id.clone(), buildings.clone()),
-                GeoView::FloorDetail(id) => render_floor_detail(id.clone(), floors.clone(), buildings.clone(), spaces.clone()),
+                GeoView::FloorDetail(id) => render_floor_detail(id.clone(), floors.clone(), buildings.clone(), spaces.clone(), &people, &racks),
                 GeoView::CreateSpace(floor_id) => render_create_space(floor_id.clone(), floors.clone()),
-                GeoView::SpaceDetail(id) => render_space_detail(id.clone(), spaces.clone(), floors.clone(), racks.clone()),
+                GeoView::SpaceDetail(id) => render_space_detail(id.clone(), spaces.clone(), floors.clone(), racks.clone(), &desks, &desk_bookings, &people),
                 GeoView::CreateRack(space_id) => render_create_rack(space_id.clone(), spaces.clone()),
-                GeoView::RackDetail(id) => render_rack_detail(id.clone(), racks.clone(), spaces.clone(), devices.clone()),
+                GeoView::RackDetail(id) => render_rack_detail(id.clone(), racks.clone(), spaces.clone(), devices.clone(), components.clone(), RackCablingData { panels: patch_panels.clone(), ports: ports.clone(), cables: cables.clone(), pending: pending_connections.clone() }),
                 GeoView::CreateDevice(rack_id) => render_create_device(rack_id.clone(), racks.clone()),
-                GeoView::DeviceDetail(_) => view! { <div class="card"><p>"Device detail coming soon"</p></div> }.into_any(),
+                GeoView::DeviceDetail(id) => render_device_detail(id.clone(), devices.clone(), racks.clone(), components.clone()),
             }}
         </div>
     }
@@ -256,7 +305,7 @@ fn render_create_region() -> leptos::tachys::view::any_view::AnyView {
                 <input type="hidden" id="lat" name="lat"/><input type="hidden" id="lon" name="lon"/>
                 <div style="display: flex; gap: var(--space-3); margin-top: var(--space-4);"><button type="submit" class="btn btn-primary">"Create Region"</button><a href="/?tab=sites" class="btn btn-secondary">"Cancel"</a></div>
             </form>
-            <script>r#"(function(){const s=document.getElementById('city-search'),r=document.getElementById('city-results');let t;s.addEventListener('input',function(){clearTimeout(t);const q=this.value.trim();if(q.length<2){r.innerHTML='';return;}t=setTimeout(()=>{fetch('/api/cities/search?q='+encodeURIComponent(q)).then(x=>x.json()).then(c=>{r.innerHTML=c.slice(0,8).map(x=>`<div class="city-result" style="padding:8px 12px;cursor:pointer;border-radius:6px;background:var(--bg-hover);margin-bottom:4px;" data-name="${x.name}" data-country="${x.country}" data-lat="${x.location[1]}" data-lon="${x.location[0]}">${x.name}, ${x.country}</div>`).join('');r.querySelectorAll('.city-result').forEach(el=>{el.addEventListener('click',function(){document.getElementById('name').value=this.dataset.name;document.getElementById('city').value=this.dataset.name;document.getElementById('country').value=this.dataset.country;document.getElementById('lat').value=this.dataset.lat;document.getElementById('lon').value=this.dataset.lon;r.innerHTML='';s.value=this.dataset.name+', '+this.dataset.country;});});});},300);});})();"#</script>
+            <script>r#"(function(){const s=document.getElementById('city-search'),r=document.getElementById('city-results');let t;s.addEventListener('input',function(){clearTimeout(t);const q=this.value.trim();if(q.length<2){r.innerHTML='';return;}t=setTimeout(()=>{fetch('/api/cities/search?limit=8&q='+encodeURIComponent(q)).then(x=>x.json()).then(c=>{r.innerHTML=c.slice(0,8).map(x=>`<div class="city-result" style="padding:8px 12px;cursor:pointer;border-radius:6px;background:var(--bg-hover);margin-bottom:4px;" data-name="${x.name}" data-country="${x.country}" data-lat="${x.location[1]}" data-lon="${x.location[0]}">${x.name}, ${x.country}</div>`).join('');r.querySelectorAll('.city-result').forEach(el=>{el.addEventListener('click',function(){document.getElementById('name').value=this.dataset.name;document.getElementById('city').value=this.dataset.name;document.getElementById('country').value=this.dataset.country;document.getElementById('lat').value=this.dataset.lat;document.getElementById('lon').value=this.dataset.lon;r.innerHTML='';s.value=this.dataset.name+', '+this.dataset.country;});});});},300);});})();"#</script>
         </div>
     }.into_any()
 }
@@ -428,6 +477,8 @@ fn render_floor_detail(
     floors: Vec<Floor>,
     buildings: Vec<Building>,
     spaces: Vec<Space>,
+    people: &[Person],
+    racks: &[Rack],
 ) -> leptos::tachys::view::any_view::AnyView {
     let floor = floors
         .iter()
@@ -446,6 +497,35 @@ fn render_floor_detail(
             .find(|b| b.id.as_ref().map(|t| t.to_string()).unwrap_or_default() == building_id)
             .map(|b| b.name.clone())
             .unwrap_or_else(|| "Building".to_string());
+
+        // Heat is relative to the busiest space on this floor
+        let occupancy = floorplan::occupancy(&floor_spaces, people, racks);
+        let occupancy_of = |s: &Space| {
+            s.id.as_ref()
+                .and_then(|id| occupancy.get(&id.to_string()))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let max_load = floor_spaces
+            .iter()
+            .map(|s| occupancy_of(s).load())
+            .fold(0.0, f64::max);
+        let plan: Vec<PlanSpace> = floor_spaces
+            .iter()
+            .map(|s| {
+                let occ = occupancy_of(s);
+                PlanSpace {
+                    id: s.id.as_ref().map(|t| t.to_string()).unwrap_or_default(),
+                    name: s.name.clone(),
+                    rect: s.bounds.map(|b| [b.x, b.y, b.width, b.height]),
+                    people: occ.people,
+                    racks: occ.racks,
+                    heat: floorplan::heat(occ.load(), max_load),
+                }
+            })
+            .collect();
+        let outline_url = format!("/floors/{}/outline", floor_id);
+
         view! {
             <div class="card">
                 <nav style="margin-bottom: var(--space-4); font-size: 14px;"><a href="/?tab=sites">"Regions"</a><span class="text-muted">" / ... / "</span><a href={format!("/?tab=sites&building_id={}", building_id)}>{building_name}</a><span class="text-muted">" / "</span><span>{f.name.clone()}</span></nav>
@@ -455,8 +535,21 @@ fn render_floor_detail(
                 </div>
                 <hr style="border: none; border-top: 1px solid var(--border-subtle); margin: var(--space-4) 0;"/>
                 <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: var(--space-3);"><h3 style="margin: 0;">"Spaces"</h3><a href={format!("/?tab=sites&view=create_space&floor_id={}", floor_id)} class="btn btn-primary btn-sm">"+ Add Space"</a></div>
-                <table class="data-table"><thead><tr><th>"Name"</th><th>"Locator"</th><th>"Type"</th><th>"Actions"</th></tr></thead><tbody>{floor_spaces.iter().map(|s| { let sid = s.id.as_ref().map(|t| t.to_string()).unwrap_or_default(); view! { <tr><td><strong>{s.name.clone()}</strong></td><td>{s.locator.clone()}</td><td>{s.space_type.clone().unwrap_or_default()}</td><td><a href={format!("/?tab=sites&space_id={}", sid)} class="btn btn-sm btn-secondary">"View"</a></td></tr> } }).collect_view()}</tbody></table>
+                <table class="data-table"><thead><tr><th>"Name"</th><th>"Locator"</th><th>"Type"</th><th>"People"</th><th>"Racks"</th><th>"Actions"</th></tr></thead><tbody>{floor_spaces.iter().map(|s| { let sid = s.id.as_ref().map(|t| t.to_string()).unwrap_or_default(); let occ = occupancy_of(s); view! { <tr><td><strong>{s.name.clone()}</strong></td><td>{s.locator.clone()}</td><td>{s.space_type.clone().unwrap_or_default()}</td><td>{occ.people}</td><td>{occ.racks}</td><td><a href={format!("/?tab=sites&space_id={}", sid)} class="btn btn-sm btn-secondary">"View"</a></td></tr> } }).collect_view()}</tbody></table>
                 {if floor_spaces.is_empty() { view! { <p class="text-muted text-center" style="padding: var(--space-4);">"No spaces yet."</p> }.into_any() } else { view! { <div></div> }.into_any() }}
+                <hr style="border: none; border-top: 1px solid var(--border-subtle); margin: var(--space-4) 0;"/>
+                <h3 style="margin: 0 0 var(--space-3) 0;">"Floorplan"</h3>
+                <FloorplanEditor floor_id=floor_id.clone() outline=floorplan::floor_outline(&f) spaces=plan/>
+                <details class="floorplan-upload">
+                    <summary>"Upload outline"</summary>
+                    <form action=outline_url method="post" class="form-stack">
+                        <div class="form-group">
+                            <label for="outline">"GeoJSON polygon or x,y pairs in metres"</label>
+                            <textarea id="outline" name="outline" rows="4" required placeholder="0,0 40,0 40,25 0,25"></textarea>
+                        </div>
+                        <button type="submit" class="btn btn-secondary btn-sm">"Replace Outline"</button>
+                    </form>
+                </details>
             </div>
         }.into_any()
     } else {
@@ -493,6 +586,9 @@ fn render_space_detail(
     spaces: Vec<Space>,
     floors: Vec<Floor>,
     racks: Vec<Rack>,
+    desks: &[Desk],
+    desk_bookings: &[DeskBooking],
+    people: &[Person],
 ) -> leptos::tachys::view::any_view::AnyView {
     let space = spaces
         .iter()
@@ -503,6 +599,20 @@ fn render_space_detail(
         .filter(|r| r.space_id.to_string() == space_id)
         .cloned()
         .collect();
+    let space_desks: Vec<(String, String)> = desks
+        .iter()
+        .filter(|d| d.space_id.to_string() == space_id)
+        .map(|d| {
+            let desk_id = d.id.as_ref().map(|t| t.to_string()).unwrap_or_default();
+            let holder = desk_bookings
+                .iter()
+                .find(|b| b.kind == BookingKind::Permanent && b.desk_id.to_string() == desk_id)
+                .and_then(|b| people.iter().find(|p| p.id.as_ref() == Some(&b.person_id)))
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| "Hot desk".to_string());
+            (d.name.clone(), holder)
+        })
+        .collect();
 
     if let Some(s) = space {
         let floor_id = s.floor_id.to_string();
@@ -522,6 +632,14 @@ fn render_space_detail(
                 <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: var(--space-3);"><h3 style="margin: 0;">"Racks"</h3><a href={format!("/?tab=sites&view=create_rack&space_id={}", space_id)} class="btn btn-primary btn-sm">"+ Add Rack"</a></div>
                 <table class="data-table"><thead><tr><th>"Name"</th><th>"Height (U)"</th><th>"Actions"</th></tr></thead><tbody>{space_racks.iter().map(|r| { let rid = r.id.as_ref().map(|t| t.to_string()).unwrap_or_default(); view! { <tr><td><strong>{r.name.clone()}</strong></td><td>{r.height_u}"U"</td><td><a href={format!("/?tab=sites&rack_id={}", rid)} class="btn btn-sm btn-secondary">"View"</a></td></tr> } }).collect_view()}</tbody></table>
                 {if space_racks.is_empty() { view! { <p class="text-muted text-center" style="padding: var(--space-4);">"No racks yet."</p> }.into_any() } else { view! { <div></div> }.into_any() }}
+                <hr style="border: none; border-top: 1px solid var(--border-subtle); margin: var(--space-4) 0;"/>
+                <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: var(--space-3);"><h3 style="margin: 0;">"Desks"</h3><a href="/?tab=calendar&modal=desk" class="btn btn-secondary btn-sm">"Book a Desk"</a></div>
+                <table class="data-table"><thead><tr><th>"Desk"</th><th>"Seated"</th></tr></thead><tbody>{space_desks.into_iter().map(|(name, holder)| view! { <tr><td><strong>{name}</strong></td><td>{holder}</td></tr> }).collect_view()}</tbody></table>
+                <form action="/desks/create" method="post" class="form-row">
+                    <input type="hidden" name="space_id" value=space_id.clone()/>
+                    <div class="form-group"><label>"New Desk"</label><input type="text" name="name" required placeholder=format!("e.g. {}-D01", s.locator)/></div>
+                    <button type="submit" class="btn btn-primary btn-sm">"Add Desk"</button>
+                </form>
             </div>
         }.into_any()
     } else {
@@ -552,11 +670,21 @@ fn render_create_rack(
     }.into_any()
 }
 
+/// Cabling records handed through to the rack view
+struct RackCablingData {
+    panels: Vec<PatchPanel>,
+    ports: Vec<Port>,
+    cables: Vec<Cable>,
+    pending: Vec<ConnectionConfig>,
+}
+
 fn render_rack_detail(
     rack_id: String,
     racks: Vec<Rack>,
     spaces: Vec<Space>,
     devices: Vec<Device>,
+    components: Vec<ComponentConfig>,
+    cabling: RackCablingData,
 ) -> leptos::tachys::view::any_view::AnyView {
     let rack = racks
         .iter()
@@ -584,8 +712,16 @@ fn render_rack_detail(
                 </div>
                 <hr style="border: none; border-top: 1px solid var(--border-subtle); margin: var(--space-4) 0;"/>
                 <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: var(--space-3);"><h3 style="margin: 0;">"Devices"</h3><a href={format!("/?tab=sites&view=create_device&rack_id={}", rack_id)} class="btn btn-primary btn-sm">"+ Add Device"</a></div>
-                <table class="data-table"><thead><tr><th>"Name"</th><th>"Position (U)"</th><th>"Actions"</th></tr></thead><tbody>{rack_devices.iter().map(|d| { let did = d.id.as_ref().map(|t| t.to_string()).unwrap_or_default(); view! { <tr><td><strong>{d.name.clone()}</strong></td><td>"U"{d.position_u}</td><td><a href={format!("/?tab=sites&device_id={}", did)} class="btn btn-sm btn-secondary">"View"</a></td></tr> } }).collect_view()}</tbody></table>
+                <table class="data-table"><thead><tr><th>"Name"</th><th>"Position (U)"</th><th>"Component"</th><th>"Actions"</th></tr></thead><tbody>{rack_devices.iter().map(|d| { let did = d.id.as_ref().map(|t| t.to_string()).unwrap_or_default(); let linked = linked_component(d, &components).map(|c| c.name.clone()).unwrap_or_else(|| "—".to_string()); view! { <tr><td><strong>{d.name.clone()}</strong></td><td>{if d.height_u > 1 { format!("U{}–U{}", d.position_u, d.position_u as u16 + d.height_u as u16 - 1) } else { format!("U{}", d.position_u) }}</td><td>{linked}</td><td><a href={format!("/?tab=sites&device_id={}", did)} class="btn btn-sm btn-secondary">"View"</a></td></tr> } }).collect_view()}</tbody></table>
                 {if rack_devices.is_empty() { view! { <p class="text-muted text-center" style="padding: var(--space-4);">"No devices yet."</p> }.into_any() } else { view! { <div></div> }.into_any() }}
+                <RackCabling
+                    rack_id=rack_id.clone()
+                    devices=devices
+                    panels=cabling.panels
+                    ports=cabling.ports
+                    cables=cabling.cables
+                    pending=cabling.pending
+                />
             </div>
         }.into_any()
     } else {
@@ -610,8 +746,81 @@ fn render_create_device(
                 <input type="hidden" name="rack_id" value=rack_id.clone()/>
                 <div class="form-group"><label for="name">"Device Name"</label><input type="text" id="name" name="name" required/></div>
                 <div class="form-group"><label for="position_u">"Position (U)"</label><input type="number" id="position_u" name="position_u" required placeholder="e.g. 1"/></div>
+                <div class="form-group"><label for="height_u">"Height (U)"</label><input type="number" id="height_u" name="height_u" min="1" value="1"/></div>
+                <div class="form-group"><label for="power_w">"Power (W)"</label><input type="number" id="power_w" name="power_w" min="0" value="0"/></div>
+                <p class="text-muted">"Devices that would overlap another or go over the rack or space's capacity are shown on the Capacity page first."</p>
                 <div style="display: flex; gap: var(--space-3); margin-top: var(--space-4);"><button type="submit" class="btn btn-primary">"Create Device"</button><a href={format!("/?tab=sites&rack_id={}", rack_id)} class="btn btn-secondary">"Cancel"</a></div>
             </form>
         </div>
     }.into_any()
 }
+
+/// Simulation component a device is linked to, if it still exists
+fn linked_component<'a>(device: &Device, components: &'a [ComponentConfig]) -> Option<&'a ComponentConfig> {
+    let id = device.component_id.as_ref().and_then(component_key)?;
+    components.iter().find(|c| c.id == id)
+}
+
+fn render_device_detail(
+    device_id: String,
+    devices: Vec<Device>,
+    racks: Vec<Rack>,
+    components: Vec<ComponentConfig>,
+) -> leptos::tachys::view::any_view::AnyView {
+    let Some(device) = devices
+        .iter()
+        .find(|d| d.id.as_ref().map(|t| t.to_string()).unwrap_or_default() == device_id)
+        .cloned()
+    else {
+        return view! { <div class="card"><p class="text-muted">"Device not found."</p><a href="/?tab=sites" class="btn btn-secondary">"← Back"</a></div> }.into_any();
+    };
+
+    let rack_id = device.rack_id.to_string();
+    let rack_name = racks
+        .iter()
+        .find(|r| r.id.as_ref().map(|t| t.to_string()).unwrap_or_default() == rack_id)
+        .map(|r| r.name.clone())
+        .unwrap_or_else(|| "Rack".to_string());
+    let current = linked_component(&device, &components).map(|c| c.id);
+
+    // Components already standing in for another device, for labelling the picker
+    let taken: std::collections::HashMap<u32, String> = devices
+        .iter()
+        .filter(|d| d.id != device.id)
+        .filter_map(|d| Some((d.component_id.as_ref().and_then(component_key)?, d.name.clone())))
+        .collect();
+
+    view! {
+        <div class="card">
+            <nav style="margin-bottom: var(--space-4); font-size: 14px;"><a href="/?tab=sites">"Regions"</a><span class="text-muted">" / ... / "</span><a href={format!("/?tab=sites&rack_id={}", rack_id)}>{rack_name.clone()}</a><span class="text-muted">" / "</span><span>{device.name.clone()}</span></nav>
+            <div style="display: flex; justify-content: space-between; align-items: flex-start; margin-bottom: var(--space-5);">
+                <div><h2 style="margin: 0 0 var(--space-2) 0;">{device.name.clone()}</h2><p class="text-secondary" style="margin: 0;">{format!("{} · U{}", rack_name, device.position_u)}</p></div>
+                <a href={format!("/?tab=sites&rack_id={}", rack_id)} class="btn btn-secondary">"← Back"</a>
+            </div>
+            <hr style="border: none; border-top: 1px solid var(--border-subtle); margin: var(--space-4) 0;"/>
+            <h3 style="margin: 0 0 var(--space-3) 0;">"Simulation Component"</h3>
+            <p class="text-secondary">
+                {match linked_component(&device, &components) {
+                    Some(c) => format!("Linked to {} (#{}, {:?})", c.name, c.id, c.component_type),
+                    None => "Not linked to a simulation component.".to_string(),
+                }}
+            </p>
+            <form action=format!("/devices/{}/link", device_id) method="post" class="form-stack">
+                <div class="form-group">
+                    <label for="component_id">"Component"</label>
+                    <select id="component_id" name="component_id">
+                        <option value="" selected=current.is_none()>"— Not linked —"</option>
+                        {components.iter().map(|c| {
+                            let label = match taken.get(&c.id) {
+                                Some(other) => format!("{} (#{}) — replaces link on {}", c.name, c.id, other),
+                                None => format!("{} (#{})", c.name, c.id),
+                            };
+                            view! { <option value=c.id.to_string() selected=current == Some(c.id)>{label}</option> }
+                        }).collect_view()}
+                    </select>
+                </div>
+                <div style="display: flex; gap: var(--space-3); margin-top: var(--space-4);"><button type="submit" class="btn btn-primary">"Save Link"</button></div>
+            </form>
+        </div>
+    }.into_any()
+}
@@ -0,0 +1,162 @@
+use leptos::prelude::*;
+use nexosim_hybrid::database::webhooks::{DeliveryStatus, Webhook, WebhookDelivery};
+
+#[component]
+pub fn WebhooksTab(webhooks: Vec<Webhook>, deliveries: Vec<WebhookDelivery>) -> impl IntoView {
+    let known_events = operations::webhooks::known_events().join(", ");
+    let names: Vec<(String, String)> = webhooks.iter().map(|w| (w.key(), w.name.clone())).collect();
+    let webhook_name = move |key: &str| {
+        names
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| key.to_string())
+    };
+
+    view! {
+        <div class="card">
+            <h2>"Register a Webhook"</h2>
+            <p class="text-muted">
+                "Matching events are POSTed as JSON, signed in the "<code>"X-Rubigo-Signature"</code>
+                " header with a timestamp and an HMAC-SHA256 of it and the body; reject deliveries signed "
+                "more than 5 minutes ago. Failed deliveries are retried with growing delays."
+            </p>
+            <form action="/webhooks/create" method="post" class="form-stack">
+                <div class="form-row">
+                    <div class="form-group">
+                        <label for="webhook-name">"Name"</label>
+                        <input type="text" id="webhook-name" name="name" required placeholder="e.g. Ops chat"/>
+                    </div>
+                    <div class="form-group">
+                        <label for="webhook-url">"URL"</label>
+                        <input type="url" id="webhook-url" name="url" required placeholder="https://"/>
+                    </div>
+                </div>
+                <div class="form-group">
+                    <label for="webhook-events">"Events"</label>
+                    <input type="text" id="webhook-events" name="events" required placeholder="asset.*, simulation.completed"/>
+                    <p class="text-muted">{known_events} ", " <code>"kind.*"</code> " or " <code>"*"</code></p>
+                </div>
+                <div class="form-group">
+                    <label for="webhook-secret">"Secret"</label>
+                    <input type="text" id="webhook-secret" name="secret" placeholder="Generated when left blank"/>
+                </div>
+                <button type="submit" class="btn btn-primary">"Register"</button>
+            </form>
+        </div>
+
+        <div class="card">
+            <h2>"Webhooks"</h2>
+            {if webhooks.is_empty() {
+                view! { <p class="text-muted">"No webhooks have been registered."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" id="webhooks-table">
+                        <thead>
+                            <tr>
+                                <th>"Webhook"</th>
+                                <th>"Events"</th>
+                                <th>"Secret"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {webhooks.into_iter().map(|webhook| {
+                                let key = webhook.key();
+                                let toggle_label = if webhook.enabled { "Disable" } else { "Enable" };
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <strong>{webhook.name}</strong>
+                                            {(!webhook.enabled).then(|| view! { <span class="text-muted">" (disabled)"</span> })}
+                                            <div class="text-muted"><code>{webhook.url}</code></div>
+                                        </td>
+                                        <td><code>{webhook.events.join(", ")}</code></td>
+                                        <td>
+                                            <details>
+                                                <summary>"Show"</summary>
+                                                <code>{webhook.secret}</code>
+                                            </details>
+                                        </td>
+                                        <td>
+                                            <form action=format!("/webhooks/{}/test", key) method="post" style="display:inline;">
+                                                <button type="submit" class="btn btn-sm btn-secondary">"Send test"</button>
+                                            </form>
+                                            <form action=format!("/webhooks/{}/toggle", key) method="post" style="display:inline;">
+                                                <button type="submit" class="btn btn-sm btn-secondary">{toggle_label}</button>
+                                            </form>
+                                            <form action=format!("/webhooks/{}/delete", key) method="post" style="display:inline;">
+                                                <button type="submit" class="btn btn-sm btn-danger">"Delete"</button>
+                                            </form>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+
+        <div class="card">
+            <div style="display: flex; justify-content: space-between; align-items: center;">
+                <h2>"Delivery Log"</h2>
+                <a href="/?tab=webhooks" class="btn btn-sm btn-secondary">"Refresh"</a>
+            </div>
+
+            {if deliveries.is_empty() {
+                view! { <p class="text-muted">"Nothing has been delivered yet."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="data-table" id="webhook-deliveries-table">
+                        <thead>
+                            <tr>
+                                <th>"Event"</th>
+                                <th>"Webhook"</th>
+                                <th>"Status"</th>
+                                <th>"Attempts"</th>
+                                <th>"Updated"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {deliveries.into_iter().map(|delivery| {
+                                let redeliver_url = format!("/webhooks/deliveries/{}/redeliver", delivery.key());
+                                let finished = delivery.status != DeliveryStatus::Pending;
+                                let response = delivery.response_code.map(|code| format!("HTTP {}", code));
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <code>{delivery.event}</code>
+                                            <details>
+                                                <summary class="text-muted">"Payload"</summary>
+                                                <pre class="job-detail">{delivery.payload}</pre>
+                                            </details>
+                                        </td>
+                                        <td>{webhook_name(&delivery.webhook)}</td>
+                                        <td>
+                                            <span class=format!("job-status webhook-status-{}", delivery.status)>
+                                                {delivery.status.to_string()}
+                                            </span>
+                                            {response.map(|r| view! { <div class="text-muted">{r}</div> })}
+                                            {delivery.error.map(|e| view! { <div class="job-detail">{e}</div> })}
+                                        </td>
+                                        <td>{delivery.attempts}</td>
+                                        <td class="text-muted">{delivery.updated_at}</td>
+                                        <td>
+                                            {finished.then(|| view! {
+                                                <form action=redeliver_url method="post" style="display:inline;">
+                                                    <button type="submit" class="btn btn-sm btn-primary">"Redeliver"</button>
+                                                </form>
+                                            })}
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,90 @@
+//! Energy Widget Component
+//!
+//! Energy drawn in the latest simulation of the live topology, with the
+//! change from the run before it and the sites that drew the most.
+
+use leptos::prelude::*;
+use leptos::IntoView;
+use nexosim_hybrid::database::simulation::SimulationRun;
+
+use super::stats_widget::StatsWidget;
+
+/// Sites listed under the totals
+const TOP_SITES: usize = 3;
+
+/// Percent change from `before` to `after` as a trend and its label
+fn trend(before: f64, after: f64) -> Option<(&'static str, String)> {
+    if before <= 0.0 {
+        return None;
+    }
+    let change = (after - before) / before * 100.0;
+    let direction = match change {
+        c if c > 0.5 => "up",
+        c if c < -0.5 => "down",
+        _ => "neutral",
+    };
+    Some((direction, format!("{:.0}%", change.abs())))
+}
+
+/// Energy widget
+///
+/// # Props
+/// - `runs`: Simulation runs, in any order; sandbox runs are ignored
+#[component]
+pub fn EnergyWidget(runs: Vec<SimulationRun>) -> impl IntoView {
+    let mut live: Vec<SimulationRun> = runs
+        .into_iter()
+        .filter(|r| r.sandbox.is_none() && !r.sites.is_empty())
+        .collect();
+    live.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    let Some(latest) = live.first().cloned() else {
+        return view! {
+            <div class="energy-widget">
+                <p class="text-muted">"No energy figures yet."</p>
+                <a href="/?tab=simulation">"Run a simulation"</a>
+            </div>
+        }
+        .into_any();
+    };
+    let energy = latest.energy_wh();
+    let average: f64 = latest.sites.iter().map(|s| s.average_w).sum();
+    let (direction, change) = live
+        .get(1)
+        .and_then(|previous| trend(previous.energy_wh(), energy))
+        .unzip();
+    let mut sites = latest.sites.clone();
+    sites.sort_by(|a, b| b.energy_wh.total_cmp(&a.energy_wh));
+    sites.truncate(TOP_SITES);
+
+    view! {
+        <div class="energy-widget">
+            <div class="stats-grid">
+                <StatsWidget label="Energy (Wh)" value=format!("{energy:.1}") trend=direction trend_value=change />
+                <StatsWidget label="Average (W)" value=format!("{average:.0}") />
+            </div>
+            <ul class="energy-sites">
+                {sites.into_iter().map(|s| view! {
+                    <li>
+                        <span>{s.site}</span>
+                        <span class="text-muted">{format!("{:.1} Wh", s.energy_wh)}</span>
+                    </li>
+                }).collect_view()}
+            </ul>
+            <p class="text-muted">{format!("Latest run {}", latest.started_at)}</p>
+        </div>
+    }
+    .into_any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trend_is_the_change_from_the_previous_run() {
+        assert_eq!(trend(100.0, 125.0), Some(("up", "25%".to_string())));
+        assert_eq!(trend(100.0, 80.0), Some(("down", "20%".to_string())));
+        assert_eq!(trend(100.0, 100.2), Some(("neutral", "0%".to_string())));
+        assert_eq!(trend(0.0, 10.0), None);
+    }
+}
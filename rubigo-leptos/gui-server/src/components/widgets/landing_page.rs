@@ -7,8 +7,10 @@ use leptos::prelude::*;
 use leptos::IntoView;
 use nexosim_hybrid::config::RoleType;
 use nexosim_hybrid::database::geo::Person;
+use nexosim_hybrid::database::simulation::SimulationRun;
 
 use super::activity_widget::{ActivityItem, ActivityWidget};
+use super::energy_widget::EnergyWidget;
 use super::quick_actions::{QuickAction, QuickActionsWidget};
 use super::stats_widget::StatsWidget;
 use super::widget_card::{WidgetCard, WidgetSize};
@@ -21,6 +23,7 @@ use super::widget_card::{WidgetCard, WidgetSize};
 /// - `region_count`: Number of regions
 /// - `site_count`: Number of sites
 /// - `building_count`: Number of buildings
+/// - `runs`: Simulation runs, for the energy widget
 #[component]
 pub fn LandingPage(
     current_persona: Option<Person>,
@@ -28,6 +31,7 @@ pub fn LandingPage(
     region_count: usize,
     site_count: usize,
     building_count: usize,
+    #[prop(default = Vec::new())] runs: Vec<SimulationRun>,
 ) -> impl IntoView {
     // Get role for layout customization
     let role = current_persona
@@ -94,6 +98,11 @@ pub fn LandingPage(
                 <WidgetCard title="Recent Activity" size=WidgetSize::Medium icon="📋">
                     <ActivityWidget items=activities />
                 </WidgetCard>
+
+                // Medium widget: Energy from the latest simulation
+                <WidgetCard title="Energy" size=WidgetSize::Medium icon="🔋">
+                    <EnergyWidget runs=runs />
+                </WidgetCard>
             </div>
         </div>
     }
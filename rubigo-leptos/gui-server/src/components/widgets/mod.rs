@@ -4,6 +4,7 @@
 //! Each widget follows Leptos component patterns with props for configuration.
 
 pub mod activity_widget;
+pub mod energy_widget;
 pub mod landing_page;
 pub mod quick_actions;
 pub mod stats_widget;
@@ -0,0 +1,577 @@
+//! Outgoing email
+//!
+//! Meeting invites, report deliveries and chat mentions are built from the
+//! templates in [`operations::email`], addressed to the person's directory
+//! email and queued in the `outbound_email` outbox, unless the person has
+//! turned that kind of email off. A worker sends the queue through the
+//! configured [`Transport`]: SMTP, or in development the console, which
+//! only logs each message. Failed sends are tried again on the next pass,
+//! up to [`MAX_ATTEMPTS`].
+//!
+//! Preferences are read and changed at `/api/email/preferences`; the
+//! outbox is listed at `/api/email/outbox` and on the Email page.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use config::{EmailSettings, EmailTransport};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment as MailAttachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use nexosim_hybrid::database::calendar::Meeting;
+use nexosim_hybrid::database::chat::{ChatRepository, MessageView};
+use nexosim_hybrid::database::email::{
+    EmailAttachment, EmailPreference, EmailRepository, EmailStatus, OutboundEmail,
+};
+use nexosim_hybrid::database::geo::{GeoRepository, Person};
+use nexosim_hybrid::database::reports::ReportRepository;
+use nexosim_hybrid::database::Database;
+use operations::email::{
+    invite_email, mention_email, mentioned, report_email, Attachment, Attendee, EmailMessage,
+    EmailPreferences, InviteDetails,
+};
+use serde::Deserialize;
+use tokio::sync::Notify;
+
+use crate::api::{found, record_key, ApiError, ApiResult};
+use crate::clock::now;
+use crate::identity::acting_as;
+use crate::notifications::PersonaQuery;
+use crate::AppState;
+
+/// `local`, an ISO 8601 time in the IANA zone `zone`, in UTC; `None` if
+/// either doesn't parse or the time is skipped by a clock change
+fn to_utc(local: &str, zone: &str) -> Option<DateTime<Utc>> {
+    let zone: Tz = zone.parse().ok()?;
+    let (local, _) = NaiveDateTime::parse_and_remainder(local.trim(), "%Y-%m-%dT%H:%M").ok()?;
+    let time = zone.from_local_datetime(&local).earliest()?;
+    Some(time.with_timezone(&Utc))
+}
+
+/// Sends made before an email is given up on
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// How often the queue is looked at when nothing new arrives, so failed
+/// sends are tried again
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Where queued emails go
+pub enum Transport {
+    /// Logged, not sent
+    Console,
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+}
+
+impl Transport {
+    /// The transport `settings` ask for; the console if SMTP can't be set up
+    pub fn from_settings(settings: &EmailSettings) -> Self {
+        match settings.transport {
+            EmailTransport::Console => Transport::Console,
+            EmailTransport::Smtp => match smtp(settings) {
+                Ok(smtp) => Transport::Smtp(smtp),
+                Err(e) => {
+                    tracing::warn!("Could not set up SMTP ({}); emails will only be logged", e);
+                    Transport::Console
+                }
+            },
+        }
+    }
+
+    async fn send(&self, from: &str, email: &OutboundEmail) -> anyhow::Result<()> {
+        match self {
+            Transport::Console => {
+                let attachments: Vec<&str> = email
+                    .attachments
+                    .iter()
+                    .map(|a| a.filename.as_str())
+                    .collect();
+                tracing::info!(
+                    "Email to {} <{}>: {}\n{}\nAttachments: {:?}",
+                    email.recipient,
+                    email.address,
+                    email.subject,
+                    email.text,
+                    attachments
+                );
+                Ok(())
+            }
+            Transport::Smtp(smtp) => {
+                smtp.send(message(from, email)?).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn smtp(settings: &EmailSettings) -> anyhow::Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let mut builder = if settings.starttls {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.smtp_host)?
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.smtp_host)
+    };
+    builder = builder.port(settings.smtp_port);
+    if let (Some(username), Some(password)) = (&settings.smtp_username, &settings.smtp_password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+    Ok(builder.build())
+}
+
+/// The MIME message for a queued email
+fn message(from: &str, email: &OutboundEmail) -> anyhow::Result<Message> {
+    let to = Mailbox::new(Some(email.recipient.clone()), email.address.parse()?);
+    let builder = Message::builder()
+        .from(from.parse()?)
+        .to(to)
+        .subject(email.subject.clone());
+    if email.attachments.is_empty() {
+        return Ok(builder
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.text.clone())?);
+    }
+    let mut parts = MultiPart::mixed().singlepart(SinglePart::plain(email.text.clone()));
+    for attachment in &email.attachments {
+        let content = base64::engine::general_purpose::STANDARD.decode(&attachment.content)?;
+        let content_type = ContentType::parse(&attachment.content_type)?;
+        parts = parts.singlepart(
+            MailAttachment::new(attachment.filename.clone()).body(content, content_type),
+        );
+    }
+    Ok(builder.multipart(parts)?)
+}
+
+#[derive(Clone)]
+pub struct EmailService {
+    db: Arc<Database>,
+    from: String,
+    transport: Arc<Transport>,
+    wake: Arc<Notify>,
+}
+
+impl EmailService {
+    pub fn new(db: Arc<Database>, settings: &EmailSettings) -> Self {
+        Self {
+            db,
+            from: settings.from.clone(),
+            transport: Arc::new(Transport::from_settings(settings)),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Send queued emails for as long as the server is up
+    pub fn start(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = service.send_queued().await {
+                    tracing::warn!("Could not read the email queue: {}", e);
+                }
+                tokio::select! {
+                    _ = service.wake.notified() => {}
+                    _ = tokio::time::sleep(RETRY_INTERVAL) => {}
+                }
+            }
+        });
+    }
+
+    async fn send_queued(&self) -> anyhow::Result<()> {
+        for mut email in EmailRepository::queued(&self.db.client).await? {
+            let key = email.key();
+            email.attempts += 1;
+            match self.transport.send(&self.from, &email).await {
+                Ok(()) => {
+                    email.status = EmailStatus::Sent;
+                    email.error = None;
+                    email.sent_at = Some(now());
+                }
+                Err(e) => {
+                    tracing::warn!("Could not send email {} to {}: {}", key, email.address, e);
+                    email.error = Some(e.to_string());
+                    if email.attempts >= MAX_ATTEMPTS {
+                        email.status = EmailStatus::Failed;
+                    }
+                }
+            }
+            EmailRepository::update(&self.db.client, &key, email).await?;
+        }
+        Ok(())
+    }
+
+    /// A persona's preferences, the defaults until they set any
+    pub async fn preferences(&self, persona: &str) -> anyhow::Result<EmailPreferences> {
+        Ok(EmailRepository::preferences(&self.db.client, persona)
+            .await?
+            .map_or_else(EmailPreferences::default, |p| EmailPreferences {
+                invites: p.invites,
+                reports: p.reports,
+                mentions: p.mentions,
+            }))
+    }
+
+    /// Queue `message` for `person`; `None` if they turned its kind off or
+    /// have no address
+    async fn queue(
+        &self,
+        person: &Person,
+        message: EmailMessage,
+    ) -> anyhow::Result<Option<OutboundEmail>> {
+        if person.email.trim().is_empty()
+            || !self.preferences(&person.name).await?.allows(message.kind)
+        {
+            return Ok(None);
+        }
+        let attachments = message
+            .attachments
+            .into_iter()
+            .map(|a| EmailAttachment {
+                filename: a.filename,
+                content_type: a.content_type,
+                content: base64::engine::general_purpose::STANDARD.encode(a.content),
+            })
+            .collect();
+        let email = OutboundEmail {
+            id: None,
+            kind: message.kind.name().to_string(),
+            recipient: person.name.clone(),
+            address: person.email.trim().to_string(),
+            subject: message.subject,
+            text: message.text,
+            attachments,
+            status: EmailStatus::Queued,
+            attempts: 0,
+            error: None,
+            created_at: now(),
+            sent_at: None,
+        };
+        let queued = EmailRepository::queue(&self.db.client, email).await?;
+        self.wake.notify_one();
+        Ok(Some(queued))
+    }
+
+    /// Email `persona` if there is such a person
+    pub async fn send_to(
+        &self,
+        persona: &str,
+        message: EmailMessage,
+    ) -> anyhow::Result<Option<OutboundEmail>> {
+        let people = GeoRepository::list_all_people(&self.db.client).await?;
+        match people.iter().find(|p| p.name == persona) {
+            Some(person) => self.queue(person, message).await,
+            None => anyhow::bail!("No person named '{}'", persona),
+        }
+    }
+
+    /// Email a meeting's participants an invite with the meeting attached,
+    /// skipping the organizer
+    ///
+    /// Failures are logged; an email is never worth failing the action
+    /// that raised it.
+    pub async fn invite(&self, meeting: &Meeting) {
+        if let Err(e) = self.try_invite(meeting).await {
+            tracing::warn!("Could not email invites for {}: {}", meeting.title, e);
+        }
+    }
+
+    async fn try_invite(&self, meeting: &Meeting) -> anyhow::Result<()> {
+        let people = GeoRepository::list_all_people(&self.db.client).await?;
+        let person = |id: &surrealdb::sql::Thing| people.iter().find(|p| p.id.as_ref() == Some(id));
+        let attendee = |p: &Person| Attendee {
+            name: p.name.clone(),
+            email: p.email.clone(),
+        };
+        let organizer = meeting.organizer_id.as_ref().and_then(person);
+        let participants: Vec<&Person> = meeting
+            .participant_ids
+            .iter()
+            .filter(|id| Some(*id) != meeting.organizer_id.as_ref())
+            .filter_map(person)
+            .collect();
+        let details = InviteDetails {
+            uid: format!(
+                "{}@rubigo",
+                meeting
+                    .id
+                    .as_ref()
+                    .map(|t| t.id.to_raw())
+                    .unwrap_or_default()
+            ),
+            title: meeting.title.clone(),
+            description: meeting.description.clone(),
+            start: meeting.start_time.clone(),
+            end: meeting.end_time.clone(),
+            all_day: meeting.all_day,
+            timezone: meeting.timezone.clone(),
+            utc_start: to_utc(&meeting.start_time, &meeting.timezone),
+            utc_end: to_utc(&meeting.end_time, &meeting.timezone),
+            location: meeting.virtual_url.clone(),
+            organizer: organizer.map(attendee),
+            attendees: participants.iter().map(|p| attendee(p)).collect(),
+        };
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let message = invite_email(&details, &stamp).map_err(anyhow::Error::msg)?;
+        for participant in participants {
+            self.queue(participant, message.clone()).await?;
+        }
+        Ok(())
+    }
+
+    /// Email the participants `message` mentions, other than its sender
+    pub async fn mentions(&self, message: &MessageView, recipients: &[String]) {
+        let others: Vec<String> = recipients
+            .iter()
+            .filter(|r| **r != message.sender)
+            .cloned()
+            .collect();
+        let mentioned = mentioned(&message.body, &others);
+        if mentioned.is_empty() {
+            return;
+        }
+        let result = async {
+            let conversation =
+                ChatRepository::get_conversation(&self.db.client, &message.conversation_id).await?;
+            let title = conversation
+                .and_then(|c| c.title)
+                .unwrap_or_else(|| "a direct message".to_string());
+            let email = mention_email(&message.sender, &title, &message.body);
+            for persona in &mentioned {
+                self.send_to(persona, email.clone()).await?;
+            }
+            anyhow::Ok(())
+        };
+        if let Err(e) = result.await {
+            tracing::warn!("Could not email mentions in message {}: {}", message.id, e);
+        }
+    }
+
+    /// Email the stored report `report` to `persona`
+    pub async fn report(
+        &self,
+        persona: &str,
+        report: &str,
+    ) -> anyhow::Result<Option<OutboundEmail>> {
+        let report = ReportRepository::get(&self.db.client, report)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Report '{}' not found", report))?;
+        let attachment = Attachment {
+            filename: report.file_name(),
+            content_type: report.format.content_type().to_string(),
+            content: report.bytes()?,
+        };
+        self.send_to(
+            persona,
+            report_email(&report.title, &report.generated_at, attachment),
+        )
+        .await
+    }
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct PreferencesInput {
+    pub persona: String,
+    pub invites: bool,
+    pub reports: bool,
+    pub mentions: bool,
+}
+
+/// Save `input` as its persona's preferences
+pub async fn save_preferences(
+    state: &AppState,
+    input: PreferencesInput,
+) -> ApiResult<EmailPreference> {
+    if input.persona.trim().is_empty() {
+        return Err(ApiError::bad_request("Preferences need a persona"));
+    }
+    let preference = EmailPreference {
+        id: None,
+        persona: input.persona,
+        invites: input.invites,
+        reports: input.reports,
+        mentions: input.mentions,
+    };
+    Ok(EmailRepository::set_preferences(&state.db.client, preference).await?)
+}
+
+/// Which emails a persona gets; all of them until they choose
+#[utoipa::path(
+    get,
+    path = "/api/email/preferences",
+    tag = "email",
+    params(PersonaQuery),
+    responses((status = 200, description = "The persona's preferences", body = EmailPreference))
+)]
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> ApiResult<Json<EmailPreference>> {
+    let persona = acting_as(&state, &jar, &query.persona).await?;
+    let prefs = state.email.preferences(&persona).await?;
+    Ok(Json(EmailPreference {
+        id: None,
+        persona,
+        invites: prefs.invites,
+        reports: prefs.reports,
+        mentions: prefs.mentions,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/email/preferences",
+    tag = "email",
+    request_body = PreferencesInput,
+    responses(
+        (status = 200, description = "Preferences saved", body = EmailPreference),
+        (status = 400, description = "No persona given", body = ApiError),
+    )
+)]
+pub async fn put_preferences(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(input): Json<PreferencesInput>,
+) -> ApiResult<Json<EmailPreference>> {
+    let persona = acting_as(&state, &jar, &input.persona).await?;
+    let input = PreferencesInput { persona, ..input };
+    Ok(Json(save_preferences(&state, input).await?))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct OutboxQuery {
+    /// Only emails to this persona
+    pub persona: Option<String>,
+}
+
+/// Queued, sent and failed emails, newest first
+#[utoipa::path(
+    get,
+    path = "/api/email/outbox",
+    tag = "email",
+    params(OutboxQuery),
+    responses((status = 200, description = "Emails with their status", body = Vec<OutboundEmail>))
+)]
+pub async fn outbox(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<OutboxQuery>,
+) -> ApiResult<Json<Vec<OutboundEmail>>> {
+    let persona = match &query.persona {
+        Some(named) => Some(acting_as(&state, &jar, named).await?),
+        None => None,
+    };
+    Ok(Json(
+        EmailRepository::outbox(&state.db.client, persona.as_deref()).await?,
+    ))
+}
+
+/// Email a generated report to a persona, if they take report emails
+#[utoipa::path(
+    post,
+    path = "/api/reports/documents/{id}/email",
+    tag = "email",
+    params(("id" = String, Path, description = "Report document id"), PersonaQuery),
+    responses(
+        (status = 202, description = "Email queued", body = OutboundEmail),
+        (status = 204, description = "The persona has report emails turned off or no address"),
+        (status = 404, description = "Report not found", body = ApiError),
+    )
+)]
+pub async fn email_report(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> ApiResult<axum::response::Response> {
+    use axum::response::IntoResponse;
+    let persona = acting_as(&state, &jar, &query.persona).await?;
+    let key = record_key(&id, "report");
+    found(
+        ReportRepository::get(&state.db.client, key).await?,
+        "Report",
+        &id,
+    )?;
+    Ok(match state.email.report(&persona, key).await? {
+        Some(email) => (StatusCode::ACCEPTED, Json(email)).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use operations::email::EmailKind;
+
+    #[test]
+    fn meeting_times_convert_to_utc() {
+        let utc = |local, zone| to_utc(local, zone).map(|t| t.to_rfc3339());
+        assert_eq!(
+            utc("2026-03-02T09:00:00", "America/New_York").as_deref(),
+            Some("2026-03-02T14:00:00+00:00")
+        );
+        // Summer time
+        assert_eq!(
+            utc("2026-07-01T09:00", "America/New_York").as_deref(),
+            Some("2026-07-01T13:00:00+00:00")
+        );
+        // 02:30 never happens when the clocks go forward
+        assert_eq!(utc("2026-03-08T02:30", "America/New_York"), None);
+        assert_eq!(utc("2026-03-02T09:00", "Mars/Olympus"), None);
+    }
+
+    fn email(attachments: Vec<EmailAttachment>) -> OutboundEmail {
+        OutboundEmail {
+            id: None,
+            kind: EmailKind::Report.name().to_string(),
+            recipient: "Ada Lovelace".to_string(),
+            address: "ada@example.com".to_string(),
+            subject: "Report: Inventory".to_string(),
+            text: "Attached.".to_string(),
+            attachments,
+            status: EmailStatus::Queued,
+            attempts: 0,
+            error: None,
+            created_at: now(),
+            sent_at: None,
+        }
+    }
+
+    #[test]
+    fn queued_emails_become_mime_messages() {
+        let plain = String::from_utf8(
+            message("Rubigo <rubigo@localhost>", &email(vec![]))
+                .unwrap()
+                .formatted(),
+        )
+        .unwrap();
+        assert!(plain.contains("Ada Lovelace"));
+        assert!(plain.contains("<ada@example.com>"));
+        assert!(plain.contains("Subject: Report: Inventory"));
+        assert!(plain.contains("Content-Type: text/plain"));
+
+        let attachment = EmailAttachment {
+            filename: "invite.ics".to_string(),
+            content_type: "text/calendar; method=REQUEST; charset=UTF-8".to_string(),
+            content: base64::engine::general_purpose::STANDARD.encode("BEGIN:VCALENDAR"),
+        };
+        let mixed = String::from_utf8(
+            message("rubigo@localhost", &email(vec![attachment]))
+                .unwrap()
+                .formatted(),
+        )
+        .unwrap();
+        assert!(mixed.contains("multipart/mixed"));
+        assert!(mixed.contains("filename=\"invite.ics\""));
+
+        assert!(message("not an address", &email(vec![])).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+//! List export
+//!
+//! Any flat list the API serves can be downloaded as CSV, JSON or XLSX,
+//! filtered by the same [`QueryOptions`] as its list endpoint. CSV and JSON
+//! are streamed row by row; XLSX is built in memory by [`crate::xlsx`].
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use axum::body::{Body, Bytes};
+use futures::stream;
+use nexosim_hybrid::database::cabling::CablingRepository;
+use nexosim_hybrid::database::calendar::CalendarRepository;
+use nexosim_hybrid::database::components::ComponentRepository;
+use nexosim_hybrid::database::connections::ConnectionRepository;
+use nexosim_hybrid::database::geo::GeoRepository;
+use nexosim_hybrid::database::jobs::JobRepository;
+use nexosim_hybrid::database::reports::ReportRepository;
+use nexosim_hybrid::database::simulation::SimulationRepository;
+use nexosim_hybrid::database::DbClient;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::query::{cell_text, QueryOptions};
+
+/// Lists that can be exported, as used in `/api/export/{resource}`
+pub const RESOURCES: [&str; 11] = [
+    "cables",
+    "components",
+    "connections",
+    "desks",
+    "events",
+    "jobs",
+    "people",
+    "regions",
+    "reports",
+    "runs",
+    "sites",
+];
+
+/// Fields left out of exports; photos are large base64 blobs
+const OMITTED_FIELDS: [&str; 1] = ["photo_data"];
+
+/// Download format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "xlsx" | "excel" => Ok(ExportFormat::Xlsx),
+            _ => anyhow::bail!("Unknown export format '{}', expected csv, json or xlsx", s),
+        }
+    }
+}
+
+/// Rows of `resource` matching `options`, as JSON objects
+///
+/// Returns `None` for a resource that cannot be exported.
+pub async fn fetch(
+    db: &DbClient,
+    resource: &str,
+    options: &QueryOptions,
+) -> anyhow::Result<Option<Vec<Value>>> {
+    fn rows<T: Serialize>(items: Vec<T>, options: &QueryOptions) -> Vec<Value> {
+        options
+            .apply(items)
+            .iter()
+            .map(|item| serde_json::to_value(item).unwrap_or(Value::Null))
+            .collect()
+    }
+
+    let rows = match resource {
+        "cables" => rows(CablingRepository::list_all_cables(db).await?, options),
+        "components" => rows(ComponentRepository::get_all(db).await?, options),
+        "connections" => rows(ConnectionRepository::get_all(db).await?, options),
+        "desks" => rows(GeoRepository::list_all_desks(db).await?, options),
+        "events" => rows(CalendarRepository::get_all(db).await?, options),
+        "jobs" => rows(JobRepository::get_all(db).await?, options),
+        "people" => rows(GeoRepository::list_all_people(db).await?, options),
+        "regions" => rows(GeoRepository::list_regions(db).await?, options),
+        "reports" => rows(ReportRepository::list(db).await?, options),
+        "runs" => rows(SimulationRepository::get_all(db).await?, options),
+        "sites" => rows(GeoRepository::list_sites(db).await?, options),
+        _ => return Ok(None),
+    };
+    Ok(Some(rows))
+}
+
+/// Column names in first-seen order, then each row's cells as text
+pub fn table(rows: &[Value]) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        if let Value::Object(fields) = row {
+            for key in fields.keys() {
+                if !OMITTED_FIELDS.contains(&key.as_str()) && !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+    let cells = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|c| row.get(c).map(cell_text).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    (columns, cells)
+}
+
+/// Response body for `rows` in `format`
+pub fn body(resource: &str, rows: Vec<Value>, format: ExportFormat) -> anyhow::Result<Body> {
+    let body = match format {
+        ExportFormat::Csv => {
+            let (columns, cells) = table(&rows);
+            let lines = std::iter::once(columns)
+                .chain(cells)
+                .map(|record| Ok::<_, Infallible>(Bytes::from(csv_line(&record))));
+            Body::from_stream(stream::iter(lines.collect::<Vec<_>>()))
+        }
+        ExportFormat::Json => {
+            let count = rows.len();
+            let chunks = rows.into_iter().enumerate().map(move |(i, mut row)| {
+                if let Value::Object(fields) = &mut row {
+                    fields.retain(|k, _| !OMITTED_FIELDS.contains(&k.as_str()));
+                }
+                let open = if i == 0 { "[\n" } else { "" };
+                let close = if i + 1 == count { "\n]\n" } else { ",\n" };
+                Ok::<_, Infallible>(Bytes::from(format!("{}{}{}", open, row, close)))
+            });
+            if count == 0 {
+                Body::from("[]\n")
+            } else {
+                Body::from_stream(stream::iter(chunks.collect::<Vec<_>>()))
+            }
+        }
+        ExportFormat::Xlsx => {
+            let (columns, cells) = table(&rows);
+            Body::from(crate::xlsx::workbook(resource, &columns, &cells)?)
+        }
+    };
+    Ok(body)
+}
+
+/// Characters that make a spreadsheet read a cell as a formula
+const FORMULA_START: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// One RFC 4180 record, quoting fields that need it
+///
+/// Fields that a spreadsheet would run as a formula get a leading `'`, so
+/// opening an export never evaluates what someone typed into a record.
+fn csv_line(fields: &[String]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|f| {
+            let f = if f.starts_with(FORMULA_START) {
+                format!("'{f}")
+            } else {
+                f.clone()
+            };
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        let line = csv_line(&["plain".into(), "a, b".into(), "say \"hi\"".into()]);
+        assert_eq!(line, "plain,\"a, b\",\"say \"\"hi\"\"\"\r\n");
+    }
+
+    #[test]
+    fn csv_never_starts_a_formula() {
+        let line = csv_line(&[
+            "=HYPERLINK(\"http://x\")".into(),
+            "+1".into(),
+            "@SUM(A1)".into(),
+            "a=b".into(),
+        ]);
+        assert_eq!(
+            line,
+            "\"'=HYPERLINK(\"\"http://x\"\")\",'+1,'@SUM(A1),a=b\r\n"
+        );
+    }
+
+    #[test]
+    fn table_unions_columns_and_omits_photos() {
+        let rows = vec![
+            json!({"name": "Ada", "photo_data": "AAAA"}),
+            json!({"name": "Grace", "title": "Admiral"}),
+        ];
+        let (columns, cells) = table(&rows);
+        assert_eq!(columns, vec!["name", "title"]);
+        assert_eq!(cells[0], vec!["Ada", ""]);
+        assert_eq!(cells[1], vec!["Grace", "Admiral"]);
+    }
+
+    #[test]
+    fn formats_parse_with_excel_alias() {
+        assert_eq!("XLSX".parse::<ExportFormat>().unwrap(), ExportFormat::Xlsx);
+        assert_eq!("excel".parse::<ExportFormat>().unwrap(), ExportFormat::Xlsx);
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,125 @@
+//! Feature flags
+//!
+//! Switches for half-migrated features, so the old and new versions can be
+//! swapped at runtime without a rebuild. Each flag is declared here with a
+//! default; `[runtime.features]` in `rubigo.toml` (or `--feature`) changes
+//! the default and may add flags of its own. Toggles made on the admin page
+//! are stored in the database and win over the default until reset.
+
+use nexosim_hybrid::database::flags::FlagRepository;
+use nexosim_hybrid::database::DbClient;
+use ui_core::hooks::FeatureFlag;
+
+/// Flags the server knows about: name, description and built-in default
+pub const FLAGS: [(&str, &str, bool); 2] = [
+    (
+        "new_calendar",
+        "Calendar built on the shared ui-core components instead of the server-rendered month grid",
+        false,
+    ),
+    (
+        "bevy_globe",
+        "3D globe rendered by the Bevy viewer where the GPU allows; off shows the SVG globe only",
+        true,
+    ),
+];
+
+/// Every flag with its default, before runtime overrides
+fn defaults(settings: &config::Settings) -> Vec<FeatureFlag> {
+    let mut flags: Vec<FeatureFlag> = FLAGS
+        .into_iter()
+        .map(|(name, description, default)| {
+            let default = settings.features.get(name).copied().unwrap_or(default);
+            FeatureFlag {
+                name: name.to_string(),
+                description: description.to_string(),
+                enabled: default,
+                default,
+            }
+        })
+        .collect();
+    for (name, &default) in &settings.features {
+        if !flags.iter().any(|f| f.name == *name) {
+            flags.push(FeatureFlag {
+                name: name.clone(),
+                description: "Defined in rubigo.toml".to_string(),
+                enabled: default,
+                default,
+            });
+        }
+    }
+    flags
+}
+
+/// Every flag in its current state
+pub async fn list(db: &DbClient, settings: &config::Settings) -> anyhow::Result<Vec<FeatureFlag>> {
+    let overrides = FlagRepository::overrides(db).await?;
+    Ok(defaults(settings)
+        .into_iter()
+        .map(|flag| FeatureFlag {
+            enabled: overrides.get(&flag.name).copied().unwrap_or(flag.default),
+            ..flag
+        })
+        .collect())
+}
+
+/// Whether flag `name` is currently on; unknown flags and lookup failures are off
+pub async fn enabled(db: &DbClient, settings: &config::Settings, name: &str) -> bool {
+    match list(db, settings).await {
+        Ok(flags) => ui_core::hooks::flags::is_enabled(&flags, name),
+        Err(e) => {
+            tracing::warn!("Could not read feature flags: {}", e);
+            false
+        }
+    }
+}
+
+/// Turn flag `name` on or off, or back to its default with `None`
+///
+/// Returns the flag's new state, or `None` if there is no such flag.
+pub async fn set(
+    db: &DbClient,
+    settings: &config::Settings,
+    name: &str,
+    enabled: Option<bool>,
+) -> anyhow::Result<Option<FeatureFlag>> {
+    let Some(flag) = defaults(settings).into_iter().find(|f| f.name == name) else {
+        return Ok(None);
+    };
+    match enabled {
+        // Storing the default would pin it against later config changes
+        Some(on) if on != flag.default => FlagRepository::set(db, name, on).await?,
+        _ => FlagRepository::clear(db, name).await?,
+    }
+    let enabled = enabled.unwrap_or(flag.default);
+    tracing::info!(
+        "Feature flag {} is now {}",
+        name,
+        if enabled { "on" } else { "off" }
+    );
+    Ok(Some(FeatureFlag { enabled, ..flag }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_overrides_defaults_and_adds_flags() {
+        let mut settings = config::Settings::default();
+        settings.features.insert("bevy_globe".to_string(), false);
+        settings.features.insert("beta_reports".to_string(), true);
+
+        let flags = defaults(&settings);
+        let state = |name: &str| {
+            flags
+                .iter()
+                .find(|f| f.name == name)
+                .map(|f| (f.enabled, f.default))
+        };
+        assert_eq!(state("new_calendar"), Some((false, false)));
+        assert_eq!(state("bevy_globe"), Some((false, false)));
+        assert_eq!(state("beta_reports"), Some((true, true)));
+        assert_eq!(flags.len(), FLAGS.len() + 1);
+    }
+}
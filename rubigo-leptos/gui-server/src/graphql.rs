@@ -0,0 +1,604 @@
+//! GraphQL API
+//!
+//! One schema over the db repositories at `/api/graphql`, so a dashboard
+//! widget or an external consumer can fetch people, sites, assets,
+//! components, connections and events with their relations in one round
+//! trip instead of stitching several list endpoints together:
+//!
+//! ```graphql
+//! {
+//!   sites(first: 5) {
+//!     nodes { name people(first: 3) { nodes { name manager { name } } } }
+//!     pageInfo { hasNextPage endCursor }
+//!   }
+//! }
+//! ```
+//!
+//! Lists are Relay-style connections (`first`/`after`) and take the same
+//! `search` as the REST list endpoints. Ids are the record keys used in the
+//! REST paths. `GET /api/graphql` serves GraphiQL for exploring the schema.
+
+use async_graphql::connection::{self, Connection, Edge};
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, OutputType, Schema, ID};
+use axum::extract::State;
+use axum::response::{Html, IntoResponse};
+use axum::Json;
+use nexosim_hybrid::config::{ComponentConfig, ConnectionConfig};
+use nexosim_hybrid::database::calendar::{CalendarRepository, Meeting};
+use nexosim_hybrid::database::components::ComponentRepository;
+use nexosim_hybrid::database::connections::ConnectionRepository;
+use nexosim_hybrid::database::geo::{Building, GeoRepository, NetworkAsset, Person, Site};
+use nexosim_hybrid::database::DbClient;
+use serde::Serialize;
+use surrealdb::sql::Thing;
+
+use crate::query::QueryOptions;
+use crate::AppState;
+
+/// Page size when a list is queried without `first`
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page a single list returns
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// Deepest query accepted, so relation cycles can't fan out without bound
+const MAX_DEPTH: usize = 12;
+
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the schema; resolvers read the database from its data
+pub fn schema(db: DbClient) -> ApiSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(db)
+        .limit_depth(MAX_DEPTH)
+        .finish()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/graphql",
+    tag = "graphql",
+    request_body(content = Object, description = "GraphQL request: `query`, optional `variables` and `operationName`"),
+    responses((status = 200, description = "GraphQL response with `data` and/or `errors`", body = Object))
+)]
+pub async fn execute(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> impl IntoResponse {
+    Json(state.graphql.execute(request).await)
+}
+
+/// GraphiQL explorer
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/api/graphql").finish())
+}
+
+fn db<'a>(ctx: &Context<'a>) -> &'a DbClient {
+    ctx.data_unchecked::<DbClient>()
+}
+
+/// Record key of `thing`, as used in REST paths
+fn key(thing: &Thing) -> ID {
+    ID(thing.id.to_raw())
+}
+
+fn same(thing: &Thing, id: &str) -> bool {
+    thing.id.to_raw() == id
+}
+
+/// Search and page `items`, with the position in the filtered list as cursor
+async fn paginate<T: Serialize + Send, N: OutputType>(
+    items: Vec<T>,
+    search: Option<String>,
+    after: Option<String>,
+    first: Option<i32>,
+    node: impl Fn(T) -> N + Send,
+) -> async_graphql::Result<Connection<usize, N>> {
+    let items = QueryOptions {
+        q: search,
+        ..Default::default()
+    }
+    .apply(items);
+
+    connection::query(
+        after,
+        None,
+        first,
+        None,
+        |after: Option<usize>, _: Option<usize>, first, _| async move {
+            let start = after.map_or(0, |cursor| cursor + 1);
+            let end = items
+                .len()
+                .min(start + first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE));
+            let mut page = Connection::new(start > 0, end < items.len());
+            page.edges.extend(
+                items
+                    .into_iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(end.saturating_sub(start))
+                    .map(|(cursor, item)| Edge::new(cursor, node(item))),
+            );
+            Ok::<_, async_graphql::Error>(page)
+        },
+    )
+    .await
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn people(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> async_graphql::Result<Connection<usize, PersonNode>> {
+        let people = GeoRepository::list_all_people(db(ctx)).await?;
+        paginate(people, search, after, first, PersonNode).await
+    }
+
+    async fn person(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Option<PersonNode>> {
+        Ok(GeoRepository::get_person_by_id(db(ctx), &id)
+            .await?
+            .map(PersonNode))
+    }
+
+    async fn sites(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> async_graphql::Result<Connection<usize, SiteNode>> {
+        let sites = GeoRepository::list_sites(db(ctx)).await?;
+        paginate(sites, search, after, first, SiteNode).await
+    }
+
+    async fn site(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Option<SiteNode>> {
+        find_site(db(ctx), &id).await
+    }
+
+    async fn assets(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> async_graphql::Result<Connection<usize, AssetNode>> {
+        let assets = GeoRepository::list_all_assets(db(ctx)).await?;
+        paginate(assets, search, after, first, AssetNode).await
+    }
+
+    async fn asset(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Option<AssetNode>> {
+        Ok(GeoRepository::get_asset_by_id(db(ctx), &id)
+            .await?
+            .map(AssetNode))
+    }
+
+    async fn components(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> async_graphql::Result<Connection<usize, ComponentNode>> {
+        let components = ComponentRepository::get_all(db(ctx)).await?;
+        paginate(components, search, after, first, ComponentNode).await
+    }
+
+    async fn component(
+        &self,
+        ctx: &Context<'_>,
+        id: u32,
+    ) -> async_graphql::Result<Option<ComponentNode>> {
+        find_component(db(ctx), id).await
+    }
+
+    async fn connections(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> async_graphql::Result<Connection<usize, ConnectionNode>> {
+        let connections = ConnectionRepository::get_all(db(ctx)).await?;
+        paginate(connections, None, after, first, ConnectionNode).await
+    }
+
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> async_graphql::Result<Connection<usize, EventNode>> {
+        let events = CalendarRepository::get_all(db(ctx)).await?;
+        paginate(events, search, after, first, EventNode).await
+    }
+
+    async fn event(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Option<EventNode>> {
+        let events = CalendarRepository::get_all(db(ctx)).await?;
+        Ok(events
+            .into_iter()
+            .find(|e| e.id.as_ref().is_some_and(|t| same(t, &id)))
+            .map(EventNode))
+    }
+}
+
+async fn find_person(
+    db: &DbClient,
+    id: Option<&Thing>,
+) -> async_graphql::Result<Option<PersonNode>> {
+    match id {
+        Some(id) => Ok(GeoRepository::get_person_by_id(db, &id.id.to_raw())
+            .await?
+            .map(PersonNode)),
+        None => Ok(None),
+    }
+}
+
+async fn find_site(db: &DbClient, id: &str) -> async_graphql::Result<Option<SiteNode>> {
+    let sites = GeoRepository::list_sites(db).await?;
+    Ok(sites
+        .into_iter()
+        .find(|s| s.id.as_ref().is_some_and(|t| same(t, id)))
+        .map(SiteNode))
+}
+
+async fn find_component(db: &DbClient, id: u32) -> async_graphql::Result<Option<ComponentNode>> {
+    let components = ComponentRepository::get_all(db).await?;
+    Ok(components
+        .into_iter()
+        .find(|c| c.id == id)
+        .map(ComponentNode))
+}
+
+pub struct PersonNode(Person);
+
+#[Object(name = "Person")]
+impl PersonNode {
+    async fn id(&self) -> Option<ID> {
+        self.0.id.as_ref().map(key)
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn email(&self) -> &str {
+        &self.0.email
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn department(&self) -> &str {
+        &self.0.department
+    }
+
+    async fn role(&self) -> String {
+        format!("{:?}", self.0.role)
+    }
+
+    async fn bio(&self) -> Option<&str> {
+        self.0.bio.as_deref()
+    }
+
+    async fn desk_phone(&self) -> Option<&str> {
+        self.0.desk_phone.as_deref()
+    }
+
+    async fn cell_phone(&self) -> Option<&str> {
+        self.0.cell_phone.as_deref()
+    }
+
+    /// Photo endpoint, when the person has one
+    async fn photo_url(&self) -> Option<String> {
+        let id = self.0.id.as_ref()?;
+        self.0
+            .photo_data
+            .as_ref()
+            .map(|_| format!("/api/people/{}/photo", id.id.to_raw()))
+    }
+
+    async fn site(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<SiteNode>> {
+        find_site(db(ctx), &self.0.site_id.id.to_raw()).await
+    }
+
+    async fn manager(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<PersonNode>> {
+        find_person(db(ctx), self.0.manager_id.as_ref()).await
+    }
+
+    /// People whose manager this person is
+    async fn reports(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PersonNode>> {
+        let Some(id) = &self.0.id else {
+            return Ok(Vec::new());
+        };
+        let people = GeoRepository::list_all_people(db(ctx)).await?;
+        Ok(people
+            .into_iter()
+            .filter(|p| p.manager_id.as_ref() == Some(id))
+            .map(PersonNode)
+            .collect())
+    }
+
+    /// Events this person organizes or attends
+    async fn events(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<EventNode>> {
+        let Some(id) = &self.0.id else {
+            return Ok(Vec::new());
+        };
+        let events = CalendarRepository::get_all(db(ctx)).await?;
+        Ok(events
+            .into_iter()
+            .filter(|e| e.organizer_id.as_ref() == Some(id) || e.participant_ids.contains(id))
+            .map(EventNode)
+            .collect())
+    }
+}
+
+pub struct SiteNode(Site);
+
+#[Object(name = "Site")]
+impl SiteNode {
+    async fn id(&self) -> Option<ID> {
+        self.0.id.as_ref().map(key)
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn status(&self) -> &str {
+        &self.0.status
+    }
+
+    async fn longitude(&self) -> f64 {
+        self.0.location.0
+    }
+
+    async fn latitude(&self) -> f64 {
+        self.0.location.1
+    }
+
+    async fn buildings(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<BuildingNode>> {
+        let Some(id) = &self.0.id else {
+            return Ok(Vec::new());
+        };
+        let buildings = GeoRepository::list_all_buildings(db(ctx)).await?;
+        Ok(buildings
+            .into_iter()
+            .filter(|b| b.site_id == *id)
+            .map(BuildingNode)
+            .collect())
+    }
+
+    async fn people(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> async_graphql::Result<Connection<usize, PersonNode>> {
+        let people: Vec<Person> = GeoRepository::list_all_people(db(ctx))
+            .await?
+            .into_iter()
+            .filter(|p| self.0.id.as_ref() == Some(&p.site_id))
+            .collect();
+        paginate(people, search, after, first, PersonNode).await
+    }
+}
+
+pub struct BuildingNode(Building);
+
+#[Object(name = "Building")]
+impl BuildingNode {
+    async fn id(&self) -> Option<ID> {
+        self.0.id.as_ref().map(key)
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+}
+
+pub struct AssetNode(NetworkAsset);
+
+#[Object(name = "Asset")]
+impl AssetNode {
+    async fn id(&self) -> Option<ID> {
+        self.0.id.as_ref().map(key)
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn asset_tag(&self) -> Option<&str> {
+        self.0.asset_tag.as_deref()
+    }
+
+    async fn category(&self) -> String {
+        self.0.category.to_string()
+    }
+
+    async fn manufacturer(&self) -> &str {
+        &self.0.manufacturer
+    }
+
+    async fn model(&self) -> &str {
+        &self.0.model
+    }
+
+    async fn serial_number(&self) -> &str {
+        &self.0.serial_number
+    }
+
+    async fn status(&self) -> String {
+        self.0.status.to_string()
+    }
+
+    async fn lifecycle(&self) -> String {
+        self.0.lifecycle.to_string()
+    }
+
+    async fn warranty_end(&self) -> Option<&str> {
+        self.0.warranty_end.as_deref()
+    }
+
+    /// Rack the asset is mounted in, if racked
+    async fn rack_id(&self) -> Option<ID> {
+        self.0.rack_id.as_ref().map(key)
+    }
+
+    /// Lowest rack unit it occupies, if racked
+    async fn position_u(&self) -> Option<u8> {
+        self.0.position_u
+    }
+
+    /// Space it stands in, if not racked
+    async fn space_id(&self) -> Option<ID> {
+        self.0.space_id.as_ref().map(key)
+    }
+}
+
+pub struct ComponentNode(ComponentConfig);
+
+#[Object(name = "Component")]
+impl ComponentNode {
+    async fn id(&self) -> u32 {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Component type, e.g. `Router`
+    async fn kind(&self) -> String {
+        serde_json::to_value(&self.0.component_type)
+            .ok()
+            .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    /// Components linked to this one, in either direction
+    async fn neighbours(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<ComponentNode>> {
+        let connections = ConnectionRepository::get_all(db(ctx)).await?;
+        let ids: Vec<u32> = connections
+            .iter()
+            .filter_map(|c| match (c.from, c.to) {
+                (from, to) if from == self.0.id => Some(to),
+                (from, to) if to == self.0.id => Some(from),
+                _ => None,
+            })
+            .collect();
+        let components = ComponentRepository::get_all(db(ctx)).await?;
+        Ok(components
+            .into_iter()
+            .filter(|c| ids.contains(&c.id))
+            .map(ComponentNode)
+            .collect())
+    }
+}
+
+pub struct ConnectionNode(ConnectionConfig);
+
+#[Object(name = "Connection")]
+impl ConnectionNode {
+    async fn from_id(&self) -> u32 {
+        self.0.from
+    }
+
+    async fn to_id(&self) -> u32 {
+        self.0.to
+    }
+
+    async fn from(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<ComponentNode>> {
+        find_component(db(ctx), self.0.from).await
+    }
+
+    async fn to(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<ComponentNode>> {
+        find_component(db(ctx), self.0.to).await
+    }
+}
+
+pub struct EventNode(Meeting);
+
+#[Object(name = "Event")]
+impl EventNode {
+    async fn id(&self) -> Option<ID> {
+        self.0.id.as_ref().map(key)
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    /// ISO 8601, e.g. `2025-01-15T09:00:00`
+    async fn start_time(&self) -> &str {
+        &self.0.start_time
+    }
+
+    async fn end_time(&self) -> &str {
+        &self.0.end_time
+    }
+
+    async fn all_day(&self) -> bool {
+        self.0.all_day
+    }
+
+    async fn meeting_type(&self) -> String {
+        self.0.meeting_type.to_string()
+    }
+
+    async fn timezone(&self) -> &str {
+        &self.0.timezone
+    }
+
+    async fn organizer(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<PersonNode>> {
+        find_person(db(ctx), self.0.organizer_id.as_ref()).await
+    }
+
+    async fn participants(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PersonNode>> {
+        let people = GeoRepository::list_all_people(db(ctx)).await?;
+        Ok(people
+            .into_iter()
+            .filter(|p| {
+                p.id.as_ref()
+                    .is_some_and(|id| self.0.participant_ids.contains(id))
+            })
+            .map(PersonNode)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_exposes_every_resource() {
+        let sdl = ApiSchema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .finish()
+            .sdl();
+        for ty in [
+            "type Person",
+            "type Site",
+            "type Asset",
+            "type Component",
+            "type Connection",
+            "type Event",
+        ] {
+            assert!(sdl.contains(ty), "missing {ty}");
+        }
+        assert!(sdl.contains("PersonConnection"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Liveness / readiness probes and graceful shutdown
+//!
+//! - `/healthz` answers as soon as the listener is up (process is alive)
+//! - `/readyz` answers 200 only once the database responds and the initial
+//!   scenario seed + city import have finished, and flips back to 503 while
+//!   the server is draining on shutdown
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Serialize;
+
+use crate::AppState;
+
+/// Startup / shutdown flags consulted by `/readyz`
+#[derive(Clone, Default)]
+pub struct Readiness {
+    seeded: Arc<AtomicBool>,
+    draining: Arc<AtomicBool>,
+}
+
+impl Readiness {
+    /// Initial seed and city import are complete
+    pub fn mark_seeded(&self) {
+        self.seeded.store(true, Ordering::SeqCst);
+    }
+
+    /// Shutdown has started; stop accepting new traffic
+    pub fn mark_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_seeded(&self) -> bool {
+        self.seeded.load(Ordering::SeqCst)
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub database: bool,
+    pub seeded: bool,
+    pub draining: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    responses((status = 200, description = "Process is alive", body = String))
+)]
+pub async fn healthz() -> &'static str {
+    "ok"
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = ReadinessReport),
+        (status = 503, description = "Starting up or draining", body = ReadinessReport),
+    )
+)]
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadinessReport>) {
+    let database = state.db.client.health().await.is_ok();
+    let seeded = state.readiness.is_seeded();
+    let draining = state.readiness.is_draining();
+    let ready = database && seeded && !draining;
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(ReadinessReport {
+            ready,
+            database,
+            seeded,
+            draining,
+        }),
+    )
+}
+
+/// Resolves on Ctrl+C or SIGTERM, marking the server as draining
+pub async fn shutdown_signal(readiness: Readiness) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutdown requested, draining in-flight requests...");
+    readiness.mark_draining();
+}
+
+/// Write any buffered log lines out through tracing before exit
+pub async fn flush_logs(state: &AppState) {
+    let lines = state.logs.get_and_clear().await;
+    if !lines.is_empty() {
+        tracing::info!("Flushing {} buffered log lines", lines.len());
+    }
+    for line in lines {
+        tracing::info!(target: "gui_server::logs", "{}", line);
+    }
+}
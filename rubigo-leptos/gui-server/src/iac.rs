@@ -0,0 +1,959 @@
+//! Topology as code
+//!
+//! Exports the site hierarchy (sites down to devices) and the simulated
+//! topology (components and connections) as a declarative document that
+//! automation pipelines can take over: Terraform's JSON syntax
+//! (`.tf.json`) or the same resources as TOML (see [`operations::iac`]).
+//!
+//! Importing a document applies it: each resource is matched to the record
+//! with the same name under the same parent (components by id) and
+//! updated, or created when there is none. Records the document leaves
+//! out are kept, so exporting and importing again changes nothing. The
+//! whole document is checked before anything is written.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::Json;
+use nexosim_hybrid::config::{ComponentConfig, ConnectionConfig};
+use nexosim_hybrid::database::components::ComponentRepository;
+use nexosim_hybrid::database::connections::ConnectionRepository;
+use nexosim_hybrid::database::geo::{
+    Building, Device, Floor, GeoRepository, Rack, Region, Site, Space,
+};
+use nexosim_hybrid::database::maintenance::{Hierarchy, MaintenanceRepository};
+use nexosim_hybrid::database::DbClient;
+use operations::iac::{from_document, reference, to_document, Addresses, Resource};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use surrealdb::sql::Thing;
+use utoipa::ToSchema;
+
+use crate::api::{ApiError, ApiResult};
+use crate::AppState;
+
+/// Document syntax
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IacFormat {
+    /// Terraform JSON configuration syntax
+    #[default]
+    Terraform,
+    Toml,
+}
+
+impl IacFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            IacFormat::Terraform => "tf.json",
+            IacFormat::Toml => "toml",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            IacFormat::Terraform => "application/json",
+            IacFormat::Toml => "application/toml",
+        }
+    }
+
+    pub fn render(&self, document: &Value) -> anyhow::Result<String> {
+        Ok(match self {
+            IacFormat::Terraform => serde_json::to_string_pretty(document)?,
+            IacFormat::Toml => toml::to_string_pretty(document)?,
+        })
+    }
+
+    pub fn parse(&self, text: &str) -> anyhow::Result<Value> {
+        Ok(match self {
+            IacFormat::Terraform => serde_json::from_str(text)?,
+            IacFormat::Toml => toml::from_str(text)?,
+        })
+    }
+}
+
+impl fmt::Display for IacFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for IacFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tf" | "tf.json" | "terraform" | "json" => Ok(IacFormat::Terraform),
+            "toml" => Ok(IacFormat::Toml),
+            _ => anyhow::bail!("Unknown format '{}', expected tf or toml", s),
+        }
+    }
+}
+
+fn thing_key(thing: &Thing) -> String {
+    thing.to_string()
+}
+
+/// The stored topology and hierarchy as a document
+pub async fn export(db: &DbClient) -> anyhow::Result<Value> {
+    let hierarchy = MaintenanceRepository::hierarchy(db).await?;
+    let regions = GeoRepository::list_regions(db).await?;
+    Ok(to_document(&resources(&hierarchy, &regions)))
+}
+
+/// Builds resources, remembering each exported record's address so its
+/// children can refer to it
+#[derive(Default)]
+struct Exporter {
+    addresses: Addresses,
+    /// Kind and address by record id
+    known: HashMap<String, (String, String)>,
+    resources: Vec<Resource>,
+}
+
+impl Exporter {
+    /// Address for `name` under `parent` and the reference to `parent`, or
+    /// `None` when the parent was not exported
+    fn child(&mut self, kind: &str, parent: &Thing, name: &str) -> Option<(String, String)> {
+        let (parent_kind, parent_address) = self.known.get(&thing_key(parent))?.clone();
+        let address = self
+            .addresses
+            .assign(kind, &format!("{parent_address} {name}"));
+        Some((address, reference(&parent_kind, &parent_address)))
+    }
+
+    fn push(&mut self, id: Option<Thing>, resource: Resource) {
+        if let Some(id) = id {
+            self.known.insert(
+                thing_key(&id),
+                (resource.kind.clone(), resource.address.clone()),
+            );
+        }
+        self.resources.push(resource);
+    }
+
+    /// The reference to record `id`, if it was exported
+    fn reference(&self, id: &Thing) -> Option<String> {
+        self.known
+            .get(&thing_key(id))
+            .map(|(kind, address)| reference(kind, address))
+    }
+}
+
+fn component_thing(id: u32) -> Thing {
+    Thing::from(("component", id.to_string().as_str()))
+}
+
+/// Resources for `hierarchy`; records whose parent is missing are left out
+fn resources(hierarchy: &Hierarchy, regions: &[Region]) -> Vec<Resource> {
+    let mut out = Exporter::default();
+    for site in &hierarchy.sites {
+        let address = out.addresses.assign("rubigo_site", &site.name);
+        let region = site
+            .region_id
+            .as_ref()
+            .and_then(|id| regions.iter().find(|r| r.id.as_ref() == Some(id)))
+            .map(|r| r.name.clone());
+        let resource = Resource::new("rubigo_site", address)
+            .with("name", site.name.clone())
+            .with("status", site.status.clone())
+            .with("lon", site.location.0)
+            .with("lat", site.location.1)
+            .with("region", region);
+        out.push(site.id.clone(), resource);
+    }
+    for building in &hierarchy.buildings {
+        let Some((address, site)) = out.child("rubigo_building", &building.site_id, &building.name)
+        else {
+            continue;
+        };
+        let resource = Resource::new("rubigo_building", address)
+            .with("name", building.name.clone())
+            .with("site", site);
+        out.push(building.id.clone(), resource);
+    }
+    for floor in &hierarchy.floors {
+        let Some((address, building)) = out.child("rubigo_floor", &floor.building_id, &floor.name)
+        else {
+            continue;
+        };
+        let outline: Vec<[f64; 2]> = floor.outline.iter().map(|&(x, y)| [x, y]).collect();
+        let resource = Resource::new("rubigo_floor", address)
+            .with("name", floor.name.clone())
+            .with("level", floor.level)
+            .with("building", building)
+            .with("outline", json!(outline));
+        out.push(floor.id.clone(), resource);
+    }
+    for space in &hierarchy.spaces {
+        let Some((address, floor)) = out.child("rubigo_space", &space.floor_id, &space.name) else {
+            continue;
+        };
+        let resource = Resource::new("rubigo_space", address)
+            .with("name", space.name.clone())
+            .with("locator", space.locator.clone())
+            .with("space_type", space.space_type.clone())
+            .with("floor", floor)
+            .with("bounds", json!(space.bounds));
+        out.push(space.id.clone(), resource);
+    }
+    for rack in &hierarchy.racks {
+        let Some((address, space)) = out.child("rubigo_rack", &rack.space_id, &rack.name) else {
+            continue;
+        };
+        let resource = Resource::new("rubigo_rack", address)
+            .with("name", rack.name.clone())
+            .with("height_u", rack.height_u)
+            .with("space", space);
+        out.push(rack.id.clone(), resource);
+    }
+    for component in &hierarchy.components {
+        let address = out.addresses.assign("rubigo_component", &component.name);
+        let mut resource = Resource::new("rubigo_component", address);
+        if let Ok(Value::Object(attributes)) = serde_json::to_value(component) {
+            for (key, value) in attributes {
+                resource = resource.with(&key, value);
+            }
+        }
+        out.push(Some(component_thing(component.id)), resource);
+    }
+    for device in &hierarchy.devices {
+        let Some((address, rack)) = out.child("rubigo_device", &device.rack_id, &device.name)
+        else {
+            continue;
+        };
+        let component = device
+            .component_id
+            .as_ref()
+            .and_then(|id| out.reference(id));
+        let resource = Resource::new("rubigo_device", address)
+            .with("name", device.name.clone())
+            .with("position_u", device.position_u)
+            .with("height_u", device.height_u)
+            .with("power_w", device.power_w)
+            .with("rack", rack)
+            .with("component", component);
+        out.push(device.id.clone(), resource);
+    }
+    for connection in &hierarchy.connections {
+        let from = out
+            .known
+            .get(&thing_key(&component_thing(connection.from)))
+            .cloned();
+        let to = out
+            .known
+            .get(&thing_key(&component_thing(connection.to)))
+            .cloned();
+        let (Some((from_kind, from)), Some((to_kind, to))) = (from, to) else {
+            continue;
+        };
+        let address = out
+            .addresses
+            .assign("rubigo_connection", &format!("{from} to {to}"));
+        let resource = Resource::new("rubigo_connection", address)
+            .with("from", reference(&from_kind, &from))
+            .with("to", reference(&to_kind, &to));
+        out.push(None, resource);
+    }
+    out.resources
+}
+
+/// What applying a document did, or would do, by resource kind
+#[derive(Debug, Default, PartialEq, Serialize, ToSchema)]
+pub struct ApplyReport {
+    pub dry_run: bool,
+    pub created: BTreeMap<String, usize>,
+    pub updated: BTreeMap<String, usize>,
+    pub unchanged: BTreeMap<String, usize>,
+}
+
+/// Whether a resource's record is new, differs from the stored one, or matches it
+enum Change {
+    Create,
+    Update(String),
+    Unchanged(Thing),
+}
+
+/// `record` compared with `existing`, ignoring ids
+fn change<T: Serialize>(
+    existing: Option<(&Option<Thing>, &T)>,
+    record: &T,
+) -> anyhow::Result<Change> {
+    let Some((Some(id), existing)) = existing else {
+        return Ok(Change::Create);
+    };
+    let mut before = serde_json::to_value(existing)?;
+    let mut after = serde_json::to_value(record)?;
+    for value in [&mut before, &mut after] {
+        if let Value::Object(map) = value {
+            map.remove("id");
+        }
+    }
+    Ok(if before == after {
+        Change::Unchanged(id.clone())
+    } else {
+        Change::Update(id.id.to_raw())
+    })
+}
+
+/// One application of a document
+struct Apply<'a> {
+    db: &'a DbClient,
+    dry_run: bool,
+    stored: Hierarchy,
+    regions: Vec<Region>,
+    /// Record of each applied resource, by kind and address
+    things: HashMap<(String, String), Thing>,
+    /// Stored records already matched to a resource
+    claimed: HashSet<String>,
+    report: ApplyReport,
+}
+
+impl Apply<'_> {
+    /// The record resource attribute `key` refers to, which must be a `kind`
+    fn parent(&self, resource: &Resource, key: &str, kind: &str) -> anyhow::Result<Thing> {
+        let (target_kind, address) = resource.reference(key).map_err(anyhow::Error::msg)?;
+        if target_kind != kind {
+            anyhow::bail!(
+                "{}: `{}` must refer to a {}, not a {}",
+                resource.name(),
+                key,
+                kind,
+                target_kind
+            );
+        }
+        self.things
+            .get(&(target_kind.to_string(), address.to_string()))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{}: {}.{} was not applied",
+                    resource.name(),
+                    target_kind,
+                    address
+                )
+            })
+    }
+
+    fn number(&self, resource: &Resource, key: &str) -> anyhow::Result<f64> {
+        resource
+            .attributes
+            .get(key)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| anyhow::anyhow!("{} needs a number `{}`", resource.name(), key))
+    }
+
+    fn text(&self, resource: &Resource, key: &str) -> anyhow::Result<String> {
+        resource
+            .text(key)
+            .map(str::to_string)
+            .map_err(anyhow::Error::msg)
+    }
+
+    fn optional<T: serde::de::DeserializeOwned>(
+        &self,
+        resource: &Resource,
+        key: &str,
+    ) -> anyhow::Result<Option<T>> {
+        match resource.attributes.get(key) {
+            Some(value) => serde_json::from_value(value.clone())
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("{}: invalid `{}`: {}", resource.name(), key, e)),
+            None => Ok(None),
+        }
+    }
+
+    /// The first stored record matching `matches` not yet claimed by another resource
+    fn claim<'s, T>(
+        claimed: &mut HashSet<String>,
+        records: &'s [T],
+        id: impl Fn(&T) -> &Option<Thing>,
+        matches: impl Fn(&T) -> bool,
+    ) -> Option<(&'s Option<Thing>, &'s T)> {
+        let record = records.iter().find(|r| {
+            matches(r)
+                && id(r)
+                    .as_ref()
+                    .is_some_and(|id| !claimed.contains(&thing_key(id)))
+        })?;
+        if let Some(id) = id(record) {
+            claimed.insert(thing_key(id));
+        }
+        Some((id(record), record))
+    }
+
+    /// Count `change` and remember `resource`'s record as `thing`
+    fn record(&mut self, resource: &Resource, change: &str, thing: Thing) {
+        let counts = match change {
+            "created" => &mut self.report.created,
+            "updated" => &mut self.report.updated,
+            _ => &mut self.report.unchanged,
+        };
+        *counts.entry(resource.kind.clone()).or_default() += 1;
+        self.things
+            .insert((resource.kind.clone(), resource.address.clone()), thing);
+    }
+
+    /// Stand-in id for a record a dry run would create
+    fn planned(resource: &Resource, table: &str) -> Thing {
+        Thing::from((table, format!("planned_{}", resource.address).as_str()))
+    }
+
+    async fn apply(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        match resource.kind.as_str() {
+            "rubigo_site" => self.site(resource).await,
+            "rubigo_building" => self.building(resource).await,
+            "rubigo_floor" => self.floor(resource).await,
+            "rubigo_space" => self.space(resource).await,
+            "rubigo_rack" => self.rack(resource).await,
+            "rubigo_component" => self.component(resource).await,
+            "rubigo_device" => self.device(resource).await,
+            _ => self.connection(resource).await,
+        }
+    }
+
+    async fn site(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let region = match resource.attributes.get("region").and_then(Value::as_str) {
+            Some(name) => Some(
+                self.regions
+                    .iter()
+                    .find(|r| r.name.eq_ignore_ascii_case(name))
+                    .and_then(|r| r.id.clone())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("{}: unknown region '{}'", resource.name(), name)
+                    })?,
+            ),
+            None => None,
+        };
+        let site = Site {
+            id: None,
+            name: self.text(resource, "name")?,
+            region_id: region,
+            location: (self.number(resource, "lon")?, self.number(resource, "lat")?),
+            status: resource.text("status").unwrap_or("active").to_string(),
+        };
+        let existing = Self::claim(
+            &mut self.claimed,
+            &self.stored.sites,
+            |s| &s.id,
+            |s| s.name == site.name,
+        );
+        match change(existing, &site)? {
+            Change::Unchanged(id) => self.record(resource, "unchanged", id),
+            Change::Update(key) => {
+                if !self.dry_run {
+                    GeoRepository::update_site(self.db, &key, site).await?;
+                }
+                self.record(resource, "updated", Thing::from(("site", key.as_str())));
+            }
+            Change::Create => {
+                let id = match self.dry_run {
+                    true => Some(Self::planned(resource, "site")),
+                    false => GeoRepository::create_site(self.db, site).await?.id,
+                };
+                self.record(
+                    resource,
+                    "created",
+                    id.ok_or_else(|| anyhow::anyhow!("Site created without id"))?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn building(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let building = Building {
+            id: None,
+            name: self.text(resource, "name")?,
+            site_id: self.parent(resource, "site", "rubigo_site")?,
+        };
+        let existing = Self::claim(
+            &mut self.claimed,
+            &self.stored.buildings,
+            |b| &b.id,
+            |b| b.site_id == building.site_id && b.name == building.name,
+        );
+        match change(existing, &building)? {
+            Change::Unchanged(id) => self.record(resource, "unchanged", id),
+            Change::Update(key) => {
+                if !self.dry_run {
+                    GeoRepository::update_building(self.db, &key, building).await?;
+                }
+                self.record(resource, "updated", Thing::from(("building", key.as_str())));
+            }
+            Change::Create => {
+                let id = match self.dry_run {
+                    true => Some(Self::planned(resource, "building")),
+                    false => GeoRepository::create_building(self.db, building).await?.id,
+                };
+                self.record(
+                    resource,
+                    "created",
+                    id.ok_or_else(|| anyhow::anyhow!("Building created without id"))?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn floor(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let outline: Vec<[f64; 2]> = self.optional(resource, "outline")?.unwrap_or_default();
+        let floor = Floor {
+            id: None,
+            name: self.text(resource, "name")?,
+            building_id: self.parent(resource, "building", "rubigo_building")?,
+            level: self.optional(resource, "level")?.unwrap_or_default(),
+            outline: outline.into_iter().map(|[x, y]| (x, y)).collect(),
+        };
+        let existing = Self::claim(
+            &mut self.claimed,
+            &self.stored.floors,
+            |f| &f.id,
+            |f| f.building_id == floor.building_id && f.name == floor.name,
+        );
+        match change(existing, &floor)? {
+            Change::Unchanged(id) => self.record(resource, "unchanged", id),
+            Change::Update(key) => {
+                if !self.dry_run {
+                    GeoRepository::update_floor(self.db, &key, floor).await?;
+                }
+                self.record(resource, "updated", Thing::from(("floor", key.as_str())));
+            }
+            Change::Create => {
+                let id = match self.dry_run {
+                    true => Some(Self::planned(resource, "floor")),
+                    false => GeoRepository::create_floor(self.db, floor).await?.id,
+                };
+                self.record(
+                    resource,
+                    "created",
+                    id.ok_or_else(|| anyhow::anyhow!("Floor created without id"))?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn space(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let name = self.text(resource, "name")?;
+        let space = Space {
+            id: None,
+            locator: resource
+                .text("locator")
+                .map(str::to_string)
+                .unwrap_or_else(|_| name.clone()),
+            name,
+            floor_id: self.parent(resource, "floor", "rubigo_floor")?,
+            space_type: self.optional(resource, "space_type")?,
+            bounds: self.optional(resource, "bounds")?,
+        };
+        let existing = Self::claim(
+            &mut self.claimed,
+            &self.stored.spaces,
+            |s| &s.id,
+            |s| s.floor_id == space.floor_id && s.name == space.name,
+        );
+        match change(existing, &space)? {
+            Change::Unchanged(id) => self.record(resource, "unchanged", id),
+            Change::Update(key) => {
+                if !self.dry_run {
+                    GeoRepository::update_space(self.db, &key, space).await?;
+                }
+                self.record(resource, "updated", Thing::from(("space", key.as_str())));
+            }
+            Change::Create => {
+                let id = match self.dry_run {
+                    true => Some(Self::planned(resource, "space")),
+                    false => GeoRepository::create_space(self.db, space).await?.id,
+                };
+                self.record(
+                    resource,
+                    "created",
+                    id.ok_or_else(|| anyhow::anyhow!("Space created without id"))?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn rack(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let rack = Rack {
+            id: None,
+            name: self.text(resource, "name")?,
+            space_id: self.parent(resource, "space", "rubigo_space")?,
+            height_u: self
+                .optional(resource, "height_u")?
+                .unwrap_or(operations::netbox::DEFAULT_RACK_HEIGHT),
+        };
+        let existing = Self::claim(
+            &mut self.claimed,
+            &self.stored.racks,
+            |r| &r.id,
+            |r| r.space_id == rack.space_id && r.name == rack.name,
+        );
+        match change(existing, &rack)? {
+            Change::Unchanged(id) => self.record(resource, "unchanged", id),
+            Change::Update(key) => {
+                if !self.dry_run {
+                    GeoRepository::update_rack(self.db, &key, rack).await?;
+                }
+                self.record(resource, "updated", Thing::from(("rack", key.as_str())));
+            }
+            Change::Create => {
+                let id = match self.dry_run {
+                    true => Some(Self::planned(resource, "rack")),
+                    false => GeoRepository::create_rack(self.db, rack).await?.id,
+                };
+                self.record(
+                    resource,
+                    "created",
+                    id.ok_or_else(|| anyhow::anyhow!("Rack created without id"))?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn component(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let component: ComponentConfig =
+            serde_json::from_value(Value::Object(resource.attributes.clone()))
+                .map_err(|e| anyhow::anyhow!("{}: {}", resource.name(), e))?;
+        let thing = component_thing(component.id);
+        let existing = self.stored.components.iter().find(|c| c.id == component.id);
+        let same = match existing {
+            Some(existing) => serde_json::to_value(existing)? == serde_json::to_value(&component)?,
+            None => false,
+        };
+        match (existing.is_some(), same) {
+            (true, true) => self.record(resource, "unchanged", thing),
+            (true, false) => {
+                if !self.dry_run {
+                    ComponentRepository::update(self.db, component.id, component).await?;
+                }
+                self.record(resource, "updated", thing);
+            }
+            (false, _) => {
+                if !self.dry_run {
+                    ComponentRepository::create(self.db, component).await?;
+                }
+                self.record(resource, "created", thing);
+            }
+        }
+        Ok(())
+    }
+
+    async fn device(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let component_id = match resource.attributes.contains_key("component") {
+            true => Some(self.parent(resource, "component", "rubigo_component")?),
+            false => None,
+        };
+        let device = Device {
+            id: None,
+            name: self.text(resource, "name")?,
+            rack_id: self.parent(resource, "rack", "rubigo_rack")?,
+            position_u: self.optional(resource, "position_u")?.unwrap_or_default(),
+            component_id,
+            height_u: self.optional(resource, "height_u")?.unwrap_or(1),
+            power_w: self.optional(resource, "power_w")?.unwrap_or_default(),
+        };
+        let existing = Self::claim(
+            &mut self.claimed,
+            &self.stored.devices,
+            |d| &d.id,
+            |d| d.rack_id == device.rack_id && d.name == device.name,
+        );
+        match change(existing, &device)? {
+            Change::Unchanged(id) => self.record(resource, "unchanged", id),
+            Change::Update(key) => {
+                if !self.dry_run {
+                    GeoRepository::update_device(self.db, &key, device).await?;
+                }
+                self.record(resource, "updated", Thing::from(("device", key.as_str())));
+            }
+            Change::Create => {
+                let id = match self.dry_run {
+                    true => Some(Self::planned(resource, "device")),
+                    false => GeoRepository::create_device(self.db, device).await?.id,
+                };
+                self.record(
+                    resource,
+                    "created",
+                    id.ok_or_else(|| anyhow::anyhow!("Device created without id"))?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn connection(&mut self, resource: &Resource) -> anyhow::Result<()> {
+        let end = |key: &str| -> anyhow::Result<u32> {
+            let thing = self.parent(resource, key, "rubigo_component")?;
+            Ok(thing.id.to_raw().parse()?)
+        };
+        let connection = ConnectionConfig {
+            from: end("from")?,
+            to: end("to")?,
+        };
+        let thing = Thing::from((
+            "connection",
+            format!("{}-{}", connection.from, connection.to).as_str(),
+        ));
+        if self
+            .stored
+            .connections
+            .iter()
+            .any(|c| c.from == connection.from && c.to == connection.to)
+        {
+            self.record(resource, "unchanged", thing);
+        } else {
+            if !self.dry_run {
+                ConnectionRepository::create(self.db, connection).await?;
+            }
+            self.record(resource, "created", thing);
+        }
+        Ok(())
+    }
+}
+
+/// Apply `document`, or with `dry_run` only report what would change
+///
+/// A real run is preceded by a dry run, so a document with an error in it
+/// writes nothing.
+pub async fn apply(db: &DbClient, document: &Value, dry_run: bool) -> anyhow::Result<ApplyReport> {
+    let resources = from_document(document).map_err(anyhow::Error::msg)?;
+    let mut report = run(db, &resources, true).await?;
+    if !dry_run {
+        report = run(db, &resources, false).await?;
+    }
+    Ok(report)
+}
+
+async fn run(db: &DbClient, resources: &[Resource], dry_run: bool) -> anyhow::Result<ApplyReport> {
+    let mut apply = Apply {
+        db,
+        dry_run,
+        stored: MaintenanceRepository::hierarchy(db).await?,
+        regions: GeoRepository::list_regions(db).await?,
+        things: HashMap::new(),
+        claimed: HashSet::new(),
+        report: ApplyReport {
+            dry_run,
+            ..Default::default()
+        },
+    };
+    for resource in resources {
+        apply.apply(resource).await?;
+    }
+    Ok(apply.report)
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct FormatQuery {
+    /// `tf` (Terraform JSON, the default) or `toml`
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct ImportQuery {
+    /// `tf` (Terraform JSON, the default) or `toml`
+    pub format: Option<String>,
+    /// Only report what the import would change
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn parse_format(format: Option<&str>) -> ApiResult<IacFormat> {
+    match format {
+        Some(format) => format
+            .parse()
+            .map_err(|e: anyhow::Error| ApiError::bad_request(e.to_string())),
+        None => Ok(IacFormat::default()),
+    }
+}
+
+/// Download the topology and site hierarchy as code
+#[utoipa::path(
+    get,
+    path = "/api/iac/export",
+    tag = "iac",
+    params(FormatQuery),
+    responses(
+        (status = 200, description = "Terraform JSON, or TOML with `format=toml`", content_type = "application/json"),
+        (status = 400, description = "Unknown format", body = ApiError),
+    )
+)]
+pub async fn export_document(
+    State(state): State<AppState>,
+    Query(query): Query<FormatQuery>,
+) -> ApiResult<axum::response::Response> {
+    let format = parse_format(query.format.as_deref())?;
+    let text = format.render(&export(&state.db.client).await?)?;
+    let file_name = format!("rubigo-topology.{}", format.extension());
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}\""),
+        ),
+    ];
+    Ok((headers, text).into_response())
+}
+
+/// Apply a topology document, or only check it with `dry_run`
+#[utoipa::path(
+    post,
+    path = "/api/iac/import",
+    tag = "iac",
+    params(ImportQuery),
+    request_body(content = String, description = "Document as exported, in `format`", content_type = "text/plain"),
+    responses(
+        (status = 200, description = "Resources created, updated and unchanged by kind", body = ApplyReport),
+        (status = 400, description = "Unreadable document, unknown resource kind or broken reference", body = ApiError),
+    )
+)]
+pub async fn import_document(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> ApiResult<Json<ApplyReport>> {
+    let format = parse_format(query.format.as_deref())?;
+    let document = format
+        .parse(&body)
+        .map_err(|e| ApiError::bad_request(format!("Unreadable {format} document: {e}")))?;
+    let report = apply(&state.db.client, &document, query.dry_run)
+        .await
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexosim_hybrid::config::{ComponentType, DevicePlacement, PowerProfile};
+
+    fn hierarchy() -> Hierarchy {
+        let site = Thing::from(("site", "hq"));
+        let building = Thing::from(("building", "main"));
+        let floor = Thing::from(("floor", "ground"));
+        let space = Thing::from(("space", "hall"));
+        let rack = Thing::from(("rack", "r1"));
+        Hierarchy {
+            sites: vec![Site {
+                id: Some(site.clone()),
+                name: "HQ".to_string(),
+                region_id: None,
+                location: (-0.12, 51.5),
+                status: "active".to_string(),
+            }],
+            buildings: vec![Building {
+                id: Some(building.clone()),
+                name: "Main".to_string(),
+                site_id: site,
+            }],
+            floors: vec![Floor {
+                id: Some(floor.clone()),
+                name: "Ground".to_string(),
+                building_id: building,
+                level: 0,
+                outline: Vec::new(),
+            }],
+            spaces: vec![Space {
+                id: Some(space.clone()),
+                name: "Hall".to_string(),
+                floor_id: floor,
+                locator: "G-01".to_string(),
+                space_type: None,
+                bounds: None,
+            }],
+            racks: vec![Rack {
+                id: Some(rack.clone()),
+                name: "Rack 1".to_string(),
+                space_id: space,
+                height_u: 42,
+            }],
+            devices: vec![Device {
+                id: Some(Thing::from(("device", "d1"))),
+                name: "core-sw".to_string(),
+                rack_id: rack,
+                position_u: 10,
+                component_id: Some(component_thing(1)),
+                height_u: 2,
+                power_w: 350,
+            }],
+            desks: Vec::new(),
+            bookings: Vec::new(),
+            panels: Vec::new(),
+            ports: Vec::new(),
+            cables: Vec::new(),
+            components: vec![
+                ComponentConfig {
+                    id: 1,
+                    name: "Core Switch".to_string(),
+                    component_type: ComponentType::Switch,
+                    placement: DevicePlacement::Standalone,
+                    power: Some(PowerProfile {
+                        idle_w: 90.0,
+                        max_w: 240.0,
+                    }),
+                },
+                ComponentConfig {
+                    id: 2,
+                    name: "Edge Router".to_string(),
+                    component_type: ComponentType::Router,
+                    placement: DevicePlacement::Rack {
+                        rack: "Rack 1".to_string(),
+                        u_position: None,
+                    },
+                    power: None,
+                },
+            ],
+            connections: vec![ConnectionConfig { from: 1, to: 2 }],
+        }
+    }
+
+    #[test]
+    fn export_reads_back_in_both_formats() {
+        let document = to_document(&resources(&hierarchy(), &[]));
+        let device = &document["resource"]["rubigo_device"]["hq_main_ground_hall_rack_1_core_sw"];
+        assert_eq!(
+            device["rack"],
+            "${rubigo_rack.hq_main_ground_hall_rack_1.id}"
+        );
+        assert_eq!(device["component"], "${rubigo_component.core_switch.id}");
+        assert_eq!(
+            document["resource"]["rubigo_connection"]["core_switch_to_edge_router"]["to"],
+            "${rubigo_component.edge_router.id}"
+        );
+
+        for format in [IacFormat::Terraform, IacFormat::Toml] {
+            let text = format.render(&document).unwrap();
+            let read = format.parse(&text).unwrap();
+            let resources = from_document(&read).unwrap();
+            assert_eq!(resources.len(), 9, "{format}");
+            let router = resources
+                .iter()
+                .find(|r| r.address == "edge_router")
+                .unwrap();
+            let component: ComponentConfig =
+                serde_json::from_value(Value::Object(router.attributes.clone())).unwrap();
+            assert_eq!(component.component_type, ComponentType::Router);
+            assert_eq!(component.power, None);
+            let switch = resources
+                .iter()
+                .find(|r| r.address == "core_switch")
+                .unwrap();
+            let component: ComponentConfig =
+                serde_json::from_value(Value::Object(switch.attributes.clone())).unwrap();
+            assert_eq!(
+                component.power,
+                Some(PowerProfile {
+                    idle_w: 90.0,
+                    max_w: 240.0
+                })
+            );
+        }
+    }
+}
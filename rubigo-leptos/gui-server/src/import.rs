@@ -0,0 +1,257 @@
+//! CSV import
+//!
+//! Server side of the import wizard. Each importable resource declares its
+//! fields; a submitted file is parsed and validated with the same
+//! `ui_core::features::import` functions the wizard's preview uses, then
+//! references (a person's site, a site's region) are resolved against the
+//! database. Valid rows are written unless the request is a dry run; rows
+//! with errors are skipped and reported.
+
+use std::collections::HashMap;
+
+use nexosim_hybrid::config::RoleType;
+use nexosim_hybrid::database::geo::{GeoRepository, Person, Site};
+use nexosim_hybrid::database::DbClient;
+use surrealdb::sql::Thing;
+use ui_core::features::import::{mapped_row, validate};
+use ui_core::features::{ImportField, ImportFieldKind, ImportReport, ImportRequest, RowError};
+
+/// Resources that accept imports, as used in `/api/import/{resource}`
+pub const RESOURCES: [&str; 2] = ["people", "sites"];
+
+/// Fields of `resource`, or `None` if it cannot be imported
+pub fn fields(resource: &str) -> Option<Vec<ImportField>> {
+    let fields = match resource {
+        "people" => vec![
+            ImportField::new("name", "Name").required(),
+            ImportField::new("email", "Email")
+                .required()
+                .kind(ImportFieldKind::Email),
+            ImportField::new("title", "Title"),
+            ImportField::new("department", "Department"),
+            ImportField::new("site", "Site").required(),
+            ImportField::new("desk_phone", "Desk phone"),
+            ImportField::new("cell_phone", "Cell phone"),
+        ],
+        "sites" => vec![
+            ImportField::new("name", "Name").required(),
+            ImportField::new("region", "Region"),
+            ImportField::new("lat", "Latitude")
+                .required()
+                .kind(ImportFieldKind::Number),
+            ImportField::new("lon", "Longitude")
+                .required()
+                .kind(ImportFieldKind::Number),
+            ImportField::new("status", "Status"),
+        ],
+        _ => return None,
+    };
+    Some(fields)
+}
+
+/// Validate `request` and, unless it is a dry run, write its valid rows
+///
+/// Returns `None` for a resource that cannot be imported.
+pub async fn run(
+    db: &DbClient,
+    resource: &str,
+    request: &ImportRequest,
+) -> anyhow::Result<Option<ImportReport>> {
+    let Some(fields) = fields(resource) else {
+        return Ok(None);
+    };
+    let table = request.table();
+    let mut errors = validate(&table, &request.mapping, &fields);
+    let references = match resource {
+        "people" => References::new(
+            "site",
+            GeoRepository::list_sites(db)
+                .await?
+                .into_iter()
+                .map(|s| (s.id, s.name)),
+        ),
+        _ => References::new(
+            "region",
+            GeoRepository::list_regions(db)
+                .await?
+                .into_iter()
+                .map(|r| (r.id, r.name)),
+        ),
+    };
+
+    let mut report = ImportReport {
+        dry_run: request.dry_run,
+        total: table.rows.len(),
+        ..Default::default()
+    };
+    for row in 0..table.rows.len() {
+        if errors.iter().any(|e| e.row == row + 1) {
+            continue;
+        }
+        let values: HashMap<String, String> = mapped_row(&table, row, &request.mapping)
+            .into_iter()
+            .collect();
+        let record = match resource {
+            "people" => person(&values, &references).map(Record::Person),
+            _ => site(&values, &references).map(Record::Site),
+        };
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                errors.push(RowError {
+                    row: row + 1,
+                    ..error
+                });
+                continue;
+            }
+        };
+        report.valid += 1;
+        if request.dry_run {
+            continue;
+        }
+        let written = match record {
+            Record::Person(person) => GeoRepository::create_person(db, person).await.map(|_| ()),
+            Record::Site(site) => GeoRepository::create_site(db, site).await.map(|_| ()),
+        };
+        match written {
+            Ok(()) => report.imported += 1,
+            Err(e) => errors.push(RowError {
+                row: row + 1,
+                field: None,
+                message: format!("Could not save row: {}", e),
+            }),
+        }
+    }
+
+    errors.sort_by_key(|e| e.row);
+    report.errors = errors;
+    Ok(Some(report))
+}
+
+enum Record {
+    Person(Person),
+    Site(Site),
+}
+
+/// Existing records a column may refer to, by key, `table:key` or name
+struct References {
+    table: &'static str,
+    by_name: HashMap<String, Thing>,
+}
+
+impl References {
+    fn new(table: &'static str, records: impl Iterator<Item = (Option<Thing>, String)>) -> Self {
+        let mut by_name = HashMap::new();
+        for (id, name) in records {
+            let Some(id) = id else { continue };
+            by_name.insert(id.id.to_raw().to_lowercase(), id.clone());
+            by_name.insert(id.to_raw().to_lowercase(), id.clone());
+            by_name.insert(name.to_lowercase(), id);
+        }
+        Self { table, by_name }
+    }
+
+    fn resolve(&self, field: &str, value: &str) -> Result<Thing, RowError> {
+        self.by_name
+            .get(&value.to_lowercase())
+            .cloned()
+            .ok_or_else(|| RowError {
+                row: 0,
+                field: Some(field.to_string()),
+                message: format!("No {} named '{}'", self.table, value),
+            })
+    }
+}
+
+fn text(values: &HashMap<String, String>, key: &str) -> String {
+    values.get(key).cloned().unwrap_or_default()
+}
+
+fn optional(values: &HashMap<String, String>, key: &str) -> Option<String> {
+    values.get(key).filter(|v| !v.is_empty()).cloned()
+}
+
+fn person(values: &HashMap<String, String>, sites: &References) -> Result<Person, RowError> {
+    Ok(Person {
+        id: None,
+        name: text(values, "name"),
+        email: text(values, "email"),
+        title: text(values, "title"),
+        department: text(values, "department"),
+        site_id: sites.resolve("site", &text(values, "site"))?,
+        space_id: None,
+        manager_id: None,
+        role: RoleType::default(),
+        photo: None,
+        bio: None,
+        desk_phone: optional(values, "desk_phone"),
+        cell_phone: optional(values, "cell_phone"),
+        photo_data: None,
+    })
+}
+
+fn site(values: &HashMap<String, String>, regions: &References) -> Result<Site, RowError> {
+    // Both coordinates passed validation as numbers
+    let coordinate = |key| text(values, key).parse::<f64>().unwrap_or_default();
+    let region_id = match optional(values, "region") {
+        Some(region) => Some(regions.resolve("region", &region)?),
+        None => None,
+    };
+    Ok(Site {
+        id: None,
+        name: text(values, "name"),
+        region_id,
+        location: (coordinate("lon"), coordinate("lat")),
+        status: optional(values, "status").unwrap_or_else(|| "active".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions() -> References {
+        References::new(
+            "region",
+            vec![(Some(Thing::from(("region", "emea"))), "Europe".to_string())].into_iter(),
+        )
+    }
+
+    #[test]
+    fn references_resolve_by_key_id_or_name() {
+        let regions = regions();
+        for value in ["emea", "region:emea", "EUROPE"] {
+            assert_eq!(
+                regions.resolve("region", value).unwrap(),
+                Thing::from(("region", "emea"))
+            );
+        }
+        let error = regions.resolve("region", "Mars").unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("region"));
+    }
+
+    #[test]
+    fn site_rows_default_status_and_order_coordinates() {
+        let values: HashMap<String, String> = [
+            ("name", "Lisbon"),
+            ("lat", "38.7"),
+            ("lon", "-9.1"),
+            ("region", "emea"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let site = site(&values, &regions()).unwrap();
+        assert_eq!(site.location, (-9.1, 38.7));
+        assert_eq!(site.status, "active");
+        assert_eq!(site.region_id, Some(Thing::from(("region", "emea"))));
+    }
+
+    #[test]
+    fn every_resource_has_fields() {
+        for resource in RESOURCES {
+            assert!(fields(resource).is_some_and(|f| f.iter().any(|f| f.required)));
+        }
+        assert!(fields("cables").is_none());
+    }
+}
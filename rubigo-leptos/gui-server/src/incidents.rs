@@ -0,0 +1,422 @@
+//! Incidents
+//!
+//! Opening, assigning and working incidents, shared by the incident board's
+//! form handlers and the `/api/incidents` endpoints. The persona making the
+//! request (see [`crate::identity::persona`]) is recorded as the author of
+//! every timeline entry, and the person an
+//! incident is assigned to gets a notification. One opened without an
+//! assignee pages whoever is on call (see `crate::oncall`).
+//!
+//! A simulation run can inject faults (see
+//! `nexosim_hybrid::database::simulation::Fault`); those marked for it open a
+//! training incident against the failed component and its site once the
+//! run is recorded.
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
+use nexosim_hybrid::config::ComponentConfig;
+use nexosim_hybrid::database::device_links::PhysicalLocation;
+use nexosim_hybrid::database::geo::GeoRepository;
+use nexosim_hybrid::database::incidents::{Incident, IncidentRepository, IncidentStatus, Severity};
+use nexosim_hybrid::database::notifications::{Notification, NotificationKind};
+use nexosim_hybrid::database::oncall::OnCallRepository;
+use nexosim_hybrid::database::simulation::SimulationRun;
+use serde::Deserialize;
+use std::collections::HashMap;
+use surrealdb::sql::Thing;
+use utoipa::ToSchema;
+
+use crate::api::{found, record_key, ApiError, ApiResult};
+use crate::clock::now;
+use crate::identity::persona;
+use crate::AppState;
+
+/// What a new incident is opened with
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct IncidentInput {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Ids of the affected components
+    #[serde(default)]
+    pub components: Vec<u32>,
+    /// Affected sites, as `site:key` or `key`
+    #[serde(default)]
+    pub sites: Vec<String>,
+    /// Person to assign, as `person:key` or `key`; whoever is on call
+    /// when left out
+    #[serde(default)]
+    pub assignee_id: Option<String>,
+    /// Escalation policy to page, as `escalation_policy:key` or `key`; the
+    /// default policy when left out
+    #[serde(default)]
+    pub escalation_policy_id: Option<String>,
+}
+
+/// Open an incident as `author`, assigning it if asked to and
+/// paging whoever is on call otherwise
+pub async fn open(
+    state: &AppState,
+    input: IncidentInput,
+    author: Option<String>,
+) -> anyhow::Result<Incident> {
+    let mut incident = Incident::new(input.title.trim(), input.severity, author.clone(), &now());
+    incident.description = input.description.filter(|d| !d.trim().is_empty());
+    incident.components = input.components;
+    incident.sites = input
+        .sites
+        .iter()
+        .map(|s| Thing::from(("site", record_key(s, "site"))))
+        .collect();
+    incident.escalation_policy_id = match input.escalation_policy_id.filter(|p| !p.is_empty()) {
+        Some(policy) => Some(Thing::from((
+            "escalation_policy",
+            record_key(&policy, "escalation_policy"),
+        ))),
+        None => OnCallRepository::default_policy(&state.db.client)
+            .await?
+            .and_then(|p| p.id),
+    };
+    let incident = IncidentRepository::create(&state.db.client, incident).await?;
+    let key = incident.key();
+    if let Some(assignee) = input.assignee_id.filter(|a| !a.is_empty()) {
+        assign(state, &key, Some(&assignee), author).await?;
+    } else {
+        crate::oncall::escalate(state, &incident, &crate::clock::minute()).await?;
+    }
+    Ok(IncidentRepository::get(&state.db.client, &key)
+        .await?
+        .unwrap_or(incident))
+}
+
+/// Assign incident `id` to `assignee` (a person id), or unassign it, as
+/// `author`, and let the new assignee know; `None` when there is no such incident
+pub async fn assign(
+    state: &AppState,
+    id: &str,
+    assignee: Option<&str>,
+    author: Option<String>,
+) -> anyhow::Result<Option<Incident>> {
+    let person = match assignee {
+        Some(assignee) => {
+            let key = record_key(assignee, "person");
+            let person = GeoRepository::get_person_by_id(&state.db.client, key).await?;
+            Some(person.ok_or_else(|| anyhow::anyhow!("Person '{}' not found", assignee))?)
+        }
+        None => None,
+    };
+    let name = person.as_ref().map(|p| p.name.clone()).unwrap_or_default();
+    let thing = person.as_ref().and_then(|p| p.id.clone());
+    let updated = IncidentRepository::update(&state.db.client, id, |incident| {
+        incident.assign(thing, &name, author, &now());
+        Ok(())
+    })
+    .await?;
+    if let (Some(incident), Some(person)) = (&updated, person) {
+        state
+            .notifications
+            .notify(
+                Notification::new(
+                    NotificationKind::IncidentAssigned,
+                    Some(person.name),
+                    format!("Incident assigned to you: {}", incident.title),
+                )
+                .with_body(format!(
+                    "Severity {}, {}",
+                    incident.severity, incident.status
+                ))
+                .with_link(format!("/?tab=incidents&incident={}", incident.key())),
+            )
+            .await;
+    }
+    Ok(updated)
+}
+
+/// Move incident `id` to `status` as `author`
+pub async fn set_status(
+    state: &AppState,
+    id: &str,
+    status: IncidentStatus,
+    note: Option<&str>,
+    author: Option<String>,
+) -> anyhow::Result<Option<Incident>> {
+    IncidentRepository::update(&state.db.client, id, |incident| {
+        incident.transition(status, author, note, &now())
+    })
+    .await
+}
+
+/// Add a note to incident `id`'s timeline as `author`
+pub async fn note(
+    state: &AppState,
+    id: &str,
+    text: &str,
+    author: Option<String>,
+) -> anyhow::Result<Option<Incident>> {
+    IncidentRepository::update(&state.db.client, id, |incident| {
+        incident.note(text, author, &now());
+        Ok(())
+    })
+    .await
+}
+
+/// Open a training incident for every fault in `run` that asks for one,
+/// returning how many were opened
+pub async fn open_for_faults(
+    state: &AppState,
+    run: &SimulationRun,
+    components: &[ComponentConfig],
+    locations: &HashMap<u32, PhysicalLocation>,
+) -> anyhow::Result<usize> {
+    let faults: Vec<_> = run.faults.iter().filter(|f| f.open_incident).collect();
+    if faults.is_empty() {
+        return Ok(0);
+    }
+    let sites = GeoRepository::list_sites(&state.db.client).await?;
+    for fault in &faults {
+        let name = components
+            .iter()
+            .find(|c| c.id == fault.component)
+            .map_or_else(
+                || format!("Component {}", fault.component),
+                |c| c.name.clone(),
+            );
+        let mut incident = Incident::new(format!("{name} failed"), Severity::Major, None, &now());
+        incident.description = Some(format!(
+            "Simulated failure of '{}' at step {} of the run started {}.",
+            name, fault.step, run.started_at
+        ));
+        incident.components = vec![fault.component];
+        incident.sites = locations
+            .get(&fault.component)
+            .and_then(|l| l.site.as_ref())
+            .and_then(|site| sites.iter().find(|s| &s.name == site)?.id.clone())
+            .into_iter()
+            .collect();
+        incident.training = true;
+        incident.run_id = run.id.clone();
+        IncidentRepository::create(&state.db.client, incident).await?;
+    }
+    Ok(faults.len())
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+#[utoipa::path(
+    get,
+    path = "/api/incidents",
+    tag = "incidents",
+    responses((status = 200, description = "All incidents, most severe first", body = Vec<Incident>))
+)]
+pub async fn list(State(state): State<AppState>) -> ApiResult<Json<Vec<Incident>>> {
+    Ok(Json(IncidentRepository::list(&state.db.client).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/incidents/{id}",
+    tag = "incidents",
+    params(("id" = String, Path, description = "Incident id (`incident:key` or `key`)")),
+    responses(
+        (status = 200, description = "The incident with its timeline", body = Incident),
+        (status = 404, description = "Incident not found", body = ApiError),
+    )
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Incident>> {
+    let incident = IncidentRepository::get(&state.db.client, record_key(&id, "incident")).await?;
+    Ok(Json(found(incident, "Incident", &id)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/incidents",
+    tag = "incidents",
+    request_body = IncidentInput,
+    responses(
+        (status = 201, description = "Incident opened", body = Incident),
+        (status = 400, description = "Missing title, or unknown assignee or policy", body = ApiError),
+    )
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(input): Json<IncidentInput>,
+) -> ApiResult<(StatusCode, Json<Incident>)> {
+    if input.title.trim().is_empty() {
+        return Err(ApiError::bad_request("An incident needs a title"));
+    }
+    if let Some(assignee) = input.assignee_id.as_deref().filter(|a| !a.is_empty()) {
+        let person =
+            GeoRepository::get_person_by_id(&state.db.client, record_key(assignee, "person"))
+                .await?;
+        if person.is_none() {
+            return Err(ApiError::bad_request(format!(
+                "Person '{assignee}' not found"
+            )));
+        }
+    }
+    if let Some(policy) = input
+        .escalation_policy_id
+        .as_deref()
+        .filter(|p| !p.is_empty())
+    {
+        let key = record_key(policy, "escalation_policy");
+        if OnCallRepository::get_policy(&state.db.client, key)
+            .await?
+            .is_none()
+        {
+            return Err(ApiError::bad_request(format!(
+                "Escalation policy '{policy}' not found"
+            )));
+        }
+    }
+    let incident = open(&state, input, persona(&state, &jar).await).await?;
+    Ok((StatusCode::CREATED, Json(incident)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StatusInput {
+    pub status: IncidentStatus,
+    /// Why, added to the timeline entry
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/incidents/{id}/status",
+    tag = "incidents",
+    params(("id" = String, Path, description = "Incident id (`incident:key` or `key`)")),
+    request_body = StatusInput,
+    responses(
+        (status = 200, description = "Status changed", body = Incident),
+        (status = 404, description = "Incident not found", body = ApiError),
+        (status = 409, description = "The workflow does not allow the change", body = ApiError),
+    )
+)]
+pub async fn update_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Json(input): Json<StatusInput>,
+) -> ApiResult<Json<Incident>> {
+    let key = record_key(&id, "incident");
+    let current = found(
+        IncidentRepository::get(&state.db.client, key).await?,
+        "Incident",
+        &id,
+    )?;
+    if !current.status.can_transition_to(input.status) {
+        return Err(ApiError::conflict(format!(
+            "Cannot move incident from {} to {}",
+            current.status, input.status
+        )));
+    }
+    let author = persona(&state, &jar).await;
+    let updated = set_status(&state, key, input.status, input.note.as_deref(), author).await?;
+    Ok(Json(found(updated, "Incident", &id)?))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignInput {
+    /// Person to assign, as `person:key` or `key`; `null` to unassign
+    pub assignee_id: Option<String>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/incidents/{id}/assignee",
+    tag = "incidents",
+    params(("id" = String, Path, description = "Incident id (`incident:key` or `key`)")),
+    request_body = AssignInput,
+    responses(
+        (status = 200, description = "Assignee changed", body = Incident),
+        (status = 400, description = "Unknown person", body = ApiError),
+        (status = 404, description = "Incident not found", body = ApiError),
+    )
+)]
+pub async fn update_assignee(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Json(input): Json<AssignInput>,
+) -> ApiResult<Json<Incident>> {
+    let key = record_key(&id, "incident");
+    if let Some(assignee) = &input.assignee_id {
+        let person =
+            GeoRepository::get_person_by_id(&state.db.client, record_key(assignee, "person"))
+                .await?;
+        if person.is_none() {
+            return Err(ApiError::bad_request(format!(
+                "Person '{assignee}' not found"
+            )));
+        }
+    }
+    let author = persona(&state, &jar).await;
+    let updated = assign(&state, key, input.assignee_id.as_deref(), author).await?;
+    Ok(Json(found(updated, "Incident", &id)?))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NoteInput {
+    pub text: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/incidents/{id}/timeline",
+    tag = "incidents",
+    params(("id" = String, Path, description = "Incident id (`incident:key` or `key`)")),
+    request_body = NoteInput,
+    responses(
+        (status = 200, description = "Note added to the timeline", body = Incident),
+        (status = 400, description = "Empty note", body = ApiError),
+        (status = 404, description = "Incident not found", body = ApiError),
+    )
+)]
+pub async fn add_note(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Json(input): Json<NoteInput>,
+) -> ApiResult<Json<Incident>> {
+    if input.text.trim().is_empty() {
+        return Err(ApiError::bad_request("A note needs some text"));
+    }
+    let author = persona(&state, &jar).await;
+    let updated = note(&state, record_key(&id, "incident"), &input.text, author).await?;
+    Ok(Json(found(updated, "Incident", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/incidents/{id}",
+    tag = "incidents",
+    params(("id" = String, Path, description = "Incident id (`incident:key` or `key`)")),
+    responses(
+        (status = 204, description = "Incident deleted"),
+        (status = 404, description = "Incident not found", body = ApiError),
+    )
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let key = record_key(&id, "incident");
+    found(
+        IncidentRepository::get(&state.db.client, key).await?,
+        "Incident",
+        &id,
+    )?;
+    IncidentRepository::delete(&state.db.client, key).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,197 @@
+//! Chat client island
+//!
+//! Wraps the ui-core `ChatPanel`. Conversations and threads are loaded from
+//! `/api/chat/conversations`; new messages arrive and are sent over the
+//! `/api/chat/ws` WebSocket, so every open tab of a participant sees them
+//! without polling. Starting a conversation posts to the JSON API.
+//!
+//! Without the wasm bundle the panel renders empty.
+
+use leptos::prelude::*;
+use ui_core::features::{ChatConversation, ChatFeed, ChatMessageItem, ChatPanel};
+
+use super::encode;
+
+#[island]
+pub fn ChatClient(
+    /// Persona (person name) chatting
+    persona: String,
+    /// Other people a conversation can be started with
+    contacts: Vec<String>,
+) -> impl IntoView {
+    let conversations = RwSignal::new(Vec::<ChatConversation>::new());
+    let messages = RwSignal::new(Vec::<ChatMessageItem>::new());
+    let selected = RwSignal::new(None::<String>);
+    let contacts = RwSignal::new(contacts);
+    let query = StoredValue::new(format!("persona={}", encode(&persona)));
+
+    #[cfg(feature = "hydrate")]
+    let outbox = {
+        live::load_conversations(query.get_value(), conversations);
+        live::connect(query.get_value(), conversations, messages, selected)
+    };
+
+    // Reload the thread whenever the selection changes
+    Effect::new(move |_| {
+        let Some(id) = selected.get() else { return };
+        messages.set(Vec::new());
+        #[cfg(feature = "hydrate")]
+        live::load_messages(
+            format!(
+                "/api/chat/conversations/{id}/messages?{}",
+                query.get_value()
+            ),
+            messages,
+        );
+        #[cfg(not(feature = "hydrate"))]
+        let _ = id;
+    });
+
+    let on_send = Callback::new(move |body: String| {
+        let Some(conversation_id) = selected.get_untracked() else {
+            return;
+        };
+        let frame = serde_json::json!({ "conversation_id": conversation_id, "body": body });
+        #[cfg(feature = "hydrate")]
+        let _ = outbox.unbounded_send(frame.to_string());
+        #[cfg(not(feature = "hydrate"))]
+        let _ = frame;
+    });
+
+    let starter = persona.clone();
+    let on_start = Callback::new(move |name: String| {
+        let body = serde_json::json!({ "persona": starter, "participants": [name] });
+        #[cfg(feature = "hydrate")]
+        live::start(body, conversations, selected);
+        #[cfg(not(feature = "hydrate"))]
+        let _ = body;
+    });
+
+    let feed = ChatFeed {
+        conversations: conversations.into(),
+        selected,
+        messages: messages.into(),
+        contacts: contacts.into(),
+        on_send,
+        on_start,
+    };
+
+    view! { <ChatPanel me=persona feed=feed /> }
+}
+
+#[cfg(feature = "hydrate")]
+mod live {
+    use futures::channel::mpsc;
+    use futures::{SinkExt, StreamExt};
+    use gloo_net::websocket::{futures::WebSocket, Message};
+    use leptos::prelude::*;
+    use ui_core::features::{ChatConversation, ChatMessageItem};
+    use ui_core::utils::socket_url;
+
+    #[derive(serde::Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    enum ServerFrame {
+        Message(ChatMessageItem),
+        Error { message: String },
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+        let response = gloo_net::http::Request::get(url).send().await.ok()?;
+        response.json::<T>().await.ok()
+    }
+
+    pub fn load_conversations(query: String, conversations: RwSignal<Vec<ChatConversation>>) {
+        leptos::task::spawn_local(async move {
+            if let Some(list) = get_json(&format!("/api/chat/conversations?{query}")).await {
+                conversations.set(list);
+            }
+        });
+    }
+
+    pub fn load_messages(url: String, messages: RwSignal<Vec<ChatMessageItem>>) {
+        leptos::task::spawn_local(async move {
+            if let Some(thread) = get_json(&url).await {
+                messages.set(thread);
+            }
+        });
+    }
+
+    /// Open (or reuse) a conversation and select it
+    pub fn start(
+        body: serde_json::Value,
+        conversations: RwSignal<Vec<ChatConversation>>,
+        selected: RwSignal<Option<String>>,
+    ) {
+        leptos::task::spawn_local(async move {
+            let Ok(request) = gloo_net::http::Request::post("/api/chat/conversations").json(&body)
+            else {
+                return;
+            };
+            let Ok(response) = request.send().await else {
+                return;
+            };
+            let Ok(conversation) = response.json::<ChatConversation>().await else {
+                return;
+            };
+            let id = conversation.id.clone();
+            conversations.update(|list| {
+                if !list.iter().any(|c| c.id == id) {
+                    list.insert(0, conversation);
+                }
+            });
+            selected.set(Some(id));
+        });
+    }
+
+    /// Connect the chat socket; returns the queue outgoing frames are written to
+    pub fn connect(
+        query: String,
+        conversations: RwSignal<Vec<ChatConversation>>,
+        messages: RwSignal<Vec<ChatMessageItem>>,
+        selected: RwSignal<Option<String>>,
+    ) -> mpsc::UnboundedSender<String> {
+        let (outbox, mut queued) = mpsc::unbounded::<String>();
+        let socket =
+            socket_url(&format!("/api/chat/ws?{query}")).and_then(|url| WebSocket::open(&url).ok());
+        let Some(socket) = socket else { return outbox };
+        let (mut sink, mut stream) = socket.split();
+
+        leptos::task::spawn_local(async move {
+            while let Some(frame) = queued.next().await {
+                if sink.send(Message::Text(frame)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        leptos::task::spawn_local(async move {
+            while let Some(Ok(Message::Text(text))) = stream.next().await {
+                match serde_json::from_str::<ServerFrame>(&text) {
+                    Ok(ServerFrame::Message(message)) => {
+                        let known = conversations.try_update(|list| {
+                            let index =
+                                list.iter().position(|c| c.id == message.conversation_id)?;
+                            let mut conversation = list.remove(index);
+                            conversation.last_message = Some(message.body.clone());
+                            conversation.updated_at = message.sent_at.clone();
+                            list.insert(0, conversation);
+                            Some(())
+                        });
+                        if known.flatten().is_none() {
+                            load_conversations(query.clone(), conversations);
+                        }
+                        if selected.get_untracked().as_deref()
+                            == Some(message.conversation_id.as_str())
+                        {
+                            messages.update(|thread| thread.push(message));
+                        }
+                    }
+                    Ok(ServerFrame::Error { message }) => leptos::logging::warn!("chat: {message}"),
+                    Err(_) => {}
+                }
+            }
+        });
+
+        outbox
+    }
+}
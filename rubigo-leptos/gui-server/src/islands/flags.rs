@@ -0,0 +1,52 @@
+//! Feature flag toggles island
+//!
+//! Wraps the ui-core `FlagList` for the admin page. Toggles are sent to
+//! `PUT /api/flags/{name}` and the row updated from the response. The flags
+//! are also provided to the island's children so `use_flag` reflects a
+//! toggle without a reload.
+//!
+//! Without the wasm bundle the list renders but toggles are not saved.
+
+use leptos::prelude::*;
+use ui_core::features::{FlagList, FlagListFeed};
+use ui_core::hooks::{provide_flags, FeatureFlag};
+
+#[island]
+pub fn FlagToggles(
+    /// Flags as rendered by the server
+    flags: Vec<FeatureFlag>,
+) -> impl IntoView {
+    let set = provide_flags(flags);
+    let saving = RwSignal::new(None::<String>);
+
+    // `Some(on)` sets the flag, `None` resets it to its default
+    let save = move |name: String, enabled: Option<bool>| {
+        let url = format!("/api/flags/{}", name);
+        saving.set(Some(name));
+        #[cfg(feature = "hydrate")]
+        leptos::task::spawn_local(async move {
+            let body = serde_json::json!({ "enabled": enabled });
+            let sent = match gloo_net::http::Request::put(&url).json(&body) {
+                Ok(pending) => pending.send().await.ok().filter(|r| r.ok()),
+                Err(_) => None,
+            };
+            if let Some(response) = sent {
+                if let Ok(flag) = response.json::<FeatureFlag>().await {
+                    set.update(flag);
+                }
+            }
+            saving.set(None);
+        });
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (url, enabled, set);
+    };
+
+    let feed = FlagListFeed {
+        flags: set.all(),
+        saving: saving.into(),
+        on_toggle: Callback::new(move |(name, on): (String, bool)| save(name, Some(on))),
+        on_reset: Callback::new(move |name: String| save(name, None)),
+    };
+
+    view! { <FlagList feed=feed /> }
+}
@@ -0,0 +1,328 @@
+//! Floorplan editor island
+//!
+//! Draws a floor outline with its spaces as rectangles (all in metres).
+//! Spaces can be dragged and resized from the corner handle; each change is
+//! saved with `PUT /api/spaces/{id}/bounds`. A new outline can be clicked
+//! out point by point and saved with `PUT /api/floors/{id}/outline`. The
+//! heat layer shades spaces by occupancy.
+//!
+//! Without the wasm bundle the plan renders statically and the outline can
+//! still be replaced with the upload form next to it.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Grid the editor snaps to, in metres
+const SNAP: f64 = 0.5;
+
+/// Margin around the outline in the view box, in metres
+const MARGIN: f64 = 1.0;
+
+/// A space as the editor sees it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanSpace {
+    /// Record id (`space:key`)
+    pub id: String,
+    pub name: String,
+    /// `[x, y, width, height]`; `None` when not placed yet
+    pub rect: Option<[f64; 4]>,
+    pub people: usize,
+    pub racks: usize,
+    /// Occupancy relative to the busiest space on the floor, `0.0..=1.0`
+    pub heat: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DragMode {
+    Move,
+    Resize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    index: usize,
+    mode: DragMode,
+    start: (f64, f64),
+    orig: [f64; 4],
+}
+
+/// `[min_x, min_y, width, height]` of the outline plus margin
+fn view_box(outline: &[(f64, f64)]) -> [f64; 4] {
+    let (mut x0, mut y0, mut x1, mut y1) = (0.0_f64, 0.0_f64, 10.0_f64, 10.0_f64);
+    if let Some(&(x, y)) = outline.first() {
+        (x0, y0, x1, y1) = (x, y, x, y);
+        for &(x, y) in outline {
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x);
+            y1 = y1.max(y);
+        }
+    }
+    [
+        x0 - MARGIN,
+        y0 - MARGIN,
+        x1 - x0 + 2.0 * MARGIN,
+        y1 - y0 + 2.0 * MARGIN,
+    ]
+}
+
+fn snap(v: f64) -> f64 {
+    (v / SNAP).round() * SNAP
+}
+
+fn points_attr(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{x},{y}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Fill for the heat layer: blue when empty through to red at the busiest
+fn heat_fill(heat: f64) -> String {
+    format!(
+        "hsla({:.0}, 80%, 55%, 0.55)",
+        220.0 - 220.0 * heat.clamp(0.0, 1.0)
+    )
+}
+
+/// Pointer position in plan coordinates, honouring `preserveAspectRatio="xMidYMid meet"`
+fn plan_point(ev: &leptos::ev::PointerEvent, vb: [f64; 4]) -> Option<(f64, f64)> {
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::JsCast;
+        // Handlers sit on the svg and on shapes inside it; measure the svg
+        let target = ev.current_target()?.dyn_into::<web_sys::Element>().ok()?;
+        let svg = target.closest("svg").ok()??;
+        let r = svg.get_bounding_client_rect();
+        let scale = (r.width() / vb[2]).min(r.height() / vb[3]);
+        if scale <= 0.0 {
+            return None;
+        }
+        let ox = (r.width() - vb[2] * scale) / 2.0;
+        let oy = (r.height() - vb[3] * scale) / 2.0;
+        Some((
+            vb[0] + (ev.client_x() as f64 - r.left() - ox) / scale,
+            vb[1] + (ev.client_y() as f64 - r.top() - oy) / scale,
+        ))
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        let _ = (ev, vb);
+        None
+    }
+}
+
+/// Send a JSON `PUT` in the background, reporting failures to `status`
+fn put_json(url: String, body: serde_json::Value, status: RwSignal<Option<String>>) {
+    #[cfg(feature = "hydrate")]
+    leptos::task::spawn_local(async move {
+        let result = match gloo_net::http::Request::put(&url).json(&body) {
+            Ok(request) => request.send().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(response) if response.ok() => status.set(Some("Saved".to_string())),
+            Ok(response) => status.set(Some(format!("Save failed ({})", response.status()))),
+            Err(e) => status.set(Some(format!("Save failed: {e}"))),
+        }
+    });
+    #[cfg(not(feature = "hydrate"))]
+    let _ = (url, body, status);
+}
+
+#[island]
+pub fn FloorplanEditor(
+    /// Floor record id (`floor:key`)
+    floor_id: String,
+    /// Outline in metres
+    outline: Vec<(f64, f64)>,
+    spaces: Vec<PlanSpace>,
+) -> impl IntoView {
+    let outline = RwSignal::new(outline);
+    let spaces = RwSignal::new(spaces);
+    let drag = RwSignal::new(None::<Drag>);
+    let drawing = RwSignal::new(None::<Vec<(f64, f64)>>);
+    let show_heat = RwSignal::new(true);
+    let status = RwSignal::new(None::<String>);
+    let floor_id = StoredValue::new(floor_id);
+
+    let vb = Memo::new(move |_| view_box(&outline.get()));
+
+    let save_space = move |index: usize| {
+        let Some(space) = spaces.with_untracked(|s| s.get(index).cloned()) else {
+            return;
+        };
+        let bounds = space
+            .rect
+            .map(|[x, y, width, height]| serde_json::json!({ "x": x, "y": y, "width": width, "height": height }));
+        put_json(
+            format!("/api/spaces/{}/bounds", space.id),
+            serde_json::json!({ "bounds": bounds }),
+            status,
+        );
+    };
+
+    let on_pointer_down = move |ev: leptos::ev::PointerEvent| {
+        let Some(p) = plan_point(&ev, vb.get_untracked()) else {
+            return;
+        };
+        if drawing.with_untracked(Option::is_some) {
+            drawing.update(|d| {
+                if let Some(points) = d {
+                    points.push((snap(p.0), snap(p.1)));
+                }
+            });
+        }
+    };
+
+    let on_pointer_move = move |ev: leptos::ev::PointerEvent| {
+        let Some(d) = drag.get_untracked() else {
+            return;
+        };
+        let Some(p) = plan_point(&ev, vb.get_untracked()) else {
+            return;
+        };
+        let (dx, dy) = (p.0 - d.start.0, p.1 - d.start.1);
+        let [x, y, w, h] = d.orig;
+        let rect = match d.mode {
+            DragMode::Move => [snap(x + dx), snap(y + dy), w, h],
+            DragMode::Resize => [x, y, snap(w + dx).max(SNAP), snap(h + dy).max(SNAP)],
+        };
+        spaces.update(|s| {
+            if let Some(space) = s.get_mut(d.index) {
+                space.rect = Some(rect);
+            }
+        });
+    };
+
+    let on_pointer_up = move |_: leptos::ev::PointerEvent| {
+        if let Some(d) = drag.get_untracked() {
+            drag.set(None);
+            if spaces.with_untracked(|s| s.get(d.index).and_then(|sp| sp.rect)) != Some(d.orig) {
+                save_space(d.index);
+            }
+        }
+    };
+
+    let start_drag = move |ev: &leptos::ev::PointerEvent, index: usize, mode: DragMode| {
+        if drawing.with_untracked(Option::is_some) {
+            return;
+        }
+        let Some(start) = plan_point(ev, vb.get_untracked()) else {
+            return;
+        };
+        let Some(orig) = spaces.with_untracked(|s| s.get(index).and_then(|sp| sp.rect)) else {
+            return;
+        };
+        ev.stop_propagation();
+        drag.set(Some(Drag {
+            index,
+            mode,
+            start,
+            orig,
+        }));
+    };
+
+    let place = move |index: usize| {
+        let placed = spaces.with_untracked(|s| s.iter().filter(|sp| sp.rect.is_some()).count());
+        let [vx, vy, _, _] = vb.get_untracked();
+        let offset = (placed % 8) as f64 * 2.0;
+        spaces.update(|s| {
+            if let Some(space) = s.get_mut(index) {
+                space.rect = Some([vx + MARGIN + offset, vy + MARGIN + offset, 4.0, 3.0]);
+            }
+        });
+        save_space(index);
+    };
+
+    let finish_outline = move |_| {
+        let Some(points) = drawing.get_untracked() else {
+            return;
+        };
+        if points.len() < 3 {
+            status.set(Some("An outline needs at least 3 points".to_string()));
+            return;
+        }
+        drawing.set(None);
+        outline.set(points.clone());
+        let body = serde_json::json!({ "outline": points.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>() });
+        put_json(
+            format!("/api/floors/{}/outline", floor_id.get_value()),
+            body,
+            status,
+        );
+    };
+
+    view! {
+        <div class="floorplan-editor">
+            <div class="floorplan-toolbar">
+                {move || if drawing.with(Option::is_some) {
+                    view! {
+                        <span class="text-muted">"Click to add corners, then finish."</span>
+                        <button type="button" class="btn btn-sm btn-primary" on:click=finish_outline>"Finish Outline"</button>
+                        <button type="button" class="btn btn-sm btn-secondary" on:click=move |_| drawing.set(None)>"Cancel"</button>
+                    }.into_any()
+                } else {
+                    view! {
+                        <button type="button" class="btn btn-sm btn-secondary" on:click=move |_| drawing.set(Some(Vec::new()))>"Draw Outline"</button>
+                    }.into_any()
+                }}
+                <label class="floorplan-toggle">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || show_heat.get()
+                        on:change=move |_| show_heat.update(|v| *v = !*v)
+                    />
+                    " Occupancy heat"
+                </label>
+                <span class="text-muted" role="status">{move || status.get()}</span>
+            </div>
+
+            <svg
+                class="floorplan-canvas"
+                class:drawing=move || drawing.with(Option::is_some)
+                viewBox=move || { let [x, y, w, h] = vb.get(); format!("{x} {y} {w} {h}") }
+                preserveAspectRatio="xMidYMid meet"
+                on:pointerdown=on_pointer_down
+                on:pointermove=on_pointer_move
+                on:pointerup=on_pointer_up
+                on:pointerleave=on_pointer_up
+            >
+                <polygon class="floorplan-outline" points=move || points_attr(&outline.get())/>
+                {move || spaces.get().into_iter().enumerate().filter_map(|(i, space)| {
+                    let [x, y, w, h] = space.rect?;
+                    let fill = if show_heat.get() { heat_fill(space.heat) } else { "var(--bg-surface, #fff)".to_string() };
+                    Some(view! {
+                        <g class="floorplan-space">
+                            <rect
+                                x=x y=y width=w height=h
+                                style:fill=fill
+                                on:pointerdown=move |ev| start_drag(&ev, i, DragMode::Move)
+                            >
+                                <title>{format!("{}: {} people, {} racks", space.name, space.people, space.racks)}</title>
+                            </rect>
+                            <text x={x + 0.3} y={y + 0.9} font-size="0.7">{space.name.clone()}</text>
+                            <rect
+                                class="floorplan-handle"
+                                x={x + w - 0.4} y={y + h - 0.4} width="0.6" height="0.6"
+                                on:pointerdown=move |ev| start_drag(&ev, i, DragMode::Resize)
+                            />
+                        </g>
+                    })
+                }).collect_view()}
+                {move || drawing.get().map(|points| view! {
+                    <polyline class="floorplan-draft" points=points_attr(&points)/>
+                })}
+            </svg>
+
+            <div class="floorplan-unplaced">
+                {move || spaces.get().into_iter().enumerate().filter(|(_, s)| s.rect.is_none()).map(|(i, space)| view! {
+                    <button type="button" class="btn btn-sm btn-secondary" on:click=move |_| place(i)>
+                        {format!("Place {}", space.name)}
+                    </button>
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,85 @@
+//! Import client island
+//!
+//! Wraps the ui-core `ImportWizard`. The target's fields come from
+//! `GET /api/import/{resource}`; the wizard's request is posted to
+//! `POST /api/import/{resource}` and the returned report shown in the
+//! review step.
+//!
+//! Without the wasm bundle only the target picker renders.
+
+use leptos::prelude::*;
+use ui_core::features::{ImportFeed, ImportField, ImportReport, ImportRequest, ImportWizard};
+
+#[island]
+pub fn ImportClient(
+    /// Resources that accept imports; the first is selected
+    resources: Vec<String>,
+) -> impl IntoView {
+    let resource = RwSignal::new(resources.first().cloned().unwrap_or_default());
+    let fields = RwSignal::new(Vec::<ImportField>::new());
+    let report = RwSignal::new(None::<ImportReport>);
+    let busy = RwSignal::new(false);
+
+    Effect::new(move |_| {
+        let url = format!("/api/import/{}", resource.get());
+        fields.set(Vec::new());
+        report.set(None);
+        #[cfg(feature = "hydrate")]
+        leptos::task::spawn_local(async move {
+            let Ok(response) = gloo_net::http::Request::get(&url).send().await else {
+                return;
+            };
+            if let Ok(list) = response.json::<Vec<ImportField>>().await {
+                fields.set(list);
+            }
+        });
+        #[cfg(not(feature = "hydrate"))]
+        let _ = url;
+    });
+
+    let on_submit = Callback::new(move |request: ImportRequest| {
+        let url = format!("/api/import/{}", resource.get_untracked());
+        busy.set(true);
+        #[cfg(feature = "hydrate")]
+        leptos::task::spawn_local(async move {
+            let sent = match gloo_net::http::Request::post(&url).json(&request) {
+                Ok(pending) => pending.send().await.ok(),
+                Err(_) => None,
+            };
+            let outcome = match sent {
+                Some(response) => response.json::<ImportReport>().await.ok(),
+                None => None,
+            };
+            report.set(outcome);
+            busy.set(false);
+        });
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (url, request);
+    });
+
+    let feed = ImportFeed {
+        fields: fields.into(),
+        report: report.into(),
+        busy: busy.into(),
+        on_submit,
+    };
+
+    view! {
+        <div class="import-client">
+            <label class="import-target">
+                "Import into "
+                <select on:change=move |ev| resource.set(event_target_value(&ev))>
+                    {resources
+                        .into_iter()
+                        .map(|name| view! { <option value=name.clone()>{name.clone()}</option> })
+                        .collect_view()}
+                </select>
+            </label>
+            // A fresh wizard per target so mappings don't carry over
+            {move || {
+                resource.track();
+                view! { <ImportWizard feed=feed /> }
+            }}
+        </div>
+    }
+}
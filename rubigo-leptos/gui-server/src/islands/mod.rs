@@ -0,0 +1,258 @@
+//! Hydration islands
+//!
+//! Interactive components that are server-rendered with the rest of the
+//! page and then hydrated in the browser. Everything outside an island stays
+//! static HTML, so only these components ship to the wasm client.
+//!
+//! Each island degrades gracefully: without the wasm bundle, forms still post
+//! and redirect, and filters/dialogs simply render their static content.
+
+use leptos::prelude::*;
+use ui_core::primitives::SearchInput;
+
+mod chat;
+mod flags;
+mod floorplan;
+mod import;
+mod notifications;
+mod presence;
+
+pub use chat::ChatClient;
+pub use flags::FlagToggles;
+pub use floorplan::{FloorplanEditor, PlanSpace};
+pub use import::ImportClient;
+pub use notifications::NotificationCenter;
+pub use presence::{PresenceClient, PresenceNotice};
+
+/// Percent-encode a query value
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Client-side row filter for a server-rendered table
+///
+/// Hides `tbody tr` rows of the table with id `target` whose text does not
+/// contain the query (case-insensitive).
+#[island]
+pub fn TableFilter(
+    /// DOM id of the table to filter
+    target: String,
+) -> impl IntoView {
+    let query = RwSignal::new(String::new());
+
+    Effect::new(move |_| {
+        let q = query.get().to_lowercase();
+        #[cfg(feature = "hydrate")]
+        dom::filter_rows(&target, &q);
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (&target, q);
+    });
+
+    view! {
+        <div class="table-filter">
+            <SearchInput value=query placeholder="Filter..." />
+        </div>
+    }
+}
+
+/// Form that submits in the background instead of navigating
+///
+/// The handlers behind `action` keep their form + redirect contract; the
+/// island posts the same urlencoded body with `fetch`, follows the redirect,
+/// and then patches the current page:
+///
+/// - `swap`: CSS selector replaced with the matching element of the response
+/// - `remove_closest`: ancestor of the form removed on success (e.g. `tr`)
+#[island]
+pub fn AsyncForm(
+    /// Form handler URL
+    action: String,
+    /// CSS class for the form element
+    #[prop(optional, into)]
+    class: Option<String>,
+    /// Selector to replace from the response page
+    #[prop(optional, into)]
+    swap: Option<String>,
+    /// Ancestor selector to remove after a successful submit
+    #[prop(optional, into)]
+    remove_closest: Option<String>,
+    children: Children,
+) -> impl IntoView {
+    let pending = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        #[cfg(feature = "hydrate")]
+        {
+            ev.prevent_default();
+            if pending.get_untracked() {
+                return;
+            }
+            let swap = swap.clone();
+            let remove_closest = remove_closest.clone();
+            pending.set(true);
+            error.set(None);
+            leptos::task::spawn_local(async move {
+                let result = dom::submit(&ev, swap.as_deref(), remove_closest.as_deref()).await;
+                if let Err(e) = result {
+                    error.set(Some(e));
+                }
+                pending.set(false);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (ev, &swap, &remove_closest);
+    };
+
+    view! {
+        <form
+            action=action
+            method="post"
+            class=class.unwrap_or_default()
+            aria-busy=move || pending.get().to_string()
+            on:submit=on_submit
+        >
+            {children()}
+            {move || error.get().map(|e| view! { <span class="form-error" role="alert">{e}</span> })}
+        </form>
+    }
+}
+
+/// Button that opens a dialog without a page navigation
+///
+/// Replaces `?modal=...` links: the dialog body is server-rendered once and
+/// toggled on the client.
+#[island]
+pub fn DialogTrigger(
+    /// Trigger button label
+    label: String,
+    /// Dialog title
+    title: String,
+    /// CSS class for the trigger button
+    #[prop(optional, into)]
+    class: Option<String>,
+    children: Children,
+) -> impl IntoView {
+    let open = RwSignal::new(false);
+
+    view! {
+        <button
+            type="button"
+            class=class.unwrap_or_else(|| "btn btn-primary".to_string())
+            aria-haspopup="dialog"
+            aria-expanded=move || open.get().to_string()
+            on:click=move |_| open.set(true)
+        >
+            {label}
+        </button>
+        <div
+            class="modal-overlay"
+            style:display=move || if open.get() { "flex" } else { "none" }
+            on:click=move |_| open.set(false)
+            on:keydown=move |ev: leptos::ev::KeyboardEvent| {
+                if ev.key() == "Escape" {
+                    open.set(false);
+                }
+            }
+        >
+            <div
+                class="modal-content"
+                role="dialog"
+                aria-modal="true"
+                on:click=|ev| ev.stop_propagation()
+            >
+                <div class="modal-header">
+                    <h3>{title}</h3>
+                    <button type="button" class="modal-close" on:click=move |_| open.set(false)>"×"</button>
+                </div>
+                <div class="modal-body">{children()}</div>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(feature = "hydrate")]
+mod dom {
+    use wasm_bindgen::JsCast;
+
+    /// Toggle `hidden` on table rows that do not match `query`
+    pub fn filter_rows(table_id: &str, query: &str) {
+        let document = leptos::prelude::document();
+        let Ok(rows) = document.query_selector_all(&format!("#{table_id} tbody tr")) else {
+            return;
+        };
+        for i in 0..rows.length() {
+            let Some(row) = rows
+                .get(i)
+                .and_then(|n| n.dyn_into::<web_sys::Element>().ok())
+            else {
+                continue;
+            };
+            let text = row.text_content().unwrap_or_default().to_lowercase();
+            if query.is_empty() || text.contains(query) {
+                let _ = row.remove_attribute("hidden");
+            } else {
+                let _ = row.set_attribute("hidden", "");
+            }
+        }
+    }
+
+    /// Post the submitted form and patch the page from the response
+    pub async fn submit(
+        ev: &web_sys::SubmitEvent,
+        swap: Option<&str>,
+        remove_closest: Option<&str>,
+    ) -> Result<(), String> {
+        let form = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::HtmlFormElement>().ok())
+            .ok_or("submit target is not a form")?;
+
+        let data = web_sys::FormData::new_with_form(&form).map_err(|_| "could not read form")?;
+        let body = web_sys::UrlSearchParams::new_with_str_sequence_sequence(&data)
+            .map_err(|_| "could not encode form")?;
+
+        let response = gloo_net::http::Request::post(&form.action())
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(String::from(body.to_string()))
+            .map_err(|e| e.to_string())?
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.ok() {
+            return Err(format!("Request failed ({})", response.status()));
+        }
+
+        if let Some(selector) = swap {
+            let html = response.text().await.map_err(|e| e.to_string())?;
+            let parsed = web_sys::DomParser::new()
+                .and_then(|p| p.parse_from_string(&html, web_sys::SupportedType::TextHtml))
+                .map_err(|_| "could not parse response")?;
+            let document = leptos::prelude::document();
+            if let (Ok(Some(current)), Ok(Some(next))) = (
+                document.query_selector(selector),
+                parsed.query_selector(selector),
+            ) {
+                current.set_inner_html(&next.inner_html());
+            }
+        }
+
+        if let Some(selector) = remove_closest {
+            if let Ok(Some(el)) = form.closest(selector) {
+                el.remove();
+            }
+        }
+
+        form.reset();
+        Ok(())
+    }
+}
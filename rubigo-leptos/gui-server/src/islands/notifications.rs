@@ -0,0 +1,142 @@
+//! Notification center island
+//!
+//! Loads the persona's inbox from `GET /api/notifications`, then listens on
+//! `GET /api/notifications/stream` for new entries and unread counts. Reads
+//! are posted back to `/api/notifications/{id}/read` and `/read-all`; the
+//! list is updated optimistically and the next `unread` event corrects it.
+//!
+//! Without the wasm bundle the bell renders with an empty inbox.
+
+use leptos::prelude::*;
+use ui_core::features::{NotificationBell, NotificationFeed, NotificationItem};
+
+use super::encode;
+
+/// Send a JSON `POST` in the background; failures only cost the optimistic update
+fn post_json(url: String, body: serde_json::Value) {
+    #[cfg(feature = "hydrate")]
+    leptos::task::spawn_local(async move {
+        if let Ok(request) = gloo_net::http::Request::post(&url).json(&body) {
+            let _ = request.send().await;
+        }
+    });
+    #[cfg(not(feature = "hydrate"))]
+    let _ = (url, body);
+}
+
+#[island]
+pub fn NotificationCenter(
+    /// Persona (person name) whose inbox to show
+    persona: String,
+) -> impl IntoView {
+    let items = RwSignal::new(Vec::<NotificationItem>::new());
+    let unread = RwSignal::new(0_usize);
+    let query = format!("persona={}", encode(&persona));
+
+    #[cfg(feature = "hydrate")]
+    {
+        let list_url = format!("/api/notifications?{query}");
+        leptos::task::spawn_local(async move {
+            let Ok(response) = gloo_net::http::Request::get(&list_url).send().await else {
+                return;
+            };
+            if let Ok(inbox) = response.json::<Vec<NotificationItem>>().await {
+                items.set(inbox);
+            }
+        });
+        live::listen(format!("/api/notifications/stream?{query}"), items, unread);
+    }
+
+    let read_persona = persona.clone();
+    let on_read = Callback::new(move |id: String| {
+        let was_unread = items
+            .try_update(|list| {
+                let item = list.iter_mut().find(|n| n.id == id)?;
+                Some(!std::mem::replace(&mut item.read, true))
+            })
+            .flatten()
+            .unwrap_or(false);
+        if was_unread {
+            unread.update(|n| *n = n.saturating_sub(1));
+            post_json(
+                format!("/api/notifications/{id}/read"),
+                serde_json::json!({ "persona": read_persona }),
+            );
+        }
+    });
+
+    let on_read_all = Callback::new(move |_| {
+        items.update(|list| list.iter_mut().for_each(|n| n.read = true));
+        unread.set(0);
+        post_json(
+            "/api/notifications/read-all".to_string(),
+            serde_json::json!({ "persona": persona }),
+        );
+    });
+
+    let feed = NotificationFeed {
+        items: items.into(),
+        unread: unread.into(),
+        on_read,
+        on_read_all,
+    };
+
+    #[cfg(not(feature = "hydrate"))]
+    let _ = query;
+
+    view! { <NotificationBell feed=feed /> }
+}
+
+#[cfg(feature = "hydrate")]
+mod live {
+    use futures::StreamExt;
+    use gloo_net::eventsource::futures::EventSource;
+    use leptos::prelude::*;
+    use ui_core::features::NotificationItem;
+
+    /// Entries kept in the panel
+    const INBOX_LIMIT: usize = 50;
+
+    #[derive(serde::Deserialize)]
+    struct Unread {
+        unread: usize,
+    }
+
+    /// Apply `notification` and `unread` events from the stream to the signals
+    pub fn listen(url: String, items: RwSignal<Vec<NotificationItem>>, unread: RwSignal<usize>) {
+        let Ok(mut source) = EventSource::new(&url) else {
+            return;
+        };
+        let (Ok(notifications), Ok(counts)) =
+            (source.subscribe("notification"), source.subscribe("unread"))
+        else {
+            return;
+        };
+        let mut events = futures::stream::select(notifications, counts);
+        leptos::task::spawn_local(async move {
+            // Owned by the task so the connection lives as long as the page
+            let _source = source;
+            while let Some(Ok((kind, message))) = events.next().await {
+                let Some(data) = message.data().as_string() else {
+                    continue;
+                };
+                match kind.as_str() {
+                    "notification" => {
+                        if let Ok(item) = serde_json::from_str::<NotificationItem>(&data) {
+                            items.update(|list| {
+                                list.insert(0, item);
+                                list.truncate(INBOX_LIMIT);
+                            });
+                        }
+                    }
+                    "unread" => {
+                        if let Ok(count) = serde_json::from_str::<Unread>(&data) {
+                            unread.set(count.unread);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+}
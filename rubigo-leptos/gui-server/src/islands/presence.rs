@@ -0,0 +1,208 @@
+//! Presence islands
+//!
+//! `PresenceClient` sits in the header: it registers the persona on the
+//! `/api/presence/ws` WebSocket, reports the open tab and record, and shows
+//! who else is online. `PresenceNotice` sits in a detail panel and warns
+//! when someone else is editing the record it shows. Both read one shared
+//! roster, kept by the client's socket.
+//!
+//! The page comes from the `tab` query parameter; `action=edit&id=...`
+//! marks the tab's record as being edited. Page scripts report records
+//! opened in place by dispatching a `rubigo:presence` event whose detail is
+//! JSON, e.g. `{"entity":"asset:42"}`, or `{}` when the panel closes.
+//!
+//! Without the wasm bundle both render nothing useful: an empty indicator
+//! and no notice.
+
+use leptos::prelude::*;
+use serde::Deserialize;
+use ui_core::features::presence::{entity_key, PresenceUpdate, Roster};
+use ui_core::features::{OnlineIndicator, PresenceBanner, PresenceFeed};
+
+/// Window event page scripts dispatch when a record opens or closes in place
+const PRESENCE_EVENT: &str = "rubigo:presence";
+
+/// Detail of a [`PRESENCE_EVENT`]
+#[derive(Debug, Default, Deserialize)]
+struct OpenRecord {
+    entity: Option<String>,
+    #[serde(default)]
+    editing: bool,
+}
+
+/// Page and record named by a `?tab=...&action=edit&id=...` query string
+fn update_from_query(query: &str) -> PresenceUpdate {
+    let mut update = PresenceUpdate::default();
+    let (mut editing, mut id) = (false, None);
+    for (key, value) in query
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+    {
+        match key {
+            "tab" => update.page = value.to_string(),
+            "action" => editing = value == "edit",
+            "id" => id = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    if update.page.is_empty() {
+        update.page = "home".to_string();
+    }
+    if let (true, Some(id)) = (editing, id) {
+        // Tabs are plural ("assets"), records singular ("asset:42")
+        update.entity = Some(entity_key(update.page.trim_end_matches('s'), &id));
+        update.editing = true;
+    }
+    update
+}
+
+/// The roster shared by every presence island on the page
+fn shared_roster() -> ArcRwSignal<Roster> {
+    thread_local! {
+        static ROSTER: ArcRwSignal<Roster> = ArcRwSignal::new(Roster::default());
+    }
+    ROSTER.with(Clone::clone)
+}
+
+fn feed() -> PresenceFeed {
+    let roster = shared_roster();
+    PresenceFeed {
+        roster: Signal::derive(move || roster.get()),
+    }
+}
+
+#[island]
+pub fn PresenceClient(
+    /// Persona (person name) to register
+    persona: String,
+) -> impl IntoView {
+    #[cfg(feature = "hydrate")]
+    live::connect(&persona, shared_roster());
+
+    view! { <OnlineIndicator me=persona feed=feed() /> }
+}
+
+#[island]
+pub fn PresenceNotice(
+    /// Persona (person name) viewing the panel
+    persona: String,
+    /// Record kind the panel shows, e.g. "asset"
+    noun: String,
+) -> impl IntoView {
+    let entity = RwSignal::new(None::<String>);
+
+    #[cfg(feature = "hydrate")]
+    {
+        let prefix = format!("{noun}:");
+        let handle = window_event_listener_untyped(PRESENCE_EVENT, move |ev| {
+            let open = live::open_record(&ev);
+            entity.set(open.entity.filter(|key| key.starts_with(&prefix)));
+        });
+        on_cleanup(move || handle.remove());
+    }
+
+    view! { <PresenceBanner me=persona entity=entity noun=noun feed=feed() /> }
+}
+
+#[cfg(feature = "hydrate")]
+mod live {
+    use futures::channel::mpsc;
+    use futures::{SinkExt, StreamExt};
+    use gloo_net::websocket::{futures::WebSocket, Message};
+    use leptos::prelude::*;
+    use ui_core::features::presence::{PresenceUpdate, Roster};
+    use ui_core::utils::socket_url;
+    use wasm_bindgen::JsCast;
+
+    use super::{update_from_query, OpenRecord, PRESENCE_EVENT};
+    use crate::islands::encode;
+
+    #[derive(serde::Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    enum ServerFrame {
+        Roster(Roster),
+        Error { message: String },
+    }
+
+    /// Record named by a [`PRESENCE_EVENT`]; nothing if the detail is not JSON
+    pub fn open_record(ev: &web_sys::Event) -> OpenRecord {
+        ev.dyn_ref::<web_sys::CustomEvent>()
+            .and_then(|ev| ev.detail().as_string())
+            .and_then(|detail| serde_json::from_str(&detail).ok())
+            .unwrap_or_default()
+    }
+
+    /// Register on the presence socket and keep `roster` current
+    pub fn connect(persona: &str, roster: ArcRwSignal<Roster>) {
+        let path = format!(
+            "{}?persona={}",
+            ui_core::features::presence::PRESENCE_PATH,
+            encode(persona)
+        );
+        let Some(socket) = socket_url(&path).and_then(|url| WebSocket::open(&url).ok()) else {
+            return;
+        };
+        let (mut sink, mut stream) = socket.split();
+        let (outbox, mut queued) = mpsc::unbounded::<PresenceUpdate>();
+
+        let page = update_from_query(&window().location().search().unwrap_or_default());
+        let _ = outbox.unbounded_send(page.clone());
+
+        // A record opened in place replaces the page's own until it closes
+        let handle = window_event_listener_untyped(PRESENCE_EVENT, move |ev| {
+            let open = open_record(&ev);
+            let update = match open.entity {
+                Some(entity) => PresenceUpdate {
+                    entity: Some(entity),
+                    editing: open.editing,
+                    ..page.clone()
+                },
+                None => page.clone(),
+            };
+            let _ = outbox.unbounded_send(update);
+        });
+        on_cleanup(move || handle.remove());
+
+        leptos::task::spawn_local(async move {
+            while let Some(update) = queued.next().await {
+                let Ok(text) = serde_json::to_string(&update) else {
+                    continue;
+                };
+                if sink.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        leptos::task::spawn_local(async move {
+            while let Some(Ok(Message::Text(text))) = stream.next().await {
+                match serde_json::from_str::<ServerFrame>(&text) {
+                    Ok(ServerFrame::Roster(current)) => roster.set(current),
+                    Ok(ServerFrame::Error { message }) => {
+                        leptos::logging::warn!("presence: {message}")
+                    }
+                    Err(_) => {}
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_names_page_and_edited_record() {
+        assert_eq!(update_from_query("").page, "home");
+
+        let viewing = update_from_query("?tab=assets&id=42");
+        assert_eq!(viewing.page, "assets");
+        assert_eq!(viewing.entity, None);
+
+        let editing = update_from_query("?tab=assets&action=edit&id=42");
+        assert_eq!(editing.entity.as_deref(), Some("asset:42"));
+        assert!(editing.editing);
+    }
+}
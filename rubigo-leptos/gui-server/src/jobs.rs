@@ -0,0 +1,300 @@
+//! Background job queue
+//!
+//! Long-running work (city and GeoJSON imports) runs through a single worker
+//! instead of detached `tokio::spawn` tasks. Every job gets a `job` record in
+//! the database with status, progress and attempt count, so the Jobs page and
+//! `/api/jobs` can show what is running and what failed.
+//!
+//! Failed attempts are retried with exponential backoff up to
+//! [`DEFAULT_MAX_ATTEMPTS`]; a job that exhausts its attempts can be retried
+//! manually from the Jobs page. Finished jobs are announced to everyone's
+//! notification inbox, except quiet ones whose caller reports the outcome
+//! itself (scheduled runs).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use nexosim_hybrid::database::jobs::{Job, JobRepository, JobStatus};
+use nexosim_hybrid::database::notifications::{Notification, NotificationKind};
+use nexosim_hybrid::database::{Database, DbClient};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use crate::notifications::NotificationHub;
+
+/// Attempts made before a job is marked failed
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Base delay between attempts; doubles after each failure
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+type JobFuture = Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send>>;
+type JobFn = Arc<dyn Fn(JobContext) -> JobFuture + Send + Sync>;
+
+struct QueuedJob {
+    key: String,
+    task: JobFn,
+    /// Tell everyone how the job ended
+    announce: bool,
+    done: oneshot::Sender<JobStatus>,
+}
+
+/// Handle passed to a running job
+#[derive(Clone)]
+pub struct JobContext {
+    db: Arc<Database>,
+    key: String,
+}
+
+impl JobContext {
+    pub fn db(&self) -> &DbClient {
+        &self.db.client
+    }
+
+    /// Record completion percentage (0-100)
+    pub async fn progress(&self, percent: u8) {
+        let percent = percent.min(100);
+        update_job(&self.db, &self.key, |job| job.progress = percent).await;
+    }
+
+    /// Record progress as `done` of `total` steps
+    pub async fn step(&self, done: usize, total: usize) {
+        if total > 0 {
+            self.progress((done * 100 / total) as u8).await;
+        }
+    }
+}
+
+/// Completion handle returned by [`JobQueue::enqueue`]
+pub struct JobHandle {
+    pub key: String,
+    done: oneshot::Receiver<JobStatus>,
+}
+
+impl JobHandle {
+    /// Wait until the job succeeds or exhausts its attempts
+    pub async fn wait(self) -> JobStatus {
+        self.done.await.unwrap_or(JobStatus::Failed)
+    }
+}
+
+/// Sequential background job runner
+#[derive(Clone)]
+pub struct JobQueue {
+    db: Arc<Database>,
+    tx: mpsc::UnboundedSender<QueuedJob>,
+    /// Task bodies by job key, kept for manual retries
+    tasks: Arc<Mutex<HashMap<String, JobFn>>>,
+}
+
+impl JobQueue {
+    /// Create the queue and spawn its worker
+    pub fn start(db: Arc<Database>, notifications: NotificationHub) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(worker(db.clone(), notifications, rx));
+        Self {
+            db,
+            tx,
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Record a new job and queue it behind any running work
+    pub async fn enqueue<F, Fut>(
+        &self,
+        kind: &str,
+        label: &str,
+        task: F,
+    ) -> anyhow::Result<JobHandle>
+    where
+        F: Fn(JobContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        self.queue(kind, label, true, task).await
+    }
+
+    /// Like [`JobQueue::enqueue`], without announcing the outcome
+    pub async fn enqueue_quiet<F, Fut>(
+        &self,
+        kind: &str,
+        label: &str,
+        task: F,
+    ) -> anyhow::Result<JobHandle>
+    where
+        F: Fn(JobContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        self.queue(kind, label, false, task).await
+    }
+
+    async fn queue<F, Fut>(
+        &self,
+        kind: &str,
+        label: &str,
+        announce: bool,
+        task: F,
+    ) -> anyhow::Result<JobHandle>
+    where
+        F: Fn(JobContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        let now = crate::clock::now();
+        let job = JobRepository::create(
+            &self.db.client,
+            Job {
+                id: None,
+                kind: kind.to_string(),
+                label: label.to_string(),
+                status: JobStatus::Queued,
+                progress: 0,
+                attempts: 0,
+                max_attempts: DEFAULT_MAX_ATTEMPTS,
+                error: None,
+                result: None,
+                created_at: now.clone(),
+                updated_at: now,
+            },
+        )
+        .await?;
+
+        let task: JobFn = Arc::new(move |ctx| Box::pin(task(ctx)));
+        self.tasks.lock().await.insert(job.key(), task.clone());
+        self.submit(job.key(), task, announce)
+    }
+
+    /// Re-queue a failed job with a fresh set of attempts
+    pub async fn retry(&self, key: &str) -> anyhow::Result<JobHandle> {
+        let job = JobRepository::get_by_id(&self.db.client, key)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Job '{}' not found", key))?;
+        if job.status != JobStatus::Failed {
+            anyhow::bail!(
+                "Job '{}' is {}, only failed jobs can be retried",
+                key,
+                job.status
+            );
+        }
+        let task = self.tasks.lock().await.get(key).cloned().ok_or_else(|| {
+            anyhow::anyhow!("Job '{}' has no task registered in this process", key)
+        })?;
+
+        update_job(&self.db, key, |job| {
+            job.status = JobStatus::Queued;
+            job.attempts = 0;
+            job.progress = 0;
+            job.error = None;
+        })
+        .await;
+        // Whoever retried by hand wants to hear how it went
+        self.submit(key.to_string(), task, true)
+    }
+
+    fn submit(&self, key: String, task: JobFn, announce: bool) -> anyhow::Result<JobHandle> {
+        let (done, rx) = oneshot::channel();
+        self.tx
+            .send(QueuedJob {
+                key: key.clone(),
+                task,
+                announce,
+                done,
+            })
+            .map_err(|_| anyhow::anyhow!("Job worker has stopped"))?;
+        Ok(JobHandle { key, done: rx })
+    }
+}
+
+async fn worker(
+    db: Arc<Database>,
+    notifications: NotificationHub,
+    mut rx: mpsc::UnboundedReceiver<QueuedJob>,
+) {
+    while let Some(queued) = rx.recv().await {
+        let status = run_job(&db, &queued).await;
+        if queued.announce {
+            announce(&db, &notifications, &queued.key).await;
+        }
+        let _ = queued.done.send(status);
+    }
+}
+
+/// Tell everyone a job has finished, with its result or last error
+async fn announce(db: &Database, notifications: &NotificationHub, key: &str) {
+    let job = match JobRepository::get_by_id(&db.client, key).await {
+        Ok(Some(job)) => job,
+        _ => return,
+    };
+    let (title, body) = match job.status {
+        JobStatus::Succeeded => (format!("{} finished", job.label), job.result),
+        _ => (format!("{} failed", job.label), job.error),
+    };
+    let mut notification =
+        Notification::new(NotificationKind::ImportFinished, None, title).with_link("/?tab=jobs");
+    notification.body = body;
+    notifications.notify(notification).await;
+}
+
+async fn run_job(db: &Arc<Database>, queued: &QueuedJob) -> JobStatus {
+    let ctx = JobContext {
+        db: db.clone(),
+        key: queued.key.clone(),
+    };
+
+    for attempt in 1..=DEFAULT_MAX_ATTEMPTS {
+        update_job(db, &queued.key, |job| {
+            job.status = JobStatus::Running;
+            job.attempts = attempt;
+        })
+        .await;
+
+        match (queued.task)(ctx.clone()).await {
+            Ok(summary) => {
+                tracing::info!("Job {} succeeded: {}", queued.key, summary);
+                update_job(db, &queued.key, |job| {
+                    job.status = JobStatus::Succeeded;
+                    job.progress = 100;
+                    job.error = None;
+                    job.result = Some(summary);
+                })
+                .await;
+                return JobStatus::Succeeded;
+            }
+            Err(e) => {
+                let last = attempt == DEFAULT_MAX_ATTEMPTS;
+                tracing::warn!("Job {} attempt {} failed: {}", queued.key, attempt, e);
+                update_job(db, &queued.key, |job| {
+                    job.status = if last {
+                        JobStatus::Failed
+                    } else {
+                        JobStatus::Retrying
+                    };
+                    job.error = Some(e.to_string());
+                })
+                .await;
+                if !last {
+                    tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+                }
+            }
+        }
+    }
+
+    JobStatus::Failed
+}
+
+/// Read-modify-write a job record; failures are logged, not propagated
+async fn update_job(db: &Database, key: &str, apply: impl FnOnce(&mut Job)) {
+    let result = async {
+        let mut job = JobRepository::get_by_id(&db.client, key)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("missing record"))?;
+        apply(&mut job);
+        job.updated_at = crate::clock::now();
+        JobRepository::update(&db.client, key, job).await
+    }
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!("Could not update job {}: {}", key, e);
+    }
+}
@@ -0,0 +1,15 @@
+//! GUI Server - shared library
+//!
+//! Holds the hydration islands used by the server-rendered pages. The server
+//! binary links this crate with `ssr`; `./build-hydrate.sh` compiles it to
+//! wasm with `hydrate` and places the bundle under `assets/pkg/`.
+
+pub mod islands;
+
+/// Wasm entry point: hydrates every `<leptos-island>` on the page
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn hydrate() {
+    console_error_panic_hook::set_once();
+    leptos::mount::hydrate_islands();
+}
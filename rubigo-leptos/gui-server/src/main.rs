@@ -2,7 +2,7 @@
 
 #![recursion_limit = "256"]
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     response::IntoResponse,
     Router,
 };
@@ -12,9 +12,45 @@ use tokio::sync::Mutex;
 mod api;
 mod app;
 mod cached_geo;
+mod capacity;
+mod chat;
+mod clock;
+mod collab;
 mod components;
+mod email;
+mod export;
+mod flags;
+mod graphql;
+mod health;
+mod iac;
+mod identity;
+mod import;
+mod incidents;
+mod jobs;
+mod ndjson;
+mod netbox;
+mod notifications;
+mod oncall;
+mod openapi;
+mod pdf;
+mod presence;
+mod query;
+mod render_cache;
+mod reports;
+mod request_log;
+mod sandbox;
+mod scheduler;
+mod simulation;
+mod static_assets;
+mod sync;
+mod telemetry;
+mod tiles;
+mod vector_tile;
+mod webhooks;
+mod xlsx;
 
 use nexosim_hybrid::database::Database;
+use nexosim_hybrid::database::jobs::JobStatus;
 
 // Re-export for use in components
 pub use nexosim_hybrid::config::{ComponentConfig, ComponentType, ConnectionConfig};
@@ -31,35 +67,83 @@ pub struct AppState {
     pub current_persona: Arc<Mutex<Option<String>>>,
     /// Dev mode flag - enables persona switcher
     pub dev_mode: bool,
+    /// Layered runtime settings (defaults, rubigo.toml, env, CLI flags)
+    pub settings: Arc<config::Settings>,
+    /// Startup / shutdown state for the readiness probe
+    pub readiness: health::Readiness,
+    /// Background job runner for data imports
+    pub jobs: jobs::JobQueue,
+    /// Notification store and live push to inboxes
+    pub notifications: notifications::NotificationHub,
+    /// Chat message store and WebSocket delivery
+    pub chat: chat::ChatBroker,
+    /// Outbox of invite, report and mention emails, and who wants which
+    pub email: email::EmailService,
+    /// Connected personas and what each tab has open
+    pub presence: presence::PresenceHub,
+    /// Unsaved form edits relayed between editors of the same record
+    pub collab: collab::CollabHub,
+    /// GraphQL schema served at `/api/graphql`
+    pub graphql: graphql::ApiSchema,
+    /// Rendered tabs of the root page, reused until their data changes
+    pub render_cache: render_cache::RenderCache,
+    /// Registered webhooks and delivery of events to them
+    pub webhooks: webhooks::WebhookHub,
+    /// OIDC sign-in and LDAP personnel sync
+    pub identity: identity::IdentityService,
+    /// Polls components switched to live telemetry
+    pub telemetry: telemetry::TelemetryPoller,
+    /// Imports sites, racks, devices and cables from NetBox
+    pub netbox: netbox::NetboxImporter,
 }
 
+/// Lines kept in the log buffer; the oldest are dropped first
+const LOG_BUFFER_CAPACITY: usize = 1000;
+
 /// Simple thread-safe log buffer
 #[derive(Clone, Default)]
 pub struct LogBuffer {
-    inner: Arc<Mutex<Vec<String>>>,
+    inner: Arc<Mutex<std::collections::VecDeque<String>>>,
 }
 
 impl LogBuffer {
     pub async fn append(&self, msg: String) {
         let mut guard = self.inner.lock().await;
-        guard.push(msg);
+        if guard.len() == LOG_BUFFER_CAPACITY {
+            guard.pop_front();
+        }
+        guard.push_back(msg);
     }
-    
-    #[allow(dead_code)]
+
+    /// Up to `limit` most recent lines, newest first
+    pub async fn recent(&self, limit: usize) -> Vec<String> {
+        let guard = self.inner.lock().await;
+        guard.iter().rev().take(limit).cloned().collect()
+    }
+
     pub async fn get_and_clear(&self) -> Vec<String> {
         let mut guard = self.inner.lock().await;
-        std::mem::take(&mut *guard)
+        guard.drain(..).collect()
     }
 }
 
 #[tokio::main]
 async fn main() {
+    let settings = match config::Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Invalid configuration: {:#}", e);
+            std::process::exit(2);
+        }
+    };
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive("gui_server=info".parse().unwrap())
-                .add_directive("nexosim_hybrid=info".parse().unwrap()),
+                .add_directive("nexosim_hybrid=info".parse().unwrap())
+                .add_directive("tower_http=info".parse().unwrap()),
         )
         .init();
 
@@ -67,175 +151,400 @@ async fn main() {
     let db = Database::init()
         .await
         .expect("Failed to create database");
+    // Journal writes for desktop replicas to pull
+    ::db::sync::track(&db.client)
+        .await
+        .expect("Failed to set up the sync journal");
     
-    // Seed from scenario config
-    let scenario_path = std::env::var("SCENARIO_PATH")
-        .unwrap_or_else(|_| "../../common/scenarios/mmc/scenario.toml".to_string());
-    if let Err(e) = nexosim_hybrid::database::components::ComponentRepository::seed_from_toml(
-        &db.client,
-        &scenario_path,
-    ).await {
-        tracing::warn!("Could not seed database: {}", e);
-    }
+    let geo_cache = cached_geo::new_shared_cache();
 
-    // Import cities
-    let cities_path = std::env::var("CITIES_DB_PATH")
-        .unwrap_or_else(|_| "../../common/geo/worldcities_dev.csv".to_string());
-    tracing::info!("Importing cities from {:?}", cities_path);
-    match nexosim_hybrid::database::geo::GeoRepository::import_cities(&db.client, std::path::Path::new(&cities_path)).await {
-        Ok(count) => tracing::info!("Imported {} cities from CSV.", count),
-        Err(e) => tracing::warn!("Failed to import cities: {}", e),
-    }
+    let dev_mode = settings.dev_mode;
+    let paths = settings.paths.clone();
+    let port = settings.server.port;
+    let host: std::net::IpAddr = settings.server.host.parse().unwrap_or_else(|_| {
+        tracing::warn!("Invalid server.host '{}', listening on all interfaces", settings.server.host);
+        std::net::IpAddr::from([0, 0, 0, 0])
+    });
 
-    // Import GeoJSON in background
-    tokio::spawn({
-        let db = db.clone();
+    let db = Arc::new(db);
+    let notifications = notifications::NotificationHub::new(db.clone());
+    let email = email::EmailService::new(db.clone(), &settings.email);
+    let chat = chat::ChatBroker::new(db.clone(), email.clone());
+    let identity = identity::IdentityService::new(db.clone(), &settings.auth);
+    let webhooks = webhooks::WebhookHub::new(db.clone());
+    let telemetry = telemetry::TelemetryPoller::new(db.clone(), &settings.telemetry);
+    let netbox = netbox::NetboxImporter::new(db.clone(), &settings.netbox);
+    let graphql = graphql::schema(db.client.clone());
+    let state = AppState {
+        db: db.clone(),
+        logs: LogBuffer::default(),
+        geo_cache: geo_cache.clone(),
+        current_persona: Arc::new(Mutex::new(None)),
+        dev_mode,
+        settings: Arc::new(settings),
+        readiness: health::Readiness::default(),
+        jobs: jobs::JobQueue::start(db, notifications.clone()),
+        notifications,
+        chat,
+        email,
+        presence: presence::PresenceHub::default(),
+        collab: collab::CollabHub::default(),
+        graphql,
+        render_cache: render_cache::RenderCache::default(),
+        webhooks,
+        identity,
+        telemetry,
+        netbox,
+    };
+
+    // Queue data imports; the worker runs them in order
+    let cities_path = paths.cities.clone();
+    let cities_job = state.jobs.enqueue("import.cities", "Import cities", move |ctx| {
+        let cities_path = cities_path.clone();
         async move {
-            // Countries
-            match nexosim_hybrid::database::geo::GeoRepository::import_geojson(
-                &db.client,
-                std::path::Path::new("../../common/geo/countries_110m.geo.json"),
+            tracing::info!("Importing cities from {:?}", cities_path);
+            let count = nexosim_hybrid::database::geo::GeoRepository::import_cities(
+                ctx.db(),
+                &cities_path,
+            ).await?;
+            Ok(format!("Imported {} cities from CSV", count))
+        }
+    }).await;
+
+    let geo_dir = paths.geo_dir.clone();
+    let boundaries_job = state.jobs.enqueue("import.boundaries", "Import country and US state boundaries", move |ctx| {
+        let geo_dir = geo_dir.clone();
+        async move {
+            let countries = nexosim_hybrid::database::geo::GeoRepository::import_geojson(
+                ctx.db(),
+                &geo_dir.join("countries_110m.geo.json"),
                 "country",
-            ).await {
-                Ok(count) => tracing::info!("Imported {} countries.", count),
-                Err(e) => tracing::warn!("Failed to import countries: {}", e),
-            }
-            
-            // US States
-            match nexosim_hybrid::database::geo::GeoRepository::import_geojson(
-                &db.client,
-                std::path::Path::new("../../common/geo/us_states_20m.geo.json"),
+            ).await?;
+            ctx.step(1, 2).await;
+
+            let states = nexosim_hybrid::database::geo::GeoRepository::import_geojson(
+                ctx.db(),
+                &geo_dir.join("us_states_20m.geo.json"),
                 "state",
-            ).await {
-                Ok(count) => tracing::info!("Imported {} US states.", count),
-                Err(e) => tracing::warn!("Failed to import US states: {}", e),
-            }
+            ).await?;
+            Ok(format!("Imported {} countries and {} US states", countries, states))
         }
-    });
+    }).await;
 
-    // Spawn high-fidelity background import (runs after low-fidelity startup)
-    tokio::spawn({
-        let db = db.clone();
+    // High-fidelity data replaces the low-fidelity startup set once it is in
+    let cities_full_path = paths.cities_full.clone();
+    let _ = state.jobs.enqueue("import.cities_full", "Import high-fidelity cities (~48K)", move |ctx| {
+        let cities_full_path = cities_full_path.clone();
         async move {
-            // Wait for server to be ready and initial imports to complete
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-            tracing::info!("Starting high-fidelity background imports...");
+            let count = nexosim_hybrid::database::geo::GeoRepository::import_cities_full(
+                ctx.db(),
+                &cities_full_path,
+            ).await?;
+            Ok(format!("Imported {} cities", count))
+        }
+    }).await;
 
-            // High-fidelity cities (~48K)
-            match nexosim_hybrid::database::geo::GeoRepository::import_cities_full(
-                &db.client,
-                std::path::Path::new("../../common/geo/worldcities.csv"),
-            ).await {
-                Ok(count) => tracing::info!("High-fidelity city import complete: {} cities.", count),
-                Err(e) => tracing::warn!("Failed high-fidelity city import: {}", e),
-            }
+    let geo_dir = paths.geo_dir.clone();
+    let _ = state.jobs.enqueue("import.boundaries_hifi", "Import high-fidelity boundaries", move |ctx| {
+        let geo_dir = geo_dir.clone();
+        async move {
+            // Countries (10m detail)
+            let countries = nexosim_hybrid::database::geo::GeoRepository::import_geojson_high_fidelity(
+                ctx.db(),
+                &geo_dir.join("countries_10m.geo.json"),
+                "country",
+            ).await?;
+            ctx.step(1, 2).await;
+
+            // US states (5m detail)
+            let states = nexosim_hybrid::database::geo::GeoRepository::import_geojson_high_fidelity(
+                ctx.db(),
+                &geo_dir.join("us_states_5m.geo.json"),
+                "state",
+            ).await?;
+            Ok(format!("Imported {} countries and {} US states", countries, states))
+        }
+    }).await;
 
-            // High-fidelity countries (10m detail)
-            match nexosim_hybrid::database::geo::GeoRepository::import_geojson_high_fidelity(
+    // Seed scenario and wait for the city import; /readyz reports ready once done
+    tokio::spawn({
+        let db = state.db.clone();
+        let readiness = state.readiness.clone();
+        let scenario_path = paths.scenario.to_string_lossy().into_owned();
+        async move {
+            // Seed from scenario config
+            if let Err(e) = nexosim_hybrid::database::components::ComponentRepository::seed_from_toml(
                 &db.client,
-                std::path::Path::new("../../common/geo/countries_10m.geo.json"),
-                "country",
+                &scenario_path,
             ).await {
-                Ok(count) => tracing::info!("High-fidelity country import complete: {} countries.", count),
-                Err(e) => tracing::warn!("Failed high-fidelity country import: {}", e),
+                tracing::warn!("Could not seed database: {}", e);
             }
 
-            // High-fidelity US states (5m detail)
-            match nexosim_hybrid::database::geo::GeoRepository::import_geojson_high_fidelity(
-                &db.client,
-                std::path::Path::new("../../common/geo/us_states_5m.geo.json"),
-                "state",
-            ).await {
-                Ok(count) => tracing::info!("High-fidelity US state import complete: {} states.", count),
-                Err(e) => tracing::warn!("Failed high-fidelity US state import: {}", e),
+            match cities_job {
+                Ok(handle) => {
+                    let status = handle.wait().await;
+                    if status != JobStatus::Succeeded {
+                        tracing::warn!("City import {}; continuing without it", status);
+                    }
+                }
+                Err(e) => tracing::warn!("Could not queue city import: {}", e),
             }
 
-            tracing::info!("All high-fidelity background imports complete!");
+            readiness.mark_seeded();
+            tracing::info!("Initial seed complete, server is ready");
         }
     });
 
-    let geo_cache = cached_geo::new_shared_cache();
-
-    // Check for DEV_MODE environment variable
-    let dev_mode = std::env::var("DEV_MODE").map(|v| v == "true" || v == "1").unwrap_or(false);
-    
-    let state = AppState {
-        db: Arc::new(db),
-        logs: LogBuffer::default(),
-        geo_cache: geo_cache.clone(),
-        current_persona: Arc::new(Mutex::new(None)),
-        dev_mode,
-    };
-
-    // Spawn task to warm geo cache after initial import
+    // Warm geo cache once the boundary import has finished
     tokio::spawn({
         let db_arc = state.db.clone();
         let cache = geo_cache.clone();
         async move {
-            // Wait for initial geo imports to complete
-            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
-            
+            if let Ok(handle) = boundaries_job {
+                handle.wait().await;
+            }
+
             // Fetch features and cache paths
             if let Ok(features) = nexosim_hybrid::database::geo::GeoRepository::list_geo_features(
                 &db_arc.client, None
             ).await {
                 let cached = cached_geo::CachedGeoPaths::from_features(&features);
                 *cache.write().await = cached;
+                // The map views drew empty outlines until now
+                nexosim_hybrid::database::versions::bump(nexosim_hybrid::database::versions::Data::Geo);
                 tracing::info!("Geo path cache warmed successfully");
             }
         }
     });
 
-    // Get port from environment
-    let port: u16 = std::env::var("PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(3000);
+    // Recurring tasks queue behind the imports above
+    scheduler::start(state.clone());
+    state.webhooks.start();
+    state.email.start();
+    state.telemetry.start(&state.settings.telemetry);
 
     // Build router
     let app = Router::new()
         // Static files
-        .nest_service("/assets", tower_http::services::ServeDir::new("assets"))
+        .nest_service(static_assets::DIST_URL, static_assets::dist_service())
+        .nest_service("/assets", static_assets::assets_service())
         .nest_service("/scenarios", tower_http::services::ServeDir::new("scenarios"))
         // SSE for dev auto-reload
         .route("/sse", get(sse_handler))
+        // Probes
+        .route("/healthz", get(health::healthz))
+        .route("/readyz", get(health::readyz))
         // API routes
-        .route("/api/components", get(api::list_components))
-        .route("/api/connections", get(api::list_connections))
-        .route("/api/regions", get(api::list_regions))
-        .route("/api/sites", get(api::list_sites))
+        .route("/api/components", get(api::list_components).post(api::create_component))
+        .route("/api/components/:id", put(api::update_component).delete(api::delete_component))
+        .route("/api/components/:id/location", get(api::component_location))
+        .route("/api/components/:id/telemetry", get(telemetry::get).put(telemetry::put))
+        .route("/api/components/:id/telemetry/poll", post(telemetry::poll_now))
+        .route("/api/telemetry/sources", get(telemetry::list))
+        .route("/api/netbox/sync", post(netbox::sync))
+        .route("/api/iac/export", get(iac::export_document))
+        .route("/api/iac/import", post(iac::import_document))
+        .route("/api/netbox/links", get(netbox::links))
+        .route("/api/connections", get(api::list_connections).post(api::create_connection))
+        .route("/api/connections/:from/:to", delete(api::delete_connection))
+        .route("/api/regions", get(api::list_regions).post(api::create_region))
+        .route("/api/regions/search", get(api::search_regions))
+        .route("/api/sites", get(api::list_sites).post(api::create_site))
+        .route("/api/sites/:id", put(api::update_site).delete(api::delete_site))
+        .route("/api/sites/:id/buildings", get(api::list_buildings))
+        .route("/api/buildings", post(api::create_building))
+        .route("/api/buildings/:id", put(api::update_building).delete(api::delete_building))
+        .route("/api/buildings/:id/floors", get(api::list_floors))
+        .route("/api/floors", post(api::create_floor))
+        .route("/api/floors/:id", put(api::update_floor).delete(api::delete_floor))
+        .route("/api/floors/:id/outline", put(api::set_floor_outline))
+        .route("/api/floors/:id/spaces", get(api::list_spaces))
+        .route("/api/spaces", post(api::create_space))
+        .route("/api/spaces/:id", put(api::update_space).delete(api::delete_space))
+        .route("/api/spaces/:id/bounds", put(api::set_space_bounds))
+        .route("/api/spaces/:id/budget", put(capacity::set_budget))
+        .route("/api/spaces/:id/racks", get(api::list_racks))
+        .route("/api/racks", post(api::create_rack))
+        .route("/api/racks/:id", put(api::update_rack).delete(api::delete_rack))
+        .route("/api/racks/:id/devices", get(api::list_devices))
+        .route("/api/devices", post(api::create_device))
+        .route("/api/devices/:id", put(api::update_device).delete(api::delete_device))
+        .route("/api/devices/:id/component", put(api::link_device))
+        .route("/api/patch-panels", post(api::create_patch_panel))
+        .route("/api/patch-panels/:id", delete(api::delete_patch_panel))
+        .route("/api/ports", post(api::create_port))
+        .route("/api/ports/:id", delete(api::delete_port))
+        .route("/api/cables", get(api::list_cables).post(api::create_cable))
+        .route("/api/cables/:id", delete(api::delete_cable))
+        .route("/api/cabling/connections", get(api::projected_connections))
+        .route("/api/cabling/sync", post(api::sync_cabling))
+        .route("/api/desks", get(api::list_desks).post(api::create_desk))
+        .route("/api/desk-bookings", get(api::list_desk_bookings).post(api::create_desk_booking))
+        .route("/api/desk-bookings/:id", delete(api::cancel_desk_booking))
+        .route("/api/events", get(api::list_events).post(api::create_event))
+        .route("/api/events/:id", put(api::update_event).delete(api::delete_event))
+        .route("/api/runs", get(api::list_runs).post(api::create_run))
+        .route("/api/runs/compare", get(api::compare_runs))
+        .route("/api/runs/:id", delete(api::delete_run))
+        .route("/api/sandboxes", get(sandbox::list).post(sandbox::create))
+        .route("/api/sandboxes/:id", get(sandbox::get).delete(sandbox::discard))
+        .route("/api/sandboxes/:id/topology", put(sandbox::update_topology))
+        .route("/api/sandboxes/:id/diff", get(sandbox::changes_since_branch))
+        .route("/api/sandboxes/:id/runs", post(sandbox::simulate))
+        .route("/api/sandboxes/:id/merge", post(sandbox::merge_into_live))
+        .route("/api/capacity", get(capacity::get_report))
+        .route("/api/capacity/check", post(capacity::check))
+        .route("/api/incidents", get(incidents::list).post(incidents::create))
+        .route("/api/incidents/:id", get(incidents::get).delete(incidents::delete))
+        .route("/api/incidents/:id/status", post(incidents::update_status))
+        .route("/api/incidents/:id/assignee", put(incidents::update_assignee))
+        .route("/api/incidents/:id/timeline", post(incidents::add_note))
+        .route("/api/oncall", get(oncall::who_is_on_call))
+        .route("/api/oncall/shifts", get(oncall::list_shifts))
+        .route("/api/oncall/rotations", get(oncall::list_rotations).post(oncall::create_rotation))
+        .route(
+            "/api/oncall/rotations/:id",
+            get(oncall::get_rotation).put(oncall::update_rotation).delete(oncall::delete_rotation),
+        )
+        .route("/api/oncall/policies", get(oncall::list_policies).post(oncall::create_policy))
+        .route("/api/oncall/policies/:id", put(oncall::update_policy).delete(oncall::delete_policy))
+        .route("/api/jobs", get(api::list_jobs))
+        .route("/api/jobs/:id", get(api::get_job))
+        .route("/api/jobs/:id/retry", post(api::retry_job))
+        .route("/api/notifications", get(api::list_notifications))
+        .route("/api/notifications/unread", get(api::unread_notifications))
+        .route("/api/notifications/read-all", post(api::read_all_notifications))
+        .route("/api/notifications/stream", get(notifications::stream))
+        .route("/api/notifications/:id/read", post(api::read_notification))
+        .route("/api/chat/conversations", get(api::list_conversations).post(api::start_conversation))
+        .route("/api/chat/conversations/:id/messages", get(api::list_messages).post(api::send_message))
+        .route("/api/chat/ws", get(chat::socket))
+        .route("/api/presence", get(presence::roster))
+        .route("/api/presence/ws", get(presence::socket))
+        .route("/api/collab/ws", get(collab::socket))
+        .route("/api/reports", get(api::list_reports))
+        .route("/api/reports/documents/:id", get(api::get_report_document).delete(api::delete_report_document))
+        .route("/api/reports/documents/:id/email", post(email::email_report))
+        .route("/api/reports/:kind", get(api::generate_report))
+        .route("/api/export/:resource", get(api::export_list))
+        .route("/api/import/:resource", get(api::import_fields).post(api::run_import))
+        .route("/api/sync/changes", get(sync::pull).post(sync::push))
+        .route("/api/flags", get(api::list_flags))
+        .route("/api/flags/:name", put(api::set_flag))
+        .route("/api/graphql", get(graphql::graphiql).post(graphql::execute))
+        .route("/api/schedules", get(scheduler::list).post(scheduler::create))
+        .route("/api/schedules/tasks", get(scheduler::tasks))
+        .route("/api/schedules/:id", put(scheduler::update).delete(scheduler::delete))
+        .route("/api/schedules/:id/run", post(scheduler::run_now))
+        .route("/api/schedules/:id/runs", get(scheduler::runs))
+        .route("/api/webhooks", get(webhooks::list).post(webhooks::create))
+        .route("/api/webhooks/events", get(webhooks::events))
+        .route("/api/webhooks/:id", put(webhooks::update).delete(webhooks::delete))
+        .route("/api/webhooks/:id/test", post(webhooks::test))
+        .route("/api/webhooks/:id/deliveries", get(webhooks::deliveries))
+        .route("/api/webhooks/deliveries/:id/redeliver", post(webhooks::redeliver_now))
+        .route("/api/email/preferences", get(email::get_preferences).put(email::put_preferences))
+        .route("/api/email/outbox", get(email::outbox))
+        .route("/api/admin/seed", post(api::seed_scenario))
+        .route("/api/admin/scenario", get(api::export_scenario))
+        .route("/api/admin/migrations", get(api::list_migrations).post(api::run_migrations))
+        .route("/api/admin/vacuum", post(api::vacuum))
         .route("/api/geo/features", get(api::list_geo_features))
+        .route("/api/geo/route", get(api::great_circle_route))
+        .route("/api/tiles/:z/:x/:y", get(tiles::get_tile))
         .route("/api/cities/search", get(api::search_cities))
         // Persona management
         .route("/api/persona", get(handle_get_persona))
         .route("/api/persona", post(handle_set_persona))
         .route("/api/persona", axum::routing::delete(handle_delete_persona))
-        .route("/api/people", get(api::list_people))
+        .route("/api/auth/providers", get(identity::providers))
+        .route("/api/auth/identities", get(identity::identities).post(identity::link))
+        .route("/auth/oidc/login", get(identity::login))
+        .route("/auth/oidc/callback", get(identity::callback))
+        .route("/api/people", get(api::list_people).post(api::create_person))
         .route("/api/people/:id/photo", get(api::get_person_photo))
+        .route("/api/assets", get(api::list_assets))
+        // OpenAPI document + Swagger UI
+        .merge(openapi::swagger_ui())
         // Form handlers
         .route("/components/create", post(handle_create_component))
         .route("/components/:id/delete", post(handle_delete_component))
+        .route("/components/:id/telemetry", post(handle_component_telemetry))
         .route("/connections/create", post(handle_create_connection))
         .route("/connections/:from/:to/delete", post(handle_delete_connection))
         .route("/sites/create", post(handle_create_site))
         .route("/regions/create", post(handle_create_region))
         .route("/buildings/create", post(handle_create_building))
         .route("/floors/create", post(handle_create_floor))
+        .route("/floors/:id/outline", post(handle_upload_outline))
         .route("/spaces/create", post(handle_create_space))
+        .route("/spaces/:id/budget", post(handle_space_budget))
         .route("/racks/create", post(handle_create_rack))
         .route("/devices/create", post(handle_create_device))
+        .route("/devices/:id/link", post(handle_link_device))
+        .route("/patch-panels/create", post(handle_create_patch_panel))
+        .route("/ports/create", post(handle_create_port))
+        .route("/cables/create", post(handle_create_cable))
+        .route("/cables/:id/delete", post(handle_delete_cable))
+        .route("/cabling/sync", post(handle_sync_cabling))
+        .route("/desks/create", post(handle_create_desk))
+        .route("/desk-bookings/create", post(handle_book_desk))
+        .route("/desk-bookings/:id/delete", post(handle_cancel_desk_booking))
         .route("/simulation/start", post(handle_start_simulation))
         .route("/runs/:id/delete", post(handle_delete_run))
+        .route("/sandboxes/create", post(handle_create_sandbox))
+        .route("/sandboxes/:id/components/create", post(handle_sandbox_add_component))
+        .route("/sandboxes/:id/components/:component/delete", post(handle_sandbox_remove_component))
+        .route("/sandboxes/:id/connections/create", post(handle_sandbox_connect))
+        .route("/sandboxes/:id/connections/:from/:to/delete", post(handle_sandbox_disconnect))
+        .route("/sandboxes/:id/run", post(handle_sandbox_run))
+        .route("/sandboxes/:id/merge", post(handle_sandbox_merge))
+        .route("/sandboxes/:id/discard", post(handle_sandbox_discard))
+        .route("/jobs/:id/retry", post(handle_retry_job))
+        .route("/schedules/:id/run", post(handle_run_schedule))
+        .route("/webhooks/create", post(handle_create_webhook))
+        .route("/webhooks/:id/test", post(handle_test_webhook))
+        .route("/webhooks/:id/toggle", post(handle_toggle_webhook))
+        .route("/webhooks/:id/delete", post(handle_delete_webhook))
+        .route("/webhooks/deliveries/:id/redeliver", post(handle_redeliver_webhook))
+        .route("/reports/generate", post(handle_generate_report))
+        .route("/reports/:id/delete", post(handle_delete_report))
+        .route("/reports/:id/email", post(handle_email_report))
+        .route("/email/preferences", post(handle_email_preferences))
         .route("/events/create", post(handle_create_event))
+        .route("/maintenance/create", post(handle_create_maintenance))
+        .route("/maintenance/:id/close", post(handle_close_maintenance))
+        .route("/lifecycle/transition", post(handle_lifecycle_transition))
+        .route("/incidents/create", post(handle_create_incident))
+        .route("/incidents/:id/status", post(handle_incident_status))
+        .route("/incidents/:id/assign", post(handle_assign_incident))
+        .route("/incidents/:id/note", post(handle_incident_note))
+        .route("/incidents/:id/delete", post(handle_delete_incident))
+        .route("/oncall/rotations/create", post(handle_create_rotation))
+        .route("/oncall/rotations/:id/delete", post(handle_delete_rotation))
+        .route("/oncall/policies/create", post(handle_create_policy))
+        .route("/oncall/policies/:id/delete", post(handle_delete_policy))
         // Main page - SSR
         .route("/", get(root_handler))
-        .with_state(state);
-
-    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
+        // Debug
+        .route("/debug/requests", get(request_log::recent_requests_page))
+        .with_state(state.clone());
+    let app = request_log::layer(
+        app.layer(tower_http::compression::CompressionLayer::new().compress_when(ndjson::compress_when())),
+        state.clone(),
+    );
+
+    let addr = std::net::SocketAddr::new(host, port);
     tracing::info!("listening on http://{}", addr);
     
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(health::shutdown_signal(state.readiness.clone()))
+        .await
+        .unwrap();
+
+    health::flush_logs(&state).await;
+    tracing::info!("Server stopped");
 }
 
 // ============================================================================
@@ -243,7 +552,8 @@ async fn main() {
 // ============================================================================
 
 use axum::response::Html;
-use axum::extract::{Query, State, Form, Path};
+use axum::extract::{Query, RawQuery, State, Form, Path};
+use axum_extra::extract::cookie::CookieJar;
 
 #[derive(serde::Deserialize, Default)]
 pub struct PageParams {
@@ -256,22 +566,41 @@ pub struct PageParams {
     pub floor_id: Option<String>,
     pub space_id: Option<String>,
     pub rack_id: Option<String>,
+    pub device_id: Option<String>,
     pub month: Option<u32>,
     pub year: Option<i32>,
     pub modal: Option<String>,
+    /// `YYYY-MM-DD` shown by the desk booking panel
+    pub date: Option<String>,
     #[allow(dead_code)]
     pub run_id: Option<String>,
+    /// Comma-separated keys of runs to compare, baseline first
+    pub compare: Option<String>,
+    /// Key of the sandbox the simulation tab opens
+    pub sandbox: Option<String>,
+    /// Key of the incident open on the incident board
+    pub incident: Option<String>,
+    /// Device checked on the capacity tab, placed in `rack_id`
+    pub name: Option<String>,
+    pub position_u: Option<u8>,
+    pub height_u: Option<u8>,
+    pub power_w: Option<u32>,
 }
 
 
-async fn root_handler(
-    State(state): State<AppState>,
-    Query(params): Query<PageParams>,
-) -> Html<String> {
-    // Fetch data from database
+/// Fetch everything the page can show; each tab uses its own slice of it
+async fn load_page_data(
+    state: &AppState,
+    params: &PageParams,
+    current_persona: Option<String>,
+    now: chrono::NaiveDate,
+) -> app::PageData {
     let components = nexosim_hybrid::database::components::ComponentRepository::get_all(&state.db.client)
         .await
         .unwrap_or_default();
+    let telemetry_sources = nexosim_hybrid::database::telemetry_sources::TelemetrySourceRepository::get_all(&state.db.client)
+        .await
+        .unwrap_or_default();
     let connections = nexosim_hybrid::database::connections::ConnectionRepository::get_all(&state.db.client)
         .await
         .unwrap_or_default();
@@ -284,12 +613,88 @@ async fn root_handler(
     let runs = nexosim_hybrid::database::simulation::SimulationRepository::get_all(&state.db.client)
         .await
         .unwrap_or_default();
+    let compare: Vec<String> = params
+        .compare
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(String::from)
+        .collect();
+    let comparison = (compare.len() >= 2).then(|| {
+        let keys: Vec<&str> = compare.iter().map(String::as_str).collect();
+        simulation::compare(&runs, &keys)
+    });
+    let sandboxes = nexosim_hybrid::database::sandbox::SandboxRepository::list(&state.db.client)
+        .await
+        .unwrap_or_default();
+    // The open sandbox with its edits, checked against the live topology
+    let open_sandbox = params.sandbox.as_deref().and_then(|key| {
+        let found = sandboxes.iter().find(|s| s.id.as_ref().is_some_and(|id| id.id.to_raw() == key))?;
+        let live = nexosim_hybrid::database::sandbox::Topology {
+            components: components.clone(),
+            connections: connections.clone(),
+        };
+        Some((found.clone(), sandbox::changes(found, &live)))
+    });
+    let jobs = nexosim_hybrid::database::jobs::JobRepository::get_all(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let schedules = nexosim_hybrid::database::schedules::ScheduleRepository::get_all(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let webhooks = nexosim_hybrid::database::webhooks::WebhookRepository::get_all(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let mut webhook_deliveries = nexosim_hybrid::database::webhooks::WebhookRepository::deliveries(&state.db.client, None)
+        .await
+        .unwrap_or_default();
+    webhook_deliveries.truncate(WEBHOOK_DELIVERIES_SHOWN);
+    let reports = nexosim_hybrid::database::reports::ReportRepository::list(&state.db.client)
+        .await
+        .unwrap_or_default();
     let geo_features = nexosim_hybrid::database::geo::GeoRepository::list_geo_features(&state.db.client, None)
         .await
         .unwrap_or_default();
     
     // Determine active tab (default to home)
     let active_tab = params.tab.as_deref().unwrap_or("home");
+
+    // Capacity figures are worked out from several queries, so only for their tab
+    let (capacity, capacity_check) = if active_tab == "capacity" {
+        let report = capacity::report(&state.db.client, capacity::today()).await;
+        if let Err(e) = &report {
+            tracing::warn!("Failed to load capacity: {}", e);
+        }
+        let check = match (&params.rack_id, params.position_u) {
+            (Some(rack), Some(position_u)) => {
+                let device = nexosim_hybrid::database::geo::Device {
+                    id: None,
+                    name: params.name.clone().unwrap_or_default(),
+                    rack_id: parse_thing(rack, "rack"),
+                    position_u,
+                    component_id: None,
+                    height_u: params.height_u.unwrap_or(1),
+                    power_w: params.power_w.unwrap_or_default(),
+                };
+                let warnings = capacity::check_device(&state.db.client, &device).await.unwrap_or_default();
+                Some(capacity::DeviceCheck { device, warnings })
+            }
+            _ => None,
+        };
+        (report.ok(), check)
+    } else {
+        (None, None)
+    };
+
+    let incidents = if active_tab == "incidents" {
+        nexosim_hybrid::database::incidents::IncidentRepository::list(&state.db.client)
+            .await
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
     
     // Determine geo view based on params
     use crate::components::sites_tab::GeoView;
@@ -304,6 +709,8 @@ async fn root_handler(
             "create_device" => params.rack_id.clone().map(GeoView::CreateDevice).unwrap_or(GeoView::RegionList),
             _ => GeoView::RegionList,
         }
+    } else if let Some(id) = &params.device_id {
+        GeoView::DeviceDetail(id.clone())
     } else if let Some(id) = &params.rack_id {
         GeoView::RackDetail(id.clone())
     } else if let Some(id) = &params.space_id {
@@ -340,6 +747,34 @@ async fn root_handler(
         .await
         .unwrap_or_default();
     
+    // Fetch devices and resolve where linked simulation components are racked
+    let devices = nexosim_hybrid::database::geo::GeoRepository::list_all_devices(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let component_locations = nexosim_hybrid::database::device_links::locate_components(
+        &devices, &racks, &spaces, &floors, &buildings, &sites,
+    );
+    
+    // Physical cabling, and the simulation connections it implies but that are missing
+    use nexosim_hybrid::database::cabling::{project_connections, CablingRepository};
+    let patch_panels = CablingRepository::list_all_panels(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let ports = CablingRepository::list_all_ports(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let cables = CablingRepository::list_all_cables(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let pending_connections: Vec<_> = project_connections(&ports, &cables, &devices)
+        .into_iter()
+        .filter(|p| {
+            !connections
+                .iter()
+                .any(|c| (c.from, c.to) == (p.from, p.to) || (c.from, c.to) == (p.to, p.from))
+        })
+        .collect();
+    
     // Fetch assets for asset management
     let assets = nexosim_hybrid::database::geo::GeoRepository::list_all_assets(&state.db.client)
         .await
@@ -356,20 +791,96 @@ async fn root_handler(
         )
     };
     
-    // Get persona state
-    let current_persona = state.current_persona.lock().await.clone();
     let people = nexosim_hybrid::database::geo::GeoRepository::list_all_people(&state.db.client)
         .await
         .unwrap_or_default();
     
-    // Fetch meetings for calendar
-    let meetings = nexosim_hybrid::database::calendar::CalendarRepository::get_all(&state.db.client)
+    // Maintenance: open tickets for the next 30 days, warranties ending within 90
+    use nexosim_hybrid::database::asset_lifecycle::AssetLifecycleRepository;
+    let today = now.format("%Y-%m-%d").to_string();
+    let day = |days: i64| (now + chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+    let maintenance_tickets = AssetLifecycleRepository::upcoming_maintenance(&state.db.client, &day(30))
         .await
         .unwrap_or_default();
-    
-    // Render the page
-    let html = app::render_page(app::PageData {
+    let expiring_warranties = AssetLifecycleRepository::expiring_warranties(&state.db.client, &today, &day(90))
+        .await
+        .unwrap_or_default();
+    let lifecycle_transitions = AssetLifecycleRepository::recent_transitions(&state.db.client, 20)
+        .await
+        .unwrap_or_default();
+
+    // Fetch meetings for calendar, with scheduled maintenance as all-day events
+    let mut meetings = nexosim_hybrid::database::calendar::CalendarRepository::get_all(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let asset_names: std::collections::HashMap<String, String> = assets
+        .iter()
+        .filter_map(|a| Some((a.id.as_ref()?.id.to_raw(), a.name.clone())))
+        .collect();
+    let all_tickets = AssetLifecycleRepository::list_tickets(&state.db.client)
+        .await
+        .unwrap_or_default();
+    meetings.extend(
+        all_tickets
+            .iter()
+            .filter(|t| t.status != nexosim_hybrid::database::asset_lifecycle::TicketStatus::Closed)
+            .map(|t| {
+                let name = asset_names.get(&t.asset_id.id.to_raw()).map(String::as_str).unwrap_or("asset");
+                t.to_meeting(name)
+            }),
+    );
+
+    // Desks and bookings; day bookings also show as all-day calendar entries
+    let desks = nexosim_hybrid::database::geo::GeoRepository::list_all_desks(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let desk_bookings = nexosim_hybrid::database::desks::DeskRepository::list_all_bookings(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let desk_names: std::collections::HashMap<String, &str> = desks
+        .iter()
+        .filter_map(|d| Some((d.id.as_ref()?.to_string(), d.name.as_str())))
+        .collect();
+    let person_names: std::collections::HashMap<String, &str> = people
+        .iter()
+        .filter_map(|p| Some((p.id.as_ref()?.to_string(), p.name.as_str())))
+        .collect();
+    meetings.extend(desk_bookings.iter().filter_map(|b| {
+        let desk = desk_names.get(&b.desk_id.to_string()).copied().unwrap_or("?");
+        let person = person_names.get(&b.person_id.to_string()).copied().unwrap_or("someone");
+        b.to_meeting(desk, person)
+    }));
+
+    // On-call rotations and policies; shifts show on the calendar around its month
+    use nexosim_hybrid::database::oncall::OnCallRepository;
+    let rotations = OnCallRepository::rotations(&state.db.client).await.unwrap_or_default();
+    let escalation_policies = OnCallRepository::policies(&state.db.client).await.unwrap_or_default();
+    let on_call = oncall::on_call_at(&rotations, &people, &clock::minute());
+    if active_tab == "calendar" {
+        use chrono::{Datelike, NaiveDate, TimeDelta};
+        let month = NaiveDate::from_ymd_opt(params.year.unwrap_or(now.year()), params.month.unwrap_or(now.month()), 1);
+        if let Some(first) = month.map(|day| day.and_time(Default::default()).and_utc()) {
+            // A week either side covers the days the month grid borrows
+            let from = first - TimeDelta::days(7);
+            let to = first + TimeDelta::days(38);
+            meetings.extend(oncall::shifts_as_meetings(&rotations, &people, &from, &to));
+        }
+    }
+    let booking_date = params.date.clone().filter(|d| !d.is_empty()).unwrap_or_else(|| today.clone());
+    let flags = flags::list(&state.db.client, &state.settings).await.unwrap_or_default();
+    let (email_preferences, email_outbox) = match &current_persona {
+        Some(persona) => (
+            state.email.preferences(persona).await.unwrap_or_default(),
+            nexosim_hybrid::database::email::EmailRepository::outbox(&state.db.client, Some(persona))
+                .await
+                .unwrap_or_default(),
+        ),
+        None => Default::default(),
+    };
+
+    app::PageData {
         components,
+        telemetry_sources,
         connections,
         regions,
         sites,
@@ -377,9 +888,37 @@ async fn root_handler(
         floors,
         spaces,
         racks,
-        devices: vec![],
+        devices,
+        component_locations,
+        patch_panels,
+        ports,
+        cables,
+        pending_connections,
         assets,
+        maintenance_tickets,
+        expiring_warranties,
+        lifecycle_transitions,
+        desks,
+        desk_bookings,
+        booking_date,
+        today,
         runs,
+        compare,
+        comparison,
+        sandboxes,
+        open_sandbox,
+        capacity,
+        capacity_check,
+        incidents,
+        selected_incident: params.incident.clone(),
+        rotations,
+        escalation_policies,
+        on_call,
+        jobs,
+        schedules,
+        webhooks,
+        webhook_deliveries,
+        reports,
         geo_features,
         cached_country_paths,
         cached_state_paths,
@@ -395,13 +934,77 @@ async fn root_handler(
         modal: params.modal.clone(),
 
         dev_mode: state.dev_mode,
+        sso: state.identity.sso(),
         current_persona,
         people,
         meetings,
+        flags,
+        email_preferences,
+        email_outbox,
+    }
+}
+
+async fn root_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(params): Query<PageParams>,
+    RawQuery(query): RawQuery,
+) -> impl axum::response::IntoResponse {
+    let started = std::time::Instant::now();
+    let active_tab = params.tab.clone().unwrap_or_else(|| "home".to_string());
+    let current_persona = identity::persona(&state, &jar).await;
+    let now = chrono::Utc::now().date_naive();
+
+    // The sign-in screen is cheap and only shown until a persona is picked, so it isn't cached
+    let key = current_persona.clone().map(|persona| render_cache::FragmentKey {
+        persona,
+        today: now.format("%Y-%m-%d").to_string(),
+        query: query.unwrap_or_default(),
     });
+    // Read before fetching, so a write during the render leaves the fragment already stale
+    let version = render_cache::version(&active_tab);
+    let cached = key.as_ref().and_then(|key| state.render_cache.get(key, version));
 
-    
-    Html(html)
+    let (tab_html, people, fetch, tab, cache_status) = match cached {
+        Some(tab_html) => {
+            let people = nexosim_hybrid::database::geo::GeoRepository::list_all_people(&state.db.client)
+                .await
+                .unwrap_or_default();
+            (tab_html, people, started.elapsed(), std::time::Duration::ZERO, "hit")
+        }
+        None => {
+            let data = load_page_data(&state, &params, current_persona.clone(), now).await;
+            let fetch = started.elapsed();
+            let tab_html: std::sync::Arc<str> = app::render_tab_html(&data).into();
+            let tab = started.elapsed() - fetch;
+            let cache_status = match key {
+                Some(key) => {
+                    state.render_cache.insert(key, version, tab_html.clone());
+                    "miss"
+                }
+                None => "bypass",
+            };
+            (tab_html, data.people, fetch, tab, cache_status)
+        }
+    };
+
+    let page_started = std::time::Instant::now();
+    let html = app::render_page(
+        app::Shell { active_tab, current_persona, people },
+        tab_html,
+    );
+    let page = page_started.elapsed();
+
+    let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    let timing = format!(
+        "fetch;dur={:.2}, tab;dur={:.2};desc=\"{}\", page;dur={:.2}, total;dur={:.2}",
+        ms(fetch),
+        ms(tab),
+        cache_status,
+        ms(page),
+        ms(started.elapsed()),
+    );
+    ([("server-timing", timing), ("x-render-cache", cache_status.to_string())], Html(html))
 }
 
 // ============================================================================
@@ -413,6 +1016,16 @@ pub struct CreateComponentForm {
     pub id: Option<String>,
     pub name: String,
     pub component_type: String,
+    /// Idle and full-load watts; the type's default applies unless both are given
+    pub idle_w: Option<String>,
+    pub max_w: Option<String>,
+}
+
+impl CreateComponentForm {
+    fn power(&self) -> Option<nexosim_hybrid::config::PowerProfile> {
+        let watts = |s: &Option<String>| s.as_ref().and_then(|s| s.trim().parse::<f64>().ok());
+        Some(nexosim_hybrid::config::PowerProfile { idle_w: watts(&self.idle_w)?, max_w: watts(&self.max_w)? })
+    }
 }
 
 async fn handle_create_component(
@@ -437,11 +1050,13 @@ async fn handle_create_component(
         }
     };
     
+    let power = form.power();
     let config = ComponentConfig {
         id,
         name: form.name,
         component_type: comp_type,
         placement: Default::default(),
+        power,
     };
     
     let _ = nexosim_hybrid::database::components::ComponentRepository::create(&state.db.client, config).await;
@@ -456,6 +1071,34 @@ async fn handle_delete_component(
     axum::response::Redirect::to("/?tab=components")
 }
 
+#[derive(serde::Deserialize)]
+pub struct ComponentTelemetryForm {
+    pub mode: nexosim_hybrid::database::telemetry_sources::TelemetryMode,
+    #[serde(default)]
+    pub target: String,
+    pub community: Option<String>,
+    pub if_index: Option<String>,
+    pub interval_secs: Option<String>,
+}
+
+async fn handle_component_telemetry(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Form(form): Form<ComponentTelemetryForm>,
+) -> impl axum::response::IntoResponse {
+    let input = telemetry::TelemetrySourceInput {
+        mode: form.mode,
+        target: form.target,
+        community: form.community.filter(|c| !c.is_empty()).unwrap_or_else(|| "public".to_string()),
+        if_index: form.if_index.and_then(|i| i.trim().parse().ok()).unwrap_or(1),
+        interval_secs: form.interval_secs.and_then(|i| i.trim().parse().ok()).unwrap_or(60),
+    };
+    if let Err(e) = telemetry::set_source(&state, id, input).await {
+        tracing::warn!("Could not set telemetry source of component {}: {:?}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=components")
+}
+
 #[derive(serde::Deserialize)]
 pub struct CreateConnectionForm {
     pub from_id: u32,
@@ -522,6 +1165,7 @@ async fn handle_create_region(
     State(state): State<AppState>,
     Form(form): Form<CreateRegionForm>,
 ) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::versions::{self, Data};
     let region = Region {
         id: None,
         name: form.name,
@@ -531,6 +1175,7 @@ async fn handle_create_region(
         location: (form.lon, form.lat),
     };
     let _: Option<Region> = state.db.client.create("region").content(region).await.ok().flatten();
+    versions::bump(Data::Geo);
     axum::response::Redirect::to("/?tab=sites")
 }
 
@@ -566,11 +1211,34 @@ async fn handle_create_floor(
 ) -> impl axum::response::IntoResponse {
     use nexosim_hybrid::database::geo::{Floor, GeoRepository};
     let building_thing = parse_thing(&form.building_id, "building");
-    let floor = Floor { id: None, name: form.name, building_id: building_thing, level: form.level };
+    let floor = Floor { id: None, name: form.name, building_id: building_thing, level: form.level, outline: Vec::new() };
     let _ = GeoRepository::create_floor(&state.db.client, floor).await;
     axum::response::Redirect::to(&format!("/?tab=sites&building_id={}", form.building_id))
 }
 
+#[derive(serde::Deserialize)]
+pub struct OutlineForm {
+    pub outline: String,
+}
+
+async fn handle_upload_outline(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Form(form): Form<OutlineForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::floorplan::{parse_outline, FloorplanRepository};
+    let floor = parse_thing(&id, "floor");
+    match parse_outline(&form.outline) {
+        Ok(outline) => {
+            if let Err(e) = FloorplanRepository::set_outline(&state.db.client, &floor.id.to_raw(), outline).await {
+                tracing::warn!("Failed to save outline for {}: {}", floor, e);
+            }
+        }
+        Err(e) => tracing::warn!("Rejected outline for {}: {}", floor, e),
+    }
+    axum::response::Redirect::to(&format!("/?tab=sites&floor_id={}", floor))
+}
+
 // Space handlers
 #[derive(serde::Deserialize)]
 pub struct CreateSpaceForm {
@@ -591,7 +1259,8 @@ async fn handle_create_space(
         name: form.name, 
         locator: form.locator,
         floor_id: floor_thing, 
-        space_type: form.space_type 
+        space_type: form.space_type,
+        bounds: None,
     };
     let _ = GeoRepository::create_space(&state.db.client, space).await;
 axum::response::Redirect::to(&format!("/?tab=sites&floor_id={}", form.floor_id))
@@ -622,189 +1291,909 @@ pub struct CreateDeviceForm {
     pub name: String,
     pub position_u: u8,
     pub rack_id: String,
+    #[serde(default = "one_unit")]
+    pub height_u: u8,
+    #[serde(default)]
+    pub power_w: u32,
+    /// Create the device even when it goes over capacity
+    pub force: Option<String>,
+}
+
+fn one_unit() -> u8 {
+    1
 }
 
+/// Create a device, or show its capacity warnings first when it would go
+/// over and the form did not force it
 async fn handle_create_device(
     State(state): State<AppState>,
     Form(form): Form<CreateDeviceForm>,
 ) -> impl axum::response::IntoResponse {
     use nexosim_hybrid::database::geo::{Device, GeoRepository};
     let rack_thing = parse_thing(&form.rack_id, "rack");
-    let device = Device { id: None, name: form.name, rack_id: rack_thing, position_u: form.position_u, component_id: None };
+    let device = Device {
+        id: None,
+        name: form.name,
+        rack_id: rack_thing.clone(),
+        position_u: form.position_u,
+        component_id: None,
+        height_u: form.height_u.max(1),
+        power_w: form.power_w,
+    };
+    if form.force.is_none() {
+        match capacity::check_device(&state.db.client, &device).await {
+            Ok(warnings) if !warnings.is_empty() => return axum::response::Redirect::to(&capacity::check_link(&device)),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to check capacity: {}", e),
+        }
+    }
     let _ = GeoRepository::create_device(&state.db.client, device).await;
-    axum::response::Redirect::to(&format!("/?tab=sites&rack_id={}", form.rack_id))
+    axum::response::Redirect::to(&format!("/?tab=sites&rack_id={}", rack_thing))
 }
 
-// Helper to parse Thing from string (handles "table:id" or just "id")
-fn parse_thing(s: &str, default_table: &str) -> surrealdb::sql::Thing {
-    if s.contains(':') {
-        let parts: Vec<&str> = s.splitn(2, ':').collect();
-        surrealdb::sql::Thing::from((parts[0], parts[1]))
-    } else {
-        surrealdb::sql::Thing::from((default_table, s))
-    }
+#[derive(serde::Deserialize)]
+pub struct SpaceBudgetForm {
+    pub power_kw: f64,
+    pub cooling_kw: f64,
 }
 
-async fn handle_start_simulation(
+async fn handle_space_budget(
     State(state): State<AppState>,
+    Path(id): Path<String>,
+    Form(form): Form<SpaceBudgetForm>,
 ) -> impl axum::response::IntoResponse {
-    use chrono::Utc;
-    use nexosim_hybrid::database::simulation::{SimulationRepository, SimulationRun};
-    use nexosim_hybrid::database::components::ComponentRepository;
-    use nexosim_hybrid::database::connections::ConnectionRepository;
-    use nexosim_hybrid::simulation::SimulationBuilder;
-    use nexosim_hybrid::model::{Component, RouterModel, SwitchModel};
-    use nexosim::ports::Output;
-    use std::collections::HashMap;
-    
-    let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
-    tracing::info!("Simulation started at {}", timestamp);
-    
-    let mut logs = vec![format!("[{}] Simulation initialized", timestamp)];
-    
-    // Fetch components and connections from database
-    let components = ComponentRepository::get_all(&state.db.client)
-        .await
-        .unwrap_or_default();
-    let connections = ConnectionRepository::get_all(&state.db.client)
-        .await
-        .unwrap_or_default();
-    
-    logs.push(format!("[{}] Loaded {} components and {} connections", 
-        Utc::now().format("%H:%M:%S"), components.len(), connections.len()));
-    
-    if components.is_empty() {
-        logs.push(format!("[{}] No components found - nothing to simulate", 
-            Utc::now().format("%H:%M:%S")));
-        let run = SimulationRun {
-            id: None,
-            started_at: timestamp,
-            status: "completed".to_string(),
-            logs,
-        };
-        let _ = SimulationRepository::create(&state.db.client, run).await;
-        return axum::response::Redirect::to("/?tab=simulation");
-    }
-    
-    // Build simulation
-    let mut builder = SimulationBuilder::new();
-    let mut component_indices: HashMap<u32, usize> = HashMap::new();
-    
-    // Add components to simulation
-    for comp in &components {
-        let id = comp.id;
-        let component = match &comp.component_type {
-            nexosim_hybrid::config::ComponentType::Router => 
-                Component::Router(RouterModel { id, output: Output::default() }),
-            nexosim_hybrid::config::ComponentType::Switch => 
-                Component::Switch(SwitchModel { id, output: Output::default() }),
-            // These types don't have simulation models yet - skip them
-            nexosim_hybrid::config::ComponentType::Firewall |
-            nexosim_hybrid::config::ComponentType::Server |
-            nexosim_hybrid::config::ComponentType::Workstation |
-            nexosim_hybrid::config::ComponentType::AccessPoint |
-            nexosim_hybrid::config::ComponentType::Phone |
-            nexosim_hybrid::config::ComponentType::Printer => continue,
-            nexosim_hybrid::config::ComponentType::PacketGenerator { .. } => 
-                continue, // Skip packet generators for now - they need special handling
-            nexosim_hybrid::config::ComponentType::WasmModule { .. } => 
-                continue, // Skip wasm modules for now
-        };
-        
-        let idx = builder.add_component(component, &comp.name);
-        component_indices.insert(comp.id, idx);
-        logs.push(format!("[{}] Added {:?} '{}' (id={})", 
-            Utc::now().format("%H:%M:%S"), comp.component_type, comp.name, comp.id));
-    }
-    
-    // Establish connections
-    for conn in &connections {
-        if let (Some(&src_idx), Some(&target_idx)) = 
-            (component_indices.get(&conn.from), component_indices.get(&conn.to)) 
-        {
-            builder.connect(src_idx, target_idx);
-            logs.push(format!("[{}] Connected {} -> {}", 
-                Utc::now().format("%H:%M:%S"), 
-                conn.from, conn.to));
-        }
-    }
-    
-    logs.push(format!("[{}] Building simulation...", Utc::now().format("%H:%M:%S")));
-    
-    // Build and run simulation
-    match builder.build() {
-        Ok(mut sim) => {
-            logs.push(format!("[{}] Simulation engine started", Utc::now().format("%H:%M:%S")));
-            
-            // Run 10 simulation steps
-            let step_count = 10;
-            for step in 0..step_count {
-                match sim.step() {
-                    Ok(()) => {
-                        if step == 0 || step == step_count - 1 {
-                            logs.push(format!("[{}] Step {} completed", 
-                                Utc::now().format("%H:%M:%S"), step + 1));
-                        }
-                    }
-                    Err(e) => {
-                        logs.push(format!("[{}] Step {} error: {:?}", 
-                            Utc::now().format("%H:%M:%S"), step + 1, e));
-                        break;
-                    }
-                }
-            }
-            
-            logs.push(format!("[{}] Simulation completed: {} steps executed", 
-                Utc::now().format("%H:%M:%S"), step_count));
-        }
-        Err(e) => {
-            logs.push(format!("[{}] Simulation build failed: {:?}", 
-                Utc::now().format("%H:%M:%S"), e));
-        }
-    }
-    
-    // Save run record
-    let run = SimulationRun {
+    use nexosim_hybrid::database::capacity::{CapacityRepository, SpaceBudget};
+    let budget = SpaceBudget {
         id: None,
-        started_at: timestamp,
-        status: "completed".to_string(),
-        logs,
+        space_id: parse_thing(&id, "space"),
+        power_kw: form.power_kw.max(0.0),
+        cooling_kw: form.cooling_kw.max(0.0),
     };
-    
-    let _ = SimulationRepository::create(&state.db.client, run).await;
-    
-    axum::response::Redirect::to("/?tab=simulation")
+    if let Err(e) = CapacityRepository::set_budget(&state.db.client, budget).await {
+        tracing::warn!("Failed to save budget for space {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=capacity")
 }
 
-async fn handle_delete_run(
+#[derive(serde::Deserialize)]
+pub struct LinkDeviceForm {
+    /// Component id, or empty to unlink
+    #[serde(default)]
+    pub component_id: String,
+}
+
+async fn handle_link_device(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    Form(form): Form<LinkDeviceForm>,
 ) -> impl axum::response::IntoResponse {
-    use nexosim_hybrid::database::simulation::SimulationRepository;
-    let _ = SimulationRepository::delete(&state.db.client, &id).await;
-    axum::response::Redirect::to("/?tab=simulation")
+    use nexosim_hybrid::database::device_links::DeviceLinkRepository;
+    let device = parse_thing(&id, "device");
+    let component = form.component_id.trim().parse::<u32>().ok();
+    if let Err(e) = DeviceLinkRepository::link(&state.db.client, &device.id.to_raw(), component).await {
+        tracing::warn!("Failed to link device {}: {}", id, e);
+    }
+    axum::response::Redirect::to(&format!("/?tab=sites&device_id={}", device))
+}
+
+// Cabling handlers; each form carries the rack it was posted from
+fn rack_redirect(rack_id: &str) -> axum::response::Redirect {
+    axum::response::Redirect::to(&format!("/?tab=sites&rack_id={}", rack_id))
 }
 
-// Event creation handler
 #[derive(serde::Deserialize)]
-pub struct CreateEventForm {
-    pub title: String,
-    #[serde(rename = "type")]
-    pub event_type: Option<String>,
-    pub start_date: Option<String>,
-    pub start_time: Option<String>,
-    pub end_date: Option<String>,
-    pub end_time: Option<String>,
-    pub timezone: Option<String>,
-    pub description: Option<String>,
-    pub is_recurring: Option<String>,
-    pub freq: Option<String>,
-    pub interval: Option<u32>,
-    #[serde(default, deserialize_with = "deserialize_byday")]
-    pub byday: Vec<String>,
-    pub until_type: Option<String>,
-    pub until_date: Option<String>,
-    pub count: Option<u32>,
+pub struct CreatePatchPanelForm {
+    pub name: String,
+    pub position_u: u8,
+    pub port_count: u16,
+    #[serde(default)]
+    pub media: String,
+    pub rack_id: String,
+}
+
+async fn handle_create_patch_panel(
+    State(state): State<AppState>,
+    Form(form): Form<CreatePatchPanelForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::cabling::{CablingRepository, PatchPanel};
+    let panel = PatchPanel {
+        id: None,
+        name: form.name,
+        rack_id: parse_thing(&form.rack_id, "rack"),
+        position_u: form.position_u,
+        port_count: form.port_count,
+        media: form.media.parse().unwrap_or_default(),
+    };
+    if let Err(e) = CablingRepository::create_panel(&state.db.client, panel).await {
+        tracing::warn!("Failed to create patch panel: {}", e);
+    }
+    rack_redirect(&form.rack_id)
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreatePortForm {
+    pub owner: String,
+    pub name: String,
+    #[serde(default)]
+    pub media: String,
+    pub rack_id: String,
+}
+
+async fn handle_create_port(
+    State(state): State<AppState>,
+    Form(form): Form<CreatePortForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::cabling::{CablingRepository, Port};
+    let port = Port {
+        id: None,
+        owner: parse_thing(&form.owner, "device"),
+        name: form.name,
+        media: form.media.parse().unwrap_or_default(),
+    };
+    if let Err(e) = CablingRepository::create_port(&state.db.client, port).await {
+        tracing::warn!("Failed to create port: {}", e);
+    }
+    rack_redirect(&form.rack_id)
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateCableForm {
+    pub a: String,
+    pub b: String,
+    #[serde(default)]
+    pub label: String,
+    pub rack_id: String,
+}
+
+async fn handle_create_cable(
+    State(state): State<AppState>,
+    Form(form): Form<CreateCableForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::cabling::{Cable, CablingRepository};
+    let cable = Cable {
+        id: None,
+        a: parse_thing(&form.a, "port"),
+        b: parse_thing(&form.b, "port"),
+        label: Some(form.label).filter(|l| !l.is_empty()),
+        length_m: None,
+    };
+    if let Err(e) = CablingRepository::create_cable(&state.db.client, cable).await {
+        tracing::warn!("Rejected cable {} <-> {}: {}", form.a, form.b, e);
+    }
+    rack_redirect(&form.rack_id)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RackForm {
+    pub rack_id: String,
+}
+
+async fn handle_delete_cable(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Form(form): Form<RackForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::cabling::CablingRepository;
+    let cable = parse_thing(&id, "cable");
+    let _ = CablingRepository::delete_cable(&state.db.client, &cable.id.to_raw()).await;
+    rack_redirect(&form.rack_id)
+}
+
+async fn handle_sync_cabling(
+    State(state): State<AppState>,
+    Form(form): Form<RackForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::cabling::CablingRepository;
+    match CablingRepository::sync_connections(&state.db.client).await {
+        Ok(created) => tracing::info!("Created {} connections from cabling", created.len()),
+        Err(e) => tracing::warn!("Cabling sync failed: {}", e),
+    }
+    rack_redirect(&form.rack_id)
+}
+
+// Desk handlers; bookings return to the calendar's booking panel for their date
+fn desk_redirect(date: &str) -> axum::response::Redirect {
+    axum::response::Redirect::to(&format!("/?tab=calendar&modal=desk&date={}", date))
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateDeskForm {
+    pub name: String,
+    pub space_id: String,
+}
+
+async fn handle_create_desk(
+    State(state): State<AppState>,
+    Form(form): Form<CreateDeskForm>,
+) -> impl axum::response::IntoResponse {
+    let desk = nexosim_hybrid::database::geo::Desk {
+        id: None,
+        name: form.name,
+        space_id: parse_thing(&form.space_id, "space"),
+        assigned_to: None,
+    };
+    if let Err(e) = nexosim_hybrid::database::geo::GeoRepository::create_desk(&state.db.client, desk).await {
+        tracing::warn!("Failed to create desk: {}", e);
+    }
+    axum::response::Redirect::to(&format!("/?tab=sites&space_id={}", form.space_id))
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeskBookingForm {
+    pub desk_id: String,
+    pub person_id: String,
+    #[serde(default)]
+    pub kind: String,
+    #[serde(default)]
+    pub date: String,
+}
+
+async fn handle_book_desk(
+    State(state): State<AppState>,
+    Form(form): Form<DeskBookingForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::desks::{BookingKind, DeskBooking, DeskRepository};
+    let desk_id = parse_thing(&form.desk_id, "desk");
+    let person_id = parse_thing(&form.person_id, "person");
+    let booking = match form.kind.parse().unwrap_or_default() {
+        BookingKind::Permanent => DeskBooking::permanent(desk_id, person_id),
+        BookingKind::Day => DeskBooking::day(desk_id, person_id, &form.date),
+    };
+    if let Err(e) = DeskRepository::book(&state.db.client, booking).await {
+        tracing::warn!("Rejected desk booking for {}: {}", form.desk_id, e);
+    }
+    desk_redirect(&form.date)
+}
+
+#[derive(serde::Deserialize)]
+pub struct DateForm {
+    #[serde(default)]
+    pub date: String,
+}
+
+async fn handle_cancel_desk_booking(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Form(form): Form<DateForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::desks::DeskRepository;
+    let booking = parse_thing(&id, "desk_booking");
+    let _ = DeskRepository::cancel(&state.db.client, &booking.id.to_raw()).await;
+    desk_redirect(&form.date)
+}
+
+// Helper to parse Thing from string (handles "table:id" or just "id")
+fn parse_thing(s: &str, default_table: &str) -> surrealdb::sql::Thing {
+    if s.contains(':') {
+        let parts: Vec<&str> = s.splitn(2, ':').collect();
+        surrealdb::sql::Thing::from((parts[0], parts[1]))
+    } else {
+        surrealdb::sql::Thing::from((default_table, s))
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct StartSimulationForm {
+    pub fault_component: Option<u32>,
+    pub fault_step: Option<usize>,
+    pub open_incident: Option<String>,
+}
+
+async fn handle_start_simulation(
+    State(state): State<AppState>,
+    Form(form): Form<StartSimulationForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::simulation::Fault;
+    let faults = match (form.fault_component, form.fault_step) {
+        (Some(component), Some(step)) => {
+            vec![Fault { component, step, open_incident: form.open_incident.is_some() }]
+        }
+        _ => Vec::new(),
+    };
+    if let Err(e) = simulation::run(&state, faults).await {
+        tracing::warn!("Simulation run failed: {}", e);
+    }
+    axum::response::Redirect::to("/?tab=simulation")
+}
+
+async fn handle_delete_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::simulation::SimulationRepository;
+    let _ = SimulationRepository::delete(&state.db.client, &id).await;
+    axum::response::Redirect::to("/?tab=simulation")
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateSandboxForm {
+    #[serde(default)]
+    pub name: String,
+}
+
+fn sandbox_page(id: &str) -> axum::response::Redirect {
+    axum::response::Redirect::to(&format!("/?tab=simulation&sandbox={}", id))
+}
+
+async fn handle_create_sandbox(
+    State(state): State<AppState>,
+    Form(form): Form<CreateSandboxForm>,
+) -> impl axum::response::IntoResponse {
+    match sandbox::branch(&state.db.client, &form.name).await {
+        Ok(created) => sandbox_page(&created.id.map(|t| t.id.to_raw()).unwrap_or_default()),
+        Err(e) => {
+            tracing::warn!("Could not create sandbox: {}", e);
+            axum::response::Redirect::to("/?tab=simulation")
+        }
+    }
+}
+
+/// Apply a form's edit to a sandbox's topology and go back to it
+async fn edit_sandbox(
+    state: &AppState,
+    id: &str,
+    edit: impl FnOnce(&mut nexosim_hybrid::database::sandbox::Topology) -> bool,
+) -> axum::response::Redirect {
+    if let Err(e) = sandbox::edit(&state.db.client, id, edit).await {
+        tracing::warn!("Could not edit sandbox {}: {}", id, e);
+    }
+    sandbox_page(id)
+}
+
+async fn handle_sandbox_add_component(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Form(form): Form<CreateComponentForm>,
+) -> impl axum::response::IntoResponse {
+    edit_sandbox(&state, &id, |topology| {
+        let component_type = match form.component_type.as_str() {
+            "switch" => ComponentType::Switch,
+            _ => ComponentType::Router,
+        };
+        let id = form.id.as_ref().and_then(|s| s.trim().parse::<u32>().ok()).unwrap_or_else(|| topology.next_id());
+        let power = form.power();
+        topology.put_component(ComponentConfig { id, name: form.name, component_type, placement: Default::default(), power });
+        true
+    })
+    .await
+}
+
+async fn handle_sandbox_remove_component(
+    State(state): State<AppState>,
+    Path((id, component)): Path<(String, u32)>,
+) -> impl axum::response::IntoResponse {
+    edit_sandbox(&state, &id, |topology| topology.remove_component(component)).await
+}
+
+async fn handle_sandbox_connect(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Form(form): Form<CreateConnectionForm>,
+) -> impl axum::response::IntoResponse {
+    edit_sandbox(&state, &id, |topology| topology.connect(form.from_id, form.to_id)).await
+}
+
+async fn handle_sandbox_disconnect(
+    State(state): State<AppState>,
+    Path((id, from, to)): Path<(String, u32, u32)>,
+) -> impl axum::response::IntoResponse {
+    edit_sandbox(&state, &id, |topology| topology.disconnect(from, to)).await
+}
+
+async fn handle_sandbox_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::sandbox::SandboxRepository;
+    match SandboxRepository::get(&state.db.client, &id).await {
+        Ok(Some(found)) => {
+            if let Err(e) = sandbox::run(&state, &found).await {
+                tracing::warn!("Sandbox simulation failed: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Could not load sandbox {}: {}", id, e),
+    }
+    sandbox_page(&id)
+}
+
+#[derive(serde::Deserialize)]
+pub struct MergeSandboxForm {
+    /// Checkbox: merge despite conflicts
+    pub force: Option<String>,
+}
+
+async fn handle_sandbox_merge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Form(form): Form<MergeSandboxForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::sandbox::SandboxRepository;
+    let Ok(Some(found)) = SandboxRepository::get(&state.db.client, &id).await else {
+        return axum::response::Redirect::to("/?tab=simulation");
+    };
+    match sandbox::merge(&state.db.client, &found, form.force.is_some()).await {
+        Ok(sandbox::MergeOutcome::Merged(_)) => axum::response::Redirect::to("/?tab=connections"),
+        // The sandbox page lists the conflicts
+        Ok(sandbox::MergeOutcome::Conflicts(_)) => sandbox_page(&id),
+        Err(e) => {
+            tracing::warn!("Could not merge sandbox {}: {}", id, e);
+            sandbox_page(&id)
+        }
+    }
+}
+
+async fn handle_sandbox_discard(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    let _ = nexosim_hybrid::database::sandbox::SandboxRepository::delete(&state.db.client, &id).await;
+    axum::response::Redirect::to("/?tab=simulation")
+}
+
+async fn handle_retry_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    if let Err(e) = state.jobs.retry(&id).await {
+        tracing::warn!("Could not retry job {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=jobs")
+}
+
+async fn handle_run_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    if let Err(e) = scheduler::run(&state, &id).await {
+        tracing::warn!("Could not run schedule {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=jobs")
+}
+
+/// Deliveries listed on the Webhooks page
+const WEBHOOK_DELIVERIES_SHOWN: usize = 100;
+
+#[derive(serde::Deserialize)]
+pub struct CreateWebhookForm {
+    pub name: String,
+    pub url: String,
+    /// Comma-separated event filters
+    pub events: String,
+    pub secret: Option<String>,
+}
+
+async fn handle_create_webhook(
+    State(state): State<AppState>,
+    Form(form): Form<CreateWebhookForm>,
+) -> impl axum::response::IntoResponse {
+    let input = webhooks::WebhookInput {
+        name: form.name,
+        url: form.url,
+        events: form.events.split(',').map(str::to_string).collect(),
+        secret: form.secret,
+        enabled: true,
+    };
+    if let Err(e) = webhooks::create_webhook(&state, input).await {
+        tracing::warn!("Could not register webhook: {:?}", e);
+    }
+    axum::response::Redirect::to("/?tab=webhooks")
+}
+
+async fn handle_test_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    if let Err(e) = webhooks::send_test(&state, &id).await {
+        tracing::warn!("Could not test webhook {}: {:?}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=webhooks")
+}
+
+async fn handle_toggle_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::webhooks::WebhookRepository;
+    if let Ok(Some(mut webhook)) = WebhookRepository::get_by_id(&state.db.client, &id).await {
+        webhook.enabled = !webhook.enabled;
+        if let Err(e) = WebhookRepository::update(&state.db.client, &id, webhook).await {
+            tracing::warn!("Could not update webhook {}: {}", id, e);
+        }
+    }
+    axum::response::Redirect::to("/?tab=webhooks")
+}
+
+async fn handle_delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    if let Err(e) = nexosim_hybrid::database::webhooks::WebhookRepository::delete(&state.db.client, &id).await {
+        tracing::warn!("Could not delete webhook {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=webhooks")
+}
+
+async fn handle_redeliver_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    if let Err(e) = webhooks::redeliver(&state, &id).await {
+        tracing::warn!("Could not redeliver {}: {:?}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=webhooks")
+}
+
+#[derive(serde::Deserialize)]
+pub struct GenerateReportForm {
+    pub kind: String,
+    pub format: String,
+}
+
+async fn handle_generate_report(
+    State(state): State<AppState>,
+    Form(form): Form<GenerateReportForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::reports::{ReportFormat, ReportKind};
+    let result = async {
+        let kind: ReportKind = form.kind.parse()?;
+        let format: ReportFormat = form.format.parse()?;
+        reports::generate(&state.db.client, kind, format).await
+    };
+    if let Err(e) = result.await {
+        tracing::warn!("Could not generate {} report: {}", form.kind, e);
+    }
+    axum::response::Redirect::to("/?tab=reports")
+}
+
+async fn handle_email_report(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    if let Some(persona) = identity::persona(&state, &jar).await {
+        if let Err(e) = state.email.report(&persona, &id).await {
+            tracing::warn!("Could not email report {}: {}", id, e);
+        }
+    }
+    axum::response::Redirect::to("/?tab=reports")
+}
+
+/// Checkboxes are only sent when ticked
+#[derive(serde::Deserialize)]
+pub struct EmailPreferencesForm {
+    pub invites: Option<String>,
+    pub reports: Option<String>,
+    pub mentions: Option<String>,
+}
+
+async fn handle_email_preferences(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Form(form): Form<EmailPreferencesForm>,
+) -> impl axum::response::IntoResponse {
+    if let Some(persona) = identity::persona(&state, &jar).await {
+        let input = email::PreferencesInput {
+            persona,
+            invites: form.invites.is_some(),
+            reports: form.reports.is_some(),
+            mentions: form.mentions.is_some(),
+        };
+        if let Err(e) = email::save_preferences(&state, input).await {
+            tracing::warn!("Could not save email preferences: {:?}", e);
+        }
+    }
+    axum::response::Redirect::to("/?tab=email")
+}
+
+async fn handle_delete_report(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::reports::ReportRepository;
+    if let Err(e) = ReportRepository::delete(&state.db.client, &id).await {
+        tracing::warn!("Could not delete report {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=reports")
+}
+
+// Maintenance and lifecycle handlers
+#[derive(serde::Deserialize)]
+pub struct CreateMaintenanceForm {
+    pub asset_id: String,
+    pub title: String,
+    pub scheduled_date: String,
+    pub description: Option<String>,
+}
+
+async fn handle_create_maintenance(
+    State(state): State<AppState>,
+    Form(form): Form<CreateMaintenanceForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::asset_lifecycle::{AssetLifecycleRepository, MaintenanceTicket, TicketStatus};
+
+    let ticket = MaintenanceTicket {
+        id: None,
+        asset_id: surrealdb::sql::Thing::from(("network_asset", form.asset_id.as_str())),
+        title: form.title,
+        description: form.description.filter(|d| !d.trim().is_empty()),
+        scheduled_date: form.scheduled_date,
+        status: TicketStatus::Open,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = AssetLifecycleRepository::create_ticket(&state.db.client, ticket).await {
+        tracing::warn!("Could not schedule maintenance: {}", e);
+    }
+    axum::response::Redirect::to("/?tab=maintenance")
+}
+
+async fn handle_close_maintenance(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::asset_lifecycle::{AssetLifecycleRepository, TicketStatus};
+
+    if let Err(e) = AssetLifecycleRepository::set_ticket_status(&state.db.client, &id, TicketStatus::Closed).await {
+        tracing::warn!("Could not close maintenance ticket {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=maintenance")
+}
+
+#[derive(serde::Deserialize)]
+pub struct LifecycleTransitionForm {
+    pub asset_id: String,
+    pub state: String,
+    pub note: Option<String>,
+}
+
+async fn handle_lifecycle_transition(
+    State(state): State<AppState>,
+    Form(form): Form<LifecycleTransitionForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::asset_lifecycle::{AssetLifecycleRepository, LifecycleState};
+
+    let result = match form.state.parse::<LifecycleState>() {
+        Ok(to) => {
+            let note = form.note.filter(|n| !n.trim().is_empty());
+            let now = chrono::Utc::now().to_rfc3339();
+            AssetLifecycleRepository::transition(&state.db.client, &form.asset_id, to, note, &now)
+                .await
+                .map(|_| ())
+        }
+        Err(e) => Err(anyhow::Error::msg(e)),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Lifecycle change for asset {} rejected: {}", form.asset_id, e);
+    }
+    axum::response::Redirect::to("/?tab=maintenance")
+}
+
+// Incident board handlers
+#[derive(serde::Deserialize)]
+pub struct CreateIncidentForm {
+    pub title: String,
+    pub severity: String,
+    pub description: Option<String>,
+    pub component_id: Option<String>,
+    pub site_id: Option<String>,
+    pub assignee_id: Option<String>,
+    pub escalation_policy_id: Option<String>,
+}
+
+fn incident_link(key: &str) -> String {
+    format!("/?tab=incidents&incident={}", key)
+}
+
+async fn handle_create_incident(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Form(form): Form<CreateIncidentForm>,
+) -> impl axum::response::IntoResponse {
+    let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+    let input = incidents::IncidentInput {
+        title: form.title,
+        description: form.description,
+        severity: form.severity.parse().unwrap_or_default(),
+        components: non_empty(form.component_id).and_then(|c| c.parse().ok()).into_iter().collect(),
+        sites: non_empty(form.site_id).into_iter().collect(),
+        assignee_id: non_empty(form.assignee_id),
+        escalation_policy_id: non_empty(form.escalation_policy_id),
+    };
+    if input.title.trim().is_empty() {
+        return axum::response::Redirect::to("/?tab=incidents");
+    }
+    match incidents::open(&state, input, identity::persona(&state, &jar).await).await {
+        Ok(incident) => axum::response::Redirect::to(&incident_link(&incident.key())),
+        Err(e) => {
+            tracing::warn!("Could not open incident: {}", e);
+            axum::response::Redirect::to("/?tab=incidents")
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct IncidentStatusForm {
+    pub status: String,
+    pub note: Option<String>,
+}
+
+async fn handle_incident_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Form(form): Form<IncidentStatusForm>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::incidents::IncidentStatus;
+
+    let result = match form.status.parse::<IncidentStatus>() {
+        Ok(to) => {
+            let author = identity::persona(&state, &jar).await;
+            incidents::set_status(&state, &id, to, form.note.as_deref(), author).await.map(|_| ())
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Status change for incident {} rejected: {}", id, e);
+    }
+    axum::response::Redirect::to(&incident_link(&id))
+}
+
+#[derive(serde::Deserialize)]
+pub struct AssignIncidentForm {
+    pub assignee_id: Option<String>,
+}
+
+async fn handle_assign_incident(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Form(form): Form<AssignIncidentForm>,
+) -> impl axum::response::IntoResponse {
+    let assignee = form.assignee_id.filter(|a| !a.is_empty());
+    let author = identity::persona(&state, &jar).await;
+    if let Err(e) = incidents::assign(&state, &id, assignee.as_deref(), author).await {
+        tracing::warn!("Could not assign incident {}: {}", id, e);
+    }
+    axum::response::Redirect::to(&incident_link(&id))
+}
+
+#[derive(serde::Deserialize)]
+pub struct IncidentNoteForm {
+    pub text: String,
+}
+
+async fn handle_incident_note(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    jar: CookieJar,
+    Form(form): Form<IncidentNoteForm>,
+) -> impl axum::response::IntoResponse {
+    if !form.text.trim().is_empty() {
+        let author = identity::persona(&state, &jar).await;
+        if let Err(e) = incidents::note(&state, &id, &form.text, author).await {
+            tracing::warn!("Could not add a note to incident {}: {}", id, e);
+        }
+    }
+    axum::response::Redirect::to(&incident_link(&id))
+}
+
+async fn handle_delete_incident(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::incidents::IncidentRepository;
+
+    if let Err(e) = IncidentRepository::delete(&state.db.client, &id).await {
+        tracing::warn!("Could not delete incident {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=incidents")
+}
+
+// On-call handlers; their forms repeat fields (members, levels), so they
+// arrive as name/value pairs
+fn form_values<'a>(form: &'a [(String, String)], name: &'a str) -> impl Iterator<Item = &'a str> {
+    form.iter().filter(move |(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+fn form_value<'a>(form: &'a [(String, String)], name: &'a str) -> &'a str {
+    form_values(form, name).next().unwrap_or_default()
+}
+
+async fn handle_create_rotation(
+    State(state): State<AppState>,
+    Form(form): Form<Vec<(String, String)>>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::oncall::OnCallRepository;
+
+    let input = oncall::RotationInput {
+        name: form_value(&form, "name").to_string(),
+        members: form_values(&form, "member").map(String::from).collect(),
+        // datetime-local inputs send `YYYY-MM-DDTHH:MM`
+        start: form_value(&form, "start").replace('T', " "),
+        shift_hours: form_value(&form, "shift_hours").parse().unwrap_or_default(),
+    };
+    let result = match input.validate(&state.db.client).await {
+        Ok(rotation) => OnCallRepository::create_rotation(&state.db.client, rotation).await.map(|_| ()),
+        Err(e) => Err(anyhow::anyhow!(e.message().to_string())),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Could not create rotation: {}", e);
+    }
+    axum::response::Redirect::to("/?tab=oncall")
+}
+
+async fn handle_delete_rotation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    if let Err(e) = oncall::remove_rotation(&state.db.client, &id).await {
+        tracing::warn!("Could not delete rotation {}: {}", id, e.message());
+    }
+    axum::response::Redirect::to("/?tab=oncall")
+}
+
+async fn handle_create_policy(
+    State(state): State<AppState>,
+    Form(form): Form<Vec<(String, String)>>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::oncall::OnCallRepository;
+
+    // Level rows left without a rotation are skipped
+    let levels = form_values(&form, "level_rotation")
+        .zip(form_values(&form, "level_minutes"))
+        .filter(|(rotation, _)| !rotation.is_empty())
+        .map(|(rotation, minutes)| oncall::LevelInput {
+            rotation_id: rotation.to_string(),
+            after_minutes: minutes.parse().unwrap_or_default(),
+        })
+        .collect();
+    let input = oncall::PolicyInput {
+        name: form_value(&form, "name").to_string(),
+        levels,
+        default: form_value(&form, "default") == "on",
+    };
+    let result = match input.validate(&state.db.client).await {
+        Ok(policy) => OnCallRepository::create_policy(&state.db.client, policy).await.map(|_| ()),
+        Err(e) => Err(anyhow::anyhow!(e.message().to_string())),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Could not create escalation policy: {}", e);
+    }
+    axum::response::Redirect::to("/?tab=oncall")
+}
+
+async fn handle_delete_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl axum::response::IntoResponse {
+    use nexosim_hybrid::database::oncall::OnCallRepository;
+
+    if let Err(e) = OnCallRepository::delete_policy(&state.db.client, &id).await {
+        tracing::warn!("Could not delete escalation policy {}: {}", id, e);
+    }
+    axum::response::Redirect::to("/?tab=oncall")
+}
+
+// Event creation handler
+#[derive(serde::Deserialize)]
+pub struct CreateEventForm {
+    pub title: String,
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub start_date: Option<String>,
+    pub start_time: Option<String>,
+    pub end_date: Option<String>,
+    pub end_time: Option<String>,
+    pub timezone: Option<String>,
+    pub description: Option<String>,
+    pub is_recurring: Option<String>,
+    pub freq: Option<String>,
+    pub interval: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_byday")]
+    pub byday: Vec<String>,
+    pub until_type: Option<String>,
+    pub until_date: Option<String>,
+    pub count: Option<u32>,
 }
 
 // Custom deserializer to handle both single string and array of strings from form
@@ -963,8 +2352,12 @@ async fn handle_create_event(
         timezone: form.timezone.unwrap_or_else(|| "America/New_York".to_string()),
     };
     
-    if let Err(e) = CalendarRepository::create(&state.db.client, meeting).await {
-        tracing::warn!("Failed to create event: {}", e);
+    match CalendarRepository::create(&state.db.client, meeting).await {
+        Ok(created) => {
+            state.notifications.invite(&created).await;
+            state.email.invite(&created).await;
+        }
+        Err(e) => tracing::warn!("Failed to create event: {}", e),
     }
     
     axum::response::Redirect::to("/?tab=calendar")
@@ -974,31 +2367,52 @@ async fn handle_create_event(
 // Persona Handlers (Dev Mode Only)
 // ============================================================================
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, utoipa::ToSchema)]
 struct PersonaResponse {
     dev_mode: bool,
     current_persona: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/persona",
+    tag = "persona",
+    responses((status = 200, description = "Current persona", body = PersonaResponse))
+)]
 async fn handle_get_persona(
     State(state): State<AppState>,
+    jar: CookieJar,
 ) -> axum::Json<PersonaResponse> {
-    let persona = state.current_persona.lock().await.clone();
+    let persona = identity::persona(&state, &jar).await;
     axum::Json(PersonaResponse {
         dev_mode: state.dev_mode,
         current_persona: persona,
     })
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct SetPersonaRequest {
     name: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/persona",
+    tag = "persona",
+    request_body = SetPersonaRequest,
+    responses(
+        (status = 200, description = "Persona updated", body = PersonaResponse),
+        (status = 403, description = "People sign in through the identity provider", body = api::ApiError),
+    )
+)]
 async fn handle_set_persona(
     State(state): State<AppState>,
     axum::Json(payload): axum::Json<SetPersonaRequest>,
 ) -> impl axum::response::IntoResponse {
+    // Picking a persona would bypass OIDC sign-in outside dev mode
+    if state.identity.sso() && !state.dev_mode && payload.name.is_some() {
+        return api::ApiError::forbidden("Sign in through the identity provider").into_response();
+    }
     let mut persona = state.current_persona.lock().await;
     *persona = payload.name.clone();
     axum::Json(PersonaResponse {
@@ -1007,32 +2421,56 @@ async fn handle_set_persona(
     }).into_response()
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/persona",
+    tag = "persona",
+    responses((status = 200, description = "Persona cleared", body = PersonaResponse))
+)]
 async fn handle_delete_persona(
     State(state): State<AppState>,
+    jar: CookieJar,
 ) -> impl axum::response::IntoResponse {
-    let mut persona = state.current_persona.lock().await;
-    *persona = None;
-    axum::Json(PersonaResponse {
+    *state.current_persona.lock().await = None;
+    let jar = state.identity.end_session(jar).await;
+    (jar, axum::Json(PersonaResponse {
         dev_mode: state.dev_mode,
         current_persona: None,
-    })
+    }))
 }
 
 // ============================================================================
-// SSE Handler for Dev Auto-Reload
+// SSE Handler for Dev Auto-Reload and Data Change Events
 // ============================================================================
 
 async fn sse_handler() -> impl axum::response::IntoResponse {
     use axum::response::sse::{Event, Sse};
     use futures::stream;
+    use nexosim_hybrid::database::versions;
     use std::time::Duration;
     use tokio_stream::StreamExt;
     
-    let stream = stream::repeat_with(|| {
+    let keepalive = stream::repeat_with(|| {
         Event::default().data("keepalive")
     })
-    .map(Ok::<_, std::convert::Infallible>)
     .throttle(Duration::from_secs(10));
+
+    // `change` events name the kinds of data written since the last one, so
+    // clients can drop what they cached from them
+    let changes = stream::unfold(versions::snapshot(), |seen| async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let now = versions::snapshot();
+            let changed = versions::changed(&seen, &now);
+            if !changed.is_empty() {
+                let names: Vec<&str> = changed.iter().map(|d| d.name()).collect();
+                let event = Event::default().event("change").json_data(names).ok()?;
+                return Some((event, now));
+            }
+        }
+    });
+
+    let stream = keepalive.merge(changes).map(Ok::<_, std::convert::Infallible>);
     
     Sse::new(stream).keep_alive(
         axum::response::sse::KeepAlive::new()
@@ -0,0 +1,105 @@
+//! Streamed list responses
+//!
+//! Large lists are answered as one JSON array by default. A client that sends
+//! `Accept: application/x-ndjson` gets one JSON value per line instead,
+//! serialized and sent in batches, so it can render the first rows while the
+//! rest are still on the way.
+//!
+//! NDJSON is left out of response compression (see [`compress_when`]); the
+//! encoder would otherwise hold the batches back until its buffer fills.
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use tower_http::compression::predicate::{And, DefaultPredicate, NotForContentType, Predicate};
+
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Rows serialized into each chunk of the body
+const BATCH: usize = 64;
+
+/// Whether the request asked for NDJSON
+pub fn requested(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media| media.split(';').next().unwrap_or_default().trim() == CONTENT_TYPE)
+}
+
+/// Body chunks of `rows`, one line per row
+fn chunks<T: Serialize>(rows: Vec<T>) -> impl Iterator<Item = std::io::Result<Vec<u8>>> {
+    let mut rows = rows.into_iter();
+    std::iter::from_fn(move || {
+        let mut chunk = Vec::new();
+        for row in rows.by_ref().take(BATCH) {
+            if let Err(e) = serde_json::to_writer(&mut chunk, &row) {
+                return Some(Err(e.into()));
+            }
+            chunk.push(b'\n');
+        }
+        (!chunk.is_empty()).then_some(Ok(chunk))
+    })
+}
+
+/// `rows` as a streamed NDJSON response
+pub fn stream<T: Serialize + Send + 'static>(rows: Vec<T>) -> Response {
+    let body = Body::from_stream(futures::stream::iter(chunks(rows)));
+    (
+        [(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE))],
+        body,
+    )
+        .into_response()
+}
+
+/// `rows` as NDJSON if the request asked for it, else as a JSON array
+pub fn list<T: Serialize + Send + 'static>(headers: &HeaderMap, rows: Vec<T>) -> Response {
+    if requested(headers) {
+        stream(rows)
+    } else {
+        Json(rows).into_response()
+    }
+}
+
+/// Which responses the compression layer may compress
+pub fn compress_when() -> And<DefaultPredicate, NotForContentType> {
+    DefaultPredicate::new().and(NotForContentType::const_new(CONTENT_TYPE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_header_selects_ndjson() {
+        let mut headers = HeaderMap::new();
+        assert!(!requested(&headers));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert!(!requested(&headers));
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/x-ndjson; q=1, application/json; q=0.5"),
+        );
+        assert!(requested(&headers));
+    }
+
+    #[test]
+    fn rows_are_batched_one_per_line() {
+        let rows: Vec<u32> = (0..(BATCH as u32 + 2)).collect();
+        let body: Vec<Vec<u8>> = chunks(rows).map(Result::unwrap).collect();
+        assert_eq!(body.len(), 2);
+        assert_eq!(body[1], b"64\n65\n");
+
+        let lines: Vec<u32> = body
+            .concat()
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), BATCH + 2);
+        assert_eq!(chunks(Vec::<u32>::new()).count(), 0);
+    }
+}
@@ -0,0 +1,701 @@
+//! NetBox import
+//!
+//! Seeds the database from a NetBox instance instead of hand-entered
+//! scenarios: sites, racks, devices and the cables between device
+//! interfaces are read from its REST API and written as Rubigo records,
+//! with fields mapped as `[runtime.netbox]` says (see [`operations::netbox`]).
+//! NetBox has no buildings or floors, so racks are placed in a building,
+//! floor and space named after mapped fields, created as needed.
+//!
+//! Every imported object is linked to its record in `netbox_link`, so a
+//! second sync finds what the first created. Without re-sync it only adds
+//! new objects; with it, linked records are refreshed and those whose
+//! objects are gone from NetBox are removed. Syncs run as jobs, from the
+//! `netbox.sync` schedule task or `POST /api/netbox/sync`.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use config::NetboxSettings;
+use nexosim_hybrid::database::cabling::{Cable, CablingRepository, Port};
+use nexosim_hybrid::database::geo::{Building, Device, Floor, GeoRepository, Rack, Site, Space};
+use nexosim_hybrid::database::jobs::{Job, JobRepository};
+use nexosim_hybrid::database::netbox::{NetboxLink, NetboxRepository};
+use nexosim_hybrid::database::{Database, DbClient};
+use operations::netbox::{
+    cable_ends, lookup, object_id, plan, related_id, stale, FieldMap, SyncAction,
+    DEFAULT_RACK_HEIGHT,
+};
+use reqwest::header::{ACCEPT, AUTHORIZATION};
+use serde::Deserialize;
+use serde_json::Value;
+use surrealdb::sql::Thing;
+
+use crate::api::{found, ApiError, ApiResult};
+use crate::jobs::JobContext;
+use crate::AppState;
+
+/// How long NetBox has to answer one page
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Names used when a rack's mapped building, floor or space is blank
+const DEFAULT_FLOOR: &str = "Ground floor";
+const DEFAULT_SPACE: &str = "Data hall";
+
+/// One page of a NetBox list endpoint
+#[derive(Deserialize)]
+struct Page {
+    next: Option<String>,
+    results: Vec<Value>,
+}
+
+/// What a sync did
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncReport {
+    pub created: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub skipped: usize,
+    /// Objects that could not be imported, and why
+    pub problems: Vec<String>,
+}
+
+impl SyncReport {
+    fn problem(&mut self, problem: String) {
+        tracing::warn!("NetBox import: {}", problem);
+        self.problems.push(problem);
+    }
+}
+
+impl std::fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Created {}, updated {}, removed {} and skipped {} NetBox objects",
+            self.created, self.updated, self.removed, self.skipped
+        )?;
+        if !self.problems.is_empty() {
+            write!(
+                f,
+                "; {} objects could not be imported: {}",
+                self.problems.len(),
+                self.problems.join("; ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct NetboxImporter {
+    db: Arc<Database>,
+    client: reqwest::Client,
+    settings: NetboxSettings,
+}
+
+impl NetboxImporter {
+    pub fn new(db: Arc<Database>, settings: &NetboxSettings) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            db,
+            client,
+            settings: settings.clone(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.settings.enabled()
+    }
+
+    /// Every object at `endpoint`, e.g. `dcim/sites`, following pagination
+    async fn fetch(&self, endpoint: &str) -> anyhow::Result<Vec<Value>> {
+        let base = self
+            .settings
+            .url
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("No NetBox url is set"))?;
+        let mut next = Some(format!(
+            "{}/api/{}/?limit={}",
+            base.trim_end_matches('/'),
+            endpoint,
+            self.settings.page_size.max(1)
+        ));
+        let mut objects = Vec::new();
+        while let Some(url) = next {
+            let mut request = self.client.get(&url).header(ACCEPT, "application/json");
+            if let Some(token) = &self.settings.token {
+                request = request.header(AUTHORIZATION, format!("Token {token}"));
+            }
+            let page: Page = request.send().await?.error_for_status()?.json().await?;
+            objects.extend(page.results);
+            next = page.next;
+        }
+        Ok(objects)
+    }
+
+    /// Import sites, racks, devices and cables; see the module docs for
+    /// what `resync` changes
+    pub async fn sync(&self, ctx: &JobContext, resync: bool) -> anyhow::Result<SyncReport> {
+        if !self.enabled() {
+            anyhow::bail!("NetBox import is off; set [runtime.netbox] url");
+        }
+        let fields = FieldMap::new(&self.settings.fields, &self.settings.statuses);
+        let unknown = fields.unknown_fields();
+        if !unknown.is_empty() {
+            anyhow::bail!(
+                "Unknown fields in [runtime.netbox.fields]: {}",
+                unknown.join(", ")
+            );
+        }
+        let mut sync = SyncRun {
+            db: &self.db.client,
+            fields,
+            resync,
+            synced_at: chrono::Utc::now().to_rfc3339(),
+            report: SyncReport::default(),
+        };
+
+        let sites = self.fetch("dcim/sites").await?;
+        let site_seen = sync.sites(&sites).await?;
+        ctx.step(1, 4).await;
+        let racks = self.fetch("dcim/racks").await?;
+        let rack_seen = sync.racks(&racks).await?;
+        ctx.step(2, 4).await;
+        let devices = self.fetch("dcim/devices").await?;
+        let device_seen = sync.devices(&devices).await?;
+        ctx.step(3, 4).await;
+        let cables = self.fetch("dcim/cables").await?;
+        let (cable_seen, interface_seen) = sync.cables(&cables).await?;
+
+        // Dependents go first, so nothing is left pointing at a removed record
+        if resync {
+            sync.retire("cable", &cable_seen).await?;
+            sync.retire("interface", &interface_seen).await?;
+            sync.retire("device", &device_seen).await?;
+            sync.retire("rack", &rack_seen).await?;
+            sync.retire("site", &site_seen).await?;
+        }
+        Ok(sync.report)
+    }
+}
+
+/// One sync in progress
+struct SyncRun<'a> {
+    db: &'a DbClient,
+    fields: FieldMap,
+    resync: bool,
+    synced_at: String,
+    report: SyncReport,
+}
+
+fn record_key(record: &Thing) -> String {
+    record.id.to_raw()
+}
+
+impl SyncRun<'_> {
+    async fn link(
+        &mut self,
+        kind: &str,
+        netbox_id: u64,
+        record: Option<Thing>,
+    ) -> anyhow::Result<()> {
+        let record = record
+            .ok_or_else(|| anyhow::anyhow!("NetBox {kind} {netbox_id} was saved without an id"))?;
+        NetboxRepository::link(self.db, kind, netbox_id, record, &self.synced_at).await
+    }
+
+    /// What to do with `netbox_id` given the records linked so far, and
+    /// the existing record to update when there is one
+    fn action(&self, linked: &HashMap<u64, Thing>, netbox_id: u64) -> (SyncAction, Option<String>) {
+        let record = linked.get(&netbox_id);
+        (plan(record.is_some(), self.resync), record.map(record_key))
+    }
+
+    fn count(&mut self, action: SyncAction) {
+        match action {
+            SyncAction::Create => self.report.created += 1,
+            SyncAction::Update => self.report.updated += 1,
+            SyncAction::Skip => self.report.skipped += 1,
+        }
+    }
+
+    async fn sites(&mut self, sites: &[Value]) -> anyhow::Result<BTreeSet<u64>> {
+        let linked = NetboxRepository::records(self.db, "site").await?;
+        let mut seen = BTreeSet::new();
+        for object in sites {
+            let Some(netbox_id) = object_id(object) else {
+                continue;
+            };
+            seen.insert(netbox_id);
+            let Some(name) = self.fields.text(object, "site_name") else {
+                self.report.problem(format!("site {netbox_id} has no name"));
+                continue;
+            };
+            let (action, key) = self.action(&linked, netbox_id);
+            if action == SyncAction::Skip {
+                self.count(action);
+                continue;
+            }
+            let existing: Option<Site> = match &key {
+                Some(key) => self.db.select(("site", key.as_str())).await?,
+                None => None,
+            };
+            let status = self
+                .fields
+                .text(object, "site_status")
+                .unwrap_or_else(|| "active".to_string());
+            let site = Site {
+                id: None,
+                name,
+                // Regions are Rubigo's own; keep any the site was given
+                region_id: existing.and_then(|s| s.region_id),
+                location: (
+                    self.fields.number(object, "site_lon").unwrap_or_default(),
+                    self.fields.number(object, "site_lat").unwrap_or_default(),
+                ),
+                status: self.fields.status(&status),
+            };
+            // A linked record someone deleted since is created again
+            let saved = match key {
+                Some(key) => GeoRepository::update_site(self.db, &key, site.clone()).await?,
+                None => None,
+            };
+            let saved = match saved {
+                Some(saved) => saved,
+                None => GeoRepository::create_site(self.db, site).await?,
+            };
+            self.link("site", netbox_id, saved.id).await?;
+            self.count(action);
+        }
+        Ok(seen)
+    }
+
+    async fn racks(&mut self, racks: &[Value]) -> anyhow::Result<BTreeSet<u64>> {
+        let sites = NetboxRepository::records(self.db, "site").await?;
+        let linked = NetboxRepository::records(self.db, "rack").await?;
+        let mut places = Places::load(self.db).await?;
+        let mut seen = BTreeSet::new();
+        for object in racks {
+            let Some(netbox_id) = object_id(object) else {
+                continue;
+            };
+            seen.insert(netbox_id);
+            let Some(site) = related_id(object, "site").and_then(|id| sites.get(&id)) else {
+                self.report
+                    .problem(format!("rack {netbox_id} is not at an imported site"));
+                continue;
+            };
+            let (action, key) = self.action(&linked, netbox_id);
+            if action == SyncAction::Skip {
+                self.count(action);
+                continue;
+            }
+            let site_name = lookup(object, "site.name")
+                .and_then(Value::as_str)
+                .unwrap_or("Site");
+            let building = self
+                .fields
+                .text(object, "building")
+                .unwrap_or_else(|| site_name.to_string());
+            let floor = self
+                .fields
+                .text(object, "floor")
+                .unwrap_or_else(|| DEFAULT_FLOOR.to_string());
+            let space = self
+                .fields
+                .text(object, "space")
+                .unwrap_or_else(|| DEFAULT_SPACE.to_string());
+            let rack = Rack {
+                id: None,
+                name: self
+                    .fields
+                    .text(object, "rack_name")
+                    .unwrap_or_else(|| format!("rack-{netbox_id}")),
+                space_id: places
+                    .space(self.db, site, &building, &floor, &space)
+                    .await?,
+                height_u: self
+                    .fields
+                    .number(object, "rack_height")
+                    .map(|u| u.clamp(1.0, u8::MAX as f64) as u8)
+                    .unwrap_or(DEFAULT_RACK_HEIGHT),
+            };
+            let saved = match key {
+                Some(key) => GeoRepository::update_rack(self.db, &key, rack.clone()).await?,
+                None => None,
+            };
+            let saved = match saved {
+                Some(saved) => saved,
+                None => GeoRepository::create_rack(self.db, rack).await?,
+            };
+            self.link("rack", netbox_id, saved.id).await?;
+            self.count(action);
+        }
+        Ok(seen)
+    }
+
+    async fn devices(&mut self, devices: &[Value]) -> anyhow::Result<BTreeSet<u64>> {
+        let racks = NetboxRepository::records(self.db, "rack").await?;
+        let linked = NetboxRepository::records(self.db, "device").await?;
+        let mut seen = BTreeSet::new();
+        for object in devices {
+            let Some(netbox_id) = object_id(object) else {
+                continue;
+            };
+            seen.insert(netbox_id);
+            // Unracked devices have nowhere to go
+            let rack = related_id(object, "rack").and_then(|id| racks.get(&id));
+            let position = self.fields.number(object, "device_position");
+            let (Some(rack), Some(position)) = (rack, position) else {
+                self.report.skipped += 1;
+                continue;
+            };
+            let (action, key) = self.action(&linked, netbox_id);
+            if action == SyncAction::Skip {
+                self.count(action);
+                continue;
+            }
+            let existing: Option<Device> = match &key {
+                Some(key) => self.db.select(("device", key.as_str())).await?,
+                None => None,
+            };
+            let device = Device {
+                id: None,
+                // NetBox device names are optional
+                name: self
+                    .fields
+                    .text(object, "device_name")
+                    .unwrap_or_else(|| format!("device-{netbox_id}")),
+                rack_id: rack.clone(),
+                position_u: position.clamp(0.0, u8::MAX as f64) as u8,
+                // Kept from the last sync; NetBox holds these on the device type
+                height_u: existing.as_ref().map_or(1, |d| d.height_u),
+                power_w: existing.as_ref().map_or(0, |d| d.power_w),
+                component_id: existing.and_then(|d| d.component_id),
+            };
+            let saved = match key {
+                Some(key) => GeoRepository::update_device(self.db, &key, device.clone()).await?,
+                None => None,
+            };
+            let saved = match saved {
+                Some(saved) => saved,
+                None => GeoRepository::create_device(self.db, device).await?,
+            };
+            self.link("device", netbox_id, saved.id).await?;
+            self.count(action);
+        }
+        Ok(seen)
+    }
+
+    /// Import cables between device interfaces, creating a port for each
+    /// interface on the way; returns the cables and interfaces seen
+    async fn cables(&mut self, cables: &[Value]) -> anyhow::Result<(BTreeSet<u64>, BTreeSet<u64>)> {
+        let devices = NetboxRepository::records(self.db, "device").await?;
+        let mut interfaces = NetboxRepository::records(self.db, "interface").await?;
+        let linked = NetboxRepository::records(self.db, "cable").await?;
+        let mut seen = BTreeSet::new();
+        let mut interface_seen = BTreeSet::new();
+        for object in cables {
+            let Some(netbox_id) = object_id(object) else {
+                continue;
+            };
+            seen.insert(netbox_id);
+            let Some((a, b)) = cable_ends(object) else {
+                self.report.skipped += 1;
+                continue;
+            };
+            interface_seen.extend([a.interface, b.interface]);
+            let (action, key) = self.action(&linked, netbox_id);
+            if action == SyncAction::Skip {
+                self.count(action);
+                continue;
+            }
+            let mut ports = Vec::new();
+            for end in [&a, &b] {
+                let Some(device) = devices.get(&end.device) else {
+                    break;
+                };
+                let port = match interfaces.get(&end.interface) {
+                    Some(port) => port.clone(),
+                    None => {
+                        let port = Port {
+                            id: None,
+                            owner: device.clone(),
+                            name: end.name.clone(),
+                            media: Default::default(),
+                        };
+                        let created = CablingRepository::create_port(self.db, port).await?;
+                        self.link("interface", end.interface, created.id.clone())
+                            .await?;
+                        let id = created
+                            .id
+                            .ok_or_else(|| anyhow::anyhow!("Port created without id"))?;
+                        interfaces.insert(end.interface, id.clone());
+                        id
+                    }
+                };
+                ports.push(port);
+            }
+            let [port_a, port_b] = ports.as_slice() else {
+                self.report.problem(format!(
+                    "cable {netbox_id} connects a device that was not imported"
+                ));
+                continue;
+            };
+            let cable = Cable {
+                id: None,
+                a: port_a.clone(),
+                b: port_b.clone(),
+                label: self.fields.text(object, "cable_label"),
+                length_m: self.fields.number(object, "cable_length").map(|m| m as f32),
+            };
+            let existing: Option<Cable> = match &key {
+                Some(key) => self.db.select(("cable", key.as_str())).await?,
+                None => None,
+            };
+            let saved = match (existing, key) {
+                (Some(old), Some(key)) if old.a == cable.a && old.b == cable.b => {
+                    CablingRepository::update_cable(self.db, &key, cable).await?
+                }
+                (_, key) => {
+                    // Re-patched in NetBox: move the cable by replacing it
+                    if let Some(key) = key {
+                        CablingRepository::delete_cable(self.db, &key).await?;
+                    }
+                    match CablingRepository::create_cable(self.db, cable).await {
+                        Ok(created) => Some(created),
+                        Err(e) => {
+                            self.report.problem(format!("cable {netbox_id}: {e}"));
+                            continue;
+                        }
+                    }
+                }
+            };
+            self.link("cable", netbox_id, saved.and_then(|c| c.id))
+                .await?;
+            self.count(action);
+        }
+        Ok((seen, interface_seen))
+    }
+
+    /// Remove records of `kind` whose NetBox objects were not `seen`
+    async fn retire(&mut self, kind: &str, seen: &BTreeSet<u64>) -> anyhow::Result<()> {
+        let linked = NetboxRepository::records(self.db, kind).await?;
+        for netbox_id in stale(linked.keys().copied(), seen) {
+            let key = record_key(&linked[&netbox_id]);
+            let deleted = match kind {
+                "site" => GeoRepository::delete_site(self.db, &key).await?.is_some(),
+                "rack" => GeoRepository::delete_rack(self.db, &key).await?.is_some(),
+                "device" => GeoRepository::delete_device(self.db, &key).await?.is_some(),
+                "interface" => CablingRepository::delete_port(self.db, &key)
+                    .await?
+                    .is_some(),
+                _ => CablingRepository::delete_cable(self.db, &key)
+                    .await?
+                    .is_some(),
+            };
+            if !deleted {
+                tracing::debug!("NetBox {} {} was already gone", kind, netbox_id);
+            }
+            NetboxRepository::unlink(self.db, kind, netbox_id).await?;
+            self.report.removed += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Buildings, floors and spaces racks are placed in, found by name under
+/// their parent and created when missing
+struct Places {
+    buildings: Vec<Building>,
+    floors: Vec<Floor>,
+    spaces: Vec<Space>,
+}
+
+impl Places {
+    async fn load(db: &DbClient) -> anyhow::Result<Self> {
+        Ok(Self {
+            buildings: GeoRepository::list_all_buildings(db).await?,
+            floors: GeoRepository::list_all_floors(db).await?,
+            spaces: GeoRepository::list_all_spaces(db).await?,
+        })
+    }
+
+    async fn space(
+        &mut self,
+        db: &DbClient,
+        site: &Thing,
+        building: &str,
+        floor: &str,
+        space: &str,
+    ) -> anyhow::Result<Thing> {
+        let building_id = match self
+            .buildings
+            .iter()
+            .find(|b| &b.site_id == site && b.name == building)
+        {
+            Some(existing) => existing.id.clone(),
+            None => {
+                let created = GeoRepository::create_building(
+                    db,
+                    Building {
+                        id: None,
+                        name: building.to_string(),
+                        site_id: site.clone(),
+                    },
+                )
+                .await?;
+                self.buildings.push(created.clone());
+                created.id
+            }
+        }
+        .ok_or_else(|| anyhow::anyhow!("Building '{building}' has no id"))?;
+
+        let floor_id = match self
+            .floors
+            .iter()
+            .find(|f| f.building_id == building_id && f.name == floor)
+        {
+            Some(existing) => existing.id.clone(),
+            None => {
+                let created = GeoRepository::create_floor(
+                    db,
+                    Floor {
+                        id: None,
+                        name: floor.to_string(),
+                        building_id,
+                        level: 0,
+                        outline: Vec::new(),
+                    },
+                )
+                .await?;
+                self.floors.push(created.clone());
+                created.id
+            }
+        }
+        .ok_or_else(|| anyhow::anyhow!("Floor '{floor}' has no id"))?;
+
+        match self
+            .spaces
+            .iter()
+            .find(|s| s.floor_id == floor_id && s.name == space)
+        {
+            Some(existing) => existing.id.clone(),
+            None => {
+                let created = GeoRepository::create_space(
+                    db,
+                    Space {
+                        id: None,
+                        name: space.to_string(),
+                        floor_id,
+                        locator: space.to_string(),
+                        space_type: None,
+                        bounds: None,
+                    },
+                )
+                .await?;
+                self.spaces.push(created.clone());
+                created.id
+            }
+        }
+        .ok_or_else(|| anyhow::anyhow!("Space '{space}' has no id"))
+    }
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct SyncQuery {
+    /// Refresh and prune earlier imports; `[runtime.netbox] resync` when unset
+    pub resync: Option<bool>,
+}
+
+/// Queue a sync with NetBox
+#[utoipa::path(
+    post,
+    path = "/api/netbox/sync",
+    tag = "netbox",
+    params(SyncQuery),
+    responses(
+        (status = 202, description = "Sync queued; its outcome is the job's result", body = Job),
+        (status = 400, description = "No NetBox url is set", body = ApiError),
+    )
+)]
+pub async fn sync(
+    State(state): State<AppState>,
+    Query(query): Query<SyncQuery>,
+) -> ApiResult<(StatusCode, Json<Job>)> {
+    if !state.netbox.enabled() {
+        return Err(ApiError::bad_request(
+            "NetBox import is off; set [runtime.netbox] url",
+        ));
+    }
+    let resync = query.resync.unwrap_or(state.settings.netbox.resync);
+    let importer = state.netbox.clone();
+    let handle = state
+        .jobs
+        .enqueue("netbox.sync", "Sync from NetBox", move |ctx| {
+            let importer = importer.clone();
+            async move { Ok(importer.sync(&ctx, resync).await?.to_string()) }
+        })
+        .await?;
+    let job = found(
+        JobRepository::get_by_id(&state.db.client, &handle.key).await?,
+        "Job",
+        &handle.key,
+    )?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct LinksQuery {
+    /// `site`, `rack`, `device`, `interface` or `cable`; every kind when unset
+    pub kind: Option<String>,
+}
+
+/// Imported NetBox objects with the records they became
+#[utoipa::path(
+    get,
+    path = "/api/netbox/links",
+    tag = "netbox",
+    params(LinksQuery),
+    responses(
+        (status = 200, description = "Links by kind and NetBox id", body = Vec<NetboxLink>),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn links(
+    State(state): State<AppState>,
+    Query(query): Query<LinksQuery>,
+) -> ApiResult<Json<Vec<NetboxLink>>> {
+    Ok(Json(
+        NetboxRepository::list(&state.db.client, query.kind.as_deref()).await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_summarises_problems() {
+        let mut report = SyncReport {
+            created: 3,
+            updated: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            report.to_string(),
+            "Created 3, updated 1, removed 0 and skipped 0 NetBox objects"
+        );
+        report.problem("rack 9 is not at an imported site".to_string());
+        assert!(report
+            .to_string()
+            .ends_with("1 objects could not be imported: rack 9 is not at an imported site"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Notification hub
+//!
+//! Stores notifications and fans them out to open inboxes. Each browser
+//! tab subscribes through `GET /api/notifications/stream?persona=...`
+//! (server-sent events) and receives only what is visible to its persona:
+//! a `notification` event per new record, then an `unread` event with the
+//! persona's new unread count.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum_extra::extract::cookie::CookieJar;
+use futures::stream::{self, Stream, StreamExt};
+use nexosim_hybrid::database::calendar::Meeting;
+use nexosim_hybrid::database::geo::GeoRepository;
+use nexosim_hybrid::database::notifications::{
+    meeting_invites, Notification, NotificationRepository,
+};
+use nexosim_hybrid::database::Database;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::api::ApiResult;
+use crate::identity::acting_as;
+use crate::AppState;
+
+/// Notifications buffered for slow subscribers before they start lagging
+const CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Clone)]
+pub struct NotificationHub {
+    db: Arc<Database>,
+    tx: broadcast::Sender<Notification>,
+}
+
+impl NotificationHub {
+    pub fn new(db: Arc<Database>) -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { db, tx }
+    }
+
+    /// Store a notification and push it to connected inboxes
+    ///
+    /// Failures are logged; a notification is never worth failing the
+    /// action that raised it.
+    pub async fn notify(&self, mut notification: Notification) {
+        notification.created_at = crate::clock::now();
+        match NotificationRepository::create(&self.db.client, notification).await {
+            // No receivers is not an error
+            Ok(created) => {
+                let _ = self.tx.send(created);
+            }
+            Err(e) => tracing::warn!("Failed to store notification: {}", e),
+        }
+    }
+
+    pub async fn notify_all(&self, notifications: impl IntoIterator<Item = Notification>) {
+        for notification in notifications {
+            self.notify(notification).await;
+        }
+    }
+
+    /// Invite a meeting's participants
+    pub async fn invite(&self, meeting: &Meeting) {
+        let people = GeoRepository::list_all_people(&self.db.client)
+            .await
+            .unwrap_or_default();
+        self.notify_all(meeting_invites(meeting, &people)).await;
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Notification> {
+        self.tx.subscribe()
+    }
+}
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct PersonaQuery {
+    /// Persona (person name) whose inbox to use
+    pub persona: String,
+}
+
+fn unread_event(unread: usize) -> Event {
+    Event::default()
+        .event("unread")
+        .data(serde_json::json!({ "unread": unread }).to_string())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications/stream",
+    tag = "notifications",
+    params(PersonaQuery),
+    responses(
+        (status = 200, description = "`notification` and `unread` server-sent events", content_type = "text/event-stream"),
+        (status = 401, description = "Not signed in", body = crate::api::ApiError),
+        (status = 403, description = "Signed in as another persona", body = crate::api::ApiError),
+    )
+)]
+pub async fn stream(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let persona = acting_as(&state, &jar, &query.persona).await?;
+    // Subscribe before counting so nothing created in between is missed
+    let rx = state.notifications.subscribe();
+    let initial = NotificationRepository::unread_count(&state.db.client, &persona)
+        .await
+        .unwrap_or_default();
+    let db = state.db.clone();
+
+    let updates = BroadcastStream::new(rx)
+        .filter_map(move |received| {
+            let persona = persona.clone();
+            let db = db.clone();
+            async move {
+                // A lagged receiver only skips the push; the next unread count catches up
+                let notification = received.ok()?;
+                if !notification.visible_to(&persona) {
+                    return None;
+                }
+                let view = notification.view_for(&persona);
+                let unread = NotificationRepository::unread_count(&db.client, &persona)
+                    .await
+                    .unwrap_or_default();
+                let event = Event::default()
+                    .event("notification")
+                    .data(serde_json::to_string(&view).unwrap_or_default());
+                Some(stream::iter([event, unread_event(unread)]))
+            }
+        })
+        .flatten();
+
+    let events = stream::once(async move { unread_event(initial) })
+        .chain(updates)
+        .map(Ok);
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(Duration::from_secs(15))))
+}
@@ -0,0 +1,680 @@
+//! On-call
+//!
+//! Rotations and escalation policies (see
+//! `nexosim_hybrid::database::oncall`), worked out with
+//! [`operations::oncall`]: who is on call now, the shifts the calendar shows,
+//! and paging down an escalation chain.
+//!
+//! An incident opened without an assignee goes to whoever is on call for
+//! the first level of its policy (the default policy when it names none).
+//! While it stays open and unacknowledged, the `incidents.escalate` task
+//! pages each further level once its delay has passed. Scheduler alerts
+//! with nobody configured to receive them go to the default policy's
+//! first responder before falling back to everyone.
+
+use std::collections::HashMap;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, TimeDelta, Utc};
+use nexosim_hybrid::database::calendar::{Meeting, MeetingType, RecurrenceFrequency};
+use nexosim_hybrid::database::geo::{GeoRepository, Person};
+use nexosim_hybrid::database::incidents::{Incident, IncidentRepository};
+use nexosim_hybrid::database::notifications::{Notification, NotificationKind};
+use nexosim_hybrid::database::oncall::{EscalationPolicy, OnCallRepository, Rotation};
+use operations::schedule::{format_minute, parse_minute};
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::{found, record_key, ApiError, ApiResult};
+use crate::clock::minute;
+use crate::AppState;
+
+/// `rotation` for working out shifts, its members as person keys; `None`
+/// when its start is not a valid time
+fn plan(rotation: &Rotation) -> Option<operations::oncall::Rotation> {
+    Some(operations::oncall::Rotation {
+        start: parse_minute(&rotation.start).ok()?,
+        shift_hours: rotation.shift_hours,
+        members: rotation.members.iter().map(|m| m.id.to_raw()).collect(),
+    })
+}
+
+fn person_key(person: &Person) -> String {
+    person
+        .id
+        .as_ref()
+        .map(|t| t.id.to_raw())
+        .unwrap_or_default()
+}
+
+/// Who is on call for one rotation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OnCallNow {
+    pub rotation_id: String,
+    pub rotation: String,
+    /// `None` when nobody is: an empty rotation, or one not started yet
+    pub person_id: Option<String>,
+    pub person: Option<String>,
+    /// When the current shift hands over, `YYYY-MM-DD HH:MM` UTC
+    pub until: Option<String>,
+}
+
+/// Who is on call at `at` for each rotation
+pub fn on_call_at(rotations: &[Rotation], people: &[Person], at: &DateTime<Utc>) -> Vec<OnCallNow> {
+    let names: HashMap<String, &str> = people
+        .iter()
+        .map(|p| (person_key(p), p.name.as_str()))
+        .collect();
+    rotations
+        .iter()
+        .map(|rotation| {
+            let shift = plan(rotation).and_then(|plan| plan.shift_at(at));
+            OnCallNow {
+                rotation_id: rotation.key(),
+                rotation: rotation.name.clone(),
+                person: shift
+                    .as_ref()
+                    .and_then(|s| names.get(&s.member))
+                    .map(|n| n.to_string()),
+                until: shift.as_ref().map(|s| format_minute(&s.end)),
+                person_id: shift.map(|s| s.member),
+            }
+        })
+        .collect()
+}
+
+/// Every rotation's shifts from `from` up to `to`, as calendar entries
+pub fn shifts_as_meetings(
+    rotations: &[Rotation],
+    people: &[Person],
+    from: &DateTime<Utc>,
+    to: &DateTime<Utc>,
+) -> Vec<Meeting> {
+    let names: HashMap<String, &str> = people
+        .iter()
+        .map(|p| (person_key(p), p.name.as_str()))
+        .collect();
+    let iso = |t: &DateTime<Utc>| t.format("%Y-%m-%dT%H:%M:%S").to_string();
+    rotations
+        .iter()
+        .filter_map(|rotation| Some((rotation, plan(rotation)?)))
+        .flat_map(|(rotation, plan)| {
+            plan.shifts(from, to).into_iter().map(|shift| Meeting {
+                id: None,
+                title: format!(
+                    "On call: {} ({})",
+                    names
+                        .get(&shift.member)
+                        .copied()
+                        .unwrap_or(shift.member.as_str()),
+                    rotation.name
+                ),
+                description: None,
+                start_time: iso(&shift.start),
+                end_time: iso(&shift.end),
+                all_day: false,
+                meeting_type: MeetingType::OnCall,
+                recurrence: RecurrenceFrequency::None,
+                recurrence_interval: 1,
+                recurrence_days: Vec::new(),
+                recurrence_until: None,
+                recurrence_count: None,
+                location_id: None,
+                virtual_url: None,
+                organizer_id: None,
+                participant_ids: vec![Thing::from(("person", shift.member.as_str()))],
+                timezone: "UTC".to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Who level `level` of `policy` pages at `at`, with the rotation's name
+async fn responder(
+    db: &Surreal<Db>,
+    policy: &EscalationPolicy,
+    level: usize,
+    at: &DateTime<Utc>,
+) -> anyhow::Result<Option<(Person, String)>> {
+    let Some(step) = policy.levels.get(level) else {
+        return Ok(None);
+    };
+    let Some(rotation) = OnCallRepository::get_rotation(db, &step.rotation_id.id.to_raw()).await?
+    else {
+        return Ok(None);
+    };
+    let Some(member) = plan(&rotation).and_then(|plan| plan.on_call(at)) else {
+        return Ok(None);
+    };
+    Ok(GeoRepository::get_person_by_id(db, &member)
+        .await?
+        .map(|person| (person, rotation.name)))
+}
+
+/// Name of whoever the default policy pages first, for alerts nobody was
+/// named to receive
+pub async fn first_responder(db: &Surreal<Db>) -> Option<String> {
+    let policy = OnCallRepository::default_policy(db).await.ok()??;
+    let (person, _) = responder(db, &policy, 0, &minute()).await.ok()??;
+    Some(person.name)
+}
+
+/// Page every level of the incident's policy that is due and not yet
+/// paged; the first responder is also assigned when nobody is. Returns
+/// the levels paged.
+pub async fn escalate(
+    state: &AppState,
+    incident: &Incident,
+    at: &DateTime<Utc>,
+) -> anyhow::Result<usize> {
+    let db = &state.db.client;
+    let policy = match &incident.escalation_policy_id {
+        Some(id) => OnCallRepository::get_policy(db, &id.id.to_raw()).await?,
+        None => None,
+    };
+    let Some(policy) = policy else {
+        return Ok(0);
+    };
+    let opened = parse_minute(&incident.opened_at).map_err(anyhow::Error::msg)?;
+    let due = operations::oncall::levels_due(&policy.delays(), (*at - opened).num_minutes());
+    let key = incident.key();
+    let mut paged = 0;
+    for level in incident.escalation_level..due {
+        let Some((person, rotation)) = responder(db, &policy, level, at).await? else {
+            continue;
+        };
+        let stamp = crate::clock::now();
+        let updated = IncidentRepository::update(db, &key, |incident| {
+            incident.paged(level, &person.name, &rotation, &stamp);
+            Ok(())
+        })
+        .await?;
+        let Some(updated) = updated else {
+            return Ok(paged);
+        };
+        paged += 1;
+        if level == 0 && updated.assignee_id.is_none() {
+            let person_id = person_key(&person);
+            crate::incidents::assign(state, &key, Some(&person_id), None).await?;
+        } else {
+            state
+                .notifications
+                .notify(
+                    Notification::new(
+                        NotificationKind::IncidentEscalated,
+                        Some(person.name.clone()),
+                        format!("Escalated to you: {}", updated.title),
+                    )
+                    .with_body(format!(
+                        "Severity {}, open since {}; you are on call for {}",
+                        updated.severity, updated.opened_at, rotation
+                    ))
+                    .with_link(format!("/?tab=incidents&incident={}", key)),
+                )
+                .await;
+        }
+    }
+    Ok(paged)
+}
+
+/// Escalate every unacknowledged incident, for the `incidents.escalate` task
+pub async fn escalate_all(state: &AppState) -> anyhow::Result<String> {
+    let at = minute();
+    let mut paged = 0;
+    for incident in IncidentRepository::list(&state.db.client).await? {
+        if incident.unacknowledged() {
+            paged += escalate(state, &incident, &at).await?;
+        }
+    }
+    Ok(format!("Paged {} on-call responders", paged))
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+#[utoipa::path(
+    get,
+    path = "/api/oncall",
+    tag = "oncall",
+    responses((status = 200, description = "Who is on call now, per rotation", body = Vec<OnCallNow>))
+)]
+pub async fn who_is_on_call(State(state): State<AppState>) -> ApiResult<Json<Vec<OnCallNow>>> {
+    let rotations = OnCallRepository::rotations(&state.db.client).await?;
+    let people = GeoRepository::list_all_people(&state.db.client).await?;
+    Ok(Json(on_call_at(&rotations, &people, &minute())))
+}
+
+/// One shift in a rotation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ShiftInfo {
+    pub rotation_id: String,
+    pub rotation: String,
+    pub person_id: String,
+    pub person: Option<String>,
+    /// `YYYY-MM-DD HH:MM` UTC
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ShiftQuery {
+    /// `YYYY-MM-DD HH:MM` UTC; now when left out
+    pub from: Option<String>,
+    /// `YYYY-MM-DD HH:MM` UTC; a week after `from` when left out
+    pub to: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/oncall/shifts",
+    tag = "oncall",
+    params(ShiftQuery),
+    responses(
+        (status = 200, description = "Shifts overlapping the window, per rotation", body = Vec<ShiftInfo>),
+        (status = 400, description = "Unreadable time", body = ApiError),
+    )
+)]
+pub async fn list_shifts(
+    State(state): State<AppState>,
+    Query(query): Query<ShiftQuery>,
+) -> ApiResult<Json<Vec<ShiftInfo>>> {
+    let time = |value: Option<String>, default: DateTime<Utc>| match value {
+        Some(value) => parse_minute(&value).map_err(ApiError::bad_request),
+        None => Ok(default),
+    };
+    let from = time(query.from, minute())?;
+    let to = time(query.to, from + TimeDelta::days(7))?;
+    let rotations = OnCallRepository::rotations(&state.db.client).await?;
+    let people = GeoRepository::list_all_people(&state.db.client).await?;
+    let names: HashMap<String, &str> = people
+        .iter()
+        .map(|p| (person_key(p), p.name.as_str()))
+        .collect();
+    let shifts = rotations
+        .iter()
+        .filter_map(|rotation| Some((rotation, plan(rotation)?)))
+        .flat_map(|(rotation, plan)| {
+            plan.shifts(&from, &to).into_iter().map(|shift| ShiftInfo {
+                rotation_id: rotation.key(),
+                rotation: rotation.name.clone(),
+                person: names.get(&shift.member).map(|n| n.to_string()),
+                person_id: shift.member,
+                start: format_minute(&shift.start),
+                end: format_minute(&shift.end),
+            })
+        })
+        .collect();
+    Ok(Json(shifts))
+}
+
+/// A rotation as created or edited
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct RotationInput {
+    pub name: String,
+    /// People taking turns, in order, as `person:key` or `key`
+    pub members: Vec<String>,
+    /// First handover, `YYYY-MM-DD HH:MM` UTC
+    pub start: String,
+    pub shift_hours: u32,
+}
+
+impl RotationInput {
+    /// The rotation, once its members are known people
+    pub async fn validate(self, db: &Surreal<Db>) -> Result<Rotation, ApiError> {
+        if self.name.trim().is_empty() {
+            return Err(ApiError::bad_request("A rotation needs a name"));
+        }
+        let start = parse_minute(&self.start).map_err(ApiError::bad_request)?;
+        if self.shift_hours == 0 {
+            return Err(ApiError::bad_request(
+                "Shifts must be at least an hour long",
+            ));
+        }
+        let mut members = Vec::new();
+        for member in &self.members {
+            let key = record_key(member, "person");
+            if GeoRepository::get_person_by_id(db, key).await?.is_none() {
+                return Err(ApiError::bad_request(format!(
+                    "Person '{member}' not found"
+                )));
+            }
+            members.push(Thing::from(("person", key)));
+        }
+        Ok(Rotation {
+            id: None,
+            name: self.name.trim().to_string(),
+            members,
+            start: format_minute(&start),
+            shift_hours: self.shift_hours,
+        })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/oncall/rotations",
+    tag = "oncall",
+    responses((status = 200, description = "All rotations, by name", body = Vec<Rotation>))
+)]
+pub async fn list_rotations(State(state): State<AppState>) -> ApiResult<Json<Vec<Rotation>>> {
+    Ok(Json(OnCallRepository::rotations(&state.db.client).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/oncall/rotations/{id}",
+    tag = "oncall",
+    params(("id" = String, Path, description = "Rotation id (`rotation:key` or `key`)")),
+    responses(
+        (status = 200, description = "The rotation", body = Rotation),
+        (status = 404, description = "Rotation not found", body = ApiError),
+    )
+)]
+pub async fn get_rotation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Rotation>> {
+    let rotation =
+        OnCallRepository::get_rotation(&state.db.client, record_key(&id, "rotation")).await?;
+    Ok(Json(found(rotation, "Rotation", &id)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/oncall/rotations",
+    tag = "oncall",
+    request_body = RotationInput,
+    responses(
+        (status = 201, description = "Rotation created", body = Rotation),
+        (status = 400, description = "Missing name, bad start or unknown member", body = ApiError),
+    )
+)]
+pub async fn create_rotation(
+    State(state): State<AppState>,
+    Json(input): Json<RotationInput>,
+) -> ApiResult<(StatusCode, Json<Rotation>)> {
+    let rotation = input.validate(&state.db.client).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(OnCallRepository::create_rotation(&state.db.client, rotation).await?),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/oncall/rotations/{id}",
+    tag = "oncall",
+    params(("id" = String, Path, description = "Rotation id (`rotation:key` or `key`)")),
+    request_body = RotationInput,
+    responses(
+        (status = 200, description = "Rotation replaced", body = Rotation),
+        (status = 400, description = "Missing name, bad start or unknown member", body = ApiError),
+        (status = 404, description = "Rotation not found", body = ApiError),
+    )
+)]
+pub async fn update_rotation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<RotationInput>,
+) -> ApiResult<Json<Rotation>> {
+    let key = record_key(&id, "rotation");
+    found(
+        OnCallRepository::get_rotation(&state.db.client, key).await?,
+        "Rotation",
+        &id,
+    )?;
+    let rotation = input.validate(&state.db.client).await?;
+    let updated = OnCallRepository::update_rotation(&state.db.client, key, rotation).await?;
+    Ok(Json(found(updated, "Rotation", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/oncall/rotations/{id}",
+    tag = "oncall",
+    params(("id" = String, Path, description = "Rotation id (`rotation:key` or `key`)")),
+    responses(
+        (status = 204, description = "Rotation deleted"),
+        (status = 404, description = "Rotation not found", body = ApiError),
+        (status = 409, description = "An escalation policy still pages the rotation", body = ApiError),
+    )
+)]
+pub async fn delete_rotation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    remove_rotation(&state.db.client, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Delete rotation `id`, unless a policy still pages it
+pub async fn remove_rotation(db: &Surreal<Db>, id: &str) -> ApiResult<()> {
+    let key = record_key(id, "rotation");
+    found(
+        OnCallRepository::get_rotation(db, key).await?,
+        "Rotation",
+        id,
+    )?;
+    let policies = OnCallRepository::policies(db).await?;
+    if let Some(policy) = policies
+        .iter()
+        .find(|p| p.levels.iter().any(|l| l.rotation_id.id.to_raw() == key))
+    {
+        return Err(ApiError::conflict(format!(
+            "Escalation policy '{}' still pages this rotation",
+            policy.name
+        )));
+    }
+    Ok(OnCallRepository::delete_rotation(db, key).await?)
+}
+
+/// One level of a policy as created or edited
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct LevelInput {
+    /// Rotation paged, as `rotation:key` or `key`
+    pub rotation_id: String,
+    #[serde(default)]
+    pub after_minutes: u32,
+}
+
+/// An escalation policy as created or edited
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PolicyInput {
+    pub name: String,
+    pub levels: Vec<LevelInput>,
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl PolicyInput {
+    /// The policy, once its rotations exist and its delays only grow
+    pub async fn validate(self, db: &Surreal<Db>) -> Result<EscalationPolicy, ApiError> {
+        use nexosim_hybrid::database::oncall::EscalationLevel;
+
+        if self.name.trim().is_empty() {
+            return Err(ApiError::bad_request("A policy needs a name"));
+        }
+        if self.levels.is_empty() {
+            return Err(ApiError::bad_request("A policy needs at least one level"));
+        }
+        if self
+            .levels
+            .windows(2)
+            .any(|w| w[1].after_minutes < w[0].after_minutes)
+        {
+            return Err(ApiError::bad_request(
+                "Each level must wait at least as long as the one before",
+            ));
+        }
+        let mut levels = Vec::new();
+        for level in &self.levels {
+            let key = record_key(&level.rotation_id, "rotation");
+            if OnCallRepository::get_rotation(db, key).await?.is_none() {
+                return Err(ApiError::bad_request(format!(
+                    "Rotation '{}' not found",
+                    level.rotation_id
+                )));
+            }
+            levels.push(EscalationLevel {
+                rotation_id: Thing::from(("rotation", key)),
+                after_minutes: level.after_minutes,
+            });
+        }
+        Ok(EscalationPolicy {
+            id: None,
+            name: self.name.trim().to_string(),
+            levels,
+            default: self.default,
+        })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/oncall/policies",
+    tag = "oncall",
+    responses((status = 200, description = "All escalation policies, by name", body = Vec<EscalationPolicy>))
+)]
+pub async fn list_policies(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<EscalationPolicy>>> {
+    Ok(Json(OnCallRepository::policies(&state.db.client).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/oncall/policies",
+    tag = "oncall",
+    request_body = PolicyInput,
+    responses(
+        (status = 201, description = "Policy created", body = EscalationPolicy),
+        (status = 400, description = "Missing name or levels, or unknown rotation", body = ApiError),
+    )
+)]
+pub async fn create_policy(
+    State(state): State<AppState>,
+    Json(input): Json<PolicyInput>,
+) -> ApiResult<(StatusCode, Json<EscalationPolicy>)> {
+    let policy = input.validate(&state.db.client).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(OnCallRepository::create_policy(&state.db.client, policy).await?),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/oncall/policies/{id}",
+    tag = "oncall",
+    params(("id" = String, Path, description = "Policy id (`escalation_policy:key` or `key`)")),
+    request_body = PolicyInput,
+    responses(
+        (status = 200, description = "Policy replaced", body = EscalationPolicy),
+        (status = 400, description = "Missing name or levels, or unknown rotation", body = ApiError),
+        (status = 404, description = "Policy not found", body = ApiError),
+    )
+)]
+pub async fn update_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<PolicyInput>,
+) -> ApiResult<Json<EscalationPolicy>> {
+    let key = record_key(&id, "escalation_policy");
+    found(
+        OnCallRepository::get_policy(&state.db.client, key).await?,
+        "Escalation policy",
+        &id,
+    )?;
+    let policy = input.validate(&state.db.client).await?;
+    let updated = OnCallRepository::update_policy(&state.db.client, key, policy).await?;
+    Ok(Json(found(updated, "Escalation policy", &id)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/oncall/policies/{id}",
+    tag = "oncall",
+    params(("id" = String, Path, description = "Policy id (`escalation_policy:key` or `key`)")),
+    responses(
+        (status = 204, description = "Policy deleted"),
+        (status = 404, description = "Policy not found", body = ApiError),
+    )
+)]
+pub async fn delete_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let key = record_key(&id, "escalation_policy");
+    found(
+        OnCallRepository::get_policy(&state.db.client, key).await?,
+        "Escalation policy",
+        &id,
+    )?;
+    OnCallRepository::delete_policy(&state.db.client, key).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifts_show_on_the_calendar_by_name() {
+        let person = |key: &str, name: &str| Person {
+            id: Some(Thing::from(("person", key))),
+            name: name.to_string(),
+            email: String::new(),
+            title: String::new(),
+            department: String::new(),
+            site_id: Thing::from(("site", "hq")),
+            space_id: None,
+            manager_id: None,
+            role: Default::default(),
+            photo: None,
+            bio: None,
+            desk_phone: None,
+            cell_phone: None,
+            photo_data: None,
+        };
+        let rotation = Rotation {
+            id: Some(Thing::from(("rotation", "primary"))),
+            name: "Primary".to_string(),
+            members: vec![
+                Thing::from(("person", "ann")),
+                Thing::from(("person", "bob")),
+            ],
+            start: "2026-03-02 09:00".to_string(),
+            shift_hours: 12,
+        };
+        let people = [person("ann", "Ann"), person("bob", "Bob")];
+        let at = |s: &str| parse_minute(s).unwrap();
+
+        let now = on_call_at(
+            std::slice::from_ref(&rotation),
+            &people,
+            &at("2026-03-02 22:00"),
+        );
+        assert_eq!(now[0].person.as_deref(), Some("Bob"));
+        assert_eq!(now[0].until.as_deref(), Some("2026-03-03 09:00"));
+
+        let meetings = shifts_as_meetings(
+            &[rotation],
+            &people,
+            &at("2026-03-02 00:00"),
+            &at("2026-03-03 00:00"),
+        );
+        let titles: Vec<&str> = meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["On call: Ann (Primary)", "On call: Bob (Primary)"]
+        );
+        assert_eq!(meetings[1].start_time, "2026-03-02T21:00:00");
+        assert_eq!(meetings[1].meeting_type, MeetingType::OnCall);
+    }
+}
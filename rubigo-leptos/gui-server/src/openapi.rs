@@ -0,0 +1,283 @@
+//! OpenAPI document for the JSON API
+//!
+//! Generated from the `#[utoipa::path]` annotations on the handlers in
+//! `api.rs` and the persona handlers in `main.rs`. Served as JSON at
+//! `/api/openapi.json` with Swagger UI at `/api/docs`.
+
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::api;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Rubigo GUI Server API",
+        description = "JSON API backing the gui-server, ui-app and Tauri frontends"
+    ),
+    paths(
+        api::list_components,
+        api::create_component,
+        api::update_component,
+        api::delete_component,
+        api::component_location,
+        api::list_connections,
+        api::create_connection,
+        api::delete_connection,
+        api::list_regions,
+        api::create_region,
+        api::search_regions,
+        api::search_cities,
+        api::list_sites,
+        api::create_site,
+        api::update_site,
+        api::delete_site,
+        api::list_buildings,
+        api::create_building,
+        api::update_building,
+        api::delete_building,
+        api::list_floors,
+        api::create_floor,
+        api::update_floor,
+        api::delete_floor,
+        api::set_floor_outline,
+        api::list_spaces,
+        api::create_space,
+        api::update_space,
+        api::delete_space,
+        api::set_space_bounds,
+        api::list_racks,
+        api::create_rack,
+        api::update_rack,
+        api::delete_rack,
+        api::list_devices,
+        api::create_device,
+        api::update_device,
+        api::delete_device,
+        api::link_device,
+        api::create_patch_panel,
+        api::delete_patch_panel,
+        api::create_port,
+        api::delete_port,
+        api::list_cables,
+        api::create_cable,
+        api::delete_cable,
+        api::projected_connections,
+        api::sync_cabling,
+        api::list_desks,
+        api::create_desk,
+        api::list_desk_bookings,
+        api::create_desk_booking,
+        api::cancel_desk_booking,
+        api::list_events,
+        api::create_event,
+        api::update_event,
+        api::delete_event,
+        api::list_runs,
+        api::create_run,
+        api::delete_run,
+        api::compare_runs,
+        api::list_jobs,
+        api::get_job,
+        api::retry_job,
+        api::list_notifications,
+        api::unread_notifications,
+        api::read_notification,
+        api::read_all_notifications,
+        crate::notifications::stream,
+        api::list_conversations,
+        api::start_conversation,
+        api::list_messages,
+        api::send_message,
+        crate::chat::socket,
+        crate::presence::roster,
+        crate::presence::socket,
+        crate::collab::socket,
+        api::list_reports,
+        api::generate_report,
+        api::get_report_document,
+        api::delete_report_document,
+        api::export_list,
+        api::import_fields,
+        api::run_import,
+        api::list_flags,
+        api::set_flag,
+        crate::scheduler::list,
+        crate::scheduler::tasks,
+        crate::scheduler::create,
+        crate::scheduler::update,
+        crate::scheduler::delete,
+        crate::scheduler::run_now,
+        crate::scheduler::runs,
+        crate::webhooks::list,
+        crate::webhooks::events,
+        crate::webhooks::create,
+        crate::webhooks::update,
+        crate::webhooks::delete,
+        crate::webhooks::test,
+        crate::webhooks::deliveries,
+        crate::webhooks::redeliver_now,
+        crate::email::get_preferences,
+        crate::email::put_preferences,
+        crate::email::outbox,
+        crate::email::email_report,
+        crate::identity::providers,
+        crate::identity::identities,
+        crate::identity::link,
+        crate::telemetry::list,
+        crate::telemetry::get,
+        crate::telemetry::put,
+        crate::telemetry::poll_now,
+        crate::netbox::sync,
+        crate::netbox::links,
+        crate::iac::export_document,
+        crate::iac::import_document,
+        crate::sandbox::list,
+        crate::sandbox::create,
+        crate::sandbox::get,
+        crate::sandbox::discard,
+        crate::sandbox::update_topology,
+        crate::sandbox::changes_since_branch,
+        crate::sandbox::simulate,
+        crate::sandbox::merge_into_live,
+        crate::capacity::get_report,
+        crate::capacity::check,
+        crate::capacity::set_budget,
+        crate::incidents::list,
+        crate::incidents::get,
+        crate::incidents::create,
+        crate::incidents::update_status,
+        crate::incidents::update_assignee,
+        crate::incidents::add_note,
+        crate::incidents::delete,
+        crate::oncall::who_is_on_call,
+        crate::oncall::list_shifts,
+        crate::oncall::list_rotations,
+        crate::oncall::get_rotation,
+        crate::oncall::create_rotation,
+        crate::oncall::update_rotation,
+        crate::oncall::delete_rotation,
+        crate::oncall::list_policies,
+        crate::oncall::create_policy,
+        crate::oncall::update_policy,
+        crate::oncall::delete_policy,
+        crate::sync::pull,
+        crate::sync::push,
+        crate::graphql::execute,
+        api::seed_scenario,
+        api::export_scenario,
+        api::list_migrations,
+        api::run_migrations,
+        api::vacuum,
+        api::list_geo_features,
+        api::great_circle_route,
+        crate::tiles::get_tile,
+        api::list_people,
+        api::create_person,
+        api::get_person_photo,
+        api::list_assets,
+        crate::handle_get_persona,
+        crate::handle_set_persona,
+        crate::handle_delete_persona,
+        crate::health::healthz,
+        crate::health::readyz,
+    ),
+    components(schemas(api::ApiError)),
+    tags(
+        (name = "components", description = "Simulation components"),
+        (name = "connections", description = "Links between components"),
+        (name = "regions", description = "Regions and city lookup"),
+        (name = "sites", description = "Sites within a region"),
+        (name = "buildings", description = "Buildings within a site"),
+        (name = "floors", description = "Floors within a building"),
+        (name = "spaces", description = "Spaces within a floor"),
+        (name = "racks", description = "Racks within a space"),
+        (name = "devices", description = "Devices mounted in a rack"),
+        (name = "cabling", description = "Ports, patch panels and cables"),
+        (name = "desks", description = "Desks and desk bookings"),
+        (name = "events", description = "Calendar events"),
+        (name = "runs", description = "Simulation runs"),
+        (name = "jobs", description = "Background import jobs"),
+        (name = "schedules", description = "Recurring server tasks and their run history"),
+        (name = "webhooks", description = "Signed outbound event deliveries and their log"),
+        (name = "email", description = "Invite, report and mention emails and per-persona preferences"),
+        (name = "auth", description = "OIDC sign-in and LDAP-synced identities"),
+        (name = "telemetry", description = "Simulated or live (SNMP, agent) telemetry per component"),
+        (name = "notifications", description = "Per-persona notification inbox"),
+        (name = "chat", description = "Conversations and messages between personas"),
+        (name = "presence", description = "Connected personas and what they have open"),
+        (name = "collab", description = "Live unsaved edits shared between editors of a record"),
+        (name = "reports", description = "Generated report documents"),
+        (name = "export", description = "CSV, JSON and XLSX downloads of list views"),
+        (name = "import", description = "CSV imports with column mapping and dry runs"),
+        (name = "netbox", description = "Sites, racks, devices and cables imported from NetBox"),
+        (name = "iac", description = "Site hierarchy and topology as Terraform JSON or TOML, exported and applied"),
+        (name = "sandboxes", description = "Scratch copies of the topology to edit, simulate, then merge or discard"),
+        (name = "capacity", description = "Rack, power, cooling and port utilization with projected exhaustion"),
+        (name = "incidents", description = "Incidents against components and sites, with timeline, assignee and status workflow"),
+        (name = "oncall", description = "Rotations, escalation policies and who is on call now"),
+        (name = "flags", description = "Runtime feature flags"),
+        (name = "admin", description = "Seeding, scenario export, migrations and vacuum for rubigo-admin"),
+        (name = "graphql", description = "GraphQL queries across people, sites, assets, components and events"),
+        (name = "geo", description = "Map boundary data"),
+        (name = "people", description = "People directory"),
+        (name = "assets", description = "Network assets"),
+        (name = "persona", description = "Dev-mode persona selection"),
+        (name = "health", description = "Liveness and readiness probes"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Swagger UI at `/api/docs`, reading the document from `/api/openapi.json`
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_covers_api_routes() {
+        let doc = ApiDoc::openapi();
+        for path in [
+            "/api/components/{id}",
+            "/api/connections/{from}/{to}",
+            "/api/sites/{id}/buildings",
+            "/api/devices/{id}",
+            "/api/devices/{id}/component",
+            "/api/cables",
+            "/api/events/{id}",
+            "/api/runs",
+            "/api/runs/compare",
+            "/api/reports/{kind}",
+            "/api/export/{resource}",
+            "/api/import/{resource}",
+            "/api/netbox/sync",
+            "/api/iac/import",
+            "/api/sandboxes/{id}/merge",
+            "/api/capacity/check",
+            "/api/incidents/{id}/status",
+            "/api/incidents/{id}/assignee",
+            "/api/incidents/{id}/timeline",
+            "/api/oncall",
+            "/api/oncall/shifts",
+            "/api/oncall/policies/{id}",
+            "/api/spaces/{id}/budget",
+            "/api/flags/{name}",
+            "/api/schedules/{id}/runs",
+            "/api/webhooks/deliveries/{id}/redeliver",
+            "/api/email/preferences",
+            "/api/auth/providers",
+            "/api/components/{id}/telemetry/poll",
+            "/api/reports/documents/{id}/email",
+            "/api/graphql",
+            "/api/admin/migrations",
+            "/api/assets",
+            "/api/persona",
+            "/readyz",
+        ] {
+            assert!(doc.paths.paths.contains_key(path), "missing {path}");
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Minimal PDF writer
+//!
+//! Just enough of PDF 1.4 for text reports: A4 pages, the built-in
+//! Helvetica/Helvetica-Bold/Courier fonts (no embedding) and lines of text
+//! placed top to bottom, breaking onto a new page when one fills up.
+//!
+//! # Limitation: WinAnsi text only
+//!
+//! Without an embedded font, text is limited to the WinAnsi character set
+//! of the built-in fonts: Latin-1 plus typographic punctuation, `€`, `Š`,
+//! `Ž` and a few others. Latin letters with other accents are printed
+//! without them ("Łukasz Dvořák" becomes "Lukasz Dvorak"); anything else,
+//! e.g. Cyrillic, Greek or CJK names, prints as `?`. Reports that must show
+//! such names should be rendered as HTML instead.
+
+use std::fmt::Write as _;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// A4 in points
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 40.0;
+
+/// Courier advance width as a fraction of the font size
+const MONO_ADVANCE: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+}
+
+/// Text document laid out line by line
+pub struct PdfDocument {
+    pages: Vec<String>,
+    /// Baseline of the next line, from the bottom of the page
+    cursor: f64,
+}
+
+impl Default for PdfDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self {
+            pages: vec![String::new()],
+            cursor: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Characters of `size` Courier that fit across the page
+    pub fn mono_columns(size: f64) -> usize {
+        ((PAGE_WIDTH - 2.0 * MARGIN) / (size * MONO_ADVANCE)) as usize
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn ensure_room(&mut self, height: f64) {
+        if self.cursor - height < MARGIN {
+            self.pages.push(String::new());
+            self.cursor = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    /// Add a line of text, moving down by 1.4 times the font size
+    pub fn line(&mut self, font: Font, size: f64, text: &str) {
+        let leading = size * 1.4;
+        self.ensure_room(leading);
+        self.cursor -= leading;
+        let page = self.pages.last_mut().expect("at least one page");
+        let _ = writeln!(
+            page,
+            "BT /{} {} Tf {} {:.2} Td ({}) Tj ET",
+            font.resource(),
+            size,
+            MARGIN,
+            self.cursor,
+            escape(text)
+        );
+    }
+
+    /// Vertical space
+    pub fn gap(&mut self, height: f64) {
+        self.cursor -= height;
+        self.ensure_room(0.0);
+    }
+
+    /// Serialize the document
+    pub fn finish(self) -> Vec<u8> {
+        // 1 catalog, 2 page tree, 3-5 fonts, then a page and a content stream per page
+        let first_page = 6;
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        let kids: Vec<String> = (0..self.pages.len())
+            .map(|i| format!("{} 0 R", first_page + 2 * i))
+            .collect();
+
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                kids.len()
+            )
+            .into_bytes(),
+        );
+        for base in ["Helvetica", "Helvetica-Bold", "Courier"] {
+            objects.push(
+                format!("<< /Type /Font /Subtype /Type1 /BaseFont /{base} /Encoding /WinAnsiEncoding >>").into_bytes(),
+            );
+        }
+        for (i, content) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                    first_page + 2 * i + 1
+                )
+                .into_bytes(),
+            );
+            let stream = win_ansi(content);
+            let mut object = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+            object.extend_from_slice(&stream);
+            object.extend_from_slice(b"\nendstream");
+            objects.push(object);
+        }
+
+        let mut out = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+}
+
+/// Escape a string literal for a content stream
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' | '\r' | '\t' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// WinAnsi code of `c`, if the built-in fonts have it
+fn win_ansi_byte(c: char) -> Option<u8> {
+    let byte = match c {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => return Some(u32::from(c) as u8),
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '\u{2018}' => 0x91,
+        '\u{2019}' => 0x92,
+        '\u{201C}' => 0x93,
+        '\u{201D}' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+/// Latin letters whose accent isn't a combining mark
+fn base_letter(c: char) -> Option<char> {
+    Some(match c {
+        'Ł' => 'L',
+        'ł' => 'l',
+        'Đ' => 'D',
+        'đ' => 'd',
+        'Ħ' => 'H',
+        'ħ' => 'h',
+        'ı' => 'i',
+        _ => return None,
+    })
+}
+
+/// Encode for the built-in fonts' WinAnsi encoding, see the module docs
+fn win_ansi(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        if let Some(byte) = win_ansi_byte(c) {
+            out.push(byte);
+            continue;
+        }
+        let folded: Option<Vec<u8>> = match base_letter(c) {
+            Some(base) => win_ansi_byte(base).map(|b| vec![b]),
+            None => c
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .map(win_ansi_byte)
+                .collect(),
+        };
+        match folded {
+            Some(bytes) if !bytes.is_empty() => out.extend(bytes),
+            _ => out.push(b'?'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xref_offsets_point_at_objects() {
+        let mut doc = PdfDocument::new();
+        doc.line(Font::Bold, 16.0, "Report (draft)");
+        let bytes = doc.finish();
+        let text = String::from_utf8_lossy(&bytes);
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("(Report \\(draft\\)) Tj"));
+
+        let xref = text.find("xref\n").unwrap();
+        let entries: Vec<usize> = text[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .map(|l| l[..10].parse().unwrap())
+            .collect();
+        assert_eq!(entries.len(), 7);
+        for (i, offset) in entries.into_iter().enumerate() {
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn long_documents_break_pages() {
+        let mut doc = PdfDocument::new();
+        for i in 0..200 {
+            doc.line(Font::Mono, 8.0, &format!("row {i}"));
+        }
+        assert!(doc.page_count() > 1);
+        assert_eq!(PdfDocument::mono_columns(8.0), 107);
+    }
+
+    #[test]
+    fn text_is_encoded_for_the_built_in_fonts() {
+        assert_eq!(win_ansi("Café"), b"Caf\xe9");
+        // Typographic punctuation has WinAnsi codes Latin-1 lacks
+        assert_eq!(
+            win_ansi("\u{201C}€5\u{201D} – ok"),
+            b"\x93\x805\x94 \x96 ok"
+        );
+        // Other accented Latin letters lose their accents
+        assert_eq!(win_ansi("Łukasz Dvořák"), b"Lukasz Dvor\xe1k");
+        // Other scripts can't be shown at all
+        assert_eq!(win_ansi("Иван 李 ✓"), b"???? ? ?");
+        // C1 controls are not WinAnsi characters
+        assert_eq!(win_ansi("a\u{81}b"), b"a?b");
+    }
+}
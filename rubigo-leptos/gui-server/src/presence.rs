@@ -0,0 +1,281 @@
+//! Presence hub
+//!
+//! Tracks which personas are connected and what each tab has open. A tab
+//! registers by opening `GET /api/presence/ws?persona=...` (WebSocket) and
+//! is removed when the socket closes. Sockets are pinged every
+//! [`PING_INTERVAL`]; a tab that has gone quiet for [`STALE_AFTER`] (a
+//! dropped connection that never closed) is swept by the scheduled session
+//! cleanup, which also closes its socket.
+//!
+//! Frames are JSON. Client to server: a [`PresenceUpdate`]
+//! (`{ "page": "assets", "entity": "asset:42", "editing": false }`).
+//! Server to client: `{ "type": "roster", "entries": [...] }` after every
+//! change, or `{ "type": "error", "message": "..." }`.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use actions::presence::{Presence, PresenceUpdate, Roster};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::identity::acting_as;
+use crate::notifications::PersonaQuery;
+use crate::AppState;
+
+/// Rosters buffered for slow sockets; only the latest one matters
+const CHANNEL_CAPACITY: usize = 16;
+
+/// How often open sockets are pinged; browsers answer with a pong
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Silence after which a tab counts as gone
+pub const STALE_AFTER: Duration = Duration::from_secs(120);
+
+struct Tab {
+    presence: Presence,
+    /// Last frame of any kind from the tab
+    seen: Instant,
+}
+
+#[derive(Clone)]
+pub struct PresenceHub {
+    /// Connected tabs by connection id
+    tabs: Arc<Mutex<BTreeMap<u64, Tab>>>,
+    next_id: Arc<AtomicU64>,
+    tx: broadcast::Sender<Roster>,
+}
+
+impl Default for PresenceHub {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            tabs: Arc::new(Mutex::new(BTreeMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            tx,
+        }
+    }
+}
+
+impl PresenceHub {
+    pub fn roster(&self) -> Roster {
+        let tabs = self.tabs.lock().unwrap();
+        Roster {
+            entries: tabs.values().map(|tab| tab.presence.clone()).collect(),
+        }
+    }
+
+    /// Add a tab for `persona`; returns its connection id
+    pub fn join(&self, persona: &str) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.tabs.lock().unwrap().insert(
+            id,
+            Tab {
+                presence: Presence::new(persona),
+                seen: Instant::now(),
+            },
+        );
+        self.publish();
+        id
+    }
+
+    pub fn update(&self, id: u64, update: PresenceUpdate) {
+        let changed = match self.tabs.lock().unwrap().get_mut(&id) {
+            Some(tab) => {
+                tab.seen = Instant::now();
+                let before = tab.presence.clone();
+                tab.presence.apply(update);
+                tab.presence != before
+            }
+            None => false,
+        };
+        if changed {
+            self.publish();
+        }
+    }
+
+    pub fn leave(&self, id: u64) {
+        if self.tabs.lock().unwrap().remove(&id).is_some() {
+            self.publish();
+        }
+    }
+
+    /// Note that tab `id` is still there
+    pub fn touch(&self, id: u64) {
+        if let Some(tab) = self.tabs.lock().unwrap().get_mut(&id) {
+            tab.seen = Instant::now();
+        }
+    }
+
+    /// Whether tab `id` is still on the roster
+    pub fn is_connected(&self, id: u64) -> bool {
+        self.tabs.lock().unwrap().contains_key(&id)
+    }
+
+    /// Drop tabs not heard from for `idle`; returns how many went
+    pub fn sweep(&self, idle: Duration) -> usize {
+        let swept = {
+            let mut tabs = self.tabs.lock().unwrap();
+            let before = tabs.len();
+            tabs.retain(|_, tab| tab.seen.elapsed() < idle);
+            before - tabs.len()
+        };
+        if swept > 0 {
+            self.publish();
+        }
+        swept
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Roster> {
+        self.tx.subscribe()
+    }
+
+    fn publish(&self) {
+        // No open sockets is not an error
+        let _ = self.tx.send(self.roster());
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerFrame {
+    Roster(Roster),
+    Error { message: String },
+}
+
+/// Everyone connected right now
+#[utoipa::path(
+    get,
+    path = "/api/presence",
+    tag = "presence",
+    responses((status = 200, description = "Connected tabs with their page and open record", body = Object))
+)]
+pub async fn roster(State(state): State<AppState>) -> Json<Roster> {
+    Json(state.presence.roster())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/presence/ws",
+    tag = "presence",
+    params(PersonaQuery),
+    responses(
+        (status = 101, description = "WebSocket registering the persona and carrying the roster"),
+        (status = 401, description = "Not signed in", body = crate::api::ApiError),
+        (status = 403, description = "Signed in as another persona", body = crate::api::ApiError),
+    )
+)]
+pub async fn socket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<PersonaQuery>,
+) -> Response {
+    match acting_as(&state, &jar, &query.persona).await {
+        Ok(persona) => ws.on_upgrade(move |socket| session(socket, state, persona)),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn session(socket: WebSocket, state: AppState, persona: String) {
+    let (mut outgoing, mut incoming) = socket.split();
+    // Subscribe before joining so the first roster includes this tab
+    let mut rosters = state.presence.subscribe();
+    let id = state.presence.join(&persona);
+    let mut pings = tokio::time::interval(PING_INTERVAL);
+
+    loop {
+        let frame = tokio::select! {
+            _ = pings.tick() => {
+                // Swept as stale; the tab reconnects if it is still there
+                if !state.presence.is_connected(id) {
+                    break;
+                }
+                if outgoing.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+                continue;
+            },
+            roster = rosters.recv() => match roster {
+                Ok(roster) => ServerFrame::Roster(roster),
+                // Skip to the current roster
+                Err(broadcast::error::RecvError::Lagged(_)) => ServerFrame::Roster(state.presence.roster()),
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            received = incoming.next() => match received {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<PresenceUpdate>(&text) {
+                    Ok(update) => {
+                        // The new roster comes back as a broadcast
+                        state.presence.update(id, update);
+                        continue;
+                    }
+                    Err(e) => ServerFrame::Error { message: format!("Invalid frame: {e}") },
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pongs and the like only show the tab is alive
+                Some(Ok(_)) => {
+                    state.presence.touch(id);
+                    continue;
+                }
+            },
+        };
+        let Ok(text) = serde_json::to_string(&frame) else {
+            continue;
+        };
+        if outgoing.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+
+    state.presence.leave(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tabs_join_update_and_leave() {
+        let hub = PresenceHub::default();
+        let mut rosters = hub.subscribe();
+
+        let id = hub.join("Ada");
+        hub.update(
+            id,
+            PresenceUpdate {
+                page: "assets".into(),
+                entity: Some("asset:1".into()),
+                editing: true,
+            },
+        );
+        assert_eq!(hub.roster().editors("asset:1", "Bo"), vec!["Ada"]);
+
+        hub.leave(id);
+        assert!(hub.roster().entries.is_empty());
+        // join, update, leave
+        assert_eq!(rosters.try_recv().unwrap().entries.len(), 1);
+        assert_eq!(rosters.try_recv().unwrap().entries[0].page, "assets");
+        assert!(rosters.try_recv().unwrap().entries.is_empty());
+    }
+
+    #[test]
+    fn quiet_tabs_are_swept() {
+        let hub = PresenceHub::default();
+        let quiet = hub.join("Ada");
+        assert_eq!(hub.sweep(STALE_AFTER), 0);
+
+        std::thread::sleep(Duration::from_millis(60));
+        let live = hub.join("Bo");
+        assert_eq!(hub.sweep(Duration::from_millis(30)), 1);
+        assert!(!hub.is_connected(quiet));
+        assert!(hub.is_connected(live));
+    }
+}
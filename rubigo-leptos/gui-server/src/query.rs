@@ -0,0 +1,187 @@
+//! List query options
+//!
+//! Search, field filters, sorting and paging shared by the flat list
+//! endpoints and the export service, so an export returns exactly the rows
+//! the list view was showing.
+//!
+//! Records are compared through their JSON form, which keeps this generic
+//! over every repository type.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Query string accepted by list endpoints,
+/// e.g. `?q=rack&filter=status:active&sort=name&order=desc&limit=50`
+#[derive(Debug, Clone, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct QueryOptions {
+    /// Case-insensitive text search across all fields
+    pub q: Option<String>,
+    /// Exact field matches, `field:value` pairs separated by commas
+    pub filter: Option<String>,
+    /// Field to sort by
+    pub sort: Option<String>,
+    /// `asc` (default) or `desc`
+    pub order: Option<String>,
+    /// Maximum number of rows
+    pub limit: Option<usize>,
+    /// Rows to skip before `limit`
+    pub offset: Option<usize>,
+}
+
+impl QueryOptions {
+    /// `(field, value)` pairs from `filter`
+    fn filters(&self) -> Vec<(&str, &str)> {
+        self.filter
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|pair| pair.split_once(':'))
+            .map(|(field, value)| (field.trim(), value.trim()))
+            .filter(|(field, _)| !field.is_empty())
+            .collect()
+    }
+
+    fn descending(&self) -> bool {
+        self.order
+            .as_deref()
+            .is_some_and(|o| o.eq_ignore_ascii_case("desc"))
+    }
+
+    /// Filter, sort and page `rows`
+    pub fn apply<T: Serialize>(&self, rows: Vec<T>) -> Vec<T> {
+        let search = self
+            .q
+            .as_deref()
+            .map(str::to_lowercase)
+            .filter(|q| !q.is_empty());
+        let filters = self.filters();
+
+        let mut matched: Vec<(Value, T)> = rows
+            .into_iter()
+            .map(|row| (serde_json::to_value(&row).unwrap_or(Value::Null), row))
+            .filter(|(value, _)| {
+                filters.iter().all(|(field, expected)| {
+                    value.get(field).is_some_and(|v| cell_text(v) == *expected)
+                })
+            })
+            .filter(|(value, _)| search.as_deref().is_none_or(|q| matches_search(value, q)))
+            .collect();
+
+        if let Some(field) = self.sort.as_deref() {
+            matched.sort_by(|(a, _), (b, _)| compare(a.get(field), b.get(field)));
+            if self.descending() {
+                matched.reverse();
+            }
+        }
+
+        matched
+            .into_iter()
+            .map(|(_, row)| row)
+            .skip(self.offset.unwrap_or(0))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// Whether any field of `row` contains the lowercase `query`
+fn matches_search(row: &Value, query: &str) -> bool {
+    match row {
+        Value::Object(fields) => fields
+            .values()
+            .any(|v| cell_text(v).to_lowercase().contains(query)),
+        other => cell_text(other).to_lowercase().contains(query),
+    }
+}
+
+/// Missing and null values sort first; numbers numerically, the rest as text
+fn compare(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    let a = a.filter(|v| !v.is_null());
+    let b = b.filter(|v| !v.is_null());
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(Value::Number(x)), Some(Value::Number(y))) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(x), Some(y)) => cell_text(x)
+            .to_lowercase()
+            .cmp(&cell_text(y).to_lowercase()),
+    }
+}
+
+/// Plain-text form of a JSON value, as shown in a table cell
+///
+/// Record ids (`{"tb": "site", "id": {"String": "hq"}}`) become `site:hq`;
+/// other objects and arrays stay compact JSON.
+pub fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::Object(map) if map.len() == 2 && map.contains_key("tb") => {
+            let table = map["tb"].as_str().unwrap_or_default();
+            let key = match map.get("id") {
+                Some(Value::Object(id)) if id.len() == 1 => id.values().next().map(cell_text),
+                Some(id) => Some(cell_text(id)),
+                None => None,
+            };
+            format!("{}:{}", table, key.unwrap_or_default())
+        }
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn names(rows: &[Value]) -> Vec<&str> {
+        rows.iter().map(|r| r["name"].as_str().unwrap()).collect()
+    }
+
+    fn rows() -> Vec<Value> {
+        vec![
+            json!({"name": "Core Switch", "status": "active", "ports": 48}),
+            json!({"name": "Edge Router", "status": "spare", "ports": 8}),
+            json!({"name": "Access Switch", "status": "active", "ports": 24}),
+        ]
+    }
+
+    #[test]
+    fn search_and_filter_narrow_rows() {
+        let options = QueryOptions {
+            q: Some("SWITCH".into()),
+            filter: Some("status:active".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&options.apply(rows())),
+            vec!["Core Switch", "Access Switch"]
+        );
+    }
+
+    #[test]
+    fn sort_is_numeric_and_pages_after_sorting() {
+        let options = QueryOptions {
+            sort: Some("ports".into()),
+            order: Some("desc".into()),
+            offset: Some(1),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(names(&options.apply(rows())), vec!["Access Switch"]);
+    }
+
+    #[test]
+    fn record_ids_render_as_table_key() {
+        let id = json!({"tb": "site", "id": {"String": "hq"}});
+        assert_eq!(cell_text(&id), "site:hq");
+        assert_eq!(cell_text(&json!(null)), "");
+        assert_eq!(cell_text(&json!([1, 2])), "[1,2]");
+    }
+}
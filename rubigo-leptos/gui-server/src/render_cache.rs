@@ -0,0 +1,169 @@
+//! Rendered tab fragments for the root page
+//!
+//! Rendering a tab means fetching every table it shows and running the
+//! Leptos SSR pass, most of which is wasted when nothing changed since the
+//! last visit. The repositories bump a [`versions`] counter after each write,
+//! so a fragment is stored with the combined version of the data its tab
+//! shows and served again until one of those counters moves.
+//!
+//! Fragments are keyed on the persona, the day and the raw query string, the
+//! only other inputs to a tab's markup.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use nexosim_hybrid::database::versions::{self, Data};
+
+/// Fragments kept before the cache is emptied and refilled
+const CAPACITY: usize = 256;
+
+/// Data shown by each tab, besides the people every tab looks the persona up in
+fn dependencies(tab: &str) -> &'static [Data] {
+    match tab {
+        "home" => &[Data::Components, Data::Geo, Data::Runs],
+        "components" => &[Data::Components, Data::Telemetry],
+        "connections" => &[Data::Connections, Data::Components],
+        "simulation" => &[
+            Data::Runs,
+            Data::Components,
+            Data::Connections,
+            Data::Geo,
+            Data::Sandboxes,
+        ],
+        "jobs" => &[Data::Jobs],
+        "flags" => &[Data::Flags],
+        "webhooks" => &[Data::Webhooks],
+        "email" => &[Data::Email],
+        "reports" => &[Data::Reports],
+        "sites" => &[
+            Data::Geo,
+            Data::DeskBookings,
+            Data::Components,
+            Data::Connections,
+            Data::Cabling,
+            Data::Flags,
+        ],
+        "personnel" => &[Data::Geo],
+        "assets" => &[Data::Assets, Data::Geo],
+        "maintenance" => &[Data::Assets, Data::Maintenance],
+        "capacity" => &[Data::Geo, Data::Cabling, Data::Capacity, Data::Runs],
+        "incidents" => &[Data::Incidents, Data::Components, Data::Geo, Data::OnCall],
+        "oncall" => &[Data::OnCall],
+        "calendar" => &[
+            Data::Meetings,
+            Data::Maintenance,
+            Data::Assets,
+            Data::DeskBookings,
+            Data::Geo,
+            Data::OnCall,
+        ],
+        // Static placeholders, or the chat island which loads its own data
+        "metrics" | "tasks" | "contracts" | "finance" | "risk" | "requirements" | "development"
+        | "chat" | "meetings" | "presentations" => &[],
+        // Anything else falls back to the landing page
+        _ => &[Data::Components, Data::Geo],
+    }
+}
+
+/// Tabs showing who is on call right now, which moves with the clock
+/// rather than with any write
+const CLOCKED: [&str; 1] = ["oncall"];
+
+/// Combined version of everything `tab` renders
+pub fn version(tab: &str) -> u64 {
+    // Minutes since the epoch only ever grow, like the counters
+    let clock = if CLOCKED.contains(&tab) {
+        chrono::Utc::now().timestamp() as u64 / 60
+    } else {
+        0
+    };
+    versions::version(Data::People) + versions::combined(dependencies(tab)) + clock
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FragmentKey {
+    pub persona: String,
+    pub today: String,
+    pub query: String,
+}
+
+struct Fragment {
+    version: u64,
+    html: Arc<str>,
+}
+
+#[derive(Clone, Default)]
+pub struct RenderCache {
+    fragments: Arc<RwLock<HashMap<FragmentKey, Fragment>>>,
+}
+
+impl RenderCache {
+    /// The fragment for `key`, if it was rendered at `version`
+    pub fn get(&self, key: &FragmentKey, version: u64) -> Option<Arc<str>> {
+        let fragments = self.fragments.read().unwrap_or_else(|e| e.into_inner());
+        fragments
+            .get(key)
+            .filter(|f| f.version == version)
+            .map(|f| f.html.clone())
+    }
+
+    /// Store a fragment rendered from data at `version`
+    ///
+    /// `version` must be read before the data is fetched; a write that lands
+    /// in between then leaves the fragment stale on arrival rather than
+    /// serving old data under the new version.
+    pub fn insert(&self, key: FragmentKey, version: u64, html: Arc<str>) {
+        let mut fragments = self.fragments.write().unwrap_or_else(|e| e.into_inner());
+        if fragments.len() >= CAPACITY && !fragments.contains_key(&key) {
+            fragments.clear();
+        }
+        fragments.insert(key, Fragment { version, html });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(query: &str) -> FragmentKey {
+        FragmentKey {
+            persona: "Ada".into(),
+            today: "2026-01-01".into(),
+            query: query.into(),
+        }
+    }
+
+    #[test]
+    fn fragments_are_served_only_at_their_version() {
+        let cache = RenderCache::default();
+        cache.insert(key("tab=jobs"), 3, "<p>jobs</p>".into());
+
+        assert_eq!(
+            cache.get(&key("tab=jobs"), 3).as_deref(),
+            Some("<p>jobs</p>")
+        );
+        assert!(cache.get(&key("tab=jobs"), 4).is_none());
+        assert!(cache.get(&key("tab=reports"), 3).is_none());
+    }
+
+    #[test]
+    fn writes_move_only_the_tabs_that_show_them() {
+        let (jobs, reports) = (version("jobs"), version("reports"));
+        versions::bump(Data::Reports);
+        assert!(version("reports") > reports);
+        // Other tests may bump concurrently, but never backwards
+        assert!(version("jobs") >= jobs);
+        assert_eq!(dependencies("metrics"), &[] as &[Data]);
+    }
+
+    #[test]
+    fn full_cache_is_emptied_before_growing() {
+        let cache = RenderCache::default();
+        for i in 0..CAPACITY {
+            cache.insert(key(&format!("tab=sites&site_id={i}")), 0, "".into());
+        }
+        cache.insert(key("tab=jobs"), 0, "".into());
+        assert!(cache.get(&key("tab=sites&site_id=0"), 0).is_none());
+        assert!(cache.get(&key("tab=jobs"), 0).is_some());
+    }
+}
@@ -0,0 +1,252 @@
+//! Report rendering
+//!
+//! Report content comes from `nexosim_hybrid::database::reports` as a title,
+//! headline figures and tables. Here it is rendered either to a standalone
+//! HTML page (styles inlined so the saved file reads on its own) or to PDF
+//! through the minimal writer in [`crate::pdf`], then stored so the Reports
+//! page can offer it again. PDFs only show WinAnsi text; names in other
+//! scripts need the HTML format.
+
+use leptos::prelude::*;
+use nexosim_hybrid::database::components::ComponentRepository;
+use nexosim_hybrid::database::connections::ConnectionRepository;
+use nexosim_hybrid::database::geo::GeoRepository;
+use nexosim_hybrid::database::reports::{
+    self, GeneratedReport, ReportData, ReportFormat, ReportKind, ReportRepository, ReportTable,
+};
+use nexosim_hybrid::database::simulation::SimulationRepository;
+use nexosim_hybrid::database::DbClient;
+
+use crate::pdf::{Font, PdfDocument};
+
+const REPORT_CSS: &str = "\
+body { font-family: Helvetica, Arial, sans-serif; color: #1a1a23; margin: 32px; }\
+h1 { margin: 0 0 4px; font-size: 24px; }\
+h2 { margin: 28px 0 8px; font-size: 16px; }\
+.generated { color: #6b6b78; font-size: 12px; }\
+dl { display: grid; grid-template-columns: max-content auto; gap: 4px 16px; font-size: 13px; }\
+dt { color: #6b6b78; }\
+dd { margin: 0; font-weight: 600; }\
+table { width: 100%; border-collapse: collapse; font-size: 12px; }\
+th, td { padding: 4px 8px; border-bottom: 1px solid #d8d8e0; text-align: left; }\
+th { background: #f0f0f4; }\
+@media print { body { margin: 0; } h2 { break-after: avoid; } }\
+@media print { thead { display: table-header-group; } tr { break-inside: avoid; } }";
+
+/// Text size of PDF table rows
+const PDF_TABLE_SIZE: f64 = 8.0;
+
+/// Fetch the data for `kind`, render it as `format` and store the document
+pub async fn generate(
+    db: &DbClient,
+    kind: ReportKind,
+    format: ReportFormat,
+) -> anyhow::Result<GeneratedReport> {
+    let generated_at = crate::clock::now();
+    let data = match kind {
+        ReportKind::AssetInventory => reports::asset_inventory(
+            &GeoRepository::list_all_assets(db).await?,
+            &GeoRepository::list_all_racks(db).await?,
+            &GeoRepository::list_all_spaces(db).await?,
+            &generated_at,
+        ),
+        ReportKind::PersonnelRoster => reports::personnel_roster(
+            &GeoRepository::list_all_people(db).await?,
+            &GeoRepository::list_sites(db).await?,
+            &generated_at,
+        ),
+        ReportKind::SimulationSummary => reports::simulation_summary(
+            &SimulationRepository::get_all(db).await?,
+            &ComponentRepository::get_all(db).await?,
+            &ConnectionRepository::get_all(db).await?,
+            &generated_at,
+        ),
+        ReportKind::EnergySummary => {
+            reports::energy_summary(&SimulationRepository::get_all(db).await?, &generated_at)
+        }
+    };
+
+    let bytes = match format {
+        ReportFormat::Html => render_html(&data).into_bytes(),
+        ReportFormat::Pdf => render_pdf(&data),
+    };
+    ReportRepository::create(db, GeneratedReport::new(&data, format, &bytes)).await
+}
+
+/// Standalone HTML page for a report
+pub fn render_html(data: &ReportData) -> String {
+    let owner = Owner::new();
+    owner.set();
+    let html = owner.with(|| {
+        let title = data.title.clone();
+        view! {
+            <html lang="en">
+                <head>
+                    <meta charset="utf-8" />
+                    <title>{title.clone()}</title>
+                    <style>{REPORT_CSS}</style>
+                </head>
+                <body>
+                    <h1>{title}</h1>
+                    <p class="generated">{format!("Generated {}", data.generated_at)}</p>
+                    <dl>
+                        {data.summary.iter().map(|(label, value)| view! {
+                            <dt>{label.clone()}</dt>
+                            <dd>{value.clone()}</dd>
+                        }).collect_view()}
+                    </dl>
+                    {data.tables.iter().map(|table| view! {
+                        <h2>{table.heading.clone()}</h2>
+                        <table>
+                            <thead>
+                                <tr>
+                                    {table.columns.iter().map(|c| view! { <th>{c.clone()}</th> }).collect_view()}
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {table.rows.iter().map(|row| view! {
+                                    <tr>
+                                        {row.iter().map(|cell| view! { <td>{cell.clone()}</td> }).collect_view()}
+                                    </tr>
+                                }).collect_view()}
+                            </tbody>
+                        </table>
+                    }).collect_view()}
+                </body>
+            </html>
+        }
+        .to_html()
+    });
+
+    format!("<!DOCTYPE html>{}", html)
+}
+
+/// PDF rendering: title, summary lines, then each table as fixed-width text
+pub fn render_pdf(data: &ReportData) -> Vec<u8> {
+    let mut doc = PdfDocument::new();
+    doc.line(Font::Bold, 20.0, &data.title);
+    doc.line(
+        Font::Regular,
+        9.0,
+        &format!("Generated {}", data.generated_at),
+    );
+    doc.gap(8.0);
+    for (label, value) in &data.summary {
+        doc.line(Font::Regular, 10.0, &format!("{label}: {value}"));
+    }
+
+    let columns = PdfDocument::mono_columns(PDF_TABLE_SIZE);
+    for table in &data.tables {
+        doc.gap(12.0);
+        doc.line(Font::Bold, 12.0, &table.heading);
+        let widths = fit_widths(table, columns);
+        doc.line(
+            Font::Mono,
+            PDF_TABLE_SIZE,
+            &text_row(&table.columns, &widths),
+        );
+        doc.line(Font::Mono, PDF_TABLE_SIZE, &"-".repeat(columns));
+        for row in &table.rows {
+            doc.line(Font::Mono, PDF_TABLE_SIZE, &text_row(row, &widths));
+        }
+        if table.rows.is_empty() {
+            doc.line(Font::Regular, 9.0, "No entries");
+        }
+    }
+    doc.finish()
+}
+
+/// Column widths in characters that fit `total`, narrowing the widest first
+fn fit_widths(table: &ReportTable, total: usize) -> Vec<usize> {
+    let mut widths: Vec<usize> = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, heading)| {
+            table
+                .rows
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(heading.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    // One space between columns
+    let available = total.saturating_sub(widths.len().saturating_sub(1));
+    while widths.iter().sum::<usize>() > available {
+        let Some(widest) = widths.iter_mut().max() else {
+            break;
+        };
+        if *widest <= 4 {
+            break;
+        }
+        *widest -= 1;
+    }
+    widths
+}
+
+fn text_row(cells: &[String], widths: &[usize]) -> String {
+    let padded: Vec<String> = widths
+        .iter()
+        .enumerate()
+        .map(|(i, &width)| {
+            let cell = cells.get(i).map(String::as_str).unwrap_or_default();
+            let count = cell.chars().count();
+            if count > width {
+                let kept: String = cell.chars().take(width.saturating_sub(1)).collect();
+                format!("{kept}~")
+            } else {
+                format!("{cell:width$}")
+            }
+        })
+        .collect();
+    padded.join(" ").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[&[&str]]) -> ReportTable {
+        ReportTable {
+            heading: "Assets".into(),
+            columns: vec!["Name".into(), "Notes".into()],
+            rows: rows
+                .iter()
+                .map(|r| r.iter().map(|c| c.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn widths_shrink_to_fit() {
+        let long = "x".repeat(200);
+        let t = table(&[&["router-01", &long]]);
+        let widths = fit_widths(&t, 107);
+        assert_eq!(widths[0], 9);
+        assert_eq!(widths.iter().sum::<usize>() + 1, 107);
+
+        let line = text_row(&t.rows[0], &widths);
+        assert_eq!(line.chars().count(), 107);
+        assert!(line.ends_with('~'));
+    }
+
+    #[test]
+    fn html_escapes_cell_text() {
+        let data = ReportData {
+            kind: ReportKind::AssetInventory,
+            title: "Asset Inventory".into(),
+            generated_at: "2025-01-06 09:00:00 UTC".into(),
+            summary: vec![("Assets".into(), "1".into())],
+            tables: vec![table(&[&["<script>", "a & b"]])],
+        };
+        let html = render_html(&data);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("a &amp; b"));
+        assert!(!html.contains("<script>"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Request IDs, per-request tracing spans and the recent-requests page
+//!
+//! Every request gets an `x-request-id` (generated unless the client sent
+//! one) that is echoed on the response and recorded on a `request` span with
+//! the matched route. Completed requests are also appended to the shared
+//! [`LogBuffer`](crate::LogBuffer), which backs `/debug/requests`.
+
+use std::time::Instant;
+
+use axum::body::Body;
+use axum::extract::{MatchedPath, Request, State};
+use axum::middleware::Next;
+use axum::response::{Html, Response};
+use axum::Router;
+use leptos::prelude::*;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
+use tower_http::LatencyUnit;
+
+use crate::AppState;
+
+/// Lines shown on the debug page
+const RECENT_LIMIT: usize = 200;
+
+/// Paths that are too chatty to be worth recording
+fn is_noise(path: &str) -> bool {
+    path.starts_with("/assets/") || path == "/sse" || path == "/healthz" || path == "/readyz"
+}
+
+fn request_id(req: &Request<Body>) -> &str {
+    req.headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("-")
+}
+
+/// Wrap the router with request-id, tracing and request-log layers
+pub fn layer(router: Router, state: AppState) -> Router {
+    // Layers run outermost-last: the id is set first, then traced, then
+    // recorded, and finally copied onto the response.
+    router
+        .layer(axum::middleware::from_fn_with_state(state, record))
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(|req: &Request<Body>| {
+                    let route = req
+                        .extensions()
+                        .get::<MatchedPath>()
+                        .map(|p| p.as_str())
+                        .unwrap_or_else(|| req.uri().path());
+                    tracing::info_span!(
+                        "request",
+                        method = %req.method(),
+                        route,
+                        request_id = request_id(req),
+                    )
+                })
+                .on_response(
+                    DefaultOnResponse::new()
+                        .level(tracing::Level::INFO)
+                        .latency_unit(LatencyUnit::Millis),
+                ),
+        )
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+/// Append a summary line for each completed request to the log buffer
+async fn record(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_string();
+    if is_noise(&path) {
+        return next.run(req).await;
+    }
+
+    let method = req.method().clone();
+    let id = request_id(&req).to_string();
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    state
+        .logs
+        .append(format!(
+            "[{}] {} {} {} -> {} ({:.1} ms)",
+            chrono::Utc::now().format("%H:%M:%S"),
+            id,
+            method,
+            path,
+            response.status().as_u16(),
+            elapsed_ms,
+        ))
+        .await;
+
+    response
+}
+
+/// Recent requests, newest first
+pub async fn recent_requests_page(State(state): State<AppState>) -> Html<String> {
+    let lines = state.logs.recent(RECENT_LIMIT).await;
+
+    let owner = Owner::new();
+    owner.set();
+    let html = owner.with(|| {
+        view! {
+            <html lang="en">
+                <head>
+                    <meta charset="utf-8" />
+                    <title>"Recent Requests"</title>
+                    <link rel="stylesheet" href="/assets/style.css" />
+                </head>
+                <body>
+                    <div class="card" style="margin: 24px;">
+                        <div style="display: flex; justify-content: space-between; align-items: center;">
+                            <h2>"Recent Requests"</h2>
+                            <a href="/debug/requests" class="btn btn-sm btn-secondary">"Refresh"</a>
+                        </div>
+                        <p class="text-muted">
+                            {format!("Last {} entries from the server log buffer", lines.len())}
+                        </p>
+                        <div class="log-output" style="padding: 12px; background: var(--bg-body); border-radius: 8px; font-family: var(--font-mono); font-size: 13px; overflow-x: auto;">
+                            {lines.into_iter().map(|line| {
+                                view! { <div style="padding: 4px 0; color: var(--text-secondary);">{line}</div> }
+                            }).collect_view()}
+                        </div>
+                    </div>
+                </body>
+            </html>
+        }
+        .to_html()
+    });
+
+    Html(format!("<!DOCTYPE html>{}", html))
+}
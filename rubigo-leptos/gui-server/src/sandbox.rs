@@ -0,0 +1,508 @@
+//! What-if topology sandboxes
+//!
+//! A sandbox branches the live components and connections into a scratch
+//! copy. The copy can be edited and simulated (runs are tagged with the
+//! sandbox's name, so they line up against live runs in the comparison
+//! view) and is then either discarded or merged back.
+//!
+//! Merging replays the sandbox's own edits, found by diffing it against the
+//! topology it was branched from, onto the live topology. Anything the live
+//! side changed in a different way since the branch is a conflict and stops
+//! the merge unless it is forced, in which case the sandbox wins.
+
+use std::collections::BTreeMap;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use nexosim_hybrid::config::ConnectionConfig;
+use nexosim_hybrid::database::components::ComponentRepository;
+use nexosim_hybrid::database::connections::ConnectionRepository;
+use nexosim_hybrid::database::sandbox::{Sandbox, SandboxRepository, Topology};
+use nexosim_hybrid::database::simulation::SimulationRun;
+use nexosim_hybrid::database::DbClient;
+use operations::topology::{conflicts, diff, Change};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::api::{found, record_key, ApiError, ApiResult};
+use crate::AppState;
+
+/// The live components and connections
+pub async fn live(db: &DbClient) -> anyhow::Result<Topology> {
+    Ok(Topology {
+        components: ComponentRepository::get_all(db).await?,
+        connections: ConnectionRepository::get_all(db).await?,
+    })
+}
+
+/// Branch the live topology into a new sandbox; a blank name gets one
+/// from the time
+pub async fn branch(db: &DbClient, name: &str) -> anyhow::Result<Sandbox> {
+    let now = crate::clock::now();
+    let name = match name.trim() {
+        "" => format!("Sandbox {now}"),
+        name => name.to_string(),
+    };
+    SandboxRepository::create(db, &name, &now, live(db).await?).await
+}
+
+/// Apply `edit` to sandbox `id`'s topology and save it when it reports a
+/// change; `None` when there is no such sandbox
+pub async fn edit(
+    db: &DbClient,
+    id: &str,
+    edit: impl FnOnce(&mut Topology) -> bool,
+) -> anyhow::Result<Option<bool>> {
+    let Some(sandbox) = SandboxRepository::get(db, id).await? else {
+        return Ok(None);
+    };
+    let mut topology = sandbox.topology;
+    if !edit(&mut topology) {
+        return Ok(Some(false));
+    }
+    SandboxRepository::update_topology(db, id, topology).await?;
+    Ok(Some(true))
+}
+
+/// Simulate the sandbox's topology
+pub async fn run(state: &AppState, sandbox: &Sandbox) -> anyhow::Result<SimulationRun> {
+    let topology = sandbox.topology.clone();
+    crate::simulation::run_topology(
+        state,
+        topology.components,
+        topology.connections,
+        Some(sandbox.name.clone()),
+        Vec::new(),
+    )
+    .await
+}
+
+fn components(topology: &Topology) -> BTreeMap<u32, Value> {
+    topology
+        .components
+        .iter()
+        .map(|c| (c.id, serde_json::to_value(c).unwrap_or_default()))
+        .collect()
+}
+
+fn connections(topology: &Topology) -> BTreeMap<(u32, u32), Value> {
+    topology
+        .connections
+        .iter()
+        .map(|c| ((c.from, c.to), Value::Bool(true)))
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentChange {
+    pub id: u32,
+    pub name: String,
+    /// `added`, `removed` or `changed`
+    #[schema(value_type = String)]
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConnectionChange {
+    pub from: u32,
+    pub to: u32,
+    /// `added` or `removed`
+    #[schema(value_type = String)]
+    pub change: Change,
+}
+
+/// The sandbox's edits since it was branched
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct SandboxDiff {
+    pub components: Vec<ComponentChange>,
+    pub connections: Vec<ConnectionChange>,
+    /// Edits the live topology has since made differently, e.g. `component 3`
+    pub conflicts: Vec<String>,
+}
+
+impl SandboxDiff {
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.connections.is_empty()
+    }
+}
+
+/// What `sandbox` changed from its base, checked against `live`
+pub fn changes(sandbox: &Sandbox, live: &Topology) -> SandboxDiff {
+    let name = |id: u32| {
+        sandbox
+            .topology
+            .component(id)
+            .or_else(|| sandbox.base.component(id))
+            .map(|c| c.name.clone())
+            .unwrap_or_default()
+    };
+    let (base, branch, now) = (
+        components(&sandbox.base),
+        components(&sandbox.topology),
+        components(live),
+    );
+    let mut conflicted: Vec<String> = conflicts(&base, &branch, &now)
+        .into_iter()
+        .map(|id| format!("component {id}"))
+        .collect();
+    let components = diff(&base, &branch)
+        .into_iter()
+        .map(|(id, change)| ComponentChange {
+            id,
+            name: name(id),
+            change,
+        })
+        .collect();
+
+    let (base, branch, now) = (
+        connections(&sandbox.base),
+        connections(&sandbox.topology),
+        connections(live),
+    );
+    conflicted.extend(
+        conflicts(&base, &branch, &now)
+            .into_iter()
+            .map(|(from, to)| format!("connection {from} → {to}")),
+    );
+    let connections = diff(&base, &branch)
+        .into_iter()
+        .map(|((from, to), change)| ConnectionChange { from, to, change })
+        .collect();
+
+    SandboxDiff {
+        components,
+        connections,
+        conflicts: conflicted,
+    }
+}
+
+/// Components and connections written by a merge
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct MergeReport {
+    pub components: usize,
+    pub connections: usize,
+}
+
+pub enum MergeOutcome {
+    Merged(MergeReport),
+    /// Nothing was written; these conflict with live edits
+    Conflicts(Vec<String>),
+}
+
+/// Replay the sandbox's edits onto the live topology and remove the sandbox
+pub async fn merge(db: &DbClient, sandbox: &Sandbox, force: bool) -> anyhow::Result<MergeOutcome> {
+    let now = live(db).await?;
+    let changes = changes(sandbox, &now);
+    if !force && !changes.conflicts.is_empty() {
+        return Ok(MergeOutcome::Conflicts(changes.conflicts));
+    }
+
+    let mut report = MergeReport::default();
+    for change in &changes.components {
+        let exists = now.component(change.id).is_some();
+        match (change.change, sandbox.topology.component(change.id)) {
+            (Change::Removed, _) => {
+                if exists {
+                    ComponentRepository::delete(db, change.id).await?;
+                }
+                // Live connections to it the sandbox never knew about would dangle
+                for c in now
+                    .connections
+                    .iter()
+                    .filter(|c| c.from == change.id || c.to == change.id)
+                {
+                    ConnectionRepository::delete(db, c.from, c.to).await?;
+                }
+            }
+            (_, Some(component)) if exists => {
+                ComponentRepository::update(db, change.id, component.clone()).await?;
+            }
+            (_, Some(component)) => {
+                ComponentRepository::create(db, component.clone()).await?;
+            }
+            (_, None) => continue,
+        }
+        report.components += 1;
+    }
+    let linked = |from: u32, to: u32| now.connections.iter().any(|c| (c.from, c.to) == (from, to));
+    for change in &changes.connections {
+        match change.change {
+            Change::Added if !linked(change.from, change.to) => {
+                ConnectionRepository::create(
+                    db,
+                    ConnectionConfig {
+                        from: change.from,
+                        to: change.to,
+                    },
+                )
+                .await?;
+            }
+            Change::Removed if linked(change.from, change.to) => {
+                ConnectionRepository::delete(db, change.from, change.to).await?;
+            }
+            _ => continue,
+        }
+        report.connections += 1;
+    }
+
+    if let Some(id) = &sandbox.id {
+        SandboxRepository::delete(db, &id.id.to_raw()).await?;
+    }
+    Ok(MergeOutcome::Merged(report))
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+async fn sandbox(state: &AppState, id: &str) -> ApiResult<Sandbox> {
+    found(
+        SandboxRepository::get(&state.db.client, record_key(id, "sandbox")).await?,
+        "Sandbox",
+        id,
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sandboxes",
+    tag = "sandboxes",
+    responses((status = 200, description = "Sandboxes, newest first", body = Vec<Sandbox>))
+)]
+pub async fn list(State(state): State<AppState>) -> ApiResult<Json<Vec<Sandbox>>> {
+    Ok(Json(SandboxRepository::list(&state.db.client).await?))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSandbox {
+    /// Defaults to one made from the time
+    #[serde(default)]
+    pub name: String,
+}
+
+/// Branch the live topology into a new sandbox
+#[utoipa::path(
+    post,
+    path = "/api/sandboxes",
+    tag = "sandboxes",
+    request_body = CreateSandbox,
+    responses((status = 201, description = "Sandbox holding a copy of the live topology", body = Sandbox))
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Json(body): Json<CreateSandbox>,
+) -> ApiResult<(StatusCode, Json<Sandbox>)> {
+    Ok((
+        StatusCode::CREATED,
+        Json(branch(&state.db.client, &body.name).await?),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sandboxes/{id}",
+    tag = "sandboxes",
+    params(("id" = String, Path, description = "Sandbox id (`sandbox:key` or `key`)")),
+    responses(
+        (status = 200, description = "The sandbox with the topology it was branched from", body = Sandbox),
+        (status = 404, description = "Sandbox not found", body = ApiError),
+    )
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Sandbox>> {
+    Ok(Json(sandbox(&state, &id).await?))
+}
+
+/// Throw the sandbox away without touching the live topology
+#[utoipa::path(
+    delete,
+    path = "/api/sandboxes/{id}",
+    tag = "sandboxes",
+    params(("id" = String, Path, description = "Sandbox id")),
+    responses(
+        (status = 204, description = "Sandbox discarded"),
+        (status = 404, description = "Sandbox not found", body = ApiError),
+    )
+)]
+pub async fn discard(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    sandbox(&state, &id).await?;
+    SandboxRepository::delete(&state.db.client, record_key(&id, "sandbox")).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Replace the sandbox's components and connections
+#[utoipa::path(
+    put,
+    path = "/api/sandboxes/{id}/topology",
+    tag = "sandboxes",
+    params(("id" = String, Path, description = "Sandbox id")),
+    request_body = Topology,
+    responses(
+        (status = 200, description = "Sandbox with its new topology", body = Sandbox),
+        (status = 400, description = "A connection names a component the topology lacks", body = ApiError),
+        (status = 404, description = "Sandbox not found", body = ApiError),
+    )
+)]
+pub async fn update_topology(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(topology): Json<Topology>,
+) -> ApiResult<Json<Sandbox>> {
+    sandbox(&state, &id).await?;
+    if let Some(c) = topology
+        .connections
+        .iter()
+        .find(|c| topology.component(c.from).is_none() || topology.component(c.to).is_none())
+    {
+        return Err(ApiError::bad_request(format!(
+            "Connection {} → {} names a missing component",
+            c.from, c.to
+        )));
+    }
+    let updated =
+        SandboxRepository::update_topology(&state.db.client, record_key(&id, "sandbox"), topology)
+            .await?;
+    Ok(Json(found(updated, "Sandbox", &id)?))
+}
+
+/// The sandbox's edits, and which of them the live topology has since
+/// changed differently
+#[utoipa::path(
+    get,
+    path = "/api/sandboxes/{id}/diff",
+    tag = "sandboxes",
+    params(("id" = String, Path, description = "Sandbox id")),
+    responses(
+        (status = 200, description = "Added, removed and changed components and connections", body = SandboxDiff),
+        (status = 404, description = "Sandbox not found", body = ApiError),
+    )
+)]
+pub async fn changes_since_branch(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<SandboxDiff>> {
+    let sandbox = sandbox(&state, &id).await?;
+    Ok(Json(changes(&sandbox, &live(&state.db.client).await?)))
+}
+
+/// Simulate the sandbox's topology
+#[utoipa::path(
+    post,
+    path = "/api/sandboxes/{id}/runs",
+    tag = "sandboxes",
+    params(("id" = String, Path, description = "Sandbox id")),
+    responses(
+        (status = 201, description = "Run completed, tagged with the sandbox name", body = SimulationRun),
+        (status = 404, description = "Sandbox not found", body = ApiError),
+    )
+)]
+pub async fn simulate(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<(StatusCode, Json<SimulationRun>)> {
+    let sandbox = sandbox(&state, &id).await?;
+    Ok((StatusCode::CREATED, Json(run(&state, &sandbox).await?)))
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct MergeQuery {
+    /// Merge despite conflicts, the sandbox's version winning
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Merge the sandbox's edits into the live topology and remove it
+#[utoipa::path(
+    post,
+    path = "/api/sandboxes/{id}/merge",
+    tag = "sandboxes",
+    params(("id" = String, Path, description = "Sandbox id"), MergeQuery),
+    responses(
+        (status = 200, description = "Components and connections written", body = MergeReport),
+        (status = 404, description = "Sandbox not found", body = ApiError),
+        (status = 409, description = "The live topology changed the same things differently", body = ApiError),
+    )
+)]
+pub async fn merge_into_live(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<MergeQuery>,
+) -> ApiResult<Json<MergeReport>> {
+    let sandbox = sandbox(&state, &id).await?;
+    match merge(&state.db.client, &sandbox, query.force).await? {
+        MergeOutcome::Merged(report) => Ok(Json(report)),
+        MergeOutcome::Conflicts(conflicts) => Err(ApiError::conflict(format!(
+            "Changed on the live topology since the sandbox was branched: {}",
+            conflicts.join(", ")
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexosim_hybrid::config::{ComponentConfig, ComponentType};
+
+    fn component(id: u32, name: &str) -> ComponentConfig {
+        ComponentConfig {
+            id,
+            name: name.to_string(),
+            component_type: ComponentType::Router,
+            placement: Default::default(),
+            power: None,
+        }
+    }
+
+    #[test]
+    fn changes_list_edits_and_live_conflicts() {
+        let mut base = Topology::default();
+        base.put_component(component(1, "r1"));
+        base.put_component(component(2, "r2"));
+        assert!(base.connect(1, 2));
+        let mut topology = base.clone();
+        topology.put_component(component(3, "fw"));
+        assert!(topology.connect(2, 3));
+        assert!(topology.remove_component(1));
+        assert!(!topology.connect(1, 3));
+        let sandbox = Sandbox {
+            id: None,
+            name: "what-if".to_string(),
+            created_at: String::new(),
+            base: base.clone(),
+            topology,
+        };
+
+        let diff = changes(&sandbox, &base);
+        let components: Vec<(u32, &str, Change)> = diff
+            .components
+            .iter()
+            .map(|c| (c.id, c.name.as_str(), c.change))
+            .collect();
+        assert_eq!(
+            components,
+            vec![(1, "r1", Change::Removed), (3, "fw", Change::Added)]
+        );
+        let connections: Vec<(u32, u32, Change)> = diff
+            .connections
+            .iter()
+            .map(|c| (c.from, c.to, c.change))
+            .collect();
+        assert_eq!(
+            connections,
+            vec![(1, 2, Change::Removed), (2, 3, Change::Added)]
+        );
+        assert!(diff.conflicts.is_empty());
+
+        // Live renamed the component the sandbox removed
+        let mut live = base;
+        live.put_component(component(1, "core"));
+        assert_eq!(changes(&sandbox, &live).conflicts, vec!["component 1"]);
+    }
+}
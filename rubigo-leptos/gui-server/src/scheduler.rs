@@ -0,0 +1,627 @@
+//! Scheduled tasks
+//!
+//! Recurring server work, driven by `schedule` records in the database: each
+//! names one of the [`TASKS`] and a cron expression (see
+//! [`operations::schedule`]). Every [`TICK`] the scheduler starts the enabled
+//! schedules that are due; a run goes through the job queue like any other
+//! background work, so it gets retries and shows on the Jobs page, and is
+//! recorded as a `schedule_run` with its outcome.
+//!
+//! A run that fails for good alerts the personas in `[runtime.scheduler]
+//! alert`; when empty, whoever is on call for the default escalation policy
+//! (see `crate::oncall`), or everyone when nobody is. The default schedules are created on first
+//! start; `/api/schedules` lists, edits and runs them by hand.
+
+use std::path::{Path as FsPath, PathBuf};
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use nexosim_hybrid::database::jobs::{JobRepository, JobStatus};
+use nexosim_hybrid::database::maintenance::MaintenanceRepository;
+use nexosim_hybrid::database::notifications::{Notification, NotificationKind};
+use nexosim_hybrid::database::schedules::{Schedule, ScheduleRepository, ScheduleRun};
+use operations::schedule::{format_minute, parse_minute, CronSchedule};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::api::{found, record_key, ApiError, ApiResult};
+use crate::clock::minute;
+use crate::jobs::JobContext;
+use crate::AppState;
+
+/// How often due schedules are looked for
+const TICK: Duration = Duration::from_secs(30);
+
+/// Tasks a schedule can run, with what each does
+pub const TASKS: [(&str, &str); 8] = [
+    (
+        "scenario.export",
+        "Write the scenario to a JSON file in the export directory",
+    ),
+    (
+        "telemetry.rollup",
+        "Fold raw simulation metrics into per-device totals",
+    ),
+    (
+        "sessions.cleanup",
+        "Drop presence sessions whose connection went quiet",
+    ),
+    (
+        "simulation.run",
+        "Run the simulation against the current topology",
+    ),
+    (
+        "directory.sync",
+        "Copy people and their roles from the LDAP directory",
+    ),
+    (
+        "netbox.sync",
+        "Import sites, racks, devices and cables from NetBox",
+    ),
+    (
+        "capacity.snapshot",
+        "Record rack, power, cooling and port usage for exhaustion trends",
+    ),
+    (
+        "incidents.escalate",
+        "Page the next on-call level for incidents nobody has picked up",
+    ),
+];
+
+/// Schedules created when there are none: name, task, cron, enabled
+const DEFAULT_SCHEDULES: [(&str, &str, &str, bool); 7] = [
+    (
+        "Nightly scenario export",
+        "scenario.export",
+        "@nightly",
+        true,
+    ),
+    (
+        "Hourly telemetry rollup",
+        "telemetry.rollup",
+        "@hourly",
+        true,
+    ),
+    (
+        "Stale session cleanup",
+        "sessions.cleanup",
+        "*/5 * * * *",
+        true,
+    ),
+    (
+        "Weekly simulation run",
+        "simulation.run",
+        "0 6 * * 1",
+        false,
+    ),
+    ("Hourly directory sync", "directory.sync", "@hourly", true),
+    (
+        "Daily capacity snapshot",
+        "capacity.snapshot",
+        "@daily",
+        true,
+    ),
+    (
+        "Incident escalation",
+        "incidents.escalate",
+        "* * * * *",
+        true,
+    ),
+];
+
+fn stamp(time: DateTime<Utc>) -> String {
+    format!("{} UTC", format_minute(&time))
+}
+
+/// When a schedule with `cron` is next due after `after`; `None` when disabled
+fn next_run(cron: &str, enabled: bool, after: DateTime<Utc>) -> Result<Option<String>, String> {
+    let schedule = CronSchedule::parse(cron)?;
+    Ok(enabled
+        .then(|| schedule.next_after(&after))
+        .flatten()
+        .map(stamp))
+}
+
+/// Create the default schedules on first start, then run due schedules
+/// for as long as the server is up (unless `[runtime.scheduler]` is off)
+pub fn start(state: AppState) {
+    tokio::spawn(async move {
+        if let Err(e) = seed(&state).await {
+            tracing::warn!("Could not create the default schedules: {}", e);
+        }
+        if !state.settings.scheduler.enabled {
+            tracing::info!("Scheduler disabled; schedules only run by hand");
+            return;
+        }
+        let mut ticks = tokio::time::interval(TICK);
+        loop {
+            ticks.tick().await;
+            if let Err(e) = run_due(&state).await {
+                tracing::warn!("Scheduler tick failed: {}", e);
+            }
+        }
+    });
+}
+
+async fn seed(state: &AppState) -> anyhow::Result<()> {
+    if !ScheduleRepository::get_all(&state.db.client)
+        .await?
+        .is_empty()
+    {
+        return Ok(());
+    }
+    let now = minute();
+    for (name, task, cron, enabled) in DEFAULT_SCHEDULES {
+        let schedule = Schedule {
+            id: None,
+            name: name.to_string(),
+            task: task.to_string(),
+            cron: cron.to_string(),
+            enabled,
+            next_run_at: next_run(cron, enabled, now).map_err(anyhow::Error::msg)?,
+            last_run_at: None,
+            last_status: None,
+            created_at: stamp(now),
+        };
+        ScheduleRepository::create(&state.db.client, schedule).await?;
+    }
+    Ok(())
+}
+
+async fn run_due(state: &AppState) -> anyhow::Result<()> {
+    let now = minute();
+    for schedule in ScheduleRepository::get_all(&state.db.client).await? {
+        let due = schedule
+            .next_run_at
+            .as_deref()
+            .and_then(|next| parse_minute(next).ok())
+            .is_some_and(|next| next <= now);
+        if schedule.enabled && due {
+            let name = schedule.name.clone();
+            if let Err(e) = launch(state, schedule, false).await {
+                tracing::warn!("Could not start schedule {}: {}", name, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Queue a run of `schedule` and move its next run on
+async fn launch(state: &AppState, schedule: Schedule, manual: bool) -> anyhow::Result<ScheduleRun> {
+    let key = schedule.key();
+    let now = minute();
+    tracing::info!("Running schedule {} ({})", schedule.name, schedule.task);
+
+    let mut updated = schedule.clone();
+    updated.last_run_at = Some(stamp(now));
+    updated.last_status = Some(JobStatus::Queued);
+    // A broken expression stops the schedule rather than failing every tick
+    updated.next_run_at = next_run(&schedule.cron, schedule.enabled, now)
+        .ok()
+        .flatten();
+    ScheduleRepository::update(&state.db.client, &key, updated).await?;
+
+    let task = schedule.task.clone();
+    let task_state = state.clone();
+    let handle = state
+        .jobs
+        .enqueue_quiet(&schedule.task, &schedule.name, move |ctx| {
+            let state = task_state.clone();
+            let task = task.clone();
+            async move { perform(&state, &ctx, &task).await }
+        })
+        .await?;
+
+    let run = ScheduleRepository::create_run(
+        &state.db.client,
+        ScheduleRun {
+            id: None,
+            schedule: key,
+            task: schedule.task.clone(),
+            job: Some(handle.key.clone()),
+            manual,
+            started_at: stamp(now),
+            finished_at: None,
+            status: JobStatus::Queued,
+            result: None,
+            error: None,
+        },
+    )
+    .await?;
+
+    tokio::spawn({
+        let state = state.clone();
+        let run = run.clone();
+        async move {
+            let status = handle.wait().await;
+            finish(&state, &schedule, run, status).await;
+        }
+    });
+    Ok(run)
+}
+
+/// Run schedule `key` now, whether or not it is enabled or due
+pub async fn run(state: &AppState, key: &str) -> anyhow::Result<ScheduleRun> {
+    let schedule = ScheduleRepository::get_by_id(&state.db.client, key)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Schedule '{}' not found", key))?;
+    launch(state, schedule, true).await
+}
+
+/// Record how a run ended and raise the alarm if it failed
+async fn finish(state: &AppState, schedule: &Schedule, mut run: ScheduleRun, status: JobStatus) {
+    let run_key = run.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default();
+    let job = match &run.job {
+        Some(job) => JobRepository::get_by_id(&state.db.client, job)
+            .await
+            .ok()
+            .flatten(),
+        None => None,
+    };
+    run.status = status;
+    run.finished_at = Some(stamp(minute()));
+    run.result = job.as_ref().and_then(|job| job.result.clone());
+    run.error = job.as_ref().and_then(|job| job.error.clone());
+    let error = run.error.clone();
+    if let Err(e) = ScheduleRepository::finish_run(&state.db.client, &run_key, run).await {
+        tracing::warn!("Could not record run of schedule {}: {}", schedule.name, e);
+    }
+
+    let key = schedule.key();
+    if let Ok(Some(mut latest)) = ScheduleRepository::get_by_id(&state.db.client, &key).await {
+        latest.last_status = Some(status);
+        let _ = ScheduleRepository::update(&state.db.client, &key, latest).await;
+    }
+
+    if status == JobStatus::Failed {
+        alert(state, schedule, error).await;
+    }
+}
+
+async fn alert(state: &AppState, schedule: &Schedule, error: Option<String>) {
+    let title = format!("Scheduled task failed: {}", schedule.name);
+    let body = error.unwrap_or_else(|| "No error was recorded".to_string());
+    let notification = |recipient: Option<String>| {
+        Notification::new(NotificationKind::ScheduleFailed, recipient, title.clone())
+            .with_body(body.clone())
+            .with_link("/?tab=jobs")
+    };
+    let recipients = &state.settings.scheduler.alert;
+    if recipients.is_empty() {
+        // Whoever is on call, or everyone when nobody is
+        let responder = crate::oncall::first_responder(&state.db.client).await;
+        state.notifications.notify(notification(responder)).await;
+    } else {
+        state
+            .notifications
+            .notify_all(recipients.iter().map(|r| notification(Some(r.clone()))))
+            .await;
+    }
+}
+
+/// Do the work of `task`, returning a summary for the run history
+async fn perform(state: &AppState, ctx: &JobContext, task: &str) -> anyhow::Result<String> {
+    match task {
+        "scenario.export" => export_scenario(state, ctx).await,
+        "telemetry.rollup" => {
+            let (samples, devices) = nexosim_hybrid::telemetry::rollup(ctx.db()).await?;
+            Ok(format!(
+                "Rolled up {} samples from {} devices",
+                samples, devices
+            ))
+        }
+        "sessions.cleanup" => {
+            let swept = state.presence.sweep(crate::presence::STALE_AFTER);
+            Ok(format!("Dropped {} stale sessions", swept))
+        }
+        "simulation.run" => {
+            let run = crate::simulation::run(state, Vec::new()).await?;
+            Ok(format!("Simulation run {} {}", run.started_at, run.status))
+        }
+        "directory.sync" => state.identity.sync_directory().await,
+        "netbox.sync" => Ok(state
+            .netbox
+            .sync(ctx, state.settings.netbox.resync)
+            .await?
+            .to_string()),
+        "capacity.snapshot" => {
+            let recorded = crate::capacity::snapshot(ctx.db(), crate::capacity::today()).await?;
+            Ok(format!("Recorded {} capacity figures", recorded))
+        }
+        "incidents.escalate" => crate::oncall::escalate_all(state).await,
+        other => anyhow::bail!("Unknown task '{}'", other),
+    }
+}
+
+/// Write the scenario snapshot to `export_dir` and prune old exports
+async fn export_scenario(state: &AppState, ctx: &JobContext) -> anyhow::Result<String> {
+    let settings = &state.settings.scheduler;
+    let snapshot = MaintenanceRepository::snapshot(ctx.db()).await?;
+    ctx.progress(50).await;
+
+    tokio::fs::create_dir_all(&settings.export_dir).await?;
+    let name = chrono::Utc::now()
+        .format("scenario-%Y%m%d-%H%M.json")
+        .to_string();
+    let path = settings.export_dir.join(name);
+    tokio::fs::write(&path, serde_json::to_vec_pretty(&snapshot)?).await?;
+
+    let pruned = prune_exports(&settings.export_dir, settings.exports_kept).await?;
+    Ok(format!(
+        "Wrote {} ({} older exports removed)",
+        path.display(),
+        pruned
+    ))
+}
+
+/// Delete all but the newest `kept` scenario exports in `dir`
+async fn prune_exports(dir: &FsPath, kept: usize) -> anyhow::Result<usize> {
+    let mut exports: Vec<PathBuf> = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with("scenario-") && name.ends_with(".json") {
+            exports.push(entry.path());
+        }
+    }
+    // Names sort by time
+    exports.sort();
+    let excess = exports.len().saturating_sub(kept.max(1));
+    for path in &exports[..excess] {
+        tokio::fs::remove_file(path).await?;
+    }
+    Ok(excess)
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+#[derive(Deserialize, ToSchema)]
+pub struct ScheduleInput {
+    pub name: String,
+    /// One of the tasks listed by `GET /api/schedules/tasks`
+    pub task: String,
+    /// Five-field cron expression (UTC) or `@hourly`, `@daily`, `@nightly`,
+    /// `@weekly`, `@monthly`
+    pub cron: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl ScheduleInput {
+    /// The schedule as stored, or why the input is invalid
+    fn into_schedule(self, created_at: String) -> ApiResult<Schedule> {
+        if self.name.trim().is_empty() {
+            return Err(ApiError::bad_request("A schedule needs a name"));
+        }
+        if !TASKS.iter().any(|(task, _)| *task == self.task) {
+            return Err(ApiError::bad_request(format!(
+                "Unknown task '{}'",
+                self.task
+            )));
+        }
+        let next_run_at =
+            next_run(&self.cron, self.enabled, minute()).map_err(ApiError::bad_request)?;
+        Ok(Schedule {
+            id: None,
+            name: self.name.trim().to_string(),
+            task: self.task,
+            cron: self.cron.trim().to_string(),
+            enabled: self.enabled,
+            next_run_at,
+            last_run_at: None,
+            last_status: None,
+            created_at,
+        })
+    }
+}
+
+#[derive(serde::Serialize, ToSchema)]
+pub struct TaskInfo {
+    pub task: &'static str,
+    pub description: &'static str,
+}
+
+/// Every schedule with its next and latest run
+#[utoipa::path(
+    get,
+    path = "/api/schedules",
+    tag = "schedules",
+    responses((status = 200, description = "All schedules by name", body = Vec<Schedule>))
+)]
+pub async fn list(State(state): State<AppState>) -> ApiResult<Json<Vec<Schedule>>> {
+    Ok(Json(ScheduleRepository::get_all(&state.db.client).await?))
+}
+
+/// Tasks a schedule can run
+#[utoipa::path(
+    get,
+    path = "/api/schedules/tasks",
+    tag = "schedules",
+    responses((status = 200, description = "Task names with what each does", body = Vec<TaskInfo>))
+)]
+pub async fn tasks() -> Json<Vec<TaskInfo>> {
+    Json(
+        TASKS
+            .iter()
+            .map(|&(task, description)| TaskInfo { task, description })
+            .collect(),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schedules",
+    tag = "schedules",
+    request_body = ScheduleInput,
+    responses(
+        (status = 201, description = "Schedule created", body = Schedule),
+        (status = 400, description = "Unknown task or invalid cron expression", body = ApiError),
+    )
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Json(input): Json<ScheduleInput>,
+) -> ApiResult<(StatusCode, Json<Schedule>)> {
+    let schedule = input.into_schedule(stamp(minute()))?;
+    Ok((
+        StatusCode::CREATED,
+        Json(ScheduleRepository::create(&state.db.client, schedule).await?),
+    ))
+}
+
+/// Change a schedule; its next run is worked out again from the new expression
+#[utoipa::path(
+    put,
+    path = "/api/schedules/{id}",
+    tag = "schedules",
+    params(("id" = String, Path, description = "Schedule id (`schedule:key` or `key`)")),
+    request_body = ScheduleInput,
+    responses(
+        (status = 200, description = "Schedule updated", body = Schedule),
+        (status = 400, description = "Unknown task or invalid cron expression", body = ApiError),
+        (status = 404, description = "Schedule not found", body = ApiError),
+    )
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<ScheduleInput>,
+) -> ApiResult<Json<Schedule>> {
+    let key = record_key(&id, "schedule");
+    let existing = found(
+        ScheduleRepository::get_by_id(&state.db.client, key).await?,
+        "Schedule",
+        &id,
+    )?;
+    let mut schedule = input.into_schedule(existing.created_at)?;
+    schedule.last_run_at = existing.last_run_at;
+    schedule.last_status = existing.last_status;
+    let updated = ScheduleRepository::update(&state.db.client, key, schedule).await?;
+    Ok(Json(found(updated, "Schedule", &id)?))
+}
+
+/// Delete a schedule and its run history
+#[utoipa::path(
+    delete,
+    path = "/api/schedules/{id}",
+    tag = "schedules",
+    params(("id" = String, Path, description = "Schedule id (`schedule:key` or `key`)")),
+    responses(
+        (status = 204, description = "Schedule deleted"),
+        (status = 404, description = "Schedule not found", body = ApiError),
+    )
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let key = record_key(&id, "schedule");
+    found(
+        ScheduleRepository::get_by_id(&state.db.client, key).await?,
+        "Schedule",
+        &id,
+    )?;
+    ScheduleRepository::delete(&state.db.client, key).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Run a schedule now, whether or not it is enabled or due
+#[utoipa::path(
+    post,
+    path = "/api/schedules/{id}/run",
+    tag = "schedules",
+    params(("id" = String, Path, description = "Schedule id (`schedule:key` or `key`)")),
+    responses(
+        (status = 202, description = "Run queued", body = ScheduleRun),
+        (status = 404, description = "Schedule not found", body = ApiError),
+    )
+)]
+pub async fn run_now(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<(StatusCode, Json<ScheduleRun>)> {
+    let key = record_key(&id, "schedule");
+    let schedule = found(
+        ScheduleRepository::get_by_id(&state.db.client, key).await?,
+        "Schedule",
+        &id,
+    )?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(launch(&state, schedule, true).await?),
+    ))
+}
+
+/// Run history of one schedule, newest first
+#[utoipa::path(
+    get,
+    path = "/api/schedules/{id}/runs",
+    tag = "schedules",
+    params(("id" = String, Path, description = "Schedule id (`schedule:key` or `key`)")),
+    responses(
+        (status = 200, description = "Runs with their outcome", body = Vec<ScheduleRun>),
+        (status = 404, description = "Schedule not found", body = ApiError),
+    )
+)]
+pub async fn runs(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Vec<ScheduleRun>>> {
+    let key = record_key(&id, "schedule");
+    found(
+        ScheduleRepository::get_by_id(&state.db.client, key).await?,
+        "Schedule",
+        &id,
+    )?;
+    Ok(Json(
+        ScheduleRepository::runs(&state.db.client, Some(key)).await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_schedules_are_valid() {
+        let now = parse_minute("2026-03-02 10:07").unwrap();
+        for (_, task, cron, enabled) in DEFAULT_SCHEDULES {
+            assert!(TASKS.iter().any(|(t, _)| *t == task), "unknown task {task}");
+            assert_eq!(next_run(cron, enabled, now).unwrap().is_some(), enabled);
+        }
+        assert_eq!(
+            next_run("@nightly", true, now).unwrap().as_deref(),
+            Some("2026-03-03 02:00 UTC")
+        );
+        assert!(next_run("0 25 * * *", true, now).is_err());
+    }
+
+    #[tokio::test]
+    async fn old_exports_are_pruned() {
+        let dir = std::env::temp_dir().join(format!("rubigo-exports-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        for name in [
+            "scenario-20260301-0200.json",
+            "scenario-20260302-0200.json",
+            "scenario-20260303-0200.json",
+            "notes.txt",
+        ] {
+            tokio::fs::write(dir.join(name), b"{}").await.unwrap();
+        }
+
+        assert_eq!(prune_exports(&dir, 2).await.unwrap(), 1);
+        assert!(!dir.join("scenario-20260301-0200.json").exists());
+        assert!(dir.join("scenario-20260303-0200.json").exists());
+        assert!(dir.join("notes.txt").exists());
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
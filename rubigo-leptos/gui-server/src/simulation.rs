@@ -0,0 +1,422 @@
+//! Simulation runner
+//!
+//! Builds a NeXosim simulation from the components and connections in the
+//! database, runs it, and records the run with its log lines, per-node
+//! metrics and per-step traces (see `operations::comparison`), and the energy
+//! each component draws per step, totalled by site (see `operations::energy`).
+//! Faults can be injected to take components down part way through; those
+//! that ask for it open training incidents (see `crate::incidents`).
+//! Shared by the
+//! `/simulation/start` form handler and the `/api/runs` JSON endpoint.
+//! A finished run is announced to every inbox and to webhooks subscribed
+//! to `simulation.completed`.
+
+use crate::AppState;
+use nexosim_hybrid::config::{ComponentConfig, ConnectionConfig};
+use nexosim_hybrid::database::notifications::{Notification, NotificationKind};
+use nexosim_hybrid::database::simulation::{Fault, SimulationRun};
+
+/// Site that components without a physical location are totalled under
+pub const UNPLACED: &str = "Unplaced";
+
+/// Run a simulation against the current topology with `faults` injected
+/// and persist the run
+pub async fn run(state: &AppState, faults: Vec<Fault>) -> anyhow::Result<SimulationRun> {
+    use nexosim_hybrid::database::components::ComponentRepository;
+    use nexosim_hybrid::database::connections::ConnectionRepository;
+
+    // Fetch components and connections from database
+    let components = ComponentRepository::get_all(&state.db.client)
+        .await
+        .unwrap_or_default();
+    let connections = ConnectionRepository::get_all(&state.db.client)
+        .await
+        .unwrap_or_default();
+    run_topology(state, components, connections, None, faults).await
+}
+
+/// Run a simulation against `components` and `connections` and persist the
+/// run, naming the sandbox they came from when it is not the live topology
+pub async fn run_topology(
+    state: &AppState,
+    components: Vec<ComponentConfig>,
+    connections: Vec<ConnectionConfig>,
+    sandbox: Option<String>,
+    faults: Vec<Fault>,
+) -> anyhow::Result<SimulationRun> {
+    use chrono::Utc;
+    use nexosim::ports::Output;
+    use nexosim_hybrid::database::simulation::{RunNode, RunSite, SimulationRepository};
+    use nexosim_hybrid::model::{Component, RouterModel, SwitchModel};
+    use nexosim_hybrid::simulation::SimulationBuilder;
+    use std::collections::{BTreeMap, HashMap};
+
+    let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    tracing::info!("Simulation started at {}", timestamp);
+
+    let mut logs = vec![format!("[{}] Simulation initialized", timestamp)];
+    if let Some(name) = &sandbox {
+        logs.push(format!(
+            "[{}] Simulating sandbox '{}'",
+            Utc::now().format("%H:%M:%S"),
+            name
+        ));
+    }
+
+    logs.push(format!(
+        "[{}] Loaded {} components and {} connections",
+        Utc::now().format("%H:%M:%S"),
+        components.len(),
+        connections.len()
+    ));
+
+    // Physical placement of components linked to racked devices
+    let locations =
+        nexosim_hybrid::database::device_links::DeviceLinkRepository::component_locations(
+            &state.db.client,
+        )
+        .await
+        .unwrap_or_default();
+
+    // Components reading telemetry from their real device stay out of the run
+    let live =
+        nexosim_hybrid::database::telemetry_sources::TelemetrySourceRepository::live_components(
+            &state.db.client,
+        )
+        .await
+        .unwrap_or_default();
+
+    if components.is_empty() {
+        logs.push(format!(
+            "[{}] No components found - nothing to simulate",
+            Utc::now().format("%H:%M:%S")
+        ));
+        let run = SimulationRun {
+            id: None,
+            started_at: timestamp,
+            status: "completed".to_string(),
+            logs,
+            nodes: Vec::new(),
+            traces: Default::default(),
+            sandbox,
+            sites: Vec::new(),
+            faults: Vec::new(),
+        };
+        return SimulationRepository::create(&state.db.client, run).await;
+    }
+
+    // Build simulation
+    let mut builder = SimulationBuilder::new();
+    let mut component_indices: HashMap<u32, usize> = HashMap::new();
+
+    // Add components to simulation
+    for comp in &components {
+        let id = comp.id;
+        if live.contains(&id) {
+            logs.push(format!(
+                "[{}] Skipped '{}' (id={}): live telemetry",
+                Utc::now().format("%H:%M:%S"),
+                comp.name,
+                id
+            ));
+            continue;
+        }
+        let component = match &comp.component_type {
+            nexosim_hybrid::config::ComponentType::Router => Component::Router(RouterModel {
+                id,
+                output: Output::default(),
+            }),
+            nexosim_hybrid::config::ComponentType::Switch => Component::Switch(SwitchModel {
+                id,
+                output: Output::default(),
+            }),
+            // These types don't have simulation models yet - skip them
+            nexosim_hybrid::config::ComponentType::Firewall
+            | nexosim_hybrid::config::ComponentType::Server
+            | nexosim_hybrid::config::ComponentType::Workstation
+            | nexosim_hybrid::config::ComponentType::AccessPoint
+            | nexosim_hybrid::config::ComponentType::Phone
+            | nexosim_hybrid::config::ComponentType::Printer => continue,
+            nexosim_hybrid::config::ComponentType::PacketGenerator { .. } => continue, // Skip packet generators for now - they need special handling
+            nexosim_hybrid::config::ComponentType::WasmModule { .. } => continue, // Skip wasm modules for now
+        };
+
+        let idx = builder.add_component(component, &comp.name);
+        component_indices.insert(comp.id, idx);
+        let placed = locations
+            .get(&comp.id)
+            .map(|loc| format!(" at {}", loc.path()))
+            .unwrap_or_default();
+        logs.push(format!(
+            "[{}] Added {:?} '{}' (id={}){}",
+            Utc::now().format("%H:%M:%S"),
+            comp.component_type,
+            comp.name,
+            comp.id,
+            placed
+        ));
+    }
+
+    // Establish connections
+    for conn in &connections {
+        if let (Some(&src_idx), Some(&target_idx)) = (
+            component_indices.get(&conn.from),
+            component_indices.get(&conn.to),
+        ) {
+            builder.connect(src_idx, target_idx);
+            logs.push(format!(
+                "[{}] Connected {} -> {}",
+                Utc::now().format("%H:%M:%S"),
+                conn.from,
+                conn.to
+            ));
+        }
+    }
+
+    // Measured on the simulated graph, so skipped components count as absent
+    let simulated: Vec<u32> = component_indices.keys().copied().collect();
+    let links: Vec<(u32, u32)> = connections.iter().map(|c| (c.from, c.to)).collect();
+    let mut metrics = operations::comparison::node_metrics(&simulated, &links);
+
+    // Each component draws power at a load set by its share of the busiest
+    // component's links, since the models carry no traffic to measure yet
+    let link_counts: BTreeMap<u32, f64> = metrics
+        .iter()
+        .map(|(&id, m)| (id, m["out_links"] + m["in_links"]))
+        .collect();
+    let draws: Vec<(u32, f64)> = operations::energy::loads(&link_counts)
+        .into_iter()
+        .filter_map(|(id, load)| {
+            let power = components.iter().find(|c| c.id == id)?.power_profile();
+            Some((
+                id,
+                operations::energy::draw(power.idle_w, power.max_w, load),
+            ))
+        })
+        .collect();
+    let mut meter = operations::energy::Meter::new();
+
+    // Faults only apply to components that are actually simulated
+    let (faults, ignored): (Vec<Fault>, Vec<Fault>) = faults
+        .into_iter()
+        .partition(|f| f.step >= 1 && component_indices.contains_key(&f.component));
+    for fault in &ignored {
+        logs.push(format!(
+            "[{}] Ignored fault on component {}: not simulated or no such step",
+            Utc::now().format("%H:%M:%S"),
+            fault.component
+        ));
+    }
+    let mut step_ms = Vec::new();
+    let mut elapsed_ms = Vec::new();
+
+    logs.push(format!(
+        "[{}] Building simulation...",
+        Utc::now().format("%H:%M:%S")
+    ));
+
+    // Build and run simulation
+    match builder.build() {
+        Ok(mut sim) => {
+            logs.push(format!(
+                "[{}] Simulation engine started",
+                Utc::now().format("%H:%M:%S")
+            ));
+
+            // Run 10 simulation steps
+            let step_count = 10;
+            let engine_started = std::time::Instant::now();
+            for step in 0..step_count {
+                let step_started = std::time::Instant::now();
+                let stepped = sim.step();
+                step_ms.push(step_started.elapsed().as_secs_f64() * 1000.0);
+                elapsed_ms.push(engine_started.elapsed().as_secs_f64() * 1000.0);
+                for fault in faults.iter().filter(|f| f.step == step + 1) {
+                    let name = components
+                        .iter()
+                        .find(|c| c.id == fault.component)
+                        .map_or("", |c| c.name.as_str());
+                    logs.push(format!(
+                        "[{}] Fault injected: '{}' (id={}) failed at step {}",
+                        Utc::now().format("%H:%M:%S"),
+                        name,
+                        fault.component,
+                        fault.step
+                    ));
+                }
+                match stepped {
+                    Ok(()) => {
+                        // Failed components draw nothing
+                        let up = |id: u32| {
+                            !faults
+                                .iter()
+                                .any(|f| f.component == id && f.step <= step + 1)
+                        };
+                        meter.step(draws.iter().copied().filter(|&(id, _)| up(id)));
+                        if step == 0 || step == step_count - 1 {
+                            logs.push(format!(
+                                "[{}] Step {} completed",
+                                Utc::now().format("%H:%M:%S"),
+                                step + 1
+                            ));
+                        }
+                    }
+                    Err(e) => {
+                        logs.push(format!(
+                            "[{}] Step {} error: {:?}",
+                            Utc::now().format("%H:%M:%S"),
+                            step + 1,
+                            e
+                        ));
+                        break;
+                    }
+                }
+            }
+
+            logs.push(format!(
+                "[{}] Simulation completed: {} steps executed",
+                Utc::now().format("%H:%M:%S"),
+                step_count
+            ));
+        }
+        Err(e) => {
+            logs.push(format!(
+                "[{}] Simulation build failed: {:?}",
+                Utc::now().format("%H:%M:%S"),
+                e
+            ));
+        }
+    }
+
+    let nodes: Vec<RunNode> = components
+        .iter()
+        .filter_map(|c| {
+            let mut metrics = metrics.remove(&c.id)?;
+            metrics.insert("energy_wh".to_string(), meter.energy_wh(c.id));
+            metrics.insert("average_w".to_string(), meter.average_w(c.id));
+            if let Some(fault) = faults.iter().find(|f| f.component == c.id) {
+                metrics.insert("failed_at_step".to_string(), fault.step as f64);
+            }
+            Some(RunNode {
+                component: c.id,
+                name: c.name.clone(),
+                metrics,
+            })
+        })
+        .collect();
+    let sites: Vec<RunSite> = meter
+        .by_site(|id| {
+            locations
+                .get(&id)
+                .and_then(|l| l.site.clone())
+                .unwrap_or_else(|| UNPLACED.to_string())
+        })
+        .into_iter()
+        .map(|s| RunSite {
+            site: s.site,
+            components: s.components,
+            energy_wh: s.energy_wh,
+            average_w: s.average_w,
+            peak_w: s.peak_w,
+        })
+        .collect();
+    if meter.steps() > 0 {
+        logs.push(format!(
+            "[{}] Energy: {:.1} Wh over {} steps of {}s",
+            Utc::now().format("%H:%M:%S"),
+            sites.iter().map(|s| s.energy_wh).sum::<f64>(),
+            meter.steps(),
+            operations::energy::STEP_SECONDS
+        ));
+    }
+
+    // Save run record
+    let entries = logs.len();
+    let run = SimulationRun {
+        id: None,
+        started_at: timestamp,
+        status: "completed".to_string(),
+        logs,
+        nodes,
+        traces: [
+            ("step_ms".to_string(), step_ms),
+            ("elapsed_ms".to_string(), elapsed_ms),
+            ("power_w".to_string(), meter.total_w()),
+        ]
+        .into(),
+        sandbox,
+        sites,
+        faults,
+    };
+
+    let run = SimulationRepository::create(&state.db.client, run).await?;
+    if let Err(e) = crate::incidents::open_for_faults(state, &run, &components, &locations).await {
+        tracing::warn!("Could not open incidents for injected faults: {}", e);
+    }
+    state
+        .notifications
+        .notify(
+            Notification::new(
+                NotificationKind::SimulationComplete,
+                None,
+                "Simulation run completed",
+            )
+            .with_body(format!(
+                "{} components, {} log entries",
+                components.len(),
+                entries
+            ))
+            .with_link("/?tab=simulation"),
+        )
+        .await;
+    state
+        .webhooks
+        .emit(
+            operations::webhooks::SIMULATION_COMPLETED,
+            serde_json::json!({
+                "id": run.id.as_ref().map(|t| t.id.to_raw()),
+                "started_at": run.started_at,
+                "status": run.status,
+                "components": components.len(),
+                "log_entries": entries,
+                "sandbox": run.sandbox,
+                "energy_wh": run.energy_wh(),
+            }),
+        )
+        .await;
+    Ok(run)
+}
+
+/// What `run` measured, as `operations::comparison` reads it, labelled by
+/// when it started
+pub fn measurements(run: &SimulationRun) -> operations::comparison::RunMetrics {
+    operations::comparison::RunMetrics {
+        label: run.started_at.clone(),
+        nodes: run
+            .nodes
+            .iter()
+            .map(|n| (n.component, (n.name.clone(), n.metrics.clone())))
+            .collect(),
+        traces: run.traces.clone(),
+    }
+}
+
+/// Line up `runs`, in the order of `keys` (run keys, baseline first)
+///
+/// Keys that match no run are reported rather than skipped, since dropping
+/// one would quietly change which run is the baseline.
+pub fn compare(
+    runs: &[SimulationRun],
+    keys: &[&str],
+) -> Result<operations::comparison::Comparison, String> {
+    let picked = keys
+        .iter()
+        .map(|&key| {
+            runs.iter()
+                .find(|r| r.id.as_ref().is_some_and(|id| id.id.to_raw() == key))
+                .map(measurements)
+                .ok_or_else(|| format!("Run '{key}' not found"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    operations::comparison::compare(&picked)
+}
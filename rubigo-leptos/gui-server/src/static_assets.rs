@@ -0,0 +1,156 @@
+//! Static asset serving and HTTP caching
+//!
+//! `build-assets.sh` writes content-hashed copies of the stylesheets, script
+//! and hydration bundle to `assets/dist/` with `.br`/`.gz` siblings. Those are
+//! served at `/static/` with `immutable` cache headers; pages link them
+//! through [`asset_url`], which falls back to the plain `/assets/` path when
+//! no manifest has been built (dev).
+//!
+//! JSON endpoints and vector tiles with large, rarely changing bodies use
+//! [`json_with_etag`] / [`bytes_with_etag`] so clients can revalidate instead
+//! of downloading them again.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::OnceLock;
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use tower_http::services::ServeDir;
+use tower_http::set_header::SetResponseHeader;
+
+/// Fingerprinted build output
+pub const DIST_DIR: &str = "assets/dist";
+
+/// URL prefix for fingerprinted assets
+pub const DIST_URL: &str = "/static";
+
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const REVALIDATE: &str = "no-cache";
+
+fn manifest() -> &'static HashMap<String, String> {
+    static MANIFEST: OnceLock<HashMap<String, String>> = OnceLock::new();
+    MANIFEST.get_or_init(|| {
+        let path = std::path::Path::new(DIST_DIR).join("manifest.json");
+        match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid asset manifest {:?}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        }
+    })
+}
+
+/// URL for a static asset, preferring its fingerprinted build
+pub fn asset_url(name: &str) -> String {
+    match manifest().get(name) {
+        Some(hashed) => format!("{}/{}", DIST_URL, hashed),
+        None => format!("/assets/{}", name),
+    }
+}
+
+/// Absolute path of the hydration hash file, if the bundle was fingerprinted
+pub fn hydration_hash_file() -> Option<String> {
+    let path = std::path::Path::new(DIST_DIR).join("hash.txt");
+    path.exists()
+        .then(|| std::fs::canonicalize(&path).ok())
+        .flatten()
+        .map(|p| p.to_string_lossy().into_owned())
+}
+
+/// `/static`: fingerprinted files, precompressed and cached forever
+pub fn dist_service() -> SetResponseHeader<ServeDir, HeaderValue> {
+    SetResponseHeader::overriding(
+        ServeDir::new(DIST_DIR)
+            .precompressed_br()
+            .precompressed_gzip(),
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(IMMUTABLE),
+    )
+}
+
+/// `/assets`: unhashed files, revalidated on every use
+pub fn assets_service() -> SetResponseHeader<ServeDir, HeaderValue> {
+    SetResponseHeader::overriding(
+        ServeDir::new("assets"),
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(REVALIDATE),
+    )
+}
+
+/// Strong ETag for a response body
+pub fn etag_for(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// True when `If-None-Match` already names `etag`
+fn matches_etag(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim() == "*" || v.split(',').any(|tag| tag.trim() == etag))
+}
+
+/// JSON response with an ETag; answers 304 when the client copy is current
+pub fn json_with_etag(headers: &HeaderMap, value: &serde_json::Value) -> Response {
+    match serde_json::to_vec(value) {
+        Ok(body) => bytes_with_etag(headers, "application/json", body),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Response body with an ETag; answers 304 when the client copy is current
+pub fn bytes_with_etag(headers: &HeaderMap, content_type: &'static str, body: Vec<u8>) -> Response {
+    let etag = etag_for(&body);
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, REVALIDATE.to_string()),
+    ];
+
+    if matches_etag(headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    (
+        cache_headers,
+        [(header::CONTENT_TYPE, content_type.to_string())],
+        body,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etag_is_stable_and_quoted() {
+        let a = etag_for(b"{\"type\":\"FeatureCollection\"}");
+        assert_eq!(a, etag_for(b"{\"type\":\"FeatureCollection\"}"));
+        assert_ne!(a, etag_for(b"{}"));
+        assert!(a.starts_with('"') && a.ends_with('"'));
+    }
+
+    #[test]
+    fn if_none_match_lists() {
+        let etag = etag_for(b"x");
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"other\", {etag}")).unwrap(),
+        );
+        assert!(matches_etag(&headers, &etag));
+        assert!(!matches_etag(&HeaderMap::new(), &etag));
+    }
+
+    #[test]
+    fn unknown_assets_fall_back_to_plain_path() {
+        assert_eq!(
+            asset_url("not-in-manifest.css"),
+            "/assets/not-in-manifest.css"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Replica sync endpoints
+//!
+//! Desktop (Tauri) clients keep a local copy of the synced tables and trade
+//! changes with this server at `/api/sync/changes`: `GET` hands out journal
+//! entries after the client's cursor, `POST` applies the client's own edits.
+//! The journal and conflict handling live in `db::sync`; conflicts are
+//! settled by `[runtime.sync] conflict_policy`.
+
+use actions::sync::{PullResponse, PushRequest, PushResponse};
+use axum::extract::{Query, State};
+use axum::Json;
+
+use crate::api::{ApiError, ApiResult};
+use crate::AppState;
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct PullQuery {
+    /// Journal cursor returned by the previous pull; 0 for everything
+    #[serde(default)]
+    pub since: i64,
+    /// Pulling replica, whose own pushed changes are left out
+    pub replica: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sync/changes",
+    tag = "sync",
+    params(PullQuery),
+    responses((status = 200, description = "Changes after the cursor, latest per record", body = Object))
+)]
+pub async fn pull(
+    State(state): State<AppState>,
+    Query(query): Query<PullQuery>,
+) -> ApiResult<Json<PullResponse>> {
+    Ok(Json(
+        db::sync::changes_since(&state.db.client, query.since, &query.replica).await?,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sync/changes",
+    tag = "sync",
+    request_body = Object,
+    responses(
+        (status = 200, description = "How many changes were applied and the server's version of those that lost a conflict", body = Object),
+        (status = 400, description = "A change names a table that is not synced", body = ApiError),
+    )
+)]
+pub async fn push(
+    State(state): State<AppState>,
+    Json(request): Json<PushRequest>,
+) -> ApiResult<Json<PushResponse>> {
+    if let Some(change) = request
+        .changes
+        .iter()
+        .find(|c| !db::sync::SYNCED_TABLES.contains(&c.table.as_str()))
+    {
+        return Err(ApiError::bad_request(format!(
+            "Table '{}' is not synced",
+            change.table
+        )));
+    }
+    let replica = request.replica.clone();
+    let response = db::sync::receive(
+        &state.db.client,
+        request,
+        state.settings.sync.conflict_policy,
+    )
+    .await?;
+    if response.applied > 0 {
+        // Pushed records may belong to any kind of data shown on the server's pages
+        nexosim_hybrid::database::versions::bump_all();
+    }
+    tracing::info!(
+        replica = %replica,
+        applied = response.applied,
+        rejected = response.rejected.len(),
+        "Sync push"
+    );
+    Ok(Json(response))
+}
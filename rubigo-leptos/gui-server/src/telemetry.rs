@@ -0,0 +1,407 @@
+//! Live telemetry
+//!
+//! Components take their telemetry from the simulation unless they are
+//! switched to a live source: SNMP or an agent endpoint on the real device
+//! (see [`operations::telemetry`]). The poller reads each live source every
+//! `interval_secs`, keeps the readings and counter rates on its
+//! `telemetry_source` record, and logs the packets seen between polls as
+//! the component's metric, so rollups and the pages built on them work the
+//! same for simulated and live components. Simulation runs leave live
+//! components out.
+//!
+//! Sources are listed at `/api/telemetry/sources` and set per component at
+//! `/api/components/{id}/telemetry`.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use config::TelemetrySettings;
+use nexosim_hybrid::database::components::ComponentRepository;
+use nexosim_hybrid::database::telemetry_sources::{
+    TelemetryMode, TelemetrySource, TelemetrySourceRepository,
+};
+use nexosim_hybrid::database::Database;
+use operations::telemetry::{
+    agent_metrics, counter_rates, decode, encode_get, packets_between, snmp_metrics, snmp_oids,
+    RESPONSE,
+};
+use serde::Deserialize;
+use tokio::net::UdpSocket;
+use utoipa::ToSchema;
+
+use crate::api::{ApiError, ApiResult};
+use crate::AppState;
+
+/// How often sources are checked for being due
+const TICK: Duration = Duration::from_secs(5);
+
+/// Port SNMP agents listen on when the target gives none
+const SNMP_PORT: u16 = 161;
+
+/// Shortest time allowed between polls of one device
+const MIN_INTERVAL_SECS: u64 = 5;
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[derive(Clone)]
+pub struct TelemetryPoller {
+    db: Arc<Database>,
+    client: reqwest::Client,
+    timeout: Duration,
+}
+
+impl TelemetryPoller {
+    pub fn new(db: Arc<Database>, settings: &TelemetrySettings) -> Self {
+        let timeout = Duration::from_secs(settings.timeout_secs.max(1));
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+        Self {
+            db,
+            client,
+            timeout,
+        }
+    }
+
+    /// Poll live sources as they come due, for as long as the server is up
+    pub fn start(&self, settings: &TelemetrySettings) {
+        if !settings.poll {
+            tracing::info!("Telemetry polling disabled; live components keep their last readings");
+            return;
+        }
+        let poller = self.clone();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(TICK);
+            loop {
+                ticks.tick().await;
+                let sources = match TelemetrySourceRepository::get_all(&poller.db.client).await {
+                    Ok(sources) => sources,
+                    Err(e) => {
+                        tracing::warn!("Could not load telemetry sources: {}", e);
+                        continue;
+                    }
+                };
+                let now = now_ms();
+                for source in sources.into_iter().filter(|s| s.due(now)) {
+                    let component = source.component_id;
+                    if let Err(e) = poller.poll(source).await {
+                        tracing::warn!(
+                            "Could not save telemetry of component {}: {}",
+                            component,
+                            e
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    /// Read `source` now and save what came back, or why nothing did
+    pub async fn poll(&self, source: TelemetrySource) -> anyhow::Result<TelemetrySource> {
+        let mut source = source;
+        let polled_at = now_ms();
+        let readings = match source.mode {
+            TelemetryMode::Snmp => self.read_snmp(&source).await,
+            TelemetryMode::Agent => self.read_agent(&source).await,
+            TelemetryMode::Simulated => Err(anyhow::anyhow!(
+                "Component {} is simulated",
+                source.component_id
+            )),
+        };
+
+        match readings {
+            Ok(readings) => {
+                if let Some(previous) = source.polled_at.filter(|_| !source.readings.is_empty()) {
+                    let secs = (polled_at - previous) as f64 / 1000.0;
+                    source.rates = counter_rates(&source.readings, &readings, secs);
+                    let packets = packets_between(&source.readings, &readings);
+                    let nanos = u64::try_from(polled_at).unwrap_or_default() * 1_000_000;
+                    nexosim_hybrid::telemetry::record_metric(
+                        &self.db.client,
+                        source.component_id,
+                        nanos,
+                        packets,
+                    )
+                    .await?;
+                }
+                source.readings = readings;
+                source.error = None;
+            }
+            Err(e) => {
+                tracing::debug!("Polling component {} failed: {}", source.component_id, e);
+                source.error = Some(e.to_string());
+            }
+        }
+        source.polled_at = Some(polled_at);
+        TelemetrySourceRepository::set(&self.db.client, source).await
+    }
+
+    /// GET the interface counters over SNMPv2c
+    async fn read_snmp(&self, source: &TelemetrySource) -> anyhow::Result<BTreeMap<String, f64>> {
+        let target = match source.target.rsplit_once(':') {
+            Some((_, port)) if port.parse::<u16>().is_ok() => source.target.clone(),
+            _ => format!("{}:{}", source.target, SNMP_PORT),
+        };
+        let oids = snmp_oids(source.if_index);
+        let names: Vec<String> = oids.iter().map(|(_, oid)| oid.clone()).collect();
+        let request_id = i64::from(uuid::Uuid::new_v4().as_u128() as u32 >> 1);
+        let request =
+            encode_get(&source.community, request_id, &names).map_err(anyhow::Error::msg)?;
+
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(&target).await?;
+        socket.send(&request).await?;
+        let mut buf = vec![0u8; 65_535];
+        let len = tokio::time::timeout(self.timeout, socket.recv(&mut buf))
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!("No answer from {} within {:?}", target, self.timeout)
+            })??;
+
+        let response = decode(&buf[..len]).map_err(anyhow::Error::msg)?;
+        if response.pdu != RESPONSE || response.request_id != request_id {
+            anyhow::bail!("Unexpected SNMP message from {}", target);
+        }
+        if response.error_status != 0 {
+            anyhow::bail!(
+                "SNMP error status {} from {}",
+                response.error_status,
+                target
+            );
+        }
+        let metrics = snmp_metrics(&oids, &response);
+        if metrics.is_empty() {
+            anyhow::bail!(
+                "{} has none of the counters of interface {}",
+                target,
+                source.if_index
+            );
+        }
+        Ok(metrics)
+    }
+
+    /// GET the agent endpoint's JSON metrics
+    async fn read_agent(&self, source: &TelemetrySource) -> anyhow::Result<BTreeMap<String, f64>> {
+        let body: serde_json::Value = self
+            .client
+            .get(&source.target)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let metrics = agent_metrics(&body);
+        if metrics.is_empty() {
+            anyhow::bail!("Agent answered without any numeric metrics");
+        }
+        Ok(metrics)
+    }
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+fn default_community() -> String {
+    "public".to_string()
+}
+
+fn default_if_index() -> u32 {
+    1
+}
+
+fn default_interval() -> u64 {
+    60
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TelemetrySourceInput {
+    pub mode: TelemetryMode,
+    /// `host[:port]` for SNMP, the endpoint URL for an agent
+    #[serde(default)]
+    pub target: String,
+    #[serde(default = "default_community")]
+    pub community: String,
+    #[serde(default = "default_if_index")]
+    pub if_index: u32,
+    #[serde(default = "default_interval")]
+    pub interval_secs: u64,
+}
+
+/// Check `input` and apply it to the component's current source, keeping
+/// its readings while the device stays the same
+pub async fn set_source(
+    state: &AppState,
+    component_id: u32,
+    input: TelemetrySourceInput,
+) -> ApiResult<TelemetrySource> {
+    let db = &state.db.client;
+    if !ComponentRepository::get_all(db)
+        .await?
+        .iter()
+        .any(|c| c.id == component_id)
+    {
+        return Err(ApiError::not_found(format!("No component {component_id}")));
+    }
+    let target = input.target.trim().to_string();
+    match input.mode {
+        TelemetryMode::Simulated => {}
+        _ if target.is_empty() => return Err(ApiError::bad_request("Live sources need a target")),
+        TelemetryMode::Agent
+            if !(target.starts_with("http://") || target.starts_with("https://")) =>
+        {
+            return Err(ApiError::bad_request(
+                "Agent targets are http:// or https:// URLs",
+            ))
+        }
+        _ => {}
+    }
+    if input.interval_secs < MIN_INTERVAL_SECS {
+        return Err(ApiError::bad_request(format!(
+            "Poll at most every {MIN_INTERVAL_SECS} seconds"
+        )));
+    }
+
+    let current = TelemetrySourceRepository::get(db, component_id).await?;
+    let same_device = current.mode == input.mode
+        && current.target == target
+        && current.if_index == input.if_index;
+    let mut source = if same_device {
+        current
+    } else {
+        TelemetrySource::simulated(component_id)
+    };
+    source.mode = input.mode;
+    source.target = target;
+    source.community = input.community;
+    source.if_index = input.if_index;
+    source.interval_secs = input.interval_secs;
+    Ok(TelemetrySourceRepository::set(db, source).await?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/telemetry/sources",
+    tag = "telemetry",
+    responses(
+        (status = 200, description = "Components with a telemetry source set; others are simulated", body = Vec<TelemetrySource>),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn list(State(state): State<AppState>) -> ApiResult<Json<Vec<TelemetrySource>>> {
+    Ok(Json(
+        TelemetrySourceRepository::get_all(&state.db.client).await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/components/{id}/telemetry",
+    tag = "telemetry",
+    params(("id" = u32, Path, description = "Component id")),
+    responses(
+        (status = 200, description = "Where the component's telemetry comes from, with the latest readings", body = TelemetrySource),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn get(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> ApiResult<Json<TelemetrySource>> {
+    Ok(Json(
+        TelemetrySourceRepository::get(&state.db.client, id).await?,
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/components/{id}/telemetry",
+    tag = "telemetry",
+    params(("id" = u32, Path, description = "Component id")),
+    request_body = TelemetrySourceInput,
+    responses(
+        (status = 200, description = "Source set", body = TelemetrySource),
+        (status = 400, description = "Missing or invalid target, or too short an interval", body = ApiError),
+        (status = 404, description = "Component not found", body = ApiError),
+    )
+)]
+pub async fn put(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Json(input): Json<TelemetrySourceInput>,
+) -> ApiResult<Json<TelemetrySource>> {
+    Ok(Json(set_source(&state, id, input).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/components/{id}/telemetry/poll",
+    tag = "telemetry",
+    params(("id" = u32, Path, description = "Component id")),
+    responses(
+        (status = 200, description = "Polled; a failed poll is reported in `error`", body = TelemetrySource),
+        (status = 400, description = "The component is simulated", body = ApiError),
+    )
+)]
+pub async fn poll_now(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> ApiResult<Json<TelemetrySource>> {
+    let source = TelemetrySourceRepository::get(&state.db.client, id).await?;
+    if !source.mode.is_live() {
+        return Err(ApiError::bad_request(format!(
+            "Component {id} is simulated"
+        )));
+    }
+    Ok(Json(state.telemetry.poll(source).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn snmp_polls_a_udp_agent() {
+        let agent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = agent.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 1500];
+            let (len, from) = agent.recv_from(&mut buf).await.unwrap();
+            let request = decode(&buf[..len]).unwrap();
+            // Echo the request back as the response
+            let oids: Vec<String> = request
+                .varbinds
+                .iter()
+                .map(|(oid, _)| oid.clone())
+                .collect();
+            let mut answer = encode_get(&request.community, request.request_id, &oids).unwrap();
+            let pdu = answer.iter().position(|b| *b == 0xA0).unwrap();
+            answer[pdu] = RESPONSE;
+            agent.send_to(&answer, from).await.unwrap();
+        });
+
+        let db = Arc::new(Database::init().await.unwrap());
+        let poller = TelemetryPoller::new(
+            db,
+            &TelemetrySettings {
+                poll: false,
+                timeout_secs: 2,
+            },
+        );
+        let mut source = TelemetrySource::simulated(1);
+        source.mode = TelemetryMode::Snmp;
+        source.target = target;
+        // The echoed request has NULL values, which are no numbers
+        let error = poller.read_snmp(&source).await.unwrap_err();
+        assert!(
+            error.to_string().contains("none of the counters"),
+            "{error}"
+        );
+    }
+}
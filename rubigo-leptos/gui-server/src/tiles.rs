@@ -0,0 +1,535 @@
+//! Mapbox Vector Tiles for the imported geo data
+//!
+//! `/api/tiles/{z}/{x}/{y}` serves MVT v2 tiles (Web Mercator, XYZ scheme)
+//! with two layers:
+//! - `boundaries`: country/state polygons from `geo_feature`
+//! - `sites`: site locations as points
+//!
+//! Geometry is simplified for the zoom level, clipped to the tile plus a
+//! small buffer and encoded with prost against the vector tile schema in
+//! [`crate::vector_tile`], so standard web map clients can consume it
+//! without a tile server.
+
+use std::f64::consts::PI;
+
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use nexosim_hybrid::database::geo::{self, GeoFeature, Site};
+use prost::Message;
+use serde_json::Value;
+
+use crate::api::ApiError;
+use crate::components::geospatial::simplify::{geometry_bbox, simplify_geometry, BBox};
+use crate::vector_tile::{self as mvt, GeomType};
+use crate::AppState;
+
+/// Tile coordinate space (MVT default)
+pub const EXTENT: u32 = 4096;
+
+/// Extra tile units kept around each edge so strokes don't seam
+const BUFFER: f64 = 64.0;
+
+/// Highest zoom served
+pub const MAX_ZOOM: u8 = 22;
+
+/// Web Mercator latitude limit
+const MAX_LAT: f64 = 85.051_128_779_806_59;
+
+pub const CONTENT_TYPE: &str = "application/vnd.mapbox-vector-tile";
+
+// ============================================================================
+// Tile math
+// ============================================================================
+
+/// XYZ tile address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileId {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileId {
+    /// Validate a tile address; `x` and `y` must be within `2^z`
+    pub fn new(z: u8, x: u32, y: u32) -> Option<Self> {
+        if z > MAX_ZOOM {
+            return None;
+        }
+        let n = 1u32 << z;
+        (x < n && y < n).then_some(Self { z, x, y })
+    }
+
+    fn scale(&self) -> f64 {
+        (1u64 << self.z) as f64
+    }
+
+    /// Geographic bounds of the tile
+    pub fn bbox(&self) -> BBox {
+        let n = self.scale();
+        let lon = |x: f64| x / n * 360.0 - 180.0;
+        let lat = |y: f64| (PI * (1.0 - 2.0 * y / n)).sinh().atan().to_degrees();
+        BBox {
+            min_lon: lon(self.x as f64),
+            min_lat: lat(self.y as f64 + 1.0),
+            max_lon: lon(self.x as f64 + 1.0),
+            max_lat: lat(self.y as f64),
+        }
+    }
+
+    /// Project `[lon, lat]` into this tile's coordinate space
+    pub fn project(&self, [lon, lat]: [f64; 2]) -> [f64; 2] {
+        let n = self.scale();
+        let lat = lat.clamp(-MAX_LAT, MAX_LAT).to_radians();
+        let wx = (lon + 180.0) / 360.0 * n;
+        let wy = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n;
+        [
+            (wx - self.x as f64) * EXTENT as f64,
+            (wy - self.y as f64) * EXTENT as f64,
+        ]
+    }
+
+    /// Simplification tolerance: roughly one pixel of a 256px tile
+    fn tolerance(&self) -> f64 {
+        360.0 / self.scale() / 256.0
+    }
+}
+
+// ============================================================================
+// Geometry
+// ============================================================================
+
+/// Sutherland-Hodgman clip of a ring against the buffered tile square
+fn clip_ring(ring: &[[f64; 2]]) -> Vec<[f64; 2]> {
+    let (lo, hi) = (-BUFFER, EXTENT as f64 + BUFFER);
+    // (axis, bound, keep values >= bound)
+    let edges = [(0, lo, true), (0, hi, false), (1, lo, true), (1, hi, false)];
+
+    let mut points = ring.to_vec();
+    for (axis, bound, keep_above) in edges {
+        if points.is_empty() {
+            break;
+        }
+        let inside = |p: &[f64; 2]| {
+            if keep_above {
+                p[axis] >= bound
+            } else {
+                p[axis] <= bound
+            }
+        };
+        let mut out = Vec::with_capacity(points.len());
+        for i in 0..points.len() {
+            let cur = points[i];
+            let prev = points[(i + points.len() - 1) % points.len()];
+            if inside(&cur) != inside(&prev) {
+                let t = (bound - prev[axis]) / (cur[axis] - prev[axis]);
+                let mut p = [
+                    prev[0] + t * (cur[0] - prev[0]),
+                    prev[1] + t * (cur[1] - prev[1]),
+                ];
+                p[axis] = bound;
+                out.push(p);
+            }
+            if inside(&cur) {
+                out.push(cur);
+            }
+        }
+        points = out;
+    }
+    points
+}
+
+/// Twice the signed area; positive is clockwise in tile space (y down)
+fn signed_area(ring: &[[i32; 2]]) -> i64 {
+    (0..ring.len())
+        .map(|i| {
+            let [x1, y1] = ring[i];
+            let [x2, y2] = ring[(i + 1) % ring.len()];
+            x1 as i64 * y2 as i64 - x2 as i64 * y1 as i64
+        })
+        .sum()
+}
+
+/// Project, clip and round one GeoJSON ring to an open integer ring
+fn tile_ring(tile: &TileId, ring: &Value, exterior: bool) -> Option<Vec<[i32; 2]>> {
+    let mut projected: Vec<[f64; 2]> = ring
+        .as_array()?
+        .iter()
+        .filter_map(|p| {
+            let p = p.as_array()?;
+            Some(tile.project([p.first()?.as_f64()?, p.get(1)?.as_f64()?]))
+        })
+        .collect();
+    if projected.len() > 1 && projected.first() == projected.last() {
+        projected.pop();
+    }
+
+    let mut points: Vec<[i32; 2]> = Vec::new();
+    for [x, y] in clip_ring(&projected) {
+        let p = [x.round() as i32, y.round() as i32];
+        if points.last() != Some(&p) {
+            points.push(p);
+        }
+    }
+    while points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        return None;
+    }
+
+    // MVT v2: exterior rings clockwise, interior rings counter-clockwise
+    let area = signed_area(&points);
+    if area == 0 {
+        return None;
+    }
+    if (area > 0) != exterior {
+        points.reverse();
+    }
+    Some(points)
+}
+
+/// Rings of a Polygon / MultiPolygon, flattened with their exterior flag
+fn polygon_rings(geometry: &Value) -> Vec<(&Value, bool)> {
+    let coords = geometry.get("coordinates");
+    let polygons: Vec<&Value> = match geometry.get("type").and_then(Value::as_str) {
+        Some("Polygon") => coords.into_iter().collect(),
+        Some("MultiPolygon") => coords
+            .and_then(Value::as_array)
+            .map(|p| p.iter().collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    polygons
+        .into_iter()
+        .filter_map(Value::as_array)
+        .flat_map(|rings| rings.iter().enumerate().map(|(i, r)| (r, i == 0)))
+        .collect()
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+const CMD_MOVE_TO: u32 = 1;
+const CMD_LINE_TO: u32 = 2;
+const CMD_CLOSE_PATH: u32 = 7;
+
+fn command(id: u32, count: u32) -> u32 {
+    (id & 0x7) | (count << 3)
+}
+
+fn zigzag(n: i32) -> u32 {
+    ((n << 1) ^ (n >> 31)) as u32
+}
+
+/// Command stream for a polygon's rings
+fn encode_polygon(rings: &[Vec<[i32; 2]>]) -> Vec<u32> {
+    let mut out = Vec::new();
+    let mut cursor = [0, 0];
+    let mut push_point = |out: &mut Vec<u32>, p: [i32; 2]| {
+        out.push(zigzag(p[0] - cursor[0]));
+        out.push(zigzag(p[1] - cursor[1]));
+        cursor = p;
+    };
+    for ring in rings {
+        out.push(command(CMD_MOVE_TO, 1));
+        push_point(&mut out, ring[0]);
+        out.push(command(CMD_LINE_TO, ring.len() as u32 - 1));
+        for &p in &ring[1..] {
+            push_point(&mut out, p);
+        }
+        out.push(command(CMD_CLOSE_PATH, 1));
+    }
+    out
+}
+
+/// One layer being assembled: features plus the shared key/value tables
+struct LayerBuilder {
+    name: &'static str,
+    keys: Vec<String>,
+    values: Vec<String>,
+    features: Vec<mvt::Feature>,
+}
+
+impl LayerBuilder {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            keys: Vec::new(),
+            values: Vec::new(),
+            features: Vec::new(),
+        }
+    }
+
+    fn index_of(table: &mut Vec<String>, s: &str) -> u32 {
+        match table.iter().position(|k| k == s) {
+            Some(i) => i as u32,
+            None => {
+                table.push(s.to_string());
+                table.len() as u32 - 1
+            }
+        }
+    }
+
+    fn add(&mut self, geom_type: GeomType, geometry: Vec<u32>, properties: &[(&str, &str)]) {
+        let mut tags = Vec::with_capacity(properties.len() * 2);
+        for (key, value) in properties {
+            tags.push(Self::index_of(&mut self.keys, key));
+            tags.push(Self::index_of(&mut self.values, value));
+        }
+
+        let mut feature = mvt::Feature {
+            id: Some(self.features.len() as u64 + 1),
+            tags,
+            geometry,
+            ..Default::default()
+        };
+        feature.set_type(geom_type);
+        self.features.push(feature);
+    }
+
+    fn build(self) -> Option<mvt::Layer> {
+        if self.features.is_empty() {
+            return None;
+        }
+        Some(mvt::Layer {
+            version: 2,
+            name: self.name.to_string(),
+            features: self.features,
+            keys: self.keys,
+            values: self
+                .values
+                .into_iter()
+                .map(|value| mvt::Value {
+                    string_value: Some(value),
+                    ..Default::default()
+                })
+                .collect(),
+            extent: Some(EXTENT),
+        })
+    }
+}
+
+/// Encode one tile from boundary features and sites
+pub fn encode_tile(tile: &TileId, features: &[GeoFeature], sites: &[Site]) -> Vec<u8> {
+    let bounds = tile.bbox();
+    let tolerance = tile.tolerance();
+
+    let mut boundaries = LayerBuilder::new("boundaries");
+    for feature in features {
+        if !geometry_bbox(&feature.geometry).is_some_and(|b| bounds.intersects(&b)) {
+            continue;
+        }
+        let simplified = simplify_geometry(&feature.geometry, tolerance);
+        let mut rings = Vec::new();
+        let mut exterior_kept = false;
+        for (ring, exterior) in polygon_rings(&simplified) {
+            // Holes only belong to a polygon whose exterior survived clipping
+            if !exterior && !exterior_kept {
+                continue;
+            }
+            let kept = tile_ring(tile, ring, exterior);
+            if exterior {
+                exterior_kept = kept.is_some();
+            }
+            rings.extend(kept);
+        }
+        if rings.is_empty() {
+            continue;
+        }
+        boundaries.add(
+            GeomType::Polygon,
+            encode_polygon(&rings),
+            &[
+                ("name", &feature.name),
+                ("feature_type", &feature.feature_type),
+                ("iso_code", feature.iso_code.as_deref().unwrap_or("")),
+            ],
+        );
+    }
+
+    let mut site_layer = LayerBuilder::new("sites");
+    let (lo, hi) = (-BUFFER, EXTENT as f64 + BUFFER);
+    for site in sites {
+        let [x, y] = tile.project([site.location.0, site.location.1]);
+        if !(lo..=hi).contains(&x) || !(lo..=hi).contains(&y) {
+            continue;
+        }
+        let geometry = vec![
+            command(CMD_MOVE_TO, 1),
+            zigzag(x.round() as i32),
+            zigzag(y.round() as i32),
+        ];
+        site_layer.add(
+            GeomType::Point,
+            geometry,
+            &[("name", &site.name), ("status", &site.status)],
+        );
+    }
+
+    mvt::Tile {
+        layers: [boundaries.build(), site_layer.build()]
+            .into_iter()
+            .flatten()
+            .collect(),
+    }
+    .encode_to_vec()
+}
+
+// ============================================================================
+// Handler
+// ============================================================================
+
+/// Get a Mapbox Vector Tile of boundaries and sites
+#[utoipa::path(
+    get,
+    path = "/api/tiles/{z}/{x}/{y}",
+    tag = "geo",
+    params(
+        ("z" = u8, Path, description = "Zoom level (0-22)"),
+        ("x" = u32, Path, description = "Tile column"),
+        ("y" = String, Path, description = "Tile row; an `.mvt` or `.pbf` suffix is accepted"),
+    ),
+    responses(
+        (status = 200, description = "MVT v2 tile (`application/vnd.mapbox-vector-tile`) with `boundaries` and `sites` layers"),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 400, description = "Tile address out of range", body = ApiError),
+    )
+)]
+pub async fn get_tile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((z, x, y)): Path<(u8, u32, String)>,
+) -> Response {
+    let y = y
+        .trim_end_matches(".mvt")
+        .trim_end_matches(".pbf")
+        .parse::<u32>()
+        .ok();
+    let Some(tile) = y.and_then(|y| TileId::new(z, x, y)) else {
+        return ApiError::bad_request(format!("invalid tile {}/{}", z, x)).into_response();
+    };
+
+    let features = match geo::GeoRepository::list_geo_features(&state.db.client, None).await {
+        Ok(features) => features,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let sites = geo::GeoRepository::list_sites(&state.db.client)
+        .await
+        .unwrap_or_default();
+
+    let body = encode_tile(&tile, &features, &sites);
+    crate::static_assets::bytes_with_etag(&headers, CONTENT_TYPE, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_bounds_and_projection() {
+        assert!(TileId::new(1, 2, 0).is_none());
+        assert!(TileId::new(MAX_ZOOM + 1, 0, 0).is_none());
+
+        let world = TileId::new(0, 0, 0).unwrap();
+        let b = world.bbox();
+        assert_eq!((b.min_lon, b.max_lon), (-180.0, 180.0));
+        assert!((b.max_lat - MAX_LAT).abs() < 1e-9);
+
+        let [x, y] = world.project([0.0, 0.0]);
+        assert!((x - 2048.0).abs() < 1e-9 && (y - 2048.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zigzag_and_commands() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        assert_eq!(zigzag(-2), 3);
+        assert_eq!(command(CMD_MOVE_TO, 1), 9);
+        assert_eq!(command(CMD_CLOSE_PATH, 1), 15);
+    }
+
+    #[test]
+    fn polygon_rings_are_clipped_and_wound_clockwise() {
+        let tile = TileId::new(0, 0, 0).unwrap();
+        // Counter-clockwise in lon/lat (GeoJSON convention)
+        let ring = serde_json::json!([
+            [-10.0, -10.0],
+            [10.0, -10.0],
+            [10.0, 10.0],
+            [-10.0, 10.0],
+            [-10.0, -10.0]
+        ]);
+        let points = tile_ring(&tile, &ring, true).unwrap();
+        assert_eq!(points.len(), 4);
+        assert!(signed_area(&points) > 0);
+
+        let hole = tile_ring(&tile, &ring, false).unwrap();
+        assert!(signed_area(&hole) < 0);
+
+        let clipped = clip_ring(&[
+            [-500.0, 100.0],
+            [100.0, 100.0],
+            [100.0, 200.0],
+            [-500.0, 200.0],
+        ]);
+        assert!(clipped.iter().all(|p| p[0] >= -BUFFER));
+    }
+
+    #[test]
+    fn tiles_decode_as_vector_tiles() {
+        let feature = GeoFeature {
+            id: None,
+            name: "Square".to_string(),
+            feature_type: "country".to_string(),
+            iso_code: Some("SQ".to_string()),
+            geometry: serde_json::json!({
+                "type": "Polygon",
+                "coordinates": [[[-10.0, -10.0], [10.0, -10.0], [10.0, 10.0], [-10.0, 10.0], [-10.0, -10.0]]],
+            }),
+        };
+        let site = Site {
+            id: None,
+            name: "HQ".to_string(),
+            region_id: None,
+            location: (0.0, 0.0),
+            status: "active".to_string(),
+        };
+        let tile = TileId::new(0, 0, 0).unwrap();
+        let bytes = encode_tile(&tile, &[feature], &[site]);
+
+        let decoded = mvt::Tile::decode(bytes.as_slice()).unwrap();
+        let names: Vec<&str> = decoded.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["boundaries", "sites"]);
+
+        let boundaries = &decoded.layers[0];
+        assert_eq!((boundaries.version, boundaries.extent), (2, Some(EXTENT)));
+        assert_eq!(boundaries.keys, ["name", "feature_type", "iso_code"]);
+        let polygon = &boundaries.features[0];
+        assert_eq!(polygon.r#type(), GeomType::Polygon);
+        assert_eq!(polygon.tags, [0, 0, 1, 1, 2, 2]);
+        assert_eq!(
+            boundaries.values[polygon.tags[1] as usize]
+                .string_value
+                .as_deref(),
+            Some("Square")
+        );
+        assert_eq!(polygon.geometry[0], command(CMD_MOVE_TO, 1));
+        assert_eq!(polygon.geometry.last(), Some(&command(CMD_CLOSE_PATH, 1)));
+
+        let point = &decoded.layers[1].features[0];
+        assert_eq!(point.r#type(), GeomType::Point);
+        assert_eq!(point.geometry, [9, zigzag(2048), zigzag(2048)]);
+    }
+
+    #[test]
+    fn encodes_polygon_commands() {
+        let square = vec![vec![[0, 0], [10, 0], [10, 10], [0, 10]]];
+        assert_eq!(
+            encode_polygon(&square),
+            vec![9, 0, 0, 26, 20, 0, 0, 20, 19, 0, 15]
+        );
+    }
+}
@@ -0,0 +1,600 @@
+//! Outbound webhooks
+//!
+//! Admins register a URL with the events it wants (see [`operations::webhooks`]
+//! for the names and filters). Each matching event is POSTed to it as a
+//! JSON [`WebhookPayload`] with these headers:
+//!
+//! - `X-Rubigo-Event`: the event name
+//! - `X-Rubigo-Delivery`: the delivery ID, also the payload's `id`
+//! - `X-Rubigo-Signature`: `t=<unix seconds>,v1=<hex HMAC-SHA256>`, the
+//!   HMAC keyed with the webhook's secret over the timestamp, a `.` and the
+//!   body
+//!
+//! Receivers should check the HMAC and then reject deliveries whose `t` is
+//! more than [`SIGNATURE_TOLERANCE_SECS`] away from their own clock, so a
+//! captured delivery can't be replayed later. Retries are signed afresh.
+//!
+//! Entity change events come from the sync journal, which already records
+//! every write to the synced tables; simulation runs announce themselves.
+//! A delivery that isn't answered with a 2xx is retried with growing delays
+//! until [`operations::webhooks::MAX_DELIVERY_ATTEMPTS`], and every delivery is
+//! logged with its outcome for the Webhooks page and
+//! `/api/webhooks/{id}/deliveries`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use hmac::{Hmac, Mac};
+use nexosim_hybrid::database::webhooks::{
+    DeliveryStatus, Webhook, WebhookDelivery, WebhookRepository,
+};
+use nexosim_hybrid::database::Database;
+use operations::webhooks::{
+    change_event, event_matches, known_events, retry_delay_secs, validate_webhook, WebhookPayload,
+    TEST_EVENT,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use utoipa::ToSchema;
+
+use crate::api::{found, record_key, ApiError, ApiResult};
+use crate::clock::now;
+use crate::AppState;
+
+/// How often the sync journal is read for entity changes
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Time an endpoint has to answer a delivery
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Replica name the change watcher reads the journal as
+const REPLICA: &str = "webhooks";
+
+/// Shown in place of secrets after a webhook is created
+const REDACTED: &str = "********";
+
+/// How far a delivery's signed timestamp may be from the receiver's clock
+pub const SIGNATURE_TOLERANCE_SECS: i64 = 5 * 60;
+
+/// `t=<timestamp>,v1=` and the hex HMAC-SHA256 of `<timestamp>.<body>`
+/// keyed with `secret`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+#[derive(Clone)]
+pub struct WebhookHub {
+    db: Arc<Database>,
+    client: reqwest::Client,
+}
+
+impl WebhookHub {
+    pub fn new(db: Arc<Database>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { db, client }
+    }
+
+    /// Resume deliveries cut short by a restart, then raise change events
+    /// for as long as the server is up
+    pub fn start(&self) {
+        let hub = self.clone();
+        tokio::spawn(async move {
+            match WebhookRepository::deliveries(&hub.db.client, None).await {
+                Ok(deliveries) => {
+                    for delivery in deliveries
+                        .into_iter()
+                        .filter(|d| d.status == DeliveryStatus::Pending)
+                    {
+                        hub.resume(delivery).await;
+                    }
+                }
+                Err(e) => tracing::warn!("Could not read pending webhook deliveries: {}", e),
+            }
+            hub.watch_changes().await;
+        });
+    }
+
+    async fn resume(&self, delivery: WebhookDelivery) {
+        match WebhookRepository::get_by_id(&self.db.client, &delivery.webhook).await {
+            Ok(Some(webhook)) => self.spawn_delivery(webhook, delivery),
+            _ => tracing::warn!("Dropping delivery {} to a missing webhook", delivery.key()),
+        }
+    }
+
+    /// Turn sync journal entries into `kind.changed` / `kind.deleted` events
+    async fn watch_changes(&self) {
+        // Only what happens from now on
+        let mut cursor = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as i64);
+        let mut ticks = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            ticks.tick().await;
+            let pulled = match db::sync::changes_since(&self.db.client, cursor, REPLICA).await {
+                Ok(pulled) => pulled,
+                Err(e) => {
+                    tracing::warn!("Could not read changes for webhooks: {}", e);
+                    continue;
+                }
+            };
+            cursor = pulled.cursor;
+            for change in pulled.changes {
+                let event = change_event(&change.table, change.data.is_none());
+                let data = json!({ "table": change.table, "id": change.id, "record": change.data });
+                self.emit(&event, data).await;
+            }
+        }
+    }
+
+    /// Deliver `event` to every enabled webhook that subscribed to it
+    ///
+    /// Failures are logged; a webhook is never worth failing the action
+    /// that raised the event.
+    pub async fn emit(&self, event: &str, data: Value) {
+        let webhooks = match WebhookRepository::get_all(&self.db.client).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::warn!("Could not load webhooks for {}: {}", event, e);
+                return;
+            }
+        };
+        for webhook in webhooks
+            .into_iter()
+            .filter(|w| w.enabled && event_matches(&w.events, event))
+        {
+            if let Err(e) = self.send(webhook, event, data.clone()).await {
+                tracing::warn!("Could not queue {} webhook delivery: {}", event, e);
+            }
+        }
+    }
+
+    /// Log a delivery of `event` to `webhook` and start sending it
+    async fn send(
+        &self,
+        webhook: Webhook,
+        event: &str,
+        data: Value,
+    ) -> anyhow::Result<WebhookDelivery> {
+        let key = uuid::Uuid::new_v4().simple().to_string();
+        let payload = WebhookPayload {
+            id: key.clone(),
+            event: event.to_string(),
+            occurred_at: chrono::Utc::now().to_rfc3339(),
+            data,
+        };
+        let delivery = WebhookDelivery {
+            id: None,
+            webhook: webhook.key(),
+            event: event.to_string(),
+            payload: serde_json::to_string(&payload)?,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            response_code: None,
+            error: None,
+            created_at: now(),
+            updated_at: now(),
+        };
+        let delivery = WebhookRepository::create_delivery(&self.db.client, &key, delivery).await?;
+        self.spawn_delivery(webhook, delivery.clone());
+        Ok(delivery)
+    }
+
+    fn spawn_delivery(&self, webhook: Webhook, delivery: WebhookDelivery) {
+        let hub = self.clone();
+        tokio::spawn(async move { hub.deliver(webhook, delivery).await });
+    }
+
+    /// Make attempts until one is accepted or they run out, logging each
+    async fn deliver(&self, webhook: Webhook, mut delivery: WebhookDelivery) {
+        let key = delivery.key();
+        loop {
+            delivery.attempts += 1;
+            let retry = match self.attempt(&webhook, &delivery).await {
+                Ok(code) => {
+                    delivery.status = DeliveryStatus::Delivered;
+                    delivery.response_code = Some(code);
+                    delivery.error = None;
+                    None
+                }
+                Err((code, error)) => {
+                    let retry = retry_delay_secs(delivery.attempts);
+                    delivery.status = if retry.is_some() {
+                        DeliveryStatus::Pending
+                    } else {
+                        DeliveryStatus::Failed
+                    };
+                    delivery.response_code = code;
+                    delivery.error = Some(error);
+                    retry
+                }
+            };
+            delivery.updated_at = now();
+            if let Err(e) =
+                WebhookRepository::update_delivery(&self.db.client, &key, delivery.clone()).await
+            {
+                tracing::warn!("Could not log webhook delivery {}: {}", key, e);
+            }
+            match retry {
+                Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+                None => break,
+            }
+        }
+        if delivery.status == DeliveryStatus::Failed {
+            tracing::warn!(
+                "Gave up delivering {} to webhook {} after {} attempts",
+                delivery.event,
+                webhook.name,
+                delivery.attempts
+            );
+        }
+    }
+
+    /// POST the delivery once; the status code on success, or what went wrong
+    async fn attempt(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, (Option<u16>, String)> {
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "Rubigo-Webhooks")
+            .header("X-Rubigo-Event", &delivery.event)
+            .header("X-Rubigo-Delivery", delivery.key())
+            .header(
+                "X-Rubigo-Signature",
+                sign(
+                    &webhook.secret,
+                    chrono::Utc::now().timestamp(),
+                    delivery.payload.as_bytes(),
+                ),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err((
+                Some(status.as_u16()),
+                format!("Endpoint answered {}", status),
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// REST
+// ============================================================================
+
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookInput {
+    pub name: String,
+    /// http(s) URL deliveries are POSTed to
+    pub url: String,
+    /// Event names, `kind.*` or `*`; see `GET /api/webhooks/events`
+    pub events: Vec<String>,
+    /// Signing key; generated when a webhook is created without one, kept
+    /// when an update leaves it out
+    pub secret: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl WebhookInput {
+    /// The webhook as stored, or why the input is invalid
+    fn into_webhook(self, secret: String, created_at: String) -> ApiResult<Webhook> {
+        if self.name.trim().is_empty() {
+            return Err(ApiError::bad_request("A webhook needs a name"));
+        }
+        let events: Vec<String> = self
+            .events
+            .iter()
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+            .collect();
+        if let Some(problem) = validate_webhook(&self.url, &events) {
+            return Err(ApiError::bad_request(problem));
+        }
+        let secret = self
+            .secret
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(secret);
+        Ok(Webhook {
+            id: None,
+            name: self.name.trim().to_string(),
+            url: self.url.trim().to_string(),
+            secret,
+            events,
+            enabled: self.enabled,
+            created_at,
+        })
+    }
+}
+
+/// Create a webhook from the admin form or the API; the new secret is returned this once
+pub async fn create_webhook(state: &AppState, input: WebhookInput) -> ApiResult<Webhook> {
+    let secret = uuid::Uuid::new_v4().simple().to_string();
+    let webhook = input.into_webhook(secret, now())?;
+    Ok(WebhookRepository::create(&state.db.client, webhook).await?)
+}
+
+/// Send a test event to one webhook, whether or not it is enabled
+pub async fn send_test(state: &AppState, key: &str) -> ApiResult<WebhookDelivery> {
+    let webhook = found(
+        WebhookRepository::get_by_id(&state.db.client, key).await?,
+        "Webhook",
+        key,
+    )?;
+    let data = json!({ "message": "Test delivery from Rubigo", "webhook": webhook.name });
+    Ok(state.webhooks.send(webhook, TEST_EVENT, data).await?)
+}
+
+/// Send a logged delivery again, byte for byte, with a fresh set of attempts
+pub async fn redeliver(state: &AppState, key: &str) -> ApiResult<WebhookDelivery> {
+    let db = &state.db.client;
+    let mut delivery = found(
+        WebhookRepository::get_delivery(db, key).await?,
+        "Delivery",
+        key,
+    )?;
+    let webhook = found(
+        WebhookRepository::get_by_id(db, &delivery.webhook).await?,
+        "Webhook",
+        &delivery.webhook,
+    )?;
+    if delivery.status == DeliveryStatus::Pending {
+        return Err(ApiError::conflict(format!(
+            "Delivery '{}' is still being attempted",
+            key
+        )));
+    }
+    delivery.status = DeliveryStatus::Pending;
+    delivery.attempts = 0;
+    delivery.updated_at = now();
+    let delivery = found(
+        WebhookRepository::update_delivery(db, key, delivery).await?,
+        "Delivery",
+        key,
+    )?;
+    state.webhooks.spawn_delivery(webhook, delivery.clone());
+    Ok(delivery)
+}
+
+fn redacted(mut webhook: Webhook) -> Webhook {
+    webhook.secret = REDACTED.to_string();
+    webhook
+}
+
+/// Every webhook, secrets hidden
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    responses((status = 200, description = "All webhooks by name", body = Vec<Webhook>))
+)]
+pub async fn list(State(state): State<AppState>) -> ApiResult<Json<Vec<Webhook>>> {
+    let webhooks = WebhookRepository::get_all(&state.db.client).await?;
+    Ok(Json(webhooks.into_iter().map(redacted).collect()))
+}
+
+/// Events a webhook can subscribe to
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/events",
+    tag = "webhooks",
+    responses((status = 200, description = "Event names", body = Vec<String>))
+)]
+pub async fn events() -> Json<Vec<String>> {
+    Json(known_events())
+}
+
+/// Register a webhook; the response is the only place its secret is shown
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    request_body = WebhookInput,
+    responses(
+        (status = 201, description = "Webhook created", body = Webhook),
+        (status = 400, description = "Invalid URL or unknown event", body = ApiError),
+    )
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Json(input): Json<WebhookInput>,
+) -> ApiResult<(StatusCode, Json<Webhook>)> {
+    Ok((
+        StatusCode::CREATED,
+        Json(create_webhook(&state, input).await?),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook id (`webhook:key` or `key`)")),
+    request_body = WebhookInput,
+    responses(
+        (status = 200, description = "Webhook updated", body = Webhook),
+        (status = 400, description = "Invalid URL or unknown event", body = ApiError),
+        (status = 404, description = "Webhook not found", body = ApiError),
+    )
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<WebhookInput>,
+) -> ApiResult<Json<Webhook>> {
+    let key = record_key(&id, "webhook");
+    let existing = found(
+        WebhookRepository::get_by_id(&state.db.client, key).await?,
+        "Webhook",
+        &id,
+    )?;
+    let webhook = input.into_webhook(existing.secret, existing.created_at)?;
+    let updated = WebhookRepository::update(&state.db.client, key, webhook).await?;
+    Ok(Json(redacted(found(updated, "Webhook", &id)?)))
+}
+
+/// Delete a webhook and its delivery log
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook id (`webhook:key` or `key`)")),
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 404, description = "Webhook not found", body = ApiError),
+    )
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let key = record_key(&id, "webhook");
+    found(
+        WebhookRepository::get_by_id(&state.db.client, key).await?,
+        "Webhook",
+        &id,
+    )?;
+    WebhookRepository::delete(&state.db.client, key).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Send a `webhook.test` event to check the endpoint
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{id}/test",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook id (`webhook:key` or `key`)")),
+    responses(
+        (status = 202, description = "Test delivery queued", body = WebhookDelivery),
+        (status = 404, description = "Webhook not found", body = ApiError),
+    )
+)]
+pub async fn test(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<(StatusCode, Json<WebhookDelivery>)> {
+    let key = record_key(&id, "webhook");
+    Ok((StatusCode::ACCEPTED, Json(send_test(&state, key).await?)))
+}
+
+/// Delivery log of one webhook, newest first
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook id (`webhook:key` or `key`)")),
+    responses(
+        (status = 200, description = "Deliveries with their outcome", body = Vec<WebhookDelivery>),
+        (status = 404, description = "Webhook not found", body = ApiError),
+    )
+)]
+pub async fn deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Vec<WebhookDelivery>>> {
+    let key = record_key(&id, "webhook");
+    found(
+        WebhookRepository::get_by_id(&state.db.client, key).await?,
+        "Webhook",
+        &id,
+    )?;
+    Ok(Json(
+        WebhookRepository::deliveries(&state.db.client, Some(key)).await?,
+    ))
+}
+
+/// Send a finished delivery again
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/deliveries/{id}/redeliver",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Delivery id (`webhook_delivery:key` or `key`)")),
+    responses(
+        (status = 202, description = "Delivery queued again", body = WebhookDelivery),
+        (status = 404, description = "Delivery not found", body = ApiError),
+        (status = 409, description = "Delivery is still being attempted", body = ApiError),
+    )
+)]
+pub async fn redeliver_now(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<(StatusCode, Json<WebhookDelivery>)> {
+    let key = record_key(&id, "webhook_delivery");
+    Ok((StatusCode::ACCEPTED, Json(redeliver(&state, key).await?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payloads_are_signed_with_their_timestamp() {
+        let body = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            sign("key", 1_700_000_000, body),
+            "t=1700000000,v1=2f658d6aef4f246e91cd741bbcded7479e9605f9d41c9e248122a117e0e1765b"
+        );
+        // A replay with a fresh timestamp needs the secret
+        assert_ne!(
+            sign("key", 1_700_000_600, body)[13..],
+            sign("key", 1_700_000_000, body)[13..]
+        );
+    }
+
+    #[test]
+    fn input_is_checked_before_storing() {
+        let input = |url: &str, events: &[&str], secret: Option<&str>| WebhookInput {
+            name: " Chat ".to_string(),
+            url: url.to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            secret: secret.map(str::to_string),
+            enabled: true,
+        };
+        let webhook = input("https://chat.example.com/hook", &["asset.*", " "], None)
+            .into_webhook("generated".to_string(), now())
+            .unwrap();
+        assert_eq!(webhook.name, "Chat");
+        assert_eq!(webhook.events, vec!["asset.*"]);
+        assert_eq!(webhook.secret, "generated");
+
+        let webhook = input("https://chat.example.com/hook", &["*"], Some("mine"))
+            .into_webhook("generated".to_string(), now())
+            .unwrap();
+        assert_eq!(webhook.secret, "mine");
+
+        assert!(input("chat.example.com", &["*"], None)
+            .into_webhook(String::new(), now())
+            .is_err());
+        assert!(input("https://chat.example.com", &[], None)
+            .into_webhook(String::new(), now())
+            .is_err());
+    }
+}
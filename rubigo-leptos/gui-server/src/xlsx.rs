@@ -0,0 +1,97 @@
+//! XLSX writer
+//!
+//! Produces a single-sheet workbook of text cells with `rust_xlsxwriter`,
+//! which takes care of the Office Open XML parts and the zip container.
+
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+/// Characters Excel does not allow in a sheet name
+const SHEET_NAME_FORBIDDEN: [char; 7] = ['[', ']', ':', '*', '?', '/', '\\'];
+
+/// Build an `.xlsx` file with a bold header row followed by `rows`
+pub fn workbook(
+    sheet_name: &str,
+    header: &[String],
+    rows: &[Vec<String>],
+) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_title(sheet_name))?;
+    for (c, text) in header.iter().enumerate() {
+        sheet.write_string_with_format(0, column(c)?, text, &bold)?;
+    }
+    for (r, cells) in rows.iter().enumerate() {
+        let row = u32::try_from(r + 1).map_err(|_| XlsxError::RowColumnLimitError)?;
+        for (c, text) in cells.iter().enumerate() {
+            sheet.write_string(row, column(c)?, text)?;
+        }
+    }
+    workbook.save_to_buffer()
+}
+
+fn column(index: usize) -> Result<u16, XlsxError> {
+    u16::try_from(index).map_err(|_| XlsxError::RowColumnLimitError)
+}
+
+/// A sheet name Excel accepts: at most 31 characters, none of
+/// [`SHEET_NAME_FORBIDDEN`], not blank and not wrapped in apostrophes
+fn sheet_title(name: &str) -> String {
+    let title: String = name
+        .trim_matches('\'')
+        .chars()
+        .map(|c| {
+            if SHEET_NAME_FORBIDDEN.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .take(31)
+        .collect();
+    if title.trim().is_empty() {
+        "Sheet1".to_string()
+    } else {
+        title
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    fn part(bytes: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut text = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn sheet_names_are_made_acceptable() {
+        assert_eq!(sheet_title("sites"), "sites");
+        assert_eq!(sheet_title("a/b:c"), "a_b_c");
+        assert_eq!(sheet_title("'quoted'"), "quoted");
+        assert_eq!(sheet_title(""), "Sheet1");
+        assert_eq!(sheet_title(&"x".repeat(40)).len(), 31);
+    }
+
+    #[test]
+    fn workbook_is_a_zip_with_escaped_cells() {
+        let bytes = workbook(
+            "Sites",
+            &["name".to_string()],
+            &[vec!["R&D <lab>".to_string()]],
+        )
+        .unwrap();
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        assert!(part(&bytes, "xl/workbook.xml").contains(r#"name="Sites""#));
+        assert!(part(&bytes, "xl/sharedStrings.xml").contains("R&amp;D &lt;lab&gt;"));
+        assert!(part(&bytes, "xl/worksheets/sheet1.xml").contains(r#"<c r="A2""#));
+    }
+}
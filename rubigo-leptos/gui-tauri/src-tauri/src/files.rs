@@ -0,0 +1,164 @@
+//! Native file dialogs
+//!
+//! Backs `actions::filesystem::TauriFileSystem`: picking a scenario
+//! directory to import, saving reports and CSVs where the user chooses, and
+//! opening what was saved with the system's default application. Only files
+//! exported during this session (and web URLs) can be opened, so the page
+//! can't launch arbitrary paths.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use actions::filesystem::{
+    is_scenario_file, ExportRequest, SavedFile, ScenarioFile, ScenarioFiles,
+};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_opener::OpenerExt;
+
+/// Paths saved by [`export_file`], which [`open_artifact`] may open
+#[derive(Default)]
+pub struct Exported(Mutex<HashSet<PathBuf>>);
+
+impl Exported {
+    fn insert(&self, path: PathBuf) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path);
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(path)
+    }
+}
+
+/// Run a blocking dialog off the async runtime
+async fn dialog<T: Send + 'static>(show: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(show)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The top-level TOML files of `dir`
+fn read_scenario(dir: &Path) -> Result<ScenarioFiles, String> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !path.is_file() || !is_scenario_file(name) {
+            continue;
+        }
+        let contents =
+            std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        files.push(ScenarioFile {
+            name: name.to_string(),
+            contents,
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let directory = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.display().to_string());
+    ScenarioFiles::new(directory, files).map_err(|e| e.to_string())
+}
+
+/// Ask for a scenario directory and read it; `None` if the user cancelled
+#[tauri::command]
+pub async fn import_scenario<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<ScenarioFiles>, String> {
+    let picked = dialog(move || {
+        app.dialog()
+            .file()
+            .set_title("Import Scenario")
+            .blocking_pick_folder()
+    })
+    .await?;
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let dir = picked.into_path().map_err(|e| e.to_string())?;
+    let scenario = read_scenario(&dir)?;
+    tracing::info!(directory = %dir.display(), files = scenario.files.len(), "Scenario imported");
+    Ok(Some(scenario))
+}
+
+/// Ask where to save `request` and write it; `None` if the user cancelled
+#[tauri::command]
+pub async fn export_file<R: Runtime>(
+    app: AppHandle<R>,
+    exported: tauri::State<'_, Exported>,
+    request: ExportRequest,
+) -> Result<Option<SavedFile>, String> {
+    let mut save = app.dialog().file().set_file_name(&request.file_name);
+    if let Some((label, extensions)) = request.filter() {
+        save = save.add_filter(label, extensions);
+    }
+    let Some(picked) = dialog(move || save.blocking_save_file()).await? else {
+        return Ok(None);
+    };
+    let path = picked.into_path().map_err(|e| e.to_string())?;
+    tokio::fs::write(&path, &request.contents)
+        .await
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    tracing::info!(path = %path.display(), bytes = request.contents.len(), "Exported file");
+
+    exported.insert(path.clone());
+    Ok(Some(SavedFile {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or(request.file_name),
+        path: Some(path.display().to_string()),
+    }))
+}
+
+/// Open an exported file, or a web URL, with the system's default handler
+#[tauri::command]
+pub fn open_artifact<R: Runtime>(
+    app: AppHandle<R>,
+    exported: tauri::State<'_, Exported>,
+    target: String,
+) -> Result<(), String> {
+    let opened = if target.starts_with("https://") || target.starts_with("http://") {
+        app.opener().open_url(&target, None::<&str>)
+    } else if exported.contains(Path::new(&target)) {
+        app.opener().open_path(&target, None::<&str>)
+    } else {
+        return Err(format!("'{}' was not exported from this app", target));
+    };
+    opened.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenario_directories_are_read_one_level_deep() {
+        let dir = std::env::temp_dir().join(format!("rubigo-scenario-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("headshots")).unwrap();
+        std::fs::write(dir.join("scenario.toml"), "[scenario]").unwrap();
+        std::fs::write(dir.join("sites.toml"), "").unwrap();
+        std::fs::write(dir.join("README.md"), "").unwrap();
+        std::fs::write(dir.join("headshots/extra.toml"), "").unwrap();
+
+        let scenario = read_scenario(&dir).unwrap();
+        let names: Vec<&str> = scenario.files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["scenario.toml", "sites.toml"]);
+        assert_eq!(scenario.get("scenario.toml"), Some("[scenario]"));
+
+        std::fs::remove_file(dir.join("scenario.toml")).unwrap();
+        assert!(read_scenario(&dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
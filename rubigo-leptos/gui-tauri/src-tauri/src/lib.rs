@@ -1,20 +1,69 @@
 //! Tauri Application Entry Point
 //!
-//! Handles action dispatch from the WASM frontend.
+//! Handles action dispatch from the WASM frontend and sets up the native
+//! shell: application menu, tray icon, the main window's saved size and
+//! position, the local database synced with a gui-server, native file
+//! dialogs and notifications (the SPA's meeting reminders), and self-update
+//! from the chosen release channel. Runtime settings come from the shared
+//! `config` crate (defaults, `rubigo.toml`, `RUBIGO_*` environment
+//! variables); command-line flags are not read since the OS may pass its own
+//! arguments to an app bundle.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 use serde_json::Value;
+use tauri::Manager;
+use tracing::Instrument;
+
+mod files;
+mod menu;
+mod sync;
+mod tray;
+mod updates;
+mod window_state;
+
+/// Sequence for dispatch request IDs
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
 /// Dispatch an action from the frontend
 /// 
 /// This command receives serialized actions from TauriBroker in the WASM frontend
-/// and routes them to the appropriate handler.
+/// and routes them to the appropriate handler. Each dispatch runs in its own
+/// `dispatch` span tagged with a request ID, and logs latency and outcome.
 #[tauri::command]
 async fn dispatch_action(action_type: String, payload: Value) -> Result<Value, String> {
-    // Log the action for debugging
-    println!("Dispatching action: {} with payload: {:?}", action_type, payload);
-    
+    let request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let span = tracing::info_span!("dispatch", request_id, action = %action_type);
+
+    async move {
+        tracing::debug!(?payload, "Dispatching action");
+        let start = Instant::now();
+        let result = route_action(&action_type, payload).await;
+        let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        match &result {
+            Ok(_) => tracing::info!(latency_ms, "Action completed"),
+            Err(e) => tracing::warn!(latency_ms, error = %e, "Action failed"),
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+/// Dev mode and feature flags, for the frontend to adapt to
+#[tauri::command]
+fn get_settings(settings: tauri::State<'_, config::Settings>) -> Value {
+    serde_json::json!({
+        "dev_mode": settings.dev_mode,
+        "features": settings.features,
+    })
+}
+
+async fn route_action(action_type: &str, payload: Value) -> Result<Value, String> {
     // Route based on action type
-    match action_type.as_str() {
+    match action_type {
         // Asset actions
         "asset/create" => handle_asset_create(payload).await,
         "asset/update" => handle_asset_update(payload).await,
@@ -69,9 +118,56 @@ async fn handle_personnel_get(_payload: Value) -> Result<Value, String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let loaded = config::Loader::new().env(std::env::vars()).load();
+    let settings = loaded.as_ref().cloned().unwrap_or_default();
+    let default_filter = if settings.dev_mode {
+        "gui_tauri_lib=debug"
+    } else {
+        "gui_tauri_lib=info"
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| default_filter.into()),
+        )
+        .init();
+
+    if let Err(e) = loaded {
+        tracing::warn!("Invalid configuration, using defaults: {:#}", e);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![dispatch_action])
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .manage(settings)
+        .manage(files::Exported::default())
+        .menu(menu::build)
+        .on_menu_event(menu::handle)
+        .setup(|app| {
+            tray::build(app.handle())?;
+            app.manage(window_state::restore(app.handle()));
+            let settings = app.state::<config::Settings>();
+            sync::start(app.handle(), &settings);
+            app.manage(updates::Updates::load(app.handle(), &settings.updates));
+            Ok(())
+        })
+        .on_window_event(window_state::on_window_event)
+        .invoke_handler(tauri::generate_handler![
+            dispatch_action,
+            get_settings,
+            sync::sync_status,
+            sync::sync_now,
+            files::import_scenario,
+            files::export_file,
+            files::open_artifact,
+            updates::update_channel,
+            updates::set_update_channel,
+            updates::check_for_update,
+            updates::install_update
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
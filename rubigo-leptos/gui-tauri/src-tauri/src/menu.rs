@@ -0,0 +1,138 @@
+//! Native application menu
+//!
+//! File, Edit and View menus for the main window. The View menu's
+//! navigation entries (also offered by the tray) don't change pages
+//! themselves: they emit a [`NAVIGATE_EVENT`] carrying the route, which the
+//! SPA's router follows. "Check for Updates…" likewise asks the SPA to open
+//! its update dialog with a [`CHECK_UPDATES_EVENT`].
+
+use actions::updates::CHECK_UPDATES_EVENT;
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItemBuilder, SubmenuBuilder};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Event the SPA listens for; the payload is the route to show
+pub const NAVIGATE_EVENT: &str = "navigate";
+
+/// Label of the window created from `tauri.conf.json`
+pub const MAIN_WINDOW: &str = "main";
+
+/// SPA routes offered in the View menu: path, label, accelerator
+pub const ROUTES: &[(&str, &str, &str)] = &[
+    ("/", "Home", "CmdOrCtrl+1"),
+    ("/calendar", "Calendar", "CmdOrCtrl+2"),
+    ("/personnel", "Personnel", "CmdOrCtrl+3"),
+    ("/sites", "Sites", "CmdOrCtrl+4"),
+    ("/assets", "Assets", "CmdOrCtrl+5"),
+    ("/connections", "Connections", "CmdOrCtrl+6"),
+];
+
+const NAV_PREFIX: &str = "nav:";
+const RELOAD: &str = "reload";
+const CHECK_UPDATES: &str = "check_updates";
+pub(crate) const SHOW: &str = "show";
+
+/// Menu item ID that navigates to `path`
+pub fn nav_id(path: &str) -> String {
+    format!("{}{}", NAV_PREFIX, path)
+}
+
+/// The route a navigation item leads to, if `id` is one
+pub fn route_for(id: &str) -> Option<&'static str> {
+    let path = id.strip_prefix(NAV_PREFIX)?;
+    ROUTES
+        .iter()
+        .map(|(route, _, _)| *route)
+        .find(|route| *route == path)
+}
+
+pub fn build<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let reload = MenuItemBuilder::with_id(RELOAD, "Reload")
+        .accelerator("CmdOrCtrl+R")
+        .build(app)?;
+    let check_updates = MenuItemBuilder::with_id(CHECK_UPDATES, "Check for Updates…").build(app)?;
+    let file = SubmenuBuilder::new(app, "File")
+        .item(&reload)
+        .separator()
+        .item(&check_updates)
+        .separator()
+        .close_window()
+        .quit()
+        .build()?;
+
+    let edit = SubmenuBuilder::new(app, "Edit")
+        .undo()
+        .redo()
+        .separator()
+        .cut()
+        .copy()
+        .paste()
+        .select_all()
+        .build()?;
+
+    let mut view = SubmenuBuilder::new(app, "View");
+    for (path, label, accelerator) in ROUTES {
+        let item = MenuItemBuilder::with_id(nav_id(path), *label)
+            .accelerator(*accelerator)
+            .build(app)?;
+        view = view.item(&item);
+    }
+    let view = view.separator().fullscreen().build()?;
+
+    MenuBuilder::new(app).items(&[&file, &edit, &view]).build()
+}
+
+/// Bring the main window to the front
+pub fn show_main<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    let shown = window
+        .unminimize()
+        .and_then(|_| window.show())
+        .and_then(|_| window.set_focus());
+    if let Err(e) = shown {
+        tracing::warn!("Could not show the main window: {}", e);
+    }
+}
+
+/// Handle a click on a menu or tray item
+pub fn handle<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
+    let id = event.id().as_ref();
+    if let Some(path) = route_for(id) {
+        show_main(app);
+        if let Err(e) = app.emit_to(MAIN_WINDOW, NAVIGATE_EVENT, path) {
+            tracing::warn!("Navigation to {} not delivered: {}", path, e);
+        }
+        return;
+    }
+    match id {
+        SHOW => show_main(app),
+        CHECK_UPDATES => {
+            show_main(app);
+            if let Err(e) = app.emit_to(MAIN_WINDOW, CHECK_UPDATES_EVENT, ()) {
+                tracing::warn!("Update check request not delivered: {}", e);
+            }
+        }
+        RELOAD => {
+            if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+                let _ = window.eval("location.reload()");
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navigation_ids_map_back_to_known_routes() {
+        for (path, _, _) in ROUTES {
+            assert_eq!(route_for(&nav_id(path)), Some(*path));
+        }
+        assert_eq!(route_for("nav:/admin"), None);
+        assert_eq!(route_for(RELOAD), None);
+        assert_eq!(route_for(CHECK_UPDATES), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! Offline-first local database
+//!
+//! The shell keeps its own SurrealDB in the app data directory, so the app
+//! keeps working with no server in reach. When `[runtime.sync] remote` names
+//! a gui-server, a background task trades changes with it every
+//! `interval_secs` (or at once on [`sync_now`]) using `db::sync`, and
+//! reports each round to the frontend as a [`SYNC_STATUS_EVENT`].
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use actions::sync::{
+    PullResponse, PushRequest, PushResponse, SyncStatus, SYNC_PATH, SYNC_STATUS_EVENT,
+};
+use async_trait::async_trait;
+use db::sync::{Remote, Replica};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::Notify;
+
+/// Directory of the local database inside the app data directory
+const DB_DIR: &str = "replica.db";
+
+/// A gui-server reached over HTTP
+struct HttpRemote {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpRemote {
+    fn new(base: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: format!("{}{}", base.trim_end_matches('/'), SYNC_PATH),
+        }
+    }
+}
+
+#[async_trait]
+impl Remote for HttpRemote {
+    async fn pull(&self, since: i64, replica: &str) -> anyhow::Result<PullResponse> {
+        let response = self
+            .client
+            .get(&self.url)
+            .query(&[
+                ("since", since.to_string()),
+                ("replica", replica.to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    async fn push(&self, request: PushRequest) -> anyhow::Result<PushResponse> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+}
+
+/// Latest sync status, and a way to cut the wait before the next round
+pub struct SyncHandle {
+    status: Mutex<SyncStatus>,
+    wake: Notify,
+}
+
+/// Status as last reported, for a frontend that missed the events
+#[tauri::command]
+pub fn sync_status(handle: tauri::State<'_, SyncHandle>) -> SyncStatus {
+    handle
+        .status
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Sync now rather than at the end of the interval
+#[tauri::command]
+pub fn sync_now(handle: tauri::State<'_, SyncHandle>) {
+    handle.wake.notify_one();
+}
+
+fn report<R: Runtime>(app: &AppHandle<R>, status: SyncStatus) {
+    if let Err(e) = app.emit(SYNC_STATUS_EVENT, &status) {
+        tracing::debug!("Sync status not delivered: {}", e);
+    }
+    *app.state::<SyncHandle>()
+        .status
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = status;
+}
+
+/// Open the local database and, with a remote configured, start syncing
+///
+/// The database is managed as a [`db::Database`] once open.
+pub fn start<R: Runtime>(app: &AppHandle<R>, settings: &config::Settings) {
+    let initial = match settings.sync.remote {
+        Some(_) => SyncStatus::Syncing { pending: 0 },
+        None => SyncStatus::LocalOnly,
+    };
+    app.manage(SyncHandle {
+        status: Mutex::new(initial),
+        wake: Notify::new(),
+    });
+
+    let app = app.clone();
+    let settings = settings.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run(&app, &settings).await {
+            tracing::error!("Local database unavailable: {:#}", e);
+            report(
+                &app,
+                SyncStatus::Offline {
+                    pending: 0,
+                    error: format!("{:#}", e),
+                },
+            );
+        }
+    });
+}
+
+async fn run<R: Runtime>(app: &AppHandle<R>, settings: &config::Settings) -> anyhow::Result<()> {
+    let path = app.path().app_data_dir()?.join(DB_DIR);
+    let database = db::Database::open(&path, &settings.database).await?;
+    let mut replica = Replica::open(database.client.clone(), settings.sync.conflict_policy).await?;
+    app.manage(database);
+
+    let Some(remote) = &settings.sync.remote else {
+        tracing::info!("No sync remote configured; working offline");
+        return Ok(());
+    };
+    tracing::info!(replica = replica.id(), remote = %remote, "Syncing local database");
+    let remote = HttpRemote::new(remote);
+    let interval = Duration::from_secs(settings.sync.interval_secs.max(1));
+    let handle = app.state::<SyncHandle>();
+
+    loop {
+        let pending = replica.pending().await.map(|p| p.len()).unwrap_or(0);
+        report(app, SyncStatus::Syncing { pending });
+        let status = match replica.sync(&remote).await {
+            Ok(round) => {
+                tracing::debug!(?round, "Sync round complete");
+                SyncStatus::Synced
+            }
+            Err(e) => {
+                tracing::warn!("Sync failed: {:#}", e);
+                SyncStatus::Offline {
+                    pending: replica.pending().await.map(|p| p.len()).unwrap_or(pending),
+                    error: format!("{:#}", e),
+                }
+            }
+        };
+        report(app, status);
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = handle.wake.notified() => {}
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! System tray
+//!
+//! A tray icon that brings the window back on click, with quick jumps to
+//! the most used pages and Quit in its menu.
+
+use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Runtime};
+
+use crate::menu;
+
+/// Pages offered in the tray menu
+const QUICK_ROUTES: &[(&str, &str)] = &[
+    ("/calendar", "Calendar"),
+    ("/personnel", "Personnel"),
+    ("/sites", "Sites"),
+];
+
+pub fn build<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<TrayIcon<R>> {
+    let mut items = MenuBuilder::new(app)
+        .item(&MenuItemBuilder::with_id(menu::SHOW, "Show Window").build(app)?)
+        .separator();
+    for (path, label) in QUICK_ROUTES {
+        items = items.item(&MenuItemBuilder::with_id(menu::nav_id(path), *label).build(app)?);
+    }
+    let tray_menu = items.separator().quit().build()?;
+
+    let mut tray = TrayIconBuilder::with_id("main")
+        .tooltip(app.package_info().name.clone())
+        .menu(&tray_menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(menu::handle)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                menu::show_main(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)
+}
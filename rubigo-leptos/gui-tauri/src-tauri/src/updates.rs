@@ -0,0 +1,170 @@
+//! Self-update
+//!
+//! Checks `[runtime.updates] endpoint` for a newer signed build on the
+//! user's release channel, and installs it on request. The frontend drives
+//! this through [`check_for_update`] and [`install_update`], and picks the
+//! channel with [`set_update_channel`]; the choice is kept in the app config
+//! directory so it outlives the settings default.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use actions::updates::{UpdateInfo, UpdateProgress, UPDATE_PROGRESS_EVENT};
+use config::UpdateChannel;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+/// File in the app config directory holding the chosen channel
+const PREFS_FILE: &str = "updates.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Prefs {
+    channel: Option<UpdateChannel>,
+}
+
+/// Chosen channel, and the update found by the last check
+pub struct Updates {
+    prefs: Option<PathBuf>,
+    channel: Mutex<UpdateChannel>,
+    pending: tokio::sync::Mutex<Option<Update>>,
+}
+
+impl Updates {
+    /// Channel from the saved preference, else from the settings
+    pub fn load<R: Runtime>(app: &AppHandle<R>, settings: &config::UpdateSettings) -> Self {
+        let prefs = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(PREFS_FILE));
+        let saved = prefs
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str::<Prefs>(&text).ok())
+            .and_then(|prefs| prefs.channel);
+        Self {
+            prefs,
+            channel: Mutex::new(saved.unwrap_or(settings.channel)),
+            pending: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    fn channel(&self) -> UpdateChannel {
+        *self.channel.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn save(&self, channel: UpdateChannel) -> std::io::Result<()> {
+        let Some(path) = &self.prefs else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let prefs = Prefs {
+            channel: Some(channel),
+        };
+        std::fs::write(path, serde_json::to_vec_pretty(&prefs)?)
+    }
+}
+
+/// Name of the channel updates come from
+#[tauri::command]
+pub fn update_channel(updates: tauri::State<'_, Updates>) -> &'static str {
+    updates.channel().name()
+}
+
+/// Switch channels; an update found on the old one is dropped
+#[tauri::command]
+pub async fn set_update_channel(
+    updates: tauri::State<'_, Updates>,
+    channel: String,
+) -> Result<(), String> {
+    let channel = UpdateChannel::from_name(&channel)
+        .ok_or_else(|| format!("Unknown update channel '{}'", channel))?;
+    *updates.channel.lock().unwrap_or_else(|e| e.into_inner()) = channel;
+    *updates.pending.lock().await = None;
+    updates.save(channel).map_err(|e| e.to_string())
+}
+
+/// Look for a newer version on the chosen channel
+///
+/// Returns `None` when up to date. The update is kept for [`install_update`].
+#[tauri::command]
+pub async fn check_for_update<R: Runtime>(
+    app: AppHandle<R>,
+    settings: tauri::State<'_, config::Settings>,
+    updates: tauri::State<'_, Updates>,
+) -> Result<Option<UpdateInfo>, String> {
+    let channel = updates.channel();
+    let endpoint = settings
+        .updates
+        .endpoint_for(channel)
+        .ok_or("Update checks are not configured")?;
+    let endpoint = endpoint
+        .parse::<tauri::Url>()
+        .map_err(|e| format!("Bad update endpoint: {}", e))?;
+
+    let found = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| {
+            tracing::warn!(channel = channel.name(), "Update check failed: {}", e);
+            e.to_string()
+        })?;
+
+    let info = found.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+        channel: channel.name().to_string(),
+    });
+    match &info {
+        Some(info) => {
+            tracing::info!(channel = channel.name(), version = %info.version, "Update available")
+        }
+        None => tracing::debug!(channel = channel.name(), "Up to date"),
+    }
+    *updates.pending.lock().await = found;
+    Ok(info)
+}
+
+/// Download and install the update found by the last check, then restart
+///
+/// Reports download progress as [`UPDATE_PROGRESS_EVENT`]s.
+#[tauri::command]
+pub async fn install_update<R: Runtime>(
+    app: AppHandle<R>,
+    updates: tauri::State<'_, Updates>,
+) -> Result<(), String> {
+    let Some(update) = updates.pending.lock().await.take() else {
+        return Err("No update to install; check for updates first".into());
+    };
+    tracing::info!(version = %update.version, "Installing update");
+
+    let mut progress = UpdateProgress {
+        downloaded: 0,
+        total: None,
+    };
+    update
+        .download_and_install(
+            |chunk, total| {
+                progress.downloaded += chunk as u64;
+                progress.total = total;
+                let _ = app.emit(UPDATE_PROGRESS_EVENT, progress);
+            },
+            || tracing::debug!("Update downloaded"),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Update failed: {}", e);
+            e.to_string()
+        })?;
+
+    app.restart()
+}
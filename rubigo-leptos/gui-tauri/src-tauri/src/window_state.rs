@@ -0,0 +1,225 @@
+//! Window size and position across launches
+//!
+//! The main window's last normal (not maximized or minimized) bounds and
+//! whether it was maximized are kept in `window-state.json` in the app's
+//! config directory, written when the window closes and applied before it
+//! is first shown. Bounds that would put the window off every connected
+//! monitor are ignored, so unplugging a screen can't strand it.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, Window, WindowEvent,
+};
+
+use crate::menu::MAIN_WINDOW;
+
+const FILE_NAME: &str = "window-state.json";
+
+/// Smallest size worth restoring; anything less is treated as corrupt
+const MIN_SIZE: (u32, u32) = (400, 300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// A monitor's bounds in physical pixels: x, y, width, height
+pub type MonitorRect = (i32, i32, u32, u32);
+
+impl WindowState {
+    pub fn load(path: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Whether these bounds can be applied on a screen made of `monitors`
+    ///
+    /// The window's title bar area (its top edge, inset a little) has to be
+    /// on some monitor so the user can still grab it.
+    pub fn fits(&self, monitors: &[MonitorRect]) -> bool {
+        const GRAB: i32 = 50;
+        if self.width < MIN_SIZE.0 || self.height < MIN_SIZE.1 {
+            return false;
+        }
+        let (left, top) = (self.x + GRAB, self.y + GRAB / 2);
+        let right = self.x.saturating_add(self.width as i32) - GRAB;
+        monitors.iter().any(|&(mx, my, mw, mh)| {
+            let (mright, mbottom) = (mx + mw as i32, my + mh as i32);
+            (left < mright && right > mx) && (top >= my && top < mbottom)
+        })
+    }
+}
+
+/// Latest bounds of the main window, kept up to date while it runs
+#[derive(Default)]
+pub struct Tracked {
+    path: Option<PathBuf>,
+    state: Mutex<Option<WindowState>>,
+}
+
+fn monitor_rects(monitors: tauri::Result<Vec<Monitor>>) -> Vec<MonitorRect> {
+    monitors
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            (
+                m.position().x,
+                m.position().y,
+                m.size().width,
+                m.size().height,
+            )
+        })
+        .collect()
+}
+
+fn bounds(
+    position: tauri::Result<PhysicalPosition<i32>>,
+    size: tauri::Result<PhysicalSize<u32>>,
+) -> Option<WindowState> {
+    let (position, size) = (position.ok()?, size.ok()?);
+    Some(WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: false,
+    })
+}
+
+/// Apply the saved bounds to the main window, then show it
+///
+/// Call from `setup`; the window starts hidden so it doesn't visibly jump.
+pub fn restore<R: Runtime>(app: &AppHandle<R>) -> Tracked {
+    let path = app
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(FILE_NAME));
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return Tracked::default();
+    };
+
+    let saved = path
+        .as_deref()
+        .and_then(WindowState::load)
+        .filter(|s| s.fits(&monitor_rects(window.available_monitors())));
+    if let Some(state) = saved {
+        let applied = window
+            .set_size(PhysicalSize::new(state.width, state.height))
+            .and_then(|_| window.set_position(PhysicalPosition::new(state.x, state.y)))
+            .and_then(|_| {
+                if state.maximized {
+                    window.maximize()
+                } else {
+                    Ok(())
+                }
+            });
+        if let Err(e) = applied {
+            tracing::warn!("Could not restore window bounds: {}", e);
+        }
+    }
+    if let Err(e) = window.show() {
+        tracing::warn!("Could not show the main window: {}", e);
+    }
+
+    Tracked {
+        path,
+        state: Mutex::new(saved.or_else(|| bounds(window.outer_position(), window.outer_size()))),
+    }
+}
+
+/// Follow the main window's bounds and save them when it closes
+///
+/// Register with `on_window_event`; does nothing until [`restore`]'s result
+/// is managed.
+pub fn on_window_event<R: Runtime>(window: &Window<R>, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+    // Events can arrive while the window is created, before `setup` has run
+    let Some(tracked) = window.try_state::<Tracked>() else {
+        return;
+    };
+    let mut state = tracked.state.lock().unwrap_or_else(|e| e.into_inner());
+    match event {
+        WindowEvent::Resized(_) | WindowEvent::Moved(_) => {
+            // Remember only normal bounds, so un-maximizing next launch lands somewhere sensible
+            let abnormal =
+                window.is_maximized().unwrap_or(false) || window.is_minimized().unwrap_or(false);
+            if !abnormal {
+                *state = bounds(window.outer_position(), window.outer_size());
+            }
+        }
+        WindowEvent::CloseRequested { .. } => {
+            let (Some(mut last), Some(path)) = (*state, tracked.path.as_deref()) else {
+                return;
+            };
+            last.maximized = window.is_maximized().unwrap_or(false);
+            if let Err(e) = last.save(path) {
+                tracing::warn!("Could not save window bounds to {}: {}", path.display(), e);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: MonitorRect = (0, 0, 1920, 1080);
+
+    fn state(x: i32, y: i32, width: u32, height: u32) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+        }
+    }
+
+    #[test]
+    fn bounds_must_be_reachable_on_a_connected_monitor() {
+        assert!(state(100, 100, 1024, 768).fits(&[SCREEN]));
+        // Partly off the left edge, but the title bar is still visible
+        assert!(state(-500, 10, 1024, 768).fits(&[SCREEN]));
+        // Was on a second monitor that is now unplugged
+        assert!(!state(2200, 100, 1024, 768).fits(&[SCREEN]));
+        assert!(state(2200, 100, 1024, 768).fits(&[SCREEN, (1920, 0, 1920, 1080)]));
+        // Title bar above the top of the screen
+        assert!(!state(100, -400, 1024, 768).fits(&[SCREEN]));
+        // Too small to be a real window
+        assert!(!state(100, 100, 120, 80).fits(&[SCREEN]));
+    }
+
+    #[test]
+    fn saved_state_round_trips() {
+        let dir = std::env::temp_dir().join(format!("rubigo-window-state-{}", std::process::id()));
+        let path = dir.join(FILE_NAME);
+        let saved = WindowState {
+            maximized: true,
+            ..state(10, 20, 1280, 800)
+        };
+        saved.save(&path).unwrap();
+        assert_eq!(WindowState::load(&path), Some(saved));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(WindowState::load(&path), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
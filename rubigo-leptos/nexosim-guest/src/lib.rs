@@ -1,3 +1,5 @@
+pub mod rules;
+
 #[derive(Debug, Clone)]
 pub struct Packet {
     pub src: u32,
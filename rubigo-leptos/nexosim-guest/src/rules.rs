@@ -0,0 +1,88 @@
+//! Business rule guests
+//!
+//! Helpers for rule modules run by the server's `rules` host. A rule
+//! implements [`Rule`] and exports it with [`export_rule!`](crate::export_rule);
+//! it is handed each action as JSON and answers through [`reject`] (before
+//! the action runs) and [`dispatch`] (after it succeeded).
+//!
+//! ```ignore
+//! struct FirstService;
+//!
+//! impl nexosim_guest::rules::Rule for FirstService {
+//!     fn on_event(event: &str) {
+//!         if event.contains(r#""phase":"after""#) && event.contains(r#""action_type":"asset.create""#) {
+//!             nexosim_guest::rules::dispatch("asset.schedule_maintenance", r#"{"ScheduleMaintenance":{}}"#);
+//!         }
+//!     }
+//! }
+//!
+//! nexosim_guest::export_rule!(FirstService);
+//! ```
+
+pub trait Rule {
+    /// Called once per event with the JSON `RuleEvent`
+    fn on_event(event: &str);
+}
+
+#[cfg(target_arch = "wasm32")]
+unsafe extern "C" {
+    fn emit(ptr: *const u8, len: usize);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe fn emit(_ptr: *const u8, _len: usize) {}
+
+fn emit_json(effect: &str) {
+    unsafe {
+        emit(effect.as_ptr(), effect.len());
+    }
+}
+
+/// Stop the action with `reason`; ignored after the fact
+pub fn reject(reason: &str) {
+    emit_json(&format!(
+        r#"{{"effect":"reject","reason":{}}}"#,
+        json_string(reason)
+    ));
+}
+
+/// Run `action_type` with a JSON payload once the current action succeeded
+pub fn dispatch(action_type: &str, payload_json: &str) {
+    emit_json(&format!(
+        r#"{{"effect":"dispatch","action_type":{},"payload":{}}}"#,
+        json_string(action_type),
+        payload_json
+    ));
+}
+
+/// `s` as a quoted JSON string
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[macro_export]
+macro_rules! export_rule {
+    ($rule:ty) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn on_event(ptr: i32, len: i32) -> i32 {
+            let slice = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
+            let event = std::str::from_utf8(slice).unwrap_or_default();
+            <$rule as $crate::rules::Rule>::on_event(event);
+            0
+        }
+    };
+}
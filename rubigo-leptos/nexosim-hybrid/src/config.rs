@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(tag = "type")]
 pub enum ComponentType {
     Router,
@@ -20,8 +21,36 @@ pub enum ComponentType {
     },
 }
 
+impl ComponentType {
+    /// Typical power draw of this kind of component, used when a component
+    /// sets no profile of its own
+    pub fn default_power(&self) -> PowerProfile {
+        let (idle_w, max_w) = match self {
+            ComponentType::Router => (150.0, 400.0),
+            ComponentType::Switch => (80.0, 250.0),
+            ComponentType::Firewall => (100.0, 300.0),
+            ComponentType::Server => (200.0, 500.0),
+            ComponentType::Workstation => (60.0, 200.0),
+            ComponentType::AccessPoint => (8.0, 20.0),
+            ComponentType::Phone => (3.0, 7.0),
+            ComponentType::Printer => (10.0, 500.0),
+            ComponentType::PacketGenerator { .. } | ComponentType::WasmModule { .. } => (0.0, 0.0),
+        };
+        PowerProfile { idle_w, max_w }
+    }
+}
+
+/// Watts a component draws when idle and at full load
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PowerProfile {
+    pub idle_w: f64,
+    pub max_w: f64,
+}
+
 /// Where a device is physically located
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(tag = "placement_type")]
 pub enum DevicePlacement {
     Rack {
@@ -36,6 +65,7 @@ pub enum DevicePlacement {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ComponentConfig {
     pub id: u32,
     pub name: String,
@@ -43,9 +73,19 @@ pub struct ComponentConfig {
     pub component_type: ComponentType,
     #[serde(default)]
     pub placement: DevicePlacement,
+    /// Power draw, when it differs from the type's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power: Option<PowerProfile>,
+}
+
+impl ComponentConfig {
+    pub fn power_profile(&self) -> PowerProfile {
+        self.power.unwrap_or_else(|| self.component_type.default_power())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ConnectionConfig {
     pub from: u32,
     pub to: u32,
@@ -172,10 +212,15 @@ pub struct AssetConfig {
     pub storage_location: Option<String>,
     #[serde(default)]
     pub notes: Option<String>,
+    #[serde(default)]
+    pub warranty_start: Option<String>, // YYYY-MM-DD
+    #[serde(default)]
+    pub warranty_end: Option<String>, // YYYY-MM-DD
 }
 
 /// User roles for role-based dashboard and permissions
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum RoleType {
     #[default]
     Employee,
@@ -0,0 +1,265 @@
+// Asset lifecycle and maintenance tracking
+// Lifecycle state machine, warranty queries, maintenance tickets and the
+// per-asset transition history
+
+pub use actions::types::LifecycleState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::calendar::{Meeting, MeetingType, RecurrenceFrequency};
+use super::geo::NetworkAsset;
+use super::versions::{self, Data};
+
+/// Recorded change of lifecycle state
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LifecycleTransition {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub asset_id: Thing,
+    pub from: LifecycleState,
+    pub to: LifecycleState,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// ISO 8601 timestamp
+    pub at: String,
+}
+
+/// Progress of a maintenance ticket
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TicketStatus {
+    #[default]
+    Open,
+    InProgress,
+    Closed,
+}
+
+impl std::fmt::Display for TicketStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketStatus::Open => write!(f, "open"),
+            TicketStatus::InProgress => write!(f, "in progress"),
+            TicketStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// Scheduled maintenance work on an asset
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MaintenanceTicket {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub asset_id: Thing,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Date the work is scheduled for (YYYY-MM-DD)
+    pub scheduled_date: String,
+    #[serde(default)]
+    pub status: TicketStatus,
+    pub created_at: String,
+}
+
+impl MaintenanceTicket {
+    /// Key of the record id, for use in URLs
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+
+    /// All-day calendar entry for this ticket
+    pub fn to_meeting(&self, asset_name: &str) -> Meeting {
+        Meeting {
+            id: None,
+            title: format!("Maintenance: {} ({})", self.title, asset_name),
+            description: self.description.clone(),
+            start_time: format!("{}T00:00:00", self.scheduled_date),
+            end_time: format!("{}T23:59:59", self.scheduled_date),
+            all_day: true,
+            meeting_type: MeetingType::Maintenance,
+            recurrence: RecurrenceFrequency::None,
+            recurrence_interval: 1,
+            recurrence_days: Vec::new(),
+            recurrence_until: None,
+            recurrence_count: None,
+            location_id: None,
+            virtual_url: None,
+            organizer_id: None,
+            participant_ids: Vec::new(),
+            timezone: "UTC".to_string(),
+        }
+    }
+}
+
+/// First `YYYY-MM-DD` of an ISO date or timestamp
+fn date_part(value: &str) -> &str {
+    value.get(..10).unwrap_or(value)
+}
+
+pub struct AssetLifecycleRepository;
+
+impl AssetLifecycleRepository {
+    /// Move an asset to a new lifecycle state and record the transition
+    pub async fn transition(
+        db: &Surreal<Db>,
+        asset_id: &str,
+        to: LifecycleState,
+        note: Option<String>,
+        now: &str,
+    ) -> Result<NetworkAsset> {
+        let mut asset: NetworkAsset = db
+            .select(("network_asset", asset_id))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Asset '{}' not found", asset_id))?;
+
+        let from = asset.lifecycle;
+        if !from.can_transition_to(to) {
+            anyhow::bail!("Cannot move asset from {} to {}", from, to);
+        }
+
+        let record = asset
+            .id
+            .take()
+            .unwrap_or_else(|| Thing::from(("network_asset", asset_id)));
+        asset.lifecycle = to;
+        let updated: NetworkAsset = db
+            .update(("network_asset", asset_id))
+            .content(asset)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to update asset '{}'", asset_id))?;
+
+        let _: Option<LifecycleTransition> = db
+            .create("asset_transition")
+            .content(LifecycleTransition {
+                id: None,
+                asset_id: record,
+                from,
+                to,
+                note,
+                at: now.to_string(),
+            })
+            .await?;
+
+        versions::bump(Data::Assets);
+        versions::bump(Data::Maintenance);
+        Ok(updated)
+    }
+
+    /// Transition history for one asset, oldest first
+    pub async fn history(db: &Surreal<Db>, asset_id: &str) -> Result<Vec<LifecycleTransition>> {
+        let sql = "SELECT * FROM asset_transition WHERE asset_id = type::thing('network_asset', $id) ORDER BY at ASC";
+        let mut result = db.query(sql).bind(("id", asset_id.to_string())).await?;
+        let transitions: Vec<LifecycleTransition> = result.take(0)?;
+        Ok(transitions)
+    }
+
+    /// Most recent transitions across all assets, newest first
+    pub async fn recent_transitions(
+        db: &Surreal<Db>,
+        limit: usize,
+    ) -> Result<Vec<LifecycleTransition>> {
+        let mut transitions: Vec<LifecycleTransition> = db.select("asset_transition").await?;
+        transitions.sort_by(|a, b| b.at.cmp(&a.at));
+        transitions.truncate(limit);
+        Ok(transitions)
+    }
+
+    pub async fn create_ticket(
+        db: &Surreal<Db>,
+        ticket: MaintenanceTicket,
+    ) -> Result<MaintenanceTicket> {
+        let created: MaintenanceTicket = db
+            .create("maintenance_ticket")
+            .content(ticket)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create maintenance ticket"))?;
+        versions::bump(Data::Maintenance);
+        Ok(created)
+    }
+
+    /// All tickets ordered by scheduled date
+    pub async fn list_tickets(db: &Surreal<Db>) -> Result<Vec<MaintenanceTicket>> {
+        let mut tickets: Vec<MaintenanceTicket> = db.select("maintenance_ticket").await?;
+        tickets.sort_by(|a, b| a.scheduled_date.cmp(&b.scheduled_date));
+        Ok(tickets)
+    }
+
+    /// Open tickets scheduled on or before `until` (YYYY-MM-DD), overdue included
+    pub async fn upcoming_maintenance(
+        db: &Surreal<Db>,
+        until: &str,
+    ) -> Result<Vec<MaintenanceTicket>> {
+        let tickets = Self::list_tickets(db).await?;
+        Ok(tickets
+            .into_iter()
+            .filter(|t| t.status != TicketStatus::Closed && date_part(&t.scheduled_date) <= until)
+            .collect())
+    }
+
+    pub async fn set_ticket_status(
+        db: &Surreal<Db>,
+        id: &str,
+        status: TicketStatus,
+    ) -> Result<Option<MaintenanceTicket>> {
+        let Some(mut ticket): Option<MaintenanceTicket> =
+            db.select(("maintenance_ticket", id)).await?
+        else {
+            return Ok(None);
+        };
+        ticket.id = None;
+        ticket.status = status;
+        let updated: Option<MaintenanceTicket> = db
+            .update(("maintenance_ticket", id))
+            .content(ticket)
+            .await?;
+        versions::bump(Data::Maintenance);
+        Ok(updated)
+    }
+
+    /// Assets whose warranty ends between `from` and `until` (YYYY-MM-DD)
+    pub async fn expiring_warranties(
+        db: &Surreal<Db>,
+        from: &str,
+        until: &str,
+    ) -> Result<Vec<NetworkAsset>> {
+        let mut assets: Vec<NetworkAsset> = db.select("network_asset").await?;
+        assets.retain(|a| {
+            a.lifecycle != LifecycleState::Retired
+                && a.warranty_end
+                    .as_deref()
+                    .is_some_and(|end| (from..=until).contains(&date_part(end)))
+        });
+        assets.sort_by(|a, b| a.warranty_end.cmp(&b.warranty_end));
+        Ok(assets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticket_becomes_all_day_meeting() {
+        let ticket = MaintenanceTicket {
+            id: None,
+            asset_id: Thing::from(("network_asset", "sw1")),
+            title: "Replace PSU".to_string(),
+            description: None,
+            scheduled_date: "2025-03-14".to_string(),
+            status: TicketStatus::Open,
+            created_at: "2025-03-01T09:00:00Z".to_string(),
+        };
+        let meeting = ticket.to_meeting("HQ-Core-SW-01");
+        assert!(meeting.all_day);
+        assert_eq!(meeting.meeting_type, MeetingType::Maintenance);
+        assert!(meeting.start_time.starts_with("2025-03-14"));
+        assert!(meeting.title.contains("HQ-Core-SW-01"));
+    }
+}
@@ -0,0 +1,536 @@
+// Physical cabling between racked equipment
+// Ports belong to a device or a patch panel, cables join two ports, and a
+// patch panel port passes a signal from its front cable to its rear cable.
+// Tracing cables end to end gives device-to-device links, which project
+// down to logical `ConnectionConfig`s for devices linked to components.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::connections::ConnectionRepository;
+use super::device_links::component_key;
+use super::geo::{Device, GeoRepository};
+use super::versions::{self, Data};
+use crate::config::ConnectionConfig;
+
+/// Physical medium of a port; cables only join matching media
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PortMedia {
+    #[default]
+    Copper,
+    Fiber,
+}
+
+impl std::fmt::Display for PortMedia {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortMedia::Copper => write!(f, "copper"),
+            PortMedia::Fiber => write!(f, "fiber"),
+        }
+    }
+}
+
+impl std::str::FromStr for PortMedia {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "copper" => Ok(PortMedia::Copper),
+            "fiber" | "fibre" => Ok(PortMedia::Fiber),
+            _ => Err(anyhow::anyhow!("Unknown port media '{}'", s)),
+        }
+    }
+}
+
+/// Passive panel in a rack; its ports are pass-through
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PatchPanel {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub rack_id: Thing,
+    pub position_u: u8,
+    pub port_count: u16,
+    #[serde(default)]
+    pub media: PortMedia,
+}
+
+/// A port on a device or patch panel
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Port {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// `device:…` or `patch_panel:…`
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub owner: Thing,
+    pub name: String,
+    #[serde(default)]
+    pub media: PortMedia,
+}
+
+impl Port {
+    pub fn is_panel_port(&self) -> bool {
+        self.owner.tb == "patch_panel"
+    }
+
+    /// Cables the port accepts: front and rear on a panel, one on a device
+    pub fn capacity(&self) -> usize {
+        if self.is_panel_port() {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// A cable between two ports
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Cable {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub a: Thing,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub b: Thing,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub length_m: Option<f32>,
+}
+
+impl Cable {
+    pub fn touches(&self, port: &Thing) -> bool {
+        &self.a == port || &self.b == port
+    }
+
+    /// The end opposite `port`
+    pub fn other_end(&self, port: &Thing) -> Option<&Thing> {
+        if &self.a == port {
+            Some(&self.b)
+        } else if &self.b == port {
+            Some(&self.a)
+        } else {
+            None
+        }
+    }
+}
+
+/// Why a cable cannot be added
+#[derive(Debug, Clone, PartialEq)]
+pub enum CablingError {
+    SamePort,
+    MediaMismatch(PortMedia, PortMedia),
+    PortOccupied(String),
+}
+
+impl std::fmt::Display for CablingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CablingError::SamePort => write!(f, "A cable needs two different ports"),
+            CablingError::MediaMismatch(a, b) => {
+                write!(f, "Cannot cable a {} port to a {} port", a, b)
+            }
+            CablingError::PortOccupied(port) => write!(f, "Port {} has no free connection", port),
+        }
+    }
+}
+
+impl std::error::Error for CablingError {}
+
+/// Cables attached to a port
+pub fn occupancy(port: &Thing, cables: &[Cable]) -> usize {
+    cables.iter().filter(|c| c.touches(port)).count()
+}
+
+/// Check a new cable between `a` and `b` against the existing cables
+pub fn validate_cable(
+    a: &Port,
+    b: &Port,
+    cables: &[Cable],
+) -> std::result::Result<(), CablingError> {
+    let (Some(a_id), Some(b_id)) = (a.id.as_ref(), b.id.as_ref()) else {
+        return Err(CablingError::SamePort);
+    };
+    if a_id == b_id {
+        return Err(CablingError::SamePort);
+    }
+    if a.media != b.media {
+        return Err(CablingError::MediaMismatch(a.media, b.media));
+    }
+    for (port, id) in [(a, a_id), (b, b_id)] {
+        if occupancy(id, cables) >= port.capacity() {
+            return Err(CablingError::PortOccupied(format!(
+                "{} ({})",
+                port.name, port.owner
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Device ports joined end to end, following cables through patch panels
+///
+/// Each pair appears once. Runs that end on a panel port with nothing on
+/// its far side are incomplete and left out.
+pub fn trace_links(ports: &[Port], cables: &[Cable]) -> Vec<(Port, Port)> {
+    let by_id: HashMap<String, &Port> = ports
+        .iter()
+        .filter_map(|p| Some((p.id.as_ref()?.to_string(), p)))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for start in ports.iter().filter(|p| !p.is_panel_port()) {
+        let Some(start_id) = start.id.as_ref() else {
+            continue;
+        };
+        let Some(first) = cables.iter().position(|c| c.touches(start_id)) else {
+            continue;
+        };
+
+        // Walk cable, port, cable, ... until a device port is reached
+        let mut cable = first;
+        let mut at = start_id.clone();
+        let mut visited = HashSet::from([first]);
+        let end = loop {
+            let Some(next) = cables[cable].other_end(&at).cloned() else {
+                break None;
+            };
+            let Some(port) = by_id.get(&next.to_string()) else {
+                break None;
+            };
+            if !port.is_panel_port() {
+                break Some(*port);
+            }
+            let onward = (0..cables.len())
+                .find(|&i| i != cable && !visited.contains(&i) && cables[i].touches(&next));
+            let Some(onward) = onward else { break None };
+            visited.insert(onward);
+            cable = onward;
+            at = next;
+        };
+
+        if let Some(end) = end {
+            let (x, y) = (
+                start_id.to_string(),
+                end.id.as_ref().map(|t| t.to_string()).unwrap_or_default(),
+            );
+            let key = if x < y { (x, y) } else { (y, x) };
+            if seen.insert(key) {
+                links.push((start.clone(), end.clone()));
+            }
+        }
+    }
+    links
+}
+
+/// Logical connections implied by the cabling between component-linked devices
+pub fn project_connections(
+    ports: &[Port],
+    cables: &[Cable],
+    devices: &[Device],
+) -> Vec<ConnectionConfig> {
+    let components: HashMap<String, u32> = devices
+        .iter()
+        .filter_map(|d| {
+            let component = d.component_id.as_ref().and_then(component_key)?;
+            Some((d.id.as_ref()?.to_string(), component))
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    trace_links(ports, cables)
+        .into_iter()
+        .filter_map(|(a, b)| {
+            let from = *components.get(&a.owner.to_string())?;
+            let to = *components.get(&b.owner.to_string())?;
+            (from != to && seen.insert((from.min(to), from.max(to))))
+                .then_some(ConnectionConfig { from, to })
+        })
+        .collect()
+}
+
+pub struct CablingRepository;
+
+impl CablingRepository {
+    /// Create a patch panel together with its numbered ports
+    pub async fn create_panel(db: &Surreal<Db>, panel: PatchPanel) -> Result<PatchPanel> {
+        let created: PatchPanel = db
+            .create("patch_panel")
+            .content(panel)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create patch panel"))?;
+        let owner = created
+            .id
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Patch panel created without id"))?;
+        for n in 1..=created.port_count {
+            Self::create_port(
+                db,
+                Port {
+                    id: None,
+                    owner: owner.clone(),
+                    name: n.to_string(),
+                    media: created.media,
+                },
+            )
+            .await?;
+        }
+        versions::bump(Data::Cabling);
+        Ok(created)
+    }
+
+    pub async fn list_all_panels(db: &Surreal<Db>) -> Result<Vec<PatchPanel>> {
+        let mut panels: Vec<PatchPanel> = db.select("patch_panel").await?;
+        panels.sort_by_key(|p| p.position_u);
+        Ok(panels)
+    }
+
+    /// Delete a panel, its ports and any cables plugged into them
+    pub async fn delete_panel(db: &Surreal<Db>, id: &str) -> Result<Option<PatchPanel>> {
+        let owner = Thing::from(("patch_panel", id));
+        for port in Self::list_all_ports(db)
+            .await?
+            .into_iter()
+            .filter(|p| p.owner == owner)
+        {
+            if let Some(port_id) = port.id {
+                Self::delete_port(db, &port_id.id.to_raw()).await?;
+            }
+        }
+        let deleted: Option<PatchPanel> = db.delete(("patch_panel", id)).await?;
+        versions::bump(Data::Cabling);
+        Ok(deleted)
+    }
+
+    pub async fn create_port(db: &Surreal<Db>, port: Port) -> Result<Port> {
+        let created: Port = db
+            .create("port")
+            .content(port)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create port"))?;
+        versions::bump(Data::Cabling);
+        Ok(created)
+    }
+
+    pub async fn list_all_ports(db: &Surreal<Db>) -> Result<Vec<Port>> {
+        let mut ports: Vec<Port> = db.select("port").await?;
+        ports.sort_by(|a, b| {
+            a.owner
+                .to_string()
+                .cmp(&b.owner.to_string())
+                .then(natural_cmp(&a.name, &b.name))
+        });
+        Ok(ports)
+    }
+
+    /// Delete a port and unplug its cables
+    pub async fn delete_port(db: &Surreal<Db>, id: &str) -> Result<Option<Port>> {
+        db.query("DELETE cable WHERE a = type::thing('port', $id) OR b = type::thing('port', $id)")
+            .bind(("id", id.to_string()))
+            .await?;
+        let deleted: Option<Port> = db.delete(("port", id)).await?;
+        versions::bump(Data::Cabling);
+        Ok(deleted)
+    }
+
+    pub async fn list_all_cables(db: &Surreal<Db>) -> Result<Vec<Cable>> {
+        let cables: Vec<Cable> = db.select("cable").await?;
+        Ok(cables)
+    }
+
+    /// Add a cable after checking both ports exist, match and have room
+    ///
+    /// Validation failures are returned as [`CablingError`] inside the
+    /// `anyhow::Error` so callers can tell them from database errors.
+    pub async fn create_cable(db: &Surreal<Db>, cable: Cable) -> Result<Cable> {
+        let a: Option<Port> = db.select(("port", cable.a.id.to_raw())).await?;
+        let b: Option<Port> = db.select(("port", cable.b.id.to_raw())).await?;
+        let a = a.ok_or_else(|| anyhow::anyhow!("Port '{}' not found", cable.a))?;
+        let b = b.ok_or_else(|| anyhow::anyhow!("Port '{}' not found", cable.b))?;
+        validate_cable(&a, &b, &Self::list_all_cables(db).await?)?;
+
+        let created: Cable = db
+            .create("cable")
+            .content(cable)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create cable"))?;
+        versions::bump(Data::Cabling);
+        Ok(created)
+    }
+
+    /// Change a cable's label or length; its ends stay where they are
+    pub async fn update_cable(db: &Surreal<Db>, id: &str, cable: Cable) -> Result<Option<Cable>> {
+        let updated: Option<Cable> = db.update(("cable", id)).content(cable).await?;
+        versions::bump(Data::Cabling);
+        Ok(updated)
+    }
+
+    pub async fn delete_cable(db: &Surreal<Db>, id: &str) -> Result<Option<Cable>> {
+        let deleted: Option<Cable> = db.delete(("cable", id)).await?;
+        versions::bump(Data::Cabling);
+        Ok(deleted)
+    }
+
+    /// Connections implied by the current cabling
+    pub async fn project(db: &Surreal<Db>) -> Result<Vec<ConnectionConfig>> {
+        let ports = Self::list_all_ports(db).await?;
+        let cables = Self::list_all_cables(db).await?;
+        let devices = GeoRepository::list_all_devices(db).await?;
+        Ok(project_connections(&ports, &cables, &devices))
+    }
+
+    /// Create the projected connections that do not exist yet, in either direction
+    pub async fn sync_connections(db: &Surreal<Db>) -> Result<Vec<ConnectionConfig>> {
+        let existing: HashSet<(u32, u32)> = ConnectionRepository::get_all(db)
+            .await?
+            .into_iter()
+            .flat_map(|c| [(c.from, c.to), (c.to, c.from)])
+            .collect();
+        let mut created = Vec::new();
+        for connection in Self::project(db).await? {
+            if !existing.contains(&(connection.from, connection.to)) {
+                created.push(ConnectionRepository::create(db, connection).await?);
+            }
+        }
+        Ok(created)
+    }
+}
+
+/// Order port names so "2" sorts before "10"
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.parse::<u32>(), b.parse::<u32>()) {
+        (Ok(x), Ok(y)) => x.cmp(&y),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::device_links::component_thing;
+
+    fn port(key: &str, owner: (&str, &str), media: PortMedia) -> Port {
+        Port {
+            id: Some(Thing::from(("port", key))),
+            owner: Thing::from(owner),
+            name: key.to_string(),
+            media,
+        }
+    }
+
+    fn cable(a: &str, b: &str) -> Cable {
+        Cable {
+            id: None,
+            a: Thing::from(("port", a)),
+            b: Thing::from(("port", b)),
+            label: None,
+            length_m: None,
+        }
+    }
+
+    fn device(key: &str, component: Option<u32>) -> Device {
+        Device {
+            id: Some(Thing::from(("device", key))),
+            name: key.to_string(),
+            rack_id: Thing::from(("rack", "r1")),
+            position_u: 1,
+            component_id: component.map(component_thing),
+            height_u: 1,
+            power_w: 0,
+        }
+    }
+
+    #[test]
+    fn device_ports_take_one_cable_panel_ports_two() {
+        let sw = port("sw1-1", ("device", "sw1"), PortMedia::Copper);
+        let rt = port("rt1-1", ("device", "rt1"), PortMedia::Copper);
+        let pp = port("pp-1", ("patch_panel", "pp"), PortMedia::Copper);
+
+        let cables = vec![cable("sw1-1", "pp-1")];
+        assert_eq!(
+            validate_cable(&sw, &rt, &cables),
+            Err(CablingError::PortOccupied("sw1-1 (device:sw1)".into()))
+        );
+        assert_eq!(validate_cable(&rt, &pp, &cables), Ok(()));
+
+        let full = vec![cable("sw1-1", "pp-1"), cable("pp-1", "rt1-1")];
+        let other = port("fw-1", ("device", "fw"), PortMedia::Copper);
+        assert!(matches!(
+            validate_cable(&other, &pp, &full),
+            Err(CablingError::PortOccupied(_))
+        ));
+        assert_eq!(validate_cable(&sw, &sw, &[]), Err(CablingError::SamePort));
+    }
+
+    #[test]
+    fn rejects_mixed_media() {
+        let copper = port("a", ("device", "x"), PortMedia::Copper);
+        let fiber = port("b", ("device", "y"), PortMedia::Fiber);
+        assert_eq!(
+            validate_cable(&copper, &fiber, &[]),
+            Err(CablingError::MediaMismatch(
+                PortMedia::Copper,
+                PortMedia::Fiber
+            ))
+        );
+    }
+
+    #[test]
+    fn traces_through_patch_panels() {
+        let ports = vec![
+            port("sw1-1", ("device", "sw1"), PortMedia::Copper),
+            port("pp1-4", ("patch_panel", "pp1"), PortMedia::Copper),
+            port("pp2-4", ("patch_panel", "pp2"), PortMedia::Copper),
+            port("rt1-1", ("device", "rt1"), PortMedia::Copper),
+            port("srv-1", ("device", "srv"), PortMedia::Copper),
+            port("pp2-5", ("patch_panel", "pp2"), PortMedia::Copper),
+        ];
+        let cables = vec![
+            cable("sw1-1", "pp1-4"),
+            cable("pp1-4", "pp2-4"),
+            cable("pp2-4", "rt1-1"),
+            // Dangling run: panel port with nothing behind it
+            cable("srv-1", "pp2-5"),
+        ];
+        let links = trace_links(&ports, &cables);
+        assert_eq!(links.len(), 1);
+        let names = [links[0].0.name.as_str(), links[0].1.name.as_str()];
+        assert!(names.contains(&"sw1-1") && names.contains(&"rt1-1"));
+    }
+
+    #[test]
+    fn projects_only_linked_devices() {
+        let ports = vec![
+            port("sw1-1", ("device", "sw1"), PortMedia::Copper),
+            port("rt1-1", ("device", "rt1"), PortMedia::Copper),
+            port("sw1-2", ("device", "sw1"), PortMedia::Copper),
+            port("srv-1", ("device", "srv"), PortMedia::Copper),
+        ];
+        let cables = vec![cable("sw1-1", "rt1-1"), cable("sw1-2", "srv-1")];
+        let devices = vec![
+            device("sw1", Some(1)),
+            device("rt1", Some(2)),
+            device("srv", None),
+        ];
+
+        let connections = project_connections(&ports, &cables, &devices);
+        assert_eq!(connections.len(), 1);
+        let pair = (
+            connections[0].from.min(connections[0].to),
+            connections[0].from.max(connections[0].to),
+        );
+        assert_eq!(pair, (1, 2));
+    }
+}
@@ -4,8 +4,11 @@
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 
+use super::versions::{self, Data};
+
 /// Recurrence frequency for repeating meetings
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum RecurrenceFrequency {
     #[default]
     None,
@@ -41,6 +44,7 @@ impl From<&str> for RecurrenceFrequency {
 
 /// Meeting type for categorization
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum MeetingType {
     #[default]
     Meeting,
@@ -53,6 +57,12 @@ pub enum MeetingType {
     Conference,
     Review,
     Planning,
+    /// Scheduled asset maintenance
+    Maintenance,
+    /// Day desk booking
+    DeskBooking,
+    /// Someone's turn in an on-call rotation
+    OnCall,
 }
 
 impl std::fmt::Display for MeetingType {
@@ -68,6 +78,9 @@ impl std::fmt::Display for MeetingType {
             MeetingType::Conference => write!(f, "conference"),
             MeetingType::Review => write!(f, "review"),
             MeetingType::Planning => write!(f, "planning"),
+            MeetingType::Maintenance => write!(f, "maintenance"),
+            MeetingType::DeskBooking => write!(f, "desk booking"),
+            MeetingType::OnCall => write!(f, "on-call"),
         }
     }
 }
@@ -84,6 +97,9 @@ impl From<&str> for MeetingType {
             "conference" => MeetingType::Conference,
             "review" => MeetingType::Review,
             "planning" => MeetingType::Planning,
+            "maintenance" => MeetingType::Maintenance,
+            "desk booking" | "deskbooking" => MeetingType::DeskBooking,
+            "on-call" | "oncall" => MeetingType::OnCall,
             _ => MeetingType::Meeting,
         }
     }
@@ -91,7 +107,9 @@ impl From<&str> for MeetingType {
 
 /// A calendar meeting/event
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Meeting {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub title: String,
     pub description: Option<String>,
@@ -127,6 +145,7 @@ pub struct Meeting {
 
     /// Location - space ID for physical location
     #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub location_id: Option<Thing>,
     /// Virtual meeting URL
     #[serde(default)]
@@ -134,9 +153,11 @@ pub struct Meeting {
 
     /// Organizer person ID
     #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub organizer_id: Option<Thing>,
     /// Participant person IDs
     #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
     pub participant_ids: Vec<Thing>,
 
     /// Timezone (e.g., "America/New_York")
@@ -169,6 +190,28 @@ impl CalendarRepository {
         meeting: Meeting,
     ) -> anyhow::Result<Meeting> {
         let created: Option<Meeting> = db.create("meeting").content(meeting).await?;
+        versions::bump(Data::Meetings);
         created.ok_or_else(|| anyhow::anyhow!("Failed to create meeting"))
     }
+
+    /// Replace an existing meeting
+    pub async fn update(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+        meeting: Meeting,
+    ) -> anyhow::Result<Option<Meeting>> {
+        let updated: Option<Meeting> = db.update(("meeting", id)).content(meeting).await?;
+        versions::bump(Data::Meetings);
+        Ok(updated)
+    }
+
+    /// Delete a meeting
+    pub async fn delete(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+    ) -> anyhow::Result<Option<Meeting>> {
+        let deleted: Option<Meeting> = db.delete(("meeting", id)).await?;
+        versions::bump(Data::Meetings);
+        Ok(deleted)
+    }
 }
@@ -0,0 +1,170 @@
+// Capacity
+// Rack units, power and ports in use, summed by the database with grouped
+// queries, alongside the power and cooling budget set for each space. A daily
+// snapshot of each figure keeps the history that exhaustion dates are
+// projected from.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+/// Power and cooling available to a space, in kilowatts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpaceBudget {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub space_id: Thing,
+    pub power_kw: f64,
+    pub cooling_kw: f64,
+}
+
+/// What the devices in one rack add up to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RackLoad {
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub rack_id: Thing,
+    /// Units taken by devices and patch panels
+    pub used_u: u64,
+    pub power_w: u64,
+    pub devices: u64,
+}
+
+/// Ports on one device and how many have a cable plugged in
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PortLoad {
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub owner: Thing,
+    pub ports: u64,
+    pub cabled: u64,
+}
+
+/// Which figure a snapshot records
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CapacityKind {
+    /// Rack units in a rack
+    RackU,
+    /// Kilowatts drawn in a space
+    Power,
+    /// Kilowatts of heat given off in a space
+    Cooling,
+    /// Cabled ports on a device
+    Ports,
+}
+
+impl CapacityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CapacityKind::RackU => "rack_u",
+            CapacityKind::Power => "power",
+            CapacityKind::Cooling => "cooling",
+            CapacityKind::Ports => "ports",
+        }
+    }
+}
+
+/// One figure for one rack, space or device on one day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CapacitySnapshot {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// The rack, space or device, as `table:key`
+    pub subject: String,
+    pub kind: CapacityKind,
+    /// Days since the Unix epoch
+    pub day: i64,
+    pub used: f64,
+    pub total: f64,
+}
+
+pub struct CapacityRepository;
+
+impl CapacityRepository {
+    /// Units, power and device count per rack; racks without devices or
+    /// panels are left out
+    pub async fn rack_loads(db: &Surreal<Db>) -> Result<Vec<RackLoad>> {
+        let sql = "SELECT rack_id, math::sum(height_u ?? 1) AS used_u, math::sum(power_w ?? 0) AS power_w, \
+                   count() AS devices FROM device GROUP BY rack_id; \
+                   SELECT rack_id, count() AS panels FROM patch_panel GROUP BY rack_id;";
+        let mut result = db.query(sql).await?;
+        let mut loads: Vec<RackLoad> = result.take(0)?;
+        #[derive(Deserialize)]
+        struct PanelCount {
+            rack_id: Thing,
+            panels: u64,
+        }
+        let panels: Vec<PanelCount> = result.take(1)?;
+        for PanelCount { rack_id, panels } in panels {
+            match loads.iter_mut().find(|l| l.rack_id == rack_id) {
+                Some(load) => load.used_u += panels,
+                None => loads.push(RackLoad {
+                    rack_id,
+                    used_u: panels,
+                    power_w: 0,
+                    devices: 0,
+                }),
+            }
+        }
+        Ok(loads)
+    }
+
+    /// Port counts per device that has ports
+    pub async fn port_loads(db: &Surreal<Db>) -> Result<Vec<PortLoad>> {
+        let sql = "LET $cabled = array::union((SELECT VALUE a FROM cable), (SELECT VALUE b FROM cable)); \
+                   SELECT owner, count() AS ports, count(id INSIDE $cabled) AS cabled FROM port \
+                   WHERE record::tb(owner) = 'device' GROUP BY owner;";
+        let mut result = db.query(sql).await?;
+        Ok(result.take(1)?)
+    }
+
+    pub async fn budgets(db: &Surreal<Db>) -> Result<Vec<SpaceBudget>> {
+        Ok(db.select("space_budget").await?)
+    }
+
+    /// Set the budget for `budget.space_id`, replacing any earlier one
+    pub async fn set_budget(db: &Surreal<Db>, budget: SpaceBudget) -> Result<SpaceBudget> {
+        let mut budget = budget;
+        budget.id = None;
+        let key = budget.space_id.id.to_raw();
+        let saved: Option<SpaceBudget> = db.upsert(("space_budget", key)).content(budget).await?;
+        versions::bump(Data::Capacity);
+        saved.ok_or_else(|| anyhow::anyhow!("Failed to save space budget"))
+    }
+
+    /// Record `snapshots`, replacing any taken earlier the same day
+    pub async fn record(db: &Surreal<Db>, snapshots: Vec<CapacitySnapshot>) -> Result<usize> {
+        let count = snapshots.len();
+        for mut snapshot in snapshots {
+            snapshot.id = None;
+            let key = format!(
+                "{}|{}|{}",
+                snapshot.subject,
+                snapshot.kind.as_str(),
+                snapshot.day
+            );
+            let _saved: Option<CapacitySnapshot> = db
+                .upsert(("capacity_snapshot", key))
+                .content(snapshot)
+                .await?;
+        }
+        versions::bump(Data::Capacity);
+        Ok(count)
+    }
+
+    /// Snapshots taken on or after `since`, oldest first
+    pub async fn snapshots(db: &Surreal<Db>, since: i64) -> Result<Vec<CapacitySnapshot>> {
+        let sql = "SELECT * FROM capacity_snapshot WHERE day >= $since ORDER BY day";
+        let mut result = db.query(sql).bind(("since", since)).await?;
+        Ok(result.take(0)?)
+    }
+}
@@ -0,0 +1,523 @@
+// Chat
+// Conversations between people and their messages. Participants and senders
+// are person links; the API and UI work with names, so conversations are
+// handed out as views with names resolved against the people table
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::geo::{GeoRepository, Person};
+
+/// Longest message body accepted, in characters
+pub const MAX_MESSAGE_LEN: usize = 4000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Conversation {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// Optional name; direct messages leave it unset
+    pub title: Option<String>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    pub participant_ids: Vec<Thing>,
+    /// Time of the last message (or creation), for ordering
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChatMessage {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub conversation_id: Thing,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub sender_id: Thing,
+    pub body: String,
+    pub sent_at: String,
+}
+
+/// A conversation as the chat panel lists it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConversationView {
+    /// Record key
+    pub id: String,
+    /// Title, or the other participants' names for direct messages
+    pub title: String,
+    pub participants: Vec<String>,
+    pub last_message: Option<String>,
+    pub updated_at: String,
+}
+
+/// A message with its sender's name
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MessageView {
+    /// Record key
+    pub id: String,
+    /// Conversation record key
+    pub conversation_id: String,
+    pub sender: String,
+    pub body: String,
+    pub sent_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatError {
+    UnknownPerson(String),
+    NotParticipant(String),
+    EmptyMessage,
+    MessageTooLong(usize),
+    NoParticipants,
+}
+
+impl std::fmt::Display for ChatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChatError::UnknownPerson(name) => write!(f, "No person named '{}'", name),
+            ChatError::NotParticipant(name) => write!(f, "{} is not in this conversation", name),
+            ChatError::EmptyMessage => write!(f, "Message is empty"),
+            ChatError::MessageTooLong(len) => {
+                write!(
+                    f,
+                    "Message is {} characters; the limit is {}",
+                    len, MAX_MESSAGE_LEN
+                )
+            }
+            ChatError::NoParticipants => write!(f, "A conversation needs at least two people"),
+        }
+    }
+}
+
+impl std::error::Error for ChatError {}
+
+/// Trimmed message body, or why it cannot be sent
+pub fn check_body(body: &str) -> std::result::Result<&str, ChatError> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(ChatError::EmptyMessage);
+    }
+    let len = body.chars().count();
+    if len > MAX_MESSAGE_LEN {
+        return Err(ChatError::MessageTooLong(len));
+    }
+    Ok(body)
+}
+
+fn key(thing: &Thing) -> String {
+    thing.id.to_raw()
+}
+
+/// Person names keyed by record id
+fn names(people: &[Person]) -> HashMap<String, String> {
+    people
+        .iter()
+        .filter_map(|p| Some((p.id.as_ref()?.to_string(), p.name.clone())))
+        .collect()
+}
+
+fn person_named(people: &[Person], name: &str) -> std::result::Result<Thing, ChatError> {
+    people
+        .iter()
+        .find(|p| p.name == name)
+        .and_then(|p| p.id.clone())
+        .ok_or_else(|| ChatError::UnknownPerson(name.to_string()))
+}
+
+impl Conversation {
+    pub fn includes(&self, person_id: &Thing) -> bool {
+        self.participant_ids.contains(person_id)
+    }
+
+    /// Same people, ignoring order, and no title
+    pub fn is_direct_between(&self, participants: &[Thing]) -> bool {
+        self.title.is_none()
+            && self.participant_ids.len() == participants.len()
+            && participants.iter().all(|p| self.includes(p))
+    }
+
+    pub fn view_for(
+        &self,
+        viewer: &str,
+        people: &[Person],
+        last_message: Option<&ChatMessage>,
+    ) -> ConversationView {
+        let names = names(people);
+        let participants: Vec<String> = self
+            .participant_ids
+            .iter()
+            .filter_map(|id| names.get(&id.to_string()).cloned())
+            .collect();
+        let title = self.title.clone().unwrap_or_else(|| {
+            participants
+                .iter()
+                .filter(|name| name.as_str() != viewer)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        ConversationView {
+            id: self.id.as_ref().map(key).unwrap_or_default(),
+            title,
+            participants,
+            last_message: last_message.map(|m| m.body.clone()),
+            updated_at: self.updated_at.clone(),
+        }
+    }
+}
+
+impl ChatMessage {
+    pub fn view(&self, people: &[Person]) -> MessageView {
+        MessageView {
+            id: self.id.as_ref().map(key).unwrap_or_default(),
+            conversation_id: key(&self.conversation_id),
+            sender: names(people)
+                .remove(&self.sender_id.to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            body: self.body.clone(),
+            sent_at: self.sent_at.clone(),
+        }
+    }
+}
+
+/// Resolve a seeded conversation's names to people
+///
+/// Returns the participant links and `(sender, sent_at, body)` per message;
+/// senders must be participants.
+pub fn resolve_seed(
+    conversation: &scenario_loader::Conversation,
+    people: &[Person],
+) -> std::result::Result<(Vec<Thing>, Vec<(Thing, String, String)>), ChatError> {
+    let participants = conversation
+        .participants
+        .iter()
+        .map(|name| person_named(people, name))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    if participants.len() < 2 {
+        return Err(ChatError::NoParticipants);
+    }
+    let messages = conversation
+        .messages
+        .iter()
+        .map(|m| {
+            let sender = person_named(people, &m.from)?;
+            if !participants.contains(&sender) {
+                return Err(ChatError::NotParticipant(m.from.clone()));
+            }
+            Ok((sender, m.sent_at.clone(), m.body.clone()))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok((participants, messages))
+}
+
+pub struct ChatRepository;
+
+impl ChatRepository {
+    pub async fn get_conversation(db: &Surreal<Db>, id: &str) -> Result<Option<Conversation>> {
+        let conversation: Option<Conversation> = db.select(("conversation", id)).await?;
+        Ok(conversation)
+    }
+
+    /// Conversations `persona` takes part in, most recent first
+    pub async fn conversations_for(
+        db: &Surreal<Db>,
+        persona: &str,
+    ) -> Result<Vec<ConversationView>> {
+        let people = GeoRepository::list_all_people(db).await?;
+        let me = person_named(&people, persona)?;
+        let mut conversations: Vec<Conversation> = db.select("conversation").await?;
+        conversations.retain(|c| c.includes(&me));
+        conversations.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        let mut views = Vec::with_capacity(conversations.len());
+        for conversation in &conversations {
+            let last = match &conversation.id {
+                Some(id) => Self::raw_messages(db, &key(id)).await?.pop(),
+                None => None,
+            };
+            views.push(conversation.view_for(persona, &people, last.as_ref()));
+        }
+        Ok(views)
+    }
+
+    /// Start a conversation, or return the existing direct one between the same people
+    pub async fn start(
+        db: &Surreal<Db>,
+        persona: &str,
+        others: &[String],
+        title: Option<String>,
+        now: &str,
+    ) -> Result<ConversationView> {
+        let people = GeoRepository::list_all_people(db).await?;
+        let mut participant_ids = vec![person_named(&people, persona)?];
+        for name in others {
+            let id = person_named(&people, name)?;
+            if !participant_ids.contains(&id) {
+                participant_ids.push(id);
+            }
+        }
+        if participant_ids.len() < 2 {
+            return Err(ChatError::NoParticipants.into());
+        }
+        let title = title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+
+        if title.is_none() {
+            let existing: Vec<Conversation> = db.select("conversation").await?;
+            if let Some(found) = existing
+                .iter()
+                .find(|c| c.is_direct_between(&participant_ids))
+            {
+                return Ok(found.view_for(persona, &people, None));
+            }
+        }
+
+        let created: Conversation = db
+            .create("conversation")
+            .content(Conversation {
+                id: None,
+                title,
+                participant_ids,
+                updated_at: now.to_string(),
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create conversation"))?;
+        Ok(created.view_for(persona, &people, None))
+    }
+
+    async fn raw_messages(db: &Surreal<Db>, conversation: &str) -> Result<Vec<ChatMessage>> {
+        let sql = "SELECT * FROM chat_message WHERE conversation_id = type::thing('conversation', $id) ORDER BY sent_at";
+        let mut result = db.query(sql).bind(("id", conversation.to_string())).await?;
+        let messages: Vec<ChatMessage> = result.take(0)?;
+        Ok(messages)
+    }
+
+    /// Messages of a conversation, oldest first; `None` if the conversation
+    /// does not exist or `persona` is not in it
+    pub async fn messages(
+        db: &Surreal<Db>,
+        conversation: &str,
+        persona: &str,
+    ) -> Result<Option<Vec<MessageView>>> {
+        let people = GeoRepository::list_all_people(db).await?;
+        let me = person_named(&people, persona)?;
+        match Self::get_conversation(db, conversation).await? {
+            Some(c) if c.includes(&me) => {}
+            _ => return Ok(None),
+        }
+        let messages = Self::raw_messages(db, conversation).await?;
+        Ok(Some(messages.iter().map(|m| m.view(&people)).collect()))
+    }
+
+    /// Post a message as `persona`
+    ///
+    /// Returns the stored message and the conversation's participants, who
+    /// are the ones to deliver it to; `None` if the conversation does not exist.
+    pub async fn send(
+        db: &Surreal<Db>,
+        conversation: &str,
+        persona: &str,
+        body: &str,
+        now: &str,
+    ) -> Result<Option<(MessageView, Vec<String>)>> {
+        let body = check_body(body)?;
+        let people = GeoRepository::list_all_people(db).await?;
+        let sender_id = person_named(&people, persona)?;
+        let Some(mut conv) = Self::get_conversation(db, conversation).await? else {
+            return Ok(None);
+        };
+        if !conv.includes(&sender_id) {
+            return Err(ChatError::NotParticipant(persona.to_string()).into());
+        }
+        let Some(conversation_id) = conv.id.take() else {
+            return Ok(None);
+        };
+
+        let message: ChatMessage = db
+            .create("chat_message")
+            .content(ChatMessage {
+                id: None,
+                conversation_id,
+                sender_id,
+                body: body.to_string(),
+                sent_at: now.to_string(),
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to store message"))?;
+
+        conv.updated_at = now.to_string();
+        let _: Option<Conversation> = db
+            .update(("conversation", conversation))
+            .content(conv.clone())
+            .await?;
+
+        let names = names(&people);
+        let recipients = conv
+            .participant_ids
+            .iter()
+            .filter_map(|id| names.get(&id.to_string()).cloned())
+            .collect();
+        Ok(Some((message.view(&people), recipients)))
+    }
+
+    /// Seed scenario chat history; skipped when any conversation exists
+    ///
+    /// Conversations naming unknown people are logged and skipped.
+    pub async fn seed_conversations(
+        db: &Surreal<Db>,
+        seed: &[scenario_loader::Conversation],
+    ) -> Result<usize> {
+        let existing: Vec<Conversation> = db.select("conversation").await?;
+        if !existing.is_empty() {
+            return Ok(0);
+        }
+        let people = GeoRepository::list_all_people(db).await?;
+        let mut seeded = 0;
+        for conversation in seed {
+            let (participant_ids, messages) = match resolve_seed(conversation, &people) {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    tracing::warn!("Skipping seeded conversation: {}", e);
+                    continue;
+                }
+            };
+            let updated_at = messages
+                .iter()
+                .map(|(_, at, _)| at.clone())
+                .max()
+                .unwrap_or_default();
+            let created: Conversation = db
+                .create("conversation")
+                .content(Conversation {
+                    id: None,
+                    title: conversation.title.clone(),
+                    participant_ids,
+                    updated_at,
+                })
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Failed to create conversation"))?;
+            let Some(conversation_id) = created.id else {
+                continue;
+            };
+            for (sender_id, sent_at, body) in messages {
+                let _: Option<ChatMessage> = db
+                    .create("chat_message")
+                    .content(ChatMessage {
+                        id: None,
+                        conversation_id: conversation_id.clone(),
+                        sender_id,
+                        body,
+                        sent_at,
+                    })
+                    .await?;
+            }
+            seeded += 1;
+        }
+        Ok(seeded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(key: &str, name: &str) -> Person {
+        Person {
+            id: Some(Thing::from(("person", key))),
+            name: name.into(),
+            email: String::new(),
+            title: String::new(),
+            department: String::new(),
+            site_id: Thing::from(("site", "hq")),
+            space_id: None,
+            manager_id: None,
+            role: Default::default(),
+            photo: None,
+            bio: None,
+            desk_phone: None,
+            cell_phone: None,
+            photo_data: None,
+        }
+    }
+
+    fn seed(participants: &[&str], from: &str) -> scenario_loader::Conversation {
+        scenario_loader::Conversation {
+            title: None,
+            participants: participants.iter().map(|p| p.to_string()).collect(),
+            messages: vec![scenario_loader::Message {
+                from: from.into(),
+                sent_at: "2025-01-06 09:15:00 UTC".into(),
+                body: "Morning".into(),
+            }],
+        }
+    }
+
+    #[test]
+    fn body_is_trimmed_and_bounded() {
+        assert_eq!(check_body("  hi \n"), Ok("hi"));
+        assert_eq!(check_body("   "), Err(ChatError::EmptyMessage));
+        let long = "x".repeat(MAX_MESSAGE_LEN + 1);
+        assert_eq!(
+            check_body(&long),
+            Err(ChatError::MessageTooLong(MAX_MESSAGE_LEN + 1))
+        );
+    }
+
+    #[test]
+    fn direct_titles_name_the_other_side() {
+        let people = vec![
+            person("ann", "Ann"),
+            person("bob", "Bob"),
+            person("cat", "Cat"),
+        ];
+        let ids = vec![
+            Thing::from(("person", "ann")),
+            Thing::from(("person", "bob")),
+        ];
+        let direct = Conversation {
+            id: Some(Thing::from(("conversation", "c1"))),
+            title: None,
+            participant_ids: ids.clone(),
+            updated_at: String::new(),
+        };
+
+        assert_eq!(direct.view_for("Ann", &people, None).title, "Bob");
+        assert_eq!(direct.view_for("Bob", &people, None).title, "Ann");
+        assert!(direct.is_direct_between(&[ids[1].clone(), ids[0].clone()]));
+        assert!(!direct.is_direct_between(&[ids[0].clone()]));
+    }
+
+    #[test]
+    fn seeds_resolve_names() {
+        let people = vec![
+            person("ann", "Ann"),
+            person("bob", "Bob"),
+            person("cat", "Cat"),
+        ];
+
+        let (participants, messages) =
+            resolve_seed(&seed(&["Ann", "Bob"], "Bob"), &people).unwrap();
+        assert_eq!(participants.len(), 2);
+        assert_eq!(messages[0].0, Thing::from(("person", "bob")));
+
+        assert_eq!(
+            resolve_seed(&seed(&["Ann", "Zed"], "Ann"), &people),
+            Err(ChatError::UnknownPerson("Zed".into()))
+        );
+        assert_eq!(
+            resolve_seed(&seed(&["Ann", "Bob"], "Cat"), &people),
+            Err(ChatError::NotParticipant("Cat".into()))
+        );
+        assert_eq!(
+            resolve_seed(&seed(&["Ann"], "Ann"), &people),
+            Err(ChatError::NoParticipants)
+        );
+    }
+}
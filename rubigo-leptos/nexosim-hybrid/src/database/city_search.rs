@@ -0,0 +1,238 @@
+//! Fuzzy city matching and ranking
+//!
+//! Scores city names against a typed query using trigram similarity (the
+//! same padding scheme as PostgreSQL `pg_trgm`), with exact, prefix and
+//! substring matches ranked above fuzzy ones. Population breaks ties so
+//! "Paris, France" comes before "Paris, Texas".
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use super::geo::City;
+
+/// Minimum trigram similarity for a fuzzy (non-substring) match
+pub const SIMILARITY_THRESHOLD: f64 = 0.3;
+
+/// Default and maximum page size
+pub const DEFAULT_LIMIT: usize = 20;
+pub const MAX_LIMIT: usize = 100;
+
+/// Weight of population in the final rank
+///
+/// Kept below the 0.1 between match kinds in [`text_score`], so population
+/// orders cities within a kind but never lifts a prefix match over an exact
+/// one.
+const POPULATION_WEIGHT: f64 = 0.09;
+
+/// City search parameters, as accepted by `/api/cities/search`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct CityQuery {
+    /// Name to match; typos and partial names are tolerated
+    #[serde(default)]
+    pub q: String,
+    /// Restrict to one country (case-insensitive, e.g. `Japan`)
+    pub country: Option<String>,
+    /// Page size (default 20, max 100)
+    pub limit: Option<usize>,
+    /// Results to skip
+    pub offset: Option<usize>,
+}
+
+impl CityQuery {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset.unwrap_or(0)
+    }
+}
+
+/// One page of ranked results
+#[derive(Debug, Clone)]
+pub struct CityPage {
+    pub cities: Vec<City>,
+    /// Matches across all pages
+    pub total: usize,
+}
+
+/// Lowercase, keep letters/digits, collapse everything else to single spaces
+pub fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Trigrams of each word, padded with two leading and one trailing space
+pub fn trigrams(normalized: &str) -> HashSet<[char; 3]> {
+    let mut set = HashSet::new();
+    for word in normalized.split(' ').filter(|w| !w.is_empty()) {
+        let chars: Vec<char> = "  ".chars().chain(word.chars()).chain([' ']).collect();
+        for w in chars.windows(3) {
+            set.insert([w[0], w[1], w[2]]);
+        }
+    }
+    set
+}
+
+/// Jaccard similarity of two trigram sets
+pub fn similarity(a: &HashSet<[char; 3]>, b: &HashSet<[char; 3]>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+/// Text score in `0.0..=1.0`, or `None` when the name does not match
+///
+/// Exact > prefix > word-prefix > substring > fuzzy.
+pub fn text_score(query: &str, query_trigrams: &HashSet<[char; 3]>, name: &str) -> Option<f64> {
+    let name = normalize(name);
+    if query.is_empty() {
+        return Some(0.0);
+    }
+    if name == query {
+        return Some(1.0);
+    }
+    if name.starts_with(query) {
+        return Some(0.9);
+    }
+    if name.split(' ').any(|w| w.starts_with(query)) {
+        return Some(0.8);
+    }
+    if name.contains(query) {
+        return Some(0.7);
+    }
+    let sim = similarity(query_trigrams, &trigrams(&name));
+    (sim >= SIMILARITY_THRESHOLD).then_some(sim * 0.7)
+}
+
+/// Population contribution in `0.0..=POPULATION_WEIGHT` (log-scaled, 10M+ caps)
+fn population_score(population: u64) -> f64 {
+    ((population as f64 + 1.0).log10() / 7.0).min(1.0) * POPULATION_WEIGHT
+}
+
+/// Rank candidates against the query and cut the requested page
+pub fn rank(candidates: Vec<City>, query: &CityQuery) -> CityPage {
+    let q = normalize(&query.q);
+    let q_trigrams = trigrams(&q);
+
+    let mut scored: Vec<(f64, City)> = candidates
+        .into_iter()
+        .filter_map(|city| {
+            let text = text_score(&q, &q_trigrams, &city.name)?;
+            Some((text + population_score(city.population), city))
+        })
+        .collect();
+
+    scored.sort_by(|(a, ca), (b, cb)| {
+        b.total_cmp(a)
+            .then_with(|| cb.population.cmp(&ca.population))
+            .then_with(|| ca.name.cmp(&cb.name))
+    });
+
+    let total = scored.len();
+    let cities = scored
+        .into_iter()
+        .skip(query.offset())
+        .take(query.limit())
+        .map(|(_, city)| city)
+        .collect();
+    CityPage { cities, total }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn city(name: &str, country: &str, population: u64) -> City {
+        City {
+            id: None,
+            name: name.to_string(),
+            country: country.to_string(),
+            population,
+            location: (0.0, 0.0),
+        }
+    }
+
+    fn query(q: &str) -> CityQuery {
+        CityQuery {
+            q: q.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn normalizes_punctuation_and_case() {
+        assert_eq!(normalize("  St. John's "), "st john s");
+        assert_eq!(trigrams("ab").len(), 3);
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        let page = rank(
+            vec![
+                city("Amsterdam", "Netherlands", 1_000_000),
+                city("Rotterdam", "Netherlands", 600_000),
+            ],
+            &query("amsterdma"),
+        );
+        assert_eq!(page.cities[0].name, "Amsterdam");
+    }
+
+    #[test]
+    fn population_breaks_ties() {
+        let page = rank(
+            vec![
+                city("Paris", "United States", 25_000),
+                city("Paris", "France", 11_000_000),
+            ],
+            &query("paris"),
+        );
+        assert_eq!(page.cities[0].country, "France");
+        assert_eq!(page.total, 2);
+    }
+
+    #[test]
+    fn small_exact_match_beats_large_prefix_match() {
+        let page = rank(
+            vec![
+                city("Mexico City", "Mexico", 21_000_000),
+                city("Mexico", "United States", 100),
+            ],
+            &query("mexico"),
+        );
+        assert_eq!(page.cities[0].country, "United States");
+    }
+
+    #[test]
+    fn exact_beats_prefix_and_pages() {
+        let candidates = vec![
+            city("Portland", "United States", 600_000),
+            city("Porto", "Portugal", 200_000),
+            city("Port Louis", "Mauritius", 150_000),
+            city("Oslo", "Norway", 700_000),
+        ];
+        let page = rank(candidates.clone(), &query("porto"));
+        assert_eq!(page.cities[0].name, "Porto");
+        assert!(page.cities.iter().all(|c| c.name != "Oslo"));
+
+        let second = rank(
+            candidates,
+            &CityQuery {
+                q: "port".into(),
+                limit: Some(1),
+                offset: Some(1),
+                ..Default::default()
+            },
+        );
+        assert_eq!(second.total, 3);
+        assert_eq!(second.cities.len(), 1);
+    }
+}
@@ -1,4 +1,5 @@
 use super::models::ComponentDbDto;
+use super::versions::{self, Data};
 use crate::config::ComponentConfig;
 use anyhow::Result;
 use surrealdb::Surreal;
@@ -259,6 +260,7 @@ impl ComponentRepository {
                         name,
                         building_id: building_thing.clone(),
                         level: level as i16,
+                        outline: Vec::new(),
                     };
 
                     if let Ok(created) =
@@ -296,6 +298,7 @@ impl ComponentRepository {
                         crate::config::SpaceType::CommonArea => Some("Common Area".to_string()),
                         crate::config::SpaceType::Closet => Some("Closet".to_string()),
                     },
+                    bounds: None,
                 };
                 if let Ok(created) = crate::database::geo::GeoRepository::create_space(db, s).await
                 {
@@ -465,6 +468,30 @@ impl ComponentRepository {
             }
         }
 
+        // Seat people at a desk in their scenario space (building + locator)
+        match crate::database::desks::DeskRepository::seed_seating(db).await {
+            Ok(seated) => tracing::info!("Seated {} people at desks", seated),
+            Err(e) => tracing::warn!("Failed to seed desk seating: {}", e),
+        }
+
+        // Chat history between the seeded people, from chat.toml beside personnel.toml
+        let chat_path = personnel_path.with_file_name("chat.toml");
+        if chat_path.exists() {
+            let seeded = match fs::read_to_string(&chat_path).await {
+                Ok(content) => match scenario_loader::parse_conversations(&content) {
+                    Ok(conversations) => {
+                        crate::database::chat::ChatRepository::seed_conversations(db, &conversations).await
+                    }
+                    Err(e) => Err(e.into()),
+                },
+                Err(e) => Err(e.into()),
+            };
+            match seeded {
+                Ok(count) => tracing::info!("Seeded {} chat conversations", count),
+                Err(e) => tracing::warn!("Failed to seed chat history: {}", e),
+            }
+        }
+
         // Seed network assets
         tracing::info!("Seeding {} network assets", config.assets.len());
         for asset in &config.assets {
@@ -526,6 +553,9 @@ impl ComponentRepository {
                 space_id: space_thing,
                 storage_location: asset.storage_location.clone(),
                 notes: asset.notes.clone(),
+                lifecycle: Default::default(),
+                warranty_start: asset.warranty_start.clone(),
+                warranty_end: asset.warranty_end.clone(),
             };
 
             if let Err(e) =
@@ -632,6 +662,7 @@ impl ComponentRepository {
         }
 
         tracing::info!("Seeding complete.");
+        versions::bump_all();
         Ok(())
     }
 
@@ -670,6 +701,7 @@ impl ComponentRepository {
 
         // CREATE returns a list of created records
         let created_list: Vec<ComponentDbDto> = response.take(0)?;
+        versions::bump(Data::Components);
         let created_dto = created_list.into_iter().next();
 
         match created_dto {
@@ -694,6 +726,7 @@ impl ComponentRepository {
             .update(("component", id.to_string()))
             .content(json_content)
             .await?;
+        versions::bump(Data::Components);
 
         match updated_dto {
             Some(d) => Ok(d.try_into()?),
@@ -703,6 +736,13 @@ impl ComponentRepository {
 
     pub async fn delete(db: &Surreal<surrealdb::engine::local::Db>, id: u32) -> Result<()> {
         let _: Option<ComponentDbDto> = db.delete(("component", id.to_string())).await?;
+        // Devices standing in for the component go back to unlinked
+        db.query("UPDATE device SET component_id = NONE WHERE component_id = type::thing('component', $id)")
+            .bind(("id", id.to_string()))
+            .await?;
+        super::telemetry_sources::TelemetrySourceRepository::clear(db, id).await?;
+        versions::bump(Data::Components);
+        versions::bump(Data::Geo);
         Ok(())
     }
 }
@@ -3,6 +3,8 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use surrealdb::Surreal;
 
+use super::versions::{self, Data};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConnectionDbDto {
     #[serde(skip_serializing)]
@@ -67,6 +69,7 @@ impl ConnectionRepository {
             .await?;
 
         let created_list: Vec<ConnectionDbDto> = response.take(0)?;
+        versions::bump(Data::Connections);
         match created_list.into_iter().next() {
             Some(d) => Ok(d.try_into()?),
             None => Err(anyhow::anyhow!("Failed to create connection")),
@@ -80,6 +83,7 @@ impl ConnectionRepository {
     ) -> Result<()> {
         let id_str = format!("{}-{}", from, to);
         let _: Option<ConnectionDbDto> = db.delete(("connection", id_str)).await?;
+        versions::bump(Data::Connections);
         Ok(())
     }
 }
@@ -0,0 +1,511 @@
+// Desk booking
+// Desks are bookable positions inside a space. A person holds a desk either
+// permanently or for a single day; bookings are checked for conflicts before
+// they are written, and day bookings show on the calendar as all-day entries
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::calendar::{Meeting, MeetingType, RecurrenceFrequency};
+use super::geo::{Desk, GeoRepository, Person, Space};
+use super::versions::{self, Data};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BookingKind {
+    /// Assigned seat, valid every day
+    Permanent,
+    /// Hot desk for one date
+    #[default]
+    Day,
+}
+
+impl std::fmt::Display for BookingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookingKind::Permanent => write!(f, "permanent"),
+            BookingKind::Day => write!(f, "day"),
+        }
+    }
+}
+
+impl std::str::FromStr for BookingKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "permanent" => Ok(BookingKind::Permanent),
+            "day" => Ok(BookingKind::Day),
+            other => anyhow::bail!("Unknown booking kind '{}'", other),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DeskBooking {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub desk_id: Thing,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub person_id: Thing,
+    #[serde(default)]
+    pub kind: BookingKind,
+    /// `YYYY-MM-DD` for day bookings
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+impl DeskBooking {
+    pub fn permanent(desk_id: Thing, person_id: Thing) -> Self {
+        Self {
+            id: None,
+            desk_id,
+            person_id,
+            kind: BookingKind::Permanent,
+            date: None,
+        }
+    }
+
+    pub fn day(desk_id: Thing, person_id: Thing, date: &str) -> Self {
+        Self {
+            id: None,
+            desk_id,
+            person_id,
+            kind: BookingKind::Day,
+            date: Some(date.to_string()),
+        }
+    }
+
+    /// Whether the booking holds its desk on `date`
+    pub fn covers(&self, date: &str) -> bool {
+        match self.kind {
+            BookingKind::Permanent => true,
+            BookingKind::Day => self.date.as_deref() == Some(date),
+        }
+    }
+
+    /// All-day calendar entry for a day booking
+    pub fn to_meeting(&self, desk_name: &str, person_name: &str) -> Option<Meeting> {
+        let date = self
+            .date
+            .as_deref()
+            .filter(|_| self.kind == BookingKind::Day)?;
+        Some(Meeting {
+            id: None,
+            title: format!("Desk {}: {}", desk_name, person_name),
+            description: None,
+            start_time: format!("{}T00:00:00", date),
+            end_time: format!("{}T23:59:59", date),
+            all_day: true,
+            meeting_type: MeetingType::DeskBooking,
+            recurrence: RecurrenceFrequency::None,
+            recurrence_interval: 1,
+            recurrence_days: Vec::new(),
+            recurrence_until: None,
+            recurrence_count: None,
+            location_id: None,
+            virtual_url: None,
+            organizer_id: None,
+            participant_ids: vec![self.person_id.clone()],
+            timezone: "UTC".to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookingConflict {
+    /// Day booking without a valid `YYYY-MM-DD` date
+    InvalidDate,
+    /// The desk is someone's permanent seat
+    DeskAssigned(String),
+    /// The desk is already booked on that date
+    DeskBooked(String),
+    /// The person already has another desk on that date
+    PersonBooked(String),
+}
+
+impl std::fmt::Display for BookingConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookingConflict::InvalidDate => write!(f, "A day booking needs a YYYY-MM-DD date"),
+            BookingConflict::DeskAssigned(person) => {
+                write!(f, "Desk is permanently assigned to {}", person)
+            }
+            BookingConflict::DeskBooked(date) => write!(f, "Desk is already booked on {}", date),
+            BookingConflict::PersonBooked(date) => {
+                write!(f, "Already booked another desk on {}", date)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BookingConflict {}
+
+fn valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    matches!(parts.as_slice(), [y, m, d]
+        if y.len() == 4 && m.len() == 2 && d.len() == 2
+            && y.parse::<u16>().is_ok()
+            && m.parse::<u8>().is_ok_and(|m| (1..=12).contains(&m))
+            && d.parse::<u8>().is_ok_and(|d| (1..=31).contains(&d)))
+}
+
+/// Check a new booking against the existing ones
+///
+/// A permanent seat replaces the person's previous one, so their own
+/// permanent booking never conflicts. A day booking may not take someone
+/// else's permanent seat, double-book a desk, or give one person two desks
+/// on the same date.
+pub fn check_booking(
+    new: &DeskBooking,
+    existing: &[DeskBooking],
+) -> std::result::Result<(), BookingConflict> {
+    let date = match new.kind {
+        BookingKind::Permanent => None,
+        BookingKind::Day => match new.date.as_deref() {
+            Some(d) if valid_date(d) => Some(d),
+            _ => return Err(BookingConflict::InvalidDate),
+        },
+    };
+
+    for other in existing.iter().filter(|b| b.id.is_none() || b.id != new.id) {
+        let same_desk = other.desk_id == new.desk_id;
+        let same_person = other.person_id == new.person_id;
+        match (other.kind, date) {
+            (BookingKind::Permanent, _) if same_desk && !same_person => {
+                return Err(BookingConflict::DeskAssigned(other.person_id.to_string()));
+            }
+            (BookingKind::Day, Some(d)) if other.covers(d) && same_desk => {
+                return Err(BookingConflict::DeskBooked(d.to_string()));
+            }
+            (BookingKind::Day, Some(d)) if other.covers(d) && same_person => {
+                return Err(BookingConflict::PersonBooked(d.to_string()));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Who holds each desk on `date`, keyed by desk id
+pub fn holders_on<'a>(bookings: &'a [DeskBooking], date: &str) -> HashMap<String, &'a DeskBooking> {
+    let mut holders = HashMap::new();
+    for booking in bookings.iter().filter(|b| b.covers(date)) {
+        // A day booking takes precedence over a permanent seat it may overlap
+        let key = booking.desk_id.to_string();
+        if booking.kind == BookingKind::Day || !holders.contains_key(&key) {
+            holders.insert(key, booking);
+        }
+    }
+    holders
+}
+
+/// Desk a person should get when seating is seeded from the scenario
+#[derive(Debug, Clone, PartialEq)]
+pub enum Seat {
+    /// Reuse an existing desk
+    Existing { desk_id: Thing, person_id: Thing },
+    /// Create a desk in the person's space first
+    New { desk: Desk, person_id: Thing },
+}
+
+/// Seat every person who has a space but no permanent desk yet
+///
+/// Desks already assigned to the person by name are used first, then free
+/// desks in their space; when the space runs out, new desks are named after
+/// its locator (`2-101-D01`, `2-101-D02`, ...).
+pub fn plan_seating(
+    people: &[Person],
+    spaces: &[Space],
+    desks: &[Desk],
+    bookings: &[DeskBooking],
+) -> Vec<Seat> {
+    let locators: HashMap<String, &str> = spaces
+        .iter()
+        .filter_map(|s| Some((s.id.as_ref()?.to_string(), s.locator.as_str())))
+        .collect();
+    let seated: HashSet<String> = bookings
+        .iter()
+        .filter(|b| b.kind == BookingKind::Permanent)
+        .map(|b| b.person_id.to_string())
+        .collect();
+    let mut taken: HashSet<String> = bookings
+        .iter()
+        .filter(|b| b.kind == BookingKind::Permanent)
+        .map(|b| b.desk_id.to_string())
+        .collect();
+    let mut created: HashMap<String, usize> = HashMap::new();
+
+    let mut seats = Vec::new();
+    for person in people {
+        let (Some(person_id), Some(space_id)) = (person.id.clone(), person.space_id.as_ref())
+        else {
+            continue;
+        };
+        if seated.contains(&person_id.to_string()) {
+            continue;
+        }
+        let free = |d: &&Desk| {
+            d.id.as_ref()
+                .is_some_and(|id| !taken.contains(&id.to_string()))
+        };
+        let desk = desks
+            .iter()
+            .filter(free)
+            .find(|d| d.assigned_to.as_deref() == Some(person.name.as_str()))
+            .or_else(|| {
+                desks
+                    .iter()
+                    .filter(free)
+                    .find(|d| d.space_id == *space_id && d.assigned_to.is_none())
+            });
+
+        if let Some(desk_id) = desk.and_then(|d| d.id.clone()) {
+            taken.insert(desk_id.to_string());
+            seats.push(Seat::Existing { desk_id, person_id });
+            continue;
+        }
+
+        let space_key = space_id.to_string();
+        let in_space = desks.iter().filter(|d| d.space_id == *space_id).count();
+        let n = created.entry(space_key.clone()).or_insert(in_space);
+        *n += 1;
+        let locator = locators.get(&space_key).copied().unwrap_or("DESK");
+        seats.push(Seat::New {
+            desk: Desk {
+                id: None,
+                name: format!("{}-D{:02}", locator, n),
+                space_id: space_id.clone(),
+                assigned_to: Some(person.name.clone()),
+            },
+            person_id,
+        });
+    }
+    seats
+}
+
+pub struct DeskRepository;
+
+impl DeskRepository {
+    pub async fn list_all_bookings(db: &Surreal<Db>) -> Result<Vec<DeskBooking>> {
+        let bookings: Vec<DeskBooking> = db.select("desk_booking").await?;
+        Ok(bookings)
+    }
+
+    /// Bookings that hold a desk on `date`
+    pub async fn bookings_on(db: &Surreal<Db>, date: &str) -> Result<Vec<DeskBooking>> {
+        let mut bookings = Self::list_all_bookings(db).await?;
+        bookings.retain(|b| b.covers(date));
+        Ok(bookings)
+    }
+
+    /// Book a desk after checking for conflicts
+    ///
+    /// A permanent booking replaces the person's previous permanent seat.
+    pub async fn book(db: &Surreal<Db>, booking: DeskBooking) -> Result<DeskBooking> {
+        let desk: Option<Desk> = db.select(("desk", booking.desk_id.id.to_raw())).await?;
+        if desk.is_none() {
+            anyhow::bail!("Desk '{}' not found", booking.desk_id);
+        }
+        let existing = Self::list_all_bookings(db).await?;
+        check_booking(&booking, &existing)?;
+
+        if booking.kind == BookingKind::Permanent {
+            for old in existing
+                .iter()
+                .filter(|b| b.kind == BookingKind::Permanent && b.person_id == booking.person_id)
+            {
+                if let Some(id) = &old.id {
+                    let _: Option<DeskBooking> =
+                        db.delete(("desk_booking", id.id.to_raw())).await?;
+                }
+            }
+        }
+
+        let created: DeskBooking = db
+            .create("desk_booking")
+            .content(booking)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create desk booking"))?;
+        versions::bump(Data::DeskBookings);
+        Ok(created)
+    }
+
+    pub async fn cancel(db: &Surreal<Db>, id: &str) -> Result<Option<DeskBooking>> {
+        let deleted: Option<DeskBooking> = db.delete(("desk_booking", id)).await?;
+        versions::bump(Data::DeskBookings);
+        Ok(deleted)
+    }
+
+    /// Give everyone with a space a permanent desk; returns how many were seated
+    pub async fn seed_seating(db: &Surreal<Db>) -> Result<usize> {
+        let people = GeoRepository::list_all_people(db).await?;
+        let spaces = GeoRepository::list_all_spaces(db).await?;
+        let desks = GeoRepository::list_all_desks(db).await?;
+        let bookings = Self::list_all_bookings(db).await?;
+
+        let seats = plan_seating(&people, &spaces, &desks, &bookings);
+        let count = seats.len();
+        for seat in seats {
+            let (desk_id, person_id) = match seat {
+                Seat::Existing { desk_id, person_id } => (desk_id, person_id),
+                Seat::New { desk, person_id } => {
+                    let created = GeoRepository::create_desk(db, desk).await?;
+                    let desk_id = created
+                        .id
+                        .ok_or_else(|| anyhow::anyhow!("Created desk has no id"))?;
+                    (desk_id, person_id)
+                }
+            };
+            let _: Option<DeskBooking> = db
+                .create("desk_booking")
+                .content(DeskBooking::permanent(desk_id, person_id))
+                .await?;
+        }
+        versions::bump(Data::DeskBookings);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thing(tb: &str, id: &str) -> Thing {
+        Thing::from((tb, id))
+    }
+
+    fn booked(mut booking: DeskBooking, key: &str) -> DeskBooking {
+        booking.id = Some(thing("desk_booking", key));
+        booking
+    }
+
+    #[test]
+    fn day_bookings_respect_seats_and_dates() {
+        let existing = vec![
+            booked(
+                DeskBooking::permanent(thing("desk", "d1"), thing("person", "ann")),
+                "b1",
+            ),
+            booked(
+                DeskBooking::day(thing("desk", "d2"), thing("person", "bob"), "2025-03-04"),
+                "b2",
+            ),
+        ];
+
+        let take_seat = DeskBooking::day(thing("desk", "d1"), thing("person", "bob"), "2025-03-05");
+        assert_eq!(
+            check_booking(&take_seat, &existing),
+            Err(BookingConflict::DeskAssigned("person:ann".into()))
+        );
+
+        let double = DeskBooking::day(thing("desk", "d2"), thing("person", "cat"), "2025-03-04");
+        assert_eq!(
+            check_booking(&double, &existing),
+            Err(BookingConflict::DeskBooked("2025-03-04".into()))
+        );
+
+        let second_desk =
+            DeskBooking::day(thing("desk", "d3"), thing("person", "bob"), "2025-03-04");
+        assert_eq!(
+            check_booking(&second_desk, &existing),
+            Err(BookingConflict::PersonBooked("2025-03-04".into()))
+        );
+
+        let next_day = DeskBooking::day(thing("desk", "d2"), thing("person", "cat"), "2025-03-05");
+        assert_eq!(check_booking(&next_day, &existing), Ok(()));
+
+        let undated = DeskBooking {
+            date: Some("tomorrow".into()),
+            ..next_day
+        };
+        assert_eq!(
+            check_booking(&undated, &existing),
+            Err(BookingConflict::InvalidDate)
+        );
+    }
+
+    #[test]
+    fn permanent_seats_move_with_the_person() {
+        let existing = vec![booked(
+            DeskBooking::permanent(thing("desk", "d1"), thing("person", "ann")),
+            "b1",
+        )];
+
+        let moved = DeskBooking::permanent(thing("desk", "d2"), thing("person", "ann"));
+        assert_eq!(check_booking(&moved, &existing), Ok(()));
+
+        let taken = DeskBooking::permanent(thing("desk", "d1"), thing("person", "bob"));
+        assert!(matches!(
+            check_booking(&taken, &existing),
+            Err(BookingConflict::DeskAssigned(_))
+        ));
+
+        let holders = holders_on(&existing, "2025-03-04");
+        assert_eq!(holders["desk:d1"].person_id, thing("person", "ann"));
+    }
+
+    #[test]
+    fn seats_people_from_their_space() {
+        let space = Space {
+            id: Some(thing("space", "s1")),
+            name: "Office".into(),
+            floor_id: thing("floor", "f1"),
+            locator: "2-101".into(),
+            space_type: Some("Office".into()),
+            bounds: None,
+        };
+        let person = |key: &str, name: &str, space: Option<&str>| Person {
+            id: Some(thing("person", key)),
+            name: name.into(),
+            email: String::new(),
+            title: String::new(),
+            department: String::new(),
+            site_id: thing("site", "hq"),
+            space_id: space.map(|s| thing("space", s)),
+            manager_id: None,
+            role: Default::default(),
+            photo: None,
+            bio: None,
+            desk_phone: None,
+            cell_phone: None,
+            photo_data: None,
+        };
+        let desks = vec![Desk {
+            id: Some(thing("desk", "d1")),
+            name: "2-101-D01".into(),
+            space_id: thing("space", "s1"),
+            assigned_to: Some("Bob".into()),
+        }];
+        let people = vec![
+            person("ann", "Ann", Some("s1")),
+            person("bob", "Bob", Some("s1")),
+            person("cat", "Cat", None),
+        ];
+
+        let seats = plan_seating(&people, &[space], &desks, &[]);
+        assert_eq!(seats.len(), 2);
+        match &seats[0] {
+            Seat::New { desk, person_id } => {
+                assert_eq!(desk.name, "2-101-D02");
+                assert_eq!(*person_id, thing("person", "ann"));
+            }
+            other => panic!("expected a new desk, got {:?}", other),
+        }
+        assert_eq!(
+            seats[1],
+            Seat::Existing {
+                desk_id: thing("desk", "d1"),
+                person_id: thing("person", "bob")
+            }
+        );
+    }
+}
@@ -0,0 +1,291 @@
+// Device to simulation component linking
+// A physical device in a rack can stand in for one simulation component;
+// the link lives on `Device.component_id` and is resolved back through the
+// rack/space/floor/building/site hierarchy to say where a node physically is
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::{Id, Thing};
+use surrealdb::Surreal;
+
+use super::geo::{Building, Device, Floor, GeoRepository, Rack, Site, Space};
+use super::models::ComponentDbDto;
+use super::versions::{self, Data};
+
+/// Where a linked device sits in the site hierarchy
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PhysicalLocation {
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub device_id: Thing,
+    pub device: String,
+    pub position_u: u8,
+    pub rack: String,
+    pub space: Option<String>,
+    pub floor: Option<String>,
+    pub building: Option<String>,
+    pub site: Option<String>,
+}
+
+impl PhysicalLocation {
+    /// Breadcrumb from site down to rack unit, skipping unknown levels
+    pub fn path(&self) -> String {
+        let rack = format!("{} U{}", self.rack, self.position_u);
+        [&self.site, &self.building, &self.floor, &self.space]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .chain(std::iter::once(rack.as_str()))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+/// Component id held in a `component:<id>` record link
+pub fn component_key(thing: &Thing) -> Option<u32> {
+    if thing.tb != "component" {
+        return None;
+    }
+    match &thing.id {
+        Id::Number(n) => u32::try_from(*n).ok(),
+        Id::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Record link for a simulation component id
+pub fn component_thing(id: u32) -> Thing {
+    Thing::from(("component", id.to_string().as_str()))
+}
+
+/// Resolve every linked device to its physical location, keyed by component id
+///
+/// Parents that no longer exist are left as `None` rather than dropping the
+/// link; a device whose rack is gone is skipped.
+pub fn locate_components(
+    devices: &[Device],
+    racks: &[Rack],
+    spaces: &[Space],
+    floors: &[Floor],
+    buildings: &[Building],
+    sites: &[Site],
+) -> HashMap<u32, PhysicalLocation> {
+    fn by_id<T>(items: &[T], id: impl Fn(&T) -> Option<&Thing>) -> HashMap<String, &T> {
+        items
+            .iter()
+            .filter_map(|item| Some((id(item)?.to_string(), item)))
+            .collect()
+    }
+    let racks = by_id(racks, |r| r.id.as_ref());
+    let spaces = by_id(spaces, |s| s.id.as_ref());
+    let floors = by_id(floors, |f| f.id.as_ref());
+    let buildings = by_id(buildings, |b| b.id.as_ref());
+    let sites = by_id(sites, |s| s.id.as_ref());
+
+    let mut located = HashMap::new();
+    for device in devices {
+        let (Some(component), Some(device_id)) = (
+            device.component_id.as_ref().and_then(component_key),
+            device.id.clone(),
+        ) else {
+            continue;
+        };
+        let Some(rack) = racks.get(&device.rack_id.to_string()) else {
+            continue;
+        };
+        let space = spaces.get(&rack.space_id.to_string());
+        let floor = space.and_then(|s| floors.get(&s.floor_id.to_string()));
+        let building = floor.and_then(|f| buildings.get(&f.building_id.to_string()));
+        let site = building.and_then(|b| sites.get(&b.site_id.to_string()));
+
+        located.insert(
+            component,
+            PhysicalLocation {
+                device_id,
+                device: device.name.clone(),
+                position_u: device.position_u,
+                rack: rack.name.clone(),
+                space: space.map(|s| s.name.clone()),
+                floor: floor.map(|f| f.name.clone()),
+                building: building.map(|b| b.name.clone()),
+                site: site.map(|s| s.name.clone()),
+            },
+        );
+    }
+    located
+}
+
+pub struct DeviceLinkRepository;
+
+impl DeviceLinkRepository {
+    /// Link a device to a simulation component, or unlink it with `None`
+    ///
+    /// A component maps to at most one device, so any other device linked to
+    /// the same component is unlinked first. Returns `None` if the device
+    /// does not exist.
+    pub async fn link(
+        db: &Surreal<Db>,
+        device_id: &str,
+        component: Option<u32>,
+    ) -> Result<Option<Device>> {
+        let Some(mut device): Option<Device> = db.select(("device", device_id)).await? else {
+            return Ok(None);
+        };
+
+        if let Some(component) = component {
+            let existing: Option<ComponentDbDto> =
+                db.select(("component", component.to_string())).await?;
+            if existing.is_none() {
+                anyhow::bail!("Component {} not found", component);
+            }
+            for mut other in Self::devices_for_component(db, component).await? {
+                let Some(other_id) = other.id.take() else {
+                    continue;
+                };
+                if other_id.id.to_raw() == device_id {
+                    continue;
+                }
+                other.component_id = None;
+                let _: Option<Device> = db
+                    .update(("device", other_id.id.to_raw()))
+                    .content(other)
+                    .await?;
+            }
+        }
+
+        device.id = None;
+        device.component_id = component.map(component_thing);
+        let updated: Option<Device> = db.update(("device", device_id)).content(device).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    /// Devices standing in for a component (normally zero or one)
+    pub async fn devices_for_component(db: &Surreal<Db>, component: u32) -> Result<Vec<Device>> {
+        let sql = "SELECT * FROM device WHERE component_id = type::thing('component', $id)";
+        let mut result = db.query(sql).bind(("id", component.to_string())).await?;
+        let devices: Vec<Device> = result.take(0)?;
+        Ok(devices)
+    }
+
+    /// Physical location of every linked component
+    pub async fn component_locations(db: &Surreal<Db>) -> Result<HashMap<u32, PhysicalLocation>> {
+        let devices = GeoRepository::list_all_devices(db).await?;
+        let racks = GeoRepository::list_all_racks(db).await?;
+        let spaces = GeoRepository::list_all_spaces(db).await?;
+        let floors = GeoRepository::list_all_floors(db).await?;
+        let buildings = GeoRepository::list_all_buildings(db).await?;
+        let sites = GeoRepository::list_sites(db).await?;
+        Ok(locate_components(
+            &devices, &racks, &spaces, &floors, &buildings, &sites,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thing(tb: &str, id: &str) -> Thing {
+        Thing::from((tb, id))
+    }
+
+    fn hierarchy() -> (Vec<Rack>, Vec<Space>, Vec<Floor>, Vec<Building>, Vec<Site>) {
+        let site = Site {
+            id: Some(thing("site", "hq")),
+            name: "HQ".into(),
+            region_id: None,
+            location: (0.0, 0.0),
+            status: "active".into(),
+        };
+        let building = Building {
+            id: Some(thing("building", "b1")),
+            name: "Main".into(),
+            site_id: thing("site", "hq"),
+        };
+        let floor = Floor {
+            id: Some(thing("floor", "f2")),
+            name: "Level 2".into(),
+            building_id: thing("building", "b1"),
+            level: 2,
+            outline: Vec::new(),
+        };
+        let space = Space {
+            id: Some(thing("space", "dc")),
+            name: "Server Room".into(),
+            floor_id: thing("floor", "f2"),
+            locator: "2-101".into(),
+            space_type: None,
+            bounds: None,
+        };
+        let rack = Rack {
+            id: Some(thing("rack", "r1")),
+            name: "Rack A".into(),
+            space_id: thing("space", "dc"),
+            height_u: 42,
+        };
+        (
+            vec![rack],
+            vec![space],
+            vec![floor],
+            vec![building],
+            vec![site],
+        )
+    }
+
+    fn device(key: &str, component: Option<u32>) -> Device {
+        Device {
+            id: Some(thing("device", key)),
+            name: key.to_uppercase(),
+            rack_id: thing("rack", "r1"),
+            position_u: 12,
+            component_id: component.map(component_thing),
+            height_u: 1,
+            power_w: 0,
+        }
+    }
+
+    #[test]
+    fn component_links_round_trip() {
+        assert_eq!(component_key(&component_thing(7)), Some(7));
+        assert_eq!(
+            component_key(&Thing::from(("component", Id::Number(3)))),
+            Some(3)
+        );
+        assert_eq!(component_key(&thing("device", "7")), None);
+    }
+
+    #[test]
+    fn resolves_full_path() {
+        let (racks, spaces, floors, buildings, sites) = hierarchy();
+        let devices = vec![device("sw1", Some(1)), device("spare", None)];
+        let located = locate_components(&devices, &racks, &spaces, &floors, &buildings, &sites);
+
+        assert_eq!(located.len(), 1);
+        assert_eq!(
+            located[&1].path(),
+            "HQ / Main / Level 2 / Server Room / Rack A U12"
+        );
+    }
+
+    #[test]
+    fn missing_parents_shorten_the_path() {
+        let (racks, spaces, floors, _, _) = hierarchy();
+        let located = locate_components(
+            &[device("sw1", Some(1))],
+            &racks,
+            &spaces,
+            &floors,
+            &[],
+            &[],
+        );
+        assert_eq!(located[&1].path(), "Level 2 / Server Room / Rack A U12");
+
+        let orphaned =
+            locate_components(&[device("sw1", Some(1))], &[], &spaces, &floors, &[], &[]);
+        assert!(orphaned.is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+// Email records
+// The outbox of queued and sent emails, and which emails each persona wants
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+/// Sent and failed emails kept; older ones are dropped as new ones are queued
+pub const OUTBOX_KEPT: usize = 500;
+
+/// Where an email stands
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum EmailStatus {
+    /// Waiting to be sent, or to be tried again
+    #[default]
+    Queued,
+    Sent,
+    /// Gave up after the last attempt
+    Failed,
+}
+
+impl std::fmt::Display for EmailStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmailStatus::Queued => write!(f, "queued"),
+            EmailStatus::Sent => write!(f, "sent"),
+            EmailStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    /// Base64 encoded content
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OutboundEmail {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// `invite`, `report` or `mention`
+    pub kind: String,
+    /// Persona (person name) it is for
+    pub recipient: String,
+    /// Address it is sent to
+    pub address: String,
+    pub subject: String,
+    pub text: String,
+    #[serde(default)]
+    pub attachments: Vec<EmailAttachment>,
+    pub status: EmailStatus,
+    pub attempts: u32,
+    /// Why the last attempt failed
+    pub error: Option<String>,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+impl OutboundEmail {
+    /// Key of the record id, for use in URLs
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+}
+
+/// Which emails a persona wants
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EmailPreference {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub persona: String,
+    pub invites: bool,
+    pub reports: bool,
+    pub mentions: bool,
+}
+
+pub struct EmailRepository;
+
+impl EmailRepository {
+    /// Queue an email, then trim finished emails to [`OUTBOX_KEPT`]
+    pub async fn queue(db: &Surreal<Db>, email: OutboundEmail) -> Result<OutboundEmail> {
+        let created: OutboundEmail = db
+            .create("outbound_email")
+            .content(email)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to queue email"))?;
+
+        let outbox = Self::outbox(db, None).await?;
+        let finished = outbox.iter().filter(|e| e.status != EmailStatus::Queued);
+        for old in finished.skip(OUTBOX_KEPT) {
+            let _deleted: Option<OutboundEmail> = db.delete(("outbound_email", old.key())).await?;
+        }
+        versions::bump(Data::Email);
+        Ok(created)
+    }
+
+    /// Emails waiting to be sent, oldest first
+    pub async fn queued(db: &Surreal<Db>) -> Result<Vec<OutboundEmail>> {
+        let mut queued = Self::outbox(db, None).await?;
+        queued.retain(|e| e.status == EmailStatus::Queued);
+        queued.reverse();
+        Ok(queued)
+    }
+
+    pub async fn update(
+        db: &Surreal<Db>,
+        id: &str,
+        email: OutboundEmail,
+    ) -> Result<Option<OutboundEmail>> {
+        let mut email = email;
+        email.id = None;
+        let updated: Option<OutboundEmail> =
+            db.update(("outbound_email", id)).content(email).await?;
+        versions::bump(Data::Email);
+        Ok(updated)
+    }
+
+    /// Emails for one persona, or for everyone, newest first
+    pub async fn outbox(db: &Surreal<Db>, recipient: Option<&str>) -> Result<Vec<OutboundEmail>> {
+        let mut emails: Vec<OutboundEmail> = db.select("outbound_email").await?;
+        if let Some(recipient) = recipient {
+            emails.retain(|e| e.recipient == recipient);
+        }
+        emails.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(emails)
+    }
+
+    /// A persona's preferences, `None` until they have set any
+    pub async fn preferences(db: &Surreal<Db>, persona: &str) -> Result<Option<EmailPreference>> {
+        let mut result = db
+            .query("SELECT * FROM email_preference WHERE persona = $persona LIMIT 1")
+            .bind(("persona", persona.to_string()))
+            .await?;
+        let preferences: Vec<EmailPreference> = result.take(0)?;
+        Ok(preferences.into_iter().next())
+    }
+
+    /// Replace a persona's preferences
+    pub async fn set_preferences(
+        db: &Surreal<Db>,
+        preference: EmailPreference,
+    ) -> Result<EmailPreference> {
+        let mut preference = preference;
+        let saved = match Self::preferences(db, &preference.persona).await? {
+            Some(existing) => {
+                let key = existing.id.map(|t| t.id.to_raw()).unwrap_or_default();
+                preference.id = None;
+                db.update(("email_preference", key))
+                    .content(preference)
+                    .await?
+            }
+            None => db.create("email_preference").content(preference).await?,
+        };
+        versions::bump(Data::Email);
+        saved.ok_or_else(|| anyhow::anyhow!("Failed to save email preferences"))
+    }
+}
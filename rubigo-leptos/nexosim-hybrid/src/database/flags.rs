@@ -0,0 +1,49 @@
+// Feature flag overrides
+// Flags and their defaults are declared by the server (and rubigo.toml); a flag toggled at
+// runtime is stored here and wins over its default until it is reset
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FlagOverride {
+    /// `feature_flag:<name>`
+    pub id: Option<Thing>,
+    pub enabled: bool,
+}
+
+pub struct FlagRepository;
+
+impl FlagRepository {
+    /// Stored overrides by flag name
+    pub async fn overrides(db: &Surreal<Db>) -> Result<BTreeMap<String, bool>> {
+        let records: Vec<FlagOverride> = db.select("feature_flag").await?;
+        Ok(records
+            .into_iter()
+            .filter_map(|r| Some((r.id?.id.to_raw(), r.enabled)))
+            .collect())
+    }
+
+    pub async fn set(db: &Surreal<Db>, name: &str, enabled: bool) -> Result<()> {
+        let _: Option<FlagOverride> = db
+            .upsert(("feature_flag", name))
+            .content(FlagOverride { id: None, enabled })
+            .await?;
+        versions::bump(Data::Flags);
+        Ok(())
+    }
+
+    /// Drop the override so the flag follows its default again
+    pub async fn clear(db: &Surreal<Db>, name: &str) -> Result<()> {
+        let _: Option<FlagOverride> = db.delete(("feature_flag", name)).await?;
+        versions::bump(Data::Flags);
+        Ok(())
+    }
+}
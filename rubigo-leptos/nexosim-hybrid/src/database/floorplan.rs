@@ -0,0 +1,335 @@
+// Floorplans
+// Floor outlines and space rectangles in floor-local metres (origin at the
+// top-left, y pointing down, matching SVG), plus the per-space occupancy
+// used for the heat layer
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::Surreal;
+
+use super::geo::{Floor, Person, Rack, Space};
+use super::versions::{self, Data};
+
+/// Smallest side a placed space may have, in metres
+pub const MIN_SPACE_SIZE: f64 = 0.5;
+
+/// Outline used for floors that have not been drawn or uploaded yet
+pub const DEFAULT_OUTLINE: [(f64, f64); 4] = [(0.0, 0.0), (40.0, 0.0), (40.0, 25.0), (0.0, 25.0)];
+
+/// Rectangle of a space on its floorplan
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpaceBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl SpaceBounds {
+    /// Keep the rectangle inside `(min_x, min_y, max_x, max_y)` with a usable size
+    pub fn clamp_to(self, (min_x, min_y, max_x, max_y): (f64, f64, f64, f64)) -> Self {
+        let width = self.width.max(MIN_SPACE_SIZE).min(max_x - min_x);
+        let height = self.height.max(MIN_SPACE_SIZE).min(max_y - min_y);
+        Self {
+            x: self.x.clamp(min_x, max_x - width),
+            y: self.y.clamp(min_y, max_y - height),
+            width,
+            height,
+        }
+    }
+
+    pub fn overlaps(&self, other: &SpaceBounds) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+}
+
+/// Bounding box `(min_x, min_y, max_x, max_y)` of an outline
+pub fn outline_bbox(outline: &[(f64, f64)]) -> Option<(f64, f64, f64, f64)> {
+    let (&(x0, y0), rest) = outline.split_first()?;
+    Some(rest.iter().fold((x0, y0, x0, y0), |(a, b, c, d), &(x, y)| {
+        (a.min(x), b.min(y), c.max(x), d.max(y))
+    }))
+}
+
+/// Outline of a floor, falling back to [`DEFAULT_OUTLINE`]
+pub fn floor_outline(floor: &Floor) -> Vec<(f64, f64)> {
+    if floor.outline.len() >= 3 {
+        floor.outline.clone()
+    } else {
+        DEFAULT_OUTLINE.to_vec()
+    }
+}
+
+/// Parse an uploaded outline
+///
+/// Accepts a GeoJSON `Polygon` (geometry or feature, first ring), a bare JSON
+/// array of `[x, y]` pairs, or plain text with one `x,y` pair per line or
+/// separated by spaces. A closing point equal to the first is dropped.
+pub fn parse_outline(input: &str) -> Result<Vec<(f64, f64)>> {
+    let input = input.trim();
+    let mut points = if input.starts_with('{') || input.starts_with('[') {
+        let value: serde_json::Value = serde_json::from_str(input)?;
+        let ring = match &value {
+            serde_json::Value::Array(_) => &value,
+            _ => {
+                let geometry = value.get("geometry").unwrap_or(&value);
+                geometry
+                    .get("coordinates")
+                    .and_then(|c| c.get(0))
+                    .ok_or_else(|| anyhow::anyhow!("Expected a Polygon with coordinates"))?
+            }
+        };
+        serde_json::from_value::<Vec<(f64, f64)>>(ring.clone())?
+    } else {
+        input
+            .split(|c: char| c.is_whitespace() || c == ';')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (x, y) = pair
+                    .split_once(',')
+                    .ok_or_else(|| anyhow::anyhow!("Expected x,y but got '{}'", pair))?;
+                Ok((x.trim().parse()?, y.trim().parse()?))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        anyhow::bail!("An outline needs at least 3 points");
+    }
+    if points.iter().any(|(x, y)| !x.is_finite() || !y.is_finite()) {
+        anyhow::bail!("Outline coordinates must be finite numbers");
+    }
+    Ok(points)
+}
+
+/// Who and what is in a space
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpaceOccupancy {
+    pub people: usize,
+    pub racks: usize,
+    /// People per 10 m², when the space has been placed
+    pub density: Option<f64>,
+}
+
+impl SpaceOccupancy {
+    /// Load used for the heat layer: people count, racks weighted as two
+    pub fn load(&self) -> f64 {
+        self.people as f64 + 2.0 * self.racks as f64
+    }
+}
+
+/// Occupancy of each space, keyed by space id
+pub fn occupancy(
+    spaces: &[Space],
+    people: &[Person],
+    racks: &[Rack],
+) -> HashMap<String, SpaceOccupancy> {
+    let mut result: HashMap<String, SpaceOccupancy> = spaces
+        .iter()
+        .filter_map(|s| Some((s.id.as_ref()?.to_string(), SpaceOccupancy::default())))
+        .collect();
+    for person in people {
+        if let Some(entry) = person
+            .space_id
+            .as_ref()
+            .and_then(|id| result.get_mut(&id.to_string()))
+        {
+            entry.people += 1;
+        }
+    }
+    for rack in racks {
+        if let Some(entry) = result.get_mut(&rack.space_id.to_string()) {
+            entry.racks += 1;
+        }
+    }
+    for space in spaces {
+        let (Some(id), Some(bounds)) = (space.id.as_ref(), space.bounds) else {
+            continue;
+        };
+        if let Some(entry) = result.get_mut(&id.to_string()) {
+            if bounds.area() > 0.0 {
+                entry.density = Some(entry.people as f64 * 10.0 / bounds.area());
+            }
+        }
+    }
+    result
+}
+
+/// Heat in `0.0..=1.0` relative to the busiest space
+pub fn heat(load: f64, max_load: f64) -> f64 {
+    if max_load <= 0.0 {
+        0.0
+    } else {
+        (load / max_load).clamp(0.0, 1.0)
+    }
+}
+
+pub struct FloorplanRepository;
+
+impl FloorplanRepository {
+    pub async fn set_outline(
+        db: &Surreal<Db>,
+        floor_id: &str,
+        outline: Vec<(f64, f64)>,
+    ) -> Result<Option<Floor>> {
+        let Some(mut floor): Option<Floor> = db.select(("floor", floor_id)).await? else {
+            return Ok(None);
+        };
+        floor.id = None;
+        floor.outline = outline;
+        let updated: Option<Floor> = db.update(("floor", floor_id)).content(floor).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    /// Place (or with `None`, unplace) a space, clamped to its floor outline
+    pub async fn set_space_bounds(
+        db: &Surreal<Db>,
+        space_id: &str,
+        bounds: Option<SpaceBounds>,
+    ) -> Result<Option<Space>> {
+        let Some(mut space): Option<Space> = db.select(("space", space_id)).await? else {
+            return Ok(None);
+        };
+        let floor: Option<Floor> = db.select(("floor", space.floor_id.id.to_raw())).await?;
+        let outline = floor
+            .as_ref()
+            .map(floor_outline)
+            .unwrap_or_else(|| DEFAULT_OUTLINE.to_vec());
+        space.id = None;
+        space.bounds = match (bounds, outline_bbox(&outline)) {
+            (Some(b), Some(bbox)) => Some(b.clamp_to(bbox)),
+            (b, _) => b,
+        };
+        let updated: Option<Space> = db.update(("space", space_id)).content(space).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use surrealdb::sql::Thing;
+
+    #[test]
+    fn parses_outline_formats() {
+        let square = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 8.0), (0.0, 8.0)];
+        assert_eq!(parse_outline("0,0 10,0\n10,8; 0,8").unwrap(), square);
+        assert_eq!(
+            parse_outline("[[0,0],[10,0],[10,8],[0,8],[0,0]]").unwrap(),
+            square
+        );
+        let feature = r#"{"type":"Feature","geometry":{"type":"Polygon","coordinates":[[[0,0],[10,0],[10,8],[0,8],[0,0]]]}}"#;
+        assert_eq!(parse_outline(feature).unwrap(), square);
+        assert!(parse_outline("0,0 1,1").is_err());
+        assert!(parse_outline("0,0 1 2,2").is_err());
+    }
+
+    #[test]
+    fn bounds_stay_inside_the_floor() {
+        let bbox = outline_bbox(&DEFAULT_OUTLINE).unwrap();
+        assert_eq!(bbox, (0.0, 0.0, 40.0, 25.0));
+
+        let dragged = SpaceBounds {
+            x: 38.0,
+            y: -3.0,
+            width: 5.0,
+            height: 0.1,
+        }
+        .clamp_to(bbox);
+        assert_eq!(
+            dragged,
+            SpaceBounds {
+                x: 35.0,
+                y: 0.0,
+                width: 5.0,
+                height: MIN_SPACE_SIZE
+            }
+        );
+
+        let a = SpaceBounds {
+            x: 0.0,
+            y: 0.0,
+            width: 4.0,
+            height: 4.0,
+        };
+        assert!(a.overlaps(&SpaceBounds {
+            x: 3.0,
+            y: 3.0,
+            width: 2.0,
+            height: 2.0
+        }));
+        assert!(!a.overlaps(&SpaceBounds {
+            x: 4.0,
+            y: 0.0,
+            width: 2.0,
+            height: 2.0
+        }));
+    }
+
+    #[test]
+    fn counts_people_and_racks() {
+        let space = Space {
+            id: Some(Thing::from(("space", "s1"))),
+            name: "Lab".into(),
+            floor_id: Thing::from(("floor", "f1")),
+            locator: "1-01".into(),
+            space_type: None,
+            bounds: Some(SpaceBounds {
+                x: 0.0,
+                y: 0.0,
+                width: 5.0,
+                height: 4.0,
+            }),
+        };
+        let person = |space: Option<&str>| Person {
+            id: None,
+            name: "A".into(),
+            email: String::new(),
+            title: String::new(),
+            department: String::new(),
+            site_id: Thing::from(("site", "hq")),
+            space_id: space.map(|s| Thing::from(("space", s))),
+            manager_id: None,
+            role: Default::default(),
+            photo: None,
+            bio: None,
+            desk_phone: None,
+            cell_phone: None,
+            photo_data: None,
+        };
+        let rack = Rack {
+            id: None,
+            name: "R1".into(),
+            space_id: Thing::from(("space", "s1")),
+            height_u: 42,
+        };
+
+        let occ = occupancy(
+            &[space],
+            &[person(Some("s1")), person(Some("s1")), person(None)],
+            &[rack],
+        );
+        let lab = &occ["space:s1"];
+        assert_eq!((lab.people, lab.racks), (2, 1));
+        assert_eq!(lab.density, Some(1.0));
+        assert_eq!(heat(lab.load(), 8.0), 0.5);
+        assert_eq!(heat(1.0, 0.0), 0.0);
+    }
+}
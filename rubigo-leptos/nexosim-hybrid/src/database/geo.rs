@@ -1,73 +1,124 @@
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 
+use super::asset_lifecycle::LifecycleState;
+use super::city_search::{self, CityPage, CityQuery};
+use super::floorplan::SpaceBounds;
+use super::versions::{self, Data};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Region {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
     pub city: String,
     pub country: String,
     pub population: u64,
+    /// `(lon, lat)`
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<f64>))]
     pub location: (f64, f64),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct City {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
     pub country: String,
     pub population: u64,
+    /// `(lon, lat)`
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<f64>))]
     pub location: (f64, f64),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Site {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub region_id: Option<Thing>,
+    /// `(lon, lat)`
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<f64>))]
     pub location: (f64, f64),
     pub status: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Building {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub site_id: Thing,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Floor {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub building_id: Thing,
     pub level: i16, // Use i16 for floor number (can be negative for basements)
+    /// Floorplan outline in metres; empty until drawn or uploaded
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Vec<f64>>))]
+    pub outline: Vec<(f64, f64)>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Space {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub floor_id: Thing,
     pub locator: String,
     pub space_type: Option<String>,
+    /// Position on the floorplan; `None` until placed
+    #[serde(default)]
+    pub bounds: Option<SpaceBounds>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Rack {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub space_id: Thing,
     pub height_u: u8,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Device {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub rack_id: Thing,
     pub position_u: u8,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub component_id: Option<Thing>,
+    /// Rack units taken, counting up from `position_u`
+    #[serde(default = "default_height_u")]
+    pub height_u: u8,
+    /// Power drawn in watts; 0 when not known
+    #[serde(default)]
+    pub power_w: u32,
+}
+
+fn default_height_u() -> u8 {
+    1
 }
 
 // =============================================================================
@@ -145,25 +196,43 @@ pub struct NetworkAsset {
 
     #[serde(default)]
     pub notes: Option<String>,
+
+    // Lifecycle
+    #[serde(default)]
+    pub lifecycle: LifecycleState,
+    #[serde(default)]
+    pub warranty_start: Option<String>, // YYYY-MM-DD
+    #[serde(default)]
+    pub warranty_end: Option<String>, // YYYY-MM-DD
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// A seat inside a space, bookable through `desks::DeskRepository`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Desk {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub space_id: Thing,
+    /// Person name from the scenario; seeding turns it into a permanent booking
     pub assigned_to: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Person {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub name: String,
     pub email: String,
     pub title: String,
     pub department: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub site_id: Thing,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub space_id: Option<Thing>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub manager_id: Option<Thing>,
     #[serde(default)]
     pub role: crate::config::RoleType,
@@ -228,6 +297,7 @@ impl GeoRepository {
             count += 1;
         }
 
+        versions::bump(Data::Geo);
         Ok(count)
     }
 
@@ -290,6 +360,7 @@ impl GeoRepository {
             feature_type,
             file_path
         );
+        versions::bump(Data::Geo);
         Ok(count)
     }
 
@@ -342,6 +413,7 @@ impl GeoRepository {
             "Completed high-fidelity city import: {} cities total",
             count
         );
+        versions::bump(Data::Geo);
         Ok(count)
     }
 
@@ -415,6 +487,7 @@ impl GeoRepository {
             count,
             file_path
         );
+        versions::bump(Data::Geo);
         Ok(count)
     }
 
@@ -439,6 +512,7 @@ impl GeoRepository {
         site: Site,
     ) -> anyhow::Result<Site> {
         let created: Option<Site> = db.create("site").content(site).await?;
+        versions::bump(Data::Geo);
         Ok(created.unwrap())
     }
 
@@ -461,14 +535,23 @@ impl GeoRepository {
         Ok(regions)
     }
 
+    /// Fuzzy, population-ranked city search with optional country filter
+    ///
+    /// Candidates are narrowed in the database (country only) and scored in
+    /// process, since typo-tolerant matching can't be expressed as a filter.
     pub async fn search_cities(
         db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
-        query: &str,
-    ) -> anyhow::Result<Vec<City>> {
-        let sql = "SELECT * FROM city WHERE string::lowercase(name) CONTAINS string::lowercase($query) LIMIT 20";
-        let mut result = db.query(sql).bind(("query", query.to_string())).await?;
-        let cities: Vec<City> = result.take(0)?;
-        Ok(cities)
+        query: &CityQuery,
+    ) -> anyhow::Result<CityPage> {
+        let candidates: Vec<City> = match query.country.as_deref().filter(|c| !c.is_empty()) {
+            Some(country) => {
+                let sql = "SELECT * FROM city WHERE string::lowercase(country) = string::lowercase($country)";
+                let mut result = db.query(sql).bind(("country", country.to_string())).await?;
+                result.take(0)?
+            }
+            None => db.select("city").await?,
+        };
+        Ok(city_search::rank(candidates, query))
     }
 
     pub async fn list_regions(
@@ -483,6 +566,7 @@ impl GeoRepository {
         rack: Rack,
     ) -> anyhow::Result<Rack> {
         let created: Option<Rack> = db.create("rack").content(rack).await?;
+        versions::bump(Data::Geo);
         Ok(created.unwrap())
     }
 
@@ -502,6 +586,7 @@ impl GeoRepository {
         building: Building,
     ) -> anyhow::Result<Building> {
         let created: Option<Building> = db.create("building").content(building).await?;
+        versions::bump(Data::Geo);
         Ok(created.unwrap())
     }
 
@@ -527,6 +612,7 @@ impl GeoRepository {
         floor: Floor,
     ) -> anyhow::Result<Floor> {
         let created: Option<Floor> = db.create("floor").content(floor).await?;
+        versions::bump(Data::Geo);
         Ok(created.unwrap())
     }
 
@@ -552,6 +638,7 @@ impl GeoRepository {
         space: Space,
     ) -> anyhow::Result<Space> {
         let created: Option<Space> = db.create("space").content(space).await?;
+        versions::bump(Data::Geo);
         Ok(created.unwrap())
     }
 
@@ -577,6 +664,7 @@ impl GeoRepository {
         device: Device,
     ) -> anyhow::Result<Device> {
         let created: Option<Device> = db.create("device").content(device).await?;
+        versions::bump(Data::Geo);
         Ok(created.unwrap())
     }
 
@@ -590,12 +678,138 @@ impl GeoRepository {
         Ok(devices)
     }
 
+    pub async fn list_all_devices(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+    ) -> anyhow::Result<Vec<Device>> {
+        let devices: Vec<Device> = db.select("device").await?;
+        Ok(devices)
+    }
+
+    // =========================================================================
+    // Hierarchy update / delete
+    // =========================================================================
+
+    pub async fn update_site(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+        site: Site,
+    ) -> anyhow::Result<Option<Site>> {
+        let updated: Option<Site> = db.update(("site", id)).content(site).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    pub async fn delete_site(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+    ) -> anyhow::Result<Option<Site>> {
+        let deleted: Option<Site> = db.delete(("site", id)).await?;
+        versions::bump(Data::Geo);
+        Ok(deleted)
+    }
+
+    pub async fn update_building(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+        building: Building,
+    ) -> anyhow::Result<Option<Building>> {
+        let updated: Option<Building> = db.update(("building", id)).content(building).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    pub async fn delete_building(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+    ) -> anyhow::Result<Option<Building>> {
+        let deleted: Option<Building> = db.delete(("building", id)).await?;
+        versions::bump(Data::Geo);
+        Ok(deleted)
+    }
+
+    pub async fn update_floor(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+        floor: Floor,
+    ) -> anyhow::Result<Option<Floor>> {
+        let updated: Option<Floor> = db.update(("floor", id)).content(floor).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    pub async fn delete_floor(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+    ) -> anyhow::Result<Option<Floor>> {
+        let deleted: Option<Floor> = db.delete(("floor", id)).await?;
+        versions::bump(Data::Geo);
+        Ok(deleted)
+    }
+
+    pub async fn update_space(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+        space: Space,
+    ) -> anyhow::Result<Option<Space>> {
+        let updated: Option<Space> = db.update(("space", id)).content(space).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    pub async fn delete_space(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+    ) -> anyhow::Result<Option<Space>> {
+        let deleted: Option<Space> = db.delete(("space", id)).await?;
+        versions::bump(Data::Geo);
+        Ok(deleted)
+    }
+
+    pub async fn update_rack(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+        rack: Rack,
+    ) -> anyhow::Result<Option<Rack>> {
+        let updated: Option<Rack> = db.update(("rack", id)).content(rack).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    pub async fn delete_rack(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+    ) -> anyhow::Result<Option<Rack>> {
+        let deleted: Option<Rack> = db.delete(("rack", id)).await?;
+        versions::bump(Data::Geo);
+        Ok(deleted)
+    }
+
+    pub async fn update_device(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+        device: Device,
+    ) -> anyhow::Result<Option<Device>> {
+        let updated: Option<Device> = db.update(("device", id)).content(device).await?;
+        versions::bump(Data::Geo);
+        Ok(updated)
+    }
+
+    pub async fn delete_device(
+        db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+        id: &str,
+    ) -> anyhow::Result<Option<Device>> {
+        let deleted: Option<Device> = db.delete(("device", id)).await?;
+        versions::bump(Data::Geo);
+        Ok(deleted)
+    }
+
     // Desk methods
     pub async fn create_desk(
         db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
         desk: Desk,
     ) -> anyhow::Result<Desk> {
         let created: Option<Desk> = db.create("desk").content(desk).await?;
+        versions::bump(Data::Geo);
         Ok(created.unwrap())
     }
 
@@ -622,6 +836,7 @@ impl GeoRepository {
         person: Person,
     ) -> anyhow::Result<Person> {
         let created: Option<Person> = db.create("person").content(person).await?;
+        versions::bump(Data::People);
         Ok(created.unwrap())
     }
 
@@ -659,6 +874,7 @@ impl GeoRepository {
         asset: NetworkAsset,
     ) -> anyhow::Result<NetworkAsset> {
         let created: Option<NetworkAsset> = db.create("network_asset").content(asset).await?;
+        versions::bump(Data::Assets);
         Ok(created.unwrap())
     }
 
@@ -683,6 +899,7 @@ impl GeoRepository {
         asset: NetworkAsset,
     ) -> anyhow::Result<Option<NetworkAsset>> {
         let updated: Option<NetworkAsset> = db.update(("network_asset", id)).content(asset).await?;
+        versions::bump(Data::Assets);
         Ok(updated)
     }
 
@@ -691,6 +908,7 @@ impl GeoRepository {
         id: &str,
     ) -> anyhow::Result<Option<NetworkAsset>> {
         let deleted: Option<NetworkAsset> = db.delete(("network_asset", id)).await?;
+        versions::bump(Data::Assets);
         Ok(deleted)
     }
 
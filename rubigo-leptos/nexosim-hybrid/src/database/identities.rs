@@ -0,0 +1,97 @@
+// External identities
+// Links between people and their accounts at an OIDC provider or in an LDAP directory
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::geo::Person;
+use super::versions::{self, Data};
+
+/// A person's account at an identity provider
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ExternalIdentity {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// `oidc` or `ldap`
+    pub provider: String,
+    /// Stable ID at the provider: the `sub` claim or the entry's DN
+    pub subject: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub person_id: Thing,
+    /// Groups the provider last listed for them
+    pub groups: Vec<String>,
+    /// When they last signed in or were synced
+    pub seen_at: String,
+}
+
+pub struct IdentityRepository;
+
+impl IdentityRepository {
+    pub async fn find(
+        db: &Surreal<Db>,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<ExternalIdentity>> {
+        let mut result = db
+            .query("SELECT * FROM external_identity WHERE provider = $provider AND subject = $subject LIMIT 1")
+            .bind(("provider", provider.to_string()))
+            .bind(("subject", subject.to_string()))
+            .await?;
+        let identities: Vec<ExternalIdentity> = result.take(0)?;
+        Ok(identities.into_iter().next())
+    }
+
+    /// Identities from one provider, or from all of them
+    pub async fn list(db: &Surreal<Db>, provider: Option<&str>) -> Result<Vec<ExternalIdentity>> {
+        let mut identities: Vec<ExternalIdentity> = db.select("external_identity").await?;
+        if let Some(provider) = provider {
+            identities.retain(|i| i.provider == provider);
+        }
+        identities.sort_by(|a, b| a.subject.cmp(&b.subject));
+        Ok(identities)
+    }
+
+    /// Record or refresh the link for `identity.provider` and `identity.subject`
+    pub async fn link(db: &Surreal<Db>, identity: ExternalIdentity) -> Result<ExternalIdentity> {
+        let mut identity = identity;
+        identity.id = None;
+        let saved = match Self::find(db, &identity.provider, &identity.subject).await? {
+            Some(existing) => {
+                let key = existing.id.map(|t| t.id.to_raw()).unwrap_or_default();
+                db.update(("external_identity", key))
+                    .content(identity)
+                    .await?
+            }
+            None => db.create("external_identity").content(identity).await?,
+        };
+        versions::bump(Data::People);
+        saved.ok_or_else(|| anyhow::anyhow!("Failed to link identity"))
+    }
+
+    /// The person with `email`, ignoring case
+    pub async fn person_by_email(db: &Surreal<Db>, email: &str) -> Result<Option<Person>> {
+        let people: Vec<Person> = db.select("person").await?;
+        Ok(people
+            .into_iter()
+            .find(|p| p.email.eq_ignore_ascii_case(email)))
+    }
+
+    /// Create `person`, or replace them when they have an id
+    pub async fn save_person(db: &Surreal<Db>, person: Person) -> Result<Person> {
+        let mut person = person;
+        let saved: Option<Person> = match person.id.take() {
+            Some(id) => {
+                db.update(("person", id.id.to_raw()))
+                    .content(person)
+                    .await?
+            }
+            None => db.create("person").content(person).await?,
+        };
+        versions::bump(Data::People);
+        saved.ok_or_else(|| anyhow::anyhow!("Failed to save person"))
+    }
+}
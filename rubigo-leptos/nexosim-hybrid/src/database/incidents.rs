@@ -0,0 +1,430 @@
+// Incidents
+// Incident records linked to the components and sites they affect, with a
+// severity, an assignee and a status workflow. Every change is appended to
+// the incident's own timeline, so the record reads as the history of the
+// response. Incidents opened by a simulated fault are flagged as training.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+/// How badly an incident hurts, most severe first
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    Major,
+    #[default]
+    Minor,
+    Low,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 4] = [
+        Severity::Critical,
+        Severity::Major,
+        Severity::Minor,
+        Severity::Low,
+    ];
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Critical => write!(f, "critical"),
+            Severity::Major => write!(f, "major"),
+            Severity::Minor => write!(f, "minor"),
+            Severity::Low => write!(f, "low"),
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Severity::ALL
+            .into_iter()
+            .find(|severity| severity.to_string() == s.to_lowercase())
+            .ok_or_else(|| anyhow::anyhow!("Unknown severity '{}'", s))
+    }
+}
+
+/// Where an incident is in its response
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum IncidentStatus {
+    #[default]
+    Open,
+    Investigating,
+    Mitigated,
+    Resolved,
+    Closed,
+}
+
+impl IncidentStatus {
+    pub const ALL: [IncidentStatus; 5] = [
+        IncidentStatus::Open,
+        IncidentStatus::Investigating,
+        IncidentStatus::Mitigated,
+        IncidentStatus::Resolved,
+        IncidentStatus::Closed,
+    ];
+
+    /// Statuses reachable from this one; a resolved incident can be
+    /// reopened for investigation until it is closed, which is final
+    pub fn next_states(&self) -> &'static [IncidentStatus] {
+        match self {
+            IncidentStatus::Open => &[IncidentStatus::Investigating, IncidentStatus::Resolved],
+            IncidentStatus::Investigating => &[IncidentStatus::Mitigated, IncidentStatus::Resolved],
+            IncidentStatus::Mitigated => &[IncidentStatus::Investigating, IncidentStatus::Resolved],
+            IncidentStatus::Resolved => &[IncidentStatus::Investigating, IncidentStatus::Closed],
+            IncidentStatus::Closed => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: IncidentStatus) -> bool {
+        self.next_states().contains(&next)
+    }
+
+    /// Whether the incident still needs work
+    pub fn is_active(&self) -> bool {
+        !matches!(self, IncidentStatus::Resolved | IncidentStatus::Closed)
+    }
+}
+
+impl std::fmt::Display for IncidentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IncidentStatus::Open => write!(f, "open"),
+            IncidentStatus::Investigating => write!(f, "investigating"),
+            IncidentStatus::Mitigated => write!(f, "mitigated"),
+            IncidentStatus::Resolved => write!(f, "resolved"),
+            IncidentStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+impl std::str::FromStr for IncidentStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        IncidentStatus::ALL
+            .into_iter()
+            .find(|status| status.to_string() == s.to_lowercase())
+            .ok_or_else(|| anyhow::anyhow!("Unknown incident status '{}'", s))
+    }
+}
+
+/// What a timeline entry records
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    Opened,
+    Status,
+    Assigned,
+    Note,
+    /// Someone on call was paged
+    Escalated,
+}
+
+/// One event in an incident's history
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TimelineEntry {
+    /// `YYYY-MM-DD HH:MM:SS UTC`
+    pub at: String,
+    pub kind: EntryKind,
+    /// Persona (person name) who made the change; `None` for the system
+    #[serde(default)]
+    pub author: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Incident {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub status: IncidentStatus,
+    /// Ids of the affected components
+    #[serde(default)]
+    pub components: Vec<u32>,
+    /// Affected sites
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    pub sites: Vec<Thing>,
+    /// Person working the incident
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub assignee_id: Option<Thing>,
+    pub opened_at: String,
+    #[serde(default)]
+    pub resolved_at: Option<String>,
+    /// Opened by a simulated fault for a training scenario
+    #[serde(default)]
+    pub training: bool,
+    /// The simulation run whose fault opened it
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub run_id: Option<Thing>,
+    /// Escalation policy paging the on-call responders; the default policy
+    /// when opened without one
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub escalation_policy_id: Option<Thing>,
+    /// Levels of the policy paged so far
+    #[serde(default)]
+    pub escalation_level: usize,
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+}
+
+impl Incident {
+    /// A new open incident with its first timeline entry
+    pub fn new(
+        title: impl Into<String>,
+        severity: Severity,
+        author: Option<String>,
+        now: &str,
+    ) -> Self {
+        let title = title.into();
+        Self {
+            id: None,
+            timeline: vec![TimelineEntry {
+                at: now.to_string(),
+                kind: EntryKind::Opened,
+                author,
+                text: format!("Opened as {severity}: {title}"),
+            }],
+            title,
+            description: None,
+            severity,
+            status: IncidentStatus::Open,
+            components: Vec::new(),
+            sites: Vec::new(),
+            assignee_id: None,
+            opened_at: now.to_string(),
+            resolved_at: None,
+            training: false,
+            run_id: None,
+            escalation_policy_id: None,
+            escalation_level: 0,
+        }
+    }
+
+    /// Key of the record id, for use in URLs
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+
+    fn log(&mut self, kind: EntryKind, author: Option<String>, text: String, now: &str) {
+        self.timeline.push(TimelineEntry {
+            at: now.to_string(),
+            kind,
+            author,
+            text,
+        });
+    }
+
+    /// Move to `to`, noting why on the timeline
+    pub fn transition(
+        &mut self,
+        to: IncidentStatus,
+        author: Option<String>,
+        note: Option<&str>,
+        now: &str,
+    ) -> Result<()> {
+        if !self.status.can_transition_to(to) {
+            anyhow::bail!("Cannot move incident from {} to {}", self.status, to);
+        }
+        let text = match note.map(str::trim).filter(|n| !n.is_empty()) {
+            Some(note) => format!("{} → {}: {}", self.status, to, note),
+            None => format!("{} → {}", self.status, to),
+        };
+        self.log(EntryKind::Status, author, text, now);
+        self.status = to;
+        self.resolved_at = match to {
+            IncidentStatus::Resolved => Some(now.to_string()),
+            IncidentStatus::Closed => self.resolved_at.take(),
+            _ => None,
+        };
+        Ok(())
+    }
+
+    /// Hand the incident to `assignee` (named `name`), or unassign it
+    pub fn assign(
+        &mut self,
+        assignee: Option<Thing>,
+        name: &str,
+        author: Option<String>,
+        now: &str,
+    ) {
+        let text = match &assignee {
+            Some(_) => format!("Assigned to {name}"),
+            None => "Unassigned".to_string(),
+        };
+        self.assignee_id = assignee;
+        self.log(EntryKind::Assigned, author, text, now);
+    }
+
+    pub fn note(&mut self, text: &str, author: Option<String>, now: &str) {
+        self.log(EntryKind::Note, author, text.trim().to_string(), now);
+    }
+
+    /// Whether the on-call chain should still be paged: nobody has picked
+    /// the incident up yet
+    pub fn unacknowledged(&self) -> bool {
+        self.status == IncidentStatus::Open
+    }
+
+    /// Record that escalation level `level` paged `person`, on call for `rotation`
+    pub fn paged(&mut self, level: usize, person: &str, rotation: &str, now: &str) {
+        let text = format!(
+            "Paged {person}, on call for {rotation} (level {})",
+            level + 1
+        );
+        self.log(EntryKind::Escalated, None, text, now);
+        self.escalation_level = self.escalation_level.max(level + 1);
+    }
+}
+
+pub struct IncidentRepository;
+
+impl IncidentRepository {
+    pub async fn create(db: &Surreal<Db>, incident: Incident) -> Result<Incident> {
+        let created: Incident = db
+            .create("incident")
+            .content(incident)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create incident"))?;
+        versions::bump(Data::Incidents);
+        Ok(created)
+    }
+
+    /// All incidents, most severe first, then newest first
+    pub async fn list(db: &Surreal<Db>) -> Result<Vec<Incident>> {
+        let mut incidents: Vec<Incident> = db.select("incident").await?;
+        incidents.sort_by(|a, b| {
+            a.severity
+                .cmp(&b.severity)
+                .then_with(|| b.opened_at.cmp(&a.opened_at))
+        });
+        Ok(incidents)
+    }
+
+    pub async fn get(db: &Surreal<Db>, id: &str) -> Result<Option<Incident>> {
+        Ok(db.select(("incident", id)).await?)
+    }
+
+    /// Apply `change` to incident `id` and store it; `None` when there is
+    /// no such incident
+    pub async fn update(
+        db: &Surreal<Db>,
+        id: &str,
+        change: impl FnOnce(&mut Incident) -> Result<()>,
+    ) -> Result<Option<Incident>> {
+        let Some(mut incident) = Self::get(db, id).await? else {
+            return Ok(None);
+        };
+        change(&mut incident)?;
+        incident.id = None;
+        let updated: Option<Incident> = db.update(("incident", id)).content(incident).await?;
+        versions::bump(Data::Incidents);
+        Ok(updated)
+    }
+
+    pub async fn delete(db: &Surreal<Db>, id: &str) -> Result<()> {
+        let _deleted: Option<Incident> = db.delete(("incident", id)).await?;
+        versions::bump(Data::Incidents);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_workflow() {
+        use IncidentStatus::*;
+        assert!(Open.can_transition_to(Investigating));
+        assert!(Resolved.can_transition_to(Investigating));
+        assert!(!Open.can_transition_to(Closed));
+        assert!(Closed.next_states().is_empty());
+        for status in IncidentStatus::ALL {
+            assert_eq!(
+                status.to_string().parse::<IncidentStatus>().unwrap(),
+                status
+            );
+        }
+        assert_eq!("MAJOR".parse::<Severity>().unwrap(), Severity::Major);
+        assert!(Severity::Critical < Severity::Low);
+    }
+
+    #[test]
+    fn changes_are_kept_on_the_timeline() {
+        let mut incident = Incident::new(
+            "Core switch down",
+            Severity::Critical,
+            Some("Ann".into()),
+            "t0",
+        );
+        incident.assign(
+            Some(Thing::from(("person", "bob"))),
+            "Bob",
+            Some("Ann".into()),
+            "t1",
+        );
+        incident
+            .transition(
+                IncidentStatus::Investigating,
+                Some("Bob".into()),
+                None,
+                "t2",
+            )
+            .unwrap();
+        incident
+            .transition(
+                IncidentStatus::Resolved,
+                Some("Bob".into()),
+                Some("PSU swapped"),
+                "t3",
+            )
+            .unwrap();
+        assert!(incident
+            .transition(IncidentStatus::Open, None, None, "t4")
+            .is_err());
+        incident.note("Postmortem booked", None, "t5");
+
+        assert_eq!(incident.resolved_at.as_deref(), Some("t3"));
+        let texts: Vec<&str> = incident.timeline.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Opened as critical: Core switch down",
+                "Assigned to Bob",
+                "open → investigating",
+                "investigating → resolved: PSU swapped",
+                "Postmortem booked",
+            ]
+        );
+        incident
+            .transition(IncidentStatus::Investigating, None, None, "t6")
+            .unwrap();
+        assert_eq!(incident.resolved_at, None);
+    }
+}
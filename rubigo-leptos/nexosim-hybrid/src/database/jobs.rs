@@ -0,0 +1,106 @@
+// Background job records
+// Tracks long-running work (data imports) so its status survives the task that runs it
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+/// Lifecycle of a background job
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    #[default]
+    Queued,
+    Running,
+    /// Failed an attempt and is waiting to retry
+    Retrying,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    /// No further attempts will be made
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Succeeded | JobStatus::Failed)
+    }
+}
+
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobStatus::Queued => write!(f, "queued"),
+            JobStatus::Running => write!(f, "running"),
+            JobStatus::Retrying => write!(f, "retrying"),
+            JobStatus::Succeeded => write!(f, "succeeded"),
+            JobStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Job {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// Machine-readable job kind (e.g. "import.cities")
+    pub kind: String,
+    /// Human-readable description
+    pub label: String,
+    pub status: JobStatus,
+    /// Completion percentage (0-100)
+    pub progress: u8,
+    pub attempts: u32,
+    pub max_attempts: u32,
+    /// Message from the last failed attempt
+    pub error: Option<String>,
+    /// Result summary from the successful attempt
+    pub result: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Job {
+    /// Key of the record id, for use in URLs
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+}
+
+pub struct JobRepository;
+
+impl JobRepository {
+    pub async fn create(db: &Surreal<Db>, job: Job) -> Result<Job> {
+        let created: Job = db
+            .create("job")
+            .content(job)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create job"))?;
+        versions::bump(Data::Jobs);
+        Ok(created)
+    }
+
+    pub async fn get_all(db: &Surreal<Db>) -> Result<Vec<Job>> {
+        let mut jobs: Vec<Job> = db.select("job").await?;
+        // Newest first
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(jobs)
+    }
+
+    pub async fn get_by_id(db: &Surreal<Db>, id: &str) -> Result<Option<Job>> {
+        let job: Option<Job> = db.select(("job", id)).await?;
+        Ok(job)
+    }
+
+    pub async fn update(db: &Surreal<Db>, id: &str, job: Job) -> Result<Option<Job>> {
+        let mut job = job;
+        job.id = None;
+        let updated: Option<Job> = db.update(("job", id)).content(job).await?;
+        versions::bump(Data::Jobs);
+        Ok(updated)
+    }
+}
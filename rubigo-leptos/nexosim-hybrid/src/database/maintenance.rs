@@ -0,0 +1,435 @@
+// Database maintenance
+// Reseeding, scenario snapshots, schema migrations and vacuuming, as run by `rubigo-admin`
+// through the admin API. Deletes elsewhere are hard deletes that don't cascade, so removing a
+// site leaves its buildings (and everything below them) pointing at nothing; vacuum sweeps those.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::cabling::{Cable, CablingRepository, PatchPanel, Port};
+use super::calendar::{CalendarRepository, Meeting};
+use super::components::ComponentRepository;
+use super::connections::ConnectionRepository;
+use super::desks::{DeskBooking, DeskRepository};
+use super::geo::{
+    Building, Desk, Device, Floor, GeoRepository, NetworkAsset, Person, Rack, Region, Site, Space,
+};
+use super::versions;
+use crate::config::{ComponentConfig, ConnectionConfig};
+
+/// Tables filled by `ComponentRepository::seed_from_toml`, cleared before a reseed
+pub const SCENARIO_TABLES: [&str; 21] = [
+    "component",
+    "connection",
+    "region",
+    "site",
+    "building",
+    "floor",
+    "space",
+    "rack",
+    "desk",
+    "desk_booking",
+    "device",
+    "patch_panel",
+    "port",
+    "cable",
+    "person",
+    "network_asset",
+    "asset_transition",
+    "maintenance_ticket",
+    "meeting",
+    "conversation",
+    "chat_message",
+];
+
+/// Schema changes in the order they apply; never edit or reuse an id once shipped
+pub const MIGRATIONS: [(&str, &str); 2] = [
+    (
+        "0001_parent_indexes",
+        "DEFINE INDEX IF NOT EXISTS building_site ON TABLE building FIELDS site_id;
+         DEFINE INDEX IF NOT EXISTS floor_building ON TABLE floor FIELDS building_id;
+         DEFINE INDEX IF NOT EXISTS space_floor ON TABLE space FIELDS floor_id;
+         DEFINE INDEX IF NOT EXISTS rack_space ON TABLE rack FIELDS space_id;
+         DEFINE INDEX IF NOT EXISTS device_rack ON TABLE device FIELDS rack_id;
+         DEFINE INDEX IF NOT EXISTS person_site ON TABLE person FIELDS site_id;
+         DEFINE INDEX IF NOT EXISTS asset_rack ON TABLE network_asset FIELDS rack_id;",
+    ),
+    (
+        "0002_meeting_start",
+        "DEFINE INDEX IF NOT EXISTS meeting_start ON TABLE meeting FIELDS start_time;",
+    ),
+];
+
+/// A migration and when it was applied, if it has been
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MigrationStatus {
+    pub id: String,
+    pub applied_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AppliedMigration {
+    /// `schema_migration:<id>`
+    id: Option<Thing>,
+    applied_at: String,
+}
+
+/// Everything a scenario seeds, as currently stored
+#[derive(Debug, Serialize, Clone)]
+pub struct ScenarioSnapshot {
+    pub regions: Vec<Region>,
+    pub sites: Vec<Site>,
+    pub buildings: Vec<Building>,
+    pub floors: Vec<Floor>,
+    pub spaces: Vec<Space>,
+    pub racks: Vec<Rack>,
+    pub devices: Vec<Device>,
+    pub desks: Vec<Desk>,
+    pub people: Vec<Person>,
+    pub assets: Vec<NetworkAsset>,
+    pub components: Vec<ComponentConfig>,
+    pub connections: Vec<ConnectionConfig>,
+    pub events: Vec<Meeting>,
+}
+
+/// Records pointing at a parent that no longer exists, by table
+#[derive(Debug, Default, Clone)]
+pub struct Orphans {
+    pub records: BTreeMap<&'static str, Vec<Thing>>,
+    pub connections: Vec<ConnectionConfig>,
+}
+
+impl Orphans {
+    /// Orphan count by table
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        let mut counts: BTreeMap<String, usize> = self
+            .records
+            .iter()
+            .filter(|(_, ids)| !ids.is_empty())
+            .map(|(table, ids)| (table.to_string(), ids.len()))
+            .collect();
+        if !self.connections.is_empty() {
+            counts.insert("connection".to_string(), self.connections.len());
+        }
+        counts
+    }
+}
+
+/// Current contents of every table that can hold orphans
+pub struct Hierarchy {
+    pub sites: Vec<Site>,
+    pub buildings: Vec<Building>,
+    pub floors: Vec<Floor>,
+    pub spaces: Vec<Space>,
+    pub racks: Vec<Rack>,
+    pub devices: Vec<Device>,
+    pub desks: Vec<Desk>,
+    pub bookings: Vec<DeskBooking>,
+    pub panels: Vec<PatchPanel>,
+    pub ports: Vec<Port>,
+    pub cables: Vec<Cable>,
+    pub components: Vec<ComponentConfig>,
+    pub connections: Vec<ConnectionConfig>,
+}
+
+/// Ids of `records` that still have a parent, and ids of those whose parent is gone
+fn split<T>(
+    records: &[T],
+    id: impl Fn(&T) -> Option<&Thing>,
+    has_parent: impl Fn(&T) -> bool,
+) -> (HashSet<Thing>, Vec<Thing>) {
+    let mut kept = HashSet::new();
+    let mut orphaned = Vec::new();
+    for record in records {
+        let Some(id) = id(record) else { continue };
+        if has_parent(record) {
+            kept.insert(id.clone());
+        } else {
+            orphaned.push(id.clone());
+        }
+    }
+    (kept, orphaned)
+}
+
+/// Find orphans top-down, so removing a building also orphans its floors in the same pass
+pub fn find_orphans(h: &Hierarchy) -> Orphans {
+    let sites: HashSet<Thing> = h.sites.iter().filter_map(|s| s.id.clone()).collect();
+    let (buildings, orphaned_buildings) = split(
+        &h.buildings,
+        |b| b.id.as_ref(),
+        |b| sites.contains(&b.site_id),
+    );
+    let (floors, orphaned_floors) = split(
+        &h.floors,
+        |f| f.id.as_ref(),
+        |f| buildings.contains(&f.building_id),
+    );
+    let (spaces, orphaned_spaces) = split(
+        &h.spaces,
+        |s| s.id.as_ref(),
+        |s| floors.contains(&s.floor_id),
+    );
+    let (racks, orphaned_racks) = split(
+        &h.racks,
+        |r| r.id.as_ref(),
+        |r| spaces.contains(&r.space_id),
+    );
+    let (devices, orphaned_devices) = split(
+        &h.devices,
+        |d| d.id.as_ref(),
+        |d| racks.contains(&d.rack_id),
+    );
+    let (desks, orphaned_desks) = split(
+        &h.desks,
+        |d| d.id.as_ref(),
+        |d| spaces.contains(&d.space_id),
+    );
+    let (_, orphaned_bookings) = split(
+        &h.bookings,
+        |b| b.id.as_ref(),
+        |b| desks.contains(&b.desk_id),
+    );
+
+    let panels: HashSet<Thing> = h.panels.iter().filter_map(|p| p.id.clone()).collect();
+    let (ports, orphaned_ports) = split(
+        &h.ports,
+        |p| p.id.as_ref(),
+        |p| devices.contains(&p.owner) || panels.contains(&p.owner),
+    );
+    let (_, orphaned_cables) = split(
+        &h.cables,
+        |c| c.id.as_ref(),
+        |c| ports.contains(&c.a) && ports.contains(&c.b),
+    );
+
+    let components: HashSet<u32> = h.components.iter().map(|c| c.id).collect();
+    let connections = h
+        .connections
+        .iter()
+        .filter(|c| !components.contains(&c.from) || !components.contains(&c.to))
+        .cloned()
+        .collect();
+
+    Orphans {
+        records: BTreeMap::from([
+            ("building", orphaned_buildings),
+            ("floor", orphaned_floors),
+            ("space", orphaned_spaces),
+            ("rack", orphaned_racks),
+            ("device", orphaned_devices),
+            ("desk", orphaned_desks),
+            ("desk_booking", orphaned_bookings),
+            ("port", orphaned_ports),
+            ("cable", orphaned_cables),
+        ]),
+        connections,
+    }
+}
+
+pub struct MaintenanceRepository;
+
+impl MaintenanceRepository {
+    /// Empty every scenario table, returning how many were cleared
+    pub async fn clear_scenario(db: &Surreal<Db>) -> Result<usize> {
+        for table in SCENARIO_TABLES {
+            db.query(format!("DELETE {table}")).await?.check()?;
+        }
+        tracing::info!("Cleared {} scenario tables", SCENARIO_TABLES.len());
+        versions::bump_all();
+        Ok(SCENARIO_TABLES.len())
+    }
+
+    pub async fn snapshot(db: &Surreal<Db>) -> Result<ScenarioSnapshot> {
+        Ok(ScenarioSnapshot {
+            regions: GeoRepository::list_regions(db).await?,
+            sites: GeoRepository::list_sites(db).await?,
+            buildings: GeoRepository::list_all_buildings(db).await?,
+            floors: GeoRepository::list_all_floors(db).await?,
+            spaces: GeoRepository::list_all_spaces(db).await?,
+            racks: GeoRepository::list_all_racks(db).await?,
+            devices: GeoRepository::list_all_devices(db).await?,
+            desks: GeoRepository::list_all_desks(db).await?,
+            people: GeoRepository::list_all_people(db).await?,
+            assets: GeoRepository::list_all_assets(db).await?,
+            components: ComponentRepository::get_all(db).await?,
+            connections: ConnectionRepository::get_all(db).await?,
+            events: CalendarRepository::get_all(db).await?,
+        })
+    }
+
+    /// Every known migration, applied or not
+    pub async fn migrations(db: &Surreal<Db>) -> Result<Vec<MigrationStatus>> {
+        let applied: Vec<AppliedMigration> = db.select("schema_migration").await?;
+        Ok(MIGRATIONS
+            .iter()
+            .map(|(id, _)| MigrationStatus {
+                id: id.to_string(),
+                applied_at: applied
+                    .iter()
+                    .find(|a| a.id.as_ref().is_some_and(|t| t.id.to_raw() == *id))
+                    .map(|a| a.applied_at.clone()),
+            })
+            .collect())
+    }
+
+    /// Apply pending migrations in order, returning the ids applied
+    pub async fn migrate(db: &Surreal<Db>, now: &str) -> Result<Vec<String>> {
+        let mut applied = Vec::new();
+        for status in Self::migrations(db).await? {
+            if status.applied_at.is_some() {
+                continue;
+            }
+            let Some((id, statements)) = MIGRATIONS.iter().find(|(id, _)| *id == status.id) else {
+                continue;
+            };
+            db.query(*statements).await?.check()?;
+            let _: Option<AppliedMigration> = db
+                .upsert(("schema_migration", *id))
+                .content(AppliedMigration {
+                    id: None,
+                    applied_at: now.to_string(),
+                })
+                .await?;
+            tracing::info!("Applied migration {}", id);
+            applied.push(id.to_string());
+        }
+        if !applied.is_empty() {
+            versions::bump_all();
+        }
+        Ok(applied)
+    }
+
+    pub async fn hierarchy(db: &Surreal<Db>) -> Result<Hierarchy> {
+        Ok(Hierarchy {
+            sites: GeoRepository::list_sites(db).await?,
+            buildings: GeoRepository::list_all_buildings(db).await?,
+            floors: GeoRepository::list_all_floors(db).await?,
+            spaces: GeoRepository::list_all_spaces(db).await?,
+            racks: GeoRepository::list_all_racks(db).await?,
+            devices: GeoRepository::list_all_devices(db).await?,
+            desks: GeoRepository::list_all_desks(db).await?,
+            bookings: DeskRepository::list_all_bookings(db).await?,
+            panels: CablingRepository::list_all_panels(db).await?,
+            ports: CablingRepository::list_all_ports(db).await?,
+            cables: CablingRepository::list_all_cables(db).await?,
+            components: ComponentRepository::get_all(db).await?,
+            connections: ConnectionRepository::get_all(db).await?,
+        })
+    }
+
+    /// Remove records whose parent was deleted; with `dry_run` only count them
+    pub async fn vacuum(db: &Surreal<Db>, dry_run: bool) -> Result<BTreeMap<String, usize>> {
+        let orphans = find_orphans(&Self::hierarchy(db).await?);
+        if !dry_run {
+            for ids in orphans.records.values().filter(|ids| !ids.is_empty()) {
+                db.query("DELETE $ids")
+                    .bind(("ids", ids.clone()))
+                    .await?
+                    .check()?;
+            }
+            for connection in &orphans.connections {
+                ConnectionRepository::delete(db, connection.from, connection.to).await?;
+            }
+            versions::bump_all();
+        }
+        let counts = orphans.counts();
+        tracing::info!(
+            "Vacuum {}: {:?}",
+            if dry_run { "dry run" } else { "removed" },
+            counts
+        );
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ComponentType, DevicePlacement};
+
+    fn thing(table: &str, key: &str) -> Option<Thing> {
+        Some(Thing::from((table, key)))
+    }
+
+    fn hierarchy() -> Hierarchy {
+        Hierarchy {
+            sites: vec![Site {
+                id: thing("site", "hq"),
+                name: "HQ".into(),
+                region_id: None,
+                location: (0.0, 0.0),
+                status: "active".into(),
+            }],
+            buildings: vec![
+                Building {
+                    id: thing("building", "main"),
+                    name: "Main".into(),
+                    site_id: Thing::from(("site", "hq")),
+                },
+                Building {
+                    id: thing("building", "old"),
+                    name: "Old".into(),
+                    site_id: Thing::from(("site", "gone")),
+                },
+            ],
+            floors: vec![Floor {
+                id: thing("floor", "old-1"),
+                name: "1".into(),
+                building_id: Thing::from(("building", "old")),
+                level: 1,
+                outline: Vec::new(),
+            }],
+            spaces: Vec::new(),
+            racks: Vec::new(),
+            devices: Vec::new(),
+            desks: Vec::new(),
+            bookings: Vec::new(),
+            panels: Vec::new(),
+            ports: Vec::new(),
+            cables: Vec::new(),
+            components: vec![ComponentConfig {
+                id: 1,
+                name: "r1".into(),
+                component_type: ComponentType::Router,
+                placement: DevicePlacement::default(),
+                power: None,
+            }],
+            connections: vec![ConnectionConfig { from: 1, to: 2 }],
+        }
+    }
+
+    #[test]
+    fn orphans_cascade_down_the_hierarchy() {
+        let orphans = find_orphans(&hierarchy());
+        assert_eq!(
+            orphans.records["building"],
+            vec![Thing::from(("building", "old"))]
+        );
+        // The floor's building still exists but is itself orphaned
+        assert_eq!(
+            orphans.records["floor"],
+            vec![Thing::from(("floor", "old-1"))]
+        );
+        assert_eq!(orphans.connections.len(), 1);
+
+        let counts = orphans.counts();
+        assert_eq!(counts.get("building"), Some(&1));
+        assert_eq!(counts.get("connection"), Some(&1));
+        assert!(!counts.contains_key("space"));
+    }
+
+    #[test]
+    fn migration_ids_are_unique_and_ordered() {
+        let ids: Vec<&str> = MIGRATIONS.iter().map(|(id, _)| *id).collect();
+        let mut sorted = ids.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(ids, sorted);
+    }
+}
@@ -1,9 +1,32 @@
+pub mod asset_lifecycle;
+pub mod cabling;
+pub mod capacity;
 pub mod calendar;
+pub mod chat;
+pub mod city_search;
 pub mod components;
 pub mod connections;
+pub mod desks;
+pub mod device_links;
+pub mod email;
+pub mod flags;
+pub mod floorplan;
 pub mod geo;
+pub mod identities;
+pub mod incidents;
+pub mod jobs;
+pub mod maintenance;
 pub mod models;
+pub mod netbox;
+pub mod notifications;
+pub mod oncall;
+pub mod reports;
+pub mod sandbox;
+pub mod schedules;
 pub mod simulation;
+pub mod telemetry_sources;
+pub mod versions;
+pub mod webhooks;
 
 use anyhow::Result;
 use surrealdb::Surreal;
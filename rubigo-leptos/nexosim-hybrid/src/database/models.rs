@@ -1,4 +1,4 @@
-use crate::config::{ComponentConfig, ComponentType, DevicePlacement};
+use crate::config::{ComponentConfig, ComponentType, DevicePlacement, PowerProfile};
 use serde::{Deserialize, Deserializer, Serialize};
 use surrealdb::sql::Thing;
 
@@ -21,6 +21,10 @@ pub struct ComponentDbDto {
     pub placement_type: String,
     #[serde(default)]
     pub placement_data: serde_json::Value,
+
+    // Power draw, when set on the component itself
+    #[serde(default)]
+    pub power: Option<PowerProfile>,
 }
 
 fn thing_to_u32<'de, D>(deserializer: D) -> Result<u32, D::Error>
@@ -80,6 +84,7 @@ impl From<ComponentConfig> for ComponentDbDto {
             type_data,
             placement_type,
             placement_data,
+            power: config.power,
         }
     }
 }
@@ -162,6 +167,7 @@ impl TryFrom<ComponentDbDto> for ComponentConfig {
             name: dto.name,
             component_type,
             placement,
+            power: dto.power,
         })
     }
 }
@@ -0,0 +1,82 @@
+// NetBox links
+// Which record each object imported from NetBox became, so syncs update
+// what they created before instead of adding it again
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+/// A NetBox object and the record it was imported as
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NetboxLink {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// `site`, `rack`, `device`, `interface` or `cable`
+    pub kind: String,
+    pub netbox_id: u64,
+    /// `site:…`, `rack:…`, `device:…`, `port:…` or `cable:…`
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub record: Thing,
+    pub synced_at: String,
+}
+
+fn key(kind: &str, netbox_id: u64) -> String {
+    format!("{kind}_{netbox_id}")
+}
+
+pub struct NetboxRepository;
+
+impl NetboxRepository {
+    /// Links of one kind, or of every kind
+    pub async fn list(db: &Surreal<Db>, kind: Option<&str>) -> Result<Vec<NetboxLink>> {
+        let mut links: Vec<NetboxLink> = db.select("netbox_link").await?;
+        if let Some(kind) = kind {
+            links.retain(|l| l.kind == kind);
+        }
+        links.sort_by(|a, b| a.kind.cmp(&b.kind).then(a.netbox_id.cmp(&b.netbox_id)));
+        Ok(links)
+    }
+
+    /// Records of one kind by NetBox id
+    pub async fn records(db: &Surreal<Db>, kind: &str) -> Result<HashMap<u64, Thing>> {
+        let links = Self::list(db, Some(kind)).await?;
+        Ok(links.into_iter().map(|l| (l.netbox_id, l.record)).collect())
+    }
+
+    /// Record that NetBox object `kind` `netbox_id` is `record`
+    pub async fn link(
+        db: &Surreal<Db>,
+        kind: &str,
+        netbox_id: u64,
+        record: Thing,
+        synced_at: &str,
+    ) -> Result<()> {
+        let link = NetboxLink {
+            id: None,
+            kind: kind.to_string(),
+            netbox_id,
+            record,
+            synced_at: synced_at.to_string(),
+        };
+        let _saved: Option<NetboxLink> = db
+            .upsert(("netbox_link", key(kind, netbox_id)))
+            .content(link)
+            .await?;
+        versions::bump(Data::Geo);
+        Ok(())
+    }
+
+    /// Forget a link once its record has been removed
+    pub async fn unlink(db: &Surreal<Db>, kind: &str, netbox_id: u64) -> Result<()> {
+        let _deleted: Option<NetboxLink> = db.delete(("netbox_link", key(kind, netbox_id))).await?;
+        versions::bump(Data::Geo);
+        Ok(())
+    }
+}
@@ -0,0 +1,324 @@
+// Notifications
+// Inbox records for personas (people, by name). A notification names its
+// recipient or goes to everyone; read state is kept per persona, so a
+// broadcast stays unread for whoever has not opened it yet
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::calendar::Meeting;
+use super::geo::Person;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    MeetingInvite,
+    SimulationComplete,
+    ImportFinished,
+    ScheduleFailed,
+    IncidentAssigned,
+    /// Paged as the on-call responder for an unacknowledged incident
+    IncidentEscalated,
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationKind::MeetingInvite => write!(f, "meeting_invite"),
+            NotificationKind::SimulationComplete => write!(f, "simulation_complete"),
+            NotificationKind::ImportFinished => write!(f, "import_finished"),
+            NotificationKind::ScheduleFailed => write!(f, "schedule_failed"),
+            NotificationKind::IncidentAssigned => write!(f, "incident_assigned"),
+            NotificationKind::IncidentEscalated => write!(f, "incident_escalated"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Notification {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub kind: NotificationKind,
+    /// Persona (person name) the notification is for; `None` for everyone
+    pub recipient: Option<String>,
+    pub title: String,
+    pub body: Option<String>,
+    /// Page to open from the inbox, e.g. `/?tab=calendar`
+    pub link: Option<String>,
+    pub created_at: String,
+    /// Personas that have read it
+    #[serde(default)]
+    pub read_by: Vec<String>,
+}
+
+/// A notification as one persona sees it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NotificationView {
+    /// Record key, for `/api/notifications/{id}/read`
+    pub id: String,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: Option<String>,
+    pub link: Option<String>,
+    pub created_at: String,
+    pub read: bool,
+}
+
+impl Notification {
+    pub fn new(
+        kind: NotificationKind,
+        recipient: Option<String>,
+        title: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: None,
+            kind,
+            recipient,
+            title: title.into(),
+            body: None,
+            link: None,
+            created_at: String::new(),
+            read_by: Vec::new(),
+        }
+    }
+
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn with_link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    pub fn visible_to(&self, persona: &str) -> bool {
+        self.recipient.as_deref().is_none_or(|r| r == persona)
+    }
+
+    pub fn is_read_by(&self, persona: &str) -> bool {
+        self.read_by.iter().any(|p| p == persona)
+    }
+
+    pub fn view_for(&self, persona: &str) -> NotificationView {
+        NotificationView {
+            id: self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default(),
+            kind: self.kind,
+            title: self.title.clone(),
+            body: self.body.clone(),
+            link: self.link.clone(),
+            created_at: self.created_at.clone(),
+            read: self.is_read_by(persona),
+        }
+    }
+}
+
+/// Notifications visible to `persona` that it has not read
+pub fn unread_count(notifications: &[Notification], persona: &str) -> usize {
+    notifications
+        .iter()
+        .filter(|n| n.visible_to(persona) && !n.is_read_by(persona))
+        .count()
+}
+
+/// One invite per participant, skipping the organizer
+pub fn meeting_invites(meeting: &Meeting, people: &[Person]) -> Vec<Notification> {
+    let name_of = |id: &Thing| {
+        people
+            .iter()
+            .find(|p| p.id.as_ref() == Some(id))
+            .map(|p| p.name.clone())
+    };
+    let organizer = meeting.organizer_id.as_ref().and_then(name_of);
+    let when = meeting.start_time.replacen('T', " ", 1);
+    let body = match &organizer {
+        Some(name) => format!("{} invited you · {}", name, when),
+        None => when,
+    };
+    meeting
+        .participant_ids
+        .iter()
+        .filter(|id| Some(*id) != meeting.organizer_id.as_ref())
+        .filter_map(name_of)
+        .map(|name| {
+            Notification::new(
+                NotificationKind::MeetingInvite,
+                Some(name),
+                format!("Invitation: {}", meeting.title),
+            )
+            .with_body(body.clone())
+            .with_link("/?tab=calendar")
+        })
+        .collect()
+}
+
+pub struct NotificationRepository;
+
+impl NotificationRepository {
+    pub async fn create(db: &Surreal<Db>, notification: Notification) -> Result<Notification> {
+        let created: Notification = db
+            .create("notification")
+            .content(notification)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create notification"))?;
+        Ok(created)
+    }
+
+    /// Newest first
+    pub async fn list_all(db: &Surreal<Db>) -> Result<Vec<Notification>> {
+        let mut notifications: Vec<Notification> = db.select("notification").await?;
+        notifications.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(notifications)
+    }
+
+    /// Newest `limit` notifications visible to `persona`
+    pub async fn list_for(
+        db: &Surreal<Db>,
+        persona: &str,
+        limit: usize,
+    ) -> Result<Vec<NotificationView>> {
+        Ok(Self::list_all(db)
+            .await?
+            .iter()
+            .filter(|n| n.visible_to(persona))
+            .take(limit)
+            .map(|n| n.view_for(persona))
+            .collect())
+    }
+
+    pub async fn unread_count(db: &Surreal<Db>, persona: &str) -> Result<usize> {
+        Ok(unread_count(&Self::list_all(db).await?, persona))
+    }
+
+    /// Mark one notification read; `None` if it does not exist or is not the persona's
+    pub async fn mark_read(
+        db: &Surreal<Db>,
+        id: &str,
+        persona: &str,
+    ) -> Result<Option<Notification>> {
+        let Some(mut notification): Option<Notification> = db.select(("notification", id)).await?
+        else {
+            return Ok(None);
+        };
+        if !notification.visible_to(persona) {
+            return Ok(None);
+        }
+        if !notification.is_read_by(persona) {
+            notification.read_by.push(persona.to_string());
+            notification.id = None;
+            notification = db
+                .update(("notification", id))
+                .content(notification)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Failed to update notification"))?;
+        }
+        Ok(Some(notification))
+    }
+
+    /// Mark everything visible to `persona` read; returns how many changed
+    pub async fn mark_all_read(db: &Surreal<Db>, persona: &str) -> Result<usize> {
+        let mut changed = 0;
+        for notification in Self::list_all(db).await? {
+            if !notification.visible_to(persona) || notification.is_read_by(persona) {
+                continue;
+            }
+            let Some(id) = notification.id.as_ref() else {
+                continue;
+            };
+            Self::mark_read(db, &id.id.to_raw(), persona).await?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::calendar::{MeetingType, RecurrenceFrequency};
+
+    fn person(key: &str, name: &str) -> Person {
+        Person {
+            id: Some(Thing::from(("person", key))),
+            name: name.into(),
+            email: String::new(),
+            title: String::new(),
+            department: String::new(),
+            site_id: Thing::from(("site", "hq")),
+            space_id: None,
+            manager_id: None,
+            role: Default::default(),
+            photo: None,
+            bio: None,
+            desk_phone: None,
+            cell_phone: None,
+            photo_data: None,
+        }
+    }
+
+    #[test]
+    fn unread_is_per_persona() {
+        let mut broadcast =
+            Notification::new(NotificationKind::ImportFinished, None, "Cities imported");
+        broadcast.read_by.push("Ann".into());
+        let direct = Notification::new(
+            NotificationKind::MeetingInvite,
+            Some("Bob".into()),
+            "Invitation",
+        );
+        let all = vec![broadcast, direct];
+
+        assert_eq!(unread_count(&all, "Ann"), 0);
+        assert_eq!(unread_count(&all, "Bob"), 2);
+        assert_eq!(unread_count(&all, "Cat"), 1);
+        assert!(all[0].view_for("Ann").read);
+        assert!(!all[0].view_for("Cat").read);
+    }
+
+    #[test]
+    fn invites_skip_the_organizer() {
+        let people = vec![
+            person("ann", "Ann"),
+            person("bob", "Bob"),
+            person("cat", "Cat"),
+        ];
+        let meeting = Meeting {
+            id: None,
+            title: "Design review".into(),
+            description: None,
+            start_time: "2025-03-04T10:00:00".into(),
+            end_time: "2025-03-04T11:00:00".into(),
+            all_day: false,
+            meeting_type: MeetingType::Review,
+            recurrence: RecurrenceFrequency::None,
+            recurrence_interval: 1,
+            recurrence_days: Vec::new(),
+            recurrence_until: None,
+            recurrence_count: None,
+            location_id: None,
+            virtual_url: None,
+            organizer_id: Some(Thing::from(("person", "ann"))),
+            participant_ids: vec![
+                Thing::from(("person", "ann")),
+                Thing::from(("person", "bob")),
+                Thing::from(("person", "gone")),
+            ],
+            timezone: "UTC".into(),
+        };
+
+        let invites = meeting_invites(&meeting, &people);
+        assert_eq!(invites.len(), 1);
+        assert_eq!(invites[0].recipient.as_deref(), Some("Bob"));
+        assert_eq!(invites[0].title, "Invitation: Design review");
+        assert_eq!(
+            invites[0].body.as_deref(),
+            Some("Ann invited you · 2025-03-04 10:00:00")
+        );
+    }
+}
@@ -0,0 +1,202 @@
+// On-call
+// Rotations over personnel, each handing over to the next member every few hours from its
+// start, and escalation policies chaining rotations with a delay per level. Working out who
+// is on call at a given time is left to the caller; records here only hold the plan.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Rotation {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub name: String,
+    /// People taking turns, in order
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    pub members: Vec<Thing>,
+    /// First handover, `YYYY-MM-DD HH:MM` UTC
+    pub start: String,
+    /// Length of each member's shift
+    pub shift_hours: u32,
+}
+
+impl Rotation {
+    /// Key of the record id, for use in URLs
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+}
+
+/// One step of an escalation chain
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EscalationLevel {
+    /// Rotation whose current member is paged
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub rotation_id: Thing,
+    /// Minutes after the incident opened, while still unacknowledged
+    pub after_minutes: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EscalationPolicy {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub name: String,
+    /// Paged in order, each after its delay
+    pub levels: Vec<EscalationLevel>,
+    /// Used for incidents that name no policy, and for alerts routed to
+    /// whoever is on call; at most one policy is the default
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl EscalationPolicy {
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+
+    /// Each level's delay, in order
+    pub fn delays(&self) -> Vec<u32> {
+        self.levels.iter().map(|l| l.after_minutes).collect()
+    }
+}
+
+pub struct OnCallRepository;
+
+impl OnCallRepository {
+    /// All rotations, by name
+    pub async fn rotations(db: &Surreal<Db>) -> Result<Vec<Rotation>> {
+        let mut rotations: Vec<Rotation> = db.select("rotation").await?;
+        rotations.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(rotations)
+    }
+
+    pub async fn get_rotation(db: &Surreal<Db>, id: &str) -> Result<Option<Rotation>> {
+        Ok(db.select(("rotation", id)).await?)
+    }
+
+    pub async fn create_rotation(db: &Surreal<Db>, rotation: Rotation) -> Result<Rotation> {
+        let created: Rotation = db
+            .create("rotation")
+            .content(rotation)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create rotation"))?;
+        versions::bump(Data::OnCall);
+        Ok(created)
+    }
+
+    pub async fn update_rotation(
+        db: &Surreal<Db>,
+        id: &str,
+        mut rotation: Rotation,
+    ) -> Result<Option<Rotation>> {
+        rotation.id = None;
+        let updated: Option<Rotation> = db.update(("rotation", id)).content(rotation).await?;
+        versions::bump(Data::OnCall);
+        Ok(updated)
+    }
+
+    pub async fn delete_rotation(db: &Surreal<Db>, id: &str) -> Result<()> {
+        let _deleted: Option<Rotation> = db.delete(("rotation", id)).await?;
+        versions::bump(Data::OnCall);
+        Ok(())
+    }
+
+    /// All escalation policies, by name
+    pub async fn policies(db: &Surreal<Db>) -> Result<Vec<EscalationPolicy>> {
+        let mut policies: Vec<EscalationPolicy> = db.select("escalation_policy").await?;
+        policies.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(policies)
+    }
+
+    pub async fn get_policy(db: &Surreal<Db>, id: &str) -> Result<Option<EscalationPolicy>> {
+        Ok(db.select(("escalation_policy", id)).await?)
+    }
+
+    /// The policy marked as the default, if any
+    pub async fn default_policy(db: &Surreal<Db>) -> Result<Option<EscalationPolicy>> {
+        Ok(Self::policies(db).await?.into_iter().find(|p| p.default))
+    }
+
+    /// Stop every policy but `keep` being the default
+    async fn clear_default(db: &Surreal<Db>, keep: &str) -> Result<()> {
+        for mut policy in Self::policies(db).await? {
+            let key = policy.key();
+            if policy.default && key != keep {
+                policy.default = false;
+                policy.id = None;
+                let _updated: Option<EscalationPolicy> = db
+                    .update(("escalation_policy", key))
+                    .content(policy)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn create_policy(
+        db: &Surreal<Db>,
+        policy: EscalationPolicy,
+    ) -> Result<EscalationPolicy> {
+        let default = policy.default;
+        let created: EscalationPolicy = db
+            .create("escalation_policy")
+            .content(policy)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create escalation policy"))?;
+        if default {
+            Self::clear_default(db, &created.key()).await?;
+        }
+        versions::bump(Data::OnCall);
+        Ok(created)
+    }
+
+    pub async fn update_policy(
+        db: &Surreal<Db>,
+        id: &str,
+        mut policy: EscalationPolicy,
+    ) -> Result<Option<EscalationPolicy>> {
+        policy.id = None;
+        if policy.default {
+            Self::clear_default(db, id).await?;
+        }
+        let updated: Option<EscalationPolicy> =
+            db.update(("escalation_policy", id)).content(policy).await?;
+        versions::bump(Data::OnCall);
+        Ok(updated)
+    }
+
+    pub async fn delete_policy(db: &Surreal<Db>, id: &str) -> Result<()> {
+        let _deleted: Option<EscalationPolicy> = db.delete(("escalation_policy", id)).await?;
+        versions::bump(Data::OnCall);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_read_without_a_default_flag() {
+        let policy: EscalationPolicy = serde_json::from_value(serde_json::json!({
+            "id": null,
+            "name": "Network",
+            "levels": [
+                { "rotation_id": { "tb": "rotation", "id": { "String": "primary" } }, "after_minutes": 0 },
+                { "rotation_id": { "tb": "rotation", "id": { "String": "managers" } }, "after_minutes": 30 },
+            ],
+        }))
+        .unwrap();
+        assert!(!policy.default);
+        assert_eq!(policy.delays(), vec![0, 30]);
+    }
+}
@@ -0,0 +1,579 @@
+// Reports
+// Report content is built here as plain tables from the other repositories'
+// records; rendering to HTML/PDF happens in the server. Each rendered
+// document is stored so the Reports page can list and re-download it
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::geo::{NetworkAsset, Person, Rack, Site, Space};
+use super::simulation::SimulationRun;
+use super::versions::{self, Data};
+use crate::config::{ComponentConfig, ConnectionConfig};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportKind {
+    AssetInventory,
+    PersonnelRoster,
+    SimulationSummary,
+    EnergySummary,
+}
+
+impl ReportKind {
+    pub const ALL: [ReportKind; 4] = [
+        ReportKind::AssetInventory,
+        ReportKind::PersonnelRoster,
+        ReportKind::SimulationSummary,
+        ReportKind::EnergySummary,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ReportKind::AssetInventory => "Asset Inventory",
+            ReportKind::PersonnelRoster => "Personnel Roster",
+            ReportKind::SimulationSummary => "Simulation Summary",
+            ReportKind::EnergySummary => "Energy Summary",
+        }
+    }
+}
+
+impl std::fmt::Display for ReportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportKind::AssetInventory => write!(f, "asset-inventory"),
+            ReportKind::PersonnelRoster => write!(f, "personnel-roster"),
+            ReportKind::SimulationSummary => write!(f, "simulation-summary"),
+            ReportKind::EnergySummary => write!(f, "energy-summary"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReportKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "asset-inventory" => Ok(ReportKind::AssetInventory),
+            "personnel-roster" => Ok(ReportKind::PersonnelRoster),
+            "simulation-summary" => Ok(ReportKind::SimulationSummary),
+            "energy-summary" => Ok(ReportKind::EnergySummary),
+            other => anyhow::bail!("Unknown report kind '{}'", other),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Html,
+    #[default]
+    Pdf,
+}
+
+impl ReportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "text/html; charset=utf-8",
+            ReportFormat::Pdf => "application/pdf",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "html" => Ok(ReportFormat::Html),
+            "pdf" => Ok(ReportFormat::Pdf),
+            other => anyhow::bail!("Unknown report format '{}'", other),
+        }
+    }
+}
+
+/// One table of a report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportTable {
+    pub heading: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ReportTable {
+    fn new(heading: &str, columns: &[&str]) -> Self {
+        Self {
+            heading: heading.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+}
+
+/// Report content ready for rendering
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportData {
+    pub kind: ReportKind,
+    pub title: String,
+    pub generated_at: String,
+    /// Headline figures as label/value pairs
+    pub summary: Vec<(String, String)>,
+    pub tables: Vec<ReportTable>,
+}
+
+impl ReportData {
+    fn new(kind: ReportKind, generated_at: &str) -> Self {
+        Self {
+            kind,
+            title: kind.title().to_string(),
+            generated_at: generated_at.to_string(),
+            summary: Vec::new(),
+            tables: Vec::new(),
+        }
+    }
+
+    fn stat(&mut self, label: &str, value: impl ToString) {
+        self.summary.push((label.to_string(), value.to_string()));
+    }
+}
+
+fn by_id<T>(items: &[T], id: impl Fn(&T) -> Option<&Thing>) -> HashMap<String, &T> {
+    items
+        .iter()
+        .filter_map(|item| Some((id(item)?.to_string(), item)))
+        .collect()
+}
+
+/// Count of each distinct value, in name order
+fn tally(values: impl Iterator<Item = String>) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    counts
+}
+
+pub fn asset_inventory(
+    assets: &[NetworkAsset],
+    racks: &[Rack],
+    spaces: &[Space],
+    generated_at: &str,
+) -> ReportData {
+    let racks = by_id(racks, |r| r.id.as_ref());
+    let spaces = by_id(spaces, |s| s.id.as_ref());
+    let location = |a: &NetworkAsset| -> String {
+        if let Some(rack) = a.rack_id.as_ref().and_then(|id| racks.get(&id.to_string())) {
+            return match a.position_u {
+                Some(u) => format!("{} U{}", rack.name, u),
+                None => rack.name.clone(),
+            };
+        }
+        if let Some(space) = a
+            .space_id
+            .as_ref()
+            .and_then(|id| spaces.get(&id.to_string()))
+        {
+            return space.name.clone();
+        }
+        a.storage_location.clone().unwrap_or_default()
+    };
+
+    let mut report = ReportData::new(ReportKind::AssetInventory, generated_at);
+    report.stat("Assets", assets.len());
+    for (status, count) in tally(assets.iter().map(|a| a.status.to_string())) {
+        report.stat(&status, count);
+    }
+
+    let mut sorted: Vec<&NetworkAsset> = assets.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut table = ReportTable::new(
+        "Assets",
+        &[
+            "Name",
+            "Category",
+            "Manufacturer",
+            "Model",
+            "Serial",
+            "Status",
+            "Lifecycle",
+            "Location",
+        ],
+    );
+    for a in sorted {
+        table.rows.push(vec![
+            a.name.clone(),
+            a.category.to_string(),
+            a.manufacturer.clone(),
+            a.model.clone(),
+            a.serial_number.clone(),
+            a.status.to_string(),
+            a.lifecycle.to_string(),
+            location(a),
+        ]);
+    }
+    report.tables.push(table);
+    report
+}
+
+pub fn personnel_roster(people: &[Person], sites: &[Site], generated_at: &str) -> ReportData {
+    let sites = by_id(sites, |s| s.id.as_ref());
+    let mut report = ReportData::new(ReportKind::PersonnelRoster, generated_at);
+    report.stat("People", people.len());
+
+    let departments = tally(people.iter().map(|p| p.department.clone()));
+    report.stat("Departments", departments.len());
+    for department in departments.keys() {
+        let mut table = ReportTable::new(
+            department,
+            &["Name", "Title", "Email", "Site", "Desk Phone"],
+        );
+        let mut staff: Vec<&Person> = people
+            .iter()
+            .filter(|p| &p.department == department)
+            .collect();
+        staff.sort_by(|a, b| a.name.cmp(&b.name));
+        for p in staff {
+            table.rows.push(vec![
+                p.name.clone(),
+                p.title.clone(),
+                p.email.clone(),
+                sites
+                    .get(&p.site_id.to_string())
+                    .map(|s| s.name.clone())
+                    .unwrap_or_default(),
+                p.desk_phone.clone().unwrap_or_default(),
+            ]);
+        }
+        report.tables.push(table);
+    }
+    report
+}
+
+pub fn simulation_summary(
+    runs: &[SimulationRun],
+    components: &[ComponentConfig],
+    connections: &[ConnectionConfig],
+    generated_at: &str,
+) -> ReportData {
+    let mut report = ReportData::new(ReportKind::SimulationSummary, generated_at);
+    report.stat("Components", components.len());
+    report.stat("Connections", connections.len());
+    report.stat("Runs", runs.len());
+
+    let mut table = ReportTable::new("Components", &["Id", "Name"]);
+    let mut sorted: Vec<&ComponentConfig> = components.iter().collect();
+    sorted.sort_by_key(|c| c.id);
+    for c in sorted {
+        table.rows.push(vec![c.id.to_string(), c.name.clone()]);
+    }
+    report.tables.push(table);
+
+    let mut table = ReportTable::new("Runs", &["Started", "Status", "Log Lines", "Last Entry"]);
+    let mut sorted: Vec<&SimulationRun> = runs.iter().collect();
+    sorted.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    for run in sorted {
+        table.rows.push(vec![
+            run.started_at.clone(),
+            run.status.clone(),
+            run.logs.len().to_string(),
+            run.logs.last().cloned().unwrap_or_default(),
+        ]);
+    }
+    report.tables.push(table);
+    report
+}
+
+/// Energy per site and per component in the latest run, with every run's
+/// total below
+pub fn energy_summary(runs: &[SimulationRun], generated_at: &str) -> ReportData {
+    let mut report = ReportData::new(ReportKind::EnergySummary, generated_at);
+    let mut sorted: Vec<&SimulationRun> = runs.iter().collect();
+    sorted.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    let Some(latest) = sorted.first() else {
+        report.stat("Runs", 0);
+        return report;
+    };
+    report.stat("Latest Run", &latest.started_at);
+    report.stat("Energy (Wh)", format!("{:.1}", latest.energy_wh()));
+    report.stat(
+        "Average Draw (W)",
+        format!(
+            "{:.0}",
+            latest.sites.iter().map(|s| s.average_w).sum::<f64>()
+        ),
+    );
+    report.stat("Sites", latest.sites.len());
+
+    let mut table = ReportTable::new(
+        "Sites",
+        &[
+            "Site",
+            "Components",
+            "Energy (Wh)",
+            "Average (W)",
+            "Peak (W)",
+        ],
+    );
+    for site in &latest.sites {
+        table.rows.push(vec![
+            site.site.clone(),
+            site.components.to_string(),
+            format!("{:.1}", site.energy_wh),
+            format!("{:.0}", site.average_w),
+            format!("{:.0}", site.peak_w),
+        ]);
+    }
+    report.tables.push(table);
+
+    let mut table = ReportTable::new("Components", &["Id", "Name", "Energy (Wh)", "Average (W)"]);
+    for node in latest
+        .nodes
+        .iter()
+        .filter(|n| n.metrics.contains_key("energy_wh"))
+    {
+        let metric = |name: &str| node.metrics.get(name).copied().unwrap_or_default();
+        table.rows.push(vec![
+            node.component.to_string(),
+            node.name.clone(),
+            format!("{:.1}", metric("energy_wh")),
+            format!("{:.0}", metric("average_w")),
+        ]);
+    }
+    report.tables.push(table);
+
+    let mut table = ReportTable::new("Runs", &["Started", "Sandbox", "Energy (Wh)"]);
+    for run in sorted {
+        table.rows.push(vec![
+            run.started_at.clone(),
+            run.sandbox.clone().unwrap_or_default(),
+            format!("{:.1}", run.energy_wh()),
+        ]);
+    }
+    report.tables.push(table);
+    report
+}
+
+/// A rendered report document
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GeneratedReport {
+    pub id: Option<Thing>,
+    pub kind: ReportKind,
+    pub format: ReportFormat,
+    pub title: String,
+    pub generated_at: String,
+    /// Base64 of the rendered document
+    pub content: String,
+    pub size_bytes: usize,
+}
+
+/// A stored report as listed, without its content
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReportDocument {
+    /// Record key, for `/api/reports/documents/{id}`
+    pub id: String,
+    pub kind: ReportKind,
+    pub format: ReportFormat,
+    pub title: String,
+    pub generated_at: String,
+    pub size_bytes: usize,
+}
+
+impl GeneratedReport {
+    pub fn new(data: &ReportData, format: ReportFormat, bytes: &[u8]) -> Self {
+        use base64::Engine;
+        Self {
+            id: None,
+            kind: data.kind,
+            format,
+            title: data.title.clone(),
+            generated_at: data.generated_at.clone(),
+            content: base64::engine::general_purpose::STANDARD.encode(bytes),
+            size_bytes: bytes.len(),
+        }
+    }
+
+    pub fn document(&self) -> ReportDocument {
+        ReportDocument {
+            id: self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default(),
+            kind: self.kind,
+            format: self.format,
+            title: self.title.clone(),
+            generated_at: self.generated_at.clone(),
+            size_bytes: self.size_bytes,
+        }
+    }
+
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        use base64::Engine;
+        Ok(base64::engine::general_purpose::STANDARD.decode(&self.content)?)
+    }
+
+    /// Download file name, e.g. `asset-inventory-2025-01-06.pdf`
+    pub fn file_name(&self) -> String {
+        let date = self
+            .generated_at
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        format!("{}-{}.{}", self.kind, date, self.format.extension())
+    }
+}
+
+pub struct ReportRepository;
+
+impl ReportRepository {
+    pub async fn create(db: &Surreal<Db>, report: GeneratedReport) -> Result<GeneratedReport> {
+        let created: GeneratedReport = db
+            .create("report")
+            .content(report)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to store report"))?;
+        versions::bump(Data::Reports);
+        Ok(created)
+    }
+
+    /// Stored documents, newest first
+    pub async fn list(db: &Surreal<Db>) -> Result<Vec<ReportDocument>> {
+        let reports: Vec<GeneratedReport> = db.select("report").await?;
+        let mut documents: Vec<ReportDocument> =
+            reports.iter().map(GeneratedReport::document).collect();
+        documents.sort_by(|a, b| b.generated_at.cmp(&a.generated_at));
+        Ok(documents)
+    }
+
+    pub async fn get(db: &Surreal<Db>, id: &str) -> Result<Option<GeneratedReport>> {
+        let report: Option<GeneratedReport> = db.select(("report", id)).await?;
+        Ok(report)
+    }
+
+    pub async fn delete(db: &Surreal<Db>, id: &str) -> Result<Option<GeneratedReport>> {
+        let deleted: Option<GeneratedReport> = db.delete(("report", id)).await?;
+        versions::bump(Data::Reports);
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(name: &str, department: &str) -> Person {
+        Person {
+            id: None,
+            name: name.into(),
+            email: format!("{}@example.com", name.to_lowercase()),
+            title: "Engineer".into(),
+            department: department.into(),
+            site_id: Thing::from(("site", "hq")),
+            space_id: None,
+            manager_id: None,
+            role: Default::default(),
+            photo: None,
+            bio: None,
+            desk_phone: None,
+            cell_phone: None,
+            photo_data: None,
+        }
+    }
+
+    #[test]
+    fn kinds_round_trip() {
+        for kind in ReportKind::ALL {
+            assert_eq!(kind.to_string().parse::<ReportKind>().unwrap(), kind);
+        }
+        assert!("payroll".parse::<ReportKind>().is_err());
+        assert_eq!("PDF".parse::<ReportFormat>().unwrap(), ReportFormat::Pdf);
+    }
+
+    #[test]
+    fn roster_groups_by_department() {
+        let sites = vec![Site {
+            id: Some(Thing::from(("site", "hq"))),
+            name: "HQ".into(),
+            region_id: None,
+            location: (0.0, 0.0),
+            status: "active".into(),
+        }];
+        let people = vec![
+            person("Zoe", "IT"),
+            person("Ann", "IT"),
+            person("Bob", "Finance"),
+        ];
+        let report = personnel_roster(&people, &sites, "2025-01-06 09:00:00 UTC");
+
+        assert_eq!(report.summary[0], ("People".to_string(), "3".to_string()));
+        let headings: Vec<&str> = report.tables.iter().map(|t| t.heading.as_str()).collect();
+        assert_eq!(headings, vec!["Finance", "IT"]);
+        assert_eq!(report.tables[1].rows[0][0], "Ann");
+        assert_eq!(report.tables[1].rows[0][3], "HQ");
+    }
+
+    #[test]
+    fn energy_summary_reads_the_latest_run() {
+        use super::super::simulation::RunSite;
+        let run = |started_at: &str, sites: Vec<RunSite>| SimulationRun {
+            id: None,
+            started_at: started_at.into(),
+            status: "completed".into(),
+            logs: Vec::new(),
+            nodes: Vec::new(),
+            traces: Default::default(),
+            sandbox: None,
+            sites,
+            faults: Vec::new(),
+        };
+        let site = |name: &str, energy_wh: f64| RunSite {
+            site: name.into(),
+            components: 1,
+            energy_wh,
+            average_w: energy_wh * 6.0,
+            peak_w: energy_wh * 6.0,
+        };
+        let runs = vec![
+            run("2025-01-05 09:00:00 UTC", vec![site("HQ", 10.0)]),
+            run(
+                "2025-01-06 09:00:00 UTC",
+                vec![site("HQ", 20.0), site("Lab", 5.0)],
+            ),
+        ];
+        let report = energy_summary(&runs, "2025-01-06 10:00:00 UTC");
+
+        assert_eq!(
+            report.summary[1],
+            ("Energy (Wh)".to_string(), "25.0".to_string())
+        );
+        assert_eq!(report.tables[0].rows.len(), 2);
+        assert_eq!(report.tables[2].rows[1][2], "10.0");
+        assert!(energy_summary(&[], "").tables.is_empty());
+    }
+
+    #[test]
+    fn file_names_use_kind_and_date() {
+        let data = ReportData::new(ReportKind::AssetInventory, "2025-01-06 09:00:00 UTC");
+        let report = GeneratedReport::new(&data, ReportFormat::Pdf, b"%PDF");
+        assert_eq!(report.file_name(), "asset-inventory-2025-01-06.pdf");
+        assert_eq!(report.bytes().unwrap(), b"%PDF");
+        assert_eq!(report.size_bytes, 4);
+    }
+}
@@ -0,0 +1,193 @@
+// Topology sandboxes
+// A scratch copy of the components and connections that can be edited and
+// simulated without touching the live topology. Each sandbox also keeps the
+// topology it was branched from, so a merge can tell its own edits apart
+// from changes made to the live topology since.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+use crate::config::{ComponentConfig, ConnectionConfig};
+
+/// Components and the connections between them
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Topology {
+    pub components: Vec<ComponentConfig>,
+    pub connections: Vec<ConnectionConfig>,
+}
+
+impl Topology {
+    pub fn component(&self, id: u32) -> Option<&ComponentConfig> {
+        self.components.iter().find(|c| c.id == id)
+    }
+
+    /// Add `component`, replacing one with the same id
+    pub fn put_component(&mut self, component: ComponentConfig) {
+        self.components.retain(|c| c.id != component.id);
+        self.components.push(component);
+        self.components.sort_by_key(|c| c.id);
+    }
+
+    /// Remove component `id` with its connections; false when it was not there
+    pub fn remove_component(&mut self, id: u32) -> bool {
+        let before = self.components.len();
+        self.components.retain(|c| c.id != id);
+        self.connections.retain(|c| c.from != id && c.to != id);
+        self.components.len() != before
+    }
+
+    /// Connect `from` to `to`; false when either end is missing or they are
+    /// already connected
+    pub fn connect(&mut self, from: u32, to: u32) -> bool {
+        if from == to
+            || self.component(from).is_none()
+            || self.component(to).is_none()
+            || self
+                .connections
+                .iter()
+                .any(|c| (c.from, c.to) == (from, to))
+        {
+            return false;
+        }
+        self.connections.push(ConnectionConfig { from, to });
+        true
+    }
+
+    pub fn disconnect(&mut self, from: u32, to: u32) -> bool {
+        let before = self.connections.len();
+        self.connections.retain(|c| (c.from, c.to) != (from, to));
+        self.connections.len() != before
+    }
+
+    /// The next free component id
+    pub fn next_id(&self) -> u32 {
+        self.components.iter().map(|c| c.id + 1).max().unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Sandbox {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub name: String,
+    pub created_at: String,
+    /// The live topology when the sandbox was branched
+    pub base: Topology,
+    /// The sandbox's own copy, which edits and runs use
+    pub topology: Topology,
+}
+
+/// Stored form of a [`Sandbox`]; topologies are kept as JSON for the same
+/// reason components store their type as data (see `ComponentDbDto`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SandboxDbDto {
+    #[serde(skip_serializing)]
+    id: Option<Thing>,
+    name: String,
+    created_at: String,
+    base: serde_json::Value,
+    topology: serde_json::Value,
+}
+
+impl TryFrom<Sandbox> for SandboxDbDto {
+    type Error = anyhow::Error;
+
+    fn try_from(sandbox: Sandbox) -> Result<Self> {
+        Ok(Self {
+            id: None,
+            name: sandbox.name,
+            created_at: sandbox.created_at,
+            base: serde_json::to_value(sandbox.base)?,
+            topology: serde_json::to_value(sandbox.topology)?,
+        })
+    }
+}
+
+impl TryFrom<SandboxDbDto> for Sandbox {
+    type Error = anyhow::Error;
+
+    fn try_from(dto: SandboxDbDto) -> Result<Self> {
+        Ok(Self {
+            id: dto.id,
+            name: dto.name,
+            created_at: dto.created_at,
+            base: serde_json::from_value(dto.base)?,
+            topology: serde_json::from_value(dto.topology)?,
+        })
+    }
+}
+
+pub struct SandboxRepository;
+
+impl SandboxRepository {
+    /// Sandboxes, newest first
+    pub async fn list(db: &Surreal<Db>) -> Result<Vec<Sandbox>> {
+        let dtos: Vec<SandboxDbDto> = db.select("sandbox").await?;
+        let mut sandboxes = dtos
+            .into_iter()
+            .map(Sandbox::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        sandboxes.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(sandboxes)
+    }
+
+    pub async fn get(db: &Surreal<Db>, id: &str) -> Result<Option<Sandbox>> {
+        let dto: Option<SandboxDbDto> = db.select(("sandbox", id)).await?;
+        dto.map(Sandbox::try_from).transpose()
+    }
+
+    /// Branch `topology` into a new sandbox
+    pub async fn create(
+        db: &Surreal<Db>,
+        name: &str,
+        created_at: &str,
+        topology: Topology,
+    ) -> Result<Sandbox> {
+        let sandbox = Sandbox {
+            id: None,
+            name: name.to_string(),
+            created_at: created_at.to_string(),
+            base: topology.clone(),
+            topology,
+        };
+        let created: Option<SandboxDbDto> = db
+            .create("sandbox")
+            .content(SandboxDbDto::try_from(sandbox)?)
+            .await?;
+        versions::bump(Data::Sandboxes);
+        created
+            .map(Sandbox::try_from)
+            .transpose()?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create sandbox"))
+    }
+
+    /// Replace the sandbox's own topology
+    pub async fn update_topology(
+        db: &Surreal<Db>,
+        id: &str,
+        topology: Topology,
+    ) -> Result<Option<Sandbox>> {
+        let Some(mut sandbox) = Self::get(db, id).await? else {
+            return Ok(None);
+        };
+        sandbox.topology = topology;
+        let updated: Option<SandboxDbDto> = db
+            .update(("sandbox", id))
+            .content(SandboxDbDto::try_from(sandbox)?)
+            .await?;
+        versions::bump(Data::Sandboxes);
+        updated.map(Sandbox::try_from).transpose()
+    }
+
+    pub async fn delete(db: &Surreal<Db>, id: &str) -> Result<()> {
+        let _deleted: Option<SandboxDbDto> = db.delete(("sandbox", id)).await?;
+        versions::bump(Data::Sandboxes);
+        Ok(())
+    }
+}
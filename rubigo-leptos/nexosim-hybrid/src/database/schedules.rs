@@ -0,0 +1,153 @@
+// Scheduled task records
+// Recurring server work (exports, rollups, cleanup, simulation runs) with a cron expression per
+// schedule, and a history of every run. Times are UTC, written `YYYY-MM-DD HH:MM`.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::jobs::JobStatus;
+use super::versions::{self, Data};
+
+/// Runs kept per schedule; older ones are dropped as new ones are recorded
+pub const RUNS_KEPT: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Schedule {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// Human-readable name
+    pub name: String,
+    /// Task to run (e.g. "scenario.export")
+    pub task: String,
+    /// Five-field cron expression or shorthand such as `@nightly`
+    pub cron: String,
+    pub enabled: bool,
+    /// When the schedule is next due; `None` while disabled
+    pub next_run_at: Option<String>,
+    pub last_run_at: Option<String>,
+    /// Status of the latest run
+    pub last_status: Option<JobStatus>,
+    pub created_at: String,
+}
+
+impl Schedule {
+    /// Key of the record id, for use in URLs
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+}
+
+/// One run of a schedule
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScheduleRun {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// Key of the schedule that ran
+    pub schedule: String,
+    pub task: String,
+    /// Key of the background job that did the work
+    pub job: Option<String>,
+    /// Started by hand rather than by the clock
+    #[serde(default)]
+    pub manual: bool,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub status: JobStatus,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct ScheduleRepository;
+
+impl ScheduleRepository {
+    pub async fn create(db: &Surreal<Db>, schedule: Schedule) -> Result<Schedule> {
+        let created: Schedule = db
+            .create("schedule")
+            .content(schedule)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create schedule"))?;
+        versions::bump(Data::Jobs);
+        Ok(created)
+    }
+
+    pub async fn get_all(db: &Surreal<Db>) -> Result<Vec<Schedule>> {
+        let mut schedules: Vec<Schedule> = db.select("schedule").await?;
+        schedules.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(schedules)
+    }
+
+    pub async fn get_by_id(db: &Surreal<Db>, id: &str) -> Result<Option<Schedule>> {
+        let schedule: Option<Schedule> = db.select(("schedule", id)).await?;
+        Ok(schedule)
+    }
+
+    pub async fn update(
+        db: &Surreal<Db>,
+        id: &str,
+        schedule: Schedule,
+    ) -> Result<Option<Schedule>> {
+        let mut schedule = schedule;
+        schedule.id = None;
+        let updated: Option<Schedule> = db.update(("schedule", id)).content(schedule).await?;
+        versions::bump(Data::Jobs);
+        Ok(updated)
+    }
+
+    /// Delete a schedule along with its run history
+    pub async fn delete(db: &Surreal<Db>, id: &str) -> Result<()> {
+        let _deleted: Option<Schedule> = db.delete(("schedule", id)).await?;
+        db.query("DELETE schedule_run WHERE schedule = $schedule")
+            .bind(("schedule", id.to_string()))
+            .await?
+            .check()?;
+        versions::bump(Data::Jobs);
+        Ok(())
+    }
+
+    pub async fn create_run(db: &Surreal<Db>, run: ScheduleRun) -> Result<ScheduleRun> {
+        let created: ScheduleRun = db
+            .create("schedule_run")
+            .content(run)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to record schedule run"))?;
+        versions::bump(Data::Jobs);
+        Ok(created)
+    }
+
+    /// Record how a run ended, then trim the schedule's history to [`RUNS_KEPT`]
+    pub async fn finish_run(
+        db: &Surreal<Db>,
+        id: &str,
+        run: ScheduleRun,
+    ) -> Result<Option<ScheduleRun>> {
+        let schedule = run.schedule.clone();
+        let mut run = run;
+        run.id = None;
+        let updated: Option<ScheduleRun> = db.update(("schedule_run", id)).content(run).await?;
+
+        let runs = Self::runs(db, Some(&schedule)).await?;
+        for old in runs.iter().skip(RUNS_KEPT) {
+            if let Some(id) = &old.id {
+                let _deleted: Option<ScheduleRun> =
+                    db.delete(("schedule_run", id.id.to_raw())).await?;
+            }
+        }
+        versions::bump(Data::Jobs);
+        Ok(updated)
+    }
+
+    /// Runs of one schedule, or of all of them, newest first
+    pub async fn runs(db: &Surreal<Db>, schedule: Option<&str>) -> Result<Vec<ScheduleRun>> {
+        let mut runs: Vec<ScheduleRun> = db.select("schedule_run").await?;
+        if let Some(schedule) = schedule {
+            runs.retain(|run| run.schedule == schedule);
+        }
+        runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        Ok(runs)
+    }
+}
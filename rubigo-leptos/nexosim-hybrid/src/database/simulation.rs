@@ -1,4 +1,6 @@
 // use super::Database;
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use surrealdb::Surreal;
@@ -6,12 +8,72 @@ use surrealdb::engine::local::Db;
 
 use surrealdb::sql::Thing;
 
+use super::versions::{self, Data};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SimulationRun {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub id: Option<Thing>,
     pub started_at: String,
     pub status: String,
     pub logs: Vec<String>,
+    /// Measurements of each simulated component; empty for runs recorded
+    /// before they were kept
+    #[serde(default)]
+    pub nodes: Vec<RunNode>,
+    /// Series sampled once per step, e.g. `step_ms`
+    #[serde(default)]
+    pub traces: BTreeMap<String, Vec<f64>>,
+    /// Name of the sandbox simulated, when it was not the live topology
+    #[serde(default)]
+    pub sandbox: Option<String>,
+    /// Energy drawn by the simulated components, per site
+    #[serde(default)]
+    pub sites: Vec<RunSite>,
+    /// Failures injected into the run
+    #[serde(default)]
+    pub faults: Vec<Fault>,
+}
+
+impl SimulationRun {
+    /// Watt-hours drawn by every simulated component over the run
+    pub fn energy_wh(&self) -> f64 {
+        self.sites.iter().map(|s| s.energy_wh).sum()
+    }
+}
+
+/// One component's measurements in a run
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunNode {
+    pub component: u32,
+    pub name: String,
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// A component made to fail part way through a run
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Fault {
+    pub component: u32,
+    /// Step the component fails at, counting from 1; it stays down for
+    /// the rest of the run
+    pub step: usize,
+    /// Open a training incident for the failure once the run is recorded
+    #[serde(default)]
+    pub open_incident: bool,
+}
+
+/// Energy drawn by one site's simulated components over a run
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunSite {
+    pub site: String,
+    pub components: usize,
+    pub energy_wh: f64,
+    pub average_w: f64,
+    pub peak_w: f64,
 }
 
 pub struct SimulationRepository;
@@ -24,6 +86,7 @@ impl SimulationRepository {
             .content(run)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Failed to create simulation run"))?;
+        versions::bump(Data::Runs);
         Ok(created)
     }
 
@@ -41,6 +104,7 @@ impl SimulationRepository {
 
     pub async fn delete(db: &Surreal<Db>, id: &str) -> Result<()> {
         let _deleted: Option<SimulationRun> = db.delete(("run", id)).await?;
+        versions::bump(Data::Runs);
         Ok(())
     }
 }
@@ -0,0 +1,158 @@
+// Telemetry sources
+// Whether each component's telemetry comes from the simulation or is polled
+// from the real device (SNMP or an agent endpoint), with the latest readings
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+/// Where a component's telemetry comes from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryMode {
+    #[default]
+    Simulated,
+    /// SNMPv2c GET of one interface's counters
+    Snmp,
+    /// HTTP GET of an agent answering with JSON metrics
+    Agent,
+}
+
+impl TelemetryMode {
+    pub fn is_live(self) -> bool {
+        self != TelemetryMode::Simulated
+    }
+}
+
+impl std::fmt::Display for TelemetryMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TelemetryMode::Simulated => write!(f, "simulated"),
+            TelemetryMode::Snmp => write!(f, "snmp"),
+            TelemetryMode::Agent => write!(f, "agent"),
+        }
+    }
+}
+
+fn default_community() -> String {
+    "public".to_string()
+}
+
+fn default_if_index() -> u32 {
+    1
+}
+
+fn default_interval() -> u64 {
+    60
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TelemetrySource {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub component_id: u32,
+    pub mode: TelemetryMode,
+    /// `host[:port]` for SNMP, the endpoint URL for an agent
+    #[serde(default)]
+    pub target: String,
+    #[serde(default = "default_community")]
+    pub community: String,
+    /// Interface whose counters are read over SNMP
+    #[serde(default = "default_if_index")]
+    pub if_index: u32,
+    #[serde(default = "default_interval")]
+    pub interval_secs: u64,
+    /// Readings from the latest poll, by metric name
+    #[serde(default)]
+    pub readings: BTreeMap<String, f64>,
+    /// Per-second rates of the counters between the last two polls
+    #[serde(default)]
+    pub rates: BTreeMap<String, f64>,
+    /// When the device was last polled, in Unix milliseconds
+    pub polled_at: Option<i64>,
+    /// Why the latest poll failed
+    pub error: Option<String>,
+}
+
+impl TelemetrySource {
+    /// A source taking telemetry from the simulation
+    pub fn simulated(component_id: u32) -> Self {
+        Self {
+            id: None,
+            component_id,
+            mode: TelemetryMode::Simulated,
+            target: String::new(),
+            community: default_community(),
+            if_index: default_if_index(),
+            interval_secs: default_interval(),
+            readings: BTreeMap::new(),
+            rates: BTreeMap::new(),
+            polled_at: None,
+            error: None,
+        }
+    }
+
+    /// Whether a live source should be polled at `now` (Unix milliseconds)
+    pub fn due(&self, now: i64) -> bool {
+        self.mode.is_live()
+            && self
+                .polled_at
+                .is_none_or(|at| now - at >= self.interval_secs.max(1) as i64 * 1000)
+    }
+}
+
+pub struct TelemetrySourceRepository;
+
+impl TelemetrySourceRepository {
+    /// Sources set for components, by component id; components without
+    /// one are simulated
+    pub async fn get_all(db: &Surreal<Db>) -> Result<Vec<TelemetrySource>> {
+        let mut sources: Vec<TelemetrySource> = db.select("telemetry_source").await?;
+        sources.sort_by_key(|s| s.component_id);
+        Ok(sources)
+    }
+
+    pub async fn get(db: &Surreal<Db>, component_id: u32) -> Result<TelemetrySource> {
+        let source: Option<TelemetrySource> =
+            db.select(("telemetry_source", component_id as i64)).await?;
+        Ok(source.unwrap_or_else(|| TelemetrySource::simulated(component_id)))
+    }
+
+    /// Replace a component's source
+    pub async fn set(db: &Surreal<Db>, source: TelemetrySource) -> Result<TelemetrySource> {
+        let mut source = source;
+        source.id = None;
+        let saved: Option<TelemetrySource> = db
+            .upsert(("telemetry_source", source.component_id as i64))
+            .content(source)
+            .await?;
+        versions::bump(Data::Telemetry);
+        saved.ok_or_else(|| anyhow::anyhow!("Failed to save telemetry source"))
+    }
+
+    /// Go back to simulated telemetry, e.g. when the component is deleted
+    pub async fn clear(db: &Surreal<Db>, component_id: u32) -> Result<()> {
+        let _deleted: Option<TelemetrySource> =
+            db.delete(("telemetry_source", component_id as i64)).await?;
+        versions::bump(Data::Telemetry);
+        Ok(())
+    }
+
+    /// IDs of components whose telemetry comes from the real device
+    pub async fn live_components(db: &Surreal<Db>) -> Result<Vec<u32>> {
+        let sources = Self::get_all(db).await?;
+        Ok(sources
+            .into_iter()
+            .filter(|s| s.mode.is_live())
+            .map(|s| s.component_id)
+            .collect())
+    }
+}
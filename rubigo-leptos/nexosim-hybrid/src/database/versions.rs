@@ -0,0 +1,158 @@
+// Data versions
+// A counter per kind of data, bumped by the repositories after every successful write. Anything
+// derived from the data (rendered pages, summaries) can remember the versions it was built from
+// and rebuild only when one of them moves. Counters are process-wide and only ever increase.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Data {
+    Components,
+    Connections,
+    /// Regions, sites, buildings, floors, spaces, racks, devices, desks and geo features
+    Geo,
+    People,
+    Assets,
+    /// Patch panels, ports and cables
+    Cabling,
+    Meetings,
+    DeskBookings,
+    /// Maintenance tickets and lifecycle transitions
+    Maintenance,
+    /// Background jobs, schedules and schedule runs
+    Jobs,
+    Reports,
+    Runs,
+    Flags,
+    /// Webhooks and their delivery log
+    Webhooks,
+    /// Outgoing email and email preferences
+    Email,
+    /// Where components get their telemetry, and the latest live readings
+    Telemetry,
+    /// Topology sandboxes
+    Sandboxes,
+    /// Power and cooling budgets, and daily capacity snapshots
+    Capacity,
+    Incidents,
+    /// On-call rotations and escalation policies
+    OnCall,
+}
+
+impl Data {
+    pub const ALL: [Data; 20] = [
+        Data::Components,
+        Data::Connections,
+        Data::Geo,
+        Data::People,
+        Data::Assets,
+        Data::Cabling,
+        Data::Meetings,
+        Data::DeskBookings,
+        Data::Maintenance,
+        Data::Jobs,
+        Data::Reports,
+        Data::Runs,
+        Data::Flags,
+        Data::Webhooks,
+        Data::Email,
+        Data::Telemetry,
+        Data::Sandboxes,
+        Data::Capacity,
+        Data::Incidents,
+        Data::OnCall,
+    ];
+
+    /// Name used when announcing changes to clients
+    pub fn name(self) -> &'static str {
+        match self {
+            Data::Components => "components",
+            Data::Connections => "connections",
+            Data::Geo => "geo",
+            Data::People => "people",
+            Data::Assets => "assets",
+            Data::Cabling => "cabling",
+            Data::Meetings => "meetings",
+            Data::DeskBookings => "desk_bookings",
+            Data::Maintenance => "maintenance",
+            Data::Jobs => "jobs",
+            Data::Reports => "reports",
+            Data::Runs => "runs",
+            Data::Flags => "flags",
+            Data::Webhooks => "webhooks",
+            Data::Email => "email",
+            Data::Telemetry => "telemetry",
+            Data::Sandboxes => "sandboxes",
+            Data::Capacity => "capacity",
+            Data::Incidents => "incidents",
+            Data::OnCall => "on_call",
+        }
+    }
+}
+
+static COUNTERS: [AtomicU64; Data::ALL.len()] = [const { AtomicU64::new(0) }; Data::ALL.len()];
+
+/// Record a write to `data`
+pub fn bump(data: Data) {
+    COUNTERS[data as usize].fetch_add(1, Ordering::Release);
+}
+
+/// Record a write that may touch anything, e.g. seeding or clearing a scenario
+pub fn bump_all() {
+    for data in Data::ALL {
+        bump(data);
+    }
+}
+
+pub fn version(data: Data) -> u64 {
+    COUNTERS[data as usize].load(Ordering::Acquire)
+}
+
+/// Current version of every kind of data, indexed like [`Data::ALL`]
+pub fn snapshot() -> [u64; Data::ALL.len()] {
+    Data::ALL.map(version)
+}
+
+/// Kinds of data written between two snapshots
+pub fn changed(before: &[u64; Data::ALL.len()], after: &[u64; Data::ALL.len()]) -> Vec<Data> {
+    Data::ALL
+        .into_iter()
+        .filter(|d| before[*d as usize] != after[*d as usize])
+        .collect()
+}
+
+/// Combined version of several kinds of data; changes whenever any of them is written
+pub fn combined(data: &[Data]) -> u64 {
+    // Counters only increase, so the sum moves with every bump
+    data.iter().map(|d| version(*d)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumps_move_only_the_versions_that_include_them() {
+        let tabs = [Data::Components, Data::Runs];
+        let before = combined(&tabs);
+        let flags = version(Data::Flags);
+
+        bump(Data::Runs);
+        assert!(combined(&tabs) > before);
+        assert!(version(Data::Flags) >= flags);
+
+        let before = combined(&tabs);
+        bump_all();
+        assert!(combined(&tabs) >= before + 2);
+    }
+
+    #[test]
+    fn changes_since_a_snapshot() {
+        let before = snapshot();
+        bump(Data::Cabling);
+        // Other tests may bump concurrently, so only check what this one wrote
+        assert!(changed(&before, &snapshot()).contains(&Data::Cabling));
+        assert!(changed(&before, &before).is_empty());
+        assert_eq!(Data::DeskBookings.name(), "desk_bookings");
+    }
+}
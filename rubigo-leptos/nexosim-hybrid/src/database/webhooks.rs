@@ -0,0 +1,184 @@
+// Webhook records
+// Registered endpoints with the events they want, and a log of every delivery made to them
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+use super::versions::{self, Data};
+
+/// Deliveries kept per webhook; older ones are dropped as new ones are made
+pub const DELIVERIES_KEPT: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Webhook {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    pub name: String,
+    pub url: String,
+    /// HMAC-SHA256 key the payloads are signed with
+    pub secret: String,
+    /// Event names, `kind.*` or `*`
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+impl Webhook {
+    /// Key of the record id, for use in URLs
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+}
+
+/// Where a delivery stands
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Not yet accepted; more attempts will be made
+    #[default]
+    Pending,
+    Delivered,
+    /// Gave up after the last attempt
+    Failed,
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryStatus::Pending => write!(f, "pending"),
+            DeliveryStatus::Delivered => write!(f, "delivered"),
+            DeliveryStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDelivery {
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub id: Option<Thing>,
+    /// Key of the webhook delivered to
+    pub webhook: String,
+    pub event: String,
+    /// JSON body exactly as sent and signed
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    /// HTTP status of the last attempt, if the endpoint answered
+    pub response_code: Option<u16>,
+    /// Why the last attempt failed
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl WebhookDelivery {
+    pub fn key(&self) -> String {
+        self.id.as_ref().map(|t| t.id.to_raw()).unwrap_or_default()
+    }
+}
+
+pub struct WebhookRepository;
+
+impl WebhookRepository {
+    pub async fn create(db: &Surreal<Db>, webhook: Webhook) -> Result<Webhook> {
+        let created: Webhook = db
+            .create("webhook")
+            .content(webhook)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to create webhook"))?;
+        versions::bump(Data::Webhooks);
+        Ok(created)
+    }
+
+    pub async fn get_all(db: &Surreal<Db>) -> Result<Vec<Webhook>> {
+        let mut webhooks: Vec<Webhook> = db.select("webhook").await?;
+        webhooks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(webhooks)
+    }
+
+    pub async fn get_by_id(db: &Surreal<Db>, id: &str) -> Result<Option<Webhook>> {
+        let webhook: Option<Webhook> = db.select(("webhook", id)).await?;
+        Ok(webhook)
+    }
+
+    pub async fn update(db: &Surreal<Db>, id: &str, webhook: Webhook) -> Result<Option<Webhook>> {
+        let mut webhook = webhook;
+        webhook.id = None;
+        let updated: Option<Webhook> = db.update(("webhook", id)).content(webhook).await?;
+        versions::bump(Data::Webhooks);
+        Ok(updated)
+    }
+
+    /// Delete a webhook along with its delivery log
+    pub async fn delete(db: &Surreal<Db>, id: &str) -> Result<()> {
+        let _deleted: Option<Webhook> = db.delete(("webhook", id)).await?;
+        db.query("DELETE webhook_delivery WHERE webhook = $webhook")
+            .bind(("webhook", id.to_string()))
+            .await?
+            .check()?;
+        versions::bump(Data::Webhooks);
+        Ok(())
+    }
+
+    /// Record a new delivery under `key`, the ID its payload carries, then trim the webhook's log to
+    /// [`DELIVERIES_KEPT`]
+    pub async fn create_delivery(
+        db: &Surreal<Db>,
+        key: &str,
+        delivery: WebhookDelivery,
+    ) -> Result<WebhookDelivery> {
+        let webhook = delivery.webhook.clone();
+        let created: WebhookDelivery = db
+            .create(("webhook_delivery", key))
+            .content(delivery)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to record webhook delivery"))?;
+
+        let deliveries = Self::deliveries(db, Some(&webhook)).await?;
+        for old in deliveries.iter().skip(DELIVERIES_KEPT) {
+            let _deleted: Option<WebhookDelivery> =
+                db.delete(("webhook_delivery", old.key())).await?;
+        }
+        versions::bump(Data::Webhooks);
+        Ok(created)
+    }
+
+    pub async fn get_delivery(db: &Surreal<Db>, id: &str) -> Result<Option<WebhookDelivery>> {
+        let delivery: Option<WebhookDelivery> = db.select(("webhook_delivery", id)).await?;
+        Ok(delivery)
+    }
+
+    pub async fn update_delivery(
+        db: &Surreal<Db>,
+        id: &str,
+        delivery: WebhookDelivery,
+    ) -> Result<Option<WebhookDelivery>> {
+        let mut delivery = delivery;
+        delivery.id = None;
+        let updated: Option<WebhookDelivery> = db
+            .update(("webhook_delivery", id))
+            .content(delivery)
+            .await?;
+        versions::bump(Data::Webhooks);
+        Ok(updated)
+    }
+
+    /// Deliveries to one webhook, or to all of them, newest first
+    pub async fn deliveries(
+        db: &Surreal<Db>,
+        webhook: Option<&str>,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let mut deliveries: Vec<WebhookDelivery> = db.select("webhook_delivery").await?;
+        if let Some(webhook) = webhook {
+            deliveries.retain(|d| d.webhook == webhook);
+        }
+        deliveries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(deliveries)
+    }
+}
@@ -1,5 +1,6 @@
-use crate::database::Database;
+use crate::database::{Database, DbClient};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct TelemetrySystem {
@@ -34,17 +35,7 @@ impl TelemetrySystem {
     }
 
     pub async fn log_metric(&self, device_id: u32, timestamp_nanos: u64, value: f64) -> Result<()> {
-        // Optimized Array-based ID: metric:[device_id, timestamp]
-        let sql = "CREATE type::thing('metric', [$id, $ts]) CONTENT { val: $val };";
-        self.db
-            .client
-            .query(sql)
-            .bind(("id", device_id))
-            .bind(("ts", timestamp_nanos))
-            .bind(("val", value))
-            .await?;
-
-        Ok(())
+        record_metric(&self.db.client, device_id, timestamp_nanos, value).await
     }
 
     pub async fn get_total_packets(&self) -> Result<usize> {
@@ -55,6 +46,100 @@ impl TelemetrySystem {
     }
 }
 
+/// Log a raw metric for `device_id`, from the simulation or a live poll
+pub async fn record_metric(db: &DbClient, device_id: u32, timestamp_nanos: u64, value: f64) -> Result<()> {
+    // Optimized Array-based ID: metric:[device_id, timestamp]
+    let sql = "CREATE type::thing('metric', [$id, $ts]) CONTENT { val: $val };";
+    db.query(sql)
+        .bind(("id", device_id))
+        .bind(("ts", timestamp_nanos))
+        .bind(("val", value))
+        .await?;
+
+    Ok(())
+}
+
+/// Per-device totals folded out of raw `metric` records
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MetricRollup {
+    pub device: u32,
+    pub samples: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Simulation time of the latest sample folded in, in nanoseconds
+    pub last_ts: u64,
+}
+
+impl MetricRollup {
+    fn add(&mut self, ts: u64, value: f64) {
+        if self.samples == 0 {
+            self.min = value;
+            self.max = value;
+        }
+        self.samples += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.last_ts = self.last_ts.max(ts);
+    }
+
+    fn merge(&mut self, other: &MetricRollup) {
+        if other.samples == 0 {
+            return;
+        }
+        if self.samples == 0 {
+            *self = other.clone();
+            return;
+        }
+        self.samples += other.samples;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.last_ts = self.last_ts.max(other.last_ts);
+    }
+}
+
+#[derive(Deserialize)]
+struct RawMetric {
+    /// `[device_id, timestamp]`
+    key: (u32, u64),
+    val: f64,
+}
+
+/// Fold raw metrics into the per-device `telemetry_rollup` records and
+/// delete them; returns how many samples and devices were rolled up
+pub async fn rollup(db: &DbClient) -> Result<(usize, usize)> {
+    let raw: Vec<RawMetric> = db
+        .query("SELECT record::id(id) AS key, val FROM metric")
+        .await?
+        .take(0)?;
+    let mut fresh: std::collections::BTreeMap<u32, MetricRollup> = Default::default();
+    for metric in &raw {
+        let (device, ts) = metric.key;
+        let rollup = fresh.entry(device).or_insert_with(|| MetricRollup { device, ..Default::default() });
+        rollup.add(ts, metric.val);
+    }
+
+    let devices = fresh.len();
+    for (device, batch) in fresh {
+        let existing: Option<MetricRollup> = db.select(("telemetry_rollup", device as i64)).await?;
+        let mut total = existing.unwrap_or_default();
+        total.merge(&batch);
+        let _: Option<MetricRollup> = db
+            .upsert(("telemetry_rollup", device as i64))
+            .content(total)
+            .await?;
+        // Samples logged after the select are newer and stay for the next rollup
+        db.query("DELETE metric WHERE record::id(id)[0] = $device AND record::id(id)[1] <= $last")
+            .bind(("device", device))
+            .bind(("last", batch.last_ts))
+            .await?
+            .check()?;
+    }
+    Ok((raw.len(), devices))
+}
+
 // Tracing subscriber integration
 pub fn init_tracing() {
     let subscriber = tracing_subscriber::fmt()
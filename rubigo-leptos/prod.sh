@@ -19,6 +19,15 @@ cd gui-server
 cargo build --release
 cd "$ROOT_DIR"
 
+# Build hydration bundle, then fingerprint + precompress static assets
+echo "Building static assets..."
+./gui-server/build-hydrate.sh || echo "Warning: hydration bundle build failed; pages will be static"
+./gui-server/build-assets.sh
+
+# Fail the build if a WASM artifact outgrew its budget in rubigo.toml
+echo "Checking WASM size budgets..."
+cargo run --quiet -p budgets
+
 # Kill any existing process on the port
 if ss -lnt | grep -q ":$PORT "; then
     echo "Stopping existing process on port $PORT..."